@@ -460,6 +460,7 @@ fn is_distill_event(event_type: &FlightRecorderEventType) -> bool {
             | FlightRecorderEventType::DistillEvalCompleted
             | FlightRecorderEventType::DistillPromotionDecided
             | FlightRecorderEventType::DistillPiiDetected
+            | FlightRecorderEventType::DistillTrainingStep
     )
 }
 
@@ -500,7 +501,8 @@ fn aggregate_distill_jobs<'a>(
             }
             FlightRecorderEventType::DistillTeacherRun
             | FlightRecorderEventType::DistillStudentRun
-            | FlightRecorderEventType::DistillScoreComputed => {
+            | FlightRecorderEventType::DistillScoreComputed
+            | FlightRecorderEventType::DistillTrainingStep => {
                 if entry.started_at_utc.is_none() {
                     entry.started_at_utc = Some(ts.clone());
                 }
@@ -1,10 +1,12 @@
 //! MT-122 Tauri IPC surface for the PEFT training job spawner.
 //!
 //! Backs the "Start Training Job" button in the Distillation Queue UI
-//! (the MT-124 surface) by spawning the real
-//! `PythonPeftTrainerExecutor` and returning a typed receipt. The
-//! actual subprocess runs in `tauri::async_runtime::spawn_blocking`
-//! because PyTorch CPU training blocks the worker thread.
+//! (the MT-124 surface) by running the trainer the request picks — the
+//! real `PythonPeftTrainerExecutor` (default) or the in-process
+//! `CandleLoraTrainerExecutor` — and returning a typed receipt.
+//! `distill_with_flight_recorder` runs the trainer on the blocking pool
+//! because training blocks the worker thread, and records its loss steps
+//! as they are reported.
 //!
 //! Spec-Realism Gate compliance:
 //! - Sub-rule 1: NO LiveXxxUnavailable / "not yet wired" returns. The
@@ -22,11 +24,12 @@ use std::sync::Arc;
 
 use chrono::Utc;
 use handshake_core::distillation::{
+    candle_trainer::CandleLoraTrainerExecutor,
     content_review::ContentReviewConfig,
     peft_pipeline::{
-        distill, read_training_corpus_jsonl, review_corpus_with_events, DistillError,
-        DistillJobConfig, PeftHyperparams, PeftProvenanceSidecar, PythonPeftTrainerExecutor,
-        TeacherSource,
+        distill_with_flight_recorder, read_training_corpus_jsonl, DistillError, DistillJobConfig,
+        PeftHyperparams, PeftProvenanceSidecar, PeftTrainerExecutor, PythonPeftTrainerExecutor,
+        TeacherSource, TrainerBackend,
     },
 };
 use handshake_core::flight_recorder::{
@@ -67,6 +70,9 @@ pub struct StartPeftTrainingJobRequest {
     pub max_steps: Option<u32>,
     #[serde(default)]
     pub cpu_only: bool,
+    /// `"python"` (default) or `"candle"`.
+    #[serde(default)]
+    pub trainer: Option<String>,
 }
 
 /// IPC response: the per-job receipt that the UI uses to track
@@ -240,6 +246,12 @@ pub async fn start_peft_training_job_inner(
     let recorder = recorder.ok_or_else(|| {
         "flight recorder must be attached before PEFT training can start".to_string()
     })?;
+    let trainer = request
+        .trainer
+        .as_deref()
+        .map(TrainerBackend::from_cli_arg)
+        .transpose()?
+        .unwrap_or_default();
     let mut config = build_config(request)?;
     let raw_corpus_path = config.corpus_jsonl_path.clone();
     config.corpus_jsonl_path = config.output_lora_dir.join("filtered_corpus.jsonl");
@@ -250,40 +262,35 @@ pub async fn start_peft_training_job_inner(
     let corpus =
         read_training_corpus_jsonl(&raw_corpus_path, "peft-tauri-ipc", &config.license_tag)
             .map_err(|err| err.to_string())?;
-    let reviewed = review_corpus_with_events(&corpus, ContentReviewConfig::defaults())
-        .map_err(|err| err.to_string())?;
-    for outcome in &reviewed.outcomes {
-        outcome
-            .record_flight_recorder_events(recorder, Uuid::now_v7(), &job_id)
-            .await
-            .map_err(|err| err.to_string())?;
-    }
-    if reviewed.summary.pass_count == 0 {
-        return Err(format!(
-            "PII/content review blocked PEFT training: {}",
-            DistillError::NoPassingTurns {
-                turn_count: reviewed.summary.turn_count,
-                pass_count: reviewed.summary.pass_count,
-            }
-        ));
-    }
-    let executor = PythonPeftTrainerExecutor::new(python_path, script_path, process_ledger)
-        .with_cpu_only(cpu_only);
+    let executor: Arc<dyn PeftTrainerExecutor + Send + Sync> = match trainer {
+        TrainerBackend::Python => Arc::new(
+            PythonPeftTrainerExecutor::new(python_path, script_path, process_ledger)
+                .with_cpu_only(cpu_only),
+        ),
+        TrainerBackend::Candle => Arc::new(CandleLoraTrainerExecutor::cpu()),
+    };
     let finished_at_utc = Utc::now().to_rfc3339();
 
-    // PyTorch CPU training blocks the worker; run on the blocking pool.
-    let exec_result = tauri::async_runtime::spawn_blocking(move || {
-        distill(
-            &corpus,
-            config,
-            ContentReviewConfig::defaults(),
-            &executor,
-            &finished_at_utc,
-        )
-    })
+    // Content review is recorded before the zero-pass gate; the trainer runs
+    // on the blocking pool and its steps land in the recorder as it reports
+    // them.
+    let artifact = distill_with_flight_recorder(
+        &corpus,
+        config,
+        ContentReviewConfig::defaults(),
+        executor,
+        &finished_at_utc,
+        recorder,
+        Uuid::now_v7(),
+        &job_id,
+    )
     .await
-    .map_err(|err| format!("trainer task panicked: {err}"))?;
-    let artifact = exec_result.map_err(|err| err.to_string())?;
+    .map_err(|err| match err {
+        DistillError::NoPassingTurns { .. } => {
+            format!("PII/content review blocked PEFT training: {err}")
+        }
+        other => other.to_string(),
+    })?;
 
     // The Python script writes provenance.json on success; surface the
     // canonical fields in the receipt so the UI can render without a
@@ -396,6 +403,7 @@ mod tests {
             batch_size: Some(2),
             max_steps: Some(1),
             cpu_only: true,
+            trainer: None,
         }
     }
 
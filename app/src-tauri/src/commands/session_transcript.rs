@@ -52,7 +52,8 @@ use handshake_core::session_transcript::{
     self, export, ChatTurnInput, ExportCounts, ExportFormat, ExportHeader, SessionTranscriptEntry,
     TranscriptKind,
 };
-use handshake_core::terminal::redaction::{PatternRedactor, SecretRedactor};
+use handshake_core::storage::Database;
+use handshake_core::terminal::redaction::{EngineRedactor, PatternRedactor, SecretRedactor};
 use handshake_core::terminal::TerminalRuntime;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -110,6 +111,9 @@ pub struct SessionTranscriptState {
     /// enrichment (a still-open capture session's current raw stdout). The
     /// durable terminal record is the FR `TerminalChunk` events.
     terminal: Option<TerminalRuntime>,
+    /// Control-plane database, read ONLY for the workspace redaction rules an
+    /// export of a workspace's session is masked with.
+    storage: Option<Arc<dyn Database>>,
 }
 
 impl std::fmt::Debug for SessionTranscriptState {
@@ -118,6 +122,7 @@ impl std::fmt::Debug for SessionTranscriptState {
             .field("recorder", &self.recorder.is_some())
             .field("app_data_root", &self.app_data_root)
             .field("terminal", &self.terminal.is_some())
+            .field("storage", &self.storage.is_some())
             .finish()
     }
 }
@@ -134,9 +139,37 @@ impl SessionTranscriptState {
             recorder,
             app_data_root: app_data_root.into(),
             terminal,
+            storage: None,
         }
     }
 
+    /// Attach the control-plane database so exports can load workspace
+    /// redaction rules.
+    pub fn with_storage(mut self, storage: Arc<dyn Database>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Redactor for an export of a session run in `workspace_id`: that
+    /// workspace's governed rules, or the built-in rules when the session has
+    /// no workspace. A workspace whose rules cannot be loaded fails the export
+    /// rather than falling back to weaker masking.
+    async fn export_redactor(
+        &self,
+        workspace_id: Option<&str>,
+    ) -> Result<Box<dyn SecretRedactor>, String> {
+        let Some(workspace_id) = workspace_id else {
+            return Ok(Box::new(PatternRedactor));
+        };
+        let storage = self.storage.as_ref().ok_or_else(|| {
+            format!("REDACTION_RULES_UNAVAILABLE: no database for workspace {workspace_id}")
+        })?;
+        let redactor = EngineRedactor::for_workspace(storage.as_ref(), Some(workspace_id))
+            .await
+            .map_err(|e| format!("REDACTION_RULES_UNAVAILABLE: workspace {workspace_id}: {e}"))?;
+        Ok(Box::new(redactor))
+    }
+
     fn sessions_root(&self) -> PathBuf {
         self.app_data_root.join("sessions")
     }
@@ -1197,8 +1230,9 @@ fn write_session_export(
     header: &ExportHeader,
     fmt: ExportFormat,
     dest_dir: &Path,
+    redactor: &dyn SecretRedactor,
 ) -> Result<SessionExportResponse, String> {
-    let rendered = export::render(entries, header, fmt, &PatternRedactor);
+    let rendered = export::render(entries, header, fmt, redactor);
 
     let stem = export::safe_session_stem(session_id);
     let suffix = export::export_timestamp_suffix(Utc::now());
@@ -1254,6 +1288,7 @@ pub async fn kernel_session_export(
     session_id: String,
     format: String,
     dest_dir: Option<String>,
+    workspace_id: Option<String>,
     state: State<'_, SessionTranscriptState>,
 ) -> Result<SessionExportResponse, String> {
     let session_id = session_id.trim().to_string();
@@ -1261,6 +1296,10 @@ pub async fn kernel_session_export(
         return Err("session_id must not be empty".to_string());
     }
     let fmt = ExportFormat::from_ipc(&format).ok_or_else(|| format!("unknown format: {format}"))?;
+    let workspace_id = workspace_id
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty());
+    let redactor = state.export_redactor(workspace_id.as_deref()).await?;
 
     // Chat lane: path-keyed by session_id (same as transcript_get).
     let chat_entries = session_chat_log::read_chat_log(&state.app_data_root, &session_id)?;
@@ -1301,7 +1340,14 @@ pub async fn kernel_session_export(
         _ => state.app_data_root.join(SESSION_EXPORT_DIR),
     };
 
-    write_session_export(&session_id, &response.entries, &header, fmt, &dest_dir)
+    write_session_export(
+        &session_id,
+        &response.entries,
+        &header,
+        fmt,
+        &dest_dir,
+        redactor.as_ref(),
+    )
 }
 
 // ---------------------------------------------------------------------------
@@ -2319,9 +2365,15 @@ mod tests {
         let header = build_export_header(&instance, &summary, &resp.entries);
 
         let dest = app_data_root.join(SESSION_EXPORT_DIR);
-        let out =
-            write_session_export(&instance, &resp.entries, &header, ExportFormat::Both, &dest)
-                .expect("export ok");
+        let out = write_session_export(
+            &instance,
+            &resp.entries,
+            &header,
+            ExportFormat::Both,
+            &dest,
+            &PatternRedactor,
+        )
+        .expect("export ok");
 
         assert_eq!(out.files.len(), 2, "both md + json written");
         assert!(!out.empty);
@@ -2380,6 +2432,7 @@ mod tests {
             &header,
             ExportFormat::Markdown,
             &dest,
+            &PatternRedactor,
         )
         .expect("export ok");
         assert_eq!(out.files.len(), 1);
@@ -2417,8 +2470,15 @@ mod tests {
         let summary = synth_min_summary(sid);
         let header = build_export_header(sid, &summary, &[]);
         let dest = app_data_root.join(SESSION_EXPORT_DIR);
-        let out = write_session_export(sid, &[], &header, ExportFormat::Both, &dest)
-            .expect("empty export ok");
+        let out = write_session_export(
+            sid,
+            &[],
+            &header,
+            ExportFormat::Both,
+            &dest,
+            &PatternRedactor,
+        )
+        .expect("empty export ok");
         assert!(out.empty, "empty flag set");
         assert_eq!(out.files.len(), 2);
         let md = std::fs::read_to_string(
@@ -2433,6 +2493,22 @@ mod tests {
         assert_eq!(out.redacted_field_count, 0);
     }
 
+    #[tokio::test]
+    async fn export_for_a_workspace_without_its_rules_fails_closed() {
+        let (_tmp, _root, state, _instance) = seed_export_state().await;
+        // No database attached: the workspace's redaction rules cannot be read,
+        // so the export must not fall back to the built-in masking.
+        let err = match state.export_redactor(Some("ws-1")).await {
+            Ok(_) => panic!("workspace export without its rules must be refused"),
+            Err(err) => err,
+        };
+        assert!(
+            err.starts_with("REDACTION_RULES_UNAVAILABLE:"),
+            "typed rules error, got: {err}"
+        );
+        assert!(state.export_redactor(None).await.is_ok());
+    }
+
     #[tokio::test]
     async fn export_default_dest_is_under_app_data_root() {
         let (_tmp, app_data_root, state, instance) = seed_export_state().await;
@@ -2525,6 +2601,14 @@ mod tests {
             Some(d) if !d.is_empty() => PathBuf::from(d),
             _ => state.app_data_root.join(SESSION_EXPORT_DIR),
         };
-        write_session_export(&session_id, &response.entries, &header, fmt, &dest_dir)
+        let redactor = state.export_redactor(None).await?;
+        write_session_export(
+            &session_id,
+            &response.entries,
+            &header,
+            fmt,
+            &dest_dir,
+            redactor.as_ref(),
+        )
     }
 }
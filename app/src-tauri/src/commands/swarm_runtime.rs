@@ -69,9 +69,11 @@ use handshake_core::distillation::swarm_trace::{
 };
 use handshake_core::flight_recorder::FlightRecorder;
 use handshake_core::model_runtime::cloud::{
-    CliBridgeConfig, CliSubprocessSpawner, LiveCliSpawner, SecretsVault,
+    CliBridgeConfig, CliSubprocessSpawner, CloudLaneObservability, CloudSpendContext,
+    CloudSpendLedger, LiveCliSpawner, SecretsVault, SpendAttribution,
 };
 use handshake_core::model_runtime::registry::RuntimeBinding;
+use handshake_core::model_runtime::store::ModelStore;
 use handshake_core::model_runtime::{
     CancellationToken, GenPrompt, GenerateRequest, ModelId, ModelRuntime, ProviderKind,
     SamplingParams, WarmVmSnapshotManifest,
@@ -401,7 +403,7 @@ impl SwarmRuntimeState {
             .unwrap_or_else(|| "openai".to_string());
         let cloud =
             CloudLaneFactoryConfig::from_vault(vault, Some(anthropic_lane), Some(openai_lane));
-        Self::production_with_cloud_and_recorder(cloud, None, app_data_root, None)
+        Self::production_with_cloud_and_recorder(cloud, None, None, app_data_root, None, None)
     }
 
     /// WP-KERNEL-004 wave 1: same as [`production`] but threads the app's sandbox
@@ -409,9 +411,11 @@ impl SwarmRuntimeState {
     /// sandboxed-local runtime even when the durable Flight Recorder fell back to
     /// the stderr sink (the FR-up path uses `production_with_fr_recorder`). Keeps
     /// `production`'s signature unchanged for the test seams that call it.
+    /// `model_store` lets spawns name stored models.
     pub fn production_with_registry(
         app_data_root: &Path,
         sandbox_registry: Option<Arc<SandboxAdapterRegistry>>,
+        model_store: Option<Arc<ModelStore>>,
     ) -> Self {
         let vault: Arc<dyn SecretsVault> = Arc::new(
             handshake_core::model_runtime::cloud::secrets_vault::OsKeychainSecretsVault::new(
@@ -428,7 +432,14 @@ impl SwarmRuntimeState {
             .unwrap_or_else(|| "openai".to_string());
         let cloud =
             CloudLaneFactoryConfig::from_vault(vault, Some(anthropic_lane), Some(openai_lane));
-        Self::production_with_cloud_and_recorder(cloud, None, app_data_root, sandbox_registry)
+        Self::production_with_cloud_and_recorder(
+            cloud,
+            None,
+            None,
+            app_data_root,
+            sandbox_registry,
+            model_store,
+        )
     }
 
     /// Build the production swarm runtime with an explicit cloud-lane config
@@ -453,7 +464,7 @@ impl SwarmRuntimeState {
         // official_cli lane stays None (the explicit-cloud seam is for callers
         // that wire the lane themselves or do not need it). A relative,
         // never-present path resolves to the unconfigured default in `load`.
-        Self::production_with_cloud_and_recorder(cloud, None, Path::new(""), None)
+        Self::production_with_cloud_and_recorder(cloud, None, None, Path::new(""), None, None)
     }
 
     /// rank-3: production swarm runtime that PERSISTS every SwarmEvent into
@@ -461,10 +472,17 @@ impl SwarmRuntimeState {
     /// Recorder) via the DurableSwarmFrBridge, so swarm/VM lifecycle events are
     /// durable + replayable for the board drill-down + audit -- not just stderr.
     /// Mirrors `production()`'s cloud-lane wiring.
+    ///
+    /// `spend_ledger` is the app's one cloud spend ledger: every BYOK and
+    /// official-CLI dispatch is admitted against its hard budgets (reserving
+    /// the estimate) and charged to it when the call settles. `model_store` is
+    /// the managed model store spawns may name models from.
     pub fn production_with_fr_recorder(
         recorder: Arc<dyn FlightRecorder>,
+        spend_ledger: Option<Arc<CloudSpendLedger>>,
         app_data_root: &Path,
         sandbox_registry: Option<Arc<SandboxAdapterRegistry>>,
+        model_store: Option<Arc<ModelStore>>,
     ) -> Self {
         let vault: Arc<dyn SecretsVault> = Arc::new(
             handshake_core::model_runtime::cloud::secrets_vault::OsKeychainSecretsVault::new(
@@ -479,28 +497,47 @@ impl SwarmRuntimeState {
             .ok()
             .filter(|s| !s.trim().is_empty())
             .unwrap_or_else(|| "openai".to_string());
-        let cloud =
-            CloudLaneFactoryConfig::from_vault(vault, Some(anthropic_lane), Some(openai_lane));
+        let spend = spend_ledger.map(|ledger| CloudSpendContext {
+            ledger,
+            attribution: SpendAttribution::new(),
+        });
+        let observability = Arc::new(CloudLaneObservability {
+            flight_recorder: recorder.clone(),
+            consent: None,
+            spend: spend.clone(),
+        });
+        let cloud = CloudLaneFactoryConfig::from_vault_observed(
+            vault,
+            Some(anthropic_lane),
+            Some(openai_lane),
+            Some(observability),
+        );
         Self::production_with_cloud_and_recorder(
             cloud,
             Some(recorder),
+            spend,
             app_data_root,
             sandbox_registry,
+            model_store,
         )
     }
 
     fn production_with_cloud_and_recorder(
         cloud: CloudLaneFactoryConfig,
         recorder: Option<Arc<dyn FlightRecorder>>,
+        spend: Option<CloudSpendContext>,
         app_data_root: &Path,
         sandbox_registry: Option<Arc<SandboxAdapterRegistry>>,
+        model_store: Option<Arc<ModelStore>>,
     ) -> Self {
         let committed_memory_ceiling_bytes = committed_memory_ceiling_from_env();
         Self::production_with_cloud_and_recorder_and_committed_memory_ceiling(
             cloud,
             recorder,
+            spend,
             app_data_root,
             sandbox_registry,
+            model_store,
             committed_memory_ceiling_bytes,
         )
     }
@@ -508,8 +545,10 @@ impl SwarmRuntimeState {
     fn production_with_cloud_and_recorder_and_committed_memory_ceiling(
         cloud: CloudLaneFactoryConfig,
         recorder: Option<Arc<dyn FlightRecorder>>,
+        spend: Option<CloudSpendContext>,
         app_data_root: &Path,
         sandbox_registry: Option<Arc<SandboxAdapterRegistry>>,
+        model_store: Option<Arc<ModelStore>>,
         committed_memory_ceiling_bytes: Option<u64>,
     ) -> Self {
         let store = InProcessLedgerStore::default();
@@ -530,14 +569,14 @@ impl SwarmRuntimeState {
             Some((spawner, config)) => {
                 // When a Flight Recorder is wired, thread it as cloud-lane
                 // observability so the CLI lane emits FR-EVT-LLM-INFER-* like the
-                // BYOK siblings. No recorder => FR-INFER-silent, otherwise identical.
+                // BYOK siblings, and is held to the same spend ledger. No
+                // recorder => FR-INFER-silent, otherwise identical.
                 let observability = recorder.clone().map(|fr| {
-                    Arc::new(
-                        handshake_core::model_runtime::cloud::CloudLaneObservability {
-                            flight_recorder: fr,
-                            consent: None,
-                        },
-                    )
+                    Arc::new(CloudLaneObservability {
+                        flight_recorder: fr,
+                        consent: None,
+                        spend: spend.clone(),
+                    })
                 });
                 cloud.with_official_cli_observed(spawner, config, observability)
             }
@@ -569,8 +608,11 @@ impl SwarmRuntimeState {
         // absent (test seams / FR-fallback `production`) the non-sandbox spawn
         // paths stay byte-for-byte unchanged and a Tier-3 spawn returns a typed
         // FactoryFailed rather than silently downgrading.
-        let production_factory =
+        let mut production_factory =
             ProductionModelSessionFactory::new(ledger.clone(), cloud, sandbox_registry);
+        if let Some(store) = model_store {
+            production_factory = production_factory.with_model_store(store);
+        }
         let factory = Arc::new(TrackingFactory {
             inner: production_factory,
             table: sessions.clone(),
@@ -3631,8 +3673,10 @@ mod tests {
             SwarmRuntimeState::production_with_cloud_and_recorder_and_committed_memory_ceiling(
                 CloudLaneFactoryConfig::unconfigured(),
                 None,
+                None,
                 std::path::Path::new(""),
                 None,
+                None,
                 Some(8 * 1024 * 1024 * 1024),
             );
         assert_eq!(
@@ -4473,8 +4517,10 @@ mod tests {
                     official_cli: None,
                 },
                 None,
+                None,
                 tmp.path(),
                 None,
+                None,
                 Some(1),
             );
         let mut cloud = cloud_request("gpt-4o");
@@ -5415,9 +5461,11 @@ mod tests {
             SwarmRuntimeState::production_with_cloud_and_recorder_and_committed_memory_ceiling(
                 CloudLaneFactoryConfig::unconfigured(),
                 None,
+                None,
                 tmp.path(),
                 None,
                 None,
+                None,
             )
             .with_spawn_template_store(store.clone());
         let sha = "ab".repeat(32);
@@ -5466,9 +5514,11 @@ mod tests {
             SwarmRuntimeState::production_with_cloud_and_recorder_and_committed_memory_ceiling(
                 CloudLaneFactoryConfig::unconfigured(),
                 None,
+                None,
                 tmp.path(),
                 None,
                 None,
+                None,
             )
             .with_spawn_template_store(store.clone());
         let sha = "ab".repeat(32);
@@ -1108,6 +1108,43 @@ pub fn run() {
                     None
                 }
             };
+            // Cloud spend: ONE ledger for the app, built from every workspace's
+            // `settings.cloud_spend` (strictest budget wins) and replaying the
+            // settled calls journaled under app_data_root, so budgets survive a
+            // restart. Every cloud lane of the swarm is admitted against it.
+            // Settings that cannot be read fall back to the built-in pricing with
+            // no budgets (logged), never to an unmetered lane.
+            let cloud_spend_ledger = {
+                use handshake_core::model_runtime::cloud::{CloudSpendLedger, CloudSpendSettings};
+                let settings = match &control_plane_storage_result {
+                    Ok(control_plane) => tauri::async_runtime::block_on(
+                        CloudSpendSettings::for_workspaces(control_plane.database.as_ref()),
+                    )
+                    .unwrap_or_else(|error| {
+                        eprintln!("cloud spend settings unavailable: {error}; no budgets enforced");
+                        CloudSpendSettings::default()
+                    }),
+                    Err(_) => CloudSpendSettings::default(),
+                };
+                let ledger = |settings| {
+                    let ledger = CloudSpendLedger::new(settings);
+                    match swarm_recorder.clone() {
+                        Some(recorder) => ledger.with_flight_recorder(recorder),
+                        None => ledger,
+                    }
+                };
+                let journal = schedule_store_root.join("cloud_spend.jsonl");
+                let ledger = ledger(settings.clone())
+                    .with_journal(&journal)
+                    .unwrap_or_else(|error| {
+                        eprintln!(
+                            "cloud spend journal {} unavailable: {error}; spend is tracked for this run only",
+                            journal.display()
+                        );
+                        ledger(settings)
+                    });
+                Arc::new(ledger)
+            };
             // INTEGRATED TERMINAL (spec §10.1): build the production
             // `TerminalRuntime` bound to the SAME durable DuckDB Flight Recorder
             // the swarm path uses, so `FR-EVT-TERMINAL-SESSION-OPEN / -COMMAND-EXEC
@@ -1145,14 +1182,20 @@ pub fn run() {
             // below so it shares their handles. When the durable FR fell back to
             // the stderr sink (`None`), the FR/terminal/process lanes report
             // `unavailable` and only `chat.jsonl` is read (honest-disabled).
+            // Exports of a workspace's session read that workspace's redaction
+            // rules through the control plane; without it they are refused.
             let transcript_recorder = swarm_recorder.clone();
             let transcript_app_data_root = schedule_store_root.clone();
             let transcript_terminal = terminal_runtime.clone();
-            app.manage(commands::session_transcript::SessionTranscriptState::new(
+            let transcript_state = commands::session_transcript::SessionTranscriptState::new(
                 transcript_recorder,
                 transcript_app_data_root,
                 transcript_terminal,
-            ));
+            );
+            app.manage(match &control_plane_storage_result {
+                Ok(control_plane) => transcript_state.with_storage(control_plane.database.clone()),
+                Err(_) => transcript_state,
+            });
             // WP-KERNEL-004 wave 1 Integrate: hand the production swarm factory the
             // SAME sandbox adapter registry that was managed into app state above
             // (line ~969), so a Local+LlamaCpp swarm spawn requesting Tier3Microvm
@@ -1162,6 +1205,17 @@ pub fn run() {
             // state (the `kernel_sandbox_*` commands still resolve it).
             let sandbox_registry_for_swarm: Arc<handshake_core::sandbox::SandboxAdapterRegistry> =
                 (*app.state::<Arc<handshake_core::sandbox::SandboxAdapterRegistry>>()).clone();
+            // The managed model store (HANDSHAKE_MODEL_STORE_ROOT, else the
+            // workspace's .handshake/models) so swarm spawns can name stored
+            // models. An unusable store leaves those spawns failing typed.
+            let model_store_for_swarm =
+                match handshake_core::model_runtime::store::ModelStore::open_default() {
+                    Ok(store) => Some(Arc::new(store)),
+                    Err(error) => {
+                        eprintln!("model store unavailable: {error}; stored-model spawns disabled");
+                        None
+                    }
+                };
             let terminal_capture_runtime = terminal_runtime.clone();
             let (swarm_state, board_events, scheduler_state) =
                 tauri::async_runtime::block_on(async move {
@@ -1183,14 +1237,17 @@ pub fn run() {
                         Some(recorder) => {
                             commands::swarm_runtime::SwarmRuntimeState::production_with_fr_recorder(
                                 recorder,
+                                Some(cloud_spend_ledger),
                                 &schedule_store_root,
                                 Some(sandbox_registry_for_swarm),
+                                model_store_for_swarm,
                             )
                         }
                         None => {
                             commands::swarm_runtime::SwarmRuntimeState::production_with_registry(
                                 &schedule_store_root,
                                 Some(sandbox_registry_for_swarm),
+                                model_store_for_swarm,
                             )
                         }
                     };
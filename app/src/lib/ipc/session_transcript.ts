@@ -327,6 +327,11 @@ export interface SessionExportRequest {
    * (`<app_data_root>/exports`). The operator owns where a share file lands.
    */
   destDir?: string | null;
+  /**
+   * Workspace the session ran in. When set, the export is masked with that
+   * workspace's redaction rules; omitted/null => the built-in rules.
+   */
+  workspaceId?: string | null;
 }
 
 // ---------------------------------------------------------------------------
@@ -371,7 +376,7 @@ export async function searchSessions(
  * ROI #5 EXPORT: export a recorded session to a portable, secret-REDACTED file
  * (markdown / json / both). Calls the REAL backend `kernel_session_export`
  * command with camelCase args matching the serde. The backend REUSES the
- * aggregator + the PatternRedactor (no secret leaks) and atomically writes the
+ * aggregator + the workspace's redactor (no secret leaks) and atomically writes the
  * file(s), returning the written path(s) + byte sizes. HONEST: an unknown
  * session id rejects with `SESSION_NOT_FOUND:`; a discoverable empty session
  * writes an empty-but-valid file (`empty: true`).
@@ -383,6 +388,7 @@ export async function exportSession(
     sessionId: request.sessionId,
     format: request.format,
     destDir: request.destDir ?? null,
+    workspaceId: request.workspaceId ?? null,
   });
 }
 
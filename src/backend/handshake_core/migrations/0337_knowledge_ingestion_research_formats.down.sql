@@ -0,0 +1,18 @@
+DELETE FROM knowledge_ingestion_spans
+WHERE anchor_kind IN ('html_section', 'epub_section', 'email_message', 'notebook_cell');
+
+ALTER TABLE knowledge_ingestion_spans
+    DROP CONSTRAINT IF EXISTS knowledge_ingestion_spans_anchor_kind_check;
+
+ALTER TABLE knowledge_ingestion_spans
+    ADD CONSTRAINT knowledge_ingestion_spans_anchor_kind_check
+    CHECK (
+        anchor_kind IN (
+            'byte_range',
+            'line_range',
+            'pdf_page',
+            'media_time',
+            'json_pointer',
+            'heading_path'
+        )
+    );
@@ -0,0 +1,24 @@
+-- WP-KERNEL-009 research-format ingestion (saved web pages, EPUB, EML/MBOX,
+-- Jupyter notebooks). Existing databases with 0163 applied need the span
+-- anchor_kind constraint widened for the section/message/cell anchors
+-- emitted by knowledge_ingestion::{web, epub, email, notebooks}.
+
+ALTER TABLE knowledge_ingestion_spans
+    DROP CONSTRAINT IF EXISTS knowledge_ingestion_spans_anchor_kind_check;
+
+ALTER TABLE knowledge_ingestion_spans
+    ADD CONSTRAINT knowledge_ingestion_spans_anchor_kind_check
+    CHECK (
+        anchor_kind IN (
+            'byte_range',
+            'line_range',
+            'pdf_page',
+            'media_time',
+            'json_pointer',
+            'heading_path',
+            'html_section',
+            'epub_section',
+            'email_message',
+            'notebook_cell'
+        )
+    );
@@ -0,0 +1,3 @@
+-- CalDAV sync conflict records rollback
+
+DROP TABLE IF EXISTS calendar_sync_conflicts;
@@ -0,0 +1,34 @@
+-- CalDAV two-way sync: explicit conflict records. When a remote change and a
+-- local edit touch the same event between syncs, the sync engine records the
+-- pair here instead of letting the last writer win; the event is skipped by
+-- pull and push until the conflict is resolved.
+
+CREATE TABLE IF NOT EXISTS calendar_sync_conflicts (
+    id TEXT PRIMARY KEY NOT NULL,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    source_id TEXT NOT NULL REFERENCES calendar_sources(id) ON DELETE CASCADE,
+    event_id TEXT,
+    external_id TEXT NOT NULL,
+    remote_href TEXT,
+    reason TEXT NOT NULL,
+    local_etag TEXT,
+    remote_etag TEXT,
+    local_snapshot_json TEXT,
+    remote_ics TEXT,
+    status TEXT NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'resolved_keep_local', 'resolved_keep_remote')),
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP,
+    last_job_id TEXT,
+    last_workflow_id TEXT,
+    last_actor_id TEXT,
+    edit_event_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000',
+    last_actor_kind TEXT NOT NULL DEFAULT 'SYSTEM' CHECK (last_actor_kind != 'AI' OR last_job_id IS NOT NULL)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calendar_sync_conflicts_open_event
+    ON calendar_sync_conflicts(source_id, external_id)
+    WHERE status = 'open';
+
+CREATE INDEX IF NOT EXISTS idx_calendar_sync_conflicts_source
+    ON calendar_sync_conflicts(workspace_id, source_id, status);
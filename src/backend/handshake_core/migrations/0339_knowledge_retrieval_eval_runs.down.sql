@@ -0,0 +1,3 @@
+-- WP-KERNEL-009 retrieval quality evaluation runs (down).
+DELETE FROM knowledge_schema_registry WHERE family_key = 'retrieval_eval_runs';
+DROP TABLE IF EXISTS knowledge_retrieval_eval_runs;
@@ -0,0 +1,45 @@
+-- WP-KERNEL-009 retrieval quality evaluation runs.
+-- A run scores one labelled query set (query -> expected entities / spans /
+-- blocks) against one retrieval surface and stores recall@k, MRR, and nDCG@k
+-- per query plus per-QueryPlan-mode means, so two runs can be diffed and
+-- per-query regressions flagged (`knowledge_retrieval::evaluation`).
+--
+-- Runs are measurements, not authority: they never feed retrieval. Each
+-- retrieval the executor target performs still persists its own bundle and
+-- replayable trace (0141), which is where a surprising score is explained.
+
+CREATE TABLE IF NOT EXISTS knowledge_retrieval_eval_runs (
+    run_id TEXT PRIMARY KEY,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    query_set_id TEXT NOT NULL,
+    -- sha256 of the bound query set; differing hashes mean the labels moved.
+    query_set_hash TEXT NOT NULL,
+    -- Retrieval surface measured (knowledge_retrieval.executor | loom.search_v2).
+    target_id TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    -- Cut-offs, JSON array of positive integers.
+    ks JSONB NOT NULL,
+    -- Per-query results [{query_id, mode, ranked_ids, metrics}].
+    results JSONB NOT NULL,
+    -- Per-mode means keyed by mode plus "all".
+    summary JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_knowledge_retrieval_eval_runs_query_set_id
+        CHECK (btrim(query_set_id) = query_set_id AND query_set_id <> ''),
+    CONSTRAINT chk_knowledge_retrieval_eval_runs_ks_is_array
+        CHECK (jsonb_typeof(ks) = 'array'),
+    CONSTRAINT chk_knowledge_retrieval_eval_runs_results_is_array
+        CHECK (jsonb_typeof(results) = 'array'),
+    CONSTRAINT chk_knowledge_retrieval_eval_runs_summary_is_object
+        CHECK (jsonb_typeof(summary) = 'object')
+);
+
+CREATE INDEX IF NOT EXISTS idx_knowledge_retrieval_eval_runs_set
+    ON knowledge_retrieval_eval_runs (workspace_id, query_set_id, created_at DESC);
+
+INSERT INTO knowledge_schema_registry
+    (family_key, table_name, record_family, authority_class, migration_file, mt_id)
+VALUES
+    ('retrieval_eval_runs', 'knowledge_retrieval_eval_runs', 'Support',
+     'support', '0339_knowledge_retrieval_eval_runs.sql', 'MT-144')
+ON CONFLICT (family_key) DO NOTHING;
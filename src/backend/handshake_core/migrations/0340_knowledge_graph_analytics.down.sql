@@ -0,0 +1,3 @@
+-- WP-KERNEL-009 graph analytics projections (down).
+DELETE FROM knowledge_schema_registry WHERE family_key = 'graph_analytics';
+DROP TABLE IF EXISTS knowledge_graph_analytics;
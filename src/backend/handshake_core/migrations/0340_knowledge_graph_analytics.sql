@@ -0,0 +1,37 @@
+-- WP-KERNEL-009 graph analytics projections.
+-- One row per (workspace, graph) holding PageRank / hub scores, communities,
+-- bridge nodes, orphans, and dead ends computed over the active knowledge
+-- edge graph or the Loom block graph (`knowledge_retrieval::graph_analytics`).
+--
+-- PROJECTION, NEVER AUTHORITY: registered with authority_class = 'projection';
+-- nothing references this table and a refresh replaces the row wholesale.
+-- `staleness_hash` is the sha256 fingerprint of the graph the report was
+-- computed from; a mismatch against the live graph marks the row stale, and
+-- retrieval ranking ignores stale hub scores.
+
+CREATE TABLE IF NOT EXISTS knowledge_graph_analytics (
+    projection_id TEXT PRIMARY KEY,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    graph_kind TEXT NOT NULL CHECK (graph_kind IN ('knowledge', 'loom')),
+    staleness_hash TEXT NOT NULL,
+    node_count BIGINT NOT NULL CHECK (node_count >= 0),
+    edge_count BIGINT NOT NULL CHECK (edge_count >= 0),
+    -- {graph_kind, nodes, communities, bridges, orphans, dead_ends, ...}
+    report JSONB NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_knowledge_graph_analytics_id
+        CHECK (projection_id ~ '^KGA-[0-9a-f]{32}$'),
+    CONSTRAINT chk_knowledge_graph_analytics_staleness_hash
+        CHECK (staleness_hash ~ '^[0-9a-f]{64}$'),
+    CONSTRAINT chk_knowledge_graph_analytics_report_is_object
+        CHECK (jsonb_typeof(report) = 'object'),
+    CONSTRAINT uq_knowledge_graph_analytics_graph
+        UNIQUE (workspace_id, graph_kind)
+);
+
+INSERT INTO knowledge_schema_registry
+    (family_key, table_name, record_family, authority_class, migration_file, mt_id)
+VALUES
+    ('graph_analytics', 'knowledge_graph_analytics', 'Projection',
+     'projection', '0340_knowledge_graph_analytics.sql', 'MT-132')
+ON CONFLICT (family_key) DO NOTHING;
@@ -0,0 +1,3 @@
+-- Obsidian / Logseq vault import into Loom (down).
+DROP TABLE IF EXISTS loom_vault_imports;
+DROP TABLE IF EXISTS loom_vault_entries;
@@ -0,0 +1,57 @@
+-- Obsidian / Logseq vault import into Loom (`loom_vault`).
+--
+-- loom_vault_entries maps each imported vault file to the LoomBlock (and, for
+-- notes, the RichDocument) it became. It is what lets a re-import update
+-- blocks in place instead of duplicating them, and lets the importer retire
+-- only the edges it created itself (`managed_edge_ids`). The vault stays a
+-- SOURCE (MT-187): the markdown text is not stored here, only its hash.
+--
+-- loom_vault_imports keeps one report per import run: created / updated /
+-- unchanged counts, unresolved links, skipped files and files that vanished
+-- from the vault since the last run.
+--
+-- Loom-domain tables, so (like loom_ai_suggestions / loom_canvas_boards) they
+-- are not registered in the `knowledge_`-prefixed knowledge_schema_registry.
+
+CREATE TABLE IF NOT EXISTS loom_vault_entries (
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    vault_name TEXT NOT NULL CHECK (length(btrim(vault_name)) > 0),
+    -- Vault-relative, `/`-separated.
+    relative_path TEXT NOT NULL CHECK (length(btrim(relative_path)) > 0),
+    entry_kind TEXT NOT NULL CHECK (entry_kind IN ('note', 'journal', 'attachment')),
+    block_id TEXT NOT NULL REFERENCES loom_blocks(block_id) ON DELETE CASCADE,
+    rich_document_id TEXT
+        REFERENCES knowledge_rich_documents(rich_document_id) ON DELETE SET NULL,
+    -- sha256 of the file bytes at the last import.
+    content_sha256 TEXT NOT NULL CHECK (content_sha256 ~ '^[0-9a-f]{64}$'),
+    -- Front-matter / Logseq page properties.
+    properties JSONB NOT NULL DEFAULT '{}'::jsonb,
+    aliases TEXT[] NOT NULL DEFAULT '{}',
+    -- loom_edges the importer created for this file (never user edges).
+    managed_edge_ids TEXT[] NOT NULL DEFAULT '{}',
+    last_import_id TEXT NOT NULL CHECK (last_import_id ~ '^LVI-[0-9a-f]{32}$'),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (workspace_id, vault_name, relative_path),
+    CONSTRAINT chk_loom_vault_entries_properties_is_object
+        CHECK (jsonb_typeof(properties) = 'object')
+);
+
+CREATE INDEX IF NOT EXISTS idx_loom_vault_entries_block
+    ON loom_vault_entries (workspace_id, block_id);
+
+CREATE TABLE IF NOT EXISTS loom_vault_imports (
+    import_id TEXT PRIMARY KEY CHECK (import_id ~ '^LVI-[0-9a-f]{32}$'),
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    vault_name TEXT NOT NULL,
+    vault_kind TEXT NOT NULL CHECK (vault_kind IN ('obsidian', 'logseq')),
+    unresolved_count BIGINT NOT NULL CHECK (unresolved_count >= 0),
+    report JSONB NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_loom_vault_imports_report_is_object
+        CHECK (jsonb_typeof(report) = 'object')
+);
+
+CREATE INDEX IF NOT EXISTS idx_loom_vault_imports_workspace
+    ON loom_vault_imports (workspace_id, completed_at DESC);
@@ -0,0 +1,2 @@
+-- Media probe metadata for the Loom preview pipeline (down).
+DROP TABLE IF EXISTS media_asset_probes;
@@ -0,0 +1,29 @@
+-- Media probe metadata for the Loom preview pipeline (`loom_preview`).
+--
+-- media_asset_probes keeps what a governed probe tool (ffprobe) reported about
+-- an ORIGINAL video asset: duration, frame size, codecs and frame rate. The
+-- preview-generate job writes it next to the poster tier so the library can
+-- show a duration badge without re-probing. One row per asset; a re-probe
+-- replaces it. Like media_asset_tiers this is derived, regenerable state: the
+-- original blob stays authority.
+--
+-- Loom/asset-domain table, so (like media_asset_tiers) it is not registered
+-- in the `knowledge_`-prefixed knowledge_schema_registry.
+
+CREATE TABLE IF NOT EXISTS media_asset_probes (
+    asset_id TEXT PRIMARY KEY REFERENCES assets(asset_id) ON DELETE CASCADE,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id)
+        ON UPDATE RESTRICT ON DELETE CASCADE,
+    -- The governed tool that produced the row (today only 'ffprobe').
+    probe_tool TEXT NOT NULL CHECK (length(btrim(probe_tool)) > 0),
+    duration_ms BIGINT CHECK (duration_ms IS NULL OR duration_ms >= 0),
+    width INTEGER CHECK (width IS NULL OR width > 0),
+    height INTEGER CHECK (height IS NULL OR height > 0),
+    video_codec TEXT,
+    audio_codec TEXT,
+    frame_rate DOUBLE PRECISION CHECK (frame_rate IS NULL OR frame_rate > 0),
+    probed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_asset_probes_workspace
+    ON media_asset_probes (workspace_id);
@@ -0,0 +1,8 @@
+-- Embedded media metadata (down).
+DELETE FROM atelier_media_sidecar WHERE relation_kind = 'xmp_sidecar';
+ALTER TABLE atelier_media_sidecar
+    DROP CONSTRAINT IF EXISTS chk_atelier_media_sidecar_relation_kind;
+ALTER TABLE atelier_media_sidecar
+    ADD CONSTRAINT atelier_media_sidecar_relation_kind_check
+    CHECK (relation_kind IN ('openpose_json', 'workflow_json'));
+DROP TABLE IF EXISTS atelier_media_metadata;
@@ -0,0 +1,59 @@
+-- Embedded media metadata (EXIF / XMP / IPTC / PNG generation text) as typed,
+-- searchable columns per atelier media asset, plus the `xmp_sidecar` relation
+-- for XMP write-back of tags and ratings. Bytes stay in ArtifactStore; this
+-- table only holds what was parsed out of them. Re-runnable by ensure_schema.
+
+CREATE TABLE IF NOT EXISTS atelier_media_metadata (
+    asset_id                    UUID PRIMARY KEY REFERENCES atelier_media_asset(asset_id) ON DELETE CASCADE,
+    camera_make                 TEXT,
+    camera_model                TEXT,
+    lens_model                  TEXT,
+    captured_at_local           TIMESTAMP,
+    gps_latitude                DOUBLE PRECISION CHECK (gps_latitude BETWEEN -90 AND 90),
+    gps_longitude               DOUBLE PRECISION CHECK (gps_longitude BETWEEN -180 AND 180),
+    gps_altitude_m              DOUBLE PRECISION,
+    orientation                 SMALLINT CHECK (orientation BETWEEN 1 AND 8),
+    creator                     TEXT,
+    title                       TEXT,
+    description                 TEXT,
+    keywords                    TEXT[] NOT NULL DEFAULT '{}',
+    embedded_rating             SMALLINT CHECK (embedded_rating BETWEEN 0 AND 5),
+    generator                   TEXT CHECK (generator IN ('a1111', 'comfyui')),
+    generation_prompt           TEXT,
+    generation_negative_prompt  TEXT,
+    generation_model            TEXT,
+    generation_sampler          TEXT,
+    generation_scheduler        TEXT,
+    generation_steps            INTEGER,
+    generation_cfg_scale        DOUBLE PRECISION,
+    generation_seed             BIGINT,
+    generation_json             JSONB,
+    metadata_sources            TEXT[] NOT NULL DEFAULT '{}',
+    extracted_at_utc            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((gps_latitude IS NULL) = (gps_longitude IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_atelier_media_metadata_camera
+    ON atelier_media_metadata(camera_make, camera_model);
+
+CREATE INDEX IF NOT EXISTS idx_atelier_media_metadata_captured
+    ON atelier_media_metadata(captured_at_local DESC);
+
+CREATE INDEX IF NOT EXISTS idx_atelier_media_metadata_keywords
+    ON atelier_media_metadata USING GIN (keywords);
+
+CREATE INDEX IF NOT EXISTS idx_atelier_media_metadata_generator
+    ON atelier_media_metadata(generator)
+    WHERE generator IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_atelier_media_metadata_gps
+    ON atelier_media_metadata(asset_id)
+    WHERE gps_latitude IS NOT NULL;
+
+ALTER TABLE atelier_media_sidecar
+    DROP CONSTRAINT IF EXISTS atelier_media_sidecar_relation_kind_check;
+ALTER TABLE atelier_media_sidecar
+    DROP CONSTRAINT IF EXISTS chk_atelier_media_sidecar_relation_kind;
+ALTER TABLE atelier_media_sidecar
+    ADD CONSTRAINT chk_atelier_media_sidecar_relation_kind
+    CHECK (relation_kind IN ('openpose_json', 'workflow_json', 'xmp_sidecar'));
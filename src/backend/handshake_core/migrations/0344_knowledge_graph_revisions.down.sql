@@ -0,0 +1,8 @@
+-- WP-KERNEL-009 graph analytics staleness stamp (down).
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_loom_edges ON loom_edges;
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_loom_blocks ON loom_blocks;
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_edges ON knowledge_edges;
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_entities ON knowledge_entities;
+DROP FUNCTION IF EXISTS knowledge_graph_revision_bump();
+DELETE FROM knowledge_schema_registry WHERE family_key = 'graph_revisions';
+DROP TABLE IF EXISTS knowledge_graph_revisions;
@@ -0,0 +1,76 @@
+-- WP-KERNEL-009 graph analytics staleness stamp.
+-- One counter per (workspace, graph) bumped by triggers whenever a row that
+-- shapes the analytics graph changes, so a staleness check is a primary-key
+-- lookup instead of hashing every node and edge on each retrieval
+-- (`knowledge_retrieval::graph_analytics::graph_revision`).
+--
+-- Only changes the analytics see bump the counter: node inserts and deletes,
+-- and edge inserts, deletes and endpoint / type / lifecycle updates. Content
+-- and title edits do not. No foreign key to `workspaces`: the triggers also
+-- fire while a workspace delete cascades through its graph rows, and a
+-- leftover counter row is harmless.
+
+CREATE TABLE IF NOT EXISTS knowledge_graph_revisions (
+    workspace_id TEXT NOT NULL,
+    graph_kind TEXT NOT NULL CHECK (graph_kind IN ('knowledge', 'loom')),
+    revision BIGINT NOT NULL DEFAULT 0 CHECK (revision >= 0),
+    bumped_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (workspace_id, graph_kind)
+);
+
+CREATE OR REPLACE FUNCTION knowledge_graph_revision_bump()
+RETURNS trigger
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        INSERT INTO knowledge_graph_revisions (workspace_id, graph_kind, revision)
+        VALUES (OLD.workspace_id, TG_ARGV[0], 1)
+        ON CONFLICT (workspace_id, graph_kind) DO UPDATE
+            SET revision = knowledge_graph_revisions.revision + 1,
+                bumped_at = NOW();
+    END IF;
+
+    IF TG_OP = 'INSERT'
+       OR (TG_OP = 'UPDATE' AND NEW.workspace_id IS DISTINCT FROM OLD.workspace_id) THEN
+        INSERT INTO knowledge_graph_revisions (workspace_id, graph_kind, revision)
+        VALUES (NEW.workspace_id, TG_ARGV[0], 1)
+        ON CONFLICT (workspace_id, graph_kind) DO UPDATE
+            SET revision = knowledge_graph_revisions.revision + 1,
+                bumped_at = NOW();
+    END IF;
+
+    RETURN NULL;
+END $$;
+
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_entities ON knowledge_entities;
+CREATE TRIGGER trg_knowledge_graph_revision_entities
+    AFTER INSERT OR DELETE OR UPDATE OF workspace_id, lifecycle_state
+    ON knowledge_entities
+    FOR EACH ROW EXECUTE FUNCTION knowledge_graph_revision_bump('knowledge');
+
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_edges ON knowledge_edges;
+CREATE TRIGGER trg_knowledge_graph_revision_edges
+    AFTER INSERT OR DELETE
+        OR UPDATE OF workspace_id, lifecycle_state, edge_type, source_entity_id, target_entity_id
+    ON knowledge_edges
+    FOR EACH ROW EXECUTE FUNCTION knowledge_graph_revision_bump('knowledge');
+
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_loom_blocks ON loom_blocks;
+CREATE TRIGGER trg_knowledge_graph_revision_loom_blocks
+    AFTER INSERT OR DELETE OR UPDATE OF workspace_id
+    ON loom_blocks
+    FOR EACH ROW EXECUTE FUNCTION knowledge_graph_revision_bump('loom');
+
+DROP TRIGGER IF EXISTS trg_knowledge_graph_revision_loom_edges ON loom_edges;
+CREATE TRIGGER trg_knowledge_graph_revision_loom_edges
+    AFTER INSERT OR DELETE
+        OR UPDATE OF workspace_id, edge_type, source_block_id, target_block_id
+    ON loom_edges
+    FOR EACH ROW EXECUTE FUNCTION knowledge_graph_revision_bump('loom');
+
+INSERT INTO knowledge_schema_registry
+    (family_key, table_name, record_family, authority_class, migration_file, mt_id)
+VALUES
+    ('graph_revisions', 'knowledge_graph_revisions', 'Projection',
+     'projection', '0344_knowledge_graph_revisions.sql', 'MT-132')
+ON CONFLICT (family_key) DO NOTHING;
@@ -1029,6 +1029,9 @@ pub struct RetrievalTrace {
     pub warnings: Vec<String>,
     /// Fatal errors (retrieval may have degraded)
     pub errors: Vec<String>,
+    /// Prompt-injection verdicts for the untrusted spans (§2.6.6.7.11.6)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injection_verdicts: Vec<InjectionVerdict>,
 }
 
 impl RetrievalTrace {
@@ -1052,9 +1055,19 @@ impl RetrievalTrace {
             truncation_flags: Vec::new(),
             warnings: Vec::new(),
             errors: Vec::new(),
+            injection_verdicts: Vec::new(),
         }
     }
 
+    /// Record injection verdicts. Each flagged verdict adds an
+    /// `injection:detected` warning, which PromptInjectionGuard turns into
+    /// `AceError::PromptInjectionDetected` [HSK-ACE-VAL-101].
+    pub fn record_injection_verdicts(&mut self, verdicts: Vec<InjectionVerdict>) {
+        self.warnings
+            .extend(verdicts.iter().filter_map(InjectionVerdict::trace_warning));
+        self.injection_verdicts.extend(verdicts);
+    }
+
     /// Enforce ViewMode semantics for strict SFW output (spec 11.2/11.3).
     ///
     /// - In `ViewMode::Sfw`, strict-drop any candidate/selected/span that is not `content_tier=sfw`.
@@ -1422,16 +1435,22 @@ pub use validators::{
     ContextDeterminismGuard,
     ContextPackFreshnessGuard,
     IndexDriftGuard,
+    // Untrusted content tiers and spotlighting (§2.6.6.7.11.6)
+    InjectionClassifier,
+    InjectionVerdict,
     JobBoundaryRoutingGuard,
     LocalPayloadGuard,
     MemoryPromotionGuard,
     PromptInjectionGuard,
+    PromptInjectionSettings,
     ResolvedSnippet,
     RetrievalBudgetGuard,
     SecurityValidationResult,
     SecurityViolation,
     SecurityViolationType,
     SensitivityLevel,
+    SpotlightConfig,
+    UntrustedSpan,
     ValidatorPipeline,
 };
 
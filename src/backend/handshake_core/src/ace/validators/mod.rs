@@ -19,6 +19,11 @@
 //! 12. LocalPayloadGuard - Encrypted local storage validation
 //! 13. ViewModeHardDropGuard - Enforces strict SFW hard-drop + labeling
 //!
+//! **Untrusted content (§2.6.6.7.11.6):**
+//! - `injection_classifier` - Decoded-content and model-based injection tiers
+//!   behind PromptInjectionGuard; verdicts are recorded in the RetrievalTrace
+//! - `spotlight` - Delimits, datamarks, or encodes untrusted spans for prompts
+//!
 //! **Hardened Security Enforcement (§2.6.6.7.11.0):**
 //! - [HSK-ACE-VAL-100] Content Awareness: Validators MUST resolve raw UTF-8 content
 //! - [HSK-ACE-VAL-101] Atomic Poisoning: Injection triggers JobState::Poisoned
@@ -34,10 +39,12 @@ pub mod determinism;
 pub mod drift;
 pub mod freshness;
 pub mod injection;
+pub mod injection_classifier;
 pub mod leakage;
 pub mod payload;
 pub mod promotion;
 pub mod role_registry_append_only;
+pub mod spotlight;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -1095,6 +1102,15 @@ pub use leakage::CloudLeakageGuard;
 pub use payload::LocalPayloadGuard;
 pub use promotion::MemoryPromotionGuard;
 
+// Re-exports - Untrusted content (§2.6.6.7.11.6)
+pub use injection_classifier::{
+    classify_untrusted, InjectionClassifier, InjectionClassifierError, InjectionClassifierPolicy,
+    InjectionTier, InjectionVerdict, PromptInjectionSettings, RuntimeInjectionClassifier,
+};
+pub use spotlight::{
+    spotlight, spotlight_all, SpotlightConfig, SpotlightMode, UntrustedSource, UntrustedSpan,
+};
+
 // Re-exports - Content Resolution [HSK-ACE-VAL-100]
 // StorageContentResolver and scan_content_for_security are exported at module level
 
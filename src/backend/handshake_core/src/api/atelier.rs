@@ -37,7 +37,8 @@ use crate::atelier::stealth_window::ResolvedContentRef;
 use crate::atelier::{
     AtelierStore, BulkOperationReceipt, ClipboardImageImportRequest, DeletionArchiveRequest,
     DeletionImpactPreview, DeletionImpactPreviewRequest, DeletionRestoreRequest, DeletionTargetRef,
-    ImageImportRecord, UrlImageImportRequest,
+    ImageImportRecord, MediaMetadataExtraction, MediaMetadataQuery, MediaMetadataRecord,
+    MediaSidecar, UrlImageImportRequest,
 };
 
 const HSK_HEADER_ACTOR_ID: &str = "x-hsk-actor-id";
@@ -93,6 +94,18 @@ pub fn routes(state: AppState) -> Router {
             "/atelier/ai-tag-suggestions/:suggestion_id/apply",
             post(apply_ai_tag_suggestion),
         )
+        .route(
+            "/atelier/media/metadata/search",
+            post(search_media_metadata),
+        )
+        .route(
+            "/atelier/media/:asset_id/metadata",
+            get(get_media_metadata).post(extract_media_metadata),
+        )
+        .route(
+            "/atelier/media/:asset_id/xmp-sidecar",
+            post(write_media_xmp_sidecar),
+        )
         .route("/atelier/stealth/windows", get(list_stealth_windows))
         .route(
             "/atelier/stealth/windows/:window_ref_id/refs/:ref_id",
@@ -949,6 +962,81 @@ struct StealthWindowResponse {
 
 /// GET /atelier/stealth/windows — registry entries visible to the calling actor,
 /// newest first, capped.
+async fn get_media_metadata(
+    State(state): State<AppState>,
+    Path(asset_id): Path<Uuid>,
+) -> Result<Json<MediaMetadataRecord>, (StatusCode, Json<ErrorResponse>)> {
+    let store = atelier_store(&state);
+    let record = store
+        .get_media_metadata(asset_id)
+        .await
+        .map_err(atelier_error)?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: "not_found" }),
+        ))?;
+    Ok(Json(record))
+}
+
+async fn extract_media_metadata(
+    State(state): State<AppState>,
+    Path(asset_id): Path<Uuid>,
+) -> Result<Json<MediaMetadataExtraction>, (StatusCode, Json<ErrorResponse>)> {
+    let store = atelier_store(&state);
+    let extraction = store
+        .extract_media_asset_metadata(asset_id)
+        .await
+        .map_err(atelier_error)?;
+
+    tracing::info!(
+        target: "handshake_core::atelier",
+        route = "/atelier/media/:asset_id/metadata",
+        status = "ok",
+        asset_id = %asset_id,
+        auto_tags = extraction.auto_tags.len(),
+        "extract media metadata"
+    );
+
+    Ok(Json(extraction))
+}
+
+async fn search_media_metadata(
+    State(state): State<AppState>,
+    Json(query): Json<MediaMetadataQuery>,
+) -> Result<Json<Vec<MediaMetadataRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    let store = atelier_store(&state);
+    let records = store
+        .search_media_metadata(&query)
+        .await
+        .map_err(atelier_error)?;
+    Ok(Json(records))
+}
+
+async fn write_media_xmp_sidecar(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(asset_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<MediaSidecar>), (StatusCode, Json<ErrorResponse>)> {
+    let actor = calling_actor(&headers)?;
+    let store = atelier_store(&state);
+    let sidecar = store
+        .write_media_xmp_sidecar(asset_id, &actor)
+        .await
+        .map_err(atelier_error)?;
+
+    tracing::info!(
+        target: "handshake_core::atelier",
+        route = "/atelier/media/:asset_id/xmp-sidecar",
+        status = "created",
+        asset_id = %asset_id,
+        sidecar_id = %sidecar.sidecar_id,
+        actor = %actor,
+        "write media XMP sidecar"
+    );
+
+    Ok((StatusCode::CREATED, Json(sidecar)))
+}
+
 async fn list_stealth_windows(
     State(state): State<AppState>,
     headers: HeaderMap,
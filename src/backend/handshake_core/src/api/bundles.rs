@@ -24,6 +24,8 @@ use crate::AppState;
 pub struct ExportRequest {
     pub scope: ExportScope,
     pub redaction_mode: RedactionMode,
+    #[serde(default)]
+    pub include_cassettes: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -159,6 +161,7 @@ async fn export_bundle(
         "scope": scope_value,
         "redaction_mode": request.redaction_mode,
         "include_artifacts": false,
+        "include_cassettes": request.include_cassettes,
     });
 
     let job = create_job(
@@ -11,6 +11,8 @@
 //!     Raw/Derived/Display) so the frontend renders stable blocks.
 //!   * MT-150 projection export + MT-151 import: render a document to a chosen
 //!     projection format, or import a snippet into a new document.
+//!   * Office import/export: create a document from a DOCX/ODT archive, or
+//!     export one as DOCX/ODT (images via the ArtifactStore).
 //!   * MT-152/153 embeds: list/repair the typed embed references and the
 //!     broken-embed repair queue.
 //!   * MT-154/155 search-index + backlinks: extract + persist the document's
@@ -32,11 +34,13 @@
 //! Conventions mirror `api/knowledge_memory.rs`.
 
 use axum::{
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
     http::{HeaderMap, StatusCode},
     routing::{get, post, put},
     Json, Router,
 };
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use serde::Deserialize;
 use serde_json::{json, Value};
 
@@ -45,6 +49,10 @@ use crate::knowledge_document::backlink::DocumentLinkReferences;
 use crate::knowledge_document::block_tree::BlockTree;
 use crate::knowledge_document::embed::{validate_block_embeds, ValidatedBlockEmbed};
 use crate::knowledge_document::import::{import_snippet, ImportFormat};
+use crate::knowledge_document::office::{
+    export_office_document, import_office_document, ArtifactStoreOfficeAssets, OfficeDocumentError,
+    OfficeFormat,
+};
 use crate::knowledge_document::permission::{
     DocumentAction, DocumentActorKind, DocumentPermission,
 };
@@ -65,10 +73,19 @@ const HSK_HEADER_KERNEL_TASK_RUN_ID: &str = "x-hsk-kernel-task-run-id";
 const HSK_HEADER_SESSION_RUN_ID: &str = "x-hsk-session-run-id";
 const HSK_HEADER_CORRELATION_ID: &str = "x-hsk-correlation-id";
 
+/// Request-body cap for an office import: the compressed archive as uploaded.
+/// Decompressed reads are bounded separately by the importer.
+const MAX_OFFICE_IMPORT_BYTES: usize = 64 * 1024 * 1024;
+
 pub fn routes(state: AppState) -> Router {
     Router::new()
         .route("/knowledge/documents", post(create_document))
         .route("/knowledge/documents/import", post(import_document))
+        .route(
+            "/knowledge/documents/import/office",
+            post(import_office_document_handler)
+                .layer(DefaultBodyLimit::max(MAX_OFFICE_IMPORT_BYTES)),
+        )
         .route("/knowledge/documents/:document_id", get(load_document))
         .route(
             "/knowledge/documents/:document_id/draft",
@@ -90,6 +107,10 @@ pub fn routes(state: AppState) -> Router {
             "/knowledge/documents/:document_id/projection",
             get(export_projection),
         )
+        .route(
+            "/knowledge/documents/:document_id/export/office",
+            get(export_office_document_handler),
+        )
         .route("/knowledge/documents/:document_id/embeds", get(list_embeds))
         .route(
             "/knowledge/documents/:document_id/embeds/broken",
@@ -520,6 +541,19 @@ struct ProjectionParams {
     format: String,
 }
 
+/// Query half of an office import; the archive itself is the raw request body.
+#[derive(Debug, Deserialize)]
+struct ImportOfficeDocumentParams {
+    workspace_id: String,
+    title: String,
+    format: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OfficeExportParams {
+    format: String,
+}
+
 /// Pagination for the history list (adversarial-v2 MT-156). Defaults bound the
 /// response even when the caller passes nothing.
 #[derive(Debug, Deserialize)]
@@ -1268,6 +1302,124 @@ async fn import_document(
     })))
 }
 
+/// POST /knowledge/documents/import/office?workspace_id=&title=&format= —
+/// import a DOCX/ODT archive (the raw request body) into a new document.
+/// Embedded images are written to the ArtifactStore and land in the tree as
+/// artifact embeds; unsupported features come back as warnings. If the import
+/// or the document create fails, the images already written are removed.
+async fn import_office_document_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ImportOfficeDocumentParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let ctx = doc_context(&headers)?;
+    ctx.require(DocumentAction::Write)?;
+    let db = db_for(&state);
+
+    let format = parse_office_format(&params.format)?;
+    let mut assets = office_asset_store()?;
+    // Archive parsing and the artifact writes are blocking work.
+    let (outcome, assets) = tokio::task::spawn_blocking(move || {
+        let outcome = import_office_document(&body, format, &mut assets);
+        (outcome, assets)
+    })
+    .await
+    .map_err(office_task_error)?;
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            discard_office_assets(&assets).await;
+            return Err(office_error(err));
+        }
+    };
+
+    let created = db
+        .create_knowledge_rich_document(NewKnowledgeRichDocument {
+            workspace_id: params.workspace_id,
+            document_id: None,
+            title: params.title,
+            schema_version: crate::knowledge_document::block_tree::DOCUMENT_SCHEMA_VERSION
+                .to_string(),
+            content_json: outcome.document_json.clone(),
+            crdt_document_id: None,
+            crdt_snapshot_id: None,
+            promotion_receipt_event_id: None,
+            project_ref: None,
+            folder_ref: None,
+            authority_label: Some("promoted".to_string()),
+            owner_actor_kind: Some(ctx.actor_kind.as_str().to_string()),
+            owner_actor_id: Some(actor_id_of(&ctx.actor)),
+        })
+        .await;
+    let created = match created {
+        Ok(created) => created,
+        Err(err) => {
+            discard_office_assets(&assets).await;
+            return Err(storage_error(err));
+        }
+    };
+
+    let (receipt, receipt_error) = record_receipt_non_fatal(
+        &db,
+        &ctx,
+        KernelEventType::KnowledgeRichDocumentSaved,
+        &created.rich_document_id,
+        json!({"event": "imported", "format": format.as_str(), "warnings": outcome.warnings.len()}),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "document": created,
+        "warnings": outcome.warnings,
+        "save_receipt_event_id": receipt,
+        "receipt_error": receipt_error,
+    })))
+}
+
+/// GET /knowledge/documents/:document_id/export/office?format= — render the
+/// document as a DOCX/ODT archive. Like every projection, exporting NEVER
+/// mutates authority.
+async fn export_office_document_handler(
+    State(state): State<AppState>,
+    Path(document_id): Path<String>,
+    Query(params): Query<OfficeExportParams>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let ctx = doc_context(&headers)?;
+    ctx.require(DocumentAction::Read)?;
+    let db = db_for(&state);
+
+    let format = parse_office_format(&params.format)?;
+    let document = db
+        .get_knowledge_rich_document(&document_id)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(|| not_found("knowledge rich document"))?;
+    let tree = BlockTree::from_document_json(
+        &document.rich_document_id,
+        &document.schema_version,
+        &document.content_json,
+    )
+    .map_err(|err| bad_request(format!("block tree: {err}")))?;
+    let assets = office_asset_store()?;
+    let title = document.title.clone();
+    // Reading the embedded images back is blocking file I/O.
+    let export =
+        tokio::task::spawn_blocking(move || export_office_document(&title, &tree, format, &assets))
+            .await
+            .map_err(office_task_error)?
+            .map_err(office_error)?;
+
+    Ok(Json(json!({
+        "rich_document_id": document.rich_document_id,
+        "format": format.as_str(),
+        "mime": format.mime(),
+        "content_base64": STANDARD.encode(&export.bytes),
+        "warnings": export.warnings,
+    })))
+}
+
 /// GET /knowledge/documents/:document_id/embeds — typed embed refs (MT-152).
 async fn list_embeds(
     State(state): State<AppState>,
@@ -1651,6 +1803,61 @@ fn parse_import_format(value: &str) -> Result<ImportFormat, ApiError> {
     })
 }
 
+fn parse_office_format(value: &str) -> Result<OfficeFormat, ApiError> {
+    Ok(match value {
+        "docx" => OfficeFormat::Docx,
+        "odt" => OfficeFormat::Odt,
+        other => return Err(bad_request(format!("unknown office format '{other}'"))),
+    })
+}
+
+fn office_asset_store() -> Result<ArtifactStoreOfficeAssets, ApiError> {
+    crate::storage::artifacts::resolve_workspace_root()
+        .map(ArtifactStoreOfficeAssets::new)
+        .map_err(|err| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "artifact_store_unavailable", "detail": err.to_string()})),
+            )
+        })
+}
+
+/// Unreadable archives are caller errors; asset-store failures are ours.
+fn office_error(err: OfficeDocumentError) -> ApiError {
+    match err {
+        OfficeDocumentError::Asset(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "office_asset_store_failed", "detail": err.to_string()})),
+        ),
+        other => bad_request(other.to_string()),
+    }
+}
+
+/// A panicked or cancelled blocking import/export task.
+fn office_task_error(err: tokio::task::JoinError) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "office_task_failed", "detail": err.to_string()})),
+    )
+}
+
+/// Remove the artifacts a failed import already wrote; nothing references
+/// them. Best effort: a leftover is logged, the import error still wins.
+async fn discard_office_assets(assets: &ArtifactStoreOfficeAssets) {
+    for dir in assets.stored_artifact_dirs() {
+        match tokio::fs::remove_dir_all(&dir).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => tracing::warn!(
+                target: "handshake_core::knowledge_documents_api",
+                error = %err,
+                path = %dir.display(),
+                "office_import_asset_cleanup_failed"
+            ),
+        }
+    }
+}
+
 /// Extract the actor id string out of a KernelActor for the document owner.
 fn actor_id_of(actor: &KernelActor) -> String {
     match actor {
@@ -34,10 +34,15 @@
 //!   touching an entity (graph neighborhood), with evidence span ids
 //! * `GET /knowledge/memory/visual-debug?workspace_id=&trusted_only=&limit=` —
 //!   the MT-127 memory-graph visual-debug payload
+//! * `GET /knowledge/memory/export?workspace_id=&format=` (optional
+//!   `edge_types=`, `entity_kinds=`, `seeds=` comma lists, `depth=`,
+//!   `include_retired=`) — the redacted knowledge graph as GraphML, JSON-LD,
+//!   or Turtle; the receipt id and redaction count ride in response headers
 
 use axum::{
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
@@ -45,6 +50,9 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 
 use crate::kernel::{KernelActor, KernelEventType, NewKernelEvent};
+use crate::knowledge_memory::export::{
+    export_knowledge_graph, GraphExportFilter, GraphExportFormat, DEFAULT_SUBGRAPH_DEPTH,
+};
 use crate::knowledge_memory::visual_debug::build_memory_graph_visual_debug;
 use crate::storage::knowledge::KnowledgeStore;
 use crate::storage::knowledge_memory::{get_memory_fact, get_memory_fact_by_claim};
@@ -57,6 +65,8 @@ const HSK_HEADER_ACTOR_ID: &str = "x-hsk-actor-id";
 const HSK_HEADER_KERNEL_TASK_RUN_ID: &str = "x-hsk-kernel-task-run-id";
 const HSK_HEADER_SESSION_RUN_ID: &str = "x-hsk-session-run-id";
 const HSK_HEADER_CORRELATION_ID: &str = "x-hsk-correlation-id";
+const HSK_HEADER_RETRIEVAL_RECEIPT: &str = "x-hsk-retrieval-receipt-event-id";
+const HSK_HEADER_REDACTION_FINDINGS: &str = "x-hsk-redaction-findings";
 
 /// Bound on list reads so a single nav query cannot pull an unbounded result.
 const LIST_CAP: i64 = 500;
@@ -74,6 +84,7 @@ pub fn routes(state: AppState) -> Router {
             get(entity_neighborhood),
         )
         .route("/knowledge/memory/visual-debug", get(visual_debug))
+        .route("/knowledge/memory/export", get(export_graph))
         .with_state(state)
 }
 
@@ -222,6 +233,32 @@ struct VisualDebugParams {
     limit: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ExportParams {
+    workspace_id: String,
+    format: String,
+    #[serde(default)]
+    edge_types: Option<String>,
+    #[serde(default)]
+    entity_kinds: Option<String>,
+    #[serde(default)]
+    seeds: Option<String>,
+    #[serde(default)]
+    depth: Option<u32>,
+    #[serde(default)]
+    include_retired: Option<bool>,
+}
+
+fn comma_set(value: Option<&str>) -> std::collections::BTreeSet<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
 fn clamp_limit(requested: Option<i64>) -> i64 {
     requested.unwrap_or(LIST_CAP).clamp(1, LIST_CAP)
 }
@@ -435,3 +472,64 @@ async fn visual_debug(
         "retrieval_receipt_event_id": receipt,
     })))
 }
+
+/// GET /knowledge/memory/export
+async fn export_graph(
+    State(state): State<AppState>,
+    Query(params): Query<ExportParams>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let ctx = nav_context(&headers)?;
+    let db = db_for(&state);
+    let format = GraphExportFormat::parse(&params.format)
+        .ok_or_else(|| bad_request("format must be one of graphml, jsonld, turtle"))?;
+    let filter = GraphExportFilter {
+        edge_types: comma_set(params.edge_types.as_deref()),
+        entity_kinds: comma_set(params.entity_kinds.as_deref()),
+        seeds: comma_set(params.seeds.as_deref()),
+        depth: params.depth.unwrap_or(DEFAULT_SUBGRAPH_DEPTH),
+        include_retired: params.include_retired.unwrap_or(false),
+    };
+
+    let export = export_knowledge_graph(
+        &db,
+        &state.postgres_pool,
+        &params.workspace_id,
+        format,
+        &filter,
+    )
+    .await
+    .map_err(storage_error)?;
+
+    let manifest = &export.manifest;
+    let receipt = record_nav_receipt(
+        &db,
+        &ctx,
+        "graph_export",
+        json!({
+            "workspace_id": params.workspace_id,
+            "format": format.as_str(),
+            "filter": manifest.filter,
+            "entity_count": manifest.entity_count,
+            "edge_count": manifest.edge_count,
+            "claim_count": manifest.claim_count,
+            "fact_count": manifest.fact_count,
+            "redaction": manifest.redaction,
+        }),
+    )
+    .await?;
+
+    let disposition = format!("attachment; filename=\"{}\"", export.file_name());
+    let findings = manifest.redaction.finding_count();
+    let mut response =
+        ([(header::CONTENT_TYPE, export.content_type())], export.body).into_response();
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        response_headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&receipt) {
+        response_headers.insert(HSK_HEADER_RETRIEVAL_RECEIPT, value);
+    }
+    response_headers.insert(HSK_HEADER_REDACTION_FINDINGS, HeaderValue::from(findings));
+    Ok(response)
+}
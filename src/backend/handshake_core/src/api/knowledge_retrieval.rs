@@ -35,6 +35,13 @@
 //!   to the stale one.
 //! * `GET /knowledge/retrieval/catalog?workspace_id=&limit=` — the active
 //!   SemanticCatalog routing contracts.
+//! * `GET /knowledge/retrieval/graph-analytics?workspace_id=&limit=` — the
+//!   knowledge graph's analytics projection (centrality, communities, bridges,
+//!   orphans, dead ends) with its staleness verdict.
+//! * `POST /knowledge/retrieval/graph-analytics/refresh` — recompute that
+//!   projection over the current edge graph.
+//! * `GET /knowledge/retrieval/graph-analytics/path?workspace_id=&from=&to=&max_hops=`
+//!   — the shortest "how is A connected to B" path between two entities.
 
 use std::collections::BTreeSet;
 
@@ -50,9 +57,14 @@ use serde_json::{json, Value};
 use crate::kernel::{KernelActor, KernelEventType, NewKernelEvent};
 use crate::knowledge_retrieval::ai_ready_export::build_evidence_manifest;
 use crate::knowledge_retrieval::compiler::BundleTargetKind;
-use crate::knowledge_retrieval::executor::execute_retrieval;
+use crate::knowledge_retrieval::executor::execute_retrieval_with_rerank;
+use crate::knowledge_retrieval::graph_analytics::{
+    explain_connection_in_workspace, graph_analytics_status, refresh_graph_analytics,
+    AnalyticsConfig, AnalyticsGraphKind, DEFAULT_EXPLAIN_HOPS,
+};
 use crate::knowledge_retrieval::graph_planner::GraphTraversalPolicy;
 use crate::knowledge_retrieval::planner::RetrievalRequest;
+use crate::knowledge_retrieval::rerank::{local_reranker, CrossEncoder};
 use crate::storage::knowledge::{KnowledgeBundleItemRefKind, KnowledgeStore};
 use crate::storage::knowledge_retrieval::list_semantic_catalog_entries;
 use crate::storage::postgres::PostgresDatabase;
@@ -87,6 +99,15 @@ pub fn routes(state: AppState) -> Router {
             post(repair_bundle),
         )
         .route("/knowledge/retrieval/catalog", get(list_catalog))
+        .route("/knowledge/retrieval/graph-analytics", get(graph_analytics))
+        .route(
+            "/knowledge/retrieval/graph-analytics/refresh",
+            post(refresh_analytics),
+        )
+        .route(
+            "/knowledge/retrieval/graph-analytics/path",
+            get(explain_entity_connection),
+        )
         .with_state(state)
 }
 
@@ -460,7 +481,8 @@ async fn repair_bundle(
     // Re-execute the recorded query against CURRENT index state.
     let mut request = RetrievalRequest::discovery(&trace_row.workspace_id, &query_text);
     request.graph_neighborhood_expected = recorded_mode == "graph_traversal";
-    let executed = execute_retrieval(
+    let reranker = local_reranker();
+    let executed = execute_retrieval_with_rerank(
         &db,
         &state.postgres_pool,
         &ctx.kernel_task_run_id,
@@ -470,6 +492,9 @@ async fn repair_bundle(
         &request,
         &BTreeSet::new(),
         GraphTraversalPolicy::default(),
+        reranker
+            .as_ref()
+            .map(|reranker| reranker as &dyn CrossEncoder),
     )
     .await
     .map_err(storage_error)?;
@@ -530,3 +555,158 @@ async fn list_catalog(
         "retrieval_receipt_event_id": receipt,
     })))
 }
+
+#[derive(Debug, Deserialize)]
+struct GraphAnalyticsParams {
+    workspace_id: String,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+/// GET /knowledge/retrieval/graph-analytics?workspace_id=&limit=
+///
+/// The knowledge graph analytics projection, every list bounded by `limit`,
+/// plus `stale` (the edge graph changed since it was computed). A stale
+/// projection is served with the flag set, never silently; retrieval ranking
+/// ignores its hub scores until it is refreshed.
+async fn graph_analytics(
+    State(state): State<AppState>,
+    Query(params): Query<GraphAnalyticsParams>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let ctx = nav_context(&headers)?;
+    let db = db_for(&state);
+    let limit = clamp_limit(params.limit) as usize;
+
+    let status = graph_analytics_status(
+        &state.postgres_pool,
+        &params.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+    )
+    .await
+    .map_err(storage_error)?
+    .ok_or_else(|| not_found("graph analytics have not been computed for this workspace"))?;
+
+    let receipt = record_nav_receipt(
+        &db,
+        &ctx,
+        "graph_analytics",
+        json!({"workspace_id": params.workspace_id}),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "projection_id": status.projection.projection_id,
+        "workspace_id": params.workspace_id,
+        "computed_at": status.projection.computed_at,
+        "staleness_hash": status.projection.staleness_hash,
+        "current_hash": status.current_hash,
+        "stale": status.stale,
+        "report": status.projection.report.bounded(limit),
+        "retrieval_receipt_event_id": receipt,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct RefreshAnalyticsBody {
+    workspace_id: String,
+}
+
+/// POST /knowledge/retrieval/graph-analytics/refresh
+///
+/// Recompute the projection over the current active edge graph, replacing
+/// the stored one.
+async fn refresh_analytics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<RefreshAnalyticsBody>,
+) -> Result<Json<Value>, ApiError> {
+    let ctx = nav_context(&headers)?;
+    let db = db_for(&state);
+
+    let projection = refresh_graph_analytics(
+        &state.postgres_pool,
+        &body.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &AnalyticsConfig::default(),
+    )
+    .await
+    .map_err(storage_error)?;
+
+    let receipt = record_nav_receipt(
+        &db,
+        &ctx,
+        "refresh_graph_analytics",
+        json!({
+            "workspace_id": body.workspace_id,
+            "projection_id": projection.projection_id,
+        }),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "projection_id": projection.projection_id,
+        "workspace_id": body.workspace_id,
+        "computed_at": projection.computed_at,
+        "staleness_hash": projection.staleness_hash,
+        "node_count": projection.report.node_count,
+        "edge_count": projection.report.edge_count,
+        "community_count": projection.report.communities.len(),
+        "retrieval_receipt_event_id": receipt,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectionParams {
+    workspace_id: String,
+    from: String,
+    to: String,
+    #[serde(default)]
+    max_hops: Option<u32>,
+}
+
+/// GET /knowledge/retrieval/graph-analytics/path?workspace_id=&from=&to=&max_hops=
+///
+/// The shortest path between two entities over active edges, walked in
+/// either direction, with one readable summary line. `connected: false` when
+/// no path exists within `max_hops`.
+async fn explain_entity_connection(
+    State(state): State<AppState>,
+    Query(params): Query<ConnectionParams>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let ctx = nav_context(&headers)?;
+    let db = db_for(&state);
+
+    let explanation = explain_connection_in_workspace(
+        &state.postgres_pool,
+        &params.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &params.from,
+        &params.to,
+        params.max_hops.unwrap_or(DEFAULT_EXPLAIN_HOPS),
+    )
+    .await
+    .map_err(storage_error)?;
+
+    let receipt = record_nav_receipt(
+        &db,
+        &ctx,
+        "explain_entity_connection",
+        json!({
+            "workspace_id": params.workspace_id,
+            "from": params.from,
+            "to": params.to,
+        }),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "workspace_id": params.workspace_id,
+        "from": params.from,
+        "to": params.to,
+        "connected": explanation.is_some(),
+        "path": explanation,
+        "retrieval_receipt_event_id": receipt,
+    })))
+}
@@ -15,7 +15,7 @@ use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
     http::{header, HeaderValue, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
     routing::{delete, get, patch, post, put},
     Json, Router,
 };
@@ -165,6 +165,10 @@ pub fn routes(state: AppState) -> Router {
             "/workspaces/:workspace_id/loom/wiki/fanout",
             post(project_wiki_fanout),
         )
+        .route(
+            "/workspaces/:workspace_id/loom/wiki/publish",
+            post(publish_project_wiki_site),
+        )
         .route(
             "/workspaces/:workspace_id/loom/wiki/:projection_id",
             get(get_loom_wiki_projection).delete(delete_loom_wiki_projection),
@@ -190,6 +194,20 @@ pub fn routes(state: AppState) -> Router {
             "/workspaces/:workspace_id/loom/import/markdown",
             post(import_markdown_to_loom),
         )
+        // Obsidian / Logseq vault import (re-import updates in place)
+        .route(
+            "/workspaces/:workspace_id/loom/import/vault",
+            get(list_loom_vault_imports).post(import_vault_to_loom),
+        )
+        .route(
+            "/workspaces/:workspace_id/loom/import/vault/:import_id",
+            get(get_loom_vault_import),
+        )
+        // Loom -> markdown vault export (round-trips through the import above)
+        .route(
+            "/workspaces/:workspace_id/loom/export/vault",
+            post(export_loom_vault),
+        )
         // MT-182: tag hubs (tags as first-class blocks) + nested tags
         .route(
             "/workspaces/:workspace_id/loom/tags",
@@ -270,6 +288,20 @@ pub fn routes(state: AppState) -> Router {
             "/workspaces/:workspace_id/loom/graph/global",
             get(global_loom_graph),
         )
+        // Whole-graph analytics projection (centrality, communities, bridges,
+        // orphans, dead ends) and "how is A connected to B" paths.
+        .route(
+            "/workspaces/:workspace_id/loom/graph/analytics",
+            get(get_loom_graph_analytics),
+        )
+        .route(
+            "/workspaces/:workspace_id/loom/graph/analytics/recompute",
+            post(recompute_loom_graph_analytics),
+        )
+        .route(
+            "/workspaces/:workspace_id/loom/graph/path",
+            get(explain_loom_graph_path),
+        )
         .route(
             "/workspaces/:workspace_id/loom/metrics/recompute",
             post(recompute_all_loom_metrics),
@@ -1152,6 +1184,76 @@ async fn project_wiki_fanout(
     Ok(Json(outcome))
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct WikiPublishRequest {
+    /// `SAFE_DEFAULT` (default) | `WORKSPACE` | `FULL_LOCAL`.
+    #[serde(default)]
+    redaction_mode: Option<crate::bundles::schemas::RedactionMode>,
+    #[serde(default)]
+    site_title: Option<String>,
+}
+
+/// POST /workspaces/:ws/loom/wiki/publish — render the compiled wiki as a
+/// self-contained static HTML site (redacted, verdict-badged) and return it
+/// as a zip. `manifest.json` inside carries the per-file hashes; the site
+/// digest and the publish receipt also ride response headers.
+async fn publish_project_wiki_site(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    headers: axum::http::HeaderMap,
+    payload: Option<Json<WikiPublishRequest>>,
+) -> ApiResult<Response> {
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let request = payload.map(|Json(p)| p).unwrap_or_default();
+    let ctx = wiki_compile_context(&headers);
+    let mut options = crate::knowledge_wiki::publish::WikiPublishOptions::default();
+    if let Some(mode) = request.redaction_mode {
+        options.redaction_mode = mode;
+    }
+    if let Some(title) = request.site_title.filter(|t| !t.trim().is_empty()) {
+        options.site_title = title;
+    }
+    let publisher = crate::knowledge_wiki::publish::WikiSitePublisher::new(wiki_pg(&state));
+    let outcome = publisher
+        .publish(&ctx, &workspace_id, &options)
+        .await
+        .map_err(map_wiki_error)?;
+    let site = &outcome.site;
+    let bytes = site.to_zip_bytes().map_err(internal_error)?;
+
+    let event = FlightRecorderEvent::new(
+        FlightRecorderEventType::LoomProjectionRebuilt,
+        FlightRecorderActor::Human,
+        Uuid::now_v7(),
+        json!({
+            "type": "loom_projection_rebuilt",
+            "workspace_id": workspace_id,
+            "operation": "wiki_publish",
+            "pages": site.manifest.page_count,
+            "site_sha256": site.manifest.site_sha256,
+        }),
+    )
+    .with_wsids(vec![workspace_id.clone()]);
+    let _ = state.flight_recorder.record_event(event).await;
+
+    let mut response = Response::new(axum::body::Body::from(bytes));
+    *response.status_mut() = StatusCode::OK;
+    let h = response.headers_mut();
+    h.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", site.file_name()))
+    {
+        h.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&site.manifest.site_sha256) {
+        h.insert("x-hsk-wiki-site-sha256", value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&outcome.receipt_event_id) {
+        h.insert("x-hsk-receipt-event-id", value);
+    }
+    Ok(response)
+}
+
 async fn delete_loom_wiki_projection(
     State(state): State<AppState>,
     Path((workspace_id, projection_id)): Path<(String, String)>,
@@ -1256,6 +1358,244 @@ async fn import_markdown_to_loom(
     Ok(Json(imported))
 }
 
+// -- Vault import handlers -------------------------------------------------
+
+fn map_vault_error(err: crate::loom_vault::LoomVaultError) -> ApiError {
+    use crate::loom_vault::LoomVaultError;
+    match err {
+        LoomVaultError::Validation(_) => bad_request("HSK-400-LOOM-VALIDATION"),
+        LoomVaultError::NoteCapExceeded(_) => bad_request("HSK-400-LOOM-VAULT-NOTE-CAP"),
+        LoomVaultError::Io { .. } => bad_request("HSK-400-LOOM-VAULT-IO"),
+        LoomVaultError::Storage(inner) => map_storage_error(inner),
+        LoomVaultError::Artifact(inner) => internal_error(inner),
+        LoomVaultError::Task(inner) => internal_error(inner),
+    }
+}
+
+/// Directories a vault may be imported from: the workspace root, plus the
+/// optional `HANDSHAKE_VAULT_IMPORT_ROOT`.
+fn vault_import_roots() -> ApiResult<Vec<std::path::PathBuf>> {
+    let mut roots =
+        vec![crate::storage::artifacts::resolve_workspace_root().map_err(internal_error)?];
+    if let Ok(root) = std::env::var(crate::loom_vault::import::VAULT_IMPORT_ROOT_ENV) {
+        if !root.trim().is_empty() {
+            roots.push(std::path::PathBuf::from(root.trim()));
+        }
+    }
+    Ok(roots)
+}
+
+/// POST /workspaces/:ws/loom/import/vault — import an Obsidian / Logseq vault
+/// directory below the workspace root or the vault import root. Returns the import report (unresolved links included) and
+/// queues preview generation for newly created attachment blocks.
+async fn import_vault_to_loom(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    Json(payload): Json<crate::loom_vault::import::LoomVaultImportRequest>,
+) -> ApiResult<Json<crate::loom_vault::import::LoomVaultImportReport>> {
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let ctx = WriteContext::human(None);
+    let importer =
+        crate::loom_vault::import::LoomVaultImporter::new(wiki_pg(&state), vault_import_roots()?);
+    let report = importer
+        .import(&ctx, &workspace_id, &payload)
+        .await
+        .map_err(map_vault_error)?;
+
+    for attachment in report.attachments.iter().filter(|a| a.created) {
+        let capability_profile_id = state
+            .capability_registry
+            .profile_for_job_request(
+                crate::storage::JobKind::LoomPreviewGenerate.as_str(),
+                "hsk.loom.preview_generate@v1",
+            )
+            .map_err(|e| internal_error(e))?;
+        let job = crate::jobs::create_job(
+            &state,
+            crate::storage::JobKind::LoomPreviewGenerate,
+            "hsk.loom.preview_generate@v1",
+            capability_profile_id.id.as_str(),
+            Some(json!({
+                "workspace_id": workspace_id.clone(),
+                "block_id": attachment.block_id.clone(),
+                "asset_id": attachment.asset_id.clone(),
+                "content_hash": attachment.content_hash.clone(),
+                "requested_tier": 1,
+            })),
+            Vec::new(),
+        )
+        .await
+        .map_err(|e| internal_error(e))?;
+        let _ = crate::workflows::start_workflow_for_job(&state, job).await;
+    }
+
+    let event = FlightRecorderEvent::new(
+        FlightRecorderEventType::LoomBlockCreated,
+        FlightRecorderActor::Human,
+        Uuid::now_v7(),
+        json!({
+            "type": "loom_vault_imported",
+            "workspace_id": workspace_id,
+            "import_id": report.import_id,
+            "vault_name": report.vault_name,
+            "vault_kind": report.vault_kind.as_str(),
+            "notes_created": report.counts.notes_created,
+            "notes_updated": report.counts.notes_updated,
+            "attachments_created": report.counts.attachments_created,
+            "unresolved_links": report.unresolved_links.len(),
+        }),
+    )
+    .with_wsids(vec![workspace_id]);
+    let _ = state.flight_recorder.record_event(event).await;
+
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct VaultImportListQuery {
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+async fn list_loom_vault_imports(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<VaultImportListQuery>,
+) -> ApiResult<Json<Vec<crate::storage::loom_vault::LoomVaultImportRecord>>> {
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let imports = crate::storage::loom_vault::list_loom_vault_imports(
+        &state.postgres_pool,
+        &workspace_id,
+        query.limit.unwrap_or(50),
+    )
+    .await
+    .map_err(map_storage_error)?;
+    Ok(Json(imports))
+}
+
+async fn get_loom_vault_import(
+    State(state): State<AppState>,
+    Path((workspace_id, import_id)): Path<(String, String)>,
+) -> ApiResult<Json<crate::storage::loom_vault::LoomVaultImportRecord>> {
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    crate::storage::loom_vault::get_loom_vault_import(
+        &state.postgres_pool,
+        &workspace_id,
+        &import_id,
+    )
+    .await
+    .map_err(map_storage_error)?
+    .map(Json)
+    .ok_or_else(|| not_found("loom_vault_import_not_found"))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct VaultExportBody {
+    #[serde(default)]
+    folder_id: Option<String>,
+    /// Write the vault into this server-local directory and return the
+    /// manifest instead of a zip. Relative to the workspace root; must stay
+    /// below it or below `HANDSHAKE_VAULT_EXPORT_ROOT`.
+    #[serde(default)]
+    output_path: Option<std::path::PathBuf>,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+/// Directories a vault export may be written under: the workspace root, plus
+/// the optional `HANDSHAKE_VAULT_EXPORT_ROOT`.
+fn vault_export_roots() -> ApiResult<Vec<std::path::PathBuf>> {
+    let mut roots =
+        vec![crate::storage::artifacts::resolve_workspace_root().map_err(internal_error)?];
+    if let Ok(root) = std::env::var(crate::loom_vault::export::VAULT_EXPORT_ROOT_ENV) {
+        if !root.trim().is_empty() {
+            roots.push(std::path::PathBuf::from(root.trim()));
+        }
+    }
+    Ok(roots)
+}
+
+/// POST /workspaces/:ws/loom/export/vault — export Loom (or one folder
+/// subtree) as an Obsidian-compatible markdown vault: a zip by default, or
+/// written to `output_path` (below the workspace root or the vault export
+/// root) with the manifest returned. Each note carries `hsk_block_id` so a
+/// later vault import updates the same blocks.
+async fn export_loom_vault(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    payload: Option<Json<VaultExportBody>>,
+) -> ApiResult<Response> {
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let body = payload.map(|Json(p)| p).unwrap_or_default();
+    let exporter = crate::loom_vault::export::LoomVaultExporter::new(wiki_pg(&state));
+    let request = crate::loom_vault::export::LoomVaultExportRequest {
+        folder_id: body.folder_id,
+    };
+    let vault = exporter
+        .export(&workspace_id, &request)
+        .await
+        .map_err(map_vault_error)?;
+    // Writing (or zipping) the vault is blocking file / compression work.
+    let (vault, zip) = match body.output_path.clone() {
+        Some(output_path) => {
+            let roots = vault_export_roots()?;
+            let overwrite = body.overwrite;
+            tokio::task::spawn_blocking(move || {
+                let dir = crate::loom_vault::export::resolve_export_dir(&output_path, &roots)?;
+                vault.write_to_dir(&dir, overwrite)?;
+                Ok::<_, crate::loom_vault::LoomVaultError>((vault, None))
+            })
+            .await
+            .map_err(internal_error)?
+            .map_err(map_vault_error)?
+        }
+        None => tokio::task::spawn_blocking(move || {
+            let bytes = vault.to_zip_bytes();
+            (vault, Some(bytes))
+        })
+        .await
+        .map_err(internal_error)?,
+    };
+
+    let counts = &vault.manifest.counts;
+    let event = FlightRecorderEvent::new(
+        FlightRecorderEventType::LoomVaultExported,
+        FlightRecorderActor::Human,
+        Uuid::now_v7(),
+        json!({
+            "type": "loom_vault_exported",
+            "workspace_id": workspace_id,
+            "folder_id": vault.manifest.folder_id,
+            "notes": counts.notes,
+            "journals": counts.journals,
+            "attachments": counts.attachments,
+            "skipped": vault.manifest.skipped.len(),
+            "written_to_path": body.output_path.is_some(),
+        }),
+    )
+    .with_wsids(vec![workspace_id.clone()]);
+    let _ = state.flight_recorder.record_event(event).await;
+
+    let Some(zip) = zip else {
+        return Ok(Json(vault.manifest).into_response());
+    };
+    let bytes = zip.map_err(internal_error)?;
+    let mut response = Response::new(axum::body::Body::from(bytes));
+    *response.status_mut() = StatusCode::OK;
+    let h = response.headers_mut();
+    h.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", vault.file_name()))
+    {
+        h.insert(header::CONTENT_DISPOSITION, value);
+    }
+    h.insert(
+        "x-hsk-vault-export-files",
+        HeaderValue::from(vault.manifest.files.len()),
+    );
+    Ok(response)
+}
+
 // -- MT-181 FolderTreeAndColorLabels handlers ------------------------------
 
 #[derive(Debug, Deserialize)]
@@ -1351,7 +1691,23 @@ async fn delete_loom_folder(
         .delete_loom_folder(&workspace_id, &folder_id)
         .await
         .map_err(map_storage_error)?;
-    Ok(Json(json!({ "status": "deleted" })))
+
+    let event = FlightRecorderEvent::new(
+        FlightRecorderEventType::LoomFolderDeleted,
+        FlightRecorderActor::Human,
+        Uuid::now_v7(),
+        json!({
+            "type": "loom_folder_deleted",
+            "folder_id": folder_id,
+            "workspace_id": workspace_id,
+        }),
+    )
+    .with_wsids(vec![workspace_id.clone()]);
+    let receipt_event_id = record_receipt(&state, event).await;
+
+    Ok(Json(
+        json!({ "status": "deleted", "receipt_event_id": receipt_event_id }),
+    ))
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -1665,9 +2021,31 @@ async fn delete_loom_block(
         }),
     )
     .with_wsids(vec![existing.workspace_id]);
-    let _ = state.flight_recorder.record_event(event).await;
+    let receipt_event_id = record_receipt(&state, event).await;
 
-    Ok(Json(json!({ "status": "deleted" })))
+    Ok(Json(json!({ "status": "deleted", "receipt_event_id": receipt_event_id })))
+}
+
+/// Edge create/delete response: the edge plus the Flight Recorder event that receipts the change.
+///
+/// `receipt_event_id` is `None` when the recorder rejected the event; the mutation itself has
+/// already committed, so the client still gets the edge back.
+#[derive(Debug, Serialize)]
+struct LoomEdgeMutationResponse {
+    #[serde(flatten)]
+    edge: LoomEdge,
+    receipt_event_id: Option<String>,
+}
+
+/// Record `event` and return its id as the mutation receipt, or `None` if recording failed.
+async fn record_receipt(state: &AppState, event: FlightRecorderEvent) -> Option<String> {
+    let event_id = event.event_id.to_string();
+    state
+        .flight_recorder
+        .record_event(event)
+        .await
+        .ok()
+        .map(|()| event_id)
 }
 
 #[derive(Debug, Deserialize)]
@@ -1750,7 +2128,7 @@ async fn create_loom_edge(
     State(state): State<AppState>,
     Path(workspace_id): Path<String>,
     Json(payload): Json<CreateLoomEdgeRequest>,
-) -> ApiResult<Json<LoomEdge>> {
+) -> ApiResult<Json<LoomEdgeMutationResponse>> {
     ensure_workspace_exists(&state, &workspace_id).await?;
 
     state
@@ -1822,15 +2200,18 @@ async fn create_loom_edge(
         edge_event,
     )
     .with_wsids(vec![workspace_id]);
-    let _ = state.flight_recorder.record_event(event).await;
+    let receipt_event_id = record_receipt(&state, event).await;
 
-    Ok(Json(edge))
+    Ok(Json(LoomEdgeMutationResponse {
+        edge,
+        receipt_event_id,
+    }))
 }
 
 async fn delete_loom_edge(
     State(state): State<AppState>,
     Path((workspace_id, edge_id)): Path<(String, String)>,
-) -> ApiResult<Json<LoomEdge>> {
+) -> ApiResult<Json<LoomEdgeMutationResponse>> {
     ensure_workspace_exists(&state, &workspace_id).await?;
 
     let ctx = WriteContext::human(None);
@@ -1853,9 +2234,12 @@ async fn delete_loom_edge(
         edge_event,
     )
     .with_wsids(vec![workspace_id]);
-    let _ = state.flight_recorder.record_event(event).await;
+    let receipt_event_id = record_receipt(&state, event).await;
 
-    Ok(Json(edge))
+    Ok(Json(LoomEdgeMutationResponse {
+        edge,
+        receipt_event_id,
+    }))
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -2321,6 +2705,9 @@ impl From<crate::storage::MediaAssetTier> for MediaTierView {
 #[derive(Debug, Serialize)]
 struct ListTiersResponse {
     tiers: Vec<MediaTierView>,
+    /// ffprobe metadata (duration, size, codecs) for video originals; absent
+    /// until a preview job probed the asset.
+    media: Option<crate::storage::media_probe::MediaAssetProbe>,
 }
 
 async fn list_asset_tiers(
@@ -2339,8 +2726,16 @@ async fn list_asset_tiers(
         .list_media_tiers(&workspace_id, &asset_id)
         .await
         .map_err(map_storage_error)?;
+    let media = crate::storage::media_probe::get_media_asset_probe(
+        &state.postgres_pool,
+        &workspace_id,
+        &asset_id,
+    )
+    .await
+    .map_err(map_storage_error)?;
     Ok(Json(ListTiersResponse {
         tiers: tiers.into_iter().map(MediaTierView::from).collect(),
+        media,
     }))
 }
 
@@ -2675,7 +3070,9 @@ struct LoomSearchV2Body {
 /// MT-264 LoomSearchV2 handler: embeds the query through the configured model
 /// runtime (typed decline -> keyword/trigram fallback) and runs the hybrid
 /// Postgres-native search. The response carries per-modality scores, content
-/// facets, ts_headline highlights, and a `semantic_available` flag.
+/// facets, ts_headline highlights, and a `semantic_available` flag. With a
+/// local reranker loaded the page is rescored by it (`rerank_score` per hit);
+/// without one it keeps the hybrid order.
 async fn loom_search_v2(
     State(state): State<AppState>,
     Path(workspace_id): Path<String>,
@@ -2691,14 +3088,37 @@ async fn loom_search_v2(
         limit: payload.limit,
         offset: payload.offset,
     };
-    let resp = crate::loom_search::search(
-        state.storage.as_ref(),
-        state.llm_client.as_ref(),
-        &workspace_id,
-        request,
-    )
-    .await
-    .map_err(map_storage_error)?;
+    let reranker = crate::knowledge_retrieval::rerank::local_reranker();
+    let resp = match &reranker {
+        Some(reranker) => {
+            let (resp, record) = crate::loom_search::search_reranked(
+                state.storage.as_ref(),
+                state.llm_client.as_ref(),
+                Some(reranker),
+                &workspace_id,
+                request,
+                &crate::knowledge_retrieval::rerank::RerankPolicy::default(),
+            )
+            .await
+            .map_err(map_storage_error)?;
+            if record.status == crate::knowledge_retrieval::rerank::RerankStatus::Skipped {
+                tracing::warn!(
+                    target: "handshake_core::loom_search",
+                    reason = %record.reason,
+                    "loom search rerank skipped; hybrid order kept"
+                );
+            }
+            resp
+        }
+        None => crate::loom_search::search(
+            state.storage.as_ref(),
+            state.llm_client.as_ref(),
+            &workspace_id,
+            request,
+        )
+        .await
+        .map_err(map_storage_error)?,
+    };
     Ok(Json(resp))
 }
 
@@ -3304,6 +3724,108 @@ async fn global_loom_graph(
     Ok(Json(graph))
 }
 
+#[derive(Debug, Deserialize, Default)]
+struct LoomGraphAnalyticsQuery {
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
+/// The workspace's Loom graph analytics projection with its staleness
+/// verdict; every report list is clipped to `limit` (default 100, max 500).
+async fn get_loom_graph_analytics(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<LoomGraphAnalyticsQuery>,
+) -> ApiResult<Json<crate::knowledge_retrieval::graph_analytics::GraphAnalyticsStatus>> {
+    use crate::knowledge_retrieval::graph_analytics::{graph_analytics_status, AnalyticsGraphKind};
+
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 500) as usize;
+    let mut status =
+        graph_analytics_status(&state.postgres_pool, &workspace_id, AnalyticsGraphKind::Loom)
+            .await
+            .map_err(map_storage_error)?
+            .ok_or_else(|| not_found("loom_graph_analytics"))?;
+    status.projection.report = status.projection.report.bounded(limit);
+    Ok(Json(status))
+}
+
+/// Recompute the Loom graph analytics projection over the current blocks and
+/// confirmed edges (`ai_suggested` edges are left out).
+async fn recompute_loom_graph_analytics(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<LoomGraphAnalyticsQuery>,
+) -> ApiResult<Json<crate::storage::knowledge_retrieval::GraphAnalyticsProjection>> {
+    use crate::knowledge_retrieval::graph_analytics::{
+        refresh_graph_analytics, AnalyticsConfig, AnalyticsGraphKind,
+    };
+
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 500) as usize;
+    let mut projection = refresh_graph_analytics(
+        &state.postgres_pool,
+        &workspace_id,
+        AnalyticsGraphKind::Loom,
+        &AnalyticsConfig::default(),
+    )
+    .await
+    .map_err(map_storage_error)?;
+    projection.report = projection.report.bounded(limit);
+    Ok(Json(projection))
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LoomGraphPathQuery {
+    from_block_id: Option<String>,
+    to_block_id: Option<String>,
+    #[serde(default)]
+    max_hops: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct LoomGraphPathResponse {
+    connected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<crate::knowledge_retrieval::graph_analytics::ConnectionExplanation>,
+}
+
+/// Shortest undirected path between two blocks, for "how is A connected to
+/// B". `connected: false` when none exists within `max_hops`.
+async fn explain_loom_graph_path(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<LoomGraphPathQuery>,
+) -> ApiResult<Json<LoomGraphPathResponse>> {
+    use crate::knowledge_retrieval::graph_analytics::{
+        explain_connection_in_workspace, AnalyticsGraphKind, DEFAULT_EXPLAIN_HOPS,
+    };
+
+    ensure_workspace_exists(&state, &workspace_id).await?;
+    let from = query
+        .from_block_id
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| bad_request("HSK-400-LOOM-START-BLOCK-REQUIRED"))?;
+    let to = query
+        .to_block_id
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| bad_request("HSK-400-LOOM-TARGET-BLOCK-REQUIRED"))?;
+    let path = explain_connection_in_workspace(
+        &state.postgres_pool,
+        &workspace_id,
+        AnalyticsGraphKind::Loom,
+        &from,
+        &to,
+        query.max_hops.unwrap_or(DEFAULT_EXPLAIN_HOPS),
+    )
+    .await
+    .map_err(map_storage_error)?;
+    Ok(Json(LoomGraphPathResponse {
+        connected: path.is_some(),
+        path,
+    }))
+}
+
 async fn recompute_loom_block_metrics(
     State(state): State<AppState>,
     Path((workspace_id, block_id)): Path<(String, String)>,
@@ -5419,4 +5941,155 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn edge_create_and_delete_return_receipts() -> Result<(), Box<dyn std::error::Error>> {
+        let Some(state) = setup_state().await? else {
+            return Ok(());
+        };
+        let workspace_id = create_workspace(&state).await?;
+
+        let mut block_ids = Vec::new();
+        for title in ["Receipt Source", "Receipt Target"] {
+            let block = create_loom_block(
+                State(state.clone()),
+                Path(workspace_id.clone()),
+                Json(CreateLoomBlockRequest {
+                    block_id: None,
+                    content_type: LoomBlockContentType::Note,
+                    document_id: None,
+                    asset_id: None,
+                    title: Some(title.to_string()),
+                    pinned: None,
+                    journal_date: None,
+                }),
+            )
+            .await
+            .map_err(|(status, Json(body))| LoomApiTestCallError {
+                status,
+                code: body.error.to_string(),
+            })?
+            .0;
+            block_ids.push(block.block_id);
+        }
+
+        let created = create_loom_edge(
+            State(state.clone()),
+            Path(workspace_id.clone()),
+            Json(CreateLoomEdgeRequest {
+                edge_id: None,
+                source_block_id: block_ids[0].clone(),
+                target_block_id: block_ids[1].clone(),
+                edge_type: LoomEdgeType::Parent,
+                created_by: LoomEdgeCreatedBy::User,
+                crdt_site_id: None,
+                source_anchor: None,
+                target_title: None,
+            }),
+        )
+        .await
+        .map_err(|(status, Json(body))| LoomApiTestCallError {
+            status,
+            code: body.error.to_string(),
+        })?
+        .0;
+        let created_receipt = created
+            .receipt_event_id
+            .clone()
+            .expect("edge create is receipted");
+
+        let deleted = delete_loom_edge(
+            State(state.clone()),
+            Path((workspace_id.clone(), created.edge.edge_id.clone())),
+        )
+        .await
+        .map_err(|(status, Json(body))| LoomApiTestCallError {
+            status,
+            code: body.error.to_string(),
+        })?
+        .0;
+        assert_eq!(deleted.edge.edge_id, created.edge.edge_id);
+        let deleted_receipt = deleted.receipt_event_id.expect("edge delete is receipted");
+
+        let events = state
+            .flight_recorder
+            .list_events(EventFilter::default())
+            .await?;
+        let receipt_type = |id: &str| {
+            events
+                .iter()
+                .find(|event| event.event_id.to_string() == id)
+                .map(|event| event.event_type.clone())
+        };
+        assert_eq!(
+            receipt_type(&created_receipt),
+            Some(FlightRecorderEventType::LoomEdgeCreated)
+        );
+        assert_eq!(
+            receipt_type(&deleted_receipt),
+            Some(FlightRecorderEventType::LoomEdgeDeleted)
+        );
+
+        let body = serde_json::to_value(&created)?;
+        assert_eq!(body["edge_type"], "parent", "edge fields stay flat on the response");
+        assert_eq!(body["receipt_event_id"], created_receipt.as_str());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn folder_delete_returns_receipt() -> Result<(), Box<dyn std::error::Error>> {
+        let Some(state) = setup_state().await? else {
+            return Ok(());
+        };
+        let workspace_id = create_workspace(&state).await?;
+
+        let folder = create_loom_folder(
+            State(state.clone()),
+            Path(workspace_id.clone()),
+            Json(CreateLoomFolderRequest {
+                name: "Receipted".to_string(),
+                parent_folder_id: None,
+                color: None,
+                sort_mode: None,
+                sort_order: None,
+                project_ref: None,
+            }),
+        )
+        .await
+        .map_err(|(status, Json(body))| LoomApiTestCallError {
+            status,
+            code: body.error.to_string(),
+        })?
+        .0;
+
+        let deleted = delete_loom_folder(
+            State(state.clone()),
+            Path((workspace_id.clone(), folder.folder_id.clone())),
+        )
+        .await
+        .map_err(|(status, Json(body))| LoomApiTestCallError {
+            status,
+            code: body.error.to_string(),
+        })?
+        .0;
+        assert_eq!(deleted["status"], "deleted");
+        let receipt = deleted["receipt_event_id"]
+            .as_str()
+            .expect("folder delete is receipted")
+            .to_string();
+
+        let events = state
+            .flight_recorder
+            .list_events(EventFilter::default())
+            .await?;
+        let event = events
+            .iter()
+            .find(|event| event.event_id.to_string() == receipt)
+            .expect("receipt event recorded");
+        assert_eq!(event.event_type, FlightRecorderEventType::LoomFolderDeleted);
+        assert_eq!(event.payload["folder_id"], folder.folder_id.as_str());
+
+        Ok(())
+    }
 }
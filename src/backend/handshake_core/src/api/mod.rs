@@ -19,9 +19,11 @@ pub mod knowledge_memory;
 pub mod knowledge_retrieval;
 pub mod logs;
 pub mod loom;
+pub mod operator_session;
 pub mod paths;
 pub mod role_mailbox;
 pub mod source_control;
+pub mod terminal;
 pub mod user_manual;
 pub mod workspaces;
 
@@ -72,3 +74,10 @@ pub fn routes(state: AppState) -> Router {
         .merge(source_control_routes)
         .merge(debug_adapter_routes)
 }
+
+/// Routes only the operator's own client may call (see
+/// [`operator_session`]). Kept out of [`routes`] so the caller can mount them
+/// outside its CORS layer.
+pub fn operator_routes(state: AppState) -> Router {
+    terminal::routes(state)
+}
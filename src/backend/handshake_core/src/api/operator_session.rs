@@ -0,0 +1,188 @@
+//! Operator session — the credential that marks a request as coming from the
+//! human operator's own client.
+//!
+//! Most routes are read/write surfaces over workspace data; a few (the
+//! integrated terminal) spawn processes and type into them, and must not be
+//! reachable by whatever else can open a socket to the loopback port — a web
+//! page in the operator's browser included. Those routes sit behind
+//! [`require_operator_session`]:
+//! * the `x-handshake-operator-session` header must constant-time-match the
+//!   per-process secret (401 otherwise),
+//! * requests carrying an `Origin` header are refused outright (403): the
+//!   native shell and Tauri IPC never send one, a browser always does.
+//!
+//! The secret is taken from `HANDSHAKE_OPERATOR_SESSION` (hex) when set, or
+//! minted from the OS RNG at startup; `main` writes it to
+//! `data/operator_session.secret` (owner-only) so the native shell can read it.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    extract::Request,
+    http::{header::ORIGIN, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+pub const OPERATOR_SESSION_HEADER: &str = "x-handshake-operator-session";
+pub const OPERATOR_SESSION_ENV: &str = "HANDSHAKE_OPERATOR_SESSION";
+pub const OPERATOR_SESSION_FILE_NAME: &str = "operator_session.secret";
+
+/// Bytes of OS entropy in a minted secret (256 bits).
+const OPERATOR_SESSION_LEN: usize = 32;
+
+/// The process-wide operator secret (lowercase hex), resolved on first use.
+pub fn operator_session() -> Arc<str> {
+    static SECRET: OnceLock<Arc<str>> = OnceLock::new();
+    SECRET
+        .get_or_init(|| {
+            let from_env = std::env::var(OPERATOR_SESSION_ENV).ok().and_then(|value| {
+                let value = value.trim().to_ascii_lowercase();
+                let valid = value.len() >= 32 && hex::decode(&value).is_ok();
+                if !valid {
+                    tracing::warn!(
+                        target: "handshake_core::api::operator_session",
+                        env = OPERATOR_SESSION_ENV,
+                        "operator session secret must be at least 16 bytes of hex; minting a fresh one"
+                    );
+                }
+                valid.then_some(value)
+            });
+            Arc::from(from_env.unwrap_or_else(mint_secret))
+        })
+        .clone()
+}
+
+/// Draw a fresh secret from the OS CSPRNG. Panics only when the OS RNG is
+/// unavailable: the terminal must not be guarded by a weak secret.
+fn mint_secret() -> String {
+    let mut bytes = [0u8; OPERATOR_SESSION_LEN];
+    getrandom::getrandom(&mut bytes)
+        .expect("OS CSPRNG must be available to mint the operator session secret");
+    hex::encode(bytes)
+}
+
+/// Write the operator secret (hex) to `data_dir/operator_session.secret`,
+/// readable by the owner only.
+pub fn write_operator_session_file(data_dir: &Path) -> std::io::Result<PathBuf> {
+    use std::io::Write;
+
+    let path = data_dir.join(OPERATOR_SESSION_FILE_NAME);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // `mode` only applies on create; tighten a file left by an older run.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(operator_session().as_bytes())?;
+    file.sync_all()?;
+    Ok(path)
+}
+
+enum OperatorCheck {
+    Ok,
+    BrowserOrigin,
+    Missing,
+    Mismatch,
+}
+
+fn classify(headers: &HeaderMap, expected: &str) -> OperatorCheck {
+    use subtle::ConstantTimeEq;
+
+    if headers.contains_key(ORIGIN) {
+        return OperatorCheck::BrowserOrigin;
+    }
+    let Some(raw) = headers.get(OPERATOR_SESSION_HEADER) else {
+        return OperatorCheck::Missing;
+    };
+    let matches = raw.to_str().is_ok_and(|value| {
+        value.len() == expected.len() && bool::from(value.as_bytes().ct_eq(expected.as_bytes()))
+    });
+    if matches {
+        OperatorCheck::Ok
+    } else {
+        OperatorCheck::Mismatch
+    }
+}
+
+/// Route-layer guard: only the operator's own client gets through.
+pub async fn require_operator_session(request: Request, next: Next) -> Response {
+    let (status, code, reason) = match classify(request.headers(), &operator_session()) {
+        OperatorCheck::Ok => return next.run(request).await,
+        OperatorCheck::BrowserOrigin => (
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "cross-origin requests are not accepted on operator routes",
+        ),
+        OperatorCheck::Missing => (
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+            "missing operator session header",
+        ),
+        OperatorCheck::Mismatch => (
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+            "operator session header mismatch",
+        ),
+    };
+    tracing::warn!(
+        target: "handshake_core::api::operator_session",
+        route = %request.uri().path(),
+        reason,
+        "rejected operator-only request"
+    );
+    (status, Json(json!({"error": code, "detail": reason}))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn only_the_matching_secret_without_an_origin_passes() {
+        let secret = hex::encode([7u8; 32]);
+
+        assert!(matches!(
+            classify(&headers(&[(OPERATOR_SESSION_HEADER, &secret)]), &secret),
+            OperatorCheck::Ok
+        ));
+        assert!(matches!(
+            classify(&headers(&[]), &secret),
+            OperatorCheck::Missing
+        ));
+        assert!(matches!(
+            classify(&headers(&[(OPERATOR_SESSION_HEADER, "00")]), &secret),
+            OperatorCheck::Mismatch
+        ));
+        assert!(matches!(
+            classify(
+                &headers(&[
+                    (OPERATOR_SESSION_HEADER, &secret),
+                    ("origin", "https://evil.example")
+                ]),
+                &secret
+            ),
+            OperatorCheck::BrowserOrigin
+        ));
+    }
+}
@@ -0,0 +1,452 @@
+//! Integrated Terminal (spec §10.1) — REST surface.
+//!
+//! Product-callable wrapper over [`crate::terminal::TerminalRuntime`] for
+//! frontends that do not speak Tauri IPC (the native egui shell):
+//! * `GET    /terminal/sessions` — every live session (human PTYs AND read-only
+//!   AiJob capture sessions),
+//! * `POST   /terminal/sessions` — spawn an interactive PTY session,
+//! * `GET    /terminal/sessions/:id/output?since=N` — output bytes after byte
+//!   offset `N` (base64) plus the exit state, so a polling UI can stream,
+//! * `POST   /terminal/sessions/:id/input` — base64 stdin,
+//! * `POST   /terminal/sessions/:id/resize` — propagate the viewport size to
+//!   `PtySession::resize`,
+//! * `POST   /terminal/sessions/:id/authorize` — the "Take control" gate for
+//!   AiJob sessions (capability-checked),
+//! * `DELETE /terminal/sessions/:id` — kill an interactive session.
+//!
+//! These routes mirror the Tauri `kernel_terminal_*` commands one-for-one and
+//! sit behind [`require_operator_session`]: only the operator's own client
+//! reaches them, and `main` merges them outside the permissive CORS layer.
+//! Nothing the caller sends decides its role or grants: a REST caller is the
+//! human at the keyboard, and an AI_JOB session's capability scope is the
+//! capability profile of the job it is bound to (`job_id`). The
+//! TERM-INVARIANTS stay enforced by the runtime: capture sessions reject stdin
+//! (409), AI writers cannot type into a HUMAN_DEV terminal or into an AiJob
+//! session that has not been authorized (403).
+//!
+//! The runtime is process-global (like the debug-session registry): sessions
+//! own live children and must outlive any one `AppState`. Background producers
+//! attach capture sessions to the same handle through [`shared_runtime`].
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, post},
+    Json, Router,
+};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::api::operator_session::require_operator_session;
+use crate::terminal::{
+    PtySpawnConfig, RuntimeError, SessionBinding, SessionOutput, TerminalRuntime,
+    TerminalSessionType,
+};
+use crate::AppState;
+
+type ApiError = (StatusCode, Json<Value>);
+
+/// Bytes of output retained per session for polling readers. Older bytes are
+/// dropped from the front; a reader that fell behind gets `gap: true`.
+const OUTPUT_LOG_CAP_BYTES: usize = 1024 * 1024;
+
+/// Largest single `output` response, so one poll never ships the whole log.
+const OUTPUT_READ_MAX_BYTES: usize = 256 * 1024;
+
+/// The process-wide terminal runtime, built from the first `AppState` that
+/// asks for it (its capability registry + Flight Recorder).
+pub fn shared_runtime(state: &AppState) -> TerminalRuntime {
+    static RUNTIME: OnceLock<TerminalRuntime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            TerminalRuntime::new(
+                Arc::clone(&state.capability_registry),
+                Arc::clone(&state.flight_recorder),
+            )
+        })
+        .clone()
+}
+
+/// Offset-addressed output of one session. The runtime only exposes a
+/// broadcast stream, which a request/response reader cannot hold open, so a
+/// forwarder task appends every chunk here and `output` reads a window.
+#[derive(Debug, Default)]
+struct OutputLog {
+    /// Absolute byte offset of `bytes[0]`.
+    start: u64,
+    bytes: VecDeque<u8>,
+    exit_code: Option<i32>,
+}
+
+impl OutputLog {
+    fn end(&self) -> u64 {
+        self.start + self.bytes.len() as u64
+    }
+
+    fn append(&mut self, chunk: &[u8]) {
+        self.bytes.extend(chunk.iter().copied());
+        let overflow = self.bytes.len().saturating_sub(OUTPUT_LOG_CAP_BYTES);
+        if overflow > 0 {
+            self.bytes.drain(..overflow);
+            self.start += overflow as u64;
+        }
+    }
+
+    /// The bytes after `since` (clamped to the retained window) and whether
+    /// some were already dropped.
+    fn read_since(&self, since: u64) -> (u64, Vec<u8>, bool) {
+        let gap = since < self.start;
+        let from = since.clamp(self.start, self.end());
+        let skip = (from - self.start) as usize;
+        let data: Vec<u8> = self
+            .bytes
+            .iter()
+            .skip(skip)
+            .take(OUTPUT_READ_MAX_BYTES)
+            .copied()
+            .collect();
+        (from, data, gap)
+    }
+}
+
+type OutputLogMap = Mutex<HashMap<String, Arc<Mutex<OutputLog>>>>;
+
+fn output_logs() -> &'static OutputLogMap {
+    static LOGS: OnceLock<OutputLogMap> = OnceLock::new();
+    LOGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The output log for `session_id`, attaching a forwarder on first use. The
+/// log is seeded from the runtime scrollback so a reader attaching late (or a
+/// capture session created by a background producer) sees the history.
+fn attach_output_log(
+    runtime: &TerminalRuntime,
+    session_id: &str,
+) -> Result<Arc<Mutex<OutputLog>>, ApiError> {
+    let mut logs = output_logs().lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(log) = logs.get(session_id) {
+        return Ok(Arc::clone(log));
+    }
+    // Subscribe BEFORE snapshotting the scrollback so no chunk falls between
+    // the two; a chunk racing the snapshot can appear twice, never zero times.
+    let mut rx = runtime.subscribe(session_id).map_err(runtime_error)?;
+    let mut seeded = OutputLog::default();
+    seeded.append(&runtime.scrollback(session_id).map_err(runtime_error)?);
+    let log = Arc::new(Mutex::new(seeded));
+    let sink = Arc::clone(&log);
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(SessionOutput::Chunk(bytes)) => {
+                    if let Ok(mut log) = sink.lock() {
+                        log.append(&bytes);
+                    }
+                }
+                Ok(SessionOutput::Exit(code)) => {
+                    if let Ok(mut log) = sink.lock() {
+                        log.exit_code = Some(code);
+                    }
+                    break;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+    logs.insert(session_id.to_string(), Arc::clone(&log));
+    Ok(log)
+}
+
+fn forget_output_log(session_id: &str) {
+    output_logs()
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .remove(session_id);
+}
+
+pub fn routes(state: AppState) -> Router {
+    Router::new()
+        .route(
+            "/terminal/sessions",
+            get(list_sessions).post(create_session),
+        )
+        .route(
+            "/terminal/sessions/:id",
+            axum::routing::delete(close_session),
+        )
+        .route("/terminal/sessions/:id/output", get(session_output))
+        .route("/terminal/sessions/:id/input", post(session_input))
+        .route("/terminal/sessions/:id/resize", post(session_resize))
+        .route("/terminal/sessions/:id/authorize", post(session_authorize))
+        .route_layer(middleware::from_fn(require_operator_session))
+        .with_state(state)
+}
+
+fn bad_request(detail: impl Into<String>) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "bad_request", "detail": detail.into()})),
+    )
+}
+
+fn runtime_error(err: RuntimeError) -> ApiError {
+    let (status, code) = match err {
+        RuntimeError::UnknownSession(_) => (StatusCode::NOT_FOUND, "session_not_found"),
+        RuntimeError::CapabilityDenied(_) | RuntimeError::Isolation(_) => {
+            (StatusCode::FORBIDDEN, "forbidden")
+        }
+        RuntimeError::CaptureReadOnly | RuntimeError::CaptureCloseDenied => {
+            (StatusCode::CONFLICT, "read_only")
+        }
+        RuntimeError::Pty(_) => (StatusCode::INTERNAL_SERVER_ERROR, "pty_error"),
+    };
+    (
+        status,
+        Json(json!({"error": code, "detail": err.to_string()})),
+    )
+}
+
+fn parse_session_type(s: Option<&str>) -> TerminalSessionType {
+    match s.map(|v| v.to_ascii_uppercase()) {
+        Some(ref v) if v == "AI_JOB" => TerminalSessionType::AiJob,
+        Some(ref v) if v == "PLUGIN_TOOL" => TerminalSessionType::PluginTool,
+        _ => TerminalSessionType::HumanDev,
+    }
+}
+
+/// `GET /terminal/sessions` — the live sessions, capture sessions included.
+async fn list_sessions(State(state): State<AppState>) -> Json<Value> {
+    let sessions = shared_runtime(&state).list_sessions();
+    Json(json!({ "sessions": sessions }))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateSessionBody {
+    /// "HUMAN_DEV" | "AI_JOB" | "PLUGIN_TOOL". Defaults to HUMAN_DEV.
+    #[serde(default)]
+    session_type: Option<String>,
+    #[serde(default)]
+    shell: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    rows: Option<u16>,
+    #[serde(default)]
+    cols: Option<u16>,
+    #[serde(default)]
+    swarm_id: Option<String>,
+    #[serde(default)]
+    worktree_id: Option<String>,
+    #[serde(default)]
+    instance_id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    /// The AI job an AI_JOB session acts for; required for that type. Its
+    /// capability profile becomes the session's capability scope.
+    #[serde(default)]
+    job_id: Option<String>,
+}
+
+/// The capability scope of a new session, derived on the server: an AI_JOB
+/// session gets its job's capability profile; human and plugin sessions get
+/// no grants (HUMAN_DEV needs none, PLUGIN_TOOL stays inspect-only).
+async fn session_capability_scope(
+    state: &AppState,
+    session_type: TerminalSessionType,
+    job_id: Option<&str>,
+) -> Result<Vec<String>, ApiError> {
+    if !matches!(session_type, TerminalSessionType::AiJob) {
+        return Ok(Vec::new());
+    }
+    let job_id = job_id.ok_or_else(|| bad_request("AI_JOB sessions require job_id"))?;
+    Uuid::parse_str(job_id).map_err(|_| bad_request("job_id must be a UUID"))?;
+    let job = state.storage.get_ai_job(job_id).await.map_err(|err| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "job_not_found", "detail": err.to_string()})),
+        )
+    })?;
+    let profile = state
+        .capability_registry
+        .profile_by_id(&job.capability_profile_id)
+        .map_err(|err| {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "forbidden", "detail": err.to_string()})),
+            )
+        })?;
+    Ok(profile.allowed.clone())
+}
+
+/// `POST /terminal/sessions` — spawn an interactive PTY. AiJob sessions stay
+/// inspect-only until `authorize` succeeds.
+async fn create_session(
+    State(state): State<AppState>,
+    Json(body): Json<CreateSessionBody>,
+) -> Result<Json<Value>, ApiError> {
+    let session_type = parse_session_type(body.session_type.as_deref());
+    let capability_scope =
+        session_capability_scope(&state, session_type, body.job_id.as_deref()).await?;
+    let runtime = shared_runtime(&state);
+    let spawn = PtySpawnConfig {
+        shell: body.shell,
+        args: body.args,
+        cwd: body.cwd.map(std::path::PathBuf::from),
+        env: Vec::new(),
+        rows: body.rows.unwrap_or(24).max(1),
+        cols: body.cols.unwrap_or(80).max(1),
+        scrollback_bytes: 0,
+        broadcast_capacity: 0,
+    };
+    let binding = SessionBinding {
+        swarm_id: body.swarm_id,
+        worktree_id: body.worktree_id,
+        instance_id: body.instance_id,
+    };
+    let info = runtime
+        .create_session(session_type, binding, capability_scope, spawn, body.title)
+        .await
+        .map_err(runtime_error)?;
+    attach_output_log(&runtime, &info.session_id)?;
+    Ok(Json(json!({ "session": info })))
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputQuery {
+    #[serde(default)]
+    since: u64,
+}
+
+/// `GET /terminal/sessions/:id/output?since=N` — output after byte offset `N`.
+/// `next` is the offset to pass on the following poll; `gap` is true when
+/// bytes between `since` and `offset` were already dropped from the log.
+async fn session_output(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<OutputQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let runtime = shared_runtime(&state);
+    let log = attach_output_log(&runtime, &id)?;
+    let (offset, data, gap, exit_code) = {
+        let log = log.lock().unwrap_or_else(|p| p.into_inner());
+        let (offset, data, gap) = log.read_since(query.since);
+        (offset, data, gap, log.exit_code)
+    };
+    let next = offset + data.len() as u64;
+    Ok(Json(json!({
+        "session_id": id,
+        "offset": offset,
+        "next": next,
+        "gap": gap,
+        "data_base64": base64::engine::general_purpose::STANDARD.encode(&data),
+        "exited": exit_code.is_some(),
+        "exit_code": exit_code,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct InputBody {
+    data_base64: String,
+}
+
+/// `POST /terminal/sessions/:id/input` — write stdin as the human operator.
+/// AI writers go through the runtime in-process, where the AI gates and the
+/// per-command Flight-Recorder link apply; no REST caller can claim that role.
+async fn session_input(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<InputBody>,
+) -> Result<StatusCode, ApiError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(body.data_base64.as_bytes())
+        .map_err(|e| bad_request(format!("invalid base64 stdin: {e}")))?;
+    shared_runtime(&state)
+        .write_stdin_recorded(&id, &bytes, false)
+        .await
+        .map_err(runtime_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct ResizeBody {
+    rows: u16,
+    cols: u16,
+}
+
+/// `POST /terminal/sessions/:id/resize` — no-op for capture sessions.
+async fn session_resize(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<ResizeBody>,
+) -> Result<StatusCode, ApiError> {
+    if body.rows == 0 || body.cols == 0 {
+        return Err(bad_request("rows and cols must be non-zero"));
+    }
+    shared_runtime(&state)
+        .resize(&id, body.rows, body.cols)
+        .map_err(runtime_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /terminal/sessions/:id/authorize` — capability-checked "Take control".
+async fn session_authorize(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    shared_runtime(&state)
+        .authorize_interactive(&id)
+        .await
+        .map_err(runtime_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /terminal/sessions/:id` — kill an interactive session. Capture
+/// sessions are producer-owned and answer 409.
+async fn close_session(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    shared_runtime(&state)
+        .close_session(&id)
+        .await
+        .map_err(runtime_error)?;
+    forget_output_log(&id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_log_reads_the_window_after_an_offset() {
+        let mut log = OutputLog::default();
+        log.append(b"hello ");
+        log.append(b"world");
+        assert_eq!(log.read_since(0), (0, b"hello world".to_vec(), false));
+        assert_eq!(log.read_since(6), (6, b"world".to_vec(), false));
+        assert_eq!(log.read_since(99), (11, Vec::new(), false));
+    }
+
+    #[test]
+    fn output_log_drops_the_front_and_reports_a_gap() {
+        let mut log = OutputLog::default();
+        log.append(&vec![b'a'; OUTPUT_LOG_CAP_BYTES]);
+        log.append(b"tail");
+        assert_eq!(log.start, 4);
+        assert_eq!(log.end(), OUTPUT_LOG_CAP_BYTES as u64 + 4);
+        let (offset, data, gap) = log.read_since(0);
+        assert!(gap);
+        assert_eq!(offset, 4);
+        assert_eq!(data.len(), OUTPUT_READ_MAX_BYTES);
+    }
+}
@@ -32,6 +32,8 @@ use sqlx::Row;
 use std::{cmp::Ordering, collections::HashSet};
 use uuid::Uuid;
 
+use super::media::{read_media_asset_payload, write_media_artifact_payload};
+use super::media_metadata::strip_gps_metadata;
 use super::{
     event_ref_for_text, reject_legacy_runtime_ref, AtelierError, AtelierResult, AtelierStore,
 };
@@ -319,6 +321,10 @@ pub struct ManifestEntry {
 pub struct SharePackSubsetSelector {
     pub include_sheet: bool,
     pub media_asset_ids: Vec<Uuid>,
+    /// Bundle GPS-stripped copies of media that carry location metadata
+    /// instead of the originals (`media_metadata::strip_gps_metadata`).
+    #[serde(default)]
+    pub strip_gps: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -341,6 +347,8 @@ pub struct SharePackBuildResult {
     pub export_id: Uuid,
     pub entries: Vec<ManifestEntry>,
     pub selected_media_count: i64,
+    /// Media entries that point at a GPS-stripped copy rather than the original.
+    pub gps_stripped_media_count: i64,
 }
 
 /// Required file roles in a deterministic model-consumable evidence pack (MT-072).
@@ -1462,10 +1470,24 @@ impl AtelierStore {
                 .await?,
             );
         }
+        let mut gps_stripped_media_count = 0i64;
         for row in &media_rows {
             let asset_id: Uuid = row.get("asset_id");
-            let artifact_ref: String = row.get("artifact_ref");
+            let mut artifact_ref: String = row.get("artifact_ref");
             let mime: String = row.get("mime");
+            if request.selector.strip_gps {
+                let asset = self.get_media_asset(asset_id).await?;
+                let original = read_media_asset_payload(&asset)?;
+                if let Some(stripped) = strip_gps_metadata(&original)? {
+                    artifact_ref = write_media_artifact_payload(
+                        &stripped,
+                        &mime,
+                        &format!("{asset_id}-gps-stripped"),
+                    )?
+                    .artifact_ref;
+                    gps_stripped_media_count += 1;
+                }
+            }
             entries.push(
                 self.add_manifest_entry(
                     request.export_id,
@@ -1490,6 +1512,7 @@ impl AtelierStore {
             export_id: request.export_id,
             entries,
             selected_media_count: media_rows.len() as i64,
+            gps_stripped_media_count,
         })
     }
 
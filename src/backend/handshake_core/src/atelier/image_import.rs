@@ -1,9 +1,11 @@
 //! Clipboard and URL image import (MT-025).
 //!
 //! Clipboard import only accepts bytes already captured into ArtifactStore and
-//! materializes them through the media store. URL import records a governed
-//! fetch request after SSRF and media-downloader capability preflight; actual
-//! network fetch remains a Workflow-Engine responsibility.
+//! materializes them through the media store, then extracts embedded metadata
+//! (`media_metadata`) so imported images are searchable and rule-tagged. URL
+//! import records a governed fetch request after SSRF and media-downloader
+//! capability preflight; actual network fetch remains a Workflow-Engine
+//! responsibility.
 
 use chrono::{DateTime, Utc};
 use reqwest::Url;
@@ -435,6 +437,7 @@ impl AtelierStore {
                 artifact_ref: input.artifact_ref.clone(),
             })
             .await?;
+        self.extract_media_asset_metadata(asset.asset_id).await?;
 
         let preflight = serde_json::json!({
             "source_application": source_application,
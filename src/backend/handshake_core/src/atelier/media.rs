@@ -3,8 +3,9 @@
 //! filesystem paths and never in `.GOV`. Identity is stable across file moves.
 
 use crate::storage::artifacts::{
-    artifact_root_rel, read_artifact_manifest, resolve_workspace_root,
-    validate_artifact_content_hash, ArtifactLayer,
+    artifact_root_dir, artifact_root_rel, read_artifact_manifest, resolve_workspace_root,
+    validate_artifact_content_hash, write_file_artifact, ArtifactClassification, ArtifactLayer,
+    ArtifactManifest, ArtifactPayloadKind,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -45,6 +46,7 @@ pub struct MediaAsset {
 pub enum MediaSidecarRelationKind {
     OpenPoseJson,
     WorkflowJson,
+    XmpSidecar,
 }
 
 impl MediaSidecarRelationKind {
@@ -52,6 +54,7 @@ impl MediaSidecarRelationKind {
         match self {
             MediaSidecarRelationKind::OpenPoseJson => "openpose_json",
             MediaSidecarRelationKind::WorkflowJson => "workflow_json",
+            MediaSidecarRelationKind::XmpSidecar => "xmp_sidecar",
         }
     }
 
@@ -59,6 +62,7 @@ impl MediaSidecarRelationKind {
         match value {
             "openpose_json" => Ok(MediaSidecarRelationKind::OpenPoseJson),
             "workflow_json" => Ok(MediaSidecarRelationKind::WorkflowJson),
+            "xmp_sidecar" => Ok(MediaSidecarRelationKind::XmpSidecar),
             other => Err(AtelierError::Validation(format!(
                 "unsupported media sidecar relation kind: {other}"
             ))),
@@ -837,6 +841,69 @@ fn verify_artifact_store_binding(new: &NewMediaAsset) -> AtelierResult<()> {
     Ok(())
 }
 
+/// Read the original bytes of a media asset back out of the ArtifactStore after
+/// re-verifying the row against its manifest, so callers never parse bytes
+/// that drifted from the recorded content hash.
+pub(crate) fn read_media_asset_payload(asset: &MediaAsset) -> AtelierResult<Vec<u8>> {
+    verify_media_asset_artifact_store_binding(asset)?;
+    let (layer, artifact_id) = parse_native_artifact_payload_ref(&asset.artifact_ref)?;
+    let workspace_root = resolve_media_artifact_root()?;
+    let payload_path = artifact_root_dir(&workspace_root, layer, artifact_id).join("payload");
+    std::fs::read(&payload_path).map_err(|err| {
+        AtelierError::Validation(format!("ArtifactStore payload read failed: {err}"))
+    })
+}
+
+/// Bytes written to the ArtifactStore for a media-derived file (XMP sidecar,
+/// GPS-stripped export copy).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WrittenMediaArtifact {
+    pub artifact_ref: String,
+    pub content_hash: String,
+    pub byte_len: i64,
+}
+
+/// Write derived media bytes as a new L1 ArtifactStore file artifact and
+/// return the native `artifact://` payload ref.
+pub(crate) fn write_media_artifact_payload(
+    bytes: &[u8],
+    mime: &str,
+    filename_hint: &str,
+) -> AtelierResult<WrittenMediaArtifact> {
+    let workspace_root = resolve_media_artifact_root()?;
+    let artifact_id = Uuid::now_v7();
+    let content_hash = hex::encode(Sha256::digest(bytes));
+    let manifest = ArtifactManifest {
+        artifact_id,
+        layer: ArtifactLayer::L1,
+        kind: ArtifactPayloadKind::File,
+        mime: mime.to_string(),
+        filename_hint: Some(filename_hint.to_string()),
+        created_at: Utc::now(),
+        created_by_job_id: None,
+        source_entity_refs: Vec::new(),
+        source_artifact_refs: Vec::new(),
+        content_hash: content_hash.clone(),
+        size_bytes: bytes.len() as u64,
+        classification: ArtifactClassification::Low,
+        exportable: true,
+        retention_ttl_days: None,
+        pinned: Some(true),
+        hash_basis: None,
+        hash_exclude_paths: Vec::new(),
+    };
+    write_file_artifact(&workspace_root, &manifest, bytes)
+        .map_err(|err| AtelierError::Validation(format!("ArtifactStore write failed: {err}")))?;
+    Ok(WrittenMediaArtifact {
+        artifact_ref: format!(
+            "artifact://{}/payload",
+            artifact_root_rel(ArtifactLayer::L1, artifact_id)
+        ),
+        content_hash,
+        byte_len: bytes.len() as i64,
+    })
+}
+
 fn verify_media_asset_artifact_store_binding(asset: &MediaAsset) -> AtelierResult<()> {
     let (layer, artifact_id) = parse_native_artifact_payload_ref(&asset.artifact_ref)?;
     let workspace_root = resolve_media_artifact_root()?;
@@ -1319,6 +1386,19 @@ impl AtelierStore {
         Ok(row.as_ref().map(asset_from_row))
     }
 
+    pub async fn get_media_asset(&self, asset_id: Uuid) -> AtelierResult<MediaAsset> {
+        let row = sqlx::query(
+            r#"SELECT asset_id, content_hash, mime, byte_len, source_provenance,
+                      artifact_ref, retention_class, artifact_manifest, created_at_utc
+               FROM atelier_media_asset WHERE asset_id = $1"#,
+        )
+        .bind(asset_id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| AtelierError::NotFound(format!("media asset_id={asset_id}")))?;
+        Ok(asset_from_row(&row))
+    }
+
     pub async fn get_media_artifact_manifest(
         &self,
         asset_id: Uuid,
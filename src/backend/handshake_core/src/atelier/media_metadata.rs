@@ -0,0 +1,1964 @@
+//! Embedded media metadata: EXIF / XMP / IPTC / PNG text chunks.
+//!
+//! Media assets (MT-015) carry identity, hash, mime and provenance, but the
+//! bytes themselves often already say where and how an image was made. This
+//! module reads that embedded metadata into typed, searchable PostgreSQL
+//! columns (`atelier_media_metadata`):
+//!   * EXIF (JPEG APP1, PNG `eXIf`, WebP `EXIF`, bare TIFF): camera make/model,
+//!     lens, capture time, orientation, artist, GPS.
+//!   * XMP packets (JPEG APP1, PNG `iTXt`, WebP `XMP `): Dublin Core creator /
+//!     title / description / subject keywords, `xmp:Rating`, EXIF/TIFF mirrors.
+//!   * IPTC-IIM (JPEG APP13 Photoshop `8BIM` 0x0404): by-line, keywords,
+//!     object name, caption.
+//!   * PNG `tEXt` / uncompressed `iTXt` generation parameters written by
+//!     ComfyUI (`prompt` graph JSON) and A1111 (`parameters`).
+//!
+//! Parsing is pure Rust over the byte buffer with no decoder or external tool;
+//! malformed or absent metadata yields empty fields, never an error. Extracted
+//! fields feed the saved tag rules (`search::evaluate_tag_rules`) through the
+//! `media.*` source field ids so metadata auto-tags land in
+//! `atelier_media_asset_tag` with source [`METADATA_RULE_TAG_SOURCE`].
+//!
+//! Write-back goes the other way: [`strip_gps_metadata`] removes GPS from a
+//! copy of the bytes for exports, and [`render_xmp_sidecar`] produces an XMP
+//! sidecar carrying Handshake tags and ratings so other DAM tools see the same
+//! organisation. Originals in the ArtifactStore are never rewritten.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::media::{read_media_asset_payload, write_media_artifact_payload};
+use super::search::evaluate_tag_rules;
+use super::{
+    AtelierError, AtelierResult, AtelierStore, MediaSidecar, MediaSidecarRelationKind,
+    NewMediaAsset, NewMediaSidecarRelation,
+};
+
+/// Event families emitted by the media metadata submodule.
+pub mod media_metadata_event_family {
+    /// Embedded metadata was extracted and metadata auto-tags recomputed.
+    pub const MEDIA_METADATA_EXTRACTED: &str = "atelier.media.metadata_extracted";
+    /// An XMP sidecar carrying tags and rating was written for a media asset.
+    pub const MEDIA_XMP_SIDECAR_WRITTEN: &str = "atelier.media.xmp_sidecar_written";
+
+    pub const ALL: &[&str] = &[MEDIA_METADATA_EXTRACTED, MEDIA_XMP_SIDECAR_WRITTEN];
+}
+
+pub use media_metadata_event_family::{MEDIA_METADATA_EXTRACTED, MEDIA_XMP_SIDECAR_WRITTEN};
+
+/// `atelier_media_asset_tag.source` for tags emitted by tag rules over metadata.
+pub const METADATA_RULE_TAG_SOURCE: &str = "metadata_rule";
+
+/// Tag-rule `source_field_id`s populated from extracted media metadata.
+pub const MEDIA_METADATA_TAG_RULE_FIELDS: &[&str] = &[
+    "media.camera_make",
+    "media.camera_model",
+    "media.lens_model",
+    "media.capture_year",
+    "media.orientation",
+    "media.has_gps",
+    "media.creator",
+    "media.title",
+    "media.keywords",
+    "media.generator",
+    "media.generation_model",
+    "media.generation_sampler",
+    "media.generation_prompt",
+];
+
+const XMP_SIDECAR_MIME: &str = "application/rdf+xml";
+const MAX_XMP_SEGMENT_BYTES: usize = 65_533 - XMP_JPEG_HEADER.len();
+const XMP_JPEG_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const PHOTOSHOP_HEADER: &[u8] = b"Photoshop 3.0\0";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
+const NS_RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+const NS_XMP: &str = "http://ns.adobe.com/xap/1.0/";
+const NS_EXIF: &str = "http://ns.adobe.com/exif/1.0/";
+const NS_EXIF_EX: &str = "http://cipa.jp/exif/1.0/";
+const NS_AUX: &str = "http://ns.adobe.com/exif/1.0/aux/";
+const NS_TIFF: &str = "http://ns.adobe.com/tiff/1.0/";
+const NS_PHOTOSHOP: &str = "http://ns.adobe.com/photoshop/1.0/";
+
+/// Which tool wrote embedded generation parameters.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationTool {
+    A1111,
+    Comfyui,
+}
+
+impl GenerationTool {
+    pub fn as_token(self) -> &'static str {
+        match self {
+            GenerationTool::A1111 => "a1111",
+            GenerationTool::Comfyui => "comfyui",
+        }
+    }
+
+    fn from_token(token: &str) -> AtelierResult<Self> {
+        match token {
+            "a1111" => Ok(GenerationTool::A1111),
+            "comfyui" => Ok(GenerationTool::Comfyui),
+            other => Err(AtelierError::Validation(format!(
+                "unknown generation tool token: {other}"
+            ))),
+        }
+    }
+}
+
+/// Diffusion generation parameters recovered from PNG text chunks.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GenerationParameters {
+    pub tool: GenerationTool,
+    pub prompt: Option<String>,
+    pub negative_prompt: Option<String>,
+    pub model: Option<String>,
+    pub sampler: Option<String>,
+    pub scheduler: Option<String>,
+    pub steps: Option<i32>,
+    pub cfg_scale: Option<f64>,
+    pub seed: Option<i64>,
+    /// The parsed source payload (A1111 settings map or ComfyUI prompt graph).
+    pub raw: serde_json::Value,
+}
+
+/// Typed metadata read from the embedded EXIF / XMP / IPTC / PNG text blocks.
+///
+/// EXIF wins over XMP, which wins over IPTC, for single-valued fields;
+/// keywords are the ordered union of XMP `dc:subject` and IPTC keywords.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MediaMetadata {
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens_model: Option<String>,
+    /// Capture time as recorded by the device (EXIF has no reliable zone).
+    pub captured_at_local: Option<NaiveDateTime>,
+    pub gps_latitude: Option<f64>,
+    pub gps_longitude: Option<f64>,
+    pub gps_altitude_m: Option<f64>,
+    /// EXIF orientation 1..=8.
+    pub orientation: Option<i16>,
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    /// Embedded `xmp:Rating`, clamped to the 0..=5 review scale.
+    pub embedded_rating: Option<i16>,
+    pub generation: Option<GenerationParameters>,
+    /// Blocks that contributed fields: `exif`, `xmp`, `iptc`, `png_text`.
+    pub sources: Vec<String>,
+}
+
+impl MediaMetadata {
+    pub fn has_gps(&self) -> bool {
+        self.gps_latitude.is_some() && self.gps_longitude.is_some()
+    }
+
+    /// `media.*` field values for tag-rule evaluation. Empty fields are left
+    /// out so rules never match on absent metadata.
+    pub fn tag_rule_fields(&self) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+        let mut put = |key: &str, value: Option<String>| {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                fields.insert(key.to_string(), value);
+            }
+        };
+        put("media.camera_make", self.camera_make.clone());
+        put("media.camera_model", self.camera_model.clone());
+        put("media.lens_model", self.lens_model.clone());
+        put(
+            "media.capture_year",
+            self.captured_at_local.map(|at| at.format("%Y").to_string()),
+        );
+        put("media.orientation", self.orientation.map(|o| o.to_string()));
+        put("media.has_gps", self.has_gps().then(|| "true".to_string()));
+        put("media.creator", self.creator.clone());
+        put("media.title", self.title.clone());
+        put("media.keywords", Some(self.keywords.join(", ")));
+        if let Some(generation) = &self.generation {
+            put(
+                "media.generator",
+                Some(generation.tool.as_token().to_string()),
+            );
+            put("media.generation_model", generation.model.clone());
+            put("media.generation_sampler", generation.sampler.clone());
+            put("media.generation_prompt", generation.prompt.clone());
+        }
+        fields
+    }
+
+    fn note_source(&mut self, source: &str) {
+        if !self.sources.iter().any(|s| s == source) {
+            self.sources.push(source.to_string());
+        }
+    }
+
+    fn add_keywords(&mut self, keywords: impl IntoIterator<Item = String>) {
+        for keyword in keywords {
+            let keyword = keyword.trim().to_string();
+            if !keyword.is_empty() && !self.keywords.contains(&keyword) {
+                self.keywords.push(keyword);
+            }
+        }
+    }
+}
+
+/// Persisted metadata row for one media asset.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MediaMetadataRecord {
+    pub asset_id: Uuid,
+    #[serde(flatten)]
+    pub metadata: MediaMetadata,
+    pub extracted_at_utc: DateTime<Utc>,
+}
+
+/// Result of extracting metadata for an asset: the stored row plus the
+/// metadata auto-tags the saved rule set emitted.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MediaMetadataExtraction {
+    pub record: MediaMetadataRecord,
+    pub auto_tags: Vec<String>,
+}
+
+/// Filters over the typed metadata columns. All set filters must match.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MediaMetadataQuery {
+    pub camera_model: Option<String>,
+    pub creator: Option<String>,
+    pub keyword: Option<String>,
+    pub generator: Option<GenerationTool>,
+    pub has_gps: Option<bool>,
+    pub captured_from: Option<NaiveDateTime>,
+    pub captured_to: Option<NaiveDateTime>,
+    pub limit: Option<i64>,
+}
+
+/// Fields written into an XMP sidecar.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct XmpSidecarContent {
+    pub tags: Vec<String>,
+    pub rating: Option<i16>,
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+// ----- Extraction ---------------------------------------------------------
+
+/// Raw metadata blocks located inside a container.
+#[derive(Default)]
+struct MetadataBlocks {
+    exif: Vec<Vec<u8>>,
+    xmp: Vec<String>,
+    iptc: Vec<Vec<u8>>,
+    png_text: Vec<(String, String)>,
+}
+
+/// Read embedded metadata from JPEG, PNG, WebP or TIFF bytes. Unknown
+/// containers and malformed blocks produce an empty [`MediaMetadata`].
+pub fn extract_media_metadata(bytes: &[u8]) -> MediaMetadata {
+    let blocks = locate_metadata_blocks(bytes);
+    let mut metadata = MediaMetadata::default();
+    for exif in &blocks.exif {
+        if read_exif(exif, &mut metadata) {
+            metadata.note_source("exif");
+        }
+    }
+    for packet in &blocks.xmp {
+        if read_xmp(packet, &mut metadata) {
+            metadata.note_source("xmp");
+        }
+    }
+    for iim in &blocks.iptc {
+        if read_iptc(iim, &mut metadata) {
+            metadata.note_source("iptc");
+        }
+    }
+    if let Some(generation) = read_generation_parameters(&blocks.png_text) {
+        metadata.generation = Some(generation);
+        metadata.note_source("png_text");
+    }
+    metadata
+}
+
+fn locate_metadata_blocks(bytes: &[u8]) -> MetadataBlocks {
+    let mut blocks = MetadataBlocks::default();
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        for segment in jpeg_segments(bytes) {
+            let data = &bytes[segment.data.clone()];
+            match segment.marker {
+                0xE1 if data.starts_with(EXIF_HEADER) => {
+                    blocks.exif.push(data[EXIF_HEADER.len()..].to_vec());
+                }
+                0xE1 if data.starts_with(XMP_JPEG_HEADER) => {
+                    let packet = &data[XMP_JPEG_HEADER.len()..];
+                    blocks
+                        .xmp
+                        .push(String::from_utf8_lossy(packet).into_owned());
+                }
+                0xED if data.starts_with(PHOTOSHOP_HEADER) => {
+                    blocks
+                        .iptc
+                        .extend(photoshop_iptc_blocks(&data[PHOTOSHOP_HEADER.len()..]));
+                }
+                _ => {}
+            }
+        }
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        for chunk in png_chunks(bytes) {
+            let data = &bytes[chunk.data.clone()];
+            match &chunk.kind {
+                b"eXIf" => blocks.exif.push(data.to_vec()),
+                b"tEXt" | b"iTXt" => {
+                    if let Some((keyword, text)) = png_text_chunk(&chunk.kind, data) {
+                        if keyword == PNG_XMP_KEYWORD {
+                            blocks.xmp.push(text);
+                        } else {
+                            blocks.png_text.push((keyword, text));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    } else if is_webp(bytes) {
+        for chunk in riff_chunks(bytes) {
+            let data = &bytes[chunk.data.clone()];
+            match &chunk.kind {
+                b"EXIF" => blocks
+                    .exif
+                    .push(data.strip_prefix(EXIF_HEADER).unwrap_or(data).to_vec()),
+                b"XMP " => blocks.xmp.push(String::from_utf8_lossy(data).into_owned()),
+                _ => {}
+            }
+        }
+    } else if TiffReader::new(bytes).is_some() {
+        blocks.exif.push(bytes.to_vec());
+    }
+    blocks
+}
+
+fn fill(slot: &mut Option<String>, value: Option<String>) {
+    if slot.is_none() {
+        *slot = value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+}
+
+// ----- EXIF (TIFF IFD walk) ------------------------------------------------
+
+const TAG_IMAGE_DESCRIPTION: u16 = 0x010E;
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_ARTIST: u16 = 0x013B;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_LENS_MODEL: u16 = 0xA434;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+const TAG_GPS_ALTITUDE_REF: u16 = 0x0005;
+const TAG_GPS_ALTITUDE: u16 = 0x0006;
+
+#[derive(Clone, Copy)]
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    /// Offset of the 12-byte entry inside the TIFF buffer.
+    offset: usize,
+}
+
+/// Minimal bounds-checked TIFF reader: byte order, IFD entries and values.
+struct TiffReader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..4)? {
+            [b'I', b'I', 42, 0] => true,
+            [b'M', b'M', 0, 42] => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let raw: [u8; 2] = self
+            .data
+            .get(offset..offset.checked_add(2)?)?
+            .try_into()
+            .ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(raw)
+        } else {
+            u16::from_be_bytes(raw)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let raw: [u8; 4] = self
+            .data
+            .get(offset..offset.checked_add(4)?)?
+            .try_into()
+            .ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(raw)
+        } else {
+            u32::from_be_bytes(raw)
+        })
+    }
+
+    fn first_ifd(&self) -> Option<usize> {
+        self.u32_at(4).map(|offset| offset as usize)
+    }
+
+    fn entries(&self, ifd_offset: usize) -> Option<Vec<IfdEntry>> {
+        let count = self.u16_at(ifd_offset)? as usize;
+        let mut entries = Vec::with_capacity(count);
+        for index in 0..count {
+            let offset = ifd_offset + 2 + index * 12;
+            entries.push(IfdEntry {
+                tag: self.u16_at(offset)?,
+                field_type: self.u16_at(offset + 2)?,
+                count: self.u32_at(offset + 4)?,
+                offset,
+            });
+        }
+        Some(entries)
+    }
+
+    /// Byte range of an entry's value (inline when it fits in four bytes).
+    fn value_range(&self, entry: &IfdEntry) -> Option<std::ops::Range<usize>> {
+        let size = tiff_type_size(entry.field_type)?.checked_mul(entry.count as usize)?;
+        let start = if size <= 4 {
+            entry.offset + 8
+        } else {
+            self.u32_at(entry.offset + 8)? as usize
+        };
+        let end = start.checked_add(size)?;
+        (end <= self.data.len()).then_some(start..end)
+    }
+
+    fn ascii(&self, entry: &IfdEntry) -> Option<String> {
+        if entry.field_type != 2 {
+            return None;
+        }
+        let raw = &self.data[self.value_range(entry)?];
+        let raw = raw.split(|b| *b == 0).next().unwrap_or_default();
+        let text = String::from_utf8_lossy(raw).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn uint(&self, entry: &IfdEntry) -> Option<u32> {
+        let range = self.value_range(entry)?;
+        match entry.field_type {
+            1 | 7 => self.data.get(range.start).map(|b| u32::from(*b)),
+            3 => self.u16_at(range.start).map(u32::from),
+            4 => self.u32_at(range.start),
+            _ => None,
+        }
+    }
+
+    fn rationals(&self, entry: &IfdEntry) -> Option<Vec<f64>> {
+        if entry.field_type != 5 {
+            return None;
+        }
+        let range = self.value_range(entry)?;
+        (0..entry.count as usize)
+            .map(|index| {
+                let at = range.start + index * 8;
+                let numerator = self.u32_at(at)?;
+                let denominator = self.u32_at(at + 4)?;
+                (denominator != 0).then(|| f64::from(numerator) / f64::from(denominator))
+            })
+            .collect()
+    }
+
+    fn pointer(&self, entries: &[IfdEntry], tag: u16) -> Option<usize> {
+        let entry = entries.iter().find(|e| e.tag == tag)?;
+        self.uint(entry).map(|offset| offset as usize)
+    }
+}
+
+fn tiff_type_size(field_type: u16) -> Option<usize> {
+    match field_type {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+fn parse_exif_datetime(raw: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(raw.trim(), "%Y:%m:%d %H:%M:%S").ok()
+}
+
+fn read_exif(tiff_bytes: &[u8], metadata: &mut MediaMetadata) -> bool {
+    let Some(tiff) = TiffReader::new(tiff_bytes) else {
+        return false;
+    };
+    let Some(ifd0) = tiff.first_ifd().and_then(|offset| tiff.entries(offset)) else {
+        return false;
+    };
+    let before = metadata.clone();
+    let mut fallback_datetime = None;
+    for entry in &ifd0 {
+        match entry.tag {
+            TAG_MAKE => fill(&mut metadata.camera_make, tiff.ascii(entry)),
+            TAG_MODEL => fill(&mut metadata.camera_model, tiff.ascii(entry)),
+            TAG_ARTIST => fill(&mut metadata.creator, tiff.ascii(entry)),
+            TAG_IMAGE_DESCRIPTION => fill(&mut metadata.description, tiff.ascii(entry)),
+            TAG_DATE_TIME => fallback_datetime = tiff.ascii(entry),
+            TAG_ORIENTATION => {
+                if metadata.orientation.is_none() {
+                    metadata.orientation = tiff
+                        .uint(entry)
+                        .filter(|o| (1..=8).contains(o))
+                        .map(|o| o as i16);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(exif_ifd) = tiff
+        .pointer(&ifd0, TAG_EXIF_IFD)
+        .and_then(|offset| tiff.entries(offset))
+    {
+        for entry in &exif_ifd {
+            match entry.tag {
+                TAG_DATE_TIME_ORIGINAL => {
+                    if metadata.captured_at_local.is_none() {
+                        metadata.captured_at_local =
+                            tiff.ascii(entry).as_deref().and_then(parse_exif_datetime);
+                    }
+                }
+                TAG_LENS_MODEL => fill(&mut metadata.lens_model, tiff.ascii(entry)),
+                _ => {}
+            }
+        }
+    }
+    if metadata.captured_at_local.is_none() {
+        metadata.captured_at_local = fallback_datetime.as_deref().and_then(parse_exif_datetime);
+    }
+    if let Some(gps_ifd) = tiff
+        .pointer(&ifd0, TAG_GPS_IFD)
+        .and_then(|offset| tiff.entries(offset))
+    {
+        read_exif_gps(&tiff, &gps_ifd, metadata);
+    }
+    *metadata != before
+}
+
+fn read_exif_gps(tiff: &TiffReader<'_>, gps_ifd: &[IfdEntry], metadata: &mut MediaMetadata) {
+    if metadata.has_gps() {
+        return;
+    }
+    let find = |tag: u16| gps_ifd.iter().find(|e| e.tag == tag);
+    let coordinate = |value_tag: u16, ref_tag: u16, negative: char, limit: f64| {
+        let dms = tiff.rationals(find(value_tag)?)?;
+        let mut degrees = dms_to_degrees(&dms)?;
+        let hemisphere = find(ref_tag).and_then(|e| tiff.ascii(e));
+        if hemisphere.is_some_and(|h| h.starts_with(negative)) {
+            degrees = -degrees;
+        }
+        (degrees.is_finite() && degrees.abs() <= limit).then_some(degrees)
+    };
+    let latitude = coordinate(TAG_GPS_LATITUDE, TAG_GPS_LATITUDE_REF, 'S', 90.0);
+    let longitude = coordinate(TAG_GPS_LONGITUDE, TAG_GPS_LONGITUDE_REF, 'W', 180.0);
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+        metadata.gps_latitude = Some(latitude);
+        metadata.gps_longitude = Some(longitude);
+        metadata.gps_altitude_m = find(TAG_GPS_ALTITUDE)
+            .and_then(|e| tiff.rationals(e))
+            .and_then(|values| values.first().copied())
+            .map(|altitude| {
+                let below_sea_level = find(TAG_GPS_ALTITUDE_REF)
+                    .and_then(|e| tiff.uint(e))
+                    .is_some_and(|r| r == 1);
+                if below_sea_level {
+                    -altitude
+                } else {
+                    altitude
+                }
+            });
+    }
+}
+
+fn dms_to_degrees(dms: &[f64]) -> Option<f64> {
+    match dms {
+        [d] => Some(*d),
+        [d, m] => Some(d + m / 60.0),
+        [d, m, s, ..] => Some(d + m / 60.0 + s / 3600.0),
+        [] => None,
+    }
+}
+
+// ----- XMP ------------------------------------------------------------------
+
+fn read_xmp(packet: &str, metadata: &mut MediaMetadata) -> bool {
+    let packet = packet.trim_matches(char::from(0)).trim();
+    let Ok(doc) = roxmltree::Document::parse(packet) else {
+        return false;
+    };
+    let before = metadata.clone();
+    fill(
+        &mut metadata.camera_make,
+        xmp_property(&doc, NS_TIFF, "Make"),
+    );
+    fill(
+        &mut metadata.camera_model,
+        xmp_property(&doc, NS_TIFF, "Model"),
+    );
+    fill(
+        &mut metadata.lens_model,
+        xmp_property(&doc, NS_EXIF_EX, "LensModel").or_else(|| xmp_property(&doc, NS_AUX, "Lens")),
+    );
+    fill(&mut metadata.creator, xmp_property(&doc, NS_DC, "creator"));
+    fill(&mut metadata.title, xmp_property(&doc, NS_DC, "title"));
+    fill(
+        &mut metadata.description,
+        xmp_property(&doc, NS_DC, "description"),
+    );
+    if metadata.orientation.is_none() {
+        metadata.orientation = xmp_property(&doc, NS_TIFF, "Orientation")
+            .and_then(|o| o.parse::<i16>().ok())
+            .filter(|o| (1..=8).contains(o));
+    }
+    if metadata.captured_at_local.is_none() {
+        metadata.captured_at_local = xmp_property(&doc, NS_EXIF, "DateTimeOriginal")
+            .or_else(|| xmp_property(&doc, NS_PHOTOSHOP, "DateCreated"))
+            .or_else(|| xmp_property(&doc, NS_XMP, "CreateDate"))
+            .as_deref()
+            .and_then(parse_xmp_datetime);
+    }
+    if metadata.embedded_rating.is_none() {
+        metadata.embedded_rating = xmp_property(&doc, NS_XMP, "Rating")
+            .and_then(|r| r.parse::<f64>().ok())
+            .filter(|r| r.is_finite())
+            .map(|r| r.round().clamp(0.0, 5.0) as i16);
+    }
+    if !metadata.has_gps() {
+        let latitude = xmp_property(&doc, NS_EXIF, "GPSLatitude")
+            .and_then(|v| parse_xmp_gps_coordinate(&v, 'S', 90.0));
+        let longitude = xmp_property(&doc, NS_EXIF, "GPSLongitude")
+            .and_then(|v| parse_xmp_gps_coordinate(&v, 'W', 180.0));
+        if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+            metadata.gps_latitude = Some(latitude);
+            metadata.gps_longitude = Some(longitude);
+        }
+    }
+    metadata.add_keywords(xmp_list(&doc, NS_DC, "subject"));
+    *metadata != before
+}
+
+/// A simple XMP property: an `rdf:Description` attribute, or an element whose
+/// text (or first `rdf:li` for Alt/Seq/Bag arrays) carries the value.
+fn xmp_property(doc: &roxmltree::Document<'_>, ns: &str, name: &str) -> Option<String> {
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        if let Some(value) = node.attribute((ns, name)) {
+            return Some(value.to_string());
+        }
+        if node.tag_name().namespace() == Some(ns) && node.tag_name().name() == name {
+            let li = node
+                .descendants()
+                .find(|n| n.tag_name().namespace() == Some(NS_RDF) && n.tag_name().name() == "li");
+            let text = match li {
+                Some(li) => li.text(),
+                None => node.text(),
+            };
+            if let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) {
+                return Some(text.to_string());
+            }
+        }
+    }
+    None
+}
+
+fn xmp_list(doc: &roxmltree::Document<'_>, ns: &str, name: &str) -> Vec<String> {
+    doc.descendants()
+        .filter(|n| n.tag_name().namespace() == Some(ns) && n.tag_name().name() == name)
+        .flat_map(|n| n.descendants())
+        .filter(|n| n.tag_name().namespace() == Some(NS_RDF) && n.tag_name().name() == "li")
+        .filter_map(|n| n.text())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn parse_xmp_datetime(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    if let Ok(with_zone) = DateTime::parse_from_rfc3339(raw) {
+        return Some(with_zone.naive_local());
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+}
+
+/// XMP GPS coordinates use `DDD,MM.mmk` or `DDD,MM,SSk` with a trailing
+/// hemisphere letter.
+fn parse_xmp_gps_coordinate(raw: &str, negative: char, limit: f64) -> Option<f64> {
+    let raw = raw.trim();
+    let hemisphere = raw.chars().last()?.to_ascii_uppercase();
+    let body = raw.strip_suffix(|c: char| c.is_ascii_alphabetic())?;
+    let parts: Vec<f64> = body
+        .split(',')
+        .map(|part| part.trim().parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    let mut degrees = dms_to_degrees(&parts)?;
+    if hemisphere == negative {
+        degrees = -degrees;
+    }
+    (degrees.is_finite() && degrees.abs() <= limit).then_some(degrees)
+}
+
+// ----- IPTC-IIM --------------------------------------------------------------
+
+/// IPTC-IIM blocks (`8BIM` resource 0x0404) inside a Photoshop APP13 payload.
+fn photoshop_iptc_blocks(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut blocks = Vec::new();
+    let mut pos = 0usize;
+    while pos + 12 <= data.len() && &data[pos..pos + 4] == b"8BIM" {
+        let resource_id = u16::from_be_bytes([data[pos + 4], data[pos + 5]]);
+        let name_len = data[pos + 6] as usize;
+        // Pascal name (length byte + name) is padded to an even size.
+        let mut cursor = pos + 6 + ((name_len + 2) & !1);
+        let Some(size_bytes) = data.get(cursor..cursor + 4) else {
+            break;
+        };
+        let size = u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]])
+            as usize;
+        cursor += 4;
+        let Some(payload) = cursor
+            .checked_add(size)
+            .and_then(|end| data.get(cursor..end))
+        else {
+            break;
+        };
+        if resource_id == 0x0404 {
+            blocks.push(payload.to_vec());
+        }
+        pos = cursor + ((size + 1) & !1);
+    }
+    blocks
+}
+
+fn read_iptc(iim: &[u8], metadata: &mut MediaMetadata) -> bool {
+    let before = metadata.clone();
+    let mut keywords = Vec::new();
+    let mut pos = 0usize;
+    while pos + 5 <= iim.len() && iim[pos] == 0x1C {
+        let record = iim[pos + 1];
+        let dataset = iim[pos + 2];
+        let size = u16::from_be_bytes([iim[pos + 3], iim[pos + 4]]);
+        // Extended-length datasets never carry the text fields read here.
+        if size & 0x8000 != 0 {
+            break;
+        }
+        let start = pos + 5;
+        let Some(value) = iim.get(start..start + size as usize) else {
+            break;
+        };
+        let text = || Some(String::from_utf8_lossy(value).into_owned());
+        if record == 2 {
+            match dataset {
+                5 => fill(&mut metadata.title, text()),
+                25 => keywords.extend(text()),
+                80 => fill(&mut metadata.creator, text()),
+                120 => fill(&mut metadata.description, text()),
+                _ => {}
+            }
+        }
+        pos = start + size as usize;
+    }
+    metadata.add_keywords(keywords);
+    *metadata != before
+}
+
+// ----- PNG generation parameters -------------------------------------------
+
+fn read_generation_parameters(png_text: &[(String, String)]) -> Option<GenerationParameters> {
+    let text_for = |key: &str| {
+        png_text
+            .iter()
+            .find(|(keyword, _)| keyword == key)
+            .map(|(_, text)| text.as_str())
+    };
+    if let Some(parameters) = text_for("parameters") {
+        if let Some(parsed) = parse_a1111_parameters(parameters) {
+            return Some(parsed);
+        }
+    }
+    text_for("prompt")
+        .and_then(parse_comfyui_prompt)
+        .or_else(|| text_for("workflow").and_then(comfyui_workflow_only))
+}
+
+/// A1111 `parameters`: prompt lines, an optional `Negative prompt:` block,
+/// then a `Steps: …, Sampler: …` settings line.
+fn parse_a1111_parameters(text: &str) -> Option<GenerationParameters> {
+    let lines: Vec<&str> = text.lines().collect();
+    let settings_index = lines
+        .iter()
+        .rposition(|line| line.trim_start().starts_with("Steps:"))?;
+    let mut prompt = Vec::new();
+    let mut negative = Vec::new();
+    let mut in_negative = false;
+    for line in &lines[..settings_index] {
+        if let Some(rest) = line.strip_prefix("Negative prompt:") {
+            in_negative = true;
+            negative.push(rest.trim());
+        } else if in_negative {
+            negative.push(line.trim());
+        } else {
+            prompt.push(line.trim());
+        }
+    }
+    let settings = parse_a1111_settings(lines[settings_index]);
+    let setting = |key: &str| {
+        settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let joined = |parts: Vec<&str>| {
+        let text = parts.join("\n").trim().to_string();
+        (!text.is_empty()).then_some(text)
+    };
+    let prompt = joined(prompt);
+    let negative_prompt = joined(negative);
+    let raw = serde_json::json!({
+        "prompt": prompt,
+        "negative_prompt": negative_prompt,
+        "settings": settings
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+    });
+    Some(GenerationParameters {
+        tool: GenerationTool::A1111,
+        prompt,
+        negative_prompt,
+        model: setting("Model").or_else(|| setting("Model hash")),
+        sampler: setting("Sampler"),
+        scheduler: setting("Schedule type"),
+        steps: setting("Steps").and_then(|v| v.parse().ok()),
+        cfg_scale: setting("CFG scale").and_then(|v| v.parse().ok()),
+        seed: setting("Seed").and_then(|v| v.parse().ok()),
+        raw,
+    })
+}
+
+/// Split `Key: value, Key: "quoted, value"` pairs.
+fn parse_a1111_settings(line: &str) -> Vec<(String, String)> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for ch in line.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                current.push(ch);
+            }
+            ',' if !quoted => parts.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+    parts
+        .iter()
+        .filter_map(|part| {
+            let (key, value) = part.split_once(':')?;
+            let value = value.trim().trim_matches('"').to_string();
+            Some((key.trim().to_string(), value))
+        })
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// ComfyUI `prompt`: the API graph `{node_id: {class_type, inputs}}`. The
+/// first sampler node (by node id) supplies seed/steps/cfg and its
+/// `positive` / `negative` links resolve to text-encode prompts.
+fn parse_comfyui_prompt(text: &str) -> Option<GenerationParameters> {
+    let graph: serde_json::Value = serde_json::from_str(text).ok()?;
+    let nodes = graph.as_object().filter(|nodes| !nodes.is_empty())?;
+    let mut node_ids: Vec<&String> = nodes.keys().collect();
+    node_ids.sort_by(|a, b| {
+        let rank = |id: &str| id.parse::<u64>().unwrap_or(u64::MAX);
+        rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+    });
+    let class_of = |id: &str| {
+        nodes
+            .get(id)
+            .and_then(|node| node.get("class_type"))
+            .and_then(|class| class.as_str())
+            .unwrap_or_default()
+    };
+    let inputs_of = |id: &str| nodes.get(id).and_then(|node| node.get("inputs"));
+
+    let sampler_inputs = node_ids
+        .iter()
+        .find(|id| class_of(id).contains("KSampler"))
+        .and_then(|id| inputs_of(id));
+    let model = node_ids.iter().find_map(|id| {
+        let class = class_of(id);
+        let inputs = inputs_of(id)?;
+        let key = if class.contains("CheckpointLoader") {
+            "ckpt_name"
+        } else if class == "UNETLoader" {
+            "unet_name"
+        } else {
+            return None;
+        };
+        inputs.get(key)?.as_str().map(str::to_string)
+    });
+    let input_str = |key: &str| {
+        sampler_inputs
+            .and_then(|inputs| inputs.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let linked_text = |key: &str| {
+        let link = sampler_inputs?.get(key)?;
+        comfyui_resolve_text(nodes, link, 0)
+    };
+    Some(GenerationParameters {
+        tool: GenerationTool::Comfyui,
+        prompt: linked_text("positive"),
+        negative_prompt: linked_text("negative"),
+        model,
+        sampler: input_str("sampler_name"),
+        scheduler: input_str("scheduler"),
+        steps: sampler_inputs
+            .and_then(|inputs| inputs.get("steps"))
+            .and_then(|v| v.as_i64())
+            .and_then(|v| i32::try_from(v).ok()),
+        cfg_scale: sampler_inputs
+            .and_then(|inputs| inputs.get("cfg"))
+            .and_then(|v| v.as_f64()),
+        seed: sampler_inputs
+            .and_then(|inputs| inputs.get("seed").or_else(|| inputs.get("noise_seed")))
+            .and_then(|v| v.as_i64()),
+        raw: graph.clone(),
+    })
+}
+
+/// Follow a `[node_id, output_index]` link to the node's `text` input,
+/// tolerating a few hops through primitive/concat nodes.
+fn comfyui_resolve_text(
+    nodes: &serde_json::Map<String, serde_json::Value>,
+    link: &serde_json::Value,
+    depth: usize,
+) -> Option<String> {
+    if let Some(text) = link.as_str() {
+        return Some(text.to_string());
+    }
+    if depth >= 4 {
+        return None;
+    }
+    let node_id = match link.as_array()?.first()? {
+        serde_json::Value::String(id) => id.clone(),
+        serde_json::Value::Number(id) => id.to_string(),
+        _ => return None,
+    };
+    let inputs = nodes.get(&node_id)?.get("inputs")?;
+    ["text", "text_g", "string", "value"]
+        .iter()
+        .find_map(|key| comfyui_resolve_text(nodes, inputs.get(*key)?, depth + 1))
+}
+
+fn comfyui_workflow_only(text: &str) -> Option<GenerationParameters> {
+    let workflow: serde_json::Value = serde_json::from_str(text).ok()?;
+    workflow.get("nodes")?.as_array()?;
+    Some(GenerationParameters {
+        tool: GenerationTool::Comfyui,
+        prompt: None,
+        negative_prompt: None,
+        model: None,
+        sampler: None,
+        scheduler: None,
+        steps: None,
+        cfg_scale: None,
+        seed: None,
+        raw: workflow,
+    })
+}
+
+// ----- Container walking ------------------------------------------------------
+
+struct JpegSegment {
+    marker: u8,
+    /// Whole segment including the `FF xx` marker and length.
+    span: std::ops::Range<usize>,
+    /// Segment payload after the length field.
+    data: std::ops::Range<usize>,
+}
+
+/// Marker segments up to (not including) start-of-scan.
+fn jpeg_segments(bytes: &[u8]) -> Vec<JpegSegment> {
+    let mut segments = Vec::new();
+    let mut pos = 2usize;
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > bytes.len() {
+            break;
+        }
+        segments.push(JpegSegment {
+            marker,
+            span: pos..end,
+            data: pos + 4..end,
+        });
+        pos = end;
+    }
+    segments
+}
+
+/// Offset where JPEG entropy-coded data (SOS onwards) begins.
+fn jpeg_scan_start(bytes: &[u8]) -> usize {
+    jpeg_segments(bytes)
+        .last()
+        .map(|segment| segment.span.end)
+        .unwrap_or(2)
+}
+
+struct Chunk {
+    kind: [u8; 4],
+    span: std::ops::Range<usize>,
+    data: std::ops::Range<usize>,
+}
+
+fn png_chunks(bytes: &[u8]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 12 <= bytes.len() {
+        let len = u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+            as usize;
+        let kind: [u8; 4] = [
+            bytes[pos + 4],
+            bytes[pos + 5],
+            bytes[pos + 6],
+            bytes[pos + 7],
+        ];
+        let Some(end) = (pos + 8).checked_add(len).and_then(|d| d.checked_add(4)) else {
+            break;
+        };
+        if end > bytes.len() {
+            break;
+        }
+        chunks.push(Chunk {
+            kind,
+            span: pos..end,
+            data: pos + 8..end - 4,
+        });
+        pos = end;
+        if &kind == b"IEND" {
+            break;
+        }
+    }
+    chunks
+}
+
+/// Keyword + text of a `tEXt` or uncompressed `iTXt` chunk. Compressed
+/// `iTXt` / `zTXt` chunks are skipped; ComfyUI and A1111 write plain text.
+fn png_text_chunk(kind: &[u8; 4], data: &[u8]) -> Option<(String, String)> {
+    let nul = data.iter().position(|b| *b == 0)?;
+    let keyword = String::from_utf8_lossy(&data[..nul]).into_owned();
+    let rest = &data[nul + 1..];
+    let text = match kind {
+        b"tEXt" => rest.iter().map(|b| char::from(*b)).collect(),
+        b"iTXt" => {
+            let (&compressed, rest) = rest.split_first()?;
+            if compressed != 0 {
+                return None;
+            }
+            let rest = rest.get(1..)?;
+            let lang_end = rest.iter().position(|b| *b == 0)?;
+            let rest = &rest[lang_end + 1..];
+            let translated_end = rest.iter().position(|b| *b == 0)?;
+            String::from_utf8_lossy(&rest[translated_end + 1..]).into_owned()
+        }
+        _ => return None,
+    };
+    Some((keyword, text))
+}
+
+fn is_webp(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP"
+}
+
+fn riff_chunks(bytes: &[u8]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut pos = 12usize;
+    while pos + 8 <= bytes.len() {
+        let kind: [u8; 4] = [bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]];
+        let len = u32::from_le_bytes([
+            bytes[pos + 4],
+            bytes[pos + 5],
+            bytes[pos + 6],
+            bytes[pos + 7],
+        ]) as usize;
+        let Some(data_end) = (pos + 8).checked_add(len) else {
+            break;
+        };
+        if data_end > bytes.len() {
+            break;
+        }
+        let end = (data_end + (len & 1)).min(bytes.len());
+        chunks.push(Chunk {
+            kind,
+            span: pos..end,
+            data: pos + 8..data_end,
+        });
+        pos = end;
+    }
+    chunks
+}
+
+// ----- GPS stripping ------------------------------------------------------------
+
+/// Return a copy of `bytes` with GPS removed from every EXIF block (the GPS
+/// IFD is zeroed and unlinked from IFD0) and XMP packet (`exif:GPS*`
+/// properties). Returns `Ok(None)` when the file carries no GPS.
+///
+/// Image data and every other metadata field are preserved byte-for-byte.
+/// The result is re-parsed; a file whose GPS could not be removed is a
+/// validation error rather than a silently leaky export.
+pub fn strip_gps_metadata(bytes: &[u8]) -> AtelierResult<Option<Vec<u8>>> {
+    if !extract_media_metadata(bytes).has_gps() && !contains_gps_blocks(bytes) {
+        return Ok(None);
+    }
+    let stripped = if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg_gps(bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        strip_png_gps(bytes)
+    } else if is_webp(bytes) {
+        strip_webp_gps(bytes)
+    } else {
+        let mut copy = bytes.to_vec();
+        neutralize_tiff_gps(&mut copy);
+        copy
+    };
+    if extract_media_metadata(&stripped).has_gps() || contains_gps_blocks(&stripped) {
+        return Err(AtelierError::Validation(
+            "GPS metadata could not be stripped from media bytes".into(),
+        ));
+    }
+    Ok(Some(stripped))
+}
+
+/// True when any EXIF block still links a GPS IFD, even one without a
+/// parseable coordinate.
+fn contains_gps_blocks(bytes: &[u8]) -> bool {
+    locate_metadata_blocks(bytes).exif.iter().any(|exif| {
+        TiffReader::new(exif).is_some_and(|tiff| {
+            tiff.first_ifd()
+                .and_then(|offset| tiff.entries(offset))
+                .is_some_and(|ifd0| ifd0.iter().any(|e| e.tag == TAG_GPS_IFD))
+        })
+    })
+}
+
+/// Zero the GPS IFD (entries and out-of-line values) and remove its pointer
+/// from IFD0 in place. Buffer size and every other offset stay unchanged.
+fn neutralize_tiff_gps(tiff_bytes: &mut [u8]) -> bool {
+    let Some(tiff) = TiffReader::new(tiff_bytes) else {
+        return false;
+    };
+    let little_endian = tiff.little_endian;
+    let Some(ifd0_offset) = tiff.first_ifd() else {
+        return false;
+    };
+    let Some(ifd0) = tiff.entries(ifd0_offset) else {
+        return false;
+    };
+    let Some(pointer_index) = ifd0.iter().position(|e| e.tag == TAG_GPS_IFD) else {
+        return false;
+    };
+    let mut zero_ranges = Vec::new();
+    if let Some(gps_offset) = tiff.pointer(&ifd0, TAG_GPS_IFD) {
+        if let Some(gps_ifd) = tiff.entries(gps_offset) {
+            for entry in &gps_ifd {
+                if let Some(range) = tiff.value_range(entry) {
+                    zero_ranges.push(range);
+                }
+            }
+            let ifd_len = 2 + gps_ifd.len() * 12 + 4;
+            zero_ranges.push(gps_offset..(gps_offset + ifd_len).min(tiff_bytes.len()));
+        }
+    }
+    for range in zero_ranges {
+        tiff_bytes[range].fill(0);
+    }
+
+    // Shift the trailing entries plus the next-IFD offset left by one slot.
+    let count = ifd0.len();
+    let entry_start = ifd0_offset + 2 + pointer_index * 12;
+    let block_end = ifd0_offset + 2 + count * 12 + 4;
+    if block_end > tiff_bytes.len() {
+        return false;
+    }
+    tiff_bytes.copy_within(entry_start + 12..block_end, entry_start);
+    tiff_bytes[block_end - 12..block_end].fill(0);
+    let new_count = (count - 1) as u16;
+    let raw = if little_endian {
+        new_count.to_le_bytes()
+    } else {
+        new_count.to_be_bytes()
+    };
+    tiff_bytes[ifd0_offset..ifd0_offset + 2].copy_from_slice(&raw);
+    true
+}
+
+fn scrub_xmp_gps(packet: &str) -> String {
+    static GPS_ATTRIBUTE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+        regex::Regex::new(r#"\s+exif:GPS\w+\s*=\s*("[^"]*"|'[^']*')"#).expect("valid regex")
+    });
+    static GPS_EMPTY_ELEMENT: once_cell::sync::Lazy<regex::Regex> =
+        once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r"<exif:GPS\w+\b[^>]*/>").expect("valid regex")
+        });
+    static GPS_ELEMENT: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+        regex::Regex::new(r"(?s)<exif:GPS\w+\b[^>]*>.*?</exif:GPS\w+\s*>").expect("valid regex")
+    });
+    let scrubbed = GPS_ATTRIBUTE.replace_all(packet, "");
+    let scrubbed = GPS_EMPTY_ELEMENT.replace_all(&scrubbed, "");
+    GPS_ELEMENT.replace_all(&scrubbed, "").into_owned()
+}
+
+fn strip_jpeg_gps(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    for segment in jpeg_segments(bytes) {
+        let data = &bytes[segment.data.clone()];
+        if segment.marker == 0xE1 && data.starts_with(EXIF_HEADER) {
+            let mut copy = bytes[segment.span.clone()].to_vec();
+            neutralize_tiff_gps(&mut copy[4 + EXIF_HEADER.len()..]);
+            out.extend_from_slice(&copy);
+        } else if segment.marker == 0xE1 && data.starts_with(XMP_JPEG_HEADER) {
+            let packet = String::from_utf8_lossy(&data[XMP_JPEG_HEADER.len()..]);
+            let scrubbed = scrub_xmp_gps(&packet);
+            let payload_len = XMP_JPEG_HEADER.len() + scrubbed.len().min(MAX_XMP_SEGMENT_BYTES);
+            out.extend_from_slice(&[0xFF, 0xE1]);
+            out.extend_from_slice(&((payload_len + 2) as u16).to_be_bytes());
+            out.extend_from_slice(XMP_JPEG_HEADER);
+            out.extend_from_slice(&scrubbed.as_bytes()[..payload_len - XMP_JPEG_HEADER.len()]);
+        } else {
+            out.extend_from_slice(&bytes[segment.span.clone()]);
+        }
+    }
+    out.extend_from_slice(&bytes[jpeg_scan_start(bytes)..]);
+    out
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+fn strip_png_gps(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(PNG_SIGNATURE);
+    let mut end = PNG_SIGNATURE.len();
+    for chunk in png_chunks(bytes) {
+        let data = &bytes[chunk.data.clone()];
+        end = chunk.span.end;
+        match &chunk.kind {
+            b"eXIf" => {
+                let mut copy = data.to_vec();
+                neutralize_tiff_gps(&mut copy);
+                write_png_chunk(&mut out, b"eXIf", &copy);
+            }
+            b"tEXt" | b"iTXt"
+                if png_text_chunk(&chunk.kind, data)
+                    .is_some_and(|(keyword, _)| keyword == PNG_XMP_KEYWORD) =>
+            {
+                let (_, packet) = png_text_chunk(&chunk.kind, data).unwrap_or_default();
+                let header_len = data.len() - png_text_body_len(&chunk.kind, &packet);
+                let mut rebuilt = data[..header_len].to_vec();
+                let scrubbed = scrub_xmp_gps(&packet);
+                if &chunk.kind == b"tEXt" {
+                    rebuilt.extend(scrubbed.chars().map(|c| c as u8));
+                } else {
+                    rebuilt.extend_from_slice(scrubbed.as_bytes());
+                }
+                write_png_chunk(&mut out, &chunk.kind, &rebuilt);
+            }
+            _ => out.extend_from_slice(&bytes[chunk.span.clone()]),
+        }
+    }
+    out.extend_from_slice(&bytes[end..]);
+    out
+}
+
+/// Encoded byte length of a PNG text body as decoded by [`png_text_chunk`].
+fn png_text_body_len(kind: &[u8; 4], text: &str) -> usize {
+    if kind == b"tEXt" {
+        text.chars().count()
+    } else {
+        text.len()
+    }
+}
+
+fn strip_webp_gps(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..12]);
+    for chunk in riff_chunks(bytes) {
+        let data = &bytes[chunk.data.clone()];
+        let rebuilt = match &chunk.kind {
+            b"EXIF" => {
+                let mut copy = data.to_vec();
+                let tiff_start = if copy.starts_with(EXIF_HEADER) {
+                    EXIF_HEADER.len()
+                } else {
+                    0
+                };
+                neutralize_tiff_gps(&mut copy[tiff_start..]);
+                Some(copy)
+            }
+            b"XMP " => Some(scrub_xmp_gps(&String::from_utf8_lossy(data)).into_bytes()),
+            _ => None,
+        };
+        match rebuilt {
+            Some(data) => {
+                out.extend_from_slice(&chunk.kind);
+                out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                out.extend_from_slice(&data);
+                if data.len() % 2 == 1 {
+                    out.push(0);
+                }
+            }
+            None => out.extend_from_slice(&bytes[chunk.span.clone()]),
+        }
+    }
+    let riff_len = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    out
+}
+
+// ----- XMP sidecar ----------------------------------------------------------
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a standalone `.xmp` sidecar with `dc:subject` tags, `xmp:Rating`
+/// and the descriptive fields. Output is deterministic for identical input
+/// so unchanged organisation dedups to the same ArtifactStore content hash.
+pub fn render_xmp_sidecar(content: &XmpSidecarContent) -> String {
+    let mut attributes = String::new();
+    if let Some(rating) = content.rating {
+        attributes.push_str(&format!("\n    xmp:Rating=\"{}\"", rating.clamp(0, 5)));
+    }
+    let mut body = String::new();
+    let alt = |name: &str, value: &str| {
+        format!(
+            "   <dc:{name}>\n    <rdf:Alt>\n     <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n    </rdf:Alt>\n   </dc:{name}>\n",
+            xml_escape(value)
+        )
+    };
+    if let Some(creator) = &content.creator {
+        body.push_str(&format!(
+            "   <dc:creator>\n    <rdf:Seq>\n     <rdf:li>{}</rdf:li>\n    </rdf:Seq>\n   </dc:creator>\n",
+            xml_escape(creator)
+        ));
+    }
+    if let Some(title) = &content.title {
+        body.push_str(&alt("title", title));
+    }
+    if let Some(description) = &content.description {
+        body.push_str(&alt("description", description));
+    }
+    if !content.tags.is_empty() {
+        body.push_str("   <dc:subject>\n    <rdf:Bag>\n");
+        for tag in &content.tags {
+            body.push_str(&format!("     <rdf:li>{}</rdf:li>\n", xml_escape(tag)));
+        }
+        body.push_str("    </rdf:Bag>\n   </dc:subject>\n");
+    }
+    format!(
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n\
+ <rdf:RDF xmlns:rdf=\"{NS_RDF}\">\n\
+  <rdf:Description rdf:about=\"\"\n\
+    xmlns:dc=\"{NS_DC}\"\n\
+    xmlns:xmp=\"{NS_XMP}\"\n\
+    xmp:CreatorTool=\"Handshake\"{attributes}>\n\
+{body}  </rdf:Description>\n\
+ </rdf:RDF>\n\
+</x:xmpmeta>\n\
+<?xpacket end=\"w\"?>\n"
+    )
+}
+
+// ----- Storage ----------------------------------------------------------------
+
+const METADATA_COLUMNS: &str = "asset_id, camera_make, camera_model, lens_model, \
+    captured_at_local, gps_latitude, gps_longitude, gps_altitude_m, orientation, creator, \
+    title, description, keywords, embedded_rating, generator, generation_prompt, \
+    generation_negative_prompt, generation_model, generation_sampler, generation_scheduler, \
+    generation_steps, generation_cfg_scale, generation_seed, generation_json, \
+    metadata_sources, extracted_at_utc";
+
+fn metadata_record_from_row(row: &sqlx::postgres::PgRow) -> AtelierResult<MediaMetadataRecord> {
+    let generator: Option<String> = row.get("generator");
+    let generation = match generator {
+        Some(token) => Some(GenerationParameters {
+            tool: GenerationTool::from_token(&token)?,
+            prompt: row.get("generation_prompt"),
+            negative_prompt: row.get("generation_negative_prompt"),
+            model: row.get("generation_model"),
+            sampler: row.get("generation_sampler"),
+            scheduler: row.get("generation_scheduler"),
+            steps: row.get("generation_steps"),
+            cfg_scale: row.get("generation_cfg_scale"),
+            seed: row.get("generation_seed"),
+            raw: row
+                .get::<Option<serde_json::Value>, _>("generation_json")
+                .unwrap_or(serde_json::Value::Null),
+        }),
+        None => None,
+    };
+    Ok(MediaMetadataRecord {
+        asset_id: row.get("asset_id"),
+        metadata: MediaMetadata {
+            camera_make: row.get("camera_make"),
+            camera_model: row.get("camera_model"),
+            lens_model: row.get("lens_model"),
+            captured_at_local: row.get("captured_at_local"),
+            gps_latitude: row.get("gps_latitude"),
+            gps_longitude: row.get("gps_longitude"),
+            gps_altitude_m: row.get("gps_altitude_m"),
+            orientation: row.get("orientation"),
+            creator: row.get("creator"),
+            title: row.get("title"),
+            description: row.get("description"),
+            keywords: row.get("keywords"),
+            embedded_rating: row.get("embedded_rating"),
+            generation,
+            sources: row.get("metadata_sources"),
+        },
+        extracted_at_utc: row.get("extracted_at_utc"),
+    })
+}
+
+impl AtelierStore {
+    /// Read the asset's bytes from the ArtifactStore, persist its embedded
+    /// metadata, and recompute its metadata auto-tags from the saved tag
+    /// rules. Re-running replaces the previous row and `metadata_rule` tags;
+    /// manual and collection tags are never touched. Emits
+    /// `MEDIA_METADATA_EXTRACTED`.
+    pub async fn extract_media_asset_metadata(
+        &self,
+        asset_id: Uuid,
+    ) -> AtelierResult<MediaMetadataExtraction> {
+        let asset = self.get_media_asset(asset_id).await?;
+        let bytes = read_media_asset_payload(&asset)?;
+        let metadata = extract_media_metadata(&bytes);
+        let rules = self.list_tag_rules().await?;
+        let auto_tags: Vec<String> = evaluate_tag_rules(&rules, &metadata.tag_rule_fields())
+            .into_iter()
+            .collect();
+        let generation = metadata.generation.as_ref();
+
+        let mut tx = self.pool().begin().await?;
+        let row = sqlx::query(&format!(
+            r#"INSERT INTO atelier_media_metadata
+                 (asset_id, camera_make, camera_model, lens_model, captured_at_local,
+                  gps_latitude, gps_longitude, gps_altitude_m, orientation, creator,
+                  title, description, keywords, embedded_rating, generator,
+                  generation_prompt, generation_negative_prompt, generation_model,
+                  generation_sampler, generation_scheduler, generation_steps,
+                  generation_cfg_scale, generation_seed, generation_json, metadata_sources)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                       $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+               ON CONFLICT (asset_id) DO UPDATE SET
+                 camera_make = EXCLUDED.camera_make,
+                 camera_model = EXCLUDED.camera_model,
+                 lens_model = EXCLUDED.lens_model,
+                 captured_at_local = EXCLUDED.captured_at_local,
+                 gps_latitude = EXCLUDED.gps_latitude,
+                 gps_longitude = EXCLUDED.gps_longitude,
+                 gps_altitude_m = EXCLUDED.gps_altitude_m,
+                 orientation = EXCLUDED.orientation,
+                 creator = EXCLUDED.creator,
+                 title = EXCLUDED.title,
+                 description = EXCLUDED.description,
+                 keywords = EXCLUDED.keywords,
+                 embedded_rating = EXCLUDED.embedded_rating,
+                 generator = EXCLUDED.generator,
+                 generation_prompt = EXCLUDED.generation_prompt,
+                 generation_negative_prompt = EXCLUDED.generation_negative_prompt,
+                 generation_model = EXCLUDED.generation_model,
+                 generation_sampler = EXCLUDED.generation_sampler,
+                 generation_scheduler = EXCLUDED.generation_scheduler,
+                 generation_steps = EXCLUDED.generation_steps,
+                 generation_cfg_scale = EXCLUDED.generation_cfg_scale,
+                 generation_seed = EXCLUDED.generation_seed,
+                 generation_json = EXCLUDED.generation_json,
+                 metadata_sources = EXCLUDED.metadata_sources,
+                 extracted_at_utc = NOW()
+               RETURNING {METADATA_COLUMNS}"#
+        ))
+        .bind(asset_id)
+        .bind(&metadata.camera_make)
+        .bind(&metadata.camera_model)
+        .bind(&metadata.lens_model)
+        .bind(metadata.captured_at_local)
+        .bind(metadata.gps_latitude)
+        .bind(metadata.gps_longitude)
+        .bind(metadata.gps_altitude_m)
+        .bind(metadata.orientation)
+        .bind(&metadata.creator)
+        .bind(&metadata.title)
+        .bind(&metadata.description)
+        .bind(&metadata.keywords)
+        .bind(metadata.embedded_rating)
+        .bind(generation.map(|g| g.tool.as_token()))
+        .bind(generation.and_then(|g| g.prompt.clone()))
+        .bind(generation.and_then(|g| g.negative_prompt.clone()))
+        .bind(generation.and_then(|g| g.model.clone()))
+        .bind(generation.and_then(|g| g.sampler.clone()))
+        .bind(generation.and_then(|g| g.scheduler.clone()))
+        .bind(generation.and_then(|g| g.steps))
+        .bind(generation.and_then(|g| g.cfg_scale))
+        .bind(generation.and_then(|g| g.seed))
+        .bind(generation.map(|g| g.raw.clone()))
+        .bind(&metadata.sources)
+        .fetch_one(&mut *tx)
+        .await?;
+        let record = metadata_record_from_row(&row)?;
+
+        sqlx::query("DELETE FROM atelier_media_asset_tag WHERE asset_id = $1 AND source = $2")
+            .bind(asset_id)
+            .bind(METADATA_RULE_TAG_SOURCE)
+            .execute(&mut *tx)
+            .await?;
+        for text in &auto_tags {
+            let tag_id: Uuid = sqlx::query_scalar(
+                r#"INSERT INTO atelier_tag (text)
+                   VALUES ($1)
+                   ON CONFLICT (text) DO UPDATE SET text = EXCLUDED.text
+                   RETURNING tag_id"#,
+            )
+            .bind(text)
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query(
+                r#"INSERT INTO atelier_media_asset_tag (asset_id, tag_id, source)
+                   VALUES ($1, $2, $3)
+                   ON CONFLICT (asset_id, tag_id) DO NOTHING"#,
+            )
+            .bind(asset_id)
+            .bind(tag_id)
+            .bind(METADATA_RULE_TAG_SOURCE)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // GPS coordinates stay in the row; the event only records presence.
+        self.record_event_in_tx(
+            &mut tx,
+            MEDIA_METADATA_EXTRACTED,
+            "atelier_media_metadata",
+            &asset_id.to_string(),
+            serde_json::json!({
+                "asset_id": asset_id,
+                "sources": record.metadata.sources,
+                "has_gps": record.metadata.has_gps(),
+                "generator": generation.map(|g| g.tool.as_token()),
+                "keyword_count": record.metadata.keywords.len(),
+                "auto_tags": auto_tags,
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(MediaMetadataExtraction { record, auto_tags })
+    }
+
+    /// The stored metadata row for an asset, if extraction has run.
+    pub async fn get_media_metadata(
+        &self,
+        asset_id: Uuid,
+    ) -> AtelierResult<Option<MediaMetadataRecord>> {
+        let row = sqlx::query(&format!(
+            "SELECT {METADATA_COLUMNS} FROM atelier_media_metadata WHERE asset_id = $1"
+        ))
+        .bind(asset_id)
+        .fetch_optional(self.pool())
+        .await?;
+        row.as_ref().map(metadata_record_from_row).transpose()
+    }
+
+    /// Search assets by their typed metadata. Text filters are
+    /// case-insensitive substring matches; `keyword` matches one keyword
+    /// exactly (case-insensitive). Newest captures first.
+    pub async fn search_media_metadata(
+        &self,
+        query: &MediaMetadataQuery,
+    ) -> AtelierResult<Vec<MediaMetadataRecord>> {
+        let limit = query.limit.unwrap_or(100);
+        if !(1..=1000).contains(&limit) {
+            return Err(AtelierError::Validation(
+                "media metadata search limit must be between 1 and 1000".into(),
+            ));
+        }
+        let rows = sqlx::query(&format!(
+            r#"SELECT {METADATA_COLUMNS}
+               FROM atelier_media_metadata
+               WHERE ($1::text IS NULL OR camera_model ILIKE '%' || $1 || '%')
+                 AND ($2::text IS NULL OR creator ILIKE '%' || $2 || '%')
+                 AND ($3::text IS NULL OR EXISTS (
+                       SELECT 1 FROM unnest(keywords) AS k WHERE lower(k) = lower($3)))
+                 AND ($4::text IS NULL OR generator = $4)
+                 AND ($5::boolean IS NULL OR (gps_latitude IS NOT NULL) = $5)
+                 AND ($6::timestamp IS NULL OR captured_at_local >= $6)
+                 AND ($7::timestamp IS NULL OR captured_at_local <= $7)
+               ORDER BY captured_at_local DESC NULLS LAST, asset_id
+               LIMIT $8"#
+        ))
+        .bind(&query.camera_model)
+        .bind(&query.creator)
+        .bind(&query.keyword)
+        .bind(query.generator.map(GenerationTool::as_token))
+        .bind(query.has_gps)
+        .bind(query.captured_from)
+        .bind(query.captured_to)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        rows.iter().map(metadata_record_from_row).collect()
+    }
+
+    /// Write an XMP sidecar for the asset carrying its Handshake tags and
+    /// review rating (plus extracted creator/title/description), store it in
+    /// the ArtifactStore as a hidden sidecar media asset, and link it with an
+    /// `xmp_sidecar` relation. Identical organisation dedups onto the same
+    /// sidecar asset. Emits `MEDIA_XMP_SIDECAR_WRITTEN`.
+    pub async fn write_media_xmp_sidecar(
+        &self,
+        asset_id: Uuid,
+        created_by: &str,
+    ) -> AtelierResult<MediaSidecar> {
+        let asset = self.get_media_asset(asset_id).await?;
+        let tags: Vec<String> = self
+            .list_media_asset_tags(asset_id)
+            .await?
+            .into_iter()
+            .map(|tag| tag.text)
+            .collect();
+        let review = self.get_media_review_metadata(asset_id).await?;
+        let extracted = self.get_media_metadata(asset_id).await?.map(|r| r.metadata);
+        let content = XmpSidecarContent {
+            tags,
+            rating: review
+                .map(|review| review.rating)
+                .or_else(|| extracted.as_ref().and_then(|m| m.embedded_rating)),
+            creator: extracted.as_ref().and_then(|m| m.creator.clone()),
+            title: extracted.as_ref().and_then(|m| m.title.clone()),
+            description: extracted.as_ref().and_then(|m| m.description.clone()),
+        };
+        let xmp = render_xmp_sidecar(&content);
+        let written = write_media_artifact_payload(
+            xmp.as_bytes(),
+            XMP_SIDECAR_MIME,
+            &format!("{asset_id}.xmp"),
+        )?;
+        let sidecar_asset = self
+            .materialize_media_asset(&NewMediaAsset {
+                content_hash: written.content_hash.clone(),
+                mime: XMP_SIDECAR_MIME.to_string(),
+                byte_len: written.byte_len,
+                source_provenance: Some(format!("xmp_sidecar:{}", asset.asset_id)),
+                artifact_ref: written.artifact_ref,
+            })
+            .await?;
+        let sidecar = self
+            .record_media_sidecar_relation(&NewMediaSidecarRelation {
+                parent_asset_id: asset_id,
+                sidecar_asset_id: sidecar_asset.asset_id,
+                relation_kind: MediaSidecarRelationKind::XmpSidecar,
+                created_by: created_by.to_string(),
+            })
+            .await?;
+        self.record_event(
+            MEDIA_XMP_SIDECAR_WRITTEN,
+            "atelier_media_sidecar",
+            &sidecar.sidecar_id.to_string(),
+            serde_json::json!({
+                "asset_id": asset_id,
+                "sidecar_asset_id": sidecar_asset.asset_id,
+                "content_hash": sidecar_asset.content_hash,
+                "tag_count": content.tags.len(),
+                "rating": content.rating,
+            }),
+        )
+        .await?;
+        Ok(sidecar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_u16(out: &mut Vec<u8>, value: u16) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_u32(out: &mut Vec<u8>, value: u32) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_entry(out: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: u32) {
+        push_u16(out, tag);
+        push_u16(out, field_type);
+        push_u32(out, count);
+        push_u32(out, value);
+    }
+
+    /// Little-endian TIFF: IFD0 {Make, Model, Orientation, GPS pointer} and a
+    /// GPS IFD at 51°30'0" N, 0°7'30" W.
+    fn exif_tiff_with_gps() -> Vec<u8> {
+        let make = b"Canon\0";
+        let model = b"EOS R5\0";
+        let ifd0_offset = 8u32;
+        let ifd0_len = 2 + 4 * 12 + 4;
+        let make_offset = ifd0_offset + ifd0_len;
+        let model_offset = make_offset + make.len() as u32;
+        let gps_offset = model_offset + model.len() as u32;
+        let gps_len = 2 + 4 * 12 + 4;
+        let lat_offset = gps_offset + gps_len;
+        let lon_offset = lat_offset + 24;
+
+        let mut out = b"II*\0".to_vec();
+        push_u32(&mut out, ifd0_offset);
+        push_u16(&mut out, 4);
+        push_entry(&mut out, TAG_MAKE, 2, make.len() as u32, make_offset);
+        push_entry(&mut out, TAG_MODEL, 2, model.len() as u32, model_offset);
+        push_entry(&mut out, TAG_ORIENTATION, 3, 1, 6);
+        push_entry(&mut out, TAG_GPS_IFD, 4, 1, gps_offset);
+        push_u32(&mut out, 0);
+        out.extend_from_slice(make);
+        out.extend_from_slice(model);
+        push_u16(&mut out, 4);
+        push_entry(&mut out, TAG_GPS_LATITUDE_REF, 2, 2, u32::from(b'N'));
+        push_entry(&mut out, TAG_GPS_LATITUDE, 5, 3, lat_offset);
+        push_entry(&mut out, TAG_GPS_LONGITUDE_REF, 2, 2, u32::from(b'W'));
+        push_entry(&mut out, TAG_GPS_LONGITUDE, 5, 3, lon_offset);
+        push_u32(&mut out, 0);
+        for (numerator, denominator) in [(51, 1), (30, 1), (0, 1), (0, 1), (15, 2), (0, 1)] {
+            push_u32(&mut out, numerator);
+            push_u32(&mut out, denominator);
+        }
+        out
+    }
+
+    fn jpeg_with_app1(payloads: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = vec![0xFF, 0xD8];
+        for payload in payloads {
+            out.extend_from_slice(&[0xFF, 0xE1]);
+            out.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+            out.extend_from_slice(payload);
+        }
+        out.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0x11, 0x22, 0xFF, 0xD9]);
+        out
+    }
+
+    fn png_with_text(chunks: &[(&str, &str)]) -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_png_chunk(&mut out, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]);
+        for (keyword, text) in chunks {
+            let mut data = keyword.as_bytes().to_vec();
+            data.push(0);
+            data.extend_from_slice(text.as_bytes());
+            write_png_chunk(&mut out, b"tEXt", &data);
+        }
+        write_png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    #[test]
+    fn exif_in_jpeg_yields_camera_orientation_and_gps() {
+        let mut app1 = EXIF_HEADER.to_vec();
+        app1.extend(exif_tiff_with_gps());
+        let metadata = extract_media_metadata(&jpeg_with_app1(&[app1]));
+        assert_eq!(metadata.camera_make.as_deref(), Some("Canon"));
+        assert_eq!(metadata.camera_model.as_deref(), Some("EOS R5"));
+        assert_eq!(metadata.orientation, Some(6));
+        assert!((metadata.gps_latitude.unwrap() - 51.5).abs() < 1e-9);
+        assert!((metadata.gps_longitude.unwrap() + 0.125).abs() < 1e-9);
+        assert_eq!(metadata.sources, vec!["exif".to_string()]);
+    }
+
+    #[test]
+    fn xmp_packet_supplies_keywords_rating_creator_and_capture_time() {
+        let packet = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmlns:exif="http://ns.adobe.com/exif/1.0/"
+ xmp:Rating="4" exif:DateTimeOriginal="2024-05-01T10:20:30+02:00">
+<dc:creator><rdf:Seq><rdf:li>Ada</rdf:li></rdf:Seq></dc:creator>
+<dc:subject><rdf:Bag><rdf:li>portrait</rdf:li><rdf:li>studio</rdf:li></rdf:Bag></dc:subject>
+</rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        let mut app1 = XMP_JPEG_HEADER.to_vec();
+        app1.extend_from_slice(packet.as_bytes());
+        let metadata = extract_media_metadata(&jpeg_with_app1(&[app1]));
+        assert_eq!(metadata.creator.as_deref(), Some("Ada"));
+        assert_eq!(metadata.keywords, vec!["portrait", "studio"]);
+        assert_eq!(metadata.embedded_rating, Some(4));
+        assert_eq!(
+            metadata
+                .captured_at_local
+                .map(|at| at.to_string())
+                .as_deref(),
+            Some("2024-05-01 10:20:30")
+        );
+    }
+
+    #[test]
+    fn iptc_keywords_and_byline_are_read_from_photoshop_app13() {
+        let mut iim = Vec::new();
+        for (dataset, value) in [(80u8, "Grace"), (25, "harbour"), (25, "dusk")] {
+            iim.extend_from_slice(&[0x1C, 2, dataset]);
+            iim.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            iim.extend_from_slice(value.as_bytes());
+        }
+        let mut resource = b"8BIM\x04\x04\0\0".to_vec();
+        resource.extend_from_slice(&(iim.len() as u32).to_be_bytes());
+        resource.extend_from_slice(&iim);
+        let mut app13 = PHOTOSHOP_HEADER.to_vec();
+        app13.extend(resource);
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xED];
+        jpeg.extend_from_slice(&((app13.len() + 2) as u16).to_be_bytes());
+        jpeg.extend(app13);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+
+        let metadata = extract_media_metadata(&jpeg);
+        assert_eq!(metadata.creator.as_deref(), Some("Grace"));
+        assert_eq!(metadata.keywords, vec!["harbour", "dusk"]);
+        assert_eq!(metadata.sources, vec!["iptc".to_string()]);
+    }
+
+    #[test]
+    fn a1111_parameters_parse_prompt_negative_and_settings() {
+        let png = png_with_text(&[(
+            "parameters",
+            "a red fox, forest\nNegative prompt: blurry\nSteps: 30, Sampler: DPM++ 2M, CFG scale: 6.5, Seed: 1234, Size: 512x512, Model: sdxl_base",
+        )]);
+        let generation = extract_media_metadata(&png).generation.expect("generation");
+        assert_eq!(generation.tool, GenerationTool::A1111);
+        assert_eq!(generation.prompt.as_deref(), Some("a red fox, forest"));
+        assert_eq!(generation.negative_prompt.as_deref(), Some("blurry"));
+        assert_eq!(generation.steps, Some(30));
+        assert_eq!(generation.sampler.as_deref(), Some("DPM++ 2M"));
+        assert_eq!(generation.cfg_scale, Some(6.5));
+        assert_eq!(generation.seed, Some(1234));
+        assert_eq!(generation.model.as_deref(), Some("sdxl_base"));
+    }
+
+    #[test]
+    fn comfyui_prompt_graph_resolves_sampler_links_and_checkpoint() {
+        let graph = serde_json::json!({
+            "3": {"class_type": "KSampler", "inputs": {
+                "seed": 42, "steps": 20, "cfg": 7.0, "sampler_name": "euler",
+                "scheduler": "normal", "positive": ["6", 0], "negative": ["7", 0]}},
+            "4": {"class_type": "CheckpointLoaderSimple", "inputs": {"ckpt_name": "dream.safetensors"}},
+            "6": {"class_type": "CLIPTextEncode", "inputs": {"text": "castle at dawn"}},
+            "7": {"class_type": "CLIPTextEncode", "inputs": {"text": "lowres"}}
+        });
+        let png = png_with_text(&[("prompt", &graph.to_string())]);
+        let metadata = extract_media_metadata(&png);
+        let generation = metadata.generation.clone().expect("generation");
+        assert_eq!(generation.tool, GenerationTool::Comfyui);
+        assert_eq!(generation.prompt.as_deref(), Some("castle at dawn"));
+        assert_eq!(generation.negative_prompt.as_deref(), Some("lowres"));
+        assert_eq!(generation.model.as_deref(), Some("dream.safetensors"));
+        assert_eq!(generation.seed, Some(42));
+        let fields = metadata.tag_rule_fields();
+        assert_eq!(
+            fields.get("media.generator").map(String::as_str),
+            Some("comfyui")
+        );
+    }
+
+    #[test]
+    fn strip_gps_removes_exif_gps_and_keeps_camera_fields() {
+        let mut app1 = EXIF_HEADER.to_vec();
+        app1.extend(exif_tiff_with_gps());
+        let xmp = format!(
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"{NS_RDF}\"><rdf:Description xmlns:exif=\"{NS_EXIF}\" exif:GPSLatitude=\"51,30.0N\" exif:GPSLongitude=\"0,7.5W\"/></rdf:RDF></x:xmpmeta>"
+        );
+        let mut xmp_app1 = XMP_JPEG_HEADER.to_vec();
+        xmp_app1.extend_from_slice(xmp.as_bytes());
+        let jpeg = jpeg_with_app1(&[app1, xmp_app1]);
+
+        let stripped = strip_gps_metadata(&jpeg)
+            .expect("strip")
+            .expect("gps was present");
+        let metadata = extract_media_metadata(&stripped);
+        assert!(!metadata.has_gps());
+        assert_eq!(metadata.camera_model.as_deref(), Some("EOS R5"));
+        assert_eq!(metadata.orientation, Some(6));
+        assert!(stripped.ends_with(&[0xFF, 0xDA, 0x00, 0x02, 0x11, 0x22, 0xFF, 0xD9]));
+
+        assert_eq!(strip_gps_metadata(&stripped).expect("no-op"), None);
+    }
+
+    #[test]
+    fn xmp_sidecar_round_trips_tags_and_rating() {
+        let xmp = render_xmp_sidecar(&XmpSidecarContent {
+            tags: vec!["hero".into(), "r&d".into()],
+            rating: Some(5),
+            creator: Some("Ada".into()),
+            title: None,
+            description: None,
+        });
+        let mut metadata = MediaMetadata::default();
+        assert!(read_xmp(&xmp, &mut metadata));
+        assert_eq!(metadata.keywords, vec!["hero", "r&d"]);
+        assert_eq!(metadata.embedded_rating, Some(5));
+        assert_eq!(metadata.creator.as_deref(), Some("Ada"));
+    }
+}
@@ -42,6 +42,7 @@ pub mod image_import;
 pub mod intake;
 pub mod links;
 pub mod media;
+pub mod media_metadata;
 pub mod model_lease;
 pub mod model_manual_merge;
 pub mod moodboards;
@@ -79,6 +80,10 @@ pub use self::media::{
     MediaSourceProvenanceRefs, NewMediaAsset, NewMediaSidecarRelation,
     SetMediaSourceProvenanceRefs,
 };
+pub use self::media_metadata::{
+    GenerationParameters, GenerationTool, MediaMetadata, MediaMetadataExtraction,
+    MediaMetadataQuery, MediaMetadataRecord, XmpSidecarContent,
+};
 pub use self::relationships::{
     CharacterRelationship, CharacterRelationshipGraph, CharacterRelationshipGraphEdge,
     CharacterRelationshipGraphNode, NewCharacterRelationship, UpdateCharacterRelationship,
@@ -126,6 +131,7 @@ pub mod event_family {
     use super::filesystem_health::filesystem_health_event_family;
     use super::intake::intake_event_family;
     use super::links::links_event_family;
+    use super::media_metadata::media_metadata_event_family;
     use super::moodboards::moodboard_event_family;
     use super::pose::pose_event_family;
     use super::relationships::relationships_event_family;
@@ -180,6 +186,8 @@ pub mod event_family {
         MEDIA_SOURCE_PROVENANCE_REFS_SET,
         IMAGE_IMPORT_RECORDED,
         BULK_OPERATION_APPLIED,
+        media_metadata_event_family::MEDIA_METADATA_EXTRACTED,
+        media_metadata_event_family::MEDIA_XMP_SIDECAR_WRITTEN,
         comfy_event_family::PROBE_RECORDED,
         comfy_event_family::CAPABILITY_REGISTERED,
         comfy_event_family::CAPABILITY_REJECTED,
@@ -643,6 +651,13 @@ impl AtelierStore {
               AND to_regclass('atelier_pose_context_state') IS NOT NULL
               AND to_regclass('atelier_pose_workspace_rig_state') IS NOT NULL
               AND to_regclass('atelier_pose_deferred_feature') IS NOT NULL
+              AND to_regclass('atelier_media_metadata') IS NOT NULL
+              AND EXISTS (
+                  SELECT 1
+                  FROM pg_constraint
+                  WHERE conname = 'chk_atelier_media_sidecar_relation_kind'
+                    AND pg_get_constraintdef(oid) LIKE '%xmp_sidecar%'
+              )
               AND EXISTS (
                   SELECT 1
                   FROM information_schema.columns
@@ -2157,6 +2172,11 @@ impl AtelierStore {
         ))
         .execute(&mut *tx)
         .await?;
+        sqlx::raw_sql(include_str!(
+            "../../migrations/0343_atelier_media_metadata.sql"
+        ))
+        .execute(&mut *tx)
+        .await?;
         tx.commit().await?;
         self.repair_contact_sheet_manifest_schema_namespace()
             .await?;
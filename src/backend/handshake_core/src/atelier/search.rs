@@ -501,6 +501,39 @@ fn is_hex64(s: &str) -> bool {
     t.len() == 16 && t.chars().all(|c| c.is_ascii_hexdigit())
 }
 
+/// Evaluate the saved rule set against `field_id -> value` pairs and return
+/// the normalized emitted tags. Shared by character derived tags and media
+/// metadata auto-tags so both surfaces apply rules identically.
+pub(crate) fn evaluate_tag_rules(
+    rules: &[TagRule],
+    values_by_field: &HashMap<String, String>,
+) -> std::collections::BTreeSet<String> {
+    let mut emitted = std::collections::BTreeSet::new();
+    for rule in rules {
+        if !rule.enabled {
+            continue;
+        }
+        let Some(val) = values_by_field.get(&rule.source_field_id) else {
+            continue;
+        };
+        if val.is_empty() {
+            continue;
+        }
+        let matched = match rule.match_type {
+            MatchType::Equals => val == &rule.pattern,
+            MatchType::Contains => val.contains(&rule.pattern),
+            MatchType::Regex => match regex::Regex::new(&rule.pattern) {
+                Ok(re) => re.is_match(val),
+                Err(_) => false,
+            },
+        };
+        if matched {
+            emitted.insert(normalize_tag(&rule.emit_tag));
+        }
+    }
+    emitted
+}
+
 /// Normalize tag text: trim + lowercase so the dictionary dedupes case- and
 /// whitespace-insensitively, matching legacy source tag handling intent.
 pub(crate) fn normalize_tag(text: &str) -> String {
@@ -1751,30 +1784,7 @@ impl AtelierStore {
         values_by_field: &std::collections::HashMap<String, String>,
     ) -> AtelierResult<Vec<String>> {
         let rules = self.list_tag_rules().await?;
-
-        let mut emitted: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
-        for rule in &rules {
-            if !rule.enabled {
-                continue;
-            }
-            let Some(val) = values_by_field.get(&rule.source_field_id) else {
-                continue;
-            };
-            if val.is_empty() {
-                continue;
-            }
-            let matched = match rule.match_type {
-                MatchType::Equals => val == &rule.pattern,
-                MatchType::Contains => val.contains(&rule.pattern),
-                MatchType::Regex => match regex::Regex::new(&rule.pattern) {
-                    Ok(re) => re.is_match(val),
-                    Err(_) => false,
-                },
-            };
-            if matched {
-                emitted.insert(normalize_tag(&rule.emit_tag));
-            }
-        }
+        let emitted = evaluate_tag_rules(&rules, values_by_field);
 
         let mut tx = self.pool().begin().await?;
         sqlx::query(
@@ -0,0 +1,232 @@
+//! Operator CLI for the managed model store (`HANDSHAKE_MODEL_STORE_ROOT`,
+//! else the workspace's `.handshake/models`): list, import from a file or URL,
+//! re-verify digests, and collect unreferenced objects. Every command prints
+//! JSON; `verify` exits non-zero when any model fails.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use handshake_core::model_runtime::{
+    store::{ModelDownloadRequest, ModelImportRequest, ModelStore},
+    BaseModelTag, RuntimeBinding,
+};
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{}", usage());
+        return ExitCode::from(2);
+    };
+    match run(command, rest) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn run(command: &str, args: &[String]) -> Result<(), String> {
+    let store = ModelStore::open_default().map_err(|error| error.to_string())?;
+    let output = match command {
+        "list" => {
+            no_options(args)?;
+            serde_json::to_value(store.list().map_err(|error| error.to_string())?)
+        }
+        "usage" => {
+            no_options(args)?;
+            serde_json::to_value(store.usage().map_err(|error| error.to_string())?)
+        }
+        "import" => {
+            let options = ModelImportOptions::parse(args)?;
+            let manifest = match (options.path, options.url) {
+                (Some(path), None) => store
+                    .import_local(&path, &options.import)
+                    .map_err(|error| error.to_string())?,
+                (None, Some(url)) => {
+                    let request = ModelDownloadRequest {
+                        url,
+                        import: options.import,
+                        sidecar_urls: options.sidecar_urls,
+                    };
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|error| error.to_string())?;
+                    runtime
+                        .block_on(store.import_url(&reqwest::Client::new(), &request))
+                        .map_err(|error| error.to_string())?
+                }
+                _ => return Err(format!("pass exactly one of --path or --url\n{}", usage())),
+            };
+            serde_json::to_value(manifest)
+        }
+        "verify" => {
+            let reports = match ModelNameOption::parse(args)?.name {
+                Some(name) => vec![store.verify(&name).map_err(|error| error.to_string())?],
+                None => store.verify_all().map_err(|error| error.to_string())?,
+            };
+            let failed = reports.iter().filter(|report| !report.ok).count();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&reports).map_err(|error| error.to_string())?
+            );
+            if failed > 0 {
+                return Err(format!("{failed} stored model(s) failed verification"));
+            }
+            return Ok(());
+        }
+        "gc" => {
+            let options = ModelGcOptions::parse(args)?;
+            serde_json::to_value(
+                store
+                    .gc(
+                        &options.keep,
+                        options.include_partial_downloads,
+                        options.dry_run,
+                    )
+                    .map_err(|error| error.to_string())?,
+            )
+        }
+        _ => return Err(format!("unknown command {command}\n{}", usage())),
+    }
+    .map_err(|error| error.to_string())?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).map_err(|error| error.to_string())?
+    );
+    Ok(())
+}
+
+fn no_options(args: &[String]) -> Result<(), String> {
+    match args.first() {
+        Some(key) => Err(format!("unknown option {key}\n{}", usage())),
+        None => Ok(()),
+    }
+}
+
+struct ModelImportOptions {
+    path: Option<PathBuf>,
+    url: Option<String>,
+    import: ModelImportRequest,
+    sidecar_urls: BTreeMap<String, String>,
+}
+
+impl ModelImportOptions {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut path = None;
+        let mut url = None;
+        let mut import = ModelImportRequest::default();
+        let mut sidecar_urls = BTreeMap::new();
+
+        let mut index = 0;
+        while index < args.len() {
+            let key = args[index].as_str();
+            let value = args
+                .get(index + 1)
+                .ok_or_else(|| format!("missing value for {key}"))?;
+            match key {
+                "--path" => path = Some(PathBuf::from(value)),
+                "--url" => url = Some(value.clone()),
+                "--name" => import.name = value.clone(),
+                "--runtime" => import.runtime_binding = Some(parse_runtime_binding(value)?),
+                "--base-model" => {
+                    import.base_model_tag =
+                        Some(BaseModelTag::try_new(value).map_err(|error| error.to_string())?)
+                }
+                "--sha256" => import.expected_sha256 = Some(value.clone()),
+                "--sidecar" => {
+                    let (file_name, sidecar_url) = value
+                        .split_once('=')
+                        .ok_or_else(|| "--sidecar takes FILE=URL".to_string())?;
+                    sidecar_urls.insert(file_name.to_string(), sidecar_url.to_string());
+                }
+                _ => return Err(format!("unknown option {key}\n{}", usage())),
+            }
+            index += 2;
+        }
+
+        if import.name.is_empty() {
+            return Err("missing --name for import".to_string());
+        }
+        if !sidecar_urls.is_empty() && url.is_none() {
+            return Err("--sidecar only applies to --url imports".to_string());
+        }
+        Ok(Self {
+            path,
+            url,
+            import,
+            sidecar_urls,
+        })
+    }
+}
+
+struct ModelNameOption {
+    name: Option<String>,
+}
+
+impl ModelNameOption {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        match args {
+            [] => Ok(Self { name: None }),
+            [key, value] if key == "--name" => Ok(Self {
+                name: Some(value.clone()),
+            }),
+            [key, ..] => Err(format!("unknown option {key}\n{}", usage())),
+        }
+    }
+}
+
+struct ModelGcOptions {
+    keep: BTreeSet<String>,
+    include_partial_downloads: bool,
+    dry_run: bool,
+}
+
+impl ModelGcOptions {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut keep = BTreeSet::new();
+        let mut include_partial_downloads = false;
+        let mut dry_run = false;
+
+        let mut index = 0;
+        while index < args.len() {
+            let key = args[index].as_str();
+            match key {
+                "--dry-run" => dry_run = true,
+                "--partial-downloads" => include_partial_downloads = true,
+                "--keep" => {
+                    let value = args
+                        .get(index + 1)
+                        .ok_or_else(|| format!("missing value for {key}"))?;
+                    keep.insert(value.trim().to_ascii_lowercase());
+                    index += 1;
+                }
+                _ => return Err(format!("unknown option {key}\n{}", usage())),
+            }
+            index += 1;
+        }
+
+        Ok(Self {
+            keep,
+            include_partial_downloads,
+            dry_run,
+        })
+    }
+}
+
+fn parse_runtime_binding(value: &str) -> Result<RuntimeBinding, String> {
+    match value {
+        "llama_cpp" | "llama-cpp" => Ok(RuntimeBinding::LlamaCpp),
+        "candle" => Ok(RuntimeBinding::Candle),
+        _ => Err(format!("unsupported --runtime {value}")),
+    }
+}
+
+fn usage() -> String {
+    "usage: handshake-models list|usage\n       handshake-models import --name NAME (--path FILE | --url URL [--sidecar FILE=URL]...) [--runtime llama_cpp|candle] [--base-model TAG] [--sha256 HEX]\n       handshake-models verify [--name NAME]\n       handshake-models gc [--dry-run] [--partial-downloads] [--keep SHA256]...".to_string()
+}
@@ -3,9 +3,10 @@
 //! Operator-facing entrypoint for the LoRA distillation pipeline. The
 //! binary lives here; the orchestration + subprocess + provenance
 //! handling logic lives in
-//! `handshake_core::distillation::peft_pipeline`. The Python
-//! `scripts/distill/train_lora.py` runs the actual peft/transformers
-//! training step.
+//! `handshake_core::distillation::peft_pipeline`. `--trainer` picks who
+//! runs the training step: the Python `scripts/distill/train_lora.py`
+//! under peft/transformers (default), or the in-process candle trainer
+//! (`candle-runtime-engine` builds).
 //!
 //! Per MT-122 operator clarification 2026-05-20:
 //! - Distillation defaults to CLOUD-TEACHER -> LOCAL-STUDENT.
@@ -35,7 +36,9 @@
 //!     [--script <PATH>] \
 //!     [--rank <N>] [--alpha <FLOAT>] [--dropout <FLOAT>] \
 //!     [--epochs <N>] [--batch-size <N>] \
-//!     [--cpu-only]
+//!     [--cpu-only] \
+//!     [--trainer <python | candle>] \
+//!     [--workspace <ID>]
 //!
 //! Adult-production discipline (GLOBAL-PRODUCTION-002..009): the CLI
 //! never moralises, censors, or rewords operator content. The
@@ -49,13 +52,15 @@ use handshake_core::distillation::{
     content_review::ContentReviewConfig,
     peft_pipeline::{
         distill, read_training_corpus_jsonl, review_corpus, DistillError, DistillJobConfig,
-        PeftHyperparams, PeftProvenanceSidecar, PythonPeftTrainerExecutor, TeacherSource,
+        PeftHyperparams, PeftProvenanceSidecar, PeftTrainerExecutor, PythonPeftTrainerExecutor,
+        TeacherSource, TrainerBackend,
     },
 };
 use handshake_core::process_ledger::{
     LedgerBatcher, LedgerBatcherConfig, LedgerEvent, NoopOverflowSink, ProcessLedgerError,
     ProcessLedgerStore,
 };
+use handshake_core::storage::init_storage;
 
 /// In-process ProcessOwnershipLedger store for the CLI run (MT-122). Durably
 /// accumulates the trainer subprocess START/STOP rows so the fork is
@@ -101,17 +106,6 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
         &trainer_args.license_tag,
     )
     .map_err(|err| err.to_string())?;
-    let (_verdicts, summary) =
-        review_corpus(&corpus, ContentReviewConfig::defaults()).map_err(|err| err.to_string())?;
-    if summary.pass_count == 0 {
-        return Err(format!(
-            "PII/content review blocked PEFT training: {}",
-            DistillError::NoPassingTurns {
-                turn_count: summary.turn_count,
-                pass_count: summary.pass_count,
-            }
-        ));
-    }
     // MT-122: the trainer subprocess is registered in the
     // ProcessOwnershipLedger unconditionally. Stand up a current-thread tokio
     // runtime to host the LedgerBatcher's background writer; the writer drains
@@ -122,6 +116,21 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
         .build()
         .map_err(|err| format!("could not build ledger runtime: {err}"))?;
     let _guard = runtime.enter();
+    let review_config = match &trainer_args.workspace {
+        Some(workspace_id) => runtime.block_on(workspace_review_config(workspace_id))?,
+        None => ContentReviewConfig::defaults(),
+    };
+    let (_verdicts, summary) =
+        review_corpus(&corpus, review_config.clone()).map_err(|err| err.to_string())?;
+    if summary.pass_count == 0 {
+        return Err(format!(
+            "PII/content review blocked PEFT training: {}",
+            DistillError::NoPassingTurns {
+                turn_count: summary.turn_count,
+                pass_count: summary.pass_count,
+            }
+        ));
+    }
     let ledger_store = Arc::new(CliLedgerStore::default());
     let (ledger, _writer_task) = LedgerBatcher::spawn(
         ledger_store.clone(),
@@ -129,6 +138,7 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
         LedgerBatcherConfig::default(),
     );
     let executor = trainer_args.build_executor(Arc::new(ledger))?;
+    let trainer = trainer_args.trainer;
 
     // The CLI is a direct subprocess driver, but it is no longer a raw
     // corpus bypass: it reviews the JSONL in-process, writes a separate
@@ -140,8 +150,8 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
     distill(
         &corpus,
         config.clone(),
-        ContentReviewConfig::defaults(),
-        &executor,
+        review_config,
+        executor.as_ref(),
         &finished_at_utc,
     )
     .map_err(|err| err.to_string())?;
@@ -154,7 +164,7 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
         .await;
     });
 
-    // Read the provenance sidecar written by the Python trainer.
+    // Read the provenance sidecar the trainer wrote.
     let provenance_path = config.output_lora_dir.join("provenance.json");
     let provenance = PeftProvenanceSidecar::read_from(&provenance_path)
         .map_err(|err| format!("read provenance: {err}"))?;
@@ -164,6 +174,7 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
         "lora_dir": config.output_lora_dir.to_string_lossy(),
         "provenance_path": provenance_path.to_string_lossy(),
         "teacher_source": config.teacher_source.cli_arg_value(),
+        "trainer": trainer.cli_arg_value(),
         "teacher_model_id": provenance.teacher_model_id,
         "student_base_id": provenance.student_base_id,
         "corpus_sha256": provenance.corpus_sha256,
@@ -176,6 +187,17 @@ fn run_cli(args: Vec<String>) -> Result<String, String> {
     Ok(serde_json::to_string_pretty(&result).map_err(|err| err.to_string())?)
 }
 
+/// Review config carrying the workspace's governed detection rules, read
+/// from the control-plane database the backend uses.
+async fn workspace_review_config(workspace_id: &str) -> Result<ContentReviewConfig, String> {
+    let db = init_storage()
+        .await
+        .map_err(|err| format!("--workspace needs the control-plane database: {err}"))?;
+    ContentReviewConfig::for_workspace(db.as_ref(), workspace_id)
+        .await
+        .map_err(|err| format!("workspace {workspace_id} redaction rules: {err}"))
+}
+
 #[derive(Debug)]
 struct TrainerArgs {
     python: Option<PathBuf>,
@@ -190,12 +212,24 @@ struct TrainerArgs {
     hyperparams: PeftHyperparams,
     max_steps: Option<u32>,
     cpu_only: bool,
+    trainer: TrainerBackend,
+    workspace: Option<String>,
 }
 
 impl TrainerArgs {
     fn build_executor(
         &self,
         process_ledger: Arc<LedgerBatcher>,
+    ) -> Result<Box<dyn PeftTrainerExecutor>, String> {
+        match self.trainer {
+            TrainerBackend::Python => Ok(Box::new(self.python_executor(process_ledger)?)),
+            TrainerBackend::Candle => candle_executor(),
+        }
+    }
+
+    fn python_executor(
+        &self,
+        process_ledger: Arc<LedgerBatcher>,
     ) -> Result<PythonPeftTrainerExecutor, String> {
         let python_path = match &self.python {
             Some(path) => path.clone(),
@@ -225,6 +259,18 @@ impl TrainerArgs {
     }
 }
 
+#[cfg(feature = "candle-runtime-engine")]
+fn candle_executor() -> Result<Box<dyn PeftTrainerExecutor>, String> {
+    Ok(Box::new(
+        handshake_core::distillation::candle_trainer::CandleLoraTrainerExecutor::cpu(),
+    ))
+}
+
+#[cfg(not(feature = "candle-runtime-engine"))]
+fn candle_executor() -> Result<Box<dyn PeftTrainerExecutor>, String> {
+    Err("--trainer candle needs a build with the candle-runtime-engine feature".to_string())
+}
+
 #[derive(Default, Debug)]
 struct ParsedArgs {
     help: bool,
@@ -245,6 +291,8 @@ struct ParsedArgs {
     batch_size: Option<u32>,
     max_steps: Option<u32>,
     cpu_only: bool,
+    trainer: Option<TrainerBackend>,
+    workspace: Option<String>,
 }
 
 impl ParsedArgs {
@@ -288,6 +336,11 @@ impl ParsedArgs {
                     parsed.max_steps = Some(parse_u32(&next_value(&mut iter, &arg)?, &arg)?);
                 }
                 "--cpu-only" => parsed.cpu_only = true,
+                "--trainer" => {
+                    let v = next_value(&mut iter, &arg)?;
+                    parsed.trainer = Some(TrainerBackend::from_cli_arg(&v)?);
+                }
+                "--workspace" => parsed.workspace = Some(next_value(&mut iter, &arg)?),
                 other => return Err(format!("unknown argument {other:?}")),
             }
         }
@@ -350,6 +403,8 @@ impl ParsedArgs {
             hyperparams,
             max_steps: self.max_steps,
             cpu_only: self.cpu_only,
+            trainer: self.trainer.unwrap_or_default(),
+            workspace: self.workspace,
         })
     }
 }
@@ -448,11 +503,18 @@ fn help_text() -> &'static str {
        --batch-size <N>               Batch size (default 4)\n\
        --max-steps <N>                Maximum training steps (default trainer-defined)\n\
      \n\
-     Subprocess control:\n\
+     Trainer:\n\
+       --trainer python               PEFT/Transformers subprocess (default)\n\
+       --trainer candle               In-process candle trainer (CPU; no Python stack)\n\
+     \n\
+     Subprocess control (--trainer python):\n\
        --python <PATH>                Python interpreter (default: HANDSHAKE_PEFT_PYTHON or 'python')\n\
        --script <PATH>                Trainer script (default: <repo>/scripts/distill/train_lora.py)\n\
        --cpu-only                     Force CPU training (required on hosts without CUDA/MPS)\n\
      \n\
+     Content review:\n\
+       --workspace <ID>               Review with this workspace's redaction rules (needs DATABASE_URL)\n\
+     \n\
      Output:\n\
        Writes adapter_model.safetensors + adapter_config.json + provenance.json\n\
        to <out-lora>. Prints a JSON receipt with the provenance summary.\n"
@@ -494,6 +556,8 @@ mod tests {
             "--batch-size",
             "2",
             "--cpu-only",
+            "--trainer",
+            "candle",
         ]
         .into_iter()
         .map(String::from)
@@ -515,6 +579,7 @@ mod tests {
         assert!((trainer.hyperparams.learning_rate - 1e-4).abs() < f32::EPSILON);
         assert_eq!(trainer.hyperparams.batch_size, 2);
         assert!(trainer.cpu_only);
+        assert_eq!(trainer.trainer, TrainerBackend::Candle);
     }
 
     #[test]
@@ -537,6 +602,7 @@ mod tests {
         let parsed = ParsedArgs::parse(args).expect("parse");
         let trainer = parsed.into_trainer_args().expect("trainer args");
         assert_eq!(trainer.teacher_source, TeacherSource::CliBridge);
+        assert_eq!(trainer.trainer, TrainerBackend::Python);
     }
 
     #[test]
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
@@ -25,6 +25,7 @@ use crate::bundles::schemas::{
 };
 use crate::bundles::templates::{render_coder_prompt, render_repro_md};
 use crate::bundles::zip::{sha256_hex, BundleFileEntry};
+use crate::cassette::{list_trace_cassettes, Cassette};
 use crate::diagnostics::{DiagFilter, Diagnostic, DiagnosticSeverity, LinkConfidence};
 use crate::flight_recorder::{
     EventFilter, FlightRecorderActor, FlightRecorderEvent, FlightRecorderEventType,
@@ -33,6 +34,7 @@ use crate::flight_recorder::{
 use crate::governance_pack::{
     DeterminismLevel, ExportActor, ExportRecord, ExportTarget, ExporterInfo,
 };
+use crate::redaction::DetectionEngine;
 use crate::storage::artifacts::{
     artifact_root_rel, bundle_index_content_hash, bundle_index_json, compute_entries_index,
     materialize_local_dir, resolve_workspace_root, write_dir_artifact, ArtifactClassification,
@@ -152,6 +154,10 @@ pub struct DebugBundleRequest {
     pub redaction_mode: RedactionMode,
     pub output_path: Option<PathBuf>,
     pub include_artifacts: bool,
+    /// Ship the LLM/MCP cassettes recorded for the scoped jobs' traces under
+    /// `cassettes/<trace_id>/`, so a failed AI job can be replayed offline.
+    #[serde(default)]
+    pub include_cassettes: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -250,11 +256,39 @@ pub trait DebugBundleExporter: Send + Sync {
 #[derive(Clone)]
 pub struct DefaultDebugBundleExporter {
     state: AppState,
+    /// Detection engine override for the bundle redactor. Without one the
+    /// redactor uses the bundled workspace's governed rules.
+    detection: Option<Arc<DetectionEngine>>,
 }
 
 impl DefaultDebugBundleExporter {
     pub fn new(state: AppState) -> Self {
-        Self { state }
+        Self {
+            state,
+            detection: None,
+        }
+    }
+
+    pub fn with_detection_engine(mut self, detection: Arc<DetectionEngine>) -> Self {
+        self.detection = Some(detection);
+        self
+    }
+
+    /// Redactor for a bundle of `wsid`'s evidence: the override engine if
+    /// set, else the workspace's rules, else (no workspace) the built-in
+    /// engine.
+    async fn redactor_for(&self, wsid: Option<&str>) -> Result<SecretRedactor, BundleExportError> {
+        if let Some(detection) = &self.detection {
+            return Ok(SecretRedactor::new().with_engine(detection.clone()));
+        }
+        match wsid {
+            Some(wsid) => SecretRedactor::for_workspace(self.state.storage.as_ref(), wsid)
+                .await
+                .map_err(|e| {
+                    BundleExportError::ExportFailed(format!("redaction rules unavailable: {e}"))
+                }),
+            None => Ok(SecretRedactor::new()),
+        }
     }
 
     pub async fn export_for_job(
@@ -1179,7 +1213,6 @@ async fn export_impl(
     let output_dir = fs::canonicalize(&output_dir)?;
 
     let mut scope = exporter.build_manifest_scope(&request.scope);
-    let redactor = SecretRedactor::new();
 
     let (diagnostics_raw, missing_evidence, mut events_raw, jobs_raw, workflow_nodes_raw) =
         match &request.scope {
@@ -1340,6 +1373,7 @@ async fn export_impl(
             .next()
             .or_else(|| jobs.iter().filter_map(|j| j.wsid.clone()).next());
     }
+    let redactor = exporter.redactor_for(scope.wsid.as_deref()).await?;
 
     let env = exporter.build_env(scope.wsid.clone(), request.redaction_mode);
 
@@ -1601,9 +1635,66 @@ async fn export_impl(
         redacted: prompt_redacted,
     });
 
+    // cassettes/<trace_id>/*.cassette.json (opt-in). Re-redacted with the bundle
+    // mode, which is then stamped on the copy so replay matches against it.
+    let mut cassette_count: u32 = 0;
+    if request.include_cassettes {
+        let mut trace_ids: Vec<Uuid> = jobs_raw.iter().map(|job| job.trace_id).collect();
+        trace_ids.sort();
+        trace_ids.dedup();
+        for trace_id in trace_ids {
+            let paths = list_trace_cassettes(&workspace_root, trace_id).map_err(|e| {
+                BundleExportError::ExportFailed(format!("list cassettes for {trace_id}: {e}"))
+            })?;
+            for path in paths {
+                let file_name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default();
+                let bundle_file = format!("cassettes/{trace_id}/{file_name}");
+                // Recorded cassettes are JSON lines; the bundle copy is one document.
+                let cassette = Cassette::load(&path)
+                    .and_then(|cassette| Ok(serde_json::to_value(cassette)?))
+                    .map_err(|e| {
+                        BundleExportError::ExportFailed(format!("parse {bundle_file}: {e}"))
+                    })?;
+                let (mut cassette, cassette_logs) =
+                    redactor.redact_value(&cassette, request.redaction_mode, "$.cassette");
+                if let Some(obj) = cassette.as_object_mut() {
+                    obj.insert(
+                        "redaction_mode".to_string(),
+                        serde_json::to_value(request.redaction_mode).unwrap_or(Value::Null),
+                    );
+                }
+                redaction_logs.extend(
+                    cassette_logs
+                        .into_iter()
+                        .map(|mut log| {
+                            log.file = bundle_file.clone();
+                            log
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                let bytes = serde_json::to_vec_pretty(&cassette).map_err(|e| {
+                    BundleExportError::ExportFailed(format!("serialize {bundle_file}: {e}"))
+                })?;
+                files.push(BundleFileEntry {
+                    path: bundle_file,
+                    bytes,
+                    redacted: true,
+                });
+                cassette_count += 1;
+            }
+        }
+    }
+
     // redaction_report.json (must include all redactions across bundle files)
-    let redaction_report =
-        exporter.build_redaction_report(request.redaction_mode, &redactor, &redaction_logs, 6);
+    let redaction_report = exporter.build_redaction_report(
+        request.redaction_mode,
+        &redactor,
+        &redaction_logs,
+        6 + cassette_count,
+    );
     let redaction_bytes = serde_json::to_vec_pretty(&redaction_report).map_err(|e| {
         BundleExportError::ExportFailed(format!("serialize redaction_report: {}", e))
     })?;
@@ -2729,6 +2820,7 @@ mod tests {
                 redaction_mode: RedactionMode::SafeDefault,
                 output_path: Some(output_dir.clone()),
                 include_artifacts: false,
+                include_cassettes: false,
             })
             .await?;
 
@@ -2822,6 +2914,7 @@ mod tests {
                 redaction_mode: RedactionMode::SafeDefault,
                 output_path: Some(output_dir.clone()),
                 include_artifacts: false,
+                include_cassettes: false,
             })
             .await?;
 
@@ -2944,6 +3037,7 @@ mod tests {
                 redaction_mode: RedactionMode::SafeDefault,
                 output_path: Some(output_dir),
                 include_artifacts: false,
+                include_cassettes: false,
             })
             .await;
 
@@ -2981,6 +3075,7 @@ mod tests {
                 redaction_mode: RedactionMode::SafeDefault,
                 output_path: Some(output_dir),
                 include_artifacts: false,
+                include_cassettes: false,
             })
             .await;
 
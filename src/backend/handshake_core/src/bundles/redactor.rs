@@ -2,11 +2,14 @@ use chrono::Utc;
 use regex::{Captures, Regex};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::bundles::schemas::{
     PolicyDecision, RedactionDetector, RedactionLogEntry, RedactionMode, RedactionReport,
     RedactionSummary,
 };
+use crate::redaction::{DetectionEngine, DetectionRuleError, DetectionSurface, ENGINE_VERSION};
+use crate::storage::Database;
 
 #[derive(Debug, Clone)]
 pub struct SecretPattern {
@@ -18,11 +21,17 @@ pub struct SecretPattern {
 #[derive(Debug, Clone, Default)]
 pub struct SecretRedactor {
     patterns: Vec<SecretPattern>,
+    /// Shared detection engine run after the bundle patterns. Its workspace
+    /// allow rules also suppress bundle-pattern matches.
+    engine: Arc<DetectionEngine>,
 }
 
 const MACHINE_LOCAL_PATH_PATTERN: &str = r#"(?m)(^|[\s"'=:\(\[<`,;])([A-Za-z]:[\\/](?:[^\\/"'\r\n\s,;\)\]>`][^"'\r\n\)\]>`]*)?|\\\\[^"'\r\n]+(?:[\\/][^"'\r\n]+)*|/(?:Users|home)(?:/[^\s"',;\)\]>`]*)?|/tmp(?:/[^\s"',;\)\]>`]*)?|/var(?:/[^\s"',;\)\]>`]*)?)([\s"',;\)\]>`]|$)"#;
 
 impl SecretRedactor {
+    /// Bundle patterns plus the built-in detection engine, for content that
+    /// belongs to no workspace. Workspace-scoped content goes through
+    /// [`SecretRedactor::for_workspace`].
     pub fn new() -> Self {
         let mut patterns = Vec::new();
         // Helper to push compiled regex while ignoring invalid patterns gracefully.
@@ -121,7 +130,25 @@ impl SecretRedactor {
             &mut patterns,
         );
 
-        Self { patterns }
+        Self {
+            patterns,
+            engine: Arc::new(DetectionEngine::shared().clone()),
+        }
+    }
+
+    /// Bundle patterns plus `workspace_id`'s governed detection rules.
+    pub async fn for_workspace(
+        db: &dyn Database,
+        workspace_id: &str,
+    ) -> Result<Self, DetectionRuleError> {
+        let engine = DetectionEngine::for_workspace(db, workspace_id).await?;
+        Ok(Self::new().with_engine(Arc::new(engine)))
+    }
+
+    /// Use a workspace-governed detection engine instead of the built-in one.
+    pub fn with_engine(mut self, engine: Arc<DetectionEngine>) -> Self {
+        self.engine = engine;
+        self
     }
 
     /// Redact a JSON value, returning the redacted value and the log entries.
@@ -183,7 +210,11 @@ impl SecretRedactor {
             }
 
             let replacement = format!("[REDACTED:{}:{}]", pattern.category, pattern.id);
-            if pattern.regex.is_match(&redacted) {
+            let applies = pattern
+                .regex
+                .find_iter(&redacted)
+                .any(|m| !self.engine.is_allowed(&pattern.id, m.as_str()));
+            if applies {
                 redacted = if pattern.id == "path_absolute" {
                     pattern
                         .regex
@@ -196,7 +227,14 @@ impl SecretRedactor {
                 } else {
                     pattern
                         .regex
-                        .replace_all(&redacted, replacement.as_str())
+                        .replace_all(&redacted, |captures: &Captures<'_>| {
+                            let matched = &captures[0];
+                            if self.engine.is_allowed(&pattern.id, matched) {
+                                matched.to_string()
+                            } else {
+                                replacement.clone()
+                            }
+                        })
                         .into_owned()
                 };
                 logs.push(RedactionLogEntry {
@@ -209,7 +247,40 @@ impl SecretRedactor {
             }
         }
 
-        (Value::String(redacted), logs)
+        // Second pass: whatever the bundle patterns left behind, checked by the
+        // shared engine (validated cards/IBANs/JWTs, vendor keys, workspace
+        // deny and custom rules).
+        let (engine_redacted, report) =
+            self.engine
+                .redact(&redacted, DetectionSurface::DebugBundle, |finding| {
+                    format!(
+                        "[REDACTED:{}:{}]",
+                        finding.category.as_str(),
+                        finding.detector_id
+                    )
+                });
+        for finding in &report.findings {
+            let replacement = format!(
+                "[REDACTED:{}:{}]",
+                finding.category.as_str(),
+                finding.detector_id
+            );
+            if logs
+                .iter()
+                .any(|log| log.detector_id == finding.detector_id && log.replacement == replacement)
+            {
+                continue;
+            }
+            logs.push(RedactionLogEntry {
+                file: "".to_string(),
+                location: location.to_string(),
+                category: finding.category.as_str().to_string(),
+                detector_id: finding.detector_id.clone(),
+                replacement,
+            });
+        }
+
+        (Value::String(engine_redacted), logs)
     }
 
     pub fn build_report(
@@ -231,6 +302,16 @@ impl SecretRedactor {
                 version: "1".to_string(),
                 patterns_count: 1,
             })
+            .chain(
+                self.engine
+                    .detector_ids()
+                    .into_iter()
+                    .map(|id| RedactionDetector {
+                        id,
+                        version: ENGINE_VERSION.to_string(),
+                        patterns_count: 1,
+                    }),
+            )
             .collect();
 
         RedactionReport {
@@ -273,4 +354,39 @@ mod tests {
         );
         assert!(!logs.is_empty(), "expected redaction logs to be captured");
     }
+
+    #[test]
+    fn shared_engine_and_workspace_rules_apply_to_bundles() {
+        let engine = DetectionEngine::with_rules(
+            serde_json::from_value(json!({
+                "allow": [{ "id": "fixture-email", "literal": "fixtures@handshake.test" }],
+                "deny": [{ "id": "codename", "literal": "Project Nightjar" }]
+            }))
+            .expect("rules"),
+        )
+        .expect("engine");
+        let redactor = SecretRedactor::new().with_engine(Arc::new(engine));
+        let (value, logs) = redactor.redact_value(
+            &json!("card 4242 4242 4242 4242 for Project Nightjar, cc fixtures@handshake.test"),
+            RedactionMode::FullLocal,
+            "$",
+        );
+        let rendered = value.as_str().unwrap_or_default();
+        assert!(!rendered.contains("4242"), "got {rendered}");
+        assert!(
+            rendered.contains("[REDACTED:custom:deny:codename]"),
+            "got {rendered}"
+        );
+        assert!(
+            rendered.contains("fixtures@handshake.test"),
+            "got {rendered}"
+        );
+        assert!(logs.iter().all(|log| log.detector_id != "pii_email"));
+
+        let report = redactor.build_report(&logs, RedactionMode::FullLocal, 1);
+        assert!(report
+            .detectors
+            .iter()
+            .any(|d| d.id == "deny:codename" && d.version == ENGINE_VERSION));
+    }
 }
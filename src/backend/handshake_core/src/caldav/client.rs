@@ -0,0 +1,632 @@
+//! WebDAV/CalDAV HTTP client (RFC 4791, RFC 6578 sync-collection, calendarserver `getctag`).
+
+use reqwest::header::{HeaderMap, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::caldav::CaldavError;
+
+pub const DAV_NS: &str = "DAV:";
+pub const CALDAV_NS: &str = "urn:ietf:params:xml:ns:caldav";
+pub const CALSERVER_NS: &str = "http://calendarserver.org/ns/";
+
+/// Objects fetched per `calendar-multiget` REPORT.
+const MULTIGET_CHUNK: usize = 50;
+
+/// Credentials stored in the secrets vault under a calendar source's `credentials_ref`, as JSON:
+/// `{"username": "...", "password": "..."}` or `{"bearer_token": "..."}`.
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CaldavCredentials {
+    Basic { username: String, password: String },
+    Bearer { bearer_token: String },
+}
+
+impl std::fmt::Debug for CaldavCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic { username, .. } => write!(f, "Basic({username}, <redacted>)"),
+            Self::Bearer { .. } => write!(f, "Bearer(<redacted>)"),
+        }
+    }
+}
+
+impl CaldavCredentials {
+    pub fn from_secret(secret: &str) -> Result<Self, CaldavError> {
+        serde_json::from_str(secret).map_err(|_| {
+            CaldavError::Credentials(
+                "vault secret must be {\"username\",\"password\"} or {\"bearer_token\"} JSON"
+                    .to_string(),
+            )
+        })
+    }
+}
+
+/// A calendar collection found by discovery.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaldavCalendar {
+    pub href: String,
+    pub display_name: Option<String>,
+    pub ctag: Option<String>,
+    pub sync_token: Option<String>,
+    /// Empty when the server does not advertise `supported-calendar-component-set`.
+    pub components: Vec<String>,
+}
+
+impl CaldavCalendar {
+    pub fn supports_events(&self) -> bool {
+        self.components.is_empty() || self.components.iter().any(|c| c == "VEVENT")
+    }
+}
+
+/// Changes reported by a `sync-collection` REPORT.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncDelta {
+    pub changed: Vec<(String, Option<String>)>,
+    pub removed: Vec<String>,
+    pub sync_token: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarObject {
+    pub href: String,
+    pub etag: Option<String>,
+    pub ics: String,
+}
+
+/// Guard for a write: create-only, or replace/delete only the version we last saw.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WritePrecondition {
+    Create,
+    Match(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// The server accepted the write; `etag` is absent when the server does not return one.
+    Stored {
+        etag: Option<String>,
+    },
+    PreconditionFailed,
+}
+
+#[derive(Clone, Debug, Default)]
+struct DavProp {
+    ns: String,
+    name: String,
+    text: String,
+    hrefs: Vec<String>,
+    children: Vec<(String, String)>,
+    comp_names: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct DavResponse {
+    href: String,
+    status: Option<u16>,
+    props: Vec<DavProp>,
+}
+
+impl DavResponse {
+    fn prop(&self, ns: &str, name: &str) -> Option<&DavProp> {
+        self.props.iter().find(|p| p.ns == ns && p.name == name)
+    }
+
+    fn text(&self, ns: &str, name: &str) -> Option<String> {
+        self.prop(ns, name)
+            .map(|p| p.text.trim().to_string())
+            .filter(|t| !t.is_empty())
+    }
+
+    fn is_calendar(&self) -> bool {
+        self.prop(DAV_NS, "resourcetype").is_some_and(|p| {
+            p.children
+                .iter()
+                .any(|(ns, n)| ns == CALDAV_NS && n == "calendar")
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Multistatus {
+    responses: Vec<DavResponse>,
+    sync_token: Option<String>,
+}
+
+fn parse_status(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn is(node: &roxmltree::Node<'_, '_>, ns: &str, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name && node.tag_name().namespace() == Some(ns)
+}
+
+fn parse_multistatus(body: &str) -> Result<Multistatus, CaldavError> {
+    let doc = roxmltree::Document::parse(body).map_err(|e| CaldavError::Xml(e.to_string()))?;
+    let root = doc.root_element();
+    if !is(&root, DAV_NS, "multistatus") {
+        return Err(CaldavError::Xml(
+            "expected a DAV:multistatus body".to_string(),
+        ));
+    }
+
+    let mut out = Multistatus::default();
+    for child in root.children().filter(|n| n.is_element()) {
+        if is(&child, DAV_NS, "sync-token") {
+            out.sync_token = child.text().map(|t| t.trim().to_string());
+            continue;
+        }
+        if !is(&child, DAV_NS, "response") {
+            continue;
+        }
+        let mut response = DavResponse::default();
+        for part in child.children().filter(|n| n.is_element()) {
+            if is(&part, DAV_NS, "href") {
+                response.href = part.text().unwrap_or_default().trim().to_string();
+            } else if is(&part, DAV_NS, "status") {
+                response.status = parse_status(part.text().unwrap_or_default());
+            } else if is(&part, DAV_NS, "propstat") {
+                let ok = part
+                    .children()
+                    .find(|n| is(n, DAV_NS, "status"))
+                    .and_then(|n| parse_status(n.text().unwrap_or_default()))
+                    .is_some_and(|code| (200..300).contains(&code));
+                let Some(prop) = part.children().find(|n| is(n, DAV_NS, "prop")) else {
+                    continue;
+                };
+                if !ok {
+                    continue;
+                }
+                for value in prop.children().filter(|n| n.is_element()) {
+                    let elements = || value.children().filter(|n| n.is_element());
+                    response.props.push(DavProp {
+                        ns: value.tag_name().namespace().unwrap_or_default().to_string(),
+                        name: value.tag_name().name().to_string(),
+                        text: value.text().unwrap_or_default().to_string(),
+                        hrefs: elements()
+                            .filter(|n| is(n, DAV_NS, "href"))
+                            .filter_map(|n| n.text().map(|t| t.trim().to_string()))
+                            .collect(),
+                        children: elements()
+                            .map(|n| {
+                                let ns = n.tag_name().namespace().unwrap_or_default();
+                                (ns.to_string(), n.tag_name().name().to_string())
+                            })
+                            .collect(),
+                        comp_names: elements()
+                            .filter(|n| is(n, CALDAV_NS, "comp"))
+                            .filter_map(|n| n.attribute("name").map(str::to_ascii_uppercase))
+                            .collect(),
+                    });
+                }
+            }
+        }
+        out.responses.push(response);
+    }
+    Ok(out)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[derive(Clone, Debug)]
+pub struct CaldavClient {
+    http: reqwest::Client,
+    base: Url,
+    credentials: CaldavCredentials,
+}
+
+impl CaldavClient {
+    pub fn new(base_url: &str, credentials: CaldavCredentials) -> Result<Self, CaldavError> {
+        let base = Url::parse(base_url)
+            .map_err(|e| CaldavError::Config(format!("invalid CalDAV url {base_url}: {e}")))?;
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| CaldavError::Http(e.to_string()))?;
+        Ok(Self {
+            http,
+            base,
+            credentials,
+        })
+    }
+
+    /// The path of the configured base URL, in the same form servers use for `DAV:href`.
+    pub fn base_href(&self) -> &str {
+        self.base.path()
+    }
+
+    /// Resolve a server href (absolute path or URL) against the base URL, returning its path.
+    pub fn href_for(&self, href: &str) -> String {
+        self.base
+            .join(href)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| href.to_string())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        href: &str,
+        headers: HeaderMap,
+        body: Option<String>,
+    ) -> Result<(StatusCode, HeaderMap, String), CaldavError> {
+        let url = self
+            .base
+            .join(href)
+            .map_err(|e| CaldavError::Config(format!("invalid href {href}: {e}")))?;
+        let mut request = self.http.request(method, url).headers(headers);
+        request = match &self.credentials {
+            CaldavCredentials::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+            CaldavCredentials::Bearer { bearer_token } => request.bearer_auth(bearer_token),
+        };
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| CaldavError::Http(e.to_string()))?;
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(CaldavError::Unauthorized);
+        }
+        let headers = response.headers().clone();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| CaldavError::Http(e.to_string()))?;
+        Ok((status, headers, text))
+    }
+
+    async fn xml_request(
+        &self,
+        method: &'static str,
+        href: &str,
+        depth: &'static str,
+        body: String,
+    ) -> Result<(StatusCode, String), CaldavError> {
+        let mut headers = HeaderMap::new();
+        headers.insert("Depth", depth.parse().expect("static header"));
+        headers.insert(
+            CONTENT_TYPE,
+            "application/xml; charset=utf-8"
+                .parse()
+                .expect("static header"),
+        );
+        let method = Method::from_bytes(method.as_bytes()).expect("static method");
+        let (status, _, text) = self.send(method, href, headers, Some(body)).await?;
+        Ok((status, text))
+    }
+
+    async fn multistatus(
+        &self,
+        method: &'static str,
+        href: &str,
+        depth: &'static str,
+        body: String,
+    ) -> Result<Multistatus, CaldavError> {
+        let (status, text) = self.xml_request(method, href, depth, body).await?;
+        if status != StatusCode::MULTI_STATUS {
+            return Err(CaldavError::Status {
+                status: status.as_u16(),
+                context: format!("{method} {href}"),
+            });
+        }
+        parse_multistatus(&text)
+    }
+
+    async fn propfind(
+        &self,
+        href: &str,
+        depth: &'static str,
+        props: &str,
+    ) -> Result<Multistatus, CaldavError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="{CALDAV_NS}" xmlns:cs="{CALSERVER_NS}"><d:prop>{props}</d:prop></d:propfind>"#
+        );
+        self.multistatus("PROPFIND", href, depth, body).await
+    }
+
+    async fn first_href(&self, href: &str, ns: &str, name: &str, prop: &str) -> Option<String> {
+        let status = self.propfind(href, "0", prop).await.ok()?;
+        status
+            .responses
+            .iter()
+            .find_map(|r| r.prop(ns, name).and_then(|p| p.hrefs.first().cloned()))
+    }
+
+    /// Find the calendars reachable from the base URL: the base itself when it is a calendar,
+    /// otherwise `current-user-principal` → `calendar-home-set` → its calendar children.
+    pub async fn discover_calendars(&self) -> Result<Vec<CaldavCalendar>, CaldavError> {
+        const CALENDAR_PROPS: &str = "<d:resourcetype/><d:displayname/><cs:getctag/>\
+<d:sync-token/><c:supported-calendar-component-set/>";
+
+        let base = self.base_href().to_string();
+        let own = self.propfind(&base, "0", CALENDAR_PROPS).await?;
+        if own.responses.iter().any(DavResponse::is_calendar) {
+            return Ok(calendars_from(&own, self));
+        }
+
+        let principal = self
+            .first_href(
+                &base,
+                DAV_NS,
+                "current-user-principal",
+                "<d:current-user-principal/>",
+            )
+            .await
+            .unwrap_or_else(|| base.clone());
+        let home = self
+            .first_href(
+                &principal,
+                CALDAV_NS,
+                "calendar-home-set",
+                "<c:calendar-home-set/>",
+            )
+            .await
+            .unwrap_or(principal);
+        let listing = self.propfind(&home, "1", CALENDAR_PROPS).await?;
+        Ok(calendars_from(&listing, self))
+    }
+
+    /// `getctag` and `sync-token` of one collection.
+    pub async fn collection_state(
+        &self,
+        calendar_href: &str,
+    ) -> Result<(Option<String>, Option<String>), CaldavError> {
+        let status = self
+            .propfind(calendar_href, "0", "<cs:getctag/><d:sync-token/>")
+            .await?;
+        let response = status.responses.first();
+        Ok((
+            response.and_then(|r| r.text(CALSERVER_NS, "getctag")),
+            response.and_then(|r| r.text(DAV_NS, "sync-token")),
+        ))
+    }
+
+    /// RFC 6578 incremental changes since `sync_token` (all members when `None`).
+    pub async fn sync_collection(
+        &self,
+        calendar_href: &str,
+        sync_token: Option<&str>,
+    ) -> Result<SyncDelta, CaldavError> {
+        let token = sync_token.map(xml_escape).unwrap_or_default();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:sync-collection xmlns:d="DAV:"><d:sync-token>{token}</d:sync-token><d:sync-level>1</d:sync-level><d:prop><d:getetag/></d:prop></d:sync-collection>"#
+        );
+        let (status, text) = self.xml_request("REPORT", calendar_href, "0", body).await?;
+        if status != StatusCode::MULTI_STATUS {
+            let token_rejected = sync_token.is_some()
+                && (text.contains("valid-sync-token")
+                    || matches!(status.as_u16(), 403 | 409 | 410));
+            return Err(if token_rejected {
+                CaldavError::InvalidSyncToken
+            } else {
+                CaldavError::SyncUnsupported
+            });
+        }
+
+        let multistatus = parse_multistatus(&text)?;
+        let collection = self.href_for(calendar_href);
+        let mut delta = SyncDelta {
+            sync_token: multistatus.sync_token.clone(),
+            ..SyncDelta::default()
+        };
+        for response in multistatus.responses {
+            let href = self.href_for(&response.href);
+            if href == collection {
+                continue;
+            }
+            if response.status == Some(404) {
+                delta.removed.push(href);
+            } else {
+                delta.changed.push((href, response.text(DAV_NS, "getetag")));
+            }
+        }
+        Ok(delta)
+    }
+
+    /// Every member href with its ETag; the fallback when sync-collection is unavailable.
+    pub async fn list_etags(
+        &self,
+        calendar_href: &str,
+    ) -> Result<Vec<(String, Option<String>)>, CaldavError> {
+        let status = self
+            .propfind(calendar_href, "1", "<d:getetag/><d:resourcetype/>")
+            .await?;
+        let collection = self.href_for(calendar_href);
+        Ok(status
+            .responses
+            .iter()
+            .map(|r| (self.href_for(&r.href), r.text(DAV_NS, "getetag")))
+            .filter(|(href, _)| *href != collection && !href.ends_with('/'))
+            .collect())
+    }
+
+    /// Fetch calendar objects by href via `calendar-multiget`.
+    pub async fn multiget(
+        &self,
+        calendar_href: &str,
+        hrefs: &[String],
+    ) -> Result<Vec<CalendarObject>, CaldavError> {
+        let mut objects = Vec::new();
+        for chunk in hrefs.chunks(MULTIGET_CHUNK) {
+            let href_xml: String = chunk
+                .iter()
+                .map(|h| format!("<d:href>{}</d:href>", xml_escape(h)))
+                .collect();
+            let body = format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-multiget xmlns:d="DAV:" xmlns:c="{CALDAV_NS}"><d:prop><d:getetag/><c:calendar-data/></d:prop>{href_xml}</c:calendar-multiget>"#
+            );
+            let status = self.multistatus("REPORT", calendar_href, "1", body).await?;
+            for response in status.responses {
+                let Some(ics) = response
+                    .prop(CALDAV_NS, "calendar-data")
+                    .map(|p| p.text.clone())
+                else {
+                    continue;
+                };
+                objects.push(CalendarObject {
+                    href: self.href_for(&response.href),
+                    etag: response.text(DAV_NS, "getetag"),
+                    ics,
+                });
+            }
+        }
+        Ok(objects)
+    }
+
+    pub async fn put(
+        &self,
+        href: &str,
+        ics: String,
+        precondition: WritePrecondition,
+    ) -> Result<WriteOutcome, CaldavError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            "text/calendar; charset=utf-8"
+                .parse()
+                .expect("static header"),
+        );
+        match &precondition {
+            WritePrecondition::Create => {
+                headers.insert(IF_NONE_MATCH, "*".parse().expect("static header"));
+            }
+            WritePrecondition::Match(etag) => {
+                let value = etag
+                    .parse()
+                    .map_err(|_| CaldavError::Config(format!("unusable ETag {etag}")))?;
+                headers.insert(IF_MATCH, value);
+            }
+        }
+        let (status, response_headers, _) =
+            self.send(Method::PUT, href, headers, Some(ics)).await?;
+        write_outcome(status, &response_headers, format!("PUT {href}"))
+    }
+
+    pub async fn delete(&self, href: &str, etag: &str) -> Result<WriteOutcome, CaldavError> {
+        let mut headers = HeaderMap::new();
+        let value = etag
+            .parse()
+            .map_err(|_| CaldavError::Config(format!("unusable ETag {etag}")))?;
+        headers.insert(IF_MATCH, value);
+        let (status, response_headers, _) = self.send(Method::DELETE, href, headers, None).await?;
+        if status == StatusCode::NOT_FOUND {
+            return Ok(WriteOutcome::Stored { etag: None });
+        }
+        write_outcome(status, &response_headers, format!("DELETE {href}"))
+    }
+}
+
+fn write_outcome(
+    status: StatusCode,
+    headers: &HeaderMap,
+    context: String,
+) -> Result<WriteOutcome, CaldavError> {
+    if status == StatusCode::PRECONDITION_FAILED {
+        return Ok(WriteOutcome::PreconditionFailed);
+    }
+    if !status.is_success() {
+        return Err(CaldavError::Status {
+            status: status.as_u16(),
+            context,
+        });
+    }
+    let etag = headers
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    Ok(WriteOutcome::Stored { etag })
+}
+
+fn calendars_from(status: &Multistatus, client: &CaldavClient) -> Vec<CaldavCalendar> {
+    status
+        .responses
+        .iter()
+        .filter(|r| r.is_calendar())
+        .map(|r| CaldavCalendar {
+            href: client.href_for(&r.href),
+            display_name: r.text(DAV_NS, "displayname"),
+            ctag: r.text(CALSERVER_NS, "getctag"),
+            sync_token: r.text(DAV_NS, "sync-token"),
+            components: r
+                .prop(CALDAV_NS, "supported-calendar-component-set")
+                .map(|p| p.comp_names.clone())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_multistatus_props_and_removed_members() {
+        let body = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">
+  <d:response>
+    <d:href>/dav/cal/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/><c:calendar/></d:resourcetype>
+        <d:displayname>Work</d:displayname>
+        <cs:getctag>"ctag-1"</cs:getctag>
+        <c:supported-calendar-component-set><c:comp name="VEVENT"/></c:supported-calendar-component-set>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:sync-token/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/cal/gone.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:sync-token>http://example.com/sync/2</d:sync-token>
+</d:multistatus>"#;
+        let status = parse_multistatus(body).unwrap();
+        assert_eq!(
+            status.sync_token.as_deref(),
+            Some("http://example.com/sync/2")
+        );
+        let calendar = &status.responses[0];
+        assert!(calendar.is_calendar());
+        assert_eq!(
+            calendar.text(DAV_NS, "displayname").as_deref(),
+            Some("Work")
+        );
+        assert_eq!(
+            calendar.text(CALSERVER_NS, "getctag").as_deref(),
+            Some("\"ctag-1\"")
+        );
+        assert!(
+            calendar.text(DAV_NS, "sync-token").is_none(),
+            "404 propstats are ignored"
+        );
+        assert_eq!(status.responses[1].status, Some(404));
+    }
+
+    #[test]
+    fn credentials_parse_and_redact() {
+        let basic = CaldavCredentials::from_secret(r#"{"username":"u","password":"p"}"#).unwrap();
+        assert_eq!(format!("{basic:?}"), "Basic(u, <redacted>)");
+        let bearer = CaldavCredentials::from_secret(r#"{"bearer_token":"s3cr3t"}"#).unwrap();
+        assert!(!format!("{bearer:?}").contains("s3cr3t"));
+        assert!(CaldavCredentials::from_secret("hunter2").is_err());
+    }
+}
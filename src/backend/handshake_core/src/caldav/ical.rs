@@ -0,0 +1,665 @@
+//! Minimal RFC 5545 mapping between CalDAV calendar objects and calendar storage rows.
+//!
+//! Only VEVENT is read. Times with a `TZID` are converted with the calendar's VTIMEZONE standard
+//! offset when one is embedded (DST rules are not evaluated); the wall-clock value and TZID are kept
+//! in `start_local`/`tzid` so nothing is lost. Pushed events are always written in UTC (or as
+//! `VALUE=DATE` for all-day events), which sidesteps timezone definitions entirely.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+
+use crate::caldav::CaldavError;
+use crate::storage::{
+    CalendarEvent, CalendarEventExportMode, CalendarEventStatus, CalendarEventVisibility,
+    CalendarSyncEventUpsert,
+};
+
+/// One content line: `NAME;PARAM=VALUE:value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IcsProperty {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl IcsProperty {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VEvent {
+    pub props: Vec<IcsProperty>,
+}
+
+impl VEvent {
+    pub fn get(&self, name: &str) -> Option<&IcsProperty> {
+        self.props.iter().find(|p| p.name == name)
+    }
+
+    pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a IcsProperty> + 'a {
+        self.props.iter().filter(move |p| p.name == name)
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .map(|p| unescape_text(&p.value))
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// The VEVENTs of one calendar object resource plus the standard offsets of its VTIMEZONEs.
+#[derive(Clone, Debug, Default)]
+pub struct ParsedCalendar {
+    pub events: Vec<VEvent>,
+    pub tz_offsets: HashMap<String, i32>,
+}
+
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+fn parse_line(line: &str) -> Option<IcsProperty> {
+    // The value starts at the first ':' outside a quoted parameter value.
+    let mut in_quotes = false;
+    let mut split = None;
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                split = Some(idx);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let split = split?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            Some((
+                key.trim().to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    Some(IcsProperty {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+pub fn parse_calendar(ics: &str) -> ParsedCalendar {
+    let mut calendar = ParsedCalendar::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut current_event: Option<VEvent> = None;
+    let mut current_tzid: Option<String> = None;
+
+    for line in unfold(ics) {
+        let Some(prop) = parse_line(&line) else {
+            continue;
+        };
+        match prop.name.as_str() {
+            "BEGIN" => {
+                let component = prop.value.to_ascii_uppercase();
+                if component == "VEVENT" && stack.last().map(String::as_str) == Some("VCALENDAR") {
+                    current_event = Some(VEvent::default());
+                }
+                stack.push(component);
+            }
+            "END" => {
+                let component = prop.value.to_ascii_uppercase();
+                if component == "VEVENT" {
+                    if let Some(event) = current_event.take() {
+                        calendar.events.push(event);
+                    }
+                }
+                if component == "VTIMEZONE" {
+                    current_tzid = None;
+                }
+                stack.pop();
+            }
+            _ => match stack.last().map(String::as_str) {
+                Some("VEVENT") => {
+                    if let Some(event) = current_event.as_mut() {
+                        event.props.push(prop);
+                    }
+                }
+                Some("VTIMEZONE") if prop.name == "TZID" => current_tzid = Some(prop.value),
+                Some("STANDARD") if prop.name == "TZOFFSETTO" => {
+                    if let (Some(tzid), Some(offset)) =
+                        (&current_tzid, parse_utc_offset(&prop.value))
+                    {
+                        calendar.tz_offsets.insert(tzid.clone(), offset);
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    calendar
+}
+
+fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, digits) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    if digits.len() < 4 {
+        return None;
+    }
+    let hours: i32 = digits.get(0..2)?.parse().ok()?;
+    let minutes: i32 = digits.get(2..4)?.parse().ok()?;
+    let seconds: i32 = digits.get(4..6).and_then(|s| s.parse().ok()).unwrap_or(0);
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+/// A DTSTART/DTEND value resolved to UTC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct IcsTime {
+    utc: DateTime<Utc>,
+    local: Option<String>,
+    tzid: Option<String>,
+    all_day: bool,
+    floating: bool,
+}
+
+fn parse_time(
+    prop: &IcsProperty,
+    tz_offsets: &HashMap<String, i32>,
+) -> Result<IcsTime, CaldavError> {
+    let value = prop.value.trim();
+    let bad = || CaldavError::Ics(format!("unparseable {} value {value}", prop.name));
+    if prop.param("VALUE") == Some("DATE") || (value.len() == 8 && !value.contains('T')) {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| bad())?;
+        let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(bad)?;
+        return Ok(IcsTime {
+            utc: Utc.from_utc_datetime(&midnight),
+            local: Some(date.format("%Y-%m-%d").to_string()),
+            tzid: None,
+            all_day: true,
+            floating: false,
+        });
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| bad())?;
+        return Ok(IcsTime {
+            utc: Utc.from_utc_datetime(&naive),
+            local: None,
+            tzid: None,
+            all_day: false,
+            floating: false,
+        });
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| bad())?;
+    let local = Some(naive.format("%Y-%m-%dT%H:%M:%S").to_string());
+    match prop.param("TZID") {
+        Some(tzid) => {
+            let offset = tz_offsets.get(tzid).copied().unwrap_or(0);
+            Ok(IcsTime {
+                utc: Utc.from_utc_datetime(&(naive - Duration::seconds(i64::from(offset)))),
+                local,
+                tzid: Some(tzid.to_string()),
+                all_day: false,
+                floating: false,
+            })
+        }
+        None => Ok(IcsTime {
+            utc: Utc.from_utc_datetime(&naive),
+            local,
+            tzid: None,
+            all_day: false,
+            floating: true,
+        }),
+    }
+}
+
+/// `[+-]P[nW][nD][T[nH][nM][nS]]`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for ch in rest.chars() {
+        match ch {
+            'T' => in_time = true,
+            '0'..='9' => number.push(ch),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(if negative { -total } else { total })
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// External id of a VEVENT: its UID, suffixed with the RECURRENCE-ID for overrides.
+pub fn external_id_for(uid: &str, recurrence_id: Option<&str>) -> String {
+    match recurrence_id {
+        Some(rid) => format!("{uid}/{rid}"),
+        None => uid.to_string(),
+    }
+}
+
+/// Map every VEVENT of a calendar object resource to a storage upsert.
+pub fn resource_to_sync_events(
+    href: &str,
+    etag: Option<&str>,
+    ics: &str,
+    default_tzid: &str,
+) -> Result<Vec<CalendarSyncEventUpsert>, CaldavError> {
+    let calendar = parse_calendar(ics);
+    let has_overrides = calendar
+        .events
+        .iter()
+        .any(|e| e.get("RECURRENCE-ID").is_some());
+    calendar
+        .events
+        .iter()
+        .map(|vevent| {
+            sync_event_from_vevent(vevent, &calendar, href, etag, default_tzid, has_overrides)
+        })
+        .collect()
+}
+
+fn sync_event_from_vevent(
+    vevent: &VEvent,
+    calendar: &ParsedCalendar,
+    href: &str,
+    etag: Option<&str>,
+    default_tzid: &str,
+    resource_has_overrides: bool,
+) -> Result<CalendarSyncEventUpsert, CaldavError> {
+    let uid = vevent
+        .get("UID")
+        .map(|p| p.value.trim().to_string())
+        .filter(|uid| !uid.is_empty())
+        .ok_or_else(|| CaldavError::Ics(format!("VEVENT without UID in {href}")))?;
+    let recurrence_id = vevent
+        .get("RECURRENCE-ID")
+        .map(|p| p.value.trim().to_string());
+    let start_prop = vevent
+        .get("DTSTART")
+        .ok_or_else(|| CaldavError::Ics(format!("VEVENT {uid} has no DTSTART")))?;
+    let start = parse_time(start_prop, &calendar.tz_offsets)?;
+    let end = match (vevent.get("DTEND"), vevent.get("DURATION")) {
+        (Some(end), _) => parse_time(end, &calendar.tz_offsets)?,
+        (None, Some(duration)) => {
+            let duration = parse_duration(&duration.value)
+                .ok_or_else(|| CaldavError::Ics(format!("VEVENT {uid} has a bad DURATION")))?;
+            IcsTime {
+                utc: start.utc + duration,
+                local: None,
+                ..start.clone()
+            }
+        }
+        // RFC 5545 §3.6.1: a date-only start lasts one day, a timed start is instantaneous.
+        (None, None) if start.all_day => IcsTime {
+            utc: start.utc + Duration::days(1),
+            local: None,
+            ..start.clone()
+        },
+        (None, None) => start.clone(),
+    };
+
+    let status = match vevent
+        .get("STATUS")
+        .map(|p| p.value.to_ascii_uppercase())
+        .as_deref()
+    {
+        Some("TENTATIVE") => CalendarEventStatus::Tentative,
+        Some("CANCELLED") => CalendarEventStatus::Cancelled,
+        _ => CalendarEventStatus::Confirmed,
+    };
+    let visibility = match vevent
+        .get("CLASS")
+        .map(|p| p.value.to_ascii_uppercase())
+        .as_deref()
+    {
+        Some("PUBLIC") => CalendarEventVisibility::Public,
+        Some("CONFIDENTIAL") => CalendarEventVisibility::BusyOnly,
+        _ => CalendarEventVisibility::Private,
+    };
+    let split_values = |name: &str| -> Vec<String> {
+        vevent
+            .all(name)
+            .flat_map(|p| {
+                p.value
+                    .split(',')
+                    .map(|v| v.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|v| !v.is_empty())
+            .collect()
+    };
+    let attendees: Vec<Value> = vevent
+        .all("ATTENDEE")
+        .map(|p| json!({ "address": p.value, "name": p.param("CN") }))
+        .collect();
+    let rrule = vevent.get("RRULE").map(|p| p.value.clone());
+
+    Ok(CalendarSyncEventUpsert {
+        id: None,
+        external_id: Some(external_id_for(&uid, recurrence_id.as_deref())),
+        external_etag: etag.map(str::to_string),
+        title: vevent
+            .text("SUMMARY")
+            .unwrap_or_else(|| "(untitled)".to_string()),
+        description: vevent.text("DESCRIPTION"),
+        location: vevent.text("LOCATION"),
+        start_ts_utc: start.utc,
+        end_ts_utc: end.utc,
+        start_local: start.local,
+        end_local: end.local,
+        tzid: start.tzid.unwrap_or_else(|| default_tzid.to_string()),
+        all_day: start.all_day,
+        was_floating: start.floating,
+        status,
+        visibility,
+        // Remote events flow back out on edit; local-only events are never pushed.
+        export_mode: CalendarEventExportMode::FullExport,
+        is_recurring: rrule.is_some(),
+        rrule,
+        rdate: split_values("RDATE"),
+        exdate: split_values("EXDATE"),
+        series_id: Some(uid.clone()),
+        instance_key: recurrence_id.clone(),
+        is_override: recurrence_id.is_some(),
+        source_last_seen_at: Some(Utc::now()),
+        attendees: Value::Array(attendees),
+        links: Value::Array(Vec::new()),
+        provider_payload: Some(json!({
+            "caldav": {
+                "href": href,
+                "uid": uid,
+                "recurrence_id": recurrence_id,
+                "resource_has_overrides": resource_has_overrides,
+            }
+        })),
+    })
+}
+
+fn fold_line(line: &str, out: &mut String) {
+    // RFC 5545 §3.1: lines longer than 75 octets are folded with CRLF + space.
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+fn format_utc(ts: &DateTime<Utc>) -> String {
+    ts.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// The UID under which a local event is (or will be) stored remotely.
+pub fn uid_for_event(event: &CalendarEvent) -> String {
+    event
+        .external_id
+        .clone()
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| event.id.clone())
+}
+
+/// Serialise a local event as a single-VEVENT calendar object. `None` for local-only events.
+pub fn event_to_ics(event: &CalendarEvent, dtstamp: DateTime<Utc>) -> Option<String> {
+    let busy_only = match event.export_mode {
+        CalendarEventExportMode::LocalOnly => return None,
+        CalendarEventExportMode::BusyOnly => true,
+        CalendarEventExportMode::FullExport => false,
+    };
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Handshake//CalDAV Sync//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", uid_for_event(event)),
+        format!("DTSTAMP:{}", format_utc(&dtstamp)),
+    ];
+    if event.all_day {
+        lines.push(format!(
+            "DTSTART;VALUE=DATE:{}",
+            event.start_ts_utc.format("%Y%m%d")
+        ));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            event.end_ts_utc.format("%Y%m%d")
+        ));
+    } else {
+        lines.push(format!("DTSTART:{}", format_utc(&event.start_ts_utc)));
+        lines.push(format!("DTEND:{}", format_utc(&event.end_ts_utc)));
+    }
+    if busy_only {
+        lines.push("SUMMARY:Busy".to_string());
+        lines.push("CLASS:CONFIDENTIAL".to_string());
+    } else {
+        lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        let class = match event.visibility {
+            CalendarEventVisibility::Public => "PUBLIC",
+            CalendarEventVisibility::Private => "PRIVATE",
+            CalendarEventVisibility::BusyOnly => "CONFIDENTIAL",
+        };
+        lines.push(format!("CLASS:{class}"));
+    }
+    lines.push(format!(
+        "STATUS:{}",
+        event.status.as_str().to_ascii_uppercase()
+    ));
+    if let Some(rrule) = &event.rrule {
+        lines.push(format!("RRULE:{rrule}"));
+    }
+    if !event.rdate.is_empty() {
+        lines.push(format!("RDATE:{}", event.rdate.join(",")));
+    }
+    if !event.exdate.is_empty() {
+        lines.push(format!("EXDATE:{}", event.exdate.join(",")));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        fold_line(line, &mut out);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\n\
+BEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\n\
+END:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:abc-1\r\nDTSTART;TZID=Europe/Berlin:20260105T\
+100000\r\nDURATION:PT1H30M\r\nSUMMARY:Planning\\, quarterly\r\nDESCRIPTION:line one\\nline \r\n two\r\n\
+CLASS:PUBLIC\r\nATTENDEE;CN=\"Ada: L\":mailto:ada@example.com\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\n\
+UID:abc-1\r\nRECURRENCE-ID:20260112T090000Z\r\nDTSTART:20260112T110000Z\r\nDTEND:20260112T120000Z\r\n\
+SUMMARY:Moved\r\nSTATUS:TENTATIVE\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_events_with_timezone_duration_and_overrides() {
+        let events =
+            resource_to_sync_events("/cal/abc-1.ics", Some("\"e1\""), SAMPLE, "UTC").unwrap();
+        assert_eq!(events.len(), 2);
+
+        let master = &events[0];
+        assert_eq!(master.external_id.as_deref(), Some("abc-1"));
+        assert_eq!(master.title, "Planning, quarterly");
+        assert_eq!(master.description.as_deref(), Some("line one\nline two"));
+        assert_eq!(
+            master.start_ts_utc,
+            Utc.with_ymd_and_hms(2026, 1, 5, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            master.end_ts_utc,
+            Utc.with_ymd_and_hms(2026, 1, 5, 10, 30, 0).unwrap()
+        );
+        assert_eq!(master.start_local.as_deref(), Some("2026-01-05T10:00:00"));
+        assert_eq!(master.tzid, "Europe/Berlin");
+        assert_eq!(master.visibility, CalendarEventVisibility::Public);
+        assert_eq!(master.attendees[0]["name"], "Ada: L");
+        assert_eq!(master.external_etag.as_deref(), Some("\"e1\""));
+
+        let moved = &events[1];
+        assert_eq!(moved.external_id.as_deref(), Some("abc-1/20260112T090000Z"));
+        assert!(moved.is_override);
+        assert_eq!(moved.status, CalendarEventStatus::Tentative);
+        assert_eq!(
+            moved.provider_payload.as_ref().unwrap()["caldav"]["resource_has_overrides"],
+            true
+        );
+    }
+
+    #[test]
+    fn all_day_without_end_lasts_one_day() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:d\nDTSTART;VALUE=DATE:20260301\nEND:VEVENT\nEND:VCALENDAR\n";
+        let events = resource_to_sync_events("/cal/d.ics", None, ics, "UTC").unwrap();
+        assert!(events[0].all_day);
+        assert_eq!(
+            events[0].end_ts_utc - events[0].start_ts_utc,
+            Duration::days(1)
+        );
+    }
+
+    #[test]
+    fn serialises_and_reparses_a_local_event() {
+        let event = CalendarEvent {
+            id: "local-1".to_string(),
+            workspace_id: "ws".to_string(),
+            source_id: "src".to_string(),
+            external_id: None,
+            external_etag: None,
+            title: "Review; notes, long ".repeat(6),
+            description: Some("a\nb".to_string()),
+            location: None,
+            start_ts_utc: Utc.with_ymd_and_hms(2026, 2, 1, 8, 0, 0).unwrap(),
+            end_ts_utc: Utc.with_ymd_and_hms(2026, 2, 1, 9, 0, 0).unwrap(),
+            start_local: None,
+            end_local: None,
+            tzid: "UTC".to_string(),
+            all_day: false,
+            was_floating: false,
+            status: CalendarEventStatus::Confirmed,
+            visibility: CalendarEventVisibility::Private,
+            export_mode: CalendarEventExportMode::FullExport,
+            rrule: Some("FREQ=WEEKLY;COUNT=3".to_string()),
+            rdate: Vec::new(),
+            exdate: Vec::new(),
+            is_recurring: true,
+            series_id: None,
+            instance_key: None,
+            is_override: false,
+            source_last_seen_at: None,
+            created_by: None,
+            attendees: Value::Array(Vec::new()),
+            links: Value::Array(Vec::new()),
+            provider_payload: None,
+            last_job_id: None,
+            last_workflow_id: None,
+            last_actor_id: None,
+            edit_event_id: "e".to_string(),
+            last_actor_kind: "HUMAN".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let ics = event_to_ics(&event, Utc::now()).unwrap();
+        assert!(ics.lines().all(|line| line.len() <= 76), "lines are folded");
+
+        let parsed = resource_to_sync_events("/cal/local-1.ics", None, &ics, "UTC").unwrap();
+        assert_eq!(parsed[0].external_id.as_deref(), Some("local-1"));
+        assert_eq!(parsed[0].title, event.title);
+        assert_eq!(parsed[0].description.as_deref(), Some("a\nb"));
+        assert_eq!(parsed[0].start_ts_utc, event.start_ts_utc);
+        assert_eq!(parsed[0].rrule.as_deref(), Some("FREQ=WEEKLY;COUNT=3"));
+
+        let busy = CalendarEvent {
+            export_mode: CalendarEventExportMode::BusyOnly,
+            ..event.clone()
+        };
+        let busy_ics = event_to_ics(&busy, Utc::now()).unwrap();
+        assert!(busy_ics.contains("SUMMARY:Busy") && !busy_ics.contains("DESCRIPTION"));
+
+        let local_only = CalendarEvent {
+            export_mode: CalendarEventExportMode::LocalOnly,
+            ..event
+        };
+        assert!(event_to_ics(&local_only, Utc::now()).is_none());
+    }
+}
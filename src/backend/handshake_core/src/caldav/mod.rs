@@ -0,0 +1,94 @@
+//! CalDAV two-way calendar sync.
+//!
+//! - [`client`]: WebDAV/CalDAV HTTP (discovery, sync-collection, multiget, ETag-guarded PUT/DELETE);
+//! - [`ical`]: VEVENT ↔ calendar storage mapping;
+//! - [`sync`]: the pull/push engine behind `calendar_sync.run` for `caldav` sources, which records
+//!   a [`crate::storage::CalendarSyncConflict`] instead of overwriting when both sides changed.
+//!
+//! A source is configured with `config.caldav_url` (a calendar, principal, or server root URL) and
+//! an optional `config.calendar_name`; `credentials_ref` names the secrets-vault lane holding the
+//! credentials JSON described on [`client::CaldavCredentials`].
+
+pub mod client;
+pub mod ical;
+pub mod sync;
+
+use thiserror::Error;
+
+use crate::model_runtime::cloud::SecretsVault;
+use crate::storage::{CalendarSource, CalendarSourceProviderType, StorageError};
+
+pub use client::{
+    CaldavCalendar, CaldavClient, CaldavCredentials, WriteOutcome, WritePrecondition,
+};
+pub use sync::{run_caldav_sync, CaldavSyncOptions, CaldavSyncReport};
+
+#[derive(Debug, Error)]
+pub enum CaldavError {
+    #[error("caldav configuration error: {0}")]
+    Config(String),
+    #[error("caldav credentials error: {0}")]
+    Credentials(String),
+    #[error("caldav server rejected the credentials")]
+    Unauthorized,
+    #[error("caldav http error: {0}")]
+    Http(String),
+    #[error("caldav server returned {status} for {context}")]
+    Status { status: u16, context: String },
+    #[error("caldav xml error: {0}")]
+    Xml(String),
+    #[error("caldav icalendar error: {0}")]
+    Ics(String),
+    #[error("caldav sync token is no longer valid")]
+    InvalidSyncToken,
+    #[error("caldav server does not support sync-collection")]
+    SyncUnsupported,
+    #[error("caldav storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+impl CaldavError {
+    /// Stable code for sync-state `last_error_code` and engine errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CaldavError::Config(_) => "CALDAV_CONFIG",
+            CaldavError::Credentials(_) => "CALDAV_CREDENTIALS",
+            CaldavError::Unauthorized => "CALDAV_UNAUTHORIZED",
+            CaldavError::Http(_) => "CALDAV_HTTP",
+            CaldavError::Status { .. } => "CALDAV_STATUS",
+            CaldavError::Xml(_) => "CALDAV_XML",
+            CaldavError::Ics(_) => "CALDAV_ICS",
+            CaldavError::InvalidSyncToken => "CALDAV_INVALID_SYNC_TOKEN",
+            CaldavError::SyncUnsupported => "CALDAV_SYNC_UNSUPPORTED",
+            CaldavError::Storage(_) => "CALDAV_STORAGE",
+        }
+    }
+}
+
+/// Build a client for a `caldav` source, reading its credentials from the secrets vault.
+pub fn client_for_source(
+    source: &CalendarSource,
+    vault: &dyn SecretsVault,
+) -> Result<CaldavClient, CaldavError> {
+    if source.provider_type != CalendarSourceProviderType::Caldav {
+        return Err(CaldavError::Config(format!(
+            "calendar source {} is not a caldav source",
+            source.id
+        )));
+    }
+    let url = source
+        .config
+        .get("caldav_url")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| CaldavError::Config("config.caldav_url is required".to_string()))?;
+    let lane = source
+        .credentials_ref
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| CaldavError::Credentials("credentials_ref is required".to_string()))?;
+    let secret = vault
+        .get(lane)
+        .map_err(|e| CaldavError::Credentials(e.to_string()))?;
+    CaldavClient::new(url, CaldavCredentials::from_secret(&secret)?)
+}
@@ -0,0 +1,667 @@
+//! Pull/push engine for one CalDAV-backed calendar source.
+//!
+//! Pull: a matching `getctag` short-circuits; otherwise `sync-collection` from the stored token,
+//! falling back to a full ETag listing when the token is rejected or unsupported. Push: local events
+//! edited since the last sync are written with `If-Match` on the ETag we last saw (or
+//! `If-None-Match: *` for new events).
+//!
+//! An event whose remote resource changed while it had unpushed local edits is never overwritten in
+//! either direction: a conflict record is written and the event is left alone until the conflict is
+//! resolved. A resolution is applied on the first sync after it is made.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::caldav::client::{
+    CaldavCalendar, CaldavClient, CalendarObject, WriteOutcome, WritePrecondition,
+};
+use crate::caldav::ical::{event_to_ics, resource_to_sync_events, uid_for_event};
+use crate::caldav::CaldavError;
+use crate::storage::{
+    CalendarEvent, CalendarEventStatus, CalendarEventUpsert, CalendarEventWindowQuery,
+    CalendarSource, CalendarSourceWritePolicy, CalendarSyncConflict, CalendarSyncConflictReason,
+    CalendarSyncConflictStatus, CalendarSyncEventUpsert, Database, NewCalendarSyncConflict,
+    WriteContext,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct CaldavSyncOptions {
+    /// Whether local edits may be written to the server (the caller holds `calendar.sync.write`).
+    pub push_allowed: bool,
+}
+
+/// What one sync run did; the caller folds it into the source's sync state.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CaldavSyncReport {
+    pub calendar_href: String,
+    pub ctag: Option<String>,
+    pub next_sync_token: Option<String>,
+    pub full_resync: bool,
+    pub remote_changed: usize,
+    pub remote_removed: usize,
+    pub pulled_upserts: usize,
+    pub remote_deletions_applied: usize,
+    pub pushed_creates: usize,
+    pub pushed_updates: usize,
+    pub pushed_deletes: usize,
+    /// Local edits not pushed: recurrence overrides, or push not permitted.
+    pub push_skipped: usize,
+    pub remote_write_attempted: bool,
+    pub conflicts_recorded: Vec<String>,
+    pub open_conflicts: usize,
+}
+
+fn caldav_payload<'a>(event: &'a CalendarEvent, key: &str) -> Option<&'a Value> {
+    event.provider_payload.as_ref()?.get("caldav")?.get(key)
+}
+
+fn href_of(event: &CalendarEvent) -> Option<String> {
+    caldav_payload(event, "href")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn remotely_deleted(event: &CalendarEvent) -> bool {
+    caldav_payload(event, "deleted_remotely").and_then(Value::as_bool) == Some(true)
+}
+
+fn upsert_from_event(event: &CalendarEvent) -> CalendarEventUpsert {
+    CalendarEventUpsert {
+        id: event.id.clone(),
+        workspace_id: event.workspace_id.clone(),
+        source_id: event.source_id.clone(),
+        external_id: event.external_id.clone(),
+        external_etag: event.external_etag.clone(),
+        title: event.title.clone(),
+        description: event.description.clone(),
+        location: event.location.clone(),
+        start_ts_utc: event.start_ts_utc,
+        end_ts_utc: event.end_ts_utc,
+        start_local: event.start_local.clone(),
+        end_local: event.end_local.clone(),
+        tzid: event.tzid.clone(),
+        all_day: event.all_day,
+        was_floating: event.was_floating,
+        status: event.status.clone(),
+        visibility: event.visibility.clone(),
+        export_mode: event.export_mode.clone(),
+        rrule: event.rrule.clone(),
+        rdate: event.rdate.clone(),
+        exdate: event.exdate.clone(),
+        is_recurring: event.is_recurring,
+        series_id: event.series_id.clone(),
+        instance_key: event.instance_key.clone(),
+        is_override: event.is_override,
+        source_last_seen_at: event.source_last_seen_at,
+        attendees: event.attendees.clone(),
+        links: event.links.clone(),
+        provider_payload: event.provider_payload.clone(),
+    }
+}
+
+fn upsert_from_remote(
+    source: &CalendarSource,
+    local_id: Option<&str>,
+    event: CalendarSyncEventUpsert,
+) -> CalendarEventUpsert {
+    CalendarEventUpsert {
+        id: local_id
+            .map(str::to_string)
+            .or(event.id)
+            .unwrap_or_else(|| uuid::Uuid::now_v7().to_string()),
+        workspace_id: source.workspace_id.clone(),
+        source_id: source.id.clone(),
+        external_id: event.external_id,
+        external_etag: event.external_etag,
+        title: event.title,
+        description: event.description,
+        location: event.location,
+        start_ts_utc: event.start_ts_utc,
+        end_ts_utc: event.end_ts_utc,
+        start_local: event.start_local,
+        end_local: event.end_local,
+        tzid: event.tzid,
+        all_day: event.all_day,
+        was_floating: event.was_floating,
+        status: event.status,
+        visibility: event.visibility,
+        export_mode: event.export_mode,
+        rrule: event.rrule,
+        rdate: event.rdate,
+        exdate: event.exdate,
+        is_recurring: event.is_recurring,
+        series_id: event.series_id,
+        instance_key: event.instance_key,
+        is_override: event.is_override,
+        source_last_seen_at: event.source_last_seen_at,
+        attendees: event.attendees,
+        links: event.links,
+        provider_payload: event.provider_payload,
+    }
+}
+
+fn with_caldav_payload(provider_payload: Option<Value>, href: &str, deleted: bool) -> Value {
+    let mut payload = provider_payload.unwrap_or_else(|| json!({}));
+    if !payload.is_object() {
+        payload = json!({});
+    }
+    let caldav = payload
+        .as_object_mut()
+        .expect("object")
+        .entry("caldav")
+        .or_insert_with(|| json!({}));
+    if !caldav.is_object() {
+        *caldav = json!({});
+    }
+    let caldav = caldav.as_object_mut().expect("object");
+    caldav.insert("href".to_string(), json!(href));
+    if deleted {
+        caldav.insert("deleted_remotely".to_string(), json!(true));
+    }
+    payload
+}
+
+struct SyncRun<'a> {
+    storage: &'a dyn Database,
+    ctx: &'a WriteContext,
+    source: &'a CalendarSource,
+    client: &'a CaldavClient,
+    calendar_href: String,
+    /// Local events keyed by external id; rows without one are pending creates.
+    by_external_id: HashMap<String, CalendarEvent>,
+    open_conflicts: HashSet<String>,
+    /// Resolved since the last sync and not yet applied, keyed by external id.
+    fresh_resolutions: HashMap<String, CalendarSyncConflict>,
+    /// Events settled this run (pulled, conflicted, or resolved) that push must not touch.
+    settled: HashSet<String>,
+    last_synced_at: Option<DateTime<Utc>>,
+    report: CaldavSyncReport,
+}
+
+impl SyncRun<'_> {
+    /// Edited locally since the last sync, by someone other than the sync itself.
+    fn is_dirty(&self, event: &CalendarEvent) -> bool {
+        if event.last_actor_id.is_some() && event.last_actor_id == self.ctx.actor_id {
+            return false;
+        }
+        self.last_synced_at.is_none_or(|at| event.updated_at > at)
+    }
+
+    async fn record_conflict(
+        &mut self,
+        external_id: &str,
+        local: Option<&CalendarEvent>,
+        reason: CalendarSyncConflictReason,
+        remote: Option<&CalendarObject>,
+        href: Option<String>,
+    ) -> Result<(), CaldavError> {
+        let conflict = self
+            .storage
+            .record_calendar_sync_conflict(
+                self.ctx,
+                NewCalendarSyncConflict {
+                    workspace_id: self.source.workspace_id.clone(),
+                    source_id: self.source.id.clone(),
+                    event_id: local.map(|e| e.id.clone()),
+                    external_id: external_id.to_string(),
+                    remote_href: remote.map(|r| r.href.clone()).or(href),
+                    reason,
+                    local_etag: local.and_then(|e| e.external_etag.clone()),
+                    remote_etag: remote.and_then(|r| r.etag.clone()),
+                    local_snapshot: local.and_then(|e| serde_json::to_value(e).ok()),
+                    remote_ics: remote.map(|r| r.ics.clone()),
+                },
+            )
+            .await?;
+        self.open_conflicts.insert(external_id.to_string());
+        self.settled.insert(external_id.to_string());
+        self.report.conflicts_recorded.push(conflict.id);
+        Ok(())
+    }
+
+    async fn resolve_calendar(&self) -> Result<String, CaldavError> {
+        if let Some(href) = self
+            .source
+            .provider_calendar_id
+            .as_deref()
+            .filter(|h| !h.trim().is_empty())
+        {
+            return Ok(self.client.href_for(href));
+        }
+        let wanted = self
+            .source
+            .config
+            .get("calendar_name")
+            .and_then(Value::as_str);
+        let calendars: Vec<CaldavCalendar> = self
+            .client
+            .discover_calendars()
+            .await?
+            .into_iter()
+            .filter(CaldavCalendar::supports_events)
+            .collect();
+        let chosen = match wanted {
+            Some(name) => calendars
+                .iter()
+                .find(|c| c.display_name.as_deref() == Some(name)),
+            None => calendars.first(),
+        };
+        chosen.map(|c| c.href.clone()).ok_or_else(|| {
+            CaldavError::Config(match wanted {
+                Some(name) => format!("no CalDAV calendar named {name}"),
+                None => "no CalDAV calendar with VEVENT support was found".to_string(),
+            })
+        })
+    }
+
+    async fn pull(&mut self) -> Result<(), CaldavError> {
+        let (ctag, server_token) = self.client.collection_state(&self.calendar_href).await?;
+        self.report.ctag = ctag.clone();
+        let state = &self.source.sync_state;
+        if ctag.is_some() && ctag == state.last_remote_watermark && state.sync_token.is_some() {
+            self.report.next_sync_token = state.sync_token.clone();
+            return Ok(());
+        }
+
+        let known: HashMap<String, Option<String>> = self
+            .by_external_id
+            .values()
+            .filter(|e| !remotely_deleted(e))
+            .filter_map(|e| href_of(e).map(|h| (h, e.external_etag.clone())))
+            .collect();
+        let (changed, removed) = match self
+            .client
+            .sync_collection(&self.calendar_href, state.sync_token.as_deref())
+            .await
+        {
+            Ok(delta) => {
+                self.report.next_sync_token = delta.sync_token.or(server_token);
+                (delta.changed, delta.removed)
+            }
+            Err(CaldavError::InvalidSyncToken) | Err(CaldavError::SyncUnsupported) => {
+                self.report.full_resync = true;
+                self.report.next_sync_token = server_token;
+                let listing = self.client.list_etags(&self.calendar_href).await?;
+                let present: HashSet<&String> = listing.iter().map(|(h, _)| h).collect();
+                let removed = known
+                    .keys()
+                    .filter(|h| !present.contains(h))
+                    .cloned()
+                    .collect();
+                let changed = listing
+                    .iter()
+                    .filter(|(href, etag)| etag.is_none() || known.get(href) != Some(etag))
+                    .cloned()
+                    .collect();
+                (changed, removed)
+            }
+            Err(err) => return Err(err),
+        };
+        self.report.remote_changed = changed.len();
+        self.report.remote_removed = removed.len();
+
+        // Our own pushes come back with the ETag we already stored; skip those without fetching.
+        let to_fetch: Vec<String> = changed
+            .into_iter()
+            .filter(|(href, etag)| etag.is_none() || known.get(href) != Some(etag))
+            .map(|(href, _)| href)
+            .collect();
+        for object in self.client.multiget(&self.calendar_href, &to_fetch).await? {
+            self.apply_remote_object(&object).await?;
+        }
+        for href in removed {
+            self.apply_remote_removal(&href).await?;
+        }
+        Ok(())
+    }
+
+    async fn apply_remote_object(&mut self, object: &CalendarObject) -> Result<(), CaldavError> {
+        let events = resource_to_sync_events(
+            &object.href,
+            object.etag.as_deref(),
+            &object.ics,
+            &self.source.default_tzid,
+        )?;
+        for remote in events {
+            let Some(external_id) = remote.external_id.clone() else {
+                continue;
+            };
+            if self.open_conflicts.contains(&external_id) {
+                continue;
+            }
+            let local = self.by_external_id.get(&external_id).cloned();
+            if let Some(resolution) = self.fresh_resolutions.get(&external_id) {
+                if resolution.status == CalendarSyncConflictStatus::ResolvedKeepLocal {
+                    // The local side wins; push overwrites this remote version.
+                    continue;
+                }
+            }
+            let resolved_for_remote = self
+                .fresh_resolutions
+                .get(&external_id)
+                .is_some_and(|c| c.status == CalendarSyncConflictStatus::ResolvedKeepRemote);
+            if let Some(local) = &local {
+                if !resolved_for_remote
+                    && self.is_dirty(local)
+                    && local.external_etag != remote.external_etag
+                {
+                    let reason = CalendarSyncConflictReason::BothModified;
+                    self.record_conflict(&external_id, Some(local), reason, Some(object), None)
+                        .await?;
+                    continue;
+                }
+            }
+            let upsert =
+                upsert_from_remote(self.source, local.as_ref().map(|e| e.id.as_str()), remote);
+            let stored = self.storage.upsert_calendar_event(self.ctx, upsert).await?;
+            self.settled.insert(external_id.clone());
+            self.by_external_id.insert(external_id, stored);
+            self.report.pulled_upserts += 1;
+        }
+        Ok(())
+    }
+
+    async fn apply_remote_removal(&mut self, href: &str) -> Result<(), CaldavError> {
+        let locals: Vec<CalendarEvent> = self
+            .by_external_id
+            .values()
+            .filter(|e| href_of(e).as_deref() == Some(href) && !remotely_deleted(e))
+            .cloned()
+            .collect();
+        for local in locals {
+            let Some(external_id) = local.external_id.clone() else {
+                continue;
+            };
+            if self.open_conflicts.contains(&external_id) {
+                continue;
+            }
+            let keep_local = self
+                .fresh_resolutions
+                .get(&external_id)
+                .is_some_and(|c| c.status == CalendarSyncConflictStatus::ResolvedKeepLocal);
+            if keep_local {
+                continue;
+            }
+            if self.is_dirty(&local) && !self.fresh_resolutions.contains_key(&external_id) {
+                let reason = CalendarSyncConflictReason::RemoteDeletedLocalModified;
+                self.record_conflict(
+                    &external_id,
+                    Some(&local),
+                    reason,
+                    None,
+                    Some(href.to_string()),
+                )
+                .await?;
+                continue;
+            }
+            let mut upsert = upsert_from_event(&local);
+            upsert.status = CalendarEventStatus::Cancelled;
+            upsert.provider_payload = Some(with_caldav_payload(
+                local.provider_payload.clone(),
+                href,
+                true,
+            ));
+            let stored = self.storage.upsert_calendar_event(self.ctx, upsert).await?;
+            self.settled.insert(external_id.clone());
+            self.by_external_id.insert(external_id, stored);
+            self.report.remote_deletions_applied += 1;
+        }
+        Ok(())
+    }
+
+    /// Apply `keep_remote` resolutions whose remote version did not come through the pull.
+    async fn apply_remote_resolutions(&mut self) -> Result<(), CaldavError> {
+        let pending: Vec<CalendarSyncConflict> = self
+            .fresh_resolutions
+            .values()
+            .filter(|c| c.status == CalendarSyncConflictStatus::ResolvedKeepRemote)
+            .filter(|c| !self.settled.contains(&c.external_id))
+            .cloned()
+            .collect();
+        for conflict in pending {
+            let local = self.by_external_id.get(&conflict.external_id).cloned();
+            match (&conflict.remote_ics, &conflict.remote_href) {
+                (Some(ics), Some(href)) => {
+                    let object = CalendarObject {
+                        href: href.clone(),
+                        etag: conflict.remote_etag.clone(),
+                        ics: ics.clone(),
+                    };
+                    self.apply_remote_object(&object).await?;
+                }
+                // The remote side was deleted: keeping it means cancelling the local event.
+                (None, href) => {
+                    if let Some(local) = local {
+                        let mut upsert = upsert_from_event(&local);
+                        upsert.status = CalendarEventStatus::Cancelled;
+                        let href = href.clone().or_else(|| href_of(&local)).unwrap_or_default();
+                        upsert.provider_payload = Some(with_caldav_payload(
+                            local.provider_payload.clone(),
+                            &href,
+                            true,
+                        ));
+                        let stored = self.storage.upsert_calendar_event(self.ctx, upsert).await?;
+                        self.by_external_id
+                            .insert(conflict.external_id.clone(), stored);
+                        self.report.remote_deletions_applied += 1;
+                    }
+                }
+                (Some(_), None) => {}
+            }
+            self.settled.insert(conflict.external_id);
+        }
+        Ok(())
+    }
+
+    async fn push(&mut self, pending_creates: Vec<CalendarEvent>) -> Result<(), CaldavError> {
+        let series_with_overrides: HashSet<String> = self
+            .by_external_id
+            .values()
+            .filter(|e| e.is_override)
+            .filter_map(|e| e.series_id.clone())
+            .collect();
+
+        let mut candidates: Vec<CalendarEvent> =
+            self.by_external_id
+                .iter()
+                .filter(|(external_id, _)| !self.settled.contains(*external_id))
+                .filter(|(external_id, _)| !self.open_conflicts.contains(*external_id))
+                .filter(|(external_id, event)| {
+                    self.is_dirty(event)
+                        || self.fresh_resolutions.get(*external_id).is_some_and(|c| {
+                            c.status == CalendarSyncConflictStatus::ResolvedKeepLocal
+                        })
+                })
+                .map(|(_, event)| event.clone())
+                .collect();
+        candidates.extend(pending_creates.into_iter().filter(|e| self.is_dirty(e)));
+
+        for event in candidates {
+            let uid = uid_for_event(&event);
+            if event.is_override || series_with_overrides.contains(&uid) {
+                // Overrides share their master's resource; rewriting it would drop them.
+                self.report.push_skipped += 1;
+                continue;
+            }
+            let Some(ics) = event_to_ics(&event, Utc::now()) else {
+                continue;
+            };
+            let resolution_etag = event
+                .external_id
+                .as_ref()
+                .and_then(|id| self.fresh_resolutions.get(id))
+                .and_then(|c| c.remote_etag.clone());
+            let known_href = href_of(&event).filter(|_| !remotely_deleted(&event));
+            let cancelled = event.status == CalendarEventStatus::Cancelled;
+
+            let (href, outcome, created) = match known_href {
+                Some(href) => {
+                    let Some(etag) = resolution_etag.or_else(|| event.external_etag.clone()) else {
+                        self.report.push_skipped += 1;
+                        continue;
+                    };
+                    self.report.remote_write_attempted = true;
+                    let outcome = if cancelled {
+                        self.client.delete(&href, &etag).await?
+                    } else {
+                        self.client
+                            .put(&href, ics, WritePrecondition::Match(etag))
+                            .await?
+                    };
+                    (href, outcome, false)
+                }
+                None if cancelled => continue,
+                None => {
+                    let collection = self.calendar_href.trim_end_matches('/');
+                    let href = self
+                        .client
+                        .href_for(&format!("{collection}/{}.ics", uid_path_segment(&uid)));
+                    self.report.remote_write_attempted = true;
+                    let outcome = self
+                        .client
+                        .put(&href, ics, WritePrecondition::Create)
+                        .await?;
+                    (href, outcome, true)
+                }
+            };
+
+            match outcome {
+                WriteOutcome::Stored { etag } => {
+                    let mut upsert = upsert_from_event(&event);
+                    upsert.external_id = Some(uid.clone());
+                    upsert.external_etag = etag;
+                    upsert.provider_payload = Some(with_caldav_payload(
+                        event.provider_payload.clone(),
+                        &href,
+                        cancelled,
+                    ));
+                    let stored = self.storage.upsert_calendar_event(self.ctx, upsert).await?;
+                    self.by_external_id.insert(uid, stored);
+                    match (created, cancelled) {
+                        (true, _) => self.report.pushed_creates += 1,
+                        (false, true) => self.report.pushed_deletes += 1,
+                        (false, false) => self.report.pushed_updates += 1,
+                    }
+                }
+                WriteOutcome::PreconditionFailed => {
+                    let remote = self
+                        .client
+                        .multiget(&self.calendar_href, std::slice::from_ref(&href))
+                        .await?
+                        .into_iter()
+                        .next();
+                    let reason = CalendarSyncConflictReason::PushPreconditionFailed;
+                    self.record_conflict(&uid, Some(&event), reason, remote.as_ref(), Some(href))
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn uid_path_segment(uid: &str) -> String {
+    uid.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Run one pull (and, when the source's write policy and `options` allow, push) for `source`.
+pub async fn run_caldav_sync(
+    storage: &dyn Database,
+    ctx: &WriteContext,
+    source: &CalendarSource,
+    client: &CaldavClient,
+    options: CaldavSyncOptions,
+) -> Result<CaldavSyncReport, CaldavError> {
+    let window_start = Utc
+        .with_ymd_and_hms(1900, 1, 1, 0, 0, 0)
+        .single()
+        .expect("valid date");
+    let window_end = Utc
+        .with_ymd_and_hms(9999, 12, 31, 0, 0, 0)
+        .single()
+        .expect("valid date");
+    let events = storage
+        .query_calendar_events(CalendarEventWindowQuery {
+            workspace_id: source.workspace_id.clone(),
+            window_start_utc: window_start,
+            window_end_utc: window_end,
+            source_ids: vec![source.id.clone()],
+        })
+        .await?;
+    let (with_id, pending_creates): (Vec<_>, Vec<_>) = events.into_iter().partition(|e| {
+        e.external_id
+            .as_deref()
+            .is_some_and(|id| !id.trim().is_empty())
+    });
+
+    let last_synced_at = source.sync_state.last_synced_at;
+    let conflicts = storage
+        .list_calendar_sync_conflicts(&source.workspace_id, &source.id)
+        .await?;
+    let open_conflicts = conflicts
+        .iter()
+        .filter(|c| c.status == CalendarSyncConflictStatus::Open)
+        .map(|c| c.external_id.clone())
+        .collect();
+    let fresh_resolutions = conflicts
+        .into_iter()
+        .filter(|c| c.status != CalendarSyncConflictStatus::Open)
+        .filter(|c| match (c.resolved_at, last_synced_at) {
+            (Some(resolved), Some(synced)) => resolved > synced,
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+        .map(|c| (c.external_id.clone(), c))
+        .collect();
+
+    let mut run = SyncRun {
+        storage,
+        ctx,
+        source,
+        client,
+        calendar_href: String::new(),
+        by_external_id: with_id
+            .into_iter()
+            .filter_map(|e| e.external_id.clone().map(|id| (id, e)))
+            .collect(),
+        open_conflicts,
+        fresh_resolutions,
+        settled: HashSet::new(),
+        last_synced_at,
+        report: CaldavSyncReport::default(),
+    };
+    run.calendar_href = run.resolve_calendar().await?;
+    run.report.calendar_href = run.calendar_href.clone();
+
+    if source.write_policy != CalendarSourceWritePolicy::PublishFromHandshake {
+        run.pull().await?;
+        run.apply_remote_resolutions().await?;
+    }
+
+    let push_policy = source.write_policy != CalendarSourceWritePolicy::ReadOnlyImport;
+    if push_policy && options.push_allowed {
+        run.push(pending_creates).await?;
+    } else if push_policy {
+        let dirty = run
+            .by_external_id
+            .values()
+            .chain(pending_creates.iter())
+            .filter(|e| run.is_dirty(e))
+            .count();
+        run.report.push_skipped = dirty;
+    }
+
+    run.report.open_conflicts = run.open_conflicts.len();
+    Ok(run.report)
+}
@@ -0,0 +1,339 @@
+//! `LlmClient` record/replay decorators.
+//!
+//! The match key for a completion is its prompt, model and sampling settings.
+//! `trace_id` and the cloud-escalation consent bundle change per run and are
+//! not part of it. Provider errors are recorded as well: rate-limit, budget
+//! and embedding-unsupported errors replay as the same variant, and every
+//! other error replays as `LlmError::ProviderError` with the recorded message.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::{
+    CassetteError, CassetteKind, CassetteOutcome, CassettePlayer, CassetteRecorder, CassetteSink,
+};
+use crate::llm::{
+    CompletionRequest, CompletionResponse, EmbeddingRequest, EmbeddingResponse, LlmClient,
+    LlmError, ModelProfile, ModelTier,
+};
+use crate::model_runtime::CancellationToken;
+use crate::workflows::ModelSwapRequestV0_4;
+
+fn completion_request_key(req: &CompletionRequest) -> Value {
+    json!({
+        "op": "completion",
+        "model_id": req.model_id,
+        "prompt": req.prompt,
+        "max_tokens": req.max_tokens,
+        "temperature": req.temperature,
+        "stop_sequences": req.stop_sequences,
+    })
+}
+
+fn embedding_request_key(req: &EmbeddingRequest) -> Value {
+    json!({
+        "op": "embedding",
+        "model_id": req.model_id,
+        "input": req.input,
+    })
+}
+
+fn profile_metadata(profile: &ModelProfile) -> Value {
+    json!({
+        "max_context_tokens": profile.max_context_tokens,
+        "supports_streaming": profile.supports_streaming,
+        "model_tier": match profile.model_tier {
+            ModelTier::Local => "local",
+            ModelTier::Cloud => "cloud",
+        },
+    })
+}
+
+fn profile_from_metadata(model_id: String, metadata: &Value) -> ModelProfile {
+    let max_context_tokens = metadata
+        .get("max_context_tokens")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0);
+    let tier = match metadata.get("model_tier").and_then(Value::as_str) {
+        Some("cloud") => ModelTier::Cloud,
+        _ => ModelTier::Local,
+    };
+    ModelProfile::new(model_id, max_context_tokens)
+        .with_streaming(
+            metadata
+                .get("supports_streaming")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        )
+        .with_tier(tier)
+}
+
+fn outcome_from_result<T: serde::Serialize>(result: &Result<T, LlmError>) -> CassetteOutcome {
+    match result {
+        Ok(body) => CassetteOutcome::Ok {
+            body: serde_json::to_value(body).unwrap_or(Value::Null),
+        },
+        Err(err) => {
+            let (code, data) = match err {
+                LlmError::RateLimit => ("rate_limit", None),
+                LlmError::BudgetExceeded(tokens) => ("budget_exceeded", Some(json!(tokens))),
+                LlmError::EmbeddingUnsupported => ("embedding_unsupported", None),
+                _ => ("provider_error", None),
+            };
+            let message = match err {
+                LlmError::ProviderError(message) => message.clone(),
+                other => other.to_string(),
+            };
+            CassetteOutcome::Error {
+                code: code.to_string(),
+                message,
+                data,
+            }
+        }
+    }
+}
+
+fn result_from_outcome<T: serde::de::DeserializeOwned>(
+    outcome: CassetteOutcome,
+) -> Result<T, LlmError> {
+    match outcome {
+        CassetteOutcome::Ok { body } => serde_json::from_value(body)
+            .map_err(|e| LlmError::Cassette(CassetteError::Format(e.to_string()))),
+        CassetteOutcome::Error {
+            code,
+            message,
+            data,
+        } => Err(match code.as_str() {
+            "rate_limit" => LlmError::RateLimit,
+            "budget_exceeded" => LlmError::BudgetExceeded(
+                data.as_ref()
+                    .and_then(Value::as_u64)
+                    .and_then(|v| u32::try_from(v).ok())
+                    .unwrap_or(0),
+            ),
+            "embedding_unsupported" => LlmError::EmbeddingUnsupported,
+            _ => LlmError::ProviderError(message),
+        }),
+    }
+}
+
+/// Wraps a live client and records every completion and embedding exchange.
+/// Recording failures are logged and never fail the live call.
+pub struct RecordingLlmClient {
+    inner: Arc<dyn LlmClient>,
+    recorder: CassetteRecorder,
+}
+
+impl RecordingLlmClient {
+    pub fn new(inner: Arc<dyn LlmClient>, sink: CassetteSink) -> Self {
+        let profile = inner.profile();
+        let recorder = CassetteRecorder::new(CassetteKind::Llm, profile.model_id.clone(), sink)
+            .with_metadata(profile_metadata(profile));
+        Self { inner, recorder }
+    }
+
+    pub fn recorder(&self) -> &CassetteRecorder {
+        &self.recorder
+    }
+
+    async fn record(&self, trace_id: Uuid, key: Value, outcome: CassetteOutcome) {
+        if let Err(err) = self
+            .recorder
+            .record_async(Some(trace_id), key, outcome)
+            .await
+        {
+            tracing::warn!(
+                target: "handshake_core::cassette",
+                trace_id = %trace_id,
+                error = %err,
+                "LLM cassette record failed"
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for RecordingLlmClient {
+    async fn completion(&self, req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        let trace_id = req.trace_id;
+        let key = completion_request_key(&req);
+        let result = self.inner.completion(req).await;
+        self.record(trace_id, key, outcome_from_result(&result))
+            .await;
+        result
+    }
+
+    async fn embedding(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, LlmError> {
+        let trace_id = req.trace_id;
+        let key = embedding_request_key(&req);
+        let result = self.inner.embedding(req).await;
+        self.record(trace_id, key, outcome_from_result(&result))
+            .await;
+        result
+    }
+
+    fn cancel(&self, model_id: &str, token: CancellationToken) {
+        self.inner.cancel(model_id, token);
+    }
+
+    async fn swap_model(&self, req: ModelSwapRequestV0_4) -> Result<(), LlmError> {
+        self.inner.swap_model(req).await
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+}
+
+/// Serves a recorded LLM cassette. The profile is rebuilt from the
+/// cassette's subject and metadata, and model swaps succeed without effect.
+pub struct ReplayLlmClient {
+    player: CassettePlayer,
+    profile: ModelProfile,
+}
+
+impl ReplayLlmClient {
+    pub fn new(player: CassettePlayer) -> Self {
+        let profile = profile_from_metadata(player.subject(), &player.metadata());
+        Self { player, profile }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, CassetteError> {
+        Ok(Self::new(CassettePlayer::load(path, CassetteKind::Llm)?))
+    }
+
+    pub fn player(&self) -> &CassettePlayer {
+        &self.player
+    }
+}
+
+#[async_trait]
+impl LlmClient for ReplayLlmClient {
+    async fn completion(&self, req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        result_from_outcome(self.player.next(&completion_request_key(&req))?)
+    }
+
+    async fn embedding(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, LlmError> {
+        result_from_outcome(self.player.next(&embedding_request_key(&req))?)
+    }
+
+    async fn swap_model(&self, _req: ModelSwapRequestV0_4) -> Result<(), LlmError> {
+        Ok(())
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        &self.profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::TokenUsage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingClient {
+        calls: AtomicUsize,
+        profile: ModelProfile,
+    }
+
+    #[async_trait]
+    impl LlmClient for CountingClient {
+        async fn completion(&self, req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if req.prompt == "overflow" {
+                return Err(LlmError::BudgetExceeded(4096));
+            }
+            Ok(CompletionResponse {
+                text: format!("answer {call}: {}", req.prompt),
+                usage: TokenUsage {
+                    prompt_tokens: 3,
+                    completion_tokens: 4,
+                    total_tokens: 7,
+                },
+                latency_ms: 12,
+            })
+        }
+
+        fn profile(&self) -> &ModelProfile {
+            &self.profile
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_completions_replay_offline_and_report_request_diffs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("run.cassette.json");
+        let live: Arc<dyn LlmClient> = Arc::new(CountingClient {
+            calls: AtomicUsize::new(0),
+            profile: ModelProfile::new("llama3.2".to_string(), 8192).with_tier(ModelTier::Cloud),
+        });
+        let recording = RecordingLlmClient::new(live, CassetteSink::File(path.clone()));
+        let hello = CompletionRequest::new(Uuid::now_v7(), "hello".into(), "llama3.2".into())
+            .with_max_tokens(32);
+        let recorded = recording
+            .completion(hello.clone())
+            .await
+            .expect("live completion");
+        assert!(matches!(
+            recording
+                .completion(CompletionRequest::new(
+                    Uuid::now_v7(),
+                    "overflow".into(),
+                    "llama3.2".into(),
+                ))
+                .await,
+            Err(LlmError::BudgetExceeded(4096))
+        ));
+
+        let replay = ReplayLlmClient::from_path(&path).expect("load cassette");
+        assert_eq!(replay.profile().max_context_tokens, 8192);
+        assert_eq!(replay.profile().model_tier, ModelTier::Cloud);
+
+        // A fresh trace id is not part of the match key.
+        let replayed = replay
+            .completion(CompletionRequest {
+                trace_id: Uuid::now_v7(),
+                ..hello
+            })
+            .await
+            .expect("replayed completion");
+        assert_eq!(replayed.text, recorded.text);
+        assert_eq!(replayed.usage.total_tokens, 7);
+
+        let err = replay
+            .completion(CompletionRequest::new(
+                Uuid::now_v7(),
+                "underflow".into(),
+                "llama3.2".into(),
+            ))
+            .await
+            .expect_err("prompt differs from the recording");
+        let LlmError::Cassette(CassetteError::Mismatch(mismatch)) = err else {
+            panic!("expected a cassette mismatch, got {err:?}");
+        };
+        assert_eq!(mismatch.index, 1);
+        assert_eq!(mismatch.diff.len(), 1);
+        assert_eq!(mismatch.diff[0].path, "$.prompt");
+
+        assert!(matches!(
+            replay
+                .completion(CompletionRequest::new(
+                    Uuid::now_v7(),
+                    "overflow".into(),
+                    "llama3.2".into(),
+                ))
+                .await,
+            Err(LlmError::BudgetExceeded(4096))
+        ));
+        replay
+            .player()
+            .finish()
+            .expect("every interaction replayed");
+    }
+}
@@ -0,0 +1,256 @@
+//! MCP record/replay transports.
+//!
+//! Both sit at the [`McpTransport`] layer, below `GatedMcpClient`, so the gate,
+//! consent and Flight Recorder paths run unchanged during replay. The match key
+//! for a request is its method and params. The fields the gate stamps per call
+//! (`_meta`, `job_id`, `progress_token`) and the JSON-RPC id are dropped, and a
+//! replayed response is re-addressed to the live request id. Client
+//! notifications and server-initiated traffic are not recorded.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::{
+    lock, CassetteError, CassetteKind, CassetteMismatch, CassetteOutcome, CassettePlayer,
+    CassetteRecorder, CassetteSink,
+};
+use crate::mcp::errors::McpResult;
+use crate::mcp::jsonrpc::{
+    JsonRpcError, JsonRpcId, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
+};
+use crate::mcp::transport::{ConnectedTransport, McpTransport, TransportIo, TransportTasks};
+
+/// JSON-RPC error code a replay transport answers with when its cassette
+/// cannot serve a request. `data` carries the typed [`CassetteError`], which
+/// the client surfaces as `McpError::Cassette`.
+pub const CASSETTE_RPC_ERROR_CODE: i64 = -32099;
+
+const VOLATILE_PARAM_KEYS: &[&str] = &["_meta", "job_id", "progress_token"];
+
+fn request_key(request: &JsonRpcRequest) -> Value {
+    let params = request.params.clone().map(|mut params| {
+        if let Value::Object(map) = &mut params {
+            for key in VOLATILE_PARAM_KEYS {
+                map.remove(*key);
+            }
+        }
+        params
+    });
+    json!({ "method": request.method, "params": params })
+}
+
+fn request_trace_id(request: &JsonRpcRequest) -> Option<Uuid> {
+    request
+        .params
+        .as_ref()?
+        .pointer("/_meta/handshake/trace_id")?
+        .as_str()?
+        .parse()
+        .ok()
+}
+
+fn response_outcome(response: &JsonRpcResponse) -> CassetteOutcome {
+    match &response.error {
+        Some(error) => CassetteOutcome::Error {
+            code: error.code.to_string(),
+            message: error.message.clone(),
+            data: error.data.clone(),
+        },
+        None => CassetteOutcome::Ok {
+            body: response.result.clone().unwrap_or(Value::Null),
+        },
+    }
+}
+
+fn outcome_response(id: JsonRpcId, outcome: CassetteOutcome) -> JsonRpcResponse {
+    match outcome {
+        CassetteOutcome::Ok { body } => JsonRpcResponse::ok(id, body),
+        CassetteOutcome::Error {
+            code,
+            message,
+            data,
+        } => JsonRpcResponse::err(id, code.parse().unwrap_or(-32603), message, data),
+    }
+}
+
+fn cassette_error_response(id: JsonRpcId, err: &CassetteError) -> JsonRpcResponse {
+    let data = match err {
+        CassetteError::Mismatch(mismatch) => json!({
+            "cassette_error": "mismatch",
+            "mismatch": mismatch,
+        }),
+        CassetteError::Exhausted { recorded } => json!({
+            "cassette_error": "exhausted",
+            "recorded": recorded,
+        }),
+        other => json!({
+            "cassette_error": "format",
+            "message": other.to_string(),
+        }),
+    };
+    JsonRpcResponse::err(id, CASSETTE_RPC_ERROR_CODE, err.to_string(), Some(data))
+}
+
+/// Recovers the typed cassette error from a replay transport's JSON-RPC error.
+pub(crate) fn cassette_error_from_rpc(error: &JsonRpcError) -> Option<CassetteError> {
+    if error.code != CASSETTE_RPC_ERROR_CODE {
+        return None;
+    }
+    let data = error.data.as_ref()?;
+    match data.get("cassette_error")?.as_str()? {
+        "mismatch" => {
+            let mismatch: CassetteMismatch =
+                serde_json::from_value(data.get("mismatch")?.clone()).ok()?;
+            Some(CassetteError::Mismatch(Box::new(mismatch)))
+        }
+        "exhausted" => Some(CassetteError::Exhausted {
+            recorded: data.get("recorded")?.as_u64()? as usize,
+        }),
+        _ => Some(CassetteError::Format(error.message.clone())),
+    }
+}
+
+/// Wraps a live transport and records every request/response pair, keyed to
+/// the trace in the request's Handshake `_meta` envelope.
+pub struct RecordingMcpTransport<T: McpTransport> {
+    inner: T,
+    recorder: CassetteRecorder,
+}
+
+impl<T: McpTransport> RecordingMcpTransport<T> {
+    pub fn new(inner: T, server_id: impl Into<String>, sink: CassetteSink) -> Self {
+        Self {
+            inner,
+            recorder: CassetteRecorder::new(CassetteKind::Mcp, server_id, sink),
+        }
+    }
+
+    pub fn recorder(&self) -> &CassetteRecorder {
+        &self.recorder
+    }
+}
+
+#[async_trait]
+impl<T: McpTransport + 'static> McpTransport for RecordingMcpTransport<T> {
+    async fn connect(&mut self) -> McpResult<ConnectedTransport> {
+        let ConnectedTransport { io, tasks } = self.inner.connect().await?;
+        let TransportIo {
+            outgoing: inner_outgoing,
+            incoming: mut inner_incoming,
+        } = io;
+        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let pending: Arc<Mutex<HashMap<JsonRpcId, (Option<Uuid>, Value)>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
+        let pending_for_writer = Arc::clone(&pending);
+        let writer = tokio::spawn(async move {
+            while let Some(msg) = outgoing_rx.recv().await {
+                if let JsonRpcMessage::Request(request) = &msg {
+                    lock(&pending_for_writer).insert(
+                        request.id.clone(),
+                        (request_trace_id(request), request_key(request)),
+                    );
+                }
+                if inner_outgoing.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let recorder = self.recorder.clone();
+        let reader = tokio::spawn(async move {
+            // Dropping this task (or finishing it) tears down the inner transport.
+            let _inner_tasks = tasks;
+            while let Some(msg) = inner_incoming.recv().await {
+                if let JsonRpcMessage::Response(response) = &msg {
+                    let entry = lock(&pending).remove(&response.id);
+                    if let Some((trace_id, key)) = entry {
+                        if let Err(err) = recorder
+                            .record_async(trace_id, key, response_outcome(response))
+                            .await
+                        {
+                            tracing::warn!(
+                                target: "handshake_core::cassette",
+                                error = %err,
+                                "MCP cassette record failed"
+                            );
+                        }
+                    }
+                }
+                if incoming_tx.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ConnectedTransport {
+            io: TransportIo {
+                outgoing: outgoing_tx,
+                incoming: incoming_rx,
+            },
+            tasks: TransportTasks::new(vec![writer, reader]),
+        })
+    }
+}
+
+/// Answers requests from a recorded MCP cassette instead of a server.
+/// Reconnects resume from the shared cursor.
+pub struct ReplayMcpTransport {
+    player: CassettePlayer,
+}
+
+impl ReplayMcpTransport {
+    pub fn new(player: CassettePlayer) -> Self {
+        Self { player }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, CassetteError> {
+        Ok(Self::new(CassettePlayer::load(path, CassetteKind::Mcp)?))
+    }
+
+    pub fn player(&self) -> &CassettePlayer {
+        &self.player
+    }
+}
+
+#[async_trait]
+impl McpTransport for ReplayMcpTransport {
+    async fn connect(&mut self) -> McpResult<ConnectedTransport> {
+        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
+        let player = self.player.clone();
+
+        let server = tokio::spawn(async move {
+            while let Some(msg) = outgoing_rx.recv().await {
+                let JsonRpcMessage::Request(request) = msg else {
+                    continue;
+                };
+                let response = match player.next(&request_key(&request)) {
+                    Ok(outcome) => outcome_response(request.id, outcome),
+                    Err(err) => cassette_error_response(request.id, &err),
+                };
+                if incoming_tx
+                    .send(JsonRpcMessage::Response(response))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Ok(ConnectedTransport {
+            io: TransportIo {
+                outgoing: outgoing_tx,
+                incoming: incoming_rx,
+            },
+            tasks: TransportTasks::new(vec![server]),
+        })
+    }
+}
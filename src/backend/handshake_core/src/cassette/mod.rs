@@ -0,0 +1,739 @@
+//! Record/replay cassettes for `LlmClient` and MCP traffic.
+//!
+//! A cassette is a versioned JSON file of request/response pairs. Recording
+//! decorators ([`RecordingLlmClient`], [`RecordingMcpTransport`]) wrap a live
+//! client and append every exchange as one JSON line, so recording a long
+//! trace stays linear; [`Cassette::load`] reads that framing as well as a
+//! single JSON document. Replay twins ([`ReplayLlmClient`],
+//! [`ReplayMcpTransport`]) serve the recording back in order without touching a
+//! model or server. A replayed request that differs from the recording fails
+//! with [`CassetteError::Mismatch`], which carries a path-level diff.
+//!
+//! Requests are normalized before they are stored: trace ids, JSON-RPC ids and
+//! the per-call Handshake `_meta` envelope change on every run and are dropped.
+//! Both sides then pass through the bundle [`SecretRedactor`]. The player
+//! redacts live requests with the cassette's recorded mode before comparing, so
+//! a request still matches its redacted recording.
+//!
+//! Runtime recording is per trace. Cassettes land under
+//! `.handshake/cassettes/<trace_id>/` in the workspace, and the debug bundle
+//! exporter picks them up from there for a job's trace.
+
+pub mod llm;
+pub mod mcp;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::bundles::redactor::SecretRedactor;
+use crate::bundles::schemas::RedactionMode;
+
+pub use llm::{RecordingLlmClient, ReplayLlmClient};
+pub use mcp::{RecordingMcpTransport, ReplayMcpTransport, CASSETTE_RPC_ERROR_CODE};
+
+pub const CASSETTE_SCHEMA_VERSION: &str = "hsk.cassette@1";
+pub const CASSETTE_FILE_SUFFIX: &str = ".cassette.json";
+const CASSETTES_DIR: &str = ".handshake/cassettes";
+const UNTRACED_DIR: &str = "untraced";
+
+#[derive(Debug, Error)]
+pub enum CassetteError {
+    #[error("HSK-500-CASSETTE-IO: {0}")]
+    Io(String),
+    #[error("HSK-400-CASSETTE-FORMAT: {0}")]
+    Format(String),
+    #[error("HSK-400-CASSETTE-VERSION: unsupported cassette schema_version `{0}`")]
+    UnsupportedVersion(String),
+    #[error("HSK-400-CASSETTE-KIND: expected a {expected} cassette, found {actual}")]
+    KindMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    #[error("HSK-409-CASSETTE-MISMATCH: {0}")]
+    Mismatch(Box<CassetteMismatch>),
+    #[error(
+        "HSK-409-CASSETTE-EXHAUSTED: all {recorded} recorded interactions were already replayed"
+    )]
+    Exhausted { recorded: usize },
+    #[error("HSK-409-CASSETTE-UNPLAYED: {remaining} recorded interactions were never requested")]
+    Unplayed { remaining: usize },
+}
+
+impl From<std::io::Error> for CassetteError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CassetteError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Format(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CassetteKind {
+    Llm,
+    Mcp,
+}
+
+impl CassetteKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Mcp => "mcp",
+        }
+    }
+}
+
+/// What the live side answered for one recorded request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CassetteOutcome {
+    Ok {
+        body: Value,
+    },
+    Error {
+        code: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<Value>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteInteraction {
+    pub index: usize,
+    /// Normalized, redacted request; the replay match key.
+    pub request: Value,
+    pub outcome: CassetteOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub schema_version: String,
+    pub kind: CassetteKind,
+    /// Model id for LLM cassettes, server id for MCP cassettes.
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<Uuid>,
+    pub recorded_at: DateTime<Utc>,
+    /// Strongest redaction applied so far; replay redacts live requests the
+    /// same way before matching.
+    pub redaction_mode: RedactionMode,
+    #[serde(default)]
+    pub metadata: Value,
+    /// Absent from the header line of the JSON-lines framing.
+    #[serde(default)]
+    pub interactions: Vec<CassetteInteraction>,
+}
+
+impl Cassette {
+    pub fn new(kind: CassetteKind, subject: impl Into<String>) -> Self {
+        Self {
+            schema_version: CASSETTE_SCHEMA_VERSION.to_string(),
+            kind,
+            subject: subject.into(),
+            trace_id: None,
+            recorded_at: Utc::now(),
+            redaction_mode: RedactionMode::FullLocal,
+            metadata: Value::Null,
+            interactions: Vec::new(),
+        }
+    }
+
+    /// Parses either framing: one JSON document, or a header line followed by
+    /// one interaction per line. A torn final line (a recorder killed mid-
+    /// append) is dropped; any other bad line is an error.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, CassetteError> {
+        if let Ok(value) = serde_json::from_slice::<Value>(bytes) {
+            return Self::from_value(value);
+        }
+        let mut lines = bytes
+            .split(|byte| *byte == b'\n')
+            .filter(|line| line.iter().any(|byte| !byte.is_ascii_whitespace()));
+        let header = lines
+            .next()
+            .ok_or_else(|| CassetteError::Format("empty cassette".to_string()))?;
+        let mut cassette = Self::from_value(serde_json::from_slice(header)?)?;
+        let lines: Vec<&[u8]> = lines.collect();
+        let torn_tail = !bytes.ends_with(b"\n");
+        for (position, line) in lines.iter().enumerate() {
+            match serde_json::from_slice::<CassetteInteraction>(line) {
+                Ok(interaction) => cassette.interactions.push(interaction),
+                Err(_) if torn_tail && position + 1 == lines.len() => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(cassette)
+    }
+
+    fn from_value(value: Value) -> Result<Self, CassetteError> {
+        let version = value
+            .get("schema_version")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if version != CASSETTE_SCHEMA_VERSION {
+            return Err(CassetteError::UnsupportedVersion(version.to_string()));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CassetteError> {
+        let bytes = fs::read(path)
+            .map_err(|e| CassetteError::Io(format!("read {}: {e}", path.display())))?;
+        Self::from_slice(&bytes)
+    }
+
+    /// Writes the cassette through a sibling temp file so a reader never sees
+    /// a half-written recording.
+    pub fn save(&self, path: &Path) -> Result<(), CassetteError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = serde_json::to_vec_pretty(self)?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Writes the JSON-lines framing the recorder appends to: the header (the
+    /// cassette without its interactions), then one line per interaction.
+    /// Goes through a sibling temp file like [`Self::save`].
+    pub fn save_lines(&self, path: &Path) -> Result<(), CassetteError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut header = serde_json::to_value(self)?;
+        if let Some(object) = header.as_object_mut() {
+            object.remove("interactions");
+        }
+        let mut bytes = serde_json::to_vec(&header)?;
+        bytes.push(b'\n');
+        for interaction in &self.interactions {
+            serde_json::to_writer(&mut bytes, interaction)?;
+            bytes.push(b'\n');
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Appends one interaction line to a file started by [`Self::save_lines`].
+    fn append_line(path: &Path, interaction: &CassetteInteraction) -> Result<(), CassetteError> {
+        let mut line = serde_json::to_vec(interaction)?;
+        line.push(b'\n');
+        let mut file = fs::OpenOptions::new().append(true).open(path)?;
+        file.write_all(&line)?;
+        Ok(())
+    }
+
+    /// `<kind>-<subject><CASSETTE_FILE_SUFFIX>`, with the subject reduced to
+    /// filename-safe characters.
+    pub fn file_name(kind: CassetteKind, subject: &str) -> String {
+        let subject: String = subject
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}-{}{}", kind.as_str(), subject, CASSETTE_FILE_SUFFIX)
+    }
+}
+
+/// Directory holding every cassette recorded for one trace.
+pub fn cassette_dir_for_trace(workspace_root: &Path, trace_id: Uuid) -> PathBuf {
+    workspace_root
+        .join(CASSETTES_DIR)
+        .join(trace_id.to_string())
+}
+
+/// Cassette files recorded for `trace_id`, sorted by file name. A trace that
+/// never recorded anything yields an empty list.
+pub fn list_trace_cassettes(
+    workspace_root: &Path,
+    trace_id: Uuid,
+) -> Result<Vec<PathBuf>, CassetteError> {
+    let dir = cassette_dir_for_trace(workspace_root, trace_id);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let is_cassette = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(CASSETTE_FILE_SUFFIX));
+        if is_cassette && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// A replayed request that does not match the recording at `index`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteMismatch {
+    pub index: usize,
+    pub expected: Value,
+    pub actual: Value,
+    pub diff: Vec<RequestDiffEntry>,
+}
+
+impl fmt::Display for CassetteMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "interaction {} request differs from the recording",
+            self.index
+        )?;
+        for entry in &self.diff {
+            write!(f, "\n~ {}", entry.path)?;
+            if let Some(expected) = &entry.expected {
+                write!(f, "\n  - {expected}")?;
+            }
+            if let Some(actual) = &entry.actual {
+                write!(f, "\n  + {actual}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One differing JSON path; a missing side means the path is absent there.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestDiffEntry {
+    pub path: String,
+    #[serde(default)]
+    pub expected: Option<Value>,
+    #[serde(default)]
+    pub actual: Option<Value>,
+}
+
+/// Leaf-level diff of two requests, in path order.
+pub fn diff_requests(expected: &Value, actual: &Value) -> Vec<RequestDiffEntry> {
+    let mut out = Vec::new();
+    diff_values("$", Some(expected), Some(actual), &mut out);
+    out
+}
+
+fn diff_values(
+    path: &str,
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+    out: &mut Vec<RequestDiffEntry>,
+) {
+    match (expected, actual) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                diff_values(&format!("{path}.{key}"), a.get(key), b.get(key), out);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for idx in 0..a.len().max(b.len()) {
+                diff_values(&format!("{path}[{idx}]"), a.get(idx), b.get(idx), out);
+            }
+        }
+        (a, b) if a == b => {}
+        (a, b) => out.push(RequestDiffEntry {
+            path: path.to_string(),
+            expected: a.cloned(),
+            actual: b.cloned(),
+        }),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Where a recording decorator writes its cassettes.
+#[derive(Debug, Clone)]
+pub enum CassetteSink {
+    /// One cassette file for everything the decorator sees (tests, repros).
+    /// Starts empty; an existing file is overwritten on the first exchange.
+    File(PathBuf),
+    /// One cassette per trace under `.handshake/cassettes/<trace_id>/`,
+    /// appended to, including across processes.
+    PerTrace { workspace_root: PathBuf },
+}
+
+/// Appends redacted interactions to cassette files. Cheap to clone; clones
+/// share the same sink.
+#[derive(Clone)]
+pub struct CassetteRecorder {
+    kind: CassetteKind,
+    subject: String,
+    metadata: Value,
+    sink: CassetteSink,
+    redactor: Arc<SecretRedactor>,
+    /// Interactions on disk per cassette file this recorder has opened (the
+    /// next index to append); also serializes writes.
+    written: Arc<Mutex<HashMap<PathBuf, usize>>>,
+}
+
+impl CassetteRecorder {
+    pub fn new(kind: CassetteKind, subject: impl Into<String>, sink: CassetteSink) -> Self {
+        Self {
+            kind,
+            subject: subject.into(),
+            metadata: Value::Null,
+            sink,
+            redactor: Arc::new(SecretRedactor::new()),
+            written: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn path_for(&self, trace_id: Option<Uuid>) -> PathBuf {
+        let file_name = Cassette::file_name(self.kind, &self.subject);
+        match &self.sink {
+            CassetteSink::File(path) => path.clone(),
+            CassetteSink::PerTrace { workspace_root } => match trace_id {
+                Some(trace_id) => cassette_dir_for_trace(workspace_root, trace_id).join(file_name),
+                None => workspace_root
+                    .join(CASSETTES_DIR)
+                    .join(UNTRACED_DIR)
+                    .join(file_name),
+            },
+        }
+    }
+
+    /// Appends one interaction line to the cassette file, so a job that dies
+    /// mid-run still leaves everything it exchanged on disk. The first record
+    /// to a file starts it (`File`) or picks up what is already there
+    /// (`PerTrace`, rewritten once into the line framing); later ones only
+    /// append. Blocking file I/O: async callers use [`Self::record_async`].
+    pub fn record(
+        &self,
+        trace_id: Option<Uuid>,
+        request: &Value,
+        outcome: CassetteOutcome,
+    ) -> Result<(), CassetteError> {
+        let mode = RedactionMode::FullLocal;
+        let request = self.redact(request, mode, "$.request");
+        let outcome = match outcome {
+            CassetteOutcome::Ok { body } => CassetteOutcome::Ok {
+                body: self.redact(&body, mode, "$.response"),
+            },
+            CassetteOutcome::Error {
+                code,
+                message,
+                data,
+            } => CassetteOutcome::Error {
+                code,
+                message: match self.redact(&Value::String(message), mode, "$.error.message") {
+                    Value::String(message) => message,
+                    other => other.to_string(),
+                },
+                data: data.map(|data| self.redact(&data, mode, "$.error.data")),
+            },
+        };
+
+        let path = self.path_for(trace_id);
+        let mut written = lock(&self.written);
+        let index = match written.get(&path) {
+            Some(&index) => index,
+            None => self.open_file(&path, trace_id)?,
+        };
+        Cassette::append_line(
+            &path,
+            &CassetteInteraction {
+                index,
+                request,
+                outcome,
+            },
+        )?;
+        written.insert(path, index + 1);
+        Ok(())
+    }
+
+    /// [`Self::record`] on the blocking pool, in call order for a caller that
+    /// awaits each record.
+    pub async fn record_async(
+        &self,
+        trace_id: Option<Uuid>,
+        request: Value,
+        outcome: CassetteOutcome,
+    ) -> Result<(), CassetteError> {
+        let recorder = self.clone();
+        tokio::task::spawn_blocking(move || recorder.record(trace_id, &request, outcome))
+            .await
+            .map_err(|err| CassetteError::Io(format!("cassette record task: {err}")))?
+    }
+
+    /// Starts (or takes over) the cassette file at `path` in the line framing
+    /// and returns how many interactions it already holds.
+    fn open_file(&self, path: &Path, trace_id: Option<Uuid>) -> Result<usize, CassetteError> {
+        let existing = match &self.sink {
+            CassetteSink::PerTrace { .. } if path.is_file() => Some(Cassette::load(path)?),
+            _ => None,
+        };
+        let cassette = existing.unwrap_or_else(|| {
+            let mut cassette = Cassette::new(self.kind, self.subject.clone());
+            cassette.trace_id = match &self.sink {
+                CassetteSink::File(_) => None,
+                CassetteSink::PerTrace { .. } => trace_id,
+            };
+            cassette.metadata = self.metadata.clone();
+            cassette
+        });
+        if cassette.kind != self.kind {
+            return Err(CassetteError::KindMismatch {
+                expected: self.kind.as_str(),
+                actual: cassette.kind.as_str(),
+            });
+        }
+        cassette.save_lines(path)?;
+        Ok(cassette.interactions.len())
+    }
+
+    fn redact(&self, value: &Value, mode: RedactionMode, location: &str) -> Value {
+        self.redactor.redact_value(value, mode, location).0
+    }
+}
+
+struct PlayerState {
+    cassette: Cassette,
+    cursor: usize,
+}
+
+/// Serves a cassette's interactions in recorded order. Cheap to clone; clones
+/// share the cursor.
+#[derive(Clone)]
+pub struct CassettePlayer {
+    redactor: Arc<SecretRedactor>,
+    state: Arc<Mutex<PlayerState>>,
+}
+
+impl CassettePlayer {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            redactor: Arc::new(SecretRedactor::new()),
+            state: Arc::new(Mutex::new(PlayerState {
+                cassette,
+                cursor: 0,
+            })),
+        }
+    }
+
+    pub fn load(path: &Path, kind: CassetteKind) -> Result<Self, CassetteError> {
+        let cassette = Cassette::load(path)?;
+        if cassette.kind != kind {
+            return Err(CassetteError::KindMismatch {
+                expected: kind.as_str(),
+                actual: cassette.kind.as_str(),
+            });
+        }
+        Ok(Self::new(cassette))
+    }
+
+    pub fn subject(&self) -> String {
+        lock(&self.state).cassette.subject.clone()
+    }
+
+    pub fn metadata(&self) -> Value {
+        lock(&self.state).cassette.metadata.clone()
+    }
+
+    /// Matches `request` against the next recorded interaction and returns its
+    /// outcome. A mismatch leaves the cursor in place.
+    pub fn next(&self, request: &Value) -> Result<CassetteOutcome, CassetteError> {
+        let mut state = lock(&self.state);
+        let actual = self
+            .redactor
+            .redact_value(request, state.cassette.redaction_mode, "$.request")
+            .0;
+        let index = state.cursor;
+        let Some(interaction) = state.cassette.interactions.get(index) else {
+            return Err(CassetteError::Exhausted {
+                recorded: state.cassette.interactions.len(),
+            });
+        };
+        if interaction.request != actual {
+            return Err(CassetteError::Mismatch(Box::new(CassetteMismatch {
+                index,
+                diff: diff_requests(&interaction.request, &actual),
+                expected: interaction.request.clone(),
+                actual,
+            })));
+        }
+        let outcome = interaction.outcome.clone();
+        state.cursor += 1;
+        Ok(outcome)
+    }
+
+    pub fn remaining(&self) -> usize {
+        let state = lock(&self.state);
+        state.cassette.interactions.len() - state.cursor
+    }
+
+    /// Fails when the run under replay stopped short of the recording.
+    pub fn finish(&self) -> Result<(), CassetteError> {
+        match self.remaining() {
+            0 => Ok(()),
+            remaining => Err(CassetteError::Unplayed { remaining }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_paths() {
+        let expected = json!({ "prompt": "a", "stop": ["x"], "max_tokens": 5 });
+        let actual = json!({ "prompt": "b", "stop": ["x", "y"], "model": "m" });
+        let diff = diff_requests(&expected, &actual);
+        let paths: Vec<&str> = diff.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["$.max_tokens", "$.model", "$.prompt", "$.stop[1]"]
+        );
+        assert_eq!(diff[0].actual, None);
+        assert_eq!(diff[1].expected, None);
+        assert_eq!(diff[2].expected, Some(json!("a")));
+    }
+
+    #[test]
+    fn recorder_redacts_secrets_and_player_matches_live_request() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("llm.cassette.json");
+        let recorder =
+            CassetteRecorder::new(CassetteKind::Llm, "m", CassetteSink::File(path.clone()));
+        let request = json!({ "prompt": "use key sk-abcdefghijklmnop please" });
+        recorder
+            .record(
+                None,
+                &request,
+                CassetteOutcome::Ok {
+                    body: json!({ "text": "ok" }),
+                },
+            )
+            .expect("record");
+
+        let raw = fs::read_to_string(&path).expect("read cassette");
+        assert!(!raw.contains("sk-abcdefghijklmnop"), "secret leaked: {raw}");
+
+        let player = CassettePlayer::load(&path, CassetteKind::Llm).expect("load");
+        let outcome = player
+            .next(&request)
+            .expect("redacted live request matches");
+        assert_eq!(
+            outcome,
+            CassetteOutcome::Ok {
+                body: json!({ "text": "ok" })
+            }
+        );
+        assert!(matches!(
+            player.next(&request),
+            Err(CassetteError::Exhausted { recorded: 1 })
+        ));
+        player.finish().expect("fully played");
+    }
+
+    #[test]
+    fn per_trace_recording_appends_lines_and_resumes_across_recorders() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let sink = CassetteSink::PerTrace {
+            workspace_root: dir.path().to_path_buf(),
+        };
+        let trace_id = Uuid::now_v7();
+        let ok = |n: usize| CassetteOutcome::Ok {
+            body: json!({ "n": n }),
+        };
+
+        let first = CassetteRecorder::new(CassetteKind::Mcp, "srv", sink.clone());
+        for n in 0..3 {
+            first
+                .record(Some(trace_id), &json!({ "call": n }), ok(n))
+                .expect("record");
+        }
+        let path = first.path_for(Some(trace_id));
+        let raw = fs::read_to_string(&path).expect("read cassette");
+        assert_eq!(raw.lines().count(), 4, "header plus one line per exchange");
+
+        // A later process picks the file up and keeps numbering.
+        let second = CassetteRecorder::new(CassetteKind::Mcp, "srv", sink);
+        second
+            .record(Some(trace_id), &json!({ "call": 3 }), ok(3))
+            .expect("record");
+        let cassette = Cassette::load(&path).expect("load");
+        assert_eq!(cassette.trace_id, Some(trace_id));
+        let indexes: Vec<usize> = cassette.interactions.iter().map(|i| i.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+
+        // A torn final line (killed mid-append) is dropped, not fatal.
+        let mut torn = fs::read(&path).expect("read");
+        torn.extend_from_slice(b"{\"index\":4,\"requ");
+        assert_eq!(
+            Cassette::from_slice(&torn)
+                .expect("torn")
+                .interactions
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn player_mismatch_keeps_cursor_and_rejects_unknown_versions() {
+        let mut cassette = Cassette::new(CassetteKind::Mcp, "srv");
+        cassette.interactions.push(CassetteInteraction {
+            index: 0,
+            request: json!({ "method": "tools/call", "params": { "name": "echo" } }),
+            outcome: CassetteOutcome::Ok { body: json!({}) },
+        });
+        let player = CassettePlayer::new(cassette.clone());
+        let err = player
+            .next(&json!({ "method": "tools/call", "params": { "name": "read" } }))
+            .expect_err("mismatch");
+        let CassetteError::Mismatch(mismatch) = err else {
+            panic!("expected mismatch, got {err:?}");
+        };
+        assert_eq!(mismatch.index, 0);
+        assert_eq!(mismatch.diff.len(), 1);
+        assert_eq!(mismatch.diff[0].path, "$.params.name");
+        assert!(mismatch.to_string().contains("- \"echo\""));
+        assert_eq!(player.remaining(), 1);
+        assert!(matches!(
+            player.finish(),
+            Err(CassetteError::Unplayed { remaining: 1 })
+        ));
+
+        cassette.schema_version = "hsk.cassette@0".to_string();
+        let bytes = serde_json::to_vec(&cassette).expect("serialize");
+        assert!(matches!(
+            Cassette::from_slice(&bytes),
+            Err(CassetteError::UnsupportedVersion(version)) if version == "hsk.cassette@0"
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+//! In-process LoRA trainer for the distillation pipeline.
+//!
+//! [`CandleLoraTrainerExecutor`] is a [`PeftTrainerExecutor`] that trains the
+//! student on the candle engine instead of a Python subprocess. It reads the
+//! reviewed corpus JSONL that `finish_distill` wrote, tokenizes it with the
+//! student's `tokenizer.json`, and runs [`train_lora`] over a
+//! [`CandleLlamaModel`]. Next to the adapter it writes the same
+//! `provenance.json` sidecar as `scripts/distill/train_lora.py`, plus a
+//! [`TRAINING_LOG_FILE_NAME`]. Each log entry is also reported as training
+//! produces it, so `distill_with_flight_recorder` records
+//! `distill.training_step` / `distill.checkpoint_created` events live.
+
+use std::path::{Path, PathBuf};
+
+use candle_core::Device;
+use sha2::{Digest, Sha256};
+
+use super::peft_pipeline::{
+    read_training_corpus_jsonl, write_training_log, DistillError, DistillJobConfig,
+    PeftProvenanceSidecar, PeftTrainerExecutor, TrainingLogEntry, TRAINING_LOG_FILE_NAME,
+};
+use crate::model_runtime::candle::{
+    lora_train::{train_lora_with_progress, LoraTrainingConfig, LoraTrainingExample},
+    tokenizer_json_path_for_artifact, CandleLlamaModel,
+};
+
+const PROVENANCE_SCHEMA: &str = "hsk.distill.lora_provenance@v1";
+const DEFAULT_MAX_SEQ_LEN: usize = 512;
+
+pub struct CandleLoraTrainerExecutor {
+    device: Device,
+    max_seq_len: usize,
+    target_modules: Option<Vec<String>>,
+    checkpoint_every_steps: Option<u32>,
+}
+
+impl CandleLoraTrainerExecutor {
+    pub fn new(device: Device) -> Self {
+        Self {
+            device,
+            max_seq_len: DEFAULT_MAX_SEQ_LEN,
+            target_modules: None,
+            checkpoint_every_steps: None,
+        }
+    }
+
+    pub fn cpu() -> Self {
+        Self::new(Device::Cpu)
+    }
+
+    pub fn with_max_seq_len(mut self, max_seq_len: usize) -> Self {
+        self.max_seq_len = max_seq_len;
+        self
+    }
+
+    /// Override the trained modules (default: `q_proj`, `v_proj`).
+    pub fn with_target_modules(mut self, target_modules: Vec<String>) -> Self {
+        self.target_modules = Some(target_modules);
+        self
+    }
+
+    /// Write `checkpoint-<step>/` every `steps` optimizer steps.
+    pub fn with_checkpoint_every_steps(mut self, steps: u32) -> Self {
+        self.checkpoint_every_steps = Some(steps);
+        self
+    }
+
+    fn training_config(&self, config: &DistillJobConfig) -> LoraTrainingConfig {
+        let hyperparams = &config.hyperparams;
+        let defaults = LoraTrainingConfig::default();
+        LoraTrainingConfig {
+            rank: hyperparams.rank,
+            alpha: hyperparams.alpha,
+            dropout: hyperparams.dropout,
+            learning_rate: hyperparams.learning_rate as f64,
+            epochs: hyperparams.epochs,
+            gradient_accumulation_steps: hyperparams.batch_size,
+            max_steps: config.max_steps,
+            max_seq_len: self.max_seq_len,
+            target_modules: self
+                .target_modules
+                .clone()
+                .unwrap_or(defaults.target_modules),
+            checkpoint_every_steps: self.checkpoint_every_steps,
+            weight_decay: defaults.weight_decay,
+        }
+    }
+}
+
+impl PeftTrainerExecutor for CandleLoraTrainerExecutor {
+    fn run(&self, config: &DistillJobConfig) -> Result<(), DistillError> {
+        self.run_with_progress(config, &mut |_| {})
+    }
+
+    fn run_with_progress(
+        &self,
+        config: &DistillJobConfig,
+        progress: &mut dyn FnMut(TrainingLogEntry),
+    ) -> Result<(), DistillError> {
+        let weights_path = student_weights_path(&config.student_base_model_path);
+        if !weights_path.is_file() {
+            return Err(DistillError::TrainerUnavailable(format!(
+                "candle student weights missing at {}",
+                weights_path.display()
+            )));
+        }
+        let tokenizer_path = tokenizer_json_path_for_artifact(&weights_path);
+        let tokenizer = tokenizers::Tokenizer::from_file(&tokenizer_path).map_err(|err| {
+            DistillError::TrainerUnavailable(format!(
+                "load student tokenizer {}: {err}",
+                tokenizer_path.display()
+            ))
+        })?;
+
+        let corpus = read_training_corpus_jsonl(
+            &config.corpus_jsonl_path,
+            "candle-lora-trainer",
+            &config.license_tag,
+        )?;
+        let mut examples = Vec::with_capacity(corpus.turns.len());
+        for turn in &corpus.turns {
+            examples.push(LoraTrainingExample {
+                prompt_ids: encode(&tokenizer, &turn.prompt, true)?,
+                completion_ids: encode(&tokenizer, &turn.completion, false)?,
+            });
+        }
+
+        let corpus_sha256 = sha256_hex(&config.corpus_jsonl_path)?;
+        let data_signature = format!("sha256:{corpus_sha256}");
+        let mut model = CandleLlamaModel::load_safetensors(&weights_path, &self.device)
+            .map_err(|err| DistillError::TrainerExec(format!("load candle student: {err}")))?;
+        let training_config = self.training_config(config);
+        let mut log = Vec::new();
+        let mut parent_checkpoint_id = None;
+        let mut report_entry = |entry: TrainingLogEntry| {
+            log.push(entry.clone());
+            progress(entry);
+        };
+        let report = train_lora_with_progress(
+            &mut model,
+            &examples,
+            &training_config,
+            &config.output_lora_dir,
+            &mut |step, checkpoint| {
+                report_entry(TrainingLogEntry::Step {
+                    step: step.step,
+                    epoch: step.epoch,
+                    loss: step.loss,
+                    learning_rate: step.learning_rate,
+                    tokens: step.tokens,
+                });
+                if let Some(checkpoint) = checkpoint {
+                    let checkpoint_id = checkpoint
+                        .dir
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| format!("checkpoint-{}", checkpoint.step));
+                    report_entry(TrainingLogEntry::Checkpoint {
+                        step: checkpoint.step,
+                        checkpoint_id: checkpoint_id.clone(),
+                        parent_checkpoint_id: parent_checkpoint_id.replace(checkpoint_id),
+                        data_signature: data_signature.clone(),
+                    });
+                }
+            },
+        )
+        .map_err(|err| DistillError::TrainerExec(err.to_string()))?;
+
+        write_training_log(&config.output_lora_dir.join(TRAINING_LOG_FILE_NAME), &log)?;
+
+        let sidecar = PeftProvenanceSidecar {
+            teacher_model_id: config.teacher_model_path.display().to_string(),
+            teacher_source: config.teacher_source.cli_arg_value().to_string(),
+            student_base_id: config.student_base_model_path.display().to_string(),
+            corpus_path: config.corpus_jsonl_path.display().to_string(),
+            corpus_sha256: Some(corpus_sha256),
+            license_tag: config.license_tag.clone(),
+            operator_signature: config.operator_signature.clone(),
+            trained_at_utc: chrono::Utc::now().to_rfc3339(),
+            training_loss: report.final_loss().unwrap_or_default(),
+            num_steps: report.steps.len() as u32,
+            hyperparams: serde_json::to_value(&config.hyperparams)
+                .map_err(|err| DistillError::TrainerExec(format!("encode hyperparams: {err}")))?,
+            format: "safetensors".to_string(),
+            schema: PROVENANCE_SCHEMA.to_string(),
+        };
+        let sidecar_path = config.output_lora_dir.join("provenance.json");
+        let raw = serde_json::to_vec_pretty(&sidecar)
+            .map_err(|err| DistillError::TrainerExec(format!("encode provenance: {err}")))?;
+        std::fs::write(&sidecar_path, raw).map_err(|err| {
+            DistillError::TrainerExec(format!(
+                "write provenance sidecar {}: {err}",
+                sidecar_path.display()
+            ))
+        })
+    }
+}
+
+/// A student path may name the safetensors file or the model directory
+/// holding `model.safetensors`.
+fn student_weights_path(student: &Path) -> PathBuf {
+    if student.is_dir() {
+        student.join("model.safetensors")
+    } else {
+        student.to_path_buf()
+    }
+}
+
+fn encode(
+    tokenizer: &tokenizers::Tokenizer,
+    text: &str,
+    add_special_tokens: bool,
+) -> Result<Vec<u32>, DistillError> {
+    tokenizer
+        .encode(text, add_special_tokens)
+        .map(|encoding| encoding.get_ids().to_vec())
+        .map_err(|err| DistillError::TrainerExec(format!("tokenize corpus row: {err}")))
+}
+
+fn sha256_hex(path: &Path) -> Result<String, DistillError> {
+    let bytes = std::fs::read(path)
+        .map_err(|err| DistillError::CorpusRead(format!("read {}: {err}", path.display())))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
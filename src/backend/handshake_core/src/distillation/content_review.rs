@@ -15,6 +15,7 @@
 //! the file move. The pipeline itself never touches the filesystem.
 
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -23,12 +24,14 @@ use thiserror::Error;
 use uuid::Uuid;
 
 use super::corpus_extractor::TrainingTurn;
-use super::pii_patterns::{scan as scan_pii, PiiDetection, PiiSeverity};
+use super::pii_patterns::{scan_with_engine, PiiDetection, PiiSeverity};
 use crate::flight_recorder::fr_event_registry::FrEventId;
 use crate::flight_recorder::{
     FlightRecorder, FlightRecorderActor, FlightRecorderEvent, FlightRecorderEventType,
     RecorderError,
 };
+use crate::redaction::{DetectionEngine, DetectionRuleError};
+use crate::storage::Database;
 
 /// Default license allowlist per MT-120 implementation_notes. Operators
 /// override via [`ContentReviewConfig`].
@@ -38,14 +41,17 @@ pub const NEAR_DUPLICATE_DETECTOR_LEXICAL_TOKEN_COSINE: &str = "lexical_token_co
 pub const DEFAULT_NEAR_DUPLICATE_SIMILARITY_THRESHOLD_MILLI: u16 = 950;
 pub const DEFAULT_NEAR_DUPLICATE_MIN_SHARED_TOKENS: usize = 8;
 
-/// Per-operator configuration. The default constructor uses the
-/// [`DEFAULT_LICENSE_ALLOWLIST`].
+/// Per-operator configuration. [`ContentReviewConfig::defaults`] uses the
+/// [`DEFAULT_LICENSE_ALLOWLIST`] and the built-in detection engine; a corpus
+/// drawn from a workspace is reviewed with
+/// [`ContentReviewConfig::for_workspace`] so its governed rules apply.
 #[derive(Clone, Debug)]
 pub struct ContentReviewConfig {
     pub license_allowlist: HashSet<String>,
     pub quarantine_root: String,
     pub near_duplicate_similarity_threshold_milli: u16,
     pub near_duplicate_min_shared_tokens: usize,
+    pub detection_engine: Arc<DetectionEngine>,
 }
 
 impl ContentReviewConfig {
@@ -59,8 +65,21 @@ impl ContentReviewConfig {
             near_duplicate_similarity_threshold_milli:
                 DEFAULT_NEAR_DUPLICATE_SIMILARITY_THRESHOLD_MILLI,
             near_duplicate_min_shared_tokens: DEFAULT_NEAR_DUPLICATE_MIN_SHARED_TOKENS,
+            detection_engine: Arc::new(DetectionEngine::shared().clone()),
         }
     }
+
+    /// Defaults with `workspace_id`'s governed detection rules.
+    pub async fn for_workspace(
+        db: &dyn Database,
+        workspace_id: &str,
+    ) -> Result<Self, DetectionRuleError> {
+        let engine = DetectionEngine::for_workspace(db, workspace_id).await?;
+        Ok(Self {
+            detection_engine: Arc::new(engine),
+            ..Self::defaults()
+        })
+    }
 }
 
 #[derive(Debug, Error)]
@@ -310,8 +329,9 @@ impl ContentReview {
         }
 
         // PII scan over BOTH prompt and completion.
-        let mut detections: Vec<PiiDetection> = scan_pii(&turn.prompt);
-        detections.extend(scan_pii(&turn.completion));
+        let engine = &self.config.detection_engine;
+        let mut detections: Vec<PiiDetection> = scan_with_engine(&turn.prompt, engine);
+        detections.extend(scan_with_engine(&turn.completion, engine));
         for detection in &detections {
             if detection.severity == PiiSeverity::High {
                 hard_reject = true;
@@ -2,6 +2,8 @@ pub mod abliterate;
 pub mod abliterate_review;
 pub mod candidate;
 pub mod candidate_registry;
+#[cfg(feature = "candle-runtime-engine")]
+pub mod candle_trainer;
 pub mod content_review;
 pub mod corpus_extractor;
 pub mod dataset;
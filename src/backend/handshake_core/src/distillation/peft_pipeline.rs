@@ -1,17 +1,19 @@
 //! MT-122: Teacher/student PEFT distillation pipeline.
 //!
 //! Per Master Spec §4.8 + refinement INF-1 (LoRA-as-distillation-output).
-//! PEFT training is not available in Rust at production maturity as of
-//! the WP-KERNEL-004 window; the practical path is to spawn a Python
-//! subprocess that runs PEFT/Transformers training and writes a LoRA
-//! artifact directory. This module is the Rust orchestrator: it gates
-//! corpus turns through `ContentReview` (MT-120), writes the filtered
-//! corpus to a JSONL file (governed artifact), spawns the configured
-//! trainer subprocess, awaits completion, and assembles the
+//! This module is the Rust orchestrator: it gates corpus turns through
+//! `ContentReview` (MT-120), writes the filtered corpus to a JSONL file
+//! (governed artifact), runs the configured trainer, and assembles the
 //! `DistilledLoraArtifact` provenance.
 //!
-//! Subprocess launch is abstracted behind the [`PeftTrainerExecutor`]
-//! trait. The production [`PythonPeftTrainerExecutor`] env-isolates the
+//! Training is abstracted behind the [`PeftTrainerExecutor`] trait. Two
+//! production executors exist, picked per job by [`TrainerBackend`]: the
+//! in-process candle trainer (`distillation::candle_trainer`,
+//! `candle-runtime-engine` feature), which needs no Python stack and reports
+//! its loss curve step by step for Flight Recorder, and the PEFT/Transformers
+//! subprocess trainer for
+//! architectures the candle trainer does not cover. The subprocess
+//! [`PythonPeftTrainerExecutor`] env-isolates the
 //! trainer fork (clears the environment, re-injects only an OS-essential
 //! allowlist + `HANDSHAKE_DISTILL_*`) and, when a process ledger is
 //! attached, registers an attributable `ProcessOwnershipLedger` row on
@@ -38,7 +40,10 @@ use super::content_review::{
     ContentReview, ContentReviewConfig, ContentReviewOutcome, ReviewVerdict,
 };
 use super::corpus_extractor::{TrainingCorpus, TrainingTurn};
-use crate::flight_recorder::{FlightRecorder, RecorderError};
+use crate::flight_recorder::{
+    FlightRecorder, FlightRecorderActor, FlightRecorderEvent, FlightRecorderEventType,
+    RecorderError,
+};
 use crate::process_ledger::{
     LedgerBatcher, ProcessEngineKind, ProcessOwnershipRecordId, ProcessStart, ProcessStop,
     SpawnMeta,
@@ -200,6 +205,38 @@ impl TeacherSource {
     }
 }
 
+/// Which trainer runs a distillation job.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrainerBackend {
+    /// [`PythonPeftTrainerExecutor`]: `scripts/distill/train_lora.py` under
+    /// PEFT/Transformers.
+    #[default]
+    Python,
+    /// `distillation::candle_trainer::CandleLoraTrainerExecutor`, in process
+    /// (`candle-runtime-engine` feature).
+    Candle,
+}
+
+impl TrainerBackend {
+    pub fn cli_arg_value(&self) -> &'static str {
+        match self {
+            TrainerBackend::Python => "python",
+            TrainerBackend::Candle => "candle",
+        }
+    }
+
+    pub fn from_cli_arg(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "python" => Ok(TrainerBackend::Python),
+            "candle" => Ok(TrainerBackend::Candle),
+            other => Err(format!(
+                "unknown trainer backend {other:?}; expected python | candle"
+            )),
+        }
+    }
+}
+
 /// Inputs to a distillation run.
 #[derive(Clone, Debug, PartialEq)]
 pub struct DistillJobConfig {
@@ -293,6 +330,19 @@ pub struct CorpusReviewWithEvents {
 /// with engine_kind=DistillationJob (MT-069).
 pub trait PeftTrainerExecutor {
     fn run(&self, config: &DistillJobConfig) -> Result<(), DistillError>;
+
+    /// [`PeftTrainerExecutor::run`], handing each [`TrainingLogEntry`] to
+    /// `progress` as training produces it. Trainers that cannot report
+    /// progress just run; a [`TRAINING_LOG_FILE_NAME`] they leave behind is
+    /// replayed once they return.
+    fn run_with_progress(
+        &self,
+        config: &DistillJobConfig,
+        progress: &mut dyn FnMut(TrainingLogEntry),
+    ) -> Result<(), DistillError> {
+        let _ = progress;
+        self.run(config)
+    }
 }
 
 /// Provenance sidecar written by `scripts/distill/train_lora.py` next
@@ -335,6 +385,110 @@ impl PeftProvenanceSidecar {
     }
 }
 
+/// Loss-curve and checkpoint log an in-process trainer writes into the LoRA
+/// output directory, one [`TrainingLogEntry`] per line.
+/// [`distill_with_flight_recorder`] records entries into Flight Recorder as
+/// the trainer reports them, and replays this file only for trainers that
+/// reported nothing; trainers that do neither record nothing extra.
+pub const TRAINING_LOG_FILE_NAME: &str = "training_log.jsonl";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrainingLogEntry {
+    Step {
+        step: u32,
+        epoch: u32,
+        loss: f64,
+        learning_rate: f64,
+        tokens: u64,
+    },
+    Checkpoint {
+        step: u32,
+        checkpoint_id: String,
+        parent_checkpoint_id: Option<String>,
+        /// `sha256:<hex>` of the corpus the checkpoint was trained on.
+        data_signature: String,
+    },
+}
+
+impl TrainingLogEntry {
+    /// `distill.training_step` for steps, `distill.checkpoint_created` for
+    /// checkpoints.
+    pub fn flight_recorder_event(&self, trace_id: Uuid, job_id: &str) -> FlightRecorderEvent {
+        let (event_type, payload) = match self {
+            TrainingLogEntry::Step {
+                step,
+                epoch,
+                loss,
+                learning_rate,
+                tokens,
+            } => (
+                FlightRecorderEventType::DistillTrainingStep,
+                serde_json::json!({
+                    "type": "distill.training_step",
+                    "job_id": job_id,
+                    "step": step,
+                    "epoch": epoch,
+                    "loss": loss,
+                    "learning_rate": learning_rate,
+                    "tokens": tokens,
+                }),
+            ),
+            TrainingLogEntry::Checkpoint {
+                checkpoint_id,
+                parent_checkpoint_id,
+                data_signature,
+                ..
+            } => (
+                FlightRecorderEventType::DistillCheckpointCreated,
+                serde_json::json!({
+                    "type": "distill.checkpoint_created",
+                    "job_id": job_id,
+                    "checkpoint_id": checkpoint_id,
+                    "parent_checkpoint_id": parent_checkpoint_id,
+                    "adapter_type": "lora",
+                    "data_signature": data_signature,
+                }),
+            ),
+        };
+        FlightRecorderEvent::new(event_type, FlightRecorderActor::System, trace_id, payload)
+            .with_job_id(job_id)
+    }
+}
+
+pub fn write_training_log(path: &Path, entries: &[TrainingLogEntry]) -> Result<(), DistillError> {
+    let mut raw = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry).map_err(|err| {
+            DistillError::TrainerExec(format!("encode training log entry: {err}"))
+        })?;
+        raw.push_str(&line);
+        raw.push('\n');
+    }
+    std::fs::write(path, raw).map_err(|err| {
+        DistillError::TrainerExec(format!("write training log {}: {err}", path.display()))
+    })
+}
+
+pub fn read_training_log(path: &Path) -> Result<Vec<TrainingLogEntry>, DistillError> {
+    let raw = std::fs::read_to_string(path).map_err(|err| {
+        DistillError::TrainerExec(format!("read training log {}: {err}", path.display()))
+    })?;
+    raw.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(idx, line)| {
+            serde_json::from_str(line).map_err(|err| {
+                DistillError::TrainerExec(format!(
+                    "parse training log {} entry {}: {err}",
+                    path.display(),
+                    idx + 1
+                ))
+            })
+        })
+        .collect()
+}
+
 /// Production `PeftTrainerExecutor` that spawns
 /// `python <script> --corpus ... --out ... --teacher-source ...`
 /// as a subprocess. The script path is resolved relative to the
@@ -708,24 +862,25 @@ pub fn distill(
     validate_distill_config(&config)?;
 
     let reviewed = review_corpus_with_events(corpus, review_config)?;
-    finish_distill(
-        corpus,
+    write_passing_corpus(corpus, &config, &reviewed.verdicts, &reviewed.summary)?;
+    executor.run(&config)?;
+    Ok(distilled_artifact(
         config,
-        reviewed.verdicts,
-        reviewed.summary,
-        executor,
+        &reviewed.summary,
         finished_at_utc,
-    )
+    ))
 }
 
 /// Async orchestrator variant for production callers that need
 /// content-review telemetry durably recorded before any trainer gate
-/// can abort the job.
+/// can abort the job. The trainer runs on the blocking pool; the training
+/// steps and checkpoints it reports are recorded while it runs.
+#[allow(clippy::too_many_arguments)]
 pub async fn distill_with_flight_recorder<R>(
     corpus: &TrainingCorpus,
     config: DistillJobConfig,
     review_config: ContentReviewConfig,
-    executor: &dyn PeftTrainerExecutor,
+    executor: Arc<dyn PeftTrainerExecutor + Send + Sync>,
     finished_at_utc: &str,
     recorder: &R,
     trace_id: Uuid,
@@ -748,14 +903,52 @@ where
             .await?;
     }
 
-    finish_distill(
-        corpus,
+    write_passing_corpus(corpus, &config, &reviewed.verdicts, &reviewed.summary)?;
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let trainer = {
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || {
+            executor.run_with_progress(&config, &mut |entry| {
+                // The receiver outlives the trainer unless recording failed,
+                // and then the entries have nowhere to go anyway.
+                let _ = progress_tx.send(entry);
+            })
+        })
+    };
+    let mut streamed = false;
+    let mut record_error = None;
+    while let Some(entry) = progress_rx.recv().await {
+        streamed = true;
+        if record_error.is_none() {
+            if let Err(err) = recorder
+                .record_event(entry.flight_recorder_event(trace_id, job_id))
+                .await
+            {
+                record_error = Some(err);
+            }
+        }
+    }
+    trainer
+        .await
+        .map_err(|err| DistillError::TrainerExec(format!("trainer task failed: {err}")))??;
+    if let Some(err) = record_error {
+        return Err(err.into());
+    }
+
+    let training_log = config.output_lora_dir.join(TRAINING_LOG_FILE_NAME);
+    if !streamed && training_log.is_file() {
+        for entry in read_training_log(&training_log)? {
+            recorder
+                .record_event(entry.flight_recorder_event(trace_id, job_id))
+                .await?;
+        }
+    }
+    Ok(distilled_artifact(
         config,
-        reviewed.verdicts,
-        reviewed.summary,
-        executor,
+        &reviewed.summary,
         finished_at_utc,
-    )
+    ))
 }
 
 fn validate_distill_config(config: &DistillJobConfig) -> Result<(), DistillError> {
@@ -772,21 +965,21 @@ fn validate_distill_config(config: &DistillJobConfig) -> Result<(), DistillError
     Ok(())
 }
 
-fn finish_distill(
+/// Gate on at least one passing turn, then write the passing turns to the
+/// trainer's corpus path.
+fn write_passing_corpus(
     corpus: &TrainingCorpus,
-    config: DistillJobConfig,
-    verdicts: Vec<ReviewVerdict>,
-    summary: CorpusReviewSummary,
-    executor: &dyn PeftTrainerExecutor,
-    finished_at_utc: &str,
-) -> Result<DistilledLoraArtifact, DistillError> {
+    config: &DistillJobConfig,
+    verdicts: &[ReviewVerdict],
+    summary: &CorpusReviewSummary,
+) -> Result<(), DistillError> {
     if summary.pass_count == 0 {
         return Err(DistillError::NoPassingTurns {
             turn_count: summary.turn_count,
             pass_count: summary.pass_count,
         });
     }
-    let written = write_filtered_corpus_jsonl(corpus, &verdicts, &config.corpus_jsonl_path)?;
+    let written = write_filtered_corpus_jsonl(corpus, verdicts, &config.corpus_jsonl_path)?;
     if written != summary.pass_count {
         return Err(DistillError::CorpusWrite(format!(
             "wrote {written} turns to {} but {} passed review",
@@ -794,8 +987,15 @@ fn finish_distill(
             summary.pass_count
         )));
     }
-    executor.run(&config)?;
-    Ok(DistilledLoraArtifact {
+    Ok(())
+}
+
+fn distilled_artifact(
+    config: DistillJobConfig,
+    summary: &CorpusReviewSummary,
+    finished_at_utc: &str,
+) -> DistilledLoraArtifact {
+    DistilledLoraArtifact {
         lora_dir: config.output_lora_dir.clone(),
         teacher_model_path: config.teacher_model_path.clone(),
         student_base_model_path: config.student_base_model_path.clone(),
@@ -807,7 +1007,7 @@ fn finish_distill(
         license_tag: config.license_tag,
         operator_signature: config.operator_signature,
         finished_at_utc: finished_at_utc.to_string(),
-    })
+    }
 }
 
 #[cfg(test)]
@@ -1109,4 +1309,42 @@ mod tests {
         .expect_err("trainer failure");
         assert!(matches!(err, DistillError::TrainerExec(_)));
     }
+
+    #[test]
+    fn training_log_round_trips_into_valid_flight_recorder_events() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(TRAINING_LOG_FILE_NAME);
+        let entries = vec![
+            TrainingLogEntry::Step {
+                step: 1,
+                epoch: 1,
+                loss: 2.5,
+                learning_rate: 2e-4,
+                tokens: 12,
+            },
+            TrainingLogEntry::Checkpoint {
+                step: 1,
+                checkpoint_id: "checkpoint-1".to_string(),
+                parent_checkpoint_id: None,
+                data_signature: "sha256:abcdef".to_string(),
+            },
+        ];
+        write_training_log(&path, &entries).unwrap();
+        assert_eq!(read_training_log(&path).unwrap(), entries);
+
+        let trace_id = Uuid::now_v7();
+        let events = entries
+            .iter()
+            .map(|entry| entry.flight_recorder_event(trace_id, "job-log"))
+            .collect::<Vec<_>>();
+        assert_eq!(events[0].event_type.to_string(), "distill.training_step");
+        assert_eq!(
+            events[1].event_type.to_string(),
+            "distill.checkpoint_created"
+        );
+        for event in &events {
+            assert_eq!(event.job_id.as_deref(), Some("job-log"));
+            event.validate().expect("training log event validates");
+        }
+    }
 }
@@ -13,11 +13,18 @@
 //! implementation_notes; the personal-name heuristic here is regex +
 //! capitalization plus a small allowlist of common-noun bigrams to
 //! suppress obvious non-name matches.
+//!
+//! [`scan_with_engine`] adds the shared detection engine's
+//! distillation-surface findings (IBANs, key material, workspace deny terms
+//! and custom patterns) that these patterns do not already cover, and drops
+//! anything a workspace allow rule permits.
 
 use std::sync::OnceLock;
 
 use regex::Regex;
 
+use crate::redaction::{DetectionCategory, DetectionEngine, DetectionSeverity, DetectionSurface};
+
 /// Severity of a detection. The review pipeline maps Low/Medium ->
 /// quarantine, High -> reject.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -38,17 +45,28 @@ pub enum PiiKind {
     WindowsUserPath,
     MacAddress,
     Ipv4,
+    /// Checksum-valid IBAN (shared engine).
+    Iban,
+    /// Key material or credential the patterns below do not name (shared
+    /// engine: PEM blocks, JWTs, connection strings, vendor keys).
+    Secret,
+    /// Workspace deny term or custom pattern; severity comes from the rule.
+    WorkspaceRule,
 }
 
 impl PiiKind {
     pub fn severity(self) -> PiiSeverity {
         match self {
             // High: leaks of credentials or financial primary keys.
-            PiiKind::ApiKey | PiiKind::CreditCard => PiiSeverity::High,
-            // Medium: contact identifiers + machine fingerprints.
-            PiiKind::Email | PiiKind::Phone | PiiKind::WindowsUserPath | PiiKind::MacAddress => {
-                PiiSeverity::Medium
+            PiiKind::ApiKey | PiiKind::CreditCard | PiiKind::Iban | PiiKind::Secret => {
+                PiiSeverity::High
             }
+            // Medium: contact identifiers + machine fingerprints.
+            PiiKind::Email
+            | PiiKind::Phone
+            | PiiKind::WindowsUserPath
+            | PiiKind::MacAddress
+            | PiiKind::WorkspaceRule => PiiSeverity::Medium,
             // Low: IPv4 addresses (may be public infra).
             PiiKind::Ipv4 => PiiSeverity::Low,
         }
@@ -63,6 +81,9 @@ impl PiiKind {
             PiiKind::WindowsUserPath => "windows_user_path",
             PiiKind::MacAddress => "mac_address",
             PiiKind::Ipv4 => "ipv4",
+            PiiKind::Iban => "iban",
+            PiiKind::Secret => "secret",
+            PiiKind::WorkspaceRule => "workspace_rule",
         }
     }
 }
@@ -185,8 +206,62 @@ fn luhn_ok(digits: &str) -> bool {
     sum % 10 == 0
 }
 
-/// Scan `text` and return all PII detections in source order.
+/// Scan `text` with the built-in engine and return all PII detections in
+/// source order.
 pub fn scan(text: &str) -> Vec<PiiDetection> {
+    scan_with_engine(text, DetectionEngine::shared())
+}
+
+/// Scan `text` with the patterns below plus `engine`'s distillation-surface
+/// findings, honouring the engine's workspace allow rules. One leak is
+/// counted once: an engine finding replaces the pattern detections it fully
+/// covers (a validated IBAN beats the phone-shaped digits inside it) and is
+/// dropped when it only partially overlaps one.
+pub fn scan_with_engine(text: &str, engine: &DetectionEngine) -> Vec<PiiDetection> {
+    let mut out: Vec<PiiDetection> = scan_patterns(text)
+        .into_iter()
+        .filter(|d| !engine.is_allowed(d.kind.label(), &d.matched))
+        .collect();
+
+    let report = engine.scan(text, DetectionSurface::Distillation);
+    for finding in report.findings {
+        let overlaps = |d: &PiiDetection| d.start < finding.byte_end && finding.byte_start < d.end;
+        let covers = |d: &PiiDetection| finding.byte_start <= d.start && d.end <= finding.byte_end;
+        if out.iter().any(|d| overlaps(d) && !covers(d)) {
+            continue;
+        }
+        out.retain(|d| !overlaps(d));
+        let kind = match (finding.detector_id.as_str(), finding.category) {
+            ("payment_card", _) => PiiKind::CreditCard,
+            ("email", _) => PiiKind::Email,
+            ("iban", _) => PiiKind::Iban,
+            ("cloud_api_key" | "aws_access_key_id", _) => PiiKind::ApiKey,
+            (_, DetectionCategory::Secret) => PiiKind::Secret,
+            _ => PiiKind::WorkspaceRule,
+        };
+        let severity = if kind == PiiKind::WorkspaceRule {
+            match finding.severity {
+                DetectionSeverity::Low => PiiSeverity::Low,
+                DetectionSeverity::Medium => PiiSeverity::Medium,
+                DetectionSeverity::High => PiiSeverity::High,
+            }
+        } else {
+            kind.severity()
+        };
+        out.push(PiiDetection {
+            kind,
+            severity,
+            start: finding.byte_start,
+            end: finding.byte_end,
+            matched: text[finding.byte_start..finding.byte_end].to_string(),
+        });
+    }
+
+    out.sort_by_key(|d| (d.start, d.end));
+    out
+}
+
+fn scan_patterns(text: &str) -> Vec<PiiDetection> {
     let mut out = Vec::new();
 
     for m in email_regex().find_iter(text) {
@@ -345,6 +420,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn engine_adds_uncovered_findings_and_honours_workspace_rules() {
+        let hits = scan("wire to GB82 WEST 1234 5698 7654 32 or mail alice@example.com");
+        let kinds: Vec<_> = hits.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![PiiKind::Iban, PiiKind::Email], "{hits:?}");
+
+        let engine = DetectionEngine::from_workspace_settings_state(&serde_json::json!({
+            "settings": { "redaction": {
+                "allow": [{ "id": "support-inbox", "literal": "support@example.com" }],
+                "custom_patterns": [{
+                    "id": "ticket", "pattern": "\\bTCK-[0-9]{5}\\b", "severity": "low"
+                }]
+            }}
+        }))
+        .expect("rules compile");
+        let hits = scan_with_engine("ask support@example.com about TCK-12345", &engine);
+        assert_eq!(hits.len(), 1, "{hits:?}");
+        assert_eq!(hits[0].kind, PiiKind::WorkspaceRule);
+        assert_eq!(hits[0].severity, PiiSeverity::Low);
+    }
+
     #[test]
     fn severity_classification_is_stable() {
         assert_eq!(PiiKind::ApiKey.severity(), PiiSeverity::High);
@@ -352,5 +448,7 @@ mod tests {
         assert_eq!(PiiKind::Email.severity(), PiiSeverity::Medium);
         assert_eq!(PiiKind::Phone.severity(), PiiSeverity::Medium);
         assert_eq!(PiiKind::Ipv4.severity(), PiiSeverity::Low);
+        assert_eq!(PiiKind::Iban.severity(), PiiSeverity::High);
+        assert_eq!(PiiKind::WorkspaceRule.severity(), PiiSeverity::Medium);
     }
 }
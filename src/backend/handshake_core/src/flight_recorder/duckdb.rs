@@ -956,6 +956,8 @@ fn decode_raw_event(raw: RawEvent) -> Result<FlightRecorderEvent, RecorderError>
             "loom_ai_tag_rejected" => super::FlightRecorderEventType::LoomAiTagRejected,
             "loom_view_queried" => super::FlightRecorderEventType::LoomViewQueried,
             "loom_search_executed" => super::FlightRecorderEventType::LoomSearchExecuted,
+            "loom_folder_deleted" => super::FlightRecorderEventType::LoomFolderDeleted,
+            "loom_vault_exported" => super::FlightRecorderEventType::LoomVaultExported,
             "session_scheduler.enqueue" | "session_scheduler_enqueue" => {
                 super::FlightRecorderEventType::SessionSchedulerEnqueue
             }
@@ -994,6 +996,7 @@ fn decode_raw_event(raw: RawEvent) -> Result<FlightRecorderEvent, RecorderError>
                 super::FlightRecorderEventType::WorkspaceCrossSessionApproved
             }
             "distill.pii_detected" => super::FlightRecorderEventType::DistillPiiDetected,
+            "distill.training_step" => super::FlightRecorderEventType::DistillTrainingStep,
             "capability_action" => {
                 if payload_type == Some("terminal_command") {
                     super::FlightRecorderEventType::TerminalCommand
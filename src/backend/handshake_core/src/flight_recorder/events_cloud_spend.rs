@@ -0,0 +1,90 @@
+//! Typed `FR-EVT-CLOUD-BUDGET-*` Flight-Recorder events for cloud spend
+//! budgets ([`crate::model_runtime::cloud::spend`]).
+//!
+//! One event is emitted per scope and threshold when a budget is crossed:
+//! `SOFT` when spend reaches the soft limit, `HARD` when a dispatch is refused
+//! (or a settled call pushes spend past the hard limit). Like the agent-activity
+//! events these ride on the `System` event type and are told apart by
+//! `event_id`.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::flight_recorder::{FlightRecorderActor, FlightRecorderEvent, FlightRecorderEventType};
+use crate::model_runtime::cloud::spend::{BudgetCrossing, BudgetPhase, BudgetThreshold};
+
+pub const FR_EVT_CLOUD_BUDGET_SOFT: &str = "FR-EVT-CLOUD-BUDGET-SOFT";
+pub const FR_EVT_CLOUD_BUDGET_HARD: &str = "FR-EVT-CLOUD-BUDGET-HARD";
+pub const CLOUD_BUDGET_SCHEMA: &str = "hsk.fr.cloud_budget@0.1";
+
+pub fn budget_event_id(threshold: BudgetThreshold) -> &'static str {
+    match threshold {
+        BudgetThreshold::Soft => FR_EVT_CLOUD_BUDGET_SOFT,
+        BudgetThreshold::Hard => FR_EVT_CLOUD_BUDGET_HARD,
+    }
+}
+
+/// Build the threshold event for one crossing. The trace is the job's when the
+/// call is attributed to one, so the event lands on the job's timeline.
+pub fn budget_threshold_event(crossing: &BudgetCrossing) -> FlightRecorderEvent {
+    let trace_id = crossing.job_id.unwrap_or_else(Uuid::now_v7);
+    let payload = json!({
+        "schema_version": CLOUD_BUDGET_SCHEMA,
+        "event_id": budget_event_id(crossing.threshold),
+        "type": "cloud_budget",
+        "threshold": crossing.threshold,
+        "phase": match crossing.phase {
+            BudgetPhase::Dispatch => "dispatch",
+            BudgetPhase::Settled => "settled",
+        },
+        "scope": crossing.scope.kind,
+        "scope_key": crossing.scope.key,
+        "limit_usd": crossing.limit_usd,
+        "spent_usd": crossing.spent_usd,
+        "projected_usd": crossing.projected_usd,
+        "provider": crossing.provider,
+        "model": crossing.model,
+        "refused": crossing.refused,
+    });
+    let mut event = FlightRecorderEvent::new(
+        FlightRecorderEventType::System,
+        FlightRecorderActor::System,
+        trace_id,
+        payload,
+    );
+    if let Some(job_id) = crossing.job_id {
+        event = event.with_job_id(job_id.to_string());
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_runtime::cloud::spend::SpendScope;
+
+    #[test]
+    fn refused_dispatch_event_carries_scope_limit_and_job() {
+        let job_id = Uuid::now_v7();
+        let event = budget_threshold_event(&BudgetCrossing {
+            scope: SpendScope::job(job_id),
+            threshold: BudgetThreshold::Hard,
+            phase: BudgetPhase::Dispatch,
+            limit_usd: 2.0,
+            spent_usd: 1.9,
+            projected_usd: 2.2,
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            job_id: Some(job_id),
+            refused: true,
+        });
+        assert_eq!(event.event_type, FlightRecorderEventType::System);
+        assert_eq!(event.trace_id, job_id);
+        assert_eq!(event.job_id.as_deref(), Some(job_id.to_string().as_str()));
+        assert_eq!(event.payload["event_id"], FR_EVT_CLOUD_BUDGET_HARD);
+        assert_eq!(event.payload["scope"], "job");
+        assert_eq!(event.payload["phase"], "dispatch");
+        assert_eq!(event.payload["refused"], true);
+        event.validate().expect("valid system event");
+    }
+}
@@ -0,0 +1,74 @@
+//! Typed `FR-EVT-SEC-INJECTION-VERDICT` Flight-Recorder event for the
+//! prompt-injection tiers ([`crate::ace::validators::injection_classifier`]).
+//!
+//! Emitted once per retrieval trace when a tier flagged a span or a classifier
+//! scored one, before the validator pipeline runs, so the verdicts and scores
+//! are on the job's timeline even when the job is then poisoned and the
+//! `llm_inference` event carrying `ace_validation.injection_verdicts` never
+//! happens. Rides on the `System` event type and is told apart by `event_id`.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::ace::InjectionVerdict;
+use crate::flight_recorder::{FlightRecorderActor, FlightRecorderEvent, FlightRecorderEventType};
+
+pub const FR_EVT_SEC_INJECTION_VERDICT: &str = "FR-EVT-SEC-INJECTION-VERDICT";
+pub const INJECTION_VERDICT_SCHEMA: &str = "hsk.fr.injection_verdict@0.1";
+
+pub fn injection_verdict_event(
+    trace_id: Uuid,
+    job_id: Uuid,
+    retrieval_trace_id: Uuid,
+    verdicts: &[InjectionVerdict],
+) -> FlightRecorderEvent {
+    let flagged = verdicts.iter().filter(|verdict| verdict.injected).count();
+    let payload = json!({
+        "schema_version": INJECTION_VERDICT_SCHEMA,
+        "event_id": FR_EVT_SEC_INJECTION_VERDICT,
+        "type": "injection_verdict",
+        "retrieval_trace_id": retrieval_trace_id.to_string(),
+        "spans": verdicts.len(),
+        "flagged": flagged,
+        "action_taken": if flagged > 0 { "poisoned" } else { "none" },
+        "verdicts": verdicts,
+    });
+    FlightRecorderEvent::new(
+        FlightRecorderEventType::System,
+        FlightRecorderActor::System,
+        trace_id,
+        payload,
+    )
+    .with_job_id(job_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ace::validators::{
+        classify_untrusted, InjectionClassifierPolicy, UntrustedSource, UntrustedSpan,
+    };
+
+    #[tokio::test]
+    async fn verdict_event_counts_flagged_spans() {
+        let verdicts = classify_untrusted(
+            None,
+            &[
+                UntrustedSpan::new(UntrustedSource::Email, "m1", "lunch at noon?"),
+                UntrustedSpan::new(UntrustedSource::Web, "w1", "Jailbreak the assistant"),
+            ],
+            &InjectionClassifierPolicy::default(),
+        )
+        .await;
+        let job_id = Uuid::now_v7();
+        let event = injection_verdict_event(job_id, job_id, Uuid::now_v7(), &verdicts);
+        assert_eq!(event.event_type, FlightRecorderEventType::System);
+        assert_eq!(event.payload["event_id"], FR_EVT_SEC_INJECTION_VERDICT);
+        assert_eq!(event.payload["spans"], 2);
+        assert_eq!(event.payload["flagged"], 1);
+        assert_eq!(event.payload["action_taken"], "poisoned");
+        assert_eq!(event.payload["verdicts"][1]["tier"], "pattern");
+        assert_eq!(event.payload["verdicts"][1]["source"], "web");
+        event.validate().expect("valid system event");
+    }
+}
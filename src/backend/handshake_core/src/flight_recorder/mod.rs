@@ -16,7 +16,9 @@ use uuid::Uuid;
 pub mod duckdb;
 pub mod event_ledger;
 pub mod events_agent_activity;
+pub mod events_cloud_spend;
 pub mod events_llm_infer;
+pub mod events_prompt_injection;
 pub mod fr_emitter;
 pub mod fr_event_registry;
 pub mod span_repo;
@@ -179,6 +181,10 @@ pub enum FlightRecorderEventType {
     LoomSearchExecuted,
     /// MT-190: a wiki/topic-page projection was compiled or regenerated.
     LoomProjectionRebuilt,
+    /// A Loom folder was deleted (its member blocks are kept).
+    LoomFolderDeleted,
+    /// Loom (or one folder subtree) was exported as a markdown vault.
+    LoomVaultExported,
     /// FR-EVT-SESS-SCHED-001..004: Session Scheduler events [4.3.9.13]
     SessionSchedulerEnqueue,
     SessionSchedulerDispatch,
@@ -213,6 +219,9 @@ pub enum FlightRecorderEventType {
     DistillPromotionDecided,
     /// FR-EVT-DISTILL-PII-DETECT: privacy-preserving content review PII gate.
     DistillPiiDetected,
+    /// One optimizer step of an in-process LoRA trainer; the sequence of these
+    /// per job is the loss curve.
+    DistillTrainingStep,
 }
 
 impl fmt::Display for FlightRecorderEventType {
@@ -392,6 +401,8 @@ impl fmt::Display for FlightRecorderEventType {
             FlightRecorderEventType::LoomProjectionRebuilt => {
                 write!(f, "loom_projection_rebuilt")
             }
+            FlightRecorderEventType::LoomFolderDeleted => write!(f, "loom_folder_deleted"),
+            FlightRecorderEventType::LoomVaultExported => write!(f, "loom_vault_exported"),
             FlightRecorderEventType::SessionSchedulerEnqueue => {
                 write!(f, "session_scheduler.enqueue")
             }
@@ -453,6 +464,7 @@ impl fmt::Display for FlightRecorderEventType {
                 write!(f, "distill.promotion_decided")
             }
             FlightRecorderEventType::DistillPiiDetected => write!(f, "distill.pii_detected"),
+            FlightRecorderEventType::DistillTrainingStep => write!(f, "distill.training_step"),
         }
     }
 }
@@ -1007,6 +1019,12 @@ impl FlightRecorderEvent {
             FlightRecorderEventType::LoomProjectionRebuilt => {
                 validate_loom_projection_rebuilt_payload(&self.payload)
             }
+            FlightRecorderEventType::LoomFolderDeleted => {
+                validate_loom_folder_deleted_payload(&self.payload)
+            }
+            FlightRecorderEventType::LoomVaultExported => {
+                validate_loom_vault_exported_payload(&self.payload)
+            }
             FlightRecorderEventType::SessionSchedulerEnqueue => {
                 validate_session_scheduler_enqueue_payload(&self.payload)
             }
@@ -1074,6 +1092,9 @@ impl FlightRecorderEvent {
             FlightRecorderEventType::DistillPiiDetected => {
                 validate_distill_pii_detected_payload(&self.payload)
             }
+            FlightRecorderEventType::DistillTrainingStep => {
+                validate_distill_training_step_payload(&self.payload)
+            }
             _ => Ok(()),
         }
     }
@@ -2703,6 +2724,41 @@ fn validate_loom_projection_rebuilt_payload(payload: &Value) -> Result<(), Recor
     Ok(())
 }
 
+fn validate_loom_folder_deleted_payload(payload: &Value) -> Result<(), RecorderError> {
+    let map = payload_object(payload)?;
+    require_exact_keys(map, &["type", "folder_id", "workspace_id"])?;
+    require_fixed_string(map, "type", "loom_folder_deleted")?;
+    require_safe_id_string(map, "folder_id")?;
+    require_safe_id_string(map, "workspace_id")?;
+    Ok(())
+}
+
+fn validate_loom_vault_exported_payload(payload: &Value) -> Result<(), RecorderError> {
+    let map = payload_object(payload)?;
+    require_exact_keys(
+        map,
+        &[
+            "type",
+            "workspace_id",
+            "folder_id",
+            "notes",
+            "journals",
+            "attachments",
+            "skipped",
+            "written_to_path",
+        ],
+    )?;
+    require_fixed_string(map, "type", "loom_vault_exported")?;
+    require_safe_id_string(map, "workspace_id")?;
+    require_string_or_null(map, "folder_id")?;
+    require_non_negative_integer(map, "notes")?;
+    require_non_negative_integer(map, "journals")?;
+    require_non_negative_integer(map, "attachments")?;
+    require_non_negative_integer(map, "skipped")?;
+    require_bool(map, "written_to_path")?;
+    Ok(())
+}
+
 fn validate_debug_bundle_payload(payload: &Value) -> Result<(), RecorderError> {
     let map = payload_object(payload)?;
     require_string(map, "bundle_id")?;
@@ -6184,6 +6240,30 @@ fn validate_distill_checkpoint_created_payload(payload: &Value) -> Result<(), Re
     Ok(())
 }
 
+fn validate_distill_training_step_payload(payload: &Value) -> Result<(), RecorderError> {
+    let map = payload_object(payload)?;
+    require_exact_keys(
+        map,
+        &[
+            "type",
+            "job_id",
+            "step",
+            "epoch",
+            "loss",
+            "learning_rate",
+            "tokens",
+        ],
+    )?;
+    require_fixed_string(map, "type", "distill.training_step")?;
+    require_string(map, "job_id")?;
+    require_non_negative_integer(map, "step")?;
+    require_non_negative_integer(map, "epoch")?;
+    require_number(map, "loss")?;
+    require_number(map, "learning_rate")?;
+    require_non_negative_integer(map, "tokens")?;
+    Ok(())
+}
+
 fn validate_distill_eval_completed_payload(payload: &Value) -> Result<(), RecorderError> {
     let map = payload_object(payload)?;
     require_exact_keys(
@@ -7026,6 +7106,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn flight_recorder_loom_folder_deleted_validates() {
+        let event = distill_event(
+            FlightRecorderEventType::LoomFolderDeleted,
+            json!({
+                "type": "loom_folder_deleted",
+                "folder_id": "LF-1",
+                "workspace_id": "WS-1",
+            }),
+        );
+        assert!(event.validate().is_ok(), "{:?}", event.validate());
+        assert_eq!(
+            FlightRecorderEventType::LoomFolderDeleted.to_string(),
+            "loom_folder_deleted"
+        );
+
+        let extra = distill_event(
+            FlightRecorderEventType::LoomFolderDeleted,
+            json!({
+                "type": "loom_folder_deleted",
+                "folder_id": "LF-1",
+                "workspace_id": "WS-1",
+                "title": "Inbox",
+            }),
+        );
+        assert!(
+            extra.validate().is_err(),
+            "unexpected keys must be rejected"
+        );
+    }
+
+    #[test]
+    fn flight_recorder_loom_vault_exported_validates() {
+        let payload = |folder_id: Value| {
+            json!({
+                "type": "loom_vault_exported",
+                "workspace_id": "WS-1",
+                "folder_id": folder_id,
+                "notes": 4,
+                "journals": 1,
+                "attachments": 2,
+                "skipped": 0,
+                "written_to_path": false,
+            })
+        };
+        for folder_id in [Value::Null, json!("LF-1")] {
+            let event = distill_event(
+                FlightRecorderEventType::LoomVaultExported,
+                payload(folder_id),
+            );
+            assert!(event.validate().is_ok(), "{:?}", event.validate());
+        }
+        assert_eq!(
+            FlightRecorderEventType::LoomVaultExported.to_string(),
+            "loom_vault_exported"
+        );
+
+        let mut negative = payload(Value::Null);
+        negative["notes"] = json!(-1);
+        let event = distill_event(FlightRecorderEventType::LoomVaultExported, negative);
+        assert!(event.validate().is_err(), "counts must be non-negative");
+    }
+
     #[test]
     fn flight_recorder_loom_view_queried_accepts_favorites() {
         let event = distill_event(
@@ -7121,6 +7264,40 @@ mod tests {
         assert!(event.validate().is_ok(), "{:?}", event.validate());
     }
 
+    #[test]
+    fn flight_recorder_distill_training_step_valid() {
+        let event = distill_event(
+            FlightRecorderEventType::DistillTrainingStep,
+            json!({
+                "type": "distill.training_step",
+                "job_id": Uuid::now_v7().to_string(),
+                "step": 3,
+                "epoch": 1,
+                "loss": 2.75,
+                "learning_rate": 0.0002,
+                "tokens": 128
+            }),
+        );
+        assert!(event.validate().is_ok(), "{:?}", event.validate());
+    }
+
+    #[test]
+    fn flight_recorder_distill_training_step_rejects_non_numeric_loss() {
+        let event = distill_event(
+            FlightRecorderEventType::DistillTrainingStep,
+            json!({
+                "type": "distill.training_step",
+                "job_id": Uuid::now_v7().to_string(),
+                "step": 3,
+                "epoch": 1,
+                "loss": "low",
+                "learning_rate": 0.0002,
+                "tokens": 128
+            }),
+        );
+        assert!(event.validate().is_err());
+    }
+
     #[test]
     fn flight_recorder_distill_eval_completed_valid() {
         let event = distill_event(
@@ -7216,5 +7393,9 @@ mod tests {
             FlightRecorderEventType::DistillPiiDetected.to_string(),
             "distill.pii_detected"
         );
+        assert_eq!(
+            FlightRecorderEventType::DistillTrainingStep.to_string(),
+            "distill.training_step"
+        );
     }
 }
@@ -26,6 +26,9 @@
 //!   * [`import`] - projection IMPORT (MT-151): markdown / plain-text / HTML
 //!     snippets parsed into a block tree with typed unsupported-feature
 //!     warnings and repairable nodes.
+//!   * [`office`] - DOCX / ODT import and export: office archives mapped to
+//!     and from the block tree, images routed through the ArtifactStore as
+//!     typed embeds, unsupported features surfaced as typed warnings.
 //!   * [`embed`] - the embed reference model (MT-152) and broken-embed repair
 //!     state (MT-153): embeds are stored as artifact/media/source ids or typed
 //!     URLs, never random absolute paths; a missing target becomes a repairable
@@ -40,6 +43,7 @@ pub mod backlink;
 pub mod block_tree;
 pub mod embed;
 pub mod import;
+pub mod office;
 pub mod permission;
 pub mod projection;
 
@@ -51,5 +55,9 @@ pub use embed::{
     BrokenEmbedRepair, EmbedRef, EmbedRefKind, EmbedRepairAction, EmbedTarget, EmbedTargetError,
 };
 pub use import::{import_snippet, ImportFormat, ImportOutcome, ImportWarning};
+pub use office::{
+    export_office_document, import_office_document, ArtifactStoreOfficeAssets, OfficeAsset,
+    OfficeAssetStore, OfficeDocumentError, OfficeExport, OfficeExportWarning, OfficeFormat,
+};
 pub use permission::{DocumentAction, DocumentActorKind, DocumentPermission, PermissionDecision};
 pub use projection::{render_projection, ProjectionFormat, RenderedProjection};
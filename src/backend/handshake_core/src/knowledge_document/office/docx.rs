@@ -0,0 +1,1137 @@
+//! DOCX (WordprocessingML) import and export.
+
+use super::*;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const R_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const REL_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const REL_TYPE_BASE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const BULLET_NUM_ID: u32 = 1;
+const ORDERED_NUM_ID: u32 = 2;
+
+/// Paragraph role resolved from the style id and its display name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StyleRole {
+    Heading(i64),
+    Code,
+    Quote,
+    Body,
+}
+
+struct Relationship {
+    target: String,
+    external: bool,
+}
+
+struct Context {
+    styles: HashMap<String, StyleRole>,
+    /// numId -> ilvl -> ordered?
+    numbering: HashMap<String, HashMap<u32, bool>>,
+    rels: HashMap<String, Relationship>,
+    comments: HashMap<String, Comment>,
+}
+
+pub(super) fn read(
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Vec<OfficeBlock>, OfficeDocumentError> {
+    let document_xml = archive.require_text(DOCUMENT_PART)?;
+    let ctx = Context {
+        styles: read_styles(archive)?,
+        numbering: read_numbering(archive)?,
+        rels: read_rels(archive)?,
+        comments: read_comments(archive)?,
+    };
+    if archive.read_bytes("word/footnotes.xml")?.is_some()
+        && document_xml.contains("footnoteReference")
+    {
+        warnings.push(
+            "footnote_not_supported",
+            "Footnotes are not imported; the reference marks were dropped.",
+        );
+    }
+    if document_xml.contains("endnoteReference") {
+        warnings.push(
+            "endnote_not_supported",
+            "Endnotes are not imported; the reference marks were dropped.",
+        );
+    }
+    let doc = parse_xml(DOCUMENT_PART, &document_xml)?;
+    let body = doc
+        .root_element()
+        .children()
+        .find(|n| is(*n, "body"))
+        .ok_or_else(|| OfficeDocumentError::MissingPart("w:body".to_string()))?;
+    let mut blocks = Vec::new();
+    read_container(body, &ctx, archive, assets, warnings, &mut blocks)?;
+    Ok(blocks)
+}
+
+fn read_container(
+    node: Node<'_, '_>,
+    ctx: &Context,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    for element in node.children().filter(|n| n.is_element()) {
+        match element.tag_name().name() {
+            "p" => read_paragraph(element, ctx, archive, assets, warnings, blocks)?,
+            "tbl" => blocks.push(read_table(element, ctx, warnings)),
+            // Content controls and tracked insertions wrap ordinary blocks.
+            "sdt" => {
+                if let Some(content) = child(element, "sdtContent") {
+                    read_container(content, ctx, archive, assets, warnings, blocks)?;
+                }
+            }
+            "ins" | "customXml" => read_container(element, ctx, archive, assets, warnings, blocks)?,
+            "del" => warnings.push(
+                "tracked_changes_flattened",
+                "Tracked changes were flattened: insertions kept, deletions dropped.",
+            ),
+            "sectPr" | "bookmarkStart" | "bookmarkEnd" | "proofErr" => {}
+            other => warnings.push(
+                "unsupported_body_element",
+                &format!("Body element `w:{other}` is not supported and was skipped."),
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn paragraph_style(p: Node<'_, '_>, ctx: &Context) -> StyleRole {
+    let Some(ppr) = child(p, "pPr") else {
+        return StyleRole::Body;
+    };
+    if let Some(style) = child(ppr, "pStyle").and_then(|s| attr(s, "val")) {
+        if let Some(role) = ctx.styles.get(style) {
+            return *role;
+        }
+        let role = role_from_style_name(style);
+        if role != StyleRole::Body {
+            return role;
+        }
+    }
+    if let Some(level) = child(ppr, "outlineLvl")
+        .and_then(|o| attr(o, "val"))
+        .and_then(|v| v.parse::<i64>().ok())
+    {
+        if level < 9 {
+            return StyleRole::Heading(level + 1);
+        }
+    }
+    StyleRole::Body
+}
+
+fn role_from_style_name(name: &str) -> StyleRole {
+    let lower = name.to_ascii_lowercase().replace(' ', "");
+    if lower == "title" {
+        return StyleRole::Heading(1);
+    }
+    if let Some(rest) = lower.strip_prefix("heading") {
+        if let Ok(level) = rest.parse::<i64>() {
+            return StyleRole::Heading(level.clamp(1, 6));
+        }
+    }
+    if lower.contains("code") || lower.contains("sourcetext") || lower == "htmlpreformatted" {
+        return StyleRole::Code;
+    }
+    if lower.contains("quote") {
+        return StyleRole::Quote;
+    }
+    StyleRole::Body
+}
+
+fn list_info(p: Node<'_, '_>, ctx: &Context) -> Option<(bool, u32)> {
+    let num_pr = child(p, "pPr").and_then(|ppr| child(ppr, "numPr"))?;
+    let num_id = child(num_pr, "numId").and_then(|n| attr(n, "val"))?;
+    // numId 0 explicitly removes numbering.
+    if num_id == "0" {
+        return None;
+    }
+    let level = child(num_pr, "ilvl")
+        .and_then(|n| attr(n, "val"))
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+    let ordered = ctx
+        .numbering
+        .get(num_id)
+        .and_then(|levels| levels.get(&level))
+        .copied()
+        .unwrap_or(false);
+    Some((ordered, level))
+}
+
+fn read_paragraph(
+    p: Node<'_, '_>,
+    ctx: &Context,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    let mut runs = Vec::new();
+    let mut comments = Vec::new();
+    let mut images = Vec::new();
+    collect_inline(
+        p,
+        &Marks::default(),
+        ctx,
+        warnings,
+        &mut runs,
+        &mut comments,
+        &mut images,
+    );
+    let has_text = runs.iter().any(|r| !r.text.is_empty());
+    let role = paragraph_style(p, ctx);
+    if has_text || images.is_empty() {
+        let block = if let Some((ordered, level)) = list_info(p, ctx) {
+            OfficeBlock::ListItem {
+                ordered,
+                level,
+                runs,
+                comments,
+            }
+        } else {
+            match role {
+                StyleRole::Heading(level) => OfficeBlock::Heading {
+                    level,
+                    runs,
+                    comments,
+                },
+                StyleRole::Code => OfficeBlock::Code {
+                    text: runs_plain_text(&runs),
+                },
+                StyleRole::Quote => OfficeBlock::Quote { runs, comments },
+                StyleRole::Body => OfficeBlock::Paragraph { runs, comments },
+            }
+        };
+        blocks.push(block);
+    }
+    for (rel_id, alt) in images {
+        let Some(rel) = ctx.rels.get(&rel_id) else {
+            warnings.push(
+                "image_part_missing",
+                "An image referenced a relationship that does not exist; it was skipped.",
+            );
+            continue;
+        };
+        if rel.external {
+            warnings.push(
+                "external_image_not_imported",
+                "Linked (external) images are not fetched during import; they were skipped.",
+            );
+            continue;
+        }
+        let part = resolve_part_path("word", &rel.target);
+        if let Some(block) = import_image(archive, &part, &alt, assets, warnings)? {
+            blocks.push(block);
+        }
+    }
+    Ok(())
+}
+
+fn run_marks(r: Node<'_, '_>, inherited: &Marks) -> Marks {
+    let mut marks = inherited.clone();
+    let Some(rpr) = child(r, "rPr") else {
+        return marks;
+    };
+    let on = |name: &str| {
+        child(rpr, name)
+            .map(|n| !matches!(attr(n, "val"), Some("0" | "false" | "none")))
+            .unwrap_or(false)
+    };
+    marks.bold |= on("b");
+    marks.italic |= on("i");
+    marks.underline |= on("u");
+    let style_is_code = child(rpr, "rStyle")
+        .and_then(|s| attr(s, "val"))
+        .map(|s| role_from_style_name(s) == StyleRole::Code)
+        .unwrap_or(false);
+    let font_is_mono = child(rpr, "rFonts")
+        .and_then(|f| attr(f, "ascii"))
+        .map(is_monospace_font)
+        .unwrap_or(false);
+    marks.code |= style_is_code || font_is_mono;
+    marks
+}
+
+fn collect_inline(
+    node: Node<'_, '_>,
+    inherited: &Marks,
+    ctx: &Context,
+    warnings: &mut WarningSink,
+    runs: &mut Vec<Run>,
+    comments: &mut Vec<Comment>,
+    images: &mut Vec<(String, String)>,
+) {
+    for child_node in node.children().filter(|n| n.is_element()) {
+        match child_node.tag_name().name() {
+            "r" => {
+                let marks = run_marks(child_node, inherited);
+                for part in child_node.children().filter(|n| n.is_element()) {
+                    match part.tag_name().name() {
+                        "t" => runs.push(Run {
+                            text: part.text().unwrap_or("").to_string(),
+                            marks: marks.clone(),
+                        }),
+                        "tab" => runs.push(Run {
+                            text: "\t".to_string(),
+                            marks: marks.clone(),
+                        }),
+                        "br" | "cr" => runs.push(Run {
+                            text: "\n".to_string(),
+                            marks: marks.clone(),
+                        }),
+                        "drawing" => {
+                            let alt = part
+                                .descendants()
+                                .find(|n| is(*n, "docPr"))
+                                .and_then(|n| attr(n, "descr").or_else(|| attr(n, "title")))
+                                .unwrap_or("")
+                                .to_string();
+                            match part
+                                .descendants()
+                                .find(|n| is(*n, "blip"))
+                                .and_then(|b| attr(b, "embed").or_else(|| attr(b, "link")))
+                            {
+                                Some(rel_id) => images.push((rel_id.to_string(), alt)),
+                                None => warnings.push(
+                                    "drawing_shape_not_supported",
+                                    "Drawing shapes and text boxes are not imported.",
+                                ),
+                            }
+                        }
+                        "pict" | "object" => warnings.push(
+                            "drawing_shape_not_supported",
+                            "Drawing shapes and text boxes are not imported.",
+                        ),
+                        "commentReference" => {
+                            if let Some(comment) =
+                                attr(part, "id").and_then(|id| ctx.comments.get(id))
+                            {
+                                comments.push(comment.clone());
+                            }
+                        }
+                        "delText" => {}
+                        _ => {}
+                    }
+                }
+            }
+            "hyperlink" => {
+                let mut marks = inherited.clone();
+                let href = attr(child_node, "id")
+                    .and_then(|id| ctx.rels.get(id))
+                    .filter(|rel| rel.external)
+                    .and_then(|rel| safe_link(&rel.target));
+                if href.is_some() {
+                    marks.link = href;
+                }
+                collect_inline(child_node, &marks, ctx, warnings, runs, comments, images);
+            }
+            "ins" | "smartTag" | "customXml" | "fldSimple" | "sdt" | "sdtContent" => {
+                if child_node.tag_name().name() == "ins" {
+                    warnings.push(
+                        "tracked_changes_flattened",
+                        "Tracked changes were flattened: insertions kept, deletions dropped.",
+                    );
+                }
+                collect_inline(child_node, inherited, ctx, warnings, runs, comments, images);
+            }
+            "del" => warnings.push(
+                "tracked_changes_flattened",
+                "Tracked changes were flattened: insertions kept, deletions dropped.",
+            ),
+            "oMath" | "oMathPara" => warnings.push(
+                "equation_not_supported",
+                "Equations are not imported; they were dropped from the paragraph.",
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn read_table(tbl: Node<'_, '_>, ctx: &Context, warnings: &mut WarningSink) -> OfficeBlock {
+    let mut rows = Vec::new();
+    let mut comments = Vec::new();
+    for tr in tbl.children().filter(|n| is(*n, "tr")) {
+        let header = child(tr, "trPr")
+            .and_then(|pr| child(pr, "tblHeader"))
+            .is_some();
+        let mut cells = Vec::new();
+        for tc in tr.children().filter(|n| is(*n, "tc")) {
+            let tc_pr = child(tc, "tcPr");
+            let colspan = tc_pr
+                .and_then(|pr| child(pr, "gridSpan"))
+                .and_then(|g| attr(g, "val"))
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(1);
+            if tc_pr.and_then(|pr| child(pr, "vMerge")).is_some() {
+                warnings.push(
+                    "vertical_merge_flattened",
+                    "Vertically merged table cells were imported as separate cells.",
+                );
+            }
+            let mut paragraphs = Vec::new();
+            for part in tc.children().filter(|n| n.is_element()) {
+                match part.tag_name().name() {
+                    "p" => {
+                        let mut runs = Vec::new();
+                        let mut images = Vec::new();
+                        collect_inline(
+                            part,
+                            &Marks::default(),
+                            ctx,
+                            warnings,
+                            &mut runs,
+                            &mut comments,
+                            &mut images,
+                        );
+                        if !images.is_empty() {
+                            warnings.push(
+                                "image_in_table_not_supported",
+                                "Images inside table cells are not imported.",
+                            );
+                        }
+                        paragraphs.push(runs);
+                    }
+                    "tbl" => {
+                        warnings.push(
+                            "nested_table_flattened",
+                            "Nested tables were flattened into their cell text.",
+                        );
+                        let text: String = part
+                            .descendants()
+                            .filter(|n| is(*n, "t"))
+                            .filter_map(|n| n.text())
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        paragraphs.push(plain_runs(&text));
+                    }
+                    _ => {}
+                }
+            }
+            cells.push(TableCell {
+                colspan,
+                paragraphs,
+            });
+        }
+        rows.push(TableRow { header, cells });
+    }
+    OfficeBlock::Table { rows, comments }
+}
+
+fn read_styles(
+    archive: &mut OfficeArchive<'_>,
+) -> Result<HashMap<String, StyleRole>, OfficeDocumentError> {
+    let mut styles = HashMap::new();
+    let Some(xml) = archive.read_text("word/styles.xml")? else {
+        return Ok(styles);
+    };
+    let doc = parse_xml("word/styles.xml", &xml)?;
+    for style in doc.root_element().children().filter(|n| is(*n, "style")) {
+        let Some(id) = attr(style, "styleId") else {
+            continue;
+        };
+        let name = child(style, "name")
+            .and_then(|n| attr(n, "val"))
+            .unwrap_or(id);
+        let mut role = role_from_style_name(name);
+        if role == StyleRole::Body {
+            role = role_from_style_name(id);
+        }
+        if role == StyleRole::Body {
+            if let Some(level) = child(style, "pPr")
+                .and_then(|p| child(p, "outlineLvl"))
+                .and_then(|o| attr(o, "val"))
+                .and_then(|v| v.parse::<i64>().ok())
+            {
+                if level < 9 {
+                    role = StyleRole::Heading((level + 1).clamp(1, 6));
+                }
+            }
+        }
+        styles.insert(id.to_string(), role);
+    }
+    Ok(styles)
+}
+
+fn read_numbering(
+    archive: &mut OfficeArchive<'_>,
+) -> Result<HashMap<String, HashMap<u32, bool>>, OfficeDocumentError> {
+    let mut out = HashMap::new();
+    let Some(xml) = archive.read_text("word/numbering.xml")? else {
+        return Ok(out);
+    };
+    let doc = parse_xml("word/numbering.xml", &xml)?;
+    let root = doc.root_element();
+    let mut abstract_levels: HashMap<String, HashMap<u32, bool>> = HashMap::new();
+    for abstract_num in root.children().filter(|n| is(*n, "abstractNum")) {
+        let Some(id) = attr(abstract_num, "abstractNumId") else {
+            continue;
+        };
+        let mut levels = HashMap::new();
+        for lvl in abstract_num.children().filter(|n| is(*n, "lvl")) {
+            let Some(ilvl) = attr(lvl, "ilvl").and_then(|v| v.parse::<u32>().ok()) else {
+                continue;
+            };
+            let fmt = child(lvl, "numFmt")
+                .and_then(|f| attr(f, "val"))
+                .unwrap_or("bullet");
+            levels.insert(ilvl, !matches!(fmt, "bullet" | "none"));
+        }
+        abstract_levels.insert(id.to_string(), levels);
+    }
+    for num in root.children().filter(|n| is(*n, "num")) {
+        let (Some(num_id), Some(abstract_id)) = (
+            attr(num, "numId"),
+            child(num, "abstractNumId").and_then(|a| attr(a, "val")),
+        ) else {
+            continue;
+        };
+        if let Some(levels) = abstract_levels.get(abstract_id) {
+            out.insert(num_id.to_string(), levels.clone());
+        }
+    }
+    Ok(out)
+}
+
+fn read_rels(
+    archive: &mut OfficeArchive<'_>,
+) -> Result<HashMap<String, Relationship>, OfficeDocumentError> {
+    let mut out = HashMap::new();
+    let part = "word/_rels/document.xml.rels";
+    let Some(xml) = archive.read_text(part)? else {
+        return Ok(out);
+    };
+    let doc = parse_xml(part, &xml)?;
+    for rel in doc
+        .root_element()
+        .children()
+        .filter(|n| is(*n, "Relationship"))
+    {
+        let (Some(id), Some(target)) = (attr(rel, "Id"), attr(rel, "Target")) else {
+            continue;
+        };
+        out.insert(
+            id.to_string(),
+            Relationship {
+                target: target.to_string(),
+                external: attr(rel, "TargetMode") == Some("External"),
+            },
+        );
+    }
+    Ok(out)
+}
+
+fn read_comments(
+    archive: &mut OfficeArchive<'_>,
+) -> Result<HashMap<String, Comment>, OfficeDocumentError> {
+    let mut out = HashMap::new();
+    let part = "word/comments.xml";
+    let Some(xml) = archive.read_text(part)? else {
+        return Ok(out);
+    };
+    let doc = parse_xml(part, &xml)?;
+    for comment in doc.root_element().children().filter(|n| is(*n, "comment")) {
+        let Some(id) = attr(comment, "id") else {
+            continue;
+        };
+        let text = comment
+            .children()
+            .filter(|n| is(*n, "p"))
+            .map(|p| {
+                p.descendants()
+                    .filter(|n| is(*n, "t"))
+                    .filter_map(|n| n.text())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        out.insert(
+            id.to_string(),
+            Comment {
+                author: attr(comment, "author").unwrap_or("").to_string(),
+                text,
+            },
+        );
+    }
+    Ok(out)
+}
+
+/// Resolve a relationship target relative to the part's folder.
+fn resolve_part_path(base: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+    parts.join("/")
+}
+
+// --- export -----------------------------------------------------------
+
+struct Writer<'w> {
+    body: String,
+    comments: Vec<(u32, Comment)>,
+    hyperlinks: BTreeMap<String, String>,
+    images: Vec<(String, &'w ExportImage)>,
+    next_drawing_id: u32,
+}
+
+impl<'w> Writer<'w> {
+    fn hyperlink_rel(&mut self, href: &str) -> String {
+        let next = format!("rIdLink{}", self.hyperlinks.len() + 1);
+        self.hyperlinks
+            .entry(href.to_string())
+            .or_insert(next)
+            .clone()
+    }
+
+    fn runs(&mut self, runs: &[Run]) {
+        for run in runs {
+            if run.text.is_empty() {
+                continue;
+            }
+            let link_rel = run
+                .marks
+                .link
+                .as_deref()
+                .map(|href| self.hyperlink_rel(href));
+            if let Some(rel) = &link_rel {
+                self.body.push_str(&format!("<w:hyperlink r:id=\"{rel}\">"));
+            }
+            self.body.push_str("<w:r>");
+            let m = &run.marks;
+            if m.bold || m.italic || m.underline || m.code || m.link.is_some() {
+                self.body.push_str("<w:rPr>");
+                if m.code {
+                    self.body.push_str(
+                        "<w:rFonts w:ascii=\"Consolas\" w:hAnsi=\"Consolas\" w:cs=\"Consolas\"/>",
+                    );
+                }
+                if m.bold {
+                    self.body.push_str("<w:b/>");
+                }
+                if m.italic {
+                    self.body.push_str("<w:i/>");
+                }
+                if m.underline || m.link.is_some() {
+                    self.body.push_str("<w:u w:val=\"single\"/>");
+                }
+                self.body.push_str("</w:rPr>");
+            }
+            self.text(&run.text);
+            self.body.push_str("</w:r>");
+            if link_rel.is_some() {
+                self.body.push_str("</w:hyperlink>");
+            }
+        }
+    }
+
+    /// Text content with tabs and newlines as their WordprocessingML
+    /// elements.
+    fn text(&mut self, text: &str) {
+        let mut first = true;
+        for line in text.split('\n') {
+            if !first {
+                self.body.push_str("<w:br/>");
+            }
+            first = false;
+            let mut first_segment = true;
+            for segment in line.split('\t') {
+                if !first_segment {
+                    self.body.push_str("<w:tab/>");
+                }
+                first_segment = false;
+                if !segment.is_empty() {
+                    self.body.push_str(&format!(
+                        "<w:t xml:space=\"preserve\">{}</w:t>",
+                        xml_escape(segment)
+                    ));
+                }
+            }
+        }
+    }
+
+    fn paragraph(
+        &mut self,
+        style: Option<&str>,
+        num: Option<(u32, u32)>,
+        runs: &[Run],
+        comments: &[Comment],
+    ) {
+        self.body.push_str("<w:p>");
+        if style.is_some() || num.is_some() {
+            self.body.push_str("<w:pPr>");
+            if let Some(style) = style {
+                self.body
+                    .push_str(&format!("<w:pStyle w:val=\"{style}\"/>"));
+            }
+            if let Some((num_id, level)) = num {
+                self.body.push_str(&format!(
+                    "<w:numPr><w:ilvl w:val=\"{level}\"/><w:numId w:val=\"{num_id}\"/></w:numPr>"
+                ));
+            }
+            self.body.push_str("</w:pPr>");
+        }
+        let ids: Vec<u32> = comments
+            .iter()
+            .map(|comment| {
+                let id = self.comments.len() as u32;
+                self.comments.push((id, comment.clone()));
+                id
+            })
+            .collect();
+        for id in &ids {
+            self.body
+                .push_str(&format!("<w:commentRangeStart w:id=\"{id}\"/>"));
+        }
+        self.runs(runs);
+        for id in &ids {
+            self.body.push_str(&format!(
+                "<w:commentRangeEnd w:id=\"{id}\"/><w:r><w:commentReference w:id=\"{id}\"/></w:r>"
+            ));
+        }
+        self.body.push_str("</w:p>");
+    }
+
+    fn image(&mut self, image: &'w ExportImage) {
+        let rel = format!("rIdImage{}", self.images.len() + 1);
+        self.images.push((rel.clone(), image));
+        self.next_drawing_id += 1;
+        let id = self.next_drawing_id;
+        let (cx, cy) = image_extent_emu(image);
+        let alt = xml_escape(&image.alt);
+        let name = xml_escape(&image.part_name);
+        self.body.push_str(&format!(
+            "<w:p><w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+<wp:extent cx=\"{cx}\" cy=\"{cy}\"/><wp:docPr id=\"{id}\" name=\"Picture {id}\" descr=\"{alt}\"/>\
+<a:graphic><a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+<pic:pic><pic:nvPicPr><pic:cNvPr id=\"{id}\" name=\"{name}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+<pic:blipFill><a:blip r:embed=\"{rel}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+<pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{cx}\" cy=\"{cy}\"/></a:xfrm>\
+<a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr></pic:pic></a:graphicData></a:graphic>\
+</wp:inline></w:drawing></w:r></w:p>"
+        ));
+    }
+
+    fn table(&mut self, rows: &[TableRow], comments: &[Comment]) {
+        let columns = rows
+            .iter()
+            .map(|r| r.cells.iter().map(|c| c.colspan).sum::<u32>())
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        self.body.push_str(
+                "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr><w:tblGrid>",
+            );
+        for _ in 0..columns {
+            self.body.push_str("<w:gridCol/>");
+        }
+        self.body.push_str("</w:tblGrid>");
+        let mut pending_comments = comments;
+        for row in rows {
+            self.body.push_str("<w:tr>");
+            if row.header {
+                self.body.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+            }
+            for cell in &row.cells {
+                self.body.push_str("<w:tc>");
+                if cell.colspan > 1 {
+                    self.body.push_str(&format!(
+                        "<w:tcPr><w:gridSpan w:val=\"{}\"/></w:tcPr>",
+                        cell.colspan
+                    ));
+                }
+                if cell.paragraphs.is_empty() {
+                    self.paragraph(None, None, &[], pending_comments);
+                    pending_comments = &[];
+                }
+                for paragraph in &cell.paragraphs {
+                    self.paragraph(None, None, paragraph, pending_comments);
+                    pending_comments = &[];
+                }
+                self.body.push_str("</w:tc>");
+            }
+            self.body.push_str("</w:tr>");
+        }
+        self.body.push_str("</w:tbl>");
+    }
+}
+
+pub(super) fn write(
+    title: &str,
+    tree: &BlockTree,
+    assets: &dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Vec<u8>, OfficeDocumentError> {
+    let blocks = export_blocks(tree, assets, warnings)?;
+    let mut writer = Writer {
+        body: String::new(),
+        comments: Vec::new(),
+        hyperlinks: BTreeMap::new(),
+        images: Vec::new(),
+        next_drawing_id: 0,
+    };
+    writer.paragraph(Some("Title"), None, &plain_runs(title), &[]);
+    for (block, comments) in &blocks {
+        match block {
+            ExportBlock::Paragraph(runs) => writer.paragraph(None, None, runs, comments),
+            ExportBlock::Heading(level, runs) => {
+                writer.paragraph(Some(&format!("Heading{level}")), None, runs, comments)
+            }
+            ExportBlock::Quote(runs) => writer.paragraph(Some("Quote"), None, runs, comments),
+            ExportBlock::Code(text) => {
+                writer.paragraph(Some("Code"), None, &plain_runs(text), comments)
+            }
+            ExportBlock::List(items) => {
+                let mut pending = comments.as_slice();
+                for item in items {
+                    let num_id = if item.ordered {
+                        ORDERED_NUM_ID
+                    } else {
+                        BULLET_NUM_ID
+                    };
+                    writer.paragraph(
+                        Some("ListParagraph"),
+                        Some((num_id, item.depth.min(8))),
+                        &item.runs,
+                        pending,
+                    );
+                    pending = &[];
+                }
+            }
+            ExportBlock::Table(rows) => writer.table(rows, comments),
+            ExportBlock::Image(image) => writer.image(image),
+        }
+    }
+
+    let document = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<w:document xmlns:w=\"{W_NS}\" xmlns:r=\"{R_NS}\" \
+xmlns:wp=\"http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing\" \
+xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+xmlns:pic=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+<w:body>{}<w:sectPr/></w:body></w:document>",
+        writer.body
+    );
+
+    let mut rels = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><Relationships xmlns=\"{REL_NS}\">\
+<Relationship Id=\"rIdStyles\" Type=\"{REL_TYPE_BASE}/styles\" Target=\"styles.xml\"/>\
+<Relationship Id=\"rIdNumbering\" Type=\"{REL_TYPE_BASE}/numbering\" Target=\"numbering.xml\"/>"
+        );
+    if !writer.comments.is_empty() {
+        rels.push_str(&format!(
+                "<Relationship Id=\"rIdComments\" Type=\"{REL_TYPE_BASE}/comments\" Target=\"comments.xml\"/>"
+            ));
+    }
+    for (href, rel) in &writer.hyperlinks {
+        rels.push_str(&format!(
+                "<Relationship Id=\"{rel}\" Type=\"{REL_TYPE_BASE}/hyperlink\" Target=\"{}\" TargetMode=\"External\"/>",
+                xml_escape(href)
+            ));
+    }
+    for (rel, image) in &writer.images {
+        rels.push_str(&format!(
+            "<Relationship Id=\"{rel}\" Type=\"{REL_TYPE_BASE}/image\" Target=\"media/{}\"/>",
+            image.part_name
+        ));
+    }
+    rels.push_str("</Relationships>");
+
+    let mut content_types = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+        );
+    let mut image_extensions: Vec<(&str, &str)> = writer
+        .images
+        .iter()
+        .map(|(_, image)| {
+            (
+                extension_for_mime(&image.asset.mime),
+                image.asset.mime.as_str(),
+            )
+        })
+        .collect();
+    image_extensions.sort();
+    image_extensions.dedup();
+    for (ext, mime) in image_extensions {
+        content_types.push_str(&format!(
+            "<Default Extension=\"{ext}\" ContentType=\"{}\"/>",
+            xml_escape(mime)
+        ));
+    }
+    content_types.push_str(
+            "<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\
+<Override PartName=\"/word/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml\"/>\
+<Override PartName=\"/word/numbering.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml\"/>",
+        );
+    if !writer.comments.is_empty() {
+        content_types.push_str(
+                "<Override PartName=\"/word/comments.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.comments+xml\"/>",
+            );
+    }
+    content_types.push_str("</Types>");
+
+    let package_rels = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><Relationships xmlns=\"{REL_NS}\">\
+<Relationship Id=\"rId1\" Type=\"{REL_TYPE_BASE}/officeDocument\" Target=\"word/document.xml\"/></Relationships>"
+        );
+
+    let mut entries: Vec<(String, Vec<u8>, bool)> = vec![
+        (
+            "[Content_Types].xml".to_string(),
+            content_types.into_bytes(),
+            false,
+        ),
+        ("_rels/.rels".to_string(), package_rels.into_bytes(), false),
+        (DOCUMENT_PART.to_string(), document.into_bytes(), false),
+        (
+            "word/_rels/document.xml.rels".to_string(),
+            rels.into_bytes(),
+            false,
+        ),
+        (
+            "word/styles.xml".to_string(),
+            styles_xml().into_bytes(),
+            false,
+        ),
+        (
+            "word/numbering.xml".to_string(),
+            numbering_xml().into_bytes(),
+            false,
+        ),
+    ];
+    if !writer.comments.is_empty() {
+        let mut comments = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><w:comments xmlns:w=\"{W_NS}\">"
+            );
+        for (id, comment) in &writer.comments {
+            comments.push_str(&format!(
+                "<w:comment w:id=\"{id}\" w:author=\"{}\">",
+                xml_escape(&comment.author)
+            ));
+            for line in comment.text.split('\n') {
+                comments.push_str(&format!(
+                    "<w:p><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:p>",
+                    xml_escape(line)
+                ));
+            }
+            comments.push_str("</w:comment>");
+        }
+        comments.push_str("</w:comments>");
+        entries.push((
+            "word/comments.xml".to_string(),
+            comments.into_bytes(),
+            false,
+        ));
+    }
+    for (_, image) in &writer.images {
+        entries.push((
+            format!("word/media/{}", image.part_name),
+            image.asset.bytes.clone(),
+            true,
+        ));
+    }
+    write_zip(&entries)
+}
+
+fn styles_xml() -> String {
+    let mut out = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><w:styles xmlns:w=\"{W_NS}\">\
+<w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/></w:style>\
+<w:style w:type=\"paragraph\" w:styleId=\"Title\"><w:name w:val=\"Title\"/><w:basedOn w:val=\"Normal\"/>\
+<w:rPr><w:b/><w:sz w:val=\"48\"/></w:rPr></w:style>"
+        );
+    for level in 1..=6 {
+        let size = 40 - level * 4;
+        out.push_str(&format!(
+                "<w:style w:type=\"paragraph\" w:styleId=\"Heading{level}\"><w:name w:val=\"heading {level}\"/>\
+<w:basedOn w:val=\"Normal\"/><w:pPr><w:outlineLvl w:val=\"{}\"/></w:pPr><w:rPr><w:b/><w:sz w:val=\"{size}\"/></w:rPr></w:style>",
+                level - 1
+            ));
+    }
+    out.push_str(
+            "<w:style w:type=\"paragraph\" w:styleId=\"Quote\"><w:name w:val=\"Quote\"/><w:basedOn w:val=\"Normal\"/>\
+<w:pPr><w:ind w:left=\"720\"/></w:pPr><w:rPr><w:i/></w:rPr></w:style>\
+<w:style w:type=\"paragraph\" w:styleId=\"Code\"><w:name w:val=\"Code\"/><w:basedOn w:val=\"Normal\"/>\
+<w:rPr><w:rFonts w:ascii=\"Consolas\" w:hAnsi=\"Consolas\" w:cs=\"Consolas\"/></w:rPr></w:style>\
+<w:style w:type=\"paragraph\" w:styleId=\"ListParagraph\"><w:name w:val=\"List Paragraph\"/><w:basedOn w:val=\"Normal\"/></w:style>\
+<w:style w:type=\"table\" w:styleId=\"TableGrid\"><w:name w:val=\"Table Grid\"/><w:tblPr><w:tblBorders>\
+<w:top w:val=\"single\" w:sz=\"4\"/><w:left w:val=\"single\" w:sz=\"4\"/><w:bottom w:val=\"single\" w:sz=\"4\"/>\
+<w:right w:val=\"single\" w:sz=\"4\"/><w:insideH w:val=\"single\" w:sz=\"4\"/><w:insideV w:val=\"single\" w:sz=\"4\"/>\
+</w:tblBorders></w:tblPr></w:style></w:styles>",
+        );
+    out
+}
+
+fn numbering_xml() -> String {
+    let mut out = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><w:numbering xmlns:w=\"{W_NS}\">"
+        );
+    for (abstract_id, ordered) in [(0, false), (1, true)] {
+        out.push_str(&format!(
+            "<w:abstractNum w:abstractNumId=\"{abstract_id}\">"
+        ));
+        for level in 0..9 {
+            let (fmt, text) = if ordered {
+                ("decimal", format!("%{}.", level + 1))
+            } else {
+                ("bullet", "\u{2022}".to_string())
+            };
+            out.push_str(&format!(
+                "<w:lvl w:ilvl=\"{level}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"{fmt}\"/>\
+<w:lvlText w:val=\"{text}\"/><w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+                720 * (level + 1)
+            ));
+        }
+        out.push_str("</w:abstractNum>");
+    }
+    out.push_str(&format!(
+        "<w:num w:numId=\"{BULLET_NUM_ID}\"><w:abstractNumId w:val=\"0\"/></w:num>\
+<w:num w:numId=\"{ORDERED_NUM_ID}\"><w:abstractNumId w:val=\"1\"/></w:num></w:numbering>"
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_document::office::tests::{
+        assert_round_trip, kinds, sample_tree, MemoryAssets,
+    };
+
+    #[test]
+    fn docx_round_trip_preserves_structure_images_and_comments() {
+        assert_round_trip(OfficeFormat::Docx);
+    }
+
+    #[test]
+    fn unresolved_image_exports_alt_text_with_warning() {
+        let export = export_office_document(
+            "Spec",
+            &sample_tree(),
+            OfficeFormat::Docx,
+            &MemoryAssets::default(),
+        )
+        .unwrap();
+        assert!(export
+            .warnings
+            .iter()
+            .any(|w| w.code == "image_not_resolved"));
+        let outcome = import_office_document(
+            &export.bytes,
+            OfficeFormat::Docx,
+            &mut MemoryAssets::default(),
+        )
+        .unwrap();
+        assert!(!kinds(&outcome.document_json).contains(&BlockKind::Image));
+        assert!(outcome.document_json.to_string().contains("diagram"));
+    }
+
+    fn docx_with_body(body: &str, extra: &[(&str, &str)]) -> Vec<u8> {
+        let document = format!(
+            "<?xml version=\"1.0\"?><w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+xmlns:m=\"http://schemas.openxmlformats.org/officeDocument/2006/math\"><w:body>{body}</w:body></w:document>"
+        );
+        let mut entries = vec![(
+            "word/document.xml".to_string(),
+            document.into_bytes(),
+            false,
+        )];
+        for (name, xml) in extra {
+            entries.push((name.to_string(), xml.as_bytes().to_vec(), false));
+        }
+        write_zip(&entries).unwrap()
+    }
+
+    #[test]
+    fn docx_unsupported_features_surface_typed_warnings() {
+        let body = "<w:p><w:r><w:t>Intro</w:t></w:r><w:r><w:footnoteReference w:id=\"1\"/></w:r></w:p>\
+<w:p><m:oMath><m:r><m:t>x</m:t></m:r></m:oMath></w:p>\
+<w:p><w:del><w:r><w:delText>gone</w:delText></w:r></w:del><w:ins><w:r><w:t>kept</w:t></w:r></w:ins></w:p>\
+<w:tbl><w:tr><w:tc><w:tcPr><w:vMerge w:val=\"restart\"/></w:tcPr><w:p><w:r><w:t>cell</w:t></w:r></w:p></w:tc></w:tr></w:tbl>";
+        let bytes = docx_with_body(
+            body,
+            &[("word/footnotes.xml", "<w:footnotes xmlns:w=\"x\"/>")],
+        );
+        let outcome =
+            import_office_document(&bytes, OfficeFormat::Docx, &mut MemoryAssets::default())
+                .unwrap();
+        let codes: Vec<&str> = outcome.warnings.iter().map(|w| w.code.as_str()).collect();
+        for code in [
+            "footnote_not_supported",
+            "equation_not_supported",
+            "tracked_changes_flattened",
+            "vertical_merge_flattened",
+        ] {
+            assert!(codes.contains(&code), "missing {code}: {codes:?}");
+        }
+        let text = outcome.document_json.to_string();
+        assert!(text.contains("kept"));
+        assert!(!text.contains("gone"));
+    }
+
+    #[test]
+    fn docx_lists_resolve_ordered_numbering_and_localized_heading_styles() {
+        let numbering = "<w:numbering xmlns:w=\"x\"><w:abstractNum w:abstractNumId=\"7\">\
+<w:lvl w:ilvl=\"0\"><w:numFmt w:val=\"decimal\"/></w:lvl></w:abstractNum>\
+<w:num w:numId=\"3\"><w:abstractNumId w:val=\"7\"/></w:num></w:numbering>";
+        let styles = "<w:styles xmlns:w=\"x\"><w:style w:styleId=\"berschrift1\"><w:name w:val=\"heading 1\"/></w:style></w:styles>";
+        let body = "<w:p><w:pPr><w:pStyle w:val=\"berschrift1\"/></w:pPr><w:r><w:t>Titel</w:t></w:r></w:p>\
+<w:p><w:pPr><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"3\"/></w:numPr></w:pPr><w:r><w:t>first</w:t></w:r></w:p>\
+<w:p><w:pPr><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"3\"/></w:numPr></w:pPr><w:r><w:t>second</w:t></w:r></w:p>";
+        let bytes = docx_with_body(
+            body,
+            &[
+                ("word/numbering.xml", numbering),
+                ("word/styles.xml", styles),
+            ],
+        );
+        let outcome =
+            import_office_document(&bytes, OfficeFormat::Docx, &mut MemoryAssets::default())
+                .unwrap();
+        assert!(outcome.warnings.is_empty(), "{:?}", outcome.warnings);
+        assert_eq!(
+            kinds(&outcome.document_json),
+            vec![BlockKind::Heading, BlockKind::OrderedList]
+        );
+        assert_eq!(
+            outcome.document_json["content"][1]["content"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn unsafe_link_schemes_import_as_plain_text() {
+        let rels = "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId9\" Type=\"hyperlink\" Target=\"javascript:alert(1)\" TargetMode=\"External\"/></Relationships>";
+        let body = "<w:p><w:hyperlink r:id=\"rId9\" xmlns:r=\"r\"><w:r><w:t>click</w:t></w:r></w:hyperlink></w:p>";
+        let bytes = docx_with_body(body, &[("word/_rels/document.xml.rels", rels)]);
+        let outcome =
+            import_office_document(&bytes, OfficeFormat::Docx, &mut MemoryAssets::default())
+                .unwrap();
+        assert_eq!(
+            outcome.document_json["content"][0]["content"][0],
+            json!({ "type": "text", "text": "click" })
+        );
+    }
+}
@@ -0,0 +1,1500 @@
+//! Office document import/export for RichDocuments (DOCX / ODT).
+//!
+//! A pure-Rust bridge between the canonical block tree and the two office
+//! container formats most operator input arrives in:
+//!
+//! * [`import_office_document`] unpacks a `.docx` (WordprocessingML) or `.odt`
+//!   (OpenDocument Text) archive and maps headings, paragraphs (with bold /
+//!   italic / underline / code / link marks), nested bullet and ordered lists,
+//!   code and quote paragraphs, tables, images, and comments into a ProseMirror
+//!   doc node. Images are handed to an [`OfficeAssetStore`] (the ArtifactStore
+//!   in production) and land in the tree as typed artifact embeds, never paths
+//!   (MT-152). Comments ride on their top-level block under `attrs.comments`.
+//! * [`export_office_document`] is a projection: it renders a block tree back
+//!   into the same container formats. Like every projection (MT-150) it is
+//!   regenerable and never authority.
+//!
+//! Both directions surface TYPED warnings for content they cannot carry
+//! faithfully (footnotes, text boxes, equations, tracked changes, merged
+//! cells, unresolved images, typed link blocks, ...) instead of silently
+//! dropping it — the same discipline as the MT-151 snippet importer.
+
+mod docx;
+mod odt;
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use super::block_tree::{extract_plain_text, Block, BlockKind, BlockTree};
+use super::embed::{block_embed_target_raw, url_scheme, EmbedRefKind, EmbedTarget};
+use super::import::{ImportOutcome, ImportWarning};
+use crate::storage::artifacts::{
+    artifact_root_dir, read_artifact_manifest, validate_artifact_content_hash, write_file_artifact,
+    ArtifactClassification, ArtifactLayer, ArtifactManifest, ArtifactPayloadKind,
+};
+
+/// Upper bound on any single decompressed archive part. Office files are zip
+/// containers; the cap keeps a hostile archive (zip bomb) from exhausting
+/// memory before the XML parser ever sees it.
+const MAX_PART_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Upper bound on all decompressed bytes read from one archive. Every part can
+/// sit under [`MAX_PART_BYTES`] while a document referencing hundreds of them
+/// still inflates without bound; this caps the import as a whole.
+const MAX_ARCHIVE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Link schemes the importer keeps as live `link` marks; anything else is
+/// imported as plain text (same allowlist as the MT-150 renderers).
+const SAFE_LINK_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// English Metric Units per pixel at 96 DPI (DrawingML image extents).
+const EMU_PER_PIXEL: u64 = 9525;
+/// Exported images are scaled down to fit a 6-inch text column.
+const MAX_IMAGE_WIDTH_EMU: u64 = 6 * 914_400;
+/// Fallback pixel size for an image whose dimensions cannot be decoded.
+const FALLBACK_IMAGE_PX: (u32, u32) = (480, 360);
+
+const ODT_MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+const DOCX_MIMETYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// The office container format of an import/export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfficeFormat {
+    Docx,
+    Odt,
+}
+
+impl OfficeFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Docx => "docx",
+            Self::Odt => "odt",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Docx => DOCX_MIMETYPE,
+            Self::Odt => ODT_MIMETYPE,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// Errors importing or exporting an office document. Content the bridge cannot
+/// represent is a warning, not an error; errors are reserved for archives that
+/// cannot be read at all or assets that cannot be persisted.
+#[derive(Debug, thiserror::Error)]
+pub enum OfficeDocumentError {
+    #[error("office archive could not be read: {0}")]
+    Archive(String),
+    #[error("office archive is missing required part `{0}`")]
+    MissingPart(String),
+    #[error("office part `{part}` exceeds the {limit}-byte import limit")]
+    PartTooLarge { part: String, limit: u64 },
+    #[error("office archive exceeds the {limit}-byte total import limit at part `{part}`")]
+    ArchiveTooLarge { part: String, limit: u64 },
+    #[error("office part `{part}` is not well-formed XML: {detail}")]
+    Xml { part: String, detail: String },
+    #[error("office asset store failed: {0}")]
+    Asset(String),
+}
+
+/// A typed warning raised while exporting a block tree to an office format:
+/// a block or mark the target format cannot carry faithfully.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfficeExportWarning {
+    /// Stable machine code (e.g. `typed_link_flattened`, `image_not_resolved`).
+    pub code: String,
+    /// Human-readable detail.
+    pub detail: String,
+}
+
+/// A rendered office export. Regenerable; never authority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfficeExport {
+    pub format: OfficeFormat,
+    pub bytes: Vec<u8>,
+    pub warnings: Vec<OfficeExportWarning>,
+}
+
+/// A binary asset (image) moving between an office archive and the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfficeAsset {
+    pub bytes: Vec<u8>,
+    pub mime: String,
+    pub filename_hint: Option<String>,
+}
+
+/// Where imported images are persisted and exported images are read from.
+///
+/// Production uses [`ArtifactStoreOfficeAssets`]; the indirection keeps the
+/// transforms pure and lets tests run without a workspace root.
+pub trait OfficeAssetStore {
+    /// Persist an imported asset and return the typed embed target the image
+    /// block should reference.
+    fn store_asset(&mut self, asset: OfficeAsset) -> Result<EmbedTarget, OfficeDocumentError>;
+
+    /// Resolve an embed target back to its bytes for export. `Ok(None)` means
+    /// the target is not held by this store (a warning, not an error).
+    fn load_asset(&self, target: &EmbedTarget) -> Result<Option<OfficeAsset>, OfficeDocumentError>;
+}
+
+/// [`OfficeAssetStore`] backed by the ArtifactStore: imported images become L1
+/// file artifacts and embeds reference them by artifact id.
+#[derive(Debug, Clone)]
+pub struct ArtifactStoreOfficeAssets {
+    workspace_root: PathBuf,
+    created_by_job_id: Option<Uuid>,
+    stored: Vec<Uuid>,
+}
+
+impl ArtifactStoreOfficeAssets {
+    pub fn new(workspace_root: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            created_by_job_id: None,
+            stored: Vec::new(),
+        }
+    }
+
+    pub fn with_job_id(mut self, job_id: Uuid) -> Self {
+        self.created_by_job_id = Some(job_id);
+        self
+    }
+
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    /// Artifact directories this store has written (or started writing), so a
+    /// caller whose import fails afterwards can remove them instead of leaving
+    /// unreferenced artifacts behind.
+    pub fn stored_artifact_dirs(&self) -> Vec<PathBuf> {
+        self.stored
+            .iter()
+            .map(|id| artifact_root_dir(&self.workspace_root, ArtifactLayer::L1, *id))
+            .collect()
+    }
+}
+
+impl OfficeAssetStore for ArtifactStoreOfficeAssets {
+    fn store_asset(&mut self, asset: OfficeAsset) -> Result<EmbedTarget, OfficeDocumentError> {
+        let artifact_id = Uuid::now_v7();
+        let manifest = ArtifactManifest {
+            artifact_id,
+            layer: ArtifactLayer::L1,
+            kind: ArtifactPayloadKind::File,
+            mime: asset.mime,
+            filename_hint: asset.filename_hint,
+            created_at: Utc::now(),
+            created_by_job_id: self.created_by_job_id,
+            source_entity_refs: Vec::new(),
+            source_artifact_refs: Vec::new(),
+            content_hash: crate::kernel::context_bundle::sha256_hex(&asset.bytes),
+            size_bytes: asset.bytes.len() as u64,
+            classification: ArtifactClassification::Low,
+            exportable: true,
+            retention_ttl_days: None,
+            pinned: None,
+            hash_basis: None,
+            hash_exclude_paths: Vec::new(),
+        };
+        // Recorded before the write: a failed write can leave a partial dir.
+        self.stored.push(artifact_id);
+        write_file_artifact(&self.workspace_root, &manifest, &asset.bytes)
+            .map_err(|err| OfficeDocumentError::Asset(err.to_string()))?;
+        EmbedTarget::new(EmbedRefKind::Artifact, artifact_id.to_string())
+            .map_err(|err| OfficeDocumentError::Asset(err.to_string()))
+    }
+
+    fn load_asset(&self, target: &EmbedTarget) -> Result<Option<OfficeAsset>, OfficeDocumentError> {
+        if target.kind == EmbedRefKind::Url {
+            return Ok(None);
+        }
+        let Ok(artifact_id) = Uuid::parse_str(&target.value) else {
+            return Ok(None);
+        };
+        let layer = ArtifactLayer::L1;
+        let Ok(manifest) = read_artifact_manifest(&self.workspace_root, layer, artifact_id) else {
+            return Ok(None);
+        };
+        validate_artifact_content_hash(&self.workspace_root, layer, artifact_id)
+            .map_err(|err| OfficeDocumentError::Asset(err.to_string()))?;
+        let payload = artifact_root_dir(&self.workspace_root, layer, artifact_id).join("payload");
+        let bytes =
+            std::fs::read(payload).map_err(|err| OfficeDocumentError::Asset(err.to_string()))?;
+        Ok(Some(OfficeAsset {
+            bytes,
+            mime: manifest.mime,
+            filename_hint: manifest.filename_hint,
+        }))
+    }
+}
+
+/// Import a `.docx` / `.odt` archive into a document block tree. The outcome
+/// is a doc node ready to seed a RichDocument plus the typed warnings for
+/// everything that could not be carried faithfully.
+pub fn import_office_document(
+    bytes: &[u8],
+    format: OfficeFormat,
+    assets: &mut dyn OfficeAssetStore,
+) -> Result<ImportOutcome, OfficeDocumentError> {
+    let mut archive = OfficeArchive::open(bytes)?;
+    let mut warnings = WarningSink::default();
+    let blocks = match format {
+        OfficeFormat::Docx => docx::read(&mut archive, assets, &mut warnings)?,
+        OfficeFormat::Odt => odt::read(&mut archive, assets, &mut warnings)?,
+    };
+    let mut content = assemble_blocks(blocks);
+    if content.is_empty() {
+        content.push(json!({ "type": "paragraph" }));
+    }
+    Ok(ImportOutcome {
+        document_json: json!({ "type": "doc", "content": content }),
+        warnings: warnings
+            .into_entries()
+            .into_iter()
+            .map(|(code, detail)| ImportWarning { code, detail })
+            .collect(),
+    })
+}
+
+/// Export a block tree into a `.docx` / `.odt` archive. Deterministic for a
+/// given tree and asset store: identical inputs yield identical bytes.
+pub fn export_office_document(
+    title: &str,
+    tree: &BlockTree,
+    format: OfficeFormat,
+    assets: &dyn OfficeAssetStore,
+) -> Result<OfficeExport, OfficeDocumentError> {
+    let mut warnings = WarningSink::default();
+    let bytes = match format {
+        OfficeFormat::Docx => docx::write(title, tree, assets, &mut warnings)?,
+        OfficeFormat::Odt => odt::write(title, tree, assets, &mut warnings)?,
+    };
+    Ok(OfficeExport {
+        format,
+        bytes,
+        warnings: warnings
+            .into_entries()
+            .into_iter()
+            .map(|(code, detail)| OfficeExportWarning { code, detail })
+            .collect(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Shared intermediate model
+// ---------------------------------------------------------------------------
+
+/// Inline formatting carried on a text run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Marks {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Run {
+    text: String,
+    marks: Marks,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comment {
+    author: String,
+    text: String,
+}
+
+/// A paragraph-level unit read out of either format, before list grouping.
+#[derive(Debug, Clone, PartialEq)]
+enum OfficeBlock {
+    Paragraph {
+        runs: Vec<Run>,
+        comments: Vec<Comment>,
+    },
+    Heading {
+        level: i64,
+        runs: Vec<Run>,
+        comments: Vec<Comment>,
+    },
+    Quote {
+        runs: Vec<Run>,
+        comments: Vec<Comment>,
+    },
+    Code {
+        text: String,
+    },
+    ListItem {
+        ordered: bool,
+        level: u32,
+        runs: Vec<Run>,
+        comments: Vec<Comment>,
+    },
+    Table {
+        rows: Vec<TableRow>,
+        comments: Vec<Comment>,
+    },
+    Image {
+        target: EmbedTarget,
+        alt: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TableRow {
+    header: bool,
+    cells: Vec<TableCell>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TableCell {
+    colspan: u32,
+    paragraphs: Vec<Vec<Run>>,
+}
+
+/// Collects typed warnings, one entry per code with an occurrence count, in
+/// first-seen order so output stays deterministic.
+#[derive(Debug, Default)]
+struct WarningSink {
+    entries: Vec<(String, String, usize)>,
+}
+
+impl WarningSink {
+    fn push(&mut self, code: &str, detail: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|(c, _, _)| c == code) {
+            entry.2 += 1;
+        } else {
+            self.entries.push((code.to_string(), detail.to_string(), 1));
+        }
+    }
+
+    fn into_entries(self) -> Vec<(String, String)> {
+        self.entries
+            .into_iter()
+            .map(|(code, detail, count)| {
+                if count > 1 {
+                    (code, format!("{detail} ({count} occurrences)"))
+                } else {
+                    (code, detail)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Read-only view over an office zip container with bounded part reads and a
+/// bounded total across all reads.
+struct OfficeArchive<'a> {
+    zip: zip::ZipArchive<Cursor<&'a [u8]>>,
+    part_limit: u64,
+    total_limit: u64,
+    read_total: u64,
+}
+
+impl<'a> OfficeArchive<'a> {
+    fn open(bytes: &'a [u8]) -> Result<Self, OfficeDocumentError> {
+        Self::open_with_limits(bytes, MAX_PART_BYTES, MAX_ARCHIVE_BYTES)
+    }
+
+    fn open_with_limits(
+        bytes: &'a [u8],
+        part_limit: u64,
+        total_limit: u64,
+    ) -> Result<Self, OfficeDocumentError> {
+        let zip = zip::ZipArchive::new(Cursor::new(bytes))
+            .map_err(|err| OfficeDocumentError::Archive(err.to_string()))?;
+        Ok(Self {
+            zip,
+            part_limit,
+            total_limit,
+            read_total: 0,
+        })
+    }
+
+    fn read_bytes(&mut self, name: &str) -> Result<Option<Vec<u8>>, OfficeDocumentError> {
+        let file = match self.zip.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(OfficeDocumentError::Archive(err.to_string())),
+        };
+        let part_too_large = || OfficeDocumentError::PartTooLarge {
+            part: name.to_string(),
+            limit: self.part_limit,
+        };
+        let archive_too_large = || OfficeDocumentError::ArchiveTooLarge {
+            part: name.to_string(),
+            limit: self.total_limit,
+        };
+        let remaining = self.total_limit.saturating_sub(self.read_total);
+        if file.size() > self.part_limit {
+            return Err(part_too_large());
+        }
+        if file.size() > remaining {
+            return Err(archive_too_large());
+        }
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        // `take` guards against a lying size header as well.
+        file.take(self.part_limit.min(remaining) + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|err| OfficeDocumentError::Archive(err.to_string()))?;
+        let len = bytes.len() as u64;
+        if len > self.part_limit {
+            return Err(part_too_large());
+        }
+        if len > remaining {
+            return Err(archive_too_large());
+        }
+        self.read_total += len;
+        Ok(Some(bytes))
+    }
+
+    fn read_text(&mut self, name: &str) -> Result<Option<String>, OfficeDocumentError> {
+        match self.read_bytes(name)? {
+            None => Ok(None),
+            Some(bytes) => {
+                String::from_utf8(bytes)
+                    .map(Some)
+                    .map_err(|err| OfficeDocumentError::Xml {
+                        part: name.to_string(),
+                        detail: err.to_string(),
+                    })
+            }
+        }
+    }
+
+    fn require_text(&mut self, name: &str) -> Result<String, OfficeDocumentError> {
+        self.read_text(name)?
+            .ok_or_else(|| OfficeDocumentError::MissingPart(name.to_string()))
+    }
+}
+
+fn parse_xml<'x>(part: &str, text: &'x str) -> Result<Document<'x>, OfficeDocumentError> {
+    Document::parse(text).map_err(|err| OfficeDocumentError::Xml {
+        part: part.to_string(),
+        detail: err.to_string(),
+    })
+}
+
+/// Attribute lookup by local name, ignoring the namespace prefix (`w:val`,
+/// `text:style-name`, ...). Both formats qualify nearly every attribute.
+fn attr<'n>(node: Node<'n, '_>, local: &str) -> Option<&'n str> {
+    node.attributes()
+        .find(|a| a.name() == local)
+        .map(|a| a.value())
+}
+
+fn is(node: Node<'_, '_>, local: &str) -> bool {
+    node.is_element() && node.tag_name().name() == local
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, local: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|c| is(*c, local))
+}
+
+fn safe_link(href: &str) -> Option<String> {
+    match url_scheme(href) {
+        Some(scheme) if SAFE_LINK_SCHEMES.contains(&scheme.as_str()) => Some(href.to_string()),
+        _ => None,
+    }
+}
+
+fn mime_for_extension(name: &str) -> &'static str {
+    let ext = name.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "svg" => "image/svg+xml",
+        "emf" => "image/emf",
+        "wmf" => "image/wmf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpeg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "image/tiff" => "tiff",
+        "image/svg+xml" => "svg",
+        "image/emf" => "emf",
+        "image/wmf" => "wmf",
+        _ => "bin",
+    }
+}
+
+fn runs_plain_text(runs: &[Run]) -> String {
+    runs.iter().map(|r| r.text.as_str()).collect()
+}
+
+/// Store one archive image through the asset store, producing an image block
+/// or a warning when the part is missing.
+fn import_image(
+    archive: &mut OfficeArchive<'_>,
+    part: &str,
+    alt: &str,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Option<OfficeBlock>, OfficeDocumentError> {
+    let Some(bytes) = archive.read_bytes(part)? else {
+        warnings.push(
+            "image_part_missing",
+            "An image referenced a part that is not present in the archive; it was skipped.",
+        );
+        return Ok(None);
+    };
+    let filename_hint = part.rsplit('/').next().map(ToOwned::to_owned);
+    let target = assets.store_asset(OfficeAsset {
+        bytes,
+        mime: mime_for_extension(part).to_string(),
+        filename_hint,
+    })?;
+    Ok(Some(OfficeBlock::Image {
+        target,
+        alt: alt.to_string(),
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Intermediate model -> ProseMirror JSON
+// ---------------------------------------------------------------------------
+
+fn marks_json(marks: &Marks) -> Vec<Value> {
+    let mut out = Vec::new();
+    if marks.bold {
+        out.push(json!({ "type": "bold" }));
+    }
+    if marks.italic {
+        out.push(json!({ "type": "italic" }));
+    }
+    if marks.underline {
+        out.push(json!({ "type": "underline" }));
+    }
+    if marks.code {
+        out.push(json!({ "type": "code" }));
+    }
+    if let Some(href) = &marks.link {
+        out.push(json!({ "type": "link", "attrs": { "href": href } }));
+    }
+    out
+}
+
+/// Text nodes for a run sequence: adjacent runs with identical marks merge and
+/// empty runs disappear, so the same visible text always yields the same JSON.
+fn inline_json(runs: &[Run]) -> Vec<Value> {
+    let mut merged: Vec<Run> = Vec::new();
+    for run in runs {
+        if run.text.is_empty() {
+            continue;
+        }
+        match merged.last_mut() {
+            Some(last) if last.marks == run.marks => last.text.push_str(&run.text),
+            _ => merged.push(run.clone()),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|run| {
+            let marks = marks_json(&run.marks);
+            if marks.is_empty() {
+                json!({ "type": "text", "text": run.text })
+            } else {
+                json!({ "type": "text", "text": run.text, "marks": marks })
+            }
+        })
+        .collect()
+}
+
+fn node_with(node_type: &str, attrs: Map<String, Value>, content: Vec<Value>) -> Value {
+    let mut obj = Map::new();
+    obj.insert("type".to_string(), json!(node_type));
+    if !attrs.is_empty() {
+        obj.insert("attrs".to_string(), Value::Object(attrs));
+    }
+    if !content.is_empty() {
+        obj.insert("content".to_string(), Value::Array(content));
+    }
+    Value::Object(obj)
+}
+
+fn paragraph_json(runs: &[Run]) -> Value {
+    node_with("paragraph", Map::new(), inline_json(runs))
+}
+
+fn comments_attr(attrs: &mut Map<String, Value>, comments: &[Comment]) {
+    if comments.is_empty() {
+        return;
+    }
+    attrs.insert(
+        "comments".to_string(),
+        Value::Array(
+            comments
+                .iter()
+                .map(|c| json!({ "author": c.author, "text": c.text }))
+                .collect(),
+        ),
+    );
+}
+
+struct ListEntry {
+    ordered: bool,
+    level: u32,
+    runs: Vec<Run>,
+}
+
+/// Build a (possibly nested) list from a run of list paragraphs. Items deeper
+/// than the first item's level nest under the preceding item.
+fn list_json(entries: &[ListEntry]) -> Value {
+    let base = entries[0].level;
+    let list_type = if entries[0].ordered {
+        "orderedList"
+    } else {
+        "bulletList"
+    };
+    let mut items: Vec<Vec<Value>> = Vec::new();
+    let mut i = 0;
+    while i < entries.len() {
+        if entries[i].level <= base || items.is_empty() {
+            items.push(vec![paragraph_json(&entries[i].runs)]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < entries.len() && entries[i].level > base {
+            i += 1;
+        }
+        let nested = list_json(&entries[start..i]);
+        if let Some(last) = items.last_mut() {
+            last.push(nested);
+        }
+    }
+    node_with(
+        list_type,
+        Map::new(),
+        items
+            .into_iter()
+            .map(|content| node_with("listItem", Map::new(), content))
+            .collect(),
+    )
+}
+
+fn table_json(rows: &[TableRow], comments: &[Comment]) -> Value {
+    let rows_json: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            let cells = row
+                .cells
+                .iter()
+                .map(|cell| {
+                    let mut attrs = Map::new();
+                    if cell.colspan > 1 {
+                        attrs.insert("colspan".to_string(), json!(cell.colspan));
+                    }
+                    let mut content: Vec<Value> =
+                        cell.paragraphs.iter().map(|p| paragraph_json(p)).collect();
+                    if content.is_empty() {
+                        content.push(json!({ "type": "paragraph" }));
+                    }
+                    node_with(
+                        if row.header {
+                            "tableHeader"
+                        } else {
+                            "tableCell"
+                        },
+                        attrs,
+                        content,
+                    )
+                })
+                .collect();
+            node_with("tableRow", Map::new(), cells)
+        })
+        .collect();
+    let mut attrs = Map::new();
+    comments_attr(&mut attrs, comments);
+    node_with("table", attrs, rows_json)
+}
+
+/// Group the flat paragraph stream into top-level ProseMirror blocks:
+/// consecutive list paragraphs become one (nested) list and consecutive code
+/// paragraphs become one code block.
+fn assemble_blocks(blocks: Vec<OfficeBlock>) -> Vec<Value> {
+    let mut out = Vec::new();
+    let mut iter = blocks.into_iter().peekable();
+    while let Some(block) = iter.next() {
+        match block {
+            OfficeBlock::Paragraph { runs, comments } => {
+                let mut attrs = Map::new();
+                comments_attr(&mut attrs, &comments);
+                out.push(node_with("paragraph", attrs, inline_json(&runs)));
+            }
+            OfficeBlock::Heading {
+                level,
+                runs,
+                comments,
+            } => {
+                let mut attrs = Map::new();
+                attrs.insert("level".to_string(), json!(level.clamp(1, 6)));
+                comments_attr(&mut attrs, &comments);
+                out.push(node_with("heading", attrs, inline_json(&runs)));
+            }
+            OfficeBlock::Quote { runs, comments } => {
+                let mut attrs = Map::new();
+                comments_attr(&mut attrs, &comments);
+                out.push(node_with("blockquote", attrs, vec![paragraph_json(&runs)]));
+            }
+            OfficeBlock::Code { text } => {
+                let mut lines = vec![text];
+                while let Some(OfficeBlock::Code { .. }) = iter.peek() {
+                    if let Some(OfficeBlock::Code { text }) = iter.next() {
+                        lines.push(text);
+                    }
+                }
+                let code = lines.join("\n");
+                let content = if code.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![json!({ "type": "text", "text": code })]
+                };
+                out.push(node_with("codeBlock", Map::new(), content));
+            }
+            OfficeBlock::ListItem {
+                ordered,
+                level,
+                runs,
+                comments,
+            } => {
+                let mut entries = vec![ListEntry {
+                    ordered,
+                    level,
+                    runs,
+                }];
+                let mut all_comments = comments;
+                let base_ordered = ordered;
+                let base_level = level;
+                while let Some(OfficeBlock::ListItem {
+                    ordered: next_ordered,
+                    level: next_level,
+                    ..
+                }) = iter.peek()
+                {
+                    // A top-level item of the other list kind starts a new list.
+                    if *next_level <= base_level && *next_ordered != base_ordered {
+                        break;
+                    }
+                    if let Some(OfficeBlock::ListItem {
+                        ordered,
+                        level,
+                        runs,
+                        comments,
+                    }) = iter.next()
+                    {
+                        entries.push(ListEntry {
+                            ordered,
+                            level: level.max(base_level),
+                            runs,
+                        });
+                        all_comments.extend(comments);
+                    }
+                }
+                let mut list = list_json(&entries);
+                if !all_comments.is_empty() {
+                    let mut attrs = Map::new();
+                    comments_attr(&mut attrs, &all_comments);
+                    list["attrs"] = Value::Object(attrs);
+                }
+                out.push(list);
+            }
+            OfficeBlock::Table { rows, comments } => out.push(table_json(&rows, &comments)),
+            OfficeBlock::Image { target, alt } => {
+                let mut attrs = Map::new();
+                attrs.insert("target".to_string(), json!(target.value));
+                attrs.insert("target_kind".to_string(), json!(target.kind.as_str()));
+                if !alt.is_empty() {
+                    attrs.insert("alt".to_string(), json!(alt));
+                }
+                out.push(node_with("image", attrs, Vec::new()));
+            }
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Block tree -> export model
+// ---------------------------------------------------------------------------
+
+fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(Value::as_str).unwrap_or("")
+}
+
+fn node_attrs(node: &Value) -> Option<&Map<String, Value>> {
+    node.get("attrs").and_then(Value::as_object)
+}
+
+fn node_children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// Flatten a node's inline content into runs. Atoms the office formats have no
+/// equivalent for (mentions, hsLinks, tag mentions) export as their label.
+fn export_runs(node: &Value, warnings: &mut WarningSink) -> Vec<Run> {
+    let mut runs = Vec::new();
+    collect_export_runs(node, &Marks::default(), &mut runs, warnings);
+    runs
+}
+
+fn collect_export_runs(
+    node: &Value,
+    inherited: &Marks,
+    out: &mut Vec<Run>,
+    warnings: &mut WarningSink,
+) {
+    match node_type(node) {
+        "text" => {
+            let mut marks = inherited.clone();
+            for mark in node
+                .get("marks")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or(&[])
+            {
+                match node_type(mark) {
+                    "bold" | "strong" => marks.bold = true,
+                    "italic" | "em" => marks.italic = true,
+                    "underline" => marks.underline = true,
+                    "code" => marks.code = true,
+                    "link" => {
+                        marks.link = node_attrs(mark)
+                            .and_then(|a| a.get("href"))
+                            .and_then(Value::as_str)
+                            .and_then(safe_link);
+                    }
+                    other => warnings.push(
+                        "inline_mark_dropped",
+                        &format!("Inline mark `{other}` has no office equivalent; its text was kept unformatted."),
+                    ),
+                }
+            }
+            let text = node.get("text").and_then(Value::as_str).unwrap_or("");
+            out.push(Run {
+                text: text.to_string(),
+                marks,
+            });
+        }
+        "hardBreak" => out.push(Run {
+            text: "\n".to_string(),
+            marks: inherited.clone(),
+        }),
+        "hsLink" | "mention" | "tagMention" => out.push(Run {
+            text: extract_plain_text(node),
+            marks: inherited.clone(),
+        }),
+        _ => {
+            for child in node_children(node) {
+                collect_export_runs(child, inherited, out, warnings);
+            }
+        }
+    }
+}
+
+fn block_comments(block: &Block) -> Vec<Comment> {
+    node_attrs(&block.content.raw)
+        .and_then(|a| a.get("comments"))
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|c| {
+                    let text = c.get("text").and_then(Value::as_str)?;
+                    Some(Comment {
+                        author: c
+                            .get("author")
+                            .and_then(Value::as_str)
+                            .unwrap_or("")
+                            .to_string(),
+                        text: text.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A list paragraph flattened for export, with its nesting depth.
+struct ExportListItem {
+    ordered: bool,
+    depth: u32,
+    runs: Vec<Run>,
+}
+
+fn flatten_list(
+    list: &Value,
+    depth: u32,
+    out: &mut Vec<ExportListItem>,
+    warnings: &mut WarningSink,
+) {
+    let ordered = node_type(list) == "orderedList";
+    for item in node_children(list) {
+        for child in node_children(item) {
+            match node_type(child) {
+                "bulletList" | "orderedList" | "taskList" => {
+                    flatten_list(child, depth + 1, out, warnings)
+                }
+                _ => out.push(ExportListItem {
+                    ordered,
+                    depth,
+                    runs: export_runs(child, warnings),
+                }),
+            }
+        }
+    }
+}
+
+/// Read a table node into rows of cells of paragraphs.
+fn export_table(table: &Value, warnings: &mut WarningSink) -> Vec<TableRow> {
+    node_children(table)
+        .iter()
+        .filter(|row| node_type(row) == "tableRow")
+        .map(|row| {
+            let cells: Vec<TableCell> = node_children(row)
+                .iter()
+                .map(|cell| {
+                    let attrs = node_attrs(cell);
+                    let colspan = attrs
+                        .and_then(|a| a.get("colspan"))
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1)
+                        .max(1) as u32;
+                    if attrs
+                        .and_then(|a| a.get("rowspan"))
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1)
+                        > 1
+                    {
+                        warnings.push(
+                            "rowspan_not_supported",
+                            "Row-spanning table cells are exported as single cells.",
+                        );
+                    }
+                    TableCell {
+                        colspan,
+                        paragraphs: node_children(cell)
+                            .iter()
+                            .map(|p| export_runs(p, warnings))
+                            .collect(),
+                    }
+                })
+                .collect();
+            TableRow {
+                header: node_children(row)
+                    .iter()
+                    .all(|cell| node_type(cell) == "tableHeader")
+                    && !cells.is_empty(),
+                cells,
+            }
+        })
+        .collect()
+}
+
+/// An export-ready image: the resolved bytes plus a stable archive name.
+struct ExportImage {
+    part_name: String,
+    asset: OfficeAsset,
+    width_px: u32,
+    height_px: u32,
+    alt: String,
+}
+
+fn resolve_export_image(
+    block: &Block,
+    index: usize,
+    assets: &dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Option<ExportImage>, OfficeDocumentError> {
+    let alt = node_attrs(&block.content.raw)
+        .and_then(|a| a.get("alt"))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| block.content.derived.plain_text.clone());
+    let target = block_embed_target_raw(block).and_then(|raw| EmbedTarget::parse_raw(&raw).ok());
+    let Some(target) = target else {
+        warnings.push(
+            "image_not_resolved",
+            "An image block has no valid embed target; its alt text was exported instead.",
+        );
+        return Ok(None);
+    };
+    let Some(asset) = assets.load_asset(&target)? else {
+        warnings.push(
+            "image_not_resolved",
+            "An image target could not be resolved in the asset store; its alt text was exported instead.",
+        );
+        return Ok(None);
+    };
+    let (width_px, height_px) = image::ImageReader::new(Cursor::new(&asset.bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .unwrap_or(FALLBACK_IMAGE_PX);
+    let part_name = format!("image{}.{}", index + 1, extension_for_mime(&asset.mime));
+    Ok(Some(ExportImage {
+        part_name,
+        asset,
+        width_px: width_px.max(1),
+        height_px: height_px.max(1),
+        alt,
+    }))
+}
+
+/// Image extent in EMU, scaled down to the text column width.
+fn image_extent_emu(image: &ExportImage) -> (u64, u64) {
+    let cx = image.width_px as u64 * EMU_PER_PIXEL;
+    let cy = image.height_px as u64 * EMU_PER_PIXEL;
+    if cx <= MAX_IMAGE_WIDTH_EMU {
+        (cx, cy)
+    } else {
+        (MAX_IMAGE_WIDTH_EMU, cy * MAX_IMAGE_WIDTH_EMU / cx)
+    }
+}
+
+/// The export shape of one top-level block.
+enum ExportBlock {
+    Paragraph(Vec<Run>),
+    Heading(i64, Vec<Run>),
+    Quote(Vec<Run>),
+    Code(String),
+    List(Vec<ExportListItem>),
+    Table(Vec<TableRow>),
+    Image(ExportImage),
+}
+
+/// Map every top-level block to its export shape, warning for anything the
+/// office formats cannot carry.
+fn export_blocks(
+    tree: &BlockTree,
+    assets: &dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Vec<(ExportBlock, Vec<Comment>)>, OfficeDocumentError> {
+    let mut out = Vec::with_capacity(tree.blocks.len());
+    let mut image_index = 0;
+    for block in &tree.blocks {
+        let raw = &block.content.raw;
+        let comments = block_comments(block);
+        let text = &block.content.derived.plain_text;
+        let export = match block.kind {
+            BlockKind::Paragraph => ExportBlock::Paragraph(export_runs(raw, warnings)),
+            BlockKind::Heading => ExportBlock::Heading(
+                block.heading_level.unwrap_or(1).clamp(1, 6),
+                export_runs(raw, warnings),
+            ),
+            BlockKind::Blockquote => ExportBlock::Quote(export_runs(raw, warnings)),
+            BlockKind::CodeBlock => ExportBlock::Code(text.clone()),
+            BlockKind::BulletList | BlockKind::OrderedList | BlockKind::TaskList => {
+                if block.kind == BlockKind::TaskList {
+                    warnings.push(
+                        "task_list_exported_as_bullets",
+                        "Task lists are exported as bullet lists; checked state is not carried.",
+                    );
+                }
+                let mut items = Vec::new();
+                flatten_list(raw, 0, &mut items, warnings);
+                ExportBlock::List(items)
+            }
+            BlockKind::Table => ExportBlock::Table(export_table(raw, warnings)),
+            BlockKind::Image => match resolve_export_image(block, image_index, assets, warnings)? {
+                Some(image) => {
+                    image_index += 1;
+                    ExportBlock::Image(image)
+                }
+                None => ExportBlock::Paragraph(plain_runs(text)),
+            },
+            BlockKind::Video | BlockKind::Album | BlockKind::Slideshow => {
+                warnings.push(
+                    "embed_kind_not_supported",
+                    "Video, album, and slideshow embeds have no office equivalent; their captions were exported as text.",
+                );
+                ExportBlock::Paragraph(plain_runs(text))
+            }
+            kind if kind.is_typed_link() => {
+                warnings.push(
+                    "typed_link_flattened",
+                    "Typed link blocks (file/folder/project/spec/wp/symbol) are exported as text with their target.",
+                );
+                let target = node_attrs(raw)
+                    .and_then(|a| a.get("target"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("");
+                let label = if text.is_empty() {
+                    target.to_string()
+                } else if target.is_empty() {
+                    text.clone()
+                } else {
+                    format!("{text} <{target}>")
+                };
+                ExportBlock::Paragraph(plain_runs(&label))
+            }
+            BlockKind::Transclusion => {
+                warnings.push(
+                    "transclusion_not_resolved",
+                    "Transcluded blocks are references to other documents and are exported as a placeholder.",
+                );
+                let source = node_attrs(raw)
+                    .and_then(|a| a.get("refValue"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("");
+                ExportBlock::Paragraph(plain_runs(&format!("[transclusion {source}]")))
+            }
+            BlockKind::ImportedRaw => {
+                warnings.push(
+                    "imported_raw_exported_as_text",
+                    "Repairable importedRaw blocks are exported as their captured source text.",
+                );
+                ExportBlock::Code(text.clone())
+            }
+            _ => ExportBlock::Paragraph(plain_runs(text)),
+        };
+        out.push((export, comments));
+    }
+    Ok(out)
+}
+
+fn plain_runs(text: &str) -> Vec<Run> {
+    vec![Run {
+        text: text.to_string(),
+        marks: Marks::default(),
+    }]
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // XML 1.0 forbids most C0 controls even when escaped.
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Write archive entries in order. Entries flagged `stored` are not compressed
+/// (ODT requires an uncompressed leading `mimetype`).
+fn write_zip(entries: &[(String, Vec<u8>, bool)]) -> Result<Vec<u8>, OfficeDocumentError> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes, stored) in entries {
+        let method = if *stored {
+            zip::CompressionMethod::Stored
+        } else {
+            zip::CompressionMethod::Deflated
+        };
+        // A fixed timestamp keeps the archive bytes deterministic.
+        let options = zip::write::FileOptions::default()
+            .compression_method(method)
+            .last_modified_time(zip::DateTime::default());
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|err| OfficeDocumentError::Archive(err.to_string()))?;
+        writer
+            .write_all(bytes)
+            .map_err(|err| OfficeDocumentError::Archive(err.to_string()))?;
+    }
+    let cursor = writer
+        .finish()
+        .map_err(|err| OfficeDocumentError::Archive(err.to_string()))?;
+    Ok(cursor.into_inner())
+}
+
+fn is_monospace_font(font: &str) -> bool {
+    let lower = font.to_ascii_lowercase();
+    [
+        "consolas",
+        "courier",
+        "mono",
+        "menlo",
+        "monaco",
+        "source code",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_document::block_tree::DOCUMENT_SCHEMA_VERSION;
+
+    /// In-memory asset store: targets are `asset-<n>` media ids.
+    #[derive(Default)]
+    pub(super) struct MemoryAssets {
+        assets: Vec<OfficeAsset>,
+    }
+
+    impl OfficeAssetStore for MemoryAssets {
+        fn store_asset(&mut self, asset: OfficeAsset) -> Result<EmbedTarget, OfficeDocumentError> {
+            self.assets.push(asset);
+            EmbedTarget::new(
+                EmbedRefKind::Artifact,
+                format!("asset-{}", self.assets.len()),
+            )
+            .map_err(|err| OfficeDocumentError::Asset(err.to_string()))
+        }
+
+        fn load_asset(
+            &self,
+            target: &EmbedTarget,
+        ) -> Result<Option<OfficeAsset>, OfficeDocumentError> {
+            Ok(target
+                .value
+                .strip_prefix("asset-")
+                .and_then(|n| n.parse::<usize>().ok())
+                .and_then(|n| self.assets.get(n - 1))
+                .cloned())
+        }
+    }
+
+    fn png_bytes() -> Vec<u8> {
+        let mut out = Vec::new();
+        image::DynamicImage::new_rgb8(4, 2)
+            .write_to(&mut Cursor::new(&mut out), image::ImageFormat::Png)
+            .unwrap();
+        out
+    }
+
+    pub(super) fn sample_tree() -> BlockTree {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                { "type": "heading", "attrs": { "level": 2 }, "content": [{ "type": "text", "text": "Overview" }] },
+                { "type": "paragraph",
+                  "attrs": { "comments": [{ "author": "Reviewer", "text": "Check this." }] },
+                  "content": [
+                    { "type": "text", "text": "Plain " },
+                    { "type": "text", "text": "bold", "marks": [{ "type": "bold" }] },
+                    { "type": "text", "text": " and " },
+                    { "type": "text", "text": "a link", "marks": [{ "type": "link", "attrs": { "href": "https://example.com" } }] }
+                ] },
+                { "type": "bulletList", "content": [
+                    { "type": "listItem", "content": [
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "one" }] },
+                        { "type": "orderedList", "content": [
+                            { "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "one.a" }] }] }
+                        ] }
+                    ] },
+                    { "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "two" }] }] }
+                ] },
+                { "type": "codeBlock", "content": [{ "type": "text", "text": "fn main() {\n    run();\n}" }] },
+                { "type": "table", "content": [
+                    { "type": "tableRow", "content": [
+                        { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Key" }] }] },
+                        { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Value" }] }] }
+                    ] },
+                    { "type": "tableRow", "content": [
+                        { "type": "tableCell", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "a" }] }] },
+                        { "type": "tableCell", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "1" }] }] }
+                    ] }
+                ] },
+                { "type": "image", "attrs": { "target": "asset-1", "alt": "diagram" } },
+                { "type": "fileLink", "attrs": { "target": "src/main.rs" }, "content": [{ "type": "text", "text": "entry" }] }
+            ]
+        });
+        BlockTree::from_document_json("KRD-office", DOCUMENT_SCHEMA_VERSION, &doc).unwrap()
+    }
+
+    fn assets_with_image() -> MemoryAssets {
+        MemoryAssets {
+            assets: vec![OfficeAsset {
+                bytes: png_bytes(),
+                mime: "image/png".to_string(),
+                filename_hint: Some("diagram.png".to_string()),
+            }],
+        }
+    }
+
+    pub(super) fn kinds(doc: &Value) -> Vec<BlockKind> {
+        BlockTree::from_document_json("KRD-x", DOCUMENT_SCHEMA_VERSION, doc)
+            .unwrap()
+            .blocks
+            .iter()
+            .map(|b| b.kind)
+            .collect()
+    }
+
+    pub(super) fn assert_round_trip(format: OfficeFormat) {
+        let source = assets_with_image();
+        let export = export_office_document("Spec", &sample_tree(), format, &source).unwrap();
+        assert!(export
+            .warnings
+            .iter()
+            .any(|w| w.code == "typed_link_flattened"));
+
+        let mut imported_assets = MemoryAssets::default();
+        let outcome = import_office_document(&export.bytes, format, &mut imported_assets).unwrap();
+        let doc = &outcome.document_json;
+        assert_eq!(
+            kinds(doc),
+            vec![
+                BlockKind::Heading,
+                BlockKind::Heading,
+                BlockKind::Paragraph,
+                BlockKind::BulletList,
+                BlockKind::CodeBlock,
+                BlockKind::Table,
+                BlockKind::Image,
+                BlockKind::Paragraph,
+            ],
+            "{format:?}: {doc:#}"
+        );
+        // Title + heading levels.
+        assert_eq!(doc["content"][0]["attrs"]["level"], json!(1));
+        assert_eq!(doc["content"][1]["attrs"]["level"], json!(2));
+        // Inline marks and the comment survive.
+        let paragraph = &doc["content"][2];
+        assert_eq!(
+            paragraph["attrs"]["comments"][0],
+            json!({ "author": "Reviewer", "text": "Check this." })
+        );
+        let texts = paragraph["content"].as_array().unwrap();
+        assert!(texts
+            .iter()
+            .any(|t| t["text"] == json!("bold") && t["marks"] == json!([{ "type": "bold" }])));
+        assert!(texts.iter().any(|t| t["text"] == json!("a link")
+            && t["marks"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|m| m["attrs"]["href"] == json!("https://example.com"))));
+        // Nested ordered list under the first bullet item.
+        let list = &doc["content"][3];
+        assert_eq!(list["content"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            list["content"][0]["content"][1]["type"],
+            json!("orderedList")
+        );
+        // Code keeps its line structure.
+        assert_eq!(
+            doc["content"][4]["content"][0]["text"],
+            json!("fn main() {\n    run();\n}")
+        );
+        // Header row is typed.
+        assert_eq!(
+            doc["content"][5]["content"][0]["content"][0]["type"],
+            json!("tableHeader")
+        );
+        // The image went through the asset store and is a typed embed.
+        assert_eq!(imported_assets.assets.len(), 1);
+        assert_eq!(imported_assets.assets[0].bytes, png_bytes());
+        assert_eq!(doc["content"][6]["attrs"]["target"], json!("asset-1"));
+        assert_eq!(doc["content"][6]["attrs"]["alt"], json!("diagram"));
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        for format in [OfficeFormat::Docx, OfficeFormat::Odt] {
+            let assets = assets_with_image();
+            let a = export_office_document("Spec", &sample_tree(), format, &assets).unwrap();
+            let b = export_office_document("Spec", &sample_tree(), format, &assets).unwrap();
+            assert_eq!(a, b, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn malformed_archives_fail_with_typed_errors() {
+        assert!(matches!(
+            import_office_document(
+                b"not a zip",
+                OfficeFormat::Docx,
+                &mut MemoryAssets::default()
+            ),
+            Err(OfficeDocumentError::Archive(_))
+        ));
+        let empty = write_zip(&[("other.xml".to_string(), b"<x/>".to_vec(), false)]).unwrap();
+        assert!(matches!(
+            import_office_document(&empty, OfficeFormat::Odt, &mut MemoryAssets::default()),
+            Err(OfficeDocumentError::MissingPart(part)) if part == "content.xml"
+        ));
+        let broken = write_zip(&[(
+            "word/document.xml".to_string(),
+            b"<w:document".to_vec(),
+            false,
+        )])
+        .unwrap();
+        assert!(matches!(
+            import_office_document(&broken, OfficeFormat::Docx, &mut MemoryAssets::default()),
+            Err(OfficeDocumentError::Xml { .. })
+        ));
+    }
+
+    #[test]
+    fn archive_reads_are_capped_in_total_not_only_per_part() {
+        let zip = write_zip(&[
+            ("a.xml".to_string(), vec![b'a'; 10], false),
+            ("b.xml".to_string(), vec![b'b'; 10], false),
+        ])
+        .unwrap();
+        let mut archive = OfficeArchive::open_with_limits(&zip, 10, 15).unwrap();
+        assert_eq!(
+            archive.read_bytes("a.xml").unwrap().map(|b| b.len()),
+            Some(10)
+        );
+        assert!(matches!(
+            archive.read_bytes("b.xml"),
+            Err(OfficeDocumentError::ArchiveTooLarge { part, limit: 15 }) if part == "b.xml"
+        ));
+
+        let mut archive = OfficeArchive::open_with_limits(&zip, 5, 100).unwrap();
+        assert!(matches!(
+            archive.read_bytes("a.xml"),
+            Err(OfficeDocumentError::PartTooLarge { limit: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn imported_documents_load_as_block_trees_and_round_trip() {
+        for format in [OfficeFormat::Docx, OfficeFormat::Odt] {
+            let export =
+                export_office_document("Spec", &sample_tree(), format, &assets_with_image())
+                    .unwrap();
+            let outcome =
+                import_office_document(&export.bytes, format, &mut MemoryAssets::default())
+                    .unwrap();
+            let tree = BlockTree::from_document_json(
+                "KRD-x",
+                DOCUMENT_SCHEMA_VERSION,
+                &outcome.document_json,
+            )
+            .expect("imported office documents load as block trees");
+            assert_eq!(tree.to_document_json(), outcome.document_json);
+        }
+    }
+}
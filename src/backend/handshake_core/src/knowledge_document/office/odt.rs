@@ -0,0 +1,926 @@
+//! ODT (OpenDocument Text) import and export.
+
+use super::*;
+
+const CONTENT_PART: &str = "content.xml";
+const BULLET_LIST_STYLE: &str = "HskBulletList";
+const ORDERED_LIST_STYLE: &str = "HskOrderedList";
+
+#[derive(Debug, Clone, Default)]
+struct ParagraphStyle {
+    parent: Option<String>,
+    display_name: Option<String>,
+}
+
+struct Context {
+    paragraph_styles: HashMap<String, ParagraphStyle>,
+    text_styles: HashMap<String, Marks>,
+    /// list style name -> level-1 numbering?
+    list_styles: HashMap<String, bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParagraphRole {
+    Code,
+    Quote,
+    Title,
+    Body,
+}
+
+pub(super) fn read(
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Vec<OfficeBlock>, OfficeDocumentError> {
+    let content_xml = archive.require_text(CONTENT_PART)?;
+    let styles_xml = archive.read_text("styles.xml")?;
+    let content = parse_xml(CONTENT_PART, &content_xml)?;
+    let styles = match &styles_xml {
+        Some(xml) => Some(parse_xml("styles.xml", xml)?),
+        None => None,
+    };
+    let mut ctx = Context {
+        paragraph_styles: HashMap::new(),
+        text_styles: HashMap::new(),
+        list_styles: HashMap::new(),
+    };
+    if let Some(styles) = &styles {
+        collect_styles(styles.root_element(), &mut ctx);
+    }
+    collect_styles(content.root_element(), &mut ctx);
+
+    let text = content
+        .root_element()
+        .children()
+        .find(|n| is(*n, "body"))
+        .and_then(|body| child(body, "text"))
+        .ok_or_else(|| OfficeDocumentError::MissingPart("office:text".to_string()))?;
+    let mut blocks = Vec::new();
+    read_container(text, &ctx, archive, assets, warnings, &mut blocks)?;
+    Ok(blocks)
+}
+
+fn collect_styles(root: Node<'_, '_>, ctx: &mut Context) {
+    for container in root
+        .children()
+        .filter(|n| is(*n, "styles") || is(*n, "automatic-styles"))
+    {
+        for style in container.children().filter(|n| n.is_element()) {
+            let Some(name) = attr(style, "name") else {
+                continue;
+            };
+            match style.tag_name().name() {
+                "style" => match attr(style, "family") {
+                    Some("paragraph") => {
+                        ctx.paragraph_styles.insert(
+                            name.to_string(),
+                            ParagraphStyle {
+                                parent: attr(style, "parent-style-name").map(ToOwned::to_owned),
+                                display_name: attr(style, "display-name").map(ToOwned::to_owned),
+                            },
+                        );
+                    }
+                    Some("text") => {
+                        if let Some(props) = child(style, "text-properties") {
+                            let font = attr(props, "font-name").unwrap_or("");
+                            ctx.text_styles.insert(
+                                name.to_string(),
+                                Marks {
+                                    bold: attr(props, "font-weight") == Some("bold"),
+                                    italic: attr(props, "font-style") == Some("italic"),
+                                    underline: attr(props, "text-underline-style")
+                                        .map(|s| s != "none")
+                                        .unwrap_or(false),
+                                    code: is_monospace_font(font)
+                                        || name.to_ascii_lowercase().contains("source"),
+                                    link: None,
+                                },
+                            );
+                        }
+                    }
+                    _ => {}
+                },
+                "list-style" => {
+                    let ordered = style
+                        .children()
+                        .find(|n| n.is_element())
+                        .map(|first| is(first, "list-level-style-number"))
+                        .unwrap_or(false);
+                    ctx.list_styles.insert(name.to_string(), ordered);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Resolve a paragraph style through its parent chain to a role.
+fn paragraph_role(style_name: Option<&str>, ctx: &Context) -> ParagraphRole {
+    let mut current = style_name.map(ToOwned::to_owned);
+    for _ in 0..16 {
+        let Some(name) = current else {
+            break;
+        };
+        let display = ctx
+            .paragraph_styles
+            .get(&name)
+            .and_then(|s| s.display_name.clone())
+            .unwrap_or_else(|| name.replace("_20_", " "));
+        let lower = display.to_ascii_lowercase();
+        if lower.contains("preformatted") || lower.contains("code") || lower.contains("source text")
+        {
+            return ParagraphRole::Code;
+        }
+        if lower.contains("quotation") || lower.contains("quote") {
+            return ParagraphRole::Quote;
+        }
+        if lower == "title" {
+            return ParagraphRole::Title;
+        }
+        current = ctx
+            .paragraph_styles
+            .get(&name)
+            .and_then(|s| s.parent.clone());
+    }
+    ParagraphRole::Body
+}
+
+fn read_container(
+    node: Node<'_, '_>,
+    ctx: &Context,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    for child_node in node.children().filter(|n| n.is_element()) {
+        match child_node.tag_name().name() {
+            "h" => {
+                let level = attr(child_node, "outline-level")
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .unwrap_or(1);
+                let (runs, comments, images) = read_inline(child_node, ctx, warnings);
+                blocks.push(OfficeBlock::Heading {
+                    level: level.clamp(1, 6),
+                    runs,
+                    comments,
+                });
+                push_images(images, archive, assets, warnings, blocks)?;
+            }
+            "p" => read_paragraph(child_node, ctx, None, archive, assets, warnings, blocks)?,
+            "list" => read_list(child_node, ctx, None, 0, archive, assets, warnings, blocks)?,
+            "table" => blocks.push(read_table(child_node, ctx, warnings)),
+            "section" => read_container(child_node, ctx, archive, assets, warnings, blocks)?,
+            "table-of-content" | "alphabetical-index" | "bibliography" => warnings.push(
+                "generated_index_not_supported",
+                "Generated indexes (tables of contents, bibliographies) are not imported.",
+            ),
+            "tracked-changes" => warnings.push(
+                "tracked_changes_flattened",
+                "Tracked changes were flattened to the current document text.",
+            ),
+            "sequence-decls" | "variable-decls" | "user-field-decls" | "soft-page-break" => {}
+            other => warnings.push(
+                "unsupported_body_element",
+                &format!("Body element `{other}` is not supported and was skipped."),
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn read_paragraph(
+    p: Node<'_, '_>,
+    ctx: &Context,
+    list: Option<(bool, u32)>,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    let (runs, comments, images) = read_inline(p, ctx, warnings);
+    let has_text = runs.iter().any(|r| !r.text.is_empty());
+    if has_text || images.is_empty() {
+        let block = if let Some((ordered, level)) = list {
+            OfficeBlock::ListItem {
+                ordered,
+                level,
+                runs,
+                comments,
+            }
+        } else {
+            match paragraph_role(attr(p, "style-name"), ctx) {
+                ParagraphRole::Code => OfficeBlock::Code {
+                    text: runs_plain_text(&runs),
+                },
+                ParagraphRole::Quote => OfficeBlock::Quote { runs, comments },
+                ParagraphRole::Title => OfficeBlock::Heading {
+                    level: 1,
+                    runs,
+                    comments,
+                },
+                ParagraphRole::Body => OfficeBlock::Paragraph { runs, comments },
+            }
+        };
+        blocks.push(block);
+    }
+    push_images(images, archive, assets, warnings, blocks)
+}
+
+fn push_images(
+    images: Vec<(String, String)>,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    for (href, alt) in images {
+        if url_scheme(&href).is_some() {
+            warnings.push(
+                "external_image_not_imported",
+                "Linked (external) images are not fetched during import; they were skipped.",
+            );
+            continue;
+        }
+        let part = href.trim_start_matches("./").to_string();
+        if let Some(block) = import_image(archive, &part, &alt, assets, warnings)? {
+            blocks.push(block);
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_list(
+    list: Node<'_, '_>,
+    ctx: &Context,
+    inherited_ordered: Option<bool>,
+    level: u32,
+    archive: &mut OfficeArchive<'_>,
+    assets: &mut dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+    blocks: &mut Vec<OfficeBlock>,
+) -> Result<(), OfficeDocumentError> {
+    let ordered = attr(list, "style-name")
+        .and_then(|name| ctx.list_styles.get(name).copied())
+        .or(inherited_ordered)
+        .unwrap_or(false);
+    for item in list
+        .children()
+        .filter(|n| is(*n, "list-item") || is(*n, "list-header"))
+    {
+        for part in item.children().filter(|n| n.is_element()) {
+            match part.tag_name().name() {
+                "p" | "h" => read_paragraph(
+                    part,
+                    ctx,
+                    Some((ordered, level)),
+                    archive,
+                    assets,
+                    warnings,
+                    blocks,
+                )?,
+                "list" => read_list(
+                    part,
+                    ctx,
+                    Some(ordered),
+                    level + 1,
+                    archive,
+                    assets,
+                    warnings,
+                    blocks,
+                )?,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+type Inline = (Vec<Run>, Vec<Comment>, Vec<(String, String)>);
+
+fn read_inline(p: Node<'_, '_>, ctx: &Context, warnings: &mut WarningSink) -> Inline {
+    let mut runs = Vec::new();
+    let mut comments = Vec::new();
+    let mut images = Vec::new();
+    collect_inline(
+        p,
+        &Marks::default(),
+        ctx,
+        warnings,
+        &mut runs,
+        &mut comments,
+        &mut images,
+    );
+    (runs, comments, images)
+}
+
+fn collect_inline(
+    node: Node<'_, '_>,
+    marks: &Marks,
+    ctx: &Context,
+    warnings: &mut WarningSink,
+    runs: &mut Vec<Run>,
+    comments: &mut Vec<Comment>,
+    images: &mut Vec<(String, String)>,
+) {
+    for child_node in node.children() {
+        if child_node.is_text() {
+            runs.push(Run {
+                text: child_node.text().unwrap_or("").to_string(),
+                marks: marks.clone(),
+            });
+            continue;
+        }
+        if !child_node.is_element() {
+            continue;
+        }
+        match child_node.tag_name().name() {
+            "span" => {
+                let mut span_marks = marks.clone();
+                if let Some(style) =
+                    attr(child_node, "style-name").and_then(|name| ctx.text_styles.get(name))
+                {
+                    span_marks.bold |= style.bold;
+                    span_marks.italic |= style.italic;
+                    span_marks.underline |= style.underline;
+                    span_marks.code |= style.code;
+                }
+                collect_inline(
+                    child_node,
+                    &span_marks,
+                    ctx,
+                    warnings,
+                    runs,
+                    comments,
+                    images,
+                );
+            }
+            "a" => {
+                let mut link_marks = marks.clone();
+                if let Some(href) = attr(child_node, "href").and_then(safe_link) {
+                    link_marks.link = Some(href);
+                }
+                collect_inline(
+                    child_node,
+                    &link_marks,
+                    ctx,
+                    warnings,
+                    runs,
+                    comments,
+                    images,
+                );
+            }
+            "s" => {
+                let count = attr(child_node, "c")
+                    .and_then(|c| c.parse::<usize>().ok())
+                    .unwrap_or(1)
+                    .min(1024);
+                runs.push(Run {
+                    text: " ".repeat(count),
+                    marks: marks.clone(),
+                });
+            }
+            "tab" => runs.push(Run {
+                text: "\t".to_string(),
+                marks: marks.clone(),
+            }),
+            "line-break" => runs.push(Run {
+                text: "\n".to_string(),
+                marks: marks.clone(),
+            }),
+            "annotation" => {
+                let author = child(child_node, "creator")
+                    .and_then(|c| c.text())
+                    .unwrap_or("")
+                    .to_string();
+                let text = child_node
+                    .children()
+                    .filter(|n| is(*n, "p"))
+                    .map(|p| {
+                        p.descendants()
+                            .filter(|n| n.is_text())
+                            .filter_map(|n| n.text())
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                comments.push(Comment { author, text });
+            }
+            "frame" => {
+                let alt = child(child_node, "desc")
+                    .or_else(|| child(child_node, "title"))
+                    .and_then(|d| d.text())
+                    .unwrap_or("")
+                    .to_string();
+                match child(child_node, "image").and_then(|img| attr(img, "href")) {
+                    Some(href) => images.push((href.to_string(), alt)),
+                    None => warnings.push(
+                        "drawing_shape_not_supported",
+                        "Drawing shapes and text boxes are not imported.",
+                    ),
+                }
+            }
+            "note" => warnings.push(
+                "footnote_not_supported",
+                "Footnotes are not imported; the reference marks were dropped.",
+            ),
+            "custom-shape" | "rect" | "line" | "ellipse" | "g" => warnings.push(
+                "drawing_shape_not_supported",
+                "Drawing shapes and text boxes are not imported.",
+            ),
+            "change-start" | "change-end" | "change" => warnings.push(
+                "tracked_changes_flattened",
+                "Tracked changes were flattened to the current document text.",
+            ),
+            "annotation-end" | "bookmark" | "bookmark-start" | "bookmark-end"
+            | "soft-page-break" | "reference-mark" => {}
+            _ => collect_inline(child_node, marks, ctx, warnings, runs, comments, images),
+        }
+    }
+}
+
+fn read_table(table: Node<'_, '_>, ctx: &Context, warnings: &mut WarningSink) -> OfficeBlock {
+    let mut rows = Vec::new();
+    let mut comments = Vec::new();
+    let mut push_row = |row: Node<'_, '_>, header: bool, rows: &mut Vec<TableRow>| {
+        let mut cells = Vec::new();
+        for cell in row.children().filter(|n| is(*n, "table-cell")) {
+            let colspan = attr(cell, "number-columns-spanned")
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(1);
+            if attr(cell, "number-rows-spanned")
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(1)
+                > 1
+            {
+                warnings.push(
+                    "vertical_merge_flattened",
+                    "Vertically merged table cells were imported as separate cells.",
+                );
+            }
+            let mut paragraphs = Vec::new();
+            for part in cell.children().filter(|n| n.is_element()) {
+                match part.tag_name().name() {
+                    "p" | "h" => {
+                        let (runs, cell_comments, images) = read_inline(part, ctx, warnings);
+                        if !images.is_empty() {
+                            warnings.push(
+                                "image_in_table_not_supported",
+                                "Images inside table cells are not imported.",
+                            );
+                        }
+                        comments.extend(cell_comments);
+                        paragraphs.push(runs);
+                    }
+                    "table" => {
+                        warnings.push(
+                            "nested_table_flattened",
+                            "Nested tables were flattened into their cell text.",
+                        );
+                        let text = part
+                            .descendants()
+                            .filter(|n| n.is_text())
+                            .filter_map(|n| n.text())
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        paragraphs.push(plain_runs(&text));
+                    }
+                    _ => {}
+                }
+            }
+            cells.push(TableCell {
+                colspan,
+                paragraphs,
+            });
+        }
+        rows.push(TableRow { header, cells });
+    };
+    for part in table.children().filter(|n| n.is_element()) {
+        match part.tag_name().name() {
+            "table-header-rows" => {
+                for row in part.children().filter(|n| is(*n, "table-row")) {
+                    push_row(row, true, &mut rows);
+                }
+            }
+            "table-row" => push_row(part, false, &mut rows),
+            "table-rows" => {
+                for row in part.children().filter(|n| is(*n, "table-row")) {
+                    push_row(row, false, &mut rows);
+                }
+            }
+            _ => {}
+        }
+    }
+    OfficeBlock::Table { rows, comments }
+}
+
+// --- export -----------------------------------------------------------
+
+fn text_style_name(marks: &Marks) -> Option<String> {
+    let mut name = String::new();
+    if marks.bold {
+        name.push('B');
+    }
+    if marks.italic {
+        name.push('I');
+    }
+    if marks.underline {
+        name.push('U');
+    }
+    if marks.code {
+        name.push('C');
+    }
+    if name.is_empty() {
+        None
+    } else {
+        Some(format!("HskText{name}"))
+    }
+}
+
+struct Writer<'w> {
+    body: String,
+    text_styles: BTreeMap<String, Marks>,
+    images: Vec<&'w ExportImage>,
+}
+
+impl<'w> Writer<'w> {
+    fn text(&mut self, text: &str) {
+        let mut first = true;
+        for line in text.split('\n') {
+            if !first {
+                self.body.push_str("<text:line-break/>");
+            }
+            first = false;
+            let mut first_segment = true;
+            for segment in line.split('\t') {
+                if !first_segment {
+                    self.body.push_str("<text:tab/>");
+                }
+                first_segment = false;
+                self.spaces(segment);
+            }
+        }
+    }
+
+    /// ODF collapses runs of spaces; repeated spaces become `text:s`.
+    fn spaces(&mut self, segment: &str) {
+        let mut pending_spaces = 0usize;
+        let mut at_start = true;
+        for ch in segment.chars() {
+            if ch == ' ' {
+                pending_spaces += 1;
+                continue;
+            }
+            self.flush_spaces(pending_spaces, at_start);
+            pending_spaces = 0;
+            at_start = false;
+            self.body.push_str(&xml_escape(&ch.to_string()));
+        }
+        self.flush_spaces(pending_spaces, at_start);
+    }
+
+    fn flush_spaces(&mut self, count: usize, at_start: bool) {
+        match count {
+            0 => {}
+            1 if !at_start => self.body.push(' '),
+            n if at_start => self.body.push_str(&format!("<text:s text:c=\"{n}\"/>")),
+            n => {
+                self.body.push(' ');
+                if n > 2 {
+                    self.body
+                        .push_str(&format!("<text:s text:c=\"{}\"/>", n - 1));
+                } else {
+                    self.body.push_str("<text:s/>");
+                }
+            }
+        }
+    }
+
+    fn runs(&mut self, runs: &[Run]) {
+        for run in runs {
+            if run.text.is_empty() {
+                continue;
+            }
+            if let Some(href) = &run.marks.link {
+                self.body.push_str(&format!(
+                    "<text:a xlink:type=\"simple\" xlink:href=\"{}\">",
+                    xml_escape(href)
+                ));
+            }
+            let style = text_style_name(&run.marks);
+            if let Some(style) = &style {
+                self.text_styles
+                    .entry(style.clone())
+                    .or_insert_with(|| run.marks.clone());
+                self.body
+                    .push_str(&format!("<text:span text:style-name=\"{style}\">"));
+            }
+            self.text(&run.text);
+            if style.is_some() {
+                self.body.push_str("</text:span>");
+            }
+            if run.marks.link.is_some() {
+                self.body.push_str("</text:a>");
+            }
+        }
+    }
+
+    fn annotations(&mut self, comments: &[Comment]) {
+        for comment in comments {
+            self.body.push_str(&format!(
+                "<office:annotation><dc:creator>{}</dc:creator>",
+                xml_escape(&comment.author)
+            ));
+            for line in comment.text.split('\n') {
+                self.body
+                    .push_str(&format!("<text:p>{}</text:p>", xml_escape(line)));
+            }
+            self.body.push_str("</office:annotation>");
+        }
+    }
+
+    fn paragraph(&mut self, style: &str, runs: &[Run], comments: &[Comment]) {
+        self.body
+            .push_str(&format!("<text:p text:style-name=\"{style}\">"));
+        self.annotations(comments);
+        self.runs(runs);
+        self.body.push_str("</text:p>");
+    }
+
+    fn list(&mut self, items: &[ExportListItem], comments: &[Comment]) {
+        // Rebuild nesting from depths: open one text:list per level.
+        let mut open: Vec<u32> = Vec::new();
+        let mut pending = comments;
+        for item in items {
+            while open.last().is_some_and(|depth| *depth > item.depth) {
+                self.body.push_str("</text:list-item></text:list>");
+                open.pop();
+            }
+            if open.last() == Some(&item.depth) {
+                self.body.push_str("</text:list-item><text:list-item>");
+            } else {
+                while open.last().map(|d| *d < item.depth).unwrap_or(true) {
+                    let depth = open.last().map(|d| d + 1).unwrap_or(0);
+                    let style = if item.ordered {
+                        ORDERED_LIST_STYLE
+                    } else {
+                        BULLET_LIST_STYLE
+                    };
+                    if depth == 0 || open.is_empty() {
+                        self.body.push_str(&format!(
+                            "<text:list text:style-name=\"{style}\"><text:list-item>"
+                        ));
+                    } else {
+                        self.body.push_str(&format!(
+                            "<text:list text:style-name=\"{style}\"><text:list-item>"
+                        ));
+                    }
+                    open.push(depth);
+                    if depth >= item.depth {
+                        break;
+                    }
+                }
+            }
+            self.paragraph("Standard", &item.runs, pending);
+            pending = &[];
+        }
+        while open.pop().is_some() {
+            self.body.push_str("</text:list-item></text:list>");
+        }
+    }
+
+    fn table(&mut self, index: usize, rows: &[TableRow], comments: &[Comment]) {
+        let columns = rows
+            .iter()
+            .map(|r| r.cells.iter().map(|c| c.colspan).sum::<u32>())
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        self.body.push_str(&format!(
+                "<table:table table:name=\"Table{}\"><table:table-column table:number-columns-repeated=\"{columns}\"/>",
+                index + 1
+            ));
+        let mut pending = comments;
+        let mut in_header = false;
+        for row in rows {
+            if row.header && !in_header {
+                self.body.push_str("<table:table-header-rows>");
+                in_header = true;
+            } else if !row.header && in_header {
+                self.body.push_str("</table:table-header-rows>");
+                in_header = false;
+            }
+            self.body.push_str("<table:table-row>");
+            for cell in &row.cells {
+                if cell.colspan > 1 {
+                    self.body.push_str(&format!(
+                            "<table:table-cell table:number-columns-spanned=\"{}\" office:value-type=\"string\">",
+                            cell.colspan
+                        ));
+                } else {
+                    self.body
+                        .push_str("<table:table-cell office:value-type=\"string\">");
+                }
+                if cell.paragraphs.is_empty() {
+                    self.paragraph("Standard", &[], pending);
+                    pending = &[];
+                }
+                for paragraph in &cell.paragraphs {
+                    self.paragraph("Standard", paragraph, pending);
+                    pending = &[];
+                }
+                self.body.push_str("</table:table-cell>");
+                for _ in 1..cell.colspan {
+                    self.body.push_str("<table:covered-table-cell/>");
+                }
+            }
+            self.body.push_str("</table:table-row>");
+        }
+        if in_header {
+            self.body.push_str("</table:table-header-rows>");
+        }
+        self.body.push_str("</table:table>");
+    }
+
+    fn image(&mut self, image: &'w ExportImage) {
+        self.images.push(image);
+        let (cx, cy) = image_extent_emu(image);
+        // 914400 EMU per inch.
+        let width_in = cx as f64 / 914_400.0;
+        let height_in = cy as f64 / 914_400.0;
+        self.body.push_str(&format!(
+                "<text:p text:style-name=\"Standard\"><draw:frame draw:name=\"Image{}\" text:anchor-type=\"as-char\" \
+svg:width=\"{width_in:.3}in\" svg:height=\"{height_in:.3}in\"><draw:image xlink:href=\"Pictures/{}\" \
+xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"/><svg:desc>{}</svg:desc></draw:frame></text:p>",
+                self.images.len(),
+                image.part_name,
+                xml_escape(&image.alt)
+            ));
+    }
+}
+
+const NAMESPACES: &str = "xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+xmlns:style=\"urn:oasis:names:tc:opendocument:xmlns:style:1.0\" \
+xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+xmlns:draw=\"urn:oasis:names:tc:opendocument:xmlns:drawing:1.0\" \
+xmlns:fo=\"urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0\" \
+xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+xmlns:svg=\"urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0\" office:version=\"1.3\"";
+
+pub(super) fn write(
+    title: &str,
+    tree: &BlockTree,
+    assets: &dyn OfficeAssetStore,
+    warnings: &mut WarningSink,
+) -> Result<Vec<u8>, OfficeDocumentError> {
+    let blocks = export_blocks(tree, assets, warnings)?;
+    let mut writer = Writer {
+        body: String::new(),
+        text_styles: BTreeMap::new(),
+        images: Vec::new(),
+    };
+    writer.paragraph("Title", &plain_runs(title), &[]);
+    let mut table_index = 0;
+    for (block, comments) in &blocks {
+        match block {
+            ExportBlock::Paragraph(runs) => writer.paragraph("Standard", runs, comments),
+            ExportBlock::Heading(level, runs) => {
+                writer.body.push_str(&format!(
+                        "<text:h text:style-name=\"Heading_20_{level}\" text:outline-level=\"{level}\">"
+                    ));
+                writer.annotations(comments);
+                writer.runs(runs);
+                writer.body.push_str("</text:h>");
+            }
+            ExportBlock::Quote(runs) => writer.paragraph("Quotations", runs, comments),
+            ExportBlock::Code(text) => {
+                writer.paragraph("Preformatted_20_Text", &plain_runs(text), comments)
+            }
+            ExportBlock::List(items) => writer.list(items, comments),
+            ExportBlock::Table(rows) => {
+                writer.table(table_index, rows, comments);
+                table_index += 1;
+            }
+            ExportBlock::Image(image) => writer.image(image),
+        }
+    }
+
+    let mut automatic = String::new();
+    for (name, marks) in &writer.text_styles {
+        automatic.push_str(&format!(
+            "<style:style style:name=\"{name}\" style:family=\"text\"><style:text-properties"
+        ));
+        if marks.bold {
+            automatic.push_str(" fo:font-weight=\"bold\"");
+        }
+        if marks.italic {
+            automatic.push_str(" fo:font-style=\"italic\"");
+        }
+        if marks.underline {
+            automatic.push_str(" style:text-underline-style=\"solid\"");
+        }
+        if marks.code {
+            automatic.push_str(" style:font-name=\"Liberation Mono\"");
+        }
+        automatic.push_str("/></style:style>");
+    }
+    for (name, numbered) in [(BULLET_LIST_STYLE, false), (ORDERED_LIST_STYLE, true)] {
+        automatic.push_str(&format!("<text:list-style style:name=\"{name}\">"));
+        for level in 1..=10 {
+            if numbered {
+                automatic.push_str(&format!(
+                        "<text:list-level-style-number text:level=\"{level}\" style:num-format=\"1\" style:num-suffix=\".\"/>"
+                    ));
+            } else {
+                automatic.push_str(&format!(
+                        "<text:list-level-style-bullet text:level=\"{level}\" text:bullet-char=\"\u{2022}\"/>"
+                    ));
+            }
+        }
+        automatic.push_str("</text:list-style>");
+    }
+
+    let content = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><office:document-content {NAMESPACES}>\
+<office:automatic-styles>{automatic}</office:automatic-styles>\
+<office:body><office:text>{}</office:text></office:body></office:document-content>",
+        writer.body
+    );
+
+    let mut manifest = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<manifest:manifest xmlns:manifest=\"urn:oasis:names:tc:opendocument:xmlns:manifest:1.0\" manifest:version=\"1.3\">\
+<manifest:file-entry manifest:full-path=\"/\" manifest:media-type=\"application/vnd.oasis.opendocument.text\"/>\
+<manifest:file-entry manifest:full-path=\"content.xml\" manifest:media-type=\"text/xml\"/>\
+<manifest:file-entry manifest:full-path=\"styles.xml\" manifest:media-type=\"text/xml\"/>",
+        );
+    for image in &writer.images {
+        manifest.push_str(&format!(
+            "<manifest:file-entry manifest:full-path=\"Pictures/{}\" manifest:media-type=\"{}\"/>",
+            image.part_name,
+            xml_escape(&image.asset.mime)
+        ));
+    }
+    manifest.push_str("</manifest:manifest>");
+
+    let mut entries: Vec<(String, Vec<u8>, bool)> = vec![
+        (
+            "mimetype".to_string(),
+            ODT_MIMETYPE.as_bytes().to_vec(),
+            true,
+        ),
+        (
+            "META-INF/manifest.xml".to_string(),
+            manifest.into_bytes(),
+            false,
+        ),
+        (CONTENT_PART.to_string(), content.into_bytes(), false),
+        ("styles.xml".to_string(), styles_xml().into_bytes(), false),
+    ];
+    for image in &writer.images {
+        entries.push((
+            format!("Pictures/{}", image.part_name),
+            image.asset.bytes.clone(),
+            true,
+        ));
+    }
+    write_zip(&entries)
+}
+
+fn styles_xml() -> String {
+    let mut styles = String::from(
+            "<style:style style:name=\"Standard\" style:family=\"paragraph\"/>\
+<style:style style:name=\"Title\" style:family=\"paragraph\" style:parent-style-name=\"Standard\">\
+<style:text-properties fo:font-size=\"24pt\" fo:font-weight=\"bold\"/></style:style>\
+<style:style style:name=\"Quotations\" style:family=\"paragraph\" style:parent-style-name=\"Standard\">\
+<style:paragraph-properties fo:margin-left=\"0.4in\"/><style:text-properties fo:font-style=\"italic\"/></style:style>\
+<style:style style:name=\"Preformatted_20_Text\" style:display-name=\"Preformatted Text\" style:family=\"paragraph\" \
+style:parent-style-name=\"Standard\"><style:text-properties style:font-name=\"Liberation Mono\"/></style:style>",
+        );
+    for level in 1..=6 {
+        styles.push_str(&format!(
+                "<style:style style:name=\"Heading_20_{level}\" style:display-name=\"Heading {level}\" \
+style:family=\"paragraph\" style:parent-style-name=\"Standard\" style:default-outline-level=\"{level}\">\
+<style:text-properties fo:font-size=\"{}pt\" fo:font-weight=\"bold\"/></style:style>",
+                20 - level * 2
+            ));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><office:document-styles {NAMESPACES}>\
+<office:styles>{styles}</office:styles></office:document-styles>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_document::office::tests::assert_round_trip;
+
+    #[test]
+    fn odt_round_trip_preserves_structure_images_and_comments() {
+        assert_round_trip(OfficeFormat::Odt);
+    }
+}
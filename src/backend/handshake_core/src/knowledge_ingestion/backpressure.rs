@@ -19,7 +19,8 @@ use super::kinds::IngestionSourceKind;
 pub struct IngestionLimits {
     /// Default per-file byte ceiling.
     pub max_bytes: u64,
-    /// Byte ceiling for PDFs (binary, page-structured: higher).
+    /// Byte ceiling for PDFs (binary, page-structured: higher). EPUBs and
+    /// notebooks share it: both carry embedded images around their text.
     pub max_pdf_bytes: u64,
     /// Line ceiling for text sources.
     pub max_lines: u64,
@@ -76,7 +77,9 @@ impl IngestionLimits {
     /// Byte ceiling for a kind.
     pub fn byte_limit_for(&self, kind: IngestionSourceKind) -> u64 {
         match kind {
-            IngestionSourceKind::Pdf => self.max_pdf_bytes,
+            IngestionSourceKind::Pdf
+            | IngestionSourceKind::Ebook
+            | IngestionSourceKind::JupyterNotebook => self.max_pdf_bytes,
             _ => self.max_bytes,
         }
     }
@@ -136,10 +139,13 @@ mod tests {
         assert_eq!(deferral.reason, BackpressureReason::OversizeBytes);
         assert_eq!(deferral.limit, 2 * 1024 * 1024);
         assert_eq!(deferral.actual, 3 * 1024 * 1024);
-        // A 3 MiB PDF is fine.
+        // A 3 MiB PDF is fine, and so is a notebook with embedded plots.
         assert!(limits
             .check_size(IngestionSourceKind::Pdf, 3 * 1024 * 1024)
             .is_ok());
+        assert!(limits
+            .check_size(IngestionSourceKind::JupyterNotebook, 3 * 1024 * 1024)
+            .is_ok());
     }
 
     #[test]
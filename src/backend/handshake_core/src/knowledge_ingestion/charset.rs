@@ -0,0 +1,104 @@
+//! Text decoding for formats that declare their own charset: saved web pages
+//! (`<meta charset>`) and email (per-part `Content-Type; charset=`).
+//!
+//! Only the encodings these artifacts realistically carry are decoded
+//! exactly: UTF-8, UTF-16 (by BOM), and the Latin-1 family. Latin-1 labels
+//! decode as windows-1252, as browsers do, since real "ISO-8859-1" text uses
+//! the 0x80..0x9F range for curly quotes and dashes. An unlabeled or unknown
+//! charset is UTF-8 when the bytes are valid UTF-8 and windows-1252 otherwise,
+//! so no byte is ever turned into U+FFFD.
+
+/// windows-1252 code points for 0x80..=0x9F; the five unassigned bytes map
+/// to the matching C1 control, as in the WHATWG table.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+pub fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(byte - 0x80)],
+            _ => char::from(byte),
+        })
+        .collect()
+}
+
+/// UTF-8 when valid, windows-1252 otherwise.
+pub fn decode_unlabeled(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => decode_windows_1252(bytes),
+    }
+}
+
+/// Decode `bytes` declared as `label` (case-insensitive, surrounding quotes
+/// and whitespace ignored).
+pub fn decode_labeled(bytes: &[u8], label: &str) -> String {
+    let label = label.trim().trim_matches(['"', '\'']).to_ascii_lowercase();
+    match label.as_str() {
+        "utf-8" | "utf8" | "unicode-1-1-utf-8" => String::from_utf8_lossy(bytes).into_owned(),
+        "iso-8859-1" | "iso8859-1" | "latin1" | "latin-1" | "l1" | "windows-1252" | "cp1252"
+        | "x-cp1252" | "us-ascii" | "ascii" => decode_windows_1252(bytes),
+        "utf-16le" | "utf-16" => decode_utf16(bytes, u16::from_le_bytes),
+        "utf-16be" => decode_utf16(bytes, u16::from_be_bytes),
+        _ => decode_unlabeled(bytes),
+    }
+}
+
+/// Strip a byte-order mark and decode what it names; `None` without one.
+pub fn decode_bom(bytes: &[u8]) -> Option<String> {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return Some(String::from_utf8_lossy(rest).into_owned());
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return Some(decode_utf16(rest, u16::from_le_bytes));
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        return Some(decode_utf16(rest, u16::from_be_bytes));
+    }
+    None
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin1_labels_decode_as_windows_1252() {
+        let bytes = b"\x93Caf\xe9\x94 \x96 5\x80";
+        assert_eq!(
+            decode_labeled(bytes, "ISO-8859-1"),
+            "\u{201C}Café\u{201D} \u{2013} 5€"
+        );
+        assert_eq!(
+            decode_labeled(bytes, "\"windows-1252\""),
+            decode_windows_1252(bytes)
+        );
+    }
+
+    #[test]
+    fn unlabeled_bytes_prefer_utf8_then_fall_back_without_replacement() {
+        assert_eq!(decode_unlabeled("Café".as_bytes()), "Café");
+        assert_eq!(decode_unlabeled(b"Caf\xe9"), "Café");
+        assert_eq!(decode_labeled(b"Caf\xe9", "x-unknown"), "Café");
+    }
+
+    #[test]
+    fn byte_order_marks_pick_the_encoding() {
+        assert_eq!(decode_bom(b"\xEF\xBB\xBFhi").as_deref(), Some("hi"));
+        assert_eq!(decode_bom(b"\xFF\xFEh\x00i\x00").as_deref(), Some("hi"));
+        assert_eq!(decode_bom(b"\xFE\xFF\x00h\x00i").as_deref(), Some("hi"));
+        assert_eq!(decode_bom(b"hi"), None);
+    }
+}
@@ -0,0 +1,692 @@
+//! Email ingestion: single messages (`.eml`, RFC 5322) and mailboxes
+//! (`.mbox`, mboxrd/mboxo) into one span per message.
+//!
+//! Anchor model (`email_message`): every span names its `Message-ID`, the
+//! thread it belongs to, and its parent. The thread id is the first
+//! `References` entry (the thread root by RFC 5322 convention), falling back
+//! to `In-Reply-To`, then the message's own id — so replies from the same
+//! conversation share a thread id across separately ingested files. A message
+//! without a `Message-ID` gets a content-derived id (`sha256:<hex16>`) so its
+//! anchor is still stable across re-ingestion.
+//!
+//! MIME handling is deliberately small: `multipart/*` is walked recursively,
+//! `text/plain` parts win over `text/html` (which goes through the web page
+//! text splitter), attachments are listed by name but never decoded into
+//! spans, and `base64` / `quoted-printable` transfer encodings plus RFC 2047
+//! encoded-word headers are decoded.
+//!
+//! Charsets: the artifact is read as bytes, and a mailbox routinely mixes
+//! messages in different charsets (an old Latin-1 message beside a UTF-8
+//! one), so nothing decodes the file as a whole. The structure (headers,
+//! boundaries, `From ` lines) is ASCII and is parsed over a byte-preserving
+//! view; each text part is decoded with its own `charset=` and raw 8-bit
+//! header text as UTF-8 falling back to windows-1252 ([`super::charset`]).
+//!
+//! Malformed-message policy: a mailbox entry with no header block is recorded
+//! as a typed [`MalformedMessage`] and parsing CONTINUES — the engine writes a
+//! `partial` receipt naming every lost message, never a silent success. A
+//! file with no well-formed message at all is a `PARSE_ERROR` failure.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::charset::{decode_labeled, decode_unlabeled};
+use super::receipts::IngestionErrorClass;
+use super::spans::{ExtractedSpan, SpanAnchor};
+use super::web::extract_html_sections;
+
+/// Email artifact format.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailFormat {
+    Eml,
+    Mbox,
+}
+
+impl EmailFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eml => "eml",
+            Self::Mbox => "mbox",
+        }
+    }
+}
+
+/// One well-formed message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EmailMessage {
+    /// 0-based message index in the artifact.
+    pub index: u32,
+    pub message_id: String,
+    pub thread_id: String,
+    pub in_reply_to: Option<String>,
+    pub subject: Option<String>,
+    pub from: Option<String>,
+    pub date: Option<String>,
+    /// Decoded body text (preferred text part).
+    pub body: String,
+    /// Attachment file names (never decoded into spans).
+    pub attachments: Vec<String>,
+}
+
+impl EmailMessage {
+    /// Stored span content: the citation-relevant headers, then the body.
+    pub fn span_content(&self) -> String {
+        let mut content = String::new();
+        for (label, value) in [
+            ("Subject", &self.subject),
+            ("From", &self.from),
+            ("Date", &self.date),
+        ] {
+            if let Some(value) = value {
+                content.push_str(&format!("{label}: {value}\n"));
+            }
+        }
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&self.body);
+        content
+    }
+}
+
+/// One mailbox entry the parser had to drop, with a typed reason.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MalformedMessage {
+    /// 1-based line where the entry starts.
+    pub line: u32,
+    pub reason: String,
+}
+
+/// Parse outcome: messages + losses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmailParse {
+    pub format: EmailFormat,
+    pub messages: Vec<EmailMessage>,
+    pub malformed: Vec<MalformedMessage>,
+}
+
+impl EmailParse {
+    /// Distinct thread count, for receipt detail.
+    pub fn thread_count(&self) -> usize {
+        let mut threads: Vec<&str> = self.messages.iter().map(|m| m.thread_id.as_str()).collect();
+        threads.sort_unstable();
+        threads.dedup();
+        threads.len()
+    }
+
+    /// `email_message` spans in artifact order.
+    pub fn spans(&self) -> Vec<ExtractedSpan> {
+        self.messages
+            .iter()
+            .map(|message| {
+                ExtractedSpan::new(
+                    SpanAnchor::EmailMessage {
+                        message_index: message.index,
+                        message_id: message.message_id.clone(),
+                        thread_id: message.thread_id.clone(),
+                        in_reply_to: message.in_reply_to.clone(),
+                    },
+                    message.span_content(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Typed whole-file parse failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmailParseError {
+    pub class: IngestionErrorClass,
+    pub detail: String,
+}
+
+/// Dispatch by extension: `.eml` / `.mbox`.
+pub fn parse_email_artifact(
+    relative_path: &str,
+    bytes: &[u8],
+) -> Result<EmailParse, EmailParseError> {
+    let lower = relative_path.to_ascii_lowercase();
+    if lower.ends_with(".eml") {
+        parse_eml(bytes)
+    } else if lower.ends_with(".mbox") {
+        parse_mbox(bytes)
+    } else {
+        Err(EmailParseError {
+            class: IngestionErrorClass::UnsupportedFormat,
+            detail: format!("unknown email artifact extension: {relative_path}"),
+        })
+    }
+}
+
+/// Byte-preserving view: byte `b` becomes `char::from(b)`, so the ASCII
+/// structure parses as text and [`raw_bytes`] recovers the original bytes of
+/// any slice for decoding with the right charset.
+fn byte_view(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// Inverse of [`byte_view`] for a slice of it.
+fn raw_bytes(view: &str) -> Vec<u8> {
+    view.chars()
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Parse a single RFC 5322 message.
+pub fn parse_eml(bytes: &[u8]) -> Result<EmailParse, EmailParseError> {
+    let text = byte_view(bytes);
+    match parse_message(&text, 0) {
+        Ok(message) => Ok(EmailParse {
+            format: EmailFormat::Eml,
+            messages: vec![message],
+            malformed: Vec::new(),
+        }),
+        Err(reason) => Err(EmailParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: format!("not an RFC 5322 message: {reason}"),
+        }),
+    }
+}
+
+/// Parse an mbox mailbox. Entries start at `From ` lines at the top of the
+/// file or after a blank line; `>From ` escapes are undone in bodies.
+pub fn parse_mbox(bytes: &[u8]) -> Result<EmailParse, EmailParseError> {
+    let text = byte_view(bytes);
+    if !text.starts_with("From ") {
+        return Err(EmailParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: "not an mbox mailbox: first line is not a 'From ' separator".to_string(),
+        });
+    }
+
+    let mut entries: Vec<(u32, Vec<&str>)> = Vec::new();
+    let mut previous_blank = true;
+    for (idx, line) in text.lines().enumerate() {
+        if previous_blank && line.starts_with("From ") {
+            entries.push(((idx + 1) as u32, Vec::new()));
+        } else if let Some((_, lines)) = entries.last_mut() {
+            lines.push(line);
+        }
+        previous_blank = line.trim().is_empty();
+    }
+
+    let mut messages = Vec::new();
+    let mut malformed = Vec::new();
+    for (line, lines) in entries {
+        let raw = lines
+            .iter()
+            .map(|line| unescape_from_line(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match parse_message(&raw, messages.len() as u32) {
+            Ok(message) => messages.push(message),
+            Err(reason) => malformed.push(MalformedMessage { line, reason }),
+        }
+    }
+
+    if messages.is_empty() {
+        return Err(EmailParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: format!(
+                "no well-formed message in mbox ({} malformed)",
+                malformed.len()
+            ),
+        });
+    }
+    Ok(EmailParse {
+        format: EmailFormat::Mbox,
+        messages,
+        malformed,
+    })
+}
+
+/// mboxrd quoting: `>From ` / `>>From ` lose one `>`.
+fn unescape_from_line(line: &str) -> &str {
+    let quotes = line.bytes().take_while(|b| *b == b'>').count();
+    if quotes > 0 && line[quotes..].starts_with("From ") {
+        &line[1..]
+    } else {
+        line
+    }
+}
+
+/// Unfolded header block + raw body.
+struct MimeEntity<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a str,
+}
+
+impl MimeEntity<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Split a header block (RFC 5322 unfolding) from the body.
+fn split_entity(raw: &str) -> MimeEntity<'_> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut offset = 0usize;
+    for line in raw.split_inclusive('\n') {
+        offset += line.len();
+        let content = line.trim_end_matches(['\r', '\n']);
+        if content.is_empty() {
+            return MimeEntity {
+                headers,
+                body: &raw[offset..],
+            };
+        }
+        if content.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(content.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = content.split_once(':') {
+            let name = name.trim();
+            if !name.is_empty() && !name.contains(' ') {
+                headers.push((name.to_string(), value.trim().to_string()));
+            }
+        }
+    }
+    MimeEntity { headers, body: "" }
+}
+
+fn parse_message(raw: &str, index: u32) -> Result<EmailMessage, String> {
+    let entity = split_entity(raw);
+    if entity.headers.is_empty() {
+        return Err("entry has no header block".to_string());
+    }
+    let has_address_header = ["From", "Message-ID", "Subject", "Date"]
+        .iter()
+        .any(|name| entity.header(name).is_some());
+    if !has_address_header {
+        return Err("header block has no From/Message-ID/Subject/Date".to_string());
+    }
+
+    let message_id = entity
+        .header("Message-ID")
+        .and_then(|value| message_ids(value).into_iter().next())
+        .unwrap_or_else(|| {
+            let digest = Sha256::digest(raw_bytes(raw));
+            format!("sha256:{}", &hex::encode(digest)[..16])
+        });
+    let in_reply_to = entity
+        .header("In-Reply-To")
+        .and_then(|value| message_ids(value).into_iter().next());
+    let thread_id = entity
+        .header("References")
+        .and_then(|value| message_ids(value).into_iter().next())
+        .or_else(|| in_reply_to.clone())
+        .unwrap_or_else(|| message_id.clone());
+
+    let mut attachments = Vec::new();
+    let body = entity_text(&entity, &mut attachments, 0).unwrap_or_default();
+    Ok(EmailMessage {
+        index,
+        message_id,
+        thread_id,
+        in_reply_to,
+        subject: entity.header("Subject").map(decode_encoded_words),
+        from: entity.header("From").map(decode_encoded_words),
+        date: entity.header("Date").map(decode_header_text),
+        body: body.replace("\r\n", "\n").trim().to_string(),
+        attachments,
+    })
+}
+
+/// `<id>` tokens of a Message-ID-bearing header, brackets stripped.
+fn message_ids(value: &str) -> Vec<String> {
+    let ids: Vec<String> = value
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('>'))
+        .map(|(id, _)| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    if ids.is_empty() && !value.trim().is_empty() {
+        // Some clients omit the brackets.
+        return value.split_whitespace().map(str::to_string).collect();
+    }
+    ids
+}
+
+/// `type/subtype` plus lower-cased parameters of a Content-Type header.
+fn content_type(entity: &MimeEntity<'_>) -> (String, Vec<(String, String)>) {
+    let raw = entity.header("Content-Type").unwrap_or("text/plain");
+    let mut parts = raw.split(';');
+    let mime = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_ascii_lowercase(),
+                value.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    (mime, params)
+}
+
+fn param<'p>(params: &'p [(String, String)], name: &str) -> Option<&'p str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Best text of an entity: `text/plain` over `text/html`, recursing through
+/// multiparts. Attachment names are collected on the way.
+fn entity_text(
+    entity: &MimeEntity<'_>,
+    attachments: &mut Vec<String>,
+    depth: usize,
+) -> Option<String> {
+    // Nested multiparts beyond this depth are pathological; stop descending.
+    const MAX_MIME_DEPTH: usize = 8;
+    let (mime, params) = content_type(entity);
+    let disposition = entity.header("Content-Disposition").unwrap_or_default();
+    if disposition.to_ascii_lowercase().starts_with("attachment") {
+        let name = disposition
+            .split(';')
+            .filter_map(|p| p.split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("filename"))
+            .map(|(_, value)| decode_encoded_words(value.trim().trim_matches('"')))
+            .or_else(|| param(&params, "name").map(decode_encoded_words))
+            .unwrap_or_else(|| "unnamed".to_string());
+        attachments.push(name);
+        return None;
+    }
+
+    if mime.starts_with("multipart/") {
+        let boundary = param(&params, "boundary")?;
+        if depth >= MAX_MIME_DEPTH {
+            return None;
+        }
+        let parts: Vec<MimeEntity<'_>> = multipart_bodies(entity.body, boundary)
+            .into_iter()
+            .map(split_entity)
+            .collect();
+        let mut plain: Option<String> = None;
+        let mut html: Option<String> = None;
+        for part in &parts {
+            let (part_mime, _) = content_type(part);
+            let Some(text) = entity_text(part, attachments, depth + 1) else {
+                continue;
+            };
+            if part_mime == "text/html" {
+                html.get_or_insert(text);
+            } else if mime == "multipart/alternative" {
+                plain.get_or_insert(text);
+            } else {
+                // multipart/mixed: keep every inline text part, in order.
+                match plain.as_mut() {
+                    Some(existing) => {
+                        existing.push_str("\n\n");
+                        existing.push_str(&text);
+                    }
+                    None => plain = Some(text),
+                }
+            }
+        }
+        return plain.or(html);
+    }
+
+    if mime == "message/rfc822" {
+        let inner = split_entity(entity.body);
+        return entity_text(&inner, attachments, depth + 1);
+    }
+    if !mime.starts_with("text/") {
+        if let Some(name) = param(&params, "name") {
+            attachments.push(decode_encoded_words(name));
+        }
+        return None;
+    }
+
+    let charset = param(&params, "charset");
+    let encoding = entity
+        .header("Content-Transfer-Encoding")
+        .unwrap_or("7bit")
+        .trim()
+        .to_ascii_lowercase();
+    let bytes = match encoding.as_str() {
+        "base64" => {
+            let compact: String = entity.body.split_whitespace().collect();
+            STANDARD.decode(compact.as_bytes()).ok()?
+        }
+        "quoted-printable" => decode_quoted_printable(&raw_bytes(entity.body)),
+        _ => raw_bytes(entity.body),
+    };
+    let text = match charset {
+        Some(charset) => decode_labeled(&bytes, charset),
+        None => decode_unlabeled(&bytes),
+    };
+    if mime == "text/html" {
+        let parse = extract_html_sections(&text);
+        return Some(
+            parse
+                .sections
+                .iter()
+                .map(|section| section.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        );
+    }
+    Some(text)
+}
+
+/// Bodies of a multipart, between `--boundary` delimiter lines.
+fn multipart_bodies<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{boundary}");
+    let closing = format!("{delimiter}--");
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0usize;
+    for line in body.split_inclusive('\n') {
+        let content = line.trim_end();
+        if content == delimiter || content == closing {
+            if let Some(part_start) = start {
+                parts.push(&body[part_start..offset]);
+            }
+            if content == closing {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    if let Some(part_start) = start {
+        parts.push(&body[part_start..]);
+    }
+    parts
+}
+
+fn decode_quoted_printable(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    for line in body.split(|byte| *byte == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let (bytes, soft_break) = match line.strip_suffix(b"=") {
+            Some(stripped) => (stripped, true),
+            None => (line, false),
+        };
+        let mut idx = 0;
+        while idx < bytes.len() {
+            if bytes[idx] == b'=' && idx + 2 < bytes.len() {
+                let hex = std::str::from_utf8(&bytes[idx + 1..idx + 3]).ok();
+                if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    out.push(byte);
+                    idx += 3;
+                    continue;
+                }
+            }
+            out.push(bytes[idx]);
+            idx += 1;
+        }
+        if !soft_break {
+            out.push(b'\n');
+        }
+    }
+    if out.last() == Some(&b'\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Raw 8-bit header text (a [`byte_view`] slice): UTF-8, else windows-1252.
+fn decode_header_text(value: &str) -> String {
+    decode_unlabeled(&raw_bytes(value))
+}
+
+/// Decode RFC 2047 encoded words (`=?charset?B|Q?text?=`) in a header value;
+/// the text around them is raw header text ([`decode_header_text`]).
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut last_was_word = false;
+    while let Some(start) = rest.find("=?") {
+        let between = &rest[..start];
+        // Whitespace between two adjacent encoded words is dropped.
+        if !(last_was_word && between.trim().is_empty()) {
+            out.push_str(&decode_header_text(between));
+        }
+        let word = &rest[start + 2..];
+        let decoded = word.split_once('?').and_then(|(charset, tail)| {
+            let (encoding, tail) = tail.split_once('?')?;
+            let end = tail.find("?=")?;
+            let payload = &tail[..end];
+            let bytes = match encoding {
+                "B" | "b" => STANDARD.decode(payload.as_bytes()).ok()?,
+                "Q" | "q" => decode_quoted_printable(payload.replace('_', " ").as_bytes()),
+                _ => return None,
+            };
+            let consumed = charset.len() + 1 + encoding.len() + 1 + end + 2;
+            Some((decode_labeled(&bytes, charset), consumed))
+        });
+        match decoded {
+            Some((text, consumed)) => {
+                out.push_str(&text);
+                rest = &word[consumed..];
+                last_was_word = true;
+            }
+            None => {
+                out.push_str("=?");
+                rest = word;
+                last_was_word = false;
+            }
+        }
+    }
+    out.push_str(&decode_header_text(rest));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: &str = "From: Ada <ada@example.org>\r\nTo: team@example.org\r\nSubject: =?UTF-8?B?UsOpc3Vtw6k=?= plan\r\nDate: Mon, 5 Oct 2026 09:00:00 +0000\r\nMessage-ID: <root@example.org>\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nFirst draft of the\r\nplan.\r\n";
+
+    #[test]
+    fn eml_headers_body_and_thread_ids() {
+        let parse = parse_eml(ROOT.as_bytes()).expect("well-formed message");
+        let message = &parse.messages[0];
+        assert_eq!(message.message_id, "root@example.org");
+        assert_eq!(message.thread_id, "root@example.org");
+        assert_eq!(message.subject.as_deref(), Some("Résumé plan"));
+        assert_eq!(message.body, "First draft of the\nplan.");
+        assert!(message
+            .span_content()
+            .starts_with("Subject: Résumé plan\nFrom: Ada"));
+    }
+
+    #[test]
+    fn mbox_splits_messages_threads_replies_and_records_malformed_entries() {
+        let mbox = "From ada@example.org Mon Oct  5 09:00:00 2026\n\
+Message-ID: <root@example.org>\nSubject: Plan\n\nRoot body\n>From the archive.\n\n\
+From bob@example.org Mon Oct  5 10:00:00 2026\n\
+Message-ID: <reply@example.org>\nIn-Reply-To: <root@example.org>\nReferences: <root@example.org>\nSubject: Re: Plan\n\
+Content-Type: multipart/alternative; boundary=\"b1\"\n\n\
+--b1\nContent-Type: text/plain\nContent-Transfer-Encoding: quoted-printable\n\nLooks =\ngood =3D yes\n\
+--b1\nContent-Type: text/html\n\n<p>Looks good</p>\n--b1--\n\n\
+From nobody Mon Oct  5 11:00:00 2026\njust text with no headers\n";
+        let parse = parse_mbox(mbox.as_bytes()).expect("partial mailbox");
+        assert_eq!(parse.messages.len(), 2);
+        assert_eq!(parse.malformed.len(), 1);
+        assert_eq!(parse.thread_count(), 1);
+        assert_eq!(parse.messages[0].body, "Root body\nFrom the archive.");
+        assert_eq!(parse.messages[1].body, "Looks good = yes");
+
+        let spans = parse.spans();
+        assert_eq!(
+            spans[1].anchor,
+            SpanAnchor::EmailMessage {
+                message_index: 1,
+                message_id: "reply@example.org".to_string(),
+                thread_id: "root@example.org".to_string(),
+                in_reply_to: Some("root@example.org".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn attachments_are_listed_and_html_only_bodies_are_flattened() {
+        let eml = "From: a@example.org\nSubject: Files\n\
+Content-Type: multipart/mixed; boundary=XYZ\n\n\
+--XYZ\nContent-Type: text/html; charset=iso-8859-1\nContent-Transfer-Encoding: base64\n\n\
+PGgxPlJlcG9ydDwvaDE+PHA+Q2Fm6TwvcD4=\n\
+--XYZ\nContent-Type: application/pdf; name=\"report.pdf\"\nContent-Disposition: attachment; filename=\"report.pdf\"\nContent-Transfer-Encoding: base64\n\nJVBERi0=\n\
+--XYZ--\n";
+        let parse = parse_eml(eml.as_bytes()).expect("multipart message");
+        let message = &parse.messages[0];
+        assert_eq!(message.body, "Café");
+        assert_eq!(message.attachments, vec!["report.pdf".to_string()]);
+        // No Message-ID: a stable content-derived id.
+        assert!(message.message_id.starts_with("sha256:"));
+        assert_eq!(
+            parse_eml(eml.as_bytes()).unwrap().messages[0].message_id,
+            message.message_id
+        );
+    }
+
+    #[test]
+    fn each_message_and_part_decodes_with_its_own_charset() {
+        let mbox = b"From ada@example.org Mon Oct  5 09:00:00 2026\n\
+Message-ID: <old@example.org>\nSubject: Caf\xe9 notes\n\
+Content-Type: text/plain; charset=iso-8859-1\n\n\
+Caf\xe9 cr\xe8me \x96 done\n\n\
+From bob@example.org Mon Oct  5 10:00:00 2026\n\
+Message-ID: <new@example.org>\nSubject: =?windows-1252?Q?=93Plan=94?=\n\
+Content-Type: multipart/mixed; boundary=b2\n\n\
+--b2\nContent-Type: text/plain; charset=utf-8\n\nCaf\xc3\xa9 \xe2\x80\x93 utf8\n\
+--b2\nContent-Type: text/plain; charset=windows-1252\n\
+Content-Transfer-Encoding: quoted-printable\n\n=93quoted=94 \x85\n\
+--b2--\n";
+        let parse = parse_mbox(mbox).expect("mixed-charset mailbox");
+        assert_eq!(parse.messages.len(), 2);
+        assert_eq!(parse.messages[0].subject.as_deref(), Some("Café notes"));
+        assert_eq!(parse.messages[0].body, "Café crème \u{2013} done");
+        assert_eq!(
+            parse.messages[1].subject.as_deref(),
+            Some("\u{201C}Plan\u{201D}")
+        );
+        assert_eq!(
+            parse.messages[1].body,
+            "Café \u{2013} utf8\n\n\u{201C}quoted\u{201D} \u{2026}"
+        );
+    }
+
+    #[test]
+    fn non_messages_fail_typed() {
+        let err = parse_eml(b"just some prose\nwithout headers").expect_err("no headers");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+        let err = parse_mbox(b"Message-ID: <x@y>\n\nbody").expect_err("no separator");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+        let err = parse_email_artifact("mail/x.msg", b"").expect_err("unknown extension");
+        assert_eq!(err.class, IngestionErrorClass::UnsupportedFormat);
+    }
+}
@@ -17,6 +17,7 @@ use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
 use crate::kernel::{KernelActor, KernelEventType, NewKernelEvent};
+use crate::redaction::DetectionEngine;
 use crate::storage::knowledge::{
     KnowledgeExtractionStatus, KnowledgeIndexingEligibility, KnowledgeParserStatus,
     KnowledgePermissionScope, KnowledgeRedactionState, KnowledgeRootKind, KnowledgeSource,
@@ -28,9 +29,13 @@ use crate::storage::Database;
 
 use super::allowlist::{CompiledRootPolicy, RootRegistrationPolicy};
 use super::backpressure::IngestionLimits;
+use super::charset::decode_unlabeled;
+use super::email::parse_email_artifact;
+use super::epub::parse_epub;
 use super::governance::{parse_governance_json, parse_governance_jsonl, GovernanceSpanLimits};
 use super::hashing::{compute_content_hashes, ContentHashes};
 use super::kinds::{detect_governance_sub_kind, detect_kind, spec_for, IngestionSourceKind};
+use super::notebooks::parse_notebook;
 use super::notes::parse_note;
 use super::paths::normalize_source_relative_path;
 use super::pdf::{extract_pdf_text, PDF_EXTRACTOR_ID, PDF_EXTRACTOR_VERSION};
@@ -39,11 +44,12 @@ use super::receipts::{
 };
 use super::repair::{NewRepairEntry, RepairAttemptOutcome, RepairEntry, RepairReason};
 use super::secrets::{
-    redact_span_with_whole_file_findings, redact_text, scan_text, SecretScanReport,
+    redact_span_with_whole_file_findings, redact_text, scan_text_with_engine, SecretScanReport,
 };
 use super::spans::{ExtractedSpan, SpanAnchor, SpanRedaction};
 use super::store::{KnowledgeIngestionStore, NewPolicyDecision, PolicyDecision, StoredSpan};
 use super::transcripts::parse_transcript_artifact;
+use super::web::{decode_html_bytes, html_section_spans, parse_html_page};
 use super::{new_ingestion_id, IngestionError, IngestionResult};
 
 /// Backend-navigation context (spec 2.3.13.11): every engine mutation must
@@ -247,8 +253,20 @@ impl IngestionEngine {
         ctx.validate()?;
         let normalized = normalize_source_relative_path(relative_path)?;
         let hashes = compute_content_hashes(bytes);
+        // The root's workspace decides the allow/deny rules and custom
+        // patterns the secret preflight runs with.
+        let detection = DetectionEngine::for_workspace(&*self.db, &root.workspace_id)
+            .await
+            .map_err(|err| IngestionError::Validation(err.to_string()))?;
         let started = Instant::now();
-        let extraction = run_extraction(root.root_kind, &normalized, bytes, &hashes, limits);
+        let extraction = run_extraction(
+            root.root_kind,
+            &normalized,
+            bytes,
+            &hashes,
+            limits,
+            &detection,
+        );
         let duration_ms = started.elapsed().as_millis() as i64;
         self.persist_attempt(
             ctx,
@@ -874,6 +892,55 @@ impl ExtractionOutput {
             redaction_state: KnowledgeRedactionState::None,
         }
     }
+
+    /// Output for kinds whose span text is DECODED out of the raw bytes
+    /// (EPUB chapters, MIME-encoded mail bodies, HTML entities, notebook JSON
+    /// strings). The raw-byte preflight cannot see a secret hidden by an
+    /// encoding, so the MT-091 block decision re-runs over the extracted text
+    /// (same rule as PDF page text). `spans_failed > 0` makes the receipt
+    /// `partial` with `partial_class`.
+    fn decoded_spans(
+        kind: IngestionSourceKind,
+        spans: Vec<ExtractedSpan>,
+        spans_failed: usize,
+        partial_class: IngestionErrorClass,
+        detail: Value,
+        detection: &DetectionEngine,
+    ) -> Self {
+        let concatenated: String = spans
+            .iter()
+            .map(|span| span.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let report = scan_text_with_engine(&concatenated, detection);
+        if report.must_block() {
+            return Self {
+                kind: Some(kind),
+                status: ExtractionStatus::Blocked,
+                error_class: Some(IngestionErrorClass::SecretBlocked),
+                error_detail: Some(secret_block_detail(&report)),
+                spans: Vec::new(),
+                spans_failed: 0,
+                redaction_count: report.redaction_count() as i32,
+                redaction_state: KnowledgeRedactionState::Redacted,
+            };
+        }
+        let partial = spans_failed > 0;
+        Self {
+            kind: Some(kind),
+            status: if partial {
+                ExtractionStatus::Partial
+            } else {
+                ExtractionStatus::Success
+            },
+            error_class: partial.then_some(partial_class),
+            error_detail: Some(detail),
+            spans,
+            spans_failed: spans_failed as i32,
+            redaction_count: 0,
+            redaction_state: KnowledgeRedactionState::None,
+        }
+    }
 }
 
 /// Extractor identity per kind (recorded on receipts; version-bumped when
@@ -884,6 +951,10 @@ fn extractor_identity(kind: Option<IngestionSourceKind>) -> (&'static str, &'sta
         Some(IngestionSourceKind::MediaTranscript) => ("media_transcript", "v1"),
         Some(IngestionSourceKind::GovernanceArtifact) => ("governance_artifact", "v1"),
         Some(IngestionSourceKind::OperatorResearchNote) => ("operator_research_note", "v1"),
+        Some(IngestionSourceKind::WebPage) => ("web_page_sections", "v1"),
+        Some(IngestionSourceKind::Ebook) => ("epub_sections", "v1"),
+        Some(IngestionSourceKind::Email) => ("email_messages", "v1"),
+        Some(IngestionSourceKind::JupyterNotebook) => ("notebook_cells", "v1"),
         Some(IngestionSourceKind::MarkdownText) => ("markdown_text", "v1"),
         Some(IngestionSourceKind::CodeFile) => ("code_file_windows", "v1"),
         Some(IngestionSourceKind::RichDocument) => ("rich_document", "v1"),
@@ -940,7 +1011,11 @@ fn code_spans(text: &str) -> Vec<ExtractedSpan> {
 /// touches. Spans WITHOUT byte offsets (PDF pages, transcript cues, JSON
 /// pointers) are natural units extracted from their own text and fall back to
 /// a per-span scan. Returns the total redaction count.
-fn redact_spans(spans: &mut [ExtractedSpan], whole_file: &SecretScanReport) -> i32 {
+fn redact_spans(
+    spans: &mut [ExtractedSpan],
+    whole_file: &SecretScanReport,
+    detection: &DetectionEngine,
+) -> i32 {
     let mut total = 0i32;
     for span in spans.iter_mut() {
         match span.byte_start {
@@ -958,7 +1033,7 @@ fn redact_spans(spans: &mut [ExtractedSpan], whole_file: &SecretScanReport) -> i
             }
             // No source-byte mapping: scan the span's own content directly.
             _ => {
-                let report = scan_text(&span.content);
+                let report = scan_text_with_engine(&span.content, detection);
                 if !report.is_clean() {
                     total += report.redaction_count() as i32;
                     span.content = redact_text(&span.content, &report);
@@ -978,6 +1053,7 @@ fn run_extraction(
     bytes: &[u8],
     hashes: &ContentHashes,
     limits: &IngestionLimits,
+    detection: &DetectionEngine,
 ) -> ExtractionOutput {
     let Some(kind) = detect_kind(root_kind, normalized_path) else {
         return ExtractionOutput::failure(
@@ -998,8 +1074,18 @@ fn run_extraction(
         );
     }
 
-    // Text decode + whole-file secret preflight for text-based kinds.
-    let text: Option<&str> = if hashes.is_text {
+    // Text decode + whole-file secret preflight for text-based kinds. Saved
+    // pages and mail carry their own charsets, so they decode from bytes
+    // rather than having to be UTF-8; email parts are decoded again per part
+    // by the parser, this text only feeds the limits and the secret scan.
+    let decoded: Option<String> = match kind {
+        IngestionSourceKind::WebPage => Some(decode_html_bytes(bytes)),
+        IngestionSourceKind::Email => Some(decode_unlabeled(bytes)),
+        _ => None,
+    };
+    let text: Option<&str> = if decoded.is_some() {
+        decoded.as_deref()
+    } else if hashes.is_text {
         // Safe: is_text == valid UTF-8 (computed by compute_content_hashes).
         std::str::from_utf8(bytes).ok()
     } else {
@@ -1008,7 +1094,7 @@ fn run_extraction(
 
     let needs_text = !matches!(
         kind,
-        IngestionSourceKind::Pdf | IngestionSourceKind::ExternalImport
+        IngestionSourceKind::Pdf | IngestionSourceKind::Ebook | IngestionSourceKind::ExternalImport
     );
     let Some(text) = text.or(if needs_text { None } else { Some("") }) else {
         return ExtractionOutput::failure(
@@ -1037,7 +1123,7 @@ fn run_extraction(
     // scan even when no single window holds the whole secret.
     let whole_file_report: Option<SecretScanReport> =
         if spec.capabilities.secret_scan && needs_text {
-            let report = scan_text(text);
+            let report = scan_text_with_engine(text, detection);
             if report.must_block() {
                 return ExtractionOutput {
                     kind: Some(kind),
@@ -1189,7 +1275,7 @@ fn run_extraction(
                     .map(|p| p.text.as_str())
                     .collect::<Vec<_>>()
                     .join("\n");
-                let report = scan_text(&concatenated);
+                let report = scan_text_with_engine(&concatenated, detection);
                 if report.must_block() {
                     ExtractionOutput {
                         kind: Some(kind),
@@ -1232,6 +1318,106 @@ fn run_extraction(
                 json!({"detail": err.detail}),
             ),
         },
+        IngestionSourceKind::WebPage => match parse_html_page(text) {
+            Ok(parse) => ExtractionOutput::decoded_spans(
+                kind,
+                html_section_spans(&parse),
+                0,
+                IngestionErrorClass::ParseError,
+                json!({
+                    "title": parse.title,
+                    "canonical_url": parse.canonical_url,
+                    "headings_seen": parse.headings_seen,
+                }),
+                detection,
+            ),
+            Err(err) => ExtractionOutput::failure(
+                Some(kind),
+                ExtractionStatus::Failed,
+                err.class,
+                json!({"detail": err.detail}),
+            ),
+        },
+        IngestionSourceKind::Ebook => match parse_epub(bytes) {
+            Ok(parse) => {
+                // A DRM-locked chapter is repairable by supplying a DRM-free
+                // copy; name that class rather than a generic parse loss.
+                let partial_class = if parse.failed_chapters.iter().any(|f| f.encrypted) {
+                    IngestionErrorClass::Encrypted
+                } else {
+                    IngestionErrorClass::ParseError
+                };
+                ExtractionOutput::decoded_spans(
+                    kind,
+                    parse.spans(),
+                    parse.failed_chapters.len(),
+                    partial_class,
+                    json!({
+                        "title": parse.title,
+                        "language": parse.language,
+                        "chapters": parse.chapters.len(),
+                        "empty_chapters": parse.empty_chapters,
+                        "failed_chapters": parse.failed_chapters,
+                    }),
+                    detection,
+                )
+            }
+            Err(err) => ExtractionOutput::failure(
+                Some(kind),
+                ExtractionStatus::Failed,
+                err.class,
+                json!({"detail": err.detail}),
+            ),
+        },
+        IngestionSourceKind::Email => match parse_email_artifact(normalized_path, bytes) {
+            Ok(parse) => ExtractionOutput::decoded_spans(
+                kind,
+                parse.spans(),
+                parse.malformed.len(),
+                IngestionErrorClass::ParseError,
+                json!({
+                    "format": parse.format.as_str(),
+                    "messages": parse.messages.len(),
+                    "threads": parse.thread_count(),
+                    "attachments": parse
+                        .messages
+                        .iter()
+                        .map(|m| m.attachments.len())
+                        .sum::<usize>(),
+                    "malformed_messages": parse.malformed,
+                }),
+                detection,
+            ),
+            Err(err) => ExtractionOutput::failure(
+                Some(kind),
+                ExtractionStatus::Failed,
+                err.class,
+                json!({"detail": err.detail}),
+            ),
+        },
+        IngestionSourceKind::JupyterNotebook => match parse_notebook(text) {
+            Ok(parse) => ExtractionOutput::decoded_spans(
+                kind,
+                parse.spans(),
+                parse.malformed.len(),
+                IngestionErrorClass::ParseError,
+                json!({
+                    "nbformat": parse.nbformat,
+                    "language": parse.language,
+                    "empty_cells": parse.empty_cells,
+                    "skipped_rich_outputs": parse.skipped_rich_outputs,
+                    "truncated_outputs": parse.truncated_outputs,
+                    "malformed_cells": parse.malformed,
+                }),
+                detection,
+            ),
+            Err(err) => ExtractionOutput::failure(
+                Some(kind),
+                ExtractionStatus::Failed,
+                err.class,
+                json!({"detail": err.detail}),
+            ),
+        },
         IngestionSourceKind::RichDocument | IngestionSourceKind::ExternalImport => {
             // File-backed external imports register hash+provenance only
             // (capability span_extraction = false). Rich documents are
@@ -1251,14 +1437,15 @@ fn run_extraction(
 
     // MT-091 span-level redaction pass (MEDIUM severity rewrites content).
     // Whole-file findings drive redaction for byte-anchored spans (#1); kinds
-    // without a whole-file scan (PDF page text, external imports) fall back to
-    // a per-span scan inside redact_spans against this empty report.
+    // without a whole-file scan (PDF page text, EPUB chapters, external
+    // imports) fall back to a per-span scan inside redact_spans against this
+    // empty report.
     if spec.capabilities.secret_scan && !output.spans.is_empty() {
         let empty = SecretScanReport {
             findings: Vec::new(),
         };
         let report = whole_file_report.as_ref().unwrap_or(&empty);
-        let redactions = redact_spans(&mut output.spans, report);
+        let redactions = redact_spans(&mut output.spans, report, detection);
         if redactions > 0 {
             output.redaction_count += redactions;
             output.redaction_state = KnowledgeRedactionState::Partial;
@@ -0,0 +1,519 @@
+//! EPUB ebook ingestion: spine chapters into chapter/section spans.
+//!
+//! An EPUB is a zip container: `META-INF/container.xml` names the OPF package
+//! document, whose `<manifest>` maps ids to archive paths and whose `<spine>`
+//! fixes the reading order. Chapters are (X)HTML and go through the same
+//! lenient section splitter as saved web pages ([`super::web`]), so every span
+//! is anchored by spine position, chapter path, and in-chapter heading path
+//! (`epub_section`).
+//!
+//! Crate decision: `zip` (already used by the DOCX/ODT bridge) for the
+//! container and `roxmltree` for the two small, well-formed package XML parts
+//! (container + OPF). Chapter markup is NOT parsed as XML — real-world EPUBs
+//! ship named HTML entities an XML parser rejects.
+//!
+//! Partial-failure policy: a chapter that is missing from the archive,
+//! oversize, not UTF-8, or DRM-encrypted (listed in
+//! `META-INF/encryption.xml`) is recorded as a typed [`EpubChapterFailure`]
+//! and extraction CONTINUES — the engine writes a `partial` receipt naming
+//! every lost chapter. A book whose every chapter is encrypted is a typed
+//! `ENCRYPTED` failure; a container that cannot be read at all is a
+//! `PARSE_ERROR`. Chapters without visible text (cover images, blank
+//! separators) are counted, not failed.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+
+use super::receipts::IngestionErrorClass;
+use super::spans::{ExtractedSpan, SpanAnchor};
+use super::web::{extract_html_sections, HtmlSection};
+
+/// Upper bound on any single decompressed archive part (zip-bomb guard).
+const MAX_PART_BYTES: u64 = 16 * 1024 * 1024;
+
+const CONTAINER_PART: &str = "META-INF/container.xml";
+const ENCRYPTION_PART: &str = "META-INF/encryption.xml";
+const EPUB_MIMETYPE: &str = "application/epub+zip";
+const CHAPTER_MEDIA_TYPES: &[&str] = &["application/xhtml+xml", "text/html"];
+
+/// One chapter that produced sections.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EpubChapter {
+    /// 0-based spine position.
+    pub chapter_index: u32,
+    /// Archive path of the chapter document.
+    pub href: String,
+    pub sections: Vec<HtmlSection>,
+}
+
+/// One chapter the extractor had to drop, with a typed reason.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EpubChapterFailure {
+    pub chapter_index: u32,
+    pub href: String,
+    pub reason: String,
+    /// The chapter is listed in `META-INF/encryption.xml`.
+    pub encrypted: bool,
+}
+
+/// Parse outcome: chapters + losses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpubParse {
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub chapters: Vec<EpubChapter>,
+    pub failed_chapters: Vec<EpubChapterFailure>,
+    /// Spine documents with no visible text (not failures).
+    pub empty_chapters: u32,
+}
+
+impl EpubParse {
+    pub fn is_partial(&self) -> bool {
+        !self.failed_chapters.is_empty()
+    }
+
+    /// `epub_section` spans in reading order.
+    pub fn spans(&self) -> Vec<ExtractedSpan> {
+        self.chapters
+            .iter()
+            .flat_map(|chapter| {
+                chapter.sections.iter().enumerate().map(|(index, section)| {
+                    ExtractedSpan::new(
+                        SpanAnchor::EpubSection {
+                            chapter_index: chapter.chapter_index,
+                            chapter_href: chapter.href.clone(),
+                            section_index: index as u32,
+                            heading_path: section.heading_path.clone(),
+                        },
+                        section.text.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Typed whole-file parse failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpubParseError {
+    pub class: IngestionErrorClass,
+    pub detail: String,
+}
+
+impl EpubParseError {
+    fn parse(detail: impl Into<String>) -> Self {
+        Self {
+            class: IngestionErrorClass::ParseError,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A part read out of the container, or why it could not be.
+enum PartRead {
+    Bytes(Vec<u8>),
+    Missing,
+    TooLarge,
+}
+
+struct EpubArchive<'a> {
+    zip: zip::ZipArchive<Cursor<&'a [u8]>>,
+}
+
+impl<'a> EpubArchive<'a> {
+    fn open(bytes: &'a [u8]) -> Result<Self, EpubParseError> {
+        let zip = zip::ZipArchive::new(Cursor::new(bytes))
+            .map_err(|err| EpubParseError::parse(format!("not a readable EPUB archive: {err}")))?;
+        Ok(Self { zip })
+    }
+
+    fn read(&mut self, name: &str) -> Result<PartRead, EpubParseError> {
+        let file = match self.zip.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(PartRead::Missing),
+            Err(err) => return Err(EpubParseError::parse(format!("archive part {name}: {err}"))),
+        };
+        if file.size() > MAX_PART_BYTES {
+            return Ok(PartRead::TooLarge);
+        }
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        // `take` guards against a lying size header as well.
+        file.take(MAX_PART_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|err| EpubParseError::parse(format!("archive part {name}: {err}")))?;
+        if bytes.len() as u64 > MAX_PART_BYTES {
+            return Ok(PartRead::TooLarge);
+        }
+        Ok(PartRead::Bytes(bytes))
+    }
+
+    /// A package part the book cannot be read without.
+    fn require_text(&mut self, name: &str) -> Result<String, EpubParseError> {
+        match self.read(name)? {
+            PartRead::Bytes(bytes) => String::from_utf8(bytes)
+                .map_err(|_| EpubParseError::parse(format!("package part {name} is not UTF-8"))),
+            PartRead::Missing => Err(EpubParseError::parse(format!(
+                "EPUB is missing required part {name}"
+            ))),
+            PartRead::TooLarge => Err(EpubParseError::parse(format!(
+                "package part {name} exceeds the {MAX_PART_BYTES}-byte limit"
+            ))),
+        }
+    }
+}
+
+fn parse_xml<'x>(part: &str, text: &'x str) -> Result<Document<'x>, EpubParseError> {
+    Document::parse(text)
+        .map_err(|err| EpubParseError::parse(format!("{part} is not well-formed XML: {err}")))
+}
+
+fn descendants_named<'a, 'i>(
+    node: Node<'a, 'i>,
+    local: &'a str,
+) -> impl Iterator<Item = Node<'a, 'i>> + 'a {
+    node.descendants()
+        .filter(move |n| n.is_element() && n.tag_name().name() == local)
+}
+
+fn first_text(doc: &Document<'_>, local: &str) -> Option<String> {
+    descendants_named(doc.root(), local)
+        .find_map(|n| n.text())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Resolve a manifest/encryption href against a base directory inside the
+/// archive: strip the fragment, percent-decode, and fold `.`/`..` segments.
+fn resolve_href(base_dir: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let decoded = percent_decode(href);
+    let joined = if decoded.starts_with('/') {
+        decoded.trim_start_matches('/').to_string()
+    } else {
+        format!("{base_dir}{decoded}")
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            other => segments.push(other),
+        }
+    }
+    segments.join("/")
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[idx + 1..idx + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                idx += 3;
+                continue;
+            }
+        }
+        out.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Archive paths listed as encrypted in `META-INF/encryption.xml`. Paths there
+/// are relative to the container root.
+fn encrypted_parts(archive: &mut EpubArchive<'_>) -> Result<HashSet<String>, EpubParseError> {
+    let PartRead::Bytes(bytes) = archive.read(ENCRYPTION_PART)? else {
+        return Ok(HashSet::new());
+    };
+    let text = String::from_utf8(bytes)
+        .map_err(|_| EpubParseError::parse(format!("{ENCRYPTION_PART} is not UTF-8")))?;
+    let doc = parse_xml(ENCRYPTION_PART, &text)?;
+    Ok(descendants_named(doc.root(), "CipherReference")
+        .filter_map(|n| n.attribute("URI"))
+        .map(|uri| resolve_href("", uri))
+        .collect())
+}
+
+/// Parse an EPUB archive into chapter sections.
+pub fn parse_epub(bytes: &[u8]) -> Result<EpubParse, EpubParseError> {
+    let mut archive = EpubArchive::open(bytes)?;
+
+    if let PartRead::Bytes(mimetype) = archive.read("mimetype")? {
+        let mimetype = String::from_utf8_lossy(&mimetype);
+        if mimetype.trim() != EPUB_MIMETYPE {
+            return Err(EpubParseError::parse(format!(
+                "archive mimetype is '{}', not {EPUB_MIMETYPE}",
+                mimetype.trim()
+            )));
+        }
+    }
+
+    let container_text = archive.require_text(CONTAINER_PART)?;
+    let container = parse_xml(CONTAINER_PART, &container_text)?;
+    let opf_path = descendants_named(container.root(), "rootfile")
+        .find_map(|n| n.attribute("full-path"))
+        .map(|path| resolve_href("", path))
+        .ok_or_else(|| EpubParseError::parse("container.xml names no rootfile"))?;
+    let opf_dir = match opf_path.rsplit_once('/') {
+        Some((dir, _)) => format!("{dir}/"),
+        None => String::new(),
+    };
+
+    let opf_text = archive.require_text(&opf_path)?;
+    let opf = parse_xml(&opf_path, &opf_text)?;
+    let manifest: HashMap<&str, (&str, &str)> = descendants_named(opf.root(), "item")
+        .filter_map(|item| {
+            Some((
+                item.attribute("id")?,
+                (
+                    item.attribute("href")?,
+                    item.attribute("media-type").unwrap_or_default(),
+                ),
+            ))
+        })
+        .collect();
+    let spine: Vec<&str> = descendants_named(opf.root(), "itemref")
+        .filter_map(|itemref| itemref.attribute("idref"))
+        .collect();
+    if spine.is_empty() {
+        return Err(EpubParseError::parse("OPF spine lists no documents"));
+    }
+
+    let encrypted_hrefs = encrypted_parts(&mut archive)?;
+    let mut parse = EpubParse {
+        title: first_text(&opf, "title"),
+        language: first_text(&opf, "language"),
+        chapters: Vec::new(),
+        failed_chapters: Vec::new(),
+        empty_chapters: 0,
+    };
+
+    for (position, idref) in spine.into_iter().enumerate() {
+        let chapter_index = position as u32;
+        let Some(&(href, media_type)) = manifest.get(idref) else {
+            parse.failed_chapters.push(EpubChapterFailure {
+                chapter_index,
+                href: idref.to_string(),
+                reason: format!("spine idref '{idref}' has no manifest item"),
+                encrypted: false,
+            });
+            continue;
+        };
+        let href = resolve_href(&opf_dir, href);
+        if !CHAPTER_MEDIA_TYPES.contains(&media_type) {
+            // Image / SVG spine entries carry no text layer.
+            parse.empty_chapters += 1;
+            continue;
+        }
+        let failure = |reason: String, encrypted: bool| EpubChapterFailure {
+            chapter_index,
+            href: href.clone(),
+            reason,
+            encrypted,
+        };
+        if encrypted_hrefs.contains(&href) {
+            parse.failed_chapters.push(failure(
+                "chapter is encrypted (listed in META-INF/encryption.xml)".to_string(),
+                true,
+            ));
+            continue;
+        }
+        let text = match archive.read(&href)? {
+            PartRead::Bytes(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => {
+                    parse
+                        .failed_chapters
+                        .push(failure("chapter is not UTF-8".to_string(), false));
+                    continue;
+                }
+            },
+            PartRead::Missing => {
+                parse.failed_chapters.push(failure(
+                    "chapter is missing from the archive".to_string(),
+                    false,
+                ));
+                continue;
+            }
+            PartRead::TooLarge => {
+                parse.failed_chapters.push(failure(
+                    format!("chapter exceeds the {MAX_PART_BYTES}-byte limit"),
+                    false,
+                ));
+                continue;
+            }
+        };
+        let sections = extract_html_sections(&text).sections;
+        if sections.is_empty() {
+            parse.empty_chapters += 1;
+            continue;
+        }
+        parse.chapters.push(EpubChapter {
+            chapter_index,
+            href,
+            sections,
+        });
+    }
+
+    if parse.chapters.is_empty() {
+        let all_encrypted = !parse.failed_chapters.is_empty()
+            && parse
+                .failed_chapters
+                .iter()
+                .all(|failure| failure.encrypted);
+        if all_encrypted {
+            return Err(EpubParseError {
+                class: IngestionErrorClass::Encrypted,
+                detail:
+                    "every EPUB chapter is DRM-encrypted; provide a DRM-free copy for ingestion"
+                        .to_string(),
+            });
+        }
+        return Err(EpubParseError::parse(format!(
+            "no EPUB chapter yielded text ({} failed, {} empty)",
+            parse.failed_chapters.len(),
+            parse.empty_chapters
+        )));
+    }
+    Ok(parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+
+    const OPF: &str = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Field Notes</dc:title><dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="cover" href="images/cover.svg" media-type="image/svg+xml"/>
+    <item id="c1" href="text/chapter%201.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c3" href="text/ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="cover"/><itemref idref="c1"/><itemref idref="c2"/><itemref idref="c3"/></spine>
+</package>"#;
+
+    fn epub(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+        for (name, content) in parts {
+            writer.start_file(*name, options).expect("start part");
+            writer.write_all(content.as_bytes()).expect("write part");
+        }
+        writer.finish().expect("finish zip").into_inner()
+    }
+
+    fn book(extra: &[(&str, &str)]) -> Vec<u8> {
+        let mut parts = vec![
+            ("mimetype", EPUB_MIMETYPE),
+            (CONTAINER_PART, CONTAINER),
+            ("OEBPS/content.opf", OPF),
+            (
+                "OEBPS/text/chapter 1.xhtml",
+                "<html><body><h1>One</h1><p>Alpha&nbsp;text.</p><h2>Detail</h2><p>Beta.</p></body></html>",
+            ),
+            (
+                "OEBPS/text/ch2.xhtml",
+                "<html><body><h1>Two</h1><p>Gamma.</p></body></html>",
+            ),
+        ];
+        parts.extend_from_slice(extra);
+        epub(&parts)
+    }
+
+    #[test]
+    fn spine_chapters_become_anchored_sections_and_missing_chapters_are_partial() {
+        let parse = parse_epub(&book(&[])).expect("readable EPUB");
+        assert_eq!(parse.title.as_deref(), Some("Field Notes"));
+        assert_eq!(parse.language.as_deref(), Some("en"));
+        assert_eq!(parse.empty_chapters, 1, "the SVG cover has no text layer");
+        assert_eq!(parse.chapters.len(), 2);
+        assert_eq!(parse.failed_chapters.len(), 1);
+        assert_eq!(parse.failed_chapters[0].chapter_index, 3);
+        assert_eq!(parse.failed_chapters[0].href, "OEBPS/text/ch3.xhtml");
+        assert!(parse.is_partial());
+
+        let spans = parse.spans();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(
+            spans[1].anchor,
+            SpanAnchor::EpubSection {
+                chapter_index: 1,
+                chapter_href: "OEBPS/text/chapter 1.xhtml".to_string(),
+                section_index: 1,
+                heading_path: vec!["One".to_string(), "Detail".to_string()],
+            }
+        );
+        assert_eq!(spans[1].content, "Beta.");
+        assert_eq!(spans[2].content, "Gamma.");
+    }
+
+    #[test]
+    fn encrypted_chapters_fail_typed() {
+        let encryption = r#"<encryption xmlns="urn:oasis:names:tc:opendocument:xmlns:container"
+  xmlns:enc="http://www.w3.org/2001/04/xmlenc#">
+  <enc:EncryptedData><enc:CipherData><enc:CipherReference URI="OEBPS/text/ch3.xhtml"/></enc:CipherData></enc:EncryptedData>
+</encryption>"#;
+        let parse = parse_epub(&book(&[
+            (ENCRYPTION_PART, encryption),
+            ("OEBPS/text/ch3.xhtml", "ciphertext"),
+        ]))
+        .expect("partially encrypted EPUB");
+        assert!(parse.failed_chapters[0].encrypted);
+
+        let opf = OPF
+            .replace(r#"<itemref idref="c1"/><itemref idref="c2"/>"#, "")
+            .replace(r#"<itemref idref="cover"/>"#, "");
+        let err = parse_epub(&epub(&[
+            (CONTAINER_PART, CONTAINER),
+            ("OEBPS/content.opf", &opf),
+            (ENCRYPTION_PART, encryption),
+            ("OEBPS/text/ch3.xhtml", "ciphertext"),
+        ]))
+        .expect_err("fully encrypted EPUB");
+        assert_eq!(err.class, IngestionErrorClass::Encrypted);
+    }
+
+    #[test]
+    fn unreadable_containers_fail_as_parse_errors() {
+        let err = parse_epub(b"not a zip").expect_err("not an archive");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+
+        let err = parse_epub(&epub(&[("mimetype", EPUB_MIMETYPE)])).expect_err("no container");
+        assert!(err.detail.contains(CONTAINER_PART));
+
+        let err = parse_epub(&epub(&[("mimetype", "application/zip")])).expect_err("wrong type");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+    }
+
+    #[test]
+    fn hrefs_resolve_relative_to_the_package() {
+        assert_eq!(
+            resolve_href("OEBPS/", "text/a%20b.xhtml#frag"),
+            "OEBPS/text/a b.xhtml"
+        );
+        assert_eq!(
+            resolve_href("OEBPS/text/", "../images/x.png"),
+            "OEBPS/images/x.png"
+        );
+        assert_eq!(resolve_href("", "/root.opf"), "root.opf");
+    }
+}
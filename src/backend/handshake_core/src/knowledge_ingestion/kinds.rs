@@ -10,7 +10,7 @@
 //! by [`sync_kind_projection`] and never hand-edited.
 //!
 //! Two levels:
-//! * [`IngestionSourceKind`]: the eight primary kinds the ingestion engine
+//! * [`IngestionSourceKind`]: the twelve primary kinds the ingestion engine
 //!   dispatches on.
 //! * [`GovernanceSubKind`]: governance artifacts subdivide into the WP-009
 //!   contract vocabulary (spec module, WP contract, MT contract, taskboard,
@@ -38,6 +38,10 @@ pub enum IngestionSourceKind {
     MediaTranscript,
     GovernanceArtifact,
     OperatorResearchNote,
+    WebPage,
+    Ebook,
+    Email,
+    JupyterNotebook,
     ExternalImport,
 }
 
@@ -51,6 +55,10 @@ impl IngestionSourceKind {
             Self::MediaTranscript => "media_transcript",
             Self::GovernanceArtifact => "governance_artifact",
             Self::OperatorResearchNote => "operator_research_note",
+            Self::WebPage => "web_page",
+            Self::Ebook => "ebook",
+            Self::Email => "email",
+            Self::JupyterNotebook => "jupyter_notebook",
             Self::ExternalImport => "external_import",
         }
     }
@@ -137,7 +145,7 @@ const REGISTRY: &[IngestionKindSpec] = &[
         display_name: "Code file",
         extensions: &[
             "rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "sql", "toml", "yaml", "yml", "sh",
-            "ps1", "css", "html",
+            "ps1", "css", "html", "htm", "xhtml",
         ],
         mime_types: &[
             "text/x-rust",
@@ -239,6 +247,64 @@ const REGISTRY: &[IngestionKindSpec] = &[
             secret_scan: true,
         },
     },
+    IngestionKindSpec {
+        kind: IngestionSourceKind::WebPage,
+        kind_key: "web_page",
+        display_name: "Saved web page (HTML/XHTML)",
+        // `html` is also a code-file extension: only operator/import roots
+        // claim it as a saved page (see `detect_kind`).
+        extensions: &["html", "htm", "xhtml"],
+        mime_types: &["text/html", "application/xhtml+xml"],
+        capabilities: IngestionCapabilities {
+            span_extraction: true,
+            anchor_kinds: &["html_section"],
+            supports_partial_extraction: false,
+            requires_text_layer_detection: false,
+            secret_scan: true,
+        },
+    },
+    IngestionKindSpec {
+        kind: IngestionSourceKind::Ebook,
+        kind_key: "ebook",
+        display_name: "EPUB ebook",
+        extensions: &["epub"],
+        mime_types: &["application/epub+zip"],
+        capabilities: IngestionCapabilities {
+            span_extraction: true,
+            anchor_kinds: &["epub_section"],
+            supports_partial_extraction: true,
+            requires_text_layer_detection: false,
+            secret_scan: true,
+        },
+    },
+    IngestionKindSpec {
+        kind: IngestionSourceKind::Email,
+        kind_key: "email",
+        display_name: "Email message / mailbox (EML/MBOX)",
+        extensions: &["eml", "mbox"],
+        mime_types: &["message/rfc822", "application/mbox"],
+        capabilities: IngestionCapabilities {
+            span_extraction: true,
+            anchor_kinds: &["email_message"],
+            supports_partial_extraction: true,
+            requires_text_layer_detection: false,
+            secret_scan: true,
+        },
+    },
+    IngestionKindSpec {
+        kind: IngestionSourceKind::JupyterNotebook,
+        kind_key: "jupyter_notebook",
+        display_name: "Jupyter notebook (nbformat 4)",
+        extensions: &["ipynb"],
+        mime_types: &["application/x-ipynb+json"],
+        capabilities: IngestionCapabilities {
+            span_extraction: true,
+            anchor_kinds: &["notebook_cell"],
+            supports_partial_extraction: true,
+            requires_text_layer_detection: false,
+            secret_scan: true,
+        },
+    },
     IngestionKindSpec {
         kind: IngestionSourceKind::ExternalImport,
         kind_key: "external_import",
@@ -283,7 +349,8 @@ fn extension_of(path: &str) -> Option<String> {
 /// * `governance` roots claim `json`/`jsonl`/`md` as governance artifacts /
 ///   research notes feed,
 /// * `operator_folder` / `external_import` roots claim `md`/`txt` as operator
-///   research notes and unknown files as external imports,
+///   research notes, HTML (`html`/`htm`/`xhtml`) as saved web pages, and
+///   unknown files as external imports,
 /// * everywhere else falls back to the primary extension table.
 ///
 /// `None` = unsupported format: the engine must record a typed
@@ -313,6 +380,7 @@ pub fn detect_kind(
             Some("md") | Some("markdown") | Some("txt") => {
                 Some(IngestionSourceKind::OperatorResearchNote)
             }
+            Some("html") | Some("htm") | Some("xhtml") => Some(IngestionSourceKind::WebPage),
             _ => detect_by_extension(ext).or(Some(IngestionSourceKind::ExternalImport)),
         },
         _ => detect_by_extension(ext),
@@ -430,6 +498,10 @@ mod tests {
             IngestionSourceKind::MediaTranscript,
             IngestionSourceKind::GovernanceArtifact,
             IngestionSourceKind::OperatorResearchNote,
+            IngestionSourceKind::WebPage,
+            IngestionSourceKind::Ebook,
+            IngestionSourceKind::Email,
+            IngestionSourceKind::JupyterNotebook,
             IngestionSourceKind::ExternalImport,
         ] {
             let matches: Vec<_> = registry().iter().filter(|s| s.kind == kind).collect();
@@ -454,7 +526,8 @@ mod tests {
         // emit span content: code/markdown/notes are UTF-8 source; PDF spans
         // are extracted page TEXT; transcript spans are cue TEXT; governance
         // spans are JSON/JSONL TEXT; rich-document spans are ProseMirror JSON
-        // text. So each is asserted text-decodable below.
+        // text; web page, EPUB chapter, email, and notebook spans are decoded
+        // markup / MIME / cell TEXT. So each is asserted text-decodable below.
         fn is_text_decodable(kind: IngestionSourceKind) -> bool {
             match kind {
                 IngestionSourceKind::CodeFile
@@ -463,7 +536,11 @@ mod tests {
                 | IngestionSourceKind::Pdf
                 | IngestionSourceKind::MediaTranscript
                 | IngestionSourceKind::GovernanceArtifact
-                | IngestionSourceKind::OperatorResearchNote => true,
+                | IngestionSourceKind::OperatorResearchNote
+                | IngestionSourceKind::WebPage
+                | IngestionSourceKind::Ebook
+                | IngestionSourceKind::Email
+                | IngestionSourceKind::JupyterNotebook => true,
                 // External imports register hash+provenance only (no span
                 // extraction); the bytes are treated as opaque.
                 IngestionSourceKind::ExternalImport => false,
@@ -495,13 +572,40 @@ mod tests {
             }
         }
         // Guard against a registry that silently lost its span kinds (a vacuous
-        // pass): there are seven text-decodable span-extracting kinds today.
+        // pass): there are eleven text-decodable span-extracting kinds today.
         assert_eq!(
-            checked_span_kinds, 7,
-            "expected 7 text-decodable span-extracting kinds to be invariant-checked"
+            checked_span_kinds, 11,
+            "expected 11 text-decodable span-extracting kinds to be invariant-checked"
         );
     }
 
+    #[test]
+    fn html_extensions_detect_the_same_kind_within_a_root() {
+        use KnowledgeRootKind::*;
+        for path in ["site/page.html", "site/page.htm", "site/page.XHTML"] {
+            assert_eq!(
+                detect_kind(ProjectRepo, path),
+                Some(IngestionSourceKind::CodeFile),
+                "{path}"
+            );
+            assert_eq!(
+                detect_kind(Artifacts, path),
+                Some(IngestionSourceKind::CodeFile),
+                "{path}"
+            );
+            assert_eq!(
+                detect_kind(OperatorFolder, path),
+                Some(IngestionSourceKind::WebPage),
+                "{path}"
+            );
+            assert_eq!(
+                detect_kind(ExternalImport, path),
+                Some(IngestionSourceKind::WebPage),
+                "{path}"
+            );
+        }
+    }
+
     #[test]
     fn kind_keys_round_trip_through_from_str() {
         for spec in registry() {
@@ -542,6 +646,31 @@ mod tests {
             detect_kind(ProjectRepo, "docs/spec.pdf"),
             Some(IngestionSourceKind::Pdf)
         );
+        // Repo HTML is source; operator-saved HTML is a web page.
+        assert_eq!(
+            detect_kind(ProjectRepo, "app/index.html"),
+            Some(IngestionSourceKind::CodeFile)
+        );
+        assert_eq!(
+            detect_kind(OperatorFolder, "clips/article.html"),
+            Some(IngestionSourceKind::WebPage)
+        );
+        assert_eq!(
+            detect_kind(ProjectRepo, "docs/saved.htm"),
+            Some(IngestionSourceKind::CodeFile)
+        );
+        assert_eq!(
+            detect_kind(OperatorFolder, "books/field-notes.epub"),
+            Some(IngestionSourceKind::Ebook)
+        );
+        assert_eq!(
+            detect_kind(ExternalImport, "mail/archive.mbox"),
+            Some(IngestionSourceKind::Email)
+        );
+        assert_eq!(
+            detect_kind(ProjectRepo, "notebooks/eval.ipynb"),
+            Some(IngestionSourceKind::JupyterNotebook)
+        );
         // Unknown binary under a repo root: unsupported, never guessed.
         assert_eq!(detect_kind(ProjectRepo, "build/output.bin"), None);
         // Unknown under external import: operator-gated import bucket.
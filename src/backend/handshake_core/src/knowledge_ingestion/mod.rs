@@ -3,8 +3,9 @@
 //! Master Spec anchor: 02-system-architecture.md section 2.3.13.11 "Project
 //! Knowledge Index and Rich Document Authority" [ADD v02.192]. This module is
 //! the ingestion front door of the ProjectKnowledgeIndex: it turns operator
-//! project roots, governance artifacts, research notes, PDFs, and media
-//! transcripts into durable `KnowledgeSource` rows, ingestion spans,
+//! project roots, governance artifacts, research notes, PDFs, media
+//! transcripts, saved web pages, EPUBs, email exports, and Jupyter notebooks
+//! into durable `KnowledgeSource` rows, ingestion spans,
 //! extraction receipts, and repair-queue entries — all PostgreSQL +
 //! EventLedger authority, never prose.
 //!
@@ -25,7 +26,8 @@
 //!    detects the kind ([`kinds`]), normalizes the path ([`paths`]), hashes
 //!    content ([`hashing`]), enforces size/line limits ([`backpressure`]),
 //!    runs the secret preflight ([`secrets`]), extracts spans
-//!    ([`pdf`]/[`transcripts`]/[`governance`]/[`notes`]/code windows), and
+//!    ([`pdf`]/[`transcripts`]/[`governance`]/[`notes`]/[`web`]/[`epub`]/
+//!    [`email`]/[`notebooks`]/code windows), and
 //!    persists source + receipt + spans + repair entries.
 //! 4. Failures land in the durable repair queue ([`repair`]);
 //!    [`engine::IngestionEngine::retry_repair`] re-runs a budgeted attempt.
@@ -36,7 +38,8 @@
 //! * `knowledge_ingestion_root_policies` + `knowledge_ingestion_policy_decisions` (0160, MT-081)
 //! * `knowledge_ingestion_kind_registry` (0161, MT-082, projection of [`kinds`])
 //! * `knowledge_ingestion_receipts` (0162, MT-085, one row per extraction attempt)
-//! * `knowledge_ingestion_spans` (0163, MT-087..MT-091, citable evidence units)
+//! * `knowledge_ingestion_spans` (0163, MT-087..MT-091, citable evidence units;
+//!   research-format anchor kinds widened in 0337)
 //! * `knowledge_ingestion_repair_queue` (0164, MT-094, durable retry queue)
 //! Sources/roots themselves live in `knowledge_source_roots`/`knowledge_sources`
 //! (0131/0132) and are written ONLY through `storage::knowledge::KnowledgeStore`.
@@ -55,13 +58,19 @@
 //!   secret material; clean the file, then re-run the pass.
 //! * Image-only PDF -> `NO_TEXT_LAYER` with OCR guidance in the detail: run
 //!   external OCR, import the produced transcript artifact instead.
+//! * DRM-locked EPUB chapters -> `partial` + `ENCRYPTED`; the readable
+//!   chapters are indexed and the locked hrefs are named in the detail.
 
 pub mod allowlist;
 pub mod backpressure;
+pub mod charset;
+pub mod email;
 pub mod engine;
+pub mod epub;
 pub mod governance;
 pub mod hashing;
 pub mod kinds;
+pub mod notebooks;
 pub mod notes;
 pub mod paths;
 pub mod pdf;
@@ -71,6 +80,7 @@ pub mod secrets;
 pub mod spans;
 pub mod store;
 pub mod transcripts;
+pub mod web;
 
 use crate::storage::StorageError;
 use thiserror::Error;
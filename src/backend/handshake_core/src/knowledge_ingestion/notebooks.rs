@@ -0,0 +1,351 @@
+//! Jupyter notebook ingestion: `.ipynb` (nbformat 4) cells into one span per
+//! cell.
+//!
+//! Anchor model (`notebook_cell`): the cell's position in the `cells` array,
+//! its type, and — for nbformat >= 4.5 — its `id`, which survives cell
+//! reordering and is the anchor a citation should prefer.
+//!
+//! Span content: the cell source; for code cells, followed by the TEXT of its
+//! outputs (`stream` text, `text/plain` of execute results / display data,
+//! `ename: evalue` of errors) under an `[output]` marker. Binary outputs
+//! (images, widgets) are counted, never stored. Outputs are capped per cell so
+//! one chatty training loop cannot dominate the source.
+//!
+//! Malformed-cell policy: a cell that is not an object, has no known
+//! `cell_type`, or whose `source` is neither a string nor a string array is
+//! recorded as a typed [`MalformedCell`] and parsing CONTINUES — the engine
+//! writes a `partial` receipt naming every lost cell. nbformat 3 and older
+//! (`worksheets`) is `UNSUPPORTED_FORMAT`; invalid JSON is `PARSE_ERROR`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::receipts::IngestionErrorClass;
+use super::spans::{ExtractedSpan, SpanAnchor};
+
+/// Output text kept per code cell; the rest is dropped and counted.
+const MAX_OUTPUT_BYTES_PER_CELL: usize = 8 * 1024;
+
+const CELL_TYPES: &[&str] = &["code", "markdown", "raw"];
+
+/// One well-formed, non-empty cell.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotebookCell {
+    /// 0-based index into the `cells` array.
+    pub index: u32,
+    pub cell_type: String,
+    pub cell_id: Option<String>,
+    pub source: String,
+    /// Text outputs (code cells only), already capped.
+    pub output_text: Option<String>,
+}
+
+impl NotebookCell {
+    /// Stored span content: source, then output text under a marker.
+    pub fn span_content(&self) -> String {
+        match &self.output_text {
+            Some(output) => format!("{}\n\n[output]\n{output}", self.source),
+            None => self.source.clone(),
+        }
+    }
+}
+
+/// One cell the parser had to drop, with a typed reason.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MalformedCell {
+    pub index: u32,
+    pub reason: String,
+}
+
+/// Parse outcome: cells + losses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotebookParse {
+    pub nbformat: u64,
+    /// Kernel language from `metadata.kernelspec` / `metadata.language_info`.
+    pub language: Option<String>,
+    pub cells: Vec<NotebookCell>,
+    pub malformed: Vec<MalformedCell>,
+    /// Cells with blank source (not failures).
+    pub empty_cells: u32,
+    /// Rich outputs (images, HTML, widgets) not carried into spans.
+    pub skipped_rich_outputs: u32,
+    /// Cells whose output text was cut at the per-cell cap.
+    pub truncated_outputs: u32,
+}
+
+impl NotebookParse {
+    /// `notebook_cell` spans in notebook order.
+    pub fn spans(&self) -> Vec<ExtractedSpan> {
+        self.cells
+            .iter()
+            .map(|cell| {
+                ExtractedSpan::new(
+                    SpanAnchor::NotebookCell {
+                        cell_index: cell.index,
+                        cell_type: cell.cell_type.clone(),
+                        cell_id: cell.cell_id.clone(),
+                    },
+                    cell.span_content(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Typed whole-file parse failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotebookParseError {
+    pub class: IngestionErrorClass,
+    pub detail: String,
+}
+
+/// nbformat multiline strings are a string or an array of line strings.
+fn multiline(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(lines) => lines
+            .iter()
+            .map(|line| line.as_str())
+            .collect::<Option<Vec<_>>>()
+            .map(|lines| lines.concat()),
+        _ => None,
+    }
+}
+
+/// Text of a code cell's outputs plus the count of rich outputs skipped.
+fn output_text(outputs: &[Value]) -> (String, u32) {
+    let mut text = String::new();
+    let mut skipped = 0u32;
+    for output in outputs {
+        let piece = match output.get("output_type").and_then(Value::as_str) {
+            Some("stream") => output.get("text").and_then(multiline),
+            Some("execute_result") | Some("display_data") => {
+                let data = output.get("data");
+                let plain = data.and_then(|d| d.get("text/plain")).and_then(multiline);
+                let has_rich = data
+                    .and_then(Value::as_object)
+                    .is_some_and(|d| d.keys().any(|key| key != "text/plain"));
+                if has_rich {
+                    skipped += 1;
+                }
+                plain
+            }
+            Some("error") => {
+                let name = output
+                    .get("ename")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Error");
+                let value = output.get("evalue").and_then(Value::as_str).unwrap_or("");
+                Some(format!("{name}: {value}"))
+            }
+            _ => None,
+        };
+        if let Some(piece) = piece {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&piece);
+        }
+    }
+    (text, skipped)
+}
+
+/// Cut at the cap on a char boundary.
+fn cap_output(text: &str) -> (String, bool) {
+    if text.len() <= MAX_OUTPUT_BYTES_PER_CELL {
+        return (text.to_string(), false);
+    }
+    let mut end = MAX_OUTPUT_BYTES_PER_CELL;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (format!("{}\n[output truncated]", &text[..end]), true)
+}
+
+/// Parse a `.ipynb` notebook.
+pub fn parse_notebook(text: &str) -> Result<NotebookParse, NotebookParseError> {
+    let value: Value = serde_json::from_str(text).map_err(|err| NotebookParseError {
+        class: IngestionErrorClass::ParseError,
+        detail: format!("invalid notebook JSON: {err}"),
+    })?;
+
+    let nbformat = value.get("nbformat").and_then(Value::as_u64).unwrap_or(0);
+    if value.get("worksheets").is_some() || (nbformat != 0 && nbformat < 4) {
+        return Err(NotebookParseError {
+            class: IngestionErrorClass::UnsupportedFormat,
+            detail: format!(
+                "nbformat {nbformat} notebooks are not supported; upgrade with `jupyter nbconvert --to notebook`"
+            ),
+        });
+    }
+    let Some(cells) = value.get("cells").and_then(Value::as_array) else {
+        return Err(NotebookParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: "notebook must carry a 'cells' array".to_string(),
+        });
+    };
+
+    let metadata = value.get("metadata");
+    let language = metadata
+        .and_then(|m| m.pointer("/kernelspec/language"))
+        .or_else(|| metadata.and_then(|m| m.pointer("/language_info/name")))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let mut parse = NotebookParse {
+        nbformat,
+        language,
+        cells: Vec::new(),
+        malformed: Vec::new(),
+        empty_cells: 0,
+        skipped_rich_outputs: 0,
+        truncated_outputs: 0,
+    };
+    for (position, cell) in cells.iter().enumerate() {
+        let index = position as u32;
+        let malformed = |reason: &str| MalformedCell {
+            index,
+            reason: reason.to_string(),
+        };
+        let Some(cell_type) = cell.get("cell_type").and_then(Value::as_str) else {
+            parse.malformed.push(malformed("cell has no cell_type"));
+            continue;
+        };
+        if !CELL_TYPES.contains(&cell_type) {
+            parse
+                .malformed
+                .push(malformed(&format!("unknown cell_type '{cell_type}'")));
+            continue;
+        }
+        let Some(source) = cell.get("source").and_then(multiline) else {
+            parse
+                .malformed
+                .push(malformed("cell source is not a string or string array"));
+            continue;
+        };
+
+        let mut output = None;
+        if let Some(outputs) = cell.get("outputs").and_then(Value::as_array) {
+            let (text, skipped) = output_text(outputs);
+            parse.skipped_rich_outputs += skipped;
+            if !text.trim().is_empty() {
+                let (capped, truncated) = cap_output(text.trim_end());
+                if truncated {
+                    parse.truncated_outputs += 1;
+                }
+                output = Some(capped);
+            }
+        }
+        if source.trim().is_empty() && output.is_none() {
+            parse.empty_cells += 1;
+            continue;
+        }
+        parse.cells.push(NotebookCell {
+            index,
+            cell_type: cell_type.to_string(),
+            cell_id: cell.get("id").and_then(Value::as_str).map(str::to_string),
+            source: source.trim_end().to_string(),
+            output_text: output,
+        });
+    }
+
+    if parse.cells.is_empty() && !parse.malformed.is_empty() {
+        return Err(NotebookParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: format!(
+                "no well-formed cell in notebook ({} malformed)",
+                parse.malformed.len()
+            ),
+        });
+    }
+    Ok(parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn notebook(cells: Value) -> String {
+        json!({
+            "nbformat": 4,
+            "nbformat_minor": 5,
+            "metadata": {"kernelspec": {"name": "python3", "language": "python"}},
+            "cells": cells,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn cells_become_anchored_spans_with_text_outputs() {
+        let text = notebook(json!([
+            {"cell_type": "markdown", "id": "intro", "source": ["# Title\n", "Some notes."]},
+            {"cell_type": "code", "id": "c1", "source": "print('hi')", "outputs": [
+                {"output_type": "stream", "name": "stdout", "text": ["hi\n"]},
+                {"output_type": "display_data", "data": {"image/png": "iVBOR", "text/plain": ["<Figure>"]}},
+                {"output_type": "error", "ename": "ValueError", "evalue": "bad"}
+            ]},
+            {"cell_type": "code", "source": "   ", "outputs": []},
+        ]));
+        let parse = parse_notebook(&text).expect("well-formed notebook");
+        assert_eq!(parse.language.as_deref(), Some("python"));
+        assert_eq!(parse.cells.len(), 2);
+        assert_eq!(parse.empty_cells, 1);
+        assert_eq!(parse.skipped_rich_outputs, 1);
+        assert_eq!(parse.cells[0].source, "# Title\nSome notes.");
+        assert_eq!(
+            parse.cells[1].span_content(),
+            "print('hi')\n\n[output]\nhi\n<Figure>\nValueError: bad"
+        );
+
+        let spans = parse.spans();
+        assert_eq!(
+            spans[1].anchor,
+            SpanAnchor::NotebookCell {
+                cell_index: 1,
+                cell_type: "code".to_string(),
+                cell_id: Some("c1".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_cells_are_recorded_and_parsing_continues() {
+        let text = notebook(json!([
+            {"cell_type": "code", "source": "x = 1", "outputs": []},
+            "not a cell",
+            {"cell_type": "widget", "source": "?"},
+            {"cell_type": "markdown", "source": 42},
+        ]));
+        let parse = parse_notebook(&text).expect("partial notebook");
+        assert_eq!(parse.cells.len(), 1);
+        let indexes: Vec<u32> = parse.malformed.iter().map(|m| m.index).collect();
+        assert_eq!(indexes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn long_outputs_are_capped() {
+        let long = "x".repeat(MAX_OUTPUT_BYTES_PER_CELL * 2);
+        let text = notebook(json!([
+            {"cell_type": "code", "source": "loop()", "outputs": [
+                {"output_type": "stream", "name": "stdout", "text": long}
+            ]},
+        ]));
+        let parse = parse_notebook(&text).expect("notebook");
+        assert_eq!(parse.truncated_outputs, 1);
+        assert!(parse.cells[0].output_text.as_ref().unwrap().len() < long.len());
+    }
+
+    #[test]
+    fn old_and_invalid_notebooks_fail_typed() {
+        let err = parse_notebook(r#"{"nbformat": 3, "worksheets": [{"cells": []}]}"#)
+            .expect_err("nbformat 3");
+        assert_eq!(err.class, IngestionErrorClass::UnsupportedFormat);
+        let err = parse_notebook("{not json").expect_err("invalid json");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+        let err = parse_notebook(r#"{"nbformat": 4}"#).expect_err("no cells");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+        let err = parse_notebook(&notebook(json!([7]))).expect_err("only malformed cells");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+    }
+}
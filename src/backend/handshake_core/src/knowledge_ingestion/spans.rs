@@ -53,6 +53,44 @@ pub enum SpanAnchor {
         line_start: u32,
         line_end: u32,
     },
+    HtmlSection {
+        /// 0-based section index in document order.
+        section_index: u32,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        heading_path: Vec<String>,
+        /// `id` of the opening heading: the page fragment to cite.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        element_id: Option<String>,
+    },
+    EpubSection {
+        /// 0-based position of the chapter in the EPUB spine.
+        chapter_index: u32,
+        /// Archive path of the chapter document (stable across re-exports).
+        chapter_href: String,
+        /// 0-based section index within the chapter.
+        section_index: u32,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        heading_path: Vec<String>,
+    },
+    EmailMessage {
+        /// 0-based message index in the artifact (always 0 for `.eml`).
+        message_index: u32,
+        /// `Message-ID` without angle brackets (content-derived when absent).
+        message_id: String,
+        /// Message-ID of the thread root (first `References` entry).
+        thread_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        in_reply_to: Option<String>,
+    },
+    NotebookCell {
+        /// 0-based index into the notebook `cells` array.
+        cell_index: u32,
+        /// `code`, `markdown`, or `raw`.
+        cell_type: String,
+        /// nbformat >= 4.5 cell id.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cell_id: Option<String>,
+    },
 }
 
 impl SpanAnchor {
@@ -66,6 +104,10 @@ impl SpanAnchor {
             Self::MediaTime { .. } => "media_time",
             Self::JsonPointer { .. } => "json_pointer",
             Self::HeadingPath { .. } => "heading_path",
+            Self::HtmlSection { .. } => "html_section",
+            Self::EpubSection { .. } => "epub_section",
+            Self::EmailMessage { .. } => "email_message",
+            Self::NotebookCell { .. } => "notebook_cell",
         }
     }
 
@@ -224,6 +266,28 @@ mod tests {
                 line_start: 10,
                 line_end: 20,
             },
+            SpanAnchor::HtmlSection {
+                section_index: 2,
+                heading_path: vec!["Overview".into()],
+                element_id: Some("overview".into()),
+            },
+            SpanAnchor::EpubSection {
+                chapter_index: 4,
+                chapter_href: "OEBPS/ch05.xhtml".into(),
+                section_index: 0,
+                heading_path: vec!["Chapter 5".into()],
+            },
+            SpanAnchor::EmailMessage {
+                message_index: 1,
+                message_id: "reply@example.org".into(),
+                thread_id: "root@example.org".into(),
+                in_reply_to: Some("root@example.org".into()),
+            },
+            SpanAnchor::NotebookCell {
+                cell_index: 3,
+                cell_type: "code".into(),
+                cell_id: None,
+            },
         ];
         for anchor in anchors {
             let value = anchor.to_json();
@@ -0,0 +1,723 @@
+//! Saved web page ingestion: HTML / XHTML pages into heading-scoped section
+//! spans.
+//!
+//! Saved pages are rarely well-formed (unclosed `<p>`, stray entities, inline
+//! scripts), so this module does NOT use an XML parser: a tolerant tag scanner
+//! walks the markup, drops non-content elements (`script`, `style`,
+//! `noscript`, `template`, `svg`, embedded objects), decodes entities, and splits
+//! the visible text at `<h1>`..`<h6>` boundaries.
+//!
+//! Span strategy:
+//! * Every heading opens a new section; text before the first heading is a
+//!   section with an empty heading path.
+//! * Each section becomes one `html_section` span carrying its heading path
+//!   and, when the heading has one, its `id` attribute — the fragment a
+//!   citation can link back to (`page.html#<id>`).
+//! * Oversized sections split at paragraph boundaries into consecutive spans
+//!   that share the same heading path.
+//!
+//! The same splitter backs EPUB chapters ([`super::epub`]), whose XHTML is
+//! the same shape, and HTML-only email bodies ([`super::email`]).
+//!
+//! Saved pages arrive as bytes: [`parse_html_page_bytes`] decodes them by BOM,
+//! then the charset the page declares (`<meta charset>`, `http-equiv`, or an
+//! XML declaration in the first 1024 bytes), then UTF-8 with a windows-1252
+//! fallback ([`super::charset`]).
+
+use serde::{Deserialize, Serialize};
+
+use super::charset::{decode_bom, decode_labeled, decode_unlabeled};
+use super::receipts::IngestionErrorClass;
+use super::spans::{ExtractedSpan, SpanAnchor};
+
+/// Section text above this many bytes is split at paragraph boundaries.
+const MAX_SECTION_BYTES: usize = 16 * 1024;
+
+/// Elements whose content is never visible text.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "object", "iframe",
+];
+
+/// Elements that end the current line of text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "br",
+    "li",
+    "tr",
+    "td",
+    "th",
+    "dt",
+    "dd",
+    "ul",
+    "ol",
+    "dl",
+    "table",
+    "section",
+    "article",
+    "aside",
+    "header",
+    "footer",
+    "nav",
+    "main",
+    "blockquote",
+    "pre",
+    "figure",
+    "figcaption",
+    "hr",
+    "address",
+];
+
+/// One heading-scoped section of visible text.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HtmlSection {
+    /// Heading texts from the outermost to the innermost enclosing heading.
+    pub heading_path: Vec<String>,
+    /// `id` attribute of the heading that opened the section, if any.
+    pub element_id: Option<String>,
+    /// Paragraph-separated visible text.
+    pub text: String,
+}
+
+/// Parse outcome for one page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtmlPageParse {
+    pub title: Option<String>,
+    /// `<link rel="canonical">` target, when the saved page recorded one.
+    pub canonical_url: Option<String>,
+    pub sections: Vec<HtmlSection>,
+    pub headings_seen: usize,
+}
+
+/// Typed whole-file parse failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebPageParseError {
+    pub class: IngestionErrorClass,
+    pub detail: String,
+}
+
+/// Parse a saved web page. A page with no visible text is a `PARSE_ERROR`
+/// (never an empty success).
+pub fn parse_html_page(text: &str) -> Result<HtmlPageParse, WebPageParseError> {
+    let parse = extract_html_sections(text);
+    if parse.sections.is_empty() {
+        return Err(WebPageParseError {
+            class: IngestionErrorClass::ParseError,
+            detail: "web page has no visible text".to_string(),
+        });
+    }
+    Ok(parse)
+}
+
+/// [`parse_html_page`] over the raw bytes of a saved page.
+pub fn parse_html_page_bytes(bytes: &[u8]) -> Result<HtmlPageParse, WebPageParseError> {
+    parse_html_page(&decode_html_bytes(bytes))
+}
+
+/// Decode a saved page: BOM, else its declared charset, else UTF-8 falling
+/// back to windows-1252.
+pub fn decode_html_bytes(bytes: &[u8]) -> String {
+    if let Some(text) = decode_bom(bytes) {
+        return text;
+    }
+    match declared_html_charset(bytes) {
+        // A page whose declaration was readable as ASCII is not UTF-16;
+        // browsers treat that label as UTF-8 too.
+        Some(label) if label.starts_with("utf-16") => decode_unlabeled(bytes),
+        Some(label) => decode_labeled(bytes, &label),
+        None => decode_unlabeled(bytes),
+    }
+}
+
+/// The charset a page declares in its first 1024 bytes, lower-cased.
+fn declared_html_charset(bytes: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_ascii_lowercase();
+    if head.trim_start().starts_with("<?xml") {
+        let declaration = &head[..head.find("?>").unwrap_or(head.len())];
+        if let Some(label) = attribute_token(declaration, "encoding") {
+            return Some(label);
+        }
+    }
+    let mut rest = head.as_str();
+    while let Some(start) = rest.find("<meta") {
+        let tag = &rest[start..];
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        if let Some(label) = attribute_token(tag, "charset") {
+            return Some(label);
+        }
+        rest = &rest[start + tag.len()..];
+    }
+    None
+}
+
+/// The value after `name=` (quotes and whitespace skipped) inside `tag`; also
+/// finds `charset=` inside a `content="text/html; charset=..."` value.
+fn attribute_token(tag: &str, name: &str) -> Option<String> {
+    let at = tag.find(name)?;
+    let value = tag[at + name.len()..].trim_start().strip_prefix('=')?;
+    let value = value.trim_start().trim_start_matches(['"', '\'']);
+    let token: String = value
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Convert a page parse into `html_section` spans, numbered in document order.
+pub fn html_section_spans(parse: &HtmlPageParse) -> Vec<ExtractedSpan> {
+    parse
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(index, section)| {
+            ExtractedSpan::new(
+                SpanAnchor::HtmlSection {
+                    section_index: index as u32,
+                    heading_path: section.heading_path.clone(),
+                    element_id: section.element_id.clone(),
+                },
+                section.text.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Lenient section extraction. Never fails: malformed markup degrades to
+/// whatever visible text can be recovered (possibly none).
+pub(crate) fn extract_html_sections(html: &str) -> HtmlPageParse {
+    let html = html.strip_prefix('\u{feff}').unwrap_or(html);
+    let mut builder = SectionBuilder::default();
+    let mut title: Option<String> = None;
+    let mut canonical_url: Option<String> = None;
+    let mut title_buf: Option<String> = None;
+    let mut pre_depth = 0usize;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            builder.push_text(&decode_entities(rest), pre_depth > 0, &mut title_buf);
+            break;
+        };
+        if lt > 0 {
+            builder.push_text(&decode_entities(&rest[..lt]), pre_depth > 0, &mut title_buf);
+        }
+        rest = &rest[lt..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let Some(tag) = parse_tag(rest) else {
+            // A bare `<` that opens no tag is literal text.
+            builder.push_text("<", pre_depth > 0, &mut title_buf);
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+
+        if !tag.closing && SKIPPED_ELEMENTS.contains(&tag.name.as_str()) {
+            if !tag.self_closing {
+                rest = skip_element(rest, &tag.name);
+            }
+            continue;
+        }
+
+        if let Some(level) = heading_level(&tag.name) {
+            if tag.closing {
+                builder.close_heading();
+            } else {
+                builder.open_heading(level, tag.attr("id").map(str::to_string));
+            }
+            continue;
+        }
+
+        match tag.name.as_str() {
+            "title" if !tag.closing => title_buf = Some(String::new()),
+            "title" => {
+                if let Some(buf) = title_buf.take() {
+                    let trimmed = collapse_whitespace(&buf);
+                    if !trimmed.is_empty() && title.is_none() {
+                        title = Some(trimmed);
+                    }
+                }
+            }
+            "link" if !tag.closing => {
+                let is_canonical = tag
+                    .attr("rel")
+                    .is_some_and(|rel| rel.eq_ignore_ascii_case("canonical"));
+                if is_canonical && canonical_url.is_none() {
+                    canonical_url = tag.attr("href").map(str::to_string);
+                }
+            }
+            "pre" if tag.closing => {
+                builder.end_line();
+                pre_depth = pre_depth.saturating_sub(1);
+            }
+            "pre" => {
+                builder.end_line();
+                pre_depth += 1;
+            }
+            name if BLOCK_ELEMENTS.contains(&name) => builder.end_line(),
+            _ => {}
+        }
+    }
+
+    let (sections, headings_seen) = builder.finish();
+    HtmlPageParse {
+        title,
+        canonical_url,
+        sections,
+        headings_seen,
+    }
+}
+
+fn heading_level(name: &str) -> Option<usize> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Skip past the matching close tag of a non-content element.
+fn skip_element<'a>(rest: &'a str, name: &str) -> &'a str {
+    let needle = format!("</{name}");
+    let lower = rest.to_ascii_lowercase();
+    match lower.find(&needle) {
+        Some(pos) => {
+            let after = &rest[pos..];
+            after.find('>').map_or("", |end| &after[end + 1..])
+        }
+        None => "",
+    }
+}
+
+/// Accumulates visible text into heading-scoped sections.
+#[derive(Default)]
+struct SectionBuilder {
+    /// `(level, text)` of the enclosing headings, outermost first.
+    heading_stack: Vec<(usize, String)>,
+    current_id: Option<String>,
+    paragraphs: Vec<String>,
+    line: String,
+    /// `Some` while inside an open heading element.
+    heading: Option<(usize, Option<String>, String)>,
+    sections: Vec<HtmlSection>,
+    headings_seen: usize,
+}
+
+impl SectionBuilder {
+    fn push_text(&mut self, text: &str, preformatted: bool, title: &mut Option<String>) {
+        if let Some(buf) = title.as_mut() {
+            buf.push_str(text);
+            return;
+        }
+        let target = match self.heading.as_mut() {
+            Some((_, _, heading_text)) => heading_text,
+            None => &mut self.line,
+        };
+        if preformatted {
+            target.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !target.is_empty() && !target.ends_with(' ') {
+                    target.push(' ');
+                }
+            } else {
+                target.push(c);
+            }
+        }
+    }
+
+    fn end_line(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        // Preformatted text keeps its inner indentation; only the blank edges go.
+        let line = line.trim_end().trim_start_matches(['\n', '\r']);
+        if !line.is_empty() {
+            self.paragraphs.push(line.to_string());
+        }
+    }
+
+    fn open_heading(&mut self, level: usize, id: Option<String>) {
+        self.end_line();
+        self.heading = Some((level, id, String::new()));
+    }
+
+    fn close_heading(&mut self) {
+        let Some((level, id, text)) = self.heading.take() else {
+            return;
+        };
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        self.flush_section();
+        self.headings_seen += 1;
+        while self
+            .heading_stack
+            .last()
+            .is_some_and(|(open, _)| *open >= level)
+        {
+            self.heading_stack.pop();
+        }
+        self.heading_stack.push((level, text));
+        self.current_id = id;
+    }
+
+    fn flush_section(&mut self) {
+        self.end_line();
+        if self.paragraphs.is_empty() {
+            return;
+        }
+        let heading_path: Vec<String> = self
+            .heading_stack
+            .iter()
+            .map(|(_, text)| text.clone())
+            .collect();
+        let mut chunk = String::new();
+        for paragraph in std::mem::take(&mut self.paragraphs) {
+            if !chunk.is_empty() && chunk.len() + paragraph.len() + 2 > MAX_SECTION_BYTES {
+                self.sections.push(HtmlSection {
+                    heading_path: heading_path.clone(),
+                    element_id: self.current_id.clone(),
+                    text: std::mem::take(&mut chunk),
+                });
+            }
+            if !chunk.is_empty() {
+                chunk.push_str("\n\n");
+            }
+            chunk.push_str(&paragraph);
+        }
+        self.sections.push(HtmlSection {
+            heading_path,
+            element_id: self.current_id.clone(),
+            text: chunk,
+        });
+    }
+
+    fn finish(mut self) -> (Vec<HtmlSection>, usize) {
+        // An unclosed heading at EOF still names the trailing text.
+        if self.heading.is_some() {
+            self.close_heading();
+        }
+        self.flush_section();
+        (self.sections, self.headings_seen)
+    }
+}
+
+/// One scanned tag.
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+    /// Bytes consumed, including `<` and `>`.
+    len: usize,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Scan one tag at the start of `input` (which begins with `<`). `None` when
+/// the `<` does not open a tag (e.g. `a < b` in text).
+fn parse_tag(input: &str) -> Option<Tag> {
+    let bytes = input.as_bytes();
+    let mut pos = 1;
+    let closing = bytes.get(pos) == Some(&b'/');
+    if closing {
+        pos += 1;
+    }
+    let name_start = pos;
+    while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b':') {
+        pos += 1;
+    }
+    if pos == name_start || !bytes[name_start].is_ascii_alphabetic() {
+        return None;
+    }
+    let raw_name = input[name_start..pos].to_ascii_lowercase();
+    // XHTML may prefix elements (`<xhtml:p>`); match on the local name.
+    let name = raw_name
+        .rsplit_once(':')
+        .map_or(raw_name.as_str(), |(_, local)| local)
+        .to_string();
+
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            None => return None,
+            Some(b'>') => {
+                pos += 1;
+                break;
+            }
+            Some(b'/') => {
+                self_closing = true;
+                pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let key_start = pos;
+        while pos < bytes.len()
+            && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let key = input[key_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let value_start = pos + 1;
+                    let end = input[value_start..].find(quote as char)?;
+                    value = decode_entities(&input[value_start..value_start + end]);
+                    pos = value_start + end + 1;
+                }
+                _ => {
+                    let value_start = pos;
+                    while pos < bytes.len()
+                        && !bytes[pos].is_ascii_whitespace()
+                        && bytes[pos] != b'>'
+                    {
+                        pos += 1;
+                    }
+                    value = decode_entities(&input[value_start..pos]);
+                }
+            }
+        }
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+    }
+    Some(Tag {
+        name,
+        closing,
+        self_closing,
+        attrs,
+        len: pos,
+    })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode HTML character references: numeric (`&#39;`, `&#x27;`) and the
+/// named entities saved pages actually use. Unknown names stay literal.
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&after[..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(numeric) = name.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '\u{2013}',
+        "mdash" => '\u{2014}',
+        "hellip" => '\u{2026}',
+        "lsquo" => '\u{2018}',
+        "rsquo" => '\u{2019}',
+        "ldquo" => '\u{201c}',
+        "rdquo" => '\u{201d}',
+        "laquo" => '\u{ab}',
+        "raquo" => '\u{bb}',
+        "middot" => '\u{b7}',
+        "bull" => '\u{2022}',
+        "copy" => '\u{a9}',
+        "reg" => '\u{ae}',
+        "trade" => '\u{2122}',
+        "deg" => '\u{b0}',
+        "times" => '\u{d7}',
+        "shy" => '\u{ad}',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Retrieval &amp; Ranking</title>
+<link rel="canonical" href="https://example.org/rr">
+<style>body { color: red }</style></head>
+<body>
+<p>Intro text before any heading.</p>
+<h1 id="overview">Overview</h1>
+<p>First <b>paragraph</b>
+   spans lines.</p><p>Second&nbsp;paragraph &mdash; done.</p>
+<script>var secret = "not content";</script>
+<h2 id="bm25">BM25</h2><ul><li>term frequency</li><li>idf</li></ul>
+<h2>Dense</h2><pre>fn embed() {
+    todo()
+}</pre>
+<h1>Appendix</h1><p>a &lt; b</p>
+</body></html>"#;
+
+    #[test]
+    fn sections_follow_heading_structure_with_ids() {
+        let parse = parse_html_page(PAGE).expect("page parses");
+        assert_eq!(parse.title.as_deref(), Some("Retrieval & Ranking"));
+        assert_eq!(
+            parse.canonical_url.as_deref(),
+            Some("https://example.org/rr")
+        );
+        assert_eq!(parse.headings_seen, 4);
+
+        let paths: Vec<Vec<String>> = parse
+            .sections
+            .iter()
+            .map(|s| s.heading_path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![],
+                vec!["Overview".to_string()],
+                vec!["Overview".to_string(), "BM25".to_string()],
+                vec!["Overview".to_string(), "Dense".to_string()],
+                vec!["Appendix".to_string()],
+            ]
+        );
+        assert_eq!(parse.sections[1].element_id.as_deref(), Some("overview"));
+        assert_eq!(
+            parse.sections[1].text,
+            "First paragraph spans lines.\n\nSecond paragraph \u{2014} done."
+        );
+        assert_eq!(parse.sections[2].text, "term frequency\n\nidf");
+        assert_eq!(parse.sections[3].element_id, None);
+        assert!(parse.sections[3].text.contains("    todo()"));
+        assert_eq!(parse.sections[4].text, "a < b");
+        assert!(!parse
+            .sections
+            .iter()
+            .any(|s| s.text.contains("not content") || s.text.contains("color")));
+    }
+
+    #[test]
+    fn spans_carry_html_section_anchors() {
+        let parse = parse_html_page(PAGE).expect("page parses");
+        let spans = html_section_spans(&parse);
+        assert_eq!(spans.len(), parse.sections.len());
+        assert_eq!(
+            spans[2].anchor,
+            SpanAnchor::HtmlSection {
+                section_index: 2,
+                heading_path: vec!["Overview".to_string(), "BM25".to_string()],
+                element_id: Some("bm25".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn oversized_sections_split_at_paragraph_boundaries() {
+        let paragraph = format!("<p>{}</p>", "word ".repeat(2000));
+        let html = format!("<h1>Long</h1>{}", paragraph.repeat(4));
+        let parse = parse_html_page(&html).expect("page parses");
+        assert!(parse.sections.len() > 1);
+        assert!(parse.sections.iter().all(
+            |s| s.heading_path == vec!["Long".to_string()] && s.text.len() <= MAX_SECTION_BYTES
+        ));
+    }
+
+    #[test]
+    fn malformed_markup_degrades_and_empty_pages_fail_typed() {
+        let parse = parse_html_page("<p>unclosed <b>bold <h2>Tail</h2>more").expect("lenient");
+        assert_eq!(parse.sections[0].text, "unclosed bold");
+        assert_eq!(parse.sections[1].heading_path, vec!["Tail".to_string()]);
+        assert_eq!(parse.sections[1].text, "more");
+
+        let err =
+            parse_html_page("<html><script>x()</script></html>").expect_err("no visible text");
+        assert_eq!(err.class, IngestionErrorClass::ParseError);
+    }
+
+    #[test]
+    fn page_bytes_decode_by_declared_charset() {
+        let latin = b"<html><head><meta http-equiv=\"Content-Type\" \
+content=\"text/html; charset=windows-1252\"></head><body><p>Caf\xe9 \x96 menu</p></body></html>";
+        let parse = parse_html_page_bytes(latin).expect("windows-1252 page");
+        assert_eq!(parse.sections[0].text, "Café \u{2013} menu");
+
+        let meta = b"<meta charset='iso-8859-1'><p>Gr\xfc\xdfe</p>";
+        assert!(decode_html_bytes(meta).contains("Grüße"));
+
+        let xml = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><html><p>na\xefve</p></html>";
+        assert!(decode_html_bytes(xml).contains("naïve"));
+
+        // No declaration: UTF-8 when valid, windows-1252 otherwise.
+        assert!(decode_html_bytes("<p>Café</p>".as_bytes()).contains("Café"));
+        assert!(decode_html_bytes(b"<p>Caf\xe9</p>").contains("Café"));
+    }
+
+    #[test]
+    fn entities_decode_numeric_and_named_forms() {
+        assert_eq!(
+            decode_entities("&#39;a&#x27; &amp; &unknown; &"),
+            "'a' & &unknown; &"
+        );
+    }
+}
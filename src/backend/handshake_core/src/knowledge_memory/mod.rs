@@ -5,8 +5,9 @@
 //! Master Spec anchor: 02-system-architecture.md section 2.3.13.11 and the
 //! WP-009 contract field `translated_memory_system_spec`. This module is the
 //! product-logic layer (projections, conflict detection/resolution jobs, bridge
-//! generation, claim authority labels, visual-debug payloads, fixtures) over
-//! the PostgreSQL storage surface in `storage/knowledge_memory.rs`.
+//! generation, claim authority labels, visual-debug payloads, graph exports,
+//! fixtures) over the PostgreSQL storage surface in
+//! `storage/knowledge_memory.rs`.
 //!
 //! It EXTENDS the committed knowledge substrate (entities, edges, claims,
 //! spans, passages) — it REUSES the claim lifecycle, the claim conflict table
@@ -19,6 +20,7 @@
 pub mod bridge;
 pub mod claim_labels;
 pub mod conflict;
+pub mod export;
 pub mod fact;
 pub mod fixtures;
 pub mod passage;
@@ -0,0 +1,1019 @@
+//! Retrieval quality evaluation: labelled query sets, ranking metrics, stored
+//! runs, and run-to-run regression diffs.
+//!
+//! Ranking is deterministic (MT-134) and every retrieval leaves a replayable
+//! trace (MT-138), but neither says whether a change to the weights, the
+//! planner, or `loom_search_v2` made results better. This module closes that
+//! loop:
+//!
+//! ```text
+//! LabelledQuerySet (query -> expected entities/spans/blocks, graded)
+//!     -> RetrievalEvalTarget::retrieve (executor pipeline | loom_search_v2)
+//!     -> recall@k, MRR, nDCG@k per query
+//!     -> RetrievalEvalRun, summarised per QueryPlan mode, persisted (0339)
+//!     -> diff_runs(baseline, candidate) flags per-query regressions
+//! ```
+//!
+//! Query sets are portable JSON. Fixture ids generated at seed time are
+//! written as `{{name}}` placeholders and bound with
+//! [`LabelledQuerySet::bind`], so one file drives every fresh workspace.
+//! Metrics are pure functions of the ranked list and the labels; the stored
+//! run keeps the top-ranked ids so a diff can show what moved.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::knowledge_retrieval::compiler::BundleTargetKind;
+use crate::knowledge_retrieval::executor::execute_retrieval;
+use crate::knowledge_retrieval::graph_planner::GraphTraversalPolicy;
+use crate::knowledge_retrieval::planner::{AuthoritativeHandle, RetrievalRequest};
+use crate::memory::retrieval_mode::QueryRetrievalMode;
+use crate::storage::knowledge::{KnowledgePassageEvidenceRef, KnowledgeStore};
+use crate::storage::loom::LoomSearchV2Request;
+use crate::storage::postgres::PostgresDatabase;
+use crate::storage::{Database, StorageError, StorageResult};
+
+pub const RETRIEVAL_EVAL_QUERY_SET_SCHEMA_ID: &str = "hsk.retrieval_eval_query_set@1";
+/// Cut-offs reported when a run does not name its own.
+pub const DEFAULT_EVAL_KS: [u32; 4] = [1, 3, 5, 10];
+/// Summary key aggregating every query regardless of mode.
+pub const ALL_MODES: &str = "all";
+/// Mode label for targets that do not go through the QueryPlan planner.
+pub const LOOM_SEARCH_MODE: &str = "loom_search_v2";
+/// Highest relevance grade a label may carry.
+pub const MAX_GRADE: u8 = 3;
+
+// ---------------------------------------------------------------------------
+// Labelled query sets
+// ---------------------------------------------------------------------------
+
+/// What a retrieved hit can be credited as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalRefKind {
+    Entity,
+    Relationship,
+    Span,
+    Passage,
+    Block,
+}
+
+/// A stable id of one kind.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct EvalRef {
+    pub kind: EvalRefKind,
+    pub id: String,
+}
+
+impl EvalRef {
+    pub fn new(kind: EvalRefKind, id: impl Into<String>) -> Self {
+        Self {
+            kind,
+            id: id.into(),
+        }
+    }
+}
+
+/// One labelled answer. `grade` is graded relevance for nDCG (1 = relevant,
+/// [`MAX_GRADE`] = the answer); recall and MRR treat every label as relevant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedRef {
+    pub kind: EvalRefKind,
+    pub id: String,
+    #[serde(default = "default_grade")]
+    pub grade: u8,
+}
+
+fn default_grade() -> u8 {
+    1
+}
+
+/// One labelled query. Seeds and handles feed the executor target the same
+/// way a caller would; targets that do not use them ignore them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LabelledQuery {
+    pub query_id: String,
+    pub query_text: String,
+    pub expected: Vec<ExpectedRef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub graph_seeds: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_handles: Vec<String>,
+    #[serde(default)]
+    pub graph_neighborhood_expected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LabelledQuerySet {
+    pub schema_id: String,
+    pub query_set_id: String,
+    pub queries: Vec<LabelledQuery>,
+}
+
+impl LabelledQuerySet {
+    pub fn from_json(json: &str) -> StorageResult<Self> {
+        let set: Self = serde_json::from_str(json)?;
+        set.validate()?;
+        Ok(set)
+    }
+
+    pub fn validate(&self) -> StorageResult<()> {
+        if self.schema_id != RETRIEVAL_EVAL_QUERY_SET_SCHEMA_ID {
+            return Err(StorageError::Validation(
+                "retrieval eval query set schema_id must be hsk.retrieval_eval_query_set@1",
+            ));
+        }
+        if self.query_set_id.trim().is_empty() || self.queries.is_empty() {
+            return Err(StorageError::Validation(
+                "retrieval eval query set needs an id and at least one query",
+            ));
+        }
+        let mut seen = BTreeSet::new();
+        for query in &self.queries {
+            if query.query_id.trim().is_empty() || !seen.insert(query.query_id.as_str()) {
+                return Err(StorageError::Validation(
+                    "retrieval eval query ids must be non-empty and unique",
+                ));
+            }
+            if query.expected.is_empty() {
+                return Err(StorageError::Validation(
+                    "retrieval eval query must label at least one expected ref",
+                ));
+            }
+            if query
+                .expected
+                .iter()
+                .any(|e| e.grade == 0 || e.grade > MAX_GRADE)
+            {
+                return Err(StorageError::Validation(
+                    "retrieval eval grade must be between 1 and 3",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace `{{name}}` placeholders in ids, seeds, and handles with the ids
+    /// a fixture generated. Fails closed on any placeholder left unbound.
+    pub fn bind(&self, bindings: &BTreeMap<String, String>) -> StorageResult<Self> {
+        let sub = |value: &str| -> StorageResult<String> {
+            let mut out = value.to_string();
+            for (name, id) in bindings {
+                out = out.replace(&format!("{{{{{name}}}}}"), id);
+            }
+            if out.contains("{{") {
+                return Err(StorageError::Validation(
+                    "retrieval eval query set has an unbound placeholder",
+                ));
+            }
+            Ok(out)
+        };
+        let mut bound = self.clone();
+        for query in &mut bound.queries {
+            query.query_text = sub(&query.query_text)?;
+            for expected in &mut query.expected {
+                expected.id = sub(&expected.id)?;
+            }
+            for seed in &mut query.graph_seeds {
+                *seed = sub(seed)?;
+            }
+            for handle in &mut query.entity_handles {
+                *handle = sub(handle)?;
+            }
+        }
+        Ok(bound)
+    }
+
+    /// `sha256` over the canonical JSON, so a diff can tell whether two runs
+    /// scored the same labels.
+    pub fn content_hash(&self) -> String {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        hex::encode(Sha256::digest(bytes))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Metrics
+// ---------------------------------------------------------------------------
+
+/// One ranked result and every ref it can be credited as (a traversed edge is
+/// its relationship, both endpoint entities, and its evidence spans).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RankedHit {
+    pub candidate_id: String,
+    pub refs: Vec<EvalRef>,
+}
+
+/// What a target returned for one query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryOutcome {
+    /// The executed QueryPlan mode (after any fallback revision), or the
+    /// target's surface name when it does not plan.
+    pub mode: String,
+    pub hits: Vec<RankedHit>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryMetrics {
+    pub recall_at: BTreeMap<u32, f64>,
+    pub ndcg_at: BTreeMap<u32, f64>,
+    pub reciprocal_rank: f64,
+    /// 1-based rank of the first hit matching any label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_relevant_rank: Option<u32>,
+}
+
+/// 1-based rank at which each label is first satisfied. A hit credits every
+/// label it matches; a label is credited once.
+fn label_ranks(hits: &[RankedHit], expected: &[ExpectedRef]) -> Vec<Option<u32>> {
+    expected
+        .iter()
+        .map(|label| {
+            hits.iter()
+                .position(|hit| {
+                    hit.refs
+                        .iter()
+                        .any(|r| r.kind == label.kind && r.id == label.id)
+                })
+                .map(|idx| idx as u32 + 1)
+        })
+        .collect()
+}
+
+pub fn recall_at_k(hits: &[RankedHit], expected: &[ExpectedRef], k: u32) -> f64 {
+    if expected.is_empty() {
+        return 0.0;
+    }
+    let found = label_ranks(hits, expected)
+        .into_iter()
+        .filter(|rank| rank.is_some_and(|r| r <= k))
+        .count();
+    found as f64 / expected.len() as f64
+}
+
+pub fn reciprocal_rank(hits: &[RankedHit], expected: &[ExpectedRef]) -> f64 {
+    label_ranks(hits, expected)
+        .into_iter()
+        .flatten()
+        .min()
+        .map_or(0.0, |rank| 1.0 / f64::from(rank))
+}
+
+fn gain(grade: u8) -> f64 {
+    2f64.powi(i32::from(grade)) - 1.0
+}
+
+fn discount(rank: u32) -> f64 {
+    (f64::from(rank) + 1.0).log2()
+}
+
+/// nDCG@k with exponential gain. A hit's gain is the best grade among the
+/// labels it is the first to satisfy, so duplicates earn nothing.
+pub fn ndcg_at_k(hits: &[RankedHit], expected: &[ExpectedRef], k: u32) -> f64 {
+    let ranks = label_ranks(hits, expected);
+    let mut best_at_rank: BTreeMap<u32, u8> = BTreeMap::new();
+    for (label, rank) in expected.iter().zip(ranks) {
+        if let Some(rank) = rank.filter(|r| *r <= k) {
+            let slot = best_at_rank.entry(rank).or_insert(0);
+            *slot = (*slot).max(label.grade);
+        }
+    }
+    let dcg: f64 = best_at_rank
+        .iter()
+        .map(|(rank, grade)| gain(*grade) / discount(*rank))
+        .sum();
+    let mut ideal: Vec<u8> = expected.iter().map(|e| e.grade).collect();
+    ideal.sort_unstable_by(|a, b| b.cmp(a));
+    let idcg: f64 = ideal
+        .iter()
+        .take(k as usize)
+        .enumerate()
+        .map(|(idx, grade)| gain(*grade) / discount(idx as u32 + 1))
+        .sum();
+    if idcg == 0.0 {
+        0.0
+    } else {
+        dcg / idcg
+    }
+}
+
+pub fn score_query(hits: &[RankedHit], expected: &[ExpectedRef], ks: &[u32]) -> QueryMetrics {
+    QueryMetrics {
+        recall_at: ks
+            .iter()
+            .map(|k| (*k, recall_at_k(hits, expected, *k)))
+            .collect(),
+        ndcg_at: ks
+            .iter()
+            .map(|k| (*k, ndcg_at_k(hits, expected, *k)))
+            .collect(),
+        reciprocal_rank: reciprocal_rank(hits, expected),
+        first_relevant_rank: label_ranks(hits, expected).into_iter().flatten().min(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Runs
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryEvalResult {
+    pub query_id: String,
+    pub mode: String,
+    /// Top `max(ks)` candidate ids, in ranked order.
+    pub ranked_ids: Vec<String>,
+    pub metrics: QueryMetrics,
+}
+
+/// Mean metrics over the queries that ran in one mode.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModeSummary {
+    pub queries: u32,
+    pub mean_recall_at: BTreeMap<u32, f64>,
+    pub mean_ndcg_at: BTreeMap<u32, f64>,
+    pub mrr: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RetrievalEvalRun {
+    pub run_id: String,
+    pub workspace_id: String,
+    pub query_set_id: String,
+    pub query_set_hash: String,
+    /// Which retrieval surface was measured (see [`RetrievalEvalTarget::target_id`]).
+    pub target_id: String,
+    /// Free-form operator label, e.g. the change under test.
+    pub label: String,
+    pub ks: Vec<u32>,
+    pub results: Vec<QueryEvalResult>,
+    /// Keyed by mode, plus [`ALL_MODES`].
+    pub summary: BTreeMap<String, ModeSummary>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn summarize(results: &[QueryEvalResult], ks: &[u32]) -> BTreeMap<String, ModeSummary> {
+    let mut groups: BTreeMap<String, Vec<&QueryEvalResult>> = BTreeMap::new();
+    for result in results {
+        groups.entry(result.mode.clone()).or_default().push(result);
+        groups
+            .entry(ALL_MODES.to_string())
+            .or_default()
+            .push(result);
+    }
+    groups
+        .into_iter()
+        .map(|(mode, group)| {
+            let n = group.len() as f64;
+            let mean =
+                |f: &dyn Fn(&QueryEvalResult) -> f64| group.iter().map(|r| f(r)).sum::<f64>() / n;
+            let summary = ModeSummary {
+                queries: group.len() as u32,
+                mean_recall_at: ks
+                    .iter()
+                    .map(|k| {
+                        (
+                            *k,
+                            mean(&|r| r.metrics.recall_at.get(k).copied().unwrap_or(0.0)),
+                        )
+                    })
+                    .collect(),
+                mean_ndcg_at: ks
+                    .iter()
+                    .map(|k| {
+                        (
+                            *k,
+                            mean(&|r| r.metrics.ndcg_at.get(k).copied().unwrap_or(0.0)),
+                        )
+                    })
+                    .collect(),
+                mrr: mean(&|r| r.metrics.reciprocal_rank),
+            };
+            (mode, summary)
+        })
+        .collect()
+}
+
+/// A retrieval surface the harness can measure.
+#[async_trait]
+pub trait RetrievalEvalTarget: Send + Sync {
+    /// Stable name of the surface, stored on the run.
+    fn target_id(&self) -> String;
+    fn workspace_id(&self) -> &str;
+    async fn retrieve(&self, query: &LabelledQuery) -> StorageResult<QueryOutcome>;
+}
+
+/// Run every query in `set` against `target` and score it. The set must
+/// already be bound; `ks` empty means [`DEFAULT_EVAL_KS`].
+pub async fn run_evaluation(
+    target: &dyn RetrievalEvalTarget,
+    set: &LabelledQuerySet,
+    ks: &[u32],
+    label: &str,
+) -> StorageResult<RetrievalEvalRun> {
+    set.validate()?;
+    if set
+        .queries
+        .iter()
+        .flat_map(|q| q.expected.iter())
+        .any(|e| e.id.contains("{{"))
+    {
+        return Err(StorageError::Validation(
+            "retrieval eval query set has an unbound placeholder",
+        ));
+    }
+    let mut ks: Vec<u32> = if ks.is_empty() {
+        DEFAULT_EVAL_KS.to_vec()
+    } else {
+        ks.to_vec()
+    };
+    ks.sort_unstable();
+    ks.dedup();
+    if ks.first() == Some(&0) {
+        return Err(StorageError::Validation("retrieval eval k must be >= 1"));
+    }
+    let keep = ks.last().copied().unwrap_or(0) as usize;
+
+    let mut results = Vec::with_capacity(set.queries.len());
+    for query in &set.queries {
+        let outcome = target.retrieve(query).await?;
+        results.push(QueryEvalResult {
+            query_id: query.query_id.clone(),
+            mode: outcome.mode,
+            ranked_ids: outcome
+                .hits
+                .iter()
+                .take(keep)
+                .map(|hit| hit.candidate_id.clone())
+                .collect(),
+            metrics: score_query(&outcome.hits, &query.expected, &ks),
+        });
+    }
+    let summary = summarize(&results, &ks);
+    Ok(RetrievalEvalRun {
+        run_id: format!("KREV-{}", Uuid::now_v7().simple()),
+        workspace_id: target.workspace_id().to_string(),
+        query_set_id: set.query_set_id.clone(),
+        query_set_hash: set.content_hash(),
+        target_id: target.target_id(),
+        label: label.to_string(),
+        ks,
+        results,
+        summary,
+        created_at: Utc::now(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Diffs
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryDiffStatus {
+    Regressed,
+    Improved,
+    Unchanged,
+    /// Only in the candidate run.
+    Added,
+    /// Only in the baseline run.
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryDiff {
+    pub query_id: String,
+    pub status: QueryDiffStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_mode: Option<String>,
+    /// Candidate minus baseline, per metric (`recall@k`, `ndcg@k`, `mrr`).
+    pub deltas: BTreeMap<String, f64>,
+    /// Metrics that dropped by more than the tolerance.
+    pub regressed_metrics: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RetrievalEvalDiff {
+    pub baseline_run_id: String,
+    pub candidate_run_id: String,
+    /// The runs scored different labels; deltas may reflect relabelling.
+    pub query_set_changed: bool,
+    pub queries: Vec<QueryDiff>,
+    /// Candidate minus baseline summary, per mode and metric.
+    pub summary_deltas: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl RetrievalEvalDiff {
+    pub fn regressions(&self) -> impl Iterator<Item = &QueryDiff> {
+        self.queries
+            .iter()
+            .filter(|q| q.status == QueryDiffStatus::Regressed)
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+}
+
+fn metric_map(metrics: &QueryMetrics) -> BTreeMap<String, f64> {
+    let mut map = BTreeMap::new();
+    for (k, v) in &metrics.recall_at {
+        map.insert(format!("recall@{k}"), *v);
+    }
+    for (k, v) in &metrics.ndcg_at {
+        map.insert(format!("ndcg@{k}"), *v);
+    }
+    map.insert("mrr".to_string(), metrics.reciprocal_rank);
+    map
+}
+
+fn summary_map(summary: &ModeSummary) -> BTreeMap<String, f64> {
+    let mut map = BTreeMap::new();
+    for (k, v) in &summary.mean_recall_at {
+        map.insert(format!("recall@{k}"), *v);
+    }
+    for (k, v) in &summary.mean_ndcg_at {
+        map.insert(format!("ndcg@{k}"), *v);
+    }
+    map.insert("mrr".to_string(), summary.mrr);
+    map
+}
+
+fn delta_map(
+    baseline: &BTreeMap<String, f64>,
+    candidate: &BTreeMap<String, f64>,
+) -> BTreeMap<String, f64> {
+    baseline
+        .iter()
+        .filter_map(|(name, before)| {
+            candidate
+                .get(name)
+                .map(|after| (name.clone(), after - before))
+        })
+        .collect()
+}
+
+/// Compare two runs of the same query set query by query. A query regresses
+/// when any shared metric drops by more than `tolerance`; it improves when
+/// none drop and at least one rises by more than `tolerance`.
+pub fn diff_runs(
+    baseline: &RetrievalEvalRun,
+    candidate: &RetrievalEvalRun,
+    tolerance: f64,
+) -> StorageResult<RetrievalEvalDiff> {
+    if baseline.query_set_id != candidate.query_set_id {
+        return Err(StorageError::Validation(
+            "retrieval eval runs scored different query sets",
+        ));
+    }
+    let tolerance = tolerance.abs();
+    let before: BTreeMap<&str, &QueryEvalResult> = baseline
+        .results
+        .iter()
+        .map(|r| (r.query_id.as_str(), r))
+        .collect();
+    let after: BTreeMap<&str, &QueryEvalResult> = candidate
+        .results
+        .iter()
+        .map(|r| (r.query_id.as_str(), r))
+        .collect();
+    let ids: BTreeSet<&str> = before.keys().chain(after.keys()).copied().collect();
+
+    let mut queries = Vec::with_capacity(ids.len());
+    for id in ids {
+        let diff = match (before.get(id), after.get(id)) {
+            (Some(b), Some(a)) => {
+                let deltas = delta_map(&metric_map(&b.metrics), &metric_map(&a.metrics));
+                let regressed_metrics: Vec<String> = deltas
+                    .iter()
+                    .filter(|(_, d)| **d < -tolerance)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                let status = if !regressed_metrics.is_empty() {
+                    QueryDiffStatus::Regressed
+                } else if deltas.values().any(|d| *d > tolerance) {
+                    QueryDiffStatus::Improved
+                } else {
+                    QueryDiffStatus::Unchanged
+                };
+                QueryDiff {
+                    query_id: id.to_string(),
+                    status,
+                    baseline_mode: Some(b.mode.clone()),
+                    candidate_mode: Some(a.mode.clone()),
+                    deltas,
+                    regressed_metrics,
+                }
+            }
+            (Some(b), None) => QueryDiff {
+                query_id: id.to_string(),
+                status: QueryDiffStatus::Removed,
+                baseline_mode: Some(b.mode.clone()),
+                candidate_mode: None,
+                deltas: BTreeMap::new(),
+                regressed_metrics: Vec::new(),
+            },
+            (None, Some(a)) => QueryDiff {
+                query_id: id.to_string(),
+                status: QueryDiffStatus::Added,
+                baseline_mode: None,
+                candidate_mode: Some(a.mode.clone()),
+                deltas: BTreeMap::new(),
+                regressed_metrics: Vec::new(),
+            },
+            (None, None) => continue,
+        };
+        queries.push(diff);
+    }
+
+    let summary_deltas = baseline
+        .summary
+        .iter()
+        .filter_map(|(mode, b)| {
+            candidate
+                .summary
+                .get(mode)
+                .map(|a| (mode.clone(), delta_map(&summary_map(b), &summary_map(a))))
+        })
+        .collect();
+
+    Ok(RetrievalEvalDiff {
+        baseline_run_id: baseline.run_id.clone(),
+        candidate_run_id: candidate.run_id.clone(),
+        query_set_changed: baseline.query_set_hash != candidate.query_set_hash,
+        queries,
+        summary_deltas,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Targets
+// ---------------------------------------------------------------------------
+
+/// The serde name of a planner mode. Unlike
+/// [`QueryRetrievalMode::to_storage_str`] this keeps `passage_fallback`
+/// distinct from `hybrid_rag`, which is the distinction evaluation needs.
+pub fn mode_label(mode: QueryRetrievalMode) -> &'static str {
+    match mode {
+        QueryRetrievalMode::None => "none",
+        QueryRetrievalMode::DirectLoad => "direct_load",
+        QueryRetrievalMode::ExactLookup => "exact_lookup",
+        QueryRetrievalMode::GraphTraversal => "graph_traversal",
+        QueryRetrievalMode::HybridRag => "hybrid_rag",
+        QueryRetrievalMode::PassageFallback => "passage_fallback",
+        QueryRetrievalMode::Blocked => "blocked",
+    }
+}
+
+/// The executed retrieval pipeline ([`execute_retrieval`]). Each query
+/// persists its bundle and trace like any other retrieval, so a surprising
+/// score can be replayed from the trace.
+pub struct ExecutorEvalTarget<'a> {
+    db: &'a PostgresDatabase,
+    pool: &'a PgPool,
+    workspace_id: String,
+    graph_policy: GraphTraversalPolicy,
+}
+
+impl<'a> ExecutorEvalTarget<'a> {
+    pub fn new(
+        db: &'a PostgresDatabase,
+        pool: &'a PgPool,
+        workspace_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            db,
+            pool,
+            workspace_id: workspace_id.into(),
+            graph_policy: GraphTraversalPolicy::default(),
+        }
+    }
+
+    pub fn with_graph_policy(mut self, graph_policy: GraphTraversalPolicy) -> Self {
+        self.graph_policy = graph_policy;
+        self
+    }
+
+    /// A traversed edge counts as its relationship, both endpoints, and its
+    /// evidence spans; a passage counts as itself and its evidence spans.
+    async fn hit_refs(&self, candidate_id: &str, kind: &str) -> StorageResult<Vec<EvalRef>> {
+        let mut refs = Vec::new();
+        if kind == "passage_ref" {
+            refs.push(EvalRef::new(EvalRefKind::Passage, candidate_id));
+            for evidence in self
+                .db
+                .list_knowledge_passage_evidence(candidate_id)
+                .await?
+            {
+                if let KnowledgePassageEvidenceRef::Span { span_id } = evidence {
+                    refs.push(EvalRef::new(EvalRefKind::Span, span_id));
+                }
+            }
+            return Ok(refs);
+        }
+        refs.push(EvalRef::new(EvalRefKind::Relationship, candidate_id));
+        let endpoints: Vec<(String, String)> = sqlx::query_as(
+            "SELECT source_entity_id, target_entity_id FROM knowledge_edges
+             WHERE workspace_id = $1 AND relationship_id = $2",
+        )
+        .bind(&self.workspace_id)
+        .bind(candidate_id)
+        .fetch_all(self.pool)
+        .await?;
+        for (source, target) in endpoints {
+            refs.push(EvalRef::new(EvalRefKind::Entity, source));
+            refs.push(EvalRef::new(EvalRefKind::Entity, target));
+        }
+        let spans: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT s.span_id FROM knowledge_edge_spans s
+             JOIN knowledge_edges e ON e.edge_id = s.edge_id
+             WHERE e.workspace_id = $1 AND e.relationship_id = $2
+             ORDER BY s.span_id",
+        )
+        .bind(&self.workspace_id)
+        .bind(candidate_id)
+        .fetch_all(self.pool)
+        .await?;
+        refs.extend(
+            spans
+                .into_iter()
+                .map(|s| EvalRef::new(EvalRefKind::Span, s)),
+        );
+        refs.sort();
+        refs.dedup();
+        Ok(refs)
+    }
+}
+
+#[async_trait]
+impl RetrievalEvalTarget for ExecutorEvalTarget<'_> {
+    fn target_id(&self) -> String {
+        "knowledge_retrieval.executor".to_string()
+    }
+
+    fn workspace_id(&self) -> &str {
+        &self.workspace_id
+    }
+
+    async fn retrieve(&self, query: &LabelledQuery) -> StorageResult<QueryOutcome> {
+        let mut request = RetrievalRequest::discovery(&self.workspace_id, &query.query_text);
+        request.graph_neighborhood_expected = query.graph_neighborhood_expected;
+        for handle in &query.entity_handles {
+            request = request.with_handle(AuthoritativeHandle::EntityId(handle.clone()));
+        }
+        let seeds: BTreeSet<String> = query.graph_seeds.iter().cloned().collect();
+        let run_ref = format!("retrieval-eval:{}", query.query_id);
+        let executed = execute_retrieval(
+            self.db,
+            self.pool,
+            &run_ref,
+            &run_ref,
+            BundleTargetKind::Task,
+            &query.query_id,
+            &request,
+            &seeds,
+            self.graph_policy.clone(),
+        )
+        .await?;
+        let mut hits = Vec::with_capacity(executed.ranked.len());
+        for candidate in &executed.ranked {
+            hits.push(RankedHit {
+                candidate_id: candidate.candidate_id.clone(),
+                refs: self
+                    .hit_refs(&candidate.candidate_id, &candidate.kind)
+                    .await?,
+            });
+        }
+        Ok(QueryOutcome {
+            mode: mode_label(executed.planned.plan.retrieval_mode).to_string(),
+            hits,
+        })
+    }
+}
+
+/// `loom_search_v2` keyword/trigram (and vector, when an embedding is
+/// supplied) search; hits are credited as blocks.
+pub struct LoomSearchEvalTarget<'a> {
+    db: &'a dyn Database,
+    workspace_id: String,
+    limit: u32,
+}
+
+impl<'a> LoomSearchEvalTarget<'a> {
+    pub fn new(db: &'a dyn Database, workspace_id: impl Into<String>, limit: u32) -> Self {
+        Self {
+            db,
+            workspace_id: workspace_id.into(),
+            limit: limit.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl RetrievalEvalTarget for LoomSearchEvalTarget<'_> {
+    fn target_id(&self) -> String {
+        "loom.search_v2".to_string()
+    }
+
+    fn workspace_id(&self) -> &str {
+        &self.workspace_id
+    }
+
+    async fn retrieve(&self, query: &LabelledQuery) -> StorageResult<QueryOutcome> {
+        let response = self
+            .db
+            .loom_search_v2(
+                &self.workspace_id,
+                LoomSearchV2Request {
+                    query: query.query_text.clone(),
+                    limit: self.limit,
+                    ..LoomSearchV2Request::default()
+                },
+            )
+            .await?;
+        Ok(QueryOutcome {
+            mode: LOOM_SEARCH_MODE.to_string(),
+            hits: response
+                .hits
+                .into_iter()
+                .map(|hit| RankedHit {
+                    refs: vec![EvalRef::new(EvalRefKind::Block, hit.block.block_id.clone())],
+                    candidate_id: hit.block.block_id,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(id: &str, refs: &[(EvalRefKind, &str)]) -> RankedHit {
+        RankedHit {
+            candidate_id: id.to_string(),
+            refs: refs.iter().map(|(k, r)| EvalRef::new(*k, *r)).collect(),
+        }
+    }
+
+    fn label(kind: EvalRefKind, id: &str, grade: u8) -> ExpectedRef {
+        ExpectedRef {
+            kind,
+            id: id.to_string(),
+            grade,
+        }
+    }
+
+    #[test]
+    fn metrics_credit_each_label_once_at_its_first_rank() {
+        let hits = vec![
+            hit("rel-x", &[(EvalRefKind::Entity, "ent-noise")]),
+            hit(
+                "rel-a",
+                &[
+                    (EvalRefKind::Entity, "ent-a"),
+                    (EvalRefKind::Span, "span-1"),
+                ],
+            ),
+            hit("rel-a2", &[(EvalRefKind::Entity, "ent-a")]),
+            hit("blk-b", &[(EvalRefKind::Block, "blk-b")]),
+        ];
+        let expected = vec![
+            label(EvalRefKind::Entity, "ent-a", 3),
+            label(EvalRefKind::Block, "blk-b", 1),
+        ];
+        assert_eq!(recall_at_k(&hits, &expected, 1), 0.0);
+        assert_eq!(recall_at_k(&hits, &expected, 3), 0.5);
+        assert_eq!(recall_at_k(&hits, &expected, 4), 1.0);
+        assert_eq!(reciprocal_rank(&hits, &expected), 0.5);
+
+        // DCG = 7/log2(3) + 1/log2(5); IDCG = 7/log2(2) + 1/log2(3).
+        let dcg = 7.0 / 3f64.log2() + 1.0 / 5f64.log2();
+        let idcg = 7.0 + 1.0 / 3f64.log2();
+        assert!((ndcg_at_k(&hits, &expected, 10) - dcg / idcg).abs() < 1e-12);
+        assert_eq!(ndcg_at_k(&hits, &expected, 1), 0.0);
+
+        let perfect = vec![
+            hit("a", &[(EvalRefKind::Entity, "ent-a")]),
+            hit("b", &[(EvalRefKind::Block, "blk-b")]),
+        ];
+        assert!((ndcg_at_k(&perfect, &expected, 10) - 1.0).abs() < 1e-12);
+        assert_eq!(reciprocal_rank(&[], &expected), 0.0);
+    }
+
+    #[test]
+    fn query_sets_validate_and_bind_placeholders() {
+        let set = LabelledQuerySet::from_json(
+            r#"{
+                "schema_id": "hsk.retrieval_eval_query_set@1",
+                "query_set_id": "unit",
+                "queries": [{
+                    "query_id": "q1",
+                    "query_text": "port",
+                    "graph_seeds": ["{{hub}}"],
+                    "expected": [{ "kind": "entity", "id": "{{port}}", "grade": 3 }]
+                }]
+            }"#,
+        )
+        .expect("valid set");
+        let bindings = BTreeMap::from([
+            ("hub".to_string(), "ENT-hub".to_string()),
+            ("port".to_string(), "ENT-port".to_string()),
+        ]);
+        let bound = set.bind(&bindings).expect("bound");
+        assert_eq!(bound.queries[0].graph_seeds, vec!["ENT-hub".to_string()]);
+        assert_eq!(bound.queries[0].expected[0].id, "ENT-port");
+        assert_ne!(bound.content_hash(), set.content_hash());
+        assert!(set.bind(&BTreeMap::new()).is_err());
+
+        let mut dup = bound.clone();
+        dup.queries.push(dup.queries[0].clone());
+        assert!(dup.validate().is_err());
+        let mut ungraded = bound;
+        ungraded.queries[0].expected[0].grade = 4;
+        assert!(ungraded.validate().is_err());
+    }
+
+    fn run(id: &str, results: Vec<(&str, &str, f64)>) -> RetrievalEvalRun {
+        let ks = vec![1, 5];
+        let results: Vec<QueryEvalResult> = results
+            .into_iter()
+            .map(|(query_id, mode, recall)| QueryEvalResult {
+                query_id: query_id.to_string(),
+                mode: mode.to_string(),
+                ranked_ids: Vec::new(),
+                metrics: QueryMetrics {
+                    recall_at: BTreeMap::from([(1, recall), (5, recall)]),
+                    ndcg_at: BTreeMap::from([(1, recall), (5, recall)]),
+                    reciprocal_rank: recall,
+                    first_relevant_rank: None,
+                },
+            })
+            .collect();
+        RetrievalEvalRun {
+            run_id: id.to_string(),
+            workspace_id: "ws".to_string(),
+            query_set_id: "set".to_string(),
+            query_set_hash: "h".to_string(),
+            target_id: "t".to_string(),
+            label: String::new(),
+            summary: summarize(&results, &ks),
+            ks,
+            results,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn summaries_group_by_mode_and_diff_flags_regressions_per_query() {
+        let baseline = run(
+            "base",
+            vec![
+                ("q1", "graph_traversal", 1.0),
+                ("q2", "passage_fallback", 0.5),
+                ("q3", "graph_traversal", 0.0),
+                ("q4", "hybrid_rag", 1.0),
+            ],
+        );
+        assert_eq!(baseline.summary[ALL_MODES].queries, 4);
+        assert_eq!(baseline.summary["graph_traversal"].queries, 2);
+        assert_eq!(baseline.summary["graph_traversal"].mrr, 0.5);
+
+        let candidate = run(
+            "cand",
+            vec![
+                ("q1", "passage_fallback", 0.5),
+                ("q2", "passage_fallback", 0.5),
+                ("q3", "graph_traversal", 1.0),
+                ("q5", "hybrid_rag", 1.0),
+            ],
+        );
+        let diff = diff_runs(&baseline, &candidate, 1e-9).expect("diff");
+        let status: BTreeMap<&str, QueryDiffStatus> = diff
+            .queries
+            .iter()
+            .map(|q| (q.query_id.as_str(), q.status))
+            .collect();
+        assert_eq!(status["q1"], QueryDiffStatus::Regressed);
+        assert_eq!(status["q2"], QueryDiffStatus::Unchanged);
+        assert_eq!(status["q3"], QueryDiffStatus::Improved);
+        assert_eq!(status["q4"], QueryDiffStatus::Removed);
+        assert_eq!(status["q5"], QueryDiffStatus::Added);
+        assert!(diff.has_regressions());
+        let q1 = diff.regressions().next().expect("q1");
+        assert_eq!(q1.candidate_mode.as_deref(), Some("passage_fallback"));
+        assert!(q1.regressed_metrics.contains(&"recall@1".to_string()));
+        assert!(!diff.query_set_changed);
+
+        let mut other = candidate.clone();
+        other.query_set_id = "other".to_string();
+        assert!(diff_runs(&baseline, &other, 0.0).is_err());
+    }
+}
@@ -12,6 +12,7 @@
 //!                  -> graph traversal (MT-132)
 //!                  -> passage fallback decision (MT-133)
 //!                  -> rank_candidates (MT-134)
+//!                  -> optional cross-encoder rerank of the head
 //!                  -> evidence snippets (MT-135)
 //!                  -> compile + persist bundle + replayable trace (MT-136/138)
 //! ```
@@ -23,7 +24,7 @@
 //! the durable mode column per the MT-129 mode law) and the durable
 //! `mode_reason` + trace warnings/decisions record WHY.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use sqlx::PgPool;
 
@@ -32,6 +33,7 @@ use crate::knowledge_retrieval::budget::PriorityTier;
 use crate::knowledge_retrieval::compiler::{
     BundleCandidate, BundleTargetKind, CompiledBundle, ContextBundleCompilerV2,
 };
+use crate::knowledge_retrieval::graph_analytics::{graph_analytics_status, AnalyticsGraphKind};
 use crate::knowledge_retrieval::graph_planner::{GraphTraversalPlanner, GraphTraversalPolicy};
 use crate::knowledge_retrieval::passage_fallback::{
     decide_passage_fallback, GraphCandidateSignals,
@@ -41,6 +43,9 @@ use crate::knowledge_retrieval::planner::{
     CheapestAuthoritativePathPlanner, PlannedRetrieval, RetrievalRequest,
 };
 use crate::knowledge_retrieval::ranking::{rank_candidates, CandidateFeatures, RankingWeights};
+use crate::knowledge_retrieval::rerank::{
+    rerank_candidates, CrossEncoder, RerankPolicy, RerankStatus,
+};
 use crate::knowledge_retrieval::snippet::{assemble_span_snippet, EvidenceSnippet};
 use crate::memory::retrieval_mode::{NonHybridReason, QueryRetrievalMode};
 use crate::storage::knowledge::{KnowledgePassageEvidenceRef, KnowledgeStore};
@@ -96,6 +101,38 @@ pub async fn execute_retrieval(
     request: &RetrievalRequest,
     graph_seeds: &BTreeSet<String>,
     graph_policy: GraphTraversalPolicy,
+) -> StorageResult<ExecutedRetrieval> {
+    execute_retrieval_with_rerank(
+        db,
+        pool,
+        kernel_task_run_id,
+        session_run_id,
+        target_kind,
+        target_ref,
+        request,
+        graph_seeds,
+        graph_policy,
+        None,
+    )
+    .await
+}
+
+/// [`execute_retrieval`] with the optional cross-encoder rerank stage between
+/// ranking and snippet selection. The head is bounded by the plan's
+/// `max_rerank_candidates` and evidence-token budget; an unavailable model
+/// keeps the MT-134 order and the trace records why.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_retrieval_with_rerank(
+    db: &PostgresDatabase,
+    pool: &PgPool,
+    kernel_task_run_id: &str,
+    session_run_id: &str,
+    target_kind: BundleTargetKind,
+    target_ref: &str,
+    request: &RetrievalRequest,
+    graph_seeds: &BTreeSet<String>,
+    graph_policy: GraphTraversalPolicy,
+    reranker: Option<&dyn CrossEncoder>,
 ) -> StorageResult<ExecutedRetrieval> {
     // 1. Plan (cheapest authoritative; existence-checked handles; catalog
     //    routes when present).
@@ -156,6 +193,7 @@ pub async fn execute_retrieval(
     //    the fallback passages when the fallback fired) and rank (MT-134).
     let mut features: Vec<CandidateFeatures> = Vec::new();
     let mut snippet_span_by_candidate: Vec<(String, Option<String>, Option<i32>)> = Vec::new();
+    let mut passage_text_by_candidate: BTreeMap<String, String> = BTreeMap::new();
     if decision.fallback {
         trace.warnings.push(decision.rationale.clone());
         // The plan is REVISED to the PassageFallback posture: the durable mode
@@ -182,6 +220,7 @@ pub async fn execute_retrieval(
                 source_authority: PASSAGE_SOURCE_AUTHORITY,
                 recency: NEUTRAL_RECENCY,
                 via_hub: false,
+                hub_centrality: 0.0,
                 lexical: None,
                 vector: None,
             });
@@ -195,11 +234,37 @@ pub async fn execute_retrieval(
                     _ => None,
                 });
             snippet_span_by_candidate.push((passage.passage_id.clone(), span, passage.token_count));
+            passage_text_by_candidate
+                .insert(passage.passage_id.clone(), passage.passage_text.clone());
         }
     } else {
+        // Whole-graph centrality joins the traversal's degree cut-off in hub
+        // suppression, but only from a projection computed over the current
+        // graph; a stale one is named in the trace and ignored.
+        let hub_scores = if graph.edges.is_empty() {
+            BTreeMap::new()
+        } else {
+            match graph_analytics_status(pool, &request.workspace_id, AnalyticsGraphKind::Knowledge)
+                .await?
+            {
+                Some(status) if !status.stale => status.projection.report.hub_scores(),
+                Some(status) => {
+                    trace.warnings.push(format!(
+                        "graph analytics {} is stale; centrality hub suppression skipped",
+                        status.projection.projection_id
+                    ));
+                    BTreeMap::new()
+                }
+                None => BTreeMap::new(),
+            }
+        };
         for edge in &graph.edges {
             let via_hub = graph.suppressed_hubs.contains(&edge.source_entity_id)
                 || graph.suppressed_hubs.contains(&edge.target_entity_id);
+            let hub_centrality = [&edge.source_entity_id, &edge.target_entity_id]
+                .into_iter()
+                .filter_map(|entity_id| hub_scores.get(entity_id).copied())
+                .fold(0.0_f64, f64::max);
             features.push(CandidateFeatures {
                 candidate_id: edge.relationship_id.clone(),
                 kind: "entity_ref".to_string(),
@@ -212,6 +277,7 @@ pub async fn execute_retrieval(
                 source_authority: GRAPH_SOURCE_AUTHORITY,
                 recency: NEUTRAL_RECENCY,
                 via_hub,
+                hub_centrality,
                 lexical: None,
                 vector: None,
             });
@@ -228,12 +294,11 @@ pub async fn execute_retrieval(
         }
     }
     let ranked = rank_candidates(features, &RankingWeights::default());
-    trace.candidates = ranked.clone();
 
-    // 6. Evidence snippets (MT-135) + bundle candidates in RANKED order, then
-    //    compile + persist the bounded bundle and its replayable trace
-    //    (MT-136/138).
-    let mut bundle_candidates: Vec<BundleCandidate> = Vec::with_capacity(ranked.len());
+    // 6. Evidence snippets (MT-135) for every ranked candidate; the optional
+    //    rerank stage scores the head against passage text or the cited
+    //    excerpt.
+    let mut snippets: BTreeMap<String, (Option<EvidenceSnippet>, Option<i32>)> = BTreeMap::new();
     for candidate in &ranked {
         let (_, span_id, token_count) = snippet_span_by_candidate
             .iter()
@@ -244,6 +309,49 @@ pub async fn execute_retrieval(
             Some(span_id) => Some(assemble_span_snippet(db, &span_id).await?),
             None => None,
         };
+        snippets.insert(candidate.candidate_id.clone(), (snippet, token_count));
+    }
+    let rerank_policy = RerankPolicy::from_budgets(&planned.plan.budgets);
+    let ranked = match reranker {
+        Some(reranker) => {
+            let text_of = |candidate: &RetrievalCandidate| {
+                passage_text_by_candidate
+                    .get(&candidate.candidate_id)
+                    .cloned()
+                    .or_else(|| {
+                        snippets
+                            .get(&candidate.candidate_id)
+                            .and_then(|(snippet, _)| snippet.as_ref())
+                            .and_then(|snippet| snippet.excerpt.clone())
+                    })
+            };
+            let (reranked, record) = rerank_candidates(
+                Some(reranker),
+                &request.query_text,
+                ranked,
+                text_of,
+                &rerank_policy,
+            )
+            .await;
+            if record.status == RerankStatus::Skipped {
+                trace
+                    .warnings
+                    .push(format!("rerank skipped: {}", record.reason));
+            }
+            trace.rerank = Some(record);
+            reranked
+        }
+        None => ranked,
+    };
+    trace.candidates = ranked.clone();
+
+    // 7. Bundle candidates in final order, then compile + persist the bounded
+    //    bundle and its replayable trace (MT-136/138).
+    let mut bundle_candidates: Vec<BundleCandidate> = Vec::with_capacity(ranked.len());
+    for candidate in &ranked {
+        let (snippet, token_count) = snippets
+            .remove(&candidate.candidate_id)
+            .unwrap_or((None, None));
         let token_count = token_count
             .map(|t| t.max(1) as u32)
             .or_else(|| {
@@ -263,7 +371,7 @@ pub async fn execute_retrieval(
             ref_id: candidate.candidate_id.clone(),
             tier,
             token_count,
-            relevance_score: candidate.base_score,
+            relevance_score: rerank_policy.candidate_score(candidate),
             source_id: snippet
                 .as_ref()
                 .map(|s| s.source_id.clone())
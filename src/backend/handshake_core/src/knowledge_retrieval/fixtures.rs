@@ -72,6 +72,7 @@ pub fn candidate_feature(
         source_authority: 0.7,
         recency: 0.8,
         via_hub: false,
+        hub_centrality: 0.0,
         lexical: Some(quality),
         vector: Some(proximity),
     }
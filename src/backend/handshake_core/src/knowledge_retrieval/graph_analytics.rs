@@ -0,0 +1,914 @@
+//! Whole-graph analytics over the committed KnowledgeEdge graph and the Loom
+//! block graph.
+//!
+//! [`graph_planner`](super::graph_planner) and `Database::local_graph` /
+//! `global_graph` only walk bounded neighbourhoods. This module looks at the
+//! structure of the whole workspace graph:
+//!   * PageRank + in/out degree, and a size-independent `hub_score` derived
+//!     from PageRank (how many times the uniform share a node holds),
+//!   * community detection (Louvain modularity, deterministic tie-breaks),
+//!   * bridge nodes — nodes with neighbours in a community other than their
+//!     own,
+//!   * orphans (no edges at all) and dead ends (linked to, links nowhere),
+//!   * shortest-path "how is A connected to B" explanations.
+//!
+//! The algorithms are pure functions over an [`AnalyticsGraph`]. A refresh
+//! loads the graph from PostgreSQL, analyzes it, and stores the report as a
+//! derived projection (`knowledge_graph_analytics`, migration 0340) stamped
+//! with a `staleness_hash` of the graph's revision counter
+//! (`knowledge_graph_revisions`, migration 0344, bumped by triggers on every
+//! node and edge change). Checking staleness is one primary-key read, cheap
+//! enough for every retrieval. Projections are never authority: a stale
+//! projection is reported as stale, and the executor only lets a FRESH
+//! projection's hub scores feed hub suppression in
+//! [`ranking`](super::ranking).
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+
+use crate::storage::knowledge_retrieval::{
+    get_graph_analytics_projection, upsert_graph_analytics_projection, GraphAnalyticsProjection,
+};
+use crate::storage::{StorageError, StorageResult};
+
+/// Hard cap on the nodes one analytics refresh will load. Larger graphs are
+/// rejected rather than analyzed partially (a partial PageRank is wrong, not
+/// approximate).
+pub const MAX_ANALYTICS_NODES: i64 = 100_000;
+/// Hard cap on the edges one analytics refresh will load.
+pub const MAX_ANALYTICS_EDGES: i64 = 500_000;
+/// Default and maximum hop bound for connection explanations.
+pub const DEFAULT_EXPLAIN_HOPS: u32 = 6;
+pub const MAX_EXPLAIN_HOPS: u32 = 12;
+
+/// Which workspace graph an analytics projection describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsGraphKind {
+    /// Active `knowledge_edges` between `knowledge_entities`.
+    Knowledge,
+    /// `loom_edges` between `loom_blocks`, excluding unconfirmed
+    /// `ai_suggested` edges.
+    Loom,
+}
+
+impl AnalyticsGraphKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Knowledge => "knowledge",
+            Self::Loom => "loom",
+        }
+    }
+
+    pub fn from_db(value: &str) -> StorageResult<Self> {
+        match value {
+            "knowledge" => Ok(Self::Knowledge),
+            "loom" => Ok(Self::Loom),
+            _ => Err(StorageError::Validation("invalid graph analytics kind")),
+        }
+    }
+}
+
+/// One directed edge of the analyzed graph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyticsEdge {
+    pub edge_id: String,
+    pub edge_type: String,
+    pub source: String,
+    pub target: String,
+}
+
+/// The input to every algorithm here: a node set plus directed edges. Nodes
+/// are kept sorted and edges ordered by `edge_id` so every result is
+/// deterministic for the same graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnalyticsGraph {
+    pub nodes: Vec<String>,
+    pub edges: Vec<AnalyticsEdge>,
+    /// Display labels (entity names, block titles) used in explanations.
+    pub labels: BTreeMap<String, String>,
+}
+
+impl AnalyticsGraph {
+    /// Build a graph; edge endpoints missing from `nodes` are added.
+    pub fn new(nodes: impl IntoIterator<Item = String>, mut edges: Vec<AnalyticsEdge>) -> Self {
+        let mut node_set: BTreeSet<String> = nodes.into_iter().collect();
+        for edge in &edges {
+            node_set.insert(edge.source.clone());
+            node_set.insert(edge.target.clone());
+        }
+        edges.sort_by(|a, b| a.edge_id.cmp(&b.edge_id));
+        Self {
+            nodes: node_set.into_iter().collect(),
+            edges,
+            labels: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_labels(mut self, labels: BTreeMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    fn label<'a>(&'a self, node_id: &'a str) -> &'a str {
+        self.labels
+            .get(node_id)
+            .map(String::as_str)
+            .filter(|label| !label.is_empty())
+            .unwrap_or(node_id)
+    }
+
+    fn index(&self) -> BTreeMap<&str, usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| (id.as_str(), idx))
+            .collect()
+    }
+}
+
+/// Tunables for [`analyze`]. Defaults are the textbook PageRank settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnalyticsConfig {
+    pub damping: f64,
+    pub max_iterations: usize,
+    /// PageRank stops once the L1 change between iterations drops below this.
+    pub tolerance: f64,
+    /// Bound on Louvain local-moving sweeps per level.
+    pub max_sweeps: usize,
+    /// Bound on Louvain aggregation levels.
+    pub max_levels: usize,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            max_iterations: 100,
+            tolerance: 1e-9,
+            max_sweeps: 64,
+            max_levels: 16,
+        }
+    }
+}
+
+/// Per-node analytics.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeAnalytics {
+    pub node_id: String,
+    pub pagerank: f64,
+    /// `1 - 1 / (pagerank * node_count)` when the node holds more than its
+    /// uniform share of PageRank, else 0. 0.5 = twice the uniform share,
+    /// 0.9 = ten times; independent of graph size.
+    pub hub_score: f64,
+    pub in_degree: u32,
+    pub out_degree: u32,
+    pub community: usize,
+}
+
+/// One detected community. `anchor` is its highest-PageRank member.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphCommunity {
+    pub community: usize,
+    pub size: usize,
+    pub anchor: String,
+    pub members: Vec<String>,
+}
+
+/// A node with neighbours in communities other than its own.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BridgeNode {
+    pub node_id: String,
+    pub community: usize,
+    pub linked_communities: Vec<usize>,
+}
+
+/// The stored analytics report for one workspace graph.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphAnalyticsReport {
+    pub graph_kind: AnalyticsGraphKind,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Highest PageRank first; ties by node id.
+    pub nodes: Vec<NodeAnalytics>,
+    /// Largest community first.
+    pub communities: Vec<GraphCommunity>,
+    /// Bridges linking the most communities first.
+    pub bridges: Vec<BridgeNode>,
+    pub orphans: Vec<String>,
+    pub dead_ends: Vec<String>,
+    /// True when [`GraphAnalyticsReport::bounded`] clipped any list.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl GraphAnalyticsReport {
+    /// Hub scores keyed by node id, for ranking.
+    pub fn hub_scores(&self) -> BTreeMap<String, f64> {
+        self.nodes
+            .iter()
+            .map(|node| (node.node_id.clone(), node.hub_score))
+            .collect()
+    }
+
+    /// A copy with every list clipped to `limit` entries, for API responses.
+    pub fn bounded(&self, limit: usize) -> Self {
+        let mut out = self.clone();
+        let mut clipped = false;
+        let mut clip = |len: usize| {
+            clipped |= len > limit;
+            len.min(limit)
+        };
+        out.nodes.truncate(clip(self.nodes.len()));
+        out.communities.truncate(clip(self.communities.len()));
+        for community in &mut out.communities {
+            let keep = clip(community.members.len());
+            community.members.truncate(keep);
+        }
+        out.bridges.truncate(clip(self.bridges.len()));
+        out.orphans.truncate(clip(self.orphans.len()));
+        out.dead_ends.truncate(clip(self.dead_ends.len()));
+        out.truncated = self.truncated || clipped;
+        out
+    }
+}
+
+/// Run every analysis over `graph`.
+pub fn analyze(
+    kind: AnalyticsGraphKind,
+    graph: &AnalyticsGraph,
+    config: &AnalyticsConfig,
+) -> GraphAnalyticsReport {
+    let n = graph.nodes.len();
+    let index = graph.index();
+    let links: Vec<(usize, usize)> = graph
+        .edges
+        .iter()
+        .map(|edge| (index[edge.source.as_str()], index[edge.target.as_str()]))
+        .collect();
+
+    let mut in_degree = vec![0u32; n];
+    let mut out_degree = vec![0u32; n];
+    let mut neighbours: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+    for &(source, target) in &links {
+        out_degree[source] += 1;
+        in_degree[target] += 1;
+        if source != target {
+            neighbours[source].insert(target);
+            neighbours[target].insert(source);
+        }
+    }
+
+    let ranks = pagerank(n, &links, config);
+    let community_of = louvain(n, &links, config);
+
+    let mut nodes: Vec<NodeAnalytics> = (0..n)
+        .map(|idx| NodeAnalytics {
+            node_id: graph.nodes[idx].clone(),
+            pagerank: ranks[idx],
+            hub_score: hub_score(ranks[idx], n),
+            in_degree: in_degree[idx],
+            out_degree: out_degree[idx],
+            community: community_of[idx],
+        })
+        .collect();
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (idx, &community) in community_of.iter().enumerate() {
+        members.entry(community).or_default().push(idx);
+    }
+    let communities: Vec<GraphCommunity> = members
+        .into_iter()
+        .map(|(community, idxs)| {
+            let anchor = idxs
+                .iter()
+                .copied()
+                .max_by(|&a, &b| ranks[a].total_cmp(&ranks[b]).then(b.cmp(&a)))
+                .unwrap_or(0);
+            GraphCommunity {
+                community,
+                size: idxs.len(),
+                anchor: graph.nodes[anchor].clone(),
+                members: idxs.iter().map(|&idx| graph.nodes[idx].clone()).collect(),
+            }
+        })
+        .collect();
+
+    let mut bridges: Vec<BridgeNode> = (0..n)
+        .filter_map(|idx| {
+            let linked: BTreeSet<usize> = neighbours[idx]
+                .iter()
+                .map(|&other| community_of[other])
+                .filter(|&community| community != community_of[idx])
+                .collect();
+            (!linked.is_empty()).then(|| BridgeNode {
+                node_id: graph.nodes[idx].clone(),
+                community: community_of[idx],
+                linked_communities: linked.into_iter().collect(),
+            })
+        })
+        .collect();
+    bridges.sort_by(|a, b| {
+        b.linked_communities
+            .len()
+            .cmp(&a.linked_communities.len())
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+
+    let orphans = (0..n)
+        .filter(|&idx| in_degree[idx] == 0 && out_degree[idx] == 0)
+        .map(|idx| graph.nodes[idx].clone())
+        .collect();
+    let dead_ends = (0..n)
+        .filter(|&idx| in_degree[idx] > 0 && out_degree[idx] == 0)
+        .map(|idx| graph.nodes[idx].clone())
+        .collect();
+
+    nodes.sort_by(|a, b| {
+        b.pagerank
+            .total_cmp(&a.pagerank)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+
+    GraphAnalyticsReport {
+        graph_kind: kind,
+        node_count: n,
+        edge_count: graph.edges.len(),
+        nodes,
+        communities,
+        bridges,
+        orphans,
+        dead_ends,
+        truncated: false,
+    }
+}
+
+fn hub_score(rank: f64, node_count: usize) -> f64 {
+    let share = rank * node_count as f64;
+    if share > 1.0 {
+        1.0 - 1.0 / share
+    } else {
+        0.0
+    }
+}
+
+/// Directed PageRank. Parallel edges count with multiplicity; a dangling
+/// node's mass is spread uniformly.
+fn pagerank(n: usize, links: &[(usize, usize)], config: &AnalyticsConfig) -> Vec<f64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let uniform = 1.0 / n as f64;
+    let mut out_weight = vec![0.0f64; n];
+    for &(source, _) in links {
+        out_weight[source] += 1.0;
+    }
+    let mut ranks = vec![uniform; n];
+    for _ in 0..config.max_iterations {
+        let dangling: f64 = (0..n)
+            .filter(|&idx| out_weight[idx] == 0.0)
+            .map(|idx| ranks[idx])
+            .sum();
+        let base = (1.0 - config.damping) * uniform + config.damping * dangling * uniform;
+        let mut next = vec![base; n];
+        for &(source, target) in links {
+            next[target] += config.damping * ranks[source] / out_weight[source];
+        }
+        let delta: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if delta < config.tolerance {
+            break;
+        }
+    }
+    ranks
+}
+
+/// Louvain modularity communities over the undirected view of the graph
+/// (self-loops ignored). Nodes are visited in index order and a move needs a
+/// strictly better gain, so the partition is deterministic. Community ids are
+/// renumbered largest-first (ties by smallest member).
+fn louvain(n: usize, links: &[(usize, usize)], config: &AnalyticsConfig) -> Vec<usize> {
+    // Symmetric weights; a level's self weight is twice its internal weight so
+    // `degree = sum of row` holds at every aggregation level.
+    let mut adjacency: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); n];
+    for &(source, target) in links {
+        if source != target {
+            *adjacency[source].entry(target).or_default() += 1.0;
+            *adjacency[target].entry(source).or_default() += 1.0;
+        }
+    }
+    let mut membership: Vec<usize> = (0..n).collect();
+
+    for _ in 0..config.max_levels {
+        let level_n = adjacency.len();
+        let degree: Vec<f64> = adjacency.iter().map(|row| row.values().sum()).collect();
+        let two_m: f64 = degree.iter().sum();
+        if two_m == 0.0 {
+            break;
+        }
+        let mut community: Vec<usize> = (0..level_n).collect();
+        let mut total: Vec<f64> = degree.clone();
+
+        for _ in 0..config.max_sweeps {
+            let mut moved = false;
+            for node in 0..level_n {
+                let current = community[node];
+                let mut weight_to: BTreeMap<usize, f64> = BTreeMap::new();
+                for (&other, &weight) in &adjacency[node] {
+                    if other != node {
+                        *weight_to.entry(community[other]).or_default() += weight;
+                    }
+                }
+                total[current] -= degree[node];
+                let gain = |target: usize| {
+                    weight_to.get(&target).copied().unwrap_or(0.0)
+                        - total[target] * degree[node] / two_m
+                };
+                let mut best = current;
+                let mut best_gain = gain(current);
+                for &candidate in weight_to.keys() {
+                    let candidate_gain = gain(candidate);
+                    if candidate_gain > best_gain + 1e-12 {
+                        best = candidate;
+                        best_gain = candidate_gain;
+                    }
+                }
+                total[best] += degree[node];
+                if best != current {
+                    community[node] = best;
+                    moved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+
+        // Renumber this level's communities densely in first-seen order.
+        let mut dense: BTreeMap<usize, usize> = BTreeMap::new();
+        for &label in &community {
+            let next = dense.len();
+            dense.entry(label).or_insert(next);
+        }
+        if dense.len() == level_n {
+            break;
+        }
+        for slot in &mut membership {
+            *slot = dense[&community[*slot]];
+        }
+        let mut aggregated: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); dense.len()];
+        for (node, row) in adjacency.iter().enumerate() {
+            let from = dense[&community[node]];
+            for (&other, &weight) in row {
+                *aggregated[from]
+                    .entry(dense[&community[other]])
+                    .or_default() += weight;
+            }
+        }
+        adjacency = aggregated;
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (node, &label) in membership.iter().enumerate() {
+        groups.entry(label).or_default().push(node);
+    }
+    let mut ordered: Vec<Vec<usize>> = groups.into_values().collect();
+    ordered.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    let mut out = vec![0usize; n];
+    for (id, group) in ordered.iter().enumerate() {
+        for &node in group {
+            out[node] = id;
+        }
+    }
+    out
+}
+
+/// One hop of a connection path. `forward` is false when the edge was walked
+/// against its direction (`to` points at `from`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathHop {
+    pub from: String,
+    pub to: String,
+    pub edge_id: String,
+    pub edge_type: String,
+    pub forward: bool,
+}
+
+/// A shortest "how is A connected to B" answer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionExplanation {
+    pub from: String,
+    pub to: String,
+    pub hops: Vec<PathHop>,
+    /// One readable line, e.g. `A -[defines]-> X <-[mentions]- B`.
+    pub summary: String,
+}
+
+/// Shortest undirected path from `from` to `to` within `max_hops`, walking
+/// neighbours in (node id, edge id) order so equal-length paths resolve the
+/// same way every time. `None` when either node is absent or no path exists
+/// within the bound.
+pub fn explain_connection(
+    graph: &AnalyticsGraph,
+    from: &str,
+    to: &str,
+    max_hops: u32,
+) -> Option<ConnectionExplanation> {
+    let index = graph.index();
+    let (&start, &goal) = (index.get(from)?, index.get(to)?);
+
+    let mut adjacency: Vec<Vec<(usize, usize, bool)>> = vec![Vec::new(); graph.nodes.len()];
+    for (edge_idx, edge) in graph.edges.iter().enumerate() {
+        let (source, target) = (index[edge.source.as_str()], index[edge.target.as_str()]);
+        if source != target {
+            adjacency[source].push((target, edge_idx, true));
+            adjacency[target].push((source, edge_idx, false));
+        }
+    }
+    for row in &mut adjacency {
+        row.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+    }
+
+    let mut came_from: BTreeMap<usize, (usize, usize, bool)> = BTreeMap::new();
+    let mut depth: BTreeMap<usize, u32> = BTreeMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if node == goal {
+            break;
+        }
+        let next_depth = depth[&node] + 1;
+        if next_depth > max_hops {
+            continue;
+        }
+        for &(other, edge_idx, forward) in &adjacency[node] {
+            if let std::collections::btree_map::Entry::Vacant(slot) = depth.entry(other) {
+                slot.insert(next_depth);
+                came_from.insert(other, (node, edge_idx, forward));
+                queue.push_back(other);
+            }
+        }
+    }
+    if !depth.contains_key(&goal) {
+        return None;
+    }
+
+    let mut hops = Vec::new();
+    let mut cursor = goal;
+    while let Some(&(prev, edge_idx, forward)) = came_from.get(&cursor) {
+        let edge = &graph.edges[edge_idx];
+        hops.push(PathHop {
+            from: graph.nodes[prev].clone(),
+            to: graph.nodes[cursor].clone(),
+            edge_id: edge.edge_id.clone(),
+            edge_type: edge.edge_type.clone(),
+            forward,
+        });
+        cursor = prev;
+    }
+    hops.reverse();
+
+    let mut summary = graph.label(from).to_string();
+    for hop in &hops {
+        let arrow = if hop.forward {
+            format!(" -[{}]-> ", hop.edge_type)
+        } else {
+            format!(" <-[{}]- ", hop.edge_type)
+        };
+        summary.push_str(&arrow);
+        summary.push_str(graph.label(&hop.to));
+    }
+    Some(ConnectionExplanation {
+        from: from.to_string(),
+        to: to.to_string(),
+        hops,
+        summary,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// PostgreSQL loading + projection refresh
+// ---------------------------------------------------------------------------
+
+/// The graph's revision counter, maintained by the migration 0344 triggers.
+/// A workspace whose graph has never changed is at revision 0.
+pub async fn graph_revision(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+) -> StorageResult<i64> {
+    let revision: Option<i64> = sqlx::query_scalar(
+        "SELECT revision FROM knowledge_graph_revisions
+         WHERE workspace_id = $1 AND graph_kind = $2",
+    )
+    .bind(workspace_id)
+    .bind(kind.as_str())
+    .fetch_optional(pool)
+    .await?;
+    Ok(revision.unwrap_or(0))
+}
+
+/// The `staleness_hash` recorded for a graph at `revision`: sha256 of the
+/// kind and counter, so it keeps the projection table's hex-digest shape.
+pub fn revision_stamp(kind: AnalyticsGraphKind, revision: i64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}:{revision}", kind.as_str()).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+async fn graph_stamp(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+) -> StorageResult<String> {
+    Ok(revision_stamp(
+        kind,
+        graph_revision(pool, workspace_id, kind).await?,
+    ))
+}
+
+/// Load one workspace graph. Fails with a validation error above the
+/// node/edge caps instead of analyzing a clipped graph.
+pub async fn load_analytics_graph(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+) -> StorageResult<AnalyticsGraph> {
+    let (node_sql, edge_sql) = match kind {
+        AnalyticsGraphKind::Knowledge => (
+            r#"
+            SELECT entity_id AS node_id, display_name AS label
+            FROM knowledge_entities
+            WHERE workspace_id = $1 AND lifecycle_state = 'active'
+            LIMIT $2
+            "#,
+            r#"
+            SELECT edge_id, edge_type, source_entity_id AS source, target_entity_id AS target
+            FROM knowledge_edges
+            WHERE workspace_id = $1 AND lifecycle_state = 'active'
+            LIMIT $2
+            "#,
+        ),
+        AnalyticsGraphKind::Loom => (
+            r#"
+            SELECT block_id AS node_id, COALESCE(title, original_filename, '') AS label
+            FROM loom_blocks
+            WHERE workspace_id = $1
+            LIMIT $2
+            "#,
+            r#"
+            SELECT edge_id, edge_type, source_block_id AS source, target_block_id AS target
+            FROM loom_edges
+            WHERE workspace_id = $1 AND edge_type <> 'ai_suggested'
+            LIMIT $2
+            "#,
+        ),
+    };
+    let node_rows = sqlx::query(node_sql)
+        .bind(workspace_id)
+        .bind(MAX_ANALYTICS_NODES + 1)
+        .fetch_all(pool)
+        .await?;
+    if node_rows.len() as i64 > MAX_ANALYTICS_NODES {
+        return Err(StorageError::Validation(
+            "graph exceeds the analytics node cap",
+        ));
+    }
+    let edge_rows = sqlx::query(edge_sql)
+        .bind(workspace_id)
+        .bind(MAX_ANALYTICS_EDGES + 1)
+        .fetch_all(pool)
+        .await?;
+    if edge_rows.len() as i64 > MAX_ANALYTICS_EDGES {
+        return Err(StorageError::Validation(
+            "graph exceeds the analytics edge cap",
+        ));
+    }
+
+    let mut labels = BTreeMap::new();
+    let mut nodes = Vec::with_capacity(node_rows.len());
+    for row in &node_rows {
+        let node_id: String = row.get("node_id");
+        labels.insert(node_id.clone(), row.get::<String, _>("label"));
+        nodes.push(node_id);
+    }
+    let edges = edge_rows
+        .iter()
+        .map(|row| AnalyticsEdge {
+            edge_id: row.get("edge_id"),
+            edge_type: row.get("edge_type"),
+            source: row.get("source"),
+            target: row.get("target"),
+        })
+        .collect();
+    Ok(AnalyticsGraph::new(nodes, edges).with_labels(labels))
+}
+
+/// The analytics job: stamp, load, analyze, store. The stamp is taken
+/// BEFORE the load, so a write racing the refresh leaves the projection
+/// reported stale rather than silently fresh. PageRank and Louvain over a
+/// capped graph can take seconds, so the analysis runs on the blocking pool
+/// rather than on the request's runtime worker.
+pub async fn refresh_graph_analytics(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+    config: &AnalyticsConfig,
+) -> StorageResult<GraphAnalyticsProjection> {
+    let staleness_hash = graph_stamp(pool, workspace_id, kind).await?;
+    let graph = load_analytics_graph(pool, workspace_id, kind).await?;
+    let config = *config;
+    let report = tokio::task::spawn_blocking(move || analyze(kind, &graph, &config))
+        .await
+        .map_err(|err| StorageError::Database(format!("graph analytics task failed: {err}")))?;
+    upsert_graph_analytics_projection(pool, workspace_id, &staleness_hash, &report).await
+}
+
+/// A stored projection plus whether the graph has changed since it was
+/// computed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphAnalyticsStatus {
+    pub projection: GraphAnalyticsProjection,
+    pub stale: bool,
+    pub current_hash: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// The latest projection for a workspace graph with its staleness verdict;
+/// `None` when analytics have never been computed.
+pub async fn graph_analytics_status(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+) -> StorageResult<Option<GraphAnalyticsStatus>> {
+    let Some(projection) = get_graph_analytics_projection(pool, workspace_id, kind).await? else {
+        return Ok(None);
+    };
+    let current_hash = graph_stamp(pool, workspace_id, kind).await?;
+    Ok(Some(GraphAnalyticsStatus {
+        stale: current_hash != projection.staleness_hash,
+        projection,
+        current_hash,
+        checked_at: Utc::now(),
+    }))
+}
+
+/// Load the current graph and explain how `from` reaches `to`.
+pub async fn explain_connection_in_workspace(
+    pool: &PgPool,
+    workspace_id: &str,
+    kind: AnalyticsGraphKind,
+    from: &str,
+    to: &str,
+    max_hops: u32,
+) -> StorageResult<Option<ConnectionExplanation>> {
+    let graph = load_analytics_graph(pool, workspace_id, kind).await?;
+    if !graph.nodes.iter().any(|id| id == from) || !graph.nodes.iter().any(|id| id == to) {
+        return Err(StorageError::NotFound("graph_node"));
+    }
+    Ok(explain_connection(
+        &graph,
+        from,
+        to,
+        max_hops.clamp(1, MAX_EXPLAIN_HOPS),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(id: &str, source: &str, target: &str) -> AnalyticsEdge {
+        AnalyticsEdge {
+            edge_id: id.to_string(),
+            edge_type: "references".to_string(),
+            source: source.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    /// Two triangles joined by c -> d, a spoke x -> a, and an isolated node.
+    fn two_clusters() -> AnalyticsGraph {
+        AnalyticsGraph::new(
+            ["lonely".to_string()],
+            vec![
+                edge("e01", "a", "b"),
+                edge("e02", "b", "c"),
+                edge("e03", "c", "a"),
+                edge("e04", "d", "e"),
+                edge("e05", "e", "f"),
+                edge("e06", "f", "d"),
+                edge("e07", "c", "d"),
+                edge("e08", "x", "a"),
+                edge("e09", "b", "sink"),
+            ],
+        )
+    }
+
+    #[test]
+    fn pagerank_sums_to_one_and_favours_linked_nodes() {
+        let graph = two_clusters();
+        let report = analyze(
+            AnalyticsGraphKind::Knowledge,
+            &graph,
+            &AnalyticsConfig::default(),
+        );
+        let total: f64 = report.nodes.iter().map(|node| node.pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6, "pagerank mass {total}");
+
+        let by_id: BTreeMap<&str, &NodeAnalytics> = report
+            .nodes
+            .iter()
+            .map(|node| (node.node_id.as_str(), node))
+            .collect();
+        // c -> d drains the first triangle into the second, which keeps it.
+        assert!(by_id["d"].pagerank > by_id["c"].pagerank);
+        assert!(by_id["d"].hub_score > 0.0);
+        assert_eq!(by_id["c"].hub_score, 0.0);
+        assert_eq!(by_id["lonely"].hub_score, 0.0);
+        assert_eq!(report.orphans, vec!["lonely".to_string()]);
+        assert_eq!(report.dead_ends, vec!["sink".to_string()]);
+        assert_eq!(
+            analyze(
+                AnalyticsGraphKind::Knowledge,
+                &graph,
+                &AnalyticsConfig::default()
+            ),
+            report,
+            "analysis is deterministic"
+        );
+    }
+
+    #[test]
+    fn communities_split_the_triangles_and_bridges_join_them() {
+        let report = analyze(
+            AnalyticsGraphKind::Loom,
+            &two_clusters(),
+            &AnalyticsConfig::default(),
+        );
+        let community: BTreeMap<&str, usize> = report
+            .nodes
+            .iter()
+            .map(|node| (node.node_id.as_str(), node.community))
+            .collect();
+        assert_eq!(community["a"], community["b"]);
+        assert_eq!(community["b"], community["c"]);
+        assert_eq!(community["d"], community["e"]);
+        assert_eq!(community["e"], community["f"]);
+        assert_ne!(community["c"], community["d"]);
+
+        let bridge_ids: Vec<&str> = report.bridges.iter().map(|b| b.node_id.as_str()).collect();
+        assert!(
+            bridge_ids.contains(&"c") && bridge_ids.contains(&"d"),
+            "{bridge_ids:?}"
+        );
+        assert!(!bridge_ids.contains(&"e"));
+        assert!(report.communities[0].size >= report.communities[1].size);
+
+        let bounded = report.bounded(1);
+        assert!(bounded.truncated);
+        assert_eq!(bounded.nodes.len(), 1);
+        assert!(bounded.communities.iter().all(|c| c.members.len() <= 1));
+    }
+
+    #[test]
+    fn revision_stamps_are_digests_per_kind_and_revision() {
+        let stamp = revision_stamp(AnalyticsGraphKind::Knowledge, 7);
+        assert_eq!(stamp.len(), 64);
+        assert!(stamp.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_eq!(stamp, revision_stamp(AnalyticsGraphKind::Knowledge, 7));
+        assert_ne!(stamp, revision_stamp(AnalyticsGraphKind::Knowledge, 8));
+        assert_ne!(stamp, revision_stamp(AnalyticsGraphKind::Loom, 7));
+    }
+
+    #[test]
+    fn connection_explanation_walks_edges_in_both_directions() {
+        let graph = two_clusters().with_labels(BTreeMap::from([
+            ("x".to_string(), "Intro".to_string()),
+            ("f".to_string(), "Finale".to_string()),
+        ]));
+        let path = explain_connection(&graph, "x", "f", DEFAULT_EXPLAIN_HOPS).expect("connected");
+        assert_eq!(path.hops.len(), 4, "{path:?}");
+        assert_eq!(path.hops[0].edge_id, "e08");
+        assert!(
+            path.hops.iter().any(|hop| !hop.forward),
+            "f -> d is walked backwards"
+        );
+        assert_eq!(
+            path.summary,
+            "Intro -[references]-> a <-[references]- c -[references]-> d <-[references]- Finale"
+        );
+
+        assert!(
+            explain_connection(&graph, "x", "f", 3).is_none(),
+            "beyond the hop bound"
+        );
+        assert!(explain_connection(&graph, "x", "lonely", 12).is_none());
+        assert!(explain_connection(&graph, "x", "missing", 12).is_none());
+        let same = explain_connection(&graph, "a", "a", 1).expect("trivial path");
+        assert!(same.hops.is_empty());
+    }
+}
@@ -26,24 +26,32 @@
 //! authoritative [`plan::QueryPlan`] mode (recording the non-hybrid reason);
 //! [`schema_filter`] narrows fact candidates; [`graph_planner`] expands a
 //! bounded neighborhood; [`passage_fallback`] catches missing/stale/contradicted
-//! graphs; [`ranking`] scores candidates deterministically; [`snippet`] cites
+//! graphs; [`ranking`] scores candidates deterministically and [`rerank`]
+//! optionally rescores the head with a cross-encoder; [`snippet`] cites
 //! them with spans/hashes/receipts; [`budget`] bounds the set; [`compiler`]
 //! persists the kernel ContextBundle V1 + a replayable [`plan::RetrievalTrace`].
 //! The four bridges ([`project_brain`], [`semantic_catalog`], [`ai_ready_export`],
 //! [`context_pack_recorder`]) connect the folded-stub concepts into this layer.
+//! [`evaluation`] measures the pipeline against labelled query sets and diffs
+//! stored runs for regressions. [`graph_analytics`] computes whole-graph
+//! centrality, communities, bridges, and connection paths as stale-checked
+//! projections; fresh hub scores feed [`ranking`]'s hub suppression.
 
 pub mod ai_ready_export;
 pub mod budget;
 pub mod compiler;
 pub mod context_pack_recorder;
+pub mod evaluation;
 pub mod executor;
 pub mod fixtures;
+pub mod graph_analytics;
 pub mod graph_planner;
 pub mod passage_fallback;
 pub mod plan;
 pub mod planner;
 pub mod project_brain;
 pub mod ranking;
+pub mod rerank;
 pub mod schema_filter;
 pub mod semantic_catalog;
 pub mod snippet;
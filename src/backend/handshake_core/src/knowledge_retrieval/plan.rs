@@ -20,6 +20,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
+use crate::knowledge_retrieval::rerank::RerankRecord;
 use crate::memory::retrieval_mode::{NonHybridReason, QueryKind, QueryRetrievalMode};
 
 /// A retrieval store a route step targets (spec 2.6.6.7.14.5 `RouteStep.store`),
@@ -265,6 +266,9 @@ pub struct CandidateScores {
     pub pack: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trust_adjust: Option<f64>,
+    /// Cross-encoder relevance from the optional rerank stage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<f64>,
 }
 
 /// One taken route step in the executed trace (spec 2.6.6.7.14.5
@@ -307,6 +311,10 @@ pub struct RetrievalTrace {
     pub warnings: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<String>,
+    /// What the optional cross-encoder rerank stage did; absent when the
+    /// retrieval ran without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankRecord>,
 }
 
 impl RetrievalTrace {
@@ -324,6 +332,7 @@ impl RetrievalTrace {
             truncation_flags: Vec::new(),
             warnings: Vec::new(),
             errors: Vec::new(),
+            rerank: None,
         }
     }
 
@@ -5,7 +5,10 @@
 //! candidates using evidence quality, graph proximity, relationship type,
 //! source authority, recency, and hub suppression — DETERMINISTICALLY, with a
 //! stable tie-break so the same inputs always yield the same order (a
-//! requirement for replayable RetrievalTraces).
+//! requirement for replayable RetrievalTraces). Hub suppression takes both the
+//! traversal's degree cut-off (`via_hub`) and, when a fresh graph analytics
+//! projection exists, the PageRank-derived hub score of the nodes a candidate
+//! touches (`hub_centrality`, see [`graph_analytics`](super::graph_analytics)).
 //!
 //! This is a pure, side-effect-free scoring function over the structured
 //! features the upstream planners already gathered. It does not read the DB; it
@@ -27,6 +30,9 @@ pub struct RankingWeights {
     pub recency: f64,
     /// Penalty subtracted when the candidate came through a suppressed hub.
     pub hub_penalty: f64,
+    /// Hub score at or below which centrality adds no penalty. Above it the
+    /// penalty scales linearly up to the full `hub_penalty` at score 1.0.
+    pub centrality_hub_floor: f64,
 }
 
 impl Default for RankingWeights {
@@ -38,6 +44,7 @@ impl Default for RankingWeights {
             source_authority: 0.20,
             recency: 0.15,
             hub_penalty: 0.25,
+            centrality_hub_floor: 0.5,
         }
     }
 }
@@ -62,6 +69,9 @@ pub struct CandidateFeatures {
     pub recency: f64,
     /// Whether this candidate was reached through a suppressed hub.
     pub via_hub: bool,
+    /// Highest hub score (graph analytics, `[0, 1]`) among the nodes the
+    /// candidate touches; 0.0 when no fresh projection is available.
+    pub hub_centrality: f64,
     /// Optional component scores to surface in the trace candidate.
     pub lexical: Option<f64>,
     pub vector: Option<f64>,
@@ -82,18 +92,28 @@ impl CandidateFeatures {
     }
 }
 
+/// The hub-suppression penalty for a candidate: the full `hub_penalty` when it
+/// came through a traversal-suppressed hub, otherwise a share of it scaled by
+/// how far its hub centrality sits above `centrality_hub_floor`.
+pub fn hub_penalty_for(features: &CandidateFeatures, weights: &RankingWeights) -> f64 {
+    if features.via_hub {
+        return weights.hub_penalty;
+    }
+    let span = (1.0 - weights.centrality_hub_floor).max(f64::EPSILON);
+    let excess = (features.hub_centrality - weights.centrality_hub_floor) / span;
+    weights.hub_penalty * excess.clamp(0.0, 1.0)
+}
+
 /// Compute a candidate's deterministic base score from its features and the
-/// weights. Clamped to `[0, 1]`. A via-hub candidate is penalized (hub
+/// weights. Clamped to `[0, 1]`. A hub candidate is penalized (hub
 /// suppression) but never removed — it is recorded with a lower score.
 pub fn score_candidate(features: &CandidateFeatures, weights: &RankingWeights) -> f64 {
-    let mut score = weights.evidence_quality * features.evidence_quality
+    let score = weights.evidence_quality * features.evidence_quality
         + weights.graph_proximity * features.graph_proximity
         + weights.relationship_type * features.relationship_type_weight
         + weights.source_authority * features.source_authority
-        + weights.recency * features.recency;
-    if features.via_hub {
-        score -= weights.hub_penalty;
-    }
+        + weights.recency * features.recency
+        - hub_penalty_for(features, weights);
     score.clamp(0.0, 1.0)
 }
 
@@ -111,6 +131,7 @@ pub fn rank_candidates(
         .into_iter()
         .map(|f| {
             let base_score = score_candidate(&f, weights);
+            let hub_penalty = hub_penalty_for(&f, weights);
             let graph = if f.graph_proximity > 0.0 {
                 Some(f.graph_proximity)
             } else {
@@ -127,11 +148,8 @@ pub fn rank_candidates(
                     pack: None,
                     // Adversarial-v2 MT-134 LOW: record the ACTUAL hub penalty
                     // applied, not a -1.0 sentinel, so the trace is truthful.
-                    trust_adjust: if f.via_hub {
-                        Some(-weights.hub_penalty)
-                    } else {
-                        None
-                    },
+                    trust_adjust: (hub_penalty > 0.0).then_some(-hub_penalty),
+                    rerank: None,
                 },
                 base_score,
                 tiebreak: f.candidate_id,
@@ -165,6 +183,7 @@ mod tests {
             source_authority: 0.7,
             recency: 0.8,
             via_hub,
+            hub_centrality: 0.0,
             lexical: None,
             vector: None,
         }
@@ -205,6 +224,31 @@ mod tests {
         assert_eq!(ranked[0].scores.trust_adjust, Some(-0.25));
     }
 
+    #[test]
+    fn central_hubs_are_penalized_in_proportion_above_the_floor() {
+        let w = RankingWeights::default();
+        let mut quiet = feat("a", 0.8, 0.8, false);
+        quiet.hub_centrality = w.centrality_hub_floor;
+        let mut central = quiet.clone();
+        central.hub_centrality = 0.75;
+        let mut dominant = quiet.clone();
+        dominant.hub_centrality = 1.0;
+
+        assert_eq!(hub_penalty_for(&quiet, &w), 0.0);
+        assert!((hub_penalty_for(&central, &w) - w.hub_penalty / 2.0).abs() < 1e-12);
+        assert_eq!(hub_penalty_for(&dominant, &w), w.hub_penalty);
+        assert!(score_candidate(&central, &w) < score_candidate(&quiet, &w));
+
+        let ranked = rank_candidates(vec![quiet, central], &w);
+        assert_eq!(
+            ranked
+                .iter()
+                .filter(|c| c.scores.trust_adjust.is_some())
+                .count(),
+            1
+        );
+    }
+
     #[test]
     fn ranking_is_deterministic_with_stable_tiebreak() {
         let w = RankingWeights::default();
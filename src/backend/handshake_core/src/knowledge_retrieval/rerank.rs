@@ -0,0 +1,615 @@
+//! Optional cross-encoder rerank stage for the executed pipeline and Loom search.
+//!
+//! [`ranking`](crate::knowledge_retrieval::ranking) scores candidates from
+//! structured features and `loom_search_v2` fuses FTS, trigram, and vector
+//! similarity; neither reads the query and the candidate text together, so a
+//! semantically exact passage phrased differently from the question can land
+//! below keyword noise. This stage rescores the head of a ranked list with a
+//! [`CrossEncoder`] that sees each (query, text) pair:
+//!
+//! ```text
+//! ranked (MT-134 / loom_search_v2)
+//!     -> head: first min(top_n, budgets.max_rerank_candidates) with text,
+//!        while the estimated input tokens fit the evidence budget
+//!     -> CrossEncoder::score_pairs (local cross-encoder | ModelRuntime::score)
+//!     -> blend with the base score, reorder the head in place
+//!     -> RerankRecord (scores, moves, and why) into the RetrievalTrace
+//! ```
+//!
+//! The stage never fails a retrieval. With no encoder configured, a model
+//! that is not loaded, or a scoring error, the base order is kept and the
+//! record says why the stage was skipped. Candidates without text inside the
+//! head keep their positions; everything past the head is untouched.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::knowledge_retrieval::plan::{RetrievalBudgets, RetrievalCandidate};
+use crate::model_runtime::local_models::{self, LocalModelRole, LocalModels};
+use crate::model_runtime::{ModelId, ModelRuntime, ModelRuntimeError};
+
+/// Head size when the plan's budgets allow more.
+pub const DEFAULT_RERANK_TOP_N: u32 = 32;
+/// Per-candidate text cap; longer texts are truncated before scoring.
+pub const DEFAULT_RERANK_TEXT_TOKENS: u32 = 256;
+/// Weight of the cross-encoder score in the final score; the rest is the base
+/// score, so structural evidence still breaks near-ties.
+pub const DEFAULT_RERANK_BLEND: f64 = 0.7;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    /// No usable model is loaded; the caller degrades to the base order.
+    #[error("rerank model unavailable: {0}")]
+    Unavailable(String),
+    #[error("rerank scoring failed: {0}")]
+    Failed(String),
+}
+
+/// Scores (query, text) pairs jointly. Scores are relevance in `[0, 1]`, one
+/// per text, in input order; values outside the range are clamped.
+#[async_trait]
+pub trait CrossEncoder: Send + Sync {
+    /// Stable model reference recorded in the trace.
+    fn model_ref(&self) -> String;
+
+    async fn score_pairs(&self, query: &str, texts: &[String]) -> Result<Vec<f64>, RerankError>;
+}
+
+/// Turns a rendered pair into the token sequence `ModelRuntime::score` takes.
+pub type SequenceEncoder = Arc<dyn Fn(&str) -> Vec<u32> + Send + Sync>;
+
+/// A [`CrossEncoder`] over any loaded [`ModelRuntime`]: each pair is rendered
+/// as a relevance statement and scored with `ModelRuntime::score`; the pair's
+/// relevance is `exp(mean_logprob)`.
+pub struct RuntimeCrossEncoder {
+    runtime: Arc<dyn ModelRuntime>,
+    model_id: ModelId,
+    model_ref: String,
+    encode: SequenceEncoder,
+}
+
+impl RuntimeCrossEncoder {
+    /// Uses the byte-level sequence the `score` seam accepts from every
+    /// adapter (as `parallel_distill` does); supply the model's tokenizer with
+    /// [`Self::with_encoder`] when one is at hand.
+    pub fn new(
+        runtime: Arc<dyn ModelRuntime>,
+        model_id: ModelId,
+        model_ref: impl Into<String>,
+    ) -> Self {
+        Self {
+            runtime,
+            model_id,
+            model_ref: model_ref.into(),
+            encode: Arc::new(|text: &str| text.bytes().map(u32::from).collect()),
+        }
+    }
+
+    /// The encoder over the model `main` loaded for
+    /// [`LocalModelRole::Reranker`], if any.
+    pub fn from_local_models(models: &LocalModels) -> Option<Self> {
+        let model = models.role(LocalModelRole::Reranker)?;
+        Some(Self::new(
+            models.runtime(model),
+            model.model_id,
+            model.model_ref(),
+        ))
+    }
+
+    pub fn with_encoder(
+        mut self,
+        encode: impl Fn(&str) -> Vec<u32> + Send + Sync + 'static,
+    ) -> Self {
+        self.encode = Arc::new(encode);
+        self
+    }
+
+    fn pair_prompt(query: &str, text: &str) -> String {
+        format!("Query: {query}\nPassage: {text}\nThe passage answers the query.")
+    }
+}
+
+#[async_trait]
+impl CrossEncoder for RuntimeCrossEncoder {
+    fn model_ref(&self) -> String {
+        self.model_ref.clone()
+    }
+
+    async fn score_pairs(&self, query: &str, texts: &[String]) -> Result<Vec<f64>, RerankError> {
+        let mut scores = Vec::with_capacity(texts.len());
+        for text in texts {
+            let sequence = (self.encode)(&Self::pair_prompt(query, text));
+            let score =
+                self.runtime
+                    .score(self.model_id, sequence)
+                    .await
+                    .map_err(|err| match err {
+                        ModelRuntimeError::LoadError(_)
+                        | ModelRuntimeError::CapabilityNotSupported { .. }
+                        | ModelRuntimeError::AdapterMismatch { .. } => {
+                            RerankError::Unavailable(err.to_string())
+                        }
+                        other => RerankError::Failed(other.to_string()),
+                    })?;
+            scores.push(f64::from(score.mean_logprob).exp());
+        }
+        Ok(scores)
+    }
+}
+
+/// The reranker over the installed local models; `None` when no
+/// `HANDSHAKE_RERANKER_MODEL` is loaded, and callers keep their base order.
+pub fn local_reranker() -> Option<RuntimeCrossEncoder> {
+    local_models::shared().and_then(|models| RuntimeCrossEncoder::from_local_models(&models))
+}
+
+/// Bounds for one rerank pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RerankPolicy {
+    /// Candidates considered from the top of the list.
+    pub top_n: u32,
+    /// Per-candidate text cap, in estimated tokens.
+    pub max_text_tokens: u32,
+    /// Total estimated tokens (query + text, per pair) one pass may send.
+    pub max_input_tokens: u32,
+    /// Cross-encoder weight in `[0, 1]`; see [`DEFAULT_RERANK_BLEND`].
+    pub blend: f64,
+}
+
+impl RerankPolicy {
+    /// Caps the head at the plan's `max_rerank_candidates` and the input at
+    /// its evidence-token budget.
+    pub fn from_budgets(budgets: &RetrievalBudgets) -> Self {
+        Self {
+            top_n: budgets.max_rerank_candidates.min(DEFAULT_RERANK_TOP_N),
+            max_text_tokens: DEFAULT_RERANK_TEXT_TOKENS,
+            max_input_tokens: budgets.max_total_evidence_tokens,
+            blend: DEFAULT_RERANK_BLEND,
+        }
+    }
+
+    pub fn final_score(&self, base_score: f64, rerank_score: Option<f64>) -> f64 {
+        match rerank_score {
+            Some(rerank) => {
+                let blend = self.blend.clamp(0.0, 1.0);
+                blend * rerank + (1.0 - blend) * base_score
+            }
+            None => base_score,
+        }
+    }
+
+    /// The score a reranked retrieval candidate competes with downstream.
+    pub fn candidate_score(&self, candidate: &RetrievalCandidate) -> f64 {
+        self.final_score(candidate.base_score, candidate.scores.rerank)
+    }
+}
+
+impl Default for RerankPolicy {
+    fn default() -> Self {
+        Self::from_budgets(&RetrievalBudgets::default_bounded())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankStatus {
+    Applied,
+    Skipped,
+}
+
+/// One candidate the rerank moved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankMove {
+    pub candidate_id: String,
+    /// 1-based ranks before and after the stage.
+    pub base_rank: u32,
+    pub final_rank: u32,
+    pub rerank_score: f64,
+    pub final_score: f64,
+    pub why: String,
+}
+
+/// What the rerank stage did, recorded in the `RetrievalTrace`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankRecord {
+    pub status: RerankStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_ref: Option<String>,
+    /// Why the stage was skipped, or how the head was bounded.
+    pub reason: String,
+    /// Candidates sent to the encoder.
+    pub scored: u32,
+    /// Of those, how many had their text truncated to `max_text_tokens`.
+    pub truncated: u32,
+    pub input_tokens: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub moves: Vec<RerankMove>,
+}
+
+impl RerankRecord {
+    fn skipped(model_ref: Option<String>, reason: impl Into<String>) -> Self {
+        Self {
+            status: RerankStatus::Skipped,
+            model_ref,
+            reason: reason.into(),
+            scored: 0,
+            truncated: 0,
+            input_tokens: 0,
+            moves: Vec::new(),
+        }
+    }
+}
+
+/// One entry of a ranked list, as the stage sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RerankItem {
+    pub candidate_id: String,
+    /// Base score in `[0, 1]`.
+    pub base_score: f64,
+    /// Text the encoder scores; `None` pins the item in place.
+    pub text: Option<String>,
+}
+
+/// The reranked order as indices into the input, the cross-encoder score of
+/// each input item that was scored, and the record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RerankOutcome {
+    pub order: Vec<usize>,
+    pub rerank_scores: Vec<Option<f64>>,
+    pub record: RerankRecord,
+}
+
+impl RerankOutcome {
+    fn identity(len: usize, record: RerankRecord) -> Self {
+        Self {
+            order: (0..len).collect(),
+            rerank_scores: vec![None; len],
+            record,
+        }
+    }
+}
+
+/// Same estimate the executor uses for snippet token counts.
+fn estimate_tokens(text: &str) -> u32 {
+    (text.len() as u32 / 4).max(1)
+}
+
+fn truncate_to_tokens(text: &str, max_tokens: u32) -> (String, bool) {
+    let max_bytes = max_tokens.saturating_mul(4) as usize;
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+/// Rerank the head of `items` (already in base order).
+pub async fn rerank(
+    encoder: Option<&dyn CrossEncoder>,
+    query: &str,
+    items: &[RerankItem],
+    policy: &RerankPolicy,
+) -> RerankOutcome {
+    let Some(encoder) = encoder else {
+        return RerankOutcome::identity(
+            items.len(),
+            RerankRecord::skipped(None, "no rerank model configured"),
+        );
+    };
+    let model_ref = Some(encoder.model_ref());
+
+    let query_tokens = estimate_tokens(query);
+    let mut batch: Vec<usize> = Vec::new();
+    let mut texts: Vec<String> = Vec::new();
+    let mut truncated = 0u32;
+    let mut input_tokens = 0u32;
+    let mut budget_stop = None;
+    for (idx, item) in items.iter().enumerate().take(policy.top_n as usize) {
+        let Some(text) = item.text.as_deref().filter(|t| !t.trim().is_empty()) else {
+            continue;
+        };
+        let (text, was_truncated) = truncate_to_tokens(text, policy.max_text_tokens);
+        let cost = query_tokens + estimate_tokens(&text);
+        if input_tokens.saturating_add(cost) > policy.max_input_tokens {
+            budget_stop = Some(policy.max_input_tokens);
+            break;
+        }
+        input_tokens += cost;
+        truncated += u32::from(was_truncated);
+        batch.push(idx);
+        texts.push(text);
+    }
+    if batch.is_empty() {
+        return RerankOutcome::identity(
+            items.len(),
+            RerankRecord::skipped(model_ref, "no candidate text within the rerank budget"),
+        );
+    }
+
+    let scores = match encoder.score_pairs(query, &texts).await {
+        Ok(scores) if scores.len() == texts.len() => scores,
+        Ok(scores) => {
+            let reason = format!(
+                "encoder returned {} scores for {} pairs",
+                scores.len(),
+                texts.len()
+            );
+            return RerankOutcome::identity(items.len(), RerankRecord::skipped(model_ref, reason));
+        }
+        Err(err) => {
+            return RerankOutcome::identity(
+                items.len(),
+                RerankRecord::skipped(model_ref, err.to_string()),
+            );
+        }
+    };
+
+    let mut rerank_scores = vec![None; items.len()];
+    for (idx, score) in batch.iter().zip(&scores) {
+        let score = if score.is_finite() {
+            score.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        rerank_scores[*idx] = Some(score);
+    }
+    let final_of = |idx: usize| policy.final_score(items[idx].base_score, rerank_scores[idx]);
+
+    // Scored items compete for the slots they occupied; ties keep base order.
+    let mut reordered = batch.clone();
+    reordered.sort_by(|a, b| {
+        final_of(*b)
+            .partial_cmp(&final_of(*a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.cmp(b))
+    });
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    for (slot, idx) in batch.iter().zip(&reordered) {
+        order[*slot] = *idx;
+    }
+
+    let moves = order
+        .iter()
+        .enumerate()
+        .filter(|(pos, idx)| *pos != **idx)
+        .map(|(pos, idx)| {
+            let rerank_score = rerank_scores[*idx].unwrap_or(0.0);
+            let final_score = final_of(*idx);
+            let direction = if pos < *idx { "promoted" } else { "demoted" };
+            RerankMove {
+                candidate_id: items[*idx].candidate_id.clone(),
+                base_rank: *idx as u32 + 1,
+                final_rank: pos as u32 + 1,
+                rerank_score,
+                final_score,
+                why: format!(
+                    "{direction}: cross-encoder {rerank_score:.3} blended to {final_score:.3} \
+                     (base {:.3})",
+                    items[*idx].base_score
+                ),
+            }
+        })
+        .collect();
+
+    RerankOutcome {
+        order,
+        rerank_scores,
+        record: RerankRecord {
+            status: RerankStatus::Applied,
+            model_ref,
+            reason: match budget_stop {
+                Some(limit) => format!(
+                    "scored {} candidates; input token budget {limit} reached",
+                    batch.len()
+                ),
+                None => format!(
+                    "scored {} of the top {} candidates",
+                    batch.len(),
+                    policy.top_n
+                ),
+            },
+            scored: batch.len() as u32,
+            truncated,
+            input_tokens,
+            moves,
+        },
+    }
+}
+
+/// Rerank executed-pipeline candidates. `text_of` supplies each candidate's
+/// text (passage text or evidence excerpt). Scored candidates carry
+/// `scores.rerank`; score them downstream with [`RerankPolicy::candidate_score`].
+pub async fn rerank_candidates(
+    encoder: Option<&dyn CrossEncoder>,
+    query: &str,
+    ranked: Vec<RetrievalCandidate>,
+    text_of: impl Fn(&RetrievalCandidate) -> Option<String>,
+    policy: &RerankPolicy,
+) -> (Vec<RetrievalCandidate>, RerankRecord) {
+    let items: Vec<RerankItem> = ranked
+        .iter()
+        .map(|candidate| RerankItem {
+            candidate_id: candidate.candidate_id.clone(),
+            base_score: candidate.base_score,
+            text: text_of(candidate),
+        })
+        .collect();
+    let outcome = rerank(encoder, query, &items, policy).await;
+    let mut slots: Vec<Option<RetrievalCandidate>> = ranked.into_iter().map(Some).collect();
+    let reranked = outcome
+        .order
+        .iter()
+        .filter_map(|idx| {
+            let mut candidate = slots[*idx].take()?;
+            candidate.scores.rerank = outcome.rerank_scores[*idx];
+            Some(candidate)
+        })
+        .collect();
+    (reranked, outcome.record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_retrieval::plan::{CandidateScores, RetrievalStore};
+
+    /// Scores a pair by the share of query words the text contains.
+    struct OverlapEncoder;
+
+    #[async_trait]
+    impl CrossEncoder for OverlapEncoder {
+        fn model_ref(&self) -> String {
+            "test/overlap".to_string()
+        }
+
+        async fn score_pairs(
+            &self,
+            query: &str,
+            texts: &[String],
+        ) -> Result<Vec<f64>, RerankError> {
+            let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    let hits = words.iter().filter(|w| text.contains(w.as_str())).count();
+                    hits as f64 / words.len() as f64
+                })
+                .collect())
+        }
+    }
+
+    struct UnloadedEncoder;
+
+    #[async_trait]
+    impl CrossEncoder for UnloadedEncoder {
+        fn model_ref(&self) -> String {
+            "test/unloaded".to_string()
+        }
+
+        async fn score_pairs(
+            &self,
+            _query: &str,
+            _texts: &[String],
+        ) -> Result<Vec<f64>, RerankError> {
+            Err(RerankError::Unavailable("no model loaded".to_string()))
+        }
+    }
+
+    fn candidate(id: &str, base_score: f64) -> RetrievalCandidate {
+        RetrievalCandidate {
+            candidate_id: id.to_string(),
+            kind: "passage_ref".to_string(),
+            store: RetrievalStore::ShadowWsVector,
+            scores: CandidateScores::default(),
+            base_score,
+            tiebreak: id.to_string(),
+        }
+    }
+
+    fn texts(id: &RetrievalCandidate) -> Option<String> {
+        match id.candidate_id.as_str() {
+            "keyword-noise" => Some("postgres postgres postgres config dump".to_string()),
+            "near-miss" => {
+                Some("the database accepts connections on 5544, its listening port".to_string())
+            }
+            "pinned" => None,
+            "tail" => Some("which port does the database listen on".to_string()),
+            _ => None,
+        }
+    }
+
+    fn ranked() -> Vec<RetrievalCandidate> {
+        vec![
+            candidate("keyword-noise", 0.9),
+            candidate("pinned", 0.8),
+            candidate("near-miss", 0.6),
+            candidate("tail", 0.1),
+        ]
+    }
+
+    #[tokio::test]
+    async fn rerank_promotes_semantic_match_and_records_why() {
+        let policy = RerankPolicy {
+            top_n: 3,
+            ..RerankPolicy::default()
+        };
+        let (reranked, record) = rerank_candidates(
+            Some(&OverlapEncoder),
+            "which port does the database listen on",
+            ranked(),
+            texts,
+            &policy,
+        )
+        .await;
+        let order: Vec<&str> = reranked.iter().map(|c| c.candidate_id.as_str()).collect();
+        // The text-less candidate keeps its slot; the tail is outside the head.
+        assert_eq!(order, vec!["near-miss", "pinned", "keyword-noise", "tail"]);
+        assert_eq!(record.status, RerankStatus::Applied);
+        assert_eq!(record.model_ref.as_deref(), Some("test/overlap"));
+        assert_eq!(record.scored, 2);
+        assert_eq!(record.moves.len(), 2);
+        let promoted = &record.moves[0];
+        assert_eq!(promoted.candidate_id, "near-miss");
+        assert_eq!((promoted.base_rank, promoted.final_rank), (3, 1));
+        assert!(promoted.why.starts_with("promoted"));
+        assert!(reranked[0].scores.rerank.is_some());
+        assert!(reranked[1].scores.rerank.is_none());
+        assert!(reranked[3].scores.rerank.is_none());
+        assert!(policy.candidate_score(&reranked[0]) > policy.candidate_score(&reranked[2]));
+    }
+
+    #[tokio::test]
+    async fn rerank_degrades_to_base_order_without_a_model() {
+        let policy = RerankPolicy::default();
+        for (encoder, reason) in [
+            (None, "no rerank model configured"),
+            (
+                Some(&UnloadedEncoder as &dyn CrossEncoder),
+                "rerank model unavailable: no model loaded",
+            ),
+        ] {
+            let (reranked, record) =
+                rerank_candidates(encoder, "port", ranked(), texts, &policy).await;
+            assert_eq!(reranked, ranked());
+            assert_eq!(record.status, RerankStatus::Skipped);
+            assert_eq!(record.reason, reason);
+            assert!(record.moves.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn rerank_respects_head_and_token_budgets() {
+        let items: Vec<RerankItem> = (0..6)
+            .map(|i| RerankItem {
+                candidate_id: format!("c{i}"),
+                base_score: 1.0 - f64::from(i) * 0.1,
+                text: Some("x".repeat(400 * (i as usize + 1))),
+            })
+            .collect();
+        let policy = RerankPolicy {
+            top_n: 5,
+            max_text_tokens: 150,
+            max_input_tokens: 400,
+            blend: 1.0,
+        };
+        let outcome = rerank(Some(&OverlapEncoder), "port", &items, &policy).await;
+        // Each pair costs 1 + 100..150 tokens: two fit in 400.
+        assert_eq!(outcome.record.scored, 2);
+        assert_eq!(outcome.record.truncated, 1);
+        assert!(outcome.record.input_tokens <= 400);
+        assert!(outcome.record.reason.contains("input token budget 400"));
+        assert_eq!(outcome.order, (0..6).collect::<Vec<_>>());
+
+        let budgets = RetrievalBudgets {
+            max_rerank_candidates: 4,
+            ..RetrievalBudgets::default_bounded()
+        };
+        assert_eq!(RerankPolicy::from_budgets(&budgets).top_n, 4);
+    }
+}
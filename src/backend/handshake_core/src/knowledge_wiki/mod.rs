@@ -24,6 +24,11 @@
 //!   bounded by an explicit budget with LOUD EventLedger truncation receipts
 //!   (LM-PWIKI-011), idempotent re-run.
 //!
+//! [`publish`] renders the compiled pages into a self-contained static HTML
+//! site (index, client-side search, citation links to a sources page, visible
+//! stale/unstamped badges) through the bundle redactor, with a hashed
+//! manifest.
+//!
 //! SHIP-TOGETHER GUARD (LM-PWIKI-009): the bootstrap compiler and the
 //! staleness layer are one delivery unit. Structurally enforced:
 //! migration 0300's `chk_knowledge_wiki_projections_stamp_guard` makes a typed
@@ -38,6 +43,7 @@
 pub mod compiler;
 pub mod drift;
 pub mod fanout;
+pub mod publish;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -144,10 +144,18 @@ pub mod managed_postgres;
 pub mod api;
 #[cfg(feature = "runtime-full")]
 pub mod bundles;
+/// CalDAV two-way calendar sync: discovery, incremental pull, ETag-guarded push, and
+/// conflict records for the `calendar_sync` engine.
+#[cfg(feature = "runtime-full")]
+pub mod caldav;
 #[cfg(feature = "runtime-full")]
 pub mod capabilities;
 #[cfg(feature = "runtime-full")]
 pub mod capability_registry_workflow;
+/// Record/replay cassettes for `LlmClient` and MCP traffic: redacted,
+/// versioned request/response recordings served back deterministically.
+#[cfg(feature = "runtime-full")]
+pub mod cassette;
 /// WP-KERNEL-009 NativeDependencyAndPackaging (MT-017..MT-032): runtime
 /// dependency allowlist, operator gates for external runtime inputs, and
 /// forbidden-dependency tripwires. Unconditional: the dependency policy
@@ -220,7 +228,16 @@ pub mod logging;
 pub mod loom_ai;
 #[cfg(feature = "runtime-full")]
 pub mod loom_fs;
+/// Loom preview pipeline: thumb / preview / poster tiers for raster images,
+/// SVG, PDF first pages and video posters (ffmpeg/pdftoppm as governed tools).
+#[cfg(feature = "runtime-full")]
+pub mod loom_preview;
 pub mod loom_search;
+/// Obsidian / Logseq vault import into Loom: wikilinks, aliases, block refs
+/// and embeds become edges, tags become tag hubs, daily notes land on journal
+/// blocks, attachments go to the asset store; re-imports update in place.
+#[cfg(feature = "runtime-full")]
+pub mod loom_vault;
 #[cfg(feature = "runtime-full")]
 pub mod mcp;
 #[cfg(feature = "runtime-full")]
@@ -241,6 +258,10 @@ pub mod observability;
 pub mod operator_foreground;
 #[cfg(feature = "runtime-full")]
 pub mod process_ledger;
+/// Shared secret/PII detection engine (entropy scoring, structured validators,
+/// workspace-governed allow/deny rules) behind every redaction path.
+#[cfg(feature = "runtime-full")]
+pub mod redaction;
 #[cfg(feature = "runtime-full")]
 pub mod role_mailbox;
 #[cfg(feature = "runtime-full")]
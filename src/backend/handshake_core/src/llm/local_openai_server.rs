@@ -0,0 +1,1201 @@
+//! Optional OpenAI-compatible HTTP server fronting the local ModelRuntimes.
+//!
+//! Editor plugins and scripts speak the OpenAI wire format; this module
+//! exposes the loaded llama.cpp/candle runtimes to them behind
+//! `/v1/chat/completions` (incl. SSE streaming), `/v1/completions`,
+//! `/v1/embeddings` and `/v1/models`.
+//!
+//! Every request is served through [`LocalModelRuntimeLlmClient`], the same
+//! dispatcher internal callers use, so the `max_tokens` budget, MemoryCapsule
+//! injection and the `llm_inference` Flight Recorder event are identical to
+//! an in-process call. On top of that the server enforces:
+//! - operator consent per (client session, model) through the shared
+//!   [`ConsentGate`] before a model is touched;
+//! - a per-request `max_tokens` cap (HSK-402 when a client asks for more);
+//! - loopback-only binds unless `allow_non_loopback` is explicitly set, and
+//!   then only with an API key every request must present as a bearer token.
+//!
+//! Chat messages are rendered in the turn format of the model's own GGUF
+//! chat template (see `ChatFormat`); prompt token counts come from the
+//! model's tokenizer through [`LocalModelInfo`].
+//!
+//! Only local (UUIDv7, registry-backed) model ids are served; provider ids
+//! that `LocalModelRuntimeLlmClient` would hand to its fallback client are
+//! rejected with `model_not_found` so the server never proxies to the cloud.
+
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::{net::TcpListener, sync::mpsc};
+use uuid::Uuid;
+
+use crate::model_runtime::{
+    cloud::consent_gate::{ConsentDecision, ConsentGate, ConsentGateError, ConsentProvider},
+    FinishReason, ModelId, ModelRuntimeError, RuntimeBinding,
+};
+
+use super::{
+    local_router::LocalModelRuntimeLlmClient, CompletionRequest, EmbeddingRequest, LlmClient,
+    LlmError,
+};
+
+pub const ENV_OPENAI_SERVER_BIND: &str = "HANDSHAKE_OPENAI_SERVER_BIND";
+pub const ENV_OPENAI_SERVER_ALLOW_NON_LOOPBACK: &str = "HANDSHAKE_OPENAI_SERVER_ALLOW_NON_LOOPBACK";
+pub const ENV_OPENAI_SERVER_MAX_TOKENS: &str = "HANDSHAKE_OPENAI_SERVER_MAX_TOKENS";
+pub const ENV_OPENAI_SERVER_API_KEY: &str = "HANDSHAKE_OPENAI_SERVER_API_KEY";
+
+/// Default listen address: loopback, next to the core API port (37501).
+pub const DEFAULT_OPENAI_SERVER_BIND: ([u8; 4], u16) = ([127, 0, 0, 1], 37511);
+
+/// Header a client may send to scope operator consent to its own session.
+pub const SESSION_HEADER: &str = "x-handshake-session-id";
+/// Response header carrying the Flight Recorder trace id of the call.
+pub const TRACE_HEADER: &str = "x-handshake-trace-id";
+
+const DEFAULT_CLIENT_SESSION: &str = "openai-compat-client";
+const CONSENT_LANE_PREFIX: &str = "local_openai_server";
+
+#[derive(Debug, Error)]
+pub enum LocalOpenAiServerError {
+    #[error("HSK-400-INVALID-BIND: {0}")]
+    InvalidBind(String),
+    #[error(
+        "HSK-403-NON-LOOPBACK-BIND: refusing to bind {0}; set HANDSHAKE_OPENAI_SERVER_ALLOW_NON_LOOPBACK=1 to expose local models beyond this machine"
+    )]
+    NonLoopbackBind(SocketAddr),
+    #[error(
+        "HSK-403-NON-LOOPBACK-BIND: refusing to bind {0} without an API key; set HANDSHAKE_OPENAI_SERVER_API_KEY so clients must authenticate"
+    )]
+    NonLoopbackWithoutApiKey(SocketAddr),
+    #[error("local OpenAI server io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalOpenAiServerConfig {
+    pub bind_addr: SocketAddr,
+    /// Explicit operator opt-in to listen on a non-loopback interface.
+    pub allow_non_loopback: bool,
+    /// Upper bound on `max_tokens` a client may request. `None` uses the
+    /// dispatcher profile's `max_context_tokens`.
+    pub max_tokens_cap: Option<u32>,
+    /// Bearer token every request must carry. Required for non-loopback
+    /// binds; optional on loopback.
+    pub api_key: Option<String>,
+}
+
+impl Default for LocalOpenAiServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: DEFAULT_OPENAI_SERVER_BIND.into(),
+            allow_non_loopback: false,
+            max_tokens_cap: None,
+            api_key: None,
+        }
+    }
+}
+
+impl LocalOpenAiServerConfig {
+    /// Reads the server config from the environment. Returns `Ok(None)` when
+    /// `HANDSHAKE_OPENAI_SERVER_BIND` is unset: the server is opt-in.
+    pub fn from_env() -> Result<Option<Self>, LocalOpenAiServerError> {
+        let Ok(bind) = std::env::var(ENV_OPENAI_SERVER_BIND) else {
+            return Ok(None);
+        };
+        let bind_addr = bind.trim().parse::<SocketAddr>().map_err(|err| {
+            LocalOpenAiServerError::InvalidBind(format!("{ENV_OPENAI_SERVER_BIND}={bind}: {err}"))
+        })?;
+        let allow_non_loopback = std::env::var(ENV_OPENAI_SERVER_ALLOW_NON_LOOPBACK)
+            .map(|v| v.trim() == "1" || v.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let max_tokens_cap = std::env::var(ENV_OPENAI_SERVER_MAX_TOKENS)
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+        let api_key = std::env::var(ENV_OPENAI_SERVER_API_KEY)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let config = Self {
+            bind_addr,
+            allow_non_loopback,
+            max_tokens_cap,
+            api_key,
+        };
+        config.validate_bind()?;
+        Ok(Some(config))
+    }
+
+    /// Loopback binds are always allowed; anything else (including the
+    /// unspecified `0.0.0.0`/`::`) needs `allow_non_loopback` and an API key.
+    pub fn validate_bind(&self) -> Result<(), LocalOpenAiServerError> {
+        if is_loopback(self.bind_addr.ip()) {
+            Ok(())
+        } else if !self.allow_non_loopback {
+            Err(LocalOpenAiServerError::NonLoopbackBind(self.bind_addr))
+        } else if self.api_key.is_none() {
+            Err(LocalOpenAiServerError::NonLoopbackWithoutApiKey(
+                self.bind_addr,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn is_loopback(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback(),
+        IpAddr::V6(v6) => {
+            v6.is_loopback() || v6.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback())
+        }
+    }
+}
+
+/// Per-model facts the runtimes do not expose through `ModelRuntime`.
+pub trait LocalModelInfo: Send + Sync {
+    /// The artifact's chat template (GGUF `tokenizer.chat_template`), if any.
+    fn chat_template(&self, id: ModelId) -> Option<String>;
+
+    /// Number of tokens `text` encodes to under the model's tokenizer.
+    fn count_tokens(&self, id: ModelId, text: &str) -> Result<u32, ModelRuntimeError>;
+}
+
+/// Consent for a headless server: the operator approved exactly the models
+/// they configured it to serve; every other lane is denied.
+pub struct PreapprovedModelsConsent {
+    lanes: HashSet<String>,
+}
+
+impl PreapprovedModelsConsent {
+    pub fn new(model_ids: impl IntoIterator<Item = ModelId>) -> Self {
+        Self {
+            lanes: model_ids
+                .into_iter()
+                .map(|id| format!("{CONSENT_LANE_PREFIX}:{id}"))
+                .collect(),
+        }
+    }
+}
+
+impl ConsentProvider for PreapprovedModelsConsent {
+    fn prompt_for_decision(
+        &self,
+        _session_id: &str,
+        lane: &str,
+    ) -> Result<ConsentDecision, ConsentGateError> {
+        Ok(if self.lanes.contains(lane) {
+            ConsentDecision::Approved
+        } else {
+            ConsentDecision::Denied
+        })
+    }
+}
+
+pub struct LocalOpenAiServer {
+    config: LocalOpenAiServerConfig,
+    client: Arc<LocalModelRuntimeLlmClient>,
+    consent_gate: Arc<ConsentGate>,
+    consent_provider: Arc<dyn ConsentProvider>,
+    model_info: Option<Arc<dyn LocalModelInfo>>,
+}
+
+impl LocalOpenAiServer {
+    pub fn new(
+        config: LocalOpenAiServerConfig,
+        client: Arc<LocalModelRuntimeLlmClient>,
+        consent_provider: Arc<dyn ConsentProvider>,
+    ) -> Self {
+        Self {
+            config,
+            client,
+            consent_gate: Arc::new(ConsentGate::new()),
+            consent_provider,
+            model_info: None,
+        }
+    }
+
+    /// Builder: chat templates and tokenizers of the served models. Without
+    /// it chat renders as ChatML and embeddings cannot report usage.
+    pub fn with_model_info(mut self, model_info: Arc<dyn LocalModelInfo>) -> Self {
+        self.model_info = Some(model_info);
+        self
+    }
+
+    /// Builder: share an existing consent map (e.g. the app-wide gate) so
+    /// decisions and session-close cleanup apply to server clients too.
+    pub fn with_consent_gate(mut self, gate: Arc<ConsentGate>) -> Self {
+        self.consent_gate = gate;
+        self
+    }
+
+    pub fn config(&self) -> &LocalOpenAiServerConfig {
+        &self.config
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/v1/models", get(list_models))
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/completions", post(completions))
+            .route("/v1/embeddings", post(embeddings))
+            .route_layer(middleware::from_fn_with_state(
+                Arc::clone(&self),
+                require_api_key,
+            ))
+            .with_state(self)
+    }
+
+    /// Validates the bind policy, then serves until the listener fails.
+    pub async fn serve(self: Arc<Self>) -> Result<(), LocalOpenAiServerError> {
+        self.config.validate_bind()?;
+        let listener = TcpListener::bind(self.config.bind_addr).await?;
+        tracing::info!(
+            target: "handshake_core::llm",
+            listen_addr = %self.config.bind_addr,
+            "local OpenAI-compatible server started"
+        );
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    fn max_tokens_cap(&self) -> u32 {
+        self.config
+            .max_tokens_cap
+            .unwrap_or(self.client.profile().max_context_tokens)
+    }
+
+    /// Resolves the requested model to a registered local model and checks
+    /// operator consent for (client session, model).
+    fn admit(&self, headers: &HeaderMap, model: &str) -> Result<ModelId, ApiError> {
+        let model_id = LocalModelRuntimeLlmClient::parse_local_model_id(model)
+            .ok()
+            .flatten()
+            .filter(|id| self.client.router().registry().lookup(*id).is_some())
+            .ok_or_else(|| ApiError::model_not_found(model))?;
+
+        let session = headers
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(DEFAULT_CLIENT_SESSION);
+        let lane = format!("{CONSENT_LANE_PREFIX}:{model_id}");
+        self.consent_gate
+            .check_or_prompt(session, &lane, self.consent_provider.as_ref())
+            .map_err(ApiError::from)?;
+        Ok(model_id)
+    }
+
+    fn chat_format(&self, model_id: ModelId) -> ChatFormat {
+        let template = self
+            .model_info
+            .as_ref()
+            .and_then(|info| info.chat_template(model_id));
+        ChatFormat::from_template(template.as_deref())
+    }
+
+    fn budgeted_max_tokens(&self, requested: Option<u32>) -> Result<u32, ApiError> {
+        let cap = self.max_tokens_cap();
+        match requested {
+            Some(requested) if requested > cap => {
+                Err(ApiError::from(LlmError::BudgetExceeded(requested)))
+            }
+            Some(requested) => Ok(requested),
+            None => Ok(cap),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Wire types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(value) => vec![value],
+            Self::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentPart {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    role: String,
+    #[serde(default)]
+    content: Option<MessageContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionBody {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    max_tokens: Option<u32>,
+    #[serde(default)]
+    max_completion_tokens: Option<u32>,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    stop: Option<OneOrMany>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionBody {
+    model: String,
+    prompt: OneOrMany,
+    #[serde(default)]
+    max_tokens: Option<u32>,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    stop: Option<OneOrMany>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingBody {
+    model: String,
+    input: OneOrMany,
+}
+
+#[derive(Debug, Serialize)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+/// Turn format of a model, read off its GGUF chat template. Runtimes take a
+/// raw prompt and templates are Jinja, so rather than evaluate the template
+/// the server recognises the families local models ship with by their turn
+/// markers. No template, or one it does not recognise, renders as ChatML.
+/// BOS is left to the tokenizer, which adds it on encode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChatFormat {
+    ChatMl,
+    Llama3,
+    Gemma,
+    MistralInst,
+    Phi3,
+}
+
+impl ChatFormat {
+    fn from_template(template: Option<&str>) -> Self {
+        let Some(template) = template else {
+            return Self::ChatMl;
+        };
+        if template.contains("<|im_start|>") {
+            Self::ChatMl
+        } else if template.contains("<|start_header_id|>") {
+            Self::Llama3
+        } else if template.contains("<start_of_turn>") {
+            Self::Gemma
+        } else if template.contains("[INST]") {
+            Self::MistralInst
+        } else if template.contains("<|assistant|>") && template.contains("<|end|>") {
+            Self::Phi3
+        } else {
+            Self::ChatMl
+        }
+    }
+
+    /// End-of-turn marker, added as a stop sequence.
+    fn end_of_turn(self) -> &'static str {
+        match self {
+            Self::ChatMl => "<|im_end|>",
+            Self::Llama3 => "<|eot_id|>",
+            Self::Gemma => "<end_of_turn>",
+            Self::MistralInst => "</s>",
+            Self::Phi3 => "<|end|>",
+        }
+    }
+
+    /// Render `(role, content)` turns (roles already normalised to
+    /// system/user/assistant/tool) followed by the assistant cue.
+    fn render(self, turns: &[(&str, String)]) -> String {
+        let end = self.end_of_turn();
+        let mut prompt = String::new();
+        match self {
+            Self::ChatMl => {
+                for (role, content) in turns {
+                    prompt.push_str(&format!("<|im_start|>{role}\n{content}{end}\n"));
+                }
+                prompt.push_str("<|im_start|>assistant\n");
+            }
+            Self::Llama3 => {
+                for (role, content) in turns {
+                    let role = if *role == "tool" { "ipython" } else { *role };
+                    prompt.push_str(&format!(
+                        "<|start_header_id|>{role}<|end_header_id|>\n\n{content}{end}"
+                    ));
+                }
+                prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+            }
+            Self::Phi3 => {
+                for (role, content) in turns {
+                    let role = if *role == "tool" { "user" } else { *role };
+                    prompt.push_str(&format!("<|{role}|>\n{content}{end}\n"));
+                }
+                prompt.push_str("<|assistant|>\n");
+            }
+            // No system role: system text is prepended to the next user turn.
+            Self::Gemma => {
+                for (role, content) in merge_system_turns(turns) {
+                    let role = if role == "assistant" { "model" } else { "user" };
+                    prompt.push_str(&format!("<start_of_turn>{role}\n{content}{end}\n"));
+                }
+                prompt.push_str("<start_of_turn>model\n");
+            }
+            Self::MistralInst => {
+                for (role, content) in merge_system_turns(turns) {
+                    if role == "assistant" {
+                        prompt.push_str(&format!("{content}{end}"));
+                    } else {
+                        prompt.push_str(&format!("[INST] {content} [/INST]"));
+                    }
+                }
+            }
+        }
+        prompt
+    }
+}
+
+/// Fold system turns into the following non-assistant turn, for formats with
+/// no system role. Tool output reads as a user turn.
+fn merge_system_turns(turns: &[(&str, String)]) -> Vec<(&'static str, String)> {
+    let mut merged: Vec<(&'static str, String)> = Vec::new();
+    let mut pending_system = String::new();
+    for (role, content) in turns {
+        if *role == "system" {
+            if !pending_system.is_empty() {
+                pending_system.push_str("\n\n");
+            }
+            pending_system.push_str(content);
+            continue;
+        }
+        let role = if *role == "assistant" {
+            "assistant"
+        } else {
+            "user"
+        };
+        let mut content = content.clone();
+        if role == "user" && !pending_system.is_empty() {
+            content = format!("{}\n\n{content}", std::mem::take(&mut pending_system));
+        }
+        merged.push((role, content));
+    }
+    if !pending_system.is_empty() {
+        merged.push(("user", pending_system));
+    }
+    merged
+}
+
+fn render_chat_prompt(messages: &[ChatMessage], format: ChatFormat) -> Result<String, ApiError> {
+    if messages.is_empty() {
+        return Err(ApiError::invalid_request("messages must not be empty"));
+    }
+    let mut turns = Vec::with_capacity(messages.len());
+    for message in messages {
+        let role = message.role.trim();
+        if !matches!(role, "system" | "developer" | "user" | "assistant" | "tool") {
+            return Err(ApiError::invalid_request(format!(
+                "unsupported message role: {role}"
+            )));
+        }
+        let content = match &message.content {
+            None => String::new(),
+            Some(MessageContent::Text(text)) => text.clone(),
+            Some(MessageContent::Parts(parts)) => {
+                let mut text = String::new();
+                for part in parts {
+                    match (part.kind.as_str(), &part.text) {
+                        ("text", Some(part_text)) => text.push_str(part_text),
+                        (kind, _) => {
+                            return Err(ApiError::invalid_request(format!(
+                                "unsupported content part type for local models: {kind}"
+                            )))
+                        }
+                    }
+                }
+                text
+            }
+        };
+        let role = if role == "developer" { "system" } else { role };
+        turns.push((role, content));
+    }
+    Ok(format.render(&turns))
+}
+
+fn finish_reason_str(
+    reason: Option<FinishReason>,
+    completion_tokens: u32,
+    max_tokens: u32,
+) -> &'static str {
+    match reason {
+        Some(FinishReason::Length) => "length",
+        Some(_) => "stop",
+        None if completion_tokens >= max_tokens => "length",
+        None => "stop",
+    }
+}
+
+fn usage_of(response: &super::CompletionResponse) -> Usage {
+    Usage {
+        prompt_tokens: response.usage.prompt_tokens,
+        completion_tokens: response.usage.completion_tokens,
+        total_tokens: response.usage.total_tokens,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// OpenAI-shaped error body: `{"error": {"message", "type", "code"}}`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    kind: &'static str,
+    code: String,
+    message: String,
+}
+
+impl ApiError {
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            kind: "invalid_request_error",
+            code: "invalid_request".to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            kind: "invalid_request_error",
+            code: "invalid_api_key".to_string(),
+            message: "missing or incorrect API key; send Authorization: Bearer <key>".to_string(),
+        }
+    }
+
+    fn model_not_found(model: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            kind: "invalid_request_error",
+            code: "model_not_found".to_string(),
+            message: format!("no registered local model with id {model:?}"),
+        }
+    }
+
+    fn body(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": self.kind,
+                "code": self.code,
+            }
+        })
+    }
+}
+
+impl From<LlmError> for ApiError {
+    fn from(err: LlmError) -> Self {
+        let (status, kind, code) = match &err {
+            LlmError::BudgetExceeded(_) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                "HSK-402-BUDGET-EXCEEDED",
+            ),
+            LlmError::RateLimit => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limit_error",
+                "HSK-429-RATE-LIMIT",
+            ),
+            LlmError::EmbeddingUnsupported => (
+                StatusCode::NOT_IMPLEMENTED,
+                "invalid_request_error",
+                "HSK-501-EMBEDDING-UNSUPPORTED",
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "HSK-500-LLM",
+            ),
+        };
+        Self {
+            status,
+            kind,
+            code: code.to_string(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<ConsentGateError> for ApiError {
+    fn from(err: ConsentGateError) -> Self {
+        let (status, code) = match err {
+            ConsentGateError::ConsentDenied { .. } => {
+                (StatusCode::FORBIDDEN, "HSK-403-CONSENT-DENIED")
+            }
+            ConsentGateError::Budget(_) => (StatusCode::PAYMENT_REQUIRED, "HSK-402-CLOUD-BUDGET"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "HSK-403-CONSENT-DENIED"),
+        };
+        Self {
+            status,
+            kind: "permission_error",
+            code: code.to_string(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// With an API key configured, every route needs `Authorization: Bearer
+/// <key>` (compared in constant time).
+async fn require_api_key(
+    State(server): State<Arc<LocalOpenAiServer>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    use subtle::ConstantTimeEq;
+
+    let Some(expected) = server.config.api_key.as_deref() else {
+        return next.run(request).await;
+    };
+    let presented = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    if presented.len() == expected.len()
+        && bool::from(presented.as_bytes().ct_eq(expected.as_bytes()))
+    {
+        next.run(request).await
+    } else {
+        ApiError::unauthorized().into_response()
+    }
+}
+
+async fn list_models(State(server): State<Arc<LocalOpenAiServer>>) -> Json<Value> {
+    let data: Vec<Value> = server
+        .client
+        .router()
+        .registry()
+        .list()
+        .into_iter()
+        .map(|registration| {
+            json!({
+                "id": registration.model_id.to_string(),
+                "object": "model",
+                "created": registration.registered_at_utc.timestamp(),
+                "owned_by": "handshake",
+                "runtime": match registration.runtime_binding {
+                    RuntimeBinding::LlamaCpp => "llama_cpp",
+                    RuntimeBinding::Candle => "candle",
+                },
+                "base_model": registration.base_model_tag.as_str(),
+            })
+        })
+        .collect();
+    Json(json!({ "object": "list", "data": data }))
+}
+
+/// Which OpenAI response family a generation is rendered as.
+#[derive(Clone, Copy)]
+enum Shape {
+    Chat,
+    Text,
+}
+
+impl Shape {
+    fn id_prefix(self) -> &'static str {
+        match self {
+            Self::Chat => "chatcmpl",
+            Self::Text => "cmpl",
+        }
+    }
+
+    fn object(self, streaming: bool) -> &'static str {
+        match (self, streaming) {
+            (Self::Chat, false) => "chat.completion",
+            (Self::Chat, true) => "chat.completion.chunk",
+            (Self::Text, _) => "text_completion",
+        }
+    }
+
+    fn choice(self, text: &str, finish_reason: Option<&str>, streaming: bool) -> Value {
+        match (self, streaming) {
+            (Self::Chat, false) => json!({
+                "index": 0,
+                "message": { "role": "assistant", "content": text },
+                "finish_reason": finish_reason,
+            }),
+            (Self::Chat, true) => {
+                let delta = if text.is_empty() {
+                    json!({})
+                } else {
+                    json!({ "content": text })
+                };
+                json!({
+                    "index": 0,
+                    "delta": delta,
+                    "finish_reason": finish_reason,
+                })
+            }
+            (Self::Text, _) => json!({
+                "index": 0,
+                "text": text,
+                "logprobs": null,
+                "finish_reason": finish_reason,
+            }),
+        }
+    }
+}
+
+struct Generation {
+    shape: Shape,
+    model: String,
+    model_id: ModelId,
+    request: CompletionRequest,
+}
+
+async fn chat_completions(
+    State(server): State<Arc<LocalOpenAiServer>>,
+    headers: HeaderMap,
+    Json(body): Json<ChatCompletionBody>,
+) -> Response {
+    let stream = body.stream;
+    match prepare_chat(&server, &headers, body) {
+        Ok(generation) => run_generation(server, generation, stream).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+fn prepare_chat(
+    server: &LocalOpenAiServer,
+    headers: &HeaderMap,
+    body: ChatCompletionBody,
+) -> Result<Generation, ApiError> {
+    let model_id = server.admit(headers, &body.model)?;
+    let format = server.chat_format(model_id);
+    let prompt = render_chat_prompt(&body.messages, format)?;
+    let max_tokens = server.budgeted_max_tokens(body.max_completion_tokens.or(body.max_tokens))?;
+    let mut stop = body.stop.map(OneOrMany::into_vec).unwrap_or_default();
+    stop.push(format.end_of_turn().to_string());
+    Ok(Generation {
+        shape: Shape::Chat,
+        model: body.model,
+        model_id,
+        request: completion_request(prompt, model_id, max_tokens, body.temperature, stop),
+    })
+}
+
+async fn completions(
+    State(server): State<Arc<LocalOpenAiServer>>,
+    headers: HeaderMap,
+    Json(body): Json<CompletionBody>,
+) -> Response {
+    let stream = body.stream;
+    match prepare_completion(&server, &headers, body) {
+        Ok(generation) => run_generation(server, generation, stream).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+fn prepare_completion(
+    server: &LocalOpenAiServer,
+    headers: &HeaderMap,
+    body: CompletionBody,
+) -> Result<Generation, ApiError> {
+    let model_id = server.admit(headers, &body.model)?;
+    let mut prompts = body.prompt.into_vec();
+    if prompts.len() != 1 {
+        return Err(ApiError::invalid_request(
+            "local completions accept exactly one prompt per request",
+        ));
+    }
+    let max_tokens = server.budgeted_max_tokens(body.max_tokens)?;
+    let stop = body.stop.map(OneOrMany::into_vec).unwrap_or_default();
+    Ok(Generation {
+        shape: Shape::Text,
+        model: body.model,
+        model_id,
+        request: completion_request(
+            prompts.remove(0),
+            model_id,
+            max_tokens,
+            body.temperature,
+            stop,
+        ),
+    })
+}
+
+fn completion_request(
+    prompt: String,
+    model_id: ModelId,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    stop: Vec<String>,
+) -> CompletionRequest {
+    let mut request = CompletionRequest::new(Uuid::now_v7(), prompt, model_id.to_string())
+        .with_max_tokens(max_tokens)
+        .with_stop_sequences(stop);
+    if let Some(temperature) = temperature {
+        request = request.with_temperature(temperature);
+    }
+    request
+}
+
+fn with_trace_header(mut response: Response, trace_id: Uuid) -> Response {
+    if let Ok(value) = HeaderValue::from_str(&trace_id.to_string()) {
+        response.headers_mut().insert(TRACE_HEADER, value);
+    }
+    response
+}
+
+async fn run_generation(
+    server: Arc<LocalOpenAiServer>,
+    generation: Generation,
+    stream: bool,
+) -> Response {
+    let trace_id = generation.request.trace_id;
+    let response = if stream {
+        stream_generation(server, generation).into_response()
+    } else {
+        complete_generation(&server, generation).await
+    };
+    with_trace_header(response, trace_id)
+}
+
+async fn complete_generation(server: &LocalOpenAiServer, generation: Generation) -> Response {
+    let Generation {
+        shape,
+        model,
+        model_id,
+        request,
+    } = generation;
+    let max_tokens = request.max_tokens.unwrap_or(u32::MAX);
+    let mut finish = None;
+    let result = server
+        .client
+        .complete_local_with(&request, model_id, |token| {
+            if token.finish_reason.is_some() {
+                finish = token.finish_reason;
+            }
+            true
+        })
+        .await;
+    match result {
+        Ok(response) => {
+            let reason = finish_reason_str(finish, response.usage.completion_tokens, max_tokens);
+            Json(json!({
+                "id": format!("{}-{}", shape.id_prefix(), request.trace_id.simple()),
+                "object": shape.object(false),
+                "created": Utc::now().timestamp(),
+                "model": model,
+                "choices": [shape.choice(&response.text, Some(reason), false)],
+                "usage": usage_of(&response),
+            }))
+            .into_response()
+        }
+        Err(err) => ApiError::from(err).into_response(),
+    }
+}
+
+/// SSE stream of `data: {chunk}` events terminated by `data: [DONE]`. The
+/// generation runs on its own task; a client disconnect closes the channel,
+/// which cancels the runtime through `complete_local_with`.
+fn stream_generation(
+    server: Arc<LocalOpenAiServer>,
+    generation: Generation,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::unbounded_channel::<Event>();
+    let Generation {
+        shape,
+        model,
+        model_id,
+        request,
+    } = generation;
+    let id = format!("{}-{}", shape.id_prefix(), request.trace_id.simple());
+    let created = Utc::now().timestamp();
+    let chunk = move |choice: Value| -> Event {
+        Event::default().data(
+            json!({
+                "id": id,
+                "object": shape.object(true),
+                "created": created,
+                "model": model,
+                "choices": [choice],
+            })
+            .to_string(),
+        )
+    };
+
+    if matches!(shape, Shape::Chat) {
+        let _ = tx.send(chunk(json!({
+            "index": 0,
+            "delta": { "role": "assistant" },
+            "finish_reason": null,
+        })));
+    }
+
+    tokio::spawn(async move {
+        let max_tokens = request.max_tokens.unwrap_or(u32::MAX);
+        let mut finish = None;
+        let token_tx = tx.clone();
+        let token_chunk = chunk.clone();
+        let result = server
+            .client
+            .complete_local_with(&request, model_id, |token| {
+                if token.finish_reason.is_some() {
+                    finish = token.finish_reason;
+                }
+                token.text.is_empty()
+                    || token_tx
+                        .send(token_chunk(shape.choice(&token.text, None, true)))
+                        .is_ok()
+            })
+            .await;
+        match result {
+            Ok(response) => {
+                let reason =
+                    finish_reason_str(finish, response.usage.completion_tokens, max_tokens);
+                let _ = tx.send(chunk(shape.choice("", Some(reason), true)));
+            }
+            Err(err) => {
+                let _ = tx.send(Event::default().data(ApiError::from(err).body().to_string()));
+            }
+        }
+        let _ = tx.send(Event::default().data("[DONE]"));
+    });
+
+    let events = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|event| (Ok::<Event, Infallible>(event), rx))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn embeddings(
+    State(server): State<Arc<LocalOpenAiServer>>,
+    headers: HeaderMap,
+    Json(body): Json<EmbeddingBody>,
+) -> Response {
+    let model_id = match server.admit(&headers, &body.model) {
+        Ok(model_id) => model_id,
+        Err(err) => return err.into_response(),
+    };
+    let trace_id = Uuid::now_v7();
+    let inputs = body.input.into_vec();
+    if inputs.is_empty() {
+        return ApiError::invalid_request("input must not be empty").into_response();
+    }
+
+    let Some(model_info) = server.model_info.as_ref() else {
+        return with_trace_header(
+            ApiError::from(LlmError::EmbeddingUnsupported).into_response(),
+            trace_id,
+        );
+    };
+
+    let mut data = Vec::with_capacity(inputs.len());
+    let mut prompt_tokens = 0_u32;
+    for (index, input) in inputs.into_iter().enumerate() {
+        match model_info.count_tokens(model_id, &input) {
+            Ok(count) => prompt_tokens = prompt_tokens.saturating_add(count),
+            Err(err) => {
+                let err = LlmError::ProviderError(format!("tokenizer unavailable: {err}"));
+                return with_trace_header(ApiError::from(err).into_response(), trace_id);
+            }
+        }
+        let request = EmbeddingRequest::new(trace_id, input, model_id.to_string());
+        match server.client.embedding(request).await {
+            Ok(response) => data.push(json!({
+                "object": "embedding",
+                "index": index,
+                "embedding": response.vector,
+            })),
+            Err(err) => return with_trace_header(ApiError::from(err).into_response(), trace_id),
+        }
+    }
+
+    let response = Json(json!({
+        "object": "list",
+        "data": data,
+        "model": body.model,
+        "usage": { "prompt_tokens": prompt_tokens, "total_tokens": prompt_tokens },
+    }))
+    .into_response();
+    with_trace_header(response, trace_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: Some(MessageContent::Text(content.to_string())),
+        }
+    }
+
+    #[test]
+    fn loopback_binds_pass_and_others_need_explicit_opt_in() {
+        let mut config = LocalOpenAiServerConfig::default();
+        assert!(config.validate_bind().is_ok());
+
+        config.bind_addr = "[::1]:37511".parse().expect("v6 loopback");
+        assert!(config.validate_bind().is_ok());
+
+        for addr in ["0.0.0.0:37511", "192.168.1.20:37511", "[::]:37511"] {
+            config.bind_addr = addr.parse().expect("addr");
+            config.allow_non_loopback = false;
+            config.api_key = None;
+            assert!(matches!(
+                config.validate_bind(),
+                Err(LocalOpenAiServerError::NonLoopbackBind(_))
+            ));
+            config.allow_non_loopback = true;
+            assert!(matches!(
+                config.validate_bind(),
+                Err(LocalOpenAiServerError::NonLoopbackWithoutApiKey(_))
+            ));
+            config.api_key = Some("k".to_string());
+            assert!(config.validate_bind().is_ok());
+        }
+    }
+
+    #[test]
+    fn chat_messages_render_as_chatml_with_assistant_cue() {
+        let prompt = render_chat_prompt(
+            &[message("developer", "Be brief."), message("user", "Hi")],
+            ChatFormat::from_template(None),
+        )
+        .expect("renders");
+        assert_eq!(
+            prompt,
+            "<|im_start|>system\nBe brief.<|im_end|>\n<|im_start|>user\nHi<|im_end|>\n<|im_start|>assistant\n"
+        );
+    }
+
+    #[test]
+    fn chat_format_follows_the_model_template() {
+        let llama3 = ChatFormat::from_template(Some(
+            "{% for m in messages %}<|start_header_id|>{{ m['role'] }}<|end_header_id|>{% endfor %}",
+        ));
+        assert_eq!(llama3, ChatFormat::Llama3);
+        let messages = [message("system", "Be brief."), message("user", "Hi")];
+        assert_eq!(
+            render_chat_prompt(&messages, llama3).expect("renders"),
+            "<|start_header_id|>system<|end_header_id|>\n\nBe brief.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nHi<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+
+        let gemma = ChatFormat::from_template(Some("<start_of_turn>user\n"));
+        assert_eq!(gemma.end_of_turn(), "<end_of_turn>");
+        assert_eq!(
+            render_chat_prompt(&messages, gemma).expect("renders"),
+            "<start_of_turn>user\nBe brief.\n\nHi<end_of_turn>\n<start_of_turn>model\n"
+        );
+
+        let mistral = ChatFormat::from_template(Some("[INST] {{ m }} [/INST]"));
+        assert_eq!(
+            render_chat_prompt(&[message("user", "Hi")], mistral).expect("renders"),
+            "[INST] Hi [/INST]"
+        );
+        assert_eq!(
+            ChatFormat::from_template(Some("{{ unknown }}")),
+            ChatFormat::ChatMl
+        );
+    }
+
+    #[test]
+    fn non_text_parts_and_unknown_roles_are_invalid_requests() {
+        let image = ChatMessage {
+            role: "user".to_string(),
+            content: Some(MessageContent::Parts(vec![ContentPart {
+                kind: "image_url".to_string(),
+                text: None,
+            }])),
+        };
+        let err =
+            render_chat_prompt(&[image], ChatFormat::ChatMl).expect_err("image parts rejected");
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+
+        let err = render_chat_prompt(&[message("narrator", "x")], ChatFormat::ChatMl)
+            .expect_err("role rejected");
+        assert_eq!(err.code, "invalid_request");
+        assert!(render_chat_prompt(&[], ChatFormat::ChatMl).is_err());
+    }
+
+    #[test]
+    fn finish_reason_prefers_runtime_signal_then_budget() {
+        assert_eq!(finish_reason_str(Some(FinishReason::Stop), 8, 8), "stop");
+        assert_eq!(
+            finish_reason_str(Some(FinishReason::Length), 2, 8),
+            "length"
+        );
+        assert_eq!(finish_reason_str(None, 8, 8), "length");
+        assert_eq!(finish_reason_str(None, 3, 8), "stop");
+    }
+}
@@ -20,12 +20,15 @@ use crate::{
         MemoryInjectionReceipt, ModelCallContextSource,
     },
     model_runtime::{
-        CancellationToken, GenPrompt, GenerateRequest, ModelId, ModelRegistry, ModelRuntime,
-        ModelRuntimeError, ProviderKind, RuntimeBinding, SamplingParams,
+        CancellationToken, GenPrompt, GenerateRequest, GeneratedToken, ModelId, ModelRegistry,
+        ModelRuntime, ModelRuntimeError, ProviderKind, RuntimeBinding, SamplingParams,
     },
 };
 
-use super::{CompletionRequest, CompletionResponse, LlmClient, LlmError, ModelProfile, TokenUsage};
+use super::{
+    CompletionRequest, CompletionResponse, EmbeddingRequest, EmbeddingResponse, LlmClient,
+    LlmError, ModelProfile, TokenUsage,
+};
 
 #[derive(Clone)]
 pub struct LocalRouter {
@@ -47,6 +50,11 @@ impl LocalRouter {
         }
     }
 
+    /// Registered local models (the `/v1/models` listing source).
+    pub fn registry(&self) -> &ModelRegistry {
+        &self.registry
+    }
+
     pub fn resolve(&self, model_id: ModelId) -> Result<Arc<dyn ModelRuntime>, LlmError> {
         let registration = self.registry.lookup(model_id).ok_or_else(|| {
             LlmError::ProviderError(format!("local model is not registered: {model_id}"))
@@ -126,6 +134,11 @@ impl LocalModelRuntimeLlmClient {
         self
     }
 
+    /// Returns the router resolving local model ids to runtimes.
+    pub fn router(&self) -> &LocalRouter {
+        &self.router
+    }
+
     /// Returns the capsule injector wired into this dispatcher, if any.
     pub fn capsule_injector(&self) -> Option<&Arc<dyn MemoryCapsuleInjection>> {
         self.capsule_injector.as_ref()
@@ -189,7 +202,7 @@ impl LocalModelRuntimeLlmClient {
         }
     }
 
-    fn parse_local_model_id(model_id: &str) -> Result<Option<ModelId>, LlmError> {
+    pub(crate) fn parse_local_model_id(model_id: &str) -> Result<Option<ModelId>, LlmError> {
         let trimmed = model_id.trim();
         let parsed = match Uuid::parse_str(trimmed) {
             Ok(parsed) => parsed,
@@ -242,6 +255,43 @@ impl LocalModelRuntimeLlmClient {
         count.min(u32::MAX as usize) as u32
     }
 
+    /// Embedding calls are model calls too (§11.5): record an `llm_inference`
+    /// event with the prompt-side usage and no response hash (the vector is
+    /// not text).
+    async fn emit_llm_embedding_event(&self, req: &EmbeddingRequest, latency_ms: u64) {
+        let prompt_tokens = Self::estimate_prompt_tokens(&req.input) as u64;
+        let payload = LlmInferenceEvent {
+            event_type: "llm_inference".to_string(),
+            trace_id: req.trace_id,
+            model_id: req.model_id.clone(),
+            token_usage: LlmInferenceTokenUsage {
+                prompt_tokens,
+                completion_tokens: 0,
+                total_tokens: prompt_tokens,
+            },
+            prompt_hash: Some(Self::compute_hash(&req.input)),
+            response_hash: None,
+            latency_ms: Some(latency_ms),
+        };
+
+        let event = FlightRecorderEvent::new(
+            FlightRecorderEventType::LlmInference,
+            FlightRecorderActor::Agent,
+            req.trace_id,
+            serde_json::to_value(&payload).unwrap_or_default(),
+        )
+        .with_model_id(&req.model_id);
+
+        if let Err(err) = self.flight_recorder.record_event(event).await {
+            tracing::warn!(
+                target: "handshake_core::llm",
+                error = %err,
+                trace_id = %req.trace_id,
+                "Failed to record local embedding llm_inference event"
+            );
+        }
+    }
+
     async fn emit_llm_inference_event(
         &self,
         req: &CompletionRequest,
@@ -282,26 +332,36 @@ impl LocalModelRuntimeLlmClient {
             );
         }
     }
-}
-
-#[async_trait]
-impl LlmClient for LocalModelRuntimeLlmClient {
-    async fn completion(&self, req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
-        let Some(model_id) = Self::parse_local_model_id(&req.model_id)? else {
-            return self.fallback.completion(req).await;
-        };
 
+    /// Runs a local completion for an already-parsed model id, handing every
+    /// generated token to `on_token` as it arrives. Returning `false` from
+    /// `on_token` cancels the runtime (the consumer went away) and fails the
+    /// call with a cancellation error.
+    ///
+    /// This is the single local generate path: `completion()` and the
+    /// OpenAI-compatible streaming server both go through it, so capsule
+    /// injection, the `max_tokens` budget and the `llm_inference` Flight
+    /// Recorder event are identical for both.
+    pub async fn complete_local_with<F>(
+        &self,
+        req: &CompletionRequest,
+        model_id: ModelId,
+        mut on_token: F,
+    ) -> Result<CompletionResponse, LlmError>
+    where
+        F: FnMut(&GeneratedToken) -> bool + Send,
+    {
         let started = Instant::now();
         let runtime = self.router.resolve(model_id)?;
         let cancel = CancellationToken::new();
         self.active_tokens().insert(model_id, cancel.clone());
-        let generate_request = self.request_to_generate_request(&req, model_id, cancel);
+        let generate_request = self.request_to_generate_request(req, model_id, cancel.clone());
         // MT-144: wire MemoryCapsule injection into the ModelRuntime generate
         // call path. On `Inject` the prompt is wrapped via
         // `attach_capsule_to_generate_request`; on `Skip` it is unchanged.
         // FR-EVT-CAPSULE-INJECTED is emitted inside `inject_for_call` itself.
         let (generate_request, _capsule_receipt) =
-            match self.apply_capsule_injection(&req, generate_request) {
+            match self.apply_capsule_injection(req, generate_request) {
                 Ok(pair) => pair,
                 Err(err) => {
                     self.active_tokens().remove(&model_id);
@@ -329,6 +389,11 @@ impl LlmClient for LocalModelRuntimeLlmClient {
                     break;
                 }
             }
+            if !on_token(&token) {
+                runtime.cancel(cancel.clone());
+                result = Err(Self::map_runtime_error(ModelRuntimeError::Cancelled));
+                break;
+            }
         }
         self.active_tokens().remove(&model_id);
         result?;
@@ -345,11 +410,48 @@ impl LlmClient for LocalModelRuntimeLlmClient {
             latency_ms: (started.elapsed().as_millis() as u64).max(1),
         };
 
-        self.emit_llm_inference_event(&req, &response.text, &response.usage, response.latency_ms)
+        self.emit_llm_inference_event(req, &response.text, &response.usage, response.latency_ms)
             .await;
 
         Ok(response)
     }
+}
+
+#[async_trait]
+impl LlmClient for LocalModelRuntimeLlmClient {
+    async fn completion(&self, req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        let Some(model_id) = Self::parse_local_model_id(&req.model_id)? else {
+            return self.fallback.completion(req).await;
+        };
+
+        self.complete_local_with(&req, model_id, |_| true).await
+    }
+
+    async fn embedding(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, LlmError> {
+        let Some(model_id) = Self::parse_local_model_id(&req.model_id)? else {
+            return self.fallback.embedding(req).await;
+        };
+
+        let started = Instant::now();
+        let runtime = self.router.resolve(model_id)?;
+        let embedding = runtime
+            .embed(model_id, &req.input)
+            .await
+            .map_err(Self::map_runtime_error)?;
+        if embedding.vector.is_empty() {
+            return Err(LlmError::ProviderError(
+                "local ModelRuntime returned an empty embedding vector".to_string(),
+            ));
+        }
+        let latency_ms = (started.elapsed().as_millis() as u64).max(1);
+        self.emit_llm_embedding_event(&req, latency_ms).await;
+
+        Ok(EmbeddingResponse {
+            vector: embedding.vector,
+            model_id: req.model_id,
+            latency_ms,
+        })
+    }
 
     fn cancel(&self, model_id: &str, token: CancellationToken) {
         let route = match Self::parse_local_model_id(model_id) {
@@ -5,6 +5,7 @@
 //! centralized observability via Flight Recorder.
 
 pub mod guard;
+pub mod local_openai_server;
 pub mod local_router;
 pub mod ollama;
 pub mod openai_compat;
@@ -306,6 +307,11 @@ pub enum LlmError {
     /// MUST degrade to keyword/trigram modalities — NEVER fabricate a vector.
     #[error("HSK-501-EMBEDDING-UNSUPPORTED: no embedding model configured")]
     EmbeddingUnsupported,
+
+    /// HSK-409-CASSETTE-*: A replay client could not serve the request from its
+    /// cassette (request mismatch, exhausted or unreadable recording).
+    #[error(transparent)]
+    Cassette(#[from] crate::cassette::CassetteError),
 }
 
 /// LLM client used when the provider is unavailable at startup.
@@ -0,0 +1,702 @@
+//! Loom preview pipeline: what `loom_preview_generate` can render, and how.
+//!
+//! The background job (`hsk.loom.preview_generate@v1`, see `workflows.rs`)
+//! derives a tier pyramid from every imported original:
+//!
+//! * PNG, JPEG, GIF (first frame), WebP and TIFF decode in-process through the
+//!   `image` crate;
+//! * SVG rasterises in-process through `resvg` (external `href`s are never
+//!   followed; only `data:` images embedded in the file are drawn);
+//! * AVIF has no pure-Rust decoder, so its first frame is extracted by a
+//!   detected local `ffmpeg`;
+//! * PDF first pages are rendered by a detected local `pdftoppm` (poppler);
+//! * video posters come from `ffmpeg`, and `ffprobe` reports duration, frame
+//!   size and codecs.
+//!
+//! External tools are never bundled or downloaded here. They are looked up
+//! (env override, managed tool dirs, then `PATH`) and run as governed MEX
+//! operations on `engine.media_preview`. When a needed tool is absent the job
+//! records honest `failed` tiers with a `<tool>_not_found` reason, so they land
+//! in the retry queue instead of showing a fake poster.
+//!
+//! This module is the pure half (classification, decode, tier rendering,
+//! tool arguments, ffprobe parsing); the job in `workflows.rs` runs the tools
+//! and touches storage.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::storage::MediaTier;
+
+/// Longest side of the `thumb` tier (grid tiles).
+pub const THUMB_MAX_PX: u32 = 256;
+
+/// Longest side of the `preview` tier (in-editor preview step).
+pub const PREVIEW_MAX_PX: u32 = 1024;
+
+/// Longest side of the `poster` tier (video cover frame / PDF first page).
+pub const POSTER_MAX_PX: u32 = 1920;
+
+/// Raster MIME types the bundled `image` decoders handle.
+const RASTER_MIMES: &[&str] = &[
+    "image/png",
+    "image/apng",
+    "image/jpeg",
+    "image/jpg",
+    "image/pjpeg",
+    "image/gif",
+    "image/webp",
+    "image/tiff",
+    "image/x-tiff",
+];
+
+/// What kind of original a preview is derived from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewSource {
+    /// Decoded in-process by the `image` crate.
+    Raster,
+    /// First frame extracted by ffmpeg.
+    Avif,
+    /// Rasterised in-process by resvg.
+    Svg,
+    /// First page rendered by pdftoppm.
+    Pdf,
+    /// Poster frame extracted by ffmpeg, metadata by ffprobe.
+    Video,
+    Unsupported,
+}
+
+impl PreviewSource {
+    /// Classify an original by its recorded MIME type, sniffing the bytes
+    /// when the MIME is generic or an image type the decoders do not name.
+    pub fn classify(mime: &str, bytes: &[u8]) -> Self {
+        let mime = mime
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "image/svg+xml" => Self::Svg,
+            "image/avif" => Self::Avif,
+            "application/pdf" => Self::Pdf,
+            m if RASTER_MIMES.contains(&m) => Self::Raster,
+            m if m.starts_with("video/") => Self::Video,
+            _ => Self::sniff(bytes),
+        }
+    }
+
+    fn sniff(bytes: &[u8]) -> Self {
+        if bytes.starts_with(b"%PDF-") {
+            return Self::Pdf;
+        }
+        match image::guess_format(bytes) {
+            Ok(
+                ImageFormat::Png
+                | ImageFormat::Jpeg
+                | ImageFormat::Gif
+                | ImageFormat::WebP
+                | ImageFormat::Tiff,
+            ) => return Self::Raster,
+            Ok(ImageFormat::Avif) => return Self::Avif,
+            _ => {}
+        }
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_ascii_lowercase();
+        if head.contains("<svg") {
+            return Self::Svg;
+        }
+        Self::Unsupported
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Raster => "raster",
+            Self::Avif => "avif",
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+            Self::Video => "video",
+            Self::Unsupported => "unsupported",
+        }
+    }
+
+    /// The external tool the source image comes from, if any.
+    pub fn tool(&self) -> Option<PreviewTool> {
+        match self {
+            Self::Avif | Self::Video => Some(PreviewTool::Ffmpeg),
+            Self::Pdf => Some(PreviewTool::Pdftoppm),
+            Self::Raster | Self::Svg | Self::Unsupported => None,
+        }
+    }
+
+    /// The derived tiers this source produces (`full` always points back at
+    /// the original and is not listed). Videos and PDFs also get a poster.
+    pub fn derived_tiers(&self) -> &'static [MediaTier] {
+        match self {
+            Self::Raster | Self::Avif | Self::Svg => &[MediaTier::Thumb, MediaTier::Preview],
+            Self::Pdf | Self::Video => &[MediaTier::Thumb, MediaTier::Preview, MediaTier::Poster],
+            Self::Unsupported => &[],
+        }
+    }
+}
+
+/// A local binary the pipeline can run as a governed tool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewTool {
+    Ffmpeg,
+    Ffprobe,
+    Pdftoppm,
+}
+
+impl PreviewTool {
+    pub fn binary_name(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "ffmpeg",
+            Self::Ffprobe => "ffprobe",
+            Self::Pdftoppm => "pdftoppm",
+        }
+    }
+
+    /// Environment variable that pins the binary path, bypassing lookup.
+    pub fn env_override(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "HANDSHAKE_FFMPEG_PATH",
+            Self::Ffprobe => "HANDSHAKE_FFPROBE_PATH",
+            Self::Pdftoppm => "HANDSHAKE_PDFTOPPM_PATH",
+        }
+    }
+
+    /// MEX operation on `engine.media_preview`.
+    pub fn mex_operation(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "ffmpeg.exec",
+            Self::Ffprobe => "ffprobe.exec",
+            Self::Pdftoppm => "pdftoppm.exec",
+        }
+    }
+
+    pub fn capability(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "proc.exec:ffmpeg",
+            Self::Ffprobe => "proc.exec:ffprobe",
+            Self::Pdftoppm => "proc.exec:pdftoppm",
+        }
+    }
+
+    /// Tier `failure_reason` when the binary is absent.
+    pub fn missing_reason(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "ffmpeg_not_found",
+            Self::Ffprobe => "ffprobe_not_found",
+            Self::Pdftoppm => "pdftoppm_not_found",
+        }
+    }
+
+    /// Tier `failure_reason` when the binary ran but produced nothing usable.
+    pub fn failed_reason(&self) -> &'static str {
+        match self {
+            Self::Ffmpeg => "ffmpeg_failed",
+            Self::Ffprobe => "ffprobe_failed",
+            Self::Pdftoppm => "pdftoppm_failed",
+        }
+    }
+
+    /// Find the binary: the env override (when it names a file), then each
+    /// managed tool dir, then `PATH`.
+    pub fn detect(&self, managed_dirs: &[PathBuf]) -> Option<PathBuf> {
+        if let Some(pinned) = std::env::var_os(self.env_override()) {
+            let pinned = PathBuf::from(pinned);
+            return pinned.is_file().then_some(pinned);
+        }
+        let file_name = format!("{}{}", self.binary_name(), std::env::consts::EXE_SUFFIX);
+        managed_dirs
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|candidate| candidate.is_file())
+            .or_else(|| which::which(self.binary_name()).ok())
+    }
+}
+
+/// Why a source could not be turned into preview tiers. [`reason`] is the
+/// stable string recorded as the tier `failure_reason`.
+///
+/// [`reason`]: PreviewError::reason
+#[derive(Debug, thiserror::Error)]
+pub enum PreviewError {
+    #[error("no preview renderer for mime {0}")]
+    UnsupportedMime(String),
+    #[error("{} not found (set {} or install it on PATH)", .0.binary_name(), .0.env_override())]
+    ToolMissing(PreviewTool),
+    #[error("{} failed: {1}", .0.binary_name())]
+    ToolFailed(PreviewTool, String),
+    #[error("decode failed: {0}")]
+    Decode(String),
+    #[error("encode failed: {0}")]
+    Encode(String),
+}
+
+impl PreviewError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::UnsupportedMime(_) => "unsupported_mime",
+            Self::ToolMissing(tool) => tool.missing_reason(),
+            Self::ToolFailed(tool, _) => tool.failed_reason(),
+            Self::Decode(_) => "decode_failed",
+            Self::Encode(_) => "encode_failed",
+        }
+    }
+}
+
+// ===========================================================================
+// Decode
+// ===========================================================================
+
+/// Decode a raster original (or a PNG frame a tool wrote). Animated GIF and
+/// WebP yield their first frame.
+pub fn decode_raster(bytes: &[u8]) -> Result<DynamicImage, PreviewError> {
+    image::load_from_memory(bytes).map_err(|e| PreviewError::Decode(e.to_string()))
+}
+
+/// System fonts for SVG `<text>`, loaded once per process.
+static SVG_FONTS: Lazy<Arc<usvg::fontdb::Database>> = Lazy::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    db.load_system_fonts();
+    Arc::new(db)
+});
+
+/// Rasterise an SVG so its longest side is `long_side_px`. Small icons scale
+/// up (vector sources have no native resolution). External image `href`s are
+/// ignored so a preview never reads files outside the asset store.
+pub fn rasterize_svg(bytes: &[u8], long_side_px: u32) -> Result<DynamicImage, PreviewError> {
+    let options = usvg::Options {
+        fontdb: SVG_FONTS.clone(),
+        image_href_resolver: usvg::ImageHrefResolver {
+            resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+            resolve_string: Box::new(|_, _| None),
+        },
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_data(bytes, &options)
+        .map_err(|e| PreviewError::Decode(format!("svg: {e}")))?;
+    let size = tree.size();
+    let (width, height) = (size.width(), size.height());
+    if !(width > 0.0 && height > 0.0) {
+        return Err(PreviewError::Decode("svg has an empty canvas".to_string()));
+    }
+    let scale = long_side_px as f32 / width.max(height);
+    let px_width = ((width * scale).round() as u32).max(1);
+    let px_height = ((height * scale).round() as u32).max(1);
+    let mut pixmap = tiny_skia::Pixmap::new(px_width, px_height)
+        .ok_or_else(|| PreviewError::Decode("svg canvas too large".to_string()))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    // tiny-skia stores premultiplied alpha; the image crate expects straight.
+    let mut rgba = Vec::with_capacity((px_width * px_height * 4) as usize);
+    for pixel in pixmap.pixels() {
+        let color = pixel.demultiply();
+        rgba.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+    }
+    image::RgbaImage::from_raw(px_width, px_height, rgba)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| PreviewError::Decode("svg pixel buffer size mismatch".to_string()))
+}
+
+// ===========================================================================
+// Tiers
+// ===========================================================================
+
+/// Longest side of a derived tier; `None` for `full` (the original itself).
+pub fn tier_max_px(tier: MediaTier) -> Option<u32> {
+    match tier {
+        MediaTier::Thumb => Some(THUMB_MAX_PX),
+        MediaTier::Preview => Some(PREVIEW_MAX_PX),
+        MediaTier::Poster => Some(POSTER_MAX_PX),
+        MediaTier::Full => None,
+    }
+}
+
+/// One derived tier, PNG-encoded and content-addressed.
+#[derive(Clone, Debug)]
+pub struct RenderedTier {
+    pub tier: MediaTier,
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub content_hash: String,
+}
+
+impl RenderedTier {
+    /// `assets.kind` of the derived blob. `thumbnail` keeps the legacy
+    /// single-slot preview directory.
+    pub fn asset_kind(&self) -> &'static str {
+        match self.tier {
+            MediaTier::Thumb => "thumbnail",
+            MediaTier::Preview => "preview",
+            MediaTier::Poster => "poster",
+            MediaTier::Full => "original",
+        }
+    }
+}
+
+/// Downscale `source` to fit `tier` (never upscaling) and encode it as PNG.
+pub fn render_tier(source: &DynamicImage, tier: MediaTier) -> Result<RenderedTier, PreviewError> {
+    let max_px = tier_max_px(tier).ok_or_else(|| {
+        PreviewError::Encode("the full tier is the original, not a rendition".to_string())
+    })?;
+    let (width, height) = source.dimensions();
+    let scaled = if width <= max_px && height <= max_px {
+        source.clone()
+    } else {
+        source.thumbnail(max_px, max_px)
+    };
+    let mut png = Vec::new();
+    scaled
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| PreviewError::Encode(e.to_string()))?;
+    let content_hash = hex::encode(Sha256::digest(&png));
+    Ok(RenderedTier {
+        tier,
+        width: scaled.width(),
+        height: scaled.height(),
+        png,
+        content_hash,
+    })
+}
+
+// ===========================================================================
+// Tool arguments
+// ===========================================================================
+
+fn path_arg(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// `ffprobe` JSON report of container format and streams.
+pub fn ffprobe_args(input: &Path) -> Vec<String> {
+    vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-print_format".to_string(),
+        "json".to_string(),
+        "-show_format".to_string(),
+        "-show_streams".to_string(),
+        path_arg(input),
+    ]
+}
+
+/// `ffmpeg` single frame at `seek_secs`, written as PNG (no audio).
+pub fn ffmpeg_frame_args(input: &Path, output: &Path, seek_secs: f64) -> Vec<String> {
+    let mut args = vec!["-v".to_string(), "error".to_string(), "-y".to_string()];
+    if seek_secs > 0.0 {
+        args.push("-ss".to_string());
+        args.push(format!("{seek_secs:.3}"));
+    }
+    args.extend([
+        "-i".to_string(),
+        path_arg(input),
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-an".to_string(),
+        "-f".to_string(),
+        "image2".to_string(),
+        "-c:v".to_string(),
+        "png".to_string(),
+        path_arg(output),
+    ]);
+    args
+}
+
+/// `pdftoppm` first page as PNG, longest side [`POSTER_MAX_PX`]. pdftoppm
+/// appends `.png` to `output_prefix`.
+pub fn pdftoppm_args(input: &Path, output_prefix: &Path) -> Vec<String> {
+    vec![
+        "-f".to_string(),
+        "1".to_string(),
+        "-l".to_string(),
+        "1".to_string(),
+        "-png".to_string(),
+        "-singlefile".to_string(),
+        "-scale-to".to_string(),
+        POSTER_MAX_PX.to_string(),
+        path_arg(input),
+        path_arg(output_prefix),
+    ]
+}
+
+/// Where the poster frame is taken: a tenth into the clip (past fade-ins and
+/// black leaders), never later than five seconds; the first frame when the
+/// duration is unknown.
+pub fn poster_seek_secs(duration_ms: Option<i64>) -> f64 {
+    match duration_ms {
+        Some(ms) if ms > 0 => (ms as f64 / 1000.0 * 0.1).min(5.0),
+        _ => 0.0,
+    }
+}
+
+// ===========================================================================
+// ffprobe
+// ===========================================================================
+
+/// What ffprobe reported about a video original.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct VideoProbe {
+    pub duration_ms: Option<i64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub frame_rate: Option<f64>,
+}
+
+fn seconds_to_ms(value: Option<&Value>) -> Option<i64> {
+    let secs = match value? {
+        Value::String(s) => s.trim().parse::<f64>().ok()?,
+        Value::Number(n) => n.as_f64()?,
+        _ => return None,
+    };
+    (secs.is_finite() && secs >= 0.0).then(|| (secs * 1000.0).round() as i64)
+}
+
+/// `"30000/1001"` -> 29.97; `"0/0"` and garbage -> `None`.
+fn parse_frame_rate(value: Option<&Value>) -> Option<f64> {
+    let raw = value?.as_str()?;
+    let rate = match raw.split_once('/') {
+        Some((num, den)) => {
+            let num = num.trim().parse::<f64>().ok()?;
+            let den = den.trim().parse::<f64>().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            num / den
+        }
+        None => raw.trim().parse::<f64>().ok()?,
+    };
+    (rate.is_finite() && rate > 0.0).then_some(rate)
+}
+
+/// Parse `ffprobe -print_format json -show_format -show_streams` output.
+/// The container duration wins; the first video stream's duration is the
+/// fallback (some muxers only report it per stream).
+pub fn parse_ffprobe_json(raw: &str) -> Result<VideoProbe, PreviewError> {
+    let parsed: Value = serde_json::from_str(raw)
+        .map_err(|e| PreviewError::ToolFailed(PreviewTool::Ffprobe, e.to_string()))?;
+    let streams = parsed
+        .get("streams")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let stream_of = |kind: &str| {
+        streams
+            .iter()
+            .find(|s| s.get("codec_type").and_then(Value::as_str) == Some(kind))
+    };
+    let video = stream_of("video");
+    let audio = stream_of("audio");
+    if video.is_none() && audio.is_none() {
+        return Err(PreviewError::ToolFailed(
+            PreviewTool::Ffprobe,
+            "no audio or video streams".to_string(),
+        ));
+    }
+    let dimension = |key: &str| {
+        video
+            .and_then(|v| v.get(key))
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+            .filter(|n| *n > 0)
+    };
+    let codec = |stream: Option<&Value>| {
+        stream
+            .and_then(|s| s.get("codec_name"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    Ok(VideoProbe {
+        duration_ms: seconds_to_ms(parsed.get("format").and_then(|f| f.get("duration")))
+            .or_else(|| seconds_to_ms(video.and_then(|v| v.get("duration")))),
+        width: dimension("width"),
+        height: dimension("height"),
+        video_codec: codec(video),
+        audio_codec: codec(audio),
+        frame_rate: video.and_then(|v| {
+            parse_frame_rate(v.get("avg_frame_rate"))
+                .or_else(|| parse_frame_rate(v.get("r_frame_rate")))
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(format: ImageFormat, width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbaImage::from_fn(width, height, |x, y| {
+            image::Rgba([(x % 256) as u8, (y % 256) as u8, 128, 255])
+        });
+        let mut out = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut Cursor::new(&mut out), format)
+            .expect("encode fixture");
+        out
+    }
+
+    #[test]
+    fn classify_routes_by_mime_then_bytes() {
+        assert_eq!(
+            PreviewSource::classify("image/webp", &[]),
+            PreviewSource::Raster
+        );
+        assert_eq!(
+            PreviewSource::classify("image/avif", &[]),
+            PreviewSource::Avif
+        );
+        assert_eq!(
+            PreviewSource::classify("image/svg+xml", &[]),
+            PreviewSource::Svg
+        );
+        assert_eq!(
+            PreviewSource::classify("application/pdf", &[]),
+            PreviewSource::Pdf
+        );
+        assert_eq!(
+            PreviewSource::classify("video/mp4", &[]),
+            PreviewSource::Video
+        );
+        assert_eq!(
+            PreviewSource::classify("application/octet-stream", b"%PDF-1.7\n"),
+            PreviewSource::Pdf
+        );
+        assert_eq!(
+            PreviewSource::classify("application/octet-stream", &encoded(ImageFormat::Gif, 4, 4)),
+            PreviewSource::Raster
+        );
+        assert_eq!(
+            PreviewSource::classify("text/xml", b"<?xml version=\"1.0\"?><svg/>"),
+            PreviewSource::Svg
+        );
+        assert_eq!(
+            PreviewSource::classify("application/zip", b"PK\x03\x04"),
+            PreviewSource::Unsupported
+        );
+    }
+
+    #[test]
+    fn bundled_decoders_cover_webp_gif_and_tiff() {
+        for format in [ImageFormat::WebP, ImageFormat::Gif, ImageFormat::Tiff] {
+            let img = decode_raster(&encoded(format, 40, 30)).expect("decode");
+            assert_eq!(img.dimensions(), (40, 30), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn render_tier_downscales_but_never_upscales() {
+        let big = decode_raster(&encoded(ImageFormat::Png, 2000, 1000)).unwrap();
+        let thumb = render_tier(&big, MediaTier::Thumb).unwrap();
+        assert_eq!((thumb.width, thumb.height), (256, 128));
+        let poster = render_tier(&big, MediaTier::Poster).unwrap();
+        assert_eq!((poster.width, poster.height), (1920, 960));
+        assert_eq!(poster.asset_kind(), "poster");
+
+        let small = decode_raster(&encoded(ImageFormat::Png, 100, 50)).unwrap();
+        let preview = render_tier(&small, MediaTier::Preview).unwrap();
+        assert_eq!((preview.width, preview.height), (100, 50));
+        assert_eq!(preview.content_hash.len(), 64);
+        assert!(render_tier(&small, MediaTier::Full).is_err());
+    }
+
+    #[test]
+    fn svg_rasterises_to_the_requested_long_side() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+            <rect width="20" height="10" fill="#ff0000"/></svg>"##;
+        let img = rasterize_svg(svg, 400).expect("rasterise");
+        assert_eq!(img.dimensions(), (400, 200));
+        let center = img.to_rgba8().get_pixel(200, 100).0;
+        assert_eq!(center, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn svg_external_image_hrefs_are_not_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside.png");
+        std::fs::write(&outside, encoded(ImageFormat::Png, 10, 10)).unwrap();
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"
+            xmlns:xlink="http://www.w3.org/1999/xlink" width="10" height="10">
+            <image width="10" height="10" xlink:href="{}"/></svg>"#,
+            outside.display()
+        );
+        let img = rasterize_svg(svg.as_bytes(), 10).expect("rasterise");
+        assert!(img.to_rgba8().pixels().all(|p| p.0[3] == 0));
+    }
+
+    #[test]
+    fn ffprobe_report_parses_duration_size_codecs_and_rate() {
+        let raw = r#"{
+            "streams": [
+                {"codec_type": "audio", "codec_name": "aac"},
+                {"codec_type": "video", "codec_name": "h264", "width": 1280,
+                 "height": 720, "avg_frame_rate": "30000/1001", "duration": "9.9"}
+            ],
+            "format": {"duration": "12.345678"}
+        }"#;
+        let probe = parse_ffprobe_json(raw).unwrap();
+        assert_eq!(probe.duration_ms, Some(12_346));
+        assert_eq!((probe.width, probe.height), (Some(1280), Some(720)));
+        assert_eq!(probe.video_codec.as_deref(), Some("h264"));
+        assert_eq!(probe.audio_codec.as_deref(), Some("aac"));
+        assert!((probe.frame_rate.unwrap() - 29.97).abs() < 0.01);
+
+        let stream_only = r#"{"streams": [{"codec_type": "video", "duration": "2.5",
+            "avg_frame_rate": "0/0", "r_frame_rate": "25/1"}], "format": {}}"#;
+        let probe = parse_ffprobe_json(stream_only).unwrap();
+        assert_eq!(probe.duration_ms, Some(2_500));
+        assert_eq!(probe.frame_rate, Some(25.0));
+
+        let err = parse_ffprobe_json(r#"{"streams": [], "format": {}}"#).unwrap_err();
+        assert_eq!(err.reason(), "ffprobe_failed");
+    }
+
+    #[test]
+    fn poster_seek_skips_the_leader_but_stays_early() {
+        assert_eq!(poster_seek_secs(None), 0.0);
+        assert_eq!(poster_seek_secs(Some(0)), 0.0);
+        assert!((poster_seek_secs(Some(8_000)) - 0.8).abs() < 1e-9);
+        assert_eq!(poster_seek_secs(Some(3_600_000)), 5.0);
+        let args = ffmpeg_frame_args(Path::new("in.mp4"), Path::new("out.png"), 0.8);
+        assert_eq!(&args[3..5], &["-ss".to_string(), "0.800".to_string()]);
+        let first = ffmpeg_frame_args(Path::new("in.avif"), Path::new("out.png"), 0.0);
+        assert!(!first.iter().any(|a| a == "-ss"));
+    }
+
+    #[test]
+    fn missing_tools_map_to_honest_reasons() {
+        assert_eq!(
+            PreviewError::ToolMissing(PreviewTool::Ffmpeg).reason(),
+            "ffmpeg_not_found"
+        );
+        assert_eq!(
+            PreviewError::ToolMissing(PreviewTool::Pdftoppm).reason(),
+            "pdftoppm_not_found"
+        );
+        assert_eq!(PreviewSource::Video.tool(), Some(PreviewTool::Ffmpeg));
+        assert_eq!(PreviewSource::Pdf.tool(), Some(PreviewTool::Pdftoppm));
+        assert!(PreviewSource::Video
+            .derived_tiers()
+            .contains(&MediaTier::Poster));
+        assert!(!PreviewSource::Raster
+            .derived_tiers()
+            .contains(&MediaTier::Poster));
+    }
+}
@@ -12,6 +12,9 @@
 //!     runs the hybrid FTS + pg_trgm + pgvector kNN query, blended with the Loom
 //!     graph and faceted by content_type.
 //!
+//!   * [`search_reranked`] additionally rescores the returned page with the
+//!     optional cross-encoder stage (`knowledge_retrieval::rerank`).
+//!
 //! No-model path (HARD requirement): when the configured client declines the
 //! embedding call with a typed [`LlmError`] (e.g. [`LlmError::EmbeddingUnsupported`]
 //! from `DisabledLlmClient`), the semantic modality is OMITTED — the search
@@ -20,6 +23,9 @@
 
 use uuid::Uuid;
 
+use crate::knowledge_retrieval::rerank::{
+    rerank, CrossEncoder, RerankItem, RerankPolicy, RerankRecord,
+};
 use crate::llm::{EmbeddingRequest, LlmClient, LlmError};
 use crate::storage::{
     Database, LoomBlock, LoomSearchV2Request, LoomSearchV2Response, StorageError, StorageResult,
@@ -118,3 +124,50 @@ pub async fn search(
     }
     db.loom_search_v2(workspace_id, request).await
 }
+
+/// [`search`], then the optional cross-encoder rerank over the returned page.
+/// Each hit is scored against its flattened block text and blended with its
+/// fused score (normalized to the page maximum). With no model loaded the page
+/// keeps its hybrid order and the record says why; a rerank never fails the
+/// search.
+pub async fn search_reranked(
+    db: &dyn Database,
+    llm: &dyn LlmClient,
+    reranker: Option<&dyn CrossEncoder>,
+    workspace_id: &str,
+    request: LoomSearchV2Request,
+    policy: &RerankPolicy,
+) -> StorageResult<(LoomSearchV2Response, RerankRecord)> {
+    let query = request.query.clone();
+    let mut response = search(db, llm, workspace_id, request).await?;
+    let max_score = response
+        .hits
+        .iter()
+        .map(|hit| hit.score)
+        .fold(0.0_f64, f64::max);
+    let items: Vec<RerankItem> = response
+        .hits
+        .iter()
+        .map(|hit| RerankItem {
+            candidate_id: hit.block.block_id.clone(),
+            base_score: if max_score > 0.0 {
+                (hit.score / max_score).clamp(0.0, 1.0)
+            } else {
+                0.0
+            },
+            text: Some(block_search_text(&hit.block)),
+        })
+        .collect();
+    let outcome = rerank(reranker, &query, &items, policy).await;
+    let mut slots: Vec<_> = response.hits.into_iter().map(Some).collect();
+    response.hits = outcome
+        .order
+        .iter()
+        .filter_map(|idx| {
+            let mut hit = slots[*idx].take()?;
+            hit.rerank_score = outcome.rerank_scores[*idx];
+            Some(hit)
+        })
+        .collect();
+    Ok((response, outcome.record))
+}
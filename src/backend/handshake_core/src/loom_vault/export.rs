@@ -0,0 +1,993 @@
+//! Loom -> markdown vault export.
+//!
+//! The way back out of [`super::import`]: a workspace's Loom (or one folder
+//! subtree of it) written as an Obsidian-compatible vault.
+//!
+//! * `note` and `journal` blocks become markdown files — journals as
+//!   `YYYY-MM-DD.md`, under `journals/` unless they sit in a folder — with
+//!   the body rendered from the RichDocument a vault import gave the block,
+//!   or else from the legacy document the block points at;
+//! * Loom folders become directories;
+//! * YAML front-matter carries `hsk_block_id`, the title when the file name
+//!   cannot hold it, aliases, the block's tags (by nested hub name,
+//!   `project/alpha`), `links` for MENTION edges the body does not already
+//!   spell out, and the vault properties an earlier import kept;
+//! * `file` blocks whose asset is exportable are copied into `attachments/`.
+//!
+//! `hsk_block_id` is what makes the round trip: importing the export into
+//! the same workspace, under any vault name, binds every file back to its
+//! block and versions its RichDocument instead of duplicating it. Tag hubs,
+//! canvases and saved views have no file of their own and are listed as
+//! skipped.
+//!
+//! The export is a projection: it reads authority and never writes back. A
+//! `.handshake/vault-export.json` manifest (hidden, so an import ignores it)
+//! lists every file with its block and hash.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Cursor, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::knowledge_document::block_tree::BlockTree;
+use crate::knowledge_document::projection::{render_projection, ProjectionFormat};
+use crate::loom_fs::{loom_asset_blob_path, resolve_handshake_root};
+use crate::storage::artifacts;
+use crate::storage::knowledge::KnowledgeStore;
+use crate::storage::loom_vault::{list_loom_vault_entries_for_block, LoomVaultEntryKind};
+use crate::storage::postgres::PostgresDatabase;
+use crate::storage::{
+    Database, LoomBlock, LoomBlockContentType, LoomEdgeType, LoomFolder, LoomViewFilters,
+    LoomViewResponse, LoomViewType, StorageError,
+};
+
+use super::import::tag_hub_paths;
+use super::{
+    io_error, parse_vault_note, LoomVaultError, LoomVaultResult, ParsedVaultNote, VaultIndex,
+    VaultKind, VaultResolution, VaultTarget, MAX_VAULT_ATTACHMENT_BYTES, VAULT_BLOCK_ID_KEY,
+};
+
+/// Where the export manifest sits inside the vault.
+pub const VAULT_EXPORT_MANIFEST_PATH: &str = ".handshake/vault-export.json";
+
+/// Directory every exported attachment goes to.
+pub const VAULT_ATTACHMENTS_DIR: &str = "attachments";
+
+/// An extra directory vault exports may be written under, besides the
+/// workspace root (e.g. a synced Obsidian folder).
+pub const VAULT_EXPORT_ROOT_ENV: &str = "HANDSHAKE_VAULT_EXPORT_ROOT";
+
+/// Directory for journal blocks that are not in any exported folder.
+pub const VAULT_JOURNALS_DIR: &str = "journals";
+
+/// Front-matter keys the exporter writes itself; a kept vault property of
+/// the same name is dropped rather than written twice.
+const RESERVED_PROPERTY_KEYS: [&str; 7] = [
+    VAULT_BLOCK_ID_KEY,
+    "title",
+    "aliases",
+    "alias",
+    "tags",
+    "tag",
+    "links",
+];
+
+/// What to export.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LoomVaultExportRequest {
+    /// Export only this folder's subtree, with paths relative to it (the
+    /// root folder a vault import created exports back to that vault's
+    /// layout). `None` exports the whole workspace.
+    #[serde(default)]
+    pub folder_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LoomVaultExportCounts {
+    pub notes: u64,
+    pub journals: u64,
+    pub attachments: u64,
+    pub folders: u64,
+    /// Tag names written to front-matter (tags already inline in the body
+    /// are not repeated).
+    pub tags_written: u64,
+    /// MENTION edges written to the `links` front-matter key.
+    pub links_written: u64,
+    /// MENTION edges whose target is not part of the export.
+    pub links_outside_export: u64,
+}
+
+/// One file of the exported vault.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportedVaultFile {
+    pub relative_path: String,
+    pub block_id: String,
+    pub kind: LoomVaultEntryKind,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// A block in scope that produced no file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkippedLoomBlock {
+    pub block_id: String,
+    pub content_type: LoomBlockContentType,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VaultExportWarning {
+    pub block_id: String,
+    pub detail: String,
+}
+
+/// Written to [`VAULT_EXPORT_MANIFEST_PATH`] and returned to the caller.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoomVaultExportManifest {
+    pub workspace_id: String,
+    pub folder_id: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub counts: LoomVaultExportCounts,
+    /// Sorted by path; the manifest itself is not listed.
+    pub files: Vec<ExportedVaultFile>,
+    pub skipped: Vec<SkippedLoomBlock>,
+    pub warnings: Vec<VaultExportWarning>,
+}
+
+/// A rendered vault: every file by relative path, plus its manifest.
+#[derive(Clone, Debug)]
+pub struct ExportedLoomVault {
+    pub manifest: LoomVaultExportManifest,
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ExportedLoomVault {
+    pub fn manifest_json(&self) -> String {
+        serde_json::to_string_pretty(&self.manifest).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("loom-vault-{}.zip", self.manifest.workspace_id)
+    }
+
+    /// The vault as a zip (sorted entries, fixed timestamps), manifest
+    /// included.
+    pub fn to_zip_bytes(&self) -> std::io::Result<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::<u8>::new()));
+        let timestamp = zip::DateTime::from_date_and_time(1980, 1, 1, 0, 0, 0)
+            .unwrap_or_else(|_| zip::DateTime::default());
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .compression_level(Some(6))
+            .last_modified_time(timestamp);
+
+        let manifest = self.manifest_json();
+        let mut entries: Vec<(&str, &[u8])> = self
+            .files
+            .iter()
+            .map(|(path, bytes)| (path.as_str(), bytes.as_slice()))
+            .collect();
+        entries.push((VAULT_EXPORT_MANIFEST_PATH, manifest.as_bytes()));
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (path, bytes) in entries {
+            writer.start_file(path, options)?;
+            writer.write_all(bytes)?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    /// Write the vault under `root`, which must be missing or empty unless
+    /// `overwrite` is set (then files at the same paths are replaced and
+    /// nothing else is touched).
+    pub fn write_to_dir(&self, root: &Path, overwrite: bool) -> LoomVaultResult<()> {
+        if root.exists() {
+            if !root.is_dir() {
+                return Err(LoomVaultError::Validation(format!(
+                    "output path {} is not a directory",
+                    root.display()
+                )));
+            }
+            let mut listing = std::fs::read_dir(root).map_err(|source| io_error(root, source))?;
+            if !overwrite && listing.next().is_some() {
+                return Err(LoomVaultError::Validation(format!(
+                    "output directory {} is not empty",
+                    root.display()
+                )));
+            }
+        } else {
+            std::fs::create_dir_all(root).map_err(|source| io_error(root, source))?;
+        }
+        let manifest = self.manifest_json();
+        let files = self
+            .files
+            .iter()
+            .map(|(path, bytes)| (path.as_str(), bytes.as_slice()))
+            .chain([(VAULT_EXPORT_MANIFEST_PATH, manifest.as_bytes())]);
+        for (path, bytes) in files {
+            artifacts::write_file_atomic(root, &root.join(path), bytes, overwrite)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve a requested vault output directory against the roots an export may
+/// be written under. A relative path is taken from the first root; `..` is
+/// refused, and the deepest existing ancestor is canonicalized so a symlink
+/// cannot lead outside. The result must sit strictly below one of the roots.
+pub fn resolve_export_dir(requested: &Path, roots: &[PathBuf]) -> LoomVaultResult<PathBuf> {
+    let outside = || {
+        LoomVaultError::Validation(format!(
+            "output path {} is outside the allowed export roots",
+            requested.display()
+        ))
+    };
+    if requested
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        return Err(outside());
+    }
+    let Some(first) = roots.first() else {
+        return Err(LoomVaultError::Validation(
+            "no export root is configured".to_string(),
+        ));
+    };
+    let candidate = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        first.join(requested)
+    };
+
+    let mut existing = candidate.as_path();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let (Some(name), Some(parent)) = (existing.file_name(), existing.parent()) else {
+            return Err(outside());
+        };
+        missing.push(name);
+        existing = parent;
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|source| io_error(existing, source))?;
+    resolved.extend(missing.into_iter().rev());
+
+    let allowed = roots.iter().filter_map(|root| root.canonicalize().ok());
+    for root in allowed {
+        if resolved != root && resolved.starts_with(&root) {
+            return Ok(resolved);
+        }
+    }
+    Err(outside())
+}
+
+/// Front-matter of one exported note.
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct VaultNoteFrontMatter {
+    pub hsk_block_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `[[path|Title]]` strings.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    #[serde(flatten)]
+    pub properties: Map<String, Value>,
+}
+
+/// `---\n<yaml>---\n\n<body>\n`.
+pub fn render_vault_note(front: &VaultNoteFrontMatter, body: &str) -> LoomVaultResult<String> {
+    let yaml = serde_yaml::to_string(front).map_err(|err| {
+        LoomVaultError::Validation(format!("front-matter not serializable: {err}"))
+    })?;
+    let yaml = yaml.strip_prefix("---\n").unwrap_or(&yaml);
+    let body = body.trim();
+    let mut out = format!("---\n{yaml}---\n");
+    if !body.is_empty() {
+        out.push('\n');
+        out.push_str(body);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// A Loom name as one vault path segment: no path separators, nothing that
+/// breaks a `[[wikilink]]` or a Windows file name, no leading dot (an
+/// import skips hidden files). Empty names become `Untitled`.
+pub fn vault_file_stem(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed: String = collapsed
+        .trim_matches(|c: char| c == '.' || c == ' ')
+        .chars()
+        .take(120)
+        .collect();
+    let trimmed = trimmed.trim_end();
+    if trimmed.is_empty() {
+        "Untitled".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Hands out vault paths, unique case-insensitively (as on macOS and
+/// Windows file systems): `dir/stem.ext`, then `dir/stem (2).ext`, ...
+#[derive(Debug, Default)]
+pub struct VaultPathAllocator {
+    taken: HashSet<String>,
+}
+
+impl VaultPathAllocator {
+    /// `extension` without the dot; empty for a directory.
+    pub fn allocate(&mut self, dir: &str, stem: &str, extension: &str) -> String {
+        let join = |name: String| -> String {
+            let file = if extension.is_empty() {
+                name
+            } else {
+                format!("{name}.{extension}")
+            };
+            if dir.is_empty() {
+                file
+            } else {
+                format!("{dir}/{file}")
+            }
+        };
+        let mut candidate = join(stem.to_string());
+        let mut n = 2;
+        while !self.taken.insert(candidate.to_lowercase()) {
+            candidate = join(format!("{stem} ({n})"));
+            n += 1;
+        }
+        candidate
+    }
+}
+
+/// The markdown projection of a RichDocument without the `# Title` line the
+/// projection opens with (the title lives in the file name / front-matter).
+fn document_body(title: &str, tree: &BlockTree) -> String {
+    let rendered = render_projection(title, tree, ProjectionFormat::Markdown).content;
+    let heading = format!("# {title}");
+    match rendered.strip_prefix(&heading) {
+        Some(rest) => rest.trim_start_matches('\n').to_string(),
+        None => rendered,
+    }
+}
+
+/// Exports a workspace's Loom as a markdown vault.
+pub struct LoomVaultExporter {
+    db: Arc<PostgresDatabase>,
+}
+
+/// A note or journal block on its way to a file.
+struct NotePlan {
+    block: LoomBlock,
+    kind: LoomVaultEntryKind,
+    relative_path: String,
+    title: Option<String>,
+    aliases: Vec<String>,
+    properties: Map<String, Value>,
+    body: String,
+}
+
+/// Where an exported block's file is, for writing links to it.
+struct LinkTarget {
+    /// Path without `.md` for notes, full path for attachments.
+    link_path: String,
+    display: Option<String>,
+}
+
+const PAGE: u32 = 500;
+
+impl LoomVaultExporter {
+    pub fn new(db: Arc<PostgresDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub async fn export(
+        &self,
+        workspace_id: &str,
+        request: &LoomVaultExportRequest,
+    ) -> LoomVaultResult<ExportedLoomVault> {
+        let db = self.db.as_ref();
+        let mut manifest = LoomVaultExportManifest {
+            workspace_id: workspace_id.to_string(),
+            folder_id: request.folder_id.clone(),
+            exported_at: Utc::now(),
+            counts: LoomVaultExportCounts::default(),
+            files: Vec::new(),
+            skipped: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        // Folders -> directories, parents first.
+        let folders = db.list_loom_folders(workspace_id).await?;
+        if let Some(scope) = &request.folder_id {
+            if !folders.iter().any(|f| &f.folder_id == scope) {
+                return Err(StorageError::NotFound("loom folder").into());
+            }
+        }
+        let mut children: HashMap<Option<&str>, Vec<&LoomFolder>> = HashMap::new();
+        for folder in &folders {
+            children
+                .entry(folder.parent_folder_id.as_deref())
+                .or_default()
+                .push(folder);
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by(|a, b| (&a.name, &a.folder_id).cmp(&(&b.name, &b.folder_id)));
+        }
+        let mut paths = VaultPathAllocator::default();
+        let mut dirs: Vec<(String, String)> = Vec::new();
+        let mut queue: Vec<(Option<&str>, String)> = match &request.folder_id {
+            Some(scope) => {
+                dirs.push((scope.clone(), String::new()));
+                vec![(Some(scope.as_str()), String::new())]
+            }
+            None => vec![(None, String::new())],
+        };
+        while let Some((parent, parent_dir)) = queue.pop() {
+            for folder in children.get(&parent).map(Vec::as_slice).unwrap_or_default() {
+                let dir = paths.allocate(&parent_dir, &vault_file_stem(&folder.name), "");
+                dirs.push((folder.folder_id.clone(), dir.clone()));
+                queue.push((Some(folder.folder_id.as_str()), dir));
+                manifest.counts.folders += 1;
+            }
+        }
+
+        // Blocks in scope, each in the first folder (parents first) that
+        // holds it.
+        let mut block_dir: HashMap<String, String> = HashMap::new();
+        let mut blocks: Vec<LoomBlock> = Vec::new();
+        for (folder_id, dir) in &dirs {
+            for block in self.folder_blocks(workspace_id, folder_id).await? {
+                if let Entry::Vacant(slot) = block_dir.entry(block.block_id.clone()) {
+                    slot.insert(dir.clone());
+                    blocks.push(block);
+                }
+            }
+        }
+        if request.folder_id.is_none() {
+            for block in self.all_blocks(workspace_id).await? {
+                if !block_dir.contains_key(&block.block_id) {
+                    blocks.push(block);
+                }
+            }
+        }
+        // Oldest first: an older block keeps the un-suffixed file name.
+        blocks.sort_by(|a, b| (a.created_at, &a.block_id).cmp(&(b.created_at, &b.block_id)));
+
+        let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        let mut targets: HashMap<String, LinkTarget> = HashMap::new();
+        let mut attachment_paths: Vec<String> = Vec::new();
+        let mut attachment_blocks: Vec<String> = Vec::new();
+        let mut plans: Vec<NotePlan> = Vec::new();
+        let mut handshake_root = None;
+        for block in blocks {
+            let content_type = block.content_type.clone();
+            match content_type {
+                LoomBlockContentType::Note | LoomBlockContentType::Journal => {
+                    let dir = block_dir.get(&block.block_id).cloned();
+                    let plan = self
+                        .plan_note(workspace_id, block, dir, &mut paths, &mut manifest)
+                        .await?;
+                    plans.push(plan);
+                }
+                LoomBlockContentType::File | LoomBlockContentType::AnnotatedFile => {
+                    let Some(asset_id) = &block.asset_id else {
+                        manifest
+                            .skipped
+                            .push(skipped(&block, "file block without an asset"));
+                        continue;
+                    };
+                    let asset = db.get_asset(workspace_id, asset_id).await?;
+                    if !asset.exportable {
+                        manifest
+                            .skipped
+                            .push(skipped(&block, "asset is not exportable"));
+                        continue;
+                    }
+                    if asset.size_bytes.max(0) as u64 > MAX_VAULT_ATTACHMENT_BYTES {
+                        manifest.skipped.push(skipped(
+                            &block,
+                            "asset exceeds the vault attachment size limit",
+                        ));
+                        continue;
+                    }
+                    if handshake_root.is_none() {
+                        handshake_root = Some(resolve_handshake_root()?);
+                    }
+                    let root = handshake_root.as_deref().expect("handshake root resolved");
+                    let blob =
+                        loom_asset_blob_path(root, workspace_id, &asset.kind, &asset.content_hash);
+                    let bytes = match std::fs::read(&blob) {
+                        Ok(bytes) => bytes,
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                            manifest
+                                .skipped
+                                .push(skipped(&block, "asset blob is missing"));
+                            continue;
+                        }
+                        Err(source) => return Err(io_error(&blob, source)),
+                    };
+                    let name = asset
+                        .original_filename
+                        .clone()
+                        .or_else(|| block.original_filename.clone())
+                        .unwrap_or_else(|| asset.content_hash.chars().take(16).collect());
+                    let name = vault_file_stem(&name);
+                    let (stem, extension) = match name.rsplit_once('.') {
+                        Some((stem, extension)) if !stem.is_empty() => (stem, extension),
+                        _ => (name.as_str(), ""),
+                    };
+                    let path = paths.allocate(VAULT_ATTACHMENTS_DIR, stem, extension);
+                    targets.insert(
+                        block.block_id.clone(),
+                        LinkTarget {
+                            link_path: path.clone(),
+                            display: None,
+                        },
+                    );
+                    attachment_paths.push(path.clone());
+                    attachment_blocks.push(block.block_id.clone());
+                    add_file(
+                        &mut files,
+                        &mut manifest,
+                        path,
+                        &block.block_id,
+                        LoomVaultEntryKind::Attachment,
+                        bytes,
+                    );
+                    manifest.counts.attachments += 1;
+                }
+                LoomBlockContentType::TagHub => manifest.skipped.push(skipped(
+                    &block,
+                    "tag hubs are exported as front-matter tags",
+                )),
+                LoomBlockContentType::Canvas | LoomBlockContentType::ViewDef => {
+                    manifest.skipped.push(skipped(&block, "no markdown form"))
+                }
+            }
+        }
+
+        for plan in &plans {
+            let link_path = plan
+                .relative_path
+                .strip_suffix(".md")
+                .unwrap_or(&plan.relative_path)
+                .to_string();
+            let stem = link_path.rsplit('/').next().unwrap_or(&link_path);
+            let display = plan
+                .title
+                .as_deref()
+                .map(|t| t.replace(['|', '[', ']'], " ").trim().to_string())
+                .filter(|t| !t.is_empty() && t != stem);
+            targets.insert(
+                plan.block.block_id.clone(),
+                LinkTarget { link_path, display },
+            );
+        }
+
+        // Parse every note as an import would, to learn which links its body
+        // already makes; only the rest go into `links`.
+        let parsed = plans
+            .iter()
+            .map(|plan| {
+                let front = VaultNoteFrontMatter {
+                    hsk_block_id: plan.block.block_id.clone(),
+                    title: plan.title.clone(),
+                    aliases: plan.aliases.clone(),
+                    ..VaultNoteFrontMatter::default()
+                };
+                render_vault_note(&front, &plan.body)
+                    .map(|text| parse_vault_note(VaultKind::Obsidian, &plan.relative_path, &text))
+            })
+            .collect::<LoomVaultResult<Vec<ParsedVaultNote>>>()?;
+        let index = VaultIndex::build(&parsed, &attachment_paths);
+        let tag_names: HashMap<String, String> = tag_hub_paths(db, workspace_id)
+            .await?
+            .into_iter()
+            .map(|(hub, path)| (hub.block_id, path))
+            .collect();
+
+        for (i, plan) in plans.iter().enumerate() {
+            let note = &parsed[i];
+            let mut covered: HashSet<&str> = HashSet::new();
+            for link in &note.links {
+                let target = match index.resolve(i, link) {
+                    VaultResolution::Resolved(target)
+                    | VaultResolution::Ambiguous { chosen: target, .. } => target,
+                    VaultResolution::Unresolved(_) => continue,
+                };
+                covered.insert(match target {
+                    VaultTarget::Note(n) => plans[n].block.block_id.as_str(),
+                    VaultTarget::Attachment(a) => attachment_blocks[a].as_str(),
+                });
+            }
+
+            let mut links = BTreeSet::new();
+            let mut tags = BTreeSet::new();
+            for edge in db
+                .get_outgoing_edges(workspace_id, &plan.block.block_id)
+                .await?
+            {
+                let target = edge.target_block_id.as_str();
+                match edge.edge_type {
+                    LoomEdgeType::Mention => {
+                        if target == plan.block.block_id || covered.contains(target) {
+                            continue;
+                        }
+                        match targets.get(target) {
+                            Some(LinkTarget {
+                                link_path,
+                                display: Some(display),
+                            }) => {
+                                links.insert(format!("[[{link_path}|{display}]]"));
+                            }
+                            Some(LinkTarget { link_path, .. }) => {
+                                links.insert(format!("[[{link_path}]]"));
+                            }
+                            None => manifest.counts.links_outside_export += 1,
+                        }
+                    }
+                    LoomEdgeType::Tag => {
+                        if let Some(name) = tag_names.get(target) {
+                            if !note.tags.iter().any(|t| t.eq_ignore_ascii_case(name)) {
+                                tags.insert(name.clone());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            manifest.counts.links_written += links.len() as u64;
+            manifest.counts.tags_written += tags.len() as u64;
+
+            let front = VaultNoteFrontMatter {
+                hsk_block_id: plan.block.block_id.clone(),
+                title: plan.title.clone(),
+                aliases: plan.aliases.clone(),
+                tags: tags.into_iter().collect(),
+                links: links.into_iter().collect(),
+                properties: plan.properties.clone(),
+            };
+            let text = render_vault_note(&front, &plan.body)?;
+            add_file(
+                &mut files,
+                &mut manifest,
+                plan.relative_path.clone(),
+                &plan.block.block_id,
+                plan.kind,
+                text.into_bytes(),
+            );
+            match plan.kind {
+                LoomVaultEntryKind::Journal => manifest.counts.journals += 1,
+                _ => manifest.counts.notes += 1,
+            }
+        }
+
+        manifest
+            .files
+            .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        Ok(ExportedLoomVault { manifest, files })
+    }
+
+    /// Path, title, kept properties and body of one note or journal block.
+    async fn plan_note(
+        &self,
+        workspace_id: &str,
+        block: LoomBlock,
+        folder_dir: Option<String>,
+        paths: &mut VaultPathAllocator,
+        manifest: &mut LoomVaultExportManifest,
+    ) -> LoomVaultResult<NotePlan> {
+        let entry =
+            list_loom_vault_entries_for_block(self.db.pool(), workspace_id, &block.block_id)
+                .await?
+                .into_iter()
+                .find(|e| e.entry_kind != LoomVaultEntryKind::Attachment);
+        let title = block
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+
+        // Journals are named by date so an import recognises them as daily
+        // notes again; their `Daily Note ...` title stays on the block.
+        let journal_date = block
+            .journal_date
+            .clone()
+            .filter(|_| block.content_type == LoomBlockContentType::Journal);
+        let (kind, relative_path, title) = match journal_date {
+            Some(date) => {
+                let dir = folder_dir.unwrap_or_else(|| VAULT_JOURNALS_DIR.to_string());
+                let path = paths.allocate(&dir, &vault_file_stem(&date), "md");
+                (LoomVaultEntryKind::Journal, path, None)
+            }
+            None => {
+                let name = title.as_deref().unwrap_or("Untitled");
+                let dir = folder_dir.unwrap_or_default();
+                let path = paths.allocate(&dir, &vault_file_stem(name), "md");
+                let stem = path
+                    .rsplit('/')
+                    .next()
+                    .and_then(|f| f.strip_suffix(".md"))
+                    .unwrap_or_default();
+                // The file name carries the title unless it had to change.
+                let title = title.filter(|t| t != stem);
+                (LoomVaultEntryKind::Note, path, title)
+            }
+        };
+
+        let mut body = None;
+        if let Some(id) = entry.as_ref().and_then(|e| e.rich_document_id.as_deref()) {
+            if let Some(document) = self.db.get_knowledge_rich_document(id).await? {
+                match BlockTree::from_document_json(
+                    &document.rich_document_id,
+                    &document.schema_version,
+                    &document.content_json,
+                ) {
+                    Ok(tree) => body = Some(document_body(&document.title, &tree)),
+                    Err(err) => manifest.warnings.push(VaultExportWarning {
+                        block_id: block.block_id.clone(),
+                        detail: format!("rich document {id} not renderable: {err}"),
+                    }),
+                }
+            }
+        }
+        if body.is_none() {
+            if let Some(document_id) = &block.document_id {
+                match self.db.get_blocks(document_id).await {
+                    Ok(parts) => {
+                        let text: Vec<&str> = parts
+                            .iter()
+                            .map(|part| part.raw_content.trim())
+                            .filter(|text| !text.is_empty())
+                            .collect();
+                        body = Some(text.join("\n\n"));
+                    }
+                    Err(StorageError::NotFound(_)) => manifest.warnings.push(VaultExportWarning {
+                        block_id: block.block_id.clone(),
+                        detail: format!("document {document_id} not found"),
+                    }),
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        }
+
+        let (aliases, mut properties) = match entry {
+            Some(entry) => (
+                entry.aliases,
+                match entry.properties {
+                    Value::Object(map) => map,
+                    _ => Map::new(),
+                },
+            ),
+            None => (Vec::new(), Map::new()),
+        };
+        properties.retain(|key, _| !RESERVED_PROPERTY_KEYS.contains(&key.as_str()));
+
+        Ok(NotePlan {
+            block,
+            kind,
+            relative_path,
+            title,
+            aliases,
+            properties,
+            body: body.unwrap_or_default(),
+        })
+    }
+
+    async fn folder_blocks(
+        &self,
+        workspace_id: &str,
+        folder_id: &str,
+    ) -> LoomVaultResult<Vec<LoomBlock>> {
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .db
+                .list_loom_folder_blocks(workspace_id, folder_id, PAGE, offset)
+                .await?;
+            let fetched = page.len();
+            blocks.extend(page);
+            if fetched < PAGE as usize {
+                return Ok(blocks);
+            }
+            offset += PAGE;
+        }
+    }
+
+    async fn all_blocks(&self, workspace_id: &str) -> LoomVaultResult<Vec<LoomBlock>> {
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        loop {
+            let view = self
+                .db
+                .query_loom_view(
+                    workspace_id,
+                    LoomViewType::All,
+                    LoomViewFilters::default(),
+                    PAGE,
+                    offset,
+                )
+                .await?;
+            let LoomViewResponse::All { blocks: page } = view else {
+                return Ok(blocks);
+            };
+            let fetched = page.len();
+            blocks.extend(page);
+            if fetched < PAGE as usize {
+                return Ok(blocks);
+            }
+            offset += PAGE;
+        }
+    }
+}
+
+fn skipped(block: &LoomBlock, reason: &str) -> SkippedLoomBlock {
+    SkippedLoomBlock {
+        block_id: block.block_id.clone(),
+        content_type: block.content_type.clone(),
+        reason: reason.to_string(),
+    }
+}
+
+fn add_file(
+    files: &mut BTreeMap<String, Vec<u8>>,
+    manifest: &mut LoomVaultExportManifest,
+    relative_path: String,
+    block_id: &str,
+    kind: LoomVaultEntryKind,
+    bytes: Vec<u8>,
+) {
+    manifest.files.push(ExportedVaultFile {
+        relative_path: relative_path.clone(),
+        block_id: block_id.to_string(),
+        kind,
+        size_bytes: bytes.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(&bytes)),
+    });
+    files.insert(relative_path, bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loom_vault::VaultLinkKind;
+
+    #[test]
+    fn exported_front_matter_parses_back_to_the_same_note() {
+        let mut properties = Map::new();
+        properties.insert("status".to_string(), Value::from("draft"));
+        properties.insert("rating".to_string(), Value::from(3));
+        let front = VaultNoteFrontMatter {
+            hsk_block_id: "blk-1".to_string(),
+            title: Some("Q3: plans / ideas".to_string()),
+            aliases: vec!["Q3".to_string()],
+            tags: vec!["project/alpha".to_string(), "graph db".to_string()],
+            links: vec![
+                "[[notes/Alpha|Alpha note]]".to_string(),
+                "[[attachments/diagram.png]]".to_string(),
+            ],
+            properties,
+        };
+        let text = render_vault_note(&front, "See [[Beta]].\n\n#inline\n").expect("render");
+        assert!(text.starts_with(&format!("---\n{VAULT_BLOCK_ID_KEY}: blk-1\n")));
+        assert!(text.ends_with("---\n\nSee [[Beta]].\n\n#inline\n"));
+
+        let path = format!("notes/{}.md", vault_file_stem("Q3: plans / ideas"));
+        let parsed = parse_vault_note(VaultKind::Obsidian, &path, &text);
+        assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+        assert_eq!(parsed.hsk_block_id.as_deref(), Some("blk-1"));
+        assert_eq!(parsed.title, "Q3: plans / ideas");
+        assert_eq!(parsed.aliases, vec!["Q3"]);
+        assert_eq!(parsed.tags, vec!["project/alpha", "graph db", "inline"]);
+        assert_eq!(parsed.properties["status"], "draft");
+        assert_eq!(parsed.properties["rating"], 3);
+        let links: Vec<(VaultLinkKind, &str, Option<&str>)> = parsed
+            .links
+            .iter()
+            .map(|l| (l.kind, l.target.as_str(), l.display.as_deref()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (VaultLinkKind::Wikilink, "notes/Alpha", Some("Alpha note")),
+                (VaultLinkKind::Wikilink, "attachments/diagram.png", None),
+                (VaultLinkKind::Wikilink, "Beta", None),
+            ]
+        );
+
+        // The front-matter links resolve by path like body links do.
+        let alpha = parse_vault_note(VaultKind::Obsidian, "notes/Alpha.md", "");
+        let notes = vec![parsed, alpha];
+        let index = VaultIndex::build(&notes, &["attachments/diagram.png".to_string()]);
+        assert_eq!(
+            index.resolve(0, &notes[0].links[0]),
+            VaultResolution::Resolved(VaultTarget::Note(1))
+        );
+        assert_eq!(
+            index.resolve(0, &notes[0].links[1]),
+            VaultResolution::Resolved(VaultTarget::Attachment(0))
+        );
+    }
+
+    #[test]
+    fn file_stems_are_wikilink_safe_and_paths_unique() {
+        assert_eq!(vault_file_stem("Q3: plans / ideas"), "Q3 plans ideas");
+        assert_eq!(vault_file_stem("[[x]] | y#z^w"), "x y z w");
+        assert_eq!(vault_file_stem(".hidden."), "hidden");
+        assert_eq!(vault_file_stem(" \t "), "Untitled");
+        assert_eq!(vault_file_stem("diagram.png"), "diagram.png");
+
+        let mut paths = VaultPathAllocator::default();
+        assert_eq!(paths.allocate("", "Note", "md"), "Note.md");
+        assert_eq!(paths.allocate("", "note", "md"), "note (2).md");
+        assert_eq!(paths.allocate("", "Note", "md"), "Note (3).md");
+        assert_eq!(paths.allocate("", "Note", ""), "Note");
+        assert_eq!(paths.allocate("a/b", "Note", "md"), "a/b/Note.md");
+    }
+
+    #[test]
+    fn export_dir_must_stay_below_an_allowed_root() {
+        let workspace = tempfile::tempdir().expect("workspace");
+        let elsewhere = tempfile::tempdir().expect("elsewhere");
+        let roots = vec![workspace.path().to_path_buf()];
+        let root = workspace.path().canonicalize().unwrap();
+
+        assert_eq!(
+            resolve_export_dir(Path::new("exports/vault"), &roots).expect("relative"),
+            root.join("exports/vault")
+        );
+        assert_eq!(
+            resolve_export_dir(&workspace.path().join("vault"), &roots).expect("absolute"),
+            root.join("vault")
+        );
+        for bad in [
+            PathBuf::from("../escape"),
+            PathBuf::from("exports/../../escape"),
+            workspace.path().to_path_buf(),
+            elsewhere.path().join("vault"),
+        ] {
+            assert!(
+                matches!(
+                    resolve_export_dir(&bad, &roots),
+                    Err(LoomVaultError::Validation(_))
+                ),
+                "{} must be refused",
+                bad.display()
+            );
+        }
+
+        let both = vec![roots[0].clone(), elsewhere.path().to_path_buf()];
+        assert!(resolve_export_dir(&elsewhere.path().join("vault"), &both).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn export_dir_symlink_out_of_the_root_is_refused() {
+        let workspace = tempfile::tempdir().expect("workspace");
+        let elsewhere = tempfile::tempdir().expect("elsewhere");
+        std::os::unix::fs::symlink(elsewhere.path(), workspace.path().join("link")).unwrap();
+        let roots = vec![workspace.path().to_path_buf()];
+        assert!(resolve_export_dir(Path::new("link/vault"), &roots).is_err());
+    }
+}
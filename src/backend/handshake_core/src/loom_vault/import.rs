@@ -0,0 +1,1165 @@
+//! Vault import orchestration: walk, parse, then write Loom authority.
+//!
+//! Order matters: folders first, then attachments (so notes can link to their
+//! blocks), then notes, tag hubs, and finally edges, once every link target
+//! has a block. Every write goes through the receipted `Database` /
+//! `KnowledgeStore` paths the Loom API uses; the importer only adds its own
+//! `loom_vault_entries` bookkeeping and the run report.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::knowledge_document::block_tree::DOCUMENT_SCHEMA_VERSION;
+use crate::knowledge_document::import::{import_snippet, ImportFormat};
+use crate::loom_fs::{loom_asset_blob_path, resolve_handshake_root};
+use crate::storage::artifacts;
+use crate::storage::knowledge::{KnowledgeStore, NewKnowledgeRichDocument};
+use crate::storage::loom_vault::{
+    insert_loom_vault_import, list_loom_vault_entries, list_loom_vault_entries_for_block,
+    new_vault_import_id, set_loom_vault_entry_edges, upsert_loom_vault_entry, LoomVaultEntry,
+    LoomVaultEntryKind, UpsertLoomVaultEntry,
+};
+use crate::storage::postgres::PostgresDatabase;
+use crate::storage::{
+    Database, LoomBlock, LoomBlockContentType, LoomBlockDerived, LoomBlockUpdate,
+    LoomEdgeCreatedBy, LoomEdgeType, LoomFolderSortMode, NewAsset, NewLoomBlock, NewLoomEdge,
+    NewLoomFolder, PreviewStatus, StorageError, WriteContext,
+};
+
+use super::{
+    attachment_mime, io_error, parent_dir, parse_vault_note, scan_vault, tag_hub_path,
+    LoomVaultError, LoomVaultResult, ParsedVaultNote, SkippedVaultFile, VaultFile, VaultIndex,
+    VaultKind, VaultLinkKind, VaultResolution, VaultTarget,
+};
+
+/// Env var naming an extra server directory vaults may be imported from,
+/// next to the workspace root.
+pub const VAULT_IMPORT_ROOT_ENV: &str = "HANDSHAKE_VAULT_IMPORT_ROOT";
+
+/// What to import.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoomVaultImportRequest {
+    /// The vault's root directory. Relative to the first import root; must
+    /// sit below one of them.
+    pub vault_path: PathBuf,
+    /// Detected from the directory layout when absent.
+    #[serde(default)]
+    pub vault_kind: Option<VaultKind>,
+    /// Identity of the vault across re-imports and the name of its root
+    /// folder; defaults to the directory name.
+    #[serde(default)]
+    pub vault_name: Option<String>,
+}
+
+/// Per-run tallies.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoomVaultImportCounts {
+    pub notes_created: u64,
+    pub notes_updated: u64,
+    pub notes_unchanged: u64,
+    /// Notes that landed on a journal block (also counted above).
+    pub journals: u64,
+    pub attachments_created: u64,
+    pub attachments_reused: u64,
+    pub tag_hubs_created: u64,
+    pub folders_created: u64,
+    pub edges_created: u64,
+    pub edges_removed: u64,
+    pub links_resolved: u64,
+}
+
+/// A link that matched nothing in the vault.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedVaultLink {
+    pub source_path: String,
+    pub line: usize,
+    pub kind: VaultLinkKind,
+    pub raw: String,
+    pub target: String,
+    pub reason: String,
+}
+
+/// A link whose name matched several notes; `chosen_path` got the edge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmbiguousVaultLink {
+    pub source_path: String,
+    pub line: usize,
+    pub raw: String,
+    pub chosen_path: String,
+    pub candidates: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultImportWarning {
+    pub relative_path: String,
+    pub detail: String,
+}
+
+/// An attachment's block, so the caller can queue preview generation for the
+/// new ones.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedVaultAttachment {
+    pub relative_path: String,
+    pub block_id: String,
+    pub asset_id: Option<String>,
+    pub content_hash: String,
+    pub created: bool,
+}
+
+/// The import report, also stored in `loom_vault_imports`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoomVaultImportReport {
+    pub import_id: String,
+    pub workspace_id: String,
+    pub vault_name: String,
+    pub vault_kind: VaultKind,
+    pub root_folder_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub counts: LoomVaultImportCounts,
+    pub unresolved_links: Vec<UnresolvedVaultLink>,
+    pub ambiguous_links: Vec<AmbiguousVaultLink>,
+    pub skipped: Vec<SkippedVaultFile>,
+    /// Files imported by an earlier run that are gone from the vault. Their
+    /// blocks are left in Loom (the vault is never authority).
+    pub missing_paths: Vec<String>,
+    pub warnings: Vec<VaultImportWarning>,
+    pub attachments: Vec<ImportedVaultAttachment>,
+}
+
+/// Every tag hub of the workspace with its nested tag name
+/// ([`tag_hub_path`]), in `list_tag_hubs` order.
+pub(crate) async fn tag_hub_paths(
+    db: &PostgresDatabase,
+    workspace_id: &str,
+) -> LoomVaultResult<Vec<(LoomBlock, String)>> {
+    let mut hubs = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = db.list_tag_hubs(workspace_id, 500, offset).await?;
+        let fetched = page.len();
+        hubs.extend(page);
+        if fetched < 500 {
+            break;
+        }
+        offset += 500;
+    }
+    let mut titles = HashMap::new();
+    let mut parents = HashMap::new();
+    for hub in &hubs {
+        if let Some(title) = &hub.title {
+            titles.insert(hub.block_id.clone(), title.clone());
+        }
+        let parent = db
+            .get_outgoing_edges(workspace_id, &hub.block_id)
+            .await?
+            .into_iter()
+            .find(|e| e.edge_type == LoomEdgeType::SubTag);
+        if let Some(edge) = parent {
+            parents.insert(hub.block_id.clone(), edge.target_block_id);
+        }
+    }
+    Ok(hubs
+        .into_iter()
+        .filter_map(|hub| {
+            let path = tag_hub_path(&hub.block_id, &titles, &parents)?;
+            Some((hub, path))
+        })
+        .collect())
+}
+
+/// Resolve a requested vault directory against the roots an import may read
+/// from. A relative path is taken from the first root; `..` is refused, and
+/// the path is canonicalized so a symlink cannot lead outside. The result must
+/// be an existing directory strictly below one of the roots.
+pub fn resolve_import_dir(requested: &Path, roots: &[PathBuf]) -> LoomVaultResult<PathBuf> {
+    let outside = || {
+        LoomVaultError::Validation(format!(
+            "vault path {} is outside the allowed import roots",
+            requested.display()
+        ))
+    };
+    if requested
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        return Err(outside());
+    }
+    let Some(first) = roots.first() else {
+        return Err(LoomVaultError::Validation(
+            "no import root is configured".to_string(),
+        ));
+    };
+    let candidate = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        first.join(requested)
+    };
+    let resolved = candidate.canonicalize().map_err(|_| {
+        LoomVaultError::Validation(format!(
+            "vault path {} is not a directory",
+            requested.display()
+        ))
+    })?;
+
+    let allowed = roots.iter().filter_map(|root| root.canonicalize().ok());
+    for root in allowed {
+        if resolved != root && resolved.starts_with(&root) {
+            if !resolved.is_dir() {
+                return Err(LoomVaultError::Validation(format!(
+                    "vault path {} is not a directory",
+                    requested.display()
+                )));
+            }
+            return Ok(resolved);
+        }
+    }
+    Err(outside())
+}
+
+/// Imports Obsidian / Logseq vaults into a workspace's Loom.
+pub struct LoomVaultImporter {
+    db: Arc<PostgresDatabase>,
+    /// Directories a vault may be imported from; see [`resolve_import_dir`].
+    import_roots: Vec<PathBuf>,
+}
+
+/// A note's Loom identity after the note pass.
+struct NoteState {
+    block_id: String,
+    managed_edge_ids: Vec<String>,
+}
+
+impl LoomVaultImporter {
+    pub fn new(db: Arc<PostgresDatabase>, import_roots: Vec<PathBuf>) -> Self {
+        Self { db, import_roots }
+    }
+
+    pub async fn import(
+        &self,
+        ctx: &WriteContext,
+        workspace_id: &str,
+        request: &LoomVaultImportRequest,
+    ) -> LoomVaultResult<LoomVaultImportReport> {
+        let requested = request.vault_path.clone();
+        let roots = self.import_roots.clone();
+        let requested_kind = request.vault_kind;
+        let (root, vault_kind) = tokio::task::spawn_blocking(move || {
+            let root = resolve_import_dir(&requested, &roots)?;
+            let kind = requested_kind.unwrap_or_else(|| VaultKind::detect(&root));
+            Ok::<_, LoomVaultError>((root, kind))
+        })
+        .await??;
+        let vault_name = request
+            .vault_name
+            .clone()
+            .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| LoomVaultError::Validation("vault_name is required".to_string()))?;
+        if vault_name.contains('/') || vault_name.contains('\\') {
+            return Err(LoomVaultError::Validation(
+                "vault_name must not contain path separators".to_string(),
+            ));
+        }
+
+        // The walk and the note reads are blocking file IO.
+        let (scan, texts) = tokio::task::spawn_blocking(move || {
+            let scan = scan_vault(&root, vault_kind)?;
+            let mut texts = Vec::with_capacity(scan.notes.len());
+            for file in &scan.notes {
+                let bytes = std::fs::read(&file.absolute_path)
+                    .map_err(|source| io_error(&file.absolute_path, source))?;
+                texts.push(String::from_utf8(bytes).ok());
+            }
+            Ok::<_, LoomVaultError>((scan, texts))
+        })
+        .await??;
+        let mut report = LoomVaultImportReport {
+            import_id: new_vault_import_id(),
+            workspace_id: workspace_id.to_string(),
+            vault_name: vault_name.clone(),
+            vault_kind,
+            root_folder_id: None,
+            started_at: Utc::now(),
+            completed_at: Utc::now(),
+            counts: LoomVaultImportCounts::default(),
+            unresolved_links: Vec::new(),
+            ambiguous_links: Vec::new(),
+            skipped: scan.skipped.clone(),
+            missing_paths: Vec::new(),
+            warnings: Vec::new(),
+            attachments: Vec::new(),
+        };
+
+        let mut notes = Vec::with_capacity(scan.notes.len());
+        for (file, text) in scan.notes.iter().zip(texts) {
+            match text {
+                Some(text) => notes.push(parse_vault_note(vault_kind, &file.relative_path, &text)),
+                None => report.skipped.push(SkippedVaultFile {
+                    relative_path: file.relative_path.clone(),
+                    reason: "note is not UTF-8 text".to_string(),
+                }),
+            }
+        }
+
+        let pool = self.db.pool();
+        let entries: HashMap<String, LoomVaultEntry> =
+            list_loom_vault_entries(pool, workspace_id, &vault_name)
+                .await?
+                .into_iter()
+                .map(|entry| (entry.relative_path.clone(), entry))
+                .collect();
+        let mut run = ImportRun {
+            db: self.db.as_ref(),
+            ctx,
+            workspace_id,
+            report: &mut report,
+            entries: &entries,
+            claimed_blocks: HashSet::new(),
+        };
+
+        let folders = run.ensure_folders(&vault_name, &scan.directories()).await?;
+        let mut attachment_blocks = Vec::with_capacity(scan.attachments.len());
+        for file in &scan.attachments {
+            let block_id = run
+                .import_attachment(file, &folders[parent_dir(&file.relative_path)])
+                .await?;
+            attachment_blocks.push(block_id);
+        }
+        let mut note_states = Vec::with_capacity(notes.len());
+        for note in &notes {
+            let state = run
+                .import_note(note, &folders[parent_dir(&note.relative_path)])
+                .await?;
+            note_states.push(state);
+        }
+        let tag_hubs = run.ensure_tag_hubs(&notes).await?;
+
+        let attachment_paths: Vec<String> = scan
+            .attachments
+            .iter()
+            .map(|f| f.relative_path.clone())
+            .collect();
+        let index = VaultIndex::build(&notes, &attachment_paths);
+        for (i, note) in notes.iter().enumerate() {
+            let mut desired: BTreeSet<(String, &'static str)> = BTreeSet::new();
+            for link in &note.links {
+                let target = match index.resolve(i, link) {
+                    VaultResolution::Resolved(target) => target,
+                    VaultResolution::Ambiguous { chosen, candidates } => {
+                        run.report.ambiguous_links.push(AmbiguousVaultLink {
+                            source_path: note.relative_path.clone(),
+                            line: link.line,
+                            raw: link.raw.clone(),
+                            chosen_path: match chosen {
+                                VaultTarget::Note(n) => notes[n].relative_path.clone(),
+                                VaultTarget::Attachment(a) => attachment_paths[a].clone(),
+                            },
+                            candidates,
+                        });
+                        chosen
+                    }
+                    VaultResolution::Unresolved(reason) => {
+                        run.report.unresolved_links.push(UnresolvedVaultLink {
+                            source_path: note.relative_path.clone(),
+                            line: link.line,
+                            kind: link.kind,
+                            raw: link.raw.clone(),
+                            target: link.target.clone(),
+                            reason,
+                        });
+                        continue;
+                    }
+                };
+                run.report.counts.links_resolved += 1;
+                let target_block = match target {
+                    VaultTarget::Note(n) => &note_states[n].block_id,
+                    VaultTarget::Attachment(a) => &attachment_blocks[a],
+                };
+                if *target_block != note_states[i].block_id {
+                    desired.insert((target_block.clone(), LoomEdgeType::Mention.as_str()));
+                }
+            }
+            for tag in &note.tags {
+                if let Some(hub) = tag_hubs.get(&tag.to_lowercase()) {
+                    desired.insert((hub.clone(), LoomEdgeType::Tag.as_str()));
+                }
+            }
+            run.sync_edges(&vault_name, note, &note_states[i], &desired)
+                .await?;
+        }
+
+        let seen: HashSet<&str> = scan
+            .notes
+            .iter()
+            .chain(&scan.attachments)
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        run.report.missing_paths = entries
+            .keys()
+            .filter(|path| !seen.contains(path.as_str()))
+            .cloned()
+            .collect();
+        run.report.missing_paths.sort();
+
+        self.db.recompute_all_metrics(workspace_id).await?;
+
+        report.completed_at = Utc::now();
+        let report_json = serde_json::to_value(&report)
+            .map_err(|err| LoomVaultError::Validation(format!("report not serializable: {err}")))?;
+        insert_loom_vault_import(
+            pool,
+            &report.import_id,
+            workspace_id,
+            &vault_name,
+            vault_kind.as_str(),
+            report.unresolved_links.len() as i64,
+            &report_json,
+            report.started_at,
+        )
+        .await?;
+        Ok(report)
+    }
+}
+
+/// State of one import run.
+struct ImportRun<'a> {
+    db: &'a PostgresDatabase,
+    ctx: &'a WriteContext,
+    workspace_id: &'a str,
+    report: &'a mut LoomVaultImportReport,
+    entries: &'a HashMap<String, LoomVaultEntry>,
+    /// Blocks already bound to a file in this run; a second claim imports a
+    /// fresh block instead of letting two files overwrite one.
+    claimed_blocks: HashSet<String>,
+}
+
+impl ImportRun<'_> {
+    fn warn(&mut self, relative_path: &str, detail: impl Into<String>) {
+        self.report.warnings.push(VaultImportWarning {
+            relative_path: relative_path.to_string(),
+            detail: detail.into(),
+        });
+    }
+
+    async fn existing_block(&self, block_id: &str) -> LoomVaultResult<Option<LoomBlock>> {
+        match self.db.get_loom_block(self.workspace_id, block_id).await {
+            Ok(block) => Ok(Some(block)),
+            Err(StorageError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Root folder named after the vault, then one folder per directory.
+    /// Returns directory path (`""` for the root) -> folder id.
+    async fn ensure_folders(
+        &mut self,
+        vault_name: &str,
+        directories: &[String],
+    ) -> LoomVaultResult<HashMap<String, String>> {
+        let mut existing: HashMap<(Option<String>, String), String> = self
+            .db
+            .list_loom_folders(self.workspace_id)
+            .await?
+            .into_iter()
+            .map(|f| ((f.parent_folder_id, f.name), f.folder_id))
+            .collect();
+        let mut folders = HashMap::new();
+        let root = self.ensure_folder(&mut existing, None, vault_name).await?;
+        self.report.root_folder_id = Some(root.clone());
+        folders.insert(String::new(), root);
+        for dir in directories {
+            let parent = folders[parent_dir(dir)].clone();
+            let name = dir.rsplit('/').next().unwrap_or(dir);
+            let id = self
+                .ensure_folder(&mut existing, Some(parent), name)
+                .await?;
+            folders.insert(dir.clone(), id);
+        }
+        Ok(folders)
+    }
+
+    async fn ensure_folder(
+        &mut self,
+        existing: &mut HashMap<(Option<String>, String), String>,
+        parent_folder_id: Option<String>,
+        name: &str,
+    ) -> LoomVaultResult<String> {
+        let key = (parent_folder_id.clone(), name.to_string());
+        if let Some(id) = existing.get(&key) {
+            return Ok(id.clone());
+        }
+        let folder = self
+            .db
+            .create_loom_folder(
+                self.workspace_id,
+                NewLoomFolder {
+                    folder_id: None,
+                    workspace_id: self.workspace_id.to_string(),
+                    parent_folder_id,
+                    name: name.to_string(),
+                    color: None,
+                    sort_mode: LoomFolderSortMode::NameAsc,
+                    sort_order: None,
+                    project_ref: None,
+                },
+            )
+            .await?;
+        self.report.counts.folders_created += 1;
+        existing.insert(key, folder.folder_id.clone());
+        Ok(folder.folder_id)
+    }
+
+    /// Content-addressed like a Loom asset upload: identical bytes anywhere in
+    /// the workspace reuse the same asset and `file` block.
+    async fn import_attachment(
+        &mut self,
+        file: &VaultFile,
+        folder_id: &str,
+    ) -> LoomVaultResult<String> {
+        let path = file.absolute_path.clone();
+        let (bytes, content_hash) = tokio::task::spawn_blocking(move || {
+            let bytes = std::fs::read(&path).map_err(|source| io_error(&path, source))?;
+            let content_hash = format!("{:x}", Sha256::digest(&bytes));
+            Ok::<_, LoomVaultError>((bytes, content_hash))
+        })
+        .await??;
+
+        let mut block = None;
+        if let Some(entry) = self.entries.get(&file.relative_path) {
+            if entry.content_sha256 == content_hash {
+                block = self.existing_block(&entry.block_id).await?;
+            }
+        }
+        if block.is_none() {
+            block = self
+                .db
+                .find_loom_block_by_content_hash(self.workspace_id, &content_hash)
+                .await?;
+        }
+        let created = block.is_none();
+        let block = match block {
+            Some(block) => block,
+            None => {
+                self.create_attachment_block(file, &bytes, &content_hash)
+                    .await?
+            }
+        };
+        if created {
+            self.report.counts.attachments_created += 1;
+        } else {
+            self.report.counts.attachments_reused += 1;
+        }
+
+        upsert_loom_vault_entry(
+            self.db.pool(),
+            &UpsertLoomVaultEntry {
+                workspace_id: self.workspace_id.to_string(),
+                vault_name: self.report.vault_name.clone(),
+                relative_path: file.relative_path.clone(),
+                entry_kind: LoomVaultEntryKind::Attachment,
+                block_id: block.block_id.clone(),
+                rich_document_id: None,
+                content_sha256: content_hash.clone(),
+                properties: Value::Object(Map::new()),
+                aliases: Vec::new(),
+                managed_edge_ids: Vec::new(),
+                last_import_id: self.report.import_id.clone(),
+            },
+        )
+        .await?;
+        self.db
+            .add_block_to_loom_folder(self.workspace_id, folder_id, &block.block_id, None)
+            .await?;
+        self.report.attachments.push(ImportedVaultAttachment {
+            relative_path: file.relative_path.clone(),
+            block_id: block.block_id.clone(),
+            asset_id: block.asset_id.clone(),
+            content_hash,
+            created,
+        });
+        Ok(block.block_id)
+    }
+
+    async fn create_attachment_block(
+        &mut self,
+        file: &VaultFile,
+        bytes: &[u8],
+        content_hash: &str,
+    ) -> LoomVaultResult<LoomBlock> {
+        let kind = "original";
+        let original_filename = file.relative_path.rsplit('/').next().map(str::to_string);
+        let asset = match self
+            .db
+            .find_asset_by_content_hash(self.workspace_id, content_hash)
+            .await?
+        {
+            Some(asset) => asset,
+            None => {
+                self.db
+                    .create_asset(
+                        self.ctx,
+                        NewAsset {
+                            workspace_id: self.workspace_id.to_string(),
+                            kind: kind.to_string(),
+                            mime: attachment_mime(&file.relative_path).to_string(),
+                            original_filename: original_filename.clone(),
+                            content_hash: content_hash.to_string(),
+                            size_bytes: bytes.len() as i64,
+                            width: None,
+                            height: None,
+                            classification: "low".to_string(),
+                            exportable: true,
+                            is_proxy_of: None,
+                            proxy_asset_id: None,
+                        },
+                    )
+                    .await?
+            }
+        };
+
+        let handshake_root = resolve_handshake_root()?;
+        let blob_path =
+            loom_asset_blob_path(&handshake_root, self.workspace_id, kind, content_hash);
+        let blob = bytes.to_vec();
+        tokio::task::spawn_blocking(move || {
+            if !blob_path.exists() {
+                if let Some(parent) = blob_path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|source| io_error(parent, source))?;
+                }
+                artifacts::write_file_atomic(&handshake_root, &blob_path, &blob, false)?;
+            }
+            Ok::<_, LoomVaultError>(())
+        })
+        .await??;
+
+        let block = self
+            .db
+            .create_loom_block(
+                self.ctx,
+                NewLoomBlock {
+                    block_id: None,
+                    workspace_id: self.workspace_id.to_string(),
+                    content_type: LoomBlockContentType::File,
+                    document_id: None,
+                    asset_id: Some(asset.asset_id),
+                    title: None,
+                    original_filename,
+                    content_hash: Some(content_hash.to_string()),
+                    pinned: false,
+                    journal_date: None,
+                    imported_at: Some(Utc::now()),
+                    derived: LoomBlockDerived {
+                        preview_status: PreviewStatus::Pending,
+                        ..LoomBlockDerived::default()
+                    },
+                },
+            )
+            .await?;
+        self.db
+            .bridge_loom_block_to_knowledge(self.ctx, self.workspace_id, &block.block_id)
+            .await?;
+        Ok(block)
+    }
+
+    /// Bind the note to a block (its `hsk_block_id`, its previous entry, or a
+    /// new note / journal block) and bring its RichDocument up to date.
+    async fn import_note(
+        &mut self,
+        note: &ParsedVaultNote,
+        folder_id: &str,
+    ) -> LoomVaultResult<NoteState> {
+        let path = note.relative_path.as_str();
+        for warning in &note.warnings {
+            self.warn(path, warning.clone());
+        }
+
+        let mut block = None;
+        if let Some(block_id) = &note.hsk_block_id {
+            match self.existing_block(block_id).await? {
+                _ if self.claimed_blocks.contains(block_id) => self.warn(
+                    path,
+                    format!("hsk_block_id {block_id} already claimed by another file; imported as a new block"),
+                ),
+                Some(found)
+                    if matches!(
+                        found.content_type,
+                        LoomBlockContentType::Note | LoomBlockContentType::Journal
+                    ) =>
+                {
+                    block = Some(found)
+                }
+                Some(_) => self.warn(
+                    path,
+                    format!("hsk_block_id {block_id} is not a note or journal block; ignored"),
+                ),
+                None => self.warn(
+                    path,
+                    format!("hsk_block_id {block_id} not found in this workspace; ignored"),
+                ),
+            }
+        }
+        let entries = self.entries;
+        let entry = entries.get(path);
+        if block.is_none() {
+            if let Some(entry) = entry.filter(|e| !self.claimed_blocks.contains(&e.block_id)) {
+                block = self.existing_block(&entry.block_id).await?;
+            }
+        }
+
+        let mut created = false;
+        let mut journal = note.journal_date.is_some();
+        let block = match block {
+            Some(block) => block,
+            None => {
+                created = true;
+                let mut journal_block = None;
+                if let Some(date) = &note.journal_date {
+                    let found = self
+                        .db
+                        .get_or_create_daily_journal_block(self.ctx, self.workspace_id, date)
+                        .await?;
+                    if self.claimed_blocks.contains(&found.block_id) {
+                        self.warn(
+                            path,
+                            format!("a second daily note for {date}; imported as a regular note"),
+                        );
+                        journal = false;
+                    } else {
+                        journal_block = Some(found);
+                    }
+                }
+                match journal_block {
+                    Some(block) => block,
+                    None => self.create_note_block(&note.title).await?,
+                }
+            }
+        };
+        self.claimed_blocks.insert(block.block_id.clone());
+        let journal = journal && block.content_type == LoomBlockContentType::Journal;
+        if journal {
+            self.report.counts.journals += 1;
+        }
+
+        // The entry only describes this block if it was bound to it before.
+        let entry = entry.filter(|e| e.block_id == block.block_id);
+        let unchanged = entry.is_some_and(|e| e.content_sha256 == note.content_sha256);
+        // A block bound through `hsk_block_id` alone (an exported vault, maybe
+        // re-imported under another name) keeps versioning the RichDocument
+        // an earlier import gave it.
+        let previous_document_id = match entry.and_then(|e| e.rich_document_id.clone()) {
+            Some(id) => Some(id),
+            None if !created => list_loom_vault_entries_for_block(
+                self.db.pool(),
+                self.workspace_id,
+                &block.block_id,
+            )
+            .await?
+            .into_iter()
+            .find_map(|e| e.rich_document_id),
+            None => None,
+        };
+        let previous_document = match previous_document_id {
+            Some(id) => self.db.get_knowledge_rich_document(&id).await?,
+            None => None,
+        };
+
+        let mut changed = created || entry.is_none();
+        let rich_document_id = match previous_document {
+            Some(document) if unchanged => document.rich_document_id,
+            Some(document) => {
+                let outcome = import_snippet(&note.body, ImportFormat::Markdown);
+                self.collect_import_warnings(path, &outcome.warnings);
+                if outcome.document_json != document.content_json {
+                    self.db
+                        .save_knowledge_rich_document_version(
+                            &document.rich_document_id,
+                            document.doc_version,
+                            outcome.document_json,
+                            None,
+                            None,
+                            None,
+                        )
+                        .await?;
+                }
+                if document.title != note.title {
+                    self.db
+                        .rename_knowledge_rich_document(&document.rich_document_id, &note.title)
+                        .await?;
+                }
+                changed = true;
+                document.rich_document_id
+            }
+            None => {
+                let outcome = import_snippet(&note.body, ImportFormat::Markdown);
+                self.collect_import_warnings(path, &outcome.warnings);
+                changed = true;
+                self.db
+                    .create_knowledge_rich_document(NewKnowledgeRichDocument {
+                        workspace_id: self.workspace_id.to_string(),
+                        document_id: None,
+                        title: note.title.clone(),
+                        schema_version: DOCUMENT_SCHEMA_VERSION.to_string(),
+                        content_json: outcome.document_json,
+                        crdt_document_id: None,
+                        crdt_snapshot_id: None,
+                        promotion_receipt_event_id: None,
+                        project_ref: None,
+                        folder_ref: None,
+                        authority_label: None,
+                        owner_actor_kind: None,
+                        owner_actor_id: None,
+                    })
+                    .await?
+                    .rich_document_id
+            }
+        };
+
+        // Journal blocks keep their `Daily Note {date}` title.
+        if block.content_type == LoomBlockContentType::Note
+            && block.title.as_deref() != Some(note.title.as_str())
+        {
+            self.db
+                .update_loom_block(
+                    self.ctx,
+                    self.workspace_id,
+                    &block.block_id,
+                    LoomBlockUpdate {
+                        title: Some(note.title.clone()),
+                        ..LoomBlockUpdate::default()
+                    },
+                )
+                .await?;
+            changed = true;
+        }
+
+        if created {
+            self.report.counts.notes_created += 1;
+        } else if changed {
+            self.report.counts.notes_updated += 1;
+        } else {
+            self.report.counts.notes_unchanged += 1;
+        }
+        if changed {
+            // Keyword/trigram projection only; the embedding is refreshed by
+            // the Loom search path when a model is configured.
+            self.db
+                .reindex_loom_block_search(
+                    self.ctx,
+                    self.workspace_id,
+                    &block.block_id,
+                    &note.search_text(),
+                    None,
+                    None,
+                )
+                .await?;
+        }
+        self.db
+            .add_block_to_loom_folder(self.workspace_id, folder_id, &block.block_id, None)
+            .await?;
+
+        let managed_edge_ids = entry
+            .map(|e| e.managed_edge_ids.clone())
+            .unwrap_or_default();
+        upsert_loom_vault_entry(
+            self.db.pool(),
+            &UpsertLoomVaultEntry {
+                workspace_id: self.workspace_id.to_string(),
+                vault_name: self.report.vault_name.clone(),
+                relative_path: note.relative_path.clone(),
+                entry_kind: if journal {
+                    LoomVaultEntryKind::Journal
+                } else {
+                    LoomVaultEntryKind::Note
+                },
+                block_id: block.block_id.clone(),
+                rich_document_id: Some(rich_document_id),
+                content_sha256: note.content_sha256.clone(),
+                properties: Value::Object(note.properties.clone()),
+                aliases: note.aliases.clone(),
+                managed_edge_ids: managed_edge_ids.clone(),
+                last_import_id: self.report.import_id.clone(),
+            },
+        )
+        .await?;
+
+        Ok(NoteState {
+            block_id: block.block_id,
+            managed_edge_ids,
+        })
+    }
+
+    async fn create_note_block(&mut self, title: &str) -> LoomVaultResult<LoomBlock> {
+        let block = self
+            .db
+            .create_loom_block(
+                self.ctx,
+                NewLoomBlock {
+                    block_id: None,
+                    workspace_id: self.workspace_id.to_string(),
+                    content_type: LoomBlockContentType::Note,
+                    document_id: None,
+                    asset_id: None,
+                    title: Some(title.to_string()),
+                    original_filename: None,
+                    content_hash: None,
+                    pinned: false,
+                    journal_date: None,
+                    imported_at: Some(Utc::now()),
+                    derived: LoomBlockDerived::default(),
+                },
+            )
+            .await?;
+        self.db
+            .bridge_loom_block_to_knowledge(self.ctx, self.workspace_id, &block.block_id)
+            .await?;
+        Ok(block)
+    }
+
+    fn collect_import_warnings(
+        &mut self,
+        path: &str,
+        warnings: &[crate::knowledge_document::import::ImportWarning],
+    ) {
+        for warning in warnings {
+            self.warn(path, format!("{}: {}", warning.code, warning.detail));
+        }
+    }
+
+    /// Find or create a `tag_hub` block per tag (matched by title, case-
+    /// insensitively); `a/b` also gets `a` and a SUB_TAG edge `a/b -> a`.
+    /// Returns lowercased tag -> hub block id.
+    async fn ensure_tag_hubs(
+        &mut self,
+        notes: &[ParsedVaultNote],
+    ) -> LoomVaultResult<HashMap<String, String>> {
+        // A hub answers to its nested tag name (what an export writes) and,
+        // failing that, to its bare title.
+        let existing = tag_hub_paths(self.db, self.workspace_id).await?;
+        let mut hubs: HashMap<String, String> = HashMap::new();
+        for (hub, path) in &existing {
+            hubs.entry(path.to_lowercase())
+                .or_insert_with(|| hub.block_id.clone());
+        }
+        for (hub, _) in existing {
+            if let Some(title) = hub.title {
+                hubs.entry(title.to_lowercase()).or_insert(hub.block_id);
+            }
+        }
+
+        let wanted: BTreeSet<&str> = notes
+            .iter()
+            .flat_map(|n| n.tags.iter().map(String::as_str))
+            .collect();
+        let mut linked: HashSet<String> = HashSet::new();
+        for tag in wanted {
+            let segments: Vec<&str> = tag.split('/').filter(|s| !s.is_empty()).collect();
+            let mut parent: Option<String> = None;
+            for depth in 1..=segments.len() {
+                let name = segments[..depth].join("/");
+                let key = name.to_lowercase();
+                let hub_id = match hubs.get(&key) {
+                    Some(id) => id.clone(),
+                    None => {
+                        let hub = self
+                            .db
+                            .create_loom_block(
+                                self.ctx,
+                                NewLoomBlock {
+                                    block_id: None,
+                                    workspace_id: self.workspace_id.to_string(),
+                                    content_type: LoomBlockContentType::TagHub,
+                                    document_id: None,
+                                    asset_id: None,
+                                    title: Some(name.clone()),
+                                    original_filename: None,
+                                    content_hash: None,
+                                    pinned: false,
+                                    journal_date: None,
+                                    imported_at: Some(Utc::now()),
+                                    derived: LoomBlockDerived::default(),
+                                },
+                            )
+                            .await?;
+                        self.report.counts.tag_hubs_created += 1;
+                        hubs.insert(key.clone(), hub.block_id.clone());
+                        hub.block_id
+                    }
+                };
+                if let Some(parent_id) = parent.take() {
+                    if linked.insert(key.clone()) {
+                        self.ensure_edge(&hub_id, &parent_id, LoomEdgeType::SubTag)
+                            .await?;
+                    }
+                }
+                parent = Some(hub_id);
+            }
+        }
+        Ok(hubs)
+    }
+
+    /// Create `source -> target` unless an edge of that type already exists.
+    async fn ensure_edge(
+        &mut self,
+        source: &str,
+        target: &str,
+        edge_type: LoomEdgeType,
+    ) -> LoomVaultResult<()> {
+        let exists = self
+            .db
+            .get_outgoing_edges(self.workspace_id, source)
+            .await?
+            .iter()
+            .any(|e| e.target_block_id == target && e.edge_type == edge_type);
+        if !exists {
+            self.create_edge(source, target, edge_type).await?;
+        }
+        Ok(())
+    }
+
+    async fn create_edge(
+        &mut self,
+        source: &str,
+        target: &str,
+        edge_type: LoomEdgeType,
+    ) -> LoomVaultResult<String> {
+        let edge = self
+            .db
+            .create_loom_edge(
+                self.ctx,
+                NewLoomEdge {
+                    edge_id: None,
+                    workspace_id: self.workspace_id.to_string(),
+                    source_block_id: source.to_string(),
+                    target_block_id: target.to_string(),
+                    edge_type,
+                    created_by: LoomEdgeCreatedBy::User,
+                    crdt_site_id: None,
+                    source_anchor: None,
+                },
+            )
+            .await?;
+        self.report.counts.edges_created += 1;
+        Ok(edge.edge_id)
+    }
+
+    /// Make the note's outgoing edges match `desired`. An existing edge of the
+    /// same target and type satisfies a link whoever created it; only edges
+    /// this importer created earlier (`managed_edge_ids`) are ever deleted.
+    async fn sync_edges(
+        &mut self,
+        vault_name: &str,
+        note: &ParsedVaultNote,
+        state: &NoteState,
+        desired: &BTreeSet<(String, &'static str)>,
+    ) -> LoomVaultResult<()> {
+        let managed: HashSet<&str> = state.managed_edge_ids.iter().map(String::as_str).collect();
+        let outgoing = self
+            .db
+            .get_outgoing_edges(self.workspace_id, &state.block_id)
+            .await?;
+        let mut satisfied: BTreeSet<(String, &'static str)> = BTreeSet::new();
+        let mut keep: Vec<String> = Vec::new();
+        for edge in &outgoing {
+            let key = (edge.target_block_id.clone(), edge.edge_type.as_str());
+            let is_managed = managed.contains(edge.edge_id.as_str());
+            if desired.contains(&key) && satisfied.insert(key) {
+                if is_managed {
+                    keep.push(edge.edge_id.clone());
+                }
+            } else if is_managed {
+                self.db
+                    .delete_loom_edge(self.ctx, self.workspace_id, &edge.edge_id)
+                    .await?;
+                self.report.counts.edges_removed += 1;
+            }
+        }
+        for (target, edge_type) in desired.difference(&satisfied) {
+            let edge_type = if *edge_type == LoomEdgeType::Tag.as_str() {
+                LoomEdgeType::Tag
+            } else {
+                LoomEdgeType::Mention
+            };
+            let edge_id = self.create_edge(&state.block_id, target, edge_type).await?;
+            keep.push(edge_id);
+        }
+
+        keep.sort();
+        let mut previous = state.managed_edge_ids.clone();
+        previous.sort();
+        if keep != previous {
+            set_loom_vault_entry_edges(
+                self.db.pool(),
+                self.workspace_id,
+                vault_name,
+                &note.relative_path,
+                &keep,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_dir_must_stay_below_an_allowed_root() {
+        let workspace = tempfile::tempdir().expect("workspace");
+        let elsewhere = tempfile::tempdir().expect("elsewhere");
+        std::fs::create_dir_all(workspace.path().join("vaults/notes")).unwrap();
+        std::fs::create_dir_all(elsewhere.path().join("vault")).unwrap();
+        std::fs::write(workspace.path().join("vaults/readme.md"), "# Readme\n").unwrap();
+        let roots = vec![workspace.path().to_path_buf()];
+        let root = workspace.path().canonicalize().unwrap();
+
+        assert_eq!(
+            resolve_import_dir(Path::new("vaults/notes"), &roots).expect("relative"),
+            root.join("vaults/notes")
+        );
+        assert_eq!(
+            resolve_import_dir(&workspace.path().join("vaults/notes"), &roots).expect("absolute"),
+            root.join("vaults/notes")
+        );
+        for bad in [
+            PathBuf::from("../escape"),
+            PathBuf::from("vaults/../../escape"),
+            PathBuf::from("vaults/missing"),
+            PathBuf::from("vaults/readme.md"),
+            workspace.path().to_path_buf(),
+            elsewhere.path().join("vault"),
+            PathBuf::from("/"),
+        ] {
+            assert!(
+                matches!(
+                    resolve_import_dir(&bad, &roots),
+                    Err(LoomVaultError::Validation(_))
+                ),
+                "{} must be refused",
+                bad.display()
+            );
+        }
+
+        let both = vec![roots[0].clone(), elsewhere.path().to_path_buf()];
+        assert!(resolve_import_dir(&elsewhere.path().join("vault"), &both).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn import_dir_symlink_out_of_the_root_is_refused() {
+        let workspace = tempfile::tempdir().expect("workspace");
+        let elsewhere = tempfile::tempdir().expect("elsewhere");
+        std::os::unix::fs::symlink(elsewhere.path(), workspace.path().join("link")).unwrap();
+        let roots = vec![workspace.path().to_path_buf()];
+        assert!(resolve_import_dir(Path::new("link"), &roots).is_err());
+    }
+}
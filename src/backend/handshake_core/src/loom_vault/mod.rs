@@ -0,0 +1,1325 @@
+//! Obsidian / Logseq vault import into Loom, and export back out.
+//!
+//! Walks a whole vault directory and brings it into Loom authority:
+//!
+//! * every markdown note becomes a RichDocument + a `note` LoomBlock (daily
+//!   notes — `YYYY-MM-DD.md`, Logseq `journals/YYYY_MM_DD.md` — land on the
+//!   workspace's `journal` block for that date instead);
+//! * `[[wikilinks]]` (with `|alias`, `#heading` and `#^block` suffixes),
+//!   `![[embeds]]`, markdown links to vault files, Logseq `((block refs))`
+//!   and `{{embed ...}}` macros resolve — by path, file name, title or
+//!   front-matter alias — into MENTION edges, so backlinks come for free;
+//! * `#tags`, `#[[multi word]]` tags and front-matter / `tags::` tags map to
+//!   `tag_hub` blocks (nested `#a/b` tags chain through SUB_TAG edges);
+//! * front-matter and Logseq `key:: value` page properties are kept as the
+//!   note's vault properties;
+//! * every other file is an attachment: its bytes go into the content-
+//!   addressed Loom asset store and it becomes a `file` LoomBlock;
+//! * the directory tree is mirrored as Loom folders under one root folder
+//!   named after the vault.
+//!
+//! Every link that resolves to nothing is listed in the import report.
+//! Re-importing the same vault updates the blocks recorded in
+//! `loom_vault_entries` (by relative path, or by the `hsk_block_id`
+//! front-matter key a Loom vault export writes) instead of duplicating them;
+//! only edges the importer created itself are ever removed.
+//!
+//! MT-187 still holds: the vault is a SOURCE, never authority. The markdown
+//! text is parsed into authority rows and not stored; a file that disappeared
+//! from the vault is reported, not deleted from Loom.
+//!
+//! [`export`] goes the other way: Loom blocks, folders, tags and assets out
+//! as a vault whose front-matter `hsk_block_id` brings a re-import back to
+//! the same blocks.
+//!
+//! This module is the pure half (walk, parse, resolve); [`import`] and
+//! [`export`] touch storage.
+
+pub mod export;
+pub mod import;
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::storage::artifacts::ArtifactError;
+use crate::storage::StorageError;
+
+/// Front-matter key carrying the Loom block a note was exported from. A
+/// re-import adopts that block even when the file moved or the vault name
+/// changed.
+pub const VAULT_BLOCK_ID_KEY: &str = "hsk_block_id";
+
+/// Most notes one import will take; larger vaults are refused, not truncated.
+pub const MAX_VAULT_NOTES: usize = 10_000;
+
+/// Largest attachment copied into the asset store; bigger files are skipped
+/// and reported.
+pub const MAX_VAULT_ATTACHMENT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Which tool wrote the vault. Decides journal layout, page-name encoding and
+/// whether `key:: value` page properties are read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultKind {
+    Obsidian,
+    Logseq,
+}
+
+impl VaultKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VaultKind::Obsidian => "obsidian",
+            VaultKind::Logseq => "logseq",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "obsidian" => Some(VaultKind::Obsidian),
+            "logseq" => Some(VaultKind::Logseq),
+            _ => None,
+        }
+    }
+
+    /// `logseq/config.edn` (or a `pages/` + `journals/` pair without an
+    /// `.obsidian/` folder) marks a Logseq graph; anything else is read as
+    /// Obsidian.
+    pub fn detect(root: &Path) -> Self {
+        if root.join(".obsidian").is_dir() {
+            return VaultKind::Obsidian;
+        }
+        if root.join("logseq").join("config.edn").is_file()
+            || (root.join("pages").is_dir() && root.join("journals").is_dir())
+        {
+            return VaultKind::Logseq;
+        }
+        VaultKind::Obsidian
+    }
+}
+
+/// Errors of the vault import layer.
+#[derive(Debug, thiserror::Error)]
+pub enum LoomVaultError {
+    #[error("vault import validation: {0}")]
+    Validation(String),
+    #[error("vault has {0} notes, more than the {MAX_VAULT_NOTES} note import cap")]
+    NoteCapExceeded(usize),
+    #[error("vault io error at {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("asset store error: {0}")]
+    Artifact(#[from] ArtifactError),
+    #[error("vault blocking task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+pub type LoomVaultResult<T> = Result<T, LoomVaultError>;
+
+// ===========================================================================
+// Walk
+// ===========================================================================
+
+/// One file found in the vault. `relative_path` always uses `/`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultFile {
+    pub relative_path: String,
+    pub absolute_path: PathBuf,
+    pub size_bytes: u64,
+}
+
+/// A file the walk or the importer left out, and why.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedVaultFile {
+    pub relative_path: String,
+    pub reason: String,
+}
+
+/// The vault's files, sorted by relative path.
+#[derive(Clone, Debug, Default)]
+pub struct VaultScan {
+    pub notes: Vec<VaultFile>,
+    pub attachments: Vec<VaultFile>,
+    pub skipped: Vec<SkippedVaultFile>,
+}
+
+impl VaultScan {
+    /// Every directory holding a note or attachment, parents first.
+    pub fn directories(&self) -> Vec<String> {
+        let mut dirs = BTreeSet::new();
+        for file in self.notes.iter().chain(&self.attachments) {
+            let mut dir = parent_dir(&file.relative_path);
+            while !dir.is_empty() {
+                dirs.insert(dir.to_string());
+                dir = parent_dir(dir);
+            }
+        }
+        let mut dirs: Vec<String> = dirs.into_iter().collect();
+        dirs.sort_by_key(|d| (d.matches('/').count(), d.clone()));
+        dirs
+    }
+}
+
+/// Walk the vault. Hidden entries (`.obsidian`, `.trash`, `.git`, ...),
+/// symlinks and Logseq's own `logseq/` config folder are never entered;
+/// attachments above [`MAX_VAULT_ATTACHMENT_BYTES`] are skipped.
+pub fn scan_vault(root: &Path, kind: VaultKind) -> LoomVaultResult<VaultScan> {
+    let mut scan = VaultScan::default();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|source| io_error(&dir, source))?;
+        for entry in entries {
+            let entry = entry.map_err(|source| io_error(&dir, source))?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Some(relative_path) = relative_vault_path(root, &path) else {
+                continue;
+            };
+            let file_type = entry
+                .file_type()
+                .map_err(|source| io_error(&path, source))?;
+            if file_type.is_symlink() {
+                scan.skipped.push(SkippedVaultFile {
+                    relative_path,
+                    reason: "symlink".to_string(),
+                });
+                continue;
+            }
+            if file_type.is_dir() {
+                if kind == VaultKind::Logseq && relative_path == "logseq" {
+                    continue;
+                }
+                stack.push(path);
+                continue;
+            }
+            let size_bytes = entry
+                .metadata()
+                .map_err(|source| io_error(&path, source))?
+                .len();
+            let file = VaultFile {
+                relative_path,
+                absolute_path: path,
+                size_bytes,
+            };
+            if is_markdown_path(&file.relative_path) {
+                scan.notes.push(file);
+            } else if size_bytes > MAX_VAULT_ATTACHMENT_BYTES {
+                scan.skipped.push(SkippedVaultFile {
+                    relative_path: file.relative_path,
+                    reason: format!("attachment larger than {MAX_VAULT_ATTACHMENT_BYTES} bytes"),
+                });
+            } else {
+                scan.attachments.push(file);
+            }
+        }
+    }
+    if scan.notes.len() > MAX_VAULT_NOTES {
+        return Err(LoomVaultError::NoteCapExceeded(scan.notes.len()));
+    }
+    scan.notes
+        .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    scan.attachments
+        .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    scan.skipped
+        .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(scan)
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> LoomVaultError {
+    LoomVaultError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+fn relative_vault_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+pub fn is_markdown_path(relative_path: &str) -> bool {
+    let lower = relative_path.to_ascii_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+/// `a/b/c.md` -> `a/b`; a top-level file -> `""`.
+pub fn parent_dir(relative_path: &str) -> &str {
+    relative_path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or("")
+}
+
+fn file_name(relative_path: &str) -> &str {
+    relative_path
+        .rsplit_once('/')
+        .map(|(_, name)| name)
+        .unwrap_or(relative_path)
+}
+
+fn strip_markdown_ext(relative_path: &str) -> &str {
+    let lower = relative_path.to_ascii_lowercase();
+    if lower.ends_with(".markdown") {
+        &relative_path[..relative_path.len() - ".markdown".len()]
+    } else if lower.ends_with(".md") {
+        &relative_path[..relative_path.len() - ".md".len()]
+    } else {
+        relative_path
+    }
+}
+
+/// MIME type recorded on an attachment's asset row, by extension.
+pub fn attachment_mime(relative_path: &str) -> &'static str {
+    let ext = file_name(relative_path)
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "canvas" => "application/json",
+        "excalidraw" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+// ===========================================================================
+// Parse
+// ===========================================================================
+
+/// What kind of reference a link is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultLinkKind {
+    /// `[[Note]]`, `[text](Note.md)`.
+    Wikilink,
+    /// `![[Note]]`, `![[image.png]]`, `{{embed [[Note]]}}`, `![alt](img.png)`.
+    Embed,
+    /// Logseq `((uuid))`.
+    BlockRef,
+    /// Logseq `{{embed ((uuid))}}`.
+    BlockEmbed,
+}
+
+/// One outgoing reference found in a note body.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultLink {
+    pub kind: VaultLinkKind,
+    /// Page name, vault path or file name; empty for a same-note `[[#heading]]`
+    /// and for block refs.
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// `^id` of an Obsidian block link, or the uuid of a Logseq block ref.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// True for `[text](path)` links, whose target is relative to the note's
+    /// folder rather than a vault-wide name.
+    #[serde(default)]
+    pub relative: bool,
+    /// 1-based line in the file.
+    pub line: usize,
+    pub raw: String,
+}
+
+/// A parsed note. `body` is the markdown without front-matter or Logseq page
+/// properties; it is what becomes the RichDocument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedVaultNote {
+    pub relative_path: String,
+    pub title: String,
+    /// `YYYY-MM-DD` when the note is a daily note.
+    pub journal_date: Option<String>,
+    pub properties: Map<String, Value>,
+    pub aliases: Vec<String>,
+    /// Tag names without `#`, in first-seen order.
+    pub tags: Vec<String>,
+    pub links: Vec<VaultLink>,
+    /// Block ids defined in this note (`^id` suffixes, `id:: uuid`).
+    pub block_ids: Vec<String>,
+    pub hsk_block_id: Option<String>,
+    pub body: String,
+    /// sha256 of the raw file bytes.
+    pub content_sha256: String,
+    pub warnings: Vec<String>,
+}
+
+impl ParsedVaultNote {
+    /// Names `[[...]]` may use for this note, lowercased: title, file stem,
+    /// aliases, and for daily notes the ISO date and Logseq's default
+    /// journal title (`Jan 31st, 2024`).
+    pub fn link_names(&self) -> Vec<String> {
+        let mut names = vec![
+            self.title.to_lowercase(),
+            strip_markdown_ext(file_name(&self.relative_path)).to_lowercase(),
+        ];
+        names.extend(self.aliases.iter().map(|a| a.to_lowercase()));
+        if let Some(date) = self
+            .journal_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        {
+            names.push(date.format("%Y-%m-%d").to_string());
+            names.push(logseq_journal_title(date).to_lowercase());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Plain text for the keyword search index: title, aliases, tags,
+    /// property values and the body.
+    pub fn search_text(&self) -> String {
+        let mut parts = vec![self.title.clone()];
+        parts.extend(self.aliases.iter().cloned());
+        parts.extend(self.tags.iter().map(|t| format!("#{t}")));
+        for (key, value) in &self.properties {
+            match value {
+                Value::String(s) => parts.push(format!("{key}: {s}")),
+                other => parts.push(format!("{key}: {other}")),
+            }
+        }
+        parts.push(self.body.clone());
+        parts.join("\n")
+    }
+}
+
+/// Logseq's default journal page title: `Jan 31st, 2024`.
+pub fn logseq_journal_title(date: NaiveDate) -> String {
+    let day = date.day();
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{} {day}{suffix}, {}", date.format("%b"), date.year())
+}
+
+/// Reference tokens, in one pass so they never overlap. Groups:
+/// 1/2 `{{embed [[page]]}}` / `{{embed ((uuid))}}`; 3/4 `![[..]]` / `[[..]]`;
+/// 5 `((uuid))`; 6 `#[[tag]]`; 7/8/9 `![alt](path)` / `[text](path)`;
+/// 10 `#tag`.
+static REFERENCE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\{\{embed\s+(?:\[\[([^\[\]\n]+)\]\]|\(\(([^()\n]+)\)\))\s*\}\}|(!?)\[\[([^\[\]\n]+)\]\]|\(\(([0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12})\)\)|#\[\[([^\[\]\n]+)\]\]|(!?)\[([^\[\]\n]*)\]\(([^()\s]+)\)|(?:^|[\s(,;])#([\p{L}\p{N}_/-]*[\p{L}_/-][\p{L}\p{N}_/-]*)",
+    )
+    .expect("vault reference regex")
+});
+
+static INLINE_CODE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"`[^`\n]*`").expect("inline code regex"));
+
+/// Logseq `key:: value` property line (optionally as the first bullet).
+static LOGSEQ_PROPERTY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?:[-*]\s+)?([A-Za-z0-9_][A-Za-z0-9_.-]*)::\s?(.*)$")
+        .expect("logseq property regex")
+});
+
+/// `[[..]]` / `![[..]]` inside a property value.
+static PROPERTY_WIKILINK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(!?)\[\[([^\[\]\n]+)\]\]").expect("property wikilink regex"));
+
+/// Obsidian `^block-id` at the end of a line.
+static OBSIDIAN_BLOCK_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s\^([A-Za-z0-9-]+)\s*$").expect("obsidian block id regex"));
+
+/// Parse one note. Never fails: malformed front-matter is kept in the body
+/// and reported as a warning.
+pub fn parse_vault_note(kind: VaultKind, relative_path: &str, raw: &str) -> ParsedVaultNote {
+    let content_sha256 = format!("{:x}", Sha256::digest(raw.as_bytes()));
+    let mut warnings = Vec::new();
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+
+    let (mut properties, mut body, body_line_offset) = match split_front_matter(text) {
+        Some((yaml, rest, consumed_lines)) => match parse_front_matter(yaml) {
+            Ok(map) => (map, rest.to_string(), consumed_lines),
+            Err(err) => {
+                warnings.push(format!("front-matter ignored: {err}"));
+                (Map::new(), text.to_string(), 0)
+            }
+        },
+        None => (Map::new(), text.to_string(), 0),
+    };
+    let mut body_line_offset = body_line_offset;
+    if kind == VaultKind::Logseq {
+        let (page_properties, rest, consumed_lines) = split_logseq_page_properties(&body);
+        for (key, value) in page_properties {
+            properties.entry(key).or_insert(value);
+        }
+        body = rest;
+        body_line_offset += consumed_lines;
+    }
+
+    let hsk_block_id = properties
+        .remove(VAULT_BLOCK_ID_KEY)
+        .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+        .filter(|s| !s.is_empty());
+    let mut aliases = Vec::new();
+    for key in ["aliases", "alias"] {
+        if let Some(value) = properties.get(key) {
+            aliases.extend(property_list(value));
+        }
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for key in ["tags", "tag"] {
+        if let Some(value) = properties.get(key) {
+            for tag in property_list(value) {
+                push_tag(&mut tags, &tag);
+            }
+        }
+    }
+
+    let journal_date = journal_date_for(kind, relative_path);
+    let stem = strip_markdown_ext(file_name(relative_path));
+    let title = properties
+        .get("title")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| match kind {
+            VaultKind::Obsidian => stem.to_string(),
+            VaultKind::Logseq => logseq_page_name(stem),
+        });
+
+    // Obsidian treats `[[..]]` in property values as links (a Loom vault
+    // export writes edges it cannot place in the body there); they report
+    // the front-matter's first line.
+    let mut links = Vec::new();
+    for (key, value) in &properties {
+        if matches!(key.as_str(), "aliases" | "alias" | "tags" | "tag") {
+            continue;
+        }
+        for text in property_strings(value) {
+            for caps in PROPERTY_WIKILINK.captures_iter(text) {
+                let kind = if caps[1].is_empty() {
+                    VaultLinkKind::Wikilink
+                } else {
+                    VaultLinkKind::Embed
+                };
+                links.push(wikilink(&caps[2], kind, 1, caps[0].to_string()));
+            }
+        }
+    }
+    let mut block_ids = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in body.lines().enumerate() {
+        let line_no = index + 1 + body_line_offset;
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if kind == VaultKind::Logseq {
+            if let Some(caps) = LOGSEQ_PROPERTY.captures(line) {
+                if &caps[1] == "id" {
+                    block_ids.push(caps[2].trim().to_lowercase());
+                    continue;
+                }
+            }
+        } else if let Some(caps) = OBSIDIAN_BLOCK_ID.captures(line) {
+            block_ids.push(caps[1].to_lowercase());
+        }
+        scan_line(line, line_no, &mut links, &mut tags);
+    }
+
+    ParsedVaultNote {
+        relative_path: relative_path.to_string(),
+        title,
+        journal_date,
+        properties,
+        aliases: dedup_preserving_order(aliases),
+        tags,
+        links,
+        block_ids: dedup_preserving_order(block_ids),
+        hsk_block_id,
+        body,
+        content_sha256,
+        warnings,
+    }
+}
+
+fn scan_line(line: &str, line_no: usize, links: &mut Vec<VaultLink>, tags: &mut Vec<String>) {
+    // Blank out inline code so `[[x]]` / `#x` inside it are not references.
+    let masked = INLINE_CODE.replace_all(line, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+    for caps in REFERENCE.captures_iter(&masked) {
+        let raw = caps[0]
+            .trim_start_matches([' ', '\t', '(', ',', ';'])
+            .to_string();
+        if let Some(page) = caps.get(1) {
+            let mut link = wikilink(page.as_str(), VaultLinkKind::Embed, line_no, raw);
+            link.display = None;
+            links.push(link);
+        } else if let Some(uuid) = caps.get(2) {
+            links.push(VaultLink {
+                kind: VaultLinkKind::BlockEmbed,
+                target: String::new(),
+                heading: None,
+                block_ref: Some(uuid.as_str().trim().to_lowercase()),
+                display: None,
+                relative: false,
+                line: line_no,
+                raw,
+            });
+        } else if let Some(inner) = caps.get(4) {
+            let kind = if caps.get(3).is_some_and(|bang| !bang.as_str().is_empty()) {
+                VaultLinkKind::Embed
+            } else {
+                VaultLinkKind::Wikilink
+            };
+            links.push(wikilink(inner.as_str(), kind, line_no, raw));
+        } else if let Some(uuid) = caps.get(5) {
+            links.push(VaultLink {
+                kind: VaultLinkKind::BlockRef,
+                target: String::new(),
+                heading: None,
+                block_ref: Some(uuid.as_str().to_lowercase()),
+                display: None,
+                relative: false,
+                line: line_no,
+                raw,
+            });
+        } else if let Some(tag) = caps.get(6) {
+            push_tag(tags, tag.as_str());
+        } else if let Some(target) = caps.get(9) {
+            let target = target.as_str();
+            if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") {
+                continue;
+            }
+            let kind = if caps.get(7).is_some_and(|bang| !bang.as_str().is_empty()) {
+                VaultLinkKind::Embed
+            } else {
+                VaultLinkKind::Wikilink
+            };
+            let decoded = percent_decode(target);
+            let (path, heading) = match decoded.split_once('#') {
+                Some((path, heading)) => (path.to_string(), Some(heading.to_string())),
+                None => (decoded, None),
+            };
+            let display = caps
+                .get(8)
+                .map(|d| d.as_str().trim().to_string())
+                .filter(|d| !d.is_empty());
+            links.push(VaultLink {
+                kind,
+                target: path,
+                heading,
+                block_ref: None,
+                display,
+                relative: true,
+                line: line_no,
+                raw,
+            });
+        } else if let Some(tag) = caps.get(10) {
+            push_tag(tags, tag.as_str());
+        }
+    }
+}
+
+/// Split `Target#Heading|Alias`, `Target#^block|Alias`.
+fn wikilink(inner: &str, kind: VaultLinkKind, line: usize, raw: String) -> VaultLink {
+    let (target_part, display) = match inner.split_once('|') {
+        Some((target, display)) => (target, Some(display.trim().to_string())),
+        None => (inner, None),
+    };
+    // Obsidian escapes the pipe inside tables as `\|`.
+    let target_part = target_part.trim_end_matches('\\');
+    let (target, heading, block_ref) = match target_part.split_once('#') {
+        Some((target, rest)) => match rest.strip_prefix('^') {
+            Some(block) => (target, None, Some(block.trim().to_lowercase())),
+            None => (target, Some(rest.trim().to_string()), None),
+        },
+        None => (target_part, None, None),
+    };
+    VaultLink {
+        kind,
+        target: target.trim().to_string(),
+        heading,
+        block_ref,
+        display: display.filter(|d| !d.is_empty()),
+        relative: false,
+        line,
+        raw,
+    }
+}
+
+fn push_tag(tags: &mut Vec<String>, raw: &str) {
+    let tag = raw
+        .trim()
+        .trim_start_matches('#')
+        .trim_matches('/')
+        .trim_end_matches('-')
+        .to_string();
+    if tag.is_empty() || tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+        return;
+    }
+    tags.push(tag);
+}
+
+fn dedup_preserving_order(values: Vec<String>) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    values
+        .into_iter()
+        .filter(|v| seen.insert(v.to_lowercase()))
+        .collect()
+}
+
+/// `(yaml, rest, lines consumed)` when the text opens with a `---` block.
+fn split_front_matter(text: &str) -> Option<(&str, &str, usize)> {
+    let after_open = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for (index, line) in after_open.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            let yaml = &after_open[..offset];
+            let rest = &after_open[offset + line.len()..];
+            return Some((yaml, rest, index + 2));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn parse_front_matter(yaml: &str) -> Result<Map<String, Value>, String> {
+    if yaml.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    let value: Value = serde_yaml::from_str(yaml).map_err(|err| format!("invalid YAML: {err}"))?;
+    match value {
+        Value::Object(map) => Ok(map),
+        Value::Null => Ok(Map::new()),
+        _ => Err("front-matter is not a mapping".to_string()),
+    }
+}
+
+/// Leading `key:: value` lines of a Logseq page; `tags`/`alias` values become
+/// lists.
+fn split_logseq_page_properties(body: &str) -> (Map<String, Value>, String, usize) {
+    let mut properties = Map::new();
+    let mut consumed = 0;
+    for line in body.lines() {
+        if line.trim().is_empty() && properties.is_empty() {
+            consumed += 1;
+            continue;
+        }
+        let Some(caps) = LOGSEQ_PROPERTY.captures(line) else {
+            break;
+        };
+        let key = caps[1].to_string();
+        let value = caps[2].trim().to_string();
+        let value = if matches!(key.as_str(), "tags" | "alias" | "aliases") {
+            Value::Array(
+                split_property_list(&value)
+                    .into_iter()
+                    .map(Value::String)
+                    .collect(),
+            )
+        } else {
+            Value::String(value)
+        };
+        properties.insert(key, value);
+        consumed += 1;
+    }
+    if properties.is_empty() {
+        return (properties, body.to_string(), 0);
+    }
+    let rest: String = body.split_inclusive('\n').skip(consumed).collect();
+    (properties, rest, consumed)
+}
+
+/// A property value as a list of names: YAML lists as-is, strings split on
+/// commas; `[[..]]` and `#` decorations are dropped.
+fn property_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(clean_property_name(s)),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Value::String(s) => split_property_list(s),
+        _ => Vec::new(),
+    }
+}
+
+/// Every string in a property value (lists included).
+fn property_strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn split_property_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(clean_property_name)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn clean_property_name(value: &str) -> String {
+    value
+        .trim()
+        .trim_start_matches('#')
+        .trim_start_matches("[[")
+        .trim_end_matches("]]")
+        .trim()
+        .to_string()
+}
+
+/// `YYYY-MM-DD` for daily notes: an Obsidian `YYYY-MM-DD` file name anywhere,
+/// or a Logseq `journals/YYYY_MM_DD` page.
+fn journal_date_for(kind: VaultKind, relative_path: &str) -> Option<String> {
+    let stem = strip_markdown_ext(file_name(relative_path));
+    let date = match kind {
+        VaultKind::Obsidian => NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok(),
+        VaultKind::Logseq => {
+            if parent_dir(relative_path) != "journals" {
+                return None;
+            }
+            NaiveDate::parse_from_str(stem, "%Y_%m_%d")
+                .or_else(|_| NaiveDate::parse_from_str(stem, "%Y-%m-%d"))
+                .ok()
+        }
+    }?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+/// Logseq encodes namespace pages as `a___b.md` (and, in older graphs,
+/// `a%2Fb.md`).
+fn logseq_page_name(stem: &str) -> String {
+    percent_decode(&stem.replace("___", "/"))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| value.to_string())
+}
+
+// ===========================================================================
+// Tags
+// ===========================================================================
+
+/// The vault tag name of a Loom tag hub: its title under its SUB_TAG parent's
+/// tag name (`project/alpha` for a hub titled `alpha` under `project`). A
+/// title that already carries its parent's name — what an import of `#a/b`
+/// creates — is kept as is. `parents` maps a hub to its SUB_TAG parent; the
+/// walk stops at a hub without a title and at the first repeated hub.
+pub fn tag_hub_path(
+    block_id: &str,
+    titles: &HashMap<String, String>,
+    parents: &HashMap<String, String>,
+) -> Option<String> {
+    let mut chain = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut current = Some(block_id);
+    while let Some(id) = current {
+        if !seen.insert(id) {
+            break;
+        }
+        let Some(title) = titles.get(id) else {
+            break;
+        };
+        chain.push(title.trim());
+        current = parents.get(id).map(String::as_str);
+    }
+    let mut path = String::new();
+    for title in chain.into_iter().rev().filter(|t| !t.is_empty()) {
+        let prefixed = title
+            .to_lowercase()
+            .strip_prefix(&path.to_lowercase())
+            .is_some_and(|rest| rest.starts_with('/'));
+        if path.is_empty() || prefixed {
+            path = title.to_string();
+        } else {
+            path = format!("{path}/{title}");
+        }
+    }
+    (!path.is_empty()).then_some(path)
+}
+
+// ===========================================================================
+// Resolve
+// ===========================================================================
+
+/// What a link points at: an index into the parsed notes or the attachments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultTarget {
+    Note(usize),
+    Attachment(usize),
+}
+
+/// Outcome of resolving one link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VaultResolution {
+    Resolved(VaultTarget),
+    /// Several notes share the name; the closest one (same folder, then
+    /// shortest path) was picked.
+    Ambiguous {
+        chosen: VaultTarget,
+        candidates: usize,
+    },
+    Unresolved(String),
+}
+
+/// Name / path / block-id lookup over one vault.
+pub struct VaultIndex {
+    note_paths: Vec<String>,
+    notes_by_path: HashMap<String, usize>,
+    notes_by_name: HashMap<String, Vec<usize>>,
+    attachments_by_path: HashMap<String, usize>,
+    attachments_by_name: HashMap<String, Vec<usize>>,
+    block_owner: HashMap<String, usize>,
+    note_block_ids: Vec<BTreeSet<String>>,
+}
+
+impl VaultIndex {
+    pub fn build(notes: &[ParsedVaultNote], attachment_paths: &[String]) -> Self {
+        let mut index = VaultIndex {
+            note_paths: notes.iter().map(|n| n.relative_path.clone()).collect(),
+            notes_by_path: HashMap::new(),
+            notes_by_name: HashMap::new(),
+            attachments_by_path: HashMap::new(),
+            attachments_by_name: HashMap::new(),
+            block_owner: HashMap::new(),
+            note_block_ids: Vec::with_capacity(notes.len()),
+        };
+        for (i, note) in notes.iter().enumerate() {
+            index
+                .notes_by_path
+                .insert(strip_markdown_ext(&note.relative_path).to_lowercase(), i);
+            for name in note.link_names() {
+                index.notes_by_name.entry(name).or_default().push(i);
+            }
+            for block_id in &note.block_ids {
+                index.block_owner.entry(block_id.clone()).or_insert(i);
+            }
+            index
+                .note_block_ids
+                .push(note.block_ids.iter().cloned().collect());
+        }
+        for (i, path) in attachment_paths.iter().enumerate() {
+            index.attachments_by_path.insert(path.to_lowercase(), i);
+            index
+                .attachments_by_name
+                .entry(file_name(path).to_lowercase())
+                .or_default()
+                .push(i);
+        }
+        index
+    }
+
+    /// Resolve `link`, written in note `source`.
+    pub fn resolve(&self, source: usize, link: &VaultLink) -> VaultResolution {
+        if matches!(
+            link.kind,
+            VaultLinkKind::BlockRef | VaultLinkKind::BlockEmbed
+        ) {
+            let block = link.block_ref.as_deref().unwrap_or_default();
+            return match self.block_owner.get(block) {
+                Some(&owner) => VaultResolution::Resolved(VaultTarget::Note(owner)),
+                None => VaultResolution::Unresolved(format!("no block with id {block}")),
+            };
+        }
+        let resolution = if link.target.is_empty() {
+            VaultResolution::Resolved(VaultTarget::Note(source))
+        } else if link.relative {
+            self.resolve_relative(source, &link.target)
+        } else {
+            self.resolve_name(source, &link.target)
+        };
+        let (VaultResolution::Resolved(VaultTarget::Note(note))
+        | VaultResolution::Ambiguous {
+            chosen: VaultTarget::Note(note),
+            ..
+        }) = resolution
+        else {
+            return resolution;
+        };
+        match link.block_ref.as_deref() {
+            Some(block) if !self.note_block_ids[note].contains(block) => {
+                VaultResolution::Unresolved(format!(
+                    "no block ^{block} in {}",
+                    self.note_paths[note]
+                ))
+            }
+            _ => resolution,
+        }
+    }
+
+    fn resolve_relative(&self, source: usize, target: &str) -> VaultResolution {
+        let joined = if target.starts_with('/') {
+            target.trim_start_matches('/').to_string()
+        } else {
+            let dir = parent_dir(&self.note_paths[source]);
+            if dir.is_empty() {
+                target.to_string()
+            } else {
+                format!("{dir}/{target}")
+            }
+        };
+        let Some(normalized) = normalize_vault_path(&joined) else {
+            return VaultResolution::Unresolved(format!("{target} points outside the vault"));
+        };
+        match self.lookup_path(&normalized) {
+            Some(found) => VaultResolution::Resolved(found),
+            // Obsidian writes vault-absolute paths without the leading `/`.
+            None => match normalize_vault_path(target).and_then(|p| self.lookup_path(&p)) {
+                Some(found) => VaultResolution::Resolved(found),
+                None => VaultResolution::Unresolved(format!("no note or attachment at {target}")),
+            },
+        }
+    }
+
+    fn resolve_name(&self, source: usize, target: &str) -> VaultResolution {
+        let lowered = target.trim().trim_start_matches('/').to_lowercase();
+        if let Some(found) = self.lookup_path(&lowered) {
+            return VaultResolution::Resolved(found);
+        }
+        if let Some(candidates) = self.notes_by_name.get(&lowered) {
+            let mut candidates = candidates.clone();
+            candidates.sort();
+            candidates.dedup();
+            return self.pick(source, candidates, VaultTarget::Note);
+        }
+        // `[[folder/Note]]` written against a deeper vault path.
+        let suffix = format!("/{lowered}");
+        let mut by_suffix: Vec<usize> = self
+            .notes_by_path
+            .iter()
+            .filter(|(path, _)| path.ends_with(&suffix))
+            .map(|(_, &i)| i)
+            .collect();
+        if !by_suffix.is_empty() {
+            by_suffix.sort();
+            return self.pick(source, by_suffix, VaultTarget::Note);
+        }
+        if let Some(candidates) = self.attachments_by_name.get(file_name(&lowered)) {
+            return self.pick(source, candidates.clone(), VaultTarget::Attachment);
+        }
+        VaultResolution::Unresolved(format!("no note or attachment named {target}"))
+    }
+
+    fn lookup_path(&self, lowered: &str) -> Option<VaultTarget> {
+        let lowered = lowered.to_lowercase();
+        if let Some(&i) = self.notes_by_path.get(strip_markdown_ext(&lowered)) {
+            return Some(VaultTarget::Note(i));
+        }
+        self.attachments_by_path
+            .get(&lowered)
+            .map(|&i| VaultTarget::Attachment(i))
+    }
+
+    fn pick(
+        &self,
+        source: usize,
+        candidates: Vec<usize>,
+        wrap: fn(usize) -> VaultTarget,
+    ) -> VaultResolution {
+        if candidates.len() == 1 {
+            return VaultResolution::Resolved(wrap(candidates[0]));
+        }
+        let source_dir = parent_dir(&self.note_paths[source]).to_string();
+        let path_of = |i: usize| -> String {
+            match wrap(i) {
+                VaultTarget::Note(n) => self.note_paths[n].clone(),
+                VaultTarget::Attachment(a) => self
+                    .attachments_by_path
+                    .iter()
+                    .find(|(_, &idx)| idx == a)
+                    .map(|(p, _)| p.clone())
+                    .unwrap_or_default(),
+            }
+        };
+        let chosen = candidates
+            .iter()
+            .copied()
+            .min_by_key(|&i| {
+                let path = path_of(i);
+                (
+                    parent_dir(&path) != source_dir,
+                    path.matches('/').count(),
+                    path,
+                )
+            })
+            .expect("non-empty candidates");
+        VaultResolution::Ambiguous {
+            chosen: wrap(chosen),
+            candidates: candidates.len(),
+        }
+    }
+}
+
+/// Collapse `.` / `..` segments; `None` when the path climbs out of the vault.
+fn normalize_vault_path(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            other => parts.push(other),
+        }
+    }
+    Some(parts.join("/").to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(kind: VaultKind, path: &str, text: &str) -> ParsedVaultNote {
+        parse_vault_note(kind, path, text)
+    }
+
+    #[test]
+    fn obsidian_note_yields_links_tags_aliases_and_properties() {
+        let text = "---\naliases: [Alpha, The A]\ntags: [project, area/ops]\nstatus: draft\nhsk_block_id: blk-1\n---\n# Heading\nSee [[Beta|the beta]] and [[Gamma#Setup]], block [[Beta#^b1]].\n![[diagram.png]] and [spec](docs/Spec%20Doc.md) #idea #area/ops\n`[[not a link]]` #123 ^own-id\n```\n[[in code]] #nope\n```\n";
+        let parsed = note(VaultKind::Obsidian, "notes/Alpha note.md", text);
+
+        assert_eq!(parsed.title, "Alpha note");
+        assert_eq!(parsed.aliases, vec!["Alpha", "The A"]);
+        assert_eq!(parsed.tags, vec!["project", "area/ops", "idea"]);
+        assert_eq!(parsed.properties["status"], "draft");
+        assert!(!parsed.properties.contains_key(VAULT_BLOCK_ID_KEY));
+        assert_eq!(parsed.hsk_block_id.as_deref(), Some("blk-1"));
+        assert_eq!(parsed.block_ids, vec!["own-id"]);
+        assert!(parsed.body.starts_with("# Heading\n"));
+
+        type LinkSummary<'a> = (
+            VaultLinkKind,
+            &'a str,
+            Option<&'a str>,
+            Option<&'a str>,
+            usize,
+        );
+        let summary: Vec<LinkSummary> = parsed
+            .links
+            .iter()
+            .map(|l| {
+                (
+                    l.kind,
+                    l.target.as_str(),
+                    l.heading.as_deref(),
+                    l.block_ref.as_deref(),
+                    l.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (VaultLinkKind::Wikilink, "Beta", None, None, 8),
+                (VaultLinkKind::Wikilink, "Gamma", Some("Setup"), None, 8),
+                (VaultLinkKind::Wikilink, "Beta", None, Some("b1"), 8),
+                (VaultLinkKind::Embed, "diagram.png", None, None, 9),
+                (VaultLinkKind::Wikilink, "docs/Spec Doc.md", None, None, 9),
+            ]
+        );
+        assert_eq!(parsed.links[0].display.as_deref(), Some("the beta"));
+        assert!(parsed.links[4].relative);
+    }
+
+    #[test]
+    fn logseq_page_properties_refs_embeds_and_journals() {
+        let text = "alias:: LS, Log Seq\ntags:: [[tool]], pkm\ntype:: software\n\n- Uses #[[graph db]] and #outliner\n  id:: 6650a1b2-0000-4000-8000-00000000abcd\n- ref ((6650a1b2-0000-4000-8000-00000000ffff))\n- {{embed [[Other Page]]}} {{embed ((6650a1b2-0000-4000-8000-00000000abcd))}}\n";
+        let parsed = note(VaultKind::Logseq, "pages/tools___logseq.md", text);
+
+        assert_eq!(parsed.title, "tools/logseq");
+        assert_eq!(parsed.aliases, vec!["LS", "Log Seq"]);
+        assert_eq!(parsed.tags, vec!["tool", "pkm", "graph db", "outliner"]);
+        assert_eq!(parsed.properties["type"], "software");
+        assert_eq!(
+            parsed.block_ids,
+            vec!["6650a1b2-0000-4000-8000-00000000abcd"]
+        );
+        assert!(parsed.body.starts_with("\n- Uses"));
+        let kinds: Vec<VaultLinkKind> = parsed.links.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                VaultLinkKind::BlockRef,
+                VaultLinkKind::Embed,
+                VaultLinkKind::BlockEmbed
+            ]
+        );
+        assert_eq!(parsed.links[0].line, 7);
+
+        let journal = note(
+            VaultKind::Logseq,
+            "journals/2024_01_31.md",
+            "- did things\n",
+        );
+        assert_eq!(journal.journal_date.as_deref(), Some("2024-01-31"));
+        assert!(journal.link_names().contains(&"jan 31st, 2024".to_string()));
+        let not_journal = note(VaultKind::Logseq, "pages/2024_01_31.md", "");
+        assert_eq!(not_journal.journal_date, None);
+        let daily = note(VaultKind::Obsidian, "Daily/2024-02-02.md", "");
+        assert_eq!(daily.journal_date.as_deref(), Some("2024-02-02"));
+    }
+
+    #[test]
+    fn malformed_front_matter_is_kept_as_body_with_a_warning() {
+        let parsed = note(VaultKind::Obsidian, "a.md", "---\n: [unclosed\n---\ntext\n");
+        assert!(parsed.properties.is_empty());
+        assert!(parsed.body.starts_with("---\n"));
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn index_resolves_paths_names_aliases_blocks_and_reports_the_rest() {
+        let notes = vec![
+            note(VaultKind::Obsidian, "a/Home.md", "[[Beta]] [[b/Beta]] [[Alias B]] [[Missing]] [[b/Beta#^x1]] [[Beta#^nope]] ![[pic.png]] [up](../Top.md) [[#Local]]\n"),
+            note(VaultKind::Obsidian, "a/b/Beta.md", "---\naliases: Alias B\n---\nline ^x1\n"),
+            note(VaultKind::Obsidian, "c/Beta.md", "other beta\n"),
+            note(VaultKind::Obsidian, "Top.md", "top\n"),
+        ];
+        let attachments = vec!["assets/pic.png".to_string()];
+        let index = VaultIndex::build(&notes, &attachments);
+        let results: Vec<VaultResolution> =
+            notes[0].links.iter().map(|l| index.resolve(0, l)).collect();
+
+        assert_eq!(
+            results[0],
+            VaultResolution::Ambiguous {
+                chosen: VaultTarget::Note(2),
+                candidates: 2
+            }
+        );
+        assert_eq!(results[1], VaultResolution::Resolved(VaultTarget::Note(1)));
+        assert_eq!(results[2], VaultResolution::Resolved(VaultTarget::Note(1)));
+        assert!(matches!(results[3], VaultResolution::Unresolved(_)));
+        assert_eq!(results[4], VaultResolution::Resolved(VaultTarget::Note(1)));
+        assert!(
+            matches!(&results[5], VaultResolution::Unresolved(reason) if reason.contains("^nope"))
+        );
+        assert_eq!(
+            results[6],
+            VaultResolution::Resolved(VaultTarget::Attachment(0))
+        );
+        assert_eq!(results[7], VaultResolution::Resolved(VaultTarget::Note(3)));
+        assert_eq!(results[8], VaultResolution::Resolved(VaultTarget::Note(0)));
+    }
+
+    #[test]
+    fn tag_hub_paths_follow_sub_tag_parents() {
+        let map = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let titles = map(&[
+            ("p", "project"),
+            ("a", "alpha"),
+            ("n", "project/alpha/next"),
+            ("x", "x"),
+            ("y", "y"),
+        ]);
+        let parents = map(&[("a", "p"), ("n", "a"), ("x", "y"), ("y", "x")]);
+        assert_eq!(
+            tag_hub_path("p", &titles, &parents).as_deref(),
+            Some("project")
+        );
+        assert_eq!(
+            tag_hub_path("a", &titles, &parents).as_deref(),
+            Some("project/alpha")
+        );
+        // An imported `#project/alpha/next` hub already carries its prefix.
+        assert_eq!(
+            tag_hub_path("n", &titles, &parents).as_deref(),
+            Some("project/alpha/next")
+        );
+        assert_eq!(tag_hub_path("x", &titles, &parents).as_deref(), Some("y/x"));
+        assert_eq!(tag_hub_path("missing", &titles, &parents), None);
+    }
+
+    #[test]
+    fn scan_skips_hidden_and_config_folders_and_splits_notes_from_attachments() {
+        let root = std::env::temp_dir().join(format!("hsk-vault-scan-{}", uuid::Uuid::now_v7()));
+        for dir in [".obsidian", "logseq", "pages/sub", "assets"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join(".obsidian/app.json"), "{}").unwrap();
+        std::fs::write(root.join("logseq/config.edn"), "{}").unwrap();
+        std::fs::write(root.join("pages/sub/Note.md"), "x").unwrap();
+        std::fs::write(root.join("Index.md"), "x").unwrap();
+        std::fs::write(root.join("assets/a.png"), [0u8; 4]).unwrap();
+
+        let scan = scan_vault(&root, VaultKind::Logseq).unwrap();
+        let notes: Vec<&str> = scan
+            .notes
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        assert_eq!(notes, vec!["Index.md", "pages/sub/Note.md"]);
+        assert_eq!(scan.attachments[0].relative_path, "assets/a.png");
+        assert_eq!(scan.directories(), vec!["assets", "pages", "pages/sub"]);
+        assert_eq!(VaultKind::detect(&root), VaultKind::Obsidian);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -2,20 +2,30 @@ use axum::{Json, Router, extract::State, routing::get};
 use handshake_core::{
     AppState, api,
     capabilities::CapabilityRegistry,
+    cassette::{CassetteSink, RecordingLlmClient},
     diagnostics::DiagnosticsStore,
     flight_recorder::{FlightRecorder, duckdb::DuckDbFlightRecorder},
     llm::{
-        DisabledLlmClient, LlmClient, ModelTier,
+        DisabledLlmClient, LlmClient, ModelProfile, ModelTier,
         guard::CloudEscalationGuard,
+        local_openai_server::{
+            LocalModelInfo, LocalOpenAiServer, LocalOpenAiServerConfig, PreapprovedModelsConsent,
+        },
+        local_router::LocalModelRuntimeLlmClient,
         ollama::OllamaAdapter,
         openai_compat::{ApiKey, OpenAiCompatAdapter},
         registry::{ProviderKind, ProviderRegistry, RuntimeRole},
     },
     logging,
+    model_runtime::{
+        local_models::{self, LocalModels},
+        store::ModelStore,
+    },
     models::HealthResponse,
     process_ledger::restart_resume::PostgresRestartResumeRunner,
     storage::{
         self,
+        artifacts::resolve_workspace_root,
         retention::{Janitor, JanitorConfig},
     },
     workflows,
@@ -90,6 +100,7 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let flight_recorder: Arc<dyn FlightRecorder> = recorder.clone();
     let diagnostics: Arc<dyn DiagnosticsStore> = recorder.clone();
     let llm_client = init_llm_client(flight_recorder.clone()).await;
+    let local_models = init_local_models().await;
     let capability_registry = Arc::new(CapabilityRegistry::new());
     let session_registry = Arc::new(workflows::SessionRegistry::new(
         workflows::SessionSchedulerConfig::from_env(),
@@ -201,14 +212,33 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     ));
     let _janitor_handle = janitor.spawn_background();
 
+    start_local_openai_server(&local_models, flight_recorder.clone())?;
+
+    // The native shell reads the operator session secret from the data dir to
+    // reach the operator-only routes.
+    let operator_session_path =
+        api::operator_session::write_operator_session_file(&resolve_data_dir()?)?;
+    tracing::info!(
+        target: "handshake_core",
+        path = %operator_session_path.display(),
+        "operator session secret written"
+    );
+
     let api_routes = api::routes(state.clone());
+    // Operator-only routes (the integrated terminal) are merged AFTER the CORS
+    // layer: no browser origin is ever granted access to them.
+    let operator_routes = api::operator_routes(state.clone());
+    let operator_routes = Router::new()
+        .merge(operator_routes.clone())
+        .nest("/api", operator_routes);
 
     let app = Router::new()
         .route("/health", get(health))
         .with_state(state.clone())
         .merge(api_routes.clone())
         .nest("/api", api_routes)
-        .layer(cors);
+        .layer(cors)
+        .merge(operator_routes);
 
     tracing::info!(target: "handshake_core", listen_addr = %addr, "handshake_core started");
 
@@ -297,7 +327,8 @@ fn init_janitor_config() -> JanitorConfig {
     }
 }
 
-async fn init_flight_recorder() -> Result<Arc<DuckDbFlightRecorder>, Box<dyn std::error::Error>> {
+/// `<repo root>/data`, created on first use.
+fn resolve_data_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let root_dir = manifest_dir
         .parent()
@@ -309,6 +340,11 @@ async fn init_flight_recorder() -> Result<Arc<DuckDbFlightRecorder>, Box<dyn std
     if !data_dir.exists() {
         std::fs::create_dir_all(&data_dir)?;
     }
+    Ok(data_dir)
+}
+
+async fn init_flight_recorder() -> Result<Arc<DuckDbFlightRecorder>, Box<dyn std::error::Error>> {
+    let data_dir = resolve_data_dir()?;
     // Flight recorder gets its own file
     let fr_db_path = data_dir.join("flight_recorder.db");
 
@@ -426,7 +462,7 @@ async fn init_llm_client(flight_recorder: Arc<dyn FlightRecorder>) -> Arc<dyn Ll
         }
     };
 
-    if client.profile().model_tier == ModelTier::Cloud {
+    let client: Arc<dyn LlmClient> = if client.profile().model_tier == ModelTier::Cloud {
         match CloudEscalationGuard::from_env(client) {
             Ok(guarded) => Arc::new(guarded),
             Err(err) => {
@@ -441,6 +477,101 @@ async fn init_llm_client(flight_recorder: Arc<dyn FlightRecorder>) -> Arc<dyn Ll
         }
     } else {
         client
+    };
+
+    with_cassette_recording(client)
+}
+
+/// Load the stored models the `HANDSHAKE_*_MODEL` env vars name into the
+/// local runtimes (prompt-injection classifier, ...). An unusable store or model
+/// is logged and leaves its role empty; it never blocks startup.
+async fn init_local_models() -> Arc<LocalModels> {
+    let store = match ModelStore::open_default() {
+        Ok(store) => Some(Arc::new(store)),
+        Err(err) => {
+            tracing::warn!(
+                target: "handshake_core::model_runtime",
+                error = %err,
+                "model store unavailable; local models disabled"
+            );
+            None
+        }
+    };
+    let models = Arc::new(LocalModels::load_from_env(store).await);
+    local_models::install(models.clone());
+    models
+}
+
+/// `HANDSHAKE_OPENAI_SERVER_BIND` starts the OpenAI-compatible server over the
+/// models in `HANDSHAKE_LOCAL_CHAT_MODELS`; listing them is the operator's
+/// consent to serve them. A bad bind config fails startup.
+fn start_local_openai_server(
+    models: &Arc<LocalModels>,
+    flight_recorder: Arc<dyn FlightRecorder>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(config) = LocalOpenAiServerConfig::from_env()? else {
+        return Ok(());
+    };
+    if models.chat_models().is_empty() {
+        tracing::warn!(
+            target: "handshake_core::llm",
+            "local OpenAI server enabled but no chat models loaded"
+        );
+    }
+    let client = LocalModelRuntimeLlmClient::new(
+        models.chat_router(),
+        Arc::new(DisabledLlmClient::new(
+            "local-openai-server".to_string(),
+            "the local OpenAI server serves local models only".to_string(),
+        )),
+        flight_recorder,
+        ModelProfile::new("local-openai-server".to_string(), 8192).with_streaming(true),
+    );
+    let consent = Arc::new(PreapprovedModelsConsent::new(
+        models.chat_models().iter().map(|model| model.model_id),
+    ));
+    let model_info: Arc<dyn LocalModelInfo> = models.clone();
+    let server = Arc::new(
+        LocalOpenAiServer::new(config, Arc::new(client), consent).with_model_info(model_info),
+    );
+    tokio::spawn(async move {
+        if let Err(err) = server.serve().await {
+            tracing::error!(target: "handshake_core::llm", error = %err, "local OpenAI server stopped");
+        }
+    });
+    Ok(())
+}
+
+/// `HANDSHAKE_LLM_CASSETTE_RECORD=1` records every model exchange into per-trace
+/// cassettes under the workspace, where debug bundles can pick them up.
+fn with_cassette_recording(client: Arc<dyn LlmClient>) -> Arc<dyn LlmClient> {
+    if std::env::var("HANDSHAKE_LLM_CASSETTE_RECORD")
+        .ok()
+        .as_deref()
+        != Some("1")
+    {
+        return client;
+    }
+    match resolve_workspace_root() {
+        Ok(workspace_root) => {
+            tracing::info!(
+                target: "handshake_core::llm",
+                workspace_root = %workspace_root.display(),
+                "LLM cassette recording enabled"
+            );
+            Arc::new(RecordingLlmClient::new(
+                client,
+                CassetteSink::PerTrace { workspace_root },
+            ))
+        }
+        Err(err) => {
+            tracing::warn!(
+                target: "handshake_core::llm",
+                error = %err,
+                "LLM cassette recording disabled (cannot resolve workspace root)"
+            );
+            client
+        }
     }
 }
 
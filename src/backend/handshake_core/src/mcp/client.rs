@@ -300,10 +300,15 @@ impl Future for McpCall {
                 match result {
                     Ok(resp) => match resp.into_result() {
                         Ok(v) => Poll::Ready(Ok(v)),
-                        Err(e) => Poll::Ready(Err(McpError::Protocol(format!(
-                            "json-rpc error {}: {}",
-                            e.code, e.message
-                        )))),
+                        Err(e) => Poll::Ready(Err(
+                            match crate::cassette::mcp::cassette_error_from_rpc(&e) {
+                                Some(err) => McpError::Cassette(err),
+                                None => McpError::Protocol(format!(
+                                    "json-rpc error {}: {}",
+                                    e.code, e.message
+                                )),
+                            },
+                        )),
                     },
                     Err(e) => Poll::Ready(Err(McpError::Transport(e.to_string()))),
                 }
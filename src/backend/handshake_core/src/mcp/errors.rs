@@ -24,6 +24,8 @@ pub enum McpError {
     UnknownTool(String),
     #[error("HSK-MCP-500-FLIGHT-RECORDER: {0}")]
     FlightRecorder(String),
+    #[error(transparent)]
+    Cassette(#[from] crate::cassette::CassetteError),
 }
 
 impl From<serde_json::Error> for McpError {
@@ -269,6 +269,7 @@ pub fn single_engine_registry(engine_id: &str) -> MexRegistry {
                 output_types: vec!["artifact.model3d".to_string()],
             },
         ],
+        wasm: None,
     };
 
     let mut map = HashMap::new();
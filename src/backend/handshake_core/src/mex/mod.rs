@@ -10,6 +10,7 @@ pub mod gates;
 pub mod registry;
 pub mod runtime;
 pub mod supply_chain;
+pub mod wasm;
 
 pub use conformance::{ConformanceCase, ConformanceHarness, ConformanceResult};
 pub use envelope::{
@@ -20,9 +21,10 @@ pub use gates::{
     BudgetGate, CapabilityGate, CrossSessionGate, DetGate, Gate, GateDenial, GatePipeline,
     IntegrityGate, IsolationGate, ProvenanceGate, SchemaGate,
 };
-pub use registry::{EngineSpec, MexRegistry, OperationSpec};
+pub use registry::{EngineSpec, MexRegistry, OperationSpec, WasmModuleSpec};
 pub use runtime::{EngineAdapter, MexRuntime, MexRuntimeError};
 pub use supply_chain::{
     LicenseScanAllowlist, SecretScanAllowlist, SupplyChainAllowlists, SupplyChainEngineAdapter,
     SupplyChainReport, SupplyChainReportKind, TerminalServiceRunner, VulnScanAllowlist,
 };
+pub use wasm::{WasiGrants, WasmEngineAdapter, WasmExit, WasmLimits};
@@ -41,6 +41,20 @@ pub struct EngineSpec {
     pub default_budget: BudgetSpec,
     #[serde(default)]
     pub ops: Vec<OperationSpec>,
+    /// Present when the engine ships as a WASI module run by `mex::wasm::WasmEngineAdapter`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm: Option<WasmModuleSpec>,
+}
+
+/// WASI module backing an engine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WasmModuleSpec {
+    /// Path to the `.wasm` (or `.wat`) module, relative to the workspace root. It must resolve
+    /// (after symlinks) to a file inside the workspace root.
+    pub module: String,
+    /// Expected sha256 (hex) of the module bytes. Required: a missing, malformed or mismatched
+    /// digest denies the operation.
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -0,0 +1,1057 @@
+//! WASM/WASI engine adapter (Spec §6.3.0).
+//!
+//! Runs mechanical engines whose registry entry carries a `wasm` module spec. The module is a WASI
+//! preview1 command (`_start`); it receives `{"operation", "params", "inputs"}` as JSON on stdin.
+//!
+//! Capabilities map to what the guest can reach:
+//! - `fs.read` / `fs.read:*` preopens the staged input artifacts read-only at `/in/<artifact_id>`;
+//! - `fs.write` / `fs.write:artifacts` preopens a writable `/out`; every file left there becomes an
+//!   ArtifactStore L1 artifact;
+//! - anything the sandbox cannot express (`proc.exec`, `net.*`, ...) denies the operation.
+//!
+//! The guest may always import `handshake.log(ptr, len)`, which appends a UTF-8 line to the log
+//! artifact. `BudgetSpec` is enforced as fuel (`cpu_time_ms`), a linear-memory cap (`memory_bytes`),
+//! an epoch deadline (`wall_time_ms`) and a cap on stdout, `/out` and the log (`output_bytes`).
+//! The output cap holds while the guest runs: stdout and stderr are bounded pipes, `handshake.log`
+//! traps once the log passes the cap, and `/out` is measured on every epoch tick, so a guest that
+//! fills the disk is stopped within one [`EPOCH_TICK`] rather than after it exits.
+//!
+//! The module must sit inside the workspace root (symlinks resolved) and match the registry's
+//! required sha256 before it is compiled; staged inputs must resolve inside the same root.
+//!
+//! Execution needs the `wasm-engines` feature; the capability and budget planning is always built.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::ace::ArtifactHandle;
+use crate::mex::envelope::{
+    BudgetSpec, EngineError, EngineResult, EngineStatus, PlannedOperation, ProvenanceRecord,
+};
+use crate::mex::registry::{EngineSpec, WasmModuleSpec};
+use crate::mex::runtime::{AdapterError, EngineAdapter};
+use crate::storage::artifacts::{
+    artifact_root_rel, write_file_artifact, ArtifactClassification, ArtifactLayer,
+    ArtifactManifest, ArtifactPayloadKind,
+};
+
+/// Provenance `implementation` for operations run by this adapter.
+pub const WASM_IMPLEMENTATION: &str = "wasmtime_wasi_p1";
+/// Fuel granted per millisecond of `cpu_time_ms`. Roughly one unit per executed instruction, so
+/// this approximates a ~1 GIPS guest; it is a deterministic cap, not a wall clock.
+pub const FUEL_PER_CPU_MS: u64 = 1_000_000;
+/// Guest path of the read-only input preopen.
+pub const WASI_INPUT_DIR: &str = "/in";
+/// Guest path of the writable output preopen.
+pub const WASI_OUTPUT_DIR: &str = "/out";
+/// How often the host checks the wall-time deadline and the size of `/out` while a guest runs.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// What the WASI sandbox exposes for a granted capability set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasiGrants {
+    pub read_inputs: bool,
+    pub write_outputs: bool,
+}
+
+impl WasiGrants {
+    /// Map requested capabilities onto preopens. Returns the first capability the sandbox has no
+    /// mapping for; such an operation is denied rather than run with less than it asked for.
+    pub fn plan(capabilities: &[String]) -> Result<Self, String> {
+        let mut grants = Self::default();
+        for cap in capabilities {
+            let cap = cap.trim();
+            match cap {
+                "" => {}
+                "fs.read" => grants.read_inputs = true,
+                c if c.starts_with("fs.read:") => grants.read_inputs = true,
+                "fs.write" | "fs.write:artifacts" => grants.write_outputs = true,
+                other => return Err(other.to_string()),
+            }
+        }
+        Ok(grants)
+    }
+}
+
+/// Store limits derived from a `BudgetSpec`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmLimits {
+    pub fuel: Option<u64>,
+    pub memory_bytes: Option<usize>,
+    pub wall_time: Option<Duration>,
+    pub output_bytes: Option<u64>,
+}
+
+impl WasmLimits {
+    /// The operation's budget wins field by field; the engine's `default_budget` fills the gaps.
+    pub fn from_budget(op_budget: &BudgetSpec, engine_default: &BudgetSpec) -> Self {
+        let cpu_time_ms = op_budget.cpu_time_ms.or(engine_default.cpu_time_ms);
+        let wall_time_ms = op_budget.wall_time_ms.or(engine_default.wall_time_ms);
+        let memory_bytes = op_budget.memory_bytes.or(engine_default.memory_bytes);
+        Self {
+            fuel: cpu_time_ms.map(|ms| ms.saturating_mul(FUEL_PER_CPU_MS)),
+            memory_bytes: memory_bytes.map(|b| usize::try_from(b).unwrap_or(usize::MAX)),
+            wall_time: wall_time_ms.map(Duration::from_millis),
+            output_bytes: op_budget.output_bytes.or(engine_default.output_bytes),
+        }
+    }
+
+    /// Narrow the output cap to the operation's `output_spec.max_bytes`.
+    pub fn with_output_cap(mut self, max_bytes: Option<u64>) -> Self {
+        self.output_bytes = match (self.output_bytes, max_bytes) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
+}
+
+/// How a guest run ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmExit {
+    Exited(i32),
+    OutOfFuel,
+    WallTimeExceeded,
+    MemoryExceeded,
+    OutputExceeded,
+    Trapped(String),
+}
+
+/// Captured result of one guest run.
+#[derive(Debug, Clone)]
+pub struct WasmRun {
+    pub exit: WasmExit,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub log_lines: Vec<String>,
+}
+
+/// Per-operation host directories backing the preopens.
+#[derive(Debug, Clone)]
+struct OpDirs {
+    root: PathBuf,
+    input: PathBuf,
+    output: PathBuf,
+}
+
+/// Adapter for a single WASM engine from the registry.
+pub struct WasmEngineAdapter {
+    workspace_root: PathBuf,
+    engine: EngineSpec,
+    module: WasmModuleSpec,
+}
+
+impl WasmEngineAdapter {
+    pub fn new(workspace_root: PathBuf, engine: EngineSpec) -> Result<Self, AdapterError> {
+        let module = engine.wasm.clone().ok_or_else(|| {
+            AdapterError::Engine(format!("{} has no wasm module spec", engine.engine_id))
+        })?;
+        Ok(Self {
+            workspace_root,
+            engine,
+            module,
+        })
+    }
+
+    /// Adapters for every registry engine that declares a `wasm` module.
+    pub fn from_registry(
+        workspace_root: &Path,
+        registry: &crate::mex::registry::MexRegistry,
+    ) -> Vec<(String, Self)> {
+        registry
+            .engines()
+            .filter(|spec| spec.wasm.is_some())
+            .filter_map(|spec| {
+                Self::new(workspace_root.to_path_buf(), spec.clone())
+                    .ok()
+                    .map(|adapter| (spec.engine_id.clone(), adapter))
+            })
+            .collect()
+    }
+
+    fn op_dirs(&self, op: &PlannedOperation) -> OpDirs {
+        let root = self
+            .workspace_root
+            .join("data")
+            .join("mex_wasm")
+            .join("ops")
+            .join(op.op_id.to_string());
+        OpDirs {
+            input: root.join("in"),
+            output: root.join("out"),
+            root,
+        }
+    }
+
+    /// Canonical path of the module. The registry path is relative to the workspace root and must
+    /// still be inside it once `..` and symlinks are resolved.
+    fn resolve_module(&self) -> Result<PathBuf, String> {
+        let rel = Path::new(&self.module.module);
+        if rel.is_absolute() {
+            return Err(format!(
+                "module path must be relative: {}",
+                self.module.module
+            ));
+        }
+        let root = fs::canonicalize(&self.workspace_root)
+            .map_err(|e| format!("{}: {e}", self.workspace_root.display()))?;
+        let path =
+            fs::canonicalize(root.join(rel)).map_err(|e| format!("{}: {e}", self.module.module))?;
+        if !path.starts_with(&root) || !path.is_file() {
+            return Err(format!(
+                "module path escapes the workspace or is not a file: {}",
+                self.module.module
+            ));
+        }
+        Ok(path)
+    }
+
+    /// Canonical source file of an input handle. Handles point either at a file or at an
+    /// ArtifactStore directory holding a `payload` file; like the module, the file must still be
+    /// inside the workspace root once `..` and symlinks are resolved.
+    fn resolve_input(&self, input: &str) -> Result<PathBuf, String> {
+        let rel = Path::new(input);
+        if rel.is_absolute() || rel.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(format!("input path escapes the workspace: {input}"));
+        }
+        let root = fs::canonicalize(&self.workspace_root)
+            .map_err(|e| format!("{}: {e}", self.workspace_root.display()))?;
+        let mut path = fs::canonicalize(root.join(rel)).map_err(|e| format!("{input}: {e}"))?;
+        if path.is_dir() {
+            path = fs::canonicalize(path.join("payload")).map_err(|e| format!("{input}: {e}"))?;
+        }
+        if !path.starts_with(&root) || !path.is_file() {
+            return Err(format!(
+                "input path escapes the workspace or is not a file: {input}"
+            ));
+        }
+        Ok(path)
+    }
+
+    /// Copy each input artifact to `<in>/<artifact_id>`.
+    fn stage_inputs(&self, op: &PlannedOperation, dirs: &OpDirs) -> Result<(), AdapterError> {
+        fs::create_dir_all(&dirs.input).map_err(|e| io_err(&dirs.input, e))?;
+        for handle in &op.inputs {
+            let src = self
+                .resolve_input(&handle.path)
+                .map_err(AdapterError::Engine)?;
+            let dst = dirs.input.join(handle.artifact_id.to_string());
+            fs::copy(&src, &dst).map_err(|e| io_err(&src, e))?;
+        }
+        Ok(())
+    }
+
+    fn stdin_payload(op: &PlannedOperation, grants: WasiGrants) -> Result<Vec<u8>, AdapterError> {
+        let inputs: Vec<_> = if grants.read_inputs {
+            op.inputs
+                .iter()
+                .map(|h| {
+                    json!({
+                        "artifact_id": h.artifact_id,
+                        "path": format!("{WASI_INPUT_DIR}/{}", h.artifact_id),
+                    })
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        serde_json::to_vec(&json!({
+            "operation": op.operation,
+            "params": op.params,
+            "inputs": inputs,
+        }))
+        .map_err(|e| AdapterError::Engine(e.to_string()))
+    }
+
+    fn store_artifact(
+        &self,
+        op: &PlannedOperation,
+        filename: &str,
+        bytes: &[u8],
+    ) -> Result<ArtifactHandle, AdapterError> {
+        let artifact_id = Uuid::now_v7();
+        let manifest = ArtifactManifest {
+            artifact_id,
+            layer: ArtifactLayer::L1,
+            kind: ArtifactPayloadKind::File,
+            mime: mime_for(filename).to_string(),
+            filename_hint: Some(filename.to_string()),
+            created_at: Utc::now(),
+            created_by_job_id: Some(op.op_id),
+            source_entity_refs: Vec::new(),
+            source_artifact_refs: op.inputs.clone(),
+            content_hash: sha256_hex(bytes),
+            size_bytes: bytes.len() as u64,
+            classification: ArtifactClassification::Low,
+            exportable: true,
+            retention_ttl_days: None,
+            pinned: None,
+            hash_basis: Some("payload".to_string()),
+            hash_exclude_paths: Vec::new(),
+        };
+        write_file_artifact(&self.workspace_root, &manifest, bytes)
+            .map_err(|e| AdapterError::Engine(format!("failed to store {filename}: {e}")))?;
+        Ok(ArtifactHandle::new(
+            artifact_id,
+            artifact_root_rel(ArtifactLayer::L1, artifact_id),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn result(
+        &self,
+        op: &PlannedOperation,
+        status: EngineStatus,
+        started_at: chrono::DateTime<Utc>,
+        config_hash: Option<String>,
+        outputs: Vec<ArtifactHandle>,
+        errors: Vec<EngineError>,
+        logs_ref: Option<ArtifactHandle>,
+    ) -> EngineResult {
+        EngineResult {
+            op_id: op.op_id,
+            status,
+            started_at,
+            ended_at: Utc::now(),
+            outputs: outputs.clone(),
+            evidence: outputs.clone(),
+            provenance: ProvenanceRecord {
+                engine_id: op.engine_id.clone(),
+                engine_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                implementation: Some(WASM_IMPLEMENTATION.to_string()),
+                determinism: op.determinism,
+                config_hash,
+                inputs: op.inputs.clone(),
+                outputs,
+                capabilities_granted: op.capabilities_requested.clone(),
+                environment: Some(json!({ "module": self.module.module })),
+            },
+            errors,
+            logs_ref,
+        }
+    }
+
+    fn denied(
+        &self,
+        op: &PlannedOperation,
+        started_at: chrono::DateTime<Utc>,
+        config_hash: Option<String>,
+        code: &str,
+        message: String,
+    ) -> EngineResult {
+        let error = EngineError {
+            code: code.to_string(),
+            message,
+            details_ref: None,
+        };
+        self.result(
+            op,
+            EngineStatus::Denied,
+            started_at,
+            config_hash,
+            Vec::new(),
+            vec![error],
+            None,
+        )
+    }
+}
+
+#[async_trait]
+impl EngineAdapter for WasmEngineAdapter {
+    async fn invoke(&self, op: &PlannedOperation) -> Result<EngineResult, AdapterError> {
+        if op.engine_id != self.engine.engine_id {
+            return Err(AdapterError::Engine(format!(
+                "unsupported engine: {} (adapter serves {})",
+                op.engine_id, self.engine.engine_id
+            )));
+        }
+        let started_at = Utc::now();
+
+        let grants = match WasiGrants::plan(&op.capabilities_requested) {
+            Ok(grants) => grants,
+            Err(cap) => {
+                let message = format!("capability {cap} has no WASI mapping");
+                let code = "ENGINE_WASM_CAPABILITY_UNMAPPED";
+                return Ok(self.denied(op, started_at, None, code, message));
+            }
+        };
+
+        let expected = self.module.sha256.trim();
+        if !is_sha256_hex(expected) {
+            let message = format!(
+                "registry sha256 for {} is missing or malformed",
+                self.module.module
+            );
+            return Ok(self.denied(op, started_at, None, "ENGINE_WASM_INTEGRITY", message));
+        }
+        let module_path = match self.resolve_module() {
+            Ok(path) => path,
+            Err(message) => {
+                let code = "ENGINE_WASM_MODULE_PATH";
+                return Ok(self.denied(op, started_at, None, code, message));
+            }
+        };
+        let module_bytes = fs::read(&module_path).map_err(|e| io_err(&module_path, e))?;
+        let module_hash = sha256_hex(&module_bytes);
+        if !expected.eq_ignore_ascii_case(&module_hash) {
+            let message = format!("module sha256 {module_hash} does not match registry {expected}");
+            let code = "ENGINE_WASM_INTEGRITY";
+            return Ok(self.denied(op, started_at, Some(module_hash), code, message));
+        }
+
+        let limits = WasmLimits::from_budget(&op.budget, &self.engine.default_budget)
+            .with_output_cap(op.output_spec.max_bytes);
+        let dirs = self.op_dirs(op);
+        if grants.read_inputs {
+            self.stage_inputs(op, &dirs)?;
+        }
+        fs::create_dir_all(&dirs.output).map_err(|e| io_err(&dirs.output, e))?;
+        let stdin = Self::stdin_payload(op, grants)?;
+
+        let run_dirs = dirs.clone();
+        let run = tokio::task::spawn_blocking(move || {
+            execute_module(&module_bytes, stdin, &run_dirs, grants, limits)
+        })
+        .await
+        .map_err(|e| AdapterError::Engine(format!("wasm task failed: {e}")))?;
+        let run = match run {
+            Ok(run) => run,
+            Err(err) => {
+                let _ = fs::remove_dir_all(&dirs.root);
+                return Err(err);
+            }
+        };
+
+        let mut outputs = Vec::new();
+        let mut errors = Vec::new();
+        let mut written: u64 = run.stdout.len() as u64;
+        let mut files = Vec::new();
+        // An over-budget run keeps nothing it wrote.
+        if run.exit != WasmExit::OutputExceeded {
+            collect_files(&dirs.output, &dirs.output, &mut files)?;
+            files.sort();
+        }
+        for rel in &files {
+            let path = dirs.output.join(rel);
+            let bytes = fs::read(&path).map_err(|e| io_err(&path, e))?;
+            written = written.saturating_add(bytes.len() as u64);
+            if limits.output_bytes.is_some_and(|cap| written > cap) {
+                errors.push(EngineError {
+                    code: "ENGINE_WASM_OUTPUT_BUDGET".to_string(),
+                    message: format!("outputs exceed {} bytes", limits.output_bytes.unwrap_or(0)),
+                    details_ref: None,
+                });
+                break;
+            }
+            outputs.push(self.store_artifact(op, rel, &bytes)?);
+        }
+        if !run.stdout.is_empty() && errors.is_empty() && run.exit != WasmExit::OutputExceeded {
+            outputs.push(self.store_artifact(op, "stdout.txt", &run.stdout)?);
+        }
+        let _ = fs::remove_dir_all(&dirs.root);
+
+        let mut log = String::new();
+        for line in &run.log_lines {
+            log.push_str(line);
+            log.push('\n');
+        }
+        log.push_str(&String::from_utf8_lossy(&run.stderr));
+        let logs_ref = if log.is_empty() {
+            None
+        } else {
+            Some(self.store_artifact(op, "log.txt", log.as_bytes())?)
+        };
+
+        let exit_error = match &run.exit {
+            WasmExit::Exited(0) => None,
+            WasmExit::Exited(code) => Some((
+                "ENGINE_WASM_NONZERO_EXIT",
+                format!("module exited with code {code}"),
+            )),
+            WasmExit::OutOfFuel => Some((
+                "ENGINE_WASM_CPU_BUDGET",
+                "module ran out of fuel".to_string(),
+            )),
+            WasmExit::WallTimeExceeded => Some((
+                "ENGINE_WASM_WALL_TIME",
+                "module exceeded its wall-time budget".to_string(),
+            )),
+            WasmExit::MemoryExceeded => Some((
+                "ENGINE_WASM_MEMORY_BUDGET",
+                "module exceeded its memory budget".to_string(),
+            )),
+            WasmExit::OutputExceeded => Some((
+                "ENGINE_WASM_OUTPUT_BUDGET",
+                format!(
+                    "module output exceeded {} bytes while running",
+                    limits.output_bytes.unwrap_or(0)
+                ),
+            )),
+            WasmExit::Trapped(msg) => Some(("ENGINE_WASM_TRAP", msg.clone())),
+        };
+        if let Some((code, message)) = exit_error {
+            errors.insert(
+                0,
+                EngineError {
+                    code: code.to_string(),
+                    message,
+                    details_ref: logs_ref.clone(),
+                },
+            );
+        }
+
+        let status = if errors.is_empty() {
+            EngineStatus::Succeeded
+        } else {
+            EngineStatus::Failed
+        };
+        Ok(self.result(
+            op,
+            status,
+            started_at,
+            Some(module_hash),
+            outputs,
+            errors,
+            logs_ref,
+        ))
+    }
+}
+
+#[cfg(feature = "wasm-engines")]
+fn execute_module(
+    module_bytes: &[u8],
+    stdin: Vec<u8>,
+    dirs: &OpDirs,
+    grants: WasiGrants,
+    limits: WasmLimits,
+) -> Result<WasmRun, AdapterError> {
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+    use std::time::Instant;
+
+    use wasmtime::{
+        Caller, Config, Engine, Linker, Module, ResourceLimiter, Store, Trap, UpdateDeadline,
+    };
+    use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+    /// Refuses linear-memory growth past the budget and remembers that it did.
+    struct MemoryCap {
+        max_bytes: Option<usize>,
+        exceeded: bool,
+    }
+
+    impl ResourceLimiter for MemoryCap {
+        fn memory_growing(
+            &mut self,
+            _current: usize,
+            desired: usize,
+            _maximum: Option<usize>,
+        ) -> wasmtime::Result<bool> {
+            if self.max_bytes.is_some_and(|max| desired > max) {
+                self.exceeded = true;
+                return Ok(false);
+            }
+            Ok(true)
+        }
+
+        fn table_growing(
+            &mut self,
+            _current: usize,
+            _desired: usize,
+            _maximum: Option<usize>,
+        ) -> wasmtime::Result<bool> {
+            Ok(true)
+        }
+    }
+
+    struct HostState {
+        wasi: WasiP1Ctx,
+        memory: MemoryCap,
+        log_lines: Vec<String>,
+        log_bytes: u64,
+        output_exceeded: bool,
+        wall_exceeded: bool,
+    }
+
+    let engine_err = |e: wasmtime::Error| AdapterError::Engine(format!("wasm: {e:#}"));
+    let pipe_cap = limits
+        .output_bytes
+        .map(|b| usize::try_from(b).unwrap_or(usize::MAX))
+        .unwrap_or(usize::MAX);
+
+    // Epoch ticks drive both the wall-time deadline and the `/out` size check.
+    let watch_output = grants.write_outputs && limits.output_bytes.is_some();
+    let ticking = limits.wall_time.is_some() || watch_output;
+
+    let mut config = Config::new();
+    config.consume_fuel(limits.fuel.is_some());
+    config.epoch_interruption(ticking);
+    let engine = Engine::new(&config).map_err(engine_err)?;
+    let module = Module::new(&engine, module_bytes).map_err(engine_err)?;
+
+    let mut linker: Linker<HostState> = Linker::new(&engine);
+    preview1::add_to_linker_sync(&mut linker, |s: &mut HostState| &mut s.wasi)
+        .map_err(engine_err)?;
+    let output_cap = limits.output_bytes;
+    linker
+        .func_wrap(
+            "handshake",
+            "log",
+            move |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> wasmtime::Result<()> {
+                let state = caller.data_mut();
+                state.log_bytes = state.log_bytes.saturating_add(len as u32 as u64 + 1);
+                if output_cap.is_some_and(|cap| state.log_bytes > cap) {
+                    state.output_exceeded = true;
+                    return Err(wasmtime::Error::msg(
+                        "handshake.log: output budget exceeded",
+                    ));
+                }
+                let memory = caller
+                    .get_export("memory")
+                    .and_then(|e| e.into_memory())
+                    .ok_or_else(|| {
+                        wasmtime::Error::msg("handshake.log: module exports no memory")
+                    })?;
+                let start = ptr as u32 as usize;
+                let end = start.saturating_add(len as u32 as usize);
+                let line = memory
+                    .data(&caller)
+                    .get(start..end)
+                    .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                    .ok_or_else(|| wasmtime::Error::msg("handshake.log: range out of bounds"))?;
+                caller.data_mut().log_lines.push(line);
+                Ok(())
+            },
+        )
+        .map_err(engine_err)?;
+
+    let stdout = MemoryOutputPipe::new(pipe_cap);
+    let stderr = MemoryOutputPipe::new(pipe_cap);
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.stdin(MemoryInputPipe::new(stdin))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .arg("engine");
+    if grants.read_inputs {
+        wasi.preopened_dir(&dirs.input, WASI_INPUT_DIR, DirPerms::READ, FilePerms::READ)
+            .map_err(engine_err)?;
+    }
+    if grants.write_outputs {
+        wasi.preopened_dir(
+            &dirs.output,
+            WASI_OUTPUT_DIR,
+            DirPerms::all(),
+            FilePerms::all(),
+        )
+        .map_err(engine_err)?;
+    }
+
+    let mut store = Store::new(
+        &engine,
+        HostState {
+            wasi: wasi.build_p1(),
+            memory: MemoryCap {
+                max_bytes: limits.memory_bytes,
+                exceeded: false,
+            },
+            log_lines: Vec::new(),
+            log_bytes: 0,
+            output_exceeded: false,
+            wall_exceeded: false,
+        },
+    );
+    store.limiter(|s| &mut s.memory);
+    if let Some(fuel) = limits.fuel {
+        store.set_fuel(fuel).map_err(engine_err)?;
+    }
+
+    // The ticker bumps the epoch every EPOCH_TICK until `done` is dropped; each tick the guest
+    // yields to the callback, which enforces the wall-time deadline and the `/out` + log cap.
+    let (done, ticker_rx) = channel::<()>();
+    if ticking {
+        let started = Instant::now();
+        let wall_time = limits.wall_time;
+        let output_dir = watch_output.then(|| dirs.output.clone());
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(move |mut ctx| {
+            if wall_time.is_some_and(|wall| started.elapsed() >= wall) {
+                ctx.data_mut().wall_exceeded = true;
+                return Err(wasmtime::Error::msg("wall-time budget exceeded"));
+            }
+            if let (Some(cap), Some(dir)) = (output_cap, output_dir.as_deref()) {
+                let used = dir_bytes(dir).saturating_add(ctx.data().log_bytes);
+                if used > cap {
+                    ctx.data_mut().output_exceeded = true;
+                    return Err(wasmtime::Error::msg("output budget exceeded"));
+                }
+            }
+            Ok(UpdateDeadline::Continue(1))
+        });
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = ticker_rx.recv_timeout(EPOCH_TICK) {
+                engine.increment_epoch();
+            }
+        });
+    }
+
+    let outcome = linker
+        .instantiate(&mut store, &module)
+        .and_then(|instance| instance.get_typed_func::<(), ()>(&mut store, "_start"))
+        .and_then(|start| start.call(&mut store, ()));
+    drop(done);
+
+    let exit = match outcome {
+        Ok(()) => WasmExit::Exited(0),
+        Err(err) => {
+            let state = store.data();
+            if let Some(code) = err.downcast_ref::<I32Exit>() {
+                WasmExit::Exited(code.0)
+            } else if state.output_exceeded {
+                WasmExit::OutputExceeded
+            } else if state.wall_exceeded {
+                WasmExit::WallTimeExceeded
+            } else {
+                match err.downcast_ref::<Trap>() {
+                    Some(Trap::OutOfFuel) => WasmExit::OutOfFuel,
+                    Some(Trap::Interrupt) => WasmExit::WallTimeExceeded,
+                    _ if state.memory.exceeded => WasmExit::MemoryExceeded,
+                    _ => WasmExit::Trapped(format!("{err:#}")),
+                }
+            }
+        }
+    };
+
+    let log_lines = std::mem::take(&mut store.data_mut().log_lines);
+    drop(store);
+    Ok(WasmRun {
+        exit,
+        stdout: stdout.contents().to_vec(),
+        stderr: stderr.contents().to_vec(),
+        log_lines,
+    })
+}
+
+#[cfg(not(feature = "wasm-engines"))]
+fn execute_module(
+    _module_bytes: &[u8],
+    _stdin: Vec<u8>,
+    _dirs: &OpDirs,
+    _grants: WasiGrants,
+    _limits: WasmLimits,
+) -> Result<WasmRun, AdapterError> {
+    Err(AdapterError::Engine(
+        "WASM engines need handshake_core built with the `wasm-engines` feature".to_string(),
+    ))
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<String>) -> Result<(), AdapterError> {
+    let entries = fs::read_dir(dir).map_err(|e| io_err(dir, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| io_err(dir, e))?;
+        let path = entry.path();
+        let file_type = entry.file_type().map_err(|e| io_err(&path, e))?;
+        if file_type.is_dir() {
+            collect_files(base, &path, out)?;
+        } else if file_type.is_file() {
+            if let Ok(rel) = path.strip_prefix(base) {
+                out.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Total size of the regular files under `dir`; unreadable entries count as empty.
+#[cfg(feature = "wasm-engines")]
+fn dir_bytes(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_bytes(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .fold(0, u64::saturating_add)
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn mime_for(filename: &str) -> &'static str {
+    match Path::new(filename).extension().and_then(|e| e.to_str()) {
+        Some("json") => "application/json",
+        Some("txt") | Some("log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("html") => "text/html",
+        _ => "application/octet-stream",
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+fn io_err(path: &Path, err: std::io::Error) -> AdapterError {
+    AdapterError::Engine(format!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(list: &[&str]) -> Vec<String> {
+        list.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn fs_capabilities_map_to_preopens() {
+        let grants = WasiGrants::plan(&caps(&["fs.read:logs", "fs.write:artifacts"])).unwrap();
+        assert_eq!(
+            grants,
+            WasiGrants {
+                read_inputs: true,
+                write_outputs: true
+            }
+        );
+        assert_eq!(WasiGrants::plan(&[]).unwrap(), WasiGrants::default());
+    }
+
+    #[test]
+    fn unmappable_capabilities_are_refused() {
+        assert_eq!(
+            WasiGrants::plan(&caps(&["fs.read", "proc.exec"])),
+            Err("proc.exec".to_string())
+        );
+        assert_eq!(
+            WasiGrants::plan(&caps(&["net.http"])),
+            Err("net.http".to_string())
+        );
+    }
+
+    #[test]
+    fn budget_maps_to_limits_with_engine_defaults() {
+        let op = BudgetSpec {
+            cpu_time_ms: Some(5),
+            wall_time_ms: None,
+            memory_bytes: None,
+            output_bytes: Some(4096),
+        };
+        let engine = BudgetSpec {
+            cpu_time_ms: Some(60_000),
+            wall_time_ms: Some(2_000),
+            memory_bytes: Some(64 * 1024 * 1024),
+            output_bytes: Some(1024 * 1024),
+        };
+        let limits = WasmLimits::from_budget(&op, &engine).with_output_cap(Some(1024));
+        assert_eq!(limits.fuel, Some(5 * FUEL_PER_CPU_MS));
+        assert_eq!(limits.wall_time, Some(Duration::from_millis(2_000)));
+        assert_eq!(limits.memory_bytes, Some(64 * 1024 * 1024));
+        assert_eq!(
+            limits.output_bytes,
+            Some(1024),
+            "output_spec.max_bytes narrows the budget"
+        );
+
+        let unbounded = WasmLimits::from_budget(&BudgetSpec::default(), &BudgetSpec::default());
+        assert_eq!(unbounded, WasmLimits::default());
+    }
+
+    fn adapter_for(workspace_root: &Path, module: &str) -> WasmEngineAdapter {
+        let mut engine = crate::mex::conformance::single_engine_registry("engine.wasm")
+            .engines()
+            .next()
+            .cloned()
+            .unwrap();
+        engine.wasm = Some(WasmModuleSpec {
+            module: module.to_string(),
+            sha256: "0".repeat(64),
+        });
+        WasmEngineAdapter::new(workspace_root.to_path_buf(), engine).unwrap()
+    }
+
+    #[test]
+    fn module_path_must_stay_inside_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("ws");
+        fs::create_dir_all(workspace.join("engines")).unwrap();
+        fs::write(workspace.join("engines/ok.wat"), "(module)").unwrap();
+        fs::write(tmp.path().join("outside.wat"), "(module)").unwrap();
+
+        assert!(adapter_for(&workspace, "engines/ok.wat")
+            .resolve_module()
+            .is_ok());
+        assert!(adapter_for(&workspace, "../outside.wat")
+            .resolve_module()
+            .is_err());
+        assert!(adapter_for(&workspace, "engines/../../outside.wat")
+            .resolve_module()
+            .is_err());
+        let absolute = tmp.path().join("outside.wat");
+        assert!(adapter_for(&workspace, &absolute.to_string_lossy())
+            .resolve_module()
+            .is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&absolute, workspace.join("engines/link.wat")).unwrap();
+            assert!(adapter_for(&workspace, "engines/link.wat")
+                .resolve_module()
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn staged_inputs_must_stay_inside_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("ws");
+        fs::create_dir_all(workspace.join("artifacts/a1")).unwrap();
+        fs::write(workspace.join("artifacts/a1/payload"), "in").unwrap();
+        fs::write(workspace.join("artifacts/plain.txt"), "in").unwrap();
+        fs::create_dir_all(tmp.path().join("outside")).unwrap();
+        fs::write(tmp.path().join("outside/payload"), "secret").unwrap();
+        let adapter = adapter_for(&workspace, "engines/ok.wat");
+        let root = fs::canonicalize(&workspace).unwrap();
+
+        assert_eq!(
+            adapter.resolve_input("artifacts/a1").unwrap(),
+            root.join("artifacts/a1/payload")
+        );
+        assert_eq!(
+            adapter.resolve_input("artifacts/plain.txt").unwrap(),
+            root.join("artifacts/plain.txt")
+        );
+        assert!(adapter.resolve_input("../outside").is_err());
+        assert!(adapter.resolve_input("artifacts/missing").is_err());
+        let absolute = tmp.path().join("outside/payload");
+        assert!(adapter.resolve_input(&absolute.to_string_lossy()).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path().join("outside"), workspace.join("artifacts/dir"))
+                .unwrap();
+            std::os::unix::fs::symlink(&absolute, workspace.join("artifacts/a1/link")).unwrap();
+            fs::create_dir_all(workspace.join("artifacts/a2")).unwrap();
+            std::os::unix::fs::symlink(&absolute, workspace.join("artifacts/a2/payload")).unwrap();
+            assert!(adapter.resolve_input("artifacts/dir").is_err());
+            assert!(adapter.resolve_input("artifacts/a1/link").is_err());
+            assert!(adapter.resolve_input("artifacts/a2").is_err());
+        }
+    }
+
+    #[test]
+    fn registry_digest_must_be_sha256_hex() {
+        assert!(is_sha256_hex(&sha256_hex(b"module")));
+        assert!(!is_sha256_hex(""));
+        assert!(!is_sha256_hex(&"z".repeat(64)));
+    }
+
+    #[cfg(feature = "wasm-engines")]
+    fn run_wat(wat: &str, limits: WasmLimits) -> WasmRun {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = OpDirs {
+            root: tmp.path().to_path_buf(),
+            input: tmp.path().join("in"),
+            output: tmp.path().join("out"),
+        };
+        fs::create_dir_all(&dirs.output).unwrap();
+        execute_module(
+            wat.as_bytes(),
+            Vec::new(),
+            &dirs,
+            WasiGrants::default(),
+            limits,
+        )
+        .unwrap()
+    }
+
+    #[cfg(feature = "wasm-engines")]
+    #[test]
+    fn fuel_budget_stops_a_spinning_module() {
+        let run = run_wat(
+            r#"(module (func (export "_start") (loop (br 0))))"#,
+            WasmLimits {
+                fuel: Some(10_000),
+                ..WasmLimits::default()
+            },
+        );
+        assert_eq!(run.exit, WasmExit::OutOfFuel);
+    }
+
+    #[cfg(feature = "wasm-engines")]
+    #[test]
+    fn host_log_reaches_the_run() {
+        let run = run_wat(
+            r#"(module
+                 (import "handshake" "log" (func $log (param i32 i32)))
+                 (memory (export "memory") 1)
+                 (data (i32.const 0) "hello")
+                 (func (export "_start") (call $log (i32.const 0) (i32.const 5))))"#,
+            WasmLimits::default(),
+        );
+        assert_eq!(run.exit, WasmExit::Exited(0));
+        assert_eq!(run.log_lines, vec!["hello".to_string()]);
+    }
+
+    #[cfg(feature = "wasm-engines")]
+    #[test]
+    fn log_past_the_output_cap_stops_the_module() {
+        let run = run_wat(
+            r#"(module
+                 (import "handshake" "log" (func $log (param i32 i32)))
+                 (memory (export "memory") 1)
+                 (func (export "_start") (loop (call $log (i32.const 0) (i32.const 64)) (br 0))))"#,
+            WasmLimits {
+                output_bytes: Some(1024),
+                ..WasmLimits::default()
+            },
+        );
+        assert_eq!(run.exit, WasmExit::OutputExceeded);
+        assert!(run.log_lines.len() < 1024 / 64);
+    }
+
+    #[cfg(feature = "wasm-engines")]
+    #[test]
+    fn out_dir_is_measured_while_the_module_runs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = OpDirs {
+            root: tmp.path().to_path_buf(),
+            input: tmp.path().join("in"),
+            output: tmp.path().join("out"),
+        };
+        fs::create_dir_all(&dirs.output).unwrap();
+        // Stands in for a guest writing through the preopen: the file is already over the cap
+        // when the module starts spinning, so the first epoch tick must stop it.
+        fs::write(dirs.output.join("big.bin"), vec![0_u8; 4096]).unwrap();
+        let grants = WasiGrants {
+            read_inputs: false,
+            write_outputs: true,
+        };
+        let limits = WasmLimits {
+            output_bytes: Some(1024),
+            ..WasmLimits::default()
+        };
+        let run = execute_module(
+            br#"(module (func (export "_start") (loop (br 0))))"#,
+            Vec::new(),
+            &dirs,
+            grants,
+            limits,
+        )
+        .unwrap();
+        assert_eq!(run.exit, WasmExit::OutputExceeded);
+    }
+}
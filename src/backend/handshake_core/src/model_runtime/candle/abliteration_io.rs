@@ -11,6 +11,16 @@ pub fn run_abliteration_model_io(
     config: &AbliterationConfig,
     direction: &[f32],
 ) -> Result<Vec<String>, AbliterationError> {
+    // Orthogonalising needs dense F32 weights; re-quantizing the edited
+    // matrices would silently change every other block too.
+    if super::gguf_metadata::artifact_is_gguf(&config.base_model_path) {
+        return Err(AbliterationError::WeightTransform(format!(
+            "{} is a quantized GGUF checkpoint; abliterate the full-precision safetensors \
+             and quantize the result instead",
+            config.base_model_path.display()
+        )));
+    }
+
     let device = candle_core::Device::Cpu;
     let input_tensors =
         candle_core::safetensors::load(&config.base_model_path, &device).map_err(|err| {
@@ -22,6 +22,8 @@ use super::{
 #[cfg(feature = "candle-runtime-engine")]
 use super::{
     generate::{candle_generate_stream, CandleGenerationCodec, TokenizerGenerationCodec},
+    gguf_metadata::{artifact_is_gguf, read_gguf_metadata},
+    instrumented_decoder::artifact_config_decoder_architecture,
     mamba2::{artifact_config_declares_mamba2, CandleMamba2Model},
     rwkv_v5::{
         artifact_config_declares_rwkv_v5, artifact_config_declares_unversioned_rwkv,
@@ -31,7 +33,7 @@ use super::{
     rwkv_v7::{artifact_config_declares_rwkv_v7, CandleRwkvV7Model},
     score_embed::{candle_embed_tokens, candle_score_sequence},
     ssm_state::{LockedSsmStateSource, SsmStateSource},
-    transformer::{CandleLlamaModel, TransformerModel},
+    transformer::{CandleDecoderModel, CandleLlamaModel, TransformerModel},
 };
 use crate::model_runtime::{
     CancellationToken, Embedding, GenerateRequest, HookPoint, KvCacheHandle, KvQuantSupport,
@@ -80,6 +82,44 @@ impl CandleRuntime {
             .ok_or_else(|| ModelRuntimeError::LoadError(Self::not_loaded_message(id)))
     }
 
+    /// Chat template carried by the artifact itself (GGUF `tokenizer.chat_template`).
+    pub fn chat_template(&self, id: ModelId) -> Result<Option<String>, ModelRuntimeError> {
+        self.models
+            .get(&id)
+            .map(|handle| handle.chat_template.clone())
+            .ok_or_else(|| ModelRuntimeError::LoadError(Self::not_loaded_message(id)))
+    }
+
+    /// Token ids of `prompt` under the model's tokenizer.
+    pub fn tokenize_prompt(
+        &self,
+        id: ModelId,
+        prompt: &str,
+    ) -> Result<Vec<u32>, ModelRuntimeError> {
+        if !self.models.contains_key(&id) {
+            return Err(ModelRuntimeError::LoadError(Self::not_loaded_message(id)));
+        }
+
+        #[cfg(any(feature = "tokenization", feature = "candle-runtime-engine"))]
+        {
+            let tokenizer = self.tokenizer_cache.get(&id).ok_or_else(|| {
+                ModelRuntimeError::LoadError(format!(
+                    "candle tokenizer is not loaded for model {id}"
+                ))
+            })?;
+            let encoding = tokenizer.encode(prompt, true).map_err(|error| {
+                ModelRuntimeError::GenerateError(format!("Candle tokenizer encode failed: {error}"))
+            })?;
+            Ok(encoding.get_ids().to_vec())
+        }
+
+        #[cfg(not(any(feature = "tokenization", feature = "candle-runtime-engine")))]
+        {
+            let _ = prompt;
+            Err(Self::not_implemented("candle_tokenize"))
+        }
+    }
+
     fn not_loaded_message(id: ModelId) -> String {
         format!("candle model is not loaded: {id}")
     }
@@ -97,6 +137,47 @@ impl CandleRuntime {
         std::any::type_name::<candle_transformers::generation::LogitsProcessor>()
     }
 
+    #[cfg(feature = "candle-runtime-engine")]
+    fn load_gguf(
+        &mut self,
+        id: ModelId,
+        spec: &LoadSpec,
+        started: Instant,
+        tokenizer_path: std::path::PathBuf,
+    ) -> Result<(), ModelRuntimeError> {
+        let metadata = read_gguf_metadata(&spec.artifact_path)?;
+        // A sibling tokenizer.json wins; otherwise rebuild from the header.
+        if !self.tokenizer_cache.contains_key(&id) {
+            let tokenizer = metadata.tokenizer.build_tokenizer()?;
+            self.tokenizer_cache.insert(id, Arc::new(tokenizer));
+        }
+        let model = CandleDecoderModel::load_gguf_for_model(
+            id,
+            &spec.artifact_path,
+            &metadata,
+            &self.native_device,
+        )?;
+        let residual_width = model.hidden_dim() as usize;
+        let boxed: Box<dyn TransformerModel> = Box::new(model);
+        self.models.insert(
+            id,
+            CandleModelHandle {
+                backend: CandleModelBackend::Transformer {
+                    model: Arc::new(Mutex::new(boxed)),
+                },
+                declared_capabilities: candle_quantized_capabilities(&spec.declared_capabilities),
+                cancel: CancellationToken::new(),
+                load_duration_ms: started.elapsed().as_millis().max(1),
+                tokenizer_path,
+                device_selection: self.device_selection.clone(),
+                steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
+                state_vector: None,
+                chat_template: metadata.chat_template,
+            },
+        );
+        Ok(())
+    }
+
     pub fn state_vector(&self, id: ModelId) -> Result<StateVectorHandle, ModelRuntimeError> {
         let handle = self
             .models
@@ -128,6 +209,7 @@ struct CandleModelHandle {
     device_selection: CandleDeviceSelection,
     steering_hooks: CandleSteeringHooks,
     state_vector: Option<StateVectorHandle>,
+    chat_template: Option<String>,
 }
 
 enum CandleModelBackend {
@@ -175,12 +257,18 @@ impl ModelRuntime for CandleRuntime {
             let tokenizer_path = tokenizer_json_path_for_artifact(&spec.artifact_path);
             let artifact_sha256 = spec.sha256_expected.trim().to_ascii_lowercase();
             let _ = self.native_binding_marker();
+            // GGUF carries its own config, so skip the config.json probes.
+            if artifact_is_gguf(&spec.artifact_path) {
+                self.load_gguf(id, &spec, started, tokenizer_path)?;
+                return Ok(id);
+            }
             let is_mamba2 = artifact_config_declares_mamba2(&spec.artifact_path)?;
             let is_rwkv_v7 = artifact_config_declares_rwkv_v7(&spec.artifact_path)?;
             let is_rwkv_v6 = artifact_config_declares_rwkv_v6(&spec.artifact_path)?;
             let is_rwkv_v5 = artifact_config_declares_rwkv_v5(&spec.artifact_path)?;
             let is_unversioned_rwkv =
                 artifact_config_declares_unversioned_rwkv(&spec.artifact_path)?;
+            let decoder_architecture = artifact_config_decoder_architecture(&spec.artifact_path)?;
             let load_duration_ms = started.elapsed().as_millis().max(1);
             if is_mamba2 {
                 let model = CandleMamba2Model::load_safetensors_for_model(
@@ -212,6 +300,7 @@ impl ModelRuntime for CandleRuntime {
                         device_selection: self.device_selection.clone(),
                         steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
                         state_vector: Some(state_vector),
+                        chat_template: None,
                     },
                 );
             } else if is_rwkv_v7 {
@@ -244,6 +333,7 @@ impl ModelRuntime for CandleRuntime {
                         device_selection: self.device_selection.clone(),
                         steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
                         state_vector: Some(state_vector),
+                        chat_template: None,
                     },
                 );
             } else if is_rwkv_v6 {
@@ -276,6 +366,7 @@ impl ModelRuntime for CandleRuntime {
                         device_selection: self.device_selection.clone(),
                         steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
                         state_vector: Some(state_vector),
+                        chat_template: None,
                     },
                 );
             } else if is_rwkv_v5 {
@@ -308,6 +399,7 @@ impl ModelRuntime for CandleRuntime {
                         device_selection: self.device_selection.clone(),
                         steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
                         state_vector: Some(state_vector),
+                        chat_template: None,
                     },
                 );
             } else if is_unversioned_rwkv {
@@ -316,17 +408,27 @@ impl ModelRuntime for CandleRuntime {
                         .to_string(),
                 ));
             } else {
-                let model = CandleLlamaModel::load_safetensors_for_model(
-                    id,
-                    &spec.artifact_path,
-                    &self.native_device,
-                )?;
+                // Qwen2 / Phi-3 / Gemma / Mistral go through the owned decoder
+                // forward; everything else keeps the Llama path.
+                let model: Box<dyn TransformerModel> = if decoder_architecture.is_some() {
+                    Box::new(CandleDecoderModel::load_safetensors_for_model(
+                        id,
+                        &spec.artifact_path,
+                        &self.native_device,
+                    )?)
+                } else {
+                    Box::new(CandleLlamaModel::load_safetensors_for_model(
+                        id,
+                        &spec.artifact_path,
+                        &self.native_device,
+                    )?)
+                };
                 let residual_width = model.hidden_dim() as usize;
                 self.models.insert(
                     id,
                     CandleModelHandle {
                         backend: CandleModelBackend::Transformer {
-                            model: Arc::new(Mutex::new(Box::new(model))),
+                            model: Arc::new(Mutex::new(model)),
                         },
                         declared_capabilities: candle_transformer_capabilities(
                             &spec.declared_capabilities,
@@ -337,6 +439,7 @@ impl ModelRuntime for CandleRuntime {
                         device_selection: self.device_selection.clone(),
                         steering_hooks: CandleSteeringHooks::new_for_model(id, residual_width),
                         state_vector: None,
+                        chat_template: None,
                     },
                 );
             }
@@ -678,6 +781,22 @@ pub fn candle_transformer_capabilities(declared: &ModelCapabilities) -> ModelCap
     }
 }
 
+pub fn candle_quantized_capabilities(declared: &ModelCapabilities) -> ModelCapabilities {
+    ModelCapabilities {
+        // GGUF weights stay block-quantized; LoRA adapters add an F32 delta on
+        // the projection output, so they mount exactly as on full precision.
+        supports_lora: true,
+        supports_kv_prefix_cache: false,
+        supports_kv_quantization: KvQuantSupport::None,
+        // Steering reads and writes the F32 residual stream between blocks,
+        // which dequantized matmul outputs feed unchanged.
+        supports_activation_steering: declared.supports_activation_steering,
+        supports_subquadratic: false,
+        supports_speculative_draft: false,
+        supports_eagle3: false,
+    }
+}
+
 pub fn candle_mamba2_capabilities(_declared: &ModelCapabilities) -> ModelCapabilities {
     ModelCapabilities {
         // MT-115: the owned Mamba2 forward (mamba2.rs) routes in_proj/out_proj
@@ -0,0 +1,660 @@
+#![cfg(feature = "candle-runtime-engine")]
+
+// GGUF header reading for the candle engine's quantized path.
+//
+// llama.cpp's own reader (`llama_cpp::gguf_loader`) only pulls the tokenizer
+// ids it needs to drive the native library. Candle has to rebuild the whole
+// model from the header, so this module maps the `general.architecture`
+// metadata onto the same `DecoderConfig` the safetensors decoders use,
+// validates that every tensor is in a block format candle can matmul, and
+// synthesises a `tokenizers::Tokenizer` plus chat template from the
+// `tokenizer.*` keys so a bare `.gguf` file is self-sufficient.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use candle_core::{
+    quantized::{gguf_file, GgmlDType},
+    Device,
+};
+use serde_json::{json, Value};
+
+use super::instrumented_decoder::{DecoderArchitecture, DecoderConfig};
+use crate::model_runtime::ModelRuntimeError;
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+// llama.cpp `llama_token_type` values carried in `tokenizer.ggml.token_type`.
+const TOKEN_TYPE_NORMAL: i32 = 1;
+const TOKEN_TYPE_UNKNOWN: i32 = 2;
+const TOKEN_TYPE_CONTROL: i32 = 3;
+const TOKEN_TYPE_USER_DEFINED: i32 = 4;
+
+/// What a GGUF header says about the model, normalised for the candle loader.
+#[derive(Debug, Clone)]
+pub struct GgufModelMetadata {
+    pub config: DecoderConfig,
+    pub name: Option<String>,
+    pub tokenizer: GgufTokenizerMetadata,
+    pub chat_template: Option<String>,
+    /// Tensor count per GGML storage type, e.g. `{"q4k": 193, "q6k": 33, "f32": 65}`.
+    pub weight_types: BTreeMap<String, usize>,
+    pub eos_token_ids: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GgufTokenizerMetadata {
+    /// `tokenizer.ggml.model`: `llama` (SentencePiece BPE) or `gpt2` (byte-level BPE).
+    pub model: String,
+    pub tokens: Vec<String>,
+    pub scores: Vec<f32>,
+    pub token_types: Vec<i32>,
+    pub merges: Vec<String>,
+    pub bos_token_id: Option<u32>,
+    pub eos_token_id: Option<u32>,
+    pub unk_token_id: Option<u32>,
+    pub add_bos_token: bool,
+}
+
+/// True when the artifact starts with the GGUF magic. Short or unreadable
+/// files are simply "not GGUF"; the safetensors path reports its own errors.
+pub fn artifact_is_gguf(path: &Path) -> bool {
+    let mut magic = [0_u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| &magic == GGUF_MAGIC)
+}
+
+pub fn open_gguf(path: &Path) -> Result<(gguf_file::Content, BufReader<File>), ModelRuntimeError> {
+    let file = File::open(path).map_err(|error| {
+        ModelRuntimeError::LoadError(format!(
+            "failed to open Candle GGUF {}: {error}",
+            path.display()
+        ))
+    })?;
+    let mut reader = BufReader::new(file);
+    let content = gguf_file::Content::read(&mut reader).map_err(|error| {
+        ModelRuntimeError::LoadError(format!(
+            "failed to parse Candle GGUF header {}: {error}",
+            path.display()
+        ))
+    })?;
+    Ok((content, reader))
+}
+
+pub fn read_gguf_metadata(path: &Path) -> Result<GgufModelMetadata, ModelRuntimeError> {
+    let (content, mut reader) = open_gguf(path)?;
+    GgufModelMetadata::from_content(&content, &mut reader, path)
+}
+
+impl GgufModelMetadata {
+    pub fn from_content<R: Read + Seek>(
+        content: &gguf_file::Content,
+        reader: &mut R,
+        path: &Path,
+    ) -> Result<Self, ModelRuntimeError> {
+        let meta = GgufMeta(content);
+        let declared = meta.str("general.architecture").unwrap_or_default();
+        // convert_hf_to_gguf writes Mistral checkpoints as `llama`.
+        let architecture = match declared {
+            "llama" => DecoderArchitecture::Llama,
+            "qwen2" => DecoderArchitecture::Qwen2,
+            "gemma" => DecoderArchitecture::Gemma,
+            "phi3" => DecoderArchitecture::Phi3,
+            other => {
+                return Err(ModelRuntimeError::LoadError(format!(
+                    "Candle GGUF loading supports llama (incl. Mistral), qwen2, gemma, and phi3; {} declares `{other}`",
+                    path.display()
+                )))
+            }
+        };
+        let weight_types = validate_weight_types(content, path)?;
+        let tokenizer = GgufTokenizerMetadata::from_meta(&meta, architecture);
+        let config = decoder_config(&meta, content, reader, architecture, &tokenizer, path)?;
+        let mut eos_token_ids = tokenizer.eos_token_id.into_iter().collect::<Vec<_>>();
+        // Chat-tuned GGUFs mark end-of-turn separately (`<|im_end|>`, `<|eot_id|>`).
+        if let Some(eot) = meta.u64("tokenizer.ggml.eot_token_id") {
+            if !eos_token_ids.contains(&(eot as u32)) {
+                eos_token_ids.push(eot as u32);
+            }
+        }
+        Ok(Self {
+            config,
+            name: meta.str("general.name").map(str::to_string),
+            chat_template: meta.str("tokenizer.chat_template").map(str::to_string),
+            tokenizer,
+            weight_types,
+            eos_token_ids,
+        })
+    }
+}
+
+fn decoder_config<R: Read + Seek>(
+    meta: &GgufMeta<'_>,
+    content: &gguf_file::Content,
+    reader: &mut R,
+    architecture: DecoderArchitecture,
+    tokenizer: &GgufTokenizerMetadata,
+    path: &Path,
+) -> Result<DecoderConfig, ModelRuntimeError> {
+    let arch = architecture.as_str();
+    let required = |key: &str| {
+        meta.u64(&format!("{arch}.{key}"))
+            .map(|value| value as usize)
+            .ok_or_else(|| {
+                ModelRuntimeError::LoadError(format!(
+                    "Candle GGUF {} is missing `{arch}.{key}`",
+                    path.display()
+                ))
+            })
+    };
+    let hidden_size = required("embedding_length")?;
+    let num_attention_heads = required("attention.head_count")?;
+    let num_key_value_heads = meta
+        .u64(&format!("{arch}.attention.head_count_kv"))
+        .map_or(num_attention_heads, |value| value as usize);
+    let head_dim = meta
+        .u64(&format!("{arch}.attention.key_length"))
+        .map_or(hidden_size / num_attention_heads.max(1), |value| {
+            value as usize
+        });
+    let rope_dims = meta
+        .u64(&format!("{arch}.rope.dimension_count"))
+        .map_or(head_dim, |value| value as usize);
+    if rope_dims != head_dim {
+        return Err(ModelRuntimeError::LoadError(format!(
+            "Candle GGUF {}: partial rotary ({rope_dims} of {head_dim} dims) is not supported",
+            path.display()
+        )));
+    }
+    if meta
+        .str(&format!("{arch}.rope.scaling.type"))
+        .is_some_and(|kind| kind != "none")
+        || content
+            .tensor_infos
+            .contains_key("rope_factors_long.weight")
+    {
+        return Err(ModelRuntimeError::LoadError(format!(
+            "Candle GGUF {}: RoPE context scaling (linear/YaRN/LongRoPE) is not supported",
+            path.display()
+        )));
+    }
+    let rope_freq_factors = if content.tensor_infos.contains_key("rope_freqs.weight") {
+        let factors = content
+            .tensor(reader, "rope_freqs.weight", &Device::Cpu)
+            .and_then(|tensor| tensor.dequantize(&Device::Cpu))
+            .and_then(|tensor| tensor.flatten_all())
+            .and_then(|tensor| tensor.to_vec1::<f32>())
+            .map_err(|error| {
+                ModelRuntimeError::LoadError(format!(
+                    "Candle GGUF {}: failed to read rope_freqs: {error}",
+                    path.display()
+                ))
+            })?;
+        Some(factors)
+    } else {
+        None
+    };
+    let vocab_size = meta
+        .u64(&format!("{arch}.vocab_size"))
+        .map(|value| value as usize)
+        .or_else(|| (!tokenizer.tokens.is_empty()).then_some(tokenizer.tokens.len()))
+        .or_else(|| {
+            content
+                .tensor_infos
+                .get("token_embd.weight")
+                .and_then(|info| info.shape.dims().first().copied())
+        })
+        .ok_or_else(|| {
+            ModelRuntimeError::LoadError(format!(
+                "Candle GGUF {} does not declare a vocabulary size",
+                path.display()
+            ))
+        })?;
+    let sliding_window = match architecture {
+        DecoderArchitecture::Qwen2 | DecoderArchitecture::Phi3 => meta
+            .u64(&format!("{arch}.attention.sliding_window"))
+            .map(|value| value as usize),
+        DecoderArchitecture::Llama | DecoderArchitecture::Gemma | DecoderArchitecture::Mistral => {
+            None
+        }
+    };
+    Ok(DecoderConfig {
+        architecture,
+        vocab_size,
+        hidden_size,
+        intermediate_size: required("feed_forward_length")?,
+        num_hidden_layers: required("block_count")?,
+        num_attention_heads,
+        num_key_value_heads,
+        head_dim,
+        max_position_embeddings: meta
+            .u64(&format!("{arch}.context_length"))
+            .map_or(4096, |value| value as usize),
+        rms_norm_eps: meta
+            .f64(&format!("{arch}.attention.layer_norm_rms_epsilon"))
+            .unwrap_or(1e-5),
+        rope_theta: meta
+            .f64(&format!("{arch}.rope.freq_base"))
+            .unwrap_or(10_000.0) as f32,
+        attention_bias: content.tensor_infos.contains_key("blk.0.attn_q.bias"),
+        tie_word_embeddings: !content.tensor_infos.contains_key("output.weight"),
+        sliding_window,
+        rope_freq_factors,
+    })
+}
+
+/// Rejects storage types candle cannot matmul, so a load fails up front
+/// instead of on the first forward. Q6_K is accepted because the common
+/// Q4_K_M / Q5_K_M mixes keep a few tensors in it.
+fn validate_weight_types(
+    content: &gguf_file::Content,
+    path: &Path,
+) -> Result<BTreeMap<String, usize>, ModelRuntimeError> {
+    let mut weight_types = BTreeMap::new();
+    let mut names = content.tensor_infos.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let dtype = content.tensor_infos[name].ggml_dtype;
+        let supported = matches!(
+            dtype,
+            GgmlDType::F32
+                | GgmlDType::F16
+                | GgmlDType::BF16
+                | GgmlDType::Q4_0
+                | GgmlDType::Q4_1
+                | GgmlDType::Q4K
+                | GgmlDType::Q5_0
+                | GgmlDType::Q5_1
+                | GgmlDType::Q5K
+                | GgmlDType::Q6K
+                | GgmlDType::Q8_0
+        );
+        if !supported {
+            return Err(ModelRuntimeError::LoadError(format!(
+                "Candle GGUF {}: tensor `{name}` is stored as {dtype:?}; supported weight types are Q4_0/Q4_1/Q4_K, Q5_0/Q5_1/Q5_K, Q6_K, Q8_0, and F16/BF16/F32",
+                path.display()
+            )));
+        }
+        *weight_types
+            .entry(format!("{dtype:?}").to_ascii_lowercase())
+            .or_insert(0) += 1;
+    }
+    Ok(weight_types)
+}
+
+impl GgufTokenizerMetadata {
+    fn from_meta(meta: &GgufMeta<'_>, architecture: DecoderArchitecture) -> Self {
+        let model = meta
+            .str("tokenizer.ggml.model")
+            .unwrap_or_default()
+            .to_string();
+        let strings = |key: &str| {
+            meta.array(key)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| match value {
+                            gguf_file::Value::String(text) => Some(text.clone()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        let scores = meta
+            .array("tokenizer.ggml.scores")
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(value_f64)
+                    .map(|v| v as f32)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let token_types = meta
+            .array("tokenizer.ggml.token_type")
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(value_u64_signed)
+                    .map(|v| v as i32)
+                    .collect()
+            })
+            .unwrap_or_default();
+        // SentencePiece models prepend BOS unless told otherwise; byte-level
+        // BPE models (Qwen2) do not.
+        let add_bos_default = model == "llama" && architecture != DecoderArchitecture::Qwen2;
+        Self {
+            tokens: strings("tokenizer.ggml.tokens"),
+            merges: strings("tokenizer.ggml.merges"),
+            scores,
+            token_types,
+            bos_token_id: meta.u64("tokenizer.ggml.bos_token_id").map(|id| id as u32),
+            eos_token_id: meta.u64("tokenizer.ggml.eos_token_id").map(|id| id as u32),
+            unk_token_id: meta
+                .u64("tokenizer.ggml.unknown_token_id")
+                .map(|id| id as u32),
+            add_bos_token: meta
+                .bool("tokenizer.ggml.add_bos_token")
+                .unwrap_or(add_bos_default),
+            model,
+        }
+    }
+
+    fn token_type(&self, id: usize) -> i32 {
+        self.token_types
+            .get(id)
+            .copied()
+            .unwrap_or(TOKEN_TYPE_NORMAL)
+    }
+
+    /// The header's vocabulary rendered as a Hugging Face `tokenizer.json`
+    /// document, the same format a sibling `tokenizer.json` would carry.
+    pub fn tokenizer_json(&self) -> Result<Value, ModelRuntimeError> {
+        if self.tokens.is_empty() {
+            return Err(ModelRuntimeError::LoadError(
+                "GGUF header carries no tokenizer.ggml.tokens; place a tokenizer.json next to the model"
+                    .to_string(),
+            ));
+        }
+        let mut vocab = serde_json::Map::new();
+        let mut ids = HashMap::new();
+        for (id, token) in self.tokens.iter().enumerate() {
+            if !vocab.contains_key(token) {
+                vocab.insert(token.clone(), json!(id));
+                ids.insert(token.as_str(), id);
+            }
+        }
+        let added_tokens = self
+            .tokens
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| {
+                matches!(
+                    self.token_type(*id),
+                    TOKEN_TYPE_UNKNOWN | TOKEN_TYPE_CONTROL | TOKEN_TYPE_USER_DEFINED
+                )
+            })
+            .map(|(id, token)| {
+                json!({
+                    "id": id,
+                    "content": token,
+                    "single_word": false,
+                    "lstrip": false,
+                    "rstrip": false,
+                    "normalized": false,
+                    "special": self.token_type(id) != TOKEN_TYPE_USER_DEFINED,
+                })
+            })
+            .collect::<Vec<_>>();
+        let token_text = |id: Option<u32>| id.and_then(|id| self.tokens.get(id as usize)).cloned();
+        let post_processor = match token_text(self.bos_token_id).filter(|_| self.add_bos_token) {
+            Some(bos) => {
+                let mut special_tokens = serde_json::Map::new();
+                special_tokens.insert(
+                    bos.clone(),
+                    json!({"id": bos, "ids": [self.bos_token_id], "tokens": [bos]}),
+                );
+                json!({
+                    "type": "TemplateProcessing",
+                    "single": [
+                        {"SpecialToken": {"id": bos, "type_id": 0}},
+                        {"Sequence": {"id": "A", "type_id": 0}}
+                    ],
+                    "pair": [
+                        {"SpecialToken": {"id": bos, "type_id": 0}},
+                        {"Sequence": {"id": "A", "type_id": 0}},
+                        {"SpecialToken": {"id": bos, "type_id": 1}},
+                        {"Sequence": {"id": "B", "type_id": 1}}
+                    ],
+                    "special_tokens": special_tokens,
+                })
+            }
+            None => Value::Null,
+        };
+
+        let (normalizer, pre_tokenizer, decoder, merges, byte_fallback) =
+            match self.model.as_str() {
+                "llama" => (
+                    json!({"type": "Sequence", "normalizers": [
+                        {"type": "Prepend", "prepend": "\u{2581}"},
+                        {"type": "Replace", "pattern": {"String": " "}, "content": "\u{2581}"}
+                    ]}),
+                    Value::Null,
+                    json!({"type": "Sequence", "decoders": [
+                        {"type": "Replace", "pattern": {"String": "\u{2581}"}, "content": " "},
+                        {"type": "ByteFallback"},
+                        {"type": "Fuse"},
+                        {"type": "Strip", "content": " ", "start": 1, "stop": 0}
+                    ]}),
+                    self.sentencepiece_merges(&ids),
+                    true,
+                ),
+                "gpt2" => (
+                    Value::Null,
+                    json!({"type": "ByteLevel", "add_prefix_space": false, "trim_offsets": true, "use_regex": true}),
+                    json!({"type": "ByteLevel", "add_prefix_space": true, "trim_offsets": true, "use_regex": true}),
+                    self.merges.clone(),
+                    false,
+                ),
+                other => {
+                    return Err(ModelRuntimeError::LoadError(format!(
+                        "GGUF tokenizer model `{other}` cannot be rebuilt for candle; place a tokenizer.json next to the model"
+                    )))
+                }
+            };
+
+        Ok(json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": added_tokens,
+            "normalizer": normalizer,
+            "pre_tokenizer": pre_tokenizer,
+            "post_processor": post_processor,
+            "decoder": decoder,
+            "model": {
+                "type": "BPE",
+                "dropout": null,
+                "unk_token": token_text(self.unk_token_id),
+                "continuing_subword_prefix": null,
+                "end_of_word_suffix": null,
+                "fuse_unk": byte_fallback,
+                "byte_fallback": byte_fallback,
+                "vocab": vocab,
+                "merges": merges,
+            }
+        }))
+    }
+
+    pub fn build_tokenizer(&self) -> Result<tokenizers::Tokenizer, ModelRuntimeError> {
+        let document = serde_json::to_vec(&self.tokenizer_json()?).map_err(|error| {
+            ModelRuntimeError::LoadError(format!("GGUF tokenizer serialisation failed: {error}"))
+        })?;
+        tokenizers::Tokenizer::from_bytes(document).map_err(|error| {
+            ModelRuntimeError::LoadError(format!("GGUF tokenizer could not be rebuilt: {error}"))
+        })
+    }
+
+    /// SentencePiece GGUFs ship scores, not merges. Recover the merge list the
+    /// way Hugging Face's converter does: every split of a normal token into
+    /// two in-vocabulary pieces is a merge, ranked by the merged token's score.
+    fn sentencepiece_merges(&self, ids: &HashMap<&str, usize>) -> Vec<String> {
+        let mut merges = Vec::new();
+        for (id, token) in self.tokens.iter().enumerate() {
+            if self.token_type(id) != TOKEN_TYPE_NORMAL || token.contains(' ') {
+                continue;
+            }
+            let score = self.scores.get(id).copied().unwrap_or(0.0);
+            for (split, _) in token.char_indices().skip(1) {
+                let (left, right) = token.split_at(split);
+                if ids.contains_key(left) && ids.contains_key(right) {
+                    merges.push((score, id, format!("{left} {right}")));
+                }
+            }
+        }
+        merges.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        merges.into_iter().map(|(_, _, merge)| merge).collect()
+    }
+}
+
+struct GgufMeta<'a>(&'a gguf_file::Content);
+
+impl GgufMeta<'_> {
+    fn get(&self, key: &str) -> Option<&gguf_file::Value> {
+        self.0.metadata.get(key)
+    }
+
+    fn str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            gguf_file::Value::String(text) => Some(text.as_str()),
+            _ => None,
+        }
+    }
+
+    fn u64(&self, key: &str) -> Option<u64> {
+        self.get(key)
+            .and_then(value_u64_signed)
+            .and_then(|v| u64::try_from(v).ok())
+    }
+
+    fn f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(value_f64)
+    }
+
+    fn bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            gguf_file::Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn array(&self, key: &str) -> Option<&Vec<gguf_file::Value>> {
+        match self.get(key)? {
+            gguf_file::Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+fn value_u64_signed(value: &gguf_file::Value) -> Option<i64> {
+    use gguf_file::Value as V;
+    match value {
+        V::U8(v) => Some(i64::from(*v)),
+        V::I8(v) => Some(i64::from(*v)),
+        V::U16(v) => Some(i64::from(*v)),
+        V::I16(v) => Some(i64::from(*v)),
+        V::U32(v) => Some(i64::from(*v)),
+        V::I32(v) => Some(i64::from(*v)),
+        V::U64(v) => i64::try_from(*v).ok(),
+        V::I64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn value_f64(value: &gguf_file::Value) -> Option<f64> {
+    match value {
+        gguf_file::Value::F32(v) => Some(f64::from(*v)),
+        gguf_file::Value::F64(v) => Some(*v),
+        other => value_u64_signed(other).map(|v| v as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spm_tokenizer() -> GgufTokenizerMetadata {
+        let tokens = [
+            "<unk>",
+            "<s>",
+            "</s>",
+            "\u{2581}",
+            "h",
+            "e",
+            "l",
+            "o",
+            "\u{2581}h",
+            "he",
+            "ll",
+            "lo",
+            "\u{2581}he",
+            "llo",
+            "\u{2581}hello",
+        ];
+        let mut token_types = vec![TOKEN_TYPE_NORMAL; tokens.len()];
+        token_types[0] = TOKEN_TYPE_UNKNOWN;
+        token_types[1] = TOKEN_TYPE_CONTROL;
+        token_types[2] = TOKEN_TYPE_CONTROL;
+        GgufTokenizerMetadata {
+            model: "llama".to_string(),
+            scores: (0..tokens.len()).map(|id| -(id as f32)).collect(),
+            tokens: tokens.iter().map(|token| token.to_string()).collect(),
+            token_types,
+            merges: Vec::new(),
+            bos_token_id: Some(1),
+            eos_token_id: Some(2),
+            unk_token_id: Some(0),
+            add_bos_token: true,
+        }
+    }
+
+    #[test]
+    fn sentencepiece_header_rebuilds_a_working_tokenizer() {
+        let metadata = spm_tokenizer();
+        let tokenizer = metadata.build_tokenizer().expect("tokenizer rebuilds");
+
+        let encoding = tokenizer.encode("hello", true).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 14]);
+        assert_eq!(tokenizer.decode(&[14], true).unwrap(), "hello");
+        // Control tokens are special: never produced from text, skipped on decode.
+        assert_eq!(tokenizer.decode(&[1, 14, 2], true).unwrap(), "hello");
+    }
+
+    #[test]
+    fn byte_level_header_uses_shipped_merges_without_bos() {
+        let tokens = [
+            "h",
+            "e",
+            "l",
+            "o",
+            "\u{120}",
+            "he",
+            "ll",
+            "hell",
+            "hello",
+            "\u{120}hello",
+        ];
+        let metadata = GgufTokenizerMetadata {
+            model: "gpt2".to_string(),
+            tokens: tokens.iter().map(|token| token.to_string()).collect(),
+            merges: ["h e", "l l", "he ll", "hell o", "\u{120} hello"]
+                .iter()
+                .map(|merge| merge.to_string())
+                .collect(),
+            add_bos_token: false,
+            ..GgufTokenizerMetadata::default()
+        };
+        let tokenizer = metadata.build_tokenizer().expect("tokenizer rebuilds");
+
+        let encoding = tokenizer.encode("hello hello", true).unwrap();
+        assert_eq!(encoding.get_ids(), &[8, 9]);
+        assert_eq!(tokenizer.decode(&[8, 9], true).unwrap(), "hello hello");
+    }
+
+    #[test]
+    fn unknown_tokenizer_models_are_rejected_with_a_fix() {
+        let metadata = GgufTokenizerMetadata {
+            model: "bert".to_string(),
+            tokens: vec!["[CLS]".to_string()],
+            ..GgufTokenizerMetadata::default()
+        };
+        let err = metadata.build_tokenizer().unwrap_err();
+        assert!(err.to_string().contains("tokenizer.json"), "{err}");
+    }
+}
@@ -0,0 +1,1485 @@
+#![cfg(feature = "candle-runtime-engine")]
+
+// Handshake-owned forward for the pre-norm decoder families that share the
+// Llama block shape but differ in the details: Qwen2, Phi-3, Gemma, Mistral.
+//
+// Adapted from candle-transformers 0.10.2 `src/models/{qwen2,phi3,gemma,mistral}.rs`.
+// Upstream keeps each block loop private, so — exactly as instrumented_llama.rs
+// does for Llama — this module re-implements the forward with repo-owned
+// projections and the same two seams:
+//   - LoRA: every projection output goes through
+//     `CandleLoraStack::apply_to_linear_output(...)` under its PEFT module name
+//     (Phi-3 exposes its fused `qkv_proj` / `gate_up_proj` as single targets).
+//   - Steering: after each block the residual stream passes through
+//     `CandleSteeringHooks::apply_record_and_capture_tensor(layer, ResidStream, ...)`.
+//
+// Per-family differences are carried by `DecoderConfig` rather than by four
+// copies of the block:
+//   - Qwen2: q/k/v projections carry a bias; sliding window only when
+//     `use_sliding_window` is set.
+//   - Phi-3: fused qkv and gate/up projections; plain RoPE only (LongRoPE
+//     scaling and partial rotary are rejected at load).
+//   - Gemma: GeLU(tanh) MLP, `(1 + weight)` RMSNorm, embeddings scaled by
+//     sqrt(hidden), explicit `head_dim`, always-tied `lm_head`.
+//   - Mistral: optional explicit `head_dim` and sliding-window attention.
+//
+// The same blocks also serve block-quantized GGUF checkpoints
+// (`InstrumentedDecoder::load_gguf`): projections become `QMatMul`s while
+// embeddings and norms are dequantized, so activations, LoRA deltas, and
+// steering stay F32. llama.cpp permutes Llama q/k rows for its interleaved
+// RoPE; the loader undoes that on the projection output so the forward, the
+// LoRA targets, and the steering basis are identical to the safetensors path.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Read, Seek},
+    path::Path,
+};
+
+use candle_core::{
+    quantized::{gguf_file, QMatMul, QTensor},
+    DType, Device, IndexOp, Module, Tensor,
+};
+use candle_nn::{embedding, Embedding, Init, RmsNorm, VarBuilder};
+use candle_transformers::{
+    models::with_tracing::{linear_b, Linear},
+    utils::repeat_kv,
+};
+use serde_json::Value;
+
+use super::{
+    hooks::CandleSteeringHooks, lora_impl::CandleLoraStack,
+    transformer::config_json_path_for_artifact,
+};
+use crate::model_runtime::{HookPoint, LayerIndex, LoraId, ModelRuntimeError, SteeringVectorId};
+
+/// Decoder families served by [`InstrumentedDecoder`]. Llama safetensors stay
+/// on `InstrumentedLlama`; `Llama` only reaches this forward from a GGUF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecoderArchitecture {
+    Qwen2,
+    Phi3,
+    Gemma,
+    Mistral,
+    Llama,
+}
+
+impl DecoderArchitecture {
+    /// Families detected from a safetensors `config.json`.
+    pub const ALL: [Self; 4] = [Self::Qwen2, Self::Phi3, Self::Gemma, Self::Mistral];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Qwen2 => "qwen2",
+            Self::Phi3 => "phi3",
+            Self::Gemma => "gemma",
+            Self::Mistral => "mistral",
+            Self::Llama => "llama",
+        }
+    }
+
+    /// Base-model tag LoRA descriptors must declare to mount on this family.
+    pub fn lora_base_model(self) -> &'static str {
+        match self {
+            Self::Qwen2 => "candle-qwen2",
+            Self::Phi3 => "candle-phi3",
+            Self::Gemma => "candle-gemma",
+            Self::Mistral => "candle-mistral",
+            Self::Llama => "candle-llama",
+        }
+    }
+
+    pub fn lora_targets(self, num_layers: usize) -> Vec<String> {
+        match self {
+            Self::Phi3 => CandleLoraStack::available_phi3_targets(num_layers),
+            Self::Qwen2 | Self::Gemma | Self::Mistral | Self::Llama => {
+                CandleLoraStack::available_llama_targets(num_layers)
+            }
+        }
+    }
+
+    fn architecture_class(self) -> &'static str {
+        match self {
+            Self::Qwen2 => "qwen2forcausallm",
+            Self::Phi3 => "phi3forcausallm",
+            Self::Gemma => "gemmaforcausallm",
+            Self::Mistral => "mistralforcausallm",
+            Self::Llama => "llamaforcausallm",
+        }
+    }
+
+    /// Detects the family from a Hugging Face `config.json`: an exact
+    /// `model_type` match, or a `*ForCausalLM` entry in `architectures`.
+    /// Later generations (`qwen2_moe`, `gemma2`, `phi3small`, ...) change the
+    /// block shape and deliberately do not match.
+    pub fn from_config_value(value: &Value) -> Option<Self> {
+        let model_type = value
+            .get("model_type")
+            .and_then(Value::as_str)
+            .map(str::to_ascii_lowercase);
+        if let Some(found) = Self::ALL
+            .into_iter()
+            .find(|architecture| model_type.as_deref() == Some(architecture.as_str()))
+        {
+            return Some(found);
+        }
+        let architectures = value
+            .get("architectures")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>();
+        Self::ALL.into_iter().find(|architecture| {
+            architectures
+                .iter()
+                .any(|class| class == architecture.architecture_class())
+        })
+    }
+}
+
+/// Normalised view of the per-family `config.json` fields the forward needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecoderConfig {
+    pub architecture: DecoderArchitecture,
+    pub vocab_size: usize,
+    pub hidden_size: usize,
+    pub intermediate_size: usize,
+    pub num_hidden_layers: usize,
+    pub num_attention_heads: usize,
+    pub num_key_value_heads: usize,
+    pub head_dim: usize,
+    pub max_position_embeddings: usize,
+    pub rms_norm_eps: f64,
+    pub rope_theta: f32,
+    pub attention_bias: bool,
+    pub tie_word_embeddings: bool,
+    pub sliding_window: Option<usize>,
+    /// Per-frequency RoPE divisors (llama.cpp `rope_freqs`, used by Llama 3.1
+    /// style context extension). Only GGUF checkpoints carry them.
+    pub rope_freq_factors: Option<Vec<f32>>,
+}
+
+impl DecoderConfig {
+    pub fn from_config_value(
+        architecture: DecoderArchitecture,
+        value: &Value,
+    ) -> Result<Self, ModelRuntimeError> {
+        let family = architecture.as_str();
+        let hidden_size = required_usize(value, family, "hidden_size")?;
+        let num_attention_heads = required_usize(value, family, "num_attention_heads")?;
+        let num_key_value_heads =
+            optional_usize(value, "num_key_value_heads").unwrap_or(num_attention_heads);
+        if num_attention_heads == 0
+            || num_key_value_heads == 0
+            || !num_attention_heads.is_multiple_of(num_key_value_heads)
+        {
+            return Err(config_error(
+                family,
+                format!(
+                    "num_attention_heads {num_attention_heads} must be a non-zero multiple of num_key_value_heads {num_key_value_heads}"
+                ),
+            ));
+        }
+        let head_dim =
+            optional_usize(value, "head_dim").unwrap_or(hidden_size / num_attention_heads);
+        if head_dim == 0 || !head_dim.is_multiple_of(2) {
+            return Err(config_error(
+                family,
+                format!("head_dim {head_dim} must be a non-zero even number for RoPE"),
+            ));
+        }
+        if value
+            .get("rope_scaling")
+            .is_some_and(|scaling| !scaling.is_null())
+        {
+            return Err(config_error(
+                family,
+                "rope_scaling is not supported by the instrumented decoder".to_string(),
+            ));
+        }
+        if value
+            .get("partial_rotary_factor")
+            .and_then(Value::as_f64)
+            .is_some_and(|factor| (factor - 1.0).abs() > f64::EPSILON)
+        {
+            return Err(config_error(
+                family,
+                "partial_rotary_factor other than 1.0 is not supported".to_string(),
+            ));
+        }
+        let activation = value
+            .get("hidden_activation")
+            .or_else(|| value.get("hidden_act"))
+            .and_then(Value::as_str);
+        let activation_ok = match architecture {
+            // HF Gemma configs say "gelu" but the reference forward always
+            // runs the tanh approximation.
+            DecoderArchitecture::Gemma => {
+                activation.is_none_or(|act| matches!(act, "gelu" | "gelu_pytorch_tanh"))
+            }
+            _ => activation.is_none_or(|act| act == "silu"),
+        };
+        if !activation_ok {
+            return Err(config_error(
+                family,
+                format!("unsupported hidden activation {activation:?}"),
+            ));
+        }
+
+        let sliding_window = match architecture {
+            DecoderArchitecture::Qwen2 => value
+                .get("use_sliding_window")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+                .then(|| optional_usize(value, "sliding_window"))
+                .flatten(),
+            DecoderArchitecture::Phi3 | DecoderArchitecture::Mistral => {
+                optional_usize(value, "sliding_window")
+            }
+            DecoderArchitecture::Gemma | DecoderArchitecture::Llama => None,
+        };
+        let attention_bias = match architecture {
+            DecoderArchitecture::Qwen2 => true,
+            DecoderArchitecture::Gemma => optional_bool(value, "attention_bias").unwrap_or(false),
+            DecoderArchitecture::Phi3
+            | DecoderArchitecture::Mistral
+            | DecoderArchitecture::Llama => false,
+        };
+        let tie_word_embeddings = match architecture {
+            DecoderArchitecture::Gemma => true,
+            _ => optional_bool(value, "tie_word_embeddings").unwrap_or(false),
+        };
+        let default_eps = match architecture {
+            DecoderArchitecture::Phi3 => 1e-5,
+            _ => 1e-6,
+        };
+
+        Ok(Self {
+            architecture,
+            vocab_size: required_usize(value, family, "vocab_size")?,
+            hidden_size,
+            intermediate_size: required_usize(value, family, "intermediate_size")?,
+            num_hidden_layers: required_usize(value, family, "num_hidden_layers")?,
+            num_attention_heads,
+            num_key_value_heads,
+            head_dim,
+            max_position_embeddings: optional_usize(value, "max_position_embeddings")
+                .unwrap_or(4096),
+            rms_norm_eps: value
+                .get("rms_norm_eps")
+                .and_then(Value::as_f64)
+                .unwrap_or(default_eps),
+            rope_theta: value
+                .get("rope_theta")
+                .and_then(Value::as_f64)
+                .unwrap_or(10_000.0) as f32,
+            attention_bias,
+            tie_word_embeddings,
+            sliding_window,
+            rope_freq_factors: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InstrumentedDecoderCache {
+    masks: HashMap<(usize, usize), Tensor>,
+    kvs: Vec<Option<(Tensor, Tensor)>>,
+    cos: Tensor,
+    sin: Tensor,
+    max_position_embeddings: usize,
+    sliding_window: Option<usize>,
+    device: Device,
+}
+
+impl InstrumentedDecoderCache {
+    pub fn new(
+        dtype: DType,
+        config: &DecoderConfig,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let factors = config.rope_freq_factors.as_deref().unwrap_or_default();
+        let inv_freq = (0..config.head_dim)
+            .step_by(2)
+            .enumerate()
+            .map(|(slot, index)| {
+                let inv_freq = 1_f32
+                    / config
+                        .rope_theta
+                        .powf(index as f32 / config.head_dim as f32);
+                factors
+                    .get(slot)
+                    .map_or(inv_freq, |factor| inv_freq / factor)
+            })
+            .collect::<Vec<_>>();
+        let inv_freq_len = inv_freq.len();
+        let inv_freq =
+            Tensor::from_vec(inv_freq, (1, inv_freq_len), device).map_err(candle_load_error)?;
+        let positions = Tensor::arange(0, config.max_position_embeddings as u32, device)
+            .and_then(|tensor| tensor.to_dtype(DType::F32))
+            .and_then(|tensor| tensor.reshape((config.max_position_embeddings, 1)))
+            .map_err(candle_load_error)?;
+        let freqs = positions.matmul(&inv_freq).map_err(candle_load_error)?;
+        let cos = freqs
+            .cos()
+            .and_then(|tensor| tensor.to_dtype(dtype))
+            .map_err(candle_load_error)?;
+        let sin = freqs
+            .sin()
+            .and_then(|tensor| tensor.to_dtype(dtype))
+            .map_err(candle_load_error)?;
+        Ok(Self {
+            masks: HashMap::new(),
+            kvs: vec![None; config.num_hidden_layers],
+            cos,
+            sin,
+            max_position_embeddings: config.max_position_embeddings,
+            sliding_window: config.sliding_window,
+            device: device.clone(),
+        })
+    }
+
+    /// Causal mask (1 = masked) for `seq_len` new queries over the
+    /// `index_pos + seq_len` cached keys, additionally hiding keys that fell
+    /// out of the sliding window. `None` when nothing is masked.
+    fn mask(
+        &mut self,
+        seq_len: usize,
+        index_pos: usize,
+    ) -> Result<Option<Tensor>, ModelRuntimeError> {
+        let kv_len = index_pos + seq_len;
+        if let Some(mask) = self.masks.get(&(seq_len, index_pos)) {
+            return Ok(Some(mask.clone()));
+        }
+        let sliding_window = self.sliding_window;
+        let mut masked_any = false;
+        let mask = (0..seq_len)
+            .flat_map(|row| {
+                let query = index_pos + row;
+                (0..kv_len).map(move |key| (query, key))
+            })
+            .map(|(query, key)| {
+                let hidden =
+                    key > query || sliding_window.is_some_and(|window| query >= key + window);
+                masked_any |= hidden;
+                u8::from(hidden)
+            })
+            .collect::<Vec<_>>();
+        if !masked_any {
+            return Ok(None);
+        }
+        let mask = Tensor::from_vec(mask, (seq_len, kv_len), &self.device)
+            .map_err(candle_generate_error)?;
+        self.masks.insert((seq_len, index_pos), mask.clone());
+        Ok(Some(mask))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InstrumentedDecoder {
+    architecture: DecoderArchitecture,
+    embed_tokens: Embedding,
+    embed_scale: Option<f64>,
+    blocks: Vec<Block>,
+    norm: RmsNorm,
+    lm_head: Projection,
+}
+
+impl InstrumentedDecoder {
+    pub fn load(vb: VarBuilder, config: &DecoderConfig) -> Result<Self, ModelRuntimeError> {
+        let embed_tokens = embedding(
+            config.vocab_size,
+            config.hidden_size,
+            vb.pp("model.embed_tokens"),
+        )
+        .map_err(candle_load_error)?;
+        let lm_head = if config.tie_word_embeddings {
+            Projection::Dense(Linear::from_weights(
+                embed_tokens.embeddings().clone(),
+                None,
+            ))
+        } else {
+            dense(
+                config.hidden_size,
+                config.vocab_size,
+                false,
+                vb.pp("lm_head"),
+            )?
+        };
+        let norm = decoder_rms_norm(config, vb.pp("model.norm"))?;
+        let blocks = (0..config.num_hidden_layers)
+            .map(|index| Block::load(vb.pp(format!("model.layers.{index}")), config))
+            .collect::<Result<Vec<_>, _>>()?;
+        let embed_scale = matches!(config.architecture, DecoderArchitecture::Gemma)
+            .then(|| (config.hidden_size as f64).sqrt());
+        Ok(Self {
+            architecture: config.architecture,
+            embed_tokens,
+            embed_scale,
+            blocks,
+            norm,
+            lm_head,
+        })
+    }
+
+    /// Builds the forward from a GGUF checkpoint. `config` comes from the
+    /// file's own metadata (see `gguf_metadata::read_gguf_metadata`).
+    pub fn load_gguf<R: Read + Seek>(
+        content: &gguf_file::Content,
+        reader: &mut R,
+        config: &DecoderConfig,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let mut gguf = GgufTensors {
+            content,
+            reader,
+            device,
+        };
+        let embeddings = gguf
+            .tensor("token_embd.weight")?
+            .dequantize(device)
+            .map_err(candle_load_error)?;
+        let embed_tokens = Embedding::new(embeddings, config.hidden_size);
+        let lm_head = if gguf.contains("output.weight") {
+            gguf.projection("output")?
+        } else {
+            gguf.projection("token_embd")?
+        };
+        let norm = gguf.rms_norm("output_norm", config)?;
+        let blocks = (0..config.num_hidden_layers)
+            .map(|index| Block::load_gguf(&mut gguf, index, config))
+            .collect::<Result<Vec<_>, _>>()?;
+        let embed_scale = matches!(config.architecture, DecoderArchitecture::Gemma)
+            .then(|| (config.hidden_size as f64).sqrt());
+        Ok(Self {
+            architecture: config.architecture,
+            embed_tokens,
+            embed_scale,
+            blocks,
+            norm,
+            lm_head,
+        })
+    }
+
+    pub fn architecture(&self) -> DecoderArchitecture {
+        self.architecture
+    }
+
+    /// Incremental forward returning logits for the LAST position only,
+    /// shape `[batch, vocab]` (F32) — the generate path.
+    #[allow(clippy::too_many_arguments)]
+    pub fn forward(
+        &self,
+        input_ids: &Tensor,
+        index_pos: usize,
+        cache: &mut InstrumentedDecoderCache,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let hidden = self.forward_hidden_states(
+            input_ids,
+            index_pos,
+            cache,
+            hooks,
+            steering_overrides,
+            lora_stack,
+            lora_overrides,
+        )?;
+        let (_batch, seq_len, _hidden) = hidden.dims3().map_err(candle_generate_error)?;
+        let last = hidden
+            .i((.., seq_len - 1, ..))
+            .and_then(|tensor| tensor.contiguous())
+            .map_err(candle_generate_error)?;
+        let logits = self.lm_head.forward(&last).map_err(candle_generate_error)?;
+        logits.to_dtype(DType::F32).map_err(candle_generate_error)
+    }
+
+    /// Teacher-forcing forward returning logits for every position, shape
+    /// `[batch, seq, vocab]` (F32).
+    #[allow(clippy::too_many_arguments)]
+    pub fn forward_full_logits(
+        &self,
+        input_ids: &Tensor,
+        index_pos: usize,
+        cache: &mut InstrumentedDecoderCache,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let hidden = self.forward_hidden_states(
+            input_ids,
+            index_pos,
+            cache,
+            hooks,
+            steering_overrides,
+            lora_stack,
+            lora_overrides,
+        )?;
+        let logits = self
+            .lm_head
+            .forward(&hidden)
+            .map_err(candle_generate_error)?;
+        logits.to_dtype(DType::F32).map_err(candle_generate_error)
+    }
+
+    /// Block stack plus final norm over every position, shape
+    /// `[batch, seq, hidden]` (F32).
+    #[allow(clippy::too_many_arguments)]
+    pub fn forward_hidden_states(
+        &self,
+        input_ids: &Tensor,
+        index_pos: usize,
+        cache: &mut InstrumentedDecoderCache,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let (_batch, seq_len) = input_ids.dims2().map_err(candle_generate_error)?;
+        if seq_len == 0 {
+            return Err(ModelRuntimeError::GenerateError(
+                "empty Candle decoder input tensor".to_string(),
+            ));
+        }
+        if index_pos + seq_len > cache.max_position_embeddings {
+            return Err(ModelRuntimeError::GenerateError(format!(
+                "Candle {} context of {} tokens exceeds max_position_embeddings {}",
+                self.architecture.as_str(),
+                index_pos + seq_len,
+                cache.max_position_embeddings
+            )));
+        }
+        let mut x = self
+            .embed_tokens
+            .forward(input_ids)
+            .map_err(candle_generate_error)?;
+        if let Some(scale) = self.embed_scale {
+            x = x.affine(scale, 0.0).map_err(candle_generate_error)?;
+        }
+        let vectors = hooks.snapshot_vectors_for_request(steering_overrides)?;
+        for (block_idx, block) in self.blocks.iter().enumerate() {
+            x = block.forward(&x, index_pos, block_idx, cache, lora_stack, lora_overrides)?;
+            x = hooks.apply_record_and_capture_tensor(
+                LayerIndex::new(block_idx as u32),
+                HookPoint::ResidStream,
+                &x,
+                &vectors,
+            )?;
+        }
+        let x = self.norm.forward(&x).map_err(candle_generate_error)?;
+        x.to_dtype(DType::F32).map_err(candle_generate_error)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum QkvProjection {
+    Split {
+        q: Projection,
+        k: Projection,
+        v: Projection,
+    },
+    Fused(Projection),
+}
+
+#[derive(Debug, Clone)]
+struct Attention {
+    qkv: QkvProjection,
+    o_proj: Projection,
+    num_attention_heads: usize,
+    num_key_value_heads: usize,
+    head_dim: usize,
+    /// q/k rows are in llama.cpp's interleaved-RoPE order (Llama GGUF).
+    interleaved_qk: bool,
+}
+
+impl Attention {
+    fn load(vb: VarBuilder, config: &DecoderConfig) -> Result<Self, ModelRuntimeError> {
+        let size_q = config.num_attention_heads * config.head_dim;
+        let size_kv = config.num_key_value_heads * config.head_dim;
+        let qkv = match config.architecture {
+            DecoderArchitecture::Phi3 => QkvProjection::Fused(dense(
+                config.hidden_size,
+                size_q + 2 * size_kv,
+                false,
+                vb.pp("qkv_proj"),
+            )?),
+            _ => {
+                let bias = config.attention_bias;
+                QkvProjection::Split {
+                    q: dense(config.hidden_size, size_q, bias, vb.pp("q_proj"))?,
+                    k: dense(config.hidden_size, size_kv, bias, vb.pp("k_proj"))?,
+                    v: dense(config.hidden_size, size_kv, bias, vb.pp("v_proj"))?,
+                }
+            }
+        };
+        // Qwen2 biases only q/k/v; Gemma's attention_bias covers o_proj too.
+        let o_bias =
+            matches!(config.architecture, DecoderArchitecture::Gemma) && config.attention_bias;
+        Ok(Self {
+            qkv,
+            o_proj: dense(size_q, config.hidden_size, o_bias, vb.pp("o_proj"))?,
+            num_attention_heads: config.num_attention_heads,
+            num_key_value_heads: config.num_key_value_heads,
+            head_dim: config.head_dim,
+            interleaved_qk: false,
+        })
+    }
+
+    fn load_gguf<R: Read + Seek>(
+        gguf: &mut GgufTensors<'_, R>,
+        prefix: &str,
+        config: &DecoderConfig,
+    ) -> Result<Self, ModelRuntimeError> {
+        let qkv = match config.architecture {
+            DecoderArchitecture::Phi3 => {
+                QkvProjection::Fused(gguf.projection(&format!("{prefix}.attn_qkv"))?)
+            }
+            _ => QkvProjection::Split {
+                q: gguf.projection(&format!("{prefix}.attn_q"))?,
+                k: gguf.projection(&format!("{prefix}.attn_k"))?,
+                v: gguf.projection(&format!("{prefix}.attn_v"))?,
+            },
+        };
+        Ok(Self {
+            qkv,
+            o_proj: gguf.projection(&format!("{prefix}.attn_output"))?,
+            num_attention_heads: config.num_attention_heads,
+            num_key_value_heads: config.num_key_value_heads,
+            head_dim: config.head_dim,
+            interleaved_qk: matches!(config.architecture, DecoderArchitecture::Llama),
+        })
+    }
+
+    fn project_qkv(
+        &self,
+        x: &Tensor,
+        block_idx: usize,
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<(Tensor, Tensor, Tensor), ModelRuntimeError> {
+        let project = |linear: &Projection,
+                       name: &str,
+                       rotary_heads: Option<usize>|
+         -> Result<Tensor, ModelRuntimeError> {
+            let mut output = linear.forward(x).map_err(candle_generate_error)?;
+            if let Some(heads) = rotary_heads.filter(|_| self.interleaved_qk) {
+                output = deinterleave_rotary_rows(&output, heads, self.head_dim)?;
+            }
+            lora_stack.apply_to_linear_output(
+                &format!("model.layers.{block_idx}.self_attn.{name}"),
+                &output,
+                x,
+                lora_overrides,
+            )
+        };
+        match &self.qkv {
+            QkvProjection::Split { q, k, v } => Ok((
+                project(q, "q_proj", Some(self.num_attention_heads))?,
+                project(k, "k_proj", Some(self.num_key_value_heads))?,
+                project(v, "v_proj", None)?,
+            )),
+            QkvProjection::Fused(qkv) => {
+                let qkv = project(qkv, "qkv_proj", None)?;
+                let size_q = self.num_attention_heads * self.head_dim;
+                let size_kv = self.num_key_value_heads * self.head_dim;
+                let q = qkv.narrow(2, 0, size_q).map_err(candle_generate_error)?;
+                let k = qkv
+                    .narrow(2, size_q, size_kv)
+                    .map_err(candle_generate_error)?;
+                let v = qkv
+                    .narrow(2, size_q + size_kv, size_kv)
+                    .map_err(candle_generate_error)?;
+                Ok((q, k, v))
+            }
+        }
+    }
+
+    fn forward(
+        &self,
+        x: &Tensor,
+        index_pos: usize,
+        block_idx: usize,
+        cache: &mut InstrumentedDecoderCache,
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let (batch_size, seq_len, _hidden) = x.dims3().map_err(candle_generate_error)?;
+        let (q, k, v) = self.project_qkv(x, block_idx, lora_stack, lora_overrides)?;
+        let to_heads = |tensor: Tensor, heads: usize| {
+            tensor
+                .reshape((batch_size, seq_len, heads, self.head_dim))
+                .and_then(|tensor| tensor.transpose(1, 2))
+                .and_then(|tensor| tensor.contiguous())
+                .map_err(candle_generate_error)
+        };
+        let q = to_heads(q, self.num_attention_heads)?;
+        let k = to_heads(k, self.num_key_value_heads)?;
+        let v = to_heads(v, self.num_key_value_heads)?;
+
+        let q = apply_rotary_emb(&q, index_pos, cache)?;
+        let k = apply_rotary_emb(&k, index_pos, cache)?;
+        let (k, v) = match &cache.kvs[block_idx] {
+            Some((cache_k, cache_v)) => (
+                Tensor::cat(&[cache_k, &k], 2).map_err(candle_generate_error)?,
+                Tensor::cat(&[cache_v, &v], 2).map_err(candle_generate_error)?,
+            ),
+            None => (k, v),
+        };
+        cache.kvs[block_idx] = Some((k.clone(), v.clone()));
+
+        let groups = self.num_attention_heads / self.num_key_value_heads;
+        let k = repeat_kv(k, groups).map_err(candle_generate_error)?;
+        let v = repeat_kv(v, groups).map_err(candle_generate_error)?;
+        let in_dtype = q.dtype();
+        let q = q.to_dtype(DType::F32).map_err(candle_generate_error)?;
+        let k = k.to_dtype(DType::F32).map_err(candle_generate_error)?;
+        let v = v
+            .to_dtype(DType::F32)
+            .and_then(|tensor| tensor.contiguous())
+            .map_err(candle_generate_error)?;
+        let att = k
+            .t()
+            .and_then(|k_t| q.matmul(&k_t))
+            .and_then(|att| att.affine(1.0 / (self.head_dim as f64).sqrt(), 0.0))
+            .map_err(candle_generate_error)?;
+        let att = match cache.mask(seq_len, index_pos)? {
+            Some(mask) => {
+                let mask = mask
+                    .broadcast_as(att.shape())
+                    .map_err(candle_generate_error)?;
+                masked_fill(&att, &mask, f32::NEG_INFINITY)?
+            }
+            None => att,
+        };
+        let att = candle_nn::ops::softmax_last_dim(&att).map_err(candle_generate_error)?;
+        let y = att
+            .matmul(&v)
+            .and_then(|tensor| tensor.to_dtype(in_dtype))
+            .and_then(|tensor| tensor.transpose(1, 2))
+            .and_then(|tensor| {
+                tensor.reshape((
+                    batch_size,
+                    seq_len,
+                    self.num_attention_heads * self.head_dim,
+                ))
+            })
+            .map_err(candle_generate_error)?;
+        let output = self.o_proj.forward(&y).map_err(candle_generate_error)?;
+        lora_stack.apply_to_linear_output(
+            &format!("model.layers.{block_idx}.self_attn.o_proj"),
+            &output,
+            &y,
+            lora_overrides,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+enum GateUpProjection {
+    Split { gate: Projection, up: Projection },
+    Fused(Projection),
+}
+
+#[derive(Debug, Clone)]
+struct Mlp {
+    gate_up: GateUpProjection,
+    down_proj: Projection,
+    intermediate_size: usize,
+    gelu: bool,
+}
+
+impl Mlp {
+    fn load(vb: VarBuilder, config: &DecoderConfig) -> Result<Self, ModelRuntimeError> {
+        let hidden = config.hidden_size;
+        let intermediate = config.intermediate_size;
+        let gate_up = match config.architecture {
+            DecoderArchitecture::Phi3 => GateUpProjection::Fused(dense(
+                hidden,
+                2 * intermediate,
+                false,
+                vb.pp("gate_up_proj"),
+            )?),
+            _ => GateUpProjection::Split {
+                gate: dense(hidden, intermediate, false, vb.pp("gate_proj"))?,
+                up: dense(hidden, intermediate, false, vb.pp("up_proj"))?,
+            },
+        };
+        Ok(Self {
+            gate_up,
+            down_proj: dense(intermediate, hidden, false, vb.pp("down_proj"))?,
+            intermediate_size: intermediate,
+            gelu: matches!(config.architecture, DecoderArchitecture::Gemma),
+        })
+    }
+
+    fn load_gguf<R: Read + Seek>(
+        gguf: &mut GgufTensors<'_, R>,
+        prefix: &str,
+        config: &DecoderConfig,
+    ) -> Result<Self, ModelRuntimeError> {
+        // llama.cpp stores Phi-3's fused gate/up projection as `ffn_up`.
+        let gate_up = match config.architecture {
+            DecoderArchitecture::Phi3 => {
+                GateUpProjection::Fused(gguf.projection(&format!("{prefix}.ffn_up"))?)
+            }
+            _ => GateUpProjection::Split {
+                gate: gguf.projection(&format!("{prefix}.ffn_gate"))?,
+                up: gguf.projection(&format!("{prefix}.ffn_up"))?,
+            },
+        };
+        Ok(Self {
+            gate_up,
+            down_proj: gguf.projection(&format!("{prefix}.ffn_down"))?,
+            intermediate_size: config.intermediate_size,
+            gelu: matches!(config.architecture, DecoderArchitecture::Gemma),
+        })
+    }
+
+    fn forward(
+        &self,
+        x: &Tensor,
+        block_idx: usize,
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let project = |linear: &Projection, name: &str| -> Result<Tensor, ModelRuntimeError> {
+            let output = linear.forward(x).map_err(candle_generate_error)?;
+            lora_stack.apply_to_linear_output(
+                &format!("model.layers.{block_idx}.mlp.{name}"),
+                &output,
+                x,
+                lora_overrides,
+            )
+        };
+        let (gate, up) = match &self.gate_up {
+            GateUpProjection::Split { gate, up } => {
+                (project(gate, "gate_proj")?, project(up, "up_proj")?)
+            }
+            GateUpProjection::Fused(gate_up) => {
+                let gate_up = project(gate_up, "gate_up_proj")?;
+                (
+                    gate_up
+                        .narrow(2, 0, self.intermediate_size)
+                        .map_err(candle_generate_error)?,
+                    gate_up
+                        .narrow(2, self.intermediate_size, self.intermediate_size)
+                        .map_err(candle_generate_error)?,
+                )
+            }
+        };
+        let gate = if self.gelu {
+            gate.gelu()
+        } else {
+            candle_nn::ops::silu(&gate)
+        }
+        .map_err(candle_generate_error)?;
+        let hidden = (gate * up).map_err(candle_generate_error)?;
+        let output = self
+            .down_proj
+            .forward(&hidden)
+            .map_err(candle_generate_error)?;
+        lora_stack.apply_to_linear_output(
+            &format!("model.layers.{block_idx}.mlp.down_proj"),
+            &output,
+            &hidden,
+            lora_overrides,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    input_layernorm: RmsNorm,
+    attn: Attention,
+    post_attention_layernorm: RmsNorm,
+    mlp: Mlp,
+}
+
+impl Block {
+    fn load(vb: VarBuilder, config: &DecoderConfig) -> Result<Self, ModelRuntimeError> {
+        Ok(Self {
+            input_layernorm: decoder_rms_norm(config, vb.pp("input_layernorm"))?,
+            attn: Attention::load(vb.pp("self_attn"), config)?,
+            post_attention_layernorm: decoder_rms_norm(config, vb.pp("post_attention_layernorm"))?,
+            mlp: Mlp::load(vb.pp("mlp"), config)?,
+        })
+    }
+
+    fn load_gguf<R: Read + Seek>(
+        gguf: &mut GgufTensors<'_, R>,
+        index: usize,
+        config: &DecoderConfig,
+    ) -> Result<Self, ModelRuntimeError> {
+        let prefix = format!("blk.{index}");
+        Ok(Self {
+            input_layernorm: gguf.rms_norm(&format!("{prefix}.attn_norm"), config)?,
+            attn: Attention::load_gguf(gguf, &prefix, config)?,
+            post_attention_layernorm: gguf.rms_norm(&format!("{prefix}.ffn_norm"), config)?,
+            mlp: Mlp::load_gguf(gguf, &prefix, config)?,
+        })
+    }
+
+    fn forward(
+        &self,
+        x: &Tensor,
+        index_pos: usize,
+        block_idx: usize,
+        cache: &mut InstrumentedDecoderCache,
+        lora_stack: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let residual = x;
+        let x = self
+            .input_layernorm
+            .forward(x)
+            .map_err(candle_generate_error)?;
+        let x = (self
+            .attn
+            .forward(&x, index_pos, block_idx, cache, lora_stack, lora_overrides)?
+            + residual)
+            .map_err(candle_generate_error)?;
+        let residual = &x;
+        let x = self
+            .post_attention_layernorm
+            .forward(&x)
+            .map_err(candle_generate_error)
+            .and_then(|x| self.mlp.forward(&x, block_idx, lora_stack, lora_overrides))?;
+        (x + residual).map_err(candle_generate_error)
+    }
+}
+
+/// A projection weight: dense (safetensors) or block-quantized (GGUF). Both
+/// return F32-compatible activations, so LoRA deltas apply on top unchanged.
+#[derive(Debug, Clone)]
+enum Projection {
+    Dense(Linear),
+    Quantized {
+        weight: QMatMul,
+        bias: Option<Tensor>,
+    },
+}
+
+impl Module for Projection {
+    fn forward(&self, xs: &Tensor) -> candle_core::Result<Tensor> {
+        match self {
+            Self::Dense(linear) => linear.forward(xs),
+            Self::Quantized { weight, bias } => {
+                let output = weight.forward(xs)?;
+                match bias {
+                    Some(bias) => output.broadcast_add(bias),
+                    None => Ok(output),
+                }
+            }
+        }
+    }
+}
+
+fn dense(
+    in_dim: usize,
+    out_dim: usize,
+    bias: bool,
+    vb: VarBuilder,
+) -> Result<Projection, ModelRuntimeError> {
+    linear_b(in_dim, out_dim, bias, vb)
+        .map(Projection::Dense)
+        .map_err(candle_load_error)
+}
+
+/// Named-tensor reader over an open GGUF file.
+struct GgufTensors<'a, R> {
+    content: &'a gguf_file::Content,
+    reader: &'a mut R,
+    device: &'a Device,
+}
+
+impl<R: Read + Seek> GgufTensors<'_, R> {
+    fn contains(&self, name: &str) -> bool {
+        self.content.tensor_infos.contains_key(name)
+    }
+
+    fn tensor(&mut self, name: &str) -> Result<QTensor, ModelRuntimeError> {
+        self.content
+            .tensor(&mut *self.reader, name, self.device)
+            .map_err(|error| {
+                ModelRuntimeError::LoadError(format!(
+                    "Candle GGUF tensor `{name}` could not be read: {error}"
+                ))
+            })
+    }
+
+    /// `{prefix}.weight` as a quantized matmul plus an optional dequantized
+    /// `{prefix}.bias`.
+    fn projection(&mut self, prefix: &str) -> Result<Projection, ModelRuntimeError> {
+        let weight = self.tensor(&format!("{prefix}.weight"))?;
+        let weight = QMatMul::from_qtensor(weight).map_err(candle_load_error)?;
+        let bias_name = format!("{prefix}.bias");
+        let bias = if self.contains(&bias_name) {
+            Some(
+                self.tensor(&bias_name)?
+                    .dequantize(self.device)
+                    .map_err(candle_load_error)?,
+            )
+        } else {
+            None
+        };
+        Ok(Projection::Quantized { weight, bias })
+    }
+
+    /// GGUF norms are stored ready to multiply — llama.cpp already folded
+    /// Gemma's `+1` in at conversion time.
+    fn rms_norm(
+        &mut self,
+        prefix: &str,
+        config: &DecoderConfig,
+    ) -> Result<RmsNorm, ModelRuntimeError> {
+        let weight = self
+            .tensor(&format!("{prefix}.weight"))?
+            .dequantize(self.device)
+            .map_err(candle_load_error)?;
+        Ok(RmsNorm::new(weight, config.rms_norm_eps))
+    }
+}
+
+/// Undo llama.cpp's q/k row permutation: per head, GGUF stores rotary pairs
+/// interleaved (`[x0, y0, x1, y1, ...]`) where Hugging Face stores halves
+/// (`[x0, x1, ..., y0, y1, ...]`). Restoring the HF order lets the standard
+/// half-split RoPE and HF-named LoRA deltas apply unchanged.
+fn deinterleave_rotary_rows(
+    output: &Tensor,
+    heads: usize,
+    head_dim: usize,
+) -> Result<Tensor, ModelRuntimeError> {
+    let (batch, seq_len, _width) = output.dims3().map_err(candle_generate_error)?;
+    output
+        .reshape((batch, seq_len, heads, head_dim / 2, 2))
+        .and_then(|tensor| tensor.transpose(3, 4))
+        .and_then(|tensor| tensor.reshape((batch, seq_len, heads * head_dim)))
+        .map_err(candle_generate_error)
+}
+
+/// Gemma stores RMSNorm weights as an offset from one; fold the offset in at
+/// load so the forward is a plain RMSNorm for every family.
+fn decoder_rms_norm(config: &DecoderConfig, vb: VarBuilder) -> Result<RmsNorm, ModelRuntimeError> {
+    let weight = if matches!(config.architecture, DecoderArchitecture::Gemma) {
+        vb.get_with_hints(config.hidden_size, "weight", Init::Const(0.0))
+            .and_then(|weight| weight.affine(1.0, 1.0))
+    } else {
+        vb.get_with_hints(config.hidden_size, "weight", Init::Const(1.0))
+    }
+    .map_err(candle_load_error)?;
+    Ok(RmsNorm::new(weight, config.rms_norm_eps))
+}
+
+fn apply_rotary_emb(
+    x: &Tensor,
+    index_pos: usize,
+    cache: &InstrumentedDecoderCache,
+) -> Result<Tensor, ModelRuntimeError> {
+    let (_batch, _heads, seq_len, _head_dim) = x.dims4().map_err(candle_generate_error)?;
+    let cos = cache
+        .cos
+        .narrow(0, index_pos, seq_len)
+        .map_err(candle_generate_error)?;
+    let sin = cache
+        .sin
+        .narrow(0, index_pos, seq_len)
+        .map_err(candle_generate_error)?;
+    candle_nn::rotary_emb::rope(x, &cos, &sin).map_err(candle_generate_error)
+}
+
+fn masked_fill(
+    on_false: &Tensor,
+    mask: &Tensor,
+    on_true: f32,
+) -> Result<Tensor, ModelRuntimeError> {
+    let on_true = Tensor::new(on_true, on_false.device())
+        .and_then(|tensor| tensor.broadcast_as(mask.shape().dims()))
+        .map_err(candle_generate_error)?;
+    mask.where_cond(&on_true, on_false)
+        .map_err(candle_generate_error)
+}
+
+/// `Some(family)` when the artifact's sibling `config.json` declares one of the
+/// [`DecoderArchitecture`] families.
+pub fn artifact_config_decoder_architecture(
+    artifact_path: &Path,
+) -> Result<Option<DecoderArchitecture>, ModelRuntimeError> {
+    let value = read_config_value(artifact_path)?;
+    Ok(DecoderArchitecture::from_config_value(&value))
+}
+
+pub fn read_config_value(artifact_path: &Path) -> Result<Value, ModelRuntimeError> {
+    let config_path = config_json_path_for_artifact(artifact_path);
+    let config_json = fs::read_to_string(&config_path).map_err(|error| {
+        ModelRuntimeError::LoadError(format!(
+            "failed to read Candle config {}: {error}",
+            config_path.display()
+        ))
+    })?;
+    serde_json::from_str::<Value>(&config_json).map_err(|error| {
+        ModelRuntimeError::LoadError(format!(
+            "failed to parse Candle config {}: {error}",
+            config_path.display()
+        ))
+    })
+}
+
+pub fn eos_token_ids(value: &Value) -> Vec<u32> {
+    match value.get("eos_token_id") {
+        Some(Value::Number(number)) => number
+            .as_u64()
+            .map(|id| vec![id as u32])
+            .unwrap_or_default(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_u64)
+            .map(|id| id as u32)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn required_usize(value: &Value, family: &str, key: &str) -> Result<usize, ModelRuntimeError> {
+    optional_usize(value, key)
+        .ok_or_else(|| config_error(family, format!("missing or invalid `{key}`")))
+}
+
+fn optional_usize(value: &Value, key: &str) -> Option<usize> {
+    value.get(key).and_then(Value::as_u64).map(|n| n as usize)
+}
+
+fn optional_bool(value: &Value, key: &str) -> Option<bool> {
+    value.get(key).and_then(Value::as_bool)
+}
+
+fn config_error(family: &str, detail: String) -> ModelRuntimeError {
+    ModelRuntimeError::LoadError(format!("Candle {family} config rejected: {detail}"))
+}
+
+fn candle_load_error(error: candle_core::Error) -> ModelRuntimeError {
+    ModelRuntimeError::LoadError(format!("Candle instrumented decoder load failed: {error}"))
+}
+
+fn candle_generate_error(error: candle_core::Error) -> ModelRuntimeError {
+    ModelRuntimeError::GenerateError(format!(
+        "Candle instrumented decoder forward failed: {error}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Load-bearing proofs for the owned decoder forward, on tiny
+    //! randomly-initialised weights (no downloaded model):
+    //!   - incremental KV-cached decoding reproduces the teacher-forcing
+    //!     logits at every position, for every family (RoPE offsets, cache
+    //!     concatenation, GQA, and masks agree);
+    //!   - the Mistral sliding window actually hides tokens outside it;
+    //!   - steering and LoRA seams are live (zero vector is identity, a
+    //!     fused Phi-3 `qkv_proj` LoRA changes logits and unmount reverts).
+    use super::*;
+    use std::collections::HashMap;
+
+    use candle_nn::VarMap;
+    use serde_json::json;
+
+    use crate::model_runtime::{
+        BaseModelTag, LicenseTag, LoraDescriptor, LoraStackOps, LoraStrength, ModelId,
+        SteeringProvenance, SteeringVector, SteeringVectorValues,
+    };
+
+    fn tiny_config_value(architecture: DecoderArchitecture) -> Value {
+        let mut value = json!({
+            "model_type": architecture.as_str(),
+            "vocab_size": 24,
+            "hidden_size": 16,
+            "intermediate_size": 32,
+            "num_hidden_layers": 2,
+            "num_attention_heads": 4,
+            "num_key_value_heads": 2,
+            "max_position_embeddings": 32,
+            "rope_theta": 10000.0
+        });
+        match architecture {
+            // Gemma's head_dim is independent of hidden_size / heads.
+            DecoderArchitecture::Gemma => value["head_dim"] = json!(8),
+            DecoderArchitecture::Mistral => value["sliding_window"] = json!(3),
+            DecoderArchitecture::Qwen2 | DecoderArchitecture::Phi3 | DecoderArchitecture::Llama => {
+            }
+        }
+        value
+    }
+
+    fn tiny_model(config: &DecoderConfig) -> InstrumentedDecoder {
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+        InstrumentedDecoder::load(vb, config).expect("tiny decoder builds")
+    }
+
+    fn tiny(architecture: DecoderArchitecture) -> (DecoderConfig, InstrumentedDecoder) {
+        let config =
+            DecoderConfig::from_config_value(architecture, &tiny_config_value(architecture))
+                .expect("tiny config");
+        let model = tiny_model(&config);
+        (config, model)
+    }
+
+    fn input(tokens: &[u32]) -> Tensor {
+        Tensor::new(tokens, &Device::Cpu)
+            .and_then(|tensor| tensor.reshape((1, tokens.len())))
+            .unwrap()
+    }
+
+    fn last_logits(
+        model: &InstrumentedDecoder,
+        config: &DecoderConfig,
+        tokens: &[u32],
+        hooks: &CandleSteeringHooks,
+        lora: &CandleLoraStack,
+        lora_overrides: &[LoraId],
+    ) -> Vec<f32> {
+        let mut cache = InstrumentedDecoderCache::new(DType::F32, config, &Device::Cpu).unwrap();
+        model
+            .forward(
+                &input(tokens),
+                0,
+                &mut cache,
+                hooks,
+                &[],
+                lora,
+                lora_overrides,
+            )
+            .unwrap()
+            .flatten_all()
+            .unwrap()
+            .to_vec1::<f32>()
+            .unwrap()
+    }
+
+    fn max_abs_diff(a: &[f32], b: &[f32]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).abs())
+            .fold(0f32, f32::max)
+    }
+
+    fn lora_for(config: &DecoderConfig) -> CandleLoraStack {
+        CandleLoraStack::new(
+            ModelId::new_v7(),
+            config.architecture.lora_base_model(),
+            config.architecture.lora_targets(config.num_hidden_layers),
+        )
+    }
+
+    #[test]
+    fn decoder_config_detects_families_and_normalises_defaults() {
+        assert_eq!(
+            DecoderArchitecture::from_config_value(&json!({"model_type": "Qwen2"})),
+            Some(DecoderArchitecture::Qwen2)
+        );
+        assert_eq!(
+            DecoderArchitecture::from_config_value(
+                &json!({"architectures": ["MistralForCausalLM"]})
+            ),
+            Some(DecoderArchitecture::Mistral)
+        );
+        for unsupported in [
+            json!({"model_type": "llama"}),
+            json!({"model_type": "gemma2"}),
+            json!({"model_type": "qwen2_moe"}),
+        ] {
+            assert_eq!(DecoderArchitecture::from_config_value(&unsupported), None);
+        }
+
+        let qwen = DecoderConfig::from_config_value(
+            DecoderArchitecture::Qwen2,
+            &json!({
+                "vocab_size": 24, "hidden_size": 16, "intermediate_size": 32,
+                "num_hidden_layers": 2, "num_attention_heads": 4,
+                "sliding_window": 8, "use_sliding_window": false
+            }),
+        )
+        .unwrap();
+        assert!(qwen.attention_bias);
+        assert_eq!(qwen.sliding_window, None);
+        assert_eq!(qwen.num_key_value_heads, 4);
+        assert_eq!(qwen.head_dim, 4);
+
+        let gemma = tiny(DecoderArchitecture::Gemma).0;
+        assert!(gemma.tie_word_embeddings);
+        assert_eq!(gemma.head_dim, 8);
+
+        let mut longrope = tiny_config_value(DecoderArchitecture::Phi3);
+        longrope["rope_scaling"] = json!({"type": "longrope"});
+        let err = DecoderConfig::from_config_value(DecoderArchitecture::Phi3, &longrope)
+            .expect_err("LongRoPE is rejected");
+        assert!(err.to_string().contains("rope_scaling"), "{err}");
+
+        let mut gelu_mistral = tiny_config_value(DecoderArchitecture::Mistral);
+        gelu_mistral["hidden_act"] = json!("gelu");
+        assert!(
+            DecoderConfig::from_config_value(DecoderArchitecture::Mistral, &gelu_mistral).is_err()
+        );
+    }
+
+    #[test]
+    fn incremental_decode_matches_teacher_forcing_for_every_family() {
+        let device = Device::Cpu;
+        let tokens = [3u32, 7, 1, 5, 2, 9];
+        for architecture in DecoderArchitecture::ALL {
+            let (config, model) = tiny(architecture);
+            let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), config.hidden_size);
+            let lora = lora_for(&config);
+
+            let mut cache = InstrumentedDecoderCache::new(DType::F32, &config, &device).unwrap();
+            let full = model
+                .forward_full_logits(&input(&tokens), 0, &mut cache, &hooks, &[], &lora, &[])
+                .unwrap();
+            assert_eq!(full.dims(), &[1, tokens.len(), config.vocab_size]);
+
+            let mut cache = InstrumentedDecoderCache::new(DType::F32, &config, &device).unwrap();
+            for (pos, &token) in tokens.iter().enumerate() {
+                let step = model
+                    .forward(&input(&[token]), pos, &mut cache, &hooks, &[], &lora, &[])
+                    .unwrap()
+                    .flatten_all()
+                    .unwrap()
+                    .to_vec1::<f32>()
+                    .unwrap();
+                let expected = full.i((0, pos)).unwrap().to_vec1::<f32>().unwrap();
+                let diff = max_abs_diff(&step, &expected);
+                assert!(
+                    diff < 1e-4,
+                    "{} incremental logits diverged at position {pos}: max |Δ| = {diff}",
+                    architecture.as_str()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sliding_window_hides_tokens_outside_the_window() {
+        let mut value = tiny_config_value(DecoderArchitecture::Mistral);
+        value["sliding_window"] = json!(2);
+        let windowed = DecoderConfig::from_config_value(DecoderArchitecture::Mistral, &value)
+            .expect("windowed config");
+        let model = tiny_model(&windowed);
+        let full_attention = DecoderConfig {
+            sliding_window: None,
+            ..windowed.clone()
+        };
+        let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), windowed.hidden_size);
+        let lora = lora_for(&windowed);
+
+        // Two layers of a width-2 window: the last position sees tokens 1..=3
+        // and never token 0.
+        let a = [1u32, 2, 3, 4];
+        let b = [9u32, 2, 3, 4];
+        let windowed_diff = max_abs_diff(
+            &last_logits(&model, &windowed, &a, &hooks, &lora, &[]),
+            &last_logits(&model, &windowed, &b, &hooks, &lora, &[]),
+        );
+        assert!(windowed_diff < 1e-5, "token 0 leaked through the window");
+        let full_diff = max_abs_diff(
+            &last_logits(&model, &full_attention, &a, &hooks, &lora, &[]),
+            &last_logits(&model, &full_attention, &b, &hooks, &lora, &[]),
+        );
+        assert!(full_diff > 1e-4, "full attention must see token 0");
+    }
+
+    #[tokio::test]
+    async fn steering_and_fused_lora_seams_change_logits() {
+        let device = Device::Cpu;
+        let tokens = [4u32, 6, 8];
+
+        // Steering on Gemma: zero vector is identity, a non-zero one diverges.
+        let (config, model) = tiny(DecoderArchitecture::Gemma);
+        let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), config.hidden_size);
+        let lora = lora_for(&config);
+        let baseline = last_logits(&model, &config, &tokens, &hooks, &lora, &[]);
+        let vector = |values: Vec<f32>, name: &str| {
+            SteeringVector::try_new(
+                None,
+                name,
+                LayerIndex::new(0),
+                HookPoint::ResidStream,
+                SteeringVectorValues::try_new(values, 2.0).unwrap(),
+                "decoder steering vector",
+                Some(SteeringProvenance::Manual {
+                    author: "test".to_string(),
+                    notes: name.to_string(),
+                }),
+            )
+            .unwrap()
+        };
+        let zero = vector(vec![0.0; config.hidden_size], "zero");
+        hooks.register_vector(zero.clone()).await.unwrap();
+        hooks.set_active(vec![zero.id]).await.unwrap();
+        let zeroed = last_logits(&model, &config, &tokens, &hooks, &lora, &[]);
+        assert!(max_abs_diff(&baseline, &zeroed) < 1e-6);
+        let mut direction = vec![0.0; config.hidden_size];
+        direction[0] = 1.0;
+        direction[1] = -1.0;
+        let nonzero = vector(direction, "nonzero");
+        hooks.register_vector(nonzero.clone()).await.unwrap();
+        hooks.set_active(vec![nonzero.id]).await.unwrap();
+        let steered = last_logits(&model, &config, &tokens, &hooks, &lora, &[]);
+        assert!(max_abs_diff(&baseline, &steered) > 1e-4);
+
+        // LoRA on Phi-3's fused qkv_proj.
+        let (config, model) = tiny(DecoderArchitecture::Phi3);
+        let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), config.hidden_size);
+        let lora = lora_for(&config);
+        let baseline = last_logits(&model, &config, &tokens, &hooks, &lora, &[]);
+
+        let rank = 2usize;
+        let target = "model.layers.0.self_attn.qkv_proj".to_string();
+        let qkv_width =
+            (config.num_attention_heads + 2 * config.num_key_value_heads) * config.head_dim;
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("phi3_lora.safetensors");
+        let mut tensors = HashMap::new();
+        tensors.insert(
+            format!("{target}.lora_A.weight"),
+            Tensor::randn(0f32, 0.5f32, vec![rank, config.hidden_size], &device).unwrap(),
+        );
+        tensors.insert(
+            format!("{target}.lora_B.weight"),
+            Tensor::randn(0f32, 0.5f32, vec![qkv_width, rank], &device).unwrap(),
+        );
+        candle_core::safetensors::save(&tensors, &path).unwrap();
+        let sha = {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(std::fs::read(&path).unwrap());
+            let out: [u8; 32] = hasher.finalize().into();
+            out
+        };
+        let lora_id = LoraId::new_v7();
+        lora.mount(
+            LoraDescriptor {
+                id: lora_id,
+                artifact_path: path.clone(),
+                sha256: sha,
+                rank: rank as u32,
+                target_modules: vec![target],
+                base_model_compat: BaseModelTag::new("candle-phi3"),
+                license_tag: LicenseTag::new("test-license"),
+            },
+            LoraStrength::try_new(1.0).unwrap(),
+        )
+        .await
+        .expect("fused qkv LoRA mounts");
+        let mounted = last_logits(&model, &config, &tokens, &hooks, &lora, &[lora_id]);
+        assert!(max_abs_diff(&baseline, &mounted) > 1e-4);
+
+        lora.unmount(lora_id).await.expect("unmount");
+        let reverted = last_logits(&model, &config, &tokens, &hooks, &lora, &[]);
+        assert!(max_abs_diff(&baseline, &reverted) < 1e-6);
+    }
+}
@@ -27,6 +27,7 @@ pub struct InstrumentedLlamaCache {
     cos: Tensor,
     sin: Tensor,
     device: Device,
+    differentiable: bool,
 }
 
 impl InstrumentedLlamaCache {
@@ -89,9 +90,17 @@ impl InstrumentedLlamaCache {
             cos,
             sin,
             device: device.clone(),
+            differentiable: false,
         })
     }
 
+    /// Route RMSNorm, softmax, and RoPE through their composed tensor-op forms
+    /// so the pass records a full autograd graph. The fused kernels used for
+    /// inference have no backward; training forwards flip this on.
+    pub fn set_differentiable(&mut self, differentiable: bool) {
+        self.differentiable = differentiable;
+    }
+
     fn mask(&mut self, seq_len: usize, index_pos: usize) -> candle_core::Result<Tensor> {
         let kv_len = index_pos + seq_len;
         if let Some(mask) = self.masks.get(&(seq_len, kv_len)) {
@@ -162,7 +171,7 @@ impl InstrumentedLlama {
                 &vectors,
             )?;
         }
-        let x = self.ln_f.forward(&x).map_err(candle_generate_error)?;
+        let x = rms_norm(&self.ln_f, &x, cache.differentiable).map_err(candle_generate_error)?;
         let x = x
             .i((.., seq_len - 1, ..))
             .and_then(|tensor| tensor.contiguous())
@@ -227,7 +236,7 @@ impl InstrumentedLlama {
                 &vectors,
             )?;
         }
-        let x = self.ln_f.forward(&x).map_err(candle_generate_error)?;
+        let x = rms_norm(&self.ln_f, &x, cache.differentiable).map_err(candle_generate_error)?;
         x.to_dtype(DType::F32).map_err(candle_generate_error)
     }
 }
@@ -375,7 +384,12 @@ impl CausalSelfAttention {
                 .map_err(candle_generate_error)?;
             masked_fill(&att, &mask, f32::NEG_INFINITY)?
         };
-        let att = candle_nn::ops::softmax_last_dim(&att).map_err(candle_generate_error)?;
+        let att = if cache.differentiable {
+            candle_nn::ops::softmax(&att, D::Minus1)
+        } else {
+            candle_nn::ops::softmax_last_dim(&att)
+        }
+        .map_err(candle_generate_error)?;
         let y = att
             .matmul(&v.contiguous().map_err(candle_generate_error)?)
             .and_then(|tensor| tensor.to_dtype(in_dtype))
@@ -406,7 +420,12 @@ impl CausalSelfAttention {
             .sin
             .narrow(0, index_pos, seq_len)
             .map_err(candle_generate_error)?;
-        candle_nn::rotary_emb::rope(x, &cos, &sin).map_err(candle_generate_error)
+        if cache.differentiable {
+            candle_nn::rotary_emb::rope_slow(x, &cos, &sin)
+        } else {
+            candle_nn::rotary_emb::rope(x, &cos, &sin)
+        }
+        .map_err(candle_generate_error)
     }
 }
 
@@ -512,16 +531,14 @@ impl Block {
         lora_overrides: &[LoraId],
     ) -> Result<Tensor, ModelRuntimeError> {
         let residual = x;
-        let x = self.rms_1.forward(x).map_err(candle_generate_error)?;
+        let x = rms_norm(&self.rms_1, x, cache.differentiable).map_err(candle_generate_error)?;
         let x = (self
             .attn
             .forward(&x, index_pos, block_idx, cache, lora_stack, lora_overrides)?
             + residual)
             .map_err(candle_generate_error)?;
         let residual = &x;
-        let x = self
-            .rms_2
-            .forward(&x)
+        let x = rms_norm(&self.rms_2, &x, cache.differentiable)
             .map_err(candle_generate_error)
             .and_then(|x| self.mlp.forward(&x, block_idx, lora_stack, lora_overrides))?;
         (x + residual).map_err(candle_generate_error)
@@ -536,6 +553,14 @@ fn calculate_default_inv_freq(config: &Config) -> Vec<f32> {
         .collect()
 }
 
+fn rms_norm(norm: &RmsNorm, x: &Tensor, differentiable: bool) -> candle_core::Result<Tensor> {
+    if differentiable {
+        norm.forward_diff(x)
+    } else {
+        norm.forward(x)
+    }
+}
+
 fn masked_fill(
     on_false: &Tensor,
     mask: &Tensor,
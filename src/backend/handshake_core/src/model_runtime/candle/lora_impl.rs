@@ -29,6 +29,15 @@ pub struct CandleLoraStack {
 #[derive(Clone, Default)]
 struct CandleLoraStackState {
     active: Vec<MountedLora>,
+    trainable: Option<TrainableLora>,
+}
+
+/// Adapter under training. Its A/B tensors are `Var`-backed, so optimizer
+/// updates land in place and the next forward sees them without a remount.
+#[derive(Clone)]
+struct TrainableLora {
+    adapter: CandleLoraAdapter,
+    dropout: f32,
 }
 
 #[derive(Clone)]
@@ -96,6 +105,66 @@ impl CandleLoraStack {
         }
     }
 
+    pub fn base_model_tag(&self) -> &str {
+        &self.base_model_tag
+    }
+
+    pub fn valid_targets(&self) -> &[String] {
+        &self.valid_targets
+    }
+
+    /// Attach an adapter under training. It applies on every forward, after
+    /// the mounted stack and regardless of overrides, with `dropout` on the
+    /// LoRA branch input. Targets must be full names from [`Self::valid_targets`].
+    pub fn attach_trainable(
+        &self,
+        targets: Vec<(String, Tensor, Tensor)>,
+        scaling: f32,
+        dropout: f32,
+    ) -> Result<(), ModelRuntimeError> {
+        if targets.is_empty() {
+            return Err(lora_error("trainable LoRA must target at least one module"));
+        }
+        if !(0.0..1.0).contains(&dropout) {
+            return Err(lora_error(format!(
+                "trainable LoRA dropout must be in [0, 1), got {dropout}"
+            )));
+        }
+        let mut target_modules = HashMap::new();
+        for (target, a, b) in targets {
+            if !self.valid_targets.iter().any(|valid| *valid == target) {
+                return Err(lora_error(format!(
+                    "missing target modules for Candle LoRA: {target} (available: {})",
+                    self.valid_targets.join(", ")
+                )));
+            }
+            let (rank, _in_dim) = a.dims2().map_err(|error| {
+                lora_error(format!(
+                    "LoRA A tensor for {target} must be rank-2: {error}"
+                ))
+            })?;
+            validate_lora_shapes(rank as u32, &target, &a, &b)?;
+            target_modules.insert(target, CandleLoraTarget { a, b, scaling });
+        }
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| lora_error("Candle LoRA stack lock is poisoned"))?;
+        state.trainable = Some(TrainableLora {
+            adapter: CandleLoraAdapter { target_modules },
+            dropout,
+        });
+        Ok(())
+    }
+
+    pub fn detach_trainable(&self) -> Result<(), ModelRuntimeError> {
+        self.state
+            .lock()
+            .map_err(|_| lora_error("Candle LoRA stack lock is poisoned"))?
+            .trainable = None;
+        Ok(())
+    }
+
     pub fn handle(&self) -> LoraStackHandle {
         LoraStackHandle::with_ops(
             format!("candle:{}:lora_stack", self.model_id),
@@ -116,6 +185,24 @@ impl CandleLoraStack {
         targets
     }
 
+    /// Valid LoRA targets for the owned Phi-3 forward. Phi-3 checkpoints fuse
+    /// q/k/v into `qkv_proj` and gate/up into `gate_up_proj`, and PEFT adapters
+    /// trained against them target the fused modules, so those are the
+    /// realisable per-layer targets. Qwen2, Gemma, and Mistral share the Llama
+    /// module names and use [`Self::available_llama_targets`].
+    pub fn available_phi3_targets(num_layers: usize) -> Vec<String> {
+        let mut targets = Vec::with_capacity(num_layers * 4);
+        for layer in 0..num_layers {
+            for projection in ["qkv_proj", "o_proj"] {
+                targets.push(format!("model.layers.{layer}.self_attn.{projection}"));
+            }
+            for projection in ["gate_up_proj", "down_proj"] {
+                targets.push(format!("model.layers.{layer}.mlp.{projection}"));
+            }
+        }
+        targets
+    }
+
     /// MT-115 (INF-9 LoRA-for-SSM): valid LoRA targets for the owned Mamba2
     /// forward. candle's Mamba2 fuses x_proj/dt_proj/z into a single `in_proj`,
     /// so the realisable per-layer targets are the fused input projection
@@ -214,12 +301,13 @@ impl CandleLoraStack {
         input: &Tensor,
         lora_overrides: &[LoraId],
     ) -> Result<Tensor, ModelRuntimeError> {
-        let active = self
-            .state
-            .lock()
-            .map_err(|_| lora_error("Candle LoRA stack lock is poisoned"))?
-            .active
-            .clone();
+        let (active, trainable) = {
+            let state = self
+                .state
+                .lock()
+                .map_err(|_| lora_error("Candle LoRA stack lock is poisoned"))?;
+            (state.active.clone(), state.trainable.clone())
+        };
         let mut output = base_output.clone();
         for mounted in active {
             if !lora_overrides.is_empty() && !lora_overrides.contains(&mounted.descriptor.id) {
@@ -237,6 +325,25 @@ impl CandleLoraStack {
                 target,
             )?;
         }
+        if let Some(trainable) = trainable {
+            if let Some(lora_target) = trainable.adapter.target_modules.get(target) {
+                let input = if trainable.dropout > 0.0 {
+                    candle_nn::ops::dropout(input, trainable.dropout).map_err(|error| {
+                        lora_error(format!("LoRA dropout failed for {target}: {error}"))
+                    })?
+                } else {
+                    input.clone()
+                };
+                output = apply_lora_delta_to_linear_output(
+                    &output,
+                    &input,
+                    &lora_target.a,
+                    &lora_target.b,
+                    lora_target.scaling,
+                    target,
+                )?;
+            }
+        }
         Ok(output)
     }
 
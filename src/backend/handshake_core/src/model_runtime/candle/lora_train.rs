@@ -0,0 +1,473 @@
+#![cfg(feature = "candle-runtime-engine")]
+
+//! In-process LoRA training over a [`TransformerModel`].
+//!
+//! The trainer allocates `Var`-backed A/B tensors for the selected target
+//! modules (A uniform in `±1/sqrt(in_dim)`, B zero, as PEFT initialises them),
+//! attaches them to the model's [`CandleLoraStack`] as its trainable adapter,
+//! and runs teacher-forcing forwards through
+//! [`TransformerModel::forward_training_logits`]. Cross-entropy is taken over
+//! completion tokens only. Gradients from `gradient_accumulation_steps`
+//! examples are averaged into one AdamW step. The finished adapter (and any
+//! intermediate checkpoints) are written in PEFT layout —
+//! `adapter_model.safetensors` + `adapter_config.json` — so
+//! [`CandleLoraStack`] mounts them like any other PEFT adapter.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use candle_core::{DType, Device, Tensor, Var};
+use candle_nn::{AdamW, Optimizer, ParamsAdamW};
+use serde::Serialize;
+
+use super::{
+    hooks::CandleSteeringHooks, lora_impl::CandleLoraStack, transformer::TransformerModel,
+};
+use crate::model_runtime::{ModelId, ModelRuntimeError};
+
+pub const PEFT_ADAPTER_WEIGHTS_FILE: &str = "adapter_model.safetensors";
+pub const PEFT_ADAPTER_CONFIG_FILE: &str = "adapter_config.json";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoraTrainingConfig {
+    pub rank: u32,
+    pub alpha: f32,
+    pub dropout: f32,
+    pub learning_rate: f64,
+    pub weight_decay: f64,
+    pub epochs: u32,
+    /// Examples whose gradients are averaged into one optimizer step.
+    pub gradient_accumulation_steps: u32,
+    /// Stop after this many optimizer steps even if epochs remain.
+    pub max_steps: Option<u32>,
+    /// Prompt + completion token sequences are truncated to this length.
+    pub max_seq_len: usize,
+    /// PEFT-style module names (`q_proj`) or full target paths.
+    pub target_modules: Vec<String>,
+    /// Write `checkpoint-<step>/` every N optimizer steps.
+    pub checkpoint_every_steps: Option<u32>,
+}
+
+impl Default for LoraTrainingConfig {
+    fn default() -> Self {
+        Self {
+            rank: 16,
+            alpha: 32.0,
+            dropout: 0.05,
+            learning_rate: 2e-4,
+            weight_decay: 0.0,
+            epochs: 1,
+            gradient_accumulation_steps: 4,
+            max_steps: None,
+            max_seq_len: 512,
+            target_modules: vec!["q_proj".to_string(), "v_proj".to_string()],
+            checkpoint_every_steps: None,
+        }
+    }
+}
+
+/// One tokenized corpus row. Loss is computed on `completion_ids` only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoraTrainingExample {
+    pub prompt_ids: Vec<u32>,
+    pub completion_ids: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LoraTrainingStep {
+    pub step: u32,
+    pub epoch: u32,
+    /// Mean completion-token cross-entropy over the step's examples.
+    pub loss: f64,
+    pub learning_rate: f64,
+    /// Completion tokens that contributed to the loss.
+    pub tokens: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LoraTrainingCheckpoint {
+    pub step: u32,
+    pub dir: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoraTrainingReport {
+    pub steps: Vec<LoraTrainingStep>,
+    pub checkpoints: Vec<LoraTrainingCheckpoint>,
+    /// Full target paths the adapter was trained on.
+    pub target_modules: Vec<String>,
+    pub adapter_path: PathBuf,
+}
+
+impl LoraTrainingReport {
+    pub fn final_loss(&self) -> Option<f64> {
+        self.steps.last().map(|step| step.loss)
+    }
+}
+
+/// Train a fresh LoRA adapter on `examples` and write it to `output_dir`.
+/// The model is left without a trainable adapter attached, even on error.
+pub fn train_lora(
+    model: &mut dyn TransformerModel,
+    examples: &[LoraTrainingExample],
+    config: &LoraTrainingConfig,
+    output_dir: &Path,
+) -> Result<LoraTrainingReport, ModelRuntimeError> {
+    train_lora_with_progress(model, examples, config, output_dir, &mut |_, _| {})
+}
+
+/// [`train_lora`], calling `on_step` after every optimizer step with the step
+/// and the checkpoint written at it, if any.
+pub fn train_lora_with_progress(
+    model: &mut dyn TransformerModel,
+    examples: &[LoraTrainingExample],
+    config: &LoraTrainingConfig,
+    output_dir: &Path,
+    on_step: &mut dyn FnMut(&LoraTrainingStep, Option<&LoraTrainingCheckpoint>),
+) -> Result<LoraTrainingReport, ModelRuntimeError> {
+    validate_training_config(config)?;
+    let sequences = examples
+        .iter()
+        .filter_map(|example| TrainingSequence::new(example, config.max_seq_len))
+        .collect::<Vec<_>>();
+    if sequences.is_empty() {
+        return Err(training_error(
+            "no training example has completion tokens within max_seq_len",
+        ));
+    }
+    let stack = model.candle_lora_stack().cloned().ok_or_else(|| {
+        ModelRuntimeError::CapabilityNotSupported {
+            capability: "lora_training".to_string(),
+            adapter: "candle_transformer_model".to_string(),
+        }
+    })?;
+    let device = model.device();
+    let targets = select_targets(&model.lora_target_dims(), &config.target_modules)?;
+    let adapter = TrainableAdapter::init(&targets, config.rank as usize, &device)?;
+    stack.attach_trainable(
+        adapter.tensors(),
+        config.alpha / config.rank as f32,
+        config.dropout,
+    )?;
+    let _attached = AttachedTrainable(&stack);
+
+    let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), model.hidden_dim() as usize);
+    let vars = adapter.vars();
+    let mut optimizer = AdamW::new(
+        vars.clone(),
+        ParamsAdamW {
+            lr: config.learning_rate,
+            weight_decay: config.weight_decay,
+            ..ParamsAdamW::default()
+        },
+    )
+    .map_err(candle_training_error)?;
+
+    let mut steps = Vec::new();
+    let mut checkpoints = Vec::new();
+    let mut step = 0_u32;
+    'epochs: for epoch in 1..=config.epochs {
+        for batch in sequences.chunks(config.gradient_accumulation_steps as usize) {
+            if config.max_steps.is_some_and(|max_steps| step >= max_steps) {
+                break 'epochs;
+            }
+            let mut loss_sum = 0.0_f64;
+            let mut tokens = 0_u64;
+            let mut summed = vec![None::<Tensor>; vars.len()];
+            let mut last_grads = None;
+            for sequence in batch {
+                let loss = sequence_loss(model, &hooks, sequence, &device)?;
+                let value = loss.to_scalar::<f32>().map_err(candle_training_error)? as f64;
+                if !value.is_finite() {
+                    return Err(training_error(format!(
+                        "loss diverged to {value} at step {}",
+                        step + 1
+                    )));
+                }
+                loss_sum += value;
+                tokens += sequence.target_count() as u64;
+                let grads = loss.backward().map_err(candle_training_error)?;
+                for (slot, var) in summed.iter_mut().zip(&vars) {
+                    let Some(grad) = grads.get(var.as_tensor()) else {
+                        continue;
+                    };
+                    *slot = Some(match slot.take() {
+                        Some(sum) => (sum + grad).map_err(candle_training_error)?,
+                        None => grad.clone(),
+                    });
+                }
+                last_grads = Some(grads);
+            }
+            // `GradStore` has no public constructor; reuse the last backward's
+            // store and overwrite each var's entry with the batch mean.
+            let mut grads = last_grads.ok_or_else(|| training_error("empty training batch"))?;
+            let scale = 1.0 / batch.len() as f64;
+            for (sum, var) in summed.into_iter().zip(&vars) {
+                if let Some(sum) = sum {
+                    grads.insert(
+                        var.as_tensor(),
+                        (sum * scale).map_err(candle_training_error)?,
+                    );
+                }
+            }
+            optimizer.step(&grads).map_err(candle_training_error)?;
+            step += 1;
+            let record = LoraTrainingStep {
+                step,
+                epoch,
+                loss: loss_sum / batch.len() as f64,
+                learning_rate: optimizer.learning_rate(),
+                tokens,
+            };
+            let checkpoint = if config
+                .checkpoint_every_steps
+                .is_some_and(|every| step % every == 0)
+            {
+                let dir = output_dir.join(format!("checkpoint-{step}"));
+                adapter.save(&dir, config, stack.base_model_tag())?;
+                Some(LoraTrainingCheckpoint { step, dir })
+            } else {
+                None
+            };
+            on_step(&record, checkpoint.as_ref());
+            steps.push(record);
+            checkpoints.extend(checkpoint);
+        }
+    }
+
+    let adapter_path = adapter.save(output_dir, config, stack.base_model_tag())?;
+    Ok(LoraTrainingReport {
+        steps,
+        checkpoints,
+        target_modules: targets.into_iter().map(|(target, _, _)| target).collect(),
+        adapter_path,
+    })
+}
+
+struct TrainingSequence {
+    input_ids: Vec<u32>,
+    /// First position whose token is a loss target.
+    loss_start: usize,
+}
+
+impl TrainingSequence {
+    fn new(example: &LoraTrainingExample, max_seq_len: usize) -> Option<Self> {
+        let mut input_ids = example.prompt_ids.clone();
+        input_ids.extend_from_slice(&example.completion_ids);
+        input_ids.truncate(max_seq_len);
+        // Position 0 has no preceding logit, so a promptless example starts
+        // its loss at the second completion token.
+        let loss_start = example.prompt_ids.len().max(1);
+        (loss_start < input_ids.len()).then_some(Self {
+            input_ids,
+            loss_start,
+        })
+    }
+
+    fn target_count(&self) -> usize {
+        self.input_ids.len() - self.loss_start
+    }
+}
+
+fn sequence_loss(
+    model: &mut dyn TransformerModel,
+    hooks: &CandleSteeringHooks,
+    sequence: &TrainingSequence,
+    device: &Device,
+) -> Result<Tensor, ModelRuntimeError> {
+    let input = Tensor::new(sequence.input_ids.as_slice(), device)
+        .and_then(|tensor| tensor.unsqueeze(0))
+        .map_err(candle_training_error)?;
+    let logits = model.forward_training_logits(&input, hooks, &[], &[])?;
+    let seq_len = sequence.input_ids.len() as u32;
+    let positions = Tensor::arange(sequence.loss_start as u32 - 1, seq_len - 1, device)
+        .map_err(candle_training_error)?;
+    let targets = Tensor::new(&sequence.input_ids[sequence.loss_start..], device)
+        .map_err(candle_training_error)?;
+    logits
+        .squeeze(0)
+        .and_then(|logits| logits.index_select(&positions, 0))
+        .and_then(|predicted| candle_nn::loss::cross_entropy(&predicted, &targets))
+        .map_err(candle_training_error)
+}
+
+struct TrainableAdapter {
+    targets: Vec<(String, Var, Var)>,
+}
+
+impl TrainableAdapter {
+    fn init(
+        targets: &[(String, usize, usize)],
+        rank: usize,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let targets = targets
+            .iter()
+            .map(|(target, in_dim, out_dim)| {
+                let bound = 1.0 / (*in_dim as f32).sqrt();
+                let a = Tensor::rand(-bound, bound, (rank, *in_dim), device)
+                    .and_then(|tensor| Var::from_tensor(&tensor))
+                    .map_err(candle_training_error)?;
+                let b = Var::zeros((*out_dim, rank), DType::F32, device)
+                    .map_err(candle_training_error)?;
+                Ok((target.clone(), a, b))
+            })
+            .collect::<Result<Vec<_>, ModelRuntimeError>>()?;
+        Ok(Self { targets })
+    }
+
+    fn vars(&self) -> Vec<Var> {
+        self.targets
+            .iter()
+            .flat_map(|(_, a, b)| [a.clone(), b.clone()])
+            .collect()
+    }
+
+    fn tensors(&self) -> Vec<(String, Tensor, Tensor)> {
+        self.targets
+            .iter()
+            .map(|(target, a, b)| (target.clone(), a.as_tensor().clone(), b.as_tensor().clone()))
+            .collect()
+    }
+
+    /// Write the adapter in PEFT layout and return the weights path.
+    fn save(
+        &self,
+        dir: &Path,
+        config: &LoraTrainingConfig,
+        base_model_tag: &str,
+    ) -> Result<PathBuf, ModelRuntimeError> {
+        fs::create_dir_all(dir).map_err(|error| {
+            training_error(format!("failed to create {}: {error}", dir.display()))
+        })?;
+        let mut tensors = HashMap::new();
+        for (target, a, b) in &self.targets {
+            tensors.insert(
+                format!("base_model.model.{target}.lora_A.weight"),
+                a.as_tensor().clone(),
+            );
+            tensors.insert(
+                format!("base_model.model.{target}.lora_B.weight"),
+                b.as_tensor().clone(),
+            );
+        }
+        let weights_path = dir.join(PEFT_ADAPTER_WEIGHTS_FILE);
+        candle_core::safetensors::save(&tensors, &weights_path).map_err(|error| {
+            training_error(format!(
+                "failed to write LoRA adapter {}: {error}",
+                weights_path.display()
+            ))
+        })?;
+        let adapter_config = serde_json::json!({
+            "peft_type": "LORA",
+            "task_type": "CAUSAL_LM",
+            "base_model_name_or_path": base_model_tag,
+            "r": config.rank,
+            "lora_alpha": config.alpha,
+            "lora_dropout": config.dropout,
+            "bias": "none",
+            "target_modules": self
+                .targets
+                .iter()
+                .map(|(target, _, _)| target.as_str())
+                .collect::<Vec<_>>(),
+        });
+        let config_path = dir.join(PEFT_ADAPTER_CONFIG_FILE);
+        let bytes = serde_json::to_vec_pretty(&adapter_config).map_err(|error| {
+            training_error(format!("failed to encode PEFT adapter config: {error}"))
+        })?;
+        fs::write(&config_path, bytes).map_err(|error| {
+            training_error(format!(
+                "failed to write PEFT adapter config {}: {error}",
+                config_path.display()
+            ))
+        })?;
+        Ok(weights_path)
+    }
+}
+
+/// Detaches the trainable adapter when training ends, including on error.
+struct AttachedTrainable<'a>(&'a CandleLoraStack);
+
+impl Drop for AttachedTrainable<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.detach_trainable();
+    }
+}
+
+fn select_targets(
+    available: &[(String, usize, usize)],
+    requested: &[String],
+) -> Result<Vec<(String, usize, usize)>, ModelRuntimeError> {
+    if available.is_empty() {
+        return Err(ModelRuntimeError::CapabilityNotSupported {
+            capability: "lora_training_target_dims".to_string(),
+            adapter: "candle_transformer_model".to_string(),
+        });
+    }
+    let is_match =
+        |target: &str, module: &str| target == module || target.ends_with(&format!(".{module}"));
+    let missing = requested
+        .iter()
+        .filter(|module| {
+            !available
+                .iter()
+                .any(|(target, _, _)| is_match(target, module))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(training_error(format!(
+            "LoRA target modules not present in model: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(available
+        .iter()
+        .filter(|(target, _, _)| requested.iter().any(|module| is_match(target, module)))
+        .cloned()
+        .collect())
+}
+
+fn validate_training_config(config: &LoraTrainingConfig) -> Result<(), ModelRuntimeError> {
+    if config.rank == 0 {
+        return Err(training_error("rank must be greater than zero"));
+    }
+    if config.epochs == 0 {
+        return Err(training_error("epochs must be greater than zero"));
+    }
+    if config.gradient_accumulation_steps == 0 {
+        return Err(training_error(
+            "gradient_accumulation_steps must be greater than zero",
+        ));
+    }
+    if !(config.learning_rate.is_finite() && config.learning_rate > 0.0) {
+        return Err(training_error("learning_rate must be a positive number"));
+    }
+    if !(0.0..1.0).contains(&config.dropout) {
+        return Err(training_error("dropout must be in [0, 1)"));
+    }
+    if config.max_seq_len < 2 {
+        return Err(training_error("max_seq_len must be at least 2"));
+    }
+    if config.target_modules.is_empty() {
+        return Err(training_error("target_modules must not be empty"));
+    }
+    if config.max_steps == Some(0) || config.checkpoint_every_steps == Some(0) {
+        return Err(training_error(
+            "max_steps and checkpoint_every_steps must be greater than zero when set",
+        ));
+    }
+    Ok(())
+}
+
+fn training_error(message: impl Into<String>) -> ModelRuntimeError {
+    ModelRuntimeError::TrainingError(message.into())
+}
+
+fn candle_training_error(error: candle_core::Error) -> ModelRuntimeError {
+    training_error(format!("Candle LoRA training step failed: {error}"))
+}
@@ -4,12 +4,18 @@ pub mod adapter;
 pub mod device;
 #[cfg(feature = "candle-runtime-engine")]
 pub mod generate;
+#[cfg(feature = "candle-runtime-engine")]
+pub mod gguf_metadata;
 pub mod hooks;
 #[cfg(feature = "candle-runtime-engine")]
+pub mod instrumented_decoder;
+#[cfg(feature = "candle-runtime-engine")]
 pub mod instrumented_llama;
 #[cfg(feature = "candle-runtime-engine")]
 pub mod lora_impl;
 #[cfg(feature = "candle-runtime-engine")]
+pub mod lora_train;
+#[cfg(feature = "candle-runtime-engine")]
 pub mod mamba2;
 #[cfg(feature = "candle-runtime-engine")]
 pub mod rwkv_v5;
@@ -36,8 +42,14 @@ pub use adapter::{
 pub use device::{
     select_candle_device, CandleDeviceKind, CandleDevicePreference, CandleDeviceSelection,
 };
+#[cfg(feature = "candle-runtime-engine")]
+pub use gguf_metadata::{
+    artifact_is_gguf, read_gguf_metadata, GgufModelMetadata, GgufTokenizerMetadata,
+};
 pub use hooks::{CandleSteeringHooks, CANDLE_DEFAULT_RESIDUAL_WIDTH};
 #[cfg(feature = "candle-runtime-engine")]
+pub use instrumented_decoder::{DecoderArchitecture, DecoderConfig};
+#[cfg(feature = "candle-runtime-engine")]
 pub use mamba2::CandleMamba2Model;
 #[cfg(feature = "candle-runtime-engine")]
 pub use rwkv_v5::CandleRwkvV5Model;
@@ -59,4 +71,4 @@ pub use tokenizer::{
     cache_tokenizer_if_present, tokenizer_json_path_for_artifact, CandleTokenizerCache,
 };
 #[cfg(feature = "candle-runtime-engine")]
-pub use transformer::{CandleLlamaModel, TransformerModel};
+pub use transformer::{CandleDecoderModel, CandleLlamaModel, TransformerModel};
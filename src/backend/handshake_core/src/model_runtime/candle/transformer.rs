@@ -7,7 +7,12 @@ use candle_nn::VarBuilder;
 use candle_transformers::models::llama::{Config, LlamaConfig, LlamaEosToks};
 
 use super::{
+    gguf_metadata::{open_gguf, GgufModelMetadata},
     hooks::CandleSteeringHooks,
+    instrumented_decoder::{
+        eos_token_ids as config_eos_token_ids, read_config_value, DecoderArchitecture,
+        DecoderConfig, InstrumentedDecoder, InstrumentedDecoderCache,
+    },
     instrumented_llama::{InstrumentedLlama, InstrumentedLlamaCache},
     lora_impl::CandleLoraStack,
     state_vector::SSMStateSnapshot,
@@ -58,6 +63,34 @@ pub trait TransformerModel: Send {
         })
     }
 
+    /// Teacher-forcing forward with every op on the autograd path, so a loss
+    /// over the returned `[batch, seq, vocab]` logits backpropagates into a
+    /// trainable adapter attached to [`Self::candle_lora_stack`]. Default impl
+    /// is capability-not-supported for backends without a differentiable pass.
+    fn forward_training_logits(
+        &mut self,
+        _input_ids: &Tensor,
+        _hooks: &CandleSteeringHooks,
+        _steering_overrides: &[SteeringVectorId],
+        _lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        Err(ModelRuntimeError::CapabilityNotSupported {
+            capability: "differentiable_training_forward".to_string(),
+            adapter: "candle_transformer_model".to_string(),
+        })
+    }
+
+    /// The Candle LoRA stack this model's forward consults, if any.
+    fn candle_lora_stack(&self) -> Option<&CandleLoraStack> {
+        None
+    }
+
+    /// `(target, in_dim, out_dim)` for every linear the LoRA stack can target,
+    /// so a trainer can allocate fresh A/B tensors of the right shape.
+    fn lora_target_dims(&self) -> Vec<(String, usize, usize)> {
+        Vec::new()
+    }
+
     fn n_layers(&self) -> u32;
 
     fn hidden_dim(&self) -> u32;
@@ -273,6 +306,55 @@ impl TransformerModel for CandleLlamaModel {
         result
     }
 
+    fn forward_training_logits(
+        &mut self,
+        input_ids: &Tensor,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        self.reset_generation_state()?;
+        self.cache.set_differentiable(true);
+        let result = self.model.forward_full_logits(
+            input_ids,
+            0,
+            &mut self.cache,
+            hooks,
+            steering_overrides,
+            &self.lora_stack,
+            lora_overrides,
+        );
+        self.reset_generation_state()?;
+        result
+    }
+
+    fn candle_lora_stack(&self) -> Option<&CandleLoraStack> {
+        Some(&self.lora_stack)
+    }
+
+    fn lora_target_dims(&self) -> Vec<(String, usize, usize)> {
+        let hidden = self.config.hidden_size;
+        let head_dim = hidden / self.config.num_attention_heads;
+        let size_q = head_dim * self.config.num_attention_heads;
+        let size_kv = head_dim * self.config.num_key_value_heads;
+        let intermediate = self.config.intermediate_size;
+        let mut dims = Vec::with_capacity(self.config.num_hidden_layers * 7);
+        for layer in 0..self.config.num_hidden_layers {
+            for (module, in_dim, out_dim) in [
+                ("self_attn.q_proj", hidden, size_q),
+                ("self_attn.k_proj", hidden, size_kv),
+                ("self_attn.v_proj", hidden, size_kv),
+                ("self_attn.o_proj", size_q, hidden),
+                ("mlp.gate_proj", hidden, intermediate),
+                ("mlp.up_proj", hidden, intermediate),
+                ("mlp.down_proj", intermediate, hidden),
+            ] {
+                dims.push((format!("model.layers.{layer}.{module}"), in_dim, out_dim));
+            }
+        }
+        dims
+    }
+
     fn n_layers(&self) -> u32 {
         self.config.num_hidden_layers as u32
     }
@@ -308,6 +390,229 @@ impl TransformerModel for CandleLlamaModel {
     }
 }
 
+/// Qwen2, Phi-3, Gemma, and Mistral checkpoints served through the owned
+/// [`InstrumentedDecoder`] forward, with the same steering, LoRA, scoring, and
+/// embedding seams as [`CandleLlamaModel`].
+pub struct CandleDecoderModel {
+    model: InstrumentedDecoder,
+    cache: InstrumentedDecoderCache,
+    config: DecoderConfig,
+    eos_token_ids: Vec<u32>,
+    index_pos: usize,
+    dtype: DType,
+    device: Device,
+    lora_stack: CandleLoraStack,
+}
+
+impl CandleDecoderModel {
+    pub fn load_safetensors_for_model(
+        model_id: ModelId,
+        artifact_path: &Path,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let value = read_config_value(artifact_path)?;
+        let architecture = DecoderArchitecture::from_config_value(&value).ok_or_else(|| {
+            ModelRuntimeError::LoadError(format!(
+                "Candle config for {} does not declare a Qwen2, Phi-3, Gemma, or Mistral model",
+                artifact_path.display()
+            ))
+        })?;
+        let config = DecoderConfig::from_config_value(architecture, &value)?;
+        let eos_token_ids = config_eos_token_ids(&value);
+        let dtype = DType::F32;
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[artifact_path], dtype, device).map_err(
+                |error| {
+                    ModelRuntimeError::LoadError(format!(
+                        "failed to mmap Candle safetensors {}: {error}",
+                        artifact_path.display()
+                    ))
+                },
+            )?
+        };
+        Self::from_varbuilder_with_dtype(model_id, config, eos_token_ids, vb, dtype, device)
+    }
+
+    /// Loads a GGUF checkpoint whose header was already parsed by
+    /// `read_gguf_metadata`. Weights stay in their block format and run
+    /// through quantized matmul; activations, LoRA deltas, and steering stay F32.
+    pub fn load_gguf_for_model(
+        model_id: ModelId,
+        artifact_path: &Path,
+        metadata: &GgufModelMetadata,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let (content, mut reader) = open_gguf(artifact_path)?;
+        let config = metadata.config.clone();
+        let model = InstrumentedDecoder::load_gguf(&content, &mut reader, &config, device)?;
+        Self::from_model(
+            model_id,
+            model,
+            config,
+            metadata.eos_token_ids.clone(),
+            DType::F32,
+            device,
+        )
+    }
+
+    pub fn from_varbuilder_for_model(
+        model_id: ModelId,
+        config: DecoderConfig,
+        vb: VarBuilder,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        Self::from_varbuilder_with_dtype(model_id, config, Vec::new(), vb, DType::F32, device)
+    }
+
+    fn from_varbuilder_with_dtype(
+        model_id: ModelId,
+        config: DecoderConfig,
+        eos_token_ids: Vec<u32>,
+        vb: VarBuilder,
+        dtype: DType,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let model = InstrumentedDecoder::load(vb, &config)?;
+        Self::from_model(model_id, model, config, eos_token_ids, dtype, device)
+    }
+
+    fn from_model(
+        model_id: ModelId,
+        model: InstrumentedDecoder,
+        config: DecoderConfig,
+        eos_token_ids: Vec<u32>,
+        dtype: DType,
+        device: &Device,
+    ) -> Result<Self, ModelRuntimeError> {
+        let cache = InstrumentedDecoderCache::new(dtype, &config, device)?;
+        let lora_stack = CandleLoraStack::new_for_device(
+            model_id,
+            config.architecture.lora_base_model(),
+            config.architecture.lora_targets(config.num_hidden_layers),
+            device.clone(),
+        );
+        Ok(Self {
+            model,
+            cache,
+            config,
+            eos_token_ids,
+            index_pos: 0,
+            dtype,
+            device: device.clone(),
+            lora_stack,
+        })
+    }
+
+    pub fn architecture(&self) -> DecoderArchitecture {
+        self.config.architecture
+    }
+
+    pub fn config(&self) -> &DecoderConfig {
+        &self.config
+    }
+}
+
+impl TransformerModel for CandleDecoderModel {
+    fn forward(
+        &mut self,
+        input_ids: &Tensor,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        let seq_len = input_ids.dims().last().copied().ok_or_else(|| {
+            ModelRuntimeError::GenerateError("empty Candle input tensor".to_string())
+        })?;
+        let logits = self.model.forward(
+            input_ids,
+            self.index_pos,
+            &mut self.cache,
+            hooks,
+            steering_overrides,
+            &self.lora_stack,
+            lora_overrides,
+        )?;
+        self.index_pos = self.index_pos.saturating_add(seq_len);
+        Ok(logits)
+    }
+
+    fn forward_full_logits(
+        &mut self,
+        input_ids: &Tensor,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        self.reset_generation_state()?;
+        let result = self.model.forward_full_logits(
+            input_ids,
+            0,
+            &mut self.cache,
+            hooks,
+            steering_overrides,
+            &self.lora_stack,
+            lora_overrides,
+        );
+        self.reset_generation_state()?;
+        result
+    }
+
+    fn forward_hidden_states(
+        &mut self,
+        input_ids: &Tensor,
+        hooks: &CandleSteeringHooks,
+        steering_overrides: &[SteeringVectorId],
+        lora_overrides: &[LoraId],
+    ) -> Result<Tensor, ModelRuntimeError> {
+        self.reset_generation_state()?;
+        let result = self.model.forward_hidden_states(
+            input_ids,
+            0,
+            &mut self.cache,
+            hooks,
+            steering_overrides,
+            &self.lora_stack,
+            lora_overrides,
+        );
+        self.reset_generation_state()?;
+        result
+    }
+
+    fn n_layers(&self) -> u32 {
+        self.config.num_hidden_layers as u32
+    }
+
+    fn hidden_dim(&self) -> u32 {
+        self.config.hidden_size as u32
+    }
+
+    fn vocab_size(&self) -> u32 {
+        self.config.vocab_size as u32
+    }
+
+    fn eos_token_ids(&self) -> &[u32] {
+        &self.eos_token_ids
+    }
+
+    fn device(&self) -> Device {
+        self.device.clone()
+    }
+
+    fn reset_generation_state(&mut self) -> Result<(), ModelRuntimeError> {
+        self.cache = InstrumentedDecoderCache::new(self.dtype, &self.config, &self.device)?;
+        self.index_pos = 0;
+        Ok(())
+    }
+
+    fn lora_stack(&self) -> LoraStackHandle {
+        self.lora_stack.handle()
+    }
+
+    fn validate_lora_overrides(&self, ids: &[LoraId]) -> Result<(), ModelRuntimeError> {
+        self.lora_stack.ensure_overrides_mounted(ids)
+    }
+}
+
 pub fn config_json_path_for_artifact(artifact_path: &Path) -> std::path::PathBuf {
     artifact_path
         .parent()
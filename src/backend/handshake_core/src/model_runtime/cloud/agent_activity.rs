@@ -133,6 +133,27 @@ pub fn parse_line(kind: CliKind, line: &str) -> Vec<AgentActivity> {
     }
 }
 
+/// Token usage reported by a lifecycle line (claude `result`, codex
+/// `turn.completed`): the top-level `usage` object, accepting both the
+/// `input_tokens`/`output_tokens` and `prompt_tokens`/`completion_tokens`
+/// spellings. Returns `None` for any line without one. Like [`parse_line`] this
+/// never fails; it feeds spend accounting, not the activity timeline.
+pub fn parse_usage_line(line: &str) -> Option<(Option<u32>, Option<u32>)> {
+    let value = serde_json::from_str::<Value>(line.trim()).ok()?;
+    let usage = value.get("usage")?.as_object()?;
+    let count = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|key| usage.get(*key).and_then(Value::as_u64))
+            .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+    };
+    let input = count(["input_tokens", "prompt_tokens"]);
+    let output = count(["output_tokens", "completion_tokens"]);
+    if input.is_none() && output.is_none() {
+        return None;
+    }
+    Some((input, output))
+}
+
 /// Decode a line to JSON; on failure return the lossless `Other` fallback so the
 /// caller can early-return.
 fn json_or_other(line: &str) -> Result<Value, Vec<AgentActivity>> {
@@ -405,6 +426,25 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn usage_is_read_from_claude_result_and_codex_turn_completed() {
+        let claude = json!({
+            "type": "result",
+            "subtype": "success",
+            "usage": {"input_tokens": 1200, "output_tokens": 340}
+        })
+        .to_string();
+        assert_eq!(parse_usage_line(&claude), Some((Some(1200), Some(340))));
+        let codex = json!({
+            "type": "turn.completed",
+            "usage": {"input_tokens": 80, "cached_input_tokens": 0, "output_tokens": 12}
+        })
+        .to_string();
+        assert_eq!(parse_usage_line(&codex), Some((Some(80), Some(12))));
+        assert_eq!(parse_usage_line(r#"{"type":"assistant"}"#), None);
+        assert_eq!(parse_usage_line("not json"), None);
+    }
+
     #[test]
     fn empty_line_yields_no_activity() {
         assert!(parse_line(CliKind::ClaudeCode, "").is_empty());
@@ -61,6 +61,10 @@ use crate::flight_recorder::events_llm_infer::{
     infer_end_event, infer_start_event, infer_token_event, new_llm_infer_request_id,
     should_emit_token_event,
 };
+use crate::model_runtime::cloud::consent_gate::ConsentGateError;
+use crate::model_runtime::cloud::spend::{
+    usage_or_estimate, SpendReservation, SPEND_PROVIDER_ANTHROPIC,
+};
 use crate::model_runtime::cloud::CloudLaneObservability;
 use crate::model_runtime::{
     error::ModelRuntimeError, CancellationToken, Embedding, FinishReason, GenerateRequest,
@@ -110,8 +114,11 @@ pub const ANTHROPIC_VERSION_HEADER: &str = "anthropic-version";
 /// as `content_block_delta`; the stream terminates cleanly on
 /// `message_stop`. Other events (`message_start`,
 /// `content_block_start`, `content_block_stop`, `ping`,
-/// `message_delta`) are observable but do not yield tokens.
+/// `message_delta`) are observable but do not yield tokens;
+/// `message_start` and `message_delta` carry the usage that prices
+/// the call.
 const SSE_EVENT_CONTENT_BLOCK_DELTA: &str = "content_block_delta";
+const SSE_EVENT_MESSAGE_START: &str = "message_start";
 const SSE_EVENT_MESSAGE_DELTA: &str = "message_delta";
 const SSE_EVENT_MESSAGE_STOP: &str = "message_stop";
 
@@ -212,11 +219,36 @@ struct ContentBlockDelta {
 }
 
 /// JSON shape of a `message_delta` SSE event payload. Carries the
-/// final `stop_reason` once the model has decided to terminate.
+/// final `stop_reason` once the model has decided to terminate, and
+/// the cumulative `usage.output_tokens`.
 #[derive(Debug, Deserialize)]
 struct MessageDeltaPayload {
     #[serde(default)]
     delta: MessageDelta,
+    #[serde(default)]
+    usage: Option<MessageUsage>,
+}
+
+/// JSON shape of a `message_start` SSE event payload. Only
+/// `message.usage.input_tokens` is consumed, for spend accounting.
+#[derive(Debug, Deserialize)]
+struct MessageStartPayload {
+    #[serde(default)]
+    message: MessageStartMessage,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageStartMessage {
+    #[serde(default)]
+    usage: Option<MessageUsage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageUsage {
+    #[serde(default)]
+    input_tokens: Option<u32>,
+    #[serde(default)]
+    output_tokens: Option<u32>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -456,21 +488,37 @@ impl AnthropicByokRuntime {
             }
         };
 
+        // Best-effort prompt-token estimate: whitespace-split word
+        // count. The FR-event field need not be exact (the recorder
+        // does not constrain it for cloud lanes); a server-side exact
+        // count is not available before the response. The budget
+        // check uses the same estimate.
+        let prompt_tokens_estimate = req.prompt.as_str().split_whitespace().count() as u64;
+
         // MT-126 remediation: per-session/per-lane consent gate. When
         // an observability bundle with a consent context is attached,
         // the operator must have consented (or consent now via the
-        // provider) before any cloud bytes leave the process. On
-        // denial we surface a GenerateError and issue NO HTTP request.
+        // provider) before any cloud bytes leave the process. A spend
+        // context puts the hard budgets in front of the prompt. On
+        // refusal we surface a GenerateError and issue NO HTTP request.
         // Mirrors the OpenAI BYOK sibling (MT-125).
+        let mut spend_reservation = None;
         if let Some(lane_obs) = self.lane_obs.as_ref() {
-            if let Some(consent) = lane_obs.consent.as_ref() {
-                if let Err(err) = consent.gate.check_or_prompt(
-                    &consent.session_id,
-                    self.adapter_name(),
-                    consent.provider.as_ref(),
-                ) {
+            match lane_obs.admit_dispatch(
+                self.adapter_name(),
+                SPEND_PROVIDER_ANTHROPIC,
+                &handle.anthropic_model_name,
+                prompt_tokens_estimate,
+                req.max_tokens,
+            ) {
+                Ok(reservation) => spend_reservation = reservation,
+                Err(err) => {
+                    let refusal = match err {
+                        ConsentGateError::Budget(_) => "budget exceeded",
+                        _ => "consent denied",
+                    };
                     return single_error_stream(ModelRuntimeError::GenerateError(format!(
-                        "Anthropic BYOK cloud consent denied: {err}"
+                        "Anthropic BYOK cloud {refusal}: {err}"
                     )));
                 }
             }
@@ -531,11 +579,6 @@ impl AnthropicByokRuntime {
             .as_ref()
             .map(|obs| obs.flight_recorder.clone());
         let request_id = new_llm_infer_request_id();
-        // Best-effort prompt-token estimate: whitespace-split word
-        // count. The FR-event field need not be exact (the recorder
-        // does not constrain it for cloud lanes); a server-side exact
-        // count is not available before the response.
-        let prompt_tokens_estimate = req.prompt.as_str().split_whitespace().count() as u64;
 
         // The reqwest+SSE pipeline runs inside a spawned task and
         // pumps `GeneratedToken`s onto an mpsc channel; the
@@ -565,6 +608,9 @@ impl AnthropicByokRuntime {
                 model_id,
                 request_id,
                 prompt_tokens_estimate,
+                spend_obs: self.lane_obs.clone(),
+                wire_model: handle.anthropic_model_name.clone(),
+                spend_reservation: std::sync::Mutex::new(spend_reservation),
             },
         )
     }
@@ -581,6 +627,13 @@ struct LaneFrContext {
     model_id: ModelId,
     request_id: uuid::Uuid,
     prompt_tokens_estimate: u64,
+    /// Lane bundle whose spend ledger (if any) is charged once the
+    /// stream has produced output, plus the wire model it is priced as.
+    spend_obs: Option<Arc<CloudLaneObservability>>,
+    wire_model: String,
+    /// Estimate held by the ledger since admission; settled by
+    /// [`settle_spend`], released if the stream fails before that.
+    spend_reservation: std::sync::Mutex<Option<SpendReservation>>,
 }
 
 /// Returns a one-shot stream that yields a single error item. Used
@@ -718,9 +771,11 @@ async fn run_live_stream(
     let mut sse = response.bytes_stream().eventsource();
     let mut token_index: u32 = 0;
     let mut pending_finish: Option<FinishReason> = None;
+    let mut usage = MessageUsage::default();
 
     while let Some(event) = sse.next().await {
         if cancel_req.is_cancelled() || cancel_runtime.is_cancelled() {
+            settle_spend(&fr_ctx, &usage, token_index);
             record_final_audit(&audit_sink, &audit_template, CloudCallStatus::Cancelled);
             emit_fr_end(
                 &fr_ctx,
@@ -808,6 +863,7 @@ async fn run_live_stream(
                             .is_err()
                         {
                             // Receiver dropped — caller no longer cares.
+                            settle_spend(&fr_ctx, &usage, token_index);
                             record_final_audit(
                                 &audit_sink,
                                 &audit_template,
@@ -825,14 +881,26 @@ async fn run_live_stream(
                     }
                 }
             }
+            SSE_EVENT_MESSAGE_START => {
+                // `message_start` reports the billed input tokens.
+                if let Ok(payload) = serde_json::from_str::<MessageStartPayload>(&event.data) {
+                    if let Some(start_usage) = payload.message.usage {
+                        usage.input_tokens = start_usage.input_tokens.or(usage.input_tokens);
+                    }
+                }
+            }
             SSE_EVENT_MESSAGE_DELTA => {
-                // `message_delta` carries the final `stop_reason`.
-                // We buffer it; the actual stream-close signal is
-                // the subsequent `message_stop` event.
+                // `message_delta` carries the final `stop_reason`
+                // and the cumulative output tokens. We buffer it; the
+                // actual stream-close signal is the subsequent
+                // `message_stop` event.
                 if let Ok(payload) = serde_json::from_str::<MessageDeltaPayload>(&event.data) {
                     if let Some(reason) = payload.delta.stop_reason {
                         pending_finish = map_finish_reason(&reason);
                     }
+                    if let Some(delta_usage) = payload.usage {
+                        usage.output_tokens = delta_usage.output_tokens.or(usage.output_tokens);
+                    }
                 }
             }
             SSE_EVENT_MESSAGE_STOP => {
@@ -840,13 +908,14 @@ async fn run_live_stream(
                 // a preceding `message_delta`, emit it; otherwise
                 // default to FinishReason::Stop.
                 let finish = pending_finish.unwrap_or(FinishReason::Stop);
+                settle_spend(&fr_ctx, &usage, token_index);
                 record_final_audit(&audit_sink, &audit_template, CloudCallStatus::Succeeded);
                 emit_fr_end(&fr_ctx, token_index, &start_instant, finish).await;
                 let _ = sender.send(Ok(terminal_token(finish)));
                 return;
             }
-            // Other Anthropic events (`message_start`,
-            // `content_block_start`, `content_block_stop`, `ping`)
+            // Other Anthropic events (`content_block_start`,
+            // `content_block_stop`, `ping`)
             // are observable but do not affect the token stream.
             _ => {}
         }
@@ -856,11 +925,38 @@ async fn run_live_stream(
     // clean server-side close; a truly broken response would have
     // produced an error item above and returned.
     let finish = pending_finish.unwrap_or(FinishReason::Stop);
+    settle_spend(&fr_ctx, &usage, token_index);
     record_final_audit(&audit_sink, &audit_template, CloudCallStatus::Succeeded);
     emit_fr_end(&fr_ctx, token_index, &start_instant, finish).await;
     let _ = sender.send(Ok(terminal_token(finish)));
 }
 
+/// Charges the call to the lane's spend ledger. `message_start` and
+/// `message_delta` usage is authoritative; whatever the stream did
+/// not report falls back to the prompt estimate and the number of
+/// text deltas.
+fn settle_spend(fr_ctx: &LaneFrContext, usage: &MessageUsage, token_index: u32) {
+    if let Some(lane_obs) = fr_ctx.spend_obs.as_ref() {
+        let usage = usage_or_estimate(
+            usage.input_tokens,
+            usage.output_tokens,
+            fr_ctx.prompt_tokens_estimate,
+            token_index,
+        );
+        let reservation = fr_ctx
+            .spend_reservation
+            .lock()
+            .ok()
+            .and_then(|mut reservation| reservation.take());
+        lane_obs.record_spend(
+            SPEND_PROVIDER_ANTHROPIC,
+            &fr_ctx.wire_model,
+            &usage,
+            reservation,
+        );
+    }
+}
+
 /// MT-126 remediation: emit a single FR event through the attached
 /// recorder. When no recorder is attached this is a no-op (exact prior
 /// behaviour). Recorder failures are logged and swallowed — observ-
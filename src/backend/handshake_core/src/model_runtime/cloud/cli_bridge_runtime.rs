@@ -41,10 +41,14 @@ use async_trait::async_trait;
 use futures::stream;
 use uuid::Uuid;
 
-use super::agent_activity::parse_line as parse_agent_line;
+use super::agent_activity::{parse_line as parse_agent_line, parse_usage_line};
 use super::official_cli_bridge::{
     CliBridgeConfig, CliKind, CliOutputFormat, CliSubprocessSpawner, OfficialCliBridgeRuntime,
 };
+use super::spend::{
+    usage_or_estimate, SpendReservation, SPEND_PROVIDER_ANTHROPIC, SPEND_PROVIDER_GOOGLE,
+    SPEND_PROVIDER_OPENAI, SPEND_PROVIDER_OTHER,
+};
 use super::CloudLaneObservability;
 use crate::flight_recorder::events_agent_activity::agent_activity_event;
 use crate::flight_recorder::events_llm_infer::{
@@ -212,6 +216,33 @@ struct InferEmitStreamState {
     /// line buffer and complete lines are parsed into `FR-EVT-AGENT-*` events.
     /// `None` in `RawText` mode => zero structured behaviour, unchanged output.
     agent_capture: Option<AgentCaptureState>,
+    /// Spend settlement, charged once at END. `None` when no lane bundle is
+    /// attached.
+    spend: Option<CliSpendState>,
+}
+
+/// Per-request spend accounting state. Usage comes from the CLI's own
+/// lifecycle lines (claude `result`, codex `turn.completed`) when it runs in a
+/// JSON-stream mode; otherwise — or when the CLI reports none — the prompt
+/// word-count proxy and the streamed chunk count stand in.
+struct CliSpendState {
+    lane_obs: Arc<CloudLaneObservability>,
+    provider: &'static str,
+    model: String,
+    reported_prompt: Option<u32>,
+    reported_completion: Option<u32>,
+    /// Estimate held by the ledger since admission, settled at END.
+    reservation: Option<SpendReservation>,
+}
+
+/// The pricing-table provider a CLI bills against.
+fn spend_provider(kind: CliKind) -> &'static str {
+    match kind {
+        CliKind::ClaudeCode => SPEND_PROVIDER_ANTHROPIC,
+        CliKind::CodexCli => SPEND_PROVIDER_OPENAI,
+        CliKind::GeminiCli => SPEND_PROVIDER_GOOGLE,
+        CliKind::Other => SPEND_PROVIDER_OTHER,
+    }
 }
 
 /// Per-request structured agent-activity capture state, carried on the async
@@ -418,17 +449,46 @@ impl CliBridgeModelRuntime {
             }
         };
 
+        // Consent and hard spend budgets are checked before the subprocess is
+        // spawned; a refusal surfaces as a single error item and nothing runs.
+        let prompt_tokens = req.prompt.text.split_whitespace().count() as u64;
+        let spend_provider = spend_provider(self.config_template.cli_kind);
+        let mut spend_reservation = None;
+        if let Some(lane_obs) = self.lane_obs.as_ref() {
+            match lane_obs.admit_dispatch(
+                CLI_BRIDGE_ADAPTER,
+                spend_provider,
+                &handle.model_name,
+                prompt_tokens,
+                req.max_tokens,
+            ) {
+                Ok(reservation) => spend_reservation = reservation,
+                Err(err) => {
+                    return single_error_stream(ModelRuntimeError::GenerateError(format!(
+                        "official CLI bridge generate refused: {err}"
+                    )));
+                }
+            }
+        }
+
         let (tx, rx) =
             tokio::sync::mpsc::unbounded_channel::<Result<GeneratedToken, ModelRuntimeError>>();
 
         // Observability is read on the ASYNC side (the unfold below) where
         // `record_event().await` is legal; the blocking spawn thread only ever
         // produces tokens. A coarse prompt-token proxy (whitespace word count)
-        // is used because the CLI does not expose tokenisation — it is for the
-        // observability payload only, never billing/control.
+        // is used because the CLI does not expose tokenisation — spend falls
+        // back to it only when the CLI reports no usage of its own.
         let recorder = self.lane_obs.as_ref().map(|o| o.flight_recorder.clone());
         let request_id = new_llm_infer_request_id();
-        let prompt_tokens = req.prompt.text.split_whitespace().count() as u64;
+        let spend = self.lane_obs.as_ref().map(|lane_obs| CliSpendState {
+            lane_obs: lane_obs.clone(),
+            provider: spend_provider,
+            model: handle.model_name.clone(),
+            reported_prompt: None,
+            reported_completion: None,
+            reservation: spend_reservation,
+        });
         let infer_start = Instant::now();
         let infer_model_id = req.id;
 
@@ -547,6 +607,7 @@ impl CliBridgeModelRuntime {
             finish: FinishReason::Stop,
             ended: false,
             agent_capture,
+            spend,
         };
 
         Box::pin(stream::unfold(state, |mut st| async move {
@@ -604,11 +665,13 @@ impl CliBridgeModelRuntime {
                                     model_id,
                                     request_id,
                                     agent_capture,
+                                    spend,
                                     ..
                                 } = &mut st;
                                 if let Some(cap) = agent_capture.as_mut() {
                                     let lines = cap.line_buf.push(&token.text);
                                     for line in lines {
+                                        note_usage_line(spend, &line);
                                         emit_agent_line(
                                             recorder,
                                             cap.cli_kind,
@@ -641,10 +704,12 @@ impl CliBridgeModelRuntime {
                             model_id,
                             request_id,
                             agent_capture,
+                            spend,
                             ..
                         } = &mut st;
                         if let Some(cap) = agent_capture.as_mut() {
                             if let Some(line) = cap.line_buf.flush() {
+                                note_usage_line(spend, &line);
                                 emit_agent_line(
                                     recorder,
                                     cap.cli_kind,
@@ -661,6 +726,20 @@ impl CliBridgeModelRuntime {
                     }
                     if !st.ended {
                         st.ended = true;
+                        if let Some(spend) = st.spend.as_mut() {
+                            let usage = usage_or_estimate(
+                                spend.reported_prompt,
+                                spend.reported_completion,
+                                st.prompt_tokens,
+                                st.generated,
+                            );
+                            spend.lane_obs.record_spend(
+                                spend.provider,
+                                &spend.model,
+                                &usage,
+                                spend.reservation.take(),
+                            );
+                        }
                         let total = st.start.elapsed().as_millis() as u64;
                         emit_infer(
                             &st.recorder,
@@ -685,6 +764,15 @@ impl CliBridgeModelRuntime {
     }
 }
 
+/// Fold a lifecycle line's reported usage into the spend state (last report
+/// wins, matching the CLIs' cumulative totals).
+fn note_usage_line(spend: &mut Option<CliSpendState>, line: &str) {
+    if let (Some(spend), Some((prompt, completion))) = (spend.as_mut(), parse_usage_line(line)) {
+        spend.reported_prompt = prompt.or(spend.reported_prompt);
+        spend.reported_completion = completion.or(spend.reported_completion);
+    }
+}
+
 /// One-shot stream that yields a single error item (preflight failure surfaced
 /// inside the [`TokenStream`] contract). Mirrors the BYOK sibling helper.
 fn single_error_stream(err: ModelRuntimeError) -> TokenStream {
@@ -1197,6 +1285,7 @@ mod tests {
         let obs = Arc::new(CloudLaneObservability {
             flight_recorder: recorder.clone() as Arc<dyn FlightRecorder>,
             consent: None,
+            spend: None,
         });
         // 20 single-byte chunks => 20 generated tokens => token index 16 fires a
         // sampled TOKEN event (LLM_INFER_TOKEN_SAMPLE_INTERVAL = 16).
@@ -1312,6 +1401,7 @@ mod tests {
         Arc::new(CloudLaneObservability {
             flight_recorder: recorder as Arc<dyn FlightRecorder>,
             consent: None,
+            spend: None,
         })
     }
 
@@ -1605,6 +1695,7 @@ mod tests {
         let obs = Arc::new(CloudLaneObservability {
             flight_recorder: recorder.clone() as Arc<dyn FlightRecorder>,
             consent: None,
+            spend: None,
         });
 
         // One claude assistant line (text + thinking + tool_use), newline-terminated.
@@ -14,12 +14,19 @@
 //! lane, consent_provider)` returns Ok(()) if the operator has
 //! consented or consents now via the provider; otherwise an
 //! ConsentDenied error that the caller surfaces.
+//!
+//! [`ConsentGate::check_or_prompt_within_budget`] puts the spend
+//! ledger's hard budgets in front of that contract: a dispatch that
+//! would exceed a hard budget is refused before the operator is
+//! prompted, and the refusal is not cached as a consent decision.
 
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
 use thiserror::Error;
 
+use super::spend::{CloudSpendError, CloudSpendLedger, SpendDispatch, SpendReservation};
+
 /// Two-tuple key into the consent map: (session_id, lane_id).
 type ConsentKey = (String, String);
 
@@ -35,6 +42,8 @@ pub enum ConsentGateError {
     ProviderError(String),
     #[error("internal consent gate lock poisoned: {0}")]
     LockPoisoned(String),
+    #[error(transparent)]
+    Budget(#[from] CloudSpendError),
 }
 
 /// Operator decision returned by the consent provider.
@@ -128,6 +137,24 @@ impl ConsentGate {
         }
     }
 
+    /// [`Self::check_or_prompt`] behind the spend ledger's hard
+    /// budgets. A refused dispatch never reaches the provider prompt,
+    /// so a later call that fits the budget still asks for consent.
+    /// The returned reservation holds the call's estimate until it is
+    /// settled; a denied consent drops it again.
+    pub fn check_or_prompt_within_budget(
+        &self,
+        session_id: &str,
+        lane: &str,
+        provider: &dyn ConsentProvider,
+        ledger: &Arc<CloudSpendLedger>,
+        dispatch: &SpendDispatch,
+    ) -> Result<SpendReservation, ConsentGateError> {
+        let reservation = ledger.admit(dispatch)?;
+        self.check_or_prompt(session_id, lane, provider)?;
+        Ok(reservation)
+    }
+
     /// Forget the decision for a (session, lane) pair. Useful for
     /// tests + for the session-close cleanup path that drops
     /// per-session consent when the session ends.
@@ -288,4 +315,60 @@ mod tests {
             ConsentGateError::EmptyLaneId
         ));
     }
+
+    #[test]
+    fn hard_budget_refuses_before_prompting_for_consent() {
+        use crate::model_runtime::cloud::spend::{
+            CloudSpendSettings, SpendAttribution, SpendBudget, SpendScopeKind,
+        };
+
+        let gate = ConsentGate::new();
+        let provider = StaticProvider {
+            decision: ConsentDecision::Approved,
+            prompts: Mutex::new(0),
+        };
+        let ledger = Arc::new(CloudSpendLedger::new(CloudSpendSettings {
+            pricing: Vec::new(),
+            budgets: vec![SpendBudget {
+                scope: SpendScopeKind::Workspace,
+                soft_limit_usd: None,
+                hard_limit_usd: Some(0.01),
+            }],
+        }));
+        let dispatch = |max_output_tokens| SpendDispatch {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            attribution: SpendAttribution::new().with_workspace("ws-1"),
+            estimated_input_tokens: 100,
+            max_output_tokens,
+        };
+
+        // 100 in + 10k out on gpt-4o is $0.10, over the $0.01 limit.
+        let err = gate
+            .check_or_prompt_within_budget(
+                "session-1",
+                "openai",
+                &provider,
+                &ledger,
+                &dispatch(10_000),
+            )
+            .expect_err("over budget");
+        assert!(matches!(
+            err,
+            ConsentGateError::Budget(CloudSpendError::HardBudget(_))
+        ));
+        assert_eq!(*provider.prompts.lock().unwrap(), 0);
+
+        let reservation = gate
+            .check_or_prompt_within_budget(
+                "session-1",
+                "openai",
+                &provider,
+                &ledger,
+                &dispatch(100),
+            )
+            .expect("fits the budget");
+        assert_eq!(*provider.prompts.lock().unwrap(), 1);
+        assert!(reservation.estimate_usd() > 0.0);
+    }
 }
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use crate::flight_recorder::FlightRecorder;
+use crate::llm::TokenUsage;
 
 pub mod agent_activity;
 pub mod anthropic_byok;
@@ -11,6 +12,7 @@ pub mod consent_gate;
 pub mod official_cli_bridge;
 pub mod openai_byok;
 pub mod secrets_vault;
+pub mod spend;
 
 /// MT-125 remediation: per-session, per-lane consent context threaded
 /// into a cloud lane adapter so it can enforce the operator
@@ -30,12 +32,84 @@ pub struct CloudConsentContext {
 /// `FR-EVT-LLM-INFER-{START,TOKEN,END}` events through the
 /// [`FlightRecorder`] for HBR-INT-005 lane normalisation and (2)
 /// optionally enforce the operator [`CloudConsentContext`] before the
-/// live HTTP call. Anthropic / official-CLI adapters reuse this same
-/// type so the cloud lane shares one observability surface.
+/// live HTTP call, and (3) optionally charge the call's token usage
+/// to a [`CloudSpendLedger`] under hard/soft budgets. Anthropic /
+/// official-CLI adapters reuse this same type so the cloud lane
+/// shares one observability surface.
 #[derive(Clone)]
 pub struct CloudLaneObservability {
     pub flight_recorder: Arc<dyn FlightRecorder>,
     pub consent: Option<CloudConsentContext>,
+    /// Spend ledger and attribution. When set, a dispatch is admitted
+    /// against the hard budgets first (through the consent gate when
+    /// one is attached) and the finished call's usage is charged to
+    /// the ledger.
+    pub spend: Option<CloudSpendContext>,
+}
+
+impl CloudLaneObservability {
+    /// Pre-dispatch gate shared by the cloud adapters: hard budgets
+    /// and consent when both are attached, otherwise whichever is. A
+    /// budget refusal surfaces as [`ConsentGateError::Budget`]. An
+    /// admitted call's estimate stays reserved in the ledger until the
+    /// returned reservation is passed to [`Self::record_spend`] (or
+    /// dropped, when the call fails before reporting usage).
+    pub fn admit_dispatch(
+        &self,
+        lane: &str,
+        provider: &str,
+        model: &str,
+        estimated_input_tokens: u64,
+        max_output_tokens: u32,
+    ) -> Result<Option<SpendReservation>, ConsentGateError> {
+        let dispatch = self.spend.as_ref().map(|spend| {
+            (
+                spend,
+                spend.dispatch(provider, model, estimated_input_tokens, max_output_tokens),
+            )
+        });
+        match (self.consent.as_ref(), dispatch) {
+            (Some(consent), Some((spend, dispatch))) => consent
+                .gate
+                .check_or_prompt_within_budget(
+                    &consent.session_id,
+                    lane,
+                    consent.provider.as_ref(),
+                    &spend.ledger,
+                    &dispatch,
+                )
+                .map(Some),
+            (Some(consent), None) => consent
+                .gate
+                .check_or_prompt(&consent.session_id, lane, consent.provider.as_ref())
+                .map(|()| None),
+            (None, Some((spend, dispatch))) => Ok(Some(spend.ledger.admit(&dispatch)?)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Charges a finished call to the spend ledger, if one is attached,
+    /// settling the reservation [`Self::admit_dispatch`] took for it.
+    pub fn record_spend(
+        &self,
+        provider: &str,
+        model: &str,
+        usage: &TokenUsage,
+        reservation: Option<SpendReservation>,
+    ) {
+        match reservation {
+            Some(reservation) => {
+                reservation.settle(usage);
+            }
+            None => {
+                if let Some(spend) = self.spend.as_ref() {
+                    spend
+                        .ledger
+                        .record(provider, model, &spend.attribution, usage);
+                }
+            }
+        }
+    }
 }
 
 pub use agent_activity::{
@@ -60,3 +134,8 @@ pub use openai_byok::{
 pub use secrets_vault::{
     InMemorySecretsVault, SecretsVault, SecretsVaultError, VaultApiKeyProvider,
 };
+pub use spend::{
+    BudgetCrossing, BudgetThreshold, CloudSpendContext, CloudSpendError, CloudSpendLedger,
+    CloudSpendSettings, ModelPrice, PricingTable, SpendAttribution, SpendBudget, SpendDispatch,
+    SpendReservation, SpendRollup, SpendScope, SpendScopeKind,
+};
@@ -49,6 +49,10 @@ use crate::flight_recorder::events_llm_infer::{
     infer_end_event, infer_start_event, infer_token_event, new_llm_infer_request_id,
     should_emit_token_event,
 };
+use crate::model_runtime::cloud::consent_gate::ConsentGateError;
+use crate::model_runtime::cloud::spend::{
+    usage_or_estimate, SpendReservation, SPEND_PROVIDER_OPENAI,
+};
 use crate::model_runtime::cloud::CloudLaneObservability;
 use crate::model_runtime::{
     error::ModelRuntimeError, CancellationToken, Embedding, FinishReason, GenerateRequest,
@@ -177,6 +181,14 @@ struct ChatCompletionsRequest<'a> {
     presence_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     stop: Vec<String>,
+    /// Asks for the trailing `usage` chunk so the call can be priced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -191,6 +203,18 @@ struct ChatMessage<'a> {
 struct ChatStreamChunk {
     #[serde(default)]
     choices: Vec<ChatStreamChoice>,
+    /// Present only on the final chunk when `include_usage` is set;
+    /// that chunk carries an empty `choices` array.
+    #[serde(default)]
+    usage: Option<ChatStreamUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatStreamUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u32>,
+    #[serde(default)]
+    completion_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -472,20 +496,36 @@ impl OpenAiByokRuntime {
             }
         };
 
+        // Best-effort prompt-token estimate: whitespace-split word
+        // count. The FR-event field need not be exact (the recorder
+        // does not constrain it for cloud lanes); a server-side exact
+        // count is not available before the response. The budget
+        // check uses the same estimate.
+        let prompt_tokens_estimate = req.prompt.as_str().split_whitespace().count() as u64;
+
         // MT-125 remediation: per-session/per-lane consent gate. When
         // an observability bundle with a consent context is attached,
         // the operator must have consented (or consent now via the
-        // provider) before any cloud bytes leave the process. On
-        // denial we surface a GenerateError and issue NO HTTP request.
+        // provider) before any cloud bytes leave the process. A spend
+        // context puts the hard budgets in front of the prompt. On
+        // refusal we surface a GenerateError and issue NO HTTP request.
+        let mut spend_reservation = None;
         if let Some(lane_obs) = self.lane_obs.as_ref() {
-            if let Some(consent) = lane_obs.consent.as_ref() {
-                if let Err(err) = consent.gate.check_or_prompt(
-                    &consent.session_id,
-                    self.adapter_name(),
-                    consent.provider.as_ref(),
-                ) {
+            match lane_obs.admit_dispatch(
+                self.adapter_name(),
+                SPEND_PROVIDER_OPENAI,
+                &handle.openai_model_name,
+                prompt_tokens_estimate,
+                req.max_tokens,
+            ) {
+                Ok(reservation) => spend_reservation = reservation,
+                Err(err) => {
+                    let refusal = match err {
+                        ConsentGateError::Budget(_) => "budget exceeded",
+                        _ => "consent denied",
+                    };
                     return single_error_stream(ModelRuntimeError::GenerateError(format!(
-                        "OpenAI BYOK cloud consent denied: {err}"
+                        "OpenAI BYOK cloud {refusal}: {err}"
                     )));
                 }
             }
@@ -505,6 +545,9 @@ impl OpenAiByokRuntime {
             frequency_penalty: req.sampling.frequency_penalty,
             presence_penalty: req.sampling.presence_penalty,
             stop: req.stop_sequences.clone(),
+            stream_options: Some(StreamOptions {
+                include_usage: true,
+            }),
         };
 
         let body_json = match serde_json::to_vec(&body) {
@@ -546,11 +589,6 @@ impl OpenAiByokRuntime {
             .as_ref()
             .map(|obs| obs.flight_recorder.clone());
         let request_id = new_llm_infer_request_id();
-        // Best-effort prompt-token estimate: whitespace-split word
-        // count. The FR-event field need not be exact (the recorder
-        // does not constrain it for cloud lanes); a server-side exact
-        // count is not available before the response.
-        let prompt_tokens_estimate = req.prompt.as_str().split_whitespace().count() as u64;
 
         // The reqwest+SSE pipeline runs inside a spawned task and
         // pumps `GeneratedToken`s onto an mpsc channel; the
@@ -579,6 +617,9 @@ impl OpenAiByokRuntime {
                 model_id,
                 request_id,
                 prompt_tokens_estimate,
+                spend_obs: self.lane_obs.clone(),
+                wire_model: handle.openai_model_name.clone(),
+                spend_reservation: std::sync::Mutex::new(spend_reservation),
             },
         )
     }
@@ -594,6 +635,13 @@ struct LaneFrContext {
     model_id: ModelId,
     request_id: uuid::Uuid,
     prompt_tokens_estimate: u64,
+    /// Lane bundle whose spend ledger (if any) is charged once the
+    /// stream has produced output, plus the wire model it is priced as.
+    spend_obs: Option<Arc<CloudLaneObservability>>,
+    wire_model: String,
+    /// Estimate held by the ledger since admission; settled by
+    /// [`settle_spend`], released if the stream fails before that.
+    spend_reservation: std::sync::Mutex<Option<SpendReservation>>,
 }
 
 /// Returns a one-shot stream that yields a single error item. Used
@@ -728,9 +776,11 @@ async fn run_live_stream(
     let mut sse = response.bytes_stream().eventsource();
     let mut token_index: u32 = 0;
     let mut hit_done = false;
+    let mut reported_usage: Option<ChatStreamUsage> = None;
 
     while let Some(event) = sse.next().await {
         if cancel_req.is_cancelled() || cancel_runtime.is_cancelled() {
+            settle_spend(&fr_ctx, reported_usage.as_ref(), token_index);
             record_final_audit(&audit_sink, &audit_template, CloudCallStatus::Cancelled);
             emit_fr_end(
                 &fr_ctx,
@@ -773,6 +823,10 @@ async fn run_live_stream(
             }
         };
 
+        if chunk.usage.is_some() {
+            reported_usage = chunk.usage;
+        }
+
         if let Some(choice) = chunk.choices.into_iter().next() {
             let finish_mapped = choice.finish_reason.as_deref().and_then(map_finish_reason);
             if let Some(text) = choice.delta.content {
@@ -810,6 +864,7 @@ async fn run_live_stream(
                         .is_err()
                     {
                         // Receiver dropped — caller no longer cares.
+                        settle_spend(&fr_ctx, reported_usage.as_ref(), token_index);
                         record_final_audit(
                             &audit_sink,
                             &audit_template,
@@ -837,11 +892,38 @@ async fn run_live_stream(
     // server-side close, we treat both as Succeeded; a truly broken
     // response would have produced an error item above and returned.
     let _ = hit_done;
+    settle_spend(&fr_ctx, reported_usage.as_ref(), token_index);
     record_final_audit(&audit_sink, &audit_template, CloudCallStatus::Succeeded);
     emit_fr_end(&fr_ctx, token_index, &start_instant, FinishReason::Stop).await;
     let _ = sender.send(Ok(terminal_token(FinishReason::Stop)));
 }
 
+/// Charges the call to the lane's spend ledger. The trailing `usage`
+/// chunk is authoritative; a stream that ended without one (or a
+/// server that ignores `include_usage`) falls back to the prompt
+/// estimate and the number of content chunks.
+fn settle_spend(fr_ctx: &LaneFrContext, reported: Option<&ChatStreamUsage>, token_index: u32) {
+    if let Some(lane_obs) = fr_ctx.spend_obs.as_ref() {
+        let usage = usage_or_estimate(
+            reported.and_then(|usage| usage.prompt_tokens),
+            reported.and_then(|usage| usage.completion_tokens),
+            fr_ctx.prompt_tokens_estimate,
+            token_index,
+        );
+        let reservation = fr_ctx
+            .spend_reservation
+            .lock()
+            .ok()
+            .and_then(|mut reservation| reservation.take());
+        lane_obs.record_spend(
+            SPEND_PROVIDER_OPENAI,
+            &fr_ctx.wire_model,
+            &usage,
+            reservation,
+        );
+    }
+}
+
 /// MT-125 remediation: emit a single FR event through the attached
 /// recorder. When no recorder is attached this is a no-op (exact prior
 /// behaviour). Recorder failures are logged and swallowed — observ-
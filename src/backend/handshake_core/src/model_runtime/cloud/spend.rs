@@ -0,0 +1,1367 @@
+//! Cloud-lane spend accounting and budgets.
+//!
+//! Every cloud adapter (OpenAI / Anthropic BYOK, official-CLI bridge) reports
+//! the [`TokenUsage`] of a finished call to a shared [`CloudSpendLedger`],
+//! which prices it against a per-provider/per-model [`PricingTable`] and rolls
+//! the cost up per workspace, job, swarm and UTC day. The same ledger answers
+//! the pre-dispatch question "may this call run?" for the swarm routing policy
+//! and the cloud consent gate:
+//!
+//! - a **hard** budget refuses a dispatch whose projected spend (current
+//!   rollup, plus the estimates of calls still in flight, plus the priced
+//!   estimate for the prompt and `max_tokens`) would exceed the limit;
+//! - a **soft** budget never refuses, it only reports.
+//!
+//! [`CloudSpendLedger::admit`] reserves the estimate until the call settles
+//! ([`SpendReservation::settle`]) or fails (dropping the reservation releases
+//! it), so parallel dispatches cannot all pass against the same headroom.
+//! With a journal attached ([`CloudSpendLedger::with_journal`]) every settled
+//! call is appended as one JSON line and replayed at startup, so the rollups
+//! and the budgets they feed survive a restart.
+//!
+//! Each threshold crossing is emitted once per scope as an
+//! `FR-EVT-CLOUD-BUDGET-{SOFT,HARD}` Flight Recorder event (see
+//! [`crate::flight_recorder::events_cloud_spend`]).
+//!
+//! Pricing and budgets are workspace settings: they live under
+//! `settings.cloud_spend` in `hsk.workspace_settings_state@1`, next to the
+//! redaction rules, and are validated before the settings are saved. The app
+//! builds one ledger at startup from every workspace's settings
+//! ([`CloudSpendSettings::for_workspaces`], strictest budget wins). The
+//! built-in prices are list prices per million tokens and only a starting
+//! point; a workspace entry with the same provider and model prefix replaces
+//! the built-in one. Models without a price are still counted (calls and
+//! tokens) but cost nothing, so an unpriced model never trips a budget.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::flight_recorder::events_cloud_spend::budget_threshold_event;
+use crate::flight_recorder::FlightRecorder;
+use crate::llm::TokenUsage;
+use crate::storage::{Database, StorageError};
+
+/// Key under the workspace `settings` object holding [`CloudSpendSettings`].
+pub const WORKSPACE_SETTINGS_KEY: &str = "cloud_spend";
+
+/// Provider keys used by the pricing table and spend events. The CLI bridge
+/// prices its calls under the vendor whose CLI it drives.
+pub const SPEND_PROVIDER_ANTHROPIC: &str = "anthropic";
+pub const SPEND_PROVIDER_OPENAI: &str = "openai";
+pub const SPEND_PROVIDER_GOOGLE: &str = "google";
+pub const SPEND_PROVIDER_OTHER: &str = "other";
+
+/// Built-in list prices in USD per million tokens:
+/// `(provider, model_prefix, input, output)`.
+const BUILTIN_PRICES: &[(&str, &str, f64, f64)] = &[
+    (SPEND_PROVIDER_ANTHROPIC, "claude-opus-4", 15.0, 75.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-sonnet-4", 3.0, 15.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-haiku-4", 1.0, 5.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3.7", 3.0, 15.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3.5-sonnet", 3.0, 15.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3.5-haiku", 0.8, 4.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3-opus", 15.0, 75.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3-sonnet", 3.0, 15.0),
+    (SPEND_PROVIDER_ANTHROPIC, "claude-3-haiku", 0.25, 1.25),
+    (SPEND_PROVIDER_OPENAI, "gpt-4o", 2.5, 10.0),
+    (SPEND_PROVIDER_OPENAI, "gpt-4o-mini", 0.15, 0.6),
+    (SPEND_PROVIDER_OPENAI, "gpt-4-turbo", 10.0, 30.0),
+    (SPEND_PROVIDER_OPENAI, "gpt-4.1", 2.0, 8.0),
+    (SPEND_PROVIDER_OPENAI, "gpt-4.1-mini", 0.4, 1.6),
+    (SPEND_PROVIDER_OPENAI, "gpt-4.1-nano", 0.1, 0.4),
+    (SPEND_PROVIDER_OPENAI, "o1", 15.0, 60.0),
+    (SPEND_PROVIDER_OPENAI, "o3", 2.0, 8.0),
+    (SPEND_PROVIDER_OPENAI, "gpt-3.5-turbo", 0.5, 1.5),
+    (SPEND_PROVIDER_GOOGLE, "gemini-2.5-pro", 1.25, 10.0),
+    (SPEND_PROVIDER_GOOGLE, "gemini-2.5-flash", 0.3, 2.5),
+];
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+#[derive(Debug, Error)]
+pub enum CloudSpendError {
+    #[error("cloud_spend settings are malformed: {0}")]
+    Malformed(String),
+    #[error("cloud_spend settings are invalid: {0}")]
+    Invalid(String),
+    #[error("cloud spend hard budget reached: {0}")]
+    HardBudget(Box<BudgetCrossing>),
+    #[error("cloud_spend settings could not be loaded: {0}")]
+    Storage(String),
+}
+
+/// Price of one model family, prefix-matched against the wire model name the
+/// same way the BYOK allowlists match (`gpt-4o` covers `gpt-4o-2024-08-06`).
+/// When several prefixes match, the longest wins.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPrice {
+    pub provider: String,
+    pub model_prefix: String,
+    pub input_usd_per_mtok: f64,
+    pub output_usd_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub fn cost_usd(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.input_usd_per_mtok
+            + completion_tokens as f64 * self.output_usd_per_mtok)
+            / TOKENS_PER_PRICE_UNIT
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PricingTable {
+    prices: Vec<ModelPrice>,
+}
+
+impl PricingTable {
+    pub fn builtin() -> Self {
+        Self {
+            prices: BUILTIN_PRICES
+                .iter()
+                .map(|(provider, prefix, input, output)| ModelPrice {
+                    provider: (*provider).to_string(),
+                    model_prefix: (*prefix).to_string(),
+                    input_usd_per_mtok: *input,
+                    output_usd_per_mtok: *output,
+                })
+                .collect(),
+        }
+    }
+
+    /// Built-in prices with workspace entries layered on top. An override
+    /// with the same provider and prefix replaces the built-in entry.
+    pub fn with_overrides(overrides: &[ModelPrice]) -> Self {
+        let mut table = Self::builtin();
+        for price in overrides {
+            table.set(price.clone());
+        }
+        table
+    }
+
+    pub fn set(&mut self, price: ModelPrice) {
+        match self.prices.iter_mut().find(|existing| {
+            existing.provider == price.provider && existing.model_prefix == price.model_prefix
+        }) {
+            Some(existing) => *existing = price,
+            None => self.prices.push(price),
+        }
+    }
+
+    pub fn prices(&self) -> &[ModelPrice] {
+        &self.prices
+    }
+
+    pub fn lookup(&self, provider: &str, model: &str) -> Option<&ModelPrice> {
+        self.prices
+            .iter()
+            .filter(|price| price.provider == provider && model.starts_with(&price.model_prefix))
+            .max_by_key(|price| price.model_prefix.len())
+    }
+
+    /// Cost of a finished call, or `None` when the model has no price.
+    pub fn cost_usd(&self, provider: &str, model: &str, usage: &TokenUsage) -> Option<f64> {
+        self.lookup(provider, model).map(|price| {
+            price.cost_usd(
+                u64::from(usage.prompt_tokens),
+                u64::from(usage.completion_tokens),
+            )
+        })
+    }
+}
+
+/// The rollup dimension a budget applies to. A `Job` budget limits every job
+/// separately; `Day` limits the ledger's total for the current UTC day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendScopeKind {
+    Workspace,
+    Job,
+    Swarm,
+    Day,
+}
+
+impl SpendScopeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpendScopeKind::Workspace => "workspace",
+            SpendScopeKind::Job => "job",
+            SpendScopeKind::Swarm => "swarm",
+            SpendScopeKind::Day => "day",
+        }
+    }
+}
+
+/// One rollup bucket: a scope kind plus the workspace id, job id, swarm id or
+/// `YYYY-MM-DD` day it aggregates.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SpendScope {
+    pub kind: SpendScopeKind,
+    pub key: String,
+}
+
+impl SpendScope {
+    pub fn workspace(workspace_id: impl Into<String>) -> Self {
+        Self {
+            kind: SpendScopeKind::Workspace,
+            key: workspace_id.into(),
+        }
+    }
+
+    pub fn job(job_id: Uuid) -> Self {
+        Self {
+            kind: SpendScopeKind::Job,
+            key: job_id.to_string(),
+        }
+    }
+
+    pub fn swarm(swarm_id: impl Into<String>) -> Self {
+        Self {
+            kind: SpendScopeKind::Swarm,
+            key: swarm_id.into(),
+        }
+    }
+
+    pub fn day(day: NaiveDate) -> Self {
+        Self {
+            kind: SpendScopeKind::Day,
+            key: day.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SpendScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind.as_str(), self.key)
+    }
+}
+
+/// Soft and/or hard USD limits for one scope kind.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpendBudget {
+    pub scope: SpendScopeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_limit_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_limit_usd: Option<f64>,
+}
+
+/// The `settings.cloud_spend` workspace document.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloudSpendSettings {
+    #[serde(default)]
+    pub pricing: Vec<ModelPrice>,
+    #[serde(default)]
+    pub budgets: Vec<SpendBudget>,
+}
+
+impl CloudSpendSettings {
+    /// Read the document out of a `hsk.workspace_settings_state@1` value. A
+    /// workspace without a `settings.cloud_spend` key gets built-in prices and
+    /// no budgets.
+    pub fn from_workspace_settings_state(settings_state: &Value) -> Result<Self, CloudSpendError> {
+        let settings: Self = match settings_state
+            .get("settings")
+            .and_then(|settings| settings.get(WORKSPACE_SETTINGS_KEY))
+        {
+            None | Some(Value::Null) => return Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|err| CloudSpendError::Malformed(err.to_string()))?,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// The settings one ledger enforces for the whole app: every workspace's
+    /// document merged with [`Self::merge_strictest`]. Backends without
+    /// workspace settings, and workspaces that never saved any, contribute
+    /// nothing.
+    pub async fn for_workspaces(db: &dyn Database) -> Result<Self, CloudSpendError> {
+        let workspaces = match db.list_workspaces().await {
+            Ok(workspaces) => workspaces,
+            Err(StorageError::NotImplemented(_)) => return Ok(Self::default()),
+            Err(err) => return Err(CloudSpendError::Storage(err.to_string())),
+        };
+        let mut merged = Self::default();
+        for workspace in workspaces {
+            match db.get_workspace_settings_state(&workspace.id).await {
+                Ok(Some(state)) => {
+                    merged.merge_strictest(Self::from_workspace_settings_state(
+                        &state.settings_state,
+                    )?);
+                }
+                Ok(None) | Err(StorageError::NotImplemented(_)) => {}
+                Err(err) => return Err(CloudSpendError::Storage(err.to_string())),
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Fold `other` in: a price already present keeps its first value, and
+    /// each budget limit takes the lower of the two (a soft limit is capped
+    /// at the hard one), so no workspace's budget is loosened by another's.
+    pub fn merge_strictest(&mut self, other: Self) {
+        for price in other.pricing {
+            if !self.pricing.iter().any(|existing| {
+                existing.provider == price.provider && existing.model_prefix == price.model_prefix
+            }) {
+                self.pricing.push(price);
+            }
+        }
+        let lower = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        for budget in other.budgets {
+            match self
+                .budgets
+                .iter_mut()
+                .find(|existing| existing.scope == budget.scope)
+            {
+                Some(existing) => {
+                    existing.soft_limit_usd = lower(existing.soft_limit_usd, budget.soft_limit_usd);
+                    existing.hard_limit_usd = lower(existing.hard_limit_usd, budget.hard_limit_usd);
+                    if let (Some(soft), Some(hard)) =
+                        (existing.soft_limit_usd, existing.hard_limit_usd)
+                    {
+                        existing.soft_limit_usd = Some(soft.min(hard));
+                    }
+                }
+                None => self.budgets.push(budget),
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), CloudSpendError> {
+        let mut seen_prices = HashSet::new();
+        for price in &self.pricing {
+            if price.provider.trim().is_empty() || price.model_prefix.trim().is_empty() {
+                return Err(CloudSpendError::Invalid(
+                    "pricing entries need a provider and a model_prefix".to_string(),
+                ));
+            }
+            if !valid_amount(price.input_usd_per_mtok) || !valid_amount(price.output_usd_per_mtok) {
+                return Err(CloudSpendError::Invalid(format!(
+                    "price for {}/{} must be a finite, non-negative USD amount",
+                    price.provider, price.model_prefix
+                )));
+            }
+            if !seen_prices.insert((&price.provider, &price.model_prefix)) {
+                return Err(CloudSpendError::Invalid(format!(
+                    "duplicate price for {}/{}",
+                    price.provider, price.model_prefix
+                )));
+            }
+        }
+
+        let mut seen_scopes = HashSet::new();
+        for budget in &self.budgets {
+            if !seen_scopes.insert(budget.scope) {
+                return Err(CloudSpendError::Invalid(format!(
+                    "duplicate {} budget",
+                    budget.scope.as_str()
+                )));
+            }
+            if budget.soft_limit_usd.is_none() && budget.hard_limit_usd.is_none() {
+                return Err(CloudSpendError::Invalid(format!(
+                    "{} budget sets neither soft_limit_usd nor hard_limit_usd",
+                    budget.scope.as_str()
+                )));
+            }
+            for limit in [budget.soft_limit_usd, budget.hard_limit_usd]
+                .into_iter()
+                .flatten()
+            {
+                if !valid_amount(limit) || limit == 0.0 {
+                    return Err(CloudSpendError::Invalid(format!(
+                        "{} budget limits must be finite, positive USD amounts",
+                        budget.scope.as_str()
+                    )));
+                }
+            }
+            if let (Some(soft), Some(hard)) = (budget.soft_limit_usd, budget.hard_limit_usd) {
+                if soft > hard {
+                    return Err(CloudSpendError::Invalid(format!(
+                        "{} soft_limit_usd exceeds hard_limit_usd",
+                        budget.scope.as_str()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn valid_amount(value: f64) -> bool {
+    value.is_finite() && value >= 0.0
+}
+
+/// Which rollups a call is charged to. Every call is also charged to the
+/// current UTC day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpendAttribution {
+    pub workspace_id: Option<String>,
+    pub job_id: Option<Uuid>,
+    pub swarm_id: Option<String>,
+}
+
+impl SpendAttribution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_workspace(mut self, workspace_id: impl Into<String>) -> Self {
+        self.workspace_id = Some(workspace_id.into());
+        self
+    }
+
+    pub fn with_job(mut self, job_id: Uuid) -> Self {
+        self.job_id = Some(job_id);
+        self
+    }
+
+    pub fn with_swarm(mut self, swarm_id: impl Into<String>) -> Self {
+        self.swarm_id = Some(swarm_id.into());
+        self
+    }
+
+    pub fn scopes(&self, day: NaiveDate) -> Vec<SpendScope> {
+        let mut scopes = Vec::with_capacity(4);
+        if let Some(workspace_id) = &self.workspace_id {
+            scopes.push(SpendScope::workspace(workspace_id.clone()));
+        }
+        if let Some(job_id) = self.job_id {
+            scopes.push(SpendScope::job(job_id));
+        }
+        if let Some(swarm_id) = &self.swarm_id {
+            scopes.push(SpendScope::swarm(swarm_id.clone()));
+        }
+        scopes.push(SpendScope::day(day));
+        scopes
+    }
+}
+
+/// A cloud call about to be dispatched, as seen by [`CloudSpendLedger::admit`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpendDispatch {
+    pub provider: String,
+    pub model: String,
+    pub attribution: SpendAttribution,
+    pub estimated_input_tokens: u64,
+    pub max_output_tokens: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpendRollup {
+    pub calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetThreshold {
+    Soft,
+    Hard,
+}
+
+/// Whether a crossing was detected before dispatch (from the estimate) or
+/// after a call settled (from reported usage).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPhase {
+    Dispatch,
+    Settled,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BudgetCrossing {
+    pub scope: SpendScope,
+    pub threshold: BudgetThreshold,
+    pub phase: BudgetPhase,
+    pub limit_usd: f64,
+    pub spent_usd: f64,
+    pub projected_usd: f64,
+    pub provider: String,
+    pub model: String,
+    pub job_id: Option<Uuid>,
+    /// Set for a hard crossing at dispatch: the call was not sent.
+    pub refused: bool,
+}
+
+impl fmt::Display for BudgetCrossing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} spend ${:.4} would reach ${:.4} against a {} limit of ${:.4} ({}/{})",
+            self.scope,
+            self.spent_usd,
+            self.projected_usd,
+            match self.threshold {
+                BudgetThreshold::Soft => "soft",
+                BudgetThreshold::Hard => "hard",
+            },
+            self.limit_usd,
+            self.provider,
+            self.model
+        )
+    }
+}
+
+/// What [`CloudSpendLedger::record`] charged for one call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpendReceipt {
+    /// `None` when the model has no price.
+    pub cost_usd: Option<f64>,
+    pub crossings: Vec<BudgetCrossing>,
+}
+
+struct LedgerState {
+    pricing: PricingTable,
+    budgets: HashMap<SpendScopeKind, SpendBudget>,
+    rollups: HashMap<SpendScope, SpendRollup>,
+    /// Estimates of admitted calls that have not settled yet, by reservation.
+    reservations: HashMap<u64, Reserved>,
+    next_reservation: u64,
+    /// Scopes that already emitted a threshold event, so each crossing is
+    /// reported once rather than on every later call.
+    notified: HashSet<(SpendScope, BudgetThreshold)>,
+}
+
+struct Reserved {
+    scopes: Vec<SpendScope>,
+    usd: f64,
+}
+
+impl LedgerState {
+    fn reserved_usd(&self, scope: &SpendScope) -> f64 {
+        self.reservations
+            .values()
+            .filter(|reserved| reserved.scopes.contains(scope))
+            .map(|reserved| reserved.usd)
+            .sum()
+    }
+
+    fn charge(&mut self, scopes: &[SpendScope], usage: &TokenUsage, cost: f64) {
+        for scope in scopes {
+            let rollup = self.rollups.entry(scope.clone()).or_default();
+            rollup.calls += 1;
+            rollup.prompt_tokens += u64::from(usage.prompt_tokens);
+            rollup.completion_tokens += u64::from(usage.completion_tokens);
+            rollup.cost_usd += cost;
+        }
+    }
+}
+
+/// One settled call as written to the spend journal.
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    recorded_at: String,
+    provider: String,
+    model: String,
+    scopes: Vec<SpendScope>,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    cost_usd: f64,
+}
+
+/// Append-only JSONL journal of settled calls. Lines are written by a
+/// dedicated thread so charging a call never blocks the async runtime.
+struct SpendJournal {
+    tx: Mutex<mpsc::Sender<String>>,
+}
+
+impl SpendJournal {
+    fn append(&self, entry: &JournalEntry) {
+        let Ok(line) = serde_json::to_string(entry) else {
+            return;
+        };
+        let tx = match self.tx.lock() {
+            Ok(tx) => tx,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if tx.send(line).is_err() {
+            tracing::warn!(
+                target: "handshake_core::model_runtime::cloud::spend",
+                "cloud spend journal writer stopped; call not journaled"
+            );
+        }
+    }
+}
+
+/// Shared spend ledger. Cheap to consult from sync code: the routing policy
+/// and the consent gate call [`Self::check`] / [`Self::admit`] inline, and
+/// threshold events are handed to the Flight Recorder on the current tokio
+/// runtime.
+pub struct CloudSpendLedger {
+    state: Mutex<LedgerState>,
+    flight_recorder: Option<Arc<dyn FlightRecorder>>,
+    journal: Option<SpendJournal>,
+}
+
+/// An admitted dispatch's estimate, held against its scopes' hard budgets
+/// until the call settles. Dropping it unsettled (the call failed or was
+/// cancelled before any usage) releases the estimate.
+#[must_use = "dropping a reservation releases it; settle it with the call's usage"]
+pub struct SpendReservation {
+    ledger: Arc<CloudSpendLedger>,
+    id: u64,
+    provider: String,
+    model: String,
+    attribution: SpendAttribution,
+    estimate_usd: f64,
+    settled: bool,
+}
+
+impl fmt::Debug for SpendReservation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpendReservation")
+            .field("id", &self.id)
+            .field("provider", &self.provider)
+            .field("model", &self.model)
+            .field("estimate_usd", &self.estimate_usd)
+            .finish()
+    }
+}
+
+impl SpendReservation {
+    pub fn estimate_usd(&self) -> f64 {
+        self.estimate_usd
+    }
+
+    /// Release the estimate and charge the call's actual usage.
+    pub fn settle(mut self, usage: &TokenUsage) -> SpendReceipt {
+        self.settled = true;
+        self.ledger.release(self.id);
+        self.ledger
+            .record(&self.provider, &self.model, &self.attribution, usage)
+    }
+}
+
+impl Drop for SpendReservation {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.ledger.release(self.id);
+        }
+    }
+}
+
+impl fmt::Debug for CloudSpendLedger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("CloudSpendLedger")
+            .field("prices", &state.pricing.prices.len())
+            .field("budgets", &state.budgets.len())
+            .field("rollups", &state.rollups.len())
+            .field("reservations", &state.reservations.len())
+            .finish()
+    }
+}
+
+impl Default for CloudSpendLedger {
+    fn default() -> Self {
+        Self::new(CloudSpendSettings::default())
+    }
+}
+
+impl CloudSpendLedger {
+    pub fn new(settings: CloudSpendSettings) -> Self {
+        Self {
+            state: Mutex::new(LedgerState {
+                pricing: PricingTable::with_overrides(&settings.pricing),
+                budgets: budgets_by_scope(settings.budgets),
+                rollups: HashMap::new(),
+                reservations: HashMap::new(),
+                next_reservation: 0,
+                notified: HashSet::new(),
+            }),
+            flight_recorder: None,
+            journal: None,
+        }
+    }
+
+    pub fn with_flight_recorder(mut self, flight_recorder: Arc<dyn FlightRecorder>) -> Self {
+        self.flight_recorder = Some(flight_recorder);
+        self
+    }
+
+    /// Persist settled calls to the JSONL journal at `path`, replaying the
+    /// calls already in it into the rollups first. Call once at startup,
+    /// before the ledger is shared. Unreadable lines are skipped with a
+    /// warning rather than failing the replay.
+    pub fn with_journal(mut self, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            let file = std::fs::File::open(path)?;
+            let mut state = self.lock();
+            for (index, line) in std::io::BufReader::new(file).lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<JournalEntry>(&line) {
+                    Ok(entry) => {
+                        let usage = TokenUsage {
+                            prompt_tokens: entry.prompt_tokens,
+                            completion_tokens: entry.completion_tokens,
+                            total_tokens: entry
+                                .prompt_tokens
+                                .saturating_add(entry.completion_tokens),
+                        };
+                        state.charge(&entry.scopes, &usage, entry.cost_usd);
+                    }
+                    Err(err) => tracing::warn!(
+                        target: "handshake_core::model_runtime::cloud::spend",
+                        path = %path.display(),
+                        line = index + 1,
+                        error = %err,
+                        "skipping unreadable cloud spend journal line"
+                    ),
+                }
+            }
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let (tx, rx) = mpsc::channel::<String>();
+        let journal_path = path.to_path_buf();
+        std::thread::Builder::new()
+            .name("cloud-spend-journal".to_string())
+            .spawn(move || {
+                for line in rx {
+                    if let Err(err) = writeln!(file, "{line}") {
+                        tracing::warn!(
+                            target: "handshake_core::model_runtime::cloud::spend",
+                            path = %journal_path.display(),
+                            error = %err,
+                            "cloud spend journal write failed"
+                        );
+                    }
+                }
+            })?;
+        self.journal = Some(SpendJournal { tx: Mutex::new(tx) });
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LedgerState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Swap in edited settings. Rollups are kept; a raised limit re-arms its
+    /// threshold events.
+    pub fn apply_settings(&self, settings: CloudSpendSettings) {
+        let mut state = self.lock();
+        state.pricing = PricingTable::with_overrides(&settings.pricing);
+        state.budgets = budgets_by_scope(settings.budgets);
+        state.notified.clear();
+    }
+
+    pub fn pricing(&self) -> PricingTable {
+        self.lock().pricing.clone()
+    }
+
+    pub fn rollup(&self, scope: &SpendScope) -> SpendRollup {
+        self.lock().rollups.get(scope).copied().unwrap_or_default()
+    }
+
+    /// Every rollup of one kind, sorted by key.
+    pub fn rollups(&self, kind: SpendScopeKind) -> Vec<(String, SpendRollup)> {
+        let state = self.lock();
+        let mut rows: Vec<_> = state
+            .rollups
+            .iter()
+            .filter(|(scope, _)| scope.kind == kind)
+            .map(|(scope, rollup)| (scope.key.clone(), *rollup))
+            .collect();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        rows
+    }
+
+    /// Priced estimate for a dispatch: the prompt estimate plus the full
+    /// `max_output_tokens`, so the check is conservative.
+    pub fn estimate_usd(&self, dispatch: &SpendDispatch) -> Option<f64> {
+        self.lock()
+            .pricing
+            .lookup(&dispatch.provider, &dispatch.model)
+            .map(|price| {
+                price.cost_usd(dispatch.estimated_input_tokens, dispatch.max_output_tokens)
+            })
+    }
+
+    /// Pre-dispatch budget check that reserves nothing: would `dispatch` fit
+    /// the hard budgets right now? The routing policy previews lanes with
+    /// this; the lane that then dispatches calls [`Self::admit`].
+    pub fn check(&self, dispatch: &SpendDispatch) -> Result<(), CloudSpendError> {
+        self.evaluate(dispatch, false).map(|_| ())
+    }
+
+    /// Admit a dispatch and reserve its estimate. Refuses with
+    /// [`CloudSpendError::HardBudget`] when any scope the call is charged to
+    /// would exceed its hard limit, and reports soft-limit crossings without
+    /// refusing. The reservation counts against later admissions until it is
+    /// settled or dropped.
+    pub fn admit(
+        self: &Arc<Self>,
+        dispatch: &SpendDispatch,
+    ) -> Result<SpendReservation, CloudSpendError> {
+        let (id, estimate_usd) = self.evaluate(dispatch, true)?;
+        Ok(SpendReservation {
+            ledger: Arc::clone(self),
+            id: id.expect("reserving evaluation returns an id"),
+            provider: dispatch.provider.clone(),
+            model: dispatch.model.clone(),
+            attribution: dispatch.attribution.clone(),
+            estimate_usd,
+            settled: false,
+        })
+    }
+
+    fn release(&self, id: u64) {
+        self.lock().reservations.remove(&id);
+    }
+
+    /// Estimates of admitted calls that have not settled, for `scope`.
+    pub fn reserved_usd(&self, scope: &SpendScope) -> f64 {
+        self.lock().reserved_usd(scope)
+    }
+
+    fn evaluate(
+        &self,
+        dispatch: &SpendDispatch,
+        reserve: bool,
+    ) -> Result<(Option<u64>, f64), CloudSpendError> {
+        let day = Utc::now().date_naive();
+        let mut events = Vec::new();
+        let result = {
+            let mut state = self.lock();
+            let estimate = state
+                .pricing
+                .lookup(&dispatch.provider, &dispatch.model)
+                .map(|price| {
+                    price.cost_usd(dispatch.estimated_input_tokens, dispatch.max_output_tokens)
+                })
+                .unwrap_or(0.0);
+            let mut refusal = None;
+            for scope in dispatch.attribution.scopes(day) {
+                let Some(budget) = state.budgets.get(&scope.kind).cloned() else {
+                    continue;
+                };
+                let spent = state
+                    .rollups
+                    .get(&scope)
+                    .map(|rollup| rollup.cost_usd)
+                    .unwrap_or(0.0)
+                    + state.reserved_usd(&scope);
+                let projected = spent + estimate;
+                let crossing = |threshold, limit_usd, refused| BudgetCrossing {
+                    scope: scope.clone(),
+                    threshold,
+                    phase: BudgetPhase::Dispatch,
+                    limit_usd,
+                    spent_usd: spent,
+                    projected_usd: projected,
+                    provider: dispatch.provider.clone(),
+                    model: dispatch.model.clone(),
+                    job_id: dispatch.attribution.job_id,
+                    refused,
+                };
+                if let Some(hard) = budget.hard_limit_usd {
+                    // Once the limit is reached every further call is refused,
+                    // including unpriced ones.
+                    if projected > hard || spent >= hard {
+                        let refused = crossing(BudgetThreshold::Hard, hard, true);
+                        events.push(refused.clone());
+                        if refusal.is_none() {
+                            refusal = Some(refused);
+                        }
+                        continue;
+                    }
+                }
+                if let Some(soft) = budget.soft_limit_usd {
+                    if projected >= soft
+                        && state
+                            .notified
+                            .insert((scope.clone(), BudgetThreshold::Soft))
+                    {
+                        events.push(crossing(BudgetThreshold::Soft, soft, false));
+                    }
+                }
+            }
+            match refusal {
+                Some(crossing) => Err(CloudSpendError::HardBudget(Box::new(crossing))),
+                None if reserve => {
+                    let id = state.next_reservation;
+                    state.next_reservation += 1;
+                    state.reservations.insert(
+                        id,
+                        Reserved {
+                            scopes: dispatch.attribution.scopes(day),
+                            usd: estimate,
+                        },
+                    );
+                    Ok((Some(id), estimate))
+                }
+                None => Ok((None, estimate)),
+            }
+        };
+        for crossing in &events {
+            self.emit(crossing);
+        }
+        result
+    }
+
+    /// Charge a finished call to its rollups and report the thresholds its
+    /// cost pushed a scope across. A call admitted through [`Self::admit`]
+    /// is charged with [`SpendReservation::settle`] instead.
+    pub fn record(
+        &self,
+        provider: &str,
+        model: &str,
+        attribution: &SpendAttribution,
+        usage: &TokenUsage,
+    ) -> SpendReceipt {
+        let day = Utc::now().date_naive();
+        let scopes = attribution.scopes(day);
+        let receipt = {
+            let mut state = self.lock();
+            let cost_usd = state.pricing.cost_usd(provider, model, usage);
+            let cost = cost_usd.unwrap_or(0.0);
+            let mut crossings = Vec::new();
+            for scope in &scopes {
+                let spent = state
+                    .rollups
+                    .get(scope)
+                    .map(|rollup| rollup.cost_usd)
+                    .unwrap_or(0.0);
+                state.charge(std::slice::from_ref(scope), usage, cost);
+                let projected = spent + cost;
+
+                let Some(budget) = state.budgets.get(&scope.kind).cloned() else {
+                    continue;
+                };
+                for (threshold, limit) in [
+                    (BudgetThreshold::Soft, budget.soft_limit_usd),
+                    (BudgetThreshold::Hard, budget.hard_limit_usd),
+                ] {
+                    let Some(limit_usd) = limit else {
+                        continue;
+                    };
+                    if projected >= limit_usd && state.notified.insert((scope.clone(), threshold)) {
+                        crossings.push(BudgetCrossing {
+                            scope: scope.clone(),
+                            threshold,
+                            phase: BudgetPhase::Settled,
+                            limit_usd,
+                            spent_usd: spent,
+                            projected_usd: projected,
+                            provider: provider.to_string(),
+                            model: model.to_string(),
+                            job_id: attribution.job_id,
+                            refused: false,
+                        });
+                    }
+                }
+            }
+            SpendReceipt {
+                cost_usd,
+                crossings,
+            }
+        };
+        if let Some(journal) = self.journal.as_ref() {
+            journal.append(&JournalEntry {
+                recorded_at: Utc::now().to_rfc3339(),
+                provider: provider.to_string(),
+                model: model.to_string(),
+                scopes,
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+                cost_usd: receipt.cost_usd.unwrap_or(0.0),
+            });
+        }
+        for crossing in &receipt.crossings {
+            self.emit(crossing);
+        }
+        receipt
+    }
+
+    fn emit(&self, crossing: &BudgetCrossing) {
+        tracing::warn!(
+            target: "handshake_core::model_runtime::cloud::spend",
+            scope = %crossing.scope,
+            threshold = ?crossing.threshold,
+            refused = crossing.refused,
+            "{crossing}"
+        );
+        let Some(recorder) = self.flight_recorder.clone() else {
+            return;
+        };
+        let event = budget_threshold_event(crossing);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = recorder.record_event(event).await {
+                        tracing::warn!(
+                            target: "handshake_core::model_runtime::cloud::spend",
+                            error = %err,
+                            "FR-EVT-CLOUD-BUDGET emit failed"
+                        );
+                    }
+                });
+            }
+            Err(err) => {
+                tracing::warn!(
+                    target: "handshake_core::model_runtime::cloud::spend",
+                    error = %err,
+                    "FR-EVT-CLOUD-BUDGET not recorded outside a tokio runtime"
+                );
+            }
+        }
+    }
+}
+
+fn budgets_by_scope(budgets: Vec<SpendBudget>) -> HashMap<SpendScopeKind, SpendBudget> {
+    budgets
+        .into_iter()
+        .map(|budget| (budget.scope, budget))
+        .collect()
+}
+
+/// Token usage for a call whose provider may not report it. Reported counts
+/// win; otherwise the adapter's prompt estimate and emitted-chunk count stand
+/// in, which keeps a budget meaningful when a stream omits its usage block.
+pub fn usage_or_estimate(
+    reported_prompt_tokens: Option<u32>,
+    reported_completion_tokens: Option<u32>,
+    prompt_tokens_estimate: u64,
+    generated_chunks: u32,
+) -> TokenUsage {
+    let prompt_tokens = reported_prompt_tokens
+        .unwrap_or_else(|| u32::try_from(prompt_tokens_estimate).unwrap_or(u32::MAX));
+    let completion_tokens = reported_completion_tokens.unwrap_or(generated_chunks);
+    TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens.saturating_add(completion_tokens),
+    }
+}
+
+/// Spend wiring carried by [`super::CloudLaneObservability`]: the shared
+/// ledger plus the rollups this lane's calls are charged to.
+#[derive(Clone, Debug)]
+pub struct CloudSpendContext {
+    pub ledger: Arc<CloudSpendLedger>,
+    pub attribution: SpendAttribution,
+}
+
+impl CloudSpendContext {
+    pub fn dispatch(
+        &self,
+        provider: &str,
+        model: &str,
+        estimated_input_tokens: u64,
+        max_output_tokens: u32,
+    ) -> SpendDispatch {
+        SpendDispatch {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            attribution: self.attribution.clone(),
+            estimated_input_tokens,
+            max_output_tokens: u64::from(max_output_tokens),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn usage(prompt_tokens: u32, completion_tokens: u32) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+
+    fn dispatch(attribution: &SpendAttribution, max_output_tokens: u64) -> SpendDispatch {
+        SpendDispatch {
+            provider: SPEND_PROVIDER_ANTHROPIC.to_string(),
+            model: "claude-sonnet-4-20250514".to_string(),
+            attribution: attribution.clone(),
+            estimated_input_tokens: 1_000,
+            max_output_tokens,
+        }
+    }
+
+    #[test]
+    fn longest_prefix_prices_and_workspace_overrides_win() {
+        let table = PricingTable::builtin();
+        let mini = table
+            .cost_usd(
+                SPEND_PROVIDER_OPENAI,
+                "gpt-4o-mini-2024-07-18",
+                &usage(1_000_000, 0),
+            )
+            .expect("priced");
+        assert!((mini - 0.15).abs() < 1e-9);
+        assert!(table
+            .cost_usd(SPEND_PROVIDER_OPENAI, "claude-sonnet-4", &usage(1, 1))
+            .is_none());
+
+        let table = PricingTable::with_overrides(&[ModelPrice {
+            provider: SPEND_PROVIDER_OPENAI.to_string(),
+            model_prefix: "gpt-4o".to_string(),
+            input_usd_per_mtok: 1.0,
+            output_usd_per_mtok: 2.0,
+        }]);
+        let cost = table
+            .cost_usd(SPEND_PROVIDER_OPENAI, "gpt-4o", &usage(500_000, 500_000))
+            .expect("priced");
+        assert!((cost - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn settings_round_trip_through_workspace_state_and_reject_bad_limits() {
+        let state = json!({
+            "settings": {
+                "cloud_spend": {
+                    "pricing": [{
+                        "provider": "anthropic",
+                        "model_prefix": "claude-sonnet-4",
+                        "input_usd_per_mtok": 2.0,
+                        "output_usd_per_mtok": 10.0
+                    }],
+                    "budgets": [{ "scope": "job", "soft_limit_usd": 1.0, "hard_limit_usd": 2.0 }]
+                }
+            }
+        });
+        let settings = CloudSpendSettings::from_workspace_settings_state(&state).expect("valid");
+        assert_eq!(settings.budgets[0].scope, SpendScopeKind::Job);
+        assert_eq!(
+            CloudSpendSettings::from_workspace_settings_state(&json!({ "settings": {} }))
+                .expect("absent"),
+            CloudSpendSettings::default()
+        );
+
+        let inverted = json!({
+            "settings": { "cloud_spend": { "budgets": [
+                { "scope": "day", "soft_limit_usd": 5.0, "hard_limit_usd": 1.0 }
+            ] } }
+        });
+        assert!(matches!(
+            CloudSpendSettings::from_workspace_settings_state(&inverted),
+            Err(CloudSpendError::Invalid(_))
+        ));
+        let unknown = json!({ "settings": { "cloud_spend": { "budget": [] } } });
+        assert!(matches!(
+            CloudSpendSettings::from_workspace_settings_state(&unknown),
+            Err(CloudSpendError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rollups_split_by_workspace_job_swarm_and_day() {
+        let ledger = CloudSpendLedger::default();
+        let job_a = Uuid::now_v7();
+        let job_b = Uuid::now_v7();
+        let base = SpendAttribution::new()
+            .with_workspace("ws-1")
+            .with_swarm("swarm-1");
+        ledger.record(
+            SPEND_PROVIDER_ANTHROPIC,
+            "claude-sonnet-4",
+            &base.clone().with_job(job_a),
+            &usage(1_000_000, 0),
+        );
+        let receipt = ledger.record(
+            SPEND_PROVIDER_ANTHROPIC,
+            "claude-sonnet-4",
+            &base.clone().with_job(job_b),
+            &usage(0, 100_000),
+        );
+        assert!((receipt.cost_usd.expect("priced") - 1.5).abs() < 1e-9);
+
+        let workspace = ledger.rollup(&SpendScope::workspace("ws-1"));
+        assert_eq!(workspace.calls, 2);
+        assert!((workspace.cost_usd - 4.5).abs() < 1e-9);
+        assert!((ledger.rollup(&SpendScope::job(job_a)).cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(ledger.rollup(&SpendScope::swarm("swarm-1")).calls, 2);
+        let days = ledger.rollups(SpendScopeKind::Day);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].1.completion_tokens, 100_000);
+    }
+
+    #[test]
+    fn hard_budget_refuses_dispatch_and_soft_budget_reports_once() {
+        let ledger = Arc::new(CloudSpendLedger::new(CloudSpendSettings {
+            pricing: Vec::new(),
+            budgets: vec![SpendBudget {
+                scope: SpendScopeKind::Job,
+                soft_limit_usd: Some(0.01),
+                hard_limit_usd: Some(0.05),
+            }],
+        }));
+        let job = Uuid::now_v7();
+        let attribution = SpendAttribution::new().with_job(job);
+
+        // 1k in + 1k out on Sonnet is $0.018: over soft, under hard.
+        let reservation = ledger
+            .admit(&dispatch(&attribution, 1_000))
+            .expect("under the hard limit");
+        let receipt = reservation.settle(&usage(1_000, 1_000));
+        assert_eq!(ledger.reserved_usd(&SpendScope::job(job)), 0.0);
+        // The soft crossing was already reported at dispatch.
+        assert!(receipt.crossings.is_empty());
+
+        let err = ledger
+            .admit(&dispatch(&attribution, 4_000))
+            .expect_err("projected $0.081 exceeds the hard limit");
+        let CloudSpendError::HardBudget(crossing) = err else {
+            panic!("expected a hard budget refusal");
+        };
+        assert_eq!(crossing.threshold, BudgetThreshold::Hard);
+        assert_eq!(crossing.scope.kind, SpendScopeKind::Job);
+        assert!(crossing.refused);
+
+        // Another job has its own budget.
+        ledger
+            .admit(&dispatch(
+                &SpendAttribution::new().with_job(Uuid::now_v7()),
+                1_000,
+            ))
+            .expect("fresh job");
+    }
+
+    #[test]
+    fn reservations_hold_the_budget_until_settled_or_dropped() {
+        let ledger = Arc::new(CloudSpendLedger::new(CloudSpendSettings {
+            pricing: Vec::new(),
+            budgets: vec![SpendBudget {
+                scope: SpendScopeKind::Workspace,
+                soft_limit_usd: None,
+                hard_limit_usd: Some(0.05),
+            }],
+        }));
+        let attribution = SpendAttribution::new().with_workspace("ws-1");
+        let scope = SpendScope::workspace("ws-1");
+
+        // Each dispatch is $0.033; two in flight would overrun $0.05.
+        let first = ledger
+            .admit(&dispatch(&attribution, 2_000))
+            .expect("first fits");
+        assert!((ledger.reserved_usd(&scope) - first.estimate_usd()).abs() < 1e-12);
+        assert!(ledger.check(&dispatch(&attribution, 2_000)).is_err());
+        assert!(matches!(
+            ledger.admit(&dispatch(&attribution, 2_000)),
+            Err(CloudSpendError::HardBudget(_))
+        ));
+
+        // A call that fails before reporting usage gives its estimate back.
+        drop(first);
+        assert_eq!(ledger.reserved_usd(&scope), 0.0);
+        let second = ledger
+            .admit(&dispatch(&attribution, 2_000))
+            .expect("released estimate is available again");
+        second.settle(&usage(100, 100));
+        assert_eq!(ledger.reserved_usd(&scope), 0.0);
+        assert_eq!(ledger.rollup(&scope).calls, 1);
+    }
+
+    #[test]
+    fn journal_replays_settled_calls_into_a_fresh_ledger() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("cloud_spend.jsonl");
+        let attribution = SpendAttribution::new().with_workspace("ws-1");
+        {
+            let ledger = CloudSpendLedger::default()
+                .with_journal(&path)
+                .expect("journal opens");
+            ledger.record(
+                SPEND_PROVIDER_ANTHROPIC,
+                "claude-sonnet-4",
+                &attribution,
+                &usage(1_000_000, 0),
+            );
+        }
+        // The writer thread drains once the ledger (and its sender) is gone.
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .is_empty()
+        {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "journal never written"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "not json"))
+            .expect("append garbage");
+
+        let replayed = CloudSpendLedger::default()
+            .with_journal(&path)
+            .expect("journal replays");
+        let rollup = replayed.rollup(&SpendScope::workspace("ws-1"));
+        assert_eq!(rollup.calls, 1);
+        assert!((rollup.cost_usd - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merging_workspace_settings_keeps_the_strictest_budget() {
+        let mut merged = CloudSpendSettings {
+            pricing: Vec::new(),
+            budgets: vec![SpendBudget {
+                scope: SpendScopeKind::Day,
+                soft_limit_usd: Some(8.0),
+                hard_limit_usd: Some(10.0),
+            }],
+        };
+        merged.merge_strictest(CloudSpendSettings {
+            pricing: Vec::new(),
+            budgets: vec![
+                SpendBudget {
+                    scope: SpendScopeKind::Day,
+                    soft_limit_usd: None,
+                    hard_limit_usd: Some(5.0),
+                },
+                SpendBudget {
+                    scope: SpendScopeKind::Job,
+                    soft_limit_usd: None,
+                    hard_limit_usd: Some(1.0),
+                },
+            ],
+        });
+        assert_eq!(merged.budgets.len(), 2);
+        assert_eq!(merged.budgets[0].hard_limit_usd, Some(5.0));
+        assert_eq!(merged.budgets[0].soft_limit_usd, Some(5.0));
+        assert!(merged.validate().is_ok());
+    }
+}
@@ -18,6 +18,8 @@ pub enum ModelRuntimeError {
     KvCacheError(String),
     #[error("LoRA stack operation failed: {0}")]
     LoraStackError(String),
+    #[error("LoRA training failed: {0}")]
+    TrainingError(String),
     #[error("steering hook operation failed: {0}")]
     SteeringHookError(String),
     #[error("model operation cancelled")]
@@ -0,0 +1,330 @@
+//! Local models the backend process loads for its own use.
+//!
+//! Operators pick stored models ([`ModelStore`] names) per role through the
+//! environment; `main` loads them once at startup with
+//! [`LocalModels::load_from_env`] and installs the result with [`install`].
+//! Request paths reach it through [`shared`]:
+//!
+//! | role                         | env var                                   |
+//! |------------------------------|-------------------------------------------|
+//! | prompt-injection classifier  | `HANDSHAKE_INJECTION_CLASSIFIER_MODEL`    |
+//! | retrieval / search reranker  | `HANDSHAKE_RERANKER_MODEL`                |
+//! | chat (local OpenAI server)   | `HANDSHAKE_LOCAL_CHAT_MODELS` (comma list) |
+//!
+//! A role whose model is not configured, or failed to load, is absent; each
+//! consumer decides how to degrade (the injection tier honours the workspace's
+//! `fail_closed`; retrieval and Loom search keep their base order).
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use super::candle::CandleRuntime;
+use super::llama_cpp::LlamaCppRuntime;
+use super::store::ModelStore;
+use super::{
+    ModelCapabilities, ModelId, ModelRegistration, ModelRegistry, ModelRuntime, ModelRuntimeError,
+    OperatorId, RuntimeBinding,
+};
+use crate::llm::local_openai_server::LocalModelInfo;
+use crate::llm::local_router::LocalRouter;
+
+pub const INJECTION_CLASSIFIER_MODEL_ENV: &str = "HANDSHAKE_INJECTION_CLASSIFIER_MODEL";
+pub const RERANKER_MODEL_ENV: &str = "HANDSHAKE_RERANKER_MODEL";
+pub const LOCAL_CHAT_MODELS_ENV: &str = "HANDSHAKE_LOCAL_CHAT_MODELS";
+
+/// Operator id recorded on registrations the backend makes for itself.
+const LOCAL_MODELS_OPERATOR: &str = "handshake_core";
+
+/// What a loaded model is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LocalModelRole {
+    InjectionClassifier,
+    Reranker,
+}
+
+impl LocalModelRole {
+    pub const ALL: [Self; 2] = [Self::InjectionClassifier, Self::Reranker];
+
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Self::InjectionClassifier => INJECTION_CLASSIFIER_MODEL_ENV,
+            Self::Reranker => RERANKER_MODEL_ENV,
+        }
+    }
+}
+
+/// A stored model loaded into one of the local runtimes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadedLocalModel {
+    /// The [`ModelStore`] name.
+    pub name: String,
+    pub model_id: ModelId,
+    pub binding: RuntimeBinding,
+}
+
+impl LoadedLocalModel {
+    /// Stable reference recorded with verdicts and scores.
+    pub fn model_ref(&self) -> String {
+        format!("{}/{}", self.binding.adapter_id(), self.name)
+    }
+}
+
+/// The loaded local runtimes, the registry of what they hold, and which model
+/// serves which role.
+pub struct LocalModels {
+    store: Option<Arc<ModelStore>>,
+    registry: Arc<ModelRegistry>,
+    candle: Arc<CandleRuntime>,
+    llama: Arc<LlamaCppRuntime>,
+    roles: HashMap<LocalModelRole, LoadedLocalModel>,
+    chat: Vec<LoadedLocalModel>,
+}
+
+impl LocalModels {
+    /// No store and no models: every role is absent.
+    pub fn empty() -> Self {
+        Self {
+            store: None,
+            registry: Arc::new(ModelRegistry::default()),
+            candle: Arc::new(CandleRuntime::default()),
+            llama: Arc::new(LlamaCppRuntime::default()),
+            roles: HashMap::new(),
+            chat: Vec::new(),
+        }
+    }
+
+    /// Load the model each role's env var names, and the chat models, from
+    /// `store`. A model that cannot be resolved or loaded is logged and left
+    /// out; a name listed more than once is loaded once.
+    pub async fn load_from_env(store: Option<Arc<ModelStore>>) -> Self {
+        let wanted: Vec<(LocalModelRole, String)> = LocalModelRole::ALL
+            .into_iter()
+            .filter_map(|role| {
+                let name = std::env::var(role.env_var()).ok()?;
+                let name = name.trim();
+                (!name.is_empty()).then(|| (role, name.to_string()))
+            })
+            .collect();
+        let mut chat_names: Vec<String> = Vec::new();
+        for name in std::env::var(LOCAL_CHAT_MODELS_ENV)
+            .unwrap_or_default()
+            .split(',')
+        {
+            let name = name.trim();
+            if !name.is_empty() && !chat_names.iter().any(|seen| seen == name) {
+                chat_names.push(name.to_string());
+            }
+        }
+        let Some(store) = store else {
+            if !wanted.is_empty() || !chat_names.is_empty() {
+                tracing::warn!(
+                    target: "handshake_core::model_runtime::local_models",
+                    "local models configured but the model store is unavailable; none loaded"
+                );
+            }
+            return Self::empty();
+        };
+
+        let mut candle = CandleRuntime::default();
+        let mut llama = LlamaCppRuntime::default();
+        let mut registry = ModelRegistry::default();
+        let mut by_name: HashMap<String, Option<LoadedLocalModel>> = HashMap::new();
+        let requested = wanted
+            .iter()
+            .map(|(role, name)| (role.env_var(), name))
+            .chain(chat_names.iter().map(|name| (LOCAL_CHAT_MODELS_ENV, name)));
+        for (env, name) in requested {
+            if by_name.contains_key(name) {
+                continue;
+            }
+            let loaded =
+                match load_stored(&store, &mut candle, &mut llama, &mut registry, name).await {
+                    Ok(model) => {
+                        tracing::info!(
+                            target: "handshake_core::model_runtime::local_models",
+                            model = %name,
+                            model_id = %model.model_id,
+                            runtime = model.binding.adapter_id(),
+                            "local model loaded"
+                        );
+                        Some(model)
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            target: "handshake_core::model_runtime::local_models",
+                            model = %name,
+                            env,
+                            error = %err,
+                            "local model failed to load; left out"
+                        );
+                        None
+                    }
+                };
+            by_name.insert(name.clone(), loaded);
+        }
+        let roles = wanted
+            .into_iter()
+            .filter_map(|(role, name)| Some((role, by_name.get(&name)?.clone()?)))
+            .collect();
+        let chat = chat_names
+            .iter()
+            .filter_map(|name| by_name.get(name)?.clone())
+            .collect();
+
+        Self {
+            store: Some(store),
+            registry: Arc::new(registry),
+            candle: Arc::new(candle),
+            llama: Arc::new(llama),
+            roles,
+            chat,
+        }
+    }
+
+    pub fn store(&self) -> Option<&Arc<ModelStore>> {
+        self.store.as_ref()
+    }
+
+    pub fn registry(&self) -> &Arc<ModelRegistry> {
+        &self.registry
+    }
+
+    pub fn role(&self, role: LocalModelRole) -> Option<&LoadedLocalModel> {
+        self.roles.get(&role)
+    }
+
+    /// Models listed in `HANDSHAKE_LOCAL_CHAT_MODELS`, in order.
+    pub fn chat_models(&self) -> &[LoadedLocalModel] {
+        &self.chat
+    }
+
+    /// Router over the chat models only, so a client of the local OpenAI
+    /// server cannot reach the classifier or reranker.
+    pub fn chat_router(&self) -> LocalRouter {
+        let mut registry = ModelRegistry::default();
+        for model in &self.chat {
+            if let Some(registration) = self.registry.lookup(model.model_id) {
+                // Both were accepted by the full registry already.
+                let _ = registry.register(registration.clone());
+                let _ = registry.mark_loaded(model.model_id);
+            }
+        }
+        LocalRouter::new(Arc::new(registry), self.llama.clone(), self.candle.clone())
+    }
+
+    fn binding_of(&self, id: ModelId) -> Result<RuntimeBinding, ModelRuntimeError> {
+        self.registry
+            .lookup(id)
+            .map(|registration| registration.runtime_binding)
+            .ok_or_else(|| ModelRuntimeError::LoadError(format!("model is not registered: {id}")))
+    }
+
+    /// The runtime holding `model`.
+    pub fn runtime(&self, model: &LoadedLocalModel) -> Arc<dyn ModelRuntime> {
+        self.runtime_for(model.binding)
+    }
+
+    pub fn runtime_for(&self, binding: RuntimeBinding) -> Arc<dyn ModelRuntime> {
+        match binding {
+            RuntimeBinding::Candle => self.candle.clone(),
+            RuntimeBinding::LlamaCpp => self.llama.clone(),
+        }
+    }
+}
+
+async fn load_stored(
+    store: &ModelStore,
+    candle: &mut CandleRuntime,
+    llama: &mut LlamaCppRuntime,
+    registry: &mut ModelRegistry,
+    name: &str,
+) -> Result<LoadedLocalModel, ModelRuntimeError> {
+    let resolved = store.resolve(name)?;
+    let binding = resolved.manifest.runtime_binding;
+    let spec = super::LoadSpec {
+        artifact_path: resolved.artifact_path.clone(),
+        sha256_expected: resolved.manifest.sha256.clone(),
+        runtime_kind: binding.runtime_kind(),
+        sampling_defaults: super::SamplingParams::default(),
+        kv_cache_policy: super::KvCachePolicy::default(),
+        declared_capabilities: declared_capabilities(binding),
+        provider: super::ProviderKind::Local,
+        engine_origin: Some(binding.adapter_id().to_string()),
+        external_engine_import: None,
+    };
+    let runtime: &mut dyn ModelRuntime = match binding {
+        RuntimeBinding::Candle => candle,
+        RuntimeBinding::LlamaCpp => llama,
+    };
+    let model_id = runtime.load(spec).await?;
+    let capabilities = runtime.capabilities(model_id)?.clone();
+
+    // The registry is keyed by the runtime-minted id so routing by id reaches
+    // the model the runtime holds.
+    let mut registration = ModelRegistration::from_store(
+        store,
+        name,
+        capabilities,
+        OperatorId::new(LOCAL_MODELS_OPERATOR),
+    )?;
+    registration.model_id = model_id;
+    registry.register(registration)?;
+    registry.mark_loaded(model_id)?;
+    Ok(LoadedLocalModel {
+        name: name.to_string(),
+        model_id,
+        binding,
+    })
+}
+
+/// What to declare at load. Candle gets the same permissive base as
+/// [`super::candle::load_local_candle_model`] (its arch detection narrows it);
+/// llama.cpp must not declare activation steering.
+fn declared_capabilities(binding: RuntimeBinding) -> ModelCapabilities {
+    match binding {
+        RuntimeBinding::Candle => ModelCapabilities {
+            supports_lora: true,
+            supports_kv_prefix_cache: true,
+            supports_activation_steering: true,
+            ..ModelCapabilities::default()
+        },
+        RuntimeBinding::LlamaCpp => ModelCapabilities::default(),
+    }
+}
+
+impl LocalModelInfo for LocalModels {
+    fn chat_template(&self, id: ModelId) -> Option<String> {
+        match self.binding_of(id).ok()? {
+            RuntimeBinding::Candle => self.candle.chat_template(id).ok().flatten(),
+            // The llama.cpp adapter does not surface GGUF metadata.
+            RuntimeBinding::LlamaCpp => None,
+        }
+    }
+
+    fn count_tokens(&self, id: ModelId, text: &str) -> Result<u32, ModelRuntimeError> {
+        let tokens = match self.binding_of(id)? {
+            RuntimeBinding::Candle => self.candle.tokenize_prompt(id, text)?,
+            RuntimeBinding::LlamaCpp => self.llama.tokenize_prompt(id, text)?,
+        };
+        Ok(u32::try_from(tokens.len()).unwrap_or(u32::MAX))
+    }
+}
+
+static SHARED: OnceLock<Arc<LocalModels>> = OnceLock::new();
+
+/// Make `models` the process-wide set. The first call wins; later calls are
+/// ignored (and logged), so startup ordering bugs cannot swap runtimes under
+/// in-flight requests.
+pub fn install(models: Arc<LocalModels>) {
+    if SHARED.set(models).is_err() {
+        tracing::warn!(
+            target: "handshake_core::model_runtime::local_models",
+            "local models already installed; ignoring the second set"
+        );
+    }
+}
+
+/// The installed local models, if `main` loaded any.
+pub fn shared() -> Option<Arc<LocalModels>> {
+    SHARED.get().cloned()
+}
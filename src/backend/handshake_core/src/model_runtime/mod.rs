@@ -5,12 +5,14 @@ pub mod error;
 pub mod invariant;
 pub mod kv_cache;
 pub mod llama_cpp;
+pub mod local_models;
 pub mod lora;
 pub mod process_ledger_integration;
 pub mod registry;
 pub mod sandbox_binding;
 pub mod sandbox_runtime;
 pub mod steering;
+pub mod store;
 pub mod techniques;
 pub mod r#trait;
 pub mod types;
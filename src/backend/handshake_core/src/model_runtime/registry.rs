@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -6,7 +9,8 @@ use serde::{Deserialize, Serialize};
 use crate::process_ledger::ProcessEngineKind;
 
 use super::{
-    BaseModelTag, ModelCapabilities, ModelId, ModelRuntimeError, ProviderKind, RuntimeKind,
+    store::ModelStore, BaseModelTag, ModelCapabilities, ModelId, ModelRuntimeError, ProviderKind,
+    RuntimeKind,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -75,6 +79,36 @@ pub struct ModelRegistration {
     pub provider: ProviderKind,
 }
 
+impl ModelRegistration {
+    /// Registration for a model held by the [`ModelStore`]: artifact path,
+    /// digest, runtime binding and base model tag come from its manifest.
+    pub fn from_store(
+        store: &ModelStore,
+        name: &str,
+        declared_capabilities: ModelCapabilities,
+        registered_by: OperatorId,
+    ) -> Result<Self, ModelRuntimeError> {
+        let resolved = store.resolve(name)?;
+        let mut sha256 = [0_u8; 32];
+        hex::decode_to_slice(&resolved.manifest.sha256, &mut sha256).map_err(|error| {
+            ModelRuntimeError::LoadError(format!(
+                "stored model {name} has a malformed sha256: {error}"
+            ))
+        })?;
+        Ok(Self {
+            model_id: ModelId::new_v7(),
+            artifact_path: resolved.artifact_path,
+            sha256,
+            runtime_binding: resolved.manifest.runtime_binding,
+            declared_capabilities,
+            base_model_tag: resolved.manifest.base_model_tag,
+            registered_at_utc: Utc::now(),
+            registered_by,
+            provider: ProviderKind::Local,
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ModelRegistry {
     registrations: HashMap<ModelId, ModelRegistration>,
@@ -96,6 +130,30 @@ impl ModelRegistry {
         Ok(())
     }
 
+    /// Register a model by its [`ModelStore`] name instead of a hand-placed
+    /// path.
+    pub fn register_stored(
+        &mut self,
+        store: &ModelStore,
+        name: &str,
+        declared_capabilities: ModelCapabilities,
+        registered_by: OperatorId,
+    ) -> Result<ModelId, ModelRuntimeError> {
+        let reg = ModelRegistration::from_store(store, name, declared_capabilities, registered_by)?;
+        let model_id = reg.model_id;
+        self.register(reg)?;
+        Ok(model_id)
+    }
+
+    /// Lowercase hex digests of every registered artifact: the keep set for
+    /// [`ModelStore::gc`].
+    pub fn referenced_sha256(&self) -> BTreeSet<String> {
+        self.registrations
+            .values()
+            .map(|registration| hex::encode(registration.sha256))
+            .collect()
+    }
+
     pub fn lookup(&self, id: ModelId) -> Option<&ModelRegistration> {
         self.registrations.get(&id)
     }
@@ -0,0 +1,296 @@
+//! HTTP import into the model store, with resume.
+//!
+//! The artifact streams into `downloads/<key>.part` (the key is derived from
+//! the URL) while a `<key>.json` beside it records the URL and the server's
+//! validator. A later import of the same URL asks for the remaining bytes
+//! with `Range` (and `If-Range`, so a changed file restarts instead of being
+//! spliced); a server that ignores the range gets a fresh download. Only a
+//! complete artifact whose digest matches the request reaches the store.
+//!
+//! File writes go through `tokio::fs`; hashing and the commit into the store
+//! run on the blocking pool, so a multi-gigabyte import does not stall the
+//! runtime's workers.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use super::{
+    io_error, validate_model_name, ModelImportRequest, ModelSource, ModelStore, ModelStoreError,
+    ModelStoreManifest, ModelStoreResult, MODEL_SIDECAR_FILES,
+};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelDownloadRequest {
+    pub url: String,
+    #[serde(flatten)]
+    pub import: ModelImportRequest,
+    /// Sidecar file name (one of [`MODEL_SIDECAR_FILES`]) -> URL. Sidecars
+    /// are small and fetched whole after the artifact.
+    #[serde(default)]
+    pub sidecar_urls: BTreeMap<String, String>,
+}
+
+/// Written next to a partial download so a resume can tell whether the
+/// bytes on disk still belong to the same remote file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DownloadState {
+    url: String,
+    /// `ETag`, else `Last-Modified`: what `If-Range` sends back.
+    validator: Option<String>,
+}
+
+impl ModelStore {
+    /// Download an artifact (and any sidecars) and import it under
+    /// `request.import.name`. An interrupted download resumes on the next
+    /// call with the same URL.
+    pub async fn import_url(
+        &self,
+        client: &reqwest::Client,
+        request: &ModelDownloadRequest,
+    ) -> ModelStoreResult<ModelStoreManifest> {
+        validate_model_name(&request.import.name)?;
+        validate_url(&request.url)?;
+        for (file_name, url) in &request.sidecar_urls {
+            if !MODEL_SIDECAR_FILES.contains(&file_name.as_str()) {
+                return Err(ModelStoreError::Validation(format!(
+                    "{file_name} is not a known model sidecar"
+                )));
+            }
+            validate_url(url)?;
+        }
+
+        let key = hex::encode(&Sha256::digest(request.url.as_bytes())[..16]);
+        let part = self.downloads_dir().join(format!("{key}.part"));
+        let state_path = part.with_extension("json");
+        let (sha256, size_bytes) =
+            fetch_resumable(client, &request.url, &part, &state_path).await?;
+
+        let mut sidecars = Vec::new();
+        let mut fetched = Ok(());
+        for (file_name, url) in &request.sidecar_urls {
+            let tmp = self.tmp_path();
+            sidecars.push((file_name.clone(), tmp.clone()));
+            fetched = fetch_whole(client, url, &tmp).await;
+            if fetched.is_err() {
+                break;
+            }
+        }
+        let result = match fetched {
+            // The artifact stays in downloads/ so a retry only refetches the
+            // sidecars.
+            Err(error) => Err(error),
+            Ok(()) => {
+                let store = self.clone();
+                let committed = sidecars.clone();
+                let import = request.import.clone();
+                let source = ModelSource::Http {
+                    url: request.url.clone(),
+                };
+                let artifact = part.clone();
+                let result = blocking(&artifact, move || {
+                    store.commit(part, &sha256, size_bytes, committed, &import, source)
+                })
+                .await;
+                let _ = fs::remove_file(&state_path).await;
+                result
+            }
+        };
+        for (_, tmp) in sidecars {
+            let _ = fs::remove_file(tmp).await;
+        }
+        result
+    }
+}
+
+fn validate_url(url: &str) -> ModelStoreResult<()> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| ModelStoreError::Validation(format!("bad model url {url:?}: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ModelStoreError::Validation(format!(
+            "model url {url:?} must be http or https"
+        )));
+    }
+    Ok(())
+}
+
+fn download_error(url: &str, detail: impl std::fmt::Display) -> ModelStoreError {
+    ModelStoreError::Download(format!("GET {url}: {detail}"))
+}
+
+/// Run store work that reads or writes whole files on the blocking pool.
+async fn blocking<T, F>(path: &Path, work: F) -> ModelStoreResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> ModelStoreResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| io_error(path, io::Error::other(e)))?
+}
+
+/// Bring `part` up to the full remote file, resuming what is already there,
+/// and return its digest and size.
+async fn fetch_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    state_path: &Path,
+) -> ModelStoreResult<(String, u64)> {
+    let state = fs::read(state_path)
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<DownloadState>(&bytes).ok())
+        .filter(|state| state.url == url);
+    let mut offset = match (&state, fs::metadata(part).await) {
+        (Some(_), Ok(metadata)) => metadata.len(),
+        _ => 0,
+    };
+
+    let mut get = client.get(url);
+    if offset > 0 {
+        get = get.header(RANGE, format!("bytes={offset}-"));
+        if let Some(validator) = state.as_ref().and_then(|s| s.validator.as_deref()) {
+            get = get.header(IF_RANGE, validator);
+        }
+    }
+    let response = get.send().await.map_err(|e| download_error(url, e))?;
+    let status = response.status();
+    let content_range = response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    if offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // Nothing left to send: the part is complete if the server's length
+        // says so, and stale otherwise.
+        let total = content_range
+            .as_deref()
+            .and_then(|v| v.strip_prefix("bytes */"))
+            .and_then(|v| v.parse::<u64>().ok());
+        if total == Some(offset) {
+            let path = part.to_path_buf();
+            return blocking(part, move || super::hash_file(&path)).await;
+        }
+        let _ = fs::remove_file(part).await;
+        return Err(download_error(
+            url,
+            "partial download no longer matches the remote file; retry to restart",
+        ));
+    }
+    if !status.is_success() {
+        return Err(download_error(url, format!("HTTP {status}")));
+    }
+    let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+    if resumed {
+        let expected = format!("bytes {offset}-");
+        if !content_range
+            .as_deref()
+            .is_some_and(|v| v.starts_with(&expected))
+        {
+            let _ = fs::remove_file(part).await;
+            return Err(download_error(
+                url,
+                format!("asked to resume at {offset}, server sent {content_range:?}"),
+            ));
+        }
+    } else {
+        offset = 0;
+    }
+    let remaining = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let validator = response
+        .headers()
+        .get(ETAG)
+        .or_else(|| response.headers().get(LAST_MODIFIED))
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| state.and_then(|s| s.validator).filter(|_| resumed));
+    let state = DownloadState {
+        url: url.to_string(),
+        validator,
+    };
+    let state_bytes = serde_json::to_vec(&state).map_err(|e| download_error(url, e))?;
+    fs::write(state_path, state_bytes)
+        .await
+        .map_err(|e| io_error(state_path, e))?;
+
+    let (mut hasher, mut file) = if resumed {
+        let path = part.to_path_buf();
+        let hasher = blocking(part, move || hash_prefix(&path, offset)).await?;
+        let file = OpenOptions::new()
+            .append(true)
+            .open(part)
+            .await
+            .map_err(|e| io_error(part, e))?;
+        (hasher, file)
+    } else {
+        let file = fs::File::create(part)
+            .await
+            .map_err(|e| io_error(part, e))?;
+        (Sha256::new(), file)
+    };
+
+    let mut written = 0_u64;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| download_error(url, e))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| io_error(part, e))?;
+        hasher.update(&chunk);
+        written += chunk.len() as u64;
+    }
+    file.sync_all().await.map_err(|e| io_error(part, e))?;
+    if let Some(remaining) = remaining {
+        if written != remaining {
+            return Err(download_error(
+                url,
+                format!("connection closed after {written} of {remaining} bytes; retry to resume"),
+            ));
+        }
+    }
+    Ok((hex::encode(hasher.finalize()), offset + written))
+}
+
+/// Hash the first `len` bytes already on disk, to continue from.
+fn hash_prefix(part: &Path, len: u64) -> ModelStoreResult<Sha256> {
+    let mut hasher = Sha256::new();
+    let file = File::open(part).map_err(|e| io_error(part, e))?;
+    let hashed = super::pump(part, &mut file.take(len), &mut hasher, None)?;
+    if hashed != len {
+        return Err(ModelStoreError::Download(format!(
+            "{} shrank while resuming",
+            part.display()
+        )));
+    }
+    Ok(hasher)
+}
+
+async fn fetch_whole(client: &reqwest::Client, url: &str, target: &Path) -> ModelStoreResult<()> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| download_error(url, e))?;
+    if !response.status().is_success() {
+        return Err(download_error(url, format!("HTTP {}", response.status())));
+    }
+    let bytes = response.bytes().await.map_err(|e| download_error(url, e))?;
+    fs::write(target, &bytes)
+        .await
+        .map_err(|e| io_error(target, e))
+}
@@ -0,0 +1,935 @@
+//! Managed on-disk model store.
+//!
+//! Local artifacts used to be hand-placed: the registry and the swarm factory
+//! took whatever path they were handed, and nothing knew what was on disk.
+//! The store owns one directory instead:
+//!
+//! ```text
+//! <root>/
+//!   objects/sha256/<digest>/model.gguf | model.safetensors  (+ sidecars)
+//!   manifests/<name>.json
+//!   downloads/<key>.part, <key>.json                         (HTTP resume)
+//!   tmp/
+//! ```
+//!
+//! * objects are content-addressed by the artifact's sha256, so two names for
+//!   the same weights share one object directory;
+//! * a manifest is the catalog entry: name, digest, size, format, runtime
+//!   binding, base model tag and where the artifact came from;
+//! * candle reads `config.json` / `tokenizer.json` beside a safetensors
+//!   artifact, so the known sidecars are copied into the object directory and
+//!   hashed into the manifest as well;
+//! * an object no manifest names is garbage: [`ModelStore::gc`] removes it
+//!   unless the caller's keep set (the digests the registry has registered)
+//!   holds it.
+//!
+//! [`ModelStore::resolve`] only checks that the object is there and the right
+//! size; the loaders keep their own sha256 gate, and [`ModelStore::verify`]
+//! re-hashes on demand.
+
+mod download;
+
+pub use download::ModelDownloadRequest;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::storage::artifacts::{self, ArtifactError};
+
+use super::{BaseModelTag, ModelRuntimeError, RuntimeBinding};
+
+/// Overrides the default store root (`<workspace root>/.handshake/models`).
+pub const MODEL_STORE_ROOT_ENV: &str = "HANDSHAKE_MODEL_STORE_ROOT";
+
+pub const MODEL_STORE_MANIFEST_VERSION: u32 = 1;
+
+const OBJECTS_DIR: &str = "objects";
+const MANIFESTS_DIR: &str = "manifests";
+const DOWNLOADS_DIR: &str = "downloads";
+const TMP_DIR: &str = "tmp";
+
+/// Files next to a model artifact that travel with it into the store.
+pub const MODEL_SIDECAR_FILES: [&str; 5] = [
+    "config.json",
+    "tokenizer.json",
+    "tokenizer_config.json",
+    "generation_config.json",
+    "special_tokens_map.json",
+];
+
+const MAX_MODEL_NAME_LEN: usize = 128;
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelStoreError {
+    #[error("model store validation: {0}")]
+    Validation(String),
+    #[error("model not in store: {0}")]
+    NotFound(String),
+    #[error("model store conflict: {0}")]
+    Conflict(String),
+    #[error("sha256 mismatch for {subject}: expected {expected}, got {actual}")]
+    IntegrityMismatch {
+        subject: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("model download failed: {0}")]
+    Download(String),
+    #[error("model store io error at {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("model manifest {path} is unreadable: {source}")]
+    Manifest {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("workspace root: {0}")]
+    Artifact(#[from] ArtifactError),
+}
+
+pub type ModelStoreResult<T> = Result<T, ModelStoreError>;
+
+impl From<ModelStoreError> for ModelRuntimeError {
+    fn from(error: ModelStoreError) -> Self {
+        ModelRuntimeError::LoadError(error.to_string())
+    }
+}
+
+fn io_error(path: &Path, source: io::Error) -> ModelStoreError {
+    ModelStoreError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+// ===========================================================================
+// Manifest
+// ===========================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelArtifactFormat {
+    Gguf,
+    Safetensors,
+}
+
+impl ModelArtifactFormat {
+    /// File name of the artifact inside its object directory.
+    pub fn artifact_file_name(self) -> &'static str {
+        match self {
+            Self::Gguf => "model.gguf",
+            Self::Safetensors => "model.safetensors",
+        }
+    }
+
+    /// Binding used when the import does not name one.
+    pub fn default_binding(self) -> RuntimeBinding {
+        match self {
+            Self::Gguf => RuntimeBinding::LlamaCpp,
+            Self::Safetensors => RuntimeBinding::Candle,
+        }
+    }
+
+    /// Sniff the format from the file header: the GGUF magic, or a
+    /// safetensors little-endian header length followed by a JSON object.
+    pub fn detect(path: &Path) -> ModelStoreResult<Self> {
+        let mut header = [0_u8; 9];
+        let mut file = File::open(path).map_err(|e| io_error(path, e))?;
+        let read = read_up_to(&mut file, &mut header).map_err(|e| io_error(path, e))?;
+        if read >= 4 && &header[..4] == GGUF_MAGIC {
+            return Ok(Self::Gguf);
+        }
+        if read == header.len() && header[8] == b'{' {
+            let mut len = [0_u8; 8];
+            len.copy_from_slice(&header[..8]);
+            let header_len = u64::from_le_bytes(len);
+            let size = file.metadata().map_err(|e| io_error(path, e))?.len();
+            if header_len > 0 && header_len <= size.saturating_sub(8) {
+                return Ok(Self::Safetensors);
+            }
+        }
+        Err(ModelStoreError::Validation(format!(
+            "{} is neither a GGUF nor a safetensors artifact",
+            path.display()
+        )))
+    }
+}
+
+/// Where an artifact was imported from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ModelSource {
+    LocalPath { path: String },
+    Http { url: String },
+}
+
+/// One file of an object directory other than the artifact itself.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelStoreFile {
+    pub file_name: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+/// A catalog entry, stored as `manifests/<name>.json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelStoreManifest {
+    pub schema_version: u32,
+    pub name: String,
+    /// Lowercase hex sha256 of the artifact; also its object directory.
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub format: ModelArtifactFormat,
+    pub runtime_binding: RuntimeBinding,
+    pub base_model_tag: BaseModelTag,
+    #[serde(default)]
+    pub sidecars: Vec<ModelStoreFile>,
+    pub source: ModelSource,
+    pub imported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl ModelStoreManifest {
+    /// Total bytes of the object directory this manifest names.
+    pub fn object_bytes(&self) -> u64 {
+        self.size_bytes + self.sidecars.iter().map(|f| f.size_bytes).sum::<u64>()
+    }
+}
+
+/// What to call an imported artifact and how to run it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelImportRequest {
+    pub name: String,
+    /// Defaults from the detected format: GGUF -> llama.cpp, safetensors ->
+    /// candle.
+    #[serde(default)]
+    pub runtime_binding: Option<RuntimeBinding>,
+    #[serde(default)]
+    pub base_model_tag: Option<BaseModelTag>,
+    /// Refuse the import unless the artifact hashes to this (hex).
+    #[serde(default)]
+    pub expected_sha256: Option<String>,
+}
+
+/// A stored model ready to hand to a loader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedModel {
+    pub artifact_path: PathBuf,
+    pub manifest: ModelStoreManifest,
+}
+
+/// Outcome of re-hashing one catalog entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelVerifyReport {
+    pub name: String,
+    pub sha256: String,
+    pub ok: bool,
+    /// One line per missing or mismatching file.
+    pub problems: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelStoreUsage {
+    pub models: u64,
+    pub objects: u64,
+    pub object_bytes: u64,
+    /// Objects no manifest names (what a `gc` with an empty keep set frees).
+    pub unreferenced_objects: u64,
+    pub unreferenced_bytes: u64,
+    pub partial_downloads: u64,
+    pub partial_download_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelStoreGcReport {
+    pub dry_run: bool,
+    /// Digests of the object directories removed (or, dry run, removable).
+    pub removed_objects: Vec<String>,
+    /// Unreferenced objects spared because the keep set holds them.
+    pub kept_objects: Vec<String>,
+    pub removed_partial_downloads: u64,
+    pub freed_bytes: u64,
+}
+
+// ===========================================================================
+// Store
+// ===========================================================================
+
+#[derive(Clone, Debug)]
+pub struct ModelStore {
+    root: PathBuf,
+}
+
+impl ModelStore {
+    /// Open (creating if needed) a store rooted at `root`.
+    pub fn open(root: impl Into<PathBuf>) -> ModelStoreResult<Self> {
+        let store = Self { root: root.into() };
+        for dir in [OBJECTS_DIR, MANIFESTS_DIR, DOWNLOADS_DIR, TMP_DIR] {
+            let path = store.root.join(dir);
+            fs::create_dir_all(&path).map_err(|e| io_error(&path, e))?;
+        }
+        Ok(store)
+    }
+
+    /// Open the store at [`MODEL_STORE_ROOT_ENV`], or under the workspace
+    /// root's `.handshake/models`.
+    pub fn open_default() -> ModelStoreResult<Self> {
+        if let Ok(value) = std::env::var(MODEL_STORE_ROOT_ENV) {
+            let trimmed = value.trim();
+            if !trimmed.is_empty() {
+                return Self::open(trimmed);
+            }
+        }
+        let workspace_root = artifacts::resolve_workspace_root()?;
+        Self::open(workspace_root.join(".handshake").join("models"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Object directory of a digest.
+    pub fn object_dir(&self, sha256: &str) -> PathBuf {
+        self.root
+            .join(OBJECTS_DIR)
+            .join("sha256")
+            .join(sha256.to_ascii_lowercase())
+    }
+
+    fn manifest_path(&self, name: &str) -> PathBuf {
+        self.root.join(MANIFESTS_DIR).join(format!("{name}.json"))
+    }
+
+    fn downloads_dir(&self) -> PathBuf {
+        self.root.join(DOWNLOADS_DIR)
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        self.root
+            .join(TMP_DIR)
+            .join(format!("{}.part", uuid::Uuid::now_v7()))
+    }
+
+    // -- catalog -------------------------------------------------------------
+
+    /// Every catalog entry, sorted by name.
+    pub fn list(&self) -> ModelStoreResult<Vec<ModelStoreManifest>> {
+        let dir = self.root.join(MANIFESTS_DIR);
+        let mut manifests = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| io_error(&dir, e))? {
+            let path = entry.map_err(|e| io_error(&dir, e))?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            manifests.push(read_manifest(&path)?);
+        }
+        manifests.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(manifests)
+    }
+
+    pub fn get(&self, name: &str) -> ModelStoreResult<ModelStoreManifest> {
+        validate_model_name(name)?;
+        let path = self.manifest_path(name);
+        if !path.is_file() {
+            return Err(ModelStoreError::NotFound(name.to_string()));
+        }
+        read_manifest(&path)
+    }
+
+    /// Look a model up by name for loading. Checks the artifact is present
+    /// and the size the manifest recorded; does not re-hash.
+    pub fn resolve(&self, name: &str) -> ModelStoreResult<ResolvedModel> {
+        let manifest = self.get(name)?;
+        let artifact_path = self
+            .object_dir(&manifest.sha256)
+            .join(manifest.format.artifact_file_name());
+        let size = fs::metadata(&artifact_path)
+            .map_err(|e| io_error(&artifact_path, e))?
+            .len();
+        if size != manifest.size_bytes {
+            return Err(ModelStoreError::Conflict(format!(
+                "{name}: artifact is {size} bytes, manifest says {}",
+                manifest.size_bytes
+            )));
+        }
+        Ok(ResolvedModel {
+            artifact_path,
+            manifest,
+        })
+    }
+
+    /// Drop a catalog entry. Its object stays until the next [`Self::gc`].
+    pub fn remove(&self, name: &str) -> ModelStoreResult<ModelStoreManifest> {
+        let manifest = self.get(name)?;
+        let path = self.manifest_path(name);
+        fs::remove_file(&path).map_err(|e| io_error(&path, e))?;
+        Ok(manifest)
+    }
+
+    // -- import --------------------------------------------------------------
+
+    /// Copy a local artifact (and the known sidecars beside it) into the
+    /// store. Re-importing the same artifact under the same name is a no-op.
+    pub fn import_local(
+        &self,
+        source: &Path,
+        request: &ModelImportRequest,
+    ) -> ModelStoreResult<ModelStoreManifest> {
+        validate_model_name(&request.name)?;
+        if !source.is_file() {
+            return Err(ModelStoreError::Validation(format!(
+                "{} is not a regular file",
+                source.display()
+            )));
+        }
+        let tmp = self.tmp_path();
+        let (sha256, size_bytes) = copy_hashing(source, &tmp)?;
+        let sidecar_dir = source.parent().unwrap_or_else(|| Path::new("."));
+        let sidecars = MODEL_SIDECAR_FILES
+            .iter()
+            .map(|name| (name.to_string(), sidecar_dir.join(name)))
+            .filter(|(_, path)| path.is_file())
+            .collect();
+        self.commit(
+            tmp,
+            &sha256,
+            size_bytes,
+            sidecars,
+            request,
+            ModelSource::LocalPath {
+                path: source.display().to_string(),
+            },
+        )
+    }
+
+    /// Move a fully written temp artifact into its object directory and
+    /// write the manifest.
+    fn commit(
+        &self,
+        tmp: PathBuf,
+        sha256: &str,
+        size_bytes: u64,
+        sidecars: Vec<(String, PathBuf)>,
+        request: &ModelImportRequest,
+        source: ModelSource,
+    ) -> ModelStoreResult<ModelStoreManifest> {
+        let result = self.commit_inner(&tmp, sha256, size_bytes, sidecars, request, source);
+        if tmp.exists() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result
+    }
+
+    fn commit_inner(
+        &self,
+        tmp: &Path,
+        sha256: &str,
+        size_bytes: u64,
+        sidecars: Vec<(String, PathBuf)>,
+        request: &ModelImportRequest,
+        source: ModelSource,
+    ) -> ModelStoreResult<ModelStoreManifest> {
+        if let Some(expected) = &request.expected_sha256 {
+            if !expected.trim().eq_ignore_ascii_case(sha256) {
+                return Err(ModelStoreError::IntegrityMismatch {
+                    subject: request.name.clone(),
+                    expected: expected.trim().to_ascii_lowercase(),
+                    actual: sha256.to_string(),
+                });
+            }
+        }
+        let format = ModelArtifactFormat::detect(tmp)?;
+        let runtime_binding = request
+            .runtime_binding
+            .unwrap_or_else(|| format.default_binding());
+
+        let existing = self.manifest_path(&request.name);
+        if existing.is_file() {
+            let manifest = read_manifest(&existing)?;
+            if manifest.sha256 != sha256 {
+                return Err(ModelStoreError::Conflict(format!(
+                    "{} already names {}; remove it before importing different weights",
+                    request.name, manifest.sha256
+                )));
+            }
+            if self.resolve(&request.name).is_ok() {
+                return Ok(manifest);
+            }
+        }
+
+        let object_dir = self.object_dir(sha256);
+        fs::create_dir_all(&object_dir).map_err(|e| io_error(&object_dir, e))?;
+        let artifact = object_dir.join(format.artifact_file_name());
+        if !artifact.is_file() {
+            fs::rename(tmp, &artifact).map_err(|e| io_error(&artifact, e))?;
+        }
+
+        let mut stored_sidecars = Vec::new();
+        for (file_name, path) in sidecars {
+            let target = object_dir.join(&file_name);
+            let (digest, size) = if target.is_file() {
+                let stored = hash_file(&target)?;
+                let incoming = hash_file(&path)?;
+                if stored.0 != incoming.0 {
+                    return Err(ModelStoreError::Conflict(format!(
+                        "object {sha256} already holds a different {file_name}"
+                    )));
+                }
+                stored
+            } else {
+                let tmp_sidecar = self.tmp_path();
+                let hashed = copy_hashing(&path, &tmp_sidecar)?;
+                fs::rename(&tmp_sidecar, &target).map_err(|e| io_error(&target, e))?;
+                hashed
+            };
+            stored_sidecars.push(ModelStoreFile {
+                file_name,
+                sha256: digest,
+                size_bytes: size,
+            });
+        }
+        // Sidecars an earlier import already placed belong to the object too.
+        for file_name in MODEL_SIDECAR_FILES {
+            let path = object_dir.join(file_name);
+            if path.is_file() && !stored_sidecars.iter().any(|f| f.file_name == file_name) {
+                let (digest, size) = hash_file(&path)?;
+                stored_sidecars.push(ModelStoreFile {
+                    file_name: file_name.to_string(),
+                    sha256: digest,
+                    size_bytes: size,
+                });
+            }
+        }
+        stored_sidecars.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+        let manifest = ModelStoreManifest {
+            schema_version: MODEL_STORE_MANIFEST_VERSION,
+            name: request.name.clone(),
+            sha256: sha256.to_string(),
+            size_bytes,
+            format,
+            runtime_binding,
+            base_model_tag: request.base_model_tag.clone().unwrap_or_default(),
+            sidecars: stored_sidecars,
+            source,
+            imported_at: Utc::now(),
+            verified_at: Some(Utc::now()),
+        };
+        self.write_manifest(&manifest)?;
+        Ok(manifest)
+    }
+
+    fn write_manifest(&self, manifest: &ModelStoreManifest) -> ModelStoreResult<()> {
+        let path = self.manifest_path(&manifest.name);
+        let bytes =
+            serde_json::to_vec_pretty(manifest).map_err(|source| ModelStoreError::Manifest {
+                path: path.display().to_string(),
+                source,
+            })?;
+        let tmp = self.tmp_path();
+        fs::write(&tmp, bytes).map_err(|e| io_error(&tmp, e))?;
+        fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))
+    }
+
+    // -- integrity -----------------------------------------------------------
+
+    /// Re-hash one entry's artifact and sidecars. A clean pass stamps
+    /// `verified_at`.
+    pub fn verify(&self, name: &str) -> ModelStoreResult<ModelVerifyReport> {
+        let mut manifest = self.get(name)?;
+        let object_dir = self.object_dir(&manifest.sha256);
+        let mut expected = vec![(
+            manifest.format.artifact_file_name().to_string(),
+            manifest.sha256.clone(),
+            manifest.size_bytes,
+        )];
+        expected.extend(
+            manifest
+                .sidecars
+                .iter()
+                .map(|f| (f.file_name.clone(), f.sha256.clone(), f.size_bytes)),
+        );
+        let mut problems = Vec::new();
+        for (file_name, sha256, size) in expected {
+            let path = object_dir.join(&file_name);
+            if !path.is_file() {
+                problems.push(format!("{file_name}: missing"));
+                continue;
+            }
+            let (actual, actual_size) = hash_file(&path)?;
+            if actual != sha256 {
+                problems.push(format!(
+                    "{file_name}: sha256 {actual} ({actual_size} bytes), expected {sha256} \
+                     ({size} bytes)"
+                ));
+            }
+        }
+        let ok = problems.is_empty();
+        if ok {
+            manifest.verified_at = Some(Utc::now());
+            self.write_manifest(&manifest)?;
+        }
+        Ok(ModelVerifyReport {
+            name: manifest.name,
+            sha256: manifest.sha256,
+            ok,
+            problems,
+        })
+    }
+
+    pub fn verify_all(&self) -> ModelStoreResult<Vec<ModelVerifyReport>> {
+        self.list()?
+            .iter()
+            .map(|manifest| self.verify(&manifest.name))
+            .collect()
+    }
+
+    // -- disk usage and gc ---------------------------------------------------
+
+    /// Object digests on disk with their sizes.
+    fn objects(&self) -> ModelStoreResult<BTreeMap<String, u64>> {
+        let dir = self.root.join(OBJECTS_DIR).join("sha256");
+        let mut objects = BTreeMap::new();
+        if !dir.is_dir() {
+            return Ok(objects);
+        }
+        for entry in fs::read_dir(&dir).map_err(|e| io_error(&dir, e))? {
+            let entry = entry.map_err(|e| io_error(&dir, e))?;
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(digest) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            objects.insert(digest.to_string(), dir_bytes(&path)?);
+        }
+        Ok(objects)
+    }
+
+    fn partial_downloads(&self) -> ModelStoreResult<Vec<(PathBuf, u64)>> {
+        let mut partials = Vec::new();
+        for dir in [self.downloads_dir(), self.root.join(TMP_DIR)] {
+            for entry in fs::read_dir(&dir).map_err(|e| io_error(&dir, e))? {
+                let path = entry.map_err(|e| io_error(&dir, e))?.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("part") {
+                    let size = fs::metadata(&path).map_err(|e| io_error(&path, e))?.len();
+                    partials.push((path, size));
+                }
+            }
+        }
+        Ok(partials)
+    }
+
+    fn referenced_digests(&self) -> ModelStoreResult<BTreeSet<String>> {
+        Ok(self.list()?.into_iter().map(|m| m.sha256).collect())
+    }
+
+    pub fn usage(&self) -> ModelStoreResult<ModelStoreUsage> {
+        let referenced = self.referenced_digests()?;
+        let objects = self.objects()?;
+        let partials = self.partial_downloads()?;
+        let mut usage = ModelStoreUsage {
+            models: self.list()?.len() as u64,
+            objects: objects.len() as u64,
+            partial_downloads: partials.len() as u64,
+            partial_download_bytes: partials.iter().map(|(_, size)| size).sum(),
+            ..ModelStoreUsage::default()
+        };
+        for (digest, bytes) in objects {
+            usage.object_bytes += bytes;
+            if !referenced.contains(&digest) {
+                usage.unreferenced_objects += 1;
+                usage.unreferenced_bytes += bytes;
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Remove object directories no manifest names and that `keep` (lowercase
+    /// hex digests, e.g. [`super::ModelRegistry::referenced_sha256`]) does
+    /// not hold. `include_partial_downloads` also drops interrupted
+    /// downloads, which otherwise stay to be resumed.
+    pub fn gc(
+        &self,
+        keep: &BTreeSet<String>,
+        include_partial_downloads: bool,
+        dry_run: bool,
+    ) -> ModelStoreResult<ModelStoreGcReport> {
+        let referenced = self.referenced_digests()?;
+        let mut report = ModelStoreGcReport {
+            dry_run,
+            ..ModelStoreGcReport::default()
+        };
+        for (digest, bytes) in self.objects()? {
+            if referenced.contains(&digest) {
+                continue;
+            }
+            if keep.contains(&digest) {
+                report.kept_objects.push(digest);
+                continue;
+            }
+            if !dry_run {
+                let path = self.object_dir(&digest);
+                fs::remove_dir_all(&path).map_err(|e| io_error(&path, e))?;
+            }
+            report.freed_bytes += bytes;
+            report.removed_objects.push(digest);
+        }
+        if include_partial_downloads {
+            for (path, bytes) in self.partial_downloads()? {
+                if !dry_run {
+                    fs::remove_file(&path).map_err(|e| io_error(&path, e))?;
+                    let state = path.with_extension("json");
+                    if state.is_file() {
+                        let _ = fs::remove_file(state);
+                    }
+                }
+                report.freed_bytes += bytes;
+                report.removed_partial_downloads += 1;
+            }
+        }
+        Ok(report)
+    }
+}
+
+// ===========================================================================
+// Helpers
+// ===========================================================================
+
+/// Catalog names become file names and are typed by operators: ASCII
+/// letters, digits, `.`, `_`, `-`, not starting with a dot.
+pub fn validate_model_name(name: &str) -> ModelStoreResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_MODEL_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ModelStoreError::Validation(format!(
+            "model name {name:?} must be 1-{MAX_MODEL_NAME_LEN} of [A-Za-z0-9._-], not starting \
+             with '.'"
+        )))
+    }
+}
+
+fn read_manifest(path: &Path) -> ModelStoreResult<ModelStoreManifest> {
+    let bytes = fs::read(path).map_err(|e| io_error(path, e))?;
+    serde_json::from_slice(&bytes).map_err(|source| ModelStoreError::Manifest {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Stream `reader` into `hasher` (and `sink`), returning the byte count.
+fn pump(
+    path: &Path,
+    reader: &mut impl Read,
+    hasher: &mut Sha256,
+    mut sink: Option<&mut File>,
+) -> ModelStoreResult<u64> {
+    let mut buf = vec![0_u8; 1 << 20];
+    let mut total = 0_u64;
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| io_error(path, e))?;
+        if n == 0 {
+            return Ok(total);
+        }
+        hasher.update(&buf[..n]);
+        if let Some(file) = sink.as_deref_mut() {
+            file.write_all(&buf[..n]).map_err(|e| io_error(path, e))?;
+        }
+        total += n as u64;
+    }
+}
+
+/// Lowercase hex sha256 and size of a file.
+pub fn hash_file(path: &Path) -> ModelStoreResult<(String, u64)> {
+    let mut file = File::open(path).map_err(|e| io_error(path, e))?;
+    let mut hasher = Sha256::new();
+    let size = pump(path, &mut file, &mut hasher, None)?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+fn copy_hashing(source: &Path, target: &Path) -> ModelStoreResult<(String, u64)> {
+    let mut reader = File::open(source).map_err(|e| io_error(source, e))?;
+    let mut out = File::create(target).map_err(|e| io_error(target, e))?;
+    let mut hasher = Sha256::new();
+    let size = pump(source, &mut reader, &mut hasher, Some(&mut out))?;
+    out.sync_all().map_err(|e| io_error(target, e))?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+fn dir_bytes(dir: &Path) -> ModelStoreResult<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir).map_err(|e| io_error(dir, e))? {
+        let entry = entry.map_err(|e| io_error(dir, e))?;
+        let metadata = entry.metadata().map_err(|e| io_error(&entry.path(), e))?;
+        total += if metadata.is_dir() {
+            dir_bytes(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gguf_bytes(tail: &[u8]) -> Vec<u8> {
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend_from_slice(&3_u32.to_le_bytes());
+        bytes.extend_from_slice(tail);
+        bytes
+    }
+
+    fn safetensors_bytes() -> Vec<u8> {
+        let header = br#"{"__metadata__":{}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header);
+        bytes
+    }
+
+    fn request(name: &str) -> ModelImportRequest {
+        ModelImportRequest {
+            name: name.to_string(),
+            ..ModelImportRequest::default()
+        }
+    }
+
+    #[test]
+    fn import_is_content_addressed_and_carries_sidecars() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = ModelStore::open(dir.path().join("store")).expect("open");
+        let src = dir.path().join("src");
+        fs::create_dir_all(&src).expect("src");
+        fs::write(src.join("weights.safetensors"), safetensors_bytes()).expect("weights");
+        fs::write(src.join("config.json"), b"{}").expect("config");
+
+        let first = store
+            .import_local(&src.join("weights.safetensors"), &request("tiny"))
+            .expect("import");
+        assert_eq!(first.format, ModelArtifactFormat::Safetensors);
+        assert_eq!(first.runtime_binding, RuntimeBinding::Candle);
+        assert_eq!(first.sidecars.len(), 1);
+        assert_eq!(first.sidecars[0].file_name, "config.json");
+
+        // Same weights, second name: one object, two catalog entries.
+        let second = store
+            .import_local(&src.join("weights.safetensors"), &request("tiny-alias"))
+            .expect("alias import");
+        assert_eq!(second.sha256, first.sha256);
+        let usage = store.usage().expect("usage");
+        assert_eq!((usage.models, usage.objects), (2, 1));
+
+        let resolved = store.resolve("tiny").expect("resolve");
+        assert_eq!(
+            resolved.artifact_path,
+            store.object_dir(&first.sha256).join("model.safetensors")
+        );
+        assert!(resolved
+            .artifact_path
+            .with_file_name("config.json")
+            .is_file());
+
+        // Re-import under the same name is a no-op; different weights conflict.
+        assert_eq!(
+            store
+                .import_local(&src.join("weights.safetensors"), &request("tiny"))
+                .expect("re-import")
+                .imported_at,
+            first.imported_at
+        );
+        fs::write(src.join("other.gguf"), gguf_bytes(b"x")).expect("gguf");
+        assert!(matches!(
+            store.import_local(&src.join("other.gguf"), &request("tiny")),
+            Err(ModelStoreError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn expected_digest_and_unknown_formats_are_refused() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = ModelStore::open(dir.path().join("store")).expect("open");
+        let path = dir.path().join("m.gguf");
+        fs::write(&path, gguf_bytes(b"weights")).expect("gguf");
+        let mut req = request("m");
+        req.expected_sha256 = Some("00".repeat(32));
+        assert!(matches!(
+            store.import_local(&path, &req),
+            Err(ModelStoreError::IntegrityMismatch { .. })
+        ));
+        let junk = dir.path().join("junk.bin");
+        fs::write(&junk, b"not a model").expect("junk");
+        assert!(matches!(
+            store.import_local(&junk, &request("junk")),
+            Err(ModelStoreError::Validation(_))
+        ));
+        assert!(validate_model_name("../escape").is_err());
+        assert!(validate_model_name(".hidden").is_err());
+        // Failed imports leave nothing behind.
+        let usage = store.usage().expect("usage");
+        assert_eq!((usage.objects, usage.partial_downloads), (0, 0));
+    }
+
+    #[test]
+    fn verify_flags_corruption_and_gc_honours_the_keep_set() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = ModelStore::open(dir.path().join("store")).expect("open");
+        let a = dir.path().join("a.gguf");
+        let b = dir.path().join("b.gguf");
+        fs::write(&a, gguf_bytes(b"aaaa")).expect("a");
+        fs::write(&b, gguf_bytes(b"bbbb")).expect("b");
+        let ma = store.import_local(&a, &request("a")).expect("a");
+        let mb = store.import_local(&b, &request("b")).expect("b");
+        assert!(store.verify("a").expect("verify").ok);
+
+        let artifact = store.object_dir(&ma.sha256).join("model.gguf");
+        fs::write(&artifact, gguf_bytes(b"AAAA")).expect("corrupt");
+        let report = store.verify("a").expect("verify corrupt");
+        assert!(!report.ok);
+        assert_eq!(report.problems.len(), 1);
+
+        store.remove("a").expect("remove a");
+        store.remove("b").expect("remove b");
+        let keep = BTreeSet::from([mb.sha256.clone()]);
+        let dry = store.gc(&keep, false, true).expect("dry gc");
+        assert_eq!(dry.removed_objects, vec![ma.sha256.clone()]);
+        assert!(store.object_dir(&ma.sha256).is_dir());
+
+        let gc = store.gc(&keep, false, false).expect("gc");
+        assert_eq!(gc.removed_objects, vec![ma.sha256.clone()]);
+        assert_eq!(gc.kept_objects, vec![mb.sha256.clone()]);
+        assert!(gc.freed_bytes > 0);
+        assert!(!store.object_dir(&ma.sha256).exists());
+        assert!(store.object_dir(&mb.sha256).is_dir());
+    }
+}
@@ -0,0 +1,26 @@
+//! Shared secret and PII detection for every redaction path.
+//!
+//! Debug bundles, session-transcript export, terminal capture, source
+//! ingestion, distillation review, and knowledge-graph export all scan text
+//! through one [`DetectionEngine`] and get the same [`DetectionReport`] back:
+//! detector id, category, severity, and location per finding, never the
+//! matched bytes.
+//! Each path still owns its replacement marker and its policy (block,
+//! quarantine, mask); what counts as a finding is decided here.
+//!
+//! The engine is the built-in detector set ([`engine::builtin_detector_ids`])
+//! compiled together with one workspace's governed [`DetectionRules`]. Paths
+//! without a workspace in scope use [`DetectionEngine::shared`].
+
+pub mod engine;
+pub mod rules;
+pub mod validators;
+
+pub use engine::{
+    builtin_detector_ids, DetectionCategory, DetectionEngine, DetectionFinding, DetectionReport,
+    DetectionSeverity, DetectionSurface, ENGINE_VERSION,
+};
+pub use rules::{
+    AllowRule, CustomPattern, DenyRule, DetectionRuleError, DetectionRules, WORKSPACE_SETTINGS_KEY,
+};
+pub use validators::Validator;
@@ -0,0 +1,352 @@
+//! Per-workspace allow/deny rules and custom patterns.
+//!
+//! Rules are stored in the workspace settings document under
+//! `settings.redaction` ([`WORKSPACE_SETTINGS_KEY`]), so they travel through
+//! the governed workspace-settings path: the storage layer compiles them
+//! before accepting a save, and every accepted save is recorded as a
+//! `KNOWLEDGE_WORKSPACE_SETTINGS_STATE_RECORDED` ledger event. A rule set that
+//! does not compile is never persisted.
+//!
+//! Shape:
+//!
+//! ```json
+//! {
+//!   "allow": [{ "id": "stripe-test-card", "literal": "4242 4242 4242 4242" }],
+//!   "deny": [{ "id": "codename", "literal": "Project Nightjar", "severity": "high" }],
+//!   "custom_patterns": [{
+//!     "id": "employee-id", "pattern": "\\bEMP-[0-9]{6}\\b",
+//!     "category": "contact", "severity": "medium"
+//!   }],
+//!   "disabled_detectors": ["email"],
+//!   "entropy_threshold": 3.8
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::engine::{DetectionCategory, DetectionSeverity, DetectionSurface};
+use super::validators::Validator;
+
+/// Key of the rule document inside `settings_state.settings`.
+pub const WORKSPACE_SETTINGS_KEY: &str = "redaction";
+/// Upper bound on allow + deny + custom rules in one workspace.
+pub const MAX_RULES: usize = 256;
+/// Upper bound on one literal or pattern, in bytes.
+pub const MAX_PATTERN_LEN: usize = 1024;
+/// Default gate for generic `secret = value` assignments: random credentials
+/// sit well above 3.5 bits/char; English words and placeholders sit below.
+pub const DEFAULT_ENTROPY_THRESHOLD: f64 = 3.5;
+
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum DetectionRuleError {
+    #[error("redaction settings are malformed: {0}")]
+    Malformed(String),
+    #[error("redaction rule id `{0}` is empty, duplicated, or not [a-z0-9_-]")]
+    InvalidRuleId(String),
+    #[error("redaction rule `{0}` must set exactly one of `literal` or `pattern`")]
+    Matcher(String),
+    #[error("redaction rule `{rule_id}` has an invalid pattern: {message}")]
+    InvalidPattern { rule_id: String, message: String },
+    #[error("unknown built-in detector `{0}`")]
+    UnknownDetector(String),
+    #[error("too many redaction rules ({count}, max {max})")]
+    TooManyRules { count: usize, max: usize },
+    #[error("entropy threshold {0} is outside 0.0..=8.0")]
+    InvalidThreshold(f64),
+    #[error("load workspace redaction settings: {0}")]
+    Storage(String),
+}
+
+/// The workspace-governed rule document.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DetectionRules {
+    /// Known-safe values (published test keys, sample data). A finding whose
+    /// matched text satisfies an allow rule is suppressed and only counted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<AllowRule>,
+    /// Terms that are always sensitive in this workspace (customer names,
+    /// internal hostnames, project codenames).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<DenyRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_patterns: Vec<CustomPattern>,
+    /// Built-in detector ids switched off for this workspace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_detectors: Vec<String>,
+    /// Override for the generic-assignment entropy gate, in bits/char.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy_threshold: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AllowRule {
+    pub id: String,
+    /// Exact matched text to allow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    /// Regex tested against the matched text; anchor with `^...$` for an
+    /// exact match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Restrict the rule to these detector ids; empty applies to all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detectors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DenyRule {
+    pub id: String,
+    /// Case-insensitive term to flag wherever it appears.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default = "default_deny_severity")]
+    pub severity: DetectionSeverity,
+    /// Surfaces the rule applies to; empty applies to all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub surfaces: Vec<DetectionSurface>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomPattern {
+    pub id: String,
+    /// Regex proposing candidates. A named `value` group, when present, is
+    /// what the validator, entropy gate, and allow rules inspect.
+    pub pattern: String,
+    #[serde(default = "default_custom_category")]
+    pub category: DetectionCategory,
+    #[serde(default = "default_custom_severity")]
+    pub severity: DetectionSeverity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator: Option<Validator>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_entropy: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub surfaces: Vec<DetectionSurface>,
+}
+
+fn default_deny_severity() -> DetectionSeverity {
+    DetectionSeverity::High
+}
+
+fn default_custom_category() -> DetectionCategory {
+    DetectionCategory::Custom
+}
+
+fn default_custom_severity() -> DetectionSeverity {
+    DetectionSeverity::Medium
+}
+
+impl DetectionRules {
+    /// Read the rule document out of a `hsk.workspace_settings_state@1`
+    /// value. A workspace without a `settings.redaction` key gets the empty
+    /// rule set (built-in detectors only).
+    pub fn from_workspace_settings_state(
+        settings_state: &Value,
+    ) -> Result<Self, DetectionRuleError> {
+        match settings_state
+            .get("settings")
+            .and_then(|settings| settings.get(WORKSPACE_SETTINGS_KEY))
+        {
+            None | Some(Value::Null) => Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|err| DetectionRuleError::Malformed(err.to_string())),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub(crate) fn compile(
+        &self,
+        builtin_ids: &[&str],
+    ) -> Result<CompiledRules, DetectionRuleError> {
+        let count = self.allow.len() + self.deny.len() + self.custom_patterns.len();
+        if count > MAX_RULES {
+            return Err(DetectionRuleError::TooManyRules {
+                count,
+                max: MAX_RULES,
+            });
+        }
+        if let Some(threshold) = self.entropy_threshold {
+            if !(0.0..=8.0).contains(&threshold) {
+                return Err(DetectionRuleError::InvalidThreshold(threshold));
+            }
+        }
+        for detector in &self.disabled_detectors {
+            if !builtin_ids.contains(&detector.as_str()) {
+                return Err(DetectionRuleError::UnknownDetector(detector.clone()));
+            }
+        }
+
+        let mut ids = HashSet::new();
+        let mut claim_id = |prefix: &str, id: &str| {
+            let valid = !id.is_empty()
+                && id.bytes().all(|b| {
+                    b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-'
+                });
+            if valid && ids.insert(format!("{prefix}:{id}")) {
+                Ok(())
+            } else {
+                Err(DetectionRuleError::InvalidRuleId(id.to_string()))
+            }
+        };
+
+        let mut allow = Vec::with_capacity(self.allow.len());
+        for rule in &self.allow {
+            claim_id("allow", &rule.id)?;
+            let matcher = match (&rule.literal, &rule.pattern) {
+                (Some(literal), None) if !literal.is_empty() => {
+                    check_len(&rule.id, literal)?;
+                    AllowMatcher::Literal(literal.clone())
+                }
+                (None, Some(pattern)) => AllowMatcher::Pattern(compile_regex(&rule.id, pattern)?),
+                _ => return Err(DetectionRuleError::Matcher(rule.id.clone())),
+            };
+            allow.push(CompiledAllow {
+                matcher,
+                detectors: rule.detectors.clone(),
+            });
+        }
+
+        let mut deny = Vec::with_capacity(self.deny.len());
+        for rule in &self.deny {
+            claim_id("deny", &rule.id)?;
+            let regex = match (&rule.literal, &rule.pattern) {
+                (Some(literal), None) if !literal.is_empty() => {
+                    check_len(&rule.id, literal)?;
+                    compile_regex(&rule.id, &format!("(?i){}", regex::escape(literal)))?
+                }
+                (None, Some(pattern)) => compile_regex(&rule.id, pattern)?,
+                _ => return Err(DetectionRuleError::Matcher(rule.id.clone())),
+            };
+            deny.push(CompiledDeny {
+                detector_id: format!("deny:{}", rule.id),
+                regex,
+                severity: rule.severity,
+                surfaces: rule.surfaces.clone(),
+            });
+        }
+
+        let mut custom = Vec::with_capacity(self.custom_patterns.len());
+        for rule in &self.custom_patterns {
+            claim_id("custom", &rule.id)?;
+            if let Some(min_entropy) = rule.min_entropy {
+                if !(0.0..=8.0).contains(&min_entropy) {
+                    return Err(DetectionRuleError::InvalidThreshold(min_entropy));
+                }
+            }
+            custom.push(CompiledCustom {
+                detector_id: format!("custom:{}", rule.id),
+                regex: compile_regex(&rule.id, &rule.pattern)?,
+                category: rule.category,
+                severity: rule.severity,
+                validator: rule.validator,
+                min_entropy: rule.min_entropy,
+                surfaces: rule.surfaces.clone(),
+            });
+        }
+
+        Ok(CompiledRules {
+            allow,
+            deny,
+            custom,
+            disabled: self.disabled_detectors.iter().cloned().collect(),
+            entropy_threshold: self.entropy_threshold.unwrap_or(DEFAULT_ENTROPY_THRESHOLD),
+        })
+    }
+}
+
+fn check_len(rule_id: &str, value: &str) -> Result<(), DetectionRuleError> {
+    if value.len() > MAX_PATTERN_LEN {
+        return Err(DetectionRuleError::InvalidPattern {
+            rule_id: rule_id.to_string(),
+            message: format!("longer than {MAX_PATTERN_LEN} bytes"),
+        });
+    }
+    Ok(())
+}
+
+fn compile_regex(rule_id: &str, pattern: &str) -> Result<Regex, DetectionRuleError> {
+    check_len(rule_id, pattern)?;
+    let regex = RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|err| DetectionRuleError::InvalidPattern {
+            rule_id: rule_id.to_string(),
+            message: err.to_string(),
+        })?;
+    // A pattern that matches the empty string would flag every position.
+    if regex.is_match("") {
+        return Err(DetectionRuleError::InvalidPattern {
+            rule_id: rule_id.to_string(),
+            message: "pattern matches the empty string".to_string(),
+        });
+    }
+    Ok(regex)
+}
+
+#[derive(Debug)]
+pub(crate) enum AllowMatcher {
+    Literal(String),
+    Pattern(Regex),
+}
+
+#[derive(Debug)]
+pub(crate) struct CompiledAllow {
+    pub(crate) matcher: AllowMatcher,
+    pub(crate) detectors: Vec<String>,
+}
+
+impl CompiledAllow {
+    pub(crate) fn allows(&self, detector_id: &str, matched: &str) -> bool {
+        if !self.detectors.is_empty() && !self.detectors.iter().any(|d| d == detector_id) {
+            return false;
+        }
+        match &self.matcher {
+            AllowMatcher::Literal(literal) => literal == matched,
+            AllowMatcher::Pattern(regex) => regex.is_match(matched),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CompiledDeny {
+    pub(crate) detector_id: String,
+    pub(crate) regex: Regex,
+    pub(crate) severity: DetectionSeverity,
+    pub(crate) surfaces: Vec<DetectionSurface>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CompiledCustom {
+    pub(crate) detector_id: String,
+    pub(crate) regex: Regex,
+    pub(crate) category: DetectionCategory,
+    pub(crate) severity: DetectionSeverity,
+    pub(crate) validator: Option<Validator>,
+    pub(crate) min_entropy: Option<f64>,
+    pub(crate) surfaces: Vec<DetectionSurface>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CompiledRules {
+    pub(crate) allow: Vec<CompiledAllow>,
+    pub(crate) deny: Vec<CompiledDeny>,
+    pub(crate) custom: Vec<CompiledCustom>,
+    pub(crate) disabled: HashSet<String>,
+    pub(crate) entropy_threshold: f64,
+}
@@ -25,10 +25,13 @@
 //! re-parse failure the redacted string is stored under a `_redacted_text`
 //! envelope so a secret can NEVER leak through a parse fallback.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::redaction::DetectionSurface;
 use crate::session_transcript::SessionTranscriptEntry;
 use crate::terminal::redaction::SecretRedactor;
 
@@ -103,6 +106,10 @@ pub struct RenderedExport {
     /// Total emitted text fields that matched a secret pattern and were masked.
     /// The honest "N secrets redacted" telemetry — never the secret itself.
     pub redacted_field_count: u64,
+    /// Findings per detector id across every emitted text field, from the
+    /// redactor's detection engine (the session workspace's rules when the
+    /// caller built it with `EngineRedactor::for_workspace`). Counts only.
+    pub detections: BTreeMap<String, u64>,
 }
 
 /// The export JSON schema version. Bumped if the wrapper shape changes.
@@ -133,11 +140,15 @@ pub fn render(
 
     // Redact a clone of every entry up front so both renders share the SAME
     // redacted view and the redaction count is computed once.
-    let mut redacted_field_count: u64 = 0;
+    let mut tally = RedactionTally::default();
     let redacted: Vec<SessionTranscriptEntry> = entries
         .iter()
-        .map(|e| redact_entry(e, redactor, &mut redacted_field_count))
+        .map(|e| redact_entry(e, redactor, &mut tally))
         .collect();
+    let RedactionTally {
+        fields: redacted_field_count,
+        detections,
+    } = tally;
 
     let markdown = if fmt.wants_markdown() {
         Some(render_markdown(
@@ -165,6 +176,7 @@ pub fn render(
         markdown,
         json,
         redacted_field_count,
+        detections,
     }
 }
 
@@ -172,13 +184,31 @@ pub fn render(
 // Redaction pass (every emitted text field routed through the redactor)
 // ---------------------------------------------------------------------------
 
+/// Redaction telemetry accumulated across one export: masked field count plus
+/// per-detector finding counts.
+#[derive(Default)]
+struct RedactionTally {
+    fields: u64,
+    detections: BTreeMap<String, u64>,
+}
+
+impl RedactionTally {
+    fn record(&mut self, redactor: &dyn SecretRedactor, text: &str, matched: bool) {
+        if matched {
+            self.fields += 1;
+        }
+        let report = redactor.detect(text, DetectionSurface::TranscriptExport);
+        for (detector_id, n) in report.count_by_detector() {
+            *self.detections.entry(detector_id).or_insert(0) += u64::from(n);
+        }
+    }
+}
+
 /// Redact a single text field through the redactor, incrementing the counter
 /// when the field matched a secret pattern. Returns the masked string.
-fn redact_field(text: &str, redactor: &dyn SecretRedactor, count: &mut u64) -> String {
+fn redact_field(text: &str, redactor: &dyn SecretRedactor, tally: &mut RedactionTally) -> String {
     let result = redactor.redact_command(text);
-    if result.matched {
-        *count += 1;
-    }
+    tally.record(redactor, text, result.matched);
     result.redacted
 }
 
@@ -186,27 +216,31 @@ fn redact_field(text: &str, redactor: &dyn SecretRedactor, count: &mut u64) -> S
 fn redact_opt(
     text: &Option<String>,
     redactor: &dyn SecretRedactor,
-    count: &mut u64,
+    tally: &mut RedactionTally,
 ) -> Option<String> {
-    text.as_ref().map(|t| redact_field(t, redactor, count))
+    text.as_ref().map(|t| redact_field(t, redactor, tally))
 }
 
 /// Redact a JSON payload by compact-stringifying it (IDENTICAL to
 /// `searchable_text`'s payload handling), redacting that string, then re-parsing.
 /// On a re-parse failure the redacted string is stored under a `_redacted_text`
 /// envelope so a secret can NEVER leak through a raw-value fallback.
-fn redact_payload(payload: &Value, redactor: &dyn SecretRedactor, count: &mut u64) -> Value {
+fn redact_payload(
+    payload: &Value,
+    redactor: &dyn SecretRedactor,
+    tally: &mut RedactionTally,
+) -> Value {
     // `null`/empty payloads carry nothing to redact.
     if payload.is_null() {
         return payload.clone();
     }
     let compact = serde_json::to_string(payload).unwrap_or_default();
     let result = redactor.redact_command(&compact);
+    tally.record(redactor, &compact, result.matched);
     if !result.matched {
         // No secret matched: keep the original structured value untouched.
         return payload.clone();
     }
-    *count += 1;
     // A secret WAS masked. Try to re-parse the masked compact JSON back to a
     // structured Value; on failure, NEVER fall back to the raw payload — wrap the
     // redacted string so the secret cannot leak.
@@ -221,7 +255,7 @@ fn redact_payload(payload: &Value, redactor: &dyn SecretRedactor, count: &mut u6
 fn redact_entry(
     entry: &SessionTranscriptEntry,
     redactor: &dyn SecretRedactor,
-    count: &mut u64,
+    tally: &mut RedactionTally,
 ) -> SessionTranscriptEntry {
     match entry {
         SessionTranscriptEntry::ChatTurn {
@@ -234,9 +268,9 @@ fn redact_entry(
         } => SessionTranscriptEntry::ChatTurn {
             ts: *ts,
             seq: *seq,
-            role: redact_field(role, redactor, count),
-            model_role: redact_opt(model_role, redactor, count),
-            content: redact_field(content, redactor, count),
+            role: redact_field(role, redactor, tally),
+            model_role: redact_opt(model_role, redactor, tally),
+            content: redact_field(content, redactor, tally),
             message_id: message_id.clone(),
         },
         SessionTranscriptEntry::FrEvent {
@@ -255,7 +289,7 @@ fn redact_entry(
             fr_event: fr_event.clone(),
             actor: actor.clone(),
             model_id: model_id.clone(),
-            payload: redact_payload(payload, redactor, count),
+            payload: redact_payload(payload, redactor, tally),
             event_id: event_id.clone(),
         },
         SessionTranscriptEntry::AgentActivity {
@@ -270,11 +304,9 @@ fn redact_entry(
             ts: *ts,
             seq: *seq,
             activity_kind: activity_kind.clone(),
-            name: redact_opt(name, redactor, count),
-            detail: detail
-                .as_ref()
-                .map(|d| redact_payload(d, redactor, count)),
-            text: redact_opt(text, redactor, count),
+            name: redact_opt(name, redactor, tally),
+            detail: detail.as_ref().map(|d| redact_payload(d, redactor, tally)),
+            text: redact_opt(text, redactor, tally),
             event_id: event_id.clone(),
         },
         SessionTranscriptEntry::TerminalChunk {
@@ -289,8 +321,8 @@ fn redact_entry(
             seq: *seq,
             terminal_session_id: terminal_session_id.clone(),
             fr_event: fr_event.clone(),
-            command: redact_opt(command, redactor, count),
-            text: redact_opt(text, redactor, count),
+            command: redact_opt(command, redactor, tally),
+            text: redact_opt(text, redactor, tally),
         },
         SessionTranscriptEntry::Process {
             ts,
@@ -305,7 +337,7 @@ fn redact_entry(
             process_uuid: process_uuid.clone(),
             phase: phase.clone(),
             model_id: model_id.clone(),
-            payload: redact_payload(payload, redactor, count),
+            payload: redact_payload(payload, redactor, tally),
         },
     }
 }
@@ -865,6 +897,22 @@ mod tests {
         assert_eq!(spy.call_count(), 7, "all present text fields routed through redactor");
     }
 
+    #[test]
+    fn render_tallies_engine_detections_by_detector() {
+        let entries = vec![SessionTranscriptEntry::ChatTurn {
+            ts: at(1),
+            seq: 0,
+            role: "user".to_string(),
+            model_role: None,
+            content: "charge 4242 4242 4242 4242 and 5555-5555-5555-4444".to_string(),
+            message_id: "m1".to_string(),
+        }];
+        let r = render(&entries, &header(), ExportFormat::Json, &redactor());
+        assert_eq!(r.detections.get("payment_card"), Some(&2));
+        assert_eq!(r.redacted_field_count, 1);
+        assert!(!r.json.expect("js").contains("4242 4242"));
+    }
+
     #[test]
     fn payload_redaction_that_breaks_json_uses_envelope_not_raw() {
         // A secret whose masked form would BREAK json re-parse (an unquoted
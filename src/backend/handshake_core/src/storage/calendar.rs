@@ -444,3 +444,117 @@ pub struct CalendarEventWindowQuery {
     pub window_end_utc: DateTime<Utc>,
     pub source_ids: Vec<String>,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarSyncConflictReason {
+    /// The remote resource and the local event both changed since the last sync.
+    BothModified,
+    /// The remote resource was deleted while the local event had unpushed edits.
+    RemoteDeletedLocalModified,
+    /// A push was refused because the remote ETag no longer matched.
+    PushPreconditionFailed,
+}
+
+impl CalendarSyncConflictReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarSyncConflictReason::BothModified => "both_modified",
+            CalendarSyncConflictReason::RemoteDeletedLocalModified => {
+                "remote_deleted_local_modified"
+            }
+            CalendarSyncConflictReason::PushPreconditionFailed => "push_precondition_failed",
+        }
+    }
+}
+
+impl FromStr for CalendarSyncConflictReason {
+    type Err = crate::storage::StorageError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "both_modified" => Ok(CalendarSyncConflictReason::BothModified),
+            "remote_deleted_local_modified" => {
+                Ok(CalendarSyncConflictReason::RemoteDeletedLocalModified)
+            }
+            "push_precondition_failed" => Ok(CalendarSyncConflictReason::PushPreconditionFailed),
+            _ => Err(crate::storage::StorageError::Validation(
+                "invalid calendar sync conflict reason",
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarSyncConflictStatus {
+    Open,
+    ResolvedKeepLocal,
+    ResolvedKeepRemote,
+}
+
+impl CalendarSyncConflictStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarSyncConflictStatus::Open => "open",
+            CalendarSyncConflictStatus::ResolvedKeepLocal => "resolved_keep_local",
+            CalendarSyncConflictStatus::ResolvedKeepRemote => "resolved_keep_remote",
+        }
+    }
+}
+
+impl FromStr for CalendarSyncConflictStatus {
+    type Err = crate::storage::StorageError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "open" => Ok(CalendarSyncConflictStatus::Open),
+            "resolved_keep_local" => Ok(CalendarSyncConflictStatus::ResolvedKeepLocal),
+            "resolved_keep_remote" => Ok(CalendarSyncConflictStatus::ResolvedKeepRemote),
+            _ => Err(crate::storage::StorageError::Validation(
+                "invalid calendar sync conflict status",
+            )),
+        }
+    }
+}
+
+/// A remote/local divergence recorded by two-way sync instead of last-writer-wins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalendarSyncConflict {
+    pub id: String,
+    pub workspace_id: String,
+    pub source_id: String,
+    pub event_id: Option<String>,
+    pub external_id: String,
+    pub remote_href: Option<String>,
+    pub reason: CalendarSyncConflictReason,
+    pub local_etag: Option<String>,
+    pub remote_etag: Option<String>,
+    /// The local event as it stood when the conflict was detected.
+    pub local_snapshot: Option<Value>,
+    /// The remote iCalendar resource; `None` when the remote side was deleted.
+    pub remote_ics: Option<String>,
+    pub status: CalendarSyncConflictStatus,
+    pub detected_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub last_job_id: Option<String>,
+    pub last_workflow_id: Option<String>,
+    pub last_actor_id: Option<String>,
+    pub edit_event_id: String,
+    pub last_actor_kind: String,
+}
+
+/// Recording a conflict for an event that already has an open one refreshes that record.
+#[derive(Clone, Debug)]
+pub struct NewCalendarSyncConflict {
+    pub workspace_id: String,
+    pub source_id: String,
+    pub event_id: Option<String>,
+    pub external_id: String,
+    pub remote_href: Option<String>,
+    pub reason: CalendarSyncConflictReason,
+    pub local_etag: Option<String>,
+    pub remote_etag: Option<String>,
+    pub local_snapshot: Option<Value>,
+    pub remote_ics: Option<String>,
+}
@@ -837,3 +837,195 @@ pub async fn list_semantic_catalog_entries(
     .await?;
     rows.iter().map(catalog_from_row).collect()
 }
+
+// ===========================================================================
+// Retrieval evaluation runs (table 0339). A run is a measurement, not
+// authority: it records what the pipeline returned for a labelled query set
+// so a later run can be diffed against it (`knowledge_retrieval::evaluation`).
+// ===========================================================================
+
+use crate::knowledge_retrieval::evaluation::RetrievalEvalRun;
+
+fn eval_run_from_row(row: &sqlx::postgres::PgRow) -> StorageResult<RetrievalEvalRun> {
+    Ok(RetrievalEvalRun {
+        run_id: row.get("run_id"),
+        workspace_id: row.get("workspace_id"),
+        query_set_id: row.get("query_set_id"),
+        query_set_hash: row.get("query_set_hash"),
+        target_id: row.get("target_id"),
+        label: row.get("label"),
+        ks: serde_json::from_value(row.get("ks"))
+            .map_err(|_| StorageError::Validation("invalid eval run ks json"))?,
+        results: serde_json::from_value(row.get("results"))
+            .map_err(|_| StorageError::Validation("invalid eval run results json"))?,
+        summary: serde_json::from_value(row.get("summary"))
+            .map_err(|_| StorageError::Validation("invalid eval run summary json"))?,
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Persist a completed evaluation run. Runs are immutable; re-recording the
+/// same `run_id` is rejected by the primary key.
+pub async fn record_retrieval_eval_run(pool: &PgPool, run: &RetrievalEvalRun) -> StorageResult<()> {
+    let ks = serde_json::to_value(&run.ks)
+        .map_err(|_| StorageError::Validation("eval run ks not serializable"))?;
+    let results = serde_json::to_value(&run.results)
+        .map_err(|_| StorageError::Validation("eval run results not serializable"))?;
+    let summary = serde_json::to_value(&run.summary)
+        .map_err(|_| StorageError::Validation("eval run summary not serializable"))?;
+    sqlx::query(
+        r#"
+        INSERT INTO knowledge_retrieval_eval_runs
+            (run_id, workspace_id, query_set_id, query_set_hash, target_id, label,
+             ks, results, summary, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(&run.run_id)
+    .bind(&run.workspace_id)
+    .bind(&run.query_set_id)
+    .bind(&run.query_set_hash)
+    .bind(&run.target_id)
+    .bind(&run.label)
+    .bind(&ks)
+    .bind(&results)
+    .bind(&summary)
+    .bind(run.created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_retrieval_eval_run(
+    pool: &PgPool,
+    run_id: &str,
+) -> StorageResult<Option<RetrievalEvalRun>> {
+    let row = sqlx::query(
+        r#"
+        SELECT run_id, workspace_id, query_set_id, query_set_hash, target_id, label,
+               ks, results, summary, created_at
+        FROM knowledge_retrieval_eval_runs
+        WHERE run_id = $1
+        "#,
+    )
+    .bind(run_id)
+    .fetch_optional(pool)
+    .await?;
+    row.as_ref().map(eval_run_from_row).transpose()
+}
+
+/// Runs of one query set, newest first, so the latest run can be diffed
+/// against the one before it.
+pub async fn list_retrieval_eval_runs(
+    pool: &PgPool,
+    workspace_id: &str,
+    query_set_id: &str,
+    limit: i64,
+) -> StorageResult<Vec<RetrievalEvalRun>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT run_id, workspace_id, query_set_id, query_set_hash, target_id, label,
+               ks, results, summary, created_at
+        FROM knowledge_retrieval_eval_runs
+        WHERE workspace_id = $1 AND query_set_id = $2
+        ORDER BY created_at DESC, run_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(query_set_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(eval_run_from_row).collect()
+}
+
+// ===========================================================================
+// Graph analytics projections (table 0340). One row per workspace graph,
+// replaced on every refresh; `staleness_hash` stamps the graph revision the
+// report was computed from (`knowledge_retrieval::graph_analytics`).
+// ===========================================================================
+
+use crate::knowledge_retrieval::graph_analytics::{AnalyticsGraphKind, GraphAnalyticsReport};
+
+/// A stored graph analytics projection.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphAnalyticsProjection {
+    pub projection_id: String,
+    pub workspace_id: String,
+    pub graph_kind: AnalyticsGraphKind,
+    pub staleness_hash: String,
+    pub report: GraphAnalyticsReport,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+fn graph_analytics_from_row(
+    row: &sqlx::postgres::PgRow,
+) -> StorageResult<GraphAnalyticsProjection> {
+    Ok(GraphAnalyticsProjection {
+        projection_id: row.get("projection_id"),
+        workspace_id: row.get("workspace_id"),
+        graph_kind: AnalyticsGraphKind::from_db(row.get::<String, _>("graph_kind").as_str())?,
+        staleness_hash: row.get("staleness_hash"),
+        report: serde_json::from_value(row.get("report"))
+            .map_err(|_| StorageError::Validation("invalid graph analytics report json"))?,
+        computed_at: row.get("computed_at"),
+    })
+}
+
+/// Replace the workspace's projection for `report.graph_kind`.
+pub async fn upsert_graph_analytics_projection(
+    pool: &PgPool,
+    workspace_id: &str,
+    staleness_hash: &str,
+    report: &GraphAnalyticsReport,
+) -> StorageResult<GraphAnalyticsProjection> {
+    let projection_id = format!("KGA-{}", Uuid::now_v7().simple());
+    let report_json = serde_json::to_value(report)
+        .map_err(|_| StorageError::Validation("graph analytics report not serializable"))?;
+    let row = sqlx::query(
+        r#"
+        INSERT INTO knowledge_graph_analytics
+            (projection_id, workspace_id, graph_kind, staleness_hash, node_count,
+             edge_count, report)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (workspace_id, graph_kind) DO UPDATE SET
+            staleness_hash = EXCLUDED.staleness_hash,
+            node_count = EXCLUDED.node_count,
+            edge_count = EXCLUDED.edge_count,
+            report = EXCLUDED.report,
+            computed_at = NOW()
+        RETURNING projection_id, workspace_id, graph_kind, staleness_hash, report,
+                  computed_at
+        "#,
+    )
+    .bind(&projection_id)
+    .bind(workspace_id)
+    .bind(report.graph_kind.as_str())
+    .bind(staleness_hash)
+    .bind(report.node_count as i64)
+    .bind(report.edge_count as i64)
+    .bind(&report_json)
+    .fetch_one(pool)
+    .await?;
+    graph_analytics_from_row(&row)
+}
+
+pub async fn get_graph_analytics_projection(
+    pool: &PgPool,
+    workspace_id: &str,
+    graph_kind: AnalyticsGraphKind,
+) -> StorageResult<Option<GraphAnalyticsProjection>> {
+    let row = sqlx::query(
+        r#"
+        SELECT projection_id, workspace_id, graph_kind, staleness_hash, report, computed_at
+        FROM knowledge_graph_analytics
+        WHERE workspace_id = $1 AND graph_kind = $2
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(graph_kind.as_str())
+    .fetch_optional(pool)
+    .await?;
+    row.as_ref().map(graph_analytics_from_row).transpose()
+}
@@ -680,6 +680,10 @@ pub struct LoomSearchV2Hit {
     /// ts_headline highlight of the matching text (with <mark> markers).
     #[serde(default)]
     pub highlight: String,
+    /// Cross-encoder relevance when the optional rerank stage scored this hit
+    /// (`loom_search::search_reranked`); `None` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f64>,
 }
 
 /// A faceted, ranked LoomSearchV2 result set.
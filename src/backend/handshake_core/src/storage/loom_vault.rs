@@ -0,0 +1,349 @@
+//! Obsidian / Logseq vault import storage (migration 0341).
+//!
+//! `loom_vault_entries` maps every imported vault file — `(workspace,
+//! vault_name, relative_path)` — to the LoomBlock (and, for notes, the
+//! RichDocument) it became, with the file hash it was imported at, its
+//! front-matter properties and aliases, and the ids of the edges the importer
+//! created for it. That mapping is what makes a re-import update instead of
+//! duplicate, and lets it retire only its own edges. `loom_vault_imports`
+//! keeps one report per import run (unresolved links included).
+//!
+//! Pattern follows `storage/loom_ai.rs`: free async functions over
+//! `&sqlx::PgPool`. There is NO in-memory/SQLite fallback.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::{StorageError, StorageResult};
+
+/// Mint a `LVI-<32 hex>` import id.
+pub fn new_vault_import_id() -> String {
+    format!("LVI-{}", Uuid::now_v7().simple())
+}
+
+/// What a vault file became.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoomVaultEntryKind {
+    Note,
+    Journal,
+    Attachment,
+}
+
+impl LoomVaultEntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Journal => "journal",
+            Self::Attachment => "attachment",
+        }
+    }
+
+    pub fn parse(value: &str) -> StorageResult<Self> {
+        match value {
+            "note" => Ok(Self::Note),
+            "journal" => Ok(Self::Journal),
+            "attachment" => Ok(Self::Attachment),
+            _ => Err(StorageError::Validation("invalid loom vault entry kind")),
+        }
+    }
+}
+
+/// One imported vault file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LoomVaultEntry {
+    pub workspace_id: String,
+    pub vault_name: String,
+    pub relative_path: String,
+    pub entry_kind: LoomVaultEntryKind,
+    pub block_id: String,
+    pub rich_document_id: Option<String>,
+    pub content_sha256: String,
+    pub properties: Value,
+    pub aliases: Vec<String>,
+    pub managed_edge_ids: Vec<String>,
+    pub last_import_id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for [`upsert_loom_vault_entry`].
+#[derive(Clone, Debug)]
+pub struct UpsertLoomVaultEntry {
+    pub workspace_id: String,
+    pub vault_name: String,
+    pub relative_path: String,
+    pub entry_kind: LoomVaultEntryKind,
+    pub block_id: String,
+    pub rich_document_id: Option<String>,
+    pub content_sha256: String,
+    pub properties: Value,
+    pub aliases: Vec<String>,
+    pub managed_edge_ids: Vec<String>,
+    pub last_import_id: String,
+}
+
+const ENTRY_COLUMNS: &str = "workspace_id, vault_name, relative_path, entry_kind, block_id, \
+     rich_document_id, content_sha256, properties, aliases, managed_edge_ids, last_import_id, \
+     created_at, updated_at";
+
+fn entry_from_row(row: &sqlx::postgres::PgRow) -> StorageResult<LoomVaultEntry> {
+    Ok(LoomVaultEntry {
+        workspace_id: row.get("workspace_id"),
+        vault_name: row.get("vault_name"),
+        relative_path: row.get("relative_path"),
+        entry_kind: LoomVaultEntryKind::parse(row.get::<String, _>("entry_kind").as_str())?,
+        block_id: row.get("block_id"),
+        rich_document_id: row.get("rich_document_id"),
+        content_sha256: row.get("content_sha256"),
+        properties: row.get("properties"),
+        aliases: row.get("aliases"),
+        managed_edge_ids: row.get("managed_edge_ids"),
+        last_import_id: row.get("last_import_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/// Every entry of one vault, by relative path.
+pub async fn list_loom_vault_entries(
+    pool: &PgPool,
+    workspace_id: &str,
+    vault_name: &str,
+) -> StorageResult<Vec<LoomVaultEntry>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {ENTRY_COLUMNS} FROM loom_vault_entries \
+         WHERE workspace_id = $1 AND vault_name = $2 ORDER BY relative_path"
+    ))
+    .bind(workspace_id)
+    .bind(vault_name)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(entry_from_row).collect()
+}
+
+/// The vault entries (any vault) a block was imported from, newest first.
+pub async fn list_loom_vault_entries_for_block(
+    pool: &PgPool,
+    workspace_id: &str,
+    block_id: &str,
+) -> StorageResult<Vec<LoomVaultEntry>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {ENTRY_COLUMNS} FROM loom_vault_entries \
+         WHERE workspace_id = $1 AND block_id = $2 ORDER BY updated_at DESC, relative_path"
+    ))
+    .bind(workspace_id)
+    .bind(block_id)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(entry_from_row).collect()
+}
+
+/// Insert or update the entry at `(workspace, vault, relative_path)`. A note
+/// or journal block lives at one path per vault: when it moved, its row at
+/// the old path is dropped in the same transaction. Attachment blocks are
+/// content-addressed and may sit at several paths.
+pub async fn upsert_loom_vault_entry(
+    pool: &PgPool,
+    entry: &UpsertLoomVaultEntry,
+) -> StorageResult<LoomVaultEntry> {
+    if entry.vault_name.trim().is_empty() || entry.relative_path.trim().is_empty() {
+        return Err(StorageError::Validation(
+            "loom vault entry requires vault_name and relative_path",
+        ));
+    }
+    if !entry.properties.is_object() {
+        return Err(StorageError::Validation(
+            "loom vault entry properties must be an object",
+        ));
+    }
+    let mut tx = pool.begin().await?;
+    if entry.entry_kind != LoomVaultEntryKind::Attachment {
+        sqlx::query(
+            r#"
+            DELETE FROM loom_vault_entries
+            WHERE workspace_id = $1 AND vault_name = $2 AND block_id = $3
+              AND relative_path <> $4 AND entry_kind <> 'attachment'
+            "#,
+        )
+        .bind(&entry.workspace_id)
+        .bind(&entry.vault_name)
+        .bind(&entry.block_id)
+        .bind(&entry.relative_path)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let row = sqlx::query(&format!(
+        r#"
+        INSERT INTO loom_vault_entries
+            (workspace_id, vault_name, relative_path, entry_kind, block_id,
+             rich_document_id, content_sha256, properties, aliases, managed_edge_ids,
+             last_import_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (workspace_id, vault_name, relative_path) DO UPDATE SET
+            entry_kind = EXCLUDED.entry_kind,
+            block_id = EXCLUDED.block_id,
+            rich_document_id = EXCLUDED.rich_document_id,
+            content_sha256 = EXCLUDED.content_sha256,
+            properties = EXCLUDED.properties,
+            aliases = EXCLUDED.aliases,
+            managed_edge_ids = EXCLUDED.managed_edge_ids,
+            last_import_id = EXCLUDED.last_import_id,
+            updated_at = NOW()
+        RETURNING {ENTRY_COLUMNS}
+        "#
+    ))
+    .bind(&entry.workspace_id)
+    .bind(&entry.vault_name)
+    .bind(&entry.relative_path)
+    .bind(entry.entry_kind.as_str())
+    .bind(&entry.block_id)
+    .bind(&entry.rich_document_id)
+    .bind(&entry.content_sha256)
+    .bind(&entry.properties)
+    .bind(&entry.aliases)
+    .bind(&entry.managed_edge_ids)
+    .bind(&entry.last_import_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    entry_from_row(&row)
+}
+
+/// Replace the importer-owned edge ids of one entry.
+pub async fn set_loom_vault_entry_edges(
+    pool: &PgPool,
+    workspace_id: &str,
+    vault_name: &str,
+    relative_path: &str,
+    managed_edge_ids: &[String],
+) -> StorageResult<()> {
+    let result = sqlx::query(
+        r#"
+        UPDATE loom_vault_entries
+        SET managed_edge_ids = $4, updated_at = NOW()
+        WHERE workspace_id = $1 AND vault_name = $2 AND relative_path = $3
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(vault_name)
+    .bind(relative_path)
+    .bind(managed_edge_ids)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(StorageError::NotFound("loom vault entry"));
+    }
+    Ok(())
+}
+
+/// A stored import run.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LoomVaultImportRecord {
+    pub import_id: String,
+    pub workspace_id: String,
+    pub vault_name: String,
+    pub vault_kind: String,
+    pub unresolved_count: i64,
+    /// The full `loom_vault::import::LoomVaultImportReport`.
+    pub report: Value,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+fn import_from_row(row: &sqlx::postgres::PgRow) -> LoomVaultImportRecord {
+    LoomVaultImportRecord {
+        import_id: row.get("import_id"),
+        workspace_id: row.get("workspace_id"),
+        vault_name: row.get("vault_name"),
+        vault_kind: row.get("vault_kind"),
+        unresolved_count: row.get("unresolved_count"),
+        report: row.get("report"),
+        started_at: row.get("started_at"),
+        completed_at: row.get("completed_at"),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_loom_vault_import(
+    pool: &PgPool,
+    import_id: &str,
+    workspace_id: &str,
+    vault_name: &str,
+    vault_kind: &str,
+    unresolved_count: i64,
+    report: &Value,
+    started_at: DateTime<Utc>,
+) -> StorageResult<LoomVaultImportRecord> {
+    if !report.is_object() {
+        return Err(StorageError::Validation(
+            "loom vault import report must be an object",
+        ));
+    }
+    let row = sqlx::query(
+        r#"
+        INSERT INTO loom_vault_imports
+            (import_id, workspace_id, vault_name, vault_kind, unresolved_count, report,
+             started_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING import_id, workspace_id, vault_name, vault_kind, unresolved_count, report,
+                  started_at, completed_at
+        "#,
+    )
+    .bind(import_id)
+    .bind(workspace_id)
+    .bind(vault_name)
+    .bind(vault_kind)
+    .bind(unresolved_count)
+    .bind(report)
+    .bind(started_at)
+    .fetch_one(pool)
+    .await?;
+    Ok(import_from_row(&row))
+}
+
+pub async fn get_loom_vault_import(
+    pool: &PgPool,
+    workspace_id: &str,
+    import_id: &str,
+) -> StorageResult<Option<LoomVaultImportRecord>> {
+    let row = sqlx::query(
+        r#"
+        SELECT import_id, workspace_id, vault_name, vault_kind, unresolved_count, report,
+               started_at, completed_at
+        FROM loom_vault_imports
+        WHERE workspace_id = $1 AND import_id = $2
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(import_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.as_ref().map(import_from_row))
+}
+
+/// Import runs of a workspace, newest first.
+pub async fn list_loom_vault_imports(
+    pool: &PgPool,
+    workspace_id: &str,
+    limit: i64,
+) -> StorageResult<Vec<LoomVaultImportRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT import_id, workspace_id, vault_name, vault_kind, unresolved_count, report,
+               started_at, completed_at
+        FROM loom_vault_imports
+        WHERE workspace_id = $1
+        ORDER BY completed_at DESC, import_id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(limit.clamp(1, 200))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(import_from_row).collect())
+}
@@ -0,0 +1,131 @@
+//! Media probe metadata storage (migration 0342).
+//!
+//! `media_asset_probes` holds what ffprobe reported about an original video
+//! asset — duration, frame size, codecs, frame rate — as recorded by the
+//! `loom_preview_generate` job alongside the poster tier. One row per asset;
+//! a re-probe replaces it. Derived state: the original blob is authority.
+//!
+//! Pattern follows `storage/loom_vault.rs`: free async functions over
+//! `&sqlx::PgPool`. There is NO in-memory/SQLite fallback.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+
+use super::{StorageError, StorageResult};
+
+/// One probed asset.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MediaAssetProbe {
+    pub asset_id: String,
+    pub workspace_id: String,
+    pub probe_tool: String,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub frame_rate: Option<f64>,
+    pub probed_at: DateTime<Utc>,
+}
+
+/// Input for [`upsert_media_asset_probe`].
+#[derive(Clone, Debug)]
+pub struct UpsertMediaAssetProbe {
+    pub asset_id: String,
+    pub workspace_id: String,
+    pub probe_tool: String,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub frame_rate: Option<f64>,
+}
+
+const PROBE_COLUMNS: &str = "asset_id, workspace_id, probe_tool, duration_ms, width, height, \
+     video_codec, audio_codec, frame_rate, probed_at";
+
+fn probe_from_row(row: &sqlx::postgres::PgRow) -> MediaAssetProbe {
+    MediaAssetProbe {
+        asset_id: row.get("asset_id"),
+        workspace_id: row.get("workspace_id"),
+        probe_tool: row.get("probe_tool"),
+        duration_ms: row.get("duration_ms"),
+        width: row.get("width"),
+        height: row.get("height"),
+        video_codec: row.get("video_codec"),
+        audio_codec: row.get("audio_codec"),
+        frame_rate: row.get("frame_rate"),
+        probed_at: row.get("probed_at"),
+    }
+}
+
+/// Insert or replace the probe row of an asset.
+pub async fn upsert_media_asset_probe(
+    pool: &PgPool,
+    probe: &UpsertMediaAssetProbe,
+) -> StorageResult<MediaAssetProbe> {
+    if probe.probe_tool.trim().is_empty() {
+        return Err(StorageError::Validation(
+            "media asset probe requires probe_tool",
+        ));
+    }
+    if probe.duration_ms.is_some_and(|ms| ms < 0) {
+        return Err(StorageError::Validation(
+            "media asset probe duration_ms must be non-negative",
+        ));
+    }
+    // Zero/negative sizes and rates are what a partial probe reports for
+    // "unknown"; store them as NULL rather than tripping the CHECKs.
+    let width = probe.width.filter(|w| *w > 0);
+    let height = probe.height.filter(|h| *h > 0);
+    let frame_rate = probe.frame_rate.filter(|r| r.is_finite() && *r > 0.0);
+    let row = sqlx::query(&format!(
+        r#"
+        INSERT INTO media_asset_probes
+            (asset_id, workspace_id, probe_tool, duration_ms, width, height,
+             video_codec, audio_codec, frame_rate, probed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+        ON CONFLICT (asset_id) DO UPDATE SET
+            probe_tool = EXCLUDED.probe_tool,
+            duration_ms = EXCLUDED.duration_ms,
+            width = EXCLUDED.width,
+            height = EXCLUDED.height,
+            video_codec = EXCLUDED.video_codec,
+            audio_codec = EXCLUDED.audio_codec,
+            frame_rate = EXCLUDED.frame_rate,
+            probed_at = EXCLUDED.probed_at
+        RETURNING {PROBE_COLUMNS}
+        "#
+    ))
+    .bind(&probe.asset_id)
+    .bind(&probe.workspace_id)
+    .bind(&probe.probe_tool)
+    .bind(probe.duration_ms)
+    .bind(width)
+    .bind(height)
+    .bind(&probe.video_codec)
+    .bind(&probe.audio_codec)
+    .bind(frame_rate)
+    .fetch_one(pool)
+    .await?;
+    Ok(probe_from_row(&row))
+}
+
+/// The probe row of an asset, if it was ever probed.
+pub async fn get_media_asset_probe(
+    pool: &PgPool,
+    workspace_id: &str,
+    asset_id: &str,
+) -> StorageResult<Option<MediaAssetProbe>> {
+    let row = sqlx::query(&format!(
+        "SELECT {PROBE_COLUMNS} FROM media_asset_probes \
+         WHERE workspace_id = $1 AND asset_id = $2"
+    ))
+    .bind(workspace_id)
+    .bind(asset_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.as_ref().map(probe_from_row))
+}
@@ -26,6 +26,8 @@ pub mod knowledge_memory;
 pub mod knowledge_retrieval;
 pub mod loom;
 pub mod loom_ai;
+pub mod loom_vault;
+pub mod media_probe;
 pub mod postgres;
 pub mod retention;
 
@@ -2863,6 +2865,23 @@ pub trait Database: Send + Sync {
         workspace_id: &str,
         source_id: &str,
     ) -> StorageResult<()>;
+    async fn record_calendar_sync_conflict(
+        &self,
+        ctx: &WriteContext,
+        conflict: NewCalendarSyncConflict,
+    ) -> StorageResult<CalendarSyncConflict>;
+    async fn list_calendar_sync_conflicts(
+        &self,
+        workspace_id: &str,
+        source_id: &str,
+    ) -> StorageResult<Vec<CalendarSyncConflict>>;
+    async fn resolve_calendar_sync_conflict(
+        &self,
+        ctx: &WriteContext,
+        workspace_id: &str,
+        conflict_id: &str,
+        resolution: CalendarSyncConflictStatus,
+    ) -> StorageResult<CalendarSyncConflict>;
 
     // Canvas operations
     async fn create_canvas(&self, ctx: &WriteContext, canvas: NewCanvas) -> StorageResult<Canvas>;
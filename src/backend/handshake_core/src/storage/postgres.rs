@@ -5,8 +5,9 @@ use super::{
     BronzeRecord, CalendarEvent, CalendarEventExportMode, CalendarEventStatus, CalendarEventUpsert,
     CalendarEventVisibility, CalendarEventWindowQuery, CalendarSource, CalendarSourceProviderType,
     CalendarSourceSyncState, CalendarSourceUpsert, CalendarSourceWritePolicy,
+    CalendarSyncConflict, CalendarSyncConflictReason, CalendarSyncConflictStatus,
     CalendarSyncStateStage, Canvas, CanvasEdge, CanvasGraph, CanvasNode, DebugBreakpoint,
-    DebugBreakpointInput, DefaultStorageGuard,
+    DebugBreakpointInput, DefaultStorageGuard, NewCalendarSyncConflict,
     Document, EmbeddingModelRecord, EmbeddingRegistry, EntityRef, JobKind, JobMetrics, JobState,
     JobStatusUpdate, LoomBlock, LoomBlockContentType, LoomBlockDerived, LoomBlockSearchResult,
     LoomBlockUpdate, LoomCanvasBoard, LoomCanvasBoardView, LoomCanvasPlacement,
@@ -2662,6 +2663,31 @@ fn validate_workspace_settings_state_shape(settings_state: &Value) -> StorageRes
             WORKSPACE_SETTINGS_SHAPE_VALIDATION_ERROR,
         ));
     }
+    // Redaction rules are governed settings: a rule set that does not compile
+    // (bad regex, unknown detector, duplicate id) is never persisted.
+    if crate::redaction::DetectionEngine::from_workspace_settings_state(settings_state).is_err() {
+        return Err(StorageError::Validation(
+            "workspace settings_state redaction rules are invalid",
+        ));
+    }
+    // Cloud pricing and spend budgets are validated the same way: a negative
+    // price or a soft limit above its hard limit is never persisted.
+    if crate::model_runtime::cloud::CloudSpendSettings::from_workspace_settings_state(
+        settings_state,
+    )
+    .is_err()
+    {
+        return Err(StorageError::Validation(
+            "workspace settings_state cloud_spend settings are invalid",
+        ));
+    }
+    // Prompt-injection settings: a classifier threshold outside (0, 1] or an
+    // unusable spotlight marker is never persisted.
+    if crate::ace::PromptInjectionSettings::from_workspace_settings_state(settings_state).is_err() {
+        return Err(StorageError::Validation(
+            "workspace settings_state prompt_injection settings are invalid",
+        ));
+    }
 
     Ok(())
 }
@@ -4282,6 +4308,35 @@ fn map_calendar_source(row: PgRow) -> StorageResult<CalendarSource> {
     })
 }
 
+fn map_calendar_sync_conflict(row: PgRow) -> StorageResult<CalendarSyncConflict> {
+    let local_snapshot = row
+        .get::<Option<String>, _>("local_snapshot_json")
+        .map(|raw| serde_json::from_str(&raw))
+        .transpose()?;
+
+    Ok(CalendarSyncConflict {
+        id: row.get("id"),
+        workspace_id: row.get("workspace_id"),
+        source_id: row.get("source_id"),
+        event_id: row.get("event_id"),
+        external_id: row.get("external_id"),
+        remote_href: row.get("remote_href"),
+        reason: CalendarSyncConflictReason::from_str(row.get::<String, _>("reason").as_str())?,
+        local_etag: row.get("local_etag"),
+        remote_etag: row.get("remote_etag"),
+        local_snapshot,
+        remote_ics: row.get("remote_ics"),
+        status: CalendarSyncConflictStatus::from_str(row.get::<String, _>("status").as_str())?,
+        detected_at: map_timestamp(&row, "detected_at"),
+        resolved_at: map_optional_timestamp(&row, "resolved_at"),
+        last_job_id: row.get("last_job_id"),
+        last_workflow_id: row.get("last_workflow_id"),
+        last_actor_id: row.get("last_actor_id"),
+        edit_event_id: row.get("edit_event_id"),
+        last_actor_kind: row.get("last_actor_kind"),
+    })
+}
+
 fn map_calendar_event(row: PgRow) -> StorageResult<CalendarEvent> {
     Ok(CalendarEvent {
         id: row.get("id"),
@@ -8148,6 +8203,7 @@ impl super::Database for PostgresDatabase {
                 vector_sim: row.get::<f64, _>("vector_sim"),
                 edge_degree: row.get::<i64, _>("edge_degree"),
                 highlight: row.get::<String, _>("highlight"),
+                rerank_score: None,
             });
         }
 
@@ -11542,6 +11598,220 @@ impl super::Database for PostgresDatabase {
         Ok(())
     }
 
+    async fn record_calendar_sync_conflict(
+        &self,
+        ctx: &WriteContext,
+        conflict: NewCalendarSyncConflict,
+    ) -> StorageResult<CalendarSyncConflict> {
+        if conflict.external_id.trim().is_empty() {
+            return Err(StorageError::Validation(
+                "calendar sync conflict external_id is required",
+            ));
+        }
+
+        let now = Utc::now();
+        let id = Uuid::now_v7().to_string();
+        let metadata = self.guard.validate_write(ctx, &conflict.source_id).await?;
+        let actor_kind = metadata.actor_kind.as_str();
+        let actor_id = metadata.actor_id.clone();
+        let job_id = metadata.job_id.map(|id| id.to_string());
+        let workflow_id = metadata.workflow_id.map(|id| id.to_string());
+        let edit_event_id = metadata.edit_event_id.to_string();
+        let local_snapshot_json = conflict.local_snapshot.as_ref().map(encode_json).transpose()?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO calendar_sync_conflicts (
+                id,
+                workspace_id,
+                source_id,
+                event_id,
+                external_id,
+                remote_href,
+                reason,
+                local_etag,
+                remote_etag,
+                local_snapshot_json,
+                remote_ics,
+                status,
+                detected_at,
+                last_actor_kind,
+                last_actor_id,
+                last_job_id,
+                last_workflow_id,
+                edit_event_id
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                $11, 'open', $12, $13, $14, $15, $16, $17
+            )
+            ON CONFLICT (source_id, external_id) WHERE status = 'open' DO UPDATE SET
+                event_id = excluded.event_id,
+                remote_href = excluded.remote_href,
+                reason = excluded.reason,
+                local_etag = excluded.local_etag,
+                remote_etag = excluded.remote_etag,
+                local_snapshot_json = excluded.local_snapshot_json,
+                remote_ics = excluded.remote_ics,
+                last_actor_kind = excluded.last_actor_kind,
+                last_actor_id = excluded.last_actor_id,
+                last_job_id = excluded.last_job_id,
+                last_workflow_id = excluded.last_workflow_id,
+                edit_event_id = excluded.edit_event_id
+            RETURNING
+                id,
+                workspace_id,
+                source_id,
+                event_id,
+                external_id,
+                remote_href,
+                reason,
+                local_etag,
+                remote_etag,
+                local_snapshot_json,
+                remote_ics,
+                status,
+                detected_at,
+                resolved_at,
+                last_job_id,
+                last_workflow_id,
+                last_actor_id,
+                edit_event_id,
+                last_actor_kind
+            "#,
+        )
+        .bind(id)
+        .bind(conflict.workspace_id)
+        .bind(conflict.source_id)
+        .bind(conflict.event_id)
+        .bind(conflict.external_id)
+        .bind(conflict.remote_href)
+        .bind(conflict.reason.as_str())
+        .bind(conflict.local_etag)
+        .bind(conflict.remote_etag)
+        .bind(local_snapshot_json)
+        .bind(conflict.remote_ics)
+        .bind(now)
+        .bind(actor_kind)
+        .bind(actor_id)
+        .bind(job_id)
+        .bind(workflow_id)
+        .bind(edit_event_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        map_calendar_sync_conflict(row)
+    }
+
+    async fn list_calendar_sync_conflicts(
+        &self,
+        workspace_id: &str,
+        source_id: &str,
+    ) -> StorageResult<Vec<CalendarSyncConflict>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id,
+                workspace_id,
+                source_id,
+                event_id,
+                external_id,
+                remote_href,
+                reason,
+                local_etag,
+                remote_etag,
+                local_snapshot_json,
+                remote_ics,
+                status,
+                detected_at,
+                resolved_at,
+                last_job_id,
+                last_workflow_id,
+                last_actor_id,
+                edit_event_id,
+                last_actor_kind
+            FROM calendar_sync_conflicts
+            WHERE workspace_id = $1 AND source_id = $2
+            ORDER BY detected_at ASC, id ASC
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(map_calendar_sync_conflict).collect()
+    }
+
+    async fn resolve_calendar_sync_conflict(
+        &self,
+        ctx: &WriteContext,
+        workspace_id: &str,
+        conflict_id: &str,
+        resolution: CalendarSyncConflictStatus,
+    ) -> StorageResult<CalendarSyncConflict> {
+        if resolution == CalendarSyncConflictStatus::Open {
+            return Err(StorageError::Validation(
+                "calendar sync conflict resolution must keep local or remote",
+            ));
+        }
+
+        let metadata = self.guard.validate_write(ctx, conflict_id).await?;
+        let actor_kind = metadata.actor_kind.as_str();
+        let actor_id = metadata.actor_id.clone();
+        let job_id = metadata.job_id.map(|id| id.to_string());
+        let workflow_id = metadata.workflow_id.map(|id| id.to_string());
+        let edit_event_id = metadata.edit_event_id.to_string();
+
+        let row = sqlx::query(
+            r#"
+            UPDATE calendar_sync_conflicts
+            SET status = $3,
+                resolved_at = $4,
+                last_actor_kind = $5,
+                last_actor_id = $6,
+                last_job_id = $7,
+                last_workflow_id = $8,
+                edit_event_id = $9
+            WHERE workspace_id = $1 AND id = $2 AND status = 'open'
+            RETURNING
+                id,
+                workspace_id,
+                source_id,
+                event_id,
+                external_id,
+                remote_href,
+                reason,
+                local_etag,
+                remote_etag,
+                local_snapshot_json,
+                remote_ics,
+                status,
+                detected_at,
+                resolved_at,
+                last_job_id,
+                last_workflow_id,
+                last_actor_id,
+                edit_event_id,
+                last_actor_kind
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(conflict_id)
+        .bind(resolution.as_str())
+        .bind(Utc::now())
+        .bind(actor_kind)
+        .bind(actor_id)
+        .bind(job_id)
+        .bind(workflow_id)
+        .bind(edit_event_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(StorageError::NotFound("open calendar_sync_conflict"))?;
+
+        map_calendar_sync_conflict(row)
+    }
+
     async fn create_canvas(&self, ctx: &WriteContext, canvas: NewCanvas) -> StorageResult<Canvas> {
         let now = Utc::now();
         let id = Uuid::now_v7().to_string();
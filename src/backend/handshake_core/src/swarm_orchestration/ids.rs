@@ -96,6 +96,10 @@ pub struct SpawnRequest {
     /// integrity gate. Optional because a cloud-backed or test session may not
     /// have a local artifact.
     pub model_artifact_sha256: Option<String>,
+    /// Name of a model in the managed [`crate::model_runtime::store::ModelStore`].
+    /// When set and no explicit artifact path is given, the production factory
+    /// resolves the path and sha256 through the store it was built with.
+    pub stored_model: Option<String>,
     /// Board/lineage grouping (rank-2 structural unlock): the swarm this session
     /// belongs to. Becomes a swimlane on the operator board, a per-swarm budget
     /// scope, a Flight-Recorder drill-down join key, and a calendar schedule
@@ -173,6 +177,7 @@ impl SpawnRequest {
             parent_session_id: parent_session_id.into(),
             model_artifact_path: None,
             model_artifact_sha256: None,
+            stored_model: None,
             swarm_id: None,
             worktree_id: None,
             working_dir: None,
@@ -196,6 +201,13 @@ impl SpawnRequest {
         self
     }
 
+    /// Load a model from the managed model store by name; the factory fills
+    /// in the artifact path and sha256 from its manifest.
+    pub fn with_stored_model(mut self, name: impl Into<String>) -> Self {
+        self.stored_model = Some(name.into());
+        self
+    }
+
     /// The local model artifact path, if set.
     pub fn model_artifact_path(&self) -> Option<&str> {
         self.model_artifact_path.as_deref()
@@ -42,6 +42,7 @@ use async_trait::async_trait;
 
 use crate::model_runtime::candle::{load_local_candle_model, LoadedCandleModel};
 use crate::model_runtime::registry::RuntimeBinding;
+use crate::model_runtime::store::ModelStore;
 use crate::model_runtime::{CancellationToken, ModelId, ModelRuntime, ProviderKind};
 use crate::process_ledger::{
     record_spawn, LedgerBatcher, ProcessEngineKind, ProcessOwnershipRecordId, SpawnMeta,
@@ -249,20 +250,29 @@ impl CloudLaneFactoryConfig {
         anthropic_lane: Option<String>,
         openai_lane: Option<String>,
     ) -> Self {
-        let anthropic = anthropic_lane.map(|lane| {
-            Arc::new(VaultCloudRuntimeBuilder::new(
-                CloudProviderFlavor::Anthropic,
-                vault.clone(),
-                lane,
-            )) as Arc<dyn CloudRuntimeBuilder>
-        });
-        let openai = openai_lane.map(|lane| {
-            Arc::new(VaultCloudRuntimeBuilder::new(
-                CloudProviderFlavor::OpenAi,
-                vault.clone(),
-                lane,
-            )) as Arc<dyn CloudRuntimeBuilder>
-        });
+        Self::from_vault_observed(vault, anthropic_lane, openai_lane, None)
+    }
+
+    /// Like [`Self::from_vault`] but threads a
+    /// [`crate::model_runtime::cloud::CloudLaneObservability`] into both BYOK
+    /// lanes, so the built runtimes emit `FR-EVT-LLM-INFER-*`, honour its
+    /// consent gate, and are admitted against (and charged to) its spend
+    /// ledger.
+    pub fn from_vault_observed(
+        vault: Arc<dyn crate::model_runtime::cloud::SecretsVault>,
+        anthropic_lane: Option<String>,
+        openai_lane: Option<String>,
+        observability: Option<Arc<crate::model_runtime::cloud::CloudLaneObservability>>,
+    ) -> Self {
+        let builder = |flavor, lane| {
+            let mut builder = VaultCloudRuntimeBuilder::new(flavor, vault.clone(), lane);
+            if let Some(obs) = observability.clone() {
+                builder = builder.with_observability(obs);
+            }
+            Arc::new(builder) as Arc<dyn CloudRuntimeBuilder>
+        };
+        let anthropic = anthropic_lane.map(|lane| builder(CloudProviderFlavor::Anthropic, lane));
+        let openai = openai_lane.map(|lane| builder(CloudProviderFlavor::OpenAi, lane));
         // The vault path is BYOK-only; the official-CLI lane is configured via
         // [`CloudLaneFactoryConfig::with_official_cli`].
         Self {
@@ -505,6 +515,9 @@ pub struct VaultCloudRuntimeBuilder {
     vault: Arc<dyn crate::model_runtime::cloud::SecretsVault>,
     lane: String,
     api_base: String,
+    /// Optional cloud-lane observability (FR events, consent, spend) threaded
+    /// into every built BYOK runtime.
+    lane_obs: Option<Arc<crate::model_runtime::cloud::CloudLaneObservability>>,
 }
 
 impl VaultCloudRuntimeBuilder {
@@ -518,9 +531,19 @@ impl VaultCloudRuntimeBuilder {
             vault,
             lane: lane.into(),
             api_base: flavor.default_api_base().to_string(),
+            lane_obs: None,
         }
     }
 
+    /// Attach cloud-lane observability to every runtime this builder builds.
+    pub fn with_observability(
+        mut self,
+        lane_obs: Arc<crate::model_runtime::cloud::CloudLaneObservability>,
+    ) -> Self {
+        self.lane_obs = Some(lane_obs);
+        self
+    }
+
     /// Override the provider API base (e.g. an Azure/OpenAI-compatible gateway
     /// or a test server). Defaults to the official provider host.
     pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
@@ -611,6 +634,9 @@ impl CloudRuntimeBuilder for VaultCloudRuntimeBuilder {
                     key_provider,
                     audit_sink,
                 );
+                if let Some(obs) = self.lane_obs.clone() {
+                    rt = rt.with_lane_observability(obs);
+                }
                 let id = rt
                     .load(self.cloud_load_spec(model_name))
                     .await
@@ -625,6 +651,9 @@ impl CloudRuntimeBuilder for VaultCloudRuntimeBuilder {
                     key_provider,
                     audit_sink,
                 );
+                if let Some(obs) = self.lane_obs.clone() {
+                    rt = rt.with_lane_observability(obs);
+                }
                 let id = rt
                     .load(self.cloud_load_spec(model_name))
                     .await
@@ -745,6 +774,9 @@ pub struct ProductionModelSessionFactory {
     /// silently downgraded to an in-process spawn). Threaded by the app at
     /// startup (Integrate phase); the handshake_core change compiles with `None`.
     sandbox_registry: Option<Arc<SandboxAdapterRegistry>>,
+    /// Managed model store a [`SpawnRequest::stored_model`] name resolves
+    /// through. `None` => such a request fails with a typed error.
+    model_store: Option<Arc<ModelStore>>,
     /// Synthetic pid base for in-process sessions (candle / cloud run in-process
     /// — there is no separate OS process). A monotonic offset keeps ledger pids
     /// distinct per instance so START/STOP rows correlate one-to-one.
@@ -766,6 +798,7 @@ impl ProductionModelSessionFactory {
             ledger,
             cloud,
             sandbox_registry,
+            model_store: None,
             pid_base: 50_000,
         }
     }
@@ -783,6 +816,44 @@ impl ProductionModelSessionFactory {
         self
     }
 
+    /// Thread the managed model store so requests can name stored models.
+    pub fn with_model_store(mut self, store: Arc<ModelStore>) -> Self {
+        self.model_store = Some(store);
+        self
+    }
+
+    /// Fill a stored-model request's artifact path + sha256 from the store
+    /// manifest. `None` when the request does not name a stored model or
+    /// already carries an explicit artifact path.
+    fn resolve_stored_model(&self, request: &SpawnRequest) -> SwarmResult<Option<SpawnRequest>> {
+        let Some(name) = request.stored_model.as_deref() else {
+            return Ok(None);
+        };
+        if request.model_artifact_path.is_some() {
+            return Ok(None);
+        }
+        let store = self.model_store.as_ref().ok_or_else(|| {
+            SwarmError::FactoryFailed(format!(
+                "spawn names stored model {name} but no model store is wired into the \
+                 production factory"
+            ))
+        })?;
+        let resolved = store
+            .resolve(name)
+            .map_err(|e| SwarmError::FactoryFailed(e.to_string()))?;
+        if resolved.manifest.runtime_binding != request.runtime_binding {
+            return Err(SwarmError::FactoryFailed(format!(
+                "stored model {name} is bound to {}, spawn asked for {}",
+                resolved.manifest.runtime_binding.adapter_id(),
+                request.runtime_binding.adapter_id()
+            )));
+        }
+        Ok(Some(request.clone().with_local_artifact(
+            resolved.artifact_path.display().to_string(),
+            resolved.manifest.sha256,
+        )))
+    }
+
     fn synthetic_pid(&self, request: &SpawnRequest) -> u32 {
         self.pid_base.wrapping_add(request.instance_id.instance)
     }
@@ -1386,6 +1457,11 @@ impl ProductionModelSessionFactory {
 #[async_trait]
 impl ModelSessionFactory for ProductionModelSessionFactory {
     async fn create(&self, request: &SpawnRequest) -> SwarmResult<LiveSession> {
+        let resolved = match request.provider {
+            None | Some(ProviderKind::Local) => self.resolve_stored_model(request)?,
+            _ => None,
+        };
+        let request = resolved.as_ref().unwrap_or(request);
         match request.provider {
             None | Some(ProviderKind::Local) if request.wants_warm_vm_execution() => {
                 self.create_warm_vm_local(request).await
@@ -1813,6 +1889,52 @@ mod tests {
         assert!(matches!(err, SwarmError::FactoryFailed(_)), "got {err}");
     }
 
+    #[tokio::test]
+    async fn stored_model_request_resolves_through_the_model_store() {
+        use crate::model_runtime::store::ModelImportRequest;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let gguf = dir.path().join("tiny.gguf");
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend_from_slice(&3_u32.to_le_bytes());
+        std::fs::write(&gguf, bytes).expect("write gguf");
+        let store = ModelStore::open(dir.path().join("store")).expect("open store");
+        let manifest = store
+            .import_local(
+                &gguf,
+                &ModelImportRequest {
+                    name: "tiny".to_string(),
+                    ..ModelImportRequest::default()
+                },
+            )
+            .expect("import");
+
+        let req = |binding| {
+            SpawnRequest::new(instance(0), binding, "swarm_prod_test", "parent-1")
+                .with_stored_model("tiny")
+        };
+        let (ledger, _drain) = ledger_pair();
+        let unwired = ProductionModelSessionFactory::local_only(ledger);
+        let err = create_err(&unwired, &req(RuntimeBinding::LlamaCpp)).await;
+        assert!(format!("{err}").contains("no model store"), "got {err}");
+
+        let (ledger, _drain) = ledger_pair();
+        let factory =
+            ProductionModelSessionFactory::local_only(ledger).with_model_store(Arc::new(store));
+        let resolved = factory
+            .resolve_stored_model(&req(RuntimeBinding::LlamaCpp))
+            .expect("resolve")
+            .expect("stored model");
+        assert!(resolved
+            .model_artifact_path()
+            .is_some_and(|p| p.ends_with("model.gguf")));
+        assert_eq!(resolved.model_artifact_sha256, Some(manifest.sha256));
+        // The manifest's binding wins over a mismatched request.
+        assert!(factory
+            .resolve_stored_model(&req(RuntimeBinding::Candle))
+            .is_err());
+    }
+
     #[tokio::test]
     async fn local_candle_nonexistent_file_fails_factory_and_leaves_no_orphan_start() {
         // A nonexistent artifact must fail the real load (sha/file gate) and the
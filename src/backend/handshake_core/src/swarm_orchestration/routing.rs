@@ -40,12 +40,29 @@
 //! Closed -> Open -> Half-Open breaker (Nygard / resilience4j / Polly) keyed per
 //! downstream, which is exactly the per-provider isolation the operator asked
 //! for.
+//!
+//! ## Spend budgets
+//!
+//! When a [`CloudSpendLedger`] is attached ([`RoutingPolicy::with_spend_ledger`])
+//! every admissible cloud provider is also checked against the hard spend
+//! budgets for the request's [`SpendAttribution`] before it is chosen. A
+//! provider whose projected cost would exceed a hard limit is skipped exactly
+//! like a suppressed one: the walk moves on to the next provider, then to
+//! local, and a `ForceCloud` task with every provider over budget surfaces
+//! [`SwarmRoutingError::BudgetExhausted`]. The BYOK adapters re-check at
+//! dispatch, so the routing check only keeps the swarm from picking a lane it
+//! cannot afford.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 use super::breaker::{AdmitDecision, BreakerConfig, FailureFingerprint, FailureFingerprintBreaker};
 use super::error::SwarmErrorClass;
+use crate::model_runtime::cloud::spend::{
+    CloudSpendError, CloudSpendLedger, SpendAttribution, SpendDispatch, SPEND_PROVIDER_ANTHROPIC,
+    SPEND_PROVIDER_OPENAI,
+};
 
 /// Coarse task class the operator (or an upstream scheduler) tags work with. The
 /// classifier maps this — together with the size signals — onto a tier. Kept
@@ -91,6 +108,14 @@ impl CloudProvider {
             CloudProvider::OpenAi => "openai",
         }
     }
+
+    /// Provider key in the spend [`crate::model_runtime::cloud::PricingTable`].
+    pub fn spend_key(self) -> &'static str {
+        match self {
+            CloudProvider::Anthropic => SPEND_PROVIDER_ANTHROPIC,
+            CloudProvider::OpenAi => SPEND_PROVIDER_OPENAI,
+        }
+    }
 }
 
 /// Outcome of a prior LOCAL attempt, fed back into [`RoutingPolicy::route`] so a
@@ -147,6 +172,10 @@ pub struct RoutingRequest {
     /// breaker accounting). Applied before the decision so a just-failed
     /// provider is excluded from this same route call.
     pub cloud_outcome: Option<CloudOutcome>,
+    /// Workspace / job / swarm the task's cloud spend is charged to. Only read
+    /// when the policy carries a spend ledger; `None` charges the day rollup
+    /// alone.
+    pub spend_attribution: Option<SpendAttribution>,
 }
 
 impl RoutingRequest {
@@ -160,6 +189,7 @@ impl RoutingRequest {
             cloud_model: None,
             local_outcome: None,
             cloud_outcome: None,
+            spend_attribution: None,
         }
     }
 
@@ -182,6 +212,11 @@ impl RoutingRequest {
         self.cloud_outcome = Some(outcome);
         self
     }
+
+    pub fn with_spend_attribution(mut self, attribution: SpendAttribution) -> Self {
+        self.spend_attribution = Some(attribution);
+        self
+    }
 }
 
 /// The lane a routing decision selects, naming the explicit model. The
@@ -218,6 +253,8 @@ pub enum SwarmRoutingError {
     NoCloudProvider,
     #[error("ROUTING_ALL_LANES_SUPPRESSED: every admissible lane (local + all configured cloud providers) is breaker-suppressed; cooldown_remaining_ms={cooldown_remaining_ms}")]
     AllLanesSuppressed { cooldown_remaining_ms: u128 },
+    #[error("ROUTING_BUDGET_EXHAUSTED: every admissible cloud provider is over a hard spend budget: {0}")]
+    BudgetExhausted(String),
 }
 
 /// Tunable thresholds for the deterministic classifier. Defaults are
@@ -258,6 +295,8 @@ impl Default for RoutingPolicyConfig {
 pub struct RoutingPolicy {
     config: RoutingPolicyConfig,
     breakers: HashMap<&'static str, FailureFingerprintBreaker>,
+    /// Shared cloud spend ledger; `None` disables budget checks.
+    spend: Option<Arc<CloudSpendLedger>>,
 }
 
 const LOCAL_KEY: &str = "local";
@@ -274,13 +313,43 @@ impl RoutingPolicy {
             CloudProvider::OpenAi.breaker_key(),
             FailureFingerprintBreaker::new(config.breaker),
         );
-        Self { config, breakers }
+        Self {
+            config,
+            breakers,
+            spend: None,
+        }
     }
 
     pub fn with_default() -> Self {
         Self::new(RoutingPolicyConfig::default())
     }
 
+    /// Enforce the ledger's hard spend budgets when choosing a cloud provider.
+    pub fn with_spend_ledger(mut self, ledger: Arc<CloudSpendLedger>) -> Self {
+        self.spend = Some(ledger);
+        self
+    }
+
+    /// Would dispatching this request to `provider` stay within the hard spend
+    /// budgets? Soft crossings are reported by the ledger but never refuse.
+    fn spend_admit(
+        &self,
+        req: &RoutingRequest,
+        provider: CloudProvider,
+        model: &str,
+    ) -> Result<(), CloudSpendError> {
+        let Some(ledger) = self.spend.as_ref() else {
+            return Ok(());
+        };
+        ledger.check(&SpendDispatch {
+            provider: provider.spend_key().to_string(),
+            model: model.to_string(),
+            attribution: req.spend_attribution.clone().unwrap_or_default(),
+            estimated_input_tokens: u64::from(req.estimated_input_tokens),
+            max_output_tokens: u64::from(req.max_output_tokens),
+        })
+    }
+
     /// The deterministic, side-effect-free tier classification for a request,
     /// IGNORING breakers and prior outcomes. Exposed so a caller can preview the
     /// natural tier; [`route`] layers escalation + breaker suppression on top.
@@ -438,9 +507,11 @@ impl RoutingPolicy {
     }
 
     /// Cloud-tier route: walk the configured provider preference, choosing the
-    /// first admissible provider. If every cloud provider is suppressed, fall
-    /// back to local (when admissible + the class is not ForceCloud). If nothing
-    /// is admissible, surface AllLanesSuppressed.
+    /// first admissible provider that is within its hard spend budgets. If
+    /// every cloud provider is suppressed or over budget, fall back to local
+    /// (when admissible + the class is not ForceCloud). If nothing is
+    /// admissible, surface AllLanesSuppressed (or BudgetExhausted when budgets,
+    /// not breakers, ruled the cloud out).
     fn route_cloud_then_fallback(
         &mut self,
         req: &RoutingRequest,
@@ -461,14 +532,22 @@ impl RoutingPolicy {
             .ok_or(SwarmRoutingError::NoCloudModel)?;
 
         let mut min_cooldown: Option<u128> = None;
+        let mut over_budget: Option<CloudSpendError> = None;
         for provider in self.config.cloud_preference.clone() {
             match self.lane_admissible(provider.breaker_key(), now) {
-                AdmitDecision::Admit => {
-                    return Ok(RoutingDecision::Cloud {
-                        provider,
-                        model: cloud_model,
-                    });
-                }
+                AdmitDecision::Admit => match self.spend_admit(req, provider, &cloud_model) {
+                    Ok(()) => {
+                        return Ok(RoutingDecision::Cloud {
+                            provider,
+                            model: cloud_model,
+                        });
+                    }
+                    Err(err) => {
+                        if over_budget.is_none() {
+                            over_budget = Some(err);
+                        }
+                    }
+                },
                 AdmitDecision::Suppress {
                     cooldown_remaining_ms,
                 } => {
@@ -481,8 +560,13 @@ impl RoutingPolicy {
             }
         }
 
-        // All cloud providers suppressed. ForceCloud has nowhere to go.
+        // All cloud providers suppressed or over budget. ForceCloud has nowhere
+        // to go; a budget refusal is the more actionable reason when no breaker
+        // is holding a provider back.
         if req.class == TaskClass::ForceCloud {
+            if let (Some(err), None) = (over_budget, min_cooldown) {
+                return Err(SwarmRoutingError::BudgetExhausted(err.to_string()));
+            }
             return Err(SwarmRoutingError::AllLanesSuppressed {
                 cooldown_remaining_ms: min_cooldown.unwrap_or(0),
             });
@@ -825,4 +909,66 @@ mod tests {
         let err = p.route(&req(TaskClass::ForceCloud), now).unwrap_err();
         assert_eq!(err, SwarmRoutingError::NoCloudProvider);
     }
+
+    // ---- spend budgets ----
+
+    #[test]
+    fn hard_budget_skips_provider_then_falls_back_or_errors() {
+        use crate::llm::TokenUsage;
+        use crate::model_runtime::cloud::spend::{
+            CloudSpendSettings, ModelPrice, SpendBudget, SpendScopeKind,
+        };
+
+        let settings = CloudSpendSettings {
+            pricing: vec![ModelPrice {
+                provider: SPEND_PROVIDER_ANTHROPIC.to_string(),
+                model_prefix: "claude-sonnet-4".to_string(),
+                input_usd_per_mtok: 3.0,
+                output_usd_per_mtok: 15.0,
+            }],
+            budgets: vec![SpendBudget {
+                scope: SpendScopeKind::Swarm,
+                soft_limit_usd: None,
+                hard_limit_usd: Some(1.0),
+            }],
+        };
+        let ledger = Arc::new(CloudSpendLedger::new(settings));
+        let attribution = SpendAttribution::new().with_swarm("swarm-1");
+        let now = Instant::now();
+        let mut p = policy().with_spend_ledger(ledger.clone());
+
+        // Within budget: the first preference is chosen as before.
+        let hard = req(TaskClass::HardReasoning).with_spend_attribution(attribution.clone());
+        assert_eq!(
+            p.route(&hard, now).unwrap(),
+            RoutingDecision::Cloud {
+                provider: CloudProvider::Anthropic,
+                model: "claude-sonnet-4".to_string()
+            }
+        );
+
+        // Spend the swarm's whole hard budget.
+        ledger.record(
+            SPEND_PROVIDER_ANTHROPIC,
+            "claude-sonnet-4",
+            &attribution,
+            &TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 100_000,
+                total_tokens: 100_000,
+            },
+        );
+
+        // Every provider is over budget: hard reasoning falls back to local and
+        // ForceCloud reports the budget rather than a breaker suppression.
+        assert_eq!(p.route(&hard, now).unwrap().tier(), TaskTier::Local);
+        let force = req(TaskClass::ForceCloud).with_spend_attribution(attribution);
+        let err = p.route(&force, now).unwrap_err();
+        assert!(matches!(err, SwarmRoutingError::BudgetExhausted(_)));
+
+        // Another swarm's work is unaffected.
+        let other = req(TaskClass::HardReasoning)
+            .with_spend_attribution(SpendAttribution::new().with_swarm("swarm-2"));
+        assert_eq!(p.route(&other, now).unwrap().tier(), TaskTier::Cloud);
+    }
 }
@@ -1,5 +1,10 @@
+use std::sync::Arc;
+
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Captures, Regex};
+
+use crate::redaction::{DetectionEngine, DetectionReport, DetectionRuleError, DetectionSurface};
+use crate::storage::Database;
 
 #[derive(Clone, Debug)]
 pub struct RedactionResult {
@@ -21,12 +26,51 @@ pub trait SecretRedactor: Send + Sync {
     fn redact_chunk(&self, chunk: &[u8]) -> RedactionResult {
         // Default: lossy-decode the single stream and pattern-redact it, with no
         // join. Implementations with a faster path may override.
-        apply_patterns(&String::from_utf8_lossy(chunk))
+        apply_patterns(&String::from_utf8_lossy(chunk), DetectionEngine::shared())
+    }
+
+    /// Finding report for `text` from the detection engine this redactor
+    /// masks with. Telemetry only: callers count detector ids, they never
+    /// read matched content back out of it.
+    fn detect(&self, text: &str, surface: DetectionSurface) -> DetectionReport {
+        DetectionEngine::shared().scan(text, surface)
     }
 }
 
+/// Terminal patterns plus the built-in detection engine.
 pub struct PatternRedactor;
 
+/// Terminal patterns plus a workspace-governed detection engine, so the
+/// workspace's deny rules and custom patterns mask terminal output and its
+/// allow rules let fixture values through.
+pub struct EngineRedactor {
+    engine: Arc<DetectionEngine>,
+}
+
+impl EngineRedactor {
+    pub fn new(engine: Arc<DetectionEngine>) -> Self {
+        Self { engine }
+    }
+
+    /// Redactor for work scoped to `workspace_id`, masking with that
+    /// workspace's governed rules. Work with no workspace gets the built-in
+    /// engine, the same masking [`PatternRedactor`] applies.
+    pub async fn for_workspace(
+        db: &dyn Database,
+        workspace_id: Option<&str>,
+    ) -> Result<Self, DetectionRuleError> {
+        let engine = match workspace_id {
+            Some(workspace_id) => DetectionEngine::for_workspace(db, workspace_id).await?,
+            None => DetectionEngine::shared().clone(),
+        };
+        Ok(Self::new(Arc::new(engine)))
+    }
+}
+
+const REDACTION_MASK: &str = "***REDACTED***";
+/// Detector id workspace allow rules name to exempt terminal-pattern matches.
+const TERMINAL_PATTERN_ID: &str = "terminal_assignment";
+
 static REDACTION_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     let patterns = [
         r#"(?i)(api[_-]?key|secret|token|password)\s*=\s*[^\s"]+"#,
@@ -40,13 +84,27 @@ static REDACTION_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
         .collect()
 });
 
-fn apply_patterns(text: &str) -> RedactionResult {
-    let mut matched = false;
-    let mut redacted = text.to_string();
+fn apply_patterns(text: &str, engine: &DetectionEngine) -> RedactionResult {
+    let (mut redacted, report) = engine.redact(text, DetectionSurface::TerminalCapture, |_| {
+        REDACTION_MASK.to_string()
+    });
+    let mut matched = !report.is_clean();
     for pattern in REDACTION_PATTERNS.iter() {
-        if pattern.is_match(&redacted) {
+        let applies = pattern
+            .find_iter(&redacted)
+            .any(|m| !engine.is_allowed(TERMINAL_PATTERN_ID, m.as_str()));
+        if applies {
             matched = true;
-            redacted = pattern.replace_all(&redacted, "***REDACTED***").to_string();
+            redacted = pattern
+                .replace_all(&redacted, |captures: &Captures<'_>| {
+                    let found = &captures[0];
+                    if engine.is_allowed(TERMINAL_PATTERN_ID, found) {
+                        found.to_string()
+                    } else {
+                        REDACTION_MASK.to_string()
+                    }
+                })
+                .to_string();
         }
     }
     RedactionResult { redacted, matched }
@@ -54,7 +112,7 @@ fn apply_patterns(text: &str) -> RedactionResult {
 
 impl SecretRedactor for PatternRedactor {
     fn redact_command(&self, command: &str) -> RedactionResult {
-        apply_patterns(command)
+        apply_patterns(command, DetectionEngine::shared())
     }
 
     fn redact_output(&self, stdout: &[u8], stderr: &[u8]) -> RedactionResult {
@@ -63,13 +121,36 @@ impl SecretRedactor for PatternRedactor {
             String::from_utf8_lossy(stdout),
             String::from_utf8_lossy(stderr)
         );
-        apply_patterns(&combined)
+        apply_patterns(&combined, DetectionEngine::shared())
     }
 
     fn redact_chunk(&self, chunk: &[u8]) -> RedactionResult {
         // Byte-faithful single-stream redaction: no stdout/stderr join, so a
         // redacted streaming chunk does not gain a spurious trailing newline.
-        apply_patterns(&String::from_utf8_lossy(chunk))
+        apply_patterns(&String::from_utf8_lossy(chunk), DetectionEngine::shared())
+    }
+}
+
+impl SecretRedactor for EngineRedactor {
+    fn redact_command(&self, command: &str) -> RedactionResult {
+        apply_patterns(command, &self.engine)
+    }
+
+    fn redact_output(&self, stdout: &[u8], stderr: &[u8]) -> RedactionResult {
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(stdout),
+            String::from_utf8_lossy(stderr)
+        );
+        apply_patterns(&combined, &self.engine)
+    }
+
+    fn redact_chunk(&self, chunk: &[u8]) -> RedactionResult {
+        apply_patterns(&String::from_utf8_lossy(chunk), &self.engine)
+    }
+
+    fn detect(&self, text: &str, surface: DetectionSurface) -> DetectionReport {
+        self.engine.scan(text, surface)
     }
 }
 
@@ -109,4 +190,25 @@ mod tests {
         assert!(r.redacted.contains("REDACTED"));
         assert!(!r.redacted.contains("supersecretvalue123"));
     }
+
+    #[test]
+    fn engine_redactor_applies_workspace_rules() {
+        let rules = serde_json::from_value(serde_json::json!({
+            "allow": [{ "id": "debug-flag", "literal": "DEBUG=1" }],
+            "deny": [{ "id": "codename", "literal": "Project Nightjar" }]
+        }))
+        .expect("rules");
+        let redactor = EngineRedactor::new(Arc::new(
+            DetectionEngine::with_rules(rules).expect("engine"),
+        ));
+        let r = redactor.redact_chunk(b"DEBUG=1 deploy Project Nightjar");
+        assert!(r.matched);
+        assert_eq!(r.redacted, "DEBUG=1 deploy ***REDACTED***");
+
+        let report = redactor.detect("Project Nightjar", DetectionSurface::TranscriptExport);
+        assert_eq!(report.count_by_detector().get("deny:codename"), Some(&1));
+        assert!(PatternRedactor
+            .detect("Project Nightjar", DetectionSurface::TranscriptExport)
+            .is_clean());
+    }
 }
@@ -354,7 +354,7 @@ const SURFACES: &[SurfaceDescriptor] = &[
         "/workspaces/:workspace_id/loom/blocks/:block_id",
         "Delete a LoomBlock (bridge rows cascade; knowledge entity retired by service layer).",
         "workspace_id + block_id path params.",
-        "Empty success; 404 when absent."),
+        "JSON status plus receipt_event_id (Flight Recorder receipt); 404 when absent."),
     surface!("loom.blocks.metrics.recompute", SurfaceGroup::NotesLoom, "POST",
         "/workspaces/:workspace_id/loom/blocks/:block_id/metrics/recompute",
         "Recompute derived metrics for one block.",
@@ -409,7 +409,7 @@ const SURFACES: &[SurfaceDescriptor] = &[
         "/workspaces/:workspace_id/loom/folders/:folder_id",
         "Delete a folder.",
         "Path params.",
-        "Empty success; 404 when absent."),
+        "JSON status plus receipt_event_id (Flight Recorder receipt); 404 when absent."),
     surface!("loom.folders.blocks.list", SurfaceGroup::NotesLoom, "GET",
         "/workspaces/:workspace_id/loom/folders/:folder_id/blocks",
         "List blocks in a folder under its sort mode.",
@@ -514,12 +514,12 @@ const SURFACES: &[SurfaceDescriptor] = &[
         "/workspaces/:workspace_id/loom/edges",
         "Create a typed LoomEdge (link/reference/tag membership).",
         "JSON NewLoomEdge.",
-        "JSON created edge row."),
+        "JSON created edge row plus receipt_event_id (Flight Recorder receipt)."),
     surface!("loom.edges.delete", SurfaceGroup::NotesLoom, "DELETE",
         "/workspaces/:workspace_id/loom/edges/:edge_id",
         "Delete a LoomEdge.",
         "Path params.",
-        "Empty success."),
+        "JSON deleted edge row plus receipt_event_id (Flight Recorder receipt)."),
     surface!("loom.assets.get", SurfaceGroup::NotesLoom, "GET",
         "/workspaces/:workspace_id/assets/:asset_id",
         "Asset metadata (typed embed target for [[HS_images]]/[[HS_slideshow]]).",
@@ -1,16 +1,17 @@
 use crate::storage::{
-    CalendarEventUpsert, CalendarMutationAction, CalendarSourceSyncState, CalendarSourceUpsert,
-    CalendarSourceWritePolicy, CalendarSyncEventUpsert, CalendarSyncInput, CalendarSyncStateStage,
-    WriteContext,
+    CalendarEventUpsert, CalendarMutationAction, CalendarSource, CalendarSourceProviderType,
+    CalendarSourceSyncState, CalendarSourceUpsert, CalendarSourceWritePolicy,
+    CalendarSyncEventUpsert, CalendarSyncInput, CalendarSyncStateStage, WriteContext,
 };
 use crate::{
     ace::{
         validators::{
-            build_query_plan_from_blocks, build_retrieval_trace_from_blocks,
+            build_query_plan_from_blocks, build_retrieval_trace_from_blocks, classify_untrusted,
             freshness::{REGEN_SKIPPED_PREFIX, STALE_PACK_WARNING_PREFIX},
             promotion::PROMOTION_PROCEDURAL_REVIEW_WARNING,
-            scan_content_for_security, SecurityViolationType, StorageContentResolver,
-            ValidatorPipeline,
+            scan_content_for_security, spotlight_all, InjectionClassifier, PromptInjectionSettings,
+            RuntimeInjectionClassifier, SecurityViolationType, StorageContentResolver,
+            UntrustedSource, UntrustedSpan, ValidatorPipeline,
         },
         AceError, ArtifactHandle, CandidateRef, CandidateScores, ContextPackAnchorV1,
         ContextPackBuilder, ContextPackCoverageV1, ContextPackFreshnessPolicyV1,
@@ -24,6 +25,7 @@ use crate::{
         ViewMode,
     },
     bundles::{BundleScope, DebugBundleRequest, DefaultDebugBundleExporter, RedactionMode},
+    caldav,
     capabilities::{RegistryError, GOVERNANCE_PACK_EXPORT_PROTOCOL_ID},
     capability_registry_workflow::{
         repo_root_from_manifest_dir, run_capability_registry_workflow,
@@ -43,6 +45,7 @@ use crate::{
         },
         openai_compat_canonical_request_bytes, CompletionRequest, LlmError, ModelTier,
     },
+    loom_preview::{PreviewError, PreviewSource, PreviewTool, RenderedTier, VideoProbe},
     memory::{
         bitemporal::PostgresBitemporalMemoryIndex,
         hygiene::{HygieneConfig, HygieneJobRunner},
@@ -58,6 +61,7 @@ use crate::{
         MexRuntime, OutputSpec, PlannedOperation, ProvenanceGate, ProvenanceRecord, SchemaGate,
         POE_SCHEMA_VERSION,
     },
+    model_runtime::cloud::SecretsVault,
     models::{AiJob, JobKind, WorkflowRun},
     runtime_governance::RuntimeGovernancePaths,
     storage::{
@@ -69,7 +73,7 @@ use crate::{
     terminal::{
         config::TerminalConfig,
         guards::{DefaultTerminalGuard, TerminalGuard},
-        redaction::PatternRedactor,
+        redaction::EngineRedactor,
         JobContext, TerminalMode, TerminalRequest, TerminalService,
     },
     workspace_safety::{
@@ -10273,6 +10277,35 @@ pub async fn record_cloud_escalation_consent_v0_4(
     Ok(())
 }
 
+/// Prompt-injection settings of the document's workspace; the defaults when the
+/// document or its workspace settings cannot be read.
+async fn prompt_injection_settings_for_document(
+    state: &AppState,
+    doc_id: &str,
+) -> PromptInjectionSettings {
+    let Ok(document) = state.storage.get_document(doc_id).await else {
+        return PromptInjectionSettings::default();
+    };
+    PromptInjectionSettings::for_workspace(state.storage.as_ref(), &document.workspace_id)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!(
+                target: "handshake_core::security",
+                doc_id = %doc_id,
+                error = %err,
+                "prompt_injection settings unreadable; using defaults"
+            );
+            PromptInjectionSettings::default()
+        })
+}
+
+/// The classifier tier over the local model `main` loaded for it; `None` when
+/// none is configured, and [`classify_untrusted`] then honours `fail_closed`.
+fn local_injection_classifier() -> Option<RuntimeInjectionClassifier> {
+    crate::model_runtime::local_models::shared()
+        .and_then(|models| RuntimeInjectionClassifier::from_local_models(&models))
+}
+
 async fn run_job(
     state: &AppState,
     job: &AiJob,
@@ -10366,6 +10399,46 @@ async fn run_job(
                 .map_err(WorkflowError::SecurityViolation)?;
             trace.apply_view_mode_hard_drop();
 
+            // [§2.6.6.7.11.6] Decoded-content and classifier injection tiers over
+            // every block that reaches the prompt. Flagged verdicts land in the
+            // trace as injection warnings, so the pipeline below poisons the job.
+            let injection_settings = prompt_injection_settings_for_document(state, doc_id).await;
+            let untrusted_spans: Vec<UntrustedSpan> = blocks
+                .iter()
+                .map(|b| {
+                    UntrustedSpan::new(
+                        UntrustedSource::for_block_kind(&b.kind),
+                        b.id.clone(),
+                        b.raw_content.clone(),
+                    )
+                })
+                .collect();
+            let injection_classifier = local_injection_classifier();
+            let injection_verdicts = classify_untrusted(
+                injection_classifier
+                    .as_ref()
+                    .map(|c| c as &dyn InjectionClassifier),
+                &untrusted_spans,
+                &injection_settings.classifier_policy(),
+            )
+            .await;
+            if injection_verdicts
+                .iter()
+                .any(|v| v.injected || v.classifier_score.is_some())
+            {
+                record_event_safely(
+                    state,
+                    crate::flight_recorder::events_prompt_injection::injection_verdict_event(
+                        trace_id,
+                        job.job_id,
+                        trace.trace_id,
+                        &injection_verdicts,
+                    ),
+                )
+                .await;
+            }
+            trace.record_injection_verdicts(injection_verdicts);
+
             // WAIVER [CX-573E]: Instant::now() for observability per ┬º2.6.6.7.12
             let validation_start = std::time::Instant::now();
 
@@ -10503,7 +10576,16 @@ async fn run_job(
                     "ROLE: {role_id}\n\nTASK: Propose an improved replacement for SELECTED_TEXT ONLY.\n- Do not edit anything outside SELECTED_TEXT.\n- Output ONLY the replacement text.\n\nSELECTED_TEXT:\n{selection_text}\n"
                 )
             } else {
-                format!("Please summarize the following document:\n\n{}", full_text)
+                // [§2.6.6.7.11.6] Spotlight each block under its own source
+                // (web page, email, calendar event, snippet) so instructions
+                // inside it read as data.
+                match injection_settings.spotlight.system_notice() {
+                    Some(notice) => format!(
+                        "{notice}\n\nPlease summarize the following document:\n\n{}",
+                        spotlight_all(&untrusted_spans, &injection_settings.spotlight)
+                    ),
+                    None => format!("Please summarize the following document:\n\n{}", full_text),
+                }
             };
 
             // Compute hashes for logging
@@ -10665,6 +10747,9 @@ async fn run_job(
                             "truncation_flags": trace.truncation_flags.clone(),
                             "compaction_applied": false,
 
+                            // prompt-injection verdicts for the untrusted spans
+                            "injection_verdicts": trace.injection_verdicts.clone(),
+
                             // QueryPlan ID + hash
                             "query_plan_id": plan.plan_id.to_string(),
                             "query_plan_hash": query_plan_hash,
@@ -11275,6 +11360,10 @@ async fn run_job(
                         .get("include_artifacts")
                         .and_then(|v| v.as_bool())
                         .unwrap_or(false),
+                    include_cassettes: inputs
+                        .get("include_cassettes")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
                 },
                 job.job_id,
                 workflow_run_id,
@@ -14394,11 +14483,27 @@ struct ValidationEvidenceArtifact {
 
 struct MediaDownloaderEngineAdapter {
     artifact_root: PathBuf,
+    engine_id: &'static str,
+    artifact_dir: &'static str,
 }
 
 impl MediaDownloaderEngineAdapter {
     fn new(artifact_root: PathBuf) -> Self {
-        Self { artifact_root }
+        Self::for_engine(artifact_root, MD_TOOL_ENGINE_ID, "mex_media_downloader")
+    }
+
+    /// The same governed tool runner registered under another engine id; the
+    /// Loom preview pipeline runs ffmpeg / ffprobe / pdftoppm through it.
+    fn for_engine(
+        artifact_root: PathBuf,
+        engine_id: &'static str,
+        artifact_dir: &'static str,
+    ) -> Self {
+        Self {
+            artifact_root,
+            engine_id,
+            artifact_dir,
+        }
     }
 
     fn rel_path_string(rel_path: &std::path::Path) -> String {
@@ -14409,9 +14514,9 @@ impl MediaDownloaderEngineAdapter {
         ArtifactHandle::new(Uuid::now_v7(), Self::rel_path_string(rel_path))
     }
 
-    fn artifact_dir_rel(op: &PlannedOperation) -> PathBuf {
+    fn artifact_dir_rel(&self, op: &PlannedOperation) -> PathBuf {
         PathBuf::from("data")
-            .join("mex_media_downloader")
+            .join(self.artifact_dir)
             .join("ops")
             .join(op.op_id.to_string())
     }
@@ -14502,7 +14607,7 @@ impl MediaDownloaderEngineAdapter {
 #[async_trait]
 impl EngineAdapter for MediaDownloaderEngineAdapter {
     async fn invoke(&self, op: &PlannedOperation) -> Result<EngineResult, MexAdapterError> {
-        if op.engine_id != MD_TOOL_ENGINE_ID {
+        if op.engine_id != self.engine_id {
             return Err(MexAdapterError::Engine(format!(
                 "unsupported engine_id for adapter: {}",
                 op.engine_id
@@ -14586,7 +14691,7 @@ impl EngineAdapter for MediaDownloaderEngineAdapter {
             ));
         }
 
-        let artifact_dir_rel = Self::artifact_dir_rel(op);
+        let artifact_dir_rel = self.artifact_dir_rel(op);
         let artifact_dir_abs = self.artifact_root.join(&artifact_dir_rel);
         std::fs::create_dir_all(&artifact_dir_abs).map_err(|e| {
             MexAdapterError::Engine(format!(
@@ -14821,11 +14926,16 @@ struct CalendarSyncAdapterOutcome {
 
 struct CalendarSyncEngineAdapter {
     state: AppState,
+    /// Credentials for provider-backed sources (CalDAV); `None` leaves them unreachable.
+    secrets_vault: Option<Arc<dyn SecretsVault>>,
 }
 
 impl CalendarSyncEngineAdapter {
-    fn new(state: AppState) -> Self {
-        Self { state }
+    fn new(state: AppState, secrets_vault: Option<Arc<dyn SecretsVault>>) -> Self {
+        Self {
+            state,
+            secrets_vault,
+        }
     }
 }
 
@@ -14848,7 +14958,8 @@ impl EngineAdapter for CalendarSyncEngineAdapter {
         let started_at = Utc::now();
         let input: CalendarSyncInput = serde_json::from_value(op.params.clone())
             .map_err(|e| MexAdapterError::Engine(format!("invalid calendar sync input: {e}")))?;
-        let outcome = apply_calendar_sync(&self.state, op, input).await?;
+        let outcome =
+            apply_calendar_sync(&self.state, self.secrets_vault.as_deref(), op, input).await?;
         let ended_at = Utc::now();
         let output_handle =
             ArtifactHandle::new(op.op_id, format!("calendar_sync_result/{}.json", op.op_id));
@@ -14883,6 +14994,7 @@ impl EngineAdapter for CalendarSyncEngineAdapter {
 
 async fn apply_calendar_sync(
     state: &AppState,
+    secrets_vault: Option<&dyn SecretsVault>,
     op: &PlannedOperation,
     input: CalendarSyncInput,
 ) -> Result<CalendarSyncAdapterOutcome, MexAdapterError> {
@@ -14974,6 +15086,12 @@ async fn apply_calendar_sync(
         Some(op.op_id),
         Some(workflow_id),
     );
+    if source.provider_type == CalendarSourceProviderType::Caldav
+        && input.provider_events.is_empty()
+        && mutation_count == 0
+    {
+        return apply_caldav_sync(state, secrets_vault, op, &source, &ctx).await;
+    }
     let mut provider_events_upserted = 0usize;
     for provider_event in input.provider_events {
         let upsert = calendar_event_upsert_from_sync_event(
@@ -15119,6 +15237,186 @@ async fn apply_calendar_sync(
     })
 }
 
+/// `calendar_sync.run` for a `caldav` source with no caller-supplied events: pull from and push to
+/// the server. Push requires `calendar.sync.write`; without it the run is pull-only.
+async fn apply_caldav_sync(
+    state: &AppState,
+    secrets_vault: Option<&dyn SecretsVault>,
+    op: &PlannedOperation,
+    source: &CalendarSource,
+    ctx: &WriteContext,
+) -> Result<CalendarSyncAdapterOutcome, MexAdapterError> {
+    let now = Utc::now();
+    let profile_id = op.capability_profile_id.as_deref().unwrap_or_default();
+    let push_allowed = state
+        .capability_registry
+        .profile_can(profile_id, CALENDAR_SYNC_WRITE_CAPABILITY)
+        .map_err(|e| MexAdapterError::Engine(e.to_string()))?;
+
+    let result = match secrets_vault {
+        Some(vault) => match caldav::client_for_source(source, vault) {
+            Ok(client) => {
+                let options = caldav::CaldavSyncOptions { push_allowed };
+                caldav::run_caldav_sync(state.storage.as_ref(), ctx, source, &client, options).await
+            }
+            Err(err) => Err(err),
+        },
+        None => Err(caldav::CaldavError::Credentials(
+            "no secrets vault is configured".to_string(),
+        )),
+    };
+
+    let previous = &source.sync_state;
+    let (sync_state, provider_calendar_id) = match &result {
+        Ok(report) => {
+            let pushed = report.pushed_creates + report.pushed_updates + report.pushed_deletes;
+            let stage = if report.open_conflicts > 0 {
+                CalendarSyncStateStage::Conflicted
+            } else {
+                CalendarSyncStateStage::Idle
+            };
+            let state = CalendarSourceSyncState {
+                state: Some(stage),
+                sync_token: report.next_sync_token.clone().or(previous.sync_token.clone()),
+                last_synced_at: Some(now),
+                last_full_sync_at: if report.full_resync || previous.last_full_sync_at.is_none() {
+                    Some(now)
+                } else {
+                    previous.last_full_sync_at
+                },
+                last_ok_at: Some(now),
+                last_pull_at: Some(now),
+                last_push_at: if pushed > 0 { Some(now) } else { previous.last_push_at },
+                last_error_at: None,
+                last_error_code: None,
+                last_error: None,
+                backoff_until: None,
+                consecutive_failures: Some(0),
+                last_remote_watermark: report.ctag.clone(),
+                last_local_applied_rev: Some(
+                    previous.last_local_applied_rev.unwrap_or(0)
+                        + (report.pulled_upserts + report.remote_deletions_applied + pushed) as i64,
+                ),
+            };
+            (state, Some(report.calendar_href.clone()))
+        }
+        Err(err) => {
+            let failures = previous.consecutive_failures.unwrap_or(0) + 1;
+            let state = CalendarSourceSyncState {
+                state: Some(CalendarSyncStateStage::ErrorBackoff),
+                last_error_at: Some(now),
+                last_error_code: Some(err.code().to_string()),
+                last_error: Some(err.to_string()),
+                backoff_until: Some(now + chrono::Duration::seconds(60 * failures.min(60))),
+                consecutive_failures: Some(failures),
+                ..previous.clone()
+            };
+            (state, source.provider_calendar_id.clone())
+        }
+    };
+
+    let updated_source = state
+        .storage
+        .upsert_calendar_source(
+            ctx,
+            CalendarSourceUpsert {
+                id: source.id.clone(),
+                workspace_id: source.workspace_id.clone(),
+                display_name: source.display_name.clone(),
+                provider_type: source.provider_type.clone(),
+                write_policy: source.write_policy.clone(),
+                default_tzid: source.default_tzid.clone(),
+                auto_export: source.auto_export,
+                credentials_ref: source.credentials_ref.clone(),
+                provider_calendar_id,
+                capability_profile_id: source.capability_profile_id.clone(),
+                config: source.config.clone(),
+                sync_state,
+            },
+        )
+        .await
+        .map_err(|e| MexAdapterError::Engine(e.to_string()))?;
+
+    let sync_state_json = json!({
+        "state": updated_source.sync_state.state.as_ref().map(|s| s.as_str()),
+        "sync_token": updated_source.sync_state.sync_token,
+        "last_remote_watermark": updated_source.sync_state.last_remote_watermark,
+        "last_local_applied_rev": updated_source.sync_state.last_local_applied_rev
+    });
+    let (status, output, errors) = match result {
+        Ok(report) => (
+            EngineStatus::Succeeded,
+            json!({
+                "schema_version": "hsk.calendar_sync_result@v1",
+                "engine_id": CALENDAR_SYNC_ENGINE_ID,
+                "operation": CALENDAR_SYNC_OPERATION,
+                "status": "succeeded",
+                "workspace_id": source.workspace_id,
+                "source_id": source.id,
+                "provider_type": source.provider_type.as_str(),
+                "write_policy": source.write_policy.as_str(),
+                "push_allowed": push_allowed,
+                "remote_write_attempted": report.remote_write_attempted,
+                "caldav": report,
+                "provider_safe_evidence": {
+                    "remote_write_attempted": report.remote_write_attempted,
+                    "credentials_ref_emitted": false,
+                    "credentials_ref_present": source.credentials_ref.is_some(),
+                    "provider_payload_redacted": true
+                },
+                "sync_state": sync_state_json
+            }),
+            Vec::new(),
+        ),
+        Err(err) => (
+            EngineStatus::Failed,
+            json!({
+                "schema_version": "hsk.calendar_sync_result@v1",
+                "engine_id": CALENDAR_SYNC_ENGINE_ID,
+                "operation": CALENDAR_SYNC_OPERATION,
+                "status": "failed",
+                "workspace_id": source.workspace_id,
+                "source_id": source.id,
+                "provider_type": source.provider_type.as_str(),
+                "write_policy": source.write_policy.as_str(),
+                "provider_safe_evidence": {
+                    "credentials_ref_emitted": false,
+                    "credentials_ref_present": source.credentials_ref.is_some(),
+                    "provider_payload_redacted": true
+                },
+                "sync_state": sync_state_json,
+                "error": { "code": err.code(), "message": err.to_string() }
+            }),
+            vec![EngineError {
+                code: err.code().to_string(),
+                message: err.to_string(),
+                details_ref: None,
+            }],
+        ),
+    };
+
+    record_event_safely(
+        state,
+        FlightRecorderEvent::new(
+            FlightRecorderEventType::System,
+            FlightRecorderActor::Agent,
+            op.op_id,
+            json!({
+                "message": "calendar_sync_result",
+                "calendar_sync_result": output.clone()
+            }),
+        )
+        .with_job_id(op.op_id.to_string()),
+    )
+    .await;
+
+    Ok(CalendarSyncAdapterOutcome {
+        status,
+        output,
+        errors,
+    })
+}
+
 async fn run_calendar_sync_job(
     state: &AppState,
     job: &AiJob,
@@ -15227,6 +15525,19 @@ async fn run_calendar_sync_job(
     })
 }
 
+#[cfg(feature = "os-keychain")]
+fn calendar_sync_secrets_vault() -> Option<Arc<dyn SecretsVault>> {
+    use crate::model_runtime::cloud::secrets_vault::{
+        OsKeychainSecretsVault, HANDSHAKE_KEYCHAIN_SERVICE,
+    };
+    Some(Arc::new(OsKeychainSecretsVault::new(HANDSHAKE_KEYCHAIN_SERVICE)))
+}
+
+#[cfg(not(feature = "os-keychain"))]
+fn calendar_sync_secrets_vault() -> Option<Arc<dyn SecretsVault>> {
+    None
+}
+
 fn build_mex_runtime(state: &AppState, repo_root: &Path) -> Result<MexRuntime, WorkflowError> {
     let registry_path = repo_root.join("src/backend/handshake_core/mechanical_engines.json");
     let registry = MexRegistry::load_from_path(&registry_path)
@@ -15241,7 +15552,10 @@ fn build_mex_runtime(state: &AppState, repo_root: &Path) -> Result<MexRuntime, W
         Box::new(DetGate),
     ]);
 
-    Ok(MexRuntime::new(
+    #[cfg(feature = "wasm-engines")]
+    let wasm_adapters = crate::mex::WasmEngineAdapter::from_registry(repo_root, &registry);
+
+    let runtime = MexRuntime::new(
         registry,
         state.flight_recorder.clone(),
         state.diagnostics.clone(),
@@ -15259,10 +15573,30 @@ fn build_mex_runtime(state: &AppState, repo_root: &Path) -> Result<MexRuntime, W
         MD_TOOL_ENGINE_ID,
         Arc::new(MediaDownloaderEngineAdapter::new(repo_root.to_path_buf())),
     )
+    .with_adapter(
+        LOOM_PREVIEW_TOOL_ENGINE_ID,
+        Arc::new(MediaDownloaderEngineAdapter::for_engine(
+            repo_root.to_path_buf(),
+            LOOM_PREVIEW_TOOL_ENGINE_ID,
+            "mex_media_preview",
+        )),
+    )
     .with_adapter(
         CALENDAR_SYNC_ENGINE_ID,
-        Arc::new(CalendarSyncEngineAdapter::new(state.clone())),
-    ))
+        Arc::new(CalendarSyncEngineAdapter::new(
+            state.clone(),
+            calendar_sync_secrets_vault(),
+        )),
+    );
+
+    #[cfg(feature = "wasm-engines")]
+    let runtime = wasm_adapters
+        .into_iter()
+        .fold(runtime, |runtime, (engine_id, adapter)| {
+            runtime.with_adapter(engine_id, Arc::new(adapter))
+        });
+
+    Ok(runtime)
 }
 
 fn normalize_in_scope_paths_for_validation(in_scope_paths: &[String]) -> Vec<String> {
@@ -20569,6 +20903,21 @@ async fn run_spec_router_job(
     })
 }
 
+/// Workspace a job runs for: its `workspace` entity ref, else a
+/// `workspace_id` / `wsid` input.
+fn job_workspace_id(job: &AiJob, inputs: &Value) -> Option<String> {
+    job.entity_refs
+        .iter()
+        .find(|r| r.entity_kind == "workspace")
+        .map(|r| r.entity_id.clone())
+        .or_else(|| {
+            ["workspace_id", "wsid"]
+                .iter()
+                .find_map(|key| inputs.get(*key).and_then(|v| v.as_str()))
+                .map(str::to_string)
+        })
+}
+
 async fn execute_terminal_job(
     state: &AppState,
     job: &AiJob,
@@ -20643,7 +20992,12 @@ async fn execute_terminal_job(
 
     let cfg = TerminalConfig::with_session_scoped_denies(session_id.as_deref());
     let guards: Vec<Box<dyn TerminalGuard>> = vec![Box::new(DefaultTerminalGuard)];
-    let redactor = PatternRedactor;
+    let redactor = EngineRedactor::for_workspace(
+        state.storage.as_ref(),
+        job_workspace_id(job, &inputs).as_deref(),
+    )
+    .await
+    .map_err(|e| WorkflowError::Terminal(format!("redaction rules unavailable: {e}")))?;
 
     let output = TerminalService::run_command(
         request,
@@ -20780,239 +21134,129 @@ async fn run_loom_preview_generate_job(
         .await
         .map_err(|e| WorkflowError::Terminal(e.to_string()))?;
 
-    if !asset.mime.starts_with("image/") {
-        // MT-259 HONEST DEVIATION: no video decoder is bundled (Cargo.toml ships
-        // the `image` crate for PNG/JPEG only; adding ffmpeg would require a
-        // lockfile-pinned native decoder, deferred). For video we record an
-        // honest `failed` poster tier (NOT a synthetic/faked poster) with a real
-        // failure_reason, so the tier lands in the visible retry queue
-        // (list_failed_media_tiers) and POST .../tiers/poster/retry can requeue
-        // it (status -> pending, attempt_count++). The full video itself is
-        // still served byte-exact via the Range endpoint. When a decoder is
-        // bundled in a later MT, this branch produces a real poster frame.
-        let is_video = asset.mime.starts_with("video/");
-        let reason = if is_video {
-            "no_video_decoder_bundled"
-        } else {
-            "unsupported_mime"
-        };
-        if is_video {
-            state
-                .storage
-                .upsert_media_tier(
-                    &ctx,
-                    crate::storage::MediaTierUpsert {
-                        workspace_id: workspace_id.to_string(),
+    let target = LoomPreviewTarget {
+        workspace_id,
+        block_id,
+        asset_id,
+    };
+    let source = PreviewSource::classify(&asset.mime, &original_bytes);
+    if source == PreviewSource::Unsupported {
+        let err = PreviewError::UnsupportedMime(asset.mime.clone());
+        return finish_loom_preview_failed(state, &ctx, job, &target, source, &err, None).await;
+    }
+
+    // ffmpeg / ffprobe / pdftoppm are never bundled: they are detected on this
+    // machine and run as governed MEX operations. A missing tool is an honest
+    // `<tool>_not_found` tier failure, retryable once the tool is installed.
+    let tools = match source {
+        PreviewSource::Avif | PreviewSource::Pdf | PreviewSource::Video => {
+            Some(LoomPreviewTools::new(state, job, &handshake_root)?)
+        }
+        _ => None,
+    };
+    let work_dir = handshake_root
+        .join(".handshake")
+        .join("tmp")
+        .join("loom_preview")
+        .join(job.job_id.to_string());
+    if tools.is_some() {
+        fs::create_dir_all(&work_dir).map_err(|e| WorkflowError::Terminal(e.to_string()))?;
+    }
+
+    let mut media_probe: Option<VideoProbe> = None;
+    let mut probe_issue: Option<&'static str> = None;
+    if let (PreviewSource::Video, Some(tools)) = (source, tools.as_ref()) {
+        match tools.probe_video(&original_path).await {
+            Ok(probe) => {
+                crate::storage::media_probe::upsert_media_asset_probe(
+                    &state.postgres_pool,
+                    &crate::storage::media_probe::UpsertMediaAssetProbe {
                         asset_id: asset_id.to_string(),
-                        tier: crate::storage::MediaTier::Poster,
-                        status: crate::storage::MediaTierStatus::Failed,
-                        tier_asset_id: None,
-                        content_hash: None,
-                        failure_reason: Some(reason.to_string()),
+                        workspace_id: workspace_id.to_string(),
+                        probe_tool: PreviewTool::Ffprobe.binary_name().to_string(),
+                        duration_ms: probe.duration_ms,
+                        width: probe.width.and_then(|w| i32::try_from(w).ok()),
+                        height: probe.height.and_then(|h| i32::try_from(h).ok()),
+                        video_codec: probe.video_codec.clone(),
+                        audio_codec: probe.audio_codec.clone(),
+                        frame_rate: probe.frame_rate,
                     },
                 )
                 .await?;
+                media_probe = Some(probe);
+            }
+            // Duration is metadata, not a tier: the poster is still attempted.
+            Err(err) => probe_issue = Some(err.reason()),
         }
-
-        state
-            .storage
-            .set_loom_block_preview(
-                &ctx,
-                workspace_id,
-                block_id,
-                crate::storage::PreviewStatus::Failed,
-                None,
-                None,
-            )
-            .await?;
-
-        let payload = json!({
-            "workspace_id": workspace_id,
-            "block_id": block_id,
-            "asset_id": asset_id,
-            "preview_status": "failed",
-            "reason": reason,
-        });
-        state
-            .storage
-            .set_job_outputs(&job.job_id.to_string(), Some(payload.clone()))
-            .await?;
-
-        return Ok(RunJobOutcome {
-            state: JobState::CompletedWithIssues,
-            status_reason: reason.to_string(),
-            output: Some(payload),
-            error_message: None,
-        });
     }
 
-    let thumb = image::load_from_memory(&original_bytes)
-        .map_err(|e| WorkflowError::Terminal(e.to_string()))?
-        .thumbnail(256, 256);
-
-    let mut out = Vec::new();
-    {
-        let mut cursor = std::io::Cursor::new(&mut out);
-        thumb
-            .write_to(&mut cursor, image::ImageFormat::Png)
-            .map_err(|e| WorkflowError::Terminal(e.to_string()))?;
+    let source_image = loom_preview_source_image(
+        source,
+        &original_bytes,
+        &original_path,
+        tools.as_ref(),
+        &work_dir,
+        media_probe.as_ref(),
+    )
+    .await;
+    if tools.is_some() {
+        let _ = fs::remove_dir_all(&work_dir);
     }
-
-    let thumbnail_hash = {
-        let mut h = Sha256::new();
-        h.update(&out);
-        hex::encode(h.finalize())
-    };
-
-    let thumbnail_asset = match state
-        .storage
-        .find_asset_by_content_hash(workspace_id, &thumbnail_hash)
-        .await?
-    {
-        Some(existing) => existing,
-        None => {
-            state
-                .storage
-                .create_asset(
-                    &ctx,
-                    crate::storage::NewAsset {
-                        workspace_id: workspace_id.to_string(),
-                        kind: "thumbnail".to_string(),
-                        mime: "image/png".to_string(),
-                        original_filename: None,
-                        content_hash: thumbnail_hash.clone(),
-                        size_bytes: out.len() as i64,
-                        width: Some(thumb.width() as i64),
-                        height: Some(thumb.height() as i64),
-                        classification: "low".to_string(),
-                        exportable: true,
-                        is_proxy_of: Some(asset.asset_id.clone()),
-                        proxy_asset_id: None,
-                    },
-                )
-                .await?
+    let source_image = match source_image {
+        Ok(image) => image,
+        Err(err) => {
+            return finish_loom_preview_failed(
+                state,
+                &ctx,
+                job,
+                &target,
+                source,
+                &err,
+                media_probe.as_ref(),
+            )
+            .await;
         }
     };
 
-    let thumbnail_path = crate::loom_fs::loom_asset_blob_path(
-        &handshake_root,
-        workspace_id,
-        "thumbnail",
-        thumbnail_hash.as_str(),
-    );
-    match crate::storage::artifacts::write_file_atomic(
-        &handshake_root,
-        &thumbnail_path,
-        &out,
-        false,
-    ) {
-        Ok(()) => {}
-        Err(crate::storage::artifacts::ArtifactError::Io(io_err))
-            if io_err.kind() == std::io::ErrorKind::AlreadyExists => {}
-        Err(err) => return Err(WorkflowError::Terminal(err.to_string())),
-    }
-
-    let thumbnail_asset_id = thumbnail_asset.asset_id.clone();
-
     // MT-259 MediaCacheTiers: in addition to the legacy block-keyed thumbnail
     // slot, record per-asset, per-tier rows so the view renders from tiers and
-    // the pyramid (thumb -> preview -> full) is real and regenerable.
-    //
-    // thumb tier: the 256px PNG we just generated.
-    state
-        .storage
-        .upsert_media_tier(
-            &ctx,
-            crate::storage::MediaTierUpsert {
-                workspace_id: workspace_id.to_string(),
-                asset_id: asset_id.to_string(),
-                tier: crate::storage::MediaTier::Thumb,
-                status: crate::storage::MediaTierStatus::Ready,
-                tier_asset_id: Some(thumbnail_asset_id.clone()),
-                content_hash: Some(thumbnail_hash.clone()),
-                failure_reason: None,
-            },
-        )
-        .await?;
-
-    // preview tier: a mid-resolution (<=1024px) PNG for the in-editor preview
-    // step between thumb-grid and full-res.
-    let preview_img = image::load_from_memory(&original_bytes)
-        .map_err(|e| WorkflowError::Terminal(e.to_string()))?
-        .thumbnail(1024, 1024);
-    let mut preview_out = Vec::new();
-    {
-        let mut cursor = std::io::Cursor::new(&mut preview_out);
-        preview_img
-            .write_to(&mut cursor, image::ImageFormat::Png)
+    // the pyramid (thumb -> preview [-> poster] -> full) is real and
+    // regenerable. Every derived tier is a PNG content-addressed asset.
+    let mut thumbnail_asset_id = String::new();
+    let mut receipt_tiers = Vec::new();
+    for tier in source.derived_tiers() {
+        let rendered = crate::loom_preview::render_tier(&source_image, *tier)
             .map_err(|e| WorkflowError::Terminal(e.to_string()))?;
+        let tier_asset_id =
+            persist_loom_preview_tier(state, &ctx, &handshake_root, &target, &asset, &rendered)
+                .await?;
+        state
+            .storage
+            .upsert_media_tier(
+                &ctx,
+                crate::storage::MediaTierUpsert {
+                    workspace_id: workspace_id.to_string(),
+                    asset_id: asset_id.to_string(),
+                    tier: *tier,
+                    status: crate::storage::MediaTierStatus::Ready,
+                    tier_asset_id: Some(tier_asset_id.clone()),
+                    content_hash: Some(rendered.content_hash.clone()),
+                    failure_reason: None,
+                },
+            )
+            .await?;
+        if *tier == crate::storage::MediaTier::Thumb {
+            thumbnail_asset_id = tier_asset_id.clone();
+        }
+        receipt_tiers.push(json!({
+            "tier": tier.as_str(),
+            "status": "ready",
+            "tier_asset_id": tier_asset_id,
+            "content_hash": rendered.content_hash,
+            "width": rendered.width,
+            "height": rendered.height,
+        }));
     }
-    let preview_hash = {
-        let mut h = Sha256::new();
-        h.update(&preview_out);
-        hex::encode(h.finalize())
-    };
-    let preview_asset = match state
-        .storage
-        .find_asset_by_content_hash(workspace_id, &preview_hash)
-        .await?
-    {
-        Some(existing) => existing,
-        None => {
-            state
-                .storage
-                .create_asset(
-                    &ctx,
-                    crate::storage::NewAsset {
-                        workspace_id: workspace_id.to_string(),
-                        kind: "preview".to_string(),
-                        mime: "image/png".to_string(),
-                        original_filename: None,
-                        content_hash: preview_hash.clone(),
-                        size_bytes: preview_out.len() as i64,
-                        width: Some(preview_img.width() as i64),
-                        height: Some(preview_img.height() as i64),
-                        classification: "low".to_string(),
-                        exportable: true,
-                        is_proxy_of: Some(asset.asset_id.clone()),
-                        proxy_asset_id: None,
-                    },
-                )
-                .await?
-        }
-    };
-    let preview_path = crate::loom_fs::loom_asset_blob_path(
-        &handshake_root,
-        workspace_id,
-        "preview",
-        preview_hash.as_str(),
-    );
-    match crate::storage::artifacts::write_file_atomic(
-        &handshake_root,
-        &preview_path,
-        &preview_out,
-        false,
-    ) {
-        Ok(()) => {}
-        Err(crate::storage::artifacts::ArtifactError::Io(io_err))
-            if io_err.kind() == std::io::ErrorKind::AlreadyExists => {}
-        Err(err) => return Err(WorkflowError::Terminal(err.to_string())),
-    }
-    state
-        .storage
-        .upsert_media_tier(
-            &ctx,
-            crate::storage::MediaTierUpsert {
-                workspace_id: workspace_id.to_string(),
-                asset_id: asset_id.to_string(),
-                tier: crate::storage::MediaTier::Preview,
-                status: crate::storage::MediaTierStatus::Ready,
-                tier_asset_id: Some(preview_asset.asset_id.clone()),
-                content_hash: Some(preview_hash.clone()),
-                failure_reason: None,
-            },
-        )
-        .await?;
 
     // full tier: points back at the ORIGINAL asset (no derived blob); always
     // ready once the source exists. Tiers are derived; the original is authority.
@@ -21031,6 +21275,12 @@ async fn run_loom_preview_generate_job(
             },
         )
         .await?;
+    receipt_tiers.push(json!({
+        "tier": "full",
+        "status": "ready",
+        "tier_asset_id": asset.asset_id,
+        "content_hash": asset.content_hash,
+    }));
 
     state
         .storage
@@ -21061,30 +21311,13 @@ async fn run_loom_preview_generate_job(
                 "preview_tier": 1,
                 "format": "png",
                 "duration_ms": duration_ms,
+                "source_kind": source.as_str(),
                 // MT-259: the receipt now carries the full per-tier pyramid it
-                // produced (thumb -> preview -> full), each with the derived
-                // asset id, so the receipt records the tier dimension rather
-                // than a single hardcoded slot.
-                "tiers": [
-                    {
-                        "tier": "thumb",
-                        "status": "ready",
-                        "tier_asset_id": thumbnail_asset_id,
-                        "content_hash": thumbnail_hash,
-                    },
-                    {
-                        "tier": "preview",
-                        "status": "ready",
-                        "tier_asset_id": preview_asset.asset_id,
-                        "content_hash": preview_hash,
-                    },
-                    {
-                        "tier": "full",
-                        "status": "ready",
-                        "tier_asset_id": asset.asset_id,
-                        "content_hash": asset.content_hash,
-                    },
-                ],
+                // produced (thumb -> preview [-> poster] -> full), each with the
+                // derived asset id, so the receipt records the tier dimension
+                // rather than a single hardcoded slot.
+                "tiers": receipt_tiers,
+                "media": media_probe,
             }),
         )
         .with_job_id(job.job_id.to_string())
@@ -21099,21 +21332,315 @@ async fn run_loom_preview_generate_job(
         "asset_id": asset_id,
         "thumbnail_asset_id": thumbnail_asset_id,
         "preview_status": "generated",
+        "source_kind": source.as_str(),
         "duration_ms": duration_ms,
+        "media": media_probe,
+        "probe_issue": probe_issue,
     });
     state
         .storage
         .set_job_outputs(&job.job_id.to_string(), Some(payload.clone()))
         .await?;
 
+    // A video whose poster rendered but whose duration could not be probed
+    // is usable, yet not complete.
+    let (job_state, status_reason) = match probe_issue {
+        Some(reason) => (JobState::CompletedWithIssues, reason.to_string()),
+        None => (JobState::Completed, "completed".to_string()),
+    };
     Ok(RunJobOutcome {
-        state: JobState::Completed,
-        status_reason: "completed".to_string(),
+        state: job_state,
+        status_reason,
+        output: Some(payload),
+        error_message: None,
+    })
+}
+
+/// The (workspace, block, original asset) a preview job derives tiers for.
+struct LoomPreviewTarget<'a> {
+    workspace_id: &'a str,
+    block_id: &'a str,
+    asset_id: &'a str,
+}
+
+/// Record an honest failure: every tier the source would have produced is
+/// marked `failed` with the stable reason (so it shows in the retry queue),
+/// the block preview flips to failed, and the job completes with issues.
+async fn finish_loom_preview_failed(
+    state: &AppState,
+    ctx: &crate::storage::WriteContext,
+    job: &AiJob,
+    target: &LoomPreviewTarget<'_>,
+    source: PreviewSource,
+    err: &PreviewError,
+    media_probe: Option<&VideoProbe>,
+) -> Result<RunJobOutcome, WorkflowError> {
+    let reason = err.reason();
+    for tier in source.derived_tiers() {
+        state
+            .storage
+            .upsert_media_tier(
+                ctx,
+                crate::storage::MediaTierUpsert {
+                    workspace_id: target.workspace_id.to_string(),
+                    asset_id: target.asset_id.to_string(),
+                    tier: *tier,
+                    status: crate::storage::MediaTierStatus::Failed,
+                    tier_asset_id: None,
+                    content_hash: None,
+                    failure_reason: Some(reason.to_string()),
+                },
+            )
+            .await?;
+    }
+
+    state
+        .storage
+        .set_loom_block_preview(
+            ctx,
+            target.workspace_id,
+            target.block_id,
+            crate::storage::PreviewStatus::Failed,
+            None,
+            None,
+        )
+        .await?;
+
+    let payload = json!({
+        "workspace_id": target.workspace_id,
+        "block_id": target.block_id,
+        "asset_id": target.asset_id,
+        "preview_status": "failed",
+        "source_kind": source.as_str(),
+        "reason": reason,
+        "detail": err.to_string(),
+        "media": media_probe,
+    });
+    state
+        .storage
+        .set_job_outputs(&job.job_id.to_string(), Some(payload.clone()))
+        .await?;
+
+    Ok(RunJobOutcome {
+        state: JobState::CompletedWithIssues,
+        status_reason: reason.to_string(),
         output: Some(payload),
         error_message: None,
     })
 }
 
+/// Write one rendered tier as a content-addressed derived asset (reusing an
+/// existing asset with the same hash) and return its asset id.
+async fn persist_loom_preview_tier(
+    state: &AppState,
+    ctx: &crate::storage::WriteContext,
+    handshake_root: &Path,
+    target: &LoomPreviewTarget<'_>,
+    original: &crate::storage::Asset,
+    rendered: &RenderedTier,
+) -> Result<String, WorkflowError> {
+    let tier_asset = match state
+        .storage
+        .find_asset_by_content_hash(target.workspace_id, &rendered.content_hash)
+        .await?
+    {
+        Some(existing) => existing,
+        None => {
+            state
+                .storage
+                .create_asset(
+                    ctx,
+                    crate::storage::NewAsset {
+                        workspace_id: target.workspace_id.to_string(),
+                        kind: rendered.asset_kind().to_string(),
+                        mime: "image/png".to_string(),
+                        original_filename: None,
+                        content_hash: rendered.content_hash.clone(),
+                        size_bytes: rendered.png.len() as i64,
+                        width: Some(rendered.width as i64),
+                        height: Some(rendered.height as i64),
+                        classification: "low".to_string(),
+                        exportable: true,
+                        is_proxy_of: Some(original.asset_id.clone()),
+                        proxy_asset_id: None,
+                    },
+                )
+                .await?
+        }
+    };
+
+    let tier_path = crate::loom_fs::loom_asset_blob_path(
+        handshake_root,
+        target.workspace_id,
+        rendered.asset_kind(),
+        rendered.content_hash.as_str(),
+    );
+    match crate::storage::artifacts::write_file_atomic(
+        handshake_root,
+        &tier_path,
+        &rendered.png,
+        false,
+    ) {
+        Ok(()) => {}
+        Err(crate::storage::artifacts::ArtifactError::Io(io_err))
+            if io_err.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(err) => return Err(WorkflowError::Terminal(err.to_string())),
+    }
+
+    Ok(tier_asset.asset_id)
+}
+
+/// The full-resolution image the tiers are cut from: decoded in-process for
+/// rasters and SVG, a tool-rendered PNG frame/page for AVIF, video and PDF.
+async fn loom_preview_source_image(
+    source: PreviewSource,
+    original_bytes: &[u8],
+    original_path: &Path,
+    tools: Option<&LoomPreviewTools>,
+    work_dir: &Path,
+    media_probe: Option<&VideoProbe>,
+) -> Result<image::DynamicImage, PreviewError> {
+    let (tool, args, output) = match source {
+        PreviewSource::Raster => return crate::loom_preview::decode_raster(original_bytes),
+        PreviewSource::Svg => {
+            return crate::loom_preview::rasterize_svg(
+                original_bytes,
+                crate::loom_preview::PREVIEW_MAX_PX,
+            )
+        }
+        PreviewSource::Unsupported => {
+            return Err(PreviewError::UnsupportedMime(source.as_str().to_string()))
+        }
+        PreviewSource::Avif | PreviewSource::Video => {
+            let frame = work_dir.join("frame.png");
+            let seek = crate::loom_preview::poster_seek_secs(
+                media_probe.and_then(|probe| probe.duration_ms),
+            );
+            let args = crate::loom_preview::ffmpeg_frame_args(original_path, &frame, seek);
+            (PreviewTool::Ffmpeg, args, frame)
+        }
+        PreviewSource::Pdf => {
+            let prefix = work_dir.join("page");
+            let args = crate::loom_preview::pdftoppm_args(original_path, &prefix);
+            (PreviewTool::Pdftoppm, args, prefix.with_extension("png"))
+        }
+    };
+    let tools = tools.ok_or(PreviewError::ToolMissing(tool))?;
+    tools.run(tool, args).await?;
+    let bytes = tokio::fs::read(&output)
+        .await
+        .map_err(|e| PreviewError::ToolFailed(tool, format!("produced no image: {e}")))?;
+    crate::loom_preview::decode_raster(&bytes)
+}
+
+const LOOM_PREVIEW_TOOL_ENGINE_ID: &str = "engine.media_preview";
+const LOOM_PREVIEW_TOOL_TIMEOUT_MS: u64 = 120_000;
+const LOOM_PREVIEW_TOOL_MAX_OUTPUT_BYTES: u64 = 5_000_000;
+
+/// Runs the preview pipeline's local tools through MEX (`engine.media_preview`)
+/// under the job's capability profile, so every invocation passes the gate
+/// pipeline and leaves stdout/stderr evidence like the media downloader does.
+struct LoomPreviewTools {
+    mex_runtime: MexRuntime,
+    artifact_root: PathBuf,
+    capability_profile_id: String,
+    managed_dirs: Vec<PathBuf>,
+}
+
+impl LoomPreviewTools {
+    fn new(state: &AppState, job: &AiJob, handshake_root: &Path) -> Result<Self, WorkflowError> {
+        let artifact_root = repo_root_for_artifacts()?;
+        Ok(Self {
+            mex_runtime: build_mex_runtime(state, &artifact_root)?,
+            artifact_root,
+            capability_profile_id: job.capability_profile_id.clone(),
+            // Windows installs provisioned by the media downloader reuse its
+            // pinned ffmpeg/ffprobe.
+            managed_dirs: vec![md_tools_root(handshake_root)
+                .join("ffmpeg")
+                .join(MD_FFMPEG_VERSION)],
+        })
+    }
+
+    async fn probe_video(&self, original_path: &Path) -> Result<VideoProbe, PreviewError> {
+        let stdout = self
+            .run(
+                PreviewTool::Ffprobe,
+                crate::loom_preview::ffprobe_args(original_path),
+            )
+            .await?;
+        crate::loom_preview::parse_ffprobe_json(&stdout)
+    }
+
+    /// Run `tool` and return its stdout. A missing binary, a gate denial and
+    /// a non-zero exit are all typed [`PreviewError`]s, never panics.
+    async fn run(&self, tool: PreviewTool, args: Vec<String>) -> Result<String, PreviewError> {
+        let tool_path = tool
+            .detect(&self.managed_dirs)
+            .ok_or(PreviewError::ToolMissing(tool))?;
+        let op = PlannedOperation {
+            schema_version: POE_SCHEMA_VERSION.to_string(),
+            op_id: Uuid::now_v7(),
+            engine_id: LOOM_PREVIEW_TOOL_ENGINE_ID.to_string(),
+            engine_version_req: None,
+            operation: tool.mex_operation().to_string(),
+            inputs: Vec::new(),
+            params: json!({
+                "tool_path": tool_path.to_string_lossy().to_string(),
+                "cwd": ".",
+                "timeout_ms": LOOM_PREVIEW_TOOL_TIMEOUT_MS,
+                "args": args,
+                "env": {},
+                "cancel_keys": [],
+            }),
+            capabilities_requested: vec![
+                tool.capability().to_string(),
+                "fs.write:artifacts".to_string(),
+            ],
+            capability_profile_id: Some(self.capability_profile_id.clone()),
+            human_consent_obtained: false,
+            budget: BudgetSpec {
+                cpu_time_ms: None,
+                wall_time_ms: Some(LOOM_PREVIEW_TOOL_TIMEOUT_MS),
+                memory_bytes: None,
+                output_bytes: Some(LOOM_PREVIEW_TOOL_MAX_OUTPUT_BYTES),
+            },
+            determinism: DeterminismLevel::D3,
+            evidence_policy: Some(EvidencePolicy {
+                required: true,
+                notes: Some("capture_stdout_stderr".to_string()),
+            }),
+            output_spec: OutputSpec {
+                expected_types: vec!["artifact.terminal_output".to_string()],
+                max_bytes: Some(LOOM_PREVIEW_TOOL_MAX_OUTPUT_BYTES),
+            },
+        };
+
+        let result = self
+            .mex_runtime
+            .execute(op)
+            .await
+            .map_err(|e| PreviewError::ToolFailed(tool, format!("MEX execute failed: {e}")))?;
+        let (_output_abs, stdout_abs, stderr_abs) =
+            md_terminal_output_paths(&self.artifact_root, &result)
+                .map_err(|e| PreviewError::ToolFailed(tool, e.to_string()))?;
+        if result.status != EngineStatus::Succeeded {
+            let stderr = fs::read_to_string(&stderr_abs).unwrap_or_default();
+            let last_line = stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or("tool exited with a non-zero status");
+            return Err(PreviewError::ToolFailed(
+                tool,
+                last_line.chars().take(300).collect(),
+            ));
+        }
+        fs::read_to_string(&stdout_abs).map_err(|e| PreviewError::ToolFailed(tool, e.to_string()))
+    }
+}
+
 // =============================================================================
 // Media Downloader (Spec §10.14)
 // =============================================================================
@@ -6923,6 +6923,7 @@ async fn atelier_share_pack_subset_manifest_includes_usage_readme_and_rejects_go
             selector: SharePackSubsetSelector {
                 include_sheet: true,
                 media_asset_ids: vec![selected.asset_id],
+                strip_gps: false,
             },
             usage_readme: SharePackUsageReadmeArtifact {
                 artifact_ref: readme_artifact.artifact_ref.clone(),
@@ -1832,6 +1832,7 @@ async fn mt077_share_pack_export_fixture_corpus_round_trips() {
             selector: SharePackSubsetSelector {
                 include_sheet: true,
                 media_asset_ids: selected_assets.iter().map(|a| a.asset_id).collect(),
+                strip_gps: false,
             },
             usage_readme: SharePackUsageReadmeArtifact {
                 artifact_ref: readme_artifact.artifact_ref.clone(),
@@ -0,0 +1,233 @@
+//! Embedded media metadata proof: clipboard import extracts EXIF GPS/camera
+//! and A1111 generation parameters into typed columns, metadata tag rules emit
+//! auto-tags, metadata search finds the asset, and XMP sidecar write-back
+//! carries Handshake tags and rating as a hidden sidecar asset.
+//!
+//! Uses live PostgreSQL/EventLedger only.
+
+mod atelier_pg_support;
+
+use handshake_core::atelier::media_metadata::{
+    extract_media_metadata, strip_gps_metadata, GenerationTool, MediaMetadataQuery,
+    MEDIA_METADATA_EXTRACTED, MEDIA_XMP_SIDECAR_WRITTEN, METADATA_RULE_TAG_SOURCE,
+};
+use handshake_core::atelier::search::{MatchType, NewTagRule};
+use handshake_core::atelier::{
+    AtelierStore, ClipboardImageImportRequest, MediaReviewMetadataUpdate, MediaSidecarRelationKind,
+};
+use uuid::Uuid;
+
+async fn connected_store(url: &str) -> AtelierStore {
+    let store = AtelierStore::connect(url)
+        .await
+        .expect("connect to PostgreSQL");
+    store.ensure_schema().await.expect("ensure atelier schema");
+    store
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+/// Big-endian EXIF: IFD0 {Model, GPS pointer}; GPS IFD at 35°30' N, 139°45' E.
+fn exif_with_model_and_gps(model: &str) -> Vec<u8> {
+    let mut model_bytes = model.as_bytes().to_vec();
+    model_bytes.push(0);
+    let ifd0_len = 2 + 2 * 12 + 4;
+    let model_offset = 8 + ifd0_len;
+    let gps_offset = model_offset + model_bytes.len() as u32;
+    let gps_len = 2 + 4 * 12 + 4;
+    let lat_offset = gps_offset + gps_len;
+    let lon_offset = lat_offset + 24;
+    let entry = |out: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: u32| {
+        out.extend_from_slice(&tag.to_be_bytes());
+        out.extend_from_slice(&field_type.to_be_bytes());
+        out.extend_from_slice(&count.to_be_bytes());
+        out.extend_from_slice(&value.to_be_bytes());
+    };
+
+    let mut out = b"MM\0*".to_vec();
+    out.extend_from_slice(&8u32.to_be_bytes());
+    out.extend_from_slice(&2u16.to_be_bytes());
+    entry(&mut out, 0x0110, 2, model_bytes.len() as u32, model_offset);
+    entry(&mut out, 0x8825, 4, 1, gps_offset);
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&model_bytes);
+    out.extend_from_slice(&4u16.to_be_bytes());
+    entry(&mut out, 0x0001, 2, 2, u32::from(b'N') << 24);
+    entry(&mut out, 0x0002, 5, 3, lat_offset);
+    entry(&mut out, 0x0003, 2, 2, u32::from(b'E') << 24);
+    entry(&mut out, 0x0004, 5, 3, lon_offset);
+    out.extend_from_slice(&0u32.to_be_bytes());
+    for value in [35u32, 30, 0, 139, 45, 0] {
+        out.extend_from_slice(&value.to_be_bytes());
+        out.extend_from_slice(&1u32.to_be_bytes());
+    }
+    out
+}
+
+fn png_with_exif_and_parameters(model: &str, parameters: &str) -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut out, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]);
+    png_chunk(&mut out, b"eXIf", &exif_with_model_and_gps(model));
+    let mut text = b"parameters\0".to_vec();
+    text.extend_from_slice(parameters.as_bytes());
+    png_chunk(&mut out, b"tEXt", &text);
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[tokio::test]
+async fn clipboard_import_extracts_metadata_auto_tags_and_writes_xmp_sidecar() {
+    let Some(url) = atelier_pg_support::database_url().await else {
+        eprintln!(
+            "SKIP clipboard_import_extracts_metadata_auto_tags_and_writes_xmp_sidecar: PostgreSQL unavailable"
+        );
+        return;
+    };
+    let store = connected_store(&url).await;
+    let marker = Uuid::new_v4().simple().to_string();
+    let model = format!("Camera {marker}");
+    let auto_tag = format!("shot-on-{marker}");
+    store
+        .create_tag_rule(&NewTagRule {
+            source_field_id: "media.camera_model".to_string(),
+            match_type: MatchType::Contains,
+            pattern: marker.clone(),
+            emit_tag: auto_tag.clone(),
+            enabled: true,
+        })
+        .await
+        .expect("create metadata tag rule");
+
+    let png = png_with_exif_and_parameters(
+        &model,
+        "lighthouse at night\nNegative prompt: text\nSteps: 24, Sampler: Euler a, CFG scale: 5, Seed: 77",
+    );
+    let artifact = atelier_pg_support::write_native_media_artifact(&png);
+    let record = store
+        .import_clipboard_image(&ClipboardImageImportRequest {
+            idempotency_key: format!("metadata-import-{marker}"),
+            mime: "image/png".to_string(),
+            content_hash: artifact.content_hash.clone(),
+            byte_len: artifact.byte_len,
+            artifact_ref: artifact.artifact_ref.clone(),
+            source_application: Some("system-clipboard".to_string()),
+            requested_by: "operator-metadata".to_string(),
+        })
+        .await
+        .expect("clipboard import");
+    let asset_id = record.asset_id.expect("import materialized an asset");
+
+    let stored = store
+        .get_media_metadata(asset_id)
+        .await
+        .expect("read metadata")
+        .expect("import extracted metadata");
+    let metadata = &stored.metadata;
+    assert_eq!(metadata.camera_model.as_deref(), Some(model.as_str()));
+    assert!((metadata.gps_latitude.expect("latitude") - 35.5).abs() < 1e-9);
+    assert!((metadata.gps_longitude.expect("longitude") - 139.75).abs() < 1e-9);
+    let generation = metadata.generation.as_ref().expect("generation parameters");
+    assert_eq!(generation.tool, GenerationTool::A1111);
+    assert_eq!(generation.prompt.as_deref(), Some("lighthouse at night"));
+    assert_eq!(generation.seed, Some(77));
+    assert_eq!(
+        store
+            .count_events_for_aggregate(
+                MEDIA_METADATA_EXTRACTED,
+                "atelier_media_metadata",
+                &asset_id.to_string(),
+            )
+            .await
+            .expect("count metadata events"),
+        1
+    );
+
+    let tags = store
+        .list_media_asset_tags(asset_id)
+        .await
+        .expect("list asset tags");
+    assert!(
+        tags.iter()
+            .any(|tag| tag.text == auto_tag && tag.source == METADATA_RULE_TAG_SOURCE),
+        "metadata tag rule emitted an auto-tag: {tags:?}"
+    );
+
+    let found = store
+        .search_media_metadata(&MediaMetadataQuery {
+            camera_model: Some(marker.clone()),
+            generator: Some(GenerationTool::A1111),
+            has_gps: Some(true),
+            ..Default::default()
+        })
+        .await
+        .expect("search metadata");
+    assert_eq!(
+        found.iter().map(|r| r.asset_id).collect::<Vec<_>>(),
+        vec![asset_id]
+    );
+
+    let stripped = strip_gps_metadata(&artifact.stored_payload)
+        .expect("strip gps")
+        .expect("payload carried gps");
+    assert!(!extract_media_metadata(&stripped).has_gps());
+
+    store
+        .tag_media_asset(asset_id, &format!("manual-{marker}"), "manual")
+        .await
+        .expect("manual tag");
+    store
+        .bulk_update_media_review_metadata(
+            &[MediaReviewMetadataUpdate {
+                asset_id,
+                favorite: true,
+                rating: 4,
+                frontpage: false,
+                carousel: false,
+                notes: None,
+                review_status: "approved".to_string(),
+            }],
+            "operator-metadata",
+        )
+        .await
+        .expect("rate asset");
+    let sidecar = store
+        .write_media_xmp_sidecar(asset_id, "operator-metadata")
+        .await
+        .expect("write xmp sidecar");
+    assert_eq!(sidecar.parent_asset_id, asset_id);
+    assert_eq!(sidecar.relation_kind, MediaSidecarRelationKind::XmpSidecar);
+    assert!(sidecar.hidden_from_gallery);
+    let sidecar_asset = store
+        .get_media_asset(sidecar.sidecar_asset_id)
+        .await
+        .expect("sidecar asset");
+    assert_eq!(sidecar_asset.mime, "application/rdf+xml");
+    assert_eq!(
+        store
+            .count_events_for_aggregate(
+                MEDIA_XMP_SIDECAR_WRITTEN,
+                "atelier_media_sidecar",
+                &sidecar.sidecar_id.to_string(),
+            )
+            .await
+            .expect("count sidecar events"),
+        1
+    );
+
+    let rewritten = store
+        .write_media_xmp_sidecar(asset_id, "operator-metadata")
+        .await
+        .expect("rewrite unchanged xmp sidecar");
+    assert_eq!(
+        rewritten.sidecar_asset_id, sidecar.sidecar_asset_id,
+        "unchanged tags and rating dedup onto the same sidecar asset"
+    );
+}
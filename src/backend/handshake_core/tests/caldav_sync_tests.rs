@@ -0,0 +1,685 @@
+//! CalDAV client and sync engine against an in-process CalDAV fixture.
+//!
+//! The fixture speaks just enough WebDAV/CalDAV (PROPFIND, sync-collection, calendar-multiget,
+//! conditional PUT/DELETE) to exercise the client. Set `HANDSHAKE_CALDAV_TEST_URL` (plus optional
+//! `HANDSHAKE_CALDAV_TEST_USER` / `HANDSHAKE_CALDAV_TEST_PASSWORD`) to also run against a real
+//! server such as Radicale; the sync-engine test additionally needs `POSTGRES_TEST_URL`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::Response;
+use axum::Router;
+use chrono::{Duration, TimeZone, Utc};
+use handshake_core::caldav::ical::resource_to_sync_events;
+use handshake_core::caldav::{
+    run_caldav_sync, CaldavClient, CaldavCredentials, CaldavError, CaldavSyncOptions,
+    CaldavSyncReport, WriteOutcome, WritePrecondition,
+};
+use handshake_core::storage::tests::postgres_backend_from_env;
+use handshake_core::storage::{
+    CalendarEvent, CalendarEventExportMode, CalendarEventUpsert, CalendarEventWindowQuery,
+    CalendarSource, CalendarSourceProviderType, CalendarSourceSyncState, CalendarSourceUpsert,
+    CalendarSourceWritePolicy, CalendarSyncConflictReason, CalendarSyncConflictStatus, Database,
+    NewWorkspace, StorageError, WriteContext,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+const CALENDAR: &str = "/dav/cal/";
+
+#[derive(Default)]
+struct FakeDav {
+    version: u64,
+    /// href → (etag, ics, version of last change)
+    objects: BTreeMap<String, (String, String, u64)>,
+    /// href → version it was deleted at
+    tombstones: BTreeMap<String, u64>,
+}
+
+impl FakeDav {
+    fn store(&mut self, href: &str, ics: &str) -> String {
+        self.version += 1;
+        let etag = format!("\"v{}\"", self.version);
+        self.tombstones.remove(href);
+        self.objects.insert(
+            href.to_string(),
+            (etag.clone(), ics.to_string(), self.version),
+        );
+        etag
+    }
+
+    fn remove(&mut self, href: &str) {
+        self.version += 1;
+        self.objects.remove(href);
+        self.tombstones.insert(href.to_string(), self.version);
+    }
+
+    fn token(&self) -> String {
+        format!("http://fake.test/sync/{}", self.version)
+    }
+}
+
+type Shared = Arc<Mutex<FakeDav>>;
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn tag_values<'a>(body: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+    body.split(open)
+        .skip(1)
+        .filter_map(|rest| rest.split(close).next())
+        .collect()
+}
+
+fn multistatus(body: String) -> Response {
+    Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(Body::from(format!(
+            r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">{body}</d:multistatus>"#
+        )))
+        .expect("response")
+}
+
+fn status(code: StatusCode) -> Response {
+    Response::builder()
+        .status(code)
+        .body(Body::empty())
+        .expect("response")
+}
+
+fn member_response(href: &str, etag: &str, data: Option<&str>) -> String {
+    let data = data
+        .map(|ics| format!("<c:calendar-data>{}</c:calendar-data>", xml_escape(ics)))
+        .unwrap_or_default();
+    format!(
+        "<d:response><d:href>{href}</d:href><d:propstat><d:prop><d:getetag>{}</d:getetag>{data}\
+</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        xml_escape(etag)
+    )
+}
+
+async fn handle(
+    State(dav): State<Shared>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let path = uri.path().to_string();
+    let mut dav = dav.lock().expect("fake dav lock");
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    match method.as_str() {
+        "PROPFIND" if path == CALENDAR => {
+            let collection = format!(
+                "<d:response><d:href>{CALENDAR}</d:href><d:propstat><d:prop>\
+<d:resourcetype><d:collection/><c:calendar/></d:resourcetype><d:displayname>Work</d:displayname>\
+<cs:getctag>ctag-{}</cs:getctag><d:sync-token>{}</d:sync-token>\
+<c:supported-calendar-component-set><c:comp name=\"VEVENT\"/></c:supported-calendar-component-set>\
+</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+                dav.version,
+                dav.token()
+            );
+            let members: String = if header("Depth").as_deref() == Some("1") {
+                dav.objects
+                    .iter()
+                    .map(|(href, (etag, _, _))| member_response(href, etag, None))
+                    .collect()
+            } else {
+                String::new()
+            };
+            multistatus(format!("{collection}{members}"))
+        }
+        "PROPFIND" => status(StatusCode::NOT_FOUND),
+        "REPORT" if body.contains("sync-collection") => {
+            let token = tag_values(&body, "<d:sync-token>", "</d:sync-token>")
+                .first()
+                .map(|t| t.trim().to_string())
+                .unwrap_or_default();
+            let since = if token.is_empty() {
+                0
+            } else {
+                match token
+                    .strip_prefix("http://fake.test/sync/")
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .filter(|v| *v <= dav.version)
+                {
+                    Some(v) => v,
+                    None => {
+                        return Response::builder()
+                            .status(StatusCode::FORBIDDEN)
+                            .body(Body::from(
+                                r#"<d:error xmlns:d="DAV:"><d:valid-sync-token/></d:error>"#,
+                            ))
+                            .expect("response")
+                    }
+                }
+            };
+            let mut body: String = dav
+                .objects
+                .iter()
+                .filter(|(_, (_, _, changed))| *changed > since)
+                .map(|(href, (etag, _, _))| member_response(href, etag, None))
+                .collect();
+            if since > 0 {
+                for (href, deleted) in &dav.tombstones {
+                    if *deleted > since {
+                        body.push_str(&format!(
+                            "<d:response><d:href>{href}</d:href>\
+<d:status>HTTP/1.1 404 Not Found</d:status></d:response>"
+                        ));
+                    }
+                }
+            }
+            body.push_str(&format!("<d:sync-token>{}</d:sync-token>", dav.token()));
+            multistatus(body)
+        }
+        "REPORT" if body.contains("calendar-multiget") => {
+            let body: String = tag_values(&body, "<d:href>", "</d:href>")
+                .into_iter()
+                .filter_map(|href| {
+                    dav.objects
+                        .get(href)
+                        .map(|(etag, ics, _)| member_response(href, etag, Some(ics)))
+                })
+                .collect();
+            multistatus(body)
+        }
+        "PUT" => {
+            let current = dav.objects.get(&path).map(|(etag, _, _)| etag.clone());
+            let create_only = header("If-None-Match").as_deref() == Some("*");
+            let if_match = header("If-Match");
+            if (create_only && current.is_some())
+                || if_match.is_some_and(|etag| current.as_deref() != Some(etag.as_str()))
+            {
+                return status(StatusCode::PRECONDITION_FAILED);
+            }
+            let etag = dav.store(&path, &body);
+            let code = if current.is_some() {
+                StatusCode::NO_CONTENT
+            } else {
+                StatusCode::CREATED
+            };
+            Response::builder()
+                .status(code)
+                .header("ETag", etag)
+                .body(Body::empty())
+                .expect("response")
+        }
+        "DELETE" => {
+            let current = dav.objects.get(&path).map(|(etag, _, _)| etag.clone());
+            match (current, header("If-Match")) {
+                (None, _) => status(StatusCode::NOT_FOUND),
+                (Some(etag), Some(wanted)) if etag != wanted => {
+                    status(StatusCode::PRECONDITION_FAILED)
+                }
+                _ => {
+                    dav.remove(&path);
+                    status(StatusCode::NO_CONTENT)
+                }
+            }
+        }
+        _ => status(StatusCode::METHOD_NOT_ALLOWED),
+    }
+}
+
+async fn spawn_fake_dav() -> (String, Shared) {
+    let dav: Shared = Arc::new(Mutex::new(FakeDav::default()));
+    let app = Router::new().fallback(handle).with_state(dav.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind fake caldav server");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app)
+            .await
+            .expect("fake caldav server");
+    });
+    (format!("http://{addr}{CALENDAR}"), dav)
+}
+
+fn vevent(uid: &str, summary: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\nUID:{uid}\r\n\
+DTSTAMP:20260101T000000Z\r\nDTSTART:20260301T090000Z\r\nDTEND:20260301T100000Z\r\n\
+SUMMARY:{summary}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+fn basic_client(url: &str) -> CaldavClient {
+    CaldavClient::new(
+        url,
+        CaldavCredentials::Basic {
+            username: "tester".into(),
+            password: "secret".into(),
+        },
+    )
+    .expect("caldav client")
+}
+
+#[tokio::test]
+async fn client_discovers_syncs_incrementally_and_guards_writes() {
+    let (url, dav) = spawn_fake_dav().await;
+    let first = dav
+        .lock()
+        .unwrap()
+        .store("/dav/cal/a.ics", &vevent("a", "Standup"));
+    let client = basic_client(&url);
+
+    let calendars = client.discover_calendars().await.expect("discover");
+    assert_eq!(calendars.len(), 1);
+    assert_eq!(calendars[0].href, CALENDAR);
+    assert_eq!(calendars[0].display_name.as_deref(), Some("Work"));
+    assert!(calendars[0].supports_events());
+
+    let initial = client
+        .sync_collection(CALENDAR, None)
+        .await
+        .expect("initial sync");
+    assert_eq!(
+        initial.changed,
+        vec![("/dav/cal/a.ics".to_string(), Some(first.clone()))]
+    );
+    let token = initial.sync_token.expect("sync token");
+
+    let objects = client
+        .multiget(CALENDAR, &["/dav/cal/a.ics".to_string()])
+        .await
+        .expect("multiget");
+    assert_eq!(objects.len(), 1);
+    let events = resource_to_sync_events(
+        &objects[0].href,
+        objects[0].etag.as_deref(),
+        &objects[0].ics,
+        "UTC",
+    )
+    .expect("parse fetched resource");
+    assert_eq!(events[0].title, "Standup");
+    assert_eq!(events[0].external_etag.as_deref(), Some(first.as_str()));
+
+    let created = client
+        .put(
+            "/dav/cal/b.ics",
+            vevent("b", "Review"),
+            WritePrecondition::Create,
+        )
+        .await
+        .expect("create");
+    let WriteOutcome::Stored { etag: Some(b_etag) } = created.clone() else {
+        panic!("create should store and return an etag: {created:?}");
+    };
+    let again = client
+        .put(
+            "/dav/cal/b.ics",
+            vevent("b", "Review"),
+            WritePrecondition::Create,
+        )
+        .await
+        .expect("second create");
+    assert_eq!(again, WriteOutcome::PreconditionFailed);
+
+    let delta = client
+        .sync_collection(CALENDAR, Some(&token))
+        .await
+        .expect("delta");
+    assert_eq!(
+        delta.changed,
+        vec![("/dav/cal/b.ics".to_string(), Some(b_etag.clone()))]
+    );
+    assert!(delta.removed.is_empty());
+    let token = delta.sync_token.expect("sync token");
+
+    dav.lock()
+        .unwrap()
+        .store("/dav/cal/b.ics", &vevent("b", "Review (moved)"));
+    let stale = client
+        .put(
+            "/dav/cal/b.ics",
+            vevent("b", "Mine"),
+            WritePrecondition::Match(b_etag),
+        )
+        .await
+        .expect("stale put");
+    assert_eq!(stale, WriteOutcome::PreconditionFailed);
+
+    let deleted = client
+        .delete("/dav/cal/a.ics", &first)
+        .await
+        .expect("delete");
+    assert!(matches!(deleted, WriteOutcome::Stored { .. }));
+    let delta = client
+        .sync_collection(CALENDAR, Some(&token))
+        .await
+        .expect("delta");
+    assert_eq!(delta.removed, vec!["/dav/cal/a.ics".to_string()]);
+
+    let invalid = client
+        .sync_collection(CALENDAR, Some("http://fake.test/sync/999"))
+        .await
+        .expect_err("unknown token");
+    assert!(matches!(invalid, CaldavError::InvalidSyncToken));
+}
+
+fn upsert_of(event: &CalendarEvent) -> CalendarEventUpsert {
+    CalendarEventUpsert {
+        id: event.id.clone(),
+        workspace_id: event.workspace_id.clone(),
+        source_id: event.source_id.clone(),
+        external_id: event.external_id.clone(),
+        external_etag: event.external_etag.clone(),
+        title: event.title.clone(),
+        description: event.description.clone(),
+        location: event.location.clone(),
+        start_ts_utc: event.start_ts_utc,
+        end_ts_utc: event.end_ts_utc,
+        start_local: event.start_local.clone(),
+        end_local: event.end_local.clone(),
+        tzid: event.tzid.clone(),
+        all_day: event.all_day,
+        was_floating: event.was_floating,
+        status: event.status.clone(),
+        visibility: event.visibility.clone(),
+        export_mode: event.export_mode.clone(),
+        rrule: event.rrule.clone(),
+        rdate: event.rdate.clone(),
+        exdate: event.exdate.clone(),
+        is_recurring: event.is_recurring,
+        series_id: event.series_id.clone(),
+        instance_key: event.instance_key.clone(),
+        is_override: event.is_override,
+        source_last_seen_at: event.source_last_seen_at,
+        attendees: event.attendees.clone(),
+        links: event.links.clone(),
+        provider_payload: event.provider_payload.clone(),
+    }
+}
+
+async fn events_of(db: &dyn Database, source: &CalendarSource) -> Vec<CalendarEvent> {
+    db.query_calendar_events(CalendarEventWindowQuery {
+        workspace_id: source.workspace_id.clone(),
+        window_start_utc: Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap(),
+        window_end_utc: Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap(),
+        source_ids: vec![source.id.clone()],
+    })
+    .await
+    .expect("query events")
+}
+
+/// Run one sync and persist the resulting sync state, as the `calendar_sync` engine does.
+async fn sync_once(
+    db: &dyn Database,
+    ctx: &WriteContext,
+    source: &CalendarSource,
+    client: &CaldavClient,
+) -> (CalendarSource, CaldavSyncReport) {
+    let report = run_caldav_sync(
+        db,
+        ctx,
+        source,
+        client,
+        CaldavSyncOptions { push_allowed: true },
+    )
+    .await
+    .expect("caldav sync");
+    let updated = db
+        .upsert_calendar_source(
+            ctx,
+            CalendarSourceUpsert {
+                id: source.id.clone(),
+                workspace_id: source.workspace_id.clone(),
+                display_name: source.display_name.clone(),
+                provider_type: source.provider_type.clone(),
+                write_policy: source.write_policy.clone(),
+                default_tzid: source.default_tzid.clone(),
+                auto_export: source.auto_export,
+                credentials_ref: source.credentials_ref.clone(),
+                provider_calendar_id: Some(report.calendar_href.clone()),
+                capability_profile_id: source.capability_profile_id.clone(),
+                config: source.config.clone(),
+                sync_state: CalendarSourceSyncState {
+                    sync_token: report.next_sync_token.clone(),
+                    last_synced_at: Some(Utc::now()),
+                    last_remote_watermark: report.ctag.clone(),
+                    ..source.sync_state.clone()
+                },
+            },
+        )
+        .await
+        .expect("persist sync state");
+    (updated, report)
+}
+
+#[tokio::test]
+async fn sync_records_conflict_instead_of_overwriting_and_applies_resolution() {
+    let db = match postgres_backend_from_env().await {
+        Ok(db) => db,
+        Err(StorageError::Validation(msg)) if msg.contains("POSTGRES_TEST_URL not set") => {
+            eprintln!("Skipping caldav sync conflict test: {msg}");
+            return;
+        }
+        Err(err) => panic!("failed to init postgres backend: {err:?}"),
+    };
+    let (url, dav) = spawn_fake_dav().await;
+    dav.lock()
+        .unwrap()
+        .store("/dav/cal/a.ics", &vevent("a", "Standup"));
+    let client = basic_client(&url);
+
+    let human = WriteContext::human(Some("calendar-user".into()));
+    let sync_ctx = WriteContext::ai(Some("calendar_sync".into()), Some(Uuid::now_v7()), None);
+    let workspace = db
+        .create_workspace(
+            &human,
+            NewWorkspace {
+                name: format!("caldav-ws-{}", Uuid::now_v7()),
+            },
+        )
+        .await
+        .expect("workspace");
+    let source = db
+        .upsert_calendar_source(
+            &human,
+            CalendarSourceUpsert {
+                id: format!("caldav:test:{}", Uuid::now_v7()),
+                workspace_id: workspace.id.clone(),
+                display_name: "CalDAV / Work".into(),
+                provider_type: CalendarSourceProviderType::Caldav,
+                write_policy: CalendarSourceWritePolicy::TwoWayMirror,
+                default_tzid: "UTC".into(),
+                auto_export: true,
+                credentials_ref: Some("caldav:test".into()),
+                provider_calendar_id: None,
+                capability_profile_id: None,
+                config: json!({"caldav_url": url}),
+                sync_state: CalendarSourceSyncState::default(),
+            },
+        )
+        .await
+        .expect("source");
+
+    // Initial pull.
+    let (source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert_eq!(report.pulled_upserts, 1);
+    assert_eq!(report.calendar_href, CALENDAR);
+    let pulled = events_of(db.as_ref(), &source).await;
+    assert_eq!(pulled.len(), 1);
+    assert_eq!(pulled[0].title, "Standup");
+
+    // A local-only edit is pushed with If-Match and the new ETag is stored.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let mut edit = upsert_of(&pulled[0]);
+    edit.title = "Standup (room 2)".into();
+    db.upsert_calendar_event(&human, edit)
+        .await
+        .expect("local edit");
+    let (source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert_eq!(report.pushed_updates, 1);
+    assert!(report.remote_write_attempted);
+    let remote_ics = dav.lock().unwrap().objects["/dav/cal/a.ics"].1.clone();
+    assert!(remote_ics.contains("SUMMARY:Standup (room 2)"));
+
+    // A new local event is created on the server.
+    let local = events_of(db.as_ref(), &source).await.remove(0);
+    let mut created = upsert_of(&local);
+    created.id = Uuid::now_v7().to_string();
+    created.external_id = None;
+    created.external_etag = None;
+    created.provider_payload = None;
+    created.title = "Planning".into();
+    created.start_ts_utc = local.start_ts_utc + Duration::hours(2);
+    created.end_ts_utc = local.end_ts_utc + Duration::hours(2);
+    created.export_mode = CalendarEventExportMode::FullExport;
+    db.upsert_calendar_event(&human, created)
+        .await
+        .expect("local create");
+    let (source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert_eq!(report.pushed_creates, 1);
+    assert_eq!(dav.lock().unwrap().objects.len(), 2);
+
+    // Both sides change the same event: a conflict is recorded and neither side is overwritten.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let remote_etag = dav
+        .lock()
+        .unwrap()
+        .store("/dav/cal/a.ics", &vevent("a", "Standup (cancelled?)"));
+    let local = events_of(db.as_ref(), &source)
+        .await
+        .into_iter()
+        .find(|e| e.external_id.as_deref() == Some("a"))
+        .expect("local a");
+    let mut edit = upsert_of(&local);
+    edit.title = "Standup (moved to 10:00)".into();
+    db.upsert_calendar_event(&human, edit)
+        .await
+        .expect("local edit");
+    let (source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert_eq!(report.conflicts_recorded.len(), 1);
+    assert_eq!(report.open_conflicts, 1);
+    assert_eq!(report.pushed_updates, 0);
+    let conflicts = db
+        .list_calendar_sync_conflicts(&source.workspace_id, &source.id)
+        .await
+        .expect("conflicts");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].reason,
+        CalendarSyncConflictReason::BothModified
+    );
+    assert_eq!(
+        conflicts[0].remote_etag.as_deref(),
+        Some(remote_etag.as_str())
+    );
+    let local = events_of(db.as_ref(), &source)
+        .await
+        .into_iter()
+        .find(|e| e.external_id.as_deref() == Some("a"))
+        .expect("local a");
+    assert_eq!(local.title, "Standup (moved to 10:00)");
+    assert!(dav.lock().unwrap().objects["/dav/cal/a.ics"]
+        .1
+        .contains("Standup (cancelled?)"));
+
+    // While open, further syncs leave the event alone.
+    let (source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert!(report.conflicts_recorded.is_empty());
+    assert_eq!(report.open_conflicts, 1);
+
+    // Keeping the local side pushes it over the remote version the conflict captured.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let resolved = db
+        .resolve_calendar_sync_conflict(
+            &human,
+            &source.workspace_id,
+            &conflicts[0].id,
+            CalendarSyncConflictStatus::ResolvedKeepLocal,
+        )
+        .await
+        .expect("resolve");
+    assert_eq!(
+        resolved.status,
+        CalendarSyncConflictStatus::ResolvedKeepLocal
+    );
+    let (_source, report) = sync_once(db.as_ref(), &sync_ctx, &source, &client).await;
+    assert_eq!(report.pushed_updates, 1);
+    assert_eq!(report.open_conflicts, 0);
+    assert!(dav.lock().unwrap().objects["/dav/cal/a.ics"]
+        .1
+        .contains("SUMMARY:Standup (moved to 10:00)"));
+}
+
+/// Round trip against a real CalDAV server (e.g. `radicale --storage-filesystem-folder <tmp>`).
+#[tokio::test]
+async fn real_server_round_trip_when_configured() {
+    let Ok(url) = std::env::var("HANDSHAKE_CALDAV_TEST_URL") else {
+        eprintln!("Skipping real CalDAV round trip: HANDSHAKE_CALDAV_TEST_URL not set");
+        return;
+    };
+    let credentials = CaldavCredentials::Basic {
+        username: std::env::var("HANDSHAKE_CALDAV_TEST_USER").unwrap_or_else(|_| "test".into()),
+        password: std::env::var("HANDSHAKE_CALDAV_TEST_PASSWORD").unwrap_or_default(),
+    };
+    let client = CaldavClient::new(&url, credentials).expect("client");
+    let calendar = client
+        .discover_calendars()
+        .await
+        .expect("discover")
+        .into_iter()
+        .find(|c| c.supports_events())
+        .expect("a VEVENT calendar at HANDSHAKE_CALDAV_TEST_URL");
+
+    let uid = format!("handshake-test-{}", Uuid::now_v7());
+    let href = format!("{}/{uid}.ics", calendar.href.trim_end_matches('/'));
+    let before = client
+        .sync_collection(&calendar.href, None)
+        .await
+        .expect("sync");
+    let token = before.sync_token.expect("sync token");
+
+    let WriteOutcome::Stored { etag } = client
+        .put(
+            &href,
+            vevent(&uid, "Handshake round trip"),
+            WritePrecondition::Create,
+        )
+        .await
+        .expect("create")
+    else {
+        panic!("create was refused");
+    };
+    let delta = client
+        .sync_collection(&calendar.href, Some(&token))
+        .await
+        .expect("delta");
+    assert!(delta.changed.iter().any(|(h, _)| *h == href));
+    let fetched = client
+        .multiget(&calendar.href, &[href.clone()])
+        .await
+        .expect("multiget");
+    assert!(fetched[0].ics.contains("Handshake round trip"));
+
+    let etag = etag.or(fetched[0].etag.clone()).expect("etag");
+    let stale = client
+        .put(
+            &href,
+            vevent(&uid, "stale"),
+            WritePrecondition::Match("\"stale\"".into()),
+        )
+        .await
+        .expect("stale put");
+    assert_eq!(stale, WriteOutcome::PreconditionFailed);
+    let deleted = client.delete(&href, &etag).await.expect("delete");
+    assert!(matches!(deleted, WriteOutcome::Stored { .. }));
+}
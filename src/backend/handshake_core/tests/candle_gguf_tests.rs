@@ -0,0 +1,534 @@
+#![cfg(feature = "candle-runtime-engine")]
+
+use std::{collections::HashMap, fs, path::Path};
+
+use candle_core::{
+    quantized::{gguf_file, GgmlDType, QTensor},
+    DType, Device, Tensor,
+};
+use candle_nn::{VarBuilder, VarMap};
+use candle_transformers::models::llama::Config as LlamaRuntimeConfig;
+use futures::StreamExt;
+use handshake_core::model_runtime::{
+    candle::{
+        adapter::{candle_quantized_capabilities, CandleRuntime},
+        read_gguf_metadata,
+        transformer::{CandleDecoderModel, CandleLlamaModel, TransformerModel},
+        CandleSteeringHooks, DecoderArchitecture, DecoderConfig,
+    },
+    CancellationToken, GenPrompt, GenerateRequest, KvCachePolicy, KvQuantSupport, LoadSpec,
+    ModelCapabilities, ModelId, ModelRuntime, ProviderKind, RuntimeKind, SamplingParams,
+    CANDLE_LOCAL_ENGINE_ORIGIN,
+};
+use sha2::{Digest, Sha256};
+
+// Q8_0 blocks are 32 wide, so every matmul input width is a multiple of 32.
+const HIDDEN: usize = 32;
+const INTERMEDIATE: usize = 64;
+const VOCAB: usize = 32;
+// 2^-20: survives the f32 round trip through the GGUF header exactly.
+const RMS_EPS: f64 = 9.5367431640625e-07;
+const TOKENS: [u32; 5] = [1, 14, 5, 9, 3];
+
+#[test]
+fn gguf_llama_matches_full_precision_llama_on_the_same_weights() {
+    let varmap = VarMap::new();
+    let mut reference = CandleLlamaModel::from_varbuilder(
+        tiny_llama_config(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .expect("tiny llama builds");
+    let tempdir = tempfile::tempdir().unwrap();
+    let artifact = tempdir.path().join("model.gguf");
+    let config = decoder_config(DecoderArchitecture::Llama);
+    write_gguf(
+        &artifact,
+        &config,
+        &varmap_tensors(&varmap),
+        GgmlDType::F32,
+        &[],
+    );
+
+    let metadata = read_gguf_metadata(&artifact).expect("header parses");
+    assert_eq!(metadata.config, config);
+    assert_eq!(metadata.weight_types.get("f32"), Some(&(3 + 2 * 9)));
+    let mut quantized = CandleDecoderModel::load_gguf_for_model(
+        ModelId::new_v7(),
+        &artifact,
+        &metadata,
+        &Device::Cpu,
+    )
+    .expect("gguf llama loads");
+
+    assert_close(
+        &full_logits(&mut reference),
+        &full_logits(&mut quantized),
+        1e-4,
+    );
+}
+
+#[test]
+fn gguf_qwen2_gemma_phi3_match_the_safetensors_decoder() {
+    for architecture in [
+        DecoderArchitecture::Qwen2,
+        DecoderArchitecture::Gemma,
+        DecoderArchitecture::Phi3,
+    ] {
+        let config = decoder_config(architecture);
+        let varmap = VarMap::new();
+        let mut reference = CandleDecoderModel::from_varbuilder_for_model(
+            ModelId::new_v7(),
+            config.clone(),
+            VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+            &Device::Cpu,
+        )
+        .expect("tiny decoder builds");
+        let tempdir = tempfile::tempdir().unwrap();
+        let artifact = tempdir.path().join("model.gguf");
+        write_gguf(
+            &artifact,
+            &config,
+            &varmap_tensors(&varmap),
+            GgmlDType::F32,
+            &[],
+        );
+
+        let metadata = read_gguf_metadata(&artifact).expect("header parses");
+        assert_eq!(metadata.config, config, "{}", architecture.as_str());
+        let mut quantized = CandleDecoderModel::load_gguf_for_model(
+            ModelId::new_v7(),
+            &artifact,
+            &metadata,
+            &Device::Cpu,
+        )
+        .expect("gguf decoder loads");
+
+        assert_close(
+            &full_logits(&mut reference),
+            &full_logits(&mut quantized),
+            1e-4,
+        );
+    }
+}
+
+#[test]
+fn gguf_q8_0_weights_track_full_precision_logits() {
+    let config = decoder_config(DecoderArchitecture::Qwen2);
+    let varmap = VarMap::new();
+    let mut reference = CandleDecoderModel::from_varbuilder_for_model(
+        ModelId::new_v7(),
+        config.clone(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    let artifact = tempdir.path().join("model-q8_0.gguf");
+    write_gguf(
+        &artifact,
+        &config,
+        &varmap_tensors(&varmap),
+        GgmlDType::Q8_0,
+        &[],
+    );
+
+    let metadata = read_gguf_metadata(&artifact).unwrap();
+    assert!(metadata
+        .weight_types
+        .get("q8_0")
+        .is_some_and(|count| *count > 0));
+    let mut quantized = CandleDecoderModel::load_gguf_for_model(
+        ModelId::new_v7(),
+        &artifact,
+        &metadata,
+        &Device::Cpu,
+    )
+    .unwrap();
+
+    let expected = full_logits(&mut reference);
+    let actual = full_logits(&mut quantized);
+    let dot: f32 = expected.iter().zip(&actual).map(|(a, b)| a * b).sum();
+    let norm = |values: &[f32]| values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let cosine = dot / (norm(&expected) * norm(&actual));
+    assert!(cosine > 0.99, "Q8_0 logits drifted: cosine {cosine}");
+}
+
+#[tokio::test]
+async fn candle_runtime_loads_gguf_with_header_tokenizer_and_chat_template() {
+    let config = decoder_config(DecoderArchitecture::Llama);
+    let varmap = VarMap::new();
+    CandleLlamaModel::from_varbuilder(
+        tiny_llama_config(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .unwrap();
+    let tempdir = tempfile::tempdir().unwrap();
+    let artifact = tempdir.path().join("model-q8_0.gguf");
+    let template = "{% for message in messages %}{{ message['content'] }}{% endfor %}";
+    write_gguf(
+        &artifact,
+        &config,
+        &varmap_tensors(&varmap),
+        GgmlDType::Q8_0,
+        &spm_tokenizer_metadata(template),
+    );
+
+    let mut runtime = CandleRuntime::default();
+    let spec = load_spec(&artifact);
+    let declared = spec.declared_capabilities.clone();
+    let id = runtime
+        .load(spec)
+        .await
+        .expect("gguf loads through runtime");
+
+    assert_eq!(
+        runtime.capabilities(id).unwrap(),
+        &candle_quantized_capabilities(&declared)
+    );
+    assert_eq!(
+        runtime.chat_template(id).unwrap().as_deref(),
+        Some(template)
+    );
+    assert_eq!(runtime.tokenizer_cache_len(), 1);
+
+    let score = runtime.score(id, TOKENS.to_vec()).await.unwrap();
+    assert_eq!(score.token_logprobs.len(), TOKENS.len() - 1);
+    assert!(score
+        .token_logprobs
+        .iter()
+        .all(|logprob| logprob.is_finite() && *logprob <= 0.0));
+
+    let mut stream = runtime.generate(GenerateRequest {
+        id,
+        prompt: GenPrompt::from("hello"),
+        sampling: SamplingParams {
+            temperature: Some(0.0),
+            seed: Some(7),
+            ..SamplingParams::default()
+        },
+        lora_overrides: Vec::new(),
+        steering_overrides: Vec::new(),
+        kv_prefix_handle: None,
+        cancel: CancellationToken::new(),
+        max_tokens: 2,
+        stop_sequences: Vec::new(),
+        speculative_mode: None,
+        structured_decoding: None,
+    });
+    let first = stream.next().await.expect("stream emits token");
+    assert!(first.is_ok(), "{first:?}");
+}
+
+#[tokio::test]
+async fn candle_runtime_rejects_gguf_architectures_it_cannot_run() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let artifact = tempdir.path().join("falcon.gguf");
+    let architecture = gguf_file::Value::String("falcon".to_string());
+    let mut file = fs::File::create(&artifact).unwrap();
+    gguf_file::write(&mut file, &[("general.architecture", &architecture)], &[]).unwrap();
+    drop(file);
+
+    let error = CandleRuntime::default()
+        .load(load_spec(&artifact))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("falcon"), "{error}");
+}
+
+fn tiny_llama_config() -> LlamaRuntimeConfig {
+    LlamaRuntimeConfig {
+        hidden_size: HIDDEN,
+        intermediate_size: INTERMEDIATE,
+        vocab_size: VOCAB,
+        num_hidden_layers: 2,
+        num_attention_heads: 4,
+        num_key_value_heads: 2,
+        use_flash_attn: false,
+        rms_norm_eps: RMS_EPS,
+        rope_theta: 10_000.0,
+        bos_token_id: None,
+        eos_token_id: None,
+        rope_scaling: None,
+        max_position_embeddings: 64,
+        tie_word_embeddings: false,
+    }
+}
+
+fn decoder_config(architecture: DecoderArchitecture) -> DecoderConfig {
+    DecoderConfig {
+        architecture,
+        vocab_size: VOCAB,
+        hidden_size: HIDDEN,
+        intermediate_size: INTERMEDIATE,
+        num_hidden_layers: 2,
+        num_attention_heads: 4,
+        num_key_value_heads: 2,
+        head_dim: HIDDEN / 4,
+        max_position_embeddings: 64,
+        rms_norm_eps: RMS_EPS,
+        rope_theta: 10_000.0,
+        attention_bias: architecture == DecoderArchitecture::Qwen2,
+        tie_word_embeddings: architecture == DecoderArchitecture::Gemma,
+        sliding_window: None,
+        rope_freq_factors: None,
+    }
+}
+
+fn varmap_tensors(varmap: &VarMap) -> HashMap<String, Tensor> {
+    varmap
+        .data()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, var)| (name.clone(), var.as_tensor().clone()))
+        .collect()
+}
+
+/// Converts HF-named weights to llama.cpp names the way convert_hf_to_gguf
+/// does: Llama q/k rows are permuted for interleaved RoPE, Gemma norms get
+/// their `1 +` folded in.
+fn write_gguf(
+    path: &Path,
+    config: &DecoderConfig,
+    weights: &HashMap<String, Tensor>,
+    matrix_dtype: GgmlDType,
+    extra_metadata: &[(String, gguf_file::Value)],
+) {
+    let arch = config.architecture.as_str();
+    let mut tensors = Vec::new();
+    for (name, tensor) in weights {
+        let gguf_name = gguf_tensor_name(name);
+        let mut tensor = tensor.clone();
+        if config.architecture == DecoderArchitecture::Llama {
+            if name.ends_with("q_proj.weight") {
+                tensor = permute_rotary_rows(&tensor, config.num_attention_heads);
+            } else if name.ends_with("k_proj.weight") {
+                tensor = permute_rotary_rows(&tensor, config.num_key_value_heads);
+            }
+        }
+        if config.architecture == DecoderArchitecture::Gemma && gguf_name.ends_with("norm.weight") {
+            tensor = (tensor + 1.0).unwrap();
+        }
+        let dtype = if tensor.rank() == 2 {
+            matrix_dtype
+        } else {
+            GgmlDType::F32
+        };
+        tensors.push((gguf_name, QTensor::quantize(&tensor, dtype).unwrap()));
+    }
+    tensors.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut metadata = vec![
+        (
+            "general.architecture".to_string(),
+            gguf_file::Value::String(arch.to_string()),
+        ),
+        u32_key(arch, "block_count", config.num_hidden_layers),
+        u32_key(arch, "embedding_length", config.hidden_size),
+        u32_key(arch, "feed_forward_length", config.intermediate_size),
+        u32_key(arch, "attention.head_count", config.num_attention_heads),
+        u32_key(arch, "attention.head_count_kv", config.num_key_value_heads),
+        u32_key(arch, "attention.key_length", config.head_dim),
+        u32_key(arch, "rope.dimension_count", config.head_dim),
+        u32_key(arch, "context_length", config.max_position_embeddings),
+        (
+            format!("{arch}.attention.layer_norm_rms_epsilon"),
+            gguf_file::Value::F32(config.rms_norm_eps as f32),
+        ),
+        (
+            format!("{arch}.rope.freq_base"),
+            gguf_file::Value::F32(config.rope_theta),
+        ),
+    ];
+    metadata.extend(extra_metadata.iter().cloned());
+
+    let metadata_refs = metadata
+        .iter()
+        .map(|(key, value)| (key.as_str(), value))
+        .collect::<Vec<_>>();
+    let tensor_refs = tensors
+        .iter()
+        .map(|(name, tensor)| (name.as_str(), tensor))
+        .collect::<Vec<_>>();
+    let mut file = fs::File::create(path).unwrap();
+    gguf_file::write(&mut file, &metadata_refs, &tensor_refs).unwrap();
+}
+
+fn u32_key(arch: &str, key: &str, value: usize) -> (String, gguf_file::Value) {
+    (format!("{arch}.{key}"), gguf_file::Value::U32(value as u32))
+}
+
+fn gguf_tensor_name(name: &str) -> String {
+    match name {
+        "model.embed_tokens.weight" => return "token_embd.weight".to_string(),
+        "model.norm.weight" => return "output_norm.weight".to_string(),
+        "lm_head.weight" => return "output.weight".to_string(),
+        _ => {}
+    }
+    let rest = name
+        .strip_prefix("model.layers.")
+        .unwrap_or_else(|| panic!("unexpected tensor {name}"));
+    let (index, rest) = rest.split_once('.').unwrap();
+    let (module, suffix) = rest.rsplit_once('.').unwrap();
+    let gguf_module = match module {
+        "input_layernorm" => "attn_norm",
+        "post_attention_layernorm" => "ffn_norm",
+        "self_attn.q_proj" => "attn_q",
+        "self_attn.k_proj" => "attn_k",
+        "self_attn.v_proj" => "attn_v",
+        "self_attn.qkv_proj" => "attn_qkv",
+        "self_attn.o_proj" => "attn_output",
+        "mlp.gate_proj" => "ffn_gate",
+        "mlp.up_proj" | "mlp.gate_up_proj" => "ffn_up",
+        "mlp.down_proj" => "ffn_down",
+        other => panic!("unexpected module {other}"),
+    };
+    format!("blk.{index}.{gguf_module}.{suffix}")
+}
+
+/// llama.cpp's `permute`: within each head, rows `[0..d/2)` and `[d/2..d)`
+/// are interleaved so RoPE rotates adjacent pairs.
+fn permute_rotary_rows(weight: &Tensor, heads: usize) -> Tensor {
+    let (rows, cols) = weight.dims2().unwrap();
+    weight
+        .reshape((heads, 2, rows / heads / 2, cols))
+        .and_then(|tensor| tensor.transpose(1, 2))
+        .and_then(|tensor| tensor.contiguous())
+        .and_then(|tensor| tensor.reshape((rows, cols)))
+        .unwrap()
+}
+
+/// SentencePiece vocabulary padded to `VOCAB` so it lines up with token_embd.
+fn spm_tokenizer_metadata(chat_template: &str) -> Vec<(String, gguf_file::Value)> {
+    let mut tokens = [
+        "<unk>",
+        "<s>",
+        "</s>",
+        "\u{2581}",
+        "h",
+        "e",
+        "l",
+        "o",
+        "\u{2581}h",
+        "he",
+        "ll",
+        "lo",
+        "\u{2581}he",
+        "llo",
+        "\u{2581}hello",
+    ]
+    .iter()
+    .map(|token| token.to_string())
+    .collect::<Vec<_>>();
+    tokens.extend(
+        ('a'..='z')
+            .filter(|c| !"helo".contains(*c))
+            .map(String::from),
+    );
+    tokens.truncate(VOCAB);
+    let token_types = (0..tokens.len())
+        .map(|id| match id {
+            0 => 2,
+            1 | 2 => 3,
+            _ => 1,
+        })
+        .map(gguf_file::Value::I32)
+        .collect();
+    let scores = (0..tokens.len())
+        .map(|id| gguf_file::Value::F32(-(id as f32)))
+        .collect();
+    vec![
+        (
+            "tokenizer.ggml.model".to_string(),
+            gguf_file::Value::String("llama".to_string()),
+        ),
+        (
+            "tokenizer.ggml.tokens".to_string(),
+            gguf_file::Value::Array(tokens.into_iter().map(gguf_file::Value::String).collect()),
+        ),
+        (
+            "tokenizer.ggml.scores".to_string(),
+            gguf_file::Value::Array(scores),
+        ),
+        (
+            "tokenizer.ggml.token_type".to_string(),
+            gguf_file::Value::Array(token_types),
+        ),
+        (
+            "tokenizer.ggml.bos_token_id".to_string(),
+            gguf_file::Value::U32(1),
+        ),
+        (
+            "tokenizer.ggml.eos_token_id".to_string(),
+            gguf_file::Value::U32(2),
+        ),
+        (
+            "tokenizer.ggml.unknown_token_id".to_string(),
+            gguf_file::Value::U32(0),
+        ),
+        (
+            "tokenizer.chat_template".to_string(),
+            gguf_file::Value::String(chat_template.to_string()),
+        ),
+    ]
+}
+
+fn full_logits(model: &mut dyn TransformerModel) -> Vec<f32> {
+    let input = Tensor::new(&TOKENS, &Device::Cpu)
+        .and_then(|tensor| tensor.reshape((1, TOKENS.len())))
+        .unwrap();
+    let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), HIDDEN);
+    model
+        .forward_full_logits(&input, &hooks, &[], &[])
+        .and_then(|logits| {
+            logits
+                .flatten_all()
+                .and_then(|flat| flat.to_vec1::<f32>())
+                .map_err(|error| {
+                    handshake_core::model_runtime::ModelRuntimeError::GenerateError(
+                        error.to_string(),
+                    )
+                })
+        })
+        .expect("full logits")
+}
+
+fn assert_close(expected: &[f32], actual: &[f32], tolerance: f32) {
+    assert_eq!(expected.len(), actual.len());
+    let max_diff = expected
+        .iter()
+        .zip(actual)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0_f32, f32::max);
+    assert!(max_diff < tolerance, "max logit diff {max_diff}");
+}
+
+fn load_spec(artifact_path: &Path) -> LoadSpec {
+    LoadSpec {
+        artifact_path: artifact_path.to_path_buf(),
+        sha256_expected: sha256_file(artifact_path),
+        runtime_kind: RuntimeKind::Candle,
+        sampling_defaults: SamplingParams::default(),
+        kv_cache_policy: KvCachePolicy::Default {
+            quant: KvQuantSupport::Q4,
+            prefix_cache_ttl_seconds: 0,
+            max_bytes: None,
+        },
+        declared_capabilities: ModelCapabilities {
+            supports_activation_steering: true,
+            ..ModelCapabilities::default()
+        },
+        provider: ProviderKind::Local,
+        engine_origin: Some(CANDLE_LOCAL_ENGINE_ORIGIN.to_string()),
+        external_engine_import: None,
+    }
+}
+
+fn sha256_file(path: &Path) -> String {
+    let bytes = fs::read(path).expect("read model artifact");
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
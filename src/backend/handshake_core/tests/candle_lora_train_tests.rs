@@ -0,0 +1,397 @@
+#![cfg(feature = "candle-runtime-engine")]
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::{VarBuilder, VarMap};
+use candle_transformers::models::llama::Config as LlamaRuntimeConfig;
+use handshake_core::distillation::{
+    candle_trainer::CandleLoraTrainerExecutor,
+    content_review::ContentReviewConfig,
+    corpus_extractor::{TrainingCorpus, TrainingTurn},
+    peft_pipeline::{
+        distill_with_flight_recorder, read_training_log, DistillJobConfig, PeftHyperparams,
+        PeftProvenanceSidecar, TeacherSource, TrainingLogEntry, TRAINING_LOG_FILE_NAME,
+    },
+};
+use handshake_core::flight_recorder::{
+    EventFilter, FlightRecorder, FlightRecorderEvent, FlightRecorderEventType, RecorderError,
+};
+use handshake_core::model_runtime::{
+    candle::{
+        lora_train::{
+            train_lora, LoraTrainingConfig, LoraTrainingExample, PEFT_ADAPTER_CONFIG_FILE,
+            PEFT_ADAPTER_WEIGHTS_FILE,
+        },
+        transformer::{CandleLlamaModel, TransformerModel},
+        CandleSteeringHooks,
+    },
+    BaseModelTag, LicenseTag, LoraDescriptor, LoraId, LoraStackOps, LoraStrength, ModelId,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn candle_lora_trainer_reduces_loss_and_writes_mountable_adapter() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let varmap = VarMap::new();
+    let mut model = CandleLlamaModel::from_varbuilder(
+        tiny_llama_config(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .expect("tiny llama builds");
+    let examples = vec![
+        LoraTrainingExample {
+            prompt_ids: vec![1, 2, 3],
+            completion_ids: vec![4, 5, 6],
+        },
+        LoraTrainingExample {
+            prompt_ids: vec![7, 8],
+            completion_ids: vec![9, 10, 11],
+        },
+    ];
+    let base_loss = completion_loss(&mut model, &examples);
+    let config = LoraTrainingConfig {
+        rank: 4,
+        alpha: 8.0,
+        dropout: 0.0,
+        learning_rate: 5e-2,
+        epochs: 40,
+        gradient_accumulation_steps: 2,
+        target_modules: vec![
+            "q_proj".to_string(),
+            "v_proj".to_string(),
+            "down_proj".to_string(),
+        ],
+        checkpoint_every_steps: Some(20),
+        ..LoraTrainingConfig::default()
+    };
+
+    let report = train_lora(&mut model, &examples, &config, tempdir.path()).expect("training runs");
+
+    assert_eq!(
+        report.steps.len(),
+        40,
+        "one optimizer step per epoch of two examples"
+    );
+    assert_eq!(report.steps[0].tokens, 6);
+    assert_eq!(report.target_modules.len(), 6);
+    let first = report.steps[0].loss;
+    let last = report.final_loss().unwrap();
+    assert!(
+        last < first * 0.5,
+        "loss should fall: first={first}, last={last}"
+    );
+    assert_eq!(
+        report
+            .checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.step)
+            .collect::<Vec<_>>(),
+        vec![20, 40]
+    );
+    for checkpoint in &report.checkpoints {
+        assert!(checkpoint.dir.join(PEFT_ADAPTER_WEIGHTS_FILE).is_file());
+        assert!(checkpoint.dir.join(PEFT_ADAPTER_CONFIG_FILE).is_file());
+    }
+    assert_eq!(
+        completion_loss(&mut model, &examples),
+        base_loss,
+        "trainable adapter is detached once training returns"
+    );
+
+    let stack = model.candle_lora_stack().unwrap().clone();
+    stack
+        .mount(
+            LoraDescriptor {
+                id: LoraId::new_v7(),
+                artifact_path: report.adapter_path.clone(),
+                sha256: sha256_bytes(&report.adapter_path),
+                rank: 4,
+                target_modules: report.target_modules.clone(),
+                base_model_compat: BaseModelTag::new(stack.base_model_tag()),
+                license_tag: LicenseTag::new("MIT"),
+            },
+            LoraStrength::try_new(1.0).unwrap(),
+        )
+        .await
+        .expect("trained adapter mounts as a PEFT LoRA");
+    let mounted_loss = completion_loss(&mut model, &examples);
+    assert!(
+        mounted_loss < base_loss * 0.5,
+        "mounted adapter reproduces training: base={base_loss}, mounted={mounted_loss}"
+    );
+}
+
+#[test]
+fn candle_lora_trainer_rejects_unknown_target_modules() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let varmap = VarMap::new();
+    let mut model = CandleLlamaModel::from_varbuilder(
+        tiny_llama_config(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .unwrap();
+    let config = LoraTrainingConfig {
+        target_modules: vec!["qkv_proj".to_string()],
+        ..LoraTrainingConfig::default()
+    };
+    let error = train_lora(
+        &mut model,
+        &[LoraTrainingExample {
+            prompt_ids: vec![1],
+            completion_ids: vec![2, 3],
+        }],
+        &config,
+        tempdir.path(),
+    )
+    .expect_err("unknown target rejects");
+    assert!(error.to_string().contains("qkv_proj"), "{error}");
+}
+
+#[tokio::test]
+async fn candle_lora_trainer_executor_distills_reviewed_corpus_with_loss_curve_events() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let student_dir = tempdir.path().join("student");
+    write_tiny_student(&student_dir);
+    let corpus = TrainingCorpus {
+        session_id: "s".to_string(),
+        turns: vec![
+            turn("t1", "red apple ?", "fruit"),
+            turn("t2", "blue sky ?", "air"),
+            turn("t3", "green frog ?", "animal"),
+        ],
+    };
+    let config = DistillJobConfig {
+        teacher_model_path: tempdir.path().join("teacher"),
+        student_base_model_path: student_dir.clone(),
+        output_lora_dir: tempdir.path().join("lora"),
+        corpus_jsonl_path: tempdir.path().join("corpus.jsonl"),
+        hyperparams: PeftHyperparams {
+            rank: 2,
+            alpha: 4.0,
+            dropout: 0.0,
+            epochs: 4,
+            learning_rate: 5e-2,
+            batch_size: 2,
+        },
+        license_tag: "MIT".to_string(),
+        operator_signature: "op".to_string(),
+        teacher_source: TeacherSource::CliBridge,
+        max_steps: Some(6),
+    };
+    let recorder = MemoryFlightRecorder::default();
+    let executor = Arc::new(CandleLoraTrainerExecutor::cpu().with_checkpoint_every_steps(3));
+
+    let artifact = distill_with_flight_recorder(
+        &corpus,
+        config,
+        ContentReviewConfig::defaults(),
+        executor,
+        "2026-10-19T00:00:00Z",
+        &recorder,
+        Uuid::now_v7(),
+        "job-candle",
+    )
+    .await
+    .expect("native distillation runs");
+
+    assert_eq!(artifact.corpus_turn_count, 3);
+    assert!(artifact.lora_dir.join(PEFT_ADAPTER_WEIGHTS_FILE).is_file());
+    let sidecar = PeftProvenanceSidecar::read_from(&artifact.lora_dir.join("provenance.json"))
+        .expect("provenance sidecar");
+    assert_eq!(sidecar.schema, "hsk.distill.lora_provenance@v1");
+    assert_eq!(sidecar.num_steps, 6, "max_steps caps the run");
+    assert!(sidecar.training_loss.is_finite());
+    assert_eq!(sidecar.license_tag, "MIT");
+    assert!(sidecar.corpus_sha256.is_some());
+
+    let log = read_training_log(&artifact.lora_dir.join(TRAINING_LOG_FILE_NAME)).unwrap();
+    let checkpoint_parents = log
+        .iter()
+        .filter_map(|entry| match entry {
+            TrainingLogEntry::Checkpoint {
+                parent_checkpoint_id,
+                ..
+            } => Some(parent_checkpoint_id.clone()),
+            TrainingLogEntry::Step { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        checkpoint_parents,
+        vec![None, Some("checkpoint-3".to_string())]
+    );
+
+    let events = recorder.events.lock().unwrap();
+    let steps = events
+        .iter()
+        .filter(|event| event.event_type == FlightRecorderEventType::DistillTrainingStep)
+        .collect::<Vec<_>>();
+    assert_eq!(steps.len(), 6);
+    assert!(steps
+        .iter()
+        .all(|event| event.job_id.as_deref() == Some("job-candle")));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| event.event_type == FlightRecorderEventType::DistillCheckpointCreated)
+            .count(),
+        2
+    );
+}
+
+#[derive(Default)]
+struct MemoryFlightRecorder {
+    events: Mutex<Vec<FlightRecorderEvent>>,
+}
+
+#[async_trait::async_trait]
+impl FlightRecorder for MemoryFlightRecorder {
+    async fn record_event(&self, event: FlightRecorderEvent) -> Result<(), RecorderError> {
+        event.validate()?;
+        self.events.lock().expect("recorder lock").push(event);
+        Ok(())
+    }
+
+    async fn enforce_retention(&self) -> Result<u64, RecorderError> {
+        Ok(0)
+    }
+
+    async fn list_events(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<FlightRecorderEvent>, RecorderError> {
+        Ok(self.events.lock().expect("recorder lock").clone())
+    }
+}
+
+/// Mean completion-token cross-entropy under the model's current LoRA stack.
+fn completion_loss(model: &mut CandleLlamaModel, examples: &[LoraTrainingExample]) -> f32 {
+    let hooks = CandleSteeringHooks::new_for_model(ModelId::new_v7(), 8);
+    let mut total = 0.0;
+    for example in examples {
+        let ids = [
+            example.prompt_ids.as_slice(),
+            example.completion_ids.as_slice(),
+        ]
+        .concat();
+        let input = Tensor::new(ids.as_slice(), &Device::Cpu)
+            .unwrap()
+            .unsqueeze(0)
+            .unwrap();
+        let logits = model
+            .forward_full_logits(&input, &hooks, &[], &[])
+            .unwrap()
+            .squeeze(0)
+            .unwrap();
+        let start = example.prompt_ids.len();
+        let positions =
+            Tensor::arange(start as u32 - 1, ids.len() as u32 - 1, &Device::Cpu).unwrap();
+        let targets = Tensor::new(&ids[start..], &Device::Cpu).unwrap();
+        let predicted = logits.index_select(&positions, 0).unwrap();
+        total += candle_nn::loss::cross_entropy(&predicted, &targets)
+            .unwrap()
+            .to_scalar::<f32>()
+            .unwrap();
+    }
+    total / examples.len() as f32
+}
+
+fn tiny_llama_config() -> LlamaRuntimeConfig {
+    LlamaRuntimeConfig {
+        hidden_size: 8,
+        intermediate_size: 16,
+        vocab_size: 16,
+        num_hidden_layers: 2,
+        num_attention_heads: 2,
+        num_key_value_heads: 2,
+        use_flash_attn: false,
+        rms_norm_eps: 1e-5,
+        rope_theta: 10_000.0,
+        bos_token_id: None,
+        eos_token_id: None,
+        rope_scaling: None,
+        max_position_embeddings: 32,
+        tie_word_embeddings: false,
+    }
+}
+
+/// Random-weight tiny Llama with a whitespace word-level tokenizer, laid out
+/// the way `CandleLlamaModel::load_safetensors` expects a model directory.
+fn write_tiny_student(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    let varmap = VarMap::new();
+    CandleLlamaModel::from_varbuilder(
+        tiny_llama_config(),
+        VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+        &Device::Cpu,
+    )
+    .unwrap();
+    varmap.save(dir.join("model.safetensors")).unwrap();
+    fs::write(
+        dir.join("config.json"),
+        serde_json::to_vec(&serde_json::json!({
+            "hidden_size": 8,
+            "intermediate_size": 16,
+            "vocab_size": 16,
+            "num_hidden_layers": 2,
+            "num_attention_heads": 2,
+            "num_key_value_heads": 2,
+            "rms_norm_eps": 1e-5,
+            "rope_theta": 10000.0,
+            "max_position_embeddings": 32,
+            "tie_word_embeddings": false
+        }))
+        .unwrap(),
+    )
+    .unwrap();
+    let vocab = [
+        "[UNK]", "red", "apple", "blue", "sky", "green", "frog", "?", "fruit", "air", "animal",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(id, word)| (word.to_string(), serde_json::json!(id)))
+    .collect::<serde_json::Map<_, _>>();
+    fs::write(
+        dir.join("tokenizer.json"),
+        serde_json::to_vec(&serde_json::json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [],
+            "normalizer": null,
+            "pre_tokenizer": { "type": "Whitespace" },
+            "post_processor": null,
+            "decoder": null,
+            "model": { "type": "WordLevel", "vocab": vocab, "unk_token": "[UNK]" }
+        }))
+        .unwrap(),
+    )
+    .unwrap();
+}
+
+fn turn(id: &str, prompt: &str, completion: &str) -> TrainingTurn {
+    TrainingTurn {
+        id: id.to_string(),
+        session_id: "session".to_string(),
+        model_id: "model".to_string(),
+        prompt: prompt.to_string(),
+        completion: completion.to_string(),
+        finish_reason: Some("stop".to_string()),
+        license_tag: "MIT".to_string(),
+        source_event_ids: vec!["e1".to_string()],
+        sourced_at_utc: "2026-10-19T00:00:00Z".to_string(),
+    }
+}
+
+fn sha256_bytes(path: &Path) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(fs::read(path).unwrap());
+    hasher.finalize().into()
+}
@@ -8,15 +8,15 @@ use std::{
 
 use async_trait::async_trait;
 use candle_core::{DType, Device, Tensor};
-use candle_nn::VarBuilder;
+use candle_nn::{VarBuilder, VarMap};
 use candle_transformers::models::llama::Config as LlamaRuntimeConfig;
 use futures::StreamExt;
 use handshake_core::model_runtime::{
     candle::{
         adapter::candle_transformer_capabilities,
         generate::{candle_generate_stream, CandleGenerationCodec},
-        transformer::{CandleLlamaModel, TransformerModel},
-        CandleSteeringHooks,
+        transformer::{CandleDecoderModel, CandleLlamaModel, TransformerModel},
+        CandleSteeringHooks, DecoderArchitecture, DecoderConfig,
     },
     CancellationToken, FinishReason, GenPrompt, GenerateRequest, HookPoint, KvCachePolicy,
     KvPrefixHandle, KvQuantSupport, LayerIndex, LoadSpec, LoraId, ModelCapabilities, ModelId,
@@ -219,6 +219,66 @@ fn candle_transformer_capabilities_match_implemented_ops() {
     assert!(!actual.supports_eagle3);
 }
 
+#[tokio::test]
+async fn candle_runtime_loads_qwen2_phi3_gemma_mistral_artifacts_through_owned_decoder() {
+    for architecture in DecoderArchitecture::ALL {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config_value = serde_json::json!({
+            "architectures": [format!("{}ForCausalLM", match architecture {
+                DecoderArchitecture::Qwen2 => "Qwen2",
+                DecoderArchitecture::Phi3 => "Phi3",
+                DecoderArchitecture::Gemma => "Gemma",
+                DecoderArchitecture::Mistral => "Mistral",
+                DecoderArchitecture::Llama => "Llama",
+            })],
+            "model_type": architecture.as_str(),
+            "vocab_size": 16,
+            "hidden_size": 8,
+            "intermediate_size": 16,
+            "num_hidden_layers": 2,
+            "num_attention_heads": 2,
+            "num_key_value_heads": 1,
+            "max_position_embeddings": 16,
+            "eos_token_id": 2
+        });
+        fs::write(
+            tempdir.path().join("config.json"),
+            serde_json::to_vec(&config_value).unwrap(),
+        )
+        .unwrap();
+        let config = DecoderConfig::from_config_value(architecture, &config_value).unwrap();
+        let varmap = VarMap::new();
+        let built = CandleDecoderModel::from_varbuilder_for_model(
+            ModelId::new_v7(),
+            config,
+            VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu),
+            &Device::Cpu,
+        )
+        .expect("tiny decoder builds");
+        assert_eq!(built.architecture(), architecture);
+        let artifact = tempdir.path().join("model.safetensors");
+        varmap.save(&artifact).unwrap();
+
+        let mut runtime = handshake_core::model_runtime::candle::CandleRuntime::default();
+        let spec = load_spec(&artifact);
+        let declared = spec.declared_capabilities.clone();
+        let id = runtime
+            .load(spec)
+            .await
+            .unwrap_or_else(|error| panic!("{} loads: {error}", architecture.as_str()));
+        assert_eq!(
+            runtime.capabilities(id).unwrap(),
+            &candle_transformer_capabilities(&declared)
+        );
+        let score = runtime.score(id, vec![1, 5, 3, 7]).await.unwrap();
+        assert_eq!(score.token_logprobs.len(), 3);
+        assert!(score
+            .token_logprobs
+            .iter()
+            .all(|logprob| logprob.is_finite() && *logprob <= 0.0));
+    }
+}
+
 #[tokio::test]
 async fn candle_llama_load_from_env_model_dir_when_present() {
     let Some(model_dir) = env::var_os("HANDSHAKE_TEST_CANDLE_MODEL_DIR").map(PathBuf::from) else {
@@ -792,6 +792,7 @@ async fn anthropic_byok_emits_fr_infer_events_with_adapter_tag() {
             provider: Arc::new(ApproveProvider),
             session_id: "session-fr-test".to_string(),
         }),
+        spend: None,
     });
 
     let runtime = fixture_runtime(mock_server.uri(), sink.clone())
@@ -876,6 +877,7 @@ async fn anthropic_byok_consent_denied_blocks_http_call() {
             provider: Arc::new(DenyProvider),
             session_id: "session-deny-test".to_string(),
         }),
+        spend: None,
     });
 
     let runtime =
@@ -846,6 +846,7 @@ async fn fr_event_shape_parity_from_real_cloud_emissions() {
             provider: Arc::new(ApproveProvider),
             session_id: "session-fr-parity-openai".to_string(),
         }),
+        spend: None,
     });
     let openai = OpenAiByokRuntime::with_client(
         openai_server.uri(),
@@ -870,6 +871,7 @@ async fn fr_event_shape_parity_from_real_cloud_emissions() {
             provider: Arc::new(ApproveProvider),
             session_id: "session-fr-parity-anthropic".to_string(),
         }),
+        spend: None,
     });
     let anthropic = AnthropicByokRuntime::with_client(
         anthropic_server.uri(),
@@ -29,9 +29,10 @@ use handshake_core::flight_recorder::{
 };
 use handshake_core::model_runtime::cloud::{
     ApiKeyProvider, CloudCallKind, CloudCallStatus, CloudConsentContext, CloudInvocationAuditRow,
-    CloudInvocationAuditSink, CloudLaneObservability, ConsentDecision, ConsentGate,
-    ConsentGateError, ConsentProvider, OpenAiByokError, OpenAiByokRuntime,
-    OPENAI_CHAT_COMPLETIONS_PATH,
+    CloudInvocationAuditSink, CloudLaneObservability, CloudSpendContext, CloudSpendLedger,
+    CloudSpendSettings, ConsentDecision, ConsentGate, ConsentGateError, ConsentProvider,
+    ModelPrice, OpenAiByokError, OpenAiByokRuntime, SpendAttribution, SpendBudget, SpendScope,
+    SpendScopeKind, OPENAI_CHAT_COMPLETIONS_PATH,
 };
 use handshake_core::model_runtime::{
     CancellationToken, GenPrompt, GenerateRequest, KvCachePolicy, LoadSpec, ModelId, ModelRuntime,
@@ -657,6 +658,7 @@ async fn openai_byok_emits_fr_infer_events_with_adapter_tag() {
             provider: Arc::new(ApproveProvider),
             session_id: "session-fr-test".to_string(),
         }),
+        spend: None,
     });
 
     let runtime = fixture_runtime(mock_server.uri(), sink.clone())
@@ -740,6 +742,7 @@ async fn openai_byok_consent_denied_blocks_http_call() {
             provider: Arc::new(DenyProvider),
             session_id: "session-deny-test".to_string(),
         }),
+        spend: None,
     });
 
     let runtime =
@@ -773,3 +776,100 @@ async fn openai_byok_consent_denied_blocks_http_call() {
     // And no HTTP request reached wiremock.
     mock_server.verify().await;
 }
+
+// ---------------------------------------------------------------------
+// Spend accounting: the trailing `include_usage` chunk is priced into
+// the job rollup, and once the job's hard budget is spent the next
+// generate() is refused before any HTTP request.
+// ---------------------------------------------------------------------
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn openai_byok_charges_reported_usage_and_enforces_hard_budget() {
+    let mock_server = MockServer::start().await;
+    let mut payload = sse_payload_for(&["Hello", " world"]);
+    let usage_chunk = serde_json::json!({
+        "id": "chatcmpl-test-usage",
+        "object": "chat.completion.chunk",
+        "created": 1_700_000_100_u64,
+        "model": "gpt-4o-2024-08-06",
+        "choices": [],
+        "usage": {"prompt_tokens": 1_000, "completion_tokens": 50_000, "total_tokens": 51_000},
+    });
+    payload = payload.replace(
+        "data: [DONE]\n\n",
+        &format!("data: {usage_chunk}\n\ndata: [DONE]\n\n"),
+    );
+    Mock::given(method("POST"))
+        .and(path(OPENAI_CHAT_COMPLETIONS_PATH))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(payload),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let ledger = Arc::new(CloudSpendLedger::new(CloudSpendSettings {
+        pricing: vec![ModelPrice {
+            provider: "openai".to_string(),
+            model_prefix: "gpt-4o".to_string(),
+            input_usd_per_mtok: 1.0,
+            output_usd_per_mtok: 10.0,
+        }],
+        budgets: vec![SpendBudget {
+            scope: SpendScopeKind::Job,
+            soft_limit_usd: None,
+            hard_limit_usd: Some(0.5),
+        }],
+    }));
+    let job_id = uuid::Uuid::now_v7();
+    let sink = Arc::new(CapturingSink::default());
+    let recorder = Arc::new(CapturingFlightRecorder::default());
+    let lane_obs = Arc::new(CloudLaneObservability {
+        flight_recorder: recorder.clone(),
+        consent: None,
+        spend: Some(CloudSpendContext {
+            ledger: ledger.clone(),
+            attribution: SpendAttribution::new().with_job(job_id),
+        }),
+    });
+    let runtime =
+        fixture_runtime(mock_server.uri(), sink.clone()).with_lane_observability(lane_obs);
+    let handle = runtime
+        .register_handle("gpt-4o-2024-08-06", "2026-05-20T11:00:00Z")
+        .expect("allowlisted");
+
+    let mut stream = runtime.chat_completions_stream(fixture_generate_request(
+        handle.model_id,
+        CancellationToken::new(),
+    ));
+    while let Some(item) = stream.next().await {
+        let _ = item.expect("success path items are Ok");
+    }
+
+    // 1k in at $1/MTok + 50k out at $10/MTok = $0.501, past the $0.50 limit.
+    let rollup = ledger.rollup(&SpendScope::job(job_id));
+    assert_eq!(rollup.calls, 1);
+    assert_eq!(rollup.prompt_tokens, 1_000);
+    assert_eq!(rollup.completion_tokens, 50_000);
+    assert!((rollup.cost_usd - 0.501).abs() < 1e-9);
+
+    let mut refused = runtime.chat_completions_stream(fixture_generate_request(
+        handle.model_id,
+        CancellationToken::new(),
+    ));
+    let err = refused
+        .next()
+        .await
+        .expect("one item")
+        .expect_err("over budget");
+    assert!(
+        format!("{err}").contains("budget exceeded"),
+        "error must surface the budget refusal, got: {err}"
+    );
+    assert!(refused.next().await.is_none());
+
+    // Only the first call reached the wire.
+    mock_server.verify().await;
+}
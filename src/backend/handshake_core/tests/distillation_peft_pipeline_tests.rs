@@ -9,14 +9,15 @@ use handshake_core::distillation::{
     content_review::ContentReviewConfig,
     corpus_extractor::{TrainingCorpus, TrainingTurn},
     peft_pipeline::{
-        distill, distill_with_flight_recorder, review_corpus_with_events, DistillError,
-        DistillJobConfig, PeftHyperparams, PeftTrainerExecutor,
+        distill, distill_with_flight_recorder, review_corpus_with_events, write_training_log,
+        DistillError, DistillJobConfig, PeftHyperparams, PeftTrainerExecutor, TrainerBackend,
+        TrainingLogEntry, TRAINING_LOG_FILE_NAME,
     },
 };
 use handshake_core::flight_recorder::{
     EventFilter, FlightRecorder, FlightRecorderEvent, FlightRecorderEventType, RecorderError,
 };
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 #[derive(Default)]
@@ -58,6 +59,38 @@ impl PeftTrainerExecutor for NeverCalledExecutor {
     }
 }
 
+/// Reports two steps while it "trains" and leaves the same steps in the
+/// training log, like the candle trainer.
+struct ReportingExecutor;
+impl PeftTrainerExecutor for ReportingExecutor {
+    fn run(&self, config: &DistillJobConfig) -> Result<(), DistillError> {
+        self.run_with_progress(config, &mut |_| {})
+    }
+
+    fn run_with_progress(
+        &self,
+        config: &DistillJobConfig,
+        progress: &mut dyn FnMut(TrainingLogEntry),
+    ) -> Result<(), DistillError> {
+        let entries = (1..=2)
+            .map(|step| TrainingLogEntry::Step {
+                step,
+                epoch: 1,
+                loss: 1.0 / f64::from(step),
+                learning_rate: 1e-4,
+                tokens: 8,
+            })
+            .collect::<Vec<_>>();
+        std::fs::create_dir_all(&config.output_lora_dir).unwrap();
+        write_training_log(
+            &config.output_lora_dir.join(TRAINING_LOG_FILE_NAME),
+            &entries,
+        )?;
+        entries.into_iter().for_each(progress);
+        Ok(())
+    }
+}
+
 fn turn(id: &str, prompt: &str, completion: &str, license: &str) -> TrainingTurn {
     TrainingTurn {
         id: id.to_string(),
@@ -182,7 +215,7 @@ async fn peft_pipeline_distill_with_flight_recorder_records_sanitized_pii_before
         &corpus,
         distill_config(&tmp),
         ContentReviewConfig::defaults(),
-        &NeverCalledExecutor,
+        Arc::new(NeverCalledExecutor),
         "2026-05-20T04:00:00Z",
         &recorder,
         Uuid::now_v7(),
@@ -214,6 +247,57 @@ async fn peft_pipeline_distill_with_flight_recorder_records_sanitized_pii_before
     assert!(!payload_text.contains("email alice"));
 }
 
+#[tokio::test]
+async fn peft_pipeline_distill_with_flight_recorder_records_reported_steps_once() {
+    let corpus = TrainingCorpus {
+        session_id: "s".to_string(),
+        turns: vec![turn("t1", "Q1?", "A1", "MIT")],
+    };
+    let tmp = tempfile::tempdir().unwrap();
+    let recorder = MemoryFlightRecorder::default();
+
+    distill_with_flight_recorder(
+        &corpus,
+        distill_config(&tmp),
+        ContentReviewConfig::defaults(),
+        Arc::new(ReportingExecutor),
+        "2026-05-20T04:00:00Z",
+        &recorder,
+        Uuid::now_v7(),
+        "job-reported-steps",
+    )
+    .await
+    .expect("distill ok");
+
+    let events = recorder
+        .list_events(EventFilter::default())
+        .await
+        .expect("list recorded events");
+    let steps = events
+        .iter()
+        .filter(|event| event.event_type == FlightRecorderEventType::DistillTrainingStep)
+        .map(|event| event.payload["step"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    // Streamed while training; the log file is not replayed on top.
+    assert_eq!(steps, vec![1, 2]);
+}
+
+#[test]
+fn trainer_backend_parses_cli_values() {
+    assert_eq!(TrainerBackend::default(), TrainerBackend::Python);
+    for backend in [TrainerBackend::Python, TrainerBackend::Candle] {
+        assert_eq!(
+            TrainerBackend::from_cli_arg(backend.cli_arg_value()),
+            Ok(backend)
+        );
+    }
+    assert_eq!(
+        TrainerBackend::from_cli_arg(" Candle "),
+        Ok(TrainerBackend::Candle)
+    );
+    assert!(TrainerBackend::from_cli_arg("torch").is_err());
+}
+
 #[test]
 fn python_trainer_script_is_committed_at_known_path() {
     // The Rust executor invokes scripts/distill/train_lora.py; pin
@@ -0,0 +1,207 @@
+//! Graph analytics projections against real PostgreSQL: a seeded knowledge
+//! graph is analyzed and stored, the projection's staleness stamp tracks edge
+//! changes, connection paths explain how two entities meet, and a fresh
+//! projection's hub scores reach executed-retrieval ranking while a stale one
+//! is named in the trace and ignored.
+//!
+//! Reuses the committed `MemoryFixture`; SKIPs loudly without PostgreSQL.
+
+#[path = "knowledge_memory_fixtures.rs"]
+mod knowledge_memory_fixtures;
+
+use std::collections::BTreeSet;
+
+use handshake_core::knowledge_retrieval::compiler::BundleTargetKind;
+use handshake_core::knowledge_retrieval::executor::execute_retrieval;
+use handshake_core::knowledge_retrieval::graph_analytics::{
+    explain_connection_in_workspace, graph_analytics_status, refresh_graph_analytics,
+    AnalyticsConfig, AnalyticsGraphKind,
+};
+use handshake_core::knowledge_retrieval::graph_planner::GraphTraversalPolicy;
+use handshake_core::knowledge_retrieval::planner::RetrievalRequest;
+use handshake_core::storage::knowledge::{KnowledgeEdgeType, KnowledgeStore, NewKnowledgeEdge};
+use handshake_core::storage::knowledge_retrieval::traces_for_bundle;
+
+use knowledge_memory_fixtures::{pool_for, MemoryFixture};
+
+macro_rules! skip_if_no_pg {
+    ($opt:expr, $name:literal) => {
+        match $opt {
+            Some(value) => value,
+            None => {
+                eprintln!(concat!("SKIP ", $name, ": PostgreSQL unavailable"));
+                return;
+            }
+        }
+    };
+}
+
+async fn link(fx: &MemoryFixture, source: &str, target: &str, edge_type: KnowledgeEdgeType) {
+    fx.pg
+        .db
+        .upsert_knowledge_edge(NewKnowledgeEdge {
+            workspace_id: fx.workspace_id.clone(),
+            edge_type,
+            source_entity_id: source.to_string(),
+            target_entity_id: target.to_string(),
+            extractor_version: "test_v1".to_string(),
+            confidence: 0.9,
+            detected_in_run: None,
+            evidence_span_ids: vec![fx.span_id.clone()],
+        })
+        .await
+        .expect("edge");
+}
+
+#[tokio::test]
+async fn analytics_projection_tracks_staleness_and_feeds_hub_suppression() {
+    let fx = skip_if_no_pg!(MemoryFixture::setup().await, "graph_analytics_projection");
+    let pool = pool_for(&fx.pg).await;
+
+    // Five modules all depend on one runtime crate; one stray note is never
+    // linked.
+    let runtime = fx.entity("symbol", "runtime", "Runtime").await;
+    let mut modules = Vec::new();
+    for key in ["api", "jobs", "storage", "search", "ui"] {
+        let module = fx.entity("symbol", key, key).await;
+        link(&fx, &module, &runtime, KnowledgeEdgeType::DependsOn).await;
+        modules.push(module);
+    }
+    let stray = fx.entity("concept", "stray_note", "Stray note").await;
+
+    let projection = refresh_graph_analytics(
+        &pool,
+        &fx.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &AnalyticsConfig::default(),
+    )
+    .await
+    .expect("refresh");
+    assert!(projection.projection_id.starts_with("KGA-"));
+    assert_eq!(projection.report.edge_count, 5);
+    assert_eq!(
+        projection.report.nodes[0].node_id, runtime,
+        "runtime ranks first"
+    );
+    assert!(projection.report.nodes[0].hub_score > 0.5);
+    assert!(projection.report.orphans.contains(&stray));
+    assert_eq!(projection.report.dead_ends, vec![runtime.clone()]);
+
+    let status = graph_analytics_status(&pool, &fx.workspace_id, AnalyticsGraphKind::Knowledge)
+        .await
+        .expect("status")
+        .expect("projection stored");
+    assert!(!status.stale, "nothing changed since the refresh");
+
+    let path = explain_connection_in_workspace(
+        &pool,
+        &fx.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &modules[0],
+        &modules[4],
+        6,
+    )
+    .await
+    .expect("explain")
+    .expect("connected through the runtime");
+    assert_eq!(path.hops.len(), 2);
+    assert_eq!(
+        path.summary,
+        "api -[depends_on]-> Runtime <-[depends_on]- ui"
+    );
+    assert!(explain_connection_in_workspace(
+        &pool,
+        &fx.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &modules[0],
+        &stray,
+        6,
+    )
+    .await
+    .expect("explain")
+    .is_none());
+
+    // A fresh projection: every runtime edge carries the centrality penalty
+    // even though the traversal's degree cut-off never fires.
+    let mut request = RetrievalRequest::discovery(&fx.workspace_id, "runtime dependents");
+    request.graph_neighborhood_expected = true;
+    let seeds = BTreeSet::from([runtime.clone()]);
+    let executed = execute_retrieval(
+        &fx.pg.db,
+        &pool,
+        "ktr-analytics-fresh",
+        "sr-analytics-fresh",
+        BundleTargetKind::Symbol,
+        &runtime,
+        &request,
+        &seeds,
+        GraphTraversalPolicy::default(),
+    )
+    .await
+    .expect("execute");
+    assert!(executed.fallback_reason.is_none());
+    assert!(!executed.ranked.is_empty());
+    assert!(executed
+        .ranked
+        .iter()
+        .all(|c| c.scores.trust_adjust.is_some_and(|adjust| adjust < 0.0)));
+
+    // A new edge makes the projection stale; ranking stops using it and the
+    // trace says so.
+    link(&fx, &modules[0], &modules[1], KnowledgeEdgeType::References).await;
+    let status = graph_analytics_status(&pool, &fx.workspace_id, AnalyticsGraphKind::Knowledge)
+        .await
+        .expect("status")
+        .expect("projection stored");
+    assert!(status.stale);
+    assert_ne!(status.current_hash, status.projection.staleness_hash);
+
+    let executed = execute_retrieval(
+        &fx.pg.db,
+        &pool,
+        "ktr-analytics-stale",
+        "sr-analytics-stale",
+        BundleTargetKind::Symbol,
+        &runtime,
+        &request,
+        &seeds,
+        GraphTraversalPolicy::default(),
+    )
+    .await
+    .expect("execute");
+    assert!(executed
+        .ranked
+        .iter()
+        .all(|c| c.scores.trust_adjust.is_none()));
+    let traces = traces_for_bundle(&fx.pg.db, &executed.compiled.bundle_id)
+        .await
+        .expect("traces");
+    let warnings = traces[0].decisions["retrieval_trace"]["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .filter_map(|w| w.as_str())
+        .collect::<Vec<_>>();
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.contains(&status.projection.projection_id) && w.contains("stale")),
+        "{warnings:?}"
+    );
+
+    // Refreshing clears the verdict.
+    refresh_graph_analytics(
+        &pool,
+        &fx.workspace_id,
+        AnalyticsGraphKind::Knowledge,
+        &AnalyticsConfig::default(),
+    )
+    .await
+    .expect("refresh");
+    let status = graph_analytics_status(&pool, &fx.workspace_id, AnalyticsGraphKind::Knowledge)
+        .await
+        .expect("status")
+        .expect("projection stored");
+    assert!(!status.stale);
+    assert_eq!(status.projection.report.edge_count, 6);
+}
@@ -374,7 +374,10 @@ mod mt_082_kind_registry {
 
         let projected = sync_kind_projection(pool).await.expect("sync projection");
         assert_eq!(projected, registry().len());
-        assert_eq!(projected, 8, "the eight MT-082 primary kinds");
+        assert_eq!(
+            projected, 12,
+            "the eight MT-082 primary kinds plus web page, ebook, email, notebook"
+        );
 
         let mut conn = env.pg.raw_connection().await;
         let rows = sqlx::query(
@@ -384,7 +387,7 @@ mod mt_082_kind_registry {
         .fetch_all(&mut conn)
         .await
         .expect("read projection");
-        assert_eq!(rows.len(), 8, "stale row purged, code registry projected");
+        assert_eq!(rows.len(), 12, "stale row purged, code registry projected");
         for row in &rows {
             assert_eq!(
                 row.get::<String, _>("registry_version"),
@@ -406,13 +409,13 @@ mod mt_082_kind_registry {
 
         // Re-sync is idempotent (upsert, no duplicates, version stable).
         let projected_again = sync_kind_projection(pool).await.expect("re-sync");
-        assert_eq!(projected_again, 8);
+        assert_eq!(projected_again, 12);
         let count: i64 =
             sqlx::query_scalar("SELECT count(*) FROM knowledge_ingestion_kind_registry")
                 .fetch_one(&mut conn)
                 .await
                 .expect("count rows");
-        assert_eq!(count, 8);
+        assert_eq!(count, 12);
     }
 }
 
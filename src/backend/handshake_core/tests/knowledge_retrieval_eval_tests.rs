@@ -0,0 +1,194 @@
+//! Retrieval quality evaluation against real PostgreSQL: a labelled query set
+//! is bound to a freshly seeded workspace, scored through the executed
+//! retrieval pipeline, stored as a run, and diffed against a second run whose
+//! traversal policy regressed the graph query.
+//!
+//! Reuses the committed `MemoryFixture`; SKIPs loudly without PostgreSQL.
+
+#[path = "knowledge_memory_fixtures.rs"]
+mod knowledge_memory_fixtures;
+
+use std::collections::BTreeMap;
+
+use handshake_core::knowledge_retrieval::evaluation::{
+    diff_runs, run_evaluation, ExecutorEvalTarget, LabelledQuerySet, QueryDiffStatus, ALL_MODES,
+};
+use handshake_core::knowledge_retrieval::graph_planner::GraphTraversalPolicy;
+use handshake_core::storage::knowledge::{
+    KnowledgeCompactionPolicy, KnowledgeEdgeType, KnowledgePassageEvidenceRef,
+    KnowledgeRetrievalMode, KnowledgeStore, NewKnowledgeEdge, NewKnowledgeMemoryPassage,
+};
+use handshake_core::storage::knowledge_retrieval::{
+    get_retrieval_eval_run, list_retrieval_eval_runs, record_retrieval_eval_run,
+};
+
+use knowledge_memory_fixtures::{pool_for, MemoryFixture};
+
+macro_rules! skip_if_no_pg {
+    ($opt:expr, $name:literal) => {
+        match $opt {
+            Some(value) => value,
+            None => {
+                eprintln!(concat!("SKIP ", $name, ": PostgreSQL unavailable"));
+                return;
+            }
+        }
+    };
+}
+
+fn query_set() -> LabelledQuerySet {
+    LabelledQuerySet::from_json(include_str!(
+        "fixtures/knowledge_retrieval/eval_query_set.json"
+    ))
+    .expect("fixture query set is valid")
+}
+
+#[tokio::test]
+async fn eval_runs_persist_and_diff_flags_per_query_regressions() {
+    let fx = skip_if_no_pg!(MemoryFixture::setup().await, "eval_runs_persist_and_diff");
+    let pool = pool_for(&fx.pg).await;
+
+    // A 2-edge neighborhood (the graph query's answer) ...
+    let hub = fx
+        .entity("symbol", "managed_postgres", "ManagedPostgres")
+        .await;
+    let port = fx.entity("symbol", "pg_port", "PgPort").await;
+    let config = fx.entity("symbol", "pg_config", "PgConfig").await;
+    for (target, edge_type, confidence) in [
+        (&port, KnowledgeEdgeType::Defines, 0.95_f64),
+        (&config, KnowledgeEdgeType::Mentions, 0.5_f64),
+    ] {
+        fx.pg
+            .db
+            .upsert_knowledge_edge(NewKnowledgeEdge {
+                workspace_id: fx.workspace_id.clone(),
+                edge_type,
+                source_entity_id: hub.clone(),
+                target_entity_id: (*target).clone(),
+                extractor_version: "test_v1".to_string(),
+                confidence,
+                detected_in_run: None,
+                evidence_span_ids: vec![fx.span_id.clone()],
+            })
+            .await
+            .expect("edge");
+    }
+    // ... and span-backed passages (the unseeded query's answer).
+    let mut passage_ids = Vec::new();
+    for (text, confidence) in [
+        ("managed postgres listens on port 5544", 0.9_f64),
+        ("an older note about postgres ports", 0.4_f64),
+    ] {
+        let passage = fx
+            .pg
+            .db
+            .create_knowledge_memory_passage(NewKnowledgeMemoryPassage {
+                workspace_id: fx.workspace_id.clone(),
+                passage_text: text.to_string(),
+                token_count: Some(12),
+                ocr_transcript_metadata: None,
+                extraction_confidence: confidence,
+                ranking_features: serde_json::json!({}),
+                retrieval_mode: KnowledgeRetrievalMode::HybridRag,
+                compaction_policy: KnowledgeCompactionPolicy::Keep,
+                failure_receipt_event_id: None,
+                derived_in_run: None,
+                evidence: vec![KnowledgePassageEvidenceRef::Span {
+                    span_id: fx.span_id.clone(),
+                }],
+            })
+            .await
+            .expect("passage");
+        passage_ids.push(passage.passage_id);
+    }
+
+    let set = query_set()
+        .bind(&BTreeMap::from([
+            ("hub".to_string(), hub.clone()),
+            ("port".to_string(), port.clone()),
+            ("config".to_string(), config.clone()),
+            ("port_passage".to_string(), passage_ids[0].clone()),
+            ("span".to_string(), fx.span_id.clone()),
+        ]))
+        .expect("bind fixture ids");
+
+    // Baseline: default traversal. The graph query is answered by traversal
+    // with the grade-3 entity first; the unseeded query falls back to
+    // passages with the stronger passage first.
+    let target = ExecutorEvalTarget::new(&fx.pg.db, &pool, &fx.workspace_id);
+    let baseline = run_evaluation(&target, &set, &[], "baseline")
+        .await
+        .expect("baseline run");
+    let by_query: BTreeMap<&str, _> = baseline
+        .results
+        .iter()
+        .map(|r| (r.query_id.as_str(), r))
+        .collect();
+    let graph = by_query["pg-neighborhood"];
+    assert_eq!(graph.mode, "graph_traversal");
+    assert_eq!(graph.metrics.recall_at[&3], 1.0);
+    assert_eq!(graph.metrics.ndcg_at[&1], 1.0);
+    assert_eq!(graph.metrics.first_relevant_rank, Some(1));
+    let passage = by_query["pg-port-passage"];
+    assert_eq!(passage.mode, "passage_fallback");
+    assert_eq!(passage.ranked_ids.first(), Some(&passage_ids[0]));
+    assert_eq!(passage.metrics.reciprocal_rank, 1.0);
+    assert_eq!(baseline.summary[ALL_MODES].queries, 2);
+    assert_eq!(baseline.summary["graph_traversal"].queries, 1);
+
+    record_retrieval_eval_run(&pool, &baseline)
+        .await
+        .expect("record baseline");
+    let stored = get_retrieval_eval_run(&pool, &baseline.run_id)
+        .await
+        .expect("get run")
+        .expect("run exists");
+    assert_eq!(stored.results, baseline.results);
+    assert_eq!(stored.summary, baseline.summary);
+    assert_eq!(stored.query_set_hash, set.content_hash());
+
+    // Candidate: a policy that refuses edge traversal. The graph query now
+    // falls back to passages and loses its entities; the passage query is
+    // untouched.
+    let regressed = ExecutorEvalTarget::new(&fx.pg.db, &pool, &fx.workspace_id).with_graph_policy(
+        GraphTraversalPolicy {
+            deny_all_edges: true,
+            ..GraphTraversalPolicy::default()
+        },
+    );
+    let candidate = run_evaluation(&regressed, &set, &[], "deny_all_edges")
+        .await
+        .expect("candidate run");
+    record_retrieval_eval_run(&pool, &candidate)
+        .await
+        .expect("record candidate");
+
+    let runs = list_retrieval_eval_runs(&pool, &fx.workspace_id, &set.query_set_id, 10)
+        .await
+        .expect("list runs");
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].run_id, candidate.run_id, "newest first");
+
+    let diff = diff_runs(&runs[1], &runs[0], 1e-9).expect("diff");
+    assert!(!diff.query_set_changed);
+    let regressions: Vec<_> = diff.regressions().collect();
+    assert_eq!(regressions.len(), 1, "{diff:#?}");
+    let regression = regressions[0];
+    assert_eq!(regression.query_id, "pg-neighborhood");
+    assert_eq!(regression.baseline_mode.as_deref(), Some("graph_traversal"));
+    assert_eq!(
+        regression.candidate_mode.as_deref(),
+        Some("passage_fallback")
+    );
+    assert!(regression
+        .regressed_metrics
+        .contains(&"recall@10".to_string()));
+    assert_eq!(regression.deltas["mrr"], -1.0);
+    let passage_diff = diff
+        .queries
+        .iter()
+        .find(|q| q.query_id == "pg-port-passage")
+        .expect("passage query diffed");
+    assert_eq!(passage_diff.status, QueryDiffStatus::Unchanged);
+    assert!(diff.summary_deltas[ALL_MODES]["mrr"] < 0.0);
+}
@@ -24,13 +24,16 @@
 #[path = "knowledge_memory_fixtures.rs"]
 mod knowledge_memory_fixtures;
 
+use async_trait::async_trait;
 use handshake_core::knowledge_memory::bridge::generate_bridge_edges;
 use handshake_core::knowledge_retrieval::ai_ready_export::build_evidence_manifest;
 use handshake_core::knowledge_retrieval::budget::PriorityTier;
 use handshake_core::knowledge_retrieval::compiler::{
     BundleCandidate, BundleTargetKind, ContextBundleCompilerV2,
 };
-use handshake_core::knowledge_retrieval::executor::execute_retrieval;
+use handshake_core::knowledge_retrieval::executor::{
+    execute_retrieval, execute_retrieval_with_rerank,
+};
 use handshake_core::knowledge_retrieval::graph_planner::{
     GraphTraversalPlanner, GraphTraversalPolicy,
 };
@@ -38,6 +41,7 @@ use handshake_core::knowledge_retrieval::plan::{RetrievalBudgets, RetrievalTrace
 use handshake_core::knowledge_retrieval::planner::{
     AuthoritativeHandle, CheapestAuthoritativePathPlanner, RetrievalRequest,
 };
+use handshake_core::knowledge_retrieval::rerank::{CrossEncoder, RerankError};
 use handshake_core::knowledge_retrieval::snippet::assemble_span_snippet;
 use handshake_core::memory::retrieval_mode::{NonHybridReason, QueryRetrievalMode};
 use handshake_core::storage::knowledge::{
@@ -603,6 +607,143 @@ async fn executed_pipeline_falls_back_to_passages_ranks_and_compiles() {
     assert_eq!(candidates[0]["candidate_id"], passage_ids[0].as_str());
 }
 
+/// Stub cross-encoder: full relevance for texts containing `needle`.
+struct NeedleEncoder {
+    needle: &'static str,
+    loaded: bool,
+}
+
+#[async_trait]
+impl CrossEncoder for NeedleEncoder {
+    fn model_ref(&self) -> String {
+        "test/needle".to_string()
+    }
+
+    async fn score_pairs(&self, _query: &str, texts: &[String]) -> Result<Vec<f64>, RerankError> {
+        if !self.loaded {
+            return Err(RerankError::Unavailable("no model loaded".to_string()));
+        }
+        Ok(texts
+            .iter()
+            .map(|text| if text.contains(self.needle) { 1.0 } else { 0.0 })
+            .collect())
+    }
+}
+
+/// The optional rerank stage reorders executed fallback passages by
+/// query-passage relevance, records scores and the reason for each move in
+/// the persisted trace, and keeps the MT-134 order when no model is loaded.
+#[tokio::test]
+async fn executed_pipeline_rerank_reorders_passages_and_records_why() {
+    let fx = skip_if_no_pg!(MemoryFixture::setup().await, "executed_pipeline_rerank");
+    let pool = pool_for(&fx.pg).await;
+
+    let mut passage_ids = Vec::new();
+    for (text, confidence) in [
+        ("postgres postgres port port config dump", 0.9_f64),
+        ("the database accepts connections on 5544", 0.4_f64),
+    ] {
+        let passage = fx
+            .pg
+            .db
+            .create_knowledge_memory_passage(NewKnowledgeMemoryPassage {
+                workspace_id: fx.workspace_id.clone(),
+                passage_text: text.to_string(),
+                token_count: Some(12),
+                ocr_transcript_metadata: None,
+                extraction_confidence: confidence,
+                ranking_features: serde_json::json!({}),
+                retrieval_mode: KnowledgeRetrievalMode::HybridRag,
+                compaction_policy: KnowledgeCompactionPolicy::Keep,
+                failure_receipt_event_id: None,
+                derived_in_run: None,
+                evidence: vec![KnowledgePassageEvidenceRef::Span {
+                    span_id: fx.span_id.clone(),
+                }],
+            })
+            .await
+            .expect("passage");
+        passage_ids.push(passage.passage_id);
+    }
+
+    let mut request =
+        RetrievalRequest::discovery(&fx.workspace_id, "what port does managed postgres use");
+    request.graph_neighborhood_expected = true;
+
+    let encoder = NeedleEncoder {
+        needle: "accepts connections",
+        loaded: true,
+    };
+    let executed = execute_retrieval_with_rerank(
+        &fx.pg.db,
+        &pool,
+        "ktr-exec-rerank",
+        "sr-exec-rerank",
+        BundleTargetKind::Task,
+        "port-question",
+        &request,
+        &BTreeSet::new(),
+        GraphTraversalPolicy::default(),
+        Some(&encoder),
+    )
+    .await
+    .expect("execute with rerank");
+    // The lower-confidence but relevant passage now leads.
+    assert_eq!(executed.ranked[0].candidate_id, passage_ids[1]);
+    assert_eq!(executed.ranked[0].scores.rerank, Some(1.0));
+    assert_eq!(executed.ranked[1].candidate_id, passage_ids[0]);
+
+    let traces = traces_for_bundle(&fx.pg.db, &executed.compiled.bundle_id)
+        .await
+        .expect("traces");
+    let rerank = &traces[0].decisions["retrieval_trace"]["rerank"];
+    assert_eq!(rerank["status"], "applied");
+    assert_eq!(rerank["model_ref"], "test/needle");
+    assert_eq!(rerank["scored"], 2);
+    let moves = rerank["moves"].as_array().expect("moves");
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[0]["candidate_id"], passage_ids[1].as_str());
+    assert!(moves[0]["why"]
+        .as_str()
+        .is_some_and(|why| why.starts_with("promoted")));
+    let selected = traces[0].decisions["retrieval_trace"]["selected"]
+        .as_array()
+        .expect("selected");
+    assert_eq!(selected[0]["candidate_id"], passage_ids[1].as_str());
+
+    // No model loaded: base order, skipped with the reason recorded.
+    let unloaded = NeedleEncoder {
+        needle: "accepts connections",
+        loaded: false,
+    };
+    let executed = execute_retrieval_with_rerank(
+        &fx.pg.db,
+        &pool,
+        "ktr-exec-rerank-off",
+        "sr-exec-rerank-off",
+        BundleTargetKind::Task,
+        "port-question",
+        &request,
+        &BTreeSet::new(),
+        GraphTraversalPolicy::default(),
+        Some(&unloaded),
+    )
+    .await
+    .expect("execute without a loaded model");
+    assert_eq!(executed.ranked[0].candidate_id, passage_ids[0]);
+    assert!(executed.ranked.iter().all(|c| c.scores.rerank.is_none()));
+    let traces = traces_for_bundle(&fx.pg.db, &executed.compiled.bundle_id)
+        .await
+        .expect("traces");
+    let trace = &traces[0].decisions["retrieval_trace"];
+    assert_eq!(trace["rerank"]["status"], "skipped");
+    assert!(trace["warnings"]
+        .as_array()
+        .expect("warnings")
+        .iter()
+        .any(|w| w.as_str() == Some("rerank skipped: rerank model unavailable: no model loaded")));
+}
+
 /// Adversarial-v2 MT-134: the SAME executed path with a REAL graph: the
 /// traversal's edges become ranked entity candidates (graph features, no
 /// fallback) and compile into a persisted bundle.
@@ -0,0 +1,543 @@
+//! OpenAI-compatible local server proofs: the `/v1/*` surface drives the
+//! local ModelRuntimes through `LocalModelRuntimeLlmClient`, so consent,
+//! the `max_tokens` budget and Flight Recorder instrumentation match
+//! internal calls, and cloud provider ids are never proxied.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream;
+use handshake_core::{
+    flight_recorder::{
+        EventFilter, FlightRecorder, FlightRecorderEvent, FlightRecorderEventType, RecorderError,
+    },
+    llm::{
+        local_openai_server::{
+            LocalModelInfo, LocalOpenAiServer, LocalOpenAiServerConfig, SESSION_HEADER,
+            TRACE_HEADER,
+        },
+        local_router::{LocalModelRuntimeLlmClient, LocalRouter},
+        CompletionRequest, CompletionResponse, LlmClient, LlmError, ModelProfile, TokenUsage,
+    },
+    model_runtime::{
+        cloud::consent_gate::{ConsentDecision, ConsentGateError, ConsentProvider},
+        BaseModelTag, CancellationToken, Embedding, FinishReason, GenerateRequest, GeneratedToken,
+        KvCacheHandle, LoraStackHandle, ModelCapabilities, ModelId, ModelRegistration,
+        ModelRegistry, ModelRuntime, ModelRuntimeError, OperatorId, ProviderKind, RuntimeBinding,
+        Score, SteeringHookHandle, TokenStream,
+    },
+};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+#[derive(Clone)]
+struct ScriptedRuntime {
+    tokens: Vec<GeneratedToken>,
+    embedding: Vec<f32>,
+    capabilities: ModelCapabilities,
+    requests: Arc<Mutex<Vec<GenerateRequest>>>,
+}
+
+impl ScriptedRuntime {
+    fn new(chunks: &[&str]) -> Self {
+        let tokens = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, text)| GeneratedToken {
+                token_id: index as u32,
+                text: (*text).to_string(),
+                logprob: None,
+                finish_reason: (index + 1 == chunks.len()).then_some(FinishReason::Stop),
+            })
+            .collect();
+        Self {
+            tokens,
+            embedding: vec![0.25, -0.5, 1.0],
+            capabilities: ModelCapabilities::default(),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.lock().expect("requests lock").len()
+    }
+
+    fn last_request(&self) -> GenerateRequest {
+        self.requests
+            .lock()
+            .expect("requests lock")
+            .last()
+            .cloned()
+            .expect("runtime request")
+    }
+}
+
+#[async_trait]
+impl ModelRuntime for ScriptedRuntime {
+    async fn load(
+        &mut self,
+        _spec: handshake_core::model_runtime::LoadSpec,
+    ) -> Result<ModelId, ModelRuntimeError> {
+        Ok(ModelId::new_v7())
+    }
+
+    async fn unload(&mut self, _id: ModelId) -> Result<(), ModelRuntimeError> {
+        Ok(())
+    }
+
+    fn generate(&self, req: GenerateRequest) -> TokenStream {
+        self.requests.lock().expect("requests lock").push(req);
+        Box::pin(stream::iter(self.tokens.clone().into_iter().map(Ok)))
+    }
+
+    async fn score(&self, _id: ModelId, _sequence: Vec<u32>) -> Result<Score, ModelRuntimeError> {
+        Ok(Score {
+            token_logprobs: Vec::new(),
+            mean_logprob: 0.0,
+        })
+    }
+
+    async fn embed(&self, _id: ModelId, _text: &str) -> Result<Embedding, ModelRuntimeError> {
+        Ok(Embedding {
+            vector: self.embedding.clone(),
+        })
+    }
+
+    fn capabilities(&self, _id: ModelId) -> Result<&ModelCapabilities, ModelRuntimeError> {
+        Ok(&self.capabilities)
+    }
+
+    fn kv_cache(&self, _id: ModelId) -> Result<KvCacheHandle, ModelRuntimeError> {
+        Ok(KvCacheHandle::new("scripted-kv"))
+    }
+
+    fn lora_stack(&self, _id: ModelId) -> Result<LoraStackHandle, ModelRuntimeError> {
+        Ok(LoraStackHandle::new("scripted-lora"))
+    }
+
+    fn steering_hooks(&self, _id: ModelId) -> Result<SteeringHookHandle, ModelRuntimeError> {
+        Ok(SteeringHookHandle::new("scripted-steering"))
+    }
+
+    fn cancel(&self, token: CancellationToken) {
+        token.cancel();
+    }
+}
+
+struct CountingFallback {
+    profile: ModelProfile,
+    calls: Arc<Mutex<u32>>,
+}
+
+#[async_trait]
+impl LlmClient for CountingFallback {
+    async fn completion(&self, _req: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        *self.calls.lock().expect("calls lock") += 1;
+        Ok(CompletionResponse {
+            text: "cloud".to_string(),
+            usage: TokenUsage::default(),
+            latency_ms: 0,
+        })
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        &self.profile
+    }
+}
+
+#[derive(Clone, Default)]
+struct CapturingRecorder {
+    events: Arc<Mutex<Vec<FlightRecorderEvent>>>,
+}
+
+impl CapturingRecorder {
+    fn events(&self) -> Vec<FlightRecorderEvent> {
+        self.events.lock().expect("events lock").clone()
+    }
+}
+
+#[async_trait]
+impl FlightRecorder for CapturingRecorder {
+    async fn record_event(&self, event: FlightRecorderEvent) -> Result<(), RecorderError> {
+        self.events.lock().expect("events lock").push(event);
+        Ok(())
+    }
+
+    async fn enforce_retention(&self) -> Result<u64, RecorderError> {
+        Ok(0)
+    }
+
+    async fn list_events(
+        &self,
+        _filter: EventFilter,
+    ) -> Result<Vec<FlightRecorderEvent>, RecorderError> {
+        Ok(self.events())
+    }
+}
+
+/// Approves every session except the ones named in `deny`.
+struct ScriptedConsent {
+    deny: Vec<&'static str>,
+    prompts: Mutex<Vec<(String, String)>>,
+}
+
+impl ConsentProvider for ScriptedConsent {
+    fn prompt_for_decision(
+        &self,
+        session_id: &str,
+        lane: &str,
+    ) -> Result<ConsentDecision, ConsentGateError> {
+        self.prompts
+            .lock()
+            .expect("prompts lock")
+            .push((session_id.to_string(), lane.to_string()));
+        Ok(if self.deny.iter().any(|denied| *denied == session_id) {
+            ConsentDecision::Denied
+        } else {
+            ConsentDecision::Approved
+        })
+    }
+}
+
+/// Treats every byte as one token; no chat template, so chat renders as
+/// ChatML.
+struct ByteTokenizer;
+
+impl LocalModelInfo for ByteTokenizer {
+    fn chat_template(&self, _id: ModelId) -> Option<String> {
+        None
+    }
+
+    fn count_tokens(&self, _id: ModelId, text: &str) -> Result<u32, ModelRuntimeError> {
+        Ok(text.len() as u32)
+    }
+}
+
+struct Harness {
+    base_url: String,
+    model_id: ModelId,
+    runtime: Arc<ScriptedRuntime>,
+    recorder: Arc<CapturingRecorder>,
+    consent: Arc<ScriptedConsent>,
+    fallback_calls: Arc<Mutex<u32>>,
+    _server: tokio::task::JoinHandle<()>,
+}
+
+async fn start(chunks: &[&str], max_tokens_cap: Option<u32>) -> Harness {
+    start_with_config(
+        chunks,
+        LocalOpenAiServerConfig {
+            max_tokens_cap,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+async fn start_with_config(chunks: &[&str], config: LocalOpenAiServerConfig) -> Harness {
+    let model_id = ModelId::new_v7();
+    let mut registry = ModelRegistry::default();
+    registry
+        .register(ModelRegistration {
+            model_id,
+            artifact_path: PathBuf::from("fixtures/models/openai-server.gguf"),
+            sha256: [7; 32],
+            runtime_binding: RuntimeBinding::LlamaCpp,
+            declared_capabilities: ModelCapabilities::default(),
+            base_model_tag: BaseModelTag::new("openai-server-base"),
+            registered_at_utc: Utc::now(),
+            registered_by: OperatorId::new("operator-test"),
+            provider: ProviderKind::Local,
+        })
+        .expect("register model");
+    let runtime = Arc::new(ScriptedRuntime::new(chunks));
+    let recorder = Arc::new(CapturingRecorder::default());
+    let fallback_calls = Arc::new(Mutex::new(0));
+    let fallback = Arc::new(CountingFallback {
+        profile: ModelProfile::new("fallback".to_string(), 4096),
+        calls: fallback_calls.clone(),
+    });
+    let client = LocalModelRuntimeLlmClient::new(
+        LocalRouter::new(Arc::new(registry), runtime.clone(), runtime.clone()),
+        fallback,
+        recorder.clone(),
+        ModelProfile::new("local-router".to_string(), 512).with_streaming(true),
+    );
+    let consent = Arc::new(ScriptedConsent {
+        deny: vec!["denied-session"],
+        prompts: Mutex::new(Vec::new()),
+    });
+    let server = Arc::new(
+        LocalOpenAiServer::new(config, Arc::new(client), consent.clone())
+            .with_model_info(Arc::new(ByteTokenizer)),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, server.router()).await.expect("serve");
+    });
+
+    Harness {
+        base_url: format!("http://{addr}"),
+        model_id,
+        runtime,
+        recorder,
+        consent,
+        fallback_calls,
+        _server: handle,
+    }
+}
+
+fn llm_events(recorder: &CapturingRecorder) -> Vec<FlightRecorderEvent> {
+    recorder
+        .events()
+        .into_iter()
+        .filter(|event| matches!(event.event_type, FlightRecorderEventType::LlmInference))
+        .collect()
+}
+
+#[tokio::test]
+async fn models_and_chat_completion_route_through_local_runtime_with_fr_event() {
+    let h = start(&["Hello", " there"], None).await;
+    let http = reqwest::Client::new();
+
+    let models: Value = http
+        .get(format!("{}/v1/models", h.base_url))
+        .send()
+        .await
+        .expect("models")
+        .json()
+        .await
+        .expect("models json");
+    assert_eq!(models["object"], "list");
+    assert_eq!(models["data"][0]["id"], h.model_id.to_string());
+    assert_eq!(models["data"][0]["runtime"], "llama_cpp");
+
+    let response = http
+        .post(format!("{}/v1/chat/completions", h.base_url))
+        .json(&json!({
+            "model": h.model_id.to_string(),
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": [{"type": "text", "text": "Hi"}]}
+            ],
+            "max_tokens": 16,
+            "temperature": 0.1
+        }))
+        .send()
+        .await
+        .expect("chat");
+    assert_eq!(response.status(), 200);
+    let trace_id = response
+        .headers()
+        .get(TRACE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .expect("trace header")
+        .to_string();
+    let body: Value = response.json().await.expect("chat json");
+    assert_eq!(body["object"], "chat.completion");
+    assert_eq!(body["choices"][0]["message"]["role"], "assistant");
+    assert_eq!(body["choices"][0]["message"]["content"], "Hello there");
+    assert_eq!(body["choices"][0]["finish_reason"], "stop");
+    assert_eq!(body["usage"]["completion_tokens"], 2);
+
+    let routed = h.runtime.last_request();
+    assert!(routed
+        .prompt
+        .as_str()
+        .contains("<|im_start|>user\nHi<|im_end|>"));
+    assert!(routed.prompt.as_str().ends_with("<|im_start|>assistant\n"));
+    assert_eq!(routed.max_tokens, 16);
+    assert_eq!(routed.sampling.temperature, Some(0.1));
+    assert!(routed.stop_sequences.contains(&"<|im_end|>".to_string()));
+
+    let events = llm_events(&h.recorder);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].trace_id.to_string(), trace_id);
+    assert_eq!(events[0].payload["token_usage"]["completion_tokens"], 2);
+    assert!(events[0].validate().is_ok());
+
+    // Consent was asked once for the default client session on this model.
+    let prompts = h.consent.prompts.lock().expect("prompts lock").clone();
+    assert_eq!(prompts.len(), 1);
+    assert_eq!(prompts[0].1, format!("local_openai_server:{}", h.model_id));
+}
+
+#[tokio::test]
+async fn streaming_chat_emits_sse_deltas_and_done() {
+    let h = start(&["a", "b", "c"], None).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/v1/chat/completions", h.base_url))
+        .json(&json!({
+            "model": h.model_id.to_string(),
+            "messages": [{"role": "user", "content": "stream please"}],
+            "stream": true
+        }))
+        .send()
+        .await
+        .expect("stream");
+    assert_eq!(response.status(), 200);
+    assert!(response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream")));
+    let body = response.text().await.expect("sse body");
+
+    let payloads: Vec<&str> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .collect();
+    assert_eq!(payloads.last(), Some(&"[DONE]"));
+    let chunks: Vec<Value> = payloads[..payloads.len() - 1]
+        .iter()
+        .map(|payload| serde_json::from_str(payload).expect("chunk json"))
+        .collect();
+    assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+    let text: String = chunks
+        .iter()
+        .filter_map(|chunk| chunk["choices"][0]["delta"]["content"].as_str())
+        .collect();
+    assert_eq!(text, "abc");
+    let last = chunks.last().expect("final chunk");
+    assert_eq!(last["object"], "chat.completion.chunk");
+    assert_eq!(last["choices"][0]["finish_reason"], "stop");
+
+    assert_eq!(llm_events(&h.recorder).len(), 1);
+}
+
+#[tokio::test]
+async fn text_completion_and_embeddings_map_onto_generate_and_embed() {
+    let h = start(&["42"], None).await;
+    let http = reqwest::Client::new();
+
+    let body: Value = http
+        .post(format!("{}/v1/completions", h.base_url))
+        .json(&json!({"model": h.model_id.to_string(), "prompt": "The answer is", "stop": "\n"}))
+        .send()
+        .await
+        .expect("completion")
+        .json()
+        .await
+        .expect("completion json");
+    assert_eq!(body["object"], "text_completion");
+    assert_eq!(body["choices"][0]["text"], "42");
+    let routed = h.runtime.last_request();
+    assert_eq!(routed.prompt.as_str(), "The answer is");
+    assert_eq!(routed.stop_sequences, vec!["\n".to_string()]);
+    // No max_tokens from the client: the dispatcher profile budget applies.
+    assert_eq!(routed.max_tokens, 512);
+
+    let body: Value = http
+        .post(format!("{}/v1/embeddings", h.base_url))
+        .json(&json!({"model": h.model_id.to_string(), "input": ["one", "two words"]}))
+        .send()
+        .await
+        .expect("embeddings")
+        .json()
+        .await
+        .expect("embeddings json");
+    assert_eq!(body["data"].as_array().expect("data").len(), 2);
+    assert_eq!(body["data"][1]["index"], 1);
+    assert_eq!(body["data"][0]["embedding"], json!([0.25, -0.5, 1.0]));
+    // Counted by the model's tokenizer: 3 + 9 bytes under ByteTokenizer.
+    assert_eq!(body["usage"]["prompt_tokens"], 12);
+
+    // One completion + two embedding calls, all instrumented.
+    assert_eq!(llm_events(&h.recorder).len(), 3);
+}
+
+#[tokio::test]
+async fn consent_budget_and_unknown_models_are_rejected_before_the_runtime() {
+    let h = start(&["never"], Some(64)).await;
+    let http = reqwest::Client::new();
+    let url = format!("{}/v1/chat/completions", h.base_url);
+
+    let denied = http
+        .post(&url)
+        .header(SESSION_HEADER, "denied-session")
+        .json(&json!({
+            "model": h.model_id.to_string(),
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .send()
+        .await
+        .expect("denied");
+    assert_eq!(denied.status(), 403);
+    let body: Value = denied.json().await.expect("denied json");
+    assert_eq!(body["error"]["code"], "HSK-403-CONSENT-DENIED");
+
+    let over_budget = http
+        .post(&url)
+        .json(&json!({
+            "model": h.model_id.to_string(),
+            "messages": [{"role": "user", "content": "hi"}],
+            "max_tokens": 65
+        }))
+        .send()
+        .await
+        .expect("over budget");
+    assert_eq!(over_budget.status(), 400);
+    let body: Value = over_budget.json().await.expect("budget json");
+    assert_eq!(body["error"]["code"], "HSK-402-BUDGET-EXCEEDED");
+
+    for model in ["gpt-4o-mini".to_string(), ModelId::new_v7().to_string()] {
+        let missing = http
+            .post(&url)
+            .json(&json!({
+                "model": model,
+                "messages": [{"role": "user", "content": "hi"}]
+            }))
+            .send()
+            .await
+            .expect("missing model");
+        assert_eq!(missing.status(), 404);
+        let body: Value = missing.json().await.expect("missing json");
+        assert_eq!(body["error"]["code"], "model_not_found");
+    }
+
+    assert_eq!(h.runtime.request_count(), 0);
+    assert_eq!(*h.fallback_calls.lock().expect("calls lock"), 0);
+    assert!(llm_events(&h.recorder).is_empty());
+}
+
+#[tokio::test]
+async fn api_key_is_required_as_a_bearer_token_when_configured() {
+    let h = start_with_config(
+        &["ok"],
+        LocalOpenAiServerConfig {
+            api_key: Some("s3cret".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    let http = reqwest::Client::new();
+    let url = format!("{}/v1/models", h.base_url);
+
+    let missing = http.get(&url).send().await.expect("no key");
+    assert_eq!(missing.status(), 401);
+    let body: Value = missing.json().await.expect("401 json");
+    assert_eq!(body["error"]["code"], "invalid_api_key");
+
+    let wrong = http
+        .get(&url)
+        .bearer_auth("s3creT")
+        .send()
+        .await
+        .expect("wrong key");
+    assert_eq!(wrong.status(), 401);
+
+    let ok = http
+        .get(&url)
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .expect("right key");
+    assert_eq!(ok.status(), 200);
+    assert_eq!(h.runtime.request_count(), 0);
+}
@@ -621,9 +621,18 @@ async fn make_image_block(
     root: &std::path::Path,
     ws: &str,
 ) -> (String, String, String) {
-    let bytes = real_png(800, 600);
-    let (asset_id, content_hash, _path) =
-        make_original_asset(db, root, ws, "image/png", &bytes).await;
+    make_media_block(db, root, ws, "image/png", &real_png(800, 600)).await
+}
+
+/// [`make_image_block`] for any original: `mime` + real `bytes` on disk.
+async fn make_media_block(
+    db: &PostgresDatabase,
+    root: &std::path::Path,
+    ws: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> (String, String, String) {
+    let (asset_id, content_hash, _path) = make_original_asset(db, root, ws, mime, bytes).await;
     let ctx = WriteContext::human(None);
     let mut derived = LoomBlockDerived::default();
     derived.full_text_index = Some("media tiers fixture".to_string());
@@ -833,3 +842,124 @@ async fn mt259_http_retry_endpoint_requeues_failed_tier() {
     assert_eq!(row.status, MediaTierStatus::Pending);
     assert_eq!(row.attempt_count, 1);
 }
+
+/// Dispatch a real preview-generate job for (block, asset) and wait for it.
+async fn run_preview_job(state: &AppState, ws: &str, block_id: &str, asset_id: &str) -> JobState {
+    let profile = state
+        .capability_registry
+        .profile_for_job_request(JobKind::LoomPreviewGenerate.as_str(), "hsk.loom.preview_generate@v1")
+        .expect("profile");
+    let job = handshake_core::jobs::create_job(
+        state,
+        JobKind::LoomPreviewGenerate,
+        "hsk.loom.preview_generate@v1",
+        profile.id.as_str(),
+        Some(serde_json::json!({
+            "workspace_id": ws,
+            "block_id": block_id,
+            "asset_id": asset_id,
+        })),
+        Vec::new(),
+    )
+    .await
+    .expect("create job");
+    let job_id = job.job_id.to_string();
+    handshake_core::workflows::start_workflow_for_job(state, job)
+        .await
+        .expect("start workflow");
+    wait_for_job_done(state, &job_id).await
+}
+
+// ---------------------------------------------------------------------------
+// 7. Formats beyond PNG/JPEG: WebP, GIF and TIFF decode in-process and SVG is
+//    rasterised, each producing a ready thumb + preview (no poster) pyramid.
+// ---------------------------------------------------------------------------
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn preview_job_renders_webp_gif_tiff_and_svg_originals() {
+    let pg = pg_or_skip!("preview_formats");
+    let ws = pg.create_workspace().await;
+    let tmp = tempfile::tempdir().expect("tempdir");
+    std::env::set_var("HANDSHAKE_WORKSPACE_ROOT", tmp.path());
+    let (state, _recorder) = loom_state(&pg).await;
+
+    let encode = |format: image::ImageFormat| {
+        let decoded = image::load_from_memory(&real_png(600, 300)).expect("decode png");
+        let mut out = Vec::new();
+        decoded
+            .write_to(&mut std::io::Cursor::new(&mut out), format)
+            .expect("encode fixture");
+        out
+    };
+    let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="32">
+        <rect width="64" height="32" fill="#3366cc"/></svg>"##
+        .to_vec();
+    let originals = [
+        ("image/webp", encode(image::ImageFormat::WebP)),
+        ("image/gif", encode(image::ImageFormat::Gif)),
+        ("image/tiff", encode(image::ImageFormat::Tiff)),
+        ("image/svg+xml", svg),
+    ];
+
+    for (mime, bytes) in originals {
+        let (block_id, asset_id, _hash) =
+            make_media_block(&pg.db, tmp.path(), &ws, mime, &bytes).await;
+        let final_state = run_preview_job(&state, &ws, &block_id, &asset_id).await;
+        assert_eq!(final_state, JobState::Completed, "{mime} preview job completed");
+
+        let tiers = pg.db.list_media_tiers(&ws, &asset_id).await.expect("tiers");
+        let mut names: Vec<MediaTier> = tiers.iter().map(|t| t.tier).collect();
+        names.sort_by_key(|t| t.as_str());
+        assert_eq!(
+            names,
+            vec![MediaTier::Full, MediaTier::Preview, MediaTier::Thumb],
+            "{mime}: thumb + preview + full, no poster"
+        );
+        assert!(
+            tiers.iter().all(|t| t.status == MediaTierStatus::Ready),
+            "{mime}: every tier ready"
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 8. Video with no ffmpeg on this machine: honest failed thumb/preview/poster
+//    tiers carrying `ffmpeg_not_found` (never a synthetic poster), visible in
+//    the retry queue.
+// ---------------------------------------------------------------------------
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn preview_job_without_ffmpeg_records_honest_failed_video_tiers() {
+    let pg = pg_or_skip!("preview_video_without_ffmpeg");
+    let ws = pg.create_workspace().await;
+    let tmp = tempfile::tempdir().expect("tempdir");
+    std::env::set_var("HANDSHAKE_WORKSPACE_ROOT", tmp.path());
+    // A pinned path that names no file means "absent", without falling back
+    // to PATH.
+    let missing = tmp.path().join("no-such-ffmpeg");
+    std::env::set_var("HANDSHAKE_FFMPEG_PATH", &missing);
+    std::env::set_var("HANDSHAKE_FFPROBE_PATH", &missing);
+    let (state, _recorder) = loom_state(&pg).await;
+
+    let (block_id, asset_id, _hash) =
+        make_media_block(&pg.db, tmp.path(), &ws, "video/mp4", b"\0\0\0\x18ftypmp42").await;
+    let final_state = run_preview_job(&state, &ws, &block_id, &asset_id).await;
+    std::env::remove_var("HANDSHAKE_FFMPEG_PATH");
+    std::env::remove_var("HANDSHAKE_FFPROBE_PATH");
+    assert_eq!(final_state, JobState::CompletedWithIssues);
+
+    let tiers = pg.db.list_media_tiers(&ws, &asset_id).await.expect("tiers");
+    let failed: Vec<MediaTier> = tiers
+        .iter()
+        .filter(|t| t.status == MediaTierStatus::Failed)
+        .map(|t| t.tier)
+        .collect();
+    assert_eq!(failed.len(), 3, "thumb, preview and poster failed: {failed:?}");
+    assert!(failed.contains(&MediaTier::Poster));
+    assert!(tiers
+        .iter()
+        .all(|t| t.failure_reason.as_deref() == Some("ffmpeg_not_found")));
+    assert_eq!(
+        pg.db.list_failed_media_tiers(&ws).await.expect("failed").len(),
+        3,
+        "all three are in the retry queue"
+    );
+}
@@ -10,7 +10,9 @@
 //!   * semantic: pgvector HNSW kNN over REAL embeddings + hybrid keyword+vector,
 //!   * graph-blend: content_type facets + loom_edges degree ranking,
 //!   * reindex consistency: edit -> reflected, delete -> gone (NEGATIVE proof),
-//!   * no-model: typed keyword/trigram fallback with NO fabricated semantic.
+//!   * no-model: typed keyword/trigram fallback with NO fabricated semantic,
+//!   * rerank: the optional cross-encoder reorders the page; without one the
+//!     hybrid order is kept.
 //!
 //! The semantic modality uses `InMemoryLlmClient::with_embedding_dim(768)`, an
 //! HONEST embedding substitute: the vector is a REAL deterministic function of
@@ -22,6 +24,10 @@
 
 mod knowledge_pg_support;
 
+use async_trait::async_trait;
+use handshake_core::knowledge_retrieval::rerank::{
+    CrossEncoder, RerankError, RerankPolicy, RerankStatus,
+};
 use handshake_core::llm::ollama::InMemoryLlmClient;
 use handshake_core::llm::DisabledLlmClient;
 use handshake_core::loom_search;
@@ -389,3 +395,103 @@ async fn mt264_no_model_typed_fallback_no_fabrication() {
         "no fabricated vector similarity when no model is configured"
     );
 }
+
+/// Stub cross-encoder: full relevance for texts containing `needle`.
+struct NeedleEncoder {
+    needle: &'static str,
+}
+
+#[async_trait]
+impl CrossEncoder for NeedleEncoder {
+    fn model_ref(&self) -> String {
+        "test/needle".to_string()
+    }
+
+    async fn score_pairs(&self, _query: &str, texts: &[String]) -> Result<Vec<f64>, RerankError> {
+        Ok(texts
+            .iter()
+            .map(|text| if text.contains(self.needle) { 1.0 } else { 0.0 })
+            .collect())
+    }
+}
+
+/// Rerank: the cross-encoder lifts the passage that answers the query above
+/// the keyword-heavier one and each hit carries its rerank score; with no
+/// encoder the page keeps its hybrid order and the record says why.
+#[tokio::test]
+async fn mt264_search_reranked_reorders_page_and_keeps_order_without_model() {
+    let pg = pg_or_skip!();
+    let ws = pg.create_workspace().await;
+    let ctx = WriteContext::human(None);
+    let llm = DisabledLlmClient::new("none".into(), "no embedding model".into());
+
+    make_block(
+        &pg.db,
+        &ctx,
+        &ws,
+        "Database migration database migration",
+        "Database migration checklist: database migration, database migration, database migration.",
+    )
+    .await;
+    make_block(
+        &pg.db,
+        &ctx,
+        &ws,
+        "Rollback answer",
+        "To undo a database change, restore the snapshot taken before it ran.",
+    )
+    .await;
+
+    let base = loom_search::search(&pg.db, &llm, &ws, req("database migration"))
+        .await
+        .expect("search");
+    assert_eq!(base.hits.len(), 2, "both blocks match the keyword query");
+    assert_eq!(
+        base.hits[0].block.title.as_deref(),
+        Some("Database migration database migration"),
+        "the keyword-heavy block leads the hybrid order"
+    );
+
+    let encoder = NeedleEncoder { needle: "snapshot" };
+    let (reranked, record) = loom_search::search_reranked(
+        &pg.db,
+        &llm,
+        Some(&encoder),
+        &ws,
+        req("database migration"),
+        &RerankPolicy::default(),
+    )
+    .await
+    .expect("search reranked");
+    assert_eq!(record.status, RerankStatus::Applied);
+    assert_eq!(record.model_ref.as_deref(), Some("test/needle"));
+    assert_eq!(record.scored, 2);
+    assert_eq!(
+        reranked.hits[0].block.title.as_deref(),
+        Some("Rollback answer"),
+        "the encoder's relevant passage moves to the top"
+    );
+    assert_eq!(reranked.hits[0].rerank_score, Some(1.0));
+    assert_eq!(reranked.hits[1].rerank_score, Some(0.0));
+    assert!(!record.moves.is_empty(), "the record explains the move");
+
+    let (unranked, record) = loom_search::search_reranked(
+        &pg.db,
+        &llm,
+        None,
+        &ws,
+        req("database migration"),
+        &RerankPolicy::default(),
+    )
+    .await
+    .expect("search without reranker");
+    assert_eq!(record.status, RerankStatus::Skipped);
+    assert!(!record.reason.is_empty(), "the skip records why");
+    let order = |hits: &[handshake_core::storage::LoomSearchV2Hit]| {
+        hits.iter()
+            .map(|hit| hit.block.block_id.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(order(&unranked.hits), order(&base.hits));
+    assert!(unranked.hits.iter().all(|hit| hit.rerank_score.is_none()));
+}
@@ -0,0 +1,160 @@
+//! Loom -> markdown vault export — REAL PostgreSQL proof.
+//!
+//! Imports a small Obsidian vault, exports its root folder back out and
+//! checks that:
+//!   * the export reproduces the vault's layout, journal named by date;
+//!   * every note carries `hsk_block_id` front-matter naming its block;
+//!   * importing the export under another vault name binds every file back
+//!     to the block it came from — no note or tag hub is duplicated.
+
+mod knowledge_pg_support;
+
+use std::sync::Arc;
+
+use handshake_core::loom_vault::export::{
+    LoomVaultExportRequest, LoomVaultExporter, VAULT_EXPORT_MANIFEST_PATH,
+};
+use handshake_core::loom_vault::import::{LoomVaultImportRequest, LoomVaultImporter};
+use handshake_core::storage::loom_vault::list_loom_vault_entries;
+use handshake_core::storage::postgres::PostgresDatabase;
+use handshake_core::storage::WriteContext;
+use knowledge_pg_support::{knowledge_pg, KnowledgePg};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+macro_rules! pg_or_skip {
+    () => {{
+        match knowledge_pg().await {
+            Some(pg) => pg,
+            None => {
+                eprintln!("SKIP loom vault export proof: PostgreSQL unavailable");
+                return;
+            }
+        }
+    }};
+}
+
+async fn handle(pg: &KnowledgePg) -> (PgPool, Arc<PostgresDatabase>) {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&pg.schema_url)
+        .await
+        .expect("connect pool");
+    (pool.clone(), Arc::new(PostgresDatabase::new(pool)))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn vault_export_round_trips_through_import_onto_the_same_blocks() {
+    let pg = pg_or_skip!();
+    let ws = pg.create_workspace().await;
+    let (pool, db) = handle(&pg).await;
+    let importer = LoomVaultImporter::new(db.clone(), vec![std::env::temp_dir()]);
+    let exporter = LoomVaultExporter::new(db);
+    let ctx = WriteContext::human(None);
+
+    let source = tempfile::tempdir().expect("source vault");
+    let root = source.path();
+    std::fs::create_dir_all(root.join(".obsidian")).expect("obsidian config");
+    std::fs::create_dir_all(root.join("notes")).expect("notes dir");
+    std::fs::write(
+        root.join("Index.md"),
+        "# Index\n\nSee [[Alpha]].\n#project/alpha\n",
+    )
+    .expect("write index");
+    std::fs::write(
+        root.join("notes/Alpha.md"),
+        "---\naliases: [A]\nstatus: draft\n---\nBack to [[Index]].\n",
+    )
+    .expect("write alpha");
+    std::fs::write(root.join("2024-01-31.md"), "Worked on [[A]].\n").expect("write journal");
+
+    let imported = importer
+        .import(
+            &ctx,
+            &ws,
+            &LoomVaultImportRequest {
+                vault_path: root.to_path_buf(),
+                vault_kind: None,
+                vault_name: Some("source".to_string()),
+            },
+        )
+        .await
+        .expect("import source vault");
+    assert_eq!(imported.counts.notes_created, 3);
+
+    let vault = exporter
+        .export(
+            &ws,
+            &LoomVaultExportRequest {
+                folder_id: imported.root_folder_id.clone(),
+            },
+        )
+        .await
+        .expect("export");
+    let paths: Vec<&str> = vault
+        .manifest
+        .files
+        .iter()
+        .map(|f| f.relative_path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["2024-01-31.md", "Index.md", "notes/Alpha.md"]);
+    assert_eq!(vault.manifest.counts.notes, 3);
+    assert_eq!(vault.manifest.counts.journals, 1);
+
+    let source_entries = list_loom_vault_entries(&pool, &ws, "source")
+        .await
+        .expect("source entries");
+    for file in &vault.manifest.files {
+        let entry = source_entries
+            .iter()
+            .find(|e| e.relative_path == file.relative_path)
+            .unwrap_or_else(|| panic!("source entry for {}", file.relative_path));
+        assert_eq!(entry.block_id, file.block_id);
+        let text = String::from_utf8(vault.files[&file.relative_path].clone()).expect("utf8");
+        assert!(
+            text.contains(&format!("hsk_block_id: {}", file.block_id)),
+            "{} lacks its block id:\n{text}",
+            file.relative_path
+        );
+    }
+    let alpha = String::from_utf8(vault.files["notes/Alpha.md"].clone()).expect("utf8");
+    assert!(alpha.contains("status: draft"), "kept property:\n{alpha}");
+
+    // Write it out and import it again under a new name.
+    let target = tempfile::tempdir().expect("export dir");
+    vault
+        .write_to_dir(target.path(), false)
+        .expect("write export");
+    assert!(target.path().join(VAULT_EXPORT_MANIFEST_PATH).is_file());
+    assert!(
+        vault.write_to_dir(target.path(), false).is_err(),
+        "a non-empty target needs overwrite"
+    );
+
+    let reimported = importer
+        .import(
+            &ctx,
+            &ws,
+            &LoomVaultImportRequest {
+                vault_path: target.path().to_path_buf(),
+                vault_kind: None,
+                vault_name: Some("round-trip".to_string()),
+            },
+        )
+        .await
+        .expect("re-import export");
+    assert_eq!(reimported.counts.notes_created, 0);
+    assert_eq!(reimported.counts.tag_hubs_created, 0);
+    assert!(reimported.unresolved_links.is_empty());
+
+    let round_trip = list_loom_vault_entries(&pool, &ws, "round-trip")
+        .await
+        .expect("round-trip entries");
+    for file in &vault.manifest.files {
+        let entry = round_trip
+            .iter()
+            .find(|e| e.relative_path == file.relative_path)
+            .unwrap_or_else(|| panic!("round-trip entry for {}", file.relative_path));
+        assert_eq!(entry.block_id, file.block_id, "{}", file.relative_path);
+    }
+}
@@ -0,0 +1,212 @@
+//! MT-187 Obsidian / Logseq vault import — REAL PostgreSQL proof.
+//!
+//! Imports a small Obsidian vault from disk and checks that:
+//!   * notes become Note blocks, the daily note becomes the journal block of
+//!     its date, tags become TagHub blocks with SUB_TAG nesting;
+//!   * wikilinks (by name and by alias) become MENTION edges, dangling links
+//!     land in the report instead of failing the run;
+//!   * a re-import of an unchanged vault changes nothing, an edited note is
+//!     updated in place (same block) and its stale link edge is retired;
+//!   * a file removed from the vault is reported missing, its block is kept.
+
+mod knowledge_pg_support;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use handshake_core::loom_vault::import::{LoomVaultImportRequest, LoomVaultImporter};
+use handshake_core::loom_vault::{LoomVaultError, VaultKind};
+use handshake_core::storage::loom_vault::{list_loom_vault_entries, list_loom_vault_imports};
+use handshake_core::storage::postgres::PostgresDatabase;
+use handshake_core::storage::{Database, LoomBlockContentType, LoomEdgeType, WriteContext};
+use knowledge_pg_support::{knowledge_pg, KnowledgePg};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+macro_rules! pg_or_skip {
+    () => {{
+        match knowledge_pg().await {
+            Some(pg) => pg,
+            None => {
+                eprintln!("SKIP MT-187 loom vault import proof: PostgreSQL unavailable");
+                return;
+            }
+        }
+    }};
+}
+
+async fn handle(pg: &KnowledgePg) -> (PgPool, Arc<PostgresDatabase>) {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&pg.schema_url)
+        .await
+        .expect("connect pool");
+    (pool.clone(), Arc::new(PostgresDatabase::new(pool)))
+}
+
+fn write(root: &Path, relative_path: &str, text: &str) {
+    let path = root.join(relative_path);
+    std::fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+    std::fs::write(path, text).expect("write vault file");
+}
+
+fn request(root: &Path) -> LoomVaultImportRequest {
+    LoomVaultImportRequest {
+        vault_path: root.to_path_buf(),
+        vault_kind: None,
+        vault_name: Some("test-vault".to_string()),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn vault_import_maps_notes_links_tags_and_reimports_in_place() {
+    let pg = pg_or_skip!();
+    let ws = pg.create_workspace().await;
+    let (pool, db) = handle(&pg).await;
+    let importer = LoomVaultImporter::new(db, vec![std::env::temp_dir()]);
+    let ctx = WriteContext::human(None);
+
+    let vault = tempfile::tempdir().expect("vault dir");
+    let root = vault.path();
+    std::fs::create_dir_all(root.join(".obsidian")).expect("obsidian config");
+    write(
+        root,
+        "Index.md",
+        "# Index\n\nSee [[Alpha]] and [[A|the alias]], not [[Missing Note]].\n#project/alpha\n",
+    );
+    write(
+        root,
+        "notes/Alpha.md",
+        "---\naliases: [A]\ntags: [project]\n---\nBack to [[Index]].\n",
+    );
+    write(root, "2024-01-31.md", "Worked on [[Alpha]].\n");
+
+    let first = importer
+        .import(&ctx, &ws, &request(root))
+        .await
+        .expect("first import");
+    assert_eq!(first.vault_kind, VaultKind::Obsidian);
+    assert_eq!(first.counts.notes_created, 3);
+    assert_eq!(first.counts.journals, 1);
+    assert_eq!(first.counts.tag_hubs_created, 2);
+    assert_eq!(first.unresolved_links.len(), 1);
+    assert_eq!(first.unresolved_links[0].target, "Missing Note");
+    assert_eq!(first.unresolved_links[0].source_path, "Index.md");
+    assert!(first.missing_paths.is_empty());
+
+    let entries = list_loom_vault_entries(&pool, &ws, "test-vault")
+        .await
+        .expect("entries");
+    let block_of = |path: &str| {
+        entries
+            .iter()
+            .find(|e| e.relative_path == path)
+            .unwrap_or_else(|| panic!("entry for {path}"))
+            .block_id
+            .clone()
+    };
+    let index = block_of("Index.md");
+    let alpha = block_of("notes/Alpha.md");
+    let journal = block_of("2024-01-31.md");
+
+    let journal_block = pg.db.get_loom_block(&ws, &journal).await.expect("journal");
+    assert_eq!(journal_block.content_type, LoomBlockContentType::Journal);
+    assert_eq!(journal_block.journal_date.as_deref(), Some("2024-01-31"));
+
+    // Name and alias links to Alpha collapse into one MENTION edge; the tag
+    // lands on the nested hub, which is a SUB_TAG of its parent.
+    let out = pg.db.get_outgoing_edges(&ws, &index).await.expect("edges");
+    let mentions: Vec<&str> = out
+        .iter()
+        .filter(|e| e.edge_type == LoomEdgeType::Mention)
+        .map(|e| e.target_block_id.as_str())
+        .collect();
+    assert_eq!(mentions, vec![alpha.as_str()]);
+    let tag_target = out
+        .iter()
+        .find(|e| e.edge_type == LoomEdgeType::Tag)
+        .expect("tag edge")
+        .target_block_id
+        .clone();
+    let hub = pg.db.get_loom_block(&ws, &tag_target).await.expect("hub");
+    assert_eq!(hub.content_type, LoomBlockContentType::TagHub);
+    assert_eq!(hub.title.as_deref(), Some("project/alpha"));
+    let hub_parents = pg
+        .db
+        .get_outgoing_edges(&ws, &tag_target)
+        .await
+        .expect("hub edges");
+    assert!(hub_parents
+        .iter()
+        .any(|e| e.edge_type == LoomEdgeType::SubTag));
+
+    // Unchanged vault: nothing is created, updated or rewired.
+    let again = importer
+        .import(&ctx, &ws, &request(root))
+        .await
+        .expect("re-import");
+    assert_eq!(again.counts.notes_created, 0);
+    assert_eq!(again.counts.notes_updated, 0);
+    assert_eq!(again.counts.notes_unchanged, 3);
+    assert_eq!(again.counts.tag_hubs_created, 0);
+    assert_eq!(again.counts.edges_created, 0);
+    assert_eq!(again.counts.edges_removed, 0);
+
+    // Edit Index (drop the Alpha links) and remove the daily note.
+    write(root, "Index.md", "# Index\n\nNothing linked any more.\n");
+    std::fs::remove_file(root.join("2024-01-31.md")).expect("remove journal");
+    let third = importer
+        .import(&ctx, &ws, &request(root))
+        .await
+        .expect("third import");
+    assert_eq!(third.counts.notes_updated, 1);
+    assert!(third.counts.edges_removed >= 2);
+    assert_eq!(third.missing_paths, vec!["2024-01-31.md".to_string()]);
+
+    let entries = list_loom_vault_entries(&pool, &ws, "test-vault")
+        .await
+        .expect("entries after edit");
+    let index_entry = entries
+        .iter()
+        .find(|e| e.relative_path == "Index.md")
+        .expect("index entry");
+    assert_eq!(index_entry.block_id, index, "edited note keeps its block");
+    let out = pg.db.get_outgoing_edges(&ws, &index).await.expect("edges");
+    assert!(out.iter().all(|e| e.target_block_id != alpha));
+    // The vault is a source, not an authority: the journal block survives.
+    pg.db
+        .get_loom_block(&ws, &journal)
+        .await
+        .expect("journal block kept");
+
+    let runs = list_loom_vault_imports(&pool, &ws, 10)
+        .await
+        .expect("import runs");
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0].import_id, third.import_id);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn vault_import_refuses_a_path_outside_the_import_roots() {
+    let pg = pg_or_skip!();
+    let ws = pg.create_workspace().await;
+    let (pool, db) = handle(&pg).await;
+    let allowed = tempfile::tempdir().expect("import root");
+    let importer = LoomVaultImporter::new(db, vec![allowed.path().to_path_buf()]);
+    let ctx = WriteContext::human(None);
+
+    let vault = tempfile::tempdir().expect("vault dir");
+    write(vault.path(), "Index.md", "# Index\n");
+    let err = importer
+        .import(&ctx, &ws, &request(vault.path()))
+        .await
+        .expect_err("vault outside the import roots");
+    assert!(
+        matches!(err, LoomVaultError::Validation(_)),
+        "unexpected error: {err}"
+    );
+    let runs = list_loom_vault_imports(&pool, &ws, 10)
+        .await
+        .expect("import runs");
+    assert!(runs.is_empty(), "a refused import records no run");
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use handshake_core::capabilities::CapabilityRegistry;
+use handshake_core::cassette::{
+    CassetteError, CassetteSink, RecordingMcpTransport, ReplayMcpTransport,
+};
+use handshake_core::flight_recorder::duckdb::DuckDbFlightRecorder;
+use handshake_core::flight_recorder::FlightRecorder;
+use handshake_core::mcp::errors::McpError;
+use handshake_core::mcp::gate::{
+    canonical_mcp_tool_id, ConsentDecision, GateConfig, GatedMcpClient, McpContext,
+    StaticConsentProvider, ToolPolicy, ToolRegistryEntry, ToolTransportBindings,
+};
+use handshake_core::mcp::jsonrpc::{JsonRpcMessage, JsonRpcResponse};
+use handshake_core::mcp::transport::duplex::DuplexTransport;
+use handshake_core::mcp::transport::McpTransport;
+use handshake_core::storage::AccessMode;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter, DuplexStream};
+use uuid::Uuid;
+
+const SERVER_ID: &str = "cassette-mcp";
+
+async fn echo_server(stream: DuplexStream) {
+    let (read_half, write_half) = tokio::io::split(stream);
+    let mut lines = BufReader::new(read_half).lines();
+    let mut writer = BufWriter::new(write_half);
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(JsonRpcMessage::Request(req)) = serde_json::from_str(&line) else {
+            continue;
+        };
+        let message = req
+            .params
+            .as_ref()
+            .and_then(|v| v.pointer("/arguments/message"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let response = JsonRpcResponse::ok(req.id, json!({ "echoed": message }));
+        let line = serde_json::to_string(&JsonRpcMessage::Response(response)).unwrap();
+        writer.write_all(line.as_bytes()).await.unwrap();
+        writer.write_all(b"\n").await.unwrap();
+        writer.flush().await.unwrap();
+    }
+}
+
+fn echo_gate() -> GateConfig {
+    let tool_id = canonical_mcp_tool_id(SERVER_ID, "echo");
+    let mut gate = GateConfig::minimal();
+    gate.tool_registry.push(ToolRegistryEntry {
+        tool_id: tool_id.clone(),
+        tool_version: "1.0.0".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": { "message": { "type": "string" } },
+            "required": ["message"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+        side_effect: "READ".to_string(),
+        idempotency: "IDEMPOTENT".to_string(),
+        determinism: "DETERMINISTIC".to_string(),
+        availability: "AVAILABLE".to_string(),
+        required_capabilities: vec!["fs.read".to_string()],
+        transport_bindings: ToolTransportBindings {
+            mcp_name: "echo".to_string(),
+        },
+    });
+    gate.tool_policies.insert(
+        tool_id,
+        ToolPolicy {
+            required_capability: Some("fs.read".to_string()),
+            requires_consent: false,
+            path_argument: None,
+        },
+    );
+    gate
+}
+
+fn fresh_ctx() -> McpContext {
+    McpContext {
+        job_id: Some(Uuid::now_v7()),
+        trace_id: Uuid::now_v7(),
+        session_id: None,
+        task_id: None,
+        workflow_run_id: None,
+        granted_capabilities: vec!["fs.read".to_string()],
+        access_mode: AccessMode::AnalysisOnly,
+        human_consent_obtained: false,
+        agentic_mode_enabled: false,
+        allowed_roots: Vec::new(),
+    }
+}
+
+async fn connect<T: McpTransport + 'static>(
+    transport: T,
+) -> Result<GatedMcpClient, Box<dyn std::error::Error>> {
+    let flight_recorder: Arc<dyn FlightRecorder> =
+        Arc::new(DuckDbFlightRecorder::new_in_memory(7)?);
+    Ok(GatedMcpClient::connect(
+        SERVER_ID,
+        transport,
+        flight_recorder,
+        Arc::new(CapabilityRegistry::new()),
+        Arc::new(StaticConsentProvider::new(ConsentDecision::Allow)),
+        echo_gate(),
+        false,
+    )
+    .await?)
+}
+
+#[tokio::test]
+async fn recorded_tool_calls_replay_through_the_gate_without_a_server(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("mcp.cassette.json");
+    let tool_id = canonical_mcp_tool_id(SERVER_ID, "echo");
+
+    let (client_stream, server_stream) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(echo_server(server_stream));
+    let recording = RecordingMcpTransport::new(
+        DuplexTransport::new(client_stream),
+        SERVER_ID,
+        CassetteSink::File(path.clone()),
+    );
+    let live = connect(recording).await?;
+    for message in ["hi", "there"] {
+        let result = live
+            .tools_call(fresh_ctx(), tool_id.as_str(), json!({ "message": message }))
+            .await?;
+        assert_eq!(result["echoed"], message);
+    }
+    drop(live);
+
+    // Replay runs with new job/trace ids and no server behind the transport.
+    let replay = ReplayMcpTransport::from_path(&path)?;
+    let player = replay.player().clone();
+    let offline = connect(replay).await?;
+    let result = offline
+        .tools_call(fresh_ctx(), tool_id.as_str(), json!({ "message": "hi" }))
+        .await?;
+    assert_eq!(result["echoed"], "hi");
+
+    let err = offline
+        .tools_call(fresh_ctx(), tool_id.as_str(), json!({ "message": "bye" }))
+        .await
+        .expect_err("arguments differ from the recording");
+    let McpError::Cassette(CassetteError::Mismatch(mismatch)) = err else {
+        panic!("expected a typed cassette mismatch, got {err:?}");
+    };
+    assert_eq!(mismatch.index, 1);
+    assert_eq!(mismatch.diff.len(), 1);
+    assert_eq!(mismatch.diff[0].path, "$.params.arguments.message");
+    assert_eq!(mismatch.diff[0].expected, Some(json!("there")));
+    assert_eq!(mismatch.diff[0].actual, Some(json!("bye")));
+
+    let result = offline
+        .tools_call(fresh_ctx(), tool_id.as_str(), json!({ "message": "there" }))
+        .await?;
+    assert_eq!(result["echoed"], "there");
+    player.finish()?;
+    Ok(())
+}
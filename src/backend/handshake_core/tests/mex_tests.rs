@@ -1651,3 +1651,163 @@ async fn ci_license_scan_runs_via_terminal_service() {
         "expected FR-EVT-001 terminal_command event"
     );
 }
+
+#[cfg(feature = "wasm-engines")]
+const WASM_STDOUT_AND_LOG: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "handshake" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "ok")
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 16))
+    (i32.store (i32.const 4) (i32.const 2))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (call $log (i32.const 16) (i32.const 2))))"#;
+
+#[cfg(feature = "wasm-engines")]
+const WASM_LOG_FLOOD: &str = r#"(module
+  (import "handshake" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "_start") (loop (call $log (i32.const 0) (i32.const 64)) (br 0))))"#;
+
+/// A MEX runtime whose only engine is `engine.wasm`, backed by `module` written under
+/// `<workspace>/engines/` and registered with `sha256`.
+#[cfg(feature = "wasm-engines")]
+fn wasm_runtime(workspace: &std::path::Path, module: &str, sha256: String) -> MexRuntime {
+    use handshake_core::mex::{WasmEngineAdapter, WasmModuleSpec};
+
+    std::fs::create_dir_all(workspace.join("engines")).expect("engines dir");
+    std::fs::write(workspace.join("engines/engine.wat"), module).expect("module written");
+    let mut spec = single_engine_registry("engine.wasm")
+        .engines()
+        .next()
+        .cloned()
+        .expect("fixture engine");
+    spec.wasm = Some(WasmModuleSpec {
+        module: "engines/engine.wat".to_string(),
+        sha256,
+    });
+    let registry = MexRegistry::from_map(std::collections::HashMap::from([(
+        "engine.wasm".to_string(),
+        spec.clone(),
+    )]));
+    let adapter = WasmEngineAdapter::new(workspace.to_path_buf(), spec).expect("wasm adapter");
+
+    let recorder = recorder();
+    let flight_recorder: Arc<dyn FlightRecorder> = recorder.clone();
+    let diagnostics: Arc<dyn DiagnosticsStore> = recorder;
+    MexRuntime::new(registry, flight_recorder, diagnostics, mex_gates())
+        .with_adapter("engine.wasm", Arc::new(adapter))
+}
+
+#[cfg(feature = "wasm-engines")]
+fn wasm_op(output_bytes: u64) -> PlannedOperation {
+    PlannedOperation {
+        schema_version: POE_SCHEMA_VERSION.to_string(),
+        op_id: Uuid::now_v7(),
+        engine_id: "engine.wasm".to_string(),
+        engine_version_req: None,
+        operation: "conformance.test".to_string(),
+        inputs: Vec::new(),
+        params: serde_json::json!({}),
+        capabilities_requested: vec!["fs.read".to_string(), "fs.write".to_string()],
+        capability_profile_id: Some("Coder".to_string()),
+        human_consent_obtained: false,
+        budget: BudgetSpec {
+            cpu_time_ms: Some(1000),
+            wall_time_ms: Some(5000),
+            memory_bytes: Some(64 * 1024 * 1024),
+            output_bytes: Some(output_bytes),
+        },
+        determinism: DeterminismLevel::D2,
+        evidence_policy: Some(EvidencePolicy {
+            required: true,
+            notes: None,
+        }),
+        output_spec: OutputSpec {
+            expected_types: vec!["artifact.document".to_string()],
+            max_bytes: Some(output_bytes),
+        },
+    }
+}
+
+#[cfg(feature = "wasm-engines")]
+fn sha256_of(module: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(module.as_bytes()))
+}
+
+#[cfg(feature = "wasm-engines")]
+#[tokio::test]
+async fn wasm_engine_runs_through_the_mex_runtime() {
+    use handshake_core::mex::envelope::EngineStatus;
+
+    let workspace = tempdir().expect("workspace");
+    let runtime = wasm_runtime(
+        workspace.path(),
+        WASM_STDOUT_AND_LOG,
+        sha256_of(WASM_STDOUT_AND_LOG),
+    );
+
+    let result = runtime
+        .execute(wasm_op(1024 * 1024))
+        .await
+        .expect("runtime should succeed");
+    assert_eq!(
+        result.status,
+        EngineStatus::Succeeded,
+        "{:?}",
+        result.errors
+    );
+    assert_eq!(result.outputs.len(), 1, "stdout becomes one artifact");
+    assert!(
+        result.logs_ref.is_some(),
+        "handshake.log becomes the log artifact"
+    );
+    assert_eq!(
+        result.provenance.implementation.as_deref(),
+        Some(handshake_core::mex::wasm::WASM_IMPLEMENTATION)
+    );
+    let stdout = std::fs::read(
+        workspace
+            .path()
+            .join(&result.outputs[0].path)
+            .join("payload"),
+    )
+    .expect("stdout artifact payload");
+    assert_eq!(stdout, b"ok");
+}
+
+#[cfg(feature = "wasm-engines")]
+#[tokio::test]
+async fn wasm_engine_with_a_mismatched_digest_is_denied() {
+    use handshake_core::mex::envelope::EngineStatus;
+
+    let workspace = tempdir().expect("workspace");
+    let runtime = wasm_runtime(workspace.path(), WASM_STDOUT_AND_LOG, "ab".repeat(32));
+
+    let result = runtime
+        .execute(wasm_op(1024 * 1024))
+        .await
+        .expect("runtime should return the denial");
+    assert_eq!(result.status, EngineStatus::Denied);
+    assert_eq!(result.errors[0].code, "ENGINE_WASM_INTEGRITY");
+    assert!(result.outputs.is_empty());
+}
+
+#[cfg(feature = "wasm-engines")]
+#[tokio::test]
+async fn wasm_engine_is_stopped_when_its_log_passes_the_output_budget() {
+    use handshake_core::mex::envelope::EngineStatus;
+
+    let workspace = tempdir().expect("workspace");
+    let runtime = wasm_runtime(workspace.path(), WASM_LOG_FLOOD, sha256_of(WASM_LOG_FLOOD));
+
+    let result = runtime
+        .execute(wasm_op(4096))
+        .await
+        .expect("runtime should return the failure");
+    assert_eq!(result.status, EngineStatus::Failed);
+    assert_eq!(result.errors[0].code, "ENGINE_WASM_OUTPUT_BUDGET");
+    assert!(result.outputs.is_empty());
+}
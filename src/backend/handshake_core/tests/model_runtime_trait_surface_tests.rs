@@ -117,6 +117,7 @@ fn error_variant_completeness_has_display_text_and_unique_discriminants() {
         },
         ModelRuntimeError::KvCacheError("kv".to_string()),
         ModelRuntimeError::LoraStackError("lora".to_string()),
+        ModelRuntimeError::TrainingError("training".to_string()),
         ModelRuntimeError::SteeringHookError("steering".to_string()),
         ModelRuntimeError::Cancelled,
         ModelRuntimeError::AdapterMismatch {
@@ -126,7 +127,7 @@ fn error_variant_completeness_has_display_text_and_unique_discriminants() {
     ];
     let mut discriminants = HashSet::new();
 
-    assert_eq!(variants.len(), 11);
+    assert_eq!(variants.len(), 12);
     for variant in &variants {
         assert!(
             !variant.to_string().trim().is_empty(),
@@ -0,0 +1,218 @@
+//! Managed model store: HTTP import with resume against a local fixture,
+//! registry resolution through the store, and gc of unreferenced objects.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use handshake_core::model_runtime::store::{
+    ModelDownloadRequest, ModelImportRequest, ModelSource, ModelStore, ModelStoreError,
+};
+use handshake_core::model_runtime::{ModelCapabilities, ModelRegistry, OperatorId, RuntimeBinding};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn gguf_artifact(len: usize) -> Vec<u8> {
+    let mut bytes = b"GGUF".to_vec();
+    bytes.extend_from_slice(&3_u32.to_le_bytes());
+    bytes.extend((0..len).map(|i| (i % 251) as u8));
+    bytes
+}
+
+/// Serves `body` at any path with an ETag and byte ranges. The first
+/// `truncate_first` responses stop halfway through and drop the connection.
+/// Returns the base URL and the `Range` header of every request.
+async fn fixture(
+    body: Vec<u8>,
+    truncate_first: usize,
+) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let base = format!("http://{}", listener.local_addr().expect("addr"));
+    let ranges = Arc::new(Mutex::new(Vec::new()));
+    let seen = ranges.clone();
+    tokio::spawn(async move {
+        let mut served = 0;
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0_u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match socket.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let text = String::from_utf8_lossy(&request).to_string();
+            let range = text
+                .lines()
+                .find_map(|l| {
+                    l.strip_prefix("range: ")
+                        .or_else(|| l.strip_prefix("Range: "))
+                })
+                .map(str::to_string);
+            seen.lock().unwrap().push(range.clone());
+
+            let start = range
+                .as_deref()
+                .and_then(|r| r.strip_prefix("bytes="))
+                .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok())
+                .unwrap_or(0);
+            let total = body.len();
+            let head = if start > 0 {
+                format!(
+                    "HTTP/1.1 206 Partial Content\r\ncontent-length: {}\r\ncontent-range: bytes \
+                     {start}-{}/{total}\r\netag: \"v1\"\r\nconnection: close\r\n\r\n",
+                    total - start,
+                    total - 1
+                )
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {total}\r\netag: \"v1\"\r\n\
+                     accept-ranges: bytes\r\nconnection: close\r\n\r\n"
+                )
+            };
+            let _ = socket.write_all(head.as_bytes()).await;
+            let end = if served < truncate_first {
+                start + (total - start) / 2
+            } else {
+                total
+            };
+            served += 1;
+            let _ = socket.write_all(&body[start..end]).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+    (base, ranges)
+}
+
+fn download(url: String, name: &str) -> ModelDownloadRequest {
+    ModelDownloadRequest {
+        url,
+        import: ModelImportRequest {
+            name: name.to_string(),
+            ..ModelImportRequest::default()
+        },
+        ..ModelDownloadRequest::default()
+    }
+}
+
+#[tokio::test]
+async fn interrupted_http_import_resumes_with_a_range_request() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = ModelStore::open(dir.path()).expect("open store");
+    let body = gguf_artifact(64 * 1024);
+    let digest = hex::encode(Sha256::digest(&body));
+    let (base, ranges) = fixture(body.clone(), 1).await;
+    let client = reqwest::Client::new();
+    let mut request = download(format!("{base}/tiny.gguf"), "tiny");
+    request.import.expected_sha256 = Some(digest.clone());
+
+    let first = store.import_url(&client, &request).await;
+    assert!(
+        matches!(first, Err(ModelStoreError::Download(_))),
+        "{first:?}"
+    );
+    let usage = store.usage().expect("usage");
+    assert_eq!(usage.partial_downloads, 1);
+    assert!(usage.partial_download_bytes > 0);
+    assert!(store.get("tiny").is_err());
+
+    let manifest = store
+        .import_url(&client, &request)
+        .await
+        .expect("resumed import");
+    assert_eq!(manifest.sha256, digest);
+    assert_eq!(manifest.size_bytes, body.len() as u64);
+    assert_eq!(manifest.runtime_binding, RuntimeBinding::LlamaCpp);
+    assert!(matches!(manifest.source, ModelSource::Http { .. }));
+
+    let ranges = ranges.lock().unwrap().clone();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0], None);
+    let resumed_at = (body.len() / 2).to_string();
+    assert_eq!(ranges[1], Some(format!("bytes={resumed_at}-")));
+
+    let resolved = store.resolve("tiny").expect("resolve");
+    assert_eq!(std::fs::read(&resolved.artifact_path).expect("read"), body);
+    assert!(store.verify("tiny").expect("verify").ok);
+    assert_eq!(store.usage().expect("usage").partial_downloads, 0);
+}
+
+#[tokio::test]
+async fn mismatching_download_is_discarded() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = ModelStore::open(dir.path()).expect("open store");
+    let (base, _) = fixture(gguf_artifact(1024), 0).await;
+    let mut request = download(format!("{base}/tiny.gguf"), "tiny");
+    request.import.expected_sha256 = Some("ab".repeat(32));
+
+    let err = store
+        .import_url(&reqwest::Client::new(), &request)
+        .await
+        .expect_err("digest mismatch");
+    assert!(matches!(err, ModelStoreError::IntegrityMismatch { .. }));
+    let usage = store.usage().expect("usage");
+    assert_eq!(
+        (usage.models, usage.objects, usage.partial_downloads),
+        (0, 0, 0)
+    );
+}
+
+#[tokio::test]
+async fn registry_resolves_stored_models_and_keeps_them_from_gc() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = ModelStore::open(dir.path().join("store")).expect("open store");
+    let (base, _) = fixture(gguf_artifact(2048), 0).await;
+    let client = reqwest::Client::new();
+    let kept = store
+        .import_url(&client, &download(format!("{base}/a.gguf"), "kept"))
+        .await
+        .expect("import kept");
+    let local = dir.path().join("b.gguf");
+    std::fs::write(&local, gguf_artifact(4096)).expect("write local");
+    let dropped = store
+        .import_local(
+            &local,
+            &ModelImportRequest {
+                name: "dropped".to_string(),
+                ..ModelImportRequest::default()
+            },
+        )
+        .expect("import local");
+
+    let mut registry = ModelRegistry::default();
+    let id = registry
+        .register_stored(
+            &store,
+            "kept",
+            ModelCapabilities::default(),
+            OperatorId::new("operator"),
+        )
+        .expect("register stored");
+    let registration = registry.lookup(id).expect("registration");
+    assert_eq!(
+        registration.artifact_path,
+        store.resolve("kept").expect("resolve").artifact_path
+    );
+    assert_eq!(hex::encode(registration.sha256), kept.sha256);
+    assert!(registry
+        .register_stored(
+            &store,
+            "missing",
+            ModelCapabilities::default(),
+            OperatorId::new("operator"),
+        )
+        .is_err());
+
+    // Both catalog entries go; the registered object survives gc.
+    store.remove("kept").expect("remove kept");
+    store.remove("dropped").expect("remove dropped");
+    let keep: BTreeSet<String> = registry.referenced_sha256();
+    let report = store.gc(&keep, true, false).expect("gc");
+    assert_eq!(report.removed_objects, vec![dropped.sha256.clone()]);
+    assert_eq!(report.kept_objects, vec![kept.sha256.clone()]);
+    assert!(store.object_dir(&kept.sha256).is_dir());
+    assert!(!store.object_dir(&dropped.sha256).exists());
+}
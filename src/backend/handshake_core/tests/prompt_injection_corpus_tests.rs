@@ -0,0 +1,252 @@
+//! Labelled adversarial corpus for the prompt-injection tiers (§2.6.6.7.11.6).
+//!
+//! `tests/fixtures/prompt_injection/corpus.jsonl` holds injections written the
+//! way they arrive in retrieved snippets and web, email, and calendar payloads
+//! (literal phrases, Unicode whitespace, base64, percent-escapes, and
+//! paraphrases), alongside benign texts that look like them. Each run prints
+//! the detection rate per technique and pins the floor the tiers must keep.
+//!
+//! `CueClassifier` is a cue lexicon, not a model: it drives the classifier
+//! tier deterministically (pending spans, decoded views reaching the
+//! classifier, the threshold). Point `classify_untrusted` at a
+//! `RuntimeInjectionClassifier` over a loaded model to measure a real one
+//! against the same corpus.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use handshake_core::ace::validators::{
+    classify_untrusted, InjectionClassifier, InjectionClassifierError, InjectionClassifierPolicy,
+    InjectionTier, InjectionVerdict, UntrustedSource, UntrustedSpan,
+};
+use serde::Deserialize;
+
+const CORPUS: &str = include_str!("fixtures/prompt_injection/corpus.jsonl");
+
+#[derive(Debug, Deserialize)]
+struct CorpusEntry {
+    id: String,
+    source: UntrustedSource,
+    label: String,
+    technique: String,
+    text: String,
+}
+
+impl CorpusEntry {
+    fn is_injection(&self) -> bool {
+        self.label == "injection"
+    }
+}
+
+fn corpus() -> Vec<CorpusEntry> {
+    CORPUS
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).expect("corpus entry"))
+        .collect()
+}
+
+fn spans(entries: &[CorpusEntry]) -> Vec<UntrustedSpan> {
+    entries
+        .iter()
+        .map(|entry| UntrustedSpan::new(entry.source, entry.id.clone(), entry.text.clone()))
+        .collect()
+}
+
+struct CueClassifier;
+
+impl CueClassifier {
+    const ADDRESSEE: &'static [&'static str] = &[
+        "assistant",
+        "ai model",
+        "you were told",
+        "you were given",
+        "your earlier",
+        "your real task",
+        "your next reply",
+        "hidden prompt",
+    ];
+    const OVERRIDE: &'static [&'static str] = &[
+        "set aside",
+        "no longer apply",
+        "supersedes",
+        "from here on",
+        "instead",
+        "don't tell",
+        "do not mention",
+        "quietly",
+        "reveal",
+    ];
+}
+
+#[async_trait]
+impl InjectionClassifier for CueClassifier {
+    fn model_ref(&self) -> String {
+        "test/cue-lexicon".to_string()
+    }
+
+    async fn classify(&self, texts: &[String]) -> Result<Vec<f64>, InjectionClassifierError> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                let has = |cues: &[&str]| cues.iter().any(|cue| text.contains(cue));
+                let mut score = 0.05;
+                if has(Self::ADDRESSEE) {
+                    score += 0.45;
+                }
+                if has(Self::OVERRIDE) {
+                    score += 0.5;
+                }
+                score
+            })
+            .collect())
+    }
+}
+
+/// Detected / total injections and false positives / total benign texts, per
+/// technique.
+#[derive(Default)]
+struct DetectionReport {
+    injections: BTreeMap<String, (usize, usize)>,
+    benign: BTreeMap<String, (usize, usize)>,
+}
+
+impl DetectionReport {
+    fn new(entries: &[CorpusEntry], verdicts: &[InjectionVerdict]) -> Self {
+        let mut report = Self::default();
+        for (entry, verdict) in entries.iter().zip(verdicts) {
+            assert_eq!(entry.id, verdict.span_id);
+            let bucket = if entry.is_injection() {
+                &mut report.injections
+            } else {
+                &mut report.benign
+            };
+            let (flagged, total) = bucket.entry(entry.technique.clone()).or_default();
+            *flagged += usize::from(verdict.injected);
+            *total += 1;
+        }
+        report
+    }
+
+    fn rate(buckets: &BTreeMap<String, (usize, usize)>) -> f64 {
+        let (flagged, total) = buckets
+            .values()
+            .fold((0, 0), |(f, t), (flagged, total)| (f + flagged, t + total));
+        flagged as f64 / total.max(1) as f64
+    }
+
+    fn detection_rate(&self) -> f64 {
+        Self::rate(&self.injections)
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        Self::rate(&self.benign)
+    }
+
+    fn technique_rate(&self, technique: &str) -> f64 {
+        let (flagged, total) = self.injections[technique];
+        flagged as f64 / total as f64
+    }
+
+    fn print(&self, label: &str) {
+        eprintln!(
+            "[{label}] detection {:.2}, false positives {:.2}",
+            self.detection_rate(),
+            self.false_positive_rate()
+        );
+        for (technique, (flagged, total)) in &self.injections {
+            eprintln!("  {technique:<20} {flagged}/{total}");
+        }
+    }
+}
+
+#[test]
+fn corpus_is_labelled_and_covers_every_source() {
+    let entries = corpus();
+    assert!(entries.iter().any(CorpusEntry::is_injection));
+    assert!(entries.iter().any(|entry| entry.label == "benign"));
+    assert!(entries
+        .iter()
+        .all(|entry| entry.label == "injection" || entry.label == "benign"));
+    for source in [
+        UntrustedSource::RetrievedSnippet,
+        UntrustedSource::Web,
+        UntrustedSource::Email,
+        UntrustedSource::Calendar,
+    ] {
+        assert!(
+            entries
+                .iter()
+                .any(|entry| entry.source == source && entry.is_injection()),
+            "no injection from {}",
+            source.as_str()
+        );
+    }
+}
+
+#[tokio::test]
+async fn pattern_and_decoded_tiers_catch_literal_and_encoded_injections() {
+    let entries = corpus();
+    let verdicts = classify_untrusted(
+        None,
+        &spans(&entries),
+        &InjectionClassifierPolicy::default(),
+    )
+    .await;
+    let report = DetectionReport::new(&entries, &verdicts);
+    report.print("pattern + decoded");
+
+    for technique in [
+        "literal",
+        "unicode_whitespace",
+        "base64",
+        "percent",
+        "percent_base64",
+    ] {
+        assert_eq!(report.technique_rate(technique), 1.0, "{technique}");
+    }
+    for (entry, verdict) in entries.iter().zip(&verdicts) {
+        if entry.is_injection() && verdict.injected {
+            let expected = if matches!(entry.technique.as_str(), "literal" | "unicode_whitespace") {
+                InjectionTier::Pattern
+            } else {
+                InjectionTier::Decoded
+            };
+            assert_eq!(verdict.tier, Some(expected), "{}", entry.id);
+        }
+    }
+    assert_eq!(report.false_positive_rate(), 0.0);
+    assert!(report.detection_rate() >= 0.6);
+}
+
+#[tokio::test]
+async fn classifier_tier_catches_paraphrased_injections() {
+    let entries = corpus();
+    let verdicts = classify_untrusted(
+        Some(&CueClassifier),
+        &spans(&entries),
+        &InjectionClassifierPolicy::default(),
+    )
+    .await;
+    let report = DetectionReport::new(&entries, &verdicts);
+    report.print("pattern + decoded + classifier");
+
+    assert_eq!(report.technique_rate("paraphrase"), 1.0);
+    // Only decoded content reveals this paraphrase to the classifier.
+    assert_eq!(report.technique_rate("paraphrase_base64"), 1.0);
+    assert_eq!(report.detection_rate(), 1.0);
+    assert_eq!(report.false_positive_rate(), 0.0);
+    for (entry, verdict) in entries.iter().zip(&verdicts) {
+        if entry.technique.starts_with("paraphrase") {
+            assert_eq!(
+                verdict.tier,
+                Some(InjectionTier::Classifier),
+                "{}",
+                entry.id
+            );
+            assert_eq!(verdict.model_ref.as_deref(), Some("test/cue-lexicon"));
+            assert!(verdict.classifier_score.is_some());
+        }
+    }
+}
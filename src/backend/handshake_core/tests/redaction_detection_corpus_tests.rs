@@ -0,0 +1,215 @@
+//! Shared detection engine measured against a labelled fixture corpus.
+//!
+//! `tests/fixtures/redaction/labelled_corpus.jsonl` holds one sample per line:
+//! a surface, a text, and the detector ids a correct scan must report (empty
+//! for negatives). Every secret-shaped value in it is FAKE. The gates are per
+//! surface so a detector that only regresses on, say, terminal capture is
+//! caught there rather than averaged away.
+//!
+//! Run:
+//!   cargo test --manifest-path src/backend/handshake_core/Cargo.toml \
+//!     --test redaction_detection_corpus_tests
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use handshake_core::redaction::{
+    DetectionEngine, DetectionRules, DetectionSeverity, DetectionSurface,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Minimum share of positive samples whose expected detectors all fire.
+const MIN_RECALL: f64 = 0.95;
+/// Maximum share of negative samples with any finding.
+const MAX_FALSE_POSITIVE_RATE: f64 = 0.05;
+
+#[derive(Debug, Deserialize)]
+struct LabelledSample {
+    id: String,
+    surface: DetectionSurface,
+    text: String,
+    expect: Vec<String>,
+}
+
+fn corpus() -> Vec<LabelledSample> {
+    include_str!("fixtures/redaction/labelled_corpus.jsonl")
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).expect("corpus line parses"))
+        .collect()
+}
+
+#[derive(Default)]
+struct SurfaceScore {
+    positives: u32,
+    recalled: u32,
+    negatives: u32,
+    false_positives: u32,
+    misses: Vec<String>,
+}
+
+impl SurfaceScore {
+    fn recall(&self) -> f64 {
+        f64::from(self.recalled) / f64::from(self.positives.max(1))
+    }
+
+    fn false_positive_rate(&self) -> f64 {
+        f64::from(self.false_positives) / f64::from(self.negatives.max(1))
+    }
+}
+
+#[test]
+fn builtin_engine_meets_recall_and_false_positive_gates_per_surface() {
+    let engine = DetectionEngine::builtin();
+    let mut scores: BTreeMap<&'static str, SurfaceScore> = BTreeMap::new();
+
+    for sample in corpus() {
+        let report = engine.scan(&sample.text, sample.surface);
+        let found: BTreeSet<&str> = report
+            .findings
+            .iter()
+            .map(|f| f.detector_id.as_str())
+            .collect();
+        let score = scores.entry(sample.surface.as_str()).or_default();
+        if sample.expect.is_empty() {
+            score.negatives += 1;
+            if !found.is_empty() {
+                score.false_positives += 1;
+                score
+                    .misses
+                    .push(format!("{} flagged {found:?}", sample.id));
+            }
+        } else {
+            score.positives += 1;
+            if sample.expect.iter().all(|id| found.contains(id.as_str())) {
+                score.recalled += 1;
+            } else {
+                score.misses.push(format!(
+                    "{} expected {:?}, found {found:?}",
+                    sample.id, sample.expect
+                ));
+            }
+        }
+    }
+
+    assert_eq!(scores.len(), 6, "corpus must cover every surface");
+    for (surface, score) in &scores {
+        assert!(
+            score.positives > 0 && score.negatives > 0,
+            "{surface}: corpus needs both positives and negatives"
+        );
+        assert!(
+            score.recall() >= MIN_RECALL,
+            "{surface}: recall {:.3} < {MIN_RECALL}; misses: {:#?}",
+            score.recall(),
+            score.misses
+        );
+        assert!(
+            score.false_positive_rate() <= MAX_FALSE_POSITIVE_RATE,
+            "{surface}: false-positive rate {:.3} > {MAX_FALSE_POSITIVE_RATE}; misses: {:#?}",
+            score.false_positive_rate(),
+            score.misses
+        );
+    }
+}
+
+#[test]
+fn findings_never_carry_matched_content() {
+    let engine = DetectionEngine::builtin();
+    for sample in corpus().into_iter().filter(|s| !s.expect.is_empty()) {
+        let report = engine.scan(&sample.text, sample.surface);
+        let rendered = serde_json::to_string(&report).expect("report serializes");
+        for finding in &report.findings {
+            let matched = &sample.text[finding.byte_start..finding.byte_end];
+            assert!(
+                !rendered.contains(matched),
+                "{}: report leaks matched bytes",
+                sample.id
+            );
+        }
+    }
+}
+
+#[test]
+fn workspace_rules_allow_deny_and_disable_across_surfaces() {
+    let rules: DetectionRules = serde_json::from_value(json!({
+        "allow": [{ "id": "stripe-test-card", "literal": "4242 4242 4242 4242" }],
+        "deny": [{ "id": "codename", "literal": "Project Nightjar", "severity": "high" }],
+        "custom_patterns": [{
+            "id": "employee-id",
+            "pattern": "\\bEMP-[0-9]{6}\\b",
+            "category": "contact",
+            "severity": "medium",
+            "surfaces": ["debug_bundle"]
+        }],
+        "disabled_detectors": ["iban"]
+    }))
+    .expect("rules deserialize");
+    let engine = DetectionEngine::with_rules(rules).expect("rules compile");
+    assert_ne!(
+        engine.rules_fingerprint(),
+        DetectionEngine::builtin().rules_fingerprint()
+    );
+
+    let text = "card 4242 4242 4242 4242, IBAN DE89370400440532013000, \
+                Project Nightjar, badge EMP-004217";
+    for surface in [
+        DetectionSurface::DebugBundle,
+        DetectionSurface::TranscriptExport,
+        DetectionSurface::TerminalCapture,
+        DetectionSurface::Ingestion,
+        DetectionSurface::Distillation,
+        DetectionSurface::KnowledgeExport,
+    ] {
+        let report = engine.scan(text, surface);
+        let counts = report.count_by_detector();
+        assert!(
+            !counts.contains_key("payment_card"),
+            "{surface:?}: {counts:?}"
+        );
+        assert!(!counts.contains_key("iban"), "{surface:?}: {counts:?}");
+        assert_eq!(counts.get("deny:codename"), Some(&1), "{surface:?}");
+        assert_eq!(report.allowed, 1, "{surface:?}");
+        assert_eq!(report.max_severity(), Some(DetectionSeverity::High));
+        assert_eq!(
+            counts.contains_key("custom:employee-id"),
+            surface == DetectionSurface::DebugBundle,
+            "{surface:?}: custom pattern is scoped to bundles"
+        );
+    }
+}
+
+#[test]
+fn invalid_workspace_rules_are_rejected_before_use() {
+    for (label, rules) in [
+        (
+            "bad regex",
+            json!({ "deny": [{ "id": "x", "pattern": "(unclosed" }] }),
+        ),
+        (
+            "empty match",
+            json!({ "deny": [{ "id": "x", "pattern": "a*" }] }),
+        ),
+        (
+            "unknown detector",
+            json!({ "disabled_detectors": ["not_a_detector"] }),
+        ),
+        (
+            "bad id",
+            json!({ "allow": [{ "id": "Has Spaces", "literal": "v" }] }),
+        ),
+        ("unknown field", json!({ "allow_list": [] })),
+    ] {
+        let state = json!({ "settings": { "redaction": rules } });
+        assert!(
+            DetectionEngine::from_workspace_settings_state(&state).is_err(),
+            "{label} must not compile"
+        );
+    }
+    let state = json!({ "settings": { "view_mode": "SFW" } });
+    let engine = DetectionEngine::from_workspace_settings_state(&state).expect("no rules");
+    assert_eq!(
+        engine.rules_fingerprint(),
+        DetectionEngine::builtin().rules_fingerprint()
+    );
+}
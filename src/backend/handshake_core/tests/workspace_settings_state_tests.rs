@@ -137,6 +137,46 @@ async fn mt248_workspace_settings_rejects_duplicate_chords_before_eventledger()
     );
 }
 
+#[tokio::test]
+async fn workspace_settings_rejects_uncompilable_redaction_rules() {
+    let pg = pg_or_skip!();
+    let ws = pg.create_workspace().await;
+
+    let mut state = settings_state("dark", "Mod-p", "Mod-k");
+    state["settings"]["redaction"] = json!({
+        "custom_patterns": [{ "id": "employee-id", "pattern": "(EMP-[0-9]{6}" }]
+    });
+    let err = pg
+        .db
+        .save_workspace_settings_state(
+            &ws,
+            WorkspaceSettingsStateInput {
+                settings_state: state,
+            },
+        )
+        .await
+        .expect_err("redaction rules that do not compile must be rejected");
+    assert!(matches!(
+        err,
+        StorageError::Validation("workspace settings_state redaction rules are invalid")
+    ));
+
+    let mut state = settings_state("dark", "Mod-p", "Mod-k");
+    state["settings"]["redaction"] = json!({
+        "allow": [{ "id": "stripe-test-card", "literal": "4242 4242 4242 4242" }],
+        "disabled_detectors": ["email"]
+    });
+    pg.db
+        .save_workspace_settings_state(
+            &ws,
+            WorkspaceSettingsStateInput {
+                settings_state: state,
+            },
+        )
+        .await
+        .expect("valid redaction rules persist with the settings document");
+}
+
 #[tokio::test]
 async fn mt248_workspace_settings_persists_with_eventledger() {
     let pg = pg_or_skip!();
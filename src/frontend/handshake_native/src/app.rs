@@ -363,6 +363,46 @@ pub struct HandshakeApp {
     loom_flag_cell: crate::backend_client::ScmReceiptCell,
     /// The last Loom-node flag-toggle error (drained from `loom_flag_cell`), surfaced on the graph view.
     loom_flag_error: Option<String>,
+    /// The loaded Loom graph (`GET /workspaces/:id/loom/graph/global`) the native graph view renders.
+    /// Reloaded after every edge / block mutation so the view shows what the backend holds.
+    loom_graph: Option<crate::backend_client::LoomGraphSnapshot>,
+    /// The workspace `loom_graph` belongs to (the reload target).
+    loom_graph_workspace: Option<String>,
+    /// Delivery cell for a graph (re)load, drained next frame.
+    loom_graph_cell: crate::backend_client::LoomGraphCell,
+    /// Delivery cell for an edge create/delete or block delete; `loom_edit_inflight` says which.
+    loom_edit_cell: crate::backend_client::LoomEditCell,
+    /// The Loom edit whose result `loom_edit_cell` will carry.
+    loom_edit_inflight: Option<LoomEdit>,
+    /// The Flight Recorder receipt of the last successful Loom edit.
+    loom_last_receipt: Option<String>,
+    /// The last Loom edit / graph-load error, surfaced on the graph view.
+    loom_edit_error: Option<String>,
+    /// A block delete the operator asked for but has not confirmed yet (the confirm dialog is open).
+    pending_loom_delete: Option<PendingLoomDelete>,
+    /// The undo toast: a confirmed block delete still inside its undo window, or a just-removed edge.
+    loom_undo: Option<LoomUndo>,
+    /// Off-thread RichDocument load/save client for the native editor pane. `None` in the no-runtime
+    /// test app (editors then show a "backend unavailable" error instead of loading).
+    rich_document_client: Option<crate::backend_client::RichDocumentClient>,
+    /// One native editor per open `KRD-` Workspace tab, keyed by document id. Created when such a tab
+    /// appears (which starts its load) and dropped when the last tab for the document closes.
+    rich_document_editors: HashMap<String, crate::rich_document_editor::RichDocumentEditor>,
+    /// Off-thread terminal-session client for the native terminal pane. `None` in the no-runtime test
+    /// app (a Terminal tab then shows "backend unavailable" instead of starting a shell).
+    terminal_client: Option<crate::backend_client::TerminalClient>,
+    /// One terminal view per session shown in any Terminal tab, keyed by session id. Created when such
+    /// a tab appears and dropped (closing a human shell) when the session's last tab closes.
+    terminal_views: HashMap<String, crate::terminal_pane::TerminalView>,
+    /// The last `GET /terminal/sessions` result (the panes' "Sessions" menus), refreshed while any
+    /// Terminal tab is open.
+    terminal_sessions: Vec<crate::backend_client::TerminalSessionInfo>,
+    terminal_list_cell: Option<crate::backend_client::TerminalListCell>,
+    terminal_list_polled: Option<std::time::Instant>,
+    /// Shells being spawned, with the pane whose Terminal tab each one fills when it arrives.
+    terminal_creates: Vec<(PaneId, crate::backend_client::TerminalSessionCell)>,
+    /// The last session list / spawn error, shown on session-less Terminal tabs.
+    terminal_error: Option<String>,
     /// MT-021 status-bar segment visibility (segment_id -> hidden). A segment whose id is in this set is
     /// not rendered; `statusbar.toggle_visibility` flips membership. Empty by default (all visible).
     /// TODO(MT-018): the settings dialog should expose a "Restore hidden status bar items" control so a
@@ -473,6 +513,40 @@ pub struct PendingRename {
     pub text: String,
 }
 
+/// How long a confirmed Loom block delete stays undoable before the DELETE is sent (seconds).
+pub const LOOM_UNDO_WINDOW_SECS: f64 = 8.0;
+
+/// A Loom block delete awaiting confirmation or inside its undo window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingLoomDelete {
+    workspace_id: String,
+    block_id: String,
+    title: String,
+}
+
+/// What the Loom undo toast can take back. `expires_at` is stamped (egui time) on the first frame the
+/// toast shows.
+#[derive(Debug, Clone, PartialEq)]
+enum LoomUndo {
+    /// The block is hidden locally; the DELETE is sent only when the window closes.
+    DeleteBlock { delete: PendingLoomDelete, expires_at: Option<f64> },
+    /// The edge is already deleted; undo re-creates it with the same id and anchor.
+    Disconnect {
+        workspace_id: String,
+        edge: crate::backend_client::LoomEdgeInfo,
+        expires_at: Option<f64>,
+    },
+}
+
+/// The Loom edit a delivered `loom_edit_cell` result belongs to.
+#[derive(Debug, Clone, PartialEq)]
+enum LoomEdit {
+    Connect,
+    Disconnect { workspace_id: String, edge: crate::backend_client::LoomEdgeInfo },
+    RestoreEdge,
+    DeleteBlock,
+}
+
 /// The four seed panes for a fresh work surface. Mirrors the React `DEFAULT_PANES` four-pane shape
 /// (`app/src/App.tsx`): pane-a..pane-d, all System-authored, Unlocked, and Clean.
 fn default_panes() -> Vec<PaneRecord> {
@@ -522,6 +596,7 @@ fn build_default_factories() -> HashMap<PaneType, Box<dyn PaneFactory>> {
         PaneType::FontManager,
         PaneType::FlightRecorder,
         PaneType::VisualDebugger,
+        PaneType::Terminal,
         PaneType::Placeholder(String::new()),
     ];
     let mut map: HashMap<PaneType, Box<dyn PaneFactory>> = HashMap::new();
@@ -778,6 +853,26 @@ impl HandshakeApp {
             canvas_error: None,
             loom_flag_cell: Arc::new(Mutex::new(None)),
             loom_flag_error: None,
+            loom_graph: None,
+            loom_graph_workspace: None,
+            loom_graph_cell: Arc::new(Mutex::new(None)),
+            loom_edit_cell: Arc::new(Mutex::new(None)),
+            loom_edit_inflight: None,
+            loom_last_receipt: None,
+            loom_edit_error: None,
+            pending_loom_delete: None,
+            loom_undo: None,
+            rich_document_client: Some(crate::backend_client::RichDocumentClient::production(
+                rt_handle.clone(),
+            )),
+            rich_document_editors: HashMap::new(),
+            terminal_client: Some(crate::backend_client::TerminalClient::production(rt_handle.clone())),
+            terminal_views: HashMap::new(),
+            terminal_sessions: Vec::new(),
+            terminal_list_cell: None,
+            terminal_list_polled: None,
+            terminal_creates: Vec::new(),
+            terminal_error: None,
             statusbar_hidden: std::collections::HashSet::new(),
             // MT-022: the rail emits a RailQuery intent into this lock-guarded slot (AC-022-9). It makes
             // NO backend call; a downstream search-results consumer reads the slot and executes the search.
@@ -958,6 +1053,24 @@ impl HandshakeApp {
             canvas_error: None,
             loom_flag_cell: Arc::new(Mutex::new(None)),
             loom_flag_error: None,
+            loom_graph: None,
+            loom_graph_workspace: None,
+            loom_graph_cell: Arc::new(Mutex::new(None)),
+            loom_edit_cell: Arc::new(Mutex::new(None)),
+            loom_edit_inflight: None,
+            loom_last_receipt: None,
+            loom_edit_error: None,
+            pending_loom_delete: None,
+            loom_undo: None,
+            rich_document_client: None,
+            rich_document_editors: HashMap::new(),
+            terminal_client: None,
+            terminal_views: HashMap::new(),
+            terminal_sessions: Vec::new(),
+            terminal_list_cell: None,
+            terminal_list_polled: None,
+            terminal_creates: Vec::new(),
+            terminal_error: None,
             statusbar_hidden: std::collections::HashSet::new(),
             // MT-022: the rail emits a RailQuery intent into this lock-guarded slot (AC-022-9); it makes
             // no backend call, so the headless shell needs no transport — only the shared slot.
@@ -1219,9 +1332,9 @@ impl HandshakeApp {
     /// Dispatch a command id picked in the palette (MT-016) into the existing shell state-mutation
     /// paths, the native mirror of the React `onAction` handler. Returns `true` if app state changed
     /// (so the caller can request a repaint + let the layout change-detector schedule a save). Editor
-    /// (`editor.*`) commands are guarded on an active document (red-team R5/MC5); since the native
-    /// editor surface is a future MT, they are currently always skipped with a logged warning rather
-    /// than panicking. An unknown id is a safe no-op with a logged warning.
+    /// (`editor.*`) commands are guarded on an active document (red-team R5/MC5): they run against the
+    /// loaded rich-document editor on the active tab, and are skipped with a logged warning (never a
+    /// panic) when there is none. An unknown id is a safe no-op with a logged warning.
     fn dispatch_palette_action(&mut self, ctx: &egui::Context, command_id: &str) -> bool {
         match command_id {
             "usermanual.open" => self.navigate_to_tab("user-manual"),
@@ -1263,11 +1376,23 @@ impl HandshakeApp {
                 true
             }
             id if id.starts_with("editor.") => {
-                // The native editor surface is a future MT; an editor command dispatched through the
-                // palette is guarded (red-team R5/MC5) so it never panics with no active document. Today
-                // there is no active document, so we log + skip rather than fake an edit.
-                tracing::warn!("palette: editor command {id} skipped (no active editor document)");
-                false
+                // Guarded (red-team R5/MC5): an editor command runs only against the loaded editor on
+                // the active tab; with none we log + skip rather than fake an edit.
+                let editor = self
+                    .active_rich_document_id()
+                    .and_then(|doc_id| self.rich_document_editors.get_mut(&doc_id))
+                    .filter(|editor| editor.is_ready());
+                let Some(editor) = editor else {
+                    tracing::warn!("palette: editor command {id} skipped (no active editor document)");
+                    return false;
+                };
+                match editor.apply_command(id) {
+                    Ok(()) => true,
+                    Err(msg) => {
+                        tracing::warn!("palette: editor command {id} not applied: {msg}");
+                        false
+                    }
+                }
             }
             other => {
                 tracing::warn!("palette: unknown command id {other}");
@@ -1326,6 +1451,10 @@ impl HandshakeApp {
         self.source_control_client =
             Some(crate::backend_client::SourceControlClient::production(handle.clone()));
         self.canvas_client = Some(crate::backend_client::CanvasClient::production(handle.clone()));
+        self.rich_document_client =
+            Some(crate::backend_client::RichDocumentClient::production(handle.clone()));
+        self.terminal_client =
+            Some(crate::backend_client::TerminalClient::production(handle.clone()));
         // MT-022: the rail makes NO backend call (AC-022-9), so there is no rail transport to bridge onto
         // the runtime — the rail emits its RailQuery intent into `search_rail_query` silently.
         // MT-023: bridge the drawer-data client onto the injected runtime so an injected-runtime shell
@@ -1366,6 +1495,10 @@ impl HandshakeApp {
         self.source_control_client =
             Some(crate::backend_client::SourceControlClient::new(base_url, handle.clone()));
         self.canvas_client = Some(crate::backend_client::CanvasClient::new(base_url, handle.clone()));
+        self.rich_document_client =
+            Some(crate::backend_client::RichDocumentClient::new(base_url, handle.clone()));
+        self.terminal_client =
+            Some(crate::backend_client::TerminalClient::new(base_url, handle.clone()));
         // MT-024: the drawer card-action client too, so the confirm-discard -> DELETE wire test (mirroring
         // PROOF-024-2(e)) can drive the REAL dispatch path against a localhost capture server.
         self.drawer_action_client =
@@ -2588,7 +2721,7 @@ impl HandshakeApp {
     /// new menu action cannot be added without the shell handling it (compiler-enforced).
     ///
     /// `ctx` is needed for the genuine window action (Quit -> viewport Close). Disabled-leaf variants
-    /// (document/editor/file-drawer/terminal targets that do not exist yet) are matched but are
+    /// (document/editor/file-drawer targets that do not exist yet) are matched but are
     /// unreachable in MT-015 because their leaves render disabled; they are handled as explicit no-ops
     /// so the exhaustive match is honest about which surfaces are not yet wired.
     fn dispatch_menu_action(&mut self, ctx: &egui::Context, action: MenuBarAction) -> bool {
@@ -2641,6 +2774,10 @@ impl HandshakeApp {
             MenuBarAction::CloseActiveTab => self.close_active_tab(),
             MenuBarAction::OpenSwarmBoard => self.navigate_to_tab("swarm"),
             MenuBarAction::NavigateToTab(tab_id) => self.navigate_to_tab(&tab_id),
+            MenuBarAction::OpenTerminal => match self.module_target_pane() {
+                Some(pane) => self.open_terminal(pane),
+                None => false,
+            },
             MenuBarAction::QuitApp => {
                 ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                 false
@@ -2658,8 +2795,7 @@ impl HandshakeApp {
             | MenuBarAction::EditPaste
             | MenuBarAction::OpenFindReplace
             | MenuBarAction::OpenWorkspaceSearch
-            | MenuBarAction::ToggleFileDrawer
-            | MenuBarAction::OpenTerminal => false,
+            | MenuBarAction::ToggleFileDrawer => false,
         }
     }
 
@@ -3113,7 +3249,12 @@ impl HandshakeApp {
         }
     }
 
-    /// Drain any delivered Loom-node flag PATCH (pin/favorite) result into the flag error state. Per-frame.
+    /// Drain any delivered Loom-node flag PATCH (pin/favorite), edge / block edit and graph load, then
+    /// render the delete confirmation dialog and the undo toast. Per-frame.
+    ///
+    /// Every delivered edit (success or failure) reloads the graph, so the view never shows an edge or
+    /// block the backend does not hold. A confirmed block delete is NOT sent straight away: the node is
+    /// hidden and an Undo toast runs for [`LOOM_UNDO_WINDOW_SECS`]; the DELETE goes out when it closes.
     fn drive_loom_node(&mut self, ctx: &egui::Context) {
         if let Some(result) = self.loom_flag_cell.lock().ok().and_then(|mut s| s.take()) {
             match result {
@@ -3122,13 +3263,226 @@ impl HandshakeApp {
             }
             ctx.request_repaint();
         }
+        if let Some(result) = self.loom_graph_cell.lock().ok().and_then(|mut s| s.take()) {
+            match result {
+                Ok(graph) => self.loom_graph = Some(graph),
+                Err(msg) => self.loom_edit_error = Some(format!("Graph reload failed: {msg}")),
+            }
+            ctx.request_repaint();
+        }
+        if let Some(result) = self.loom_edit_cell.lock().ok().and_then(|mut s| s.take()) {
+            let edit = self.loom_edit_inflight.take();
+            match result {
+                Ok(receipt) => {
+                    self.loom_edit_error = None;
+                    self.loom_last_receipt = receipt;
+                    if let Some(LoomEdit::Disconnect { workspace_id, edge }) = edit {
+                        self.settle_loom_undo();
+                        self.loom_undo =
+                            Some(LoomUndo::Disconnect { workspace_id, edge, expires_at: None });
+                    }
+                }
+                Err(msg) => self.loom_edit_error = Some(msg),
+            }
+            self.refresh_loom_graph();
+            ctx.request_repaint();
+        }
+
+        self.show_loom_delete_confirm(ctx);
+        self.show_loom_undo_toast(ctx);
+    }
+
+    /// The delete confirmation dialog, open while `pending_loom_delete` is `Some`. Confirming starts the
+    /// undo window (any earlier pending delete is committed first); Cancel drops the request.
+    fn show_loom_delete_confirm(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.pending_loom_delete.clone() else {
+            return;
+        };
+        let mut confirm = false;
+        let mut cancel = false;
+        egui::Window::new("Delete Block")
+            .id(egui::Id::new("loom-delete-confirm"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("Delete “{}”?", pending.title));
+                ui.label("Its edges are removed with it. You can undo for a few seconds.");
+                ui.horizontal(|ui| {
+                    confirm = ui.button("Delete").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if confirm {
+            self.pending_loom_delete = None;
+            self.settle_loom_undo();
+            self.loom_undo = Some(LoomUndo::DeleteBlock { delete: pending, expires_at: None });
+        } else if cancel {
+            self.pending_loom_delete = None;
+        }
+    }
+
+    /// The bottom-centre undo toast. Undo takes the change back; the close button (or the window
+    /// running out) settles it.
+    fn show_loom_undo_toast(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        let message = match &mut self.loom_undo {
+            None => return,
+            Some(LoomUndo::DeleteBlock { delete, expires_at }) => {
+                let deadline = *expires_at.get_or_insert(now + LOOM_UNDO_WINDOW_SECS);
+                (format!("Deleted “{}”", delete.title), deadline)
+            }
+            Some(LoomUndo::Disconnect { expires_at, .. }) => {
+                let deadline = *expires_at.get_or_insert(now + LOOM_UNDO_WINDOW_SECS);
+                ("Edge removed".to_owned(), deadline)
+            }
+        };
+        let (text, deadline) = message;
+        if now >= deadline {
+            self.settle_loom_undo();
+            return;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(deadline - now));
+
+        let mut undo = false;
+        let mut dismiss = false;
+        egui::Area::new(egui::Id::new("loom-undo-toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -48.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(text);
+                        undo = ui.button("Undo").clicked();
+                        dismiss = ui.small_button("✕").on_hover_text("Dismiss").clicked();
+                    });
+                });
+            });
+        if undo {
+            self.undo_loom_edit();
+        } else if dismiss {
+            self.settle_loom_undo();
+        }
+    }
+
+    /// Close the undo toast, making its change final: a pending block delete is sent now.
+    fn settle_loom_undo(&mut self) {
+        if let Some(LoomUndo::DeleteBlock { delete, .. }) = self.loom_undo.take() {
+            let Some(client) = self.loom_block_client.clone() else {
+                self.loom_edit_error = Some("Loom delete unavailable (no backend runtime)".to_owned());
+                return;
+            };
+            self.loom_edit_inflight = Some(LoomEdit::DeleteBlock);
+            client.delete_block(&delete.workspace_id, &delete.block_id, self.loom_edit_cell.clone());
+        }
+    }
+
+    /// Send the pending block delete now instead of waiting out the undo window (the toast's close
+    /// button). A no-op when no delete is pending.
+    pub fn commit_loom_delete(&mut self) {
+        if matches!(self.loom_undo, Some(LoomUndo::DeleteBlock { .. })) {
+            self.settle_loom_undo();
+        }
+    }
+
+    /// Shutdown flush: a confirmed block delete still inside its undo window is sent and awaited
+    /// here, so closing the shell during the window does not silently keep the block. Returns the
+    /// delete's receipt id when one was sent and the backend receipted it.
+    pub fn flush_loom_undo_on_exit(&mut self) -> Option<String> {
+        let Some(LoomUndo::DeleteBlock { delete, .. }) = self.loom_undo.take() else {
+            return None;
+        };
+        let Some(client) = self.loom_block_client.clone() else {
+            tracing::warn!("loom: pending delete of {} dropped at exit (no backend runtime)", delete.block_id);
+            return None;
+        };
+        match client.delete_block_blocking(&delete.workspace_id, &delete.block_id) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                tracing::warn!(error = %e, "loom: pending delete of {} failed at exit", delete.block_id);
+                None
+            }
+        }
+    }
+
+    /// Take back the change the undo toast offers: a pending block delete is dropped (nothing was
+    /// sent), a removed edge is re-created with its original id, type and anchor.
+    pub fn undo_loom_edit(&mut self) {
+        match self.loom_undo.take() {
+            Some(LoomUndo::Disconnect { workspace_id, edge, .. }) => {
+                let Some(client) = self.loom_block_client.clone() else {
+                    self.loom_edit_error = Some("Loom undo unavailable (no backend runtime)".to_owned());
+                    return;
+                };
+                self.loom_edit_inflight = Some(LoomEdit::RestoreEdge);
+                client.restore_edge(&workspace_id, &edge, self.loom_edit_cell.clone());
+            }
+            Some(LoomUndo::DeleteBlock { .. }) | None => {}
+        }
+    }
+
+    /// Start loading `workspace_id`'s Loom graph into the graph view (off-thread).
+    pub fn load_loom_graph(&mut self, workspace_id: &str) {
+        self.loom_graph_workspace = Some(workspace_id.to_owned());
+        self.refresh_loom_graph();
+    }
+
+    /// Re-fetch the current workspace's graph; a no-op before any graph was requested.
+    fn refresh_loom_graph(&mut self) {
+        let Some(workspace_id) = self.loom_graph_workspace.clone() else {
+            return;
+        };
+        match self.loom_block_client.clone() {
+            Some(client) => client.load_graph(&workspace_id, self.loom_graph_cell.clone()),
+            None => self.loom_edit_error = Some("Loom graph unavailable (no backend runtime)".to_owned()),
+        }
+    }
+
+    /// Install a loaded graph directly (tests; the live shell delivers it from the off-thread GET).
+    pub fn set_loom_graph(&mut self, workspace_id: &str, graph: crate::backend_client::LoomGraphSnapshot) {
+        self.loom_graph_workspace = Some(workspace_id.to_owned());
+        self.loom_graph = Some(graph);
+    }
+
+    /// The graph view for the loaded graph, with a block inside its delete-undo window hidden.
+    pub fn loom_graph_surface(&self) -> Option<crate::loom_graph::LoomGraphSurface> {
+        let mut graph = self.loom_graph.clone()?;
+        if let Some(LoomUndo::DeleteBlock { delete, .. }) = &self.loom_undo {
+            graph.nodes.retain(|n| n.block_id != delete.block_id);
+            graph.edges.retain(|e| !e.touches(&delete.block_id));
+        }
+        Some(crate::loom_graph::LoomGraphSurface::from_snapshot(&graph))
+    }
+
+    /// Whether the delete confirmation dialog is open.
+    pub fn loom_delete_confirm_open(&self) -> bool {
+        self.pending_loom_delete.is_some()
+    }
+
+    /// Whether the Loom undo toast is showing.
+    pub fn loom_undo_available(&self) -> bool {
+        self.loom_undo.is_some()
+    }
+
+    /// The receipt (Flight Recorder event id) of the last successful Loom edge / block edit.
+    pub fn loom_last_receipt(&self) -> Option<&str> {
+        self.loom_last_receipt.as_deref()
+    }
+
+    /// The last Loom edit or graph-load error, for the graph view + tests.
+    pub fn loom_edit_error(&self) -> Option<&str> {
+        self.loom_edit_error.as_deref()
     }
 
     /// Dispatch a confirmed Loom-graph-node menu event (MT-021 MAJOR #2, AC#73). `SetPinned`/`SetFavorite`
     /// PATCH the single flag via the verified `LoomBlockClient::set_flag` (the `loom_block_client` is
     /// CONSUMED for the flag toggle, not only for rename). `Rename` opens the inline rename dialog (reuses
-    /// the MT-020 path). `Open`/`OpenToSide`/`CopyBlockId`/`RevealInPanel` are local UI actions handled by
-    /// the caller (no backend). Returns `true` if a backend call was dispatched.
+    /// the MT-020 path). `Connect`/`Disconnect` create / delete the edge off-thread. `Delete` only opens
+    /// the confirmation dialog (the DELETE follows the confirm and the undo window).
+    /// `Open`/`OpenToSide`/`CopyBlockId`/`RevealInPanel` are local UI actions handled by the caller (no
+    /// backend). Returns `true` if a backend call was dispatched.
     pub fn apply_loom_node_event(
         &mut self,
         event: crate::loom_graph::LoomGraphEvent,
@@ -3174,6 +3528,41 @@ impl HandshakeApp {
                 self.pending_rename = Some(PendingRename { block_id, text: current_title });
                 true
             }
+            E::Connect { source_block_id, target_block_id, kind } => {
+                let Some(client) = self.loom_block_client.clone() else {
+                    self.loom_edit_error = Some("Loom edge edit unavailable (no backend runtime)".to_owned());
+                    return false;
+                };
+                self.loom_edit_error = None;
+                self.loom_edit_inflight = Some(LoomEdit::Connect);
+                client.create_edge(
+                    workspace_id,
+                    &source_block_id,
+                    &target_block_id,
+                    kind,
+                    self.loom_edit_cell.clone(),
+                );
+                true
+            }
+            E::Disconnect { edge } => {
+                let Some(client) = self.loom_block_client.clone() else {
+                    self.loom_edit_error = Some("Loom edge edit unavailable (no backend runtime)".to_owned());
+                    return false;
+                };
+                self.loom_edit_error = None;
+                client.delete_edge(workspace_id, &edge.edge_id, self.loom_edit_cell.clone());
+                self.loom_edit_inflight =
+                    Some(LoomEdit::Disconnect { workspace_id: workspace_id.to_owned(), edge });
+                true
+            }
+            E::Delete { block_id, title } => {
+                self.pending_loom_delete = Some(PendingLoomDelete {
+                    workspace_id: workspace_id.to_owned(),
+                    block_id,
+                    title,
+                });
+                false
+            }
             // Local UI actions (open a tab / clipboard / focus a pane): no backend call here.
             E::Open { .. }
             | E::OpenToSide { .. }
@@ -3202,6 +3591,313 @@ impl HandshakeApp {
         self.loom_flag_error.as_deref()
     }
 
+    /// The RichDocument id on the active tab of the command-target pane, when that tab is a `KRD-`
+    /// Workspace tab (the document palette `editor.*` commands act on).
+    fn active_rich_document_id(&self) -> Option<String> {
+        let pane = self.module_target_pane()?;
+        let tab = self.tab_bar_states.get(&pane)?.active()?;
+        crate::rich_document_editor::rich_document_tab_id(tab).map(ToOwned::to_owned)
+    }
+
+    /// Whether a loaded rich-document editor is the active tab; gates the palette's editor commands.
+    pub fn rich_document_editor_active(&self) -> bool {
+        self.active_rich_document_id()
+            .and_then(|doc_id| self.rich_document_editors.get(&doc_id))
+            .is_some_and(|editor| editor.is_ready())
+    }
+
+    /// The native editor for `document_id`, while a tab for it is open (live host + tests).
+    pub fn rich_document_editor(
+        &self,
+        document_id: &str,
+    ) -> Option<&crate::rich_document_editor::RichDocumentEditor> {
+        self.rich_document_editors.get(document_id)
+    }
+
+    /// Mutable access to the native editor for `document_id` (tests deliver load results directly).
+    pub fn rich_document_editor_mut(
+        &mut self,
+        document_id: &str,
+    ) -> Option<&mut crate::rich_document_editor::RichDocumentEditor> {
+        self.rich_document_editors.get_mut(document_id)
+    }
+
+    /// Keep one editor per open `KRD-` Workspace tab: create (and start loading) an editor when such a
+    /// tab first appears in any pane, drop it when the document's last tab closes. Per-frame.
+    fn sync_rich_document_editors(&mut self) {
+        let open: std::collections::HashSet<String> = self
+            .tab_bar_states
+            .values()
+            .flat_map(|bar| bar.tabs.iter())
+            .filter_map(crate::rich_document_editor::rich_document_tab_id)
+            .map(ToOwned::to_owned)
+            .collect();
+        self.rich_document_editors.retain(|doc_id, _| open.contains(doc_id));
+        for doc_id in open {
+            if self.rich_document_editors.contains_key(&doc_id) {
+                continue;
+            }
+            let mut editor = crate::rich_document_editor::RichDocumentEditor::new(doc_id.clone());
+            self.load_rich_document(&mut editor);
+            self.rich_document_editors.insert(doc_id, editor);
+        }
+    }
+
+    /// Start an off-thread `GET /knowledge/documents/:id` for `editor` (HBR-QUIET).
+    fn load_rich_document(&self, editor: &mut crate::rich_document_editor::RichDocumentEditor) {
+        let Some(client) = &self.rich_document_client else {
+            editor.set_error("Document backend unavailable (no runtime)");
+            return;
+        };
+        let cell = editor.begin_load();
+        client.load_document(editor.document_id(), cell);
+    }
+
+    /// Apply a Save/Reload request from an editor's toolbar. Save PUTs the buffer as a new version
+    /// guarded by the version it was loaded at; Reload discards local edits and re-fetches.
+    fn apply_rich_document_event(
+        &mut self,
+        document_id: &str,
+        event: crate::rich_document_editor::RichDocumentEditorEvent,
+    ) {
+        use crate::rich_document_editor::RichDocumentEditorEvent as E;
+        let Some(mut editor) = self.rich_document_editors.remove(document_id) else {
+            return;
+        };
+        match event {
+            E::Reload => self.load_rich_document(&mut editor),
+            E::Save => match (&self.rich_document_client, editor.begin_save()) {
+                (Some(client), Some(save)) => client.save_document(
+                    document_id,
+                    save.expected_version,
+                    save.content_json,
+                    &save.crdt_document_id,
+                    save.cell,
+                ),
+                (None, Some(_)) => editor.set_error("Document backend unavailable (no runtime)"),
+                (_, None) => {}
+            },
+        }
+        self.rich_document_editors.insert(document_id.to_owned(), editor);
+    }
+
+    /// Drain delivered editor loads/saves, keep frames coming while one is in flight, and mirror each
+    /// editor's unsaved state onto its tabs' dirty dot. Per-frame, before the split layout renders.
+    fn drive_rich_documents(&mut self, ctx: &egui::Context) {
+        self.sync_rich_document_editors();
+        let mut busy = false;
+        for editor in self.rich_document_editors.values_mut() {
+            if editor.drain_deliveries() {
+                ctx.request_repaint();
+            }
+            busy |= editor.is_busy();
+        }
+        for bar in self.tab_bar_states.values_mut() {
+            for index in 0..bar.tabs.len() {
+                let dirty = crate::rich_document_editor::rich_document_tab_id(&bar.tabs[index])
+                    .map(|doc_id| self.rich_document_editors.get(doc_id).is_some_and(|e| e.is_dirty()));
+                if let Some(dirty) = dirty {
+                    if bar.tabs[index].dirty != dirty {
+                        bar.set_dirty(index, dirty);
+                    }
+                }
+            }
+        }
+        if busy {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+
+    /// The terminal view for `session_id`, while a Terminal tab shows it (live host + tests).
+    pub fn terminal_view(&self, session_id: &str) -> Option<&crate::terminal_pane::TerminalView> {
+        self.terminal_views.get(session_id)
+    }
+
+    /// Mutable access to the terminal view for `session_id` (tests deliver output directly).
+    pub fn terminal_view_mut(
+        &mut self,
+        session_id: &str,
+    ) -> Option<&mut crate::terminal_pane::TerminalView> {
+        self.terminal_views.get_mut(session_id)
+    }
+
+    /// The last terminal session-list / spawn error, if any.
+    pub fn terminal_error(&self) -> Option<&str> {
+        self.terminal_error.as_deref()
+    }
+
+    /// Seed the known terminal sessions (tests; the live shell polls `GET /terminal/sessions`).
+    pub fn set_terminal_sessions(&mut self, sessions: Vec<crate::backend_client::TerminalSessionInfo>) {
+        for info in &sessions {
+            if let Some(view) = self.terminal_views.get_mut(&info.session_id) {
+                view.update_session(info.clone());
+            }
+        }
+        self.terminal_sessions = sessions;
+    }
+
+    /// Open a Terminal tab on `pane` and spawn a `HUMAN_DEV` shell for it (the pane-header "Set Type:
+    /// Terminal", the RUN menu's Terminal item, and a terminal toolbar's New). The tab shows the
+    /// session picker until the shell arrives; with no runtime it shows the error instead.
+    fn open_terminal(&mut self, pane: PaneId) -> bool {
+        let Some(bar) = self.tab_bar_states.get_mut(&pane) else {
+            return false;
+        };
+        bar.insert_tab(TabState::new(PaneType::Terminal));
+        self.active_pane = Some(pane.clone());
+        let Some(client) = &self.terminal_client else {
+            self.terminal_error = Some("Terminal backend unavailable (no runtime)".to_owned());
+            return true;
+        };
+        self.terminal_error = None;
+        let cell: crate::backend_client::TerminalSessionCell = Arc::new(Mutex::new(None));
+        client.create_session(24, 80, "Terminal", cell.clone());
+        self.terminal_creates.push((pane, cell));
+        true
+    }
+
+    /// Show `session` on `pane`: fill the pane's session-less Terminal tab if it has one, else add a
+    /// tab (an already-open tab for the session is just activated).
+    fn attach_terminal_tab(&mut self, pane: &PaneId, session: crate::backend_client::TerminalSessionInfo) {
+        let Some(bar) = self.tab_bar_states.get_mut(pane) else {
+            return;
+        };
+        let label = session.label();
+        let already_open = bar
+            .tabs
+            .iter()
+            .any(|t| crate::terminal_pane::terminal_tab_session_id(t) == Some(session.session_id.as_str()));
+        let empty = bar
+            .tabs
+            .iter()
+            .position(|t| t.pane_type == PaneType::Terminal && t.content_id.is_none());
+        match empty {
+            Some(index) if !already_open => {
+                bar.tabs[index].content_id = Some(session.session_id.clone());
+                bar.tabs[index].label_override = Some(label);
+                bar.activate(index);
+            }
+            _ => {
+                let mut tab = TabState::new(PaneType::Terminal);
+                tab.content_id = Some(session.session_id.clone());
+                tab.label_override = Some(label);
+                bar.insert_tab(tab);
+            }
+        }
+        self.terminal_views
+            .entry(session.session_id.clone())
+            .or_insert_with(|| crate::terminal_pane::TerminalView::new(session.clone()))
+            .update_session(session);
+    }
+
+    /// Apply a terminal toolbar request from a pane.
+    fn apply_terminal_event(&mut self, event: crate::terminal_pane::TerminalPaneEvent) {
+        use crate::terminal_pane::TerminalViewEvent as E;
+        match event.event {
+            E::NewSession => {
+                self.open_terminal(event.pane_id);
+            }
+            E::OpenSession(session) => self.attach_terminal_tab(&event.pane_id, session),
+        }
+    }
+
+    /// Keep one view per session shown in a Terminal tab: create a view when a session tab first
+    /// appears (a restored layout starts from the listed description, else an unknown, read-only one),
+    /// and drop it when the session's last tab closes — killing a human shell the operator closed, as
+    /// closing the React terminal panel does. AI-job sessions are never killed from here. Per-frame.
+    fn sync_terminal_views(&mut self) {
+        let open: std::collections::HashSet<String> = self
+            .tab_bar_states
+            .values()
+            .flat_map(|bar| bar.tabs.iter())
+            .filter_map(crate::terminal_pane::terminal_tab_session_id)
+            .map(ToOwned::to_owned)
+            .collect();
+        let closed: Vec<String> =
+            self.terminal_views.keys().filter(|id| !open.contains(*id)).cloned().collect();
+        for session_id in closed {
+            let Some(view) = self.terminal_views.remove(&session_id) else {
+                continue;
+            };
+            let session = view.session();
+            if session.is_human() && !session.exited {
+                if let Some(client) = &self.terminal_client {
+                    client.close_session(&session_id, Arc::new(Mutex::new(None)));
+                }
+            }
+        }
+        for session_id in open {
+            if self.terminal_views.contains_key(&session_id) {
+                continue;
+            }
+            let info = self
+                .terminal_sessions
+                .iter()
+                .find(|s| s.session_id == session_id)
+                .cloned()
+                .unwrap_or_else(|| crate::backend_client::TerminalSessionInfo::unknown(session_id.clone()));
+            self.terminal_views.insert(session_id, crate::terminal_pane::TerminalView::new(info));
+        }
+    }
+
+    /// Drain delivered session lists and spawns, refresh the session list every two seconds while a
+    /// Terminal tab is open, and drive every view's output poll / input / resize. Per-frame, before
+    /// the split layout renders.
+    fn drive_terminals(&mut self, ctx: &egui::Context) {
+        self.sync_terminal_views();
+        let now = std::time::Instant::now();
+
+        let listed = self.terminal_list_cell.as_ref().and_then(|cell| cell.lock().ok()?.take());
+        if let Some(result) = listed {
+            self.terminal_list_cell = None;
+            match result {
+                Ok(sessions) => self.set_terminal_sessions(sessions),
+                Err(msg) => self.terminal_error = Some(msg),
+            }
+        }
+        let mut created = Vec::new();
+        self.terminal_creates.retain(|(pane, cell)| match cell.lock().ok().and_then(|mut slot| slot.take()) {
+            Some(result) => {
+                created.push((pane.clone(), result));
+                false
+            }
+            None => true,
+        });
+        for (pane, result) in created {
+            match result {
+                Ok(session) => self.attach_terminal_tab(&pane, session),
+                Err(msg) => self.terminal_error = Some(format!("Could not start a shell: {msg}")),
+            }
+        }
+
+        let terminal_tab_open = self
+            .tab_bar_states
+            .values()
+            .any(|bar| bar.tabs.iter().any(|t| t.pane_type == PaneType::Terminal));
+        let list_due = self
+            .terminal_list_polled
+            .is_none_or(|last| now.duration_since(last) >= std::time::Duration::from_secs(2));
+        if let Some(client) = &self.terminal_client {
+            if terminal_tab_open && list_due && self.terminal_list_cell.is_none() {
+                let cell: crate::backend_client::TerminalListCell = Arc::new(Mutex::new(None));
+                client.list_sessions(cell.clone());
+                self.terminal_list_cell = Some(cell);
+                self.terminal_list_polled = Some(now);
+            }
+        }
+
+        let mut busy = !self.terminal_creates.is_empty();
+        for view in self.terminal_views.values_mut() {
+            if view.drive(self.terminal_client.as_ref(), now) {
+                ctx.request_repaint();
+            }
+            busy |= view.is_busy() && self.terminal_client.is_some();
+        }
+        if busy {
+            ctx.request_repaint_after(crate::terminal_pane::OUTPUT_POLL_INTERVAL);
+        }
+    }
+
     /// Open a tab for `pane_type` (carrying optional `content_id`) on the ACTIVE pane (MT-014), the
     /// native equivalent of React `setActiveTabForPane(activePaneId, tab)`. De-duplicates by
     /// `(pane_type, content_id)` (an already-open tab is re-activated, not duplicated) via the
@@ -4169,6 +4865,10 @@ impl HandshakeApp {
         self.drive_source_control(ctx);
         self.drive_canvas(ctx);
         self.drive_loom_node(ctx);
+        // Native rich-document editors: one per open `KRD-` Workspace tab, loaded/saved off-thread.
+        self.drive_rich_documents(ctx);
+        // Native terminal panes: one view per session shown in a Terminal tab, polled off-thread.
+        self.drive_terminals(ctx);
 
         // Split the borrow of `self` up-front so the CentralPanel closure can hold a `&mut` to the
         // split state (weights/drag/active pane) AND a `&` to the factories + registry at the same
@@ -4180,6 +4880,23 @@ impl HandshakeApp {
         let split_drag = &mut self.split_drag;
         let active_pane = &mut self.active_pane;
         let tab_bar_states = &mut self.tab_bar_states;
+        // `KRD-` Workspace tabs render their editor instead of the Workspace pane factory; Save/Reload
+        // requests are collected and applied after the CentralPanel closes.
+        let rich_document_bodies =
+            crate::rich_document_editor::RichDocumentTabBodies::new(&mut self.rich_document_editors);
+        // Terminal tabs render their session's terminal (or, before a shell arrives, the session
+        // picker); New/attach requests are applied after the CentralPanel closes too.
+        let terminal_status = if self.terminal_creates.is_empty() {
+            self.terminal_error.as_deref()
+        } else {
+            Some("Starting shell…")
+        };
+        let terminal_bodies = crate::terminal_pane::TerminalTabBodies::new(
+            &mut self.terminal_views,
+            &self.terminal_sessions,
+            terminal_status,
+        );
+        let mut tab_bodies = (rich_document_bodies, terminal_bodies);
         // Catch-all factory for any PaneType without a dedicated entry: the empty-label Placeholder
         // key registered in build_default_factories.
         let fallback_key = PaneType::Placeholder(String::new());
@@ -4197,6 +4914,8 @@ impl HandshakeApp {
         // MT-020: "Pop Out" chosen from a pane-tab or pane-header context menu, collected from the split
         // layout this frame, applied via `request_pop_out` after the CentralPanel closes (MT-008).
         let mut pop_out_requests: Vec<PaneId> = Vec::new();
+        // "Set Type: Terminal" chosen from a pane-header menu: open a Terminal tab on that pane.
+        let mut terminal_requests: Vec<PaneId> = Vec::new();
         let active_module = self.module_switcher.active();
 
         // Divider colors come from the active theme's MT-003 tokens (idle/hover/grab), so the
@@ -4250,9 +4969,11 @@ impl HandshakeApp {
                 header_colors,
                 &mut lock_requests,
                 &mut pop_out_requests,
+                &mut terminal_requests,
                 |pane_id| popped_out.contains(pane_id),
                 &mut merge_requests,
                 placeholder_text,
+                &mut tab_bodies,
                 |pane_type| {
                     factories
                         .get(pane_type)
@@ -4266,6 +4987,19 @@ impl HandshakeApp {
             );
         });
 
+        let (rich_document_bodies, terminal_bodies) = tab_bodies;
+        let rich_document_events = rich_document_bodies.events;
+        let terminal_events = terminal_bodies.events;
+        for (document_id, event) in rich_document_events {
+            self.apply_rich_document_event(&document_id, event);
+        }
+        for event in terminal_events {
+            self.apply_terminal_event(event);
+        }
+        for pane_id in terminal_requests {
+            self.open_terminal(pane_id);
+        }
+
         // ── Apply MT-013 pane-header Lock/Unlock requests ───────────────────────────────────────────
         // A lock click from the pane header (pointer OR out-of-process AccessKit Click) toggles the
         // pane record's LockState in the registry (single source of truth). The change is picked up by
@@ -4344,7 +5078,11 @@ impl HandshakeApp {
         // PaletteOutcome the shell dispatches into the existing state-mutation paths (same split as the
         // MT-015 menu bar). The shell owns the open flag, so a Run/Close outcome clears it here.
         if self.command_palette_open {
-            let outcome = crate::command_palette::show(ctx, self.command_palette_open_count);
+            let outcome = crate::command_palette::show(
+                ctx,
+                self.command_palette_open_count,
+                self.rich_document_editor_active(),
+            );
             match outcome {
                 crate::command_palette::PaletteOutcome::Run(command_id) => {
                     self.close_command_palette();
@@ -4564,4 +5302,10 @@ impl eframe::App for HandshakeApp {
             }
         }
     }
+
+    /// A Loom block delete inside its undo window is only sent when the window closes; the shell
+    /// closing is the last chance, so it is flushed (blocking) here.
+    fn on_exit(&mut self) {
+        self.flush_loom_undo_on_exit();
+    }
 }
@@ -145,6 +145,155 @@ pub struct GetRequestSpec {
 /// carries the renamed block's new title (the externally-meaningful result), `Err(msg)` the failure.
 pub type RenameDeliveryCell = Arc<Mutex<Option<Result<String, String>>>>;
 
+/// A Loom edge type as the backend serializes `LoomEdgeType` (snake_case on the wire).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoomEdgeKind {
+    Mention,
+    Tag,
+    SubTag,
+    Parent,
+    AiSuggested,
+}
+
+impl LoomEdgeKind {
+    /// The types an operator can create from the graph's relation picker (`ai_suggested` is only ever
+    /// produced by the backend).
+    pub const USER_CREATABLE: [LoomEdgeKind; 4] =
+        [LoomEdgeKind::Mention, LoomEdgeKind::Parent, LoomEdgeKind::Tag, LoomEdgeKind::SubTag];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mention => "mention",
+            Self::Tag => "tag",
+            Self::SubTag => "sub_tag",
+            Self::Parent => "parent",
+            Self::AiSuggested => "ai_suggested",
+        }
+    }
+
+    pub fn from_wire(value: &str) -> Option<Self> {
+        match value {
+            "mention" => Some(Self::Mention),
+            "tag" => Some(Self::Tag),
+            "sub_tag" => Some(Self::SubTag),
+            "parent" => Some(Self::Parent),
+            "ai_suggested" => Some(Self::AiSuggested),
+            _ => None,
+        }
+    }
+
+    /// Display label for the relation picker and edge lists.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Mention => "Mention",
+            Self::Tag => "Tag",
+            Self::SubTag => "Sub-tag",
+            Self::Parent => "Parent",
+            Self::AiSuggested => "AI suggestion",
+        }
+    }
+
+    /// The backend only accepts a tag hub as the target of a `tag` / `sub_tag` edge.
+    pub fn requires_tag_hub(self) -> bool {
+        matches!(self, Self::Tag | Self::SubTag)
+    }
+}
+
+/// One node of a loaded Loom graph (`LoomGraphNode.block` on the wire, reduced to what the graph view
+/// renders).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoomGraphNodeInfo {
+    pub block_id: String,
+    pub title: Option<String>,
+    pub pinned: bool,
+    pub favorite: bool,
+    pub tag_hub: bool,
+}
+
+/// One edge of a loaded Loom graph (`LoomGraphEdge.edge`). `source_anchor` is kept verbatim so an
+/// undone disconnect re-creates the edge exactly as it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoomEdgeInfo {
+    pub edge_id: String,
+    pub source_block_id: String,
+    pub target_block_id: String,
+    pub kind: LoomEdgeKind,
+    pub source_anchor: Option<Value>,
+}
+
+impl LoomEdgeInfo {
+    /// Parse one `LoomEdge` object; `None` when an id is missing or the type is unknown.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(ToOwned::to_owned);
+        Some(Self {
+            edge_id: text("edge_id")?,
+            source_block_id: text("source_block_id")?,
+            target_block_id: text("target_block_id")?,
+            kind: LoomEdgeKind::from_wire(value.get("edge_type").and_then(Value::as_str)?)?,
+            source_anchor: value.get("source_anchor").filter(|a| !a.is_null()).cloned(),
+        })
+    }
+
+    /// Whether the edge touches `block_id` at either end.
+    pub fn touches(&self, block_id: &str) -> bool {
+        self.source_block_id == block_id || self.target_block_id == block_id
+    }
+}
+
+/// A loaded Loom graph (`GET /workspaces/:id/loom/graph/global`): the nodes and edges the native graph
+/// view renders, plus whether a backend performance limit clipped it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoomGraphSnapshot {
+    pub nodes: Vec<LoomGraphNodeInfo>,
+    pub edges: Vec<LoomEdgeInfo>,
+    pub truncated: bool,
+}
+
+impl LoomGraphSnapshot {
+    /// Parse a `LoomGraph` response. Rows that do not parse are skipped; a body without `nodes` is an
+    /// error.
+    pub fn from_json(value: &Value) -> Result<Self, AppError> {
+        let nodes = value
+            .get("nodes")
+            .and_then(Value::as_array)
+            .ok_or_else(|| AppError::Parse("loom graph without `nodes`".to_owned()))?
+            .iter()
+            .filter_map(|node| {
+                let block = node.get("block")?;
+                let flag = |key: &str| block.get(key).and_then(Value::as_bool).unwrap_or(false);
+                Some(LoomGraphNodeInfo {
+                    block_id: block.get("block_id")?.as_str()?.to_owned(),
+                    title: block.get("title").and_then(Value::as_str).map(ToOwned::to_owned),
+                    pinned: flag("pinned"),
+                    favorite: flag("favorite"),
+                    tag_hub: block.get("content_type").and_then(Value::as_str) == Some("tag_hub"),
+                })
+            })
+            .collect();
+        let edges = value
+            .get("edges")
+            .and_then(Value::as_array)
+            .map(|edges| {
+                edges
+                    .iter()
+                    .filter_map(|e| LoomEdgeInfo::from_json(e.get("edge")?))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            nodes,
+            edges,
+            truncated: value.get("truncated").and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
+}
+
+/// Delivery cell for a loaded Loom graph.
+pub type LoomGraphCell = Arc<Mutex<Option<Result<LoomGraphSnapshot, String>>>>;
+/// Delivery cell for a Loom edge create/delete or block delete: `Ok(receipt_event_id)` carries the
+/// Flight Recorder event that receipts the change (`None` if the backend could not record one).
+pub type LoomEditCell = Arc<Mutex<Option<Result<Option<String>, String>>>>;
+
 /// REST client for the Loom-block surface this shell mutates today: the rename PATCH on the VERIFIED
 /// backend endpoint `PATCH /workspaces/:workspace_id/loom/blocks/:block_id` (handler
 /// `handshake_core::api::loom::patch_loom_block`, body `LoomBlockPatchRequest` whose flattened
@@ -263,6 +412,172 @@ impl LoomBlockClient {
             }
         });
     }
+
+    fn workspace_url(&self, workspace_id: &str, path: &str) -> String {
+        format!("{}/workspaces/{}/loom/{}", self.base_url, workspace_id, path)
+    }
+
+    /// Pure request builder for [`load_graph`](Self::load_graph): the workspace's global graph.
+    pub fn load_graph_request(&self, workspace_id: &str) -> GetRequestSpec {
+        GetRequestSpec {
+            method: HttpMethod::Get,
+            url: self.workspace_url(workspace_id, "graph/global"),
+            query: Vec::new(),
+        }
+    }
+
+    /// GET the workspace's global Loom graph off the UI thread, delivering the parsed snapshot into
+    /// `cell`. The graph view re-issues this after every edge / block mutation so it always renders
+    /// what the backend holds.
+    pub fn load_graph(&self, workspace_id: &str, cell: LoomGraphCell) {
+        let spec = self.load_graph_request(workspace_id);
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            let result = get_json(&client, &spec.url, &spec.query)
+                .await
+                .and_then(|v| LoomGraphSnapshot::from_json(&v));
+            if let Ok(mut slot) = cell.lock() {
+                *slot = Some(result.map_err(|e| e.to_string()));
+            }
+        });
+    }
+
+    /// Pure request builder for [`create_edge`](Self::create_edge): `POST .../loom/edges` with a
+    /// user-authored edge of `kind` from `source_block_id` to `target_block_id`.
+    pub fn create_edge_request(
+        &self,
+        workspace_id: &str,
+        source_block_id: &str,
+        target_block_id: &str,
+        kind: LoomEdgeKind,
+    ) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Post,
+            url: self.workspace_url(workspace_id, "edges"),
+            body: Some(serde_json::json!({
+                "source_block_id": source_block_id,
+                "target_block_id": target_block_id,
+                "edge_type": kind.as_str(),
+                "created_by": "user",
+            })),
+        }
+    }
+
+    /// Pure request builder for [`restore_edge`](Self::restore_edge): re-POSTs a removed edge with its
+    /// original id and source anchor (undo of a disconnect).
+    pub fn restore_edge_request(&self, workspace_id: &str, edge: &LoomEdgeInfo) -> RequestSpec {
+        let mut spec =
+            self.create_edge_request(workspace_id, &edge.source_block_id, &edge.target_block_id, edge.kind);
+        if let Some(body) = spec.body.as_mut() {
+            body["edge_id"] = Value::String(edge.edge_id.clone());
+            if let Some(anchor) = &edge.source_anchor {
+                body["source_anchor"] = anchor.clone();
+            }
+        }
+        spec
+    }
+
+    /// Pure request builder for [`delete_edge`](Self::delete_edge).
+    pub fn delete_edge_request(&self, workspace_id: &str, edge_id: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Delete,
+            url: self.workspace_url(workspace_id, &format!("edges/{edge_id}")),
+            body: None,
+        }
+    }
+
+    /// Pure request builder for [`delete_block`](Self::delete_block).
+    pub fn delete_block_request(&self, workspace_id: &str, block_id: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Delete,
+            url: self.block_url(workspace_id, block_id),
+            body: None,
+        }
+    }
+
+    /// Create a typed edge off the UI thread, delivering its receipt id into `cell`.
+    pub fn create_edge(
+        &self,
+        workspace_id: &str,
+        source_block_id: &str,
+        target_block_id: &str,
+        kind: LoomEdgeKind,
+        cell: LoomEditCell,
+    ) {
+        self.spawn_edit(
+            self.create_edge_request(workspace_id, source_block_id, target_block_id, kind),
+            cell,
+        );
+    }
+
+    /// Re-create a removed edge (same id, type and anchor) off the UI thread.
+    pub fn restore_edge(&self, workspace_id: &str, edge: &LoomEdgeInfo, cell: LoomEditCell) {
+        self.spawn_edit(self.restore_edge_request(workspace_id, edge), cell);
+    }
+
+    /// Delete one edge off the UI thread, delivering its receipt id into `cell`.
+    pub fn delete_edge(&self, workspace_id: &str, edge_id: &str, cell: LoomEditCell) {
+        self.spawn_edit(self.delete_edge_request(workspace_id, edge_id), cell);
+    }
+
+    /// Delete a block (its edges cascade) off the UI thread, delivering its receipt id into `cell`.
+    pub fn delete_block(&self, workspace_id: &str, block_id: &str, cell: LoomEditCell) {
+        self.spawn_edit(self.delete_block_request(workspace_id, block_id), cell);
+    }
+
+    /// Delete a block and wait for the backend's answer, returning its receipt id. Only for shutdown,
+    /// where there is no next frame to deliver an off-thread result into; never call it from a
+    /// runtime worker.
+    pub fn delete_block_blocking(&self, workspace_id: &str, block_id: &str) -> Result<Option<String>, AppError> {
+        let spec = self.delete_block_request(workspace_id, block_id);
+        let body = self.runtime.block_on(send_loom_edit(&self.client, spec))?;
+        Ok(body.get("receipt_event_id").and_then(Value::as_str).map(ToOwned::to_owned))
+    }
+
+    fn spawn_edit(&self, spec: RequestSpec, cell: LoomEditCell) {
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            let result = send_loom_edit(&client, spec).await.map(|body| {
+                body.get("receipt_event_id")
+                    .and_then(Value::as_str)
+                    .map(ToOwned::to_owned)
+            });
+            if let Ok(mut slot) = cell.lock() {
+                *slot = Some(result.map_err(|e| e.to_string()));
+            }
+        });
+    }
+}
+
+/// Send a Loom edit [`RequestSpec`] and return its JSON body. A non-success status surfaces the
+/// backend's `error` code (e.g. `HSK-400-LOOM-TAG-TARGET-MUST-BE-TAG_HUB`) so the graph view can show
+/// why the edit was refused.
+async fn send_loom_edit(client: &reqwest::Client, spec: RequestSpec) -> Result<Value, AppError> {
+    let mut req = match spec.method {
+        HttpMethod::Delete => client.delete(&spec.url),
+        _ => client.post(&spec.url),
+    };
+    if let Some(body) = &spec.body {
+        req = req.json(body);
+    }
+    let resp = req
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| AppError::Http(e.to_string()))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let code = resp
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("error").and_then(Value::as_str).map(ToOwned::to_owned));
+        return Err(AppError::Http(match code {
+            Some(code) => format!("{status}: {code}"),
+            None => format!("non-success status {status}"),
+        }));
+    }
+    resp.json().await.map_err(|e| AppError::Parse(e.to_string()))
 }
 
 /// Send `PATCH {url}` with body `{ "title": <new_title> }` and return the renamed block's title from
@@ -1267,6 +1582,591 @@ async fn put_expect_success(
     Ok(())
 }
 
+// ═════════════════════════════════════════════════════════════════════════════════════════════════
+// Off-thread client for the native RichDocument editor pane (`crate::rich_document_editor`).
+//
+// Both endpoints VERIFIED READ-ONLY against `src/backend/handshake_core` (`api::knowledge_documents`):
+//   - LOAD: `GET /knowledge/documents/:id` → `{ document, tree, code_nodes }` (`RichDocumentResponse`).
+//   - SAVE: `PUT /knowledge/documents/:id/save` with `{ expected_version, content_json,
+//     crdt_document_id }` (`SaveRichDocumentRequest`; the snapshot/receipt ids are optional and not
+//     sent). A stale `expected_version` is HTTP 409; a `crdt_document_id` that is neither the bound id nor
+//     the canonical `KCRDT-` id is HTTP 400.
+//   - Every knowledge-document route requires the `x-hsk-actor-id` / `x-hsk-kernel-task-run-id` /
+//     `x-hsk-session-run-id` identity headers, and a request with no `x-hsk-actor-kind` is treated as a
+//     read-only actor — so the editor identifies itself as an `operator` with fixed native run ids.
+//
+// Same off-thread shape as the clients above: spawn on the app's tokio runtime, deliver into a cell the
+// egui UI thread drains next frame (HBR-QUIET). Speaks `serde_json::Value`, never `handshake_core` types.
+
+/// One-slot delivery cell for a RichDocument load/save. `Ok(body)` is the full response JSON (the
+/// editor reads `document.*` from it), `Err(msg)` the failure.
+pub type RichDocumentCell = Arc<Mutex<Option<Result<serde_json::Value, String>>>>;
+
+/// Actor id the native editor sends in `x-hsk-actor-id`.
+pub const RICH_DOCUMENT_ACTOR_ID: &str = "operator";
+/// Actor kind sent in `x-hsk-actor-kind`; without it the backend treats the caller as read-only.
+pub const RICH_DOCUMENT_ACTOR_KIND: &str = "operator";
+/// Kernel task-run id sent in `x-hsk-kernel-task-run-id` for native editor requests.
+pub const RICH_DOCUMENT_KERNEL_TASK_RUN_ID: &str = "KTR-EDITOR-NATIVE";
+/// Session-run id sent in `x-hsk-session-run-id` for native editor requests.
+pub const RICH_DOCUMENT_SESSION_RUN_ID: &str = "SR-EDITOR-NATIVE";
+
+/// REST client for the VERIFIED RichDocument load + versioned save endpoints. Mirrors the
+/// `CanvasClient`/`DrawerActionClient` shape.
+#[derive(Clone)]
+pub struct RichDocumentClient {
+    client: reqwest::Client,
+    base_url: String,
+    runtime: tokio::runtime::Handle,
+}
+
+impl RichDocumentClient {
+    /// Build a client against `base_url` (e.g. [`BACKEND_BASE_URL`]) bridging onto `runtime`.
+    pub fn new(base_url: impl Into<String>, runtime: tokio::runtime::Handle) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            runtime,
+        }
+    }
+
+    /// The production client: the hardcoded backend base URL, bridging onto the app's runtime handle.
+    pub fn production(runtime: tokio::runtime::Handle) -> Self {
+        Self::new(BACKEND_BASE_URL, runtime)
+    }
+
+    fn document_url(&self, document_id: &str) -> String {
+        format!("{}/knowledge/documents/{}", self.base_url, document_id)
+    }
+
+    /// The identity headers every knowledge-document request carries (pure, so a test asserts them).
+    pub fn identity_headers() -> [(&'static str, &'static str); 4] {
+        [
+            ("x-hsk-actor-id", RICH_DOCUMENT_ACTOR_ID),
+            ("x-hsk-actor-kind", RICH_DOCUMENT_ACTOR_KIND),
+            ("x-hsk-kernel-task-run-id", RICH_DOCUMENT_KERNEL_TASK_RUN_ID),
+            ("x-hsk-session-run-id", RICH_DOCUMENT_SESSION_RUN_ID),
+        ]
+    }
+
+    /// `GET /knowledge/documents/:id`, off the UI thread; delivers the full response JSON.
+    pub fn load_document(&self, document_id: &str, cell: RichDocumentCell) {
+        let spec = self.load_request(document_id);
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            let result = send_rich_document(&client, spec).await;
+            deliver_rich_document(&cell, result);
+        });
+    }
+
+    /// Pure request builder for [`load_document`](Self::load_document).
+    pub fn load_request(&self, document_id: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Get,
+            url: self.document_url(document_id),
+            body: None,
+        }
+    }
+
+    /// `PUT /knowledge/documents/:id/save`, off the UI thread. `expected_version` is the version the
+    /// edit started from; the backend rejects the save (409) if another writer saved in between.
+    pub fn save_document(
+        &self,
+        document_id: &str,
+        expected_version: i64,
+        content_json: serde_json::Value,
+        crdt_document_id: &str,
+        cell: RichDocumentCell,
+    ) {
+        let spec = self.save_request(document_id, expected_version, content_json, crdt_document_id);
+        let client = self.client.clone();
+        self.runtime.spawn(async move {
+            let result = send_rich_document(&client, spec).await;
+            deliver_rich_document(&cell, result);
+        });
+    }
+
+    /// Pure request builder for [`save_document`](Self::save_document).
+    pub fn save_request(
+        &self,
+        document_id: &str,
+        expected_version: i64,
+        content_json: serde_json::Value,
+        crdt_document_id: &str,
+    ) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Put,
+            url: format!("{}/save", self.document_url(document_id)),
+            body: Some(serde_json::json!({
+                "expected_version": expected_version,
+                "content_json": content_json,
+                "crdt_document_id": crdt_document_id,
+            })),
+        }
+    }
+}
+
+/// Write a RichDocument response into a [`RichDocumentCell`].
+fn deliver_rich_document(cell: &RichDocumentCell, result: Result<serde_json::Value, AppError>) {
+    if let Ok(mut slot) = cell.lock() {
+        *slot = Some(result.map_err(|e| e.to_string()));
+    }
+}
+
+/// Send a GET/PUT [`RequestSpec`] with the editor identity headers and parse the JSON response. A 409
+/// is reported as a version conflict (the operator must reload); any other non-success is an
+/// [`AppError::Http`].
+async fn send_rich_document(
+    client: &reqwest::Client,
+    spec: RequestSpec,
+) -> Result<serde_json::Value, AppError> {
+    let mut req = match spec.method {
+        HttpMethod::Put => client.put(&spec.url),
+        _ => client.get(&spec.url),
+    };
+    for (name, value) in RichDocumentClient::identity_headers() {
+        req = req.header(name, value);
+    }
+    if let Some(body) = &spec.body {
+        req = req.json(body);
+    }
+    let resp = req
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| AppError::Http(e.to_string()))?;
+    if resp.status() == reqwest::StatusCode::CONFLICT {
+        return Err(AppError::Http(
+            "version conflict: the document changed since it was loaded; reload before saving"
+                .to_owned(),
+        ));
+    }
+    if !resp.status().is_success() {
+        return Err(AppError::Http(format!("non-success status {}", resp.status())));
+    }
+    resp.json().await.map_err(|e| AppError::Parse(e.to_string()))
+}
+
+// ═════════════════════════════════════════════════════════════════════════════════════════════════
+// Off-thread client for the native terminal pane (`crate::terminal_pane`).
+//
+// Endpoints VERIFIED READ-ONLY against `src/backend/handshake_core` (`api::terminal`), a REST mirror of
+// the Tauri `kernel_terminal_*` commands over the process-wide `TerminalRuntime`:
+//   - `GET    /terminal/sessions` → `{ sessions: [SessionInfo] }` (human PTYs + AiJob capture sessions).
+//   - `POST   /terminal/sessions` with `{ rows, cols, title }` → `{ session }` (a HUMAN_DEV PTY).
+//   - `GET    /terminal/sessions/:id/output?since=N` → `{ next, gap, data_base64, exited, exit_code }`.
+//   - `POST   /terminal/sessions/:id/input` with `{ data_base64 }` — a REST caller is always the human
+//     operator at the keyboard; the runtime rejects stdin to a capture session (409).
+//   - `POST   /terminal/sessions/:id/resize` with `{ rows, cols }` → `PtySession::resize`.
+//   - `POST   /terminal/sessions/:id/authorize` — the capability-checked "Take control" gate.
+//   - `DELETE /terminal/sessions/:id` — kill an interactive session.
+//
+// Every terminal route is operator-only: each request carries the backend's operator session secret
+// in `x-handshake-operator-session` (read from the file the backend writes at startup), and a request
+// without it answers 401.
+//
+// Same off-thread shape as the clients above (HBR-QUIET). Parses the small wire shapes into native
+// structs so the pane never depends on `handshake_core` types.
+
+/// Header carrying the operator session secret on the operator-only terminal routes.
+pub const OPERATOR_SESSION_HEADER: &str = "x-handshake-operator-session";
+
+/// The backend's operator session secret: `HANDSHAKE_OPERATOR_SESSION` when set, else the file named by
+/// `HANDSHAKE_OPERATOR_SESSION_FILE`, else `<repo>/data/operator_session.secret` (where handshake_core
+/// writes it at startup). Read per request so a restarted backend's fresh secret is picked up.
+pub fn operator_session_secret() -> Option<String> {
+    if let Ok(secret) = std::env::var("HANDSHAKE_OPERATOR_SESSION") {
+        return Some(secret.trim().to_owned());
+    }
+    let path = std::env::var_os("HANDSHAKE_OPERATOR_SESSION_FILE")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| {
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../../..")
+                .join("data")
+                .join("operator_session.secret")
+        });
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|secret| secret.trim().to_owned())
+        .filter(|secret| !secret.is_empty())
+}
+
+/// One live terminal session as `GET /terminal/sessions` reports it (`SessionInfo` on the wire).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalSessionInfo {
+    pub session_id: String,
+    /// `INTERACTIVE` (a PTY) or `CAPTURE` (a background producer's read-only mirror).
+    pub kind: String,
+    /// `HUMAN_DEV`, `AI_JOB` or `PLUGIN_TOOL`.
+    pub session_type: String,
+    pub title: Option<String>,
+    pub swarm_id: Option<String>,
+    /// True once AI stdin was authorized for an AiJob session ("Take control" passed).
+    pub interactive_authorized: bool,
+    pub exited: bool,
+    pub exit_code: Option<i32>,
+}
+
+impl TerminalSessionInfo {
+    /// Parse one `SessionInfo` object; `None` without a `session_id`.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(ToOwned::to_owned);
+        Some(Self {
+            session_id: text("session_id")?,
+            kind: text("kind").unwrap_or_default(),
+            session_type: text("session_type").unwrap_or_default(),
+            title: text("title"),
+            swarm_id: value
+                .pointer("/binding/swarm_id")
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned),
+            interactive_authorized: value
+                .get("interactive_authorized")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            exited: value.get("exited").and_then(Value::as_bool).unwrap_or(false),
+            exit_code: value.get("exit_code").and_then(Value::as_i64).map(|c| c as i32),
+        })
+    }
+
+    /// A session known only by id (a restored tab before the first list arrives). Treated as
+    /// read-only until the backend describes it.
+    pub fn unknown(session_id: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.into(),
+            kind: String::new(),
+            session_type: String::new(),
+            title: None,
+            swarm_id: None,
+            interactive_authorized: false,
+            exited: false,
+            exit_code: None,
+        }
+    }
+
+    /// A read-only capture of a background producer's output.
+    pub fn is_capture(&self) -> bool {
+        self.kind == "CAPTURE"
+    }
+
+    /// A human developer's shell (interactive for the operator by default).
+    pub fn is_human(&self) -> bool {
+        self.session_type == "HUMAN_DEV"
+    }
+
+    /// Whether stdin can be wired at all: a live interactive PTY (the Tauri `interactiveAllowed`).
+    pub fn interactive_allowed(&self) -> bool {
+        self.kind == "INTERACTIVE" && !self.exited
+    }
+
+    /// The tab / menu label: the title, else the session type and a short id.
+    pub fn label(&self) -> String {
+        match &self.title {
+            Some(title) if !title.trim().is_empty() => title.clone(),
+            _ => {
+                let start = self.session_id.char_indices().rev().nth(5).map_or(0, |(i, _)| i);
+                let kind = if self.is_capture() { "Capture" } else { "Terminal" };
+                format!("{kind} {}", &self.session_id[start..])
+            }
+        }
+    }
+}
+
+/// One `GET .../output` window: the bytes after the requested offset and the exit state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalOutput {
+    /// The offset to request next.
+    pub next: u64,
+    pub data: Vec<u8>,
+    /// Bytes between the requested offset and `data` were already dropped by the backend log.
+    pub gap: bool,
+    pub exited: bool,
+    pub exit_code: Option<i32>,
+}
+
+impl TerminalOutput {
+    /// Parse the output response; `Err` on a missing offset or undecodable base64.
+    pub fn from_json(value: &Value) -> Result<Self, AppError> {
+        use base64::Engine;
+        let next = value
+            .get("next")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| AppError::Parse("terminal output without `next`".to_owned()))?;
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(value.get("data_base64").and_then(Value::as_str).unwrap_or(""))
+            .map_err(|e| AppError::Parse(format!("terminal output base64: {e}")))?;
+        Ok(Self {
+            next,
+            data,
+            gap: value.get("gap").and_then(Value::as_bool).unwrap_or(false),
+            exited: value.get("exited").and_then(Value::as_bool).unwrap_or(false),
+            exit_code: value.get("exit_code").and_then(Value::as_i64).map(|c| c as i32),
+        })
+    }
+}
+
+/// Delivery cell for `GET /terminal/sessions`.
+pub type TerminalListCell = Arc<Mutex<Option<Result<Vec<TerminalSessionInfo>, String>>>>;
+/// Delivery cell for a created session.
+pub type TerminalSessionCell = Arc<Mutex<Option<Result<TerminalSessionInfo, String>>>>;
+/// Delivery cell for one output poll.
+pub type TerminalOutputCell = Arc<Mutex<Option<Result<TerminalOutput, String>>>>;
+/// Delivery cell for input / resize / authorize / close (no body on success).
+pub type TerminalOpCell = Arc<Mutex<Option<Result<(), String>>>>;
+
+/// REST client for the VERIFIED terminal routes. Mirrors the `RichDocumentClient` shape.
+#[derive(Clone)]
+pub struct TerminalClient {
+    client: reqwest::Client,
+    base_url: String,
+    runtime: tokio::runtime::Handle,
+    /// Fixed operator secret; `None` reads [`operator_session_secret`] per request.
+    operator_session: Option<String>,
+}
+
+impl TerminalClient {
+    /// Build a client against `base_url` (e.g. [`BACKEND_BASE_URL`]) bridging onto `runtime`.
+    pub fn new(base_url: impl Into<String>, runtime: tokio::runtime::Handle) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            runtime,
+            operator_session: None,
+        }
+    }
+
+    /// Pin the operator session secret instead of reading it from the backend's data dir.
+    pub fn with_operator_session(mut self, secret: impl Into<String>) -> Self {
+        self.operator_session = Some(secret.into());
+        self
+    }
+
+    fn operator_session(&self) -> Option<String> {
+        self.operator_session.clone().or_else(operator_session_secret)
+    }
+
+    /// The production client: the hardcoded backend base URL, bridging onto the app's runtime handle.
+    pub fn production(runtime: tokio::runtime::Handle) -> Self {
+        Self::new(BACKEND_BASE_URL, runtime)
+    }
+
+    fn session_url(&self, session_id: &str) -> String {
+        format!("{}/terminal/sessions/{}", self.base_url, session_id)
+    }
+
+    /// `GET /terminal/sessions`, off the UI thread.
+    pub fn list_sessions(&self, cell: TerminalListCell) {
+        let spec = self.list_request();
+        let client = self.client.clone();
+        let session = self.operator_session();
+        self.runtime.spawn(async move {
+            let result = send_terminal(&client, spec, session).await.map(|body| {
+                body.get("sessions")
+                    .and_then(Value::as_array)
+                    .map(|items| items.iter().filter_map(TerminalSessionInfo::from_json).collect())
+                    .unwrap_or_default()
+            });
+            deliver_terminal(&cell, result);
+        });
+    }
+
+    /// Pure request builder for [`list_sessions`](Self::list_sessions).
+    pub fn list_request(&self) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Get,
+            url: format!("{}/terminal/sessions", self.base_url),
+            body: None,
+        }
+    }
+
+    /// `POST /terminal/sessions`: spawn a HUMAN_DEV shell sized `rows` x `cols`.
+    pub fn create_session(&self, rows: u16, cols: u16, title: &str, cell: TerminalSessionCell) {
+        let spec = self.create_request(rows, cols, title);
+        let client = self.client.clone();
+        let session = self.operator_session();
+        self.runtime.spawn(async move {
+            let result = send_terminal(&client, spec, session).await.and_then(|body| {
+                body.get("session")
+                    .and_then(TerminalSessionInfo::from_json)
+                    .ok_or_else(|| AppError::Parse("create response without `session`".to_owned()))
+            });
+            deliver_terminal(&cell, result);
+        });
+    }
+
+    /// Pure request builder for [`create_session`](Self::create_session).
+    pub fn create_request(&self, rows: u16, cols: u16, title: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Post,
+            url: format!("{}/terminal/sessions", self.base_url),
+            body: Some(serde_json::json!({
+                "session_type": "HUMAN_DEV",
+                "rows": rows,
+                "cols": cols,
+                "title": title,
+            })),
+        }
+    }
+
+    /// `GET /terminal/sessions/:id/output?since=N`, off the UI thread.
+    pub fn poll_output(&self, session_id: &str, since: u64, cell: TerminalOutputCell) {
+        let spec = self.output_request(session_id, since);
+        let client = self.client.clone();
+        let session = self.operator_session();
+        self.runtime.spawn(async move {
+            let result = async {
+                let resp = with_operator_session(client.get(&spec.url), session)
+                    .query(&spec.query)
+                    .timeout(Duration::from_secs(5))
+                    .send()
+                    .await
+                    .map_err(|e| AppError::Http(e.to_string()))?;
+                let body = terminal_body(resp).await?;
+                TerminalOutput::from_json(&body)
+            }
+            .await;
+            deliver_terminal(&cell, result);
+        });
+    }
+
+    /// Pure request builder for [`poll_output`](Self::poll_output).
+    pub fn output_request(&self, session_id: &str, since: u64) -> GetRequestSpec {
+        GetRequestSpec {
+            method: HttpMethod::Get,
+            url: format!("{}/output", self.session_url(session_id)),
+            query: vec![("since".to_owned(), since.to_string())],
+        }
+    }
+
+    /// `POST /terminal/sessions/:id/input` with the operator's keystrokes.
+    pub fn write_input(&self, session_id: &str, bytes: &[u8], cell: TerminalOpCell) {
+        self.send_op(self.input_request(session_id, bytes), cell);
+    }
+
+    /// Pure request builder for [`write_input`](Self::write_input).
+    pub fn input_request(&self, session_id: &str, bytes: &[u8]) -> RequestSpec {
+        use base64::Engine;
+        RequestSpec {
+            method: HttpMethod::Post,
+            url: format!("{}/input", self.session_url(session_id)),
+            body: Some(serde_json::json!({
+                "data_base64": base64::engine::general_purpose::STANDARD.encode(bytes),
+            })),
+        }
+    }
+
+    /// `POST /terminal/sessions/:id/resize`.
+    pub fn resize(&self, session_id: &str, rows: u16, cols: u16, cell: TerminalOpCell) {
+        self.send_op(self.resize_request(session_id, rows, cols), cell);
+    }
+
+    /// Pure request builder for [`resize`](Self::resize).
+    pub fn resize_request(&self, session_id: &str, rows: u16, cols: u16) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Post,
+            url: format!("{}/resize", self.session_url(session_id)),
+            body: Some(serde_json::json!({ "rows": rows, "cols": cols })),
+        }
+    }
+
+    /// `POST /terminal/sessions/:id/authorize` ("Take control" of an AiJob session).
+    pub fn authorize(&self, session_id: &str, cell: TerminalOpCell) {
+        self.send_op(self.authorize_request(session_id), cell);
+    }
+
+    /// Pure request builder for [`authorize`](Self::authorize).
+    pub fn authorize_request(&self, session_id: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Post,
+            url: format!("{}/authorize", self.session_url(session_id)),
+            body: Some(serde_json::json!({})),
+        }
+    }
+
+    /// `DELETE /terminal/sessions/:id`.
+    pub fn close_session(&self, session_id: &str, cell: TerminalOpCell) {
+        self.send_op(self.close_request(session_id), cell);
+    }
+
+    /// Pure request builder for [`close_session`](Self::close_session).
+    pub fn close_request(&self, session_id: &str) -> RequestSpec {
+        RequestSpec {
+            method: HttpMethod::Delete,
+            url: self.session_url(session_id),
+            body: None,
+        }
+    }
+
+    fn send_op(&self, spec: RequestSpec, cell: TerminalOpCell) {
+        let client = self.client.clone();
+        let session = self.operator_session();
+        self.runtime.spawn(async move {
+            let result = send_terminal(&client, spec, session).await.map(|_| ());
+            deliver_terminal(&cell, result);
+        });
+    }
+}
+
+/// Write a terminal result into its delivery cell.
+fn deliver_terminal<T>(cell: &Arc<Mutex<Option<Result<T, String>>>>, result: Result<T, AppError>) {
+    if let Ok(mut slot) = cell.lock() {
+        *slot = Some(result.map_err(|e| e.to_string()));
+    }
+}
+
+/// Attach the operator session header when a secret is known; without one the backend answers 401.
+fn with_operator_session(req: reqwest::RequestBuilder, session: Option<String>) -> reqwest::RequestBuilder {
+    match session {
+        Some(secret) => req.header(OPERATOR_SESSION_HEADER, secret),
+        None => req,
+    }
+}
+
+/// Send a terminal [`RequestSpec`] and return its JSON body (`Null` for a 204).
+async fn send_terminal(
+    client: &reqwest::Client,
+    spec: RequestSpec,
+    session: Option<String>,
+) -> Result<Value, AppError> {
+    let req = match spec.method {
+        HttpMethod::Post => client.post(&spec.url),
+        HttpMethod::Delete => client.delete(&spec.url),
+        _ => client.get(&spec.url),
+    };
+    let mut req = with_operator_session(req, session);
+    if let Some(body) = &spec.body {
+        req = req.json(body);
+    }
+    let resp = req
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| AppError::Http(e.to_string()))?;
+    terminal_body(resp).await
+}
+
+/// Map a terminal response: non-success surfaces the backend's `detail` (e.g. "capture session is
+/// read-only"), a 204 is `Null`, anything else is parsed JSON.
+async fn terminal_body(resp: reqwest::Response) -> Result<Value, AppError> {
+    let status = resp.status();
+    if !status.is_success() {
+        let detail = resp
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("detail").and_then(Value::as_str).map(ToOwned::to_owned));
+        return Err(AppError::Http(match detail {
+            Some(detail) => format!("{status}: {detail}"),
+            None => format!("non-success status {status}"),
+        }));
+    }
+    if status == reqwest::StatusCode::NO_CONTENT {
+        return Ok(Value::Null);
+    }
+    resp.json().await.map_err(|e| AppError::Parse(e.to_string()))
+}
+
 // ═════════════════════════════════════════════════════════════════════════════════════════════════
 // MT-021 hardening tests (MAJOR #1/#2/#3): prove every menu-action backend call constructs the EXACT
 // verified URL + JSON body. Two layers:
@@ -1431,6 +2331,82 @@ mod tests {
         assert_eq!(spec.body.unwrap(), serde_json::json!({ "title": "New Title" }));
     }
 
+    #[test]
+    fn loom_create_edge_request_is_user_authored() {
+        let rt = rt();
+        let c = LoomBlockClient::new(BASE, rt.handle().clone());
+        let spec = c.create_edge_request("ws1", "b1", "b2", LoomEdgeKind::SubTag);
+        assert_eq!(spec.method, HttpMethod::Post);
+        assert_eq!(spec.url, "http://test.local:1234/workspaces/ws1/loom/edges");
+        assert_eq!(
+            spec.body.unwrap(),
+            serde_json::json!({
+                "source_block_id": "b1",
+                "target_block_id": "b2",
+                "edge_type": "sub_tag",
+                "created_by": "user",
+            })
+        );
+    }
+
+    #[test]
+    fn loom_restore_edge_request_keeps_id_and_anchor() {
+        let rt = rt();
+        let c = LoomBlockClient::new(BASE, rt.handle().clone());
+        let anchor = serde_json::json!({ "document_id": "d1", "offset_start": 3, "offset_end": 9 });
+        let edge = LoomEdgeInfo {
+            edge_id: "e7".to_owned(),
+            source_block_id: "b1".to_owned(),
+            target_block_id: "b2".to_owned(),
+            kind: LoomEdgeKind::Mention,
+            source_anchor: Some(anchor.clone()),
+        };
+        let body = c.restore_edge_request("ws1", &edge).body.unwrap();
+        assert_eq!(body["edge_id"], "e7");
+        assert_eq!(body["edge_type"], "mention");
+        assert_eq!(body["source_anchor"], anchor);
+    }
+
+    #[test]
+    fn loom_delete_requests_target_edge_and_block_routes() {
+        let rt = rt();
+        let c = LoomBlockClient::new(BASE, rt.handle().clone());
+        let edge = c.delete_edge_request("ws1", "e7");
+        assert_eq!(edge.method, HttpMethod::Delete);
+        assert_eq!(edge.url, "http://test.local:1234/workspaces/ws1/loom/edges/e7");
+        let block = c.delete_block_request("ws1", "b3");
+        assert_eq!(block.method, HttpMethod::Delete);
+        assert_eq!(block.url, "http://test.local:1234/workspaces/ws1/loom/blocks/b3");
+        assert!(edge.body.is_none() && block.body.is_none());
+    }
+
+    #[test]
+    fn loom_graph_snapshot_parses_nodes_and_edges() {
+        let body = serde_json::json!({
+            "nodes": [
+                { "block": { "block_id": "b1", "title": "One", "pinned": true, "favorite": false,
+                             "content_type": "note" }, "depth": 0, "degree": 1, "stale": false },
+                { "block": { "block_id": "t1", "title": "Topic", "content_type": "tag_hub" },
+                  "depth": 0, "degree": 1, "stale": false },
+            ],
+            "edges": [
+                { "edge": { "edge_id": "e1", "source_block_id": "b1", "target_block_id": "t1",
+                            "edge_type": "tag", "source_anchor": null }, "stale": false },
+                { "edge": { "edge_id": "e2", "edge_type": "bogus" }, "stale": false },
+            ],
+            "truncated": true,
+        });
+        let graph = LoomGraphSnapshot::from_json(&body).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.nodes[0].pinned && !graph.nodes[0].tag_hub);
+        assert!(graph.nodes[1].tag_hub);
+        assert_eq!(graph.edges.len(), 1, "an unparseable edge row is skipped");
+        assert_eq!(graph.edges[0].kind, LoomEdgeKind::Tag);
+        assert_eq!(graph.edges[0].source_anchor, None);
+        assert!(graph.truncated);
+        assert!(LoomGraphSnapshot::from_json(&serde_json::json!({})).is_err());
+    }
+
     // ── MT-023 DrawerDataClient: verified view-count + daily-journal requests ────────────────────────
 
     #[test]
@@ -1822,4 +2798,108 @@ mod tests {
             "missing blocks field defaults to 0 (CONTROL-023-D), never an error"
         );
     }
+
+    // ── RichDocumentClient: load / versioned save ────────────────────────────────────────────────────
+
+    #[test]
+    fn rich_document_load_request_url() {
+        let rt = rt();
+        let c = RichDocumentClient::new(BASE, rt.handle().clone());
+        let spec = c.load_request("KRD-abc");
+        assert_eq!(spec.method, HttpMethod::Get);
+        assert_eq!(spec.url, "http://test.local:1234/knowledge/documents/KRD-abc");
+        assert_eq!(spec.body, None);
+    }
+
+    #[test]
+    fn rich_document_save_request_carries_version_guard_and_crdt_id() {
+        let rt = rt();
+        let c = RichDocumentClient::new(BASE, rt.handle().clone());
+        let content = serde_json::json!({ "type": "doc", "content": [] });
+        let spec = c.save_request("KRD-abc", 7, content.clone(), "KCRDT-abc");
+        assert_eq!(spec.method, HttpMethod::Put);
+        assert_eq!(spec.url, "http://test.local:1234/knowledge/documents/KRD-abc/save");
+        assert_eq!(
+            spec.body.unwrap(),
+            serde_json::json!({
+                "expected_version": 7,
+                "content_json": content,
+                "crdt_document_id": "KCRDT-abc",
+            })
+        );
+    }
+
+    #[test]
+    fn rich_document_requests_send_writable_operator_identity() {
+        let headers = RichDocumentClient::identity_headers();
+        let names: Vec<&str> = headers.iter().map(|(n, _)| *n).collect();
+        for required in ["x-hsk-actor-id", "x-hsk-kernel-task-run-id", "x-hsk-session-run-id"] {
+            assert!(names.contains(&required), "{required} is sent: {names:?}");
+        }
+        // Without an actor kind the backend treats the caller as read-only and rejects the save.
+        assert!(headers.contains(&("x-hsk-actor-kind", "operator")));
+    }
+
+    // ── TerminalClient: sessions / output / stdin / resize ───────────────────────────────────────────
+
+    #[test]
+    fn terminal_requests_target_the_verified_routes() {
+        let rt = rt();
+        let c = TerminalClient::new(BASE, rt.handle().clone());
+        let create = c.create_request(24, 80, "Terminal");
+        assert_eq!(create.method, HttpMethod::Post);
+        assert_eq!(create.url, "http://test.local:1234/terminal/sessions");
+        assert_eq!(
+            create.body.unwrap(),
+            serde_json::json!({ "session_type": "HUMAN_DEV", "rows": 24, "cols": 80, "title": "Terminal" })
+        );
+        let output = c.output_request("s-1", 42);
+        assert_eq!(output.url, "http://test.local:1234/terminal/sessions/s-1/output");
+        assert_eq!(output.query, vec![("since".to_owned(), "42".to_owned())]);
+        let resize = c.resize_request("s-1", 30, 100);
+        assert_eq!(resize.url, "http://test.local:1234/terminal/sessions/s-1/resize");
+        assert_eq!(resize.body.unwrap(), serde_json::json!({ "rows": 30, "cols": 100 }));
+        let close = c.close_request("s-1");
+        assert_eq!((close.method, close.body), (HttpMethod::Delete, None));
+    }
+
+    #[test]
+    fn terminal_input_is_base64_and_always_human() {
+        let rt = rt();
+        let c = TerminalClient::new(BASE, rt.handle().clone());
+        let spec = c.input_request("s-1", b"ls\r");
+        assert_eq!(spec.url, "http://test.local:1234/terminal/sessions/s-1/input");
+        assert_eq!(
+            spec.body.unwrap(),
+            serde_json::json!({ "data_base64": "bHMN" }),
+            "the backend decides the role; the body carries only the keystrokes"
+        );
+    }
+
+    #[test]
+    fn terminal_wire_shapes_parse() {
+        let info = TerminalSessionInfo::from_json(&serde_json::json!({
+            "session_id": "0190-abcdef123456",
+            "kind": "CAPTURE",
+            "session_type": "AI_JOB",
+            "binding": { "swarm_id": "sw-1", "worktree_id": null, "instance_id": null },
+            "trace_id": "t",
+            "title": null,
+            "interactive_authorized": false,
+            "exited": false,
+            "exit_code": null,
+        }))
+        .unwrap();
+        assert!(info.is_capture() && !info.is_human() && !info.interactive_allowed());
+        assert_eq!(info.swarm_id.as_deref(), Some("sw-1"));
+        assert_eq!(info.label(), "Capture 123456");
+
+        let out = TerminalOutput::from_json(&serde_json::json!({
+            "session_id": "s", "offset": 0, "next": 3, "gap": false,
+            "data_base64": "aGkK", "exited": true, "exit_code": 0,
+        }))
+        .unwrap();
+        assert_eq!((out.next, out.data.as_slice(), out.exited, out.exit_code), (3, &b"hi\n"[..], true, Some(0)));
+        assert!(TerminalOutput::from_json(&serde_json::json!({ "data_base64": "" })).is_err());
+    }
 }
@@ -103,7 +103,10 @@ struct PaletteState {
 /// rendered as a backdrop [`egui::Area`] (full-screen, behind the panel, catches click-to-dismiss) plus
 /// a centred [`egui::Window`] with the title bar hidden — both on the `Foreground` order so the palette
 /// sits above the workspace (AC10) but below a higher overlay the shell renders later (settings).
-pub fn show(ctx: &egui::Context, open_count: u64) -> PaletteOutcome {
+///
+/// `editor_active` is whether a loaded rich-document editor is the active tab; editor commands are only
+/// runnable while it is ([`AppCommand::is_runnable`]).
+pub fn show(ctx: &egui::Context, open_count: u64, editor_active: bool) -> PaletteOutcome {
     let state_id = egui::Id::new("command-palette.state");
     let mut state: PaletteState = ctx
         .data_mut(|d| d.get_temp::<PaletteState>(state_id))
@@ -169,7 +172,7 @@ pub fn show(ctx: &egui::Context, open_count: u64) -> PaletteOutcome {
     // Enter runs the selected ENABLED command (AC4 / AC7: disabled rows are not runnable).
     if enter {
         if let Some(cmd) = filtered.get(state.selected_index) {
-            if !cmd.disabled {
+            if cmd.is_runnable(editor_active) {
                 outcome = PaletteOutcome::Run(cmd.id.to_owned());
             }
         }
@@ -273,11 +276,12 @@ pub fn show(ctx: &egui::Context, open_count: u64) -> PaletteOutcome {
                         }
                         for (idx, cmd) in rows.iter().enumerate() {
                             let is_selected = idx == sel && !rows.is_empty();
-                            let resp = command_row(ui, cmd, is_selected);
+                            let runnable = cmd.is_runnable(editor_active);
+                            let resp = command_row(ui, cmd, is_selected, runnable);
                             if resp.hovered() {
                                 hovered_index = Some(idx);
                             }
-                            if resp.clicked() && !cmd.disabled {
+                            if resp.clicked() && runnable {
                                 clicked_command = Some(cmd.id.to_owned());
                             }
                         }
@@ -319,7 +323,12 @@ fn persist(ctx: &egui::Context, state_id: egui::Id, state: &PaletteState) {
 /// Render one command row as a full-width selectable button. The selected row uses egui's selection
 /// fill; a disabled row renders grayed and is added via `add_enabled(false, ..)` so it cannot be
 /// clicked into a run (AC7 — no fake-enable). Bold label on the left, muted description on the right.
-fn command_row(ui: &mut egui::Ui, cmd: &AppCommand, is_selected: bool) -> egui::Response {
+fn command_row(
+    ui: &mut egui::Ui,
+    cmd: &AppCommand,
+    is_selected: bool,
+    runnable: bool,
+) -> egui::Response {
     let author_id = format!("{ROW_AUTHOR_ID_PREFIX}{}", cmd.stable_id);
     let full_width = ui.available_width();
 
@@ -332,7 +341,7 @@ fn command_row(ui: &mut egui::Ui, cmd: &AppCommand, is_selected: bool) -> egui::
         cmd.label,
         0.0,
         egui::TextFormat {
-            color: if cmd.disabled { weak } else { strong },
+            color: if runnable { strong } else { weak },
             ..Default::default()
         },
     );
@@ -341,13 +350,13 @@ fn command_row(ui: &mut egui::Ui, cmd: &AppCommand, is_selected: bool) -> egui::
         0.0,
         egui::TextFormat {
             color: weak,
-            italics: cmd.disabled,
+            italics: !runnable,
             ..Default::default()
         },
     );
 
     let response = ui.add_enabled(
-        !cmd.disabled,
+        runnable,
         egui::Button::selectable(is_selected, job)
             .truncate()
             .min_size(egui::vec2(full_width, 0.0)),
@@ -357,7 +366,7 @@ fn command_row(ui: &mut egui::Ui, cmd: &AppCommand, is_selected: bool) -> egui::
     // egui built for this row (SelectableLabel derives Role + Action::Click from its Sense). This adds
     // the out-of-process address while leaving egui's interactive role/actions intact.
     let label = cmd.label.to_owned();
-    let disabled = cmd.disabled;
+    let disabled = !runnable;
     ui.ctx().accesskit_node_builder(response.id, move |node| {
         node.set_role(accesskit::Role::ListBoxOption);
         node.set_author_id(author_id);
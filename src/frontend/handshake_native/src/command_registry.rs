@@ -4,8 +4,7 @@
 //!
 //! The React shell builds its command-palette action list at runtime from `buildAppCommandRegistry`
 //! (the app-level `usermanual.*` / `settings.*` / `theme.*` etc. entries) plus the editor command
-//! catalog (`editor_commands.ts`). The native shell does not have a runtime document/editor model yet,
-//! so the catalog is COMPILE-TIME static: a `&'static [AppCommand]` computed once via [`OnceLock`]
+//! catalog (`editor_commands.ts`). The native catalog is COMPILE-TIME static: a `&'static [AppCommand]` computed once via [`OnceLock`]
 //! (red-team MC2 — no per-frame allocation). Every entry maps to a REAL dispatch arm in
 //! `app.rs::dispatch_palette_action`; there are no fake commands with no target (the contract's
 //! "do NOT fake commands" rule).
@@ -15,10 +14,11 @@
 //! - [`CommandKind::App`] entries dispatch into existing shell state mutations (theme toggle, view-mode
 //!   toggle, layout reset, settings open, navigate-to-tab). These are wired and runnable now.
 //! - [`CommandKind::Editor`] entries are a representative subset of the React `EDITOR_COMMANDS` catalog
-//!   (id/label/keywords ported verbatim). The native editor surface is a FUTURE MT, so editor commands
-//!   are rendered DISABLED (`disabled: true`) — they appear in the palette so a model can SEE the full
-//!   action surface, but they cannot be run until the editor pane lands. This mirrors how the React
-//!   registry sets `disabled: !editorCommandsEnabled` when no editor is active. No fake-enable.
+//!   (id/label/keywords ported verbatim). They run against the active native rich-document editor
+//!   (`crate::rich_document_editor`). Whether one is runnable is decided per frame by
+//!   [`AppCommand::is_runnable`]: with no loaded editor on the active pane the rows render DISABLED but
+//!   stay visible so a model can SEE the full action surface. This mirrors how the React registry sets
+//!   `disabled: !editorCommandsEnabled` when no editor is active. No fake-enable.
 //!
 //! ## Stable ids (HBR-SWARM)
 //!
@@ -29,13 +29,13 @@
 
 use std::sync::OnceLock;
 
-/// Whether a command targets the shell (runnable now) or the editor surface (a future MT).
+/// Whether a command targets the shell (always runnable) or the active rich-document editor.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommandKind {
     /// A shell-level action wired into existing `app.rs` state mutations (runnable now).
     App,
-    /// An editor-surface action ported from the React `EDITOR_COMMANDS` catalog. Rendered disabled
-    /// until the native editor pane lands (a future MT); present so the action surface is discoverable.
+    /// An editor-surface action ported from the React `EDITOR_COMMANDS` catalog. Runnable only while a
+    /// rich-document editor is active; otherwise rendered disabled so the action surface is discoverable.
     Editor,
 }
 
@@ -56,11 +56,19 @@ pub struct AppCommand {
     pub keywords: &'static [&'static str],
     /// Stable out-of-process address (kebab-case, `hs-` prefixed) — the swarm-dispatch key.
     pub stable_id: &'static str,
-    /// When true the row renders grayed and cannot be executed (Enter / click is a no-op). Editor
-    /// commands are disabled until the native editor surface lands; no fake-enable.
+    /// When true the row always renders grayed and cannot be executed (Enter / click is a no-op).
+    /// Editor commands are additionally gated on an active editor by [`AppCommand::is_runnable`].
     pub disabled: bool,
 }
 
+impl AppCommand {
+    /// Whether the palette may run this command now: never when statically `disabled`, and an editor
+    /// command only while a rich-document editor is active (React `disabled: !editorCommandsEnabled`).
+    pub fn is_runnable(&self, editor_active: bool) -> bool {
+        !self.disabled && (self.kind == CommandKind::App || editor_active)
+    }
+}
+
 /// The canonical command catalog a swarm agent may dispatch through the palette (HBR-SWARM).
 ///
 /// Returns a `&'static [AppCommand]` computed once (red-team MC2: static-ref access, no per-frame
@@ -70,8 +78,8 @@ pub struct AppCommand {
 /// - tests (no-duplicate-id proof + the live kittest open/type/run cases).
 ///
 /// App commands are enabled (wired to existing shell state). Editor commands are a representative
-/// subset of the React `EDITOR_COMMANDS` (id/label/keywords ported verbatim) and are disabled until the
-/// native editor pane lands.
+/// subset of the React `EDITOR_COMMANDS` (id/label/keywords ported verbatim) and run only while a
+/// rich-document editor is active ([`AppCommand::is_runnable`]).
 pub fn all_commands() -> &'static [AppCommand] {
     static CATALOG: OnceLock<Vec<AppCommand>> = OnceLock::new();
     CATALOG
@@ -209,9 +217,8 @@ const APP_COMMANDS: &[AppCommand] = &[
 
 /// A representative subset (13 entries) of the React `EDITOR_COMMANDS` catalog
 /// (`app/src/lib/editor/editor_commands.ts`), ported as `CommandKind::Editor` rows. The id/label/keywords
-/// are ported verbatim; the native shell has no editor surface yet, so all are `disabled: true` (the
-/// React registry sets `disabled: !editorCommandsEnabled` the same way). A follow-up MT can extend this
-/// to the full catalog and flip `disabled` when the editor pane is active.
+/// are ported verbatim. Each has a dispatch arm on the native rich-document editor; they are runnable
+/// only while one is active (the React registry sets `disabled: !editorCommandsEnabled` the same way).
 const EDITOR_COMMANDS: &[AppCommand] = &[
     editor_cmd("editor.format.bold", "Bold", &["bold", "strong", "format"], "hs-editor-command-format-bold"),
     editor_cmd("editor.format.italic", "Italic", &["italic", "emphasis", "format"], "hs-editor-command-format-italic"),
@@ -228,7 +235,7 @@ const EDITOR_COMMANDS: &[AppCommand] = &[
     editor_cmd("editor.link.wikilink", "Insert link", &["link", "wikilink", "note", "reference"], "hs-editor-command-link-wikilink"),
 ];
 
-/// Const helper building one disabled `CommandKind::Editor` entry with the React-aligned description
+/// Const helper building one `CommandKind::Editor` entry with the React-aligned description
 /// ("Editor command."), keeping the editor table compact and consistent.
 const fn editor_cmd(
     id: &'static str,
@@ -240,10 +247,10 @@ const fn editor_cmd(
         id,
         kind: CommandKind::Editor,
         label,
-        description: "Editor command (needs the editor surface — future MT).",
+        description: "Editor command (runs on the active document editor).",
         keywords,
         stable_id,
-        disabled: true,
+        disabled: false,
     }
 }
 
@@ -339,14 +346,18 @@ mod tests {
         assert!(!ids.contains(&"theme.toggle"), "theme.toggle excluded from 'manual' results: {ids:?}");
     }
 
-    /// App commands are runnable; editor commands are disabled until the editor pane lands (no fake-enable).
+    /// App commands are always runnable; editor commands only while an editor is active (no fake-enable).
     #[test]
-    fn app_commands_enabled_editor_commands_disabled() {
+    fn editor_commands_runnable_only_with_an_active_editor() {
         for cmd in all_commands() {
+            assert!(!cmd.disabled, "'{}' is not statically disabled", cmd.id);
             match cmd.kind {
-                CommandKind::App => assert!(!cmd.disabled, "App command '{}' is enabled", cmd.id),
+                CommandKind::App => {
+                    assert!(cmd.is_runnable(false), "App command '{}' runs without an editor", cmd.id);
+                }
                 CommandKind::Editor => {
-                    assert!(cmd.disabled, "Editor command '{}' is disabled (no editor yet)", cmd.id);
+                    assert!(!cmd.is_runnable(false), "Editor command '{}' needs an editor", cmd.id);
+                    assert!(cmd.is_runnable(true), "Editor command '{}' runs on an editor", cmd.id);
                     assert!(cmd.id.starts_with("editor."), "editor id prefix on '{}'", cmd.id);
                 }
             }
@@ -187,12 +187,15 @@ pub mod pane_ids {
     pub const CLOSE: &str = "pane.close";
 }
 
-/// A typed action a confirmed pane-header menu id maps to. Only the ENABLED ids (lock toggle, pop out)
-/// have variants; set-type / split / close are future-target (disabled), so they cannot fire.
+/// A typed action a confirmed pane-header menu id maps to. Only the ENABLED ids (lock toggle, set type
+/// terminal, pop out) have variants; the other set-type items, split and close are future-target
+/// (disabled), so they cannot fire.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PaneHeaderMenuAction {
     /// Toggle the pane's lock state (`LockState`), the same mutation the header Lock button performs.
     ToggleLock,
+    /// Open a new terminal session as a tab in this pane (`crate::terminal_pane`).
+    OpenTerminal,
     /// Pop the pane out into its own OS window (`HandshakeApp::request_pop_out`).
     PopOut,
 }
@@ -224,10 +227,7 @@ pub fn pane_header_context_items(locked: bool, is_last_pane: bool) -> Vec<Contex
             ContextMenuItem::action(pane_ids::SET_TYPE_EDITOR, "Set Type: Editor")
                 .disabled("Set pane type is a future surface"),
         )
-        .item(
-            ContextMenuItem::action(pane_ids::SET_TYPE_TERMINAL, "Set Type: Terminal")
-                .disabled("Set pane type is a future surface"),
-        )
+        .item(ContextMenuItem::action(pane_ids::SET_TYPE_TERMINAL, "Set Type: Terminal"))
         .item(
             ContextMenuItem::action(pane_ids::SET_TYPE_CANVAS, "Set Type: Canvas")
                 .disabled("Set pane type is a future surface"),
@@ -246,6 +246,7 @@ pub fn pane_header_context_items(locked: bool, is_last_pane: bool) -> Vec<Contex
 pub fn pane_header_action_for_id(id: &str) -> Option<PaneHeaderMenuAction> {
     match id {
         pane_ids::LOCK => Some(PaneHeaderMenuAction::ToggleLock),
+        pane_ids::SET_TYPE_TERMINAL => Some(PaneHeaderMenuAction::OpenTerminal),
         pane_ids::POP_OUT => Some(PaneHeaderMenuAction::PopOut),
         _ => None,
     }
@@ -430,8 +431,8 @@ pub mod loom_ids {
     pub const DELETE: &str = "loom.delete";
 }
 
-/// A typed action a confirmed Loom-node menu id maps to. Only ENABLED ids have variants; the V1 stubs
-/// (`connect`/`disconnect`/`delete`) are disabled, so they have NO variant and can never fire.
+/// A typed action a confirmed Loom-node menu id maps to. Every id has a variant; `disconnect` is disabled
+/// (and so can never fire) on a node with no edges.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoomNodeMenuAction {
     /// Open the block in a new tab on the active pane.
@@ -450,6 +451,15 @@ pub enum LoomNodeMenuAction {
     CopyBlockId,
     /// Open / focus the LoomBlock panel pane for the block.
     RevealInPanel,
+    /// Enter connect mode: the next node clicked becomes the edge target, then the relation-type
+    /// picker chooses the `LoomEdgeType` for `POST /workspaces/:id/loom/edges`.
+    Connect,
+    /// Open the edge picker listing this node's edges; the chosen one is removed via
+    /// `DELETE /workspaces/:id/loom/edges/:edge_id`.
+    Disconnect,
+    /// Open the delete confirmation dialog; only a confirmed delete reaches
+    /// `DELETE /workspaces/:id/loom/blocks/:block_id`.
+    Delete,
 }
 
 /// The cached Loom-block state the menu builder reads. The pin/favorite LABELS and the toggle TARGET
@@ -467,13 +477,12 @@ pub struct LoomNodeState {
 
 /// Build the Loom-graph-node context menu for `state`. Pin/Favorite labels + toggle targets come from
 /// the FRESH cached `state` (red-team stale-state control). `disconnect` is enabled iff the node has
-/// edges. `connect`/`disconnect`/`delete` are V1 stubs — disabled + disclosed (no fake-enable).
+/// edges; `delete` opens a confirmation dialog rather than deleting directly.
 pub fn loom_node_context_items(state: &LoomNodeState) -> Vec<ContextMenuItem> {
     let pin_label = if state.pinned { "Unpin" } else { "Pin" };
     let fav_label = if state.favorite { "Unfavorite" } else { "Favorite" };
     let disconnect = if state.has_edges {
-        ContextMenuItem::action(loom_ids::DISCONNECT, "Disconnect")
-            .disabled("Disconnect is a future surface (no edge-edit endpoint wired)")
+        ContextMenuItem::action(loom_ids::DISCONNECT, "Disconnect...")
     } else {
         ContextMenuItem::action(loom_ids::DISCONNECT, "Disconnect")
             .disabled("No edges on this node")
@@ -486,25 +495,19 @@ pub fn loom_node_context_items(state: &LoomNodeState) -> Vec<ContextMenuItem> {
         .item(ContextMenuItem::action(loom_ids::PIN, pin_label))
         .item(ContextMenuItem::action(loom_ids::FAVORITE, fav_label))
         .separator()
-        .item(
-            ContextMenuItem::action(loom_ids::CONNECT, "Connect to...")
-                .disabled("Connection picker is a future surface (V1 stub)"),
-        )
+        .item(ContextMenuItem::action(loom_ids::CONNECT, "Connect to..."))
         .item(disconnect)
         .separator()
         .item(ContextMenuItem::action(loom_ids::COPY_BLOCK_ID, "Copy Block ID"))
         .item(ContextMenuItem::action(loom_ids::REVEAL_IN_PANEL, "Reveal in Block Panel"))
         .separator()
-        .item(
-            ContextMenuItem::action(loom_ids::DELETE, "Delete Block")
-                .disabled("Delete needs a confirmation dialog (future surface)"),
-        )
+        .item(ContextMenuItem::action(loom_ids::DELETE, "Delete Block..."))
         .into_items()
 }
 
 /// Map a confirmed Loom-node menu id to its typed action, computing the pin/favorite toggle TARGET from
 /// the FRESH cached `state` (so the action always flips the right way even if the menu was open while
-/// another pane changed the block). A stub / unknown id maps to `None`.
+/// another pane changed the block). `disconnect` on an edgeless node, or an unknown id, maps to `None`.
 pub fn loom_node_action_for_id(id: &str, state: &LoomNodeState) -> Option<LoomNodeMenuAction> {
     match id {
         loom_ids::OPEN => Some(LoomNodeMenuAction::Open),
@@ -514,6 +517,9 @@ pub fn loom_node_action_for_id(id: &str, state: &LoomNodeState) -> Option<LoomNo
         loom_ids::FAVORITE => Some(LoomNodeMenuAction::ToggleFavorite { target: !state.favorite }),
         loom_ids::COPY_BLOCK_ID => Some(LoomNodeMenuAction::CopyBlockId),
         loom_ids::REVEAL_IN_PANEL => Some(LoomNodeMenuAction::RevealInPanel),
+        loom_ids::CONNECT => Some(LoomNodeMenuAction::Connect),
+        loom_ids::DISCONNECT if state.has_edges => Some(LoomNodeMenuAction::Disconnect),
+        loom_ids::DELETE => Some(LoomNodeMenuAction::Delete),
         _ => None,
     }
 }
@@ -1270,7 +1276,6 @@ mod tests {
             pane_ids::SPLIT_RIGHT,
             pane_ids::SPLIT_DOWN,
             pane_ids::SET_TYPE_EDITOR,
-            pane_ids::SET_TYPE_TERMINAL,
             pane_ids::SET_TYPE_CANVAS,
             pane_ids::SET_TYPE_BROWSER,
             pane_ids::CLOSE,
@@ -1293,6 +1298,10 @@ mod tests {
         assert_eq!(tab_action_for_id("bogus.id"), None);
 
         assert_eq!(pane_header_action_for_id(pane_ids::LOCK), Some(PaneHeaderMenuAction::ToggleLock));
+        assert_eq!(
+            pane_header_action_for_id(pane_ids::SET_TYPE_TERMINAL),
+            Some(PaneHeaderMenuAction::OpenTerminal)
+        );
         assert_eq!(pane_header_action_for_id(pane_ids::POP_OUT), Some(PaneHeaderMenuAction::PopOut));
 
         assert_eq!(
@@ -1545,19 +1554,29 @@ mod tests {
         );
     }
 
-    /// loom stubs (connect/disconnect/delete) are disabled + disclosed and map to no action.
+    /// connect/delete are always enabled and mapped; disconnect only when the node has edges.
     #[test]
-    fn loom_stubs_disabled_and_unmapped() {
-        let items = loom_node_context_items(&loom_state(false, false, false));
-        for sid in [loom_ids::CONNECT, loom_ids::DISCONNECT, loom_ids::DELETE] {
-            let item = items.iter().find(|i| i.id == sid).unwrap();
-            assert!(!item.enabled, "{sid} disabled (stub)");
-            assert!(item.disabled_reason.is_some(), "{sid} discloses why");
-            assert!(
-                loom_node_action_for_id(sid, &loom_state(true, true, true)).is_none(),
-                "{sid} maps to no fireable action",
-            );
+    fn loom_edge_edit_and_delete_enabled_and_mapped() {
+        let edgeless = loom_state(false, false, false);
+        let items = loom_node_context_items(&edgeless);
+        for sid in [loom_ids::CONNECT, loom_ids::DELETE] {
+            assert!(items.iter().find(|i| i.id == sid).unwrap().enabled, "{sid} enabled");
         }
+        assert_eq!(loom_node_action_for_id(loom_ids::CONNECT, &edgeless), Some(LoomNodeMenuAction::Connect));
+        assert_eq!(loom_node_action_for_id(loom_ids::DELETE, &edgeless), Some(LoomNodeMenuAction::Delete));
+
+        let disconnect = items.iter().find(|i| i.id == loom_ids::DISCONNECT).unwrap();
+        assert!(!disconnect.enabled, "no edges: disconnect disabled");
+        assert!(disconnect.disabled_reason.is_some(), "disconnect discloses why");
+        assert!(loom_node_action_for_id(loom_ids::DISCONNECT, &edgeless).is_none());
+
+        let linked = loom_state(false, false, true);
+        let items = loom_node_context_items(&linked);
+        assert!(items.iter().find(|i| i.id == loom_ids::DISCONNECT).unwrap().enabled);
+        assert_eq!(
+            loom_node_action_for_id(loom_ids::DISCONNECT, &linked),
+            Some(LoomNodeMenuAction::Disconnect)
+        );
     }
 
     fn canvas_state(kind: CanvasNodeKind, has_visual_edges: bool) -> CanvasNodeState {
@@ -31,11 +31,14 @@ pub mod quick_links;
 pub mod quiet_mode;
 pub mod quick_switcher;
 pub mod rails;
+pub mod rich_document_editor;
 pub mod search_rail;
 pub mod settings_dialog;
 pub mod split_layout;
 pub mod stash_shelf;
 pub mod tab_bar;
+pub mod terminal_emulator;
+pub mod terminal_pane;
 pub mod theme;
 pub mod top_menu_bar;
 pub mod workspace_settings;
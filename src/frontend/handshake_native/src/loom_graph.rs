@@ -15,8 +15,17 @@
 //!
 //! `pin`/`favorite`/`rename` route through the VERIFIED `PATCH /workspaces/:id/loom/blocks/:block_id`
 //! endpoint via [`crate::backend_client::LoomBlockClient`] (the same client MT-020's explorer rename
-//! uses), off the UI thread (HBR-QUIET). `connect`/`disconnect`/`delete` are V1 stubs (their menu
-//! items are disabled — no edge-edit / confirm-dialog endpoint wired yet).
+//! uses), off the UI thread (HBR-QUIET).
+//!
+//! ## Edge editing and delete
+//!
+//! Edges between rendered nodes are painted as arrows. Dragging one node onto another (or choosing
+//! `Connect to...` and then clicking the target) opens an inline relation-type picker; the chosen type
+//! becomes a [`LoomGraphEvent::Connect`] the host sends to `POST /workspaces/:id/loom/edges`.
+//! `Disconnect...` lists the node's edges and emits [`LoomGraphEvent::Disconnect`] for the one picked.
+//! `Delete Block...` emits [`LoomGraphEvent::Delete`], which the host must CONFIRM (and offer undo for)
+//! before anything is deleted. The transient connect / picker state lives in egui temp memory, so the
+//! surface itself stays a plain value rebuilt from the loaded graph each frame.
 //!
 //! ## Scope honesty
 //!
@@ -26,14 +35,15 @@
 
 use egui::accesskit;
 
+use crate::backend_client::{LoomEdgeInfo, LoomEdgeKind, LoomGraphSnapshot};
 use crate::context_menu::ContextMenu;
 use crate::context_menu_surfaces::{
     loom_node_action_for_id, loom_node_context_items, LoomNodeMenuAction, LoomNodeState,
 };
 
 /// The typed event a confirmed Loom-node menu produces, for the host to apply. Pin/favorite carry the
-/// NEW target value (computed from the fresh cached state). Stubs (connect/disconnect/delete) have no
-/// variant.
+/// NEW target value (computed from the fresh cached state). Connect/disconnect are emitted only once
+/// their picker resolves; delete is a REQUEST the host confirms.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoomGraphEvent {
     /// Open the block in a tab on the active pane.
@@ -50,6 +60,12 @@ pub enum LoomGraphEvent {
     CopyBlockId { block_id: String },
     /// Open / focus the LoomBlock panel pane for the block.
     RevealInPanel { block_id: String },
+    /// Create a user-authored edge of `kind` from `source_block_id` to `target_block_id`.
+    Connect { source_block_id: String, target_block_id: String, kind: LoomEdgeKind },
+    /// Remove `edge` (kept whole so the host can undo by re-creating it).
+    Disconnect { edge: LoomEdgeInfo },
+    /// Ask the host to confirm deleting the block; nothing is deleted until it does.
+    Delete { block_id: String, title: String },
 }
 
 /// One graph node rendered by the surface: its cached state + display title.
@@ -57,6 +73,8 @@ pub enum LoomGraphEvent {
 pub struct GraphNode {
     pub state: LoomNodeState,
     pub title: String,
+    /// The block is a tag hub, so `tag` / `sub_tag` edges may target it.
+    pub tag_hub: bool,
 }
 
 impl GraphNode {
@@ -64,8 +82,14 @@ impl GraphNode {
         Self {
             state,
             title: title.into(),
+            tag_hub: false,
         }
     }
+
+    pub fn with_tag_hub(mut self, tag_hub: bool) -> Self {
+        self.tag_hub = tag_hub;
+        self
+    }
 }
 
 /// Colors for the graph nodes, sourced from the active theme by the host.
@@ -76,35 +100,266 @@ pub struct LoomGraphColors {
     pub node_text: egui::Color32,
 }
 
-/// The native Loom-graph node surface: a set of nodes, each right-clickable for the MT-021 menu.
+/// The in-flight edge edit, kept in egui temp memory between frames.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum Interaction {
+    #[default]
+    Idle,
+    /// `Connect to...` was chosen: the next node clicked becomes the target.
+    Connecting { source: String },
+    /// Source and target are known; the relation-type picker is open.
+    PickRelation { source: String, target: String },
+    /// `Disconnect...` was chosen: the edge picker for `block_id` is open.
+    PickEdge { block_id: String },
+}
+
+/// The native Loom-graph surface: a set of nodes, each right-clickable for the MT-021 menu, and the
+/// edges between them.
 #[derive(Debug, Clone, Default)]
 pub struct LoomGraphSurface {
     pub nodes: Vec<GraphNode>,
+    pub edges: Vec<LoomEdgeInfo>,
 }
 
 impl LoomGraphSurface {
     pub fn new(nodes: Vec<GraphNode>) -> Self {
-        Self { nodes }
+        Self { nodes, edges: Vec::new() }
+    }
+
+    pub fn with_edges(mut self, edges: Vec<LoomEdgeInfo>) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// Build the surface from a loaded backend graph. `has_edges` is derived from the loaded edge list
+    /// (no per-right-click fetch).
+    pub fn from_snapshot(graph: &LoomGraphSnapshot) -> Self {
+        let nodes = graph
+            .nodes
+            .iter()
+            .map(|info| {
+                let state = LoomNodeState {
+                    block_id: info.block_id.clone(),
+                    pinned: info.pinned,
+                    favorite: info.favorite,
+                    has_edges: graph.edges.iter().any(|e| e.touches(&info.block_id)),
+                };
+                let title = info.title.clone().unwrap_or_else(|| "Untitled".to_owned());
+                GraphNode::new(state, title).with_tag_hub(info.tag_hub)
+            })
+            .collect();
+        Self::new(nodes).with_edges(graph.edges.clone())
     }
 
-    /// Render the nodes; return the typed event a confirmed right-click menu item produced this frame.
+    /// Render the nodes and edges; return the typed event a confirmed menu item or picker produced
+    /// this frame.
     pub fn show(&self, ui: &mut egui::Ui, colors: LoomGraphColors) -> Option<LoomGraphEvent> {
+        let state_id = ui.id().with("loom_graph_interaction");
+        let mut interaction: Interaction = ui.data_mut(|d| d.get_temp(state_id)).unwrap_or_default();
+        if interaction != Interaction::Idle && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            interaction = Interaction::Idle;
+        }
+
         let mut event = None;
         ui.label("Loom Graph");
+        if let Interaction::Connecting { source } = &interaction {
+            let mut cancel = false;
+            ui.horizontal(|ui| {
+                let title = self.title_of(source);
+                ui.label(format!("Connecting from “{title}”: click the target node"));
+                cancel = ui.button("Cancel").clicked();
+            });
+            if cancel {
+                interaction = Interaction::Idle;
+            }
+        }
+
+        let mut rendered = Vec::with_capacity(self.nodes.len());
         for node in &self.nodes {
-            if let Some(e) = self.node(ui, node, colors) {
-                event = Some(e);
+            let (resp, outcome) = self.node(ui, node, colors);
+            match outcome {
+                Some(NodeOutcome::Event(e)) => event = Some(e),
+                Some(NodeOutcome::Begin(next)) => interaction = next,
+                None => {}
             }
+            rendered.push((node.state.block_id.as_str(), resp));
         }
+        self.paint_edges(ui, &rendered, colors);
+
+        for (block_id, resp) in &rendered {
+            if let Interaction::Connecting { source } = &interaction {
+                if resp.clicked() && *block_id != source.as_str() {
+                    interaction = Interaction::PickRelation {
+                        source: source.clone(),
+                        target: (*block_id).to_owned(),
+                    };
+                }
+            }
+            // Drag-to-connect: a line follows the pointer; releasing over another node picks it.
+            if resp.dragged() {
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    let stroke = egui::Stroke::new(1.5, colors.node_text);
+                    ui.painter().line_segment([resp.rect.center(), pointer], stroke);
+                }
+            }
+            if resp.drag_stopped() {
+                let pointer = ui.ctx().pointer_interact_pos();
+                let target = rendered.iter().find(|(other, r)| {
+                    other != block_id && pointer.is_some_and(|p| r.rect.contains(p))
+                });
+                if let Some((target, _)) = target {
+                    interaction = Interaction::PickRelation {
+                        source: (*block_id).to_owned(),
+                        target: (*target).to_owned(),
+                    };
+                }
+            }
+        }
+
+        match &interaction {
+            Interaction::PickRelation { source, target } => {
+                let (picked, keep) = self.relation_picker(ui, source, target);
+                if let Some(e) = picked {
+                    event = Some(e);
+                }
+                if !keep {
+                    interaction = Interaction::Idle;
+                }
+            }
+            Interaction::PickEdge { block_id } => {
+                let (picked, keep) = self.edge_picker(ui, block_id);
+                if let Some(e) = picked {
+                    event = Some(e);
+                }
+                if !keep {
+                    interaction = Interaction::Idle;
+                }
+            }
+            Interaction::Idle | Interaction::Connecting { .. } => {}
+        }
+
+        ui.data_mut(|d| d.insert_temp(state_id, interaction));
         event
     }
 
+    /// The inline relation-type picker for a pending connect. Returns the chosen event and whether the
+    /// picker stays open. `tag` / `sub_tag` are offered only when the target is a tag hub (the backend
+    /// rejects them otherwise).
+    fn relation_picker(
+        &self,
+        ui: &mut egui::Ui,
+        source: &str,
+        target: &str,
+    ) -> (Option<LoomGraphEvent>, bool) {
+        let target_is_hub = self
+            .nodes
+            .iter()
+            .any(|n| n.state.block_id == target && n.tag_hub);
+        let mut picked = None;
+        let mut keep = true;
+        ui.group(|ui| {
+            ui.label(format!(
+                "Connect “{}” → “{}” as:",
+                self.title_of(source),
+                self.title_of(target)
+            ));
+            ui.horizontal_wrapped(|ui| {
+                for kind in LoomEdgeKind::USER_CREATABLE {
+                    if kind.requires_tag_hub() && !target_is_hub {
+                        continue;
+                    }
+                    let button = ui.button(kind.label());
+                    set_author_id(ui, button.id, &format!("loom_graph.relation.{}", kind.as_str()));
+                    if button.clicked() {
+                        picked = Some(LoomGraphEvent::Connect {
+                            source_block_id: source.to_owned(),
+                            target_block_id: target.to_owned(),
+                            kind,
+                        });
+                        keep = false;
+                    }
+                }
+                if ui.button("Cancel").clicked() {
+                    keep = false;
+                }
+            });
+        });
+        (picked, keep)
+    }
+
+    /// The inline edge picker for `Disconnect...`: one row per edge touching `block_id`.
+    fn edge_picker(&self, ui: &mut egui::Ui, block_id: &str) -> (Option<LoomGraphEvent>, bool) {
+        let mut picked = None;
+        let mut keep = true;
+        ui.group(|ui| {
+            ui.label(format!("Disconnect “{}” from:", self.title_of(block_id)));
+            for edge in self.edges.iter().filter(|e| e.touches(block_id)) {
+                let label = if edge.source_block_id == block_id {
+                    format!("{} → {}", edge.kind.label(), self.title_of(&edge.target_block_id))
+                } else {
+                    format!("{} ← {}", edge.kind.label(), self.title_of(&edge.source_block_id))
+                };
+                let button = ui.button(label);
+                set_author_id(
+                    ui,
+                    button.id,
+                    &format!("loom_graph.edge.{}", crate::project_tree::stable_part(&edge.edge_id)),
+                );
+                if button.clicked() {
+                    picked = Some(LoomGraphEvent::Disconnect { edge: edge.clone() });
+                    keep = false;
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                keep = false;
+            }
+        });
+        (picked, keep)
+    }
+
+    /// Paint each edge whose endpoints are both rendered as an arrow looping out to the right of the
+    /// node column.
+    fn paint_edges(
+        &self,
+        ui: &egui::Ui,
+        rendered: &[(&str, egui::Response)],
+        colors: LoomGraphColors,
+    ) {
+        let rect_of = |id: &str| rendered.iter().find(|(b, _)| *b == id).map(|(_, r)| r.rect);
+        let stroke = egui::Stroke::new(1.0, colors.node_text.gamma_multiply(0.6));
+        for edge in &self.edges {
+            let (Some(from), Some(to)) =
+                (rect_of(&edge.source_block_id), rect_of(&edge.target_block_id))
+            else {
+                continue;
+            };
+            let (a, b) = (from.right_center(), to.right_center());
+            let bulge = 16.0 + (b.y - a.y).abs() * 0.25;
+            let curve = egui::epaint::CubicBezierShape::from_points_stroke(
+                [a, a + egui::vec2(bulge, 0.0), b + egui::vec2(bulge, 0.0), b],
+                false,
+                egui::Color32::TRANSPARENT,
+                stroke,
+            );
+            ui.painter().add(curve);
+            ui.painter().line_segment([b, b + egui::vec2(6.0, -4.0)], stroke);
+            ui.painter().line_segment([b, b + egui::vec2(6.0, 4.0)], stroke);
+        }
+    }
+
+    fn title_of<'a>(&'a self, block_id: &'a str) -> &'a str {
+        self.nodes
+            .iter()
+            .find(|n| n.state.block_id == block_id)
+            .map_or(block_id, |n| n.title.as_str())
+    }
+
     fn node(
         &self,
         ui: &mut egui::Ui,
         node: &GraphNode,
         colors: LoomGraphColors,
-    ) -> Option<LoomGraphEvent> {
+    ) -> (egui::Response, Option<NodeOutcome>) {
         let author_id = loom_node_author_id(&node.state.block_id);
         let id = egui::Id::new(&author_id);
         let label = node.title.clone();
@@ -114,7 +369,7 @@ impl LoomGraphSurface {
                     egui::vec2(ui.available_width().min(220.0), 24.0),
                     egui::Sense::hover(),
                 );
-                let resp = ui.interact(rect, id, egui::Sense::click());
+                let resp = ui.interact(rect, id, egui::Sense::click_and_drag());
                 if ui.is_rect_visible(rect) {
                     let bg = if resp.hovered() { colors.node_hover_bg } else { colors.node_bg };
                     ui.painter().rect_filled(rect, 4.0, bg);
@@ -138,22 +393,24 @@ impl LoomGraphSurface {
             })
             .inner;
 
-        let mut event = None;
+        let mut outcome = None;
         let menu = ContextMenu::new("loom").items(loom_node_context_items(&node.state));
         if let Some(confirmed_id) = menu.show_on(&resp) {
             if let Some(action) = loom_node_action_for_id(confirmed_id, &node.state) {
-                event = Some(self.event_for(action, node));
+                outcome = Some(self.outcome_for(action, node));
             }
         }
         if resp.has_focus() && ui.input(|i| i.key_pressed(egui::Key::F10) && i.modifiers.shift) {
             crate::context_menu::request_open(ui.ctx(), resp.id, resp.rect.left_bottom());
         }
-        event
+        (resp, outcome)
     }
 
-    fn event_for(&self, action: LoomNodeMenuAction, node: &GraphNode) -> LoomGraphEvent {
+    /// What a confirmed menu action does: connect / disconnect open a picker on the surface; every
+    /// other action is an event for the host.
+    fn outcome_for(&self, action: LoomNodeMenuAction, node: &GraphNode) -> NodeOutcome {
         let block_id = node.state.block_id.clone();
-        match action {
+        let event = match action {
             LoomNodeMenuAction::Open => LoomGraphEvent::Open { block_id },
             LoomNodeMenuAction::OpenToSide => LoomGraphEvent::OpenToSide { block_id },
             LoomNodeMenuAction::Rename => LoomGraphEvent::Rename {
@@ -168,10 +425,36 @@ impl LoomGraphSurface {
             }
             LoomNodeMenuAction::CopyBlockId => LoomGraphEvent::CopyBlockId { block_id },
             LoomNodeMenuAction::RevealInPanel => LoomGraphEvent::RevealInPanel { block_id },
-        }
+            LoomNodeMenuAction::Delete => LoomGraphEvent::Delete {
+                block_id,
+                title: node.title.clone(),
+            },
+            LoomNodeMenuAction::Connect => {
+                return NodeOutcome::Begin(Interaction::Connecting { source: block_id });
+            }
+            LoomNodeMenuAction::Disconnect => {
+                return NodeOutcome::Begin(Interaction::PickEdge { block_id });
+            }
+        };
+        NodeOutcome::Event(event)
     }
 }
 
+/// The result of one node's menu this frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeOutcome {
+    Event(LoomGraphEvent),
+    Begin(Interaction),
+}
+
+/// Attach a stable author_id to a widget's live AccessKit node.
+fn set_author_id(ui: &egui::Ui, id: egui::Id, author_id: &str) {
+    let author_id = author_id.to_owned();
+    ui.ctx().accesskit_node_builder(id, move |node| {
+        node.set_author_id(author_id);
+    });
+}
+
 /// Stable AccessKit author_id for a graph node: `loom_node_{block_id}` (slug-safe).
 pub fn loom_node_author_id(block_id: &str) -> String {
     format!("loom_node_{}", crate::project_tree::stable_part(block_id))
@@ -197,8 +480,8 @@ mod tests {
     fn pin_event_sends_flipped_target() {
         let surface = LoomGraphSurface::new(vec![node(false)]);
         assert_eq!(
-            surface.event_for(LoomNodeMenuAction::TogglePin { target: true }, &surface.nodes[0]),
-            LoomGraphEvent::SetPinned { block_id: "blk-1".to_owned(), target: true },
+            surface.outcome_for(LoomNodeMenuAction::TogglePin { target: true }, &surface.nodes[0]),
+            NodeOutcome::Event(LoomGraphEvent::SetPinned { block_id: "blk-1".to_owned(), target: true }),
         );
     }
 
@@ -206,14 +489,55 @@ mod tests {
     fn rename_event_carries_current_title() {
         let surface = LoomGraphSurface::new(vec![node(false)]);
         assert_eq!(
-            surface.event_for(LoomNodeMenuAction::Rename, &surface.nodes[0]),
-            LoomGraphEvent::Rename {
+            surface.outcome_for(LoomNodeMenuAction::Rename, &surface.nodes[0]),
+            NodeOutcome::Event(LoomGraphEvent::Rename {
                 block_id: "blk-1".to_owned(),
                 current_title: "My Block".to_owned(),
-            },
+            }),
         );
     }
 
+    #[test]
+    fn connect_and_disconnect_open_pickers_not_events() {
+        let surface = LoomGraphSurface::new(vec![node(false)]);
+        assert_eq!(
+            surface.outcome_for(LoomNodeMenuAction::Connect, &surface.nodes[0]),
+            NodeOutcome::Begin(Interaction::Connecting { source: "blk-1".to_owned() }),
+        );
+        assert_eq!(
+            surface.outcome_for(LoomNodeMenuAction::Disconnect, &surface.nodes[0]),
+            NodeOutcome::Begin(Interaction::PickEdge { block_id: "blk-1".to_owned() }),
+        );
+    }
+
+    #[test]
+    fn from_snapshot_derives_has_edges_and_tag_hub() {
+        use crate::backend_client::LoomGraphNodeInfo;
+        let info = |id: &str, tag_hub: bool| LoomGraphNodeInfo {
+            block_id: id.to_owned(),
+            title: None,
+            pinned: false,
+            favorite: true,
+            tag_hub,
+        };
+        let graph = LoomGraphSnapshot {
+            nodes: vec![info("a", false), info("t", true), info("lonely", false)],
+            edges: vec![LoomEdgeInfo {
+                edge_id: "e1".to_owned(),
+                source_block_id: "a".to_owned(),
+                target_block_id: "t".to_owned(),
+                kind: LoomEdgeKind::Tag,
+                source_anchor: None,
+            }],
+            truncated: false,
+        };
+        let surface = LoomGraphSurface::from_snapshot(&graph);
+        let has_edges: Vec<bool> = surface.nodes.iter().map(|n| n.state.has_edges).collect();
+        assert_eq!(has_edges, vec![true, true, false]);
+        assert!(surface.nodes[1].tag_hub && surface.nodes[0].state.favorite);
+        assert_eq!(surface.nodes[2].title, "Untitled");
+    }
+
     #[test]
     fn author_id_slug_safe() {
         let id = loom_node_author_id("blk 1/x");
@@ -423,6 +423,8 @@ pub fn pane_type_tab_id(pane_type: &PaneType) -> &'static str {
         PaneType::LoomWikiPage => "loom-wiki-page",
         PaneType::AtelierEditor => "atelier",
         PaneType::VisualDebugger => "visual-debugger",
+        // Terminal has no React PaneTabId either (the React terminal is an off-main-window drawer).
+        PaneType::Terminal => "terminal",
         // Placeholder has no React PaneTabId; it is not part of any MODULE_DEFINITIONS tab list.
         PaneType::Placeholder(_) => "placeholder",
     }
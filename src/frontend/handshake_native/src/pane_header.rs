@@ -208,6 +208,9 @@ pub struct PaneHeaderResponse {
     /// MT-020 header context menu: "Pop Out Pane" was chosen (the app pops the pane into its own OS
     /// window via `HandshakeApp::request_pop_out`).
     pub pop_out_requested: bool,
+    /// Header context menu: "Set Type: Terminal" was chosen (the app opens a terminal session tab in
+    /// this pane).
+    pub terminal_requested: bool,
 }
 
 /// Colors the pane header paints with, sourced from the active theme tokens by the caller so the
@@ -300,6 +303,10 @@ impl PaneHeader {
                         response.lock_toggled = true;
                         response.focus_requested = true;
                     }
+                    PaneHeaderMenuAction::OpenTerminal => {
+                        response.terminal_requested = true;
+                        response.focus_requested = true;
+                    }
                     PaneHeaderMenuAction::PopOut => {
                         response.pop_out_requested = true;
                         response.focus_requested = true;
@@ -53,6 +53,9 @@ pub enum PaneType {
     FontManager,
     FlightRecorder,
     VisualDebugger,
+    /// A native terminal session (`crate::terminal_pane`). Opened on demand from the pane menu or
+    /// RUN > Open Terminal; no React `PaneTabId` and in no MODULE tab list.
+    Terminal,
     /// A surface with no dedicated variant yet; the carried string is the display label.
     Placeholder(String),
 }
@@ -81,6 +84,7 @@ impl PaneType {
             PaneType::FontManager => "Font Manager".to_owned(),
             PaneType::FlightRecorder => "Flight Recorder".to_owned(),
             PaneType::VisualDebugger => "Visual Debugger".to_owned(),
+            PaneType::Terminal => "Terminal".to_owned(),
             PaneType::Placeholder(name) => name.clone(),
         }
     }
@@ -112,6 +116,7 @@ impl PaneType {
             PaneType::LoomWikiPage => "Wiki Page",
             PaneType::AtelierEditor => "Atelier",
             PaneType::VisualDebugger => "Visual Debugger",
+            PaneType::Terminal => "Terminal",
             PaneType::Placeholder(name) => name.as_str(),
         }
     }
@@ -652,6 +657,7 @@ mod tests {
             PaneType::FontManager,
             PaneType::FlightRecorder,
             PaneType::VisualDebugger,
+            PaneType::Terminal,
             PaneType::Placeholder("unknown-surface".to_owned()),
         ];
         let ctx = egui::Context::default();
@@ -0,0 +1,1933 @@
+//! Native rich-document editor pane: the editor surface the MT-016 command catalog shipped disabled.
+//!
+//! ## What this is
+//!
+//! A native peer of the React `RichDocumentView` (`app/src/components/RichDocumentView.tsx`). A
+//! Workspace tab whose content id is a RichDocument authority id (`KRD-…`, the same test React's
+//! `DocumentView.isRichDocumentId` applies) renders this editor instead of the placeholder body. It
+//! loads the document's ProseMirror block tree, edits paragraphs, headings, bullet/numbered/task lists,
+//! block quotes, code blocks, and tables in place, and saves the result back as a new document version.
+//!
+//! ## Backend (verified)
+//!
+//! - load: `GET /knowledge/documents/:id` → `{ document, tree, code_nodes }`; the editor reads
+//!   `document.{rich_document_id, title, doc_version, content_json, crdt_document_id}`.
+//! - save: `PUT /knowledge/documents/:id/save` with `{ expected_version, content_json,
+//!   crdt_document_id }`. `expected_version` is the optimistic-concurrency guard (a stale version is a
+//!   409, surfaced as "reload before saving"); `crdt_document_id` is the document's bound CRDT id, or
+//!   the canonical `KRD-` → `KCRDT-` id the backend `canonical_rich_document_crdt_document_id` accepts
+//!   when none is bound yet (React `defaultRichDocumentCrdtDocumentId`).
+//!
+//! Both calls go through [`crate::backend_client::RichDocumentClient`] off the UI thread; this module is
+//! the buffer + widget only and never touches the network (HBR-QUIET).
+//!
+//! ## Editing model
+//!
+//! Each top-level block is kept as its raw authority JSON plus an editable [`BlockBody`]. A block the
+//! operator never touched is written back VERBATIM (ids, attrs, and any marks the native editor does not
+//! model survive a save). Inline content is edited as a small markdown-like source: `**bold**`,
+//! `*italic*`, `` `code` ``, `[text](href)`, `[[kind:value|label]]` typed wikilinks (`hsLink`), and a
+//! newline for a `hardBreak`; `\` escapes a literal marker. A block whose content falls outside that
+//! vocabulary (images, embeds, nested lists, merged table cells, unknown marks) renders read-only and
+//! round-trips untouched — the editor never lossily rewrites content it cannot represent.
+//!
+//! ## Scope honesty
+//!
+//! Saves are whole-document versioned saves bound to the CRDT document id; live multi-writer CRDT merge
+//! stays with the React collaboration surface. Legacy (non-`KRD-`) documents keep the placeholder body,
+//! and a popped-out pane window still renders its pane-type body rather than the editor.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use crate::backend_client::RichDocumentCell;
+use crate::pane_registry::{PaneId, PaneType};
+use crate::split_layout::TabBodyHost;
+use crate::tab_bar::TabState;
+
+/// Author-id prefix for every node the editor emits (`rich-document-editor.save`, `….block.3`, …).
+pub const EDITOR_AUTHOR_ID_PREFIX: &str = "rich-document-editor";
+/// Stable out-of-process address of the Save button.
+pub const EDITOR_SAVE_AUTHOR_ID: &str = "rich-document-editor.save";
+/// Stable out-of-process address of the Reload button.
+pub const EDITOR_RELOAD_AUTHOR_ID: &str = "rich-document-editor.reload";
+
+/// The wikilink prefixes the editor turns into typed `hsLink` nodes, with their backend ref kind. A
+/// port of `WP009_WIKILINK_KINDS` (`app/src/lib/editor/extension_inventory.ts`).
+const WIKILINK_KINDS: &[(&str, &str)] = &[
+    ("note", "note"),
+    ("file", "file"),
+    ("folder", "folder"),
+    ("project", "project"),
+    ("spec", "spec"),
+    ("wp", "wp"),
+    ("symbol", "symbol"),
+    ("album", "album"),
+    ("video", "video"),
+    ("HS_images", "images"),
+    ("HS_slideshow", "slideshow"),
+];
+
+/// Characters that are inline markers in the editable source and are backslash-escaped in text.
+const ESCAPED_CHARS: &[char] = &['\\', '*', '`', '[', ']'];
+
+/// Whether `document_id` addresses a RichDocument authority (`KRD-…`) the native editor can open.
+/// Mirrors React `DocumentView.isRichDocumentId`.
+pub fn is_rich_document_id(document_id: &str) -> bool {
+    document_id.starts_with("KRD-")
+}
+
+/// The RichDocument id a tab hosts: a Workspace tab whose content id is a `KRD-` authority id.
+pub fn rich_document_tab_id(tab: &TabState) -> Option<&str> {
+    if tab.pane_type != PaneType::Workspace {
+        return None;
+    }
+    tab.content_id.as_deref().filter(|id| is_rich_document_id(id))
+}
+
+/// The canonical CRDT document id for a RichDocument with no bound CRDT id yet: `KRD-x` → `KCRDT-x`,
+/// otherwise a `KCRDT-` prefix. Mirrors the backend `canonical_rich_document_crdt_document_id`.
+pub fn canonical_crdt_document_id(document_id: &str) -> String {
+    document_id
+        .strip_prefix("KRD-")
+        .map(|suffix| format!("KCRDT-{suffix}"))
+        .unwrap_or_else(|| format!("KCRDT-{document_id}"))
+}
+
+// ── Inline codec ─────────────────────────────────────────────────────────────────────────────────────
+
+/// The inline marks the editable source can express.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Marks {
+    bold: bool,
+    italic: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+impl Marks {
+    fn bold_mut(&mut self) -> &mut bool {
+        &mut self.bold
+    }
+
+    fn italic_mut(&mut self) -> &mut bool {
+        &mut self.italic
+    }
+
+    fn code_mut(&mut self) -> &mut bool {
+        &mut self.code
+    }
+}
+
+/// Read a text node's marks, or `None` when it carries a mark the source cannot express.
+fn text_marks(node: &Value) -> Option<Marks> {
+    let mut marks = Marks::default();
+    if let Some(list) = node.get("marks") {
+        for mark in list.as_array()? {
+            match mark.get("type").and_then(Value::as_str)? {
+                "bold" => marks.bold = true,
+                "italic" => marks.italic = true,
+                "code" => marks.code = true,
+                "link" => {
+                    let href = mark.get("attrs").and_then(|a| a.get("href")).and_then(Value::as_str)?;
+                    marks.link = Some(href.to_owned());
+                }
+                _ => return None,
+            }
+        }
+    }
+    Some(marks)
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        if ESCAPED_CHARS.contains(&ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+}
+
+fn push_href(out: &mut String, href: &str) {
+    for ch in href.chars() {
+        if ch == '\\' || ch == ')' {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+}
+
+/// Emit the marker tokens that move the source from `from` marks to `to` marks. Bold/italic/code are
+/// toggles, so their order never matters; a link closes before and opens after the toggles.
+fn push_transition(out: &mut String, from: &Marks, to: &Marks) {
+    if from.link.is_some() && from.link != to.link {
+        out.push_str("](");
+        push_href(out, from.link.as_deref().unwrap_or_default());
+        out.push(')');
+    }
+    if from.bold != to.bold {
+        out.push_str("**");
+    }
+    if from.italic != to.italic {
+        out.push('*');
+    }
+    if from.code != to.code {
+        out.push('`');
+    }
+    if to.link.is_some() && from.link != to.link {
+        out.push('[');
+    }
+}
+
+/// Render an `hsLink` node as `[[prefix:value]]` / `[[prefix:value|label]]`, or `None` for a ref kind
+/// with no wikilink prefix (an `unknown` link) or a value the syntax cannot carry.
+fn wikilink_source(node: &Value) -> Option<String> {
+    let attrs = node.get("attrs")?;
+    let kind = attrs.get("refKind").and_then(Value::as_str)?;
+    let value = attrs.get("refValue").and_then(Value::as_str)?;
+    let label = attrs.get("label").and_then(Value::as_str).unwrap_or(value);
+    let prefix = WIKILINK_KINDS.iter().find(|(_, k)| *k == kind).map(|(p, _)| *p)?;
+    let invalid = |s: &str| s.contains(']') || s.contains('\n');
+    if value.trim().is_empty() || invalid(value) || value.contains('|') || invalid(label) {
+        return None;
+    }
+    if label.trim().is_empty() || label == value {
+        Some(format!("[[{prefix}:{value}]]"))
+    } else {
+        Some(format!("[[{prefix}:{value}|{label}]]"))
+    }
+}
+
+/// Serialize ProseMirror inline nodes into the editable source. `allow_breaks` admits `hardBreak` as a
+/// newline (paragraphs, headings); list items, quote lines, and table cells are single-line. `None`
+/// means the content is outside the editable vocabulary and the block must stay read-only.
+pub fn inline_to_source(content: &[Value], allow_breaks: bool) -> Option<String> {
+    let mut out = String::new();
+    let mut current = Marks::default();
+    for node in content {
+        match node.get("type").and_then(Value::as_str)? {
+            "text" => {
+                let marks = text_marks(node)?;
+                let text = node.get("text").and_then(Value::as_str)?;
+                if !allow_breaks && text.contains('\n') {
+                    return None;
+                }
+                push_transition(&mut out, &current, &marks);
+                push_escaped(&mut out, text);
+                current = marks;
+            }
+            "hardBreak" if allow_breaks => {
+                // A link never spans a line in the source; the toggles may.
+                let marks = Marks { link: None, ..current.clone() };
+                push_transition(&mut out, &current, &marks);
+                current = marks;
+                out.push('\n');
+            }
+            "hsLink" => {
+                if node.get("marks").and_then(Value::as_array).is_some_and(|m| !m.is_empty()) {
+                    return None;
+                }
+                let link = wikilink_source(node)?;
+                push_transition(&mut out, &current, &Marks::default());
+                current = Marks::default();
+                out.push_str(&link);
+            }
+            _ => return None,
+        }
+    }
+    push_transition(&mut out, &current, &Marks::default());
+    Some(out)
+}
+
+#[derive(Debug, Clone)]
+enum SegmentKind {
+    Text(String),
+    Break,
+    Wikilink(Value),
+}
+
+/// One parsed run. `marker` runs hold an opening token (`**`, `*`, `` ` ``, `[`) that becomes empty
+/// once its closing token is seen and stays literal when it never closes.
+#[derive(Debug, Clone)]
+struct Segment {
+    kind: SegmentKind,
+    marks: Marks,
+    marker: bool,
+}
+
+fn push_char(segments: &mut Vec<Segment>, marks: &Marks, ch: char) {
+    if let Some(last) = segments.last_mut() {
+        if !last.marker && last.marks == *marks {
+            if let SegmentKind::Text(text) = &mut last.kind {
+                text.push(ch);
+                return;
+            }
+        }
+    }
+    segments.push(Segment {
+        kind: SegmentKind::Text(ch.to_string()),
+        marks: marks.clone(),
+        marker: false,
+    });
+}
+
+fn push_marker(segments: &mut Vec<Segment>, marks: &Marks, token: &str) -> usize {
+    segments.push(Segment {
+        kind: SegmentKind::Text(token.to_owned()),
+        marks: marks.clone(),
+        marker: true,
+    });
+    segments.len() - 1
+}
+
+/// Parse `[[prefix:value]]` / `[[prefix:value|label]]` at the start of `chars` into an `hsLink` node and
+/// the number of chars consumed. Unknown prefixes are left as literal text.
+fn parse_wikilink(chars: &[char]) -> Option<(Value, usize)> {
+    let close = (2..chars.len().saturating_sub(1)).find(|&i| chars[i] == ']' && chars[i + 1] == ']')?;
+    let inner: String = chars[2..close].iter().collect();
+    if inner.contains('\n') {
+        return None;
+    }
+    let (prefix, rest) = inner.split_once(':')?;
+    let mut prefix_chars = prefix.chars();
+    let first = prefix_chars.next()?;
+    if !(first.is_ascii_alphabetic() || first == '_')
+        || !prefix_chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+    let (value, label) = match rest.split_once('|') {
+        Some((value, label)) => (value.trim(), label.trim()),
+        None => (rest.trim(), ""),
+    };
+    if value.is_empty() {
+        return None;
+    }
+    let (_, ref_kind) = WIKILINK_KINDS
+        .iter()
+        .find(|(p, _)| p.eq_ignore_ascii_case(prefix.trim()))?;
+    let label = if label.is_empty() { value } else { label };
+    let node = json!({
+        "type": "hsLink",
+        "attrs": { "refKind": ref_kind, "refValue": value, "label": label, "resolved": true },
+    });
+    Some((node, close + 2))
+}
+
+/// Parse a link href after `](`, up to the first unescaped `)`. Returns the href and chars consumed.
+fn parse_href(chars: &[char]) -> Option<(String, usize)> {
+    let mut href = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                href.push(chars[i + 1]);
+                i += 2;
+            }
+            ')' => return Some((href, i + 1)),
+            '\n' => return None,
+            ch => {
+                href.push(ch);
+                i += 1;
+            }
+        }
+    }
+    None
+}
+
+fn text_node(text: String, marks: &Marks) -> Value {
+    let mut node = Map::new();
+    node.insert("type".to_owned(), json!("text"));
+    node.insert("text".to_owned(), Value::String(text));
+    let mut list = Vec::new();
+    if marks.bold {
+        list.push(json!({ "type": "bold" }));
+    }
+    if marks.italic {
+        list.push(json!({ "type": "italic" }));
+    }
+    if marks.code {
+        list.push(json!({ "type": "code" }));
+    }
+    if let Some(href) = &marks.link {
+        list.push(json!({ "type": "link", "attrs": { "href": href } }));
+    }
+    if !list.is_empty() {
+        node.insert("marks".to_owned(), Value::Array(list));
+    }
+    Value::Object(node)
+}
+
+/// Parse the editable source back into ProseMirror inline nodes (the inverse of [`inline_to_source`]).
+/// An unclosed marker stays literal text, so a stray `*` never silently italicizes the rest of a line.
+pub fn source_to_inline(source: &str) -> Vec<Value> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut marks = Marks::default();
+    let mut open_bold: Option<usize> = None;
+    let mut open_italic: Option<usize> = None;
+    let mut open_code: Option<usize> = None;
+    let mut open_link: Option<usize> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' && chars.get(i + 1) == Some(&'[') {
+            if let Some((node, consumed)) = parse_wikilink(&chars[i..]) {
+                segments.push(Segment {
+                    kind: SegmentKind::Wikilink(node),
+                    marks: Marks::default(),
+                    marker: false,
+                });
+                i += consumed;
+                continue;
+            }
+        }
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                push_char(&mut segments, &marks, chars[i + 1]);
+                i += 2;
+            }
+            '\n' => {
+                segments.push(Segment { kind: SegmentKind::Break, marks: marks.clone(), marker: false });
+                i += 1;
+            }
+            '[' if open_link.is_none() => {
+                open_link = Some(push_marker(&mut segments, &marks, "["));
+                i += 1;
+            }
+            ']' if open_link.is_some() && chars.get(i + 1) == Some(&'(') => {
+                match parse_href(&chars[i + 2..]) {
+                    Some((href, consumed)) => {
+                        let start = open_link.take().unwrap_or_default();
+                        segments[start].kind = SegmentKind::Text(String::new());
+                        for seg in &mut segments[start + 1..] {
+                            seg.marks.link = Some(href.clone());
+                        }
+                        i += 2 + consumed;
+                    }
+                    None => {
+                        push_char(&mut segments, &marks, ']');
+                        i += 1;
+                    }
+                }
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                toggle(&mut segments, &mut marks, Marks::bold_mut, &mut open_bold, "**");
+                i += 2;
+            }
+            '*' => {
+                toggle(&mut segments, &mut marks, Marks::italic_mut, &mut open_italic, "*");
+                i += 1;
+            }
+            '`' => {
+                toggle(&mut segments, &mut marks, Marks::code_mut, &mut open_code, "`");
+                i += 1;
+            }
+            ch => {
+                push_char(&mut segments, &marks, ch);
+                i += 1;
+            }
+        }
+    }
+
+    // Unclosed toggles: the opening token stays literal and its mark is withdrawn from what followed.
+    let unclosed = [
+        (open_bold, Marks::bold_mut as MarkFlag),
+        (open_italic, Marks::italic_mut),
+        (open_code, Marks::code_mut),
+    ];
+    for (open, flag) in unclosed {
+        if let Some(start) = open {
+            for seg in &mut segments[start + 1..] {
+                *flag(&mut seg.marks) = false;
+            }
+        }
+    }
+
+    let mut nodes: Vec<Value> = Vec::new();
+    let mut pending: Option<(String, Marks)> = None;
+    for seg in segments {
+        match seg.kind {
+            SegmentKind::Text(text) => {
+                if text.is_empty() {
+                    continue;
+                }
+                match &mut pending {
+                    Some((buf, m)) if *m == seg.marks => buf.push_str(&text),
+                    _ => {
+                        if let Some((buf, m)) = pending.take() {
+                            nodes.push(text_node(buf, &m));
+                        }
+                        pending = Some((text, seg.marks));
+                    }
+                }
+            }
+            other => {
+                if let Some((buf, m)) = pending.take() {
+                    nodes.push(text_node(buf, &m));
+                }
+                match other {
+                    SegmentKind::Break => nodes.push(json!({ "type": "hardBreak" })),
+                    SegmentKind::Wikilink(node) => nodes.push(node),
+                    SegmentKind::Text(_) => {}
+                }
+            }
+        }
+    }
+    if let Some((buf, m)) = pending {
+        nodes.push(text_node(buf, &m));
+    }
+    nodes
+}
+
+/// Accessor for one toggle mark's flag on [`Marks`].
+type MarkFlag = fn(&mut Marks) -> &mut bool;
+
+/// Flip one toggle mark. Opening pushes a literal marker run (carrying the marks in force before the
+/// toggle); closing blanks the matching opener so it emits nothing.
+fn toggle(
+    segments: &mut Vec<Segment>,
+    marks: &mut Marks,
+    flag: MarkFlag,
+    open: &mut Option<usize>,
+    token: &str,
+) {
+    if *flag(marks) {
+        if let Some(start) = open.take() {
+            segments[start].kind = SegmentKind::Text(String::new());
+        }
+        *flag(marks) = false;
+    } else {
+        *open = Some(push_marker(segments, marks, token));
+        *flag(marks) = true;
+    }
+}
+
+// ── Block model ──────────────────────────────────────────────────────────────────────────────────────
+
+/// The three list node families the editor edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Bullet,
+    Ordered,
+    Task,
+}
+
+impl ListKind {
+    fn node_type(self) -> &'static str {
+        match self {
+            ListKind::Bullet => "bulletList",
+            ListKind::Ordered => "orderedList",
+            ListKind::Task => "taskList",
+        }
+    }
+
+    fn item_type(self) -> &'static str {
+        match self {
+            ListKind::Task => "taskItem",
+            ListKind::Bullet | ListKind::Ordered => "listItem",
+        }
+    }
+
+    fn from_node_type(node_type: &str) -> Option<Self> {
+        match node_type {
+            "bulletList" => Some(ListKind::Bullet),
+            "orderedList" => Some(ListKind::Ordered),
+            "taskList" => Some(ListKind::Task),
+            _ => None,
+        }
+    }
+
+    /// Short gutter marker shown beside the list's text field.
+    fn marker(self) -> &'static str {
+        match self {
+            ListKind::Bullet => "•",
+            ListKind::Ordered => "1.",
+            ListKind::Task => "☐",
+        }
+    }
+}
+
+/// The editable form of one top-level block. List/quote text holds one item/paragraph per line (task
+/// items carry a `[ ] ` / `[x] ` prefix); table cells and every text field use the inline source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockBody {
+    Paragraph { text: String },
+    Heading { level: u8, text: String },
+    List { kind: ListKind, text: String },
+    Quote { text: String },
+    Code { language: String, code: String },
+    Table { header: bool, rows: Vec<Vec<String>> },
+    /// Content outside the editable vocabulary; rendered read-only and saved verbatim.
+    Opaque,
+}
+
+fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(Value::as_str).unwrap_or_default()
+}
+
+fn node_content(node: &Value) -> &[Value] {
+    node.get("content").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+fn node_attr<'a>(node: &'a Value, key: &str) -> Option<&'a Value> {
+    node.get("attrs").and_then(|a| a.get(key))
+}
+
+/// A single paragraph's inline source, for list items / quote lines / table cells.
+fn single_paragraph_source(children: &[Value]) -> Option<String> {
+    match children {
+        [] => Some(String::new()),
+        [paragraph] if node_type(paragraph) == "paragraph" => {
+            inline_to_source(node_content(paragraph), false)
+        }
+        _ => None,
+    }
+}
+
+fn split_task_line(line: &str) -> (bool, &str) {
+    if let Some(rest) = line.strip_prefix("[x] ").or_else(|| line.strip_prefix("[X] ")) {
+        (true, rest)
+    } else if let Some(rest) = line.strip_prefix("[ ] ") {
+        (false, rest)
+    } else {
+        (false, line)
+    }
+}
+
+fn make_node(node_type: &str, attrs: Map<String, Value>, content: Vec<Value>) -> Value {
+    let mut node = Map::new();
+    node.insert("type".to_owned(), json!(node_type));
+    if !attrs.is_empty() {
+        node.insert("attrs".to_owned(), Value::Object(attrs));
+    }
+    if !content.is_empty() {
+        node.insert("content".to_owned(), Value::Array(content));
+    }
+    Value::Object(node)
+}
+
+fn paragraph_node(source: &str) -> Value {
+    make_node("paragraph", Map::new(), source_to_inline(source))
+}
+
+impl BlockBody {
+    /// Classify a top-level ProseMirror node into its editable form ([`BlockBody::Opaque`] when the
+    /// node or any of its content is outside the editable vocabulary).
+    pub fn from_node(node: &Value) -> BlockBody {
+        Self::try_from_node(node).unwrap_or(BlockBody::Opaque)
+    }
+
+    fn try_from_node(node: &Value) -> Option<BlockBody> {
+        let kind = node_type(node);
+        match kind {
+            "paragraph" => Some(BlockBody::Paragraph {
+                text: inline_to_source(node_content(node), true)?,
+            }),
+            "heading" => {
+                let level = node_attr(node, "level").and_then(Value::as_u64).unwrap_or(1);
+                if !(1..=6).contains(&level) {
+                    return None;
+                }
+                Some(BlockBody::Heading {
+                    level: level as u8,
+                    text: inline_to_source(node_content(node), true)?,
+                })
+            }
+            "bulletList" | "orderedList" | "taskList" => {
+                let list_kind = ListKind::from_node_type(kind)?;
+                let mut lines = Vec::new();
+                for item in node_content(node) {
+                    if node_type(item) != list_kind.item_type() {
+                        return None;
+                    }
+                    let text = single_paragraph_source(node_content(item))?;
+                    if list_kind == ListKind::Task {
+                        let checked = node_attr(item, "checked").and_then(Value::as_bool).unwrap_or(false);
+                        lines.push(format!("[{}] {text}", if checked { "x" } else { " " }));
+                    } else {
+                        lines.push(text);
+                    }
+                }
+                Some(BlockBody::List { kind: list_kind, text: lines.join("\n") })
+            }
+            "blockquote" => {
+                let lines = node_content(node)
+                    .iter()
+                    .map(|p| single_paragraph_source(std::slice::from_ref(p)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(BlockBody::Quote { text: lines.join("\n") })
+            }
+            "codeBlock" => {
+                let mut code = String::new();
+                for child in node_content(node) {
+                    if node_type(child) != "text" || child.get("marks").is_some() {
+                        return None;
+                    }
+                    code.push_str(child.get("text").and_then(Value::as_str)?);
+                }
+                let language = node_attr(node, "language").and_then(Value::as_str).unwrap_or_default();
+                Some(BlockBody::Code { language: language.to_owned(), code })
+            }
+            "monacoCodeBlock" => Some(BlockBody::Code {
+                language: node_attr(node, "language").and_then(Value::as_str).unwrap_or_default().to_owned(),
+                code: node_attr(node, "code").and_then(Value::as_str).unwrap_or_default().to_owned(),
+            }),
+            "table" => {
+                let mut rows = Vec::new();
+                let mut header = false;
+                for (r, row) in node_content(node).iter().enumerate() {
+                    if node_type(row) != "tableRow" {
+                        return None;
+                    }
+                    let mut cells = Vec::new();
+                    let mut all_header = true;
+                    for cell in node_content(row) {
+                        let cell_type = node_type(cell);
+                        if cell_type != "tableCell" && cell_type != "tableHeader" {
+                            return None;
+                        }
+                        let spans = ["colspan", "rowspan"]
+                            .iter()
+                            .any(|k| node_attr(cell, k).and_then(Value::as_u64).unwrap_or(1) != 1);
+                        if spans {
+                            return None;
+                        }
+                        all_header &= cell_type == "tableHeader";
+                        cells.push(single_paragraph_source(node_content(cell))?);
+                    }
+                    if r == 0 {
+                        header = all_header && !cells.is_empty();
+                    }
+                    rows.push(cells);
+                }
+                let width = rows.first().map(Vec::len).unwrap_or_default();
+                if width == 0 || rows.iter().any(|r| r.len() != width) {
+                    return None;
+                }
+                Some(BlockBody::Table { header, rows })
+            }
+            _ => None,
+        }
+    }
+
+    /// An empty `rows` x `cols` table with a header row (React `insertTable({ withHeaderRow: true })`).
+    pub fn empty_table(rows: usize, cols: usize) -> BlockBody {
+        BlockBody::Table { header: true, rows: vec![vec![String::new(); cols.max(1)]; rows.max(1)] }
+    }
+
+    /// The ProseMirror node type this body serializes to. Code keeps a `monacoCodeBlock` original's type.
+    fn target_type(&self, raw_type: &str) -> &'static str {
+        match self {
+            BlockBody::Paragraph { .. } => "paragraph",
+            BlockBody::Heading { .. } => "heading",
+            BlockBody::List { kind, .. } => kind.node_type(),
+            BlockBody::Quote { .. } => "blockquote",
+            BlockBody::Code { .. } if raw_type == "monacoCodeBlock" => "monacoCodeBlock",
+            BlockBody::Code { .. } => "codeBlock",
+            BlockBody::Table { .. } => "table",
+            BlockBody::Opaque => "",
+        }
+    }
+
+    /// Serialize back to a ProseMirror node. `raw` is the block's original JSON: its attrs (block id,
+    /// ordered-list start, …) are kept when the node type is unchanged; a converted block keeps only its
+    /// `block_id` so its stable identity survives the conversion.
+    pub fn to_node(&self, raw: &Value) -> Value {
+        if matches!(self, BlockBody::Opaque) {
+            return raw.clone();
+        }
+        let raw_type = node_type(raw);
+        let target = self.target_type(raw_type);
+        let mut attrs = raw.get("attrs").and_then(Value::as_object).cloned().unwrap_or_default();
+        if target != raw_type {
+            attrs.retain(|k, _| k == "block_id");
+        }
+        match self {
+            BlockBody::Paragraph { text } => make_node(target, attrs, source_to_inline(text)),
+            BlockBody::Heading { level, text } => {
+                attrs.insert("level".to_owned(), json!(level));
+                make_node(target, attrs, source_to_inline(text))
+            }
+            BlockBody::List { kind, text } => {
+                let mut items: Vec<Value> = text
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| list_item(*kind, line))
+                    .collect();
+                if items.is_empty() {
+                    items.push(list_item(*kind, ""));
+                }
+                make_node(target, attrs, items)
+            }
+            BlockBody::Quote { text } => {
+                let mut lines: Vec<Value> = text.lines().map(paragraph_node).collect();
+                if lines.is_empty() {
+                    lines.push(paragraph_node(""));
+                }
+                make_node(target, attrs, lines)
+            }
+            BlockBody::Code { language, code } => {
+                let language = if language.trim().is_empty() { Value::Null } else { json!(language.trim()) };
+                if target == "monacoCodeBlock" {
+                    attrs.insert("language".to_owned(), language);
+                    attrs.insert("code".to_owned(), json!(code));
+                    // The content hash described the old code; the React view recomputes it.
+                    attrs.remove("contentHash");
+                    make_node(target, attrs, Vec::new())
+                } else {
+                    attrs.insert("language".to_owned(), language);
+                    let content = if code.is_empty() { Vec::new() } else { vec![json!({ "type": "text", "text": code })] };
+                    make_node(target, attrs, content)
+                }
+            }
+            BlockBody::Table { header, rows } => {
+                let rows = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(r, cells)| {
+                        let cell_type = if *header && r == 0 { "tableHeader" } else { "tableCell" };
+                        let cells = cells
+                            .iter()
+                            .map(|cell| make_node(cell_type, Map::new(), vec![paragraph_node(cell)]))
+                            .collect();
+                        make_node("tableRow", Map::new(), cells)
+                    })
+                    .collect();
+                make_node(target, attrs, rows)
+            }
+            BlockBody::Opaque => raw.clone(),
+        }
+    }
+
+    /// The block's text as lines (list task prefixes stripped), for block-type conversions. `None` for
+    /// code, tables, and read-only blocks, which the conversion commands do not apply to.
+    fn text_lines(&self) -> Option<Vec<String>> {
+        match self {
+            BlockBody::Paragraph { text } | BlockBody::Heading { text, .. } | BlockBody::Quote { text } => {
+                Some(text.split('\n').map(ToOwned::to_owned).collect())
+            }
+            BlockBody::List { kind, text } => Some(
+                text.split('\n')
+                    .map(|line| if *kind == ListKind::Task { split_task_line(line).1 } else { line })
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            ),
+            BlockBody::Code { .. } | BlockBody::Table { .. } | BlockBody::Opaque => None,
+        }
+    }
+
+    /// Short kind label shown above the block.
+    fn kind_label(&self, raw: &Value) -> String {
+        match self {
+            BlockBody::Paragraph { .. } => "Paragraph".to_owned(),
+            BlockBody::Heading { level, .. } => format!("Heading {level}"),
+            BlockBody::List { kind: ListKind::Bullet, .. } => "Bullet list".to_owned(),
+            BlockBody::List { kind: ListKind::Ordered, .. } => "Numbered list".to_owned(),
+            BlockBody::List { kind: ListKind::Task, .. } => "Task list".to_owned(),
+            BlockBody::Quote { .. } => "Block quote".to_owned(),
+            BlockBody::Code { .. } => "Code".to_owned(),
+            BlockBody::Table { .. } => "Table".to_owned(),
+            BlockBody::Opaque => format!("{} (read-only)", node_type(raw)),
+        }
+    }
+}
+
+fn list_item(kind: ListKind, line: &str) -> Value {
+    if kind == ListKind::Task {
+        let (checked, text) = split_task_line(line);
+        let mut attrs = Map::new();
+        attrs.insert("checked".to_owned(), json!(checked));
+        make_node(kind.item_type(), attrs, vec![paragraph_node(text)])
+    } else {
+        make_node(kind.item_type(), Map::new(), vec![paragraph_node(line)])
+    }
+}
+
+/// Plain text of an arbitrary node (read-only preview of an opaque block).
+fn plain_text(node: &Value, out: &mut String) {
+    if let Some(text) = node.get("text").and_then(Value::as_str) {
+        out.push_str(text);
+    }
+    if let Some(label) = node_attr(node, "label").and_then(Value::as_str) {
+        out.push_str(label);
+    }
+    for child in node_content(node) {
+        plain_text(child, out);
+    }
+}
+
+/// One top-level block: its authority JSON plus the editable body. An untouched block saves `raw`.
+#[derive(Debug, Clone)]
+pub struct EditorBlock {
+    uid: u64,
+    raw: Value,
+    pub body: BlockBody,
+    edited: bool,
+}
+
+impl EditorBlock {
+    fn from_node(uid: u64, node: Value) -> Self {
+        let body = BlockBody::from_node(&node);
+        Self { uid, raw: node, body, edited: false }
+    }
+
+    fn new(uid: u64, body: BlockBody) -> Self {
+        Self { uid, raw: Value::Null, body, edited: true }
+    }
+
+    /// The block's node for the next save: the original JSON if untouched, else the re-serialized body.
+    pub fn to_node(&self) -> Value {
+        if self.edited {
+            self.body.to_node(&self.raw)
+        } else {
+            self.raw.clone()
+        }
+    }
+
+    /// Buffer-local id addressing this block in a [`FieldKey`] (stable for the editor's lifetime).
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    /// Whether the operator changed this block since the last load/save.
+    pub fn is_edited(&self) -> bool {
+        self.edited
+    }
+}
+
+/// The loaded document: identity, version guard, and the editable block list.
+#[derive(Debug, Clone)]
+pub struct RichDocumentBuffer {
+    pub document_id: String,
+    pub title: String,
+    pub doc_version: i64,
+    pub crdt_document_id: String,
+    /// The `doc` node minus its `content` (type + any doc-level attrs), preserved across saves.
+    doc_node: Map<String, Value>,
+    blocks: Vec<EditorBlock>,
+    structure_changed: bool,
+    next_uid: u64,
+}
+
+impl RichDocumentBuffer {
+    /// Build a buffer from the `GET /knowledge/documents/:id` response.
+    pub fn from_load_response(response: &Value) -> Result<Self, String> {
+        let document = response
+            .get("document")
+            .ok_or_else(|| "load response has no `document`".to_owned())?;
+        let document_id = document
+            .get("rich_document_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "document has no `rich_document_id`".to_owned())?
+            .to_owned();
+        let doc_version = document
+            .get("doc_version")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| "document has no `doc_version`".to_owned())?;
+        let title = document.get("title").and_then(Value::as_str).unwrap_or_default().to_owned();
+        let crdt_document_id = document
+            .get("crdt_document_id")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| canonical_crdt_document_id(&document_id));
+        let content = document
+            .get("content_json")
+            .and_then(Value::as_object)
+            .filter(|c| c.get("type").and_then(Value::as_str) == Some("doc"))
+            .ok_or_else(|| "document content is not a ProseMirror `doc` node".to_owned())?;
+        let mut doc_node = content.clone();
+        let nodes = match doc_node.remove("content") {
+            Some(Value::Array(nodes)) => nodes,
+            _ => Vec::new(),
+        };
+        let blocks: Vec<EditorBlock> = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| EditorBlock::from_node(i as u64, node))
+            .collect();
+        let next_uid = blocks.len() as u64;
+        Ok(Self {
+            document_id,
+            title,
+            doc_version,
+            crdt_document_id,
+            doc_node,
+            blocks,
+            structure_changed: false,
+            next_uid,
+        })
+    }
+
+    pub fn blocks(&self) -> &[EditorBlock] {
+        &self.blocks
+    }
+
+    /// Whether there are unsaved edits (a changed block, or blocks inserted/removed).
+    pub fn is_dirty(&self) -> bool {
+        self.structure_changed || self.blocks.iter().any(|b| b.edited)
+    }
+
+    /// The full `doc` node to save.
+    pub fn content_json(&self) -> Value {
+        let mut doc = self.doc_node.clone();
+        doc.insert(
+            "content".to_owned(),
+            Value::Array(self.blocks.iter().map(EditorBlock::to_node).collect()),
+        );
+        Value::Object(doc)
+    }
+
+    /// Apply a successful `PUT …/save` response: adopt the new version + CRDT binding and make the saved
+    /// nodes the new baseline.
+    pub fn mark_saved(&mut self, response: &Value) -> Result<(), String> {
+        let document = response
+            .get("document")
+            .ok_or_else(|| "save response has no `document`".to_owned())?;
+        self.doc_version = document
+            .get("doc_version")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| "saved document has no `doc_version`".to_owned())?;
+        if let Some(crdt) = document.get("crdt_document_id").and_then(Value::as_str) {
+            self.crdt_document_id = crdt.to_owned();
+        }
+        for block in &mut self.blocks {
+            block.raw = block.to_node();
+            block.edited = false;
+        }
+        self.structure_changed = false;
+        Ok(())
+    }
+
+    fn index_of(&self, uid: u64) -> Option<usize> {
+        self.blocks.iter().position(|b| b.uid == uid)
+    }
+
+    fn insert_block(&mut self, at: usize, body: BlockBody) -> u64 {
+        let uid = self.next_uid;
+        self.next_uid += 1;
+        self.blocks.insert(at.min(self.blocks.len()), EditorBlock::new(uid, body));
+        self.structure_changed = true;
+        uid
+    }
+
+    fn remove_block(&mut self, index: usize) {
+        if index < self.blocks.len() {
+            self.blocks.remove(index);
+            self.structure_changed = true;
+        }
+    }
+
+    /// The text of one editable field, marking its block edited.
+    fn field_text_mut(&mut self, field: FieldKey) -> Option<&mut String> {
+        let index = self.index_of(field.block_uid)?;
+        let block = &mut self.blocks[index];
+        let text = match (&mut block.body, field.cell) {
+            (BlockBody::Paragraph { text }, None)
+            | (BlockBody::Heading { text, .. }, None)
+            | (BlockBody::List { text, .. }, None)
+            | (BlockBody::Quote { text }, None) => text,
+            (BlockBody::Table { rows, .. }, Some((r, c))) => rows.get_mut(r)?.get_mut(c)?,
+            _ => return None,
+        };
+        block.edited = true;
+        Some(text)
+    }
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────────────────────────────
+
+/// Addresses one text field: a block's main field, or one table cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldKey {
+    pub block_uid: u64,
+    pub cell: Option<(usize, usize)>,
+}
+
+/// The last focused field and its selection, in char indices (`start <= end`). Editor commands run
+/// against this, so a palette command still targets the field the operator was typing in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldFocus {
+    pub field: FieldKey,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn byte_offset(text: &str, char_index: usize) -> usize {
+    text.char_indices().nth(char_index).map(|(b, _)| b).unwrap_or(text.len())
+}
+
+/// Count of consecutive `ch` immediately before byte `at`.
+fn run_before(text: &str, at: usize, ch: char) -> usize {
+    text[..at].chars().rev().take_while(|&c| c == ch).count()
+}
+
+/// Count of consecutive `ch` starting at byte `at`.
+fn run_after(text: &str, at: usize, ch: char) -> usize {
+    text[at..].chars().take_while(|&c| c == ch).count()
+}
+
+/// Whether the selection `[start, end)` is already wrapped in `token`. `*` and `**` share a character,
+/// so the surrounding star runs decide: an odd run carries italic, a run of two or more carries bold.
+fn is_wrapped(text: &str, start: usize, end: usize, token: &str) -> bool {
+    let ch = token.chars().next().unwrap_or('*');
+    let (before, after) = (run_before(text, start, ch), run_after(text, end, ch));
+    match token {
+        "*" => before % 2 == 1 && after % 2 == 1,
+        "**" => before >= 2 && after >= 2,
+        _ => before >= 1 && after >= 1,
+    }
+}
+
+/// Toggle `token` around the char range `[start, end)` of `text`. Returns the new selection (char
+/// indices) covering the same inner text. An empty selection inserts a token pair with the caret
+/// between them.
+pub fn toggle_wrap(text: &mut String, start: usize, end: usize, token: &str) -> (usize, usize) {
+    let (start, end) = (start.min(end), start.max(end));
+    let (b_start, b_end) = (byte_offset(text, start), byte_offset(text, end));
+    let tok_chars = token.chars().count();
+    if start != end && is_wrapped(text, b_start, b_end, token) {
+        text.replace_range(b_end..b_end + token.len(), "");
+        text.replace_range(b_start - token.len()..b_start, "");
+        return (start - tok_chars, end - tok_chars);
+    }
+    text.insert_str(b_end, token);
+    text.insert_str(b_start, token);
+    (start + tok_chars, end + tok_chars)
+}
+
+/// Replace the char range `[start, end)` with a wikilink to the selected text (or an empty `[[note:]]`
+/// template). Returns the caret position: after the link, or inside the empty template's value.
+pub fn insert_wikilink(text: &mut String, start: usize, end: usize) -> usize {
+    let (start, end) = (start.min(end), start.max(end));
+    let (b_start, b_end) = (byte_offset(text, start), byte_offset(text, end));
+    let selected = text[b_start..b_end].to_owned();
+    if selected.trim().is_empty() {
+        text.replace_range(b_start..b_end, "[[note:]]");
+        start + "[[note:".len()
+    } else {
+        let link = format!("[[note:{}]]", selected.trim());
+        let len = link.chars().count();
+        text.replace_range(b_start..b_end, &link);
+        start + len
+    }
+}
+
+/// The block-structure target of a conversion command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockTarget {
+    Heading(u8),
+    Quote,
+    List(ListKind),
+}
+
+/// Toggle `body` into `target` (React `toggleHeading`/`toggleBlockquote`/`toggle*List`): converting a
+/// block that already IS the target turns it back into a paragraph.
+fn convert_block(body: &BlockBody, target: BlockTarget) -> Option<BlockBody> {
+    let lines = body.text_lines()?;
+    let already = match (body, target) {
+        (BlockBody::Heading { level, .. }, BlockTarget::Heading(t)) => *level == t,
+        (BlockBody::Quote { .. }, BlockTarget::Quote) => true,
+        (BlockBody::List { kind, .. }, BlockTarget::List(t)) => *kind == t,
+        _ => false,
+    };
+    let joined = lines.join("\n");
+    Some(if already {
+        BlockBody::Paragraph { text: joined }
+    } else {
+        match target {
+            BlockTarget::Heading(level) => BlockBody::Heading { level, text: joined },
+            BlockTarget::Quote => BlockBody::Quote { text: joined },
+            BlockTarget::List(ListKind::Task) => BlockBody::List {
+                kind: ListKind::Task,
+                text: lines.iter().map(|l| format!("[ ] {l}")).collect::<Vec<_>>().join("\n"),
+            },
+            BlockTarget::List(kind) => BlockBody::List { kind, text: joined },
+        }
+    })
+}
+
+// ── Editor (state + widget) ──────────────────────────────────────────────────────────────────────────
+
+/// What the operator asked the host to do this frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RichDocumentEditorEvent {
+    /// Save the buffer as a new version (`PUT …/save`).
+    Save,
+    /// Discard local edits and re-load the authority (`GET …`).
+    Reload,
+}
+
+/// The payload the host sends for a save, produced by [`RichDocumentEditor::begin_save`].
+#[derive(Debug, Clone)]
+pub struct SaveRequest {
+    pub expected_version: i64,
+    pub content_json: Value,
+    pub crdt_document_id: String,
+    pub cell: RichDocumentCell,
+}
+
+/// One open RichDocument: buffer, load/save status, and the delivery cells the host's off-thread
+/// [`crate::backend_client::RichDocumentClient`] calls write into.
+#[derive(Debug)]
+pub struct RichDocumentEditor {
+    document_id: String,
+    buffer: Option<RichDocumentBuffer>,
+    loading: bool,
+    saving: bool,
+    error: Option<String>,
+    notice: Option<String>,
+    focus: Option<FieldFocus>,
+    pending_cursor: Option<FieldFocus>,
+    field_focused: bool,
+    load_cell: RichDocumentCell,
+    save_cell: RichDocumentCell,
+}
+
+impl RichDocumentEditor {
+    pub fn new(document_id: impl Into<String>) -> Self {
+        Self {
+            document_id: document_id.into(),
+            buffer: None,
+            loading: false,
+            saving: false,
+            error: None,
+            notice: None,
+            focus: None,
+            pending_cursor: None,
+            field_focused: false,
+            load_cell: Arc::new(Mutex::new(None)),
+            save_cell: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn document_id(&self) -> &str {
+        &self.document_id
+    }
+
+    pub fn buffer(&self) -> Option<&RichDocumentBuffer> {
+        self.buffer.as_ref()
+    }
+
+    /// Whether the document is loaded and can take editor commands.
+    pub fn is_ready(&self) -> bool {
+        self.buffer.is_some() && !self.loading
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.buffer.as_ref().is_some_and(RichDocumentBuffer::is_dirty)
+    }
+
+    /// Whether a load or save is in flight (the host keeps frames coming to drain the cell).
+    pub fn is_busy(&self) -> bool {
+        self.loading || self.saving
+    }
+
+    /// The last load/save failure, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Surface a failure that did not come through a delivery cell (e.g. no backend runtime).
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.error = Some(message.into());
+        self.loading = false;
+        self.saving = false;
+    }
+
+    /// The last command notice (why a command did not apply), if any.
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    pub fn focus(&self) -> Option<FieldFocus> {
+        self.focus
+    }
+
+    /// Whether one of the editor's text fields held keyboard focus in the last rendered frame.
+    pub fn has_field_focus(&self) -> bool {
+        self.field_focused
+    }
+
+    /// Point command targeting at `field` with the char selection `[start, end)` (what a click +
+    /// selection in the widget records; tests and the AccessKit path use it directly).
+    pub fn set_focus(&mut self, field: FieldKey, start: usize, end: usize) {
+        self.focus = Some(FieldFocus { field, start: start.min(end), end: start.max(end) });
+    }
+
+    /// Mark a load in flight and hand back the cell the spawned GET delivers into.
+    pub fn begin_load(&mut self) -> RichDocumentCell {
+        self.loading = true;
+        self.error = None;
+        self.load_cell.clone()
+    }
+
+    /// Snapshot the buffer for a save and mark it in flight; `None` when nothing is loaded or a save is
+    /// already running. The buffer is read-only until the save lands so the snapshot stays the version.
+    pub fn begin_save(&mut self) -> Option<SaveRequest> {
+        if self.saving || self.loading {
+            return None;
+        }
+        let buffer = self.buffer.as_ref()?;
+        let request = SaveRequest {
+            expected_version: buffer.doc_version,
+            content_json: buffer.content_json(),
+            crdt_document_id: buffer.crdt_document_id.clone(),
+            cell: self.save_cell.clone(),
+        };
+        self.saving = true;
+        self.error = None;
+        Some(request)
+    }
+
+    /// Drain delivered load/save results. Returns `true` when state changed (host requests a repaint).
+    pub fn drain_deliveries(&mut self) -> bool {
+        let mut changed = false;
+        if let Some(result) = self.load_cell.lock().ok().and_then(|mut s| s.take()) {
+            self.apply_load_result(result);
+            changed = true;
+        }
+        if let Some(result) = self.save_cell.lock().ok().and_then(|mut s| s.take()) {
+            self.apply_save_result(result);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Apply a load result: a fresh buffer replaces the old one (a reload discards local edits).
+    pub fn apply_load_result(&mut self, result: Result<Value, String>) {
+        self.loading = false;
+        match result.and_then(|v| RichDocumentBuffer::from_load_response(&v)) {
+            Ok(buffer) => {
+                self.buffer = Some(buffer);
+                self.error = None;
+                self.notice = None;
+                self.focus = None;
+                self.pending_cursor = None;
+            }
+            Err(message) => self.error = Some(format!("Load failed: {message}")),
+        }
+    }
+
+    /// Apply a save result: success adopts the new version; failure keeps the edits for a retry.
+    pub fn apply_save_result(&mut self, result: Result<Value, String>) {
+        self.saving = false;
+        let Some(buffer) = self.buffer.as_mut() else {
+            return;
+        };
+        match result.and_then(|v| buffer.mark_saved(&v)) {
+            Ok(()) => self.error = None,
+            Err(message) => self.error = Some(format!("Save failed: {message}")),
+        }
+    }
+
+    /// Run an `editor.*` command from the palette catalog against the focused field. `Err` explains why
+    /// it did not apply (no focus, wrong block kind); the message is also shown as the editor notice.
+    pub fn apply_command(&mut self, command_id: &str) -> Result<(), String> {
+        let result = self.run_command(command_id);
+        self.notice = result.as_ref().err().cloned();
+        result
+    }
+
+    fn run_command(&mut self, command_id: &str) -> Result<(), String> {
+        if self.saving {
+            return Err("A save is in progress.".to_owned());
+        }
+        if self.buffer.is_none() {
+            return Err("The document is not loaded.".to_owned());
+        }
+        match command_id {
+            "editor.format.bold" => self.wrap_focused("**"),
+            "editor.format.italic" => self.wrap_focused("*"),
+            "editor.format.code" => self.wrap_focused("`"),
+            "editor.block.h1" => self.convert_focused(BlockTarget::Heading(1)),
+            "editor.block.h2" => self.convert_focused(BlockTarget::Heading(2)),
+            "editor.block.h3" => self.convert_focused(BlockTarget::Heading(3)),
+            "editor.block.quote" => self.convert_focused(BlockTarget::Quote),
+            "editor.list.bullet" => self.convert_focused(BlockTarget::List(ListKind::Bullet)),
+            "editor.list.ordered" => self.convert_focused(BlockTarget::List(ListKind::Ordered)),
+            "editor.list.task" => self.convert_focused(BlockTarget::List(ListKind::Task)),
+            "editor.code.insert" => {
+                self.insert_after_focus(BlockBody::Code { language: String::new(), code: String::new() }, None)
+            }
+            "editor.table.insert" => self.insert_after_focus(BlockBody::empty_table(3, 3), Some((0, 0))),
+            "editor.link.wikilink" => {
+                let focus = self.focus.ok_or_else(no_focus)?;
+                let buffer = self.buffer.as_mut().ok_or_else(no_focus)?;
+                let text = buffer.field_text_mut(focus.field).ok_or_else(not_text)?;
+                let caret = insert_wikilink(text, focus.start, focus.end);
+                self.set_cursor(focus.field, caret, caret);
+                Ok(())
+            }
+            other => Err(format!("Unknown editor command '{other}'.")),
+        }
+    }
+
+    fn wrap_focused(&mut self, token: &str) -> Result<(), String> {
+        let focus = self.focus.ok_or_else(no_focus)?;
+        let buffer = self.buffer.as_mut().ok_or_else(no_focus)?;
+        let text = buffer.field_text_mut(focus.field).ok_or_else(not_text)?;
+        let (start, end) = toggle_wrap(text, focus.start, focus.end, token);
+        self.set_cursor(focus.field, start, end);
+        Ok(())
+    }
+
+    fn convert_focused(&mut self, target: BlockTarget) -> Result<(), String> {
+        let focus = self.focus.ok_or_else(no_focus)?;
+        if focus.field.cell.is_some() {
+            return Err("Block commands do not apply inside a table cell.".to_owned());
+        }
+        let buffer = self.buffer.as_mut().ok_or_else(no_focus)?;
+        let index = buffer.index_of(focus.field.block_uid).ok_or_else(no_focus)?;
+        let block = &mut buffer.blocks[index];
+        let converted = convert_block(&block.body, target)
+            .ok_or_else(|| "Block commands apply to text blocks only.".to_owned())?;
+        block.body = converted;
+        block.edited = true;
+        let len = buffer.field_text_mut(focus.field).map(|t| t.chars().count()).unwrap_or_default();
+        self.set_cursor(focus.field, len, len);
+        Ok(())
+    }
+
+    fn insert_after_focus(&mut self, body: BlockBody, cell: Option<(usize, usize)>) -> Result<(), String> {
+        let buffer = self.buffer.as_mut().ok_or_else(no_focus)?;
+        let at = self
+            .focus
+            .and_then(|f| buffer.index_of(f.field.block_uid))
+            .map(|i| i + 1)
+            .unwrap_or(buffer.blocks.len());
+        let uid = buffer.insert_block(at, body);
+        self.set_cursor(FieldKey { block_uid: uid, cell }, 0, 0);
+        Ok(())
+    }
+
+    /// Move command focus to `field` and ask the widget to place the caret/selection there next frame.
+    fn set_cursor(&mut self, field: FieldKey, start: usize, end: usize) {
+        self.set_focus(field, start, end);
+        self.pending_cursor = self.focus;
+    }
+
+    /// Render the editor into `ui`; returns the Save/Reload request the host dispatches off-thread.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<RichDocumentEditorEvent> {
+        let mut event = None;
+        self.field_focused = false;
+        let dirty = self.is_dirty();
+
+        ui.horizontal(|ui| {
+            let title = self
+                .buffer
+                .as_ref()
+                .map(|b| if b.title.is_empty() { b.document_id.clone() } else { b.title.clone() })
+                .unwrap_or_else(|| self.document_id.clone());
+            ui.strong(title);
+            if let Some(buffer) = &self.buffer {
+                ui.label(egui::RichText::new(format!("v{}", buffer.doc_version)).small().weak());
+            }
+            if dirty {
+                ui.label(egui::RichText::new("● unsaved").small());
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let reload_label = if dirty { "Reload (discard)" } else { "Reload" };
+                let reload = ui.add_enabled(!self.is_busy(), egui::Button::new(reload_label));
+                set_author_id(ui, reload.id, EDITOR_RELOAD_AUTHOR_ID);
+                if reload.clicked() {
+                    event = Some(RichDocumentEditorEvent::Reload);
+                }
+                let save_label = if self.saving { "Saving…" } else { "Save" };
+                let save = ui.add_enabled(dirty && !self.is_busy(), egui::Button::new(save_label));
+                set_author_id(ui, save.id, EDITOR_SAVE_AUTHOR_ID);
+                if save.clicked() {
+                    event = Some(RichDocumentEditorEvent::Save);
+                }
+            });
+        });
+        if self.loading {
+            ui.label(egui::RichText::new("Loading document…").weak());
+        }
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+        }
+        if let Some(notice) = &self.notice {
+            ui.label(egui::RichText::new(notice).small().weak());
+        }
+        ui.separator();
+
+        if self.buffer.is_none() {
+            return event;
+        }
+        let saving = self.saving;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.add_enabled_ui(!saving, |ui| self.show_blocks(ui));
+            });
+        event
+    }
+
+    fn show_blocks(&mut self, ui: &mut egui::Ui) {
+        let pending = self.pending_cursor.take();
+        let mut focused: Option<FieldFocus> = None;
+        let mut remove: Option<usize> = None;
+        let mut table_ops: Vec<(usize, TableOp)> = Vec::new();
+        let Some(buffer) = self.buffer.as_mut() else {
+            return;
+        };
+
+        for (index, block) in buffer.blocks.iter_mut().enumerate() {
+            let uid = block.uid;
+            ui.push_id(uid, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(block.body.kind_label(&block.raw)).small().weak());
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(index);
+                    }
+                });
+                let mut field = |ui: &mut egui::Ui, key: FieldKey, edit: egui::TextEdit<'_>| {
+                    // Ui-relative id: the same document open in two panes gets distinct fields.
+                    let id = ui.make_persistent_id(("rich-document-field", key.block_uid, key.cell));
+                    if let Some(p) = pending.filter(|p| p.field == key) {
+                        let mut state = egui::text_edit::TextEditState::load(ui.ctx(), id).unwrap_or_default();
+                        state.cursor.set_char_range(Some(egui::text::CCursorRange::two(
+                            egui::text::CCursor::new(p.start),
+                            egui::text::CCursor::new(p.end),
+                        )));
+                        state.store(ui.ctx(), id);
+                        ui.memory_mut(|m| m.request_focus(id));
+                    }
+                    let output = edit.id(id).desired_width(f32::INFINITY).show(ui);
+                    let author_id = match key.cell {
+                        Some((r, c)) => format!("{EDITOR_AUTHOR_ID_PREFIX}.block.{index}.cell.{r}.{c}"),
+                        None => format!("{EDITOR_AUTHOR_ID_PREFIX}.block.{index}"),
+                    };
+                    set_author_id(ui, output.response.id, &author_id);
+                    if output.response.has_focus() {
+                        let (start, end) = output
+                            .cursor_range
+                            .map(|r| (r.primary.index, r.secondary.index))
+                            .unwrap_or_default();
+                        focused = Some(FieldFocus { field: key, start: start.min(end), end: start.max(end) });
+                    }
+                    output.response.changed()
+                };
+                let main = FieldKey { block_uid: uid, cell: None };
+                let changed = match &mut block.body {
+                    BlockBody::Paragraph { text } => {
+                        field(ui, main, egui::TextEdit::multiline(text).desired_rows(1))
+                    }
+                    BlockBody::Heading { level, text } => {
+                        let size = match *level { 1 => 24.0, 2 => 20.0, 3 => 17.0, _ => 15.0 };
+                        field(
+                            ui,
+                            main,
+                            egui::TextEdit::multiline(text)
+                                .desired_rows(1)
+                                .font(egui::FontId::proportional(size)),
+                        )
+                    }
+                    BlockBody::List { kind, text } => {
+                        let marker = kind.marker();
+                        ui.horizontal_top(|ui| {
+                            ui.label(marker);
+                            field(ui, main, egui::TextEdit::multiline(text).desired_rows(1))
+                        })
+                        .inner
+                    }
+                    BlockBody::Quote { text } => ui
+                        .horizontal_top(|ui| {
+                            ui.label("▌");
+                            field(ui, main, egui::TextEdit::multiline(text).desired_rows(1))
+                        })
+                        .inner,
+                    BlockBody::Code { language, code } => {
+                        let lang_changed = ui
+                            .horizontal(|ui| {
+                                ui.label(egui::RichText::new("language").small().weak());
+                                ui.add(egui::TextEdit::singleline(language).desired_width(120.0)).changed()
+                            })
+                            .inner;
+                        let edit = egui::TextEdit::multiline(code).code_editor().desired_rows(3);
+                        field(ui, main, edit) || lang_changed
+                    }
+                    BlockBody::Table { rows, .. } => {
+                        let mut changed = false;
+                        egui::Grid::new(("table", uid)).striped(true).show(ui, |ui| {
+                            for (r, row) in rows.iter_mut().enumerate() {
+                                for (c, cell) in row.iter_mut().enumerate() {
+                                    let key = FieldKey { block_uid: uid, cell: Some((r, c)) };
+                                    ui.push_id((r, c), |ui| {
+                                        ui.set_min_width(80.0);
+                                        changed |= field(ui, key, egui::TextEdit::singleline(cell));
+                                    });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            for (label, op) in [
+                                ("+ Row", TableOp::AddRow),
+                                ("+ Column", TableOp::AddColumn),
+                                ("− Row", TableOp::RemoveRow),
+                                ("− Column", TableOp::RemoveColumn),
+                            ] {
+                                if ui.small_button(label).clicked() {
+                                    table_ops.push((index, op));
+                                }
+                            }
+                        });
+                        changed
+                    }
+                    BlockBody::Opaque => {
+                        let mut preview = String::new();
+                        plain_text(&block.raw, &mut preview);
+                        ui.label(egui::RichText::new(preview).weak());
+                        false
+                    }
+                };
+                if changed {
+                    block.edited = true;
+                }
+            });
+            ui.add_space(6.0);
+        }
+
+        if ui.button("+ Paragraph").clicked() {
+            let at = buffer.blocks.len();
+            let uid = buffer.insert_block(at, BlockBody::Paragraph { text: String::new() });
+            self.pending_cursor = Some(FieldFocus { field: FieldKey { block_uid: uid, cell: None }, start: 0, end: 0 });
+        }
+
+        for (index, op) in table_ops {
+            if let Some(block) = buffer.blocks.get_mut(index) {
+                if let BlockBody::Table { rows, .. } = &mut block.body {
+                    op.apply(rows);
+                    block.edited = true;
+                }
+            }
+        }
+        if let Some(index) = remove {
+            let removed_uid = buffer.blocks.get(index).map(|b| b.uid);
+            buffer.remove_block(index);
+            if self.focus.is_some_and(|f| Some(f.field.block_uid) == removed_uid) {
+                self.focus = None;
+            }
+        }
+        if let Some(focus) = focused {
+            self.focus = Some(focus);
+            self.field_focused = true;
+            self.notice = None;
+        }
+    }
+}
+
+fn no_focus() -> String {
+    "Place the cursor in a block first.".to_owned()
+}
+
+fn not_text() -> String {
+    "This command applies to text fields only.".to_owned()
+}
+
+/// A table structure edit from the row/column buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableOp {
+    AddRow,
+    AddColumn,
+    RemoveRow,
+    RemoveColumn,
+}
+
+impl TableOp {
+    fn apply(self, rows: &mut Vec<Vec<String>>) {
+        let width = rows.first().map(Vec::len).unwrap_or(1);
+        match self {
+            TableOp::AddRow => rows.push(vec![String::new(); width]),
+            TableOp::AddColumn => rows.iter_mut().for_each(|r| r.push(String::new())),
+            TableOp::RemoveRow if rows.len() > 1 => {
+                rows.pop();
+            }
+            TableOp::RemoveColumn if width > 1 => rows.iter_mut().for_each(|r| {
+                r.pop();
+            }),
+            TableOp::RemoveRow | TableOp::RemoveColumn => {}
+        }
+    }
+}
+
+/// The [`TabBodyHost`] the split layout renders `KRD-` Workspace tabs through: looks up the tab's
+/// editor and collects its Save/Reload requests for the host to dispatch after the frame.
+pub struct RichDocumentTabBodies<'a> {
+    pub editors: &'a mut HashMap<String, RichDocumentEditor>,
+    pub events: Vec<(String, RichDocumentEditorEvent)>,
+}
+
+impl<'a> RichDocumentTabBodies<'a> {
+    pub fn new(editors: &'a mut HashMap<String, RichDocumentEditor>) -> Self {
+        Self { editors, events: Vec::new() }
+    }
+}
+
+impl TabBodyHost for RichDocumentTabBodies<'_> {
+    fn claims(&self, tab: &TabState) -> bool {
+        rich_document_tab_id(tab).is_some_and(|id| self.editors.contains_key(id))
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, pane_id: &PaneId, tab: &TabState) -> bool {
+        let Some(editor) = rich_document_tab_id(tab).and_then(|id| self.editors.get_mut(id)) else {
+            return false;
+        };
+        let event = ui.push_id(pane_id.as_ref(), |ui| editor.show(ui)).inner;
+        if let Some(event) = event {
+            self.events.push((editor.document_id().to_owned(), event));
+        }
+        editor.has_field_focus()
+    }
+}
+
+/// Attach a stable author_id to a widget's live AccessKit node.
+fn set_author_id(ui: &egui::Ui, id: egui::Id, author_id: &str) {
+    let author_id = author_id.to_owned();
+    ui.ctx().accesskit_node_builder(id, move |node| {
+        node.set_author_id(author_id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_response(content: Value) -> Value {
+        json!({
+            "document": {
+                "rich_document_id": "KRD-abc",
+                "title": "Notes",
+                "doc_version": 4,
+                "content_json": content,
+                "crdt_document_id": null,
+            },
+            "tree": [],
+            "code_nodes": [],
+        })
+    }
+
+    fn doc(blocks: Vec<Value>) -> Value {
+        json!({ "type": "doc", "content": blocks })
+    }
+
+    fn buffer_with(blocks: Vec<Value>) -> RichDocumentBuffer {
+        RichDocumentBuffer::from_load_response(&load_response(doc(blocks))).unwrap()
+    }
+
+    fn main_field(buffer: &RichDocumentBuffer, index: usize) -> FieldKey {
+        FieldKey { block_uid: buffer.blocks()[index].uid, cell: None }
+    }
+
+    #[test]
+    fn canonical_crdt_id_follows_backend_rule() {
+        assert_eq!(canonical_crdt_document_id("KRD-abc"), "KCRDT-abc");
+        assert_eq!(canonical_crdt_document_id("doc-1"), "KCRDT-doc-1");
+        assert!(is_rich_document_id("KRD-abc"));
+        assert!(!is_rich_document_id("doc-1"));
+    }
+
+    #[test]
+    fn inline_marks_round_trip_through_source() {
+        let content = vec![
+            json!({ "type": "text", "text": "plain " }),
+            json!({ "type": "text", "text": "bold", "marks": [{ "type": "bold" }] }),
+            json!({ "type": "text", "text": " both", "marks": [{ "type": "bold" }, { "type": "italic" }] }),
+            json!({ "type": "text", "text": " a*b " }),
+            json!({ "type": "text", "text": "x()", "marks": [{ "type": "code" }] }),
+            json!({ "type": "text", "text": "site", "marks": [{ "type": "link", "attrs": { "href": "https://e.x/(1)" } }] }),
+            json!({ "type": "hardBreak" }),
+            json!({ "type": "hsLink", "attrs": { "refKind": "images", "refValue": "cats", "label": "Cats", "resolved": true } }),
+        ];
+        let source = inline_to_source(&content, true).unwrap();
+        assert_eq!(
+            source,
+            "plain **bold* both*** a\\*b `x()`[site](https://e.x/(1\\))\n[[HS_images:cats|Cats]]"
+        );
+        assert_eq!(source_to_inline(&source), content);
+    }
+
+    #[test]
+    fn unclosed_markers_stay_literal() {
+        assert_eq!(
+            source_to_inline("2 * 3 and [not a link"),
+            vec![json!({ "type": "text", "text": "2 * 3 and [not a link" })]
+        );
+        assert_eq!(
+            source_to_inline("**bold** then *"),
+            vec![
+                json!({ "type": "text", "text": "bold", "marks": [{ "type": "bold" }] }),
+                json!({ "type": "text", "text": " then *" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn wikilink_prefixes_are_typed_and_unknown_ones_literal() {
+        assert_eq!(
+            source_to_inline("see [[WP:WP-1]] and [[bogus:x]]"),
+            vec![
+                json!({ "type": "text", "text": "see " }),
+                json!({ "type": "hsLink", "attrs": { "refKind": "wp", "refValue": "WP-1", "label": "WP-1", "resolved": true } }),
+                json!({ "type": "text", "text": " and [[bogus:x]]" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_content_is_opaque_and_saved_verbatim() {
+        let image = json!({ "type": "image", "attrs": { "src": "a.png", "block_id": "b1" } });
+        let underline = json!({ "type": "paragraph", "content": [
+            { "type": "text", "text": "u", "marks": [{ "type": "underline" }] },
+        ] });
+        let buffer = buffer_with(vec![image.clone(), underline.clone()]);
+        assert_eq!(buffer.blocks()[0].body, BlockBody::Opaque);
+        assert_eq!(buffer.blocks()[1].body, BlockBody::Opaque);
+        assert_eq!(buffer.content_json(), doc(vec![image, underline]));
+        assert!(!buffer.is_dirty());
+    }
+
+    #[test]
+    fn untouched_blocks_keep_their_exact_json() {
+        let para = json!({ "type": "paragraph", "attrs": { "block_id": "p1", "textAlign": null }, "content": [
+            { "type": "text", "text": "hi", "marks": [{ "type": "bold" }, { "type": "italic" }] },
+        ] });
+        let buffer = buffer_with(vec![para.clone()]);
+        assert_eq!(buffer.content_json()["content"][0], para);
+    }
+
+    #[test]
+    fn lists_tables_and_code_parse_and_serialize() {
+        let task = json!({ "type": "taskList", "attrs": { "block_id": "t1" }, "content": [
+            { "type": "taskItem", "attrs": { "checked": true }, "content": [
+                { "type": "paragraph", "content": [{ "type": "text", "text": "done" }] } ] },
+            { "type": "taskItem", "attrs": { "checked": false }, "content": [
+                { "type": "paragraph", "content": [{ "type": "text", "text": "todo" }] } ] },
+        ] });
+        let table = BlockBody::empty_table(2, 2).to_node(&Value::Null);
+        let code = json!({ "type": "monacoCodeBlock", "attrs": { "block_id": "c1", "language": "rust", "code": "fn a() {}", "contentHash": "h" } });
+        let buffer = buffer_with(vec![task.clone(), table.clone(), code.clone()]);
+
+        let task_body = &buffer.blocks()[0].body;
+        assert_eq!(task_body, &BlockBody::List { kind: ListKind::Task, text: "[x] done\n[ ] todo".to_owned() });
+        assert_eq!(task_body.to_node(&task), task);
+
+        let table_body = &buffer.blocks()[1].body;
+        assert_eq!(table_body, &BlockBody::empty_table(2, 2));
+        assert_eq!(table["content"][0]["content"][0]["type"], "tableHeader");
+        assert_eq!(table["content"][1]["content"][0]["type"], "tableCell");
+
+        let code_body = BlockBody::Code { language: "rust".to_owned(), code: "fn b() {}".to_owned() };
+        let saved = code_body.to_node(&code);
+        assert_eq!(saved["type"], "monacoCodeBlock");
+        assert_eq!(saved["attrs"]["code"], "fn b() {}");
+        assert_eq!(saved["attrs"]["block_id"], "c1");
+        assert!(saved["attrs"].get("contentHash").is_none());
+    }
+
+    #[test]
+    fn toggle_wrap_wraps_and_unwraps() {
+        let mut text = "make this bold".to_owned();
+        let (s, e) = toggle_wrap(&mut text, 5, 9, "**");
+        assert_eq!(text, "make **this** bold");
+        assert_eq!((s, e), (7, 11));
+        let (s, e) = toggle_wrap(&mut text, s, e, "**");
+        assert_eq!(text, "make this bold");
+        assert_eq!((s, e), (5, 9));
+
+        // Italic inside bold adds a single star rather than unwrapping the bold.
+        let mut text = "**word**".to_owned();
+        toggle_wrap(&mut text, 2, 6, "*");
+        assert_eq!(text, "***word***");
+        toggle_wrap(&mut text, 3, 7, "*");
+        assert_eq!(text, "**word**");
+    }
+
+    #[test]
+    fn insert_wikilink_wraps_selection() {
+        let mut text = "see Plan".to_owned();
+        let caret = insert_wikilink(&mut text, 4, 8);
+        assert_eq!(text, "see [[note:Plan]]");
+        assert_eq!(caret, text.chars().count());
+        let mut empty = String::new();
+        assert_eq!(insert_wikilink(&mut empty, 0, 0), 7);
+        assert_eq!(empty, "[[note:]]");
+    }
+
+    #[test]
+    fn block_commands_convert_and_toggle_back() {
+        let para = json!({ "type": "paragraph", "attrs": { "block_id": "p1" }, "content": [{ "type": "text", "text": "Title" }] });
+        let mut editor = RichDocumentEditor::new("KRD-abc");
+        editor.apply_load_result(Ok(load_response(doc(vec![para]))));
+        assert!(editor.is_ready());
+        assert_eq!(editor.apply_command("editor.format.bold"), Err(no_focus()));
+
+        let field = main_field(editor.buffer().unwrap(), 0);
+        editor.set_focus(field, 0, 5);
+        editor.apply_command("editor.block.h2").unwrap();
+        assert_eq!(editor.buffer().unwrap().blocks()[0].body, BlockBody::Heading { level: 2, text: "Title".to_owned() });
+        let saved = editor.buffer().unwrap().content_json();
+        assert_eq!(saved["content"][0]["attrs"], json!({ "block_id": "p1", "level": 2 }));
+
+        editor.apply_command("editor.block.h2").unwrap();
+        assert_eq!(editor.buffer().unwrap().blocks()[0].body, BlockBody::Paragraph { text: "Title".to_owned() });
+
+        editor.apply_command("editor.list.task").unwrap();
+        assert_eq!(
+            editor.buffer().unwrap().blocks()[0].body,
+            BlockBody::List { kind: ListKind::Task, text: "[ ] Title".to_owned() }
+        );
+        assert!(editor.is_dirty());
+    }
+
+    #[test]
+    fn insert_commands_add_blocks_after_focus() {
+        let mut editor = RichDocumentEditor::new("KRD-abc");
+        editor.apply_load_result(Ok(load_response(doc(vec![
+            json!({ "type": "paragraph" }),
+            json!({ "type": "paragraph" }),
+        ]))));
+        let field = main_field(editor.buffer().unwrap(), 0);
+        editor.set_focus(field, 0, 0);
+        editor.apply_command("editor.table.insert").unwrap();
+        let blocks = editor.buffer().unwrap().blocks();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1].body, BlockBody::empty_table(3, 3));
+        assert_eq!(editor.focus().unwrap().field.cell, Some((0, 0)));
+
+        editor.apply_command("editor.code.insert").unwrap();
+        assert!(matches!(editor.buffer().unwrap().blocks()[2].body, BlockBody::Code { .. }));
+        assert_eq!(
+            editor.apply_command("editor.block.h1"),
+            Err("Block commands apply to text blocks only.".to_owned())
+        );
+    }
+
+    #[test]
+    fn save_snapshot_and_result_rebaseline_the_buffer() {
+        let mut editor = RichDocumentEditor::new("KRD-abc");
+        editor.apply_load_result(Ok(load_response(doc(vec![json!({ "type": "paragraph" })]))));
+        let field = main_field(editor.buffer().unwrap(), 0);
+        editor.set_focus(field, 0, 0);
+        editor.apply_command("editor.link.wikilink").unwrap();
+
+        let request = editor.begin_save().unwrap();
+        assert_eq!(request.expected_version, 4);
+        assert_eq!(request.crdt_document_id, "KCRDT-abc");
+        assert!(editor.begin_save().is_none(), "one save in flight at a time");
+        assert!(editor.apply_command("editor.format.bold").is_err(), "buffer is frozen while saving");
+
+        editor.apply_save_result(Err("version conflict".to_owned()));
+        assert!(editor.is_dirty(), "a failed save keeps the edits");
+        assert!(editor.error().unwrap().contains("version conflict"));
+
+        editor.begin_save().unwrap();
+        editor.apply_save_result(Ok(json!({ "document": { "doc_version": 5, "crdt_document_id": "KCRDT-abc" } })));
+        assert!(!editor.is_dirty());
+        assert_eq!(editor.buffer().unwrap().doc_version, 5);
+        assert_eq!(editor.error(), None);
+    }
+}
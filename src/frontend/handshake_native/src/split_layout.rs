@@ -45,7 +45,7 @@ use crate::pane_registry::{
 use crate::popout_window::PopOutPlaceholder;
 use crate::rails::{RailColors, RailDimensions, RailOrientation, SplitterRail};
 use crate::tab_bar::{
-    apply_drop, apply_drop_same_pane, TabBar, TabBarColors, TabBarState, TAB_BAR_HEIGHT,
+    apply_drop, apply_drop_same_pane, TabBar, TabBarColors, TabBarState, TabState, TAB_BAR_HEIGHT,
 };
 
 /// Minimum fraction either pane may shrink to. Ported verbatim from `app/src/App.tsx`
@@ -245,6 +245,34 @@ pub struct DividerColors {
     pub grab: egui::Color32,
 }
 
+/// A stateful per-TAB body renderer that takes precedence over the per-[`PaneType`] factory for the
+/// tabs it claims (e.g. the native rich-document editor for a `KRD-` Workspace tab). Factories render a
+/// pane type; a tab body host renders one tab's CONTENT, which needs app-owned state the stateless
+/// factory registry cannot hold.
+pub trait TabBodyHost {
+    /// Whether this host renders the body for `tab` (checked for the pane's active tab each frame).
+    fn claims(&self, tab: &TabState) -> bool;
+    /// Render the claimed tab's body into the pane's body `ui`. Returns `true` when one of its widgets
+    /// holds keyboard focus, which makes the pane active (so palette commands target it).
+    fn render(&mut self, ui: &mut egui::Ui, pane_id: &PaneId, tab: &TabState) -> bool;
+}
+
+/// Two hosts side by side (e.g. rich-document editors and terminals): the first that claims a tab
+/// renders it.
+impl<A: TabBodyHost, B: TabBodyHost> TabBodyHost for (A, B) {
+    fn claims(&self, tab: &TabState) -> bool {
+        self.0.claims(tab) || self.1.claims(tab)
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, pane_id: &PaneId, tab: &TabState) -> bool {
+        if self.0.claims(tab) {
+            self.0.render(ui, pane_id, tab)
+        } else {
+            self.1.render(ui, pane_id, tab)
+        }
+    }
+}
+
 /// Stateless renderer for the 2x2 split layout. Borrows the registry + factories at `show` time and
 /// owns nothing, so it is safe to construct per frame (mirrors [`crate::pane_registry::PaneHostWidget`]).
 pub struct SplitLayoutWidget;
@@ -296,6 +324,8 @@ impl SplitLayoutWidget {
     ///   [`crate::pane_registry::LockState`] after this call (single source of truth for pane state).
     /// - `pop_out_requests`: MT-020 sink for "Pop Out" chosen from a pane-tab or pane-header context
     ///   menu. The app pops each pane out into its own OS window (MT-008) after this call.
+    /// - `terminal_requests`: sink for "Set Type: Terminal" chosen from a pane-header context menu. The
+    ///   app opens a new terminal session tab in each pane after this call.
     /// - `is_popped_out`: predicate over a `PaneId` (wired by the app to
     ///   [`crate::popout_window::PopOutManager::is_popped_out`]). When it returns `true` for a pane,
     ///   the pane's grid rect renders a [`PopOutPlaceholder`] tile instead of the tab bar + body, so
@@ -304,6 +334,9 @@ impl SplitLayoutWidget {
     ///   thus an out-of-process AccessKit `Click` on it) pushes the pane id here; the app applies
     ///   each request to the [`crate::popout_window::PopOutManager`] after this call.
     /// - `placeholder_text`: the MT-003 theme text token the placeholder label/button paint with.
+    /// - `tab_bodies`: renders the body of an active tab it [claims](TabBodyHost::claims) in place of
+    ///   the pane-type factory. The pane's click sense is registered BEFORE a claimed body so the body's
+    ///   own widgets (text fields) receive the pointer.
     #[allow(clippy::too_many_arguments)]
     pub fn show<'f, F, A, P>(
         ui: &mut egui::Ui,
@@ -318,9 +351,11 @@ impl SplitLayoutWidget {
         header_colors: PaneHeaderColors,
         lock_requests: &mut Vec<PaneId>,
         pop_out_requests: &mut Vec<PaneId>,
+        terminal_requests: &mut Vec<PaneId>,
         is_popped_out: P,
         merge_requests: &mut Vec<PaneId>,
         placeholder_text: egui::Color32,
+        tab_bodies: &mut dyn TabBodyHost,
         mut factory_for: F,
         mut emit_accesskit: A,
     ) where
@@ -446,6 +481,9 @@ impl SplitLayoutWidget {
                 if header_resp.pop_out_requested {
                     pop_out_requests.push(pane_id.clone());
                 }
+                if header_resp.terminal_requested {
+                    terminal_requests.push(pane_id.clone());
+                }
                 if header_resp.focus_requested {
                     *active_pane = Some(pane_id.clone());
                 }
@@ -471,6 +509,11 @@ impl SplitLayoutWidget {
                 record,
                 egui_id: pane_egui_id,
             };
+            let claimed_tab = tab_bars
+                .get(&pane_id)
+                .and_then(|bar| bar.active())
+                .filter(|tab| tab_bodies.claims(tab))
+                .cloned();
 
             let mut child = ui.new_child(
                 egui::UiBuilder::new()
@@ -479,12 +522,22 @@ impl SplitLayoutWidget {
                     .layout(egui::Layout::top_down(egui::Align::Min)),
             );
             child.set_clip_rect(body_rect);
-            factory.render(&mut child, &render_ctx);
-            // Register the pane's stable id on its content rect so the live AccessKit node attaches
-            // under this scope, and capture a click so the operator can activate a pane.
-            let pane_response = child.interact(body_rect, pane_egui_id, egui::Sense::click());
-            if pane_response.clicked() {
-                *active_pane = Some(pane_id.clone());
+            if let Some(tab) = claimed_tab {
+                // Claimed tab body: the pane's click sense goes UNDER the body's widgets so clicks
+                // reach them; a focused body widget also activates the pane.
+                let pane_response = child.interact(body_rect, pane_egui_id, egui::Sense::click());
+                let body_focused = tab_bodies.render(&mut child, &pane_id, &tab);
+                if pane_response.clicked() || body_focused {
+                    *active_pane = Some(pane_id.clone());
+                }
+            } else {
+                factory.render(&mut child, &render_ctx);
+                // Register the pane's stable id on its content rect so the live AccessKit node
+                // attaches under this scope, and capture a click so the operator can activate a pane.
+                let pane_response = child.interact(body_rect, pane_egui_id, egui::Sense::click());
+                if pane_response.clicked() {
+                    *active_pane = Some(pane_id.clone());
+                }
             }
             emit_accesskit(ui.ctx(), pane_egui_id, pane_id.as_ref(), role, &label);
         }
@@ -0,0 +1,1080 @@
+//! VT/xterm escape-sequence parser + screen model for the native terminal pane.
+//!
+//! ## What this is
+//!
+//! The egui-free half of [`crate::terminal_pane`]: a byte-stream parser (the DEC/ANSI state machine —
+//! ground / escape / CSI / OSC / ignored DCS-APC-PM-SOS strings) driving a cell grid with a bounded
+//! scrollback. Output bytes from the backend PTY are fed in as they arrive; chunk boundaries may fall
+//! anywhere (mid-UTF-8, mid-CSI) because all parser state is carried between [`TerminalEmulator::feed`]
+//! calls. It is the native peer of the xterm.js instance inside React `TerminalView.tsx`.
+//!
+//! Supported (what shells, readline, `less`, `vim`, `htop`-class programs use): cursor movement and
+//! positioning, erase in line/display, insert/delete chars and lines, scroll regions (DECSTBM) with
+//! index/reverse-index, SGR (bold/dim/italic/underline/inverse, 16/256/truecolor), autowrap with
+//! soft-wrap tracking, insert mode, save/restore cursor, the alternate screen (47/1047/1049),
+//! application cursor keys, bracketed paste, cursor visibility, OSC 0/2 titles, and the DSR/DA queries
+//! (answered through [`TerminalEmulator::take_responses`]).
+//!
+//! ## Scope honesty
+//!
+//! Every char occupies one cell (no East-Asian wide-char layout); resize truncates/pads lines rather
+//! than reflowing them; G0/G1 charset designations are consumed but not applied (no DEC line-drawing
+//! translation); tab stops are fixed every 8 columns.
+
+use std::collections::VecDeque;
+
+/// Default number of lines kept above the screen.
+pub const DEFAULT_SCROLLBACK_LINES: usize = 5_000;
+
+/// Longest OSC payload retained; longer strings are truncated (titles only need a line).
+const OSC_MAX_BYTES: usize = 4_096;
+
+/// Fixed tab-stop width.
+const TAB_WIDTH: usize = 8;
+
+/// A cell color. `Default` is the theme's foreground/background; `Indexed` is the 256-color palette
+/// (0..=15 being the ANSI colors, bright included); `Rgb` is SGR 38/48;2 truecolor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TermColor {
+    #[default]
+    Default,
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The SGR attributes a cell was printed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellStyle {
+    pub fg: TermColor,
+    pub bg: TermColor,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub inverse: bool,
+}
+
+/// One character cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: CellStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { ch: ' ', style: CellStyle::default() }
+    }
+}
+
+/// One grid or scrollback line. `wrapped` marks a line the cursor auto-wrapped OUT of, so a copied
+/// selection joins it with the next line instead of inserting a newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub cells: Vec<Cell>,
+    pub wrapped: bool,
+}
+
+impl Line {
+    fn blank(cols: usize, fill: Cell) -> Self {
+        Self { cells: vec![fill; cols], wrapped: false }
+    }
+
+    fn fit(&mut self, cols: usize) {
+        self.cells.resize(cols, Cell::default());
+    }
+
+    /// The line's text with trailing blanks removed.
+    pub fn text(&self) -> String {
+        let s: String = self.cells.iter().map(|c| c.ch).collect();
+        s.trim_end().to_owned()
+    }
+}
+
+/// A position in the whole buffer (scrollback + screen). `line` is ABSOLUTE: it counts every line
+/// ever scrolled into the scrollback, so a point stays attached to its text while new output pushes
+/// old lines out of the bounded history (see [`TerminalEmulator::first_line_number`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GridPoint {
+    pub line: u64,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParserState {
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    Csi,
+    Osc,
+    OscEscape,
+    IgnoredString,
+    IgnoredStringEscape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Cursor {
+    row: usize,
+    col: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    cursor: Cursor,
+    style: CellStyle,
+    wrap_pending: bool,
+}
+
+/// The primary screen parked while the alternate screen is active.
+#[derive(Debug, Clone)]
+struct ParkedScreen {
+    grid: Vec<Line>,
+    cursor: Cursor,
+    style: CellStyle,
+}
+
+/// A VT/xterm terminal: parser state + screen grid + scrollback.
+#[derive(Debug, Clone)]
+pub struct TerminalEmulator {
+    rows: usize,
+    cols: usize,
+    grid: Vec<Line>,
+    scrollback: VecDeque<Line>,
+    scrollback_limit: usize,
+    /// Lines dropped off the front of the bounded scrollback (the absolute number of `scrollback[0]`).
+    dropped_lines: u64,
+    cursor: Cursor,
+    wrap_pending: bool,
+    style: CellStyle,
+    saved_cursor: Option<SavedCursor>,
+    scroll_top: usize,
+    scroll_bottom: usize,
+    autowrap: bool,
+    insert_mode: bool,
+    cursor_visible: bool,
+    app_cursor_keys: bool,
+    bracketed_paste: bool,
+    parked_primary: Option<ParkedScreen>,
+    title: Option<String>,
+    responses: Vec<u8>,
+    // Parser.
+    state: ParserState,
+    params: Vec<u16>,
+    param: Option<u32>,
+    private_marker: Option<u8>,
+    intermediates: Vec<u8>,
+    osc: Vec<u8>,
+    utf8: Vec<u8>,
+    utf8_need: usize,
+}
+
+impl TerminalEmulator {
+    /// An empty `rows` x `cols` terminal with [`DEFAULT_SCROLLBACK_LINES`] of history.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self::with_scrollback(rows, cols, DEFAULT_SCROLLBACK_LINES)
+    }
+
+    /// An empty terminal keeping at most `scrollback_limit` lines above the screen.
+    pub fn with_scrollback(rows: usize, cols: usize, scrollback_limit: usize) -> Self {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        Self {
+            rows,
+            cols,
+            grid: vec![Line::blank(cols, Cell::default()); rows],
+            scrollback: VecDeque::new(),
+            scrollback_limit,
+            dropped_lines: 0,
+            cursor: Cursor::default(),
+            wrap_pending: false,
+            style: CellStyle::default(),
+            saved_cursor: None,
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            autowrap: true,
+            insert_mode: false,
+            cursor_visible: true,
+            app_cursor_keys: false,
+            bracketed_paste: false,
+            parked_primary: None,
+            title: None,
+            responses: Vec::new(),
+            state: ParserState::Ground,
+            params: Vec::new(),
+            param: None,
+            private_marker: None,
+            intermediates: Vec::new(),
+            osc: Vec::new(),
+            utf8: Vec::new(),
+            utf8_need: 0,
+        }
+    }
+
+    // ── Read side ──────────────────────────────────────────────────────────────────────────────────
+
+    /// `(rows, cols)` of the screen.
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Cursor `(row, col)` on the screen (0-based).
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cursor.row, self.cursor.col)
+    }
+
+    /// Whether the program asked for the cursor to be shown (DECTCEM).
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Whether arrow keys must be sent in application mode (`ESC O A` rather than `ESC [ A`).
+    pub fn app_cursor_keys(&self) -> bool {
+        self.app_cursor_keys
+    }
+
+    /// Whether pasted text must be wrapped in `ESC [200~` / `ESC [201~`.
+    pub fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    /// Whether a full-screen program switched to the alternate screen (no scrollback there).
+    pub fn alternate_screen(&self) -> bool {
+        self.parked_primary.is_some()
+    }
+
+    /// The last OSC 0/2 window title.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Lines in the scrollback (above the screen).
+    pub fn scrollback_len(&self) -> usize {
+        self.scrollback.len()
+    }
+
+    /// Scrollback + screen lines.
+    pub fn line_count(&self) -> usize {
+        self.scrollback.len() + self.rows
+    }
+
+    /// Line `index` of scrollback + screen (0 = oldest retained scrollback line).
+    pub fn line(&self, index: usize) -> Option<&Line> {
+        if index < self.scrollback.len() {
+            self.scrollback.get(index)
+        } else {
+            self.grid.get(index - self.scrollback.len())
+        }
+    }
+
+    /// The absolute line number of `line(0)`; add a [`line`](Self::line) index to get a [`GridPoint`]
+    /// line.
+    pub fn first_line_number(&self) -> u64 {
+        self.dropped_lines
+    }
+
+    /// The screen text, one line per row, trailing blanks trimmed (tests + AccessKit value).
+    pub fn screen_text(&self) -> String {
+        self.grid.iter().map(Line::text).collect::<Vec<_>>().join("\n")
+    }
+
+    /// The text between two points, both cells inclusive, in either order. Soft-wrapped lines are
+    /// joined; hard line ends become `\n`; trailing blanks per line are dropped. Points that scrolled
+    /// out of the bounded scrollback are clamped to the oldest retained line.
+    pub fn selection_text(&self, a: GridPoint, b: GridPoint) -> String {
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        let first = self.dropped_lines;
+        let last = first + self.line_count() as u64 - 1;
+        if end.line < first {
+            return String::new();
+        }
+        let (start_line, start_col) =
+            if start.line < first { (first, 0) } else { (start.line.min(last), start.col) };
+        let end_line = end.line.min(last);
+        let mut out = String::new();
+        for abs in start_line..=end_line {
+            let Some(line) = self.line((abs - first) as usize) else {
+                break;
+            };
+            let from = if abs == start_line { start_col } else { 0 };
+            let to = if abs == end_line { end.col.saturating_add(1) } else { line.cells.len() };
+            let to = to.min(line.cells.len());
+            let segment: String =
+                line.cells.get(from.min(to)..to).unwrap_or(&[]).iter().map(|c| c.ch).collect();
+            if abs != end_line && line.wrapped {
+                out.push_str(&segment);
+            } else {
+                out.push_str(segment.trim_end());
+                if abs != end_line {
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Bytes the terminal must send back to the program (DSR cursor reports, DA replies). The host
+    /// writes them to stdin; a read-only session drops them.
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    // ── Write side ─────────────────────────────────────────────────────────────────────────────────
+
+    /// Resize the screen. Lines are truncated/padded (no reflow). Shrinking pushes the top rows into
+    /// the scrollback (primary screen) so the cursor line stays visible.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        if rows == self.rows && cols == self.cols {
+            return;
+        }
+        for line in &mut self.grid {
+            line.fit(cols);
+        }
+        if rows < self.rows {
+            // Drop blank rows below the cursor first, then scroll the top into history.
+            let mut excess = self.rows - rows;
+            while excess > 0 && self.grid.len() - 1 > self.cursor.row {
+                self.grid.pop();
+                excess -= 1;
+            }
+            for _ in 0..excess {
+                let top = self.grid.remove(0);
+                if self.parked_primary.is_none() {
+                    self.push_scrollback(top);
+                }
+                self.cursor.row = self.cursor.row.saturating_sub(1);
+            }
+        } else {
+            for _ in self.rows..rows {
+                self.grid.push(Line::blank(cols, Cell::default()));
+            }
+        }
+        if let Some(parked) = &mut self.parked_primary {
+            for line in &mut parked.grid {
+                line.fit(cols);
+            }
+            parked.grid.resize(rows, Line::blank(cols, Cell::default()));
+            parked.cursor.row = parked.cursor.row.min(rows - 1);
+            parked.cursor.col = parked.cursor.col.min(cols - 1);
+        }
+        self.rows = rows;
+        self.cols = cols;
+        self.scroll_top = 0;
+        self.scroll_bottom = rows - 1;
+        self.cursor.row = self.cursor.row.min(rows - 1);
+        self.cursor.col = self.cursor.col.min(cols - 1);
+        self.wrap_pending = false;
+    }
+
+    /// Feed program output. May be called with any chunking of the byte stream.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.advance(byte);
+        }
+    }
+
+    fn advance(&mut self, byte: u8) {
+        // CAN / SUB abort any sequence; ESC restarts one (except inside strings, handled below).
+        match self.state {
+            ParserState::Osc | ParserState::OscEscape => return self.advance_osc(byte),
+            ParserState::IgnoredString | ParserState::IgnoredStringEscape => {
+                return self.advance_ignored_string(byte)
+            }
+            _ => {}
+        }
+        if byte == 0x18 || byte == 0x1a {
+            self.state = ParserState::Ground;
+            return;
+        }
+        if byte == 0x1b {
+            self.flush_utf8();
+            self.state = ParserState::Escape;
+            self.intermediates.clear();
+            return;
+        }
+        match self.state {
+            ParserState::Ground => self.advance_ground(byte),
+            ParserState::Escape => self.advance_escape(byte),
+            ParserState::EscapeIntermediate => {
+                if byte < 0x20 {
+                    self.execute(byte);
+                } else if byte < 0x30 {
+                    self.intermediates.push(byte);
+                } else {
+                    // Charset designations (`ESC ( B`) and DECALN (`ESC # 8`) are consumed, not applied.
+                    self.state = ParserState::Ground;
+                }
+            }
+            ParserState::Csi => self.advance_csi(byte),
+            _ => unreachable!("string states handled above"),
+        }
+    }
+
+    fn advance_ground(&mut self, byte: u8) {
+        if byte < 0x80 {
+            self.flush_utf8();
+            if byte < 0x20 || byte == 0x7f {
+                self.execute(byte);
+            } else {
+                self.print(byte as char);
+            }
+            return;
+        }
+        if byte & 0xc0 == 0x80 && self.utf8_need > 0 {
+            self.utf8.push(byte);
+            if self.utf8.len() == self.utf8_need {
+                let ch = std::str::from_utf8(&self.utf8)
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+                self.utf8.clear();
+                self.utf8_need = 0;
+                self.print(ch);
+            }
+            return;
+        }
+        self.flush_utf8();
+        let need = match byte {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        if need == 0 {
+            self.print(char::REPLACEMENT_CHARACTER);
+        } else {
+            self.utf8.push(byte);
+            self.utf8_need = need;
+        }
+    }
+
+    /// An unfinished UTF-8 sequence interrupted by something else is one replacement char.
+    fn flush_utf8(&mut self) {
+        if self.utf8_need > 0 {
+            self.utf8.clear();
+            self.utf8_need = 0;
+            self.print(char::REPLACEMENT_CHARACTER);
+        }
+    }
+
+    fn advance_escape(&mut self, byte: u8) {
+        self.state = ParserState::Ground;
+        match byte {
+            b'[' => {
+                self.params.clear();
+                self.param = None;
+                self.private_marker = None;
+                self.intermediates.clear();
+                self.state = ParserState::Csi;
+            }
+            b']' => {
+                self.osc.clear();
+                self.state = ParserState::Osc;
+            }
+            b'P' | b'X' | b'^' | b'_' => self.state = ParserState::IgnoredString,
+            0x20..=0x2f => {
+                self.intermediates.push(byte);
+                self.state = ParserState::EscapeIntermediate;
+            }
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            b'D' => self.index(),
+            b'E' => {
+                self.cursor.col = 0;
+                self.index();
+            }
+            b'M' => self.reverse_index(),
+            b'c' => self.full_reset(),
+            _ if byte < 0x20 => {
+                self.execute(byte);
+                self.state = ParserState::Escape;
+            }
+            // `=` / `>` keypad modes, `H` tab set and unknown finals are ignored.
+            _ => {}
+        }
+    }
+
+    fn advance_csi(&mut self, byte: u8) {
+        match byte {
+            0x00..=0x1f => self.execute(byte),
+            b'0'..=b'9' => {
+                let digit = u32::from(byte - b'0');
+                self.param = Some(self.param.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            // A separator with no digits before it is an explicit default (0). Colon sub-parameters
+            // (`38:2:r:g:b`) are flattened like semicolons.
+            b';' | b':' => {
+                self.params.push(self.param.take().unwrap_or(0).min(u32::from(u16::MAX)) as u16);
+            }
+            b'<'..=b'?' if self.params.is_empty() && self.param.is_none() => {
+                self.private_marker = Some(byte);
+            }
+            0x20..=0x2f => self.intermediates.push(byte),
+            0x40..=0x7e => {
+                if let Some(p) = self.param.take() {
+                    self.params.push(p.min(u32::from(u16::MAX)) as u16);
+                }
+                self.state = ParserState::Ground;
+                let params = std::mem::take(&mut self.params);
+                // Intermediate-byte finals (`CSI ! p` soft reset, `CSI SP q` cursor style) are ignored.
+                if self.intermediates.is_empty() {
+                    self.csi_dispatch(byte, &params);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn advance_osc(&mut self, byte: u8) {
+        match (self.state, byte) {
+            (_, 0x07) => self.osc_dispatch(),
+            (ParserState::OscEscape, b'\\') => self.osc_dispatch(),
+            (ParserState::OscEscape, _) => {
+                // ESC not followed by `\`: the OSC is abandoned and the byte starts a new sequence.
+                self.state = ParserState::Escape;
+                self.advance_escape(byte);
+            }
+            (_, 0x1b) => self.state = ParserState::OscEscape,
+            (_, 0x18 | 0x1a) => self.state = ParserState::Ground,
+            _ => {
+                if self.osc.len() < OSC_MAX_BYTES {
+                    self.osc.push(byte);
+                }
+            }
+        }
+    }
+
+    fn advance_ignored_string(&mut self, byte: u8) {
+        self.state = match (self.state, byte) {
+            (_, 0x07 | 0x18 | 0x1a) => ParserState::Ground,
+            (ParserState::IgnoredStringEscape, b'\\') => ParserState::Ground,
+            (_, 0x1b) => ParserState::IgnoredStringEscape,
+            _ => ParserState::IgnoredString,
+        };
+    }
+
+    fn osc_dispatch(&mut self) {
+        self.state = ParserState::Ground;
+        let payload = String::from_utf8_lossy(&self.osc).into_owned();
+        if let Some((code, text)) = payload.split_once(';') {
+            if code == "0" || code == "2" {
+                self.title = Some(text.to_owned());
+            }
+        }
+        self.osc.clear();
+    }
+
+    /// C0 controls.
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x08 => {
+                self.wrap_pending = false;
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+            }
+            0x09 => {
+                let next = (self.cursor.col / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.cursor.col = next.min(self.cols - 1);
+            }
+            0x0a..=0x0c => self.index(),
+            0x0d => {
+                self.wrap_pending = false;
+                self.cursor.col = 0;
+            }
+            // BEL, NUL, SO/SI (charset shifts) and the rest are ignored.
+            _ => {}
+        }
+    }
+
+    // ── Screen operations ──────────────────────────────────────────────────────────────────────────
+
+    /// A blank cell carrying the pen's background (xterm back-color-erase).
+    fn blank(&self) -> Cell {
+        Cell { ch: ' ', style: CellStyle { bg: self.style.bg, ..CellStyle::default() } }
+    }
+
+    fn print(&mut self, ch: char) {
+        if self.wrap_pending {
+            self.grid[self.cursor.row].wrapped = true;
+            self.cursor.col = 0;
+            self.index();
+        }
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let cell = Cell { ch, style: self.style };
+        let line = &mut self.grid[row].cells;
+        if self.insert_mode {
+            line.insert(col, cell);
+            line.truncate(self.cols);
+        } else {
+            line[col] = cell;
+        }
+        if col + 1 >= self.cols {
+            self.wrap_pending = self.autowrap;
+        } else {
+            self.cursor.col += 1;
+        }
+    }
+
+    fn push_scrollback(&mut self, line: Line) {
+        if self.scrollback_limit == 0 {
+            self.dropped_lines += 1;
+            return;
+        }
+        self.scrollback.push_back(line);
+        while self.scrollback.len() > self.scrollback_limit {
+            self.scrollback.pop_front();
+            self.dropped_lines += 1;
+        }
+    }
+
+    /// Line feed: move down, scrolling the region when at its bottom margin.
+    fn index(&mut self) {
+        self.wrap_pending = false;
+        if self.cursor.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.row + 1 < self.rows {
+            self.cursor.row += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.wrap_pending = false;
+        if self.cursor.row == self.scroll_top {
+            self.scroll_down(1);
+        } else {
+            self.cursor.row = self.cursor.row.saturating_sub(1);
+        }
+    }
+
+    /// Scroll the region up `n` lines. Lines leaving the top of a full-height region on the primary
+    /// screen go to the scrollback.
+    fn scroll_up(&mut self, n: usize) {
+        let n = n.min(self.scroll_bottom - self.scroll_top + 1);
+        let blank = Line::blank(self.cols, self.blank());
+        for _ in 0..n {
+            let removed = self.grid.remove(self.scroll_top);
+            self.grid.insert(self.scroll_bottom, blank.clone());
+            if self.scroll_top == 0 && self.parked_primary.is_none() {
+                self.push_scrollback(removed);
+            }
+        }
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        let n = n.min(self.scroll_bottom - self.scroll_top + 1);
+        let blank = Line::blank(self.cols, self.blank());
+        for _ in 0..n {
+            self.grid.remove(self.scroll_bottom);
+            self.grid.insert(self.scroll_top, blank.clone());
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.wrap_pending = false;
+        self.cursor.row = row.min(self.rows - 1);
+        self.cursor.col = col.min(self.cols - 1);
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some(SavedCursor {
+            cursor: self.cursor,
+            style: self.style,
+            wrap_pending: self.wrap_pending,
+        });
+    }
+
+    fn restore_cursor(&mut self) {
+        if let Some(saved) = self.saved_cursor {
+            self.move_to(saved.cursor.row, saved.cursor.col);
+            self.style = saved.style;
+            self.wrap_pending = saved.wrap_pending;
+        } else {
+            self.move_to(0, 0);
+        }
+    }
+
+    fn full_reset(&mut self) {
+        let mut fresh = Self::with_scrollback(self.rows, self.cols, self.scrollback_limit);
+        fresh.scrollback = std::mem::take(&mut self.scrollback);
+        fresh.dropped_lines = self.dropped_lines;
+        *self = fresh;
+    }
+
+    fn erase_cells(&mut self, row: usize, from: usize, to: usize) {
+        let blank = self.blank();
+        let line = &mut self.grid[row];
+        let to = to.min(line.cells.len());
+        for cell in line.cells.get_mut(from.min(to)..to).unwrap_or(&mut []) {
+            *cell = blank;
+        }
+        line.wrapped = false;
+    }
+
+    fn enter_alternate_screen(&mut self, save_cursor: bool) {
+        if self.parked_primary.is_some() {
+            return;
+        }
+        if save_cursor {
+            self.save_cursor();
+        }
+        let blank_grid = vec![Line::blank(self.cols, Cell::default()); self.rows];
+        let primary = std::mem::replace(&mut self.grid, blank_grid);
+        self.parked_primary =
+            Some(ParkedScreen { grid: primary, cursor: self.cursor, style: self.style });
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows - 1;
+    }
+
+    fn leave_alternate_screen(&mut self, restore_cursor: bool) {
+        let Some(parked) = self.parked_primary.take() else {
+            return;
+        };
+        self.grid = parked.grid;
+        self.cursor = parked.cursor;
+        self.style = parked.style;
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows - 1;
+        self.wrap_pending = false;
+        if restore_cursor {
+            self.restore_cursor();
+        }
+    }
+
+    fn set_private_mode(&mut self, mode: u16, on: bool) {
+        match mode {
+            1 => self.app_cursor_keys = on,
+            7 => self.autowrap = on,
+            25 => self.cursor_visible = on,
+            47 | 1047 => {
+                if on {
+                    self.enter_alternate_screen(false);
+                } else {
+                    self.leave_alternate_screen(false);
+                }
+            }
+            1049 => {
+                if on {
+                    self.enter_alternate_screen(true);
+                } else {
+                    self.leave_alternate_screen(true);
+                }
+            }
+            2004 => self.bracketed_paste = on,
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, action: u8, params: &[u16]) {
+        let arg = |i: usize, default: u16| -> usize {
+            match params.get(i) {
+                Some(&0) | None => usize::from(default),
+                Some(&v) => usize::from(v),
+            }
+        };
+        let row = self.cursor.row;
+        let col = self.cursor.col;
+        match (self.private_marker, action) {
+            (Some(b'?'), b'h') => params.iter().for_each(|&m| self.set_private_mode(m, true)),
+            (Some(b'?'), b'l') => params.iter().for_each(|&m| self.set_private_mode(m, false)),
+            (Some(b'>'), b'c') => self.responses.extend_from_slice(b"\x1b[>0;0;0c"),
+            (Some(_), _) => {}
+            (None, b'@') => {
+                let blank = self.blank();
+                let n = arg(0, 1).min(self.cols - col);
+                let line = &mut self.grid[row].cells;
+                for _ in 0..n {
+                    line.insert(col, blank);
+                }
+                line.truncate(self.cols);
+            }
+            (None, b'A') => self.move_to(row.saturating_sub(arg(0, 1)).max(self.top_limit(row)), col),
+            (None, b'B') | (None, b'e') => {
+                self.move_to((row + arg(0, 1)).min(self.bottom_limit(row)), col)
+            }
+            (None, b'C') | (None, b'a') => self.move_to(row, col + arg(0, 1)),
+            (None, b'D') => self.move_to(row, col.saturating_sub(arg(0, 1))),
+            (None, b'E') => self.move_to((row + arg(0, 1)).min(self.bottom_limit(row)), 0),
+            (None, b'F') => self.move_to(row.saturating_sub(arg(0, 1)).max(self.top_limit(row)), 0),
+            (None, b'G') | (None, b'`') => self.move_to(row, arg(0, 1) - 1),
+            (None, b'H') | (None, b'f') => self.move_to(arg(0, 1) - 1, arg(1, 1) - 1),
+            (None, b'd') => self.move_to(arg(0, 1) - 1, col),
+            (None, b'J') => match params.first().copied().unwrap_or(0) {
+                0 => {
+                    self.erase_cells(row, col, self.cols);
+                    for r in row + 1..self.rows {
+                        self.erase_cells(r, 0, self.cols);
+                    }
+                }
+                1 => {
+                    for r in 0..row {
+                        self.erase_cells(r, 0, self.cols);
+                    }
+                    self.erase_cells(row, 0, col + 1);
+                }
+                2 => {
+                    for r in 0..self.rows {
+                        self.erase_cells(r, 0, self.cols);
+                    }
+                }
+                3 => {
+                    self.dropped_lines += self.scrollback.len() as u64;
+                    self.scrollback.clear();
+                }
+                _ => {}
+            },
+            (None, b'K') => match params.first().copied().unwrap_or(0) {
+                0 => self.erase_cells(row, col, self.cols),
+                1 => self.erase_cells(row, 0, col + 1),
+                2 => self.erase_cells(row, 0, self.cols),
+                _ => {}
+            },
+            (None, b'L') | (None, b'M') => {
+                if row < self.scroll_top || row > self.scroll_bottom {
+                    return;
+                }
+                let n = arg(0, 1).min(self.scroll_bottom - row + 1);
+                let blank = Line::blank(self.cols, self.blank());
+                for _ in 0..n {
+                    if action == b'L' {
+                        self.grid.remove(self.scroll_bottom);
+                        self.grid.insert(row, blank.clone());
+                    } else {
+                        self.grid.remove(row);
+                        self.grid.insert(self.scroll_bottom, blank.clone());
+                    }
+                }
+                self.move_to(row, 0);
+            }
+            (None, b'P') => {
+                let blank = self.blank();
+                let n = arg(0, 1).min(self.cols - col);
+                let line = &mut self.grid[row].cells;
+                line.drain(col..col + n);
+                line.resize(self.cols, blank);
+            }
+            (None, b'X') => self.erase_cells(row, col, col + arg(0, 1)),
+            (None, b'S') => self.scroll_up(arg(0, 1)),
+            (None, b'T') => self.scroll_down(arg(0, 1)),
+            (None, b'm') => self.select_graphic_rendition(params),
+            (None, b'r') => {
+                let top = arg(0, 1) - 1;
+                let bottom = arg(1, self.rows as u16).min(self.rows) - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.move_to(0, 0);
+                }
+            }
+            (None, b's') => self.save_cursor(),
+            (None, b'u') => self.restore_cursor(),
+            (None, b'h') | (None, b'l') if params.contains(&4) => self.insert_mode = action == b'h',
+            (None, b'n') => match params.first().copied().unwrap_or(0) {
+                5 => self.responses.extend_from_slice(b"\x1b[0n"),
+                6 => {
+                    let report = format!("\x1b[{};{}R", row + 1, col + 1);
+                    self.responses.extend_from_slice(report.as_bytes());
+                }
+                _ => {}
+            },
+            (None, b'c') => self.responses.extend_from_slice(b"\x1b[?1;2c"),
+            // Window ops (`t`), tab clears (`g`) and the rest are ignored.
+            _ => {}
+        }
+    }
+
+    /// Cursor-up stops at the top margin when starting inside the scroll region.
+    fn top_limit(&self, row: usize) -> usize {
+        if row >= self.scroll_top { self.scroll_top } else { 0 }
+    }
+
+    fn bottom_limit(&self, row: usize) -> usize {
+        if row <= self.scroll_bottom { self.scroll_bottom } else { self.rows - 1 }
+    }
+
+    fn select_graphic_rendition(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            self.style = CellStyle::default();
+            return;
+        }
+        let mut i = 0;
+        while i < params.len() {
+            let p = params[i];
+            match p {
+                0 => self.style = CellStyle::default(),
+                1 => self.style.bold = true,
+                2 => self.style.dim = true,
+                3 => self.style.italic = true,
+                4 => self.style.underline = true,
+                7 => self.style.inverse = true,
+                22 => {
+                    self.style.bold = false;
+                    self.style.dim = false;
+                }
+                23 => self.style.italic = false,
+                24 => self.style.underline = false,
+                27 => self.style.inverse = false,
+                30..=37 => self.style.fg = TermColor::Indexed((p - 30) as u8),
+                39 => self.style.fg = TermColor::Default,
+                40..=47 => self.style.bg = TermColor::Indexed((p - 40) as u8),
+                49 => self.style.bg = TermColor::Default,
+                90..=97 => self.style.fg = TermColor::Indexed((p - 90 + 8) as u8),
+                100..=107 => self.style.bg = TermColor::Indexed((p - 100 + 8) as u8),
+                38 | 48 => {
+                    let (color, used) = extended_color(&params[i + 1..]);
+                    if let Some(color) = color {
+                        if p == 38 {
+                            self.style.fg = color;
+                        } else {
+                            self.style.bg = color;
+                        }
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Parse the tail of an SGR 38/48: `5;n` (palette) or `2;r;g;b` (truecolor). Returns the color and
+/// how many params it consumed.
+fn extended_color(rest: &[u16]) -> (Option<TermColor>, usize) {
+    let byte = |v: u16| v.min(255) as u8;
+    match rest {
+        [5, n, ..] => (Some(TermColor::Indexed(byte(*n))), 2),
+        [2, r, g, b, ..] => (Some(TermColor::Rgb(byte(*r), byte(*g), byte(*b))), 4),
+        [] => (None, 0),
+        _ => (None, rest.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(rows: usize, cols: usize, bytes: &[u8]) -> TerminalEmulator {
+        let mut t = TerminalEmulator::new(rows, cols);
+        t.feed(bytes);
+        t
+    }
+
+    #[test]
+    fn prints_text_and_handles_crlf() {
+        let t = term(3, 10, b"hello\r\nworld");
+        assert_eq!(t.screen_text(), "hello\nworld\n");
+        assert_eq!(t.cursor(), (1, 5));
+    }
+
+    #[test]
+    fn sequences_split_across_chunks_are_reassembled() {
+        let mut t = TerminalEmulator::new(2, 10);
+        t.feed(b"a\x1b[");
+        t.feed(b"3");
+        t.feed(b"1mb\xc3");
+        t.feed(b"\xa9");
+        let line = t.line(0).unwrap();
+        assert_eq!(line.cells[1].ch, 'b');
+        assert_eq!(line.cells[1].style.fg, TermColor::Indexed(1));
+        assert_eq!(line.cells[2].ch, 'é');
+        assert_eq!(line.cells[0].style.fg, TermColor::Default);
+    }
+
+    #[test]
+    fn cursor_positioning_and_erase() {
+        let t = term(3, 6, b"abcdef\x1b[1;3H\x1b[K\x1b[3;2HX");
+        assert_eq!(t.screen_text(), "ab\n\n X");
+    }
+
+    #[test]
+    fn autowrap_marks_soft_wrapped_lines() {
+        let t = term(3, 4, b"abcdef");
+        assert_eq!(t.screen_text(), "abcd\nef\n");
+        assert!(t.line(0).unwrap().wrapped);
+        let all = t.selection_text(GridPoint { line: 0, col: 0 }, GridPoint { line: 1, col: 3 });
+        assert_eq!(all, "abcdef");
+    }
+
+    #[test]
+    fn output_past_the_bottom_scrolls_into_bounded_scrollback() {
+        let mut t = TerminalEmulator::with_scrollback(2, 5, 2);
+        t.feed(b"1\r\n2\r\n3\r\n4\r\n5");
+        assert_eq!(t.screen_text(), "4\n5");
+        assert_eq!(t.scrollback_len(), 2);
+        assert_eq!(t.line(0).unwrap().text(), "2");
+        assert_eq!(t.first_line_number(), 1);
+        // Absolute line 1 is "2": selection anchors survive the drop of line 0.
+        let s = t.selection_text(GridPoint { line: 1, col: 0 }, GridPoint { line: 4, col: 4 });
+        assert_eq!(s, "2\n3\n4\n5");
+    }
+
+    #[test]
+    fn scroll_region_keeps_lines_outside_it() {
+        let t = term(4, 5, b"top\r\n\x1b[2;3r\x1b[2;1Ha\r\nb\r\nc\x1b[4;1Hbot");
+        assert_eq!(t.screen_text(), "top\nb\nc\nbot");
+        assert_eq!(t.scrollback_len(), 0, "a scrolled region does not feed history");
+    }
+
+    #[test]
+    fn alternate_screen_restores_the_primary_screen() {
+        let mut t = term(2, 8, b"shell$ ");
+        t.feed(b"\x1b[?1049h\x1b[2J\x1b[Hfull");
+        assert!(t.alternate_screen());
+        assert_eq!(t.screen_text(), "full\n");
+        t.feed(b"\x1b[?1049l");
+        assert!(!t.alternate_screen());
+        assert_eq!(t.screen_text(), "shell$\n");
+        assert_eq!(t.cursor(), (0, 7));
+    }
+
+    #[test]
+    fn sgr_parses_256_and_truecolor() {
+        let t = term(1, 4, b"\x1b[1;38;5;202;48;2;1;2;3mX\x1b[0mY");
+        let cells = &t.line(0).unwrap().cells;
+        assert!(cells[0].style.bold);
+        assert_eq!(cells[0].style.fg, TermColor::Indexed(202));
+        assert_eq!(cells[0].style.bg, TermColor::Rgb(1, 2, 3));
+        assert_eq!(cells[1].style, CellStyle::default());
+    }
+
+    #[test]
+    fn osc_title_and_device_status_reports() {
+        let mut t = term(3, 10, b"\x1b]0;my shell\x07ab\x1b[6n\x1b]2;other\x1b\\");
+        assert_eq!(t.title(), Some("other"));
+        assert_eq!(t.take_responses(), b"\x1b[1;3R".to_vec());
+        assert!(t.take_responses().is_empty());
+        assert_eq!(t.screen_text(), "ab\n\n");
+    }
+
+    #[test]
+    fn insert_and_delete_characters_and_lines() {
+        let mut t = term(3, 6, b"abcdef\x1b[1;2H\x1b[2P");
+        assert_eq!(t.screen_text(), "adef\n\n");
+        t.feed(b"\x1b[1;2H\x1b[1@");
+        assert_eq!(t.screen_text(), "a def\n\n");
+        t.feed(b"\x1b[2;1Hx\x1b[3;1Hy\x1b[1;1H\x1b[1M");
+        assert_eq!(t.screen_text(), "x\ny\n");
+    }
+
+    #[test]
+    fn resize_shrinks_into_scrollback_and_pads() {
+        let mut t = term(3, 4, b"a\r\nb\r\nc");
+        t.resize(2, 6);
+        assert_eq!(t.screen_text(), "b\nc");
+        assert_eq!(t.line(0).unwrap().text(), "a");
+        assert_eq!(t.cursor(), (1, 1));
+        t.resize(4, 2);
+        assert_eq!(t.size(), (4, 2));
+        assert_eq!(t.screen_text(), "b\nc\n\n");
+    }
+
+    #[test]
+    fn modes_track_cursor_keys_paste_and_visibility() {
+        let mut t = term(2, 4, b"\x1b[?1h\x1b[?2004h\x1b[?25l");
+        assert!(t.app_cursor_keys());
+        assert!(t.bracketed_paste());
+        assert!(!t.cursor_visible());
+        t.feed(b"\x1b[?1l\x1b[?2004l\x1b[?25h");
+        assert!(!t.app_cursor_keys() && !t.bracketed_paste() && t.cursor_visible());
+    }
+}
@@ -0,0 +1,970 @@
+//! Native terminal pane: an egui terminal over the backend `TerminalRuntime` PTY sessions.
+//!
+//! ## What this is
+//!
+//! A native peer of the React `TerminalPanel` / `TerminalView` (`app/src/components/terminal/`). A
+//! [`PaneType::Terminal`] tab whose content id is a terminal session id renders a [`TerminalView`]: the
+//! session's output fed through the [`TerminalEmulator`] VT parser and painted as a monospace cell
+//! grid with scrollback (mouse wheel), drag selection + copy, keyboard input encoded the way xterm
+//! does (application cursor keys, bracketed paste), and the pane's size propagated to the PTY. A
+//! Terminal tab with no session yet renders a small picker (New / attach to a listed session).
+//!
+//! ## Backend (verified)
+//!
+//! All calls go through [`crate::backend_client::TerminalClient`] off the UI thread (`api::terminal`
+//! in `handshake_core`, a REST mirror of the Tauri `kernel_terminal_*` commands): the view polls
+//! `GET /terminal/sessions/:id/output?since=N` every [`OUTPUT_POLL_INTERVAL`], writes keystrokes with
+//! `POST .../input` (a REST caller is always the human operator), forwards its grid size
+//! with `POST .../resize` (`PtySession::resize`), and gates AI-job stdin behind `POST .../authorize`.
+//!
+//! ## Stdin rules
+//!
+//! [`TerminalView::is_read_only`] mirrors React `TerminalPanel.isReadOnly`, which in turn mirrors the
+//! runtime guards (`terminal::guards`): an exited session or a capture session (an AI job's mirrored
+//! output) is read-only; a `HUMAN_DEV` shell is writable; any other interactive session (an AI job or
+//! plugin tool PTY) is writable only after the operator ticks "Take control" and the capability-checked
+//! authorize call succeeds. Keystrokes typed into a read-only view are dropped locally, never sent.
+//!
+//! ## Scope honesty
+//!
+//! Output is polled rather than streamed; the mouse-reporting modes (1000/1002/1006) are not
+//! forwarded, so full-screen programs see no mouse; bold renders as the bright ANSI color rather than a
+//! bold face; italic is not drawn; a popped-out pane window renders its pane-type body rather than
+//! the terminal. Emulator limits are listed in [`crate::terminal_emulator`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::backend_client::{
+    TerminalClient, TerminalOpCell, TerminalOutput, TerminalOutputCell, TerminalSessionInfo,
+};
+use crate::pane_registry::{PaneId, PaneType};
+use crate::split_layout::TabBodyHost;
+use crate::tab_bar::TabState;
+use crate::terminal_emulator::{GridPoint, TermColor, TerminalEmulator};
+
+/// Author-id prefix for every node the pane emits (`terminal.<session_id>.grid`, …).
+pub const TERMINAL_AUTHOR_ID_PREFIX: &str = "terminal";
+/// Stable out-of-process address of the "New terminal" button on a session-less Terminal tab.
+pub const TERMINAL_EMPTY_NEW_AUTHOR_ID: &str = "terminal.new";
+
+/// How often a live session's output is polled.
+pub const OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Poll back-off after a failed output poll (backend down, session gone).
+const ERROR_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// Font size of the cell grid.
+const GRID_FONT_SIZE: f32 = 13.0;
+
+/// The xterm default 16-color palette (0..=7 normal, 8..=15 bright).
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// The session id a tab addresses, when it is a Terminal tab bound to a session.
+pub fn terminal_tab_session_id(tab: &TabState) -> Option<&str> {
+    match (&tab.pane_type, tab.content_id.as_deref()) {
+        (PaneType::Terminal, Some(id)) if !id.is_empty() => Some(id),
+        _ => None,
+    }
+}
+
+/// The author id of one of a session view's nodes (`part` is `grid`, `take_control`, `copy`, …).
+pub fn terminal_author_id(session_id: &str, part: &str) -> String {
+    format!("{TERMINAL_AUTHOR_ID_PREFIX}.{session_id}.{part}")
+}
+
+/// The bytes xterm sends for a non-text key, or `None` for a key the terminal does not consume (text
+/// arrives separately as `Event::Text`). `app_cursor` is DECCKM: arrows/Home/End use `ESC O` instead
+/// of `ESC [`. Ctrl+C/X/V are left to the Copy/Cut/Paste events egui turns them into.
+pub fn encode_key(key: egui::Key, modifiers: egui::Modifiers, app_cursor: bool) -> Option<Vec<u8>> {
+    use egui::Key;
+    let cursor = |c: u8| {
+        if app_cursor {
+            vec![0x1b, b'O', c]
+        } else {
+            vec![0x1b, b'[', c]
+        }
+    };
+    let tilde = |n: &str| format!("\x1b[{n}~").into_bytes();
+    let bytes = match key {
+        Key::Enter => vec![b'\r'],
+        Key::Backspace => vec![0x7f],
+        Key::Tab if modifiers.shift => b"\x1b[Z".to_vec(),
+        Key::Tab => vec![b'\t'],
+        Key::Escape => vec![0x1b],
+        Key::ArrowUp => cursor(b'A'),
+        Key::ArrowDown => cursor(b'B'),
+        Key::ArrowRight => cursor(b'C'),
+        Key::ArrowLeft => cursor(b'D'),
+        Key::Home => cursor(b'H'),
+        Key::End => cursor(b'F'),
+        Key::Insert => tilde("2"),
+        Key::Delete => tilde("3"),
+        Key::PageUp => tilde("5"),
+        Key::PageDown => tilde("6"),
+        Key::F1 => b"\x1bOP".to_vec(),
+        Key::F2 => b"\x1bOQ".to_vec(),
+        Key::F3 => b"\x1bOR".to_vec(),
+        Key::F4 => b"\x1bOS".to_vec(),
+        Key::F5 => tilde("15"),
+        Key::F6 => tilde("17"),
+        Key::F7 => tilde("18"),
+        Key::F8 => tilde("19"),
+        Key::F9 => tilde("20"),
+        Key::F10 => tilde("21"),
+        Key::F11 => tilde("23"),
+        Key::F12 => tilde("24"),
+        _ if modifiers.ctrl && !modifiers.alt => {
+            let name = key.name();
+            let letter = name.as_bytes().first().copied().filter(|_| name.len() == 1)?;
+            if !letter.is_ascii_alphabetic() || matches!(letter, b'C' | b'X' | b'V') {
+                return None;
+            }
+            vec![letter.to_ascii_uppercase() - b'A' + 1]
+        }
+        _ => return None,
+    };
+    Some(bytes)
+}
+
+/// Paste `text` the way xterm does: line ends become `\r`, wrapped in `ESC[200~ … ESC[201~` when the
+/// program enabled bracketed paste.
+pub fn encode_paste(text: &str, bracketed: bool) -> Vec<u8> {
+    let normalized = text.replace("\r\n", "\r").replace('\n', "\r");
+    if bracketed {
+        let mut out = b"\x1b[200~".to_vec();
+        out.extend_from_slice(normalized.as_bytes());
+        out.extend_from_slice(b"\x1b[201~");
+        out
+    } else {
+        normalized.into_bytes()
+    }
+}
+
+/// A [`TermColor`] on screen. `default` is the theme color `TermColor::Default` stands for.
+pub fn term_color(color: TermColor, default: egui::Color32) -> egui::Color32 {
+    match color {
+        TermColor::Default => default,
+        TermColor::Rgb(r, g, b) => egui::Color32::from_rgb(r, g, b),
+        TermColor::Indexed(i) if i < 16 => {
+            let (r, g, b) = ANSI_COLORS[i as usize];
+            egui::Color32::from_rgb(r, g, b)
+        }
+        TermColor::Indexed(i) if i < 232 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            egui::Color32::from_rgb(level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        TermColor::Indexed(i) => {
+            let v = 8 + (i - 232) * 10;
+            egui::Color32::from_rgb(v, v, v)
+        }
+    }
+}
+
+/// A toolbar request from a [`TerminalView`] (or a session-less Terminal tab) for the app to apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalViewEvent {
+    /// Spawn a new `HUMAN_DEV` shell in this pane.
+    NewSession,
+    /// Show an existing session in this pane.
+    OpenSession(TerminalSessionInfo),
+}
+
+/// Take a delivered off-thread result out of `cell`, clearing the cell once it has delivered.
+fn take_delivered<T>(cell: &mut Option<Arc<Mutex<Option<T>>>>) -> Option<T> {
+    let delivered = cell.as_ref()?.lock().ok()?.take();
+    if delivered.is_some() {
+        *cell = None;
+    }
+    delivered
+}
+
+/// One session's terminal: emulator state plus the in-flight backend calls that feed it.
+pub struct TerminalView {
+    session: TerminalSessionInfo,
+    emulator: TerminalEmulator,
+    /// The output offset to poll from next.
+    next_offset: u64,
+    /// The exit was seen and the output after it drained; polling stops.
+    finished: bool,
+    output_cell: Option<TerminalOutputCell>,
+    last_poll: Option<Instant>,
+    /// Keystrokes (and DSR/DA replies) not yet written; sent one request at a time, in order.
+    pending_input: Vec<u8>,
+    input_cell: Option<TerminalOpCell>,
+    /// The grid size last laid out, and the size last sent to the PTY.
+    wanted_size: Option<(u16, u16)>,
+    sent_size: Option<(u16, u16)>,
+    resize_cell: Option<TerminalOpCell>,
+    /// The operator's "Take control" toggle, and whether the backend authorized it.
+    take_control: bool,
+    authorized: bool,
+    authorize_cell: Option<TerminalOpCell>,
+    /// Drag selection (anchor, head), in absolute buffer coordinates.
+    selection: Option<(GridPoint, GridPoint)>,
+    /// Lines scrolled up from the live bottom of the buffer.
+    scroll_offset: usize,
+    scroll_remainder: f32,
+    focused: bool,
+    error: Option<String>,
+    notice: Option<String>,
+}
+
+impl TerminalView {
+    /// A view for `session`, polling from the start of the backend's retained output.
+    pub fn new(session: TerminalSessionInfo) -> Self {
+        let authorized = session.interactive_authorized;
+        Self {
+            session,
+            emulator: TerminalEmulator::new(24, 80),
+            next_offset: 0,
+            finished: false,
+            output_cell: None,
+            last_poll: None,
+            pending_input: Vec::new(),
+            input_cell: None,
+            wanted_size: None,
+            sent_size: None,
+            resize_cell: None,
+            take_control: false,
+            authorized,
+            authorize_cell: None,
+            selection: None,
+            scroll_offset: 0,
+            scroll_remainder: 0.0,
+            focused: false,
+            error: None,
+            notice: None,
+        }
+    }
+
+    pub fn session(&self) -> &TerminalSessionInfo {
+        &self.session
+    }
+
+    pub fn emulator(&self) -> &TerminalEmulator {
+        &self.emulator
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Whether the grid held keyboard focus last frame.
+    pub fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    /// Keystrokes waiting to be written to the session (tests + the app's busy check).
+    pub fn pending_input(&self) -> &[u8] {
+        &self.pending_input
+    }
+
+    /// The text the current selection covers, if any.
+    pub fn selection_text(&self) -> Option<String> {
+        let (a, b) = self.selection?;
+        Some(self.emulator.selection_text(a, b)).filter(|text| !text.is_empty())
+    }
+
+    /// The React `TerminalPanel.isReadOnly` rule (see the module docs).
+    pub fn is_read_only(&self) -> bool {
+        if self.session.exited || !self.session.interactive_allowed() {
+            return true;
+        }
+        if self.session.is_human() {
+            return false;
+        }
+        !(self.take_control && self.authorized)
+    }
+
+    /// Replace the session description with a fresher one from `GET /terminal/sessions`. An exit the
+    /// view already observed is kept.
+    pub fn update_session(&mut self, info: TerminalSessionInfo) {
+        let exited = self.session.exited;
+        let exit_code = self.session.exit_code;
+        self.authorized |= info.interactive_authorized;
+        self.session = info;
+        if exited {
+            self.session.exited = true;
+            self.session.exit_code = self.session.exit_code.or(exit_code);
+        }
+    }
+
+    /// Toggle "Take control" of a non-human interactive session. Ticking it asks the backend to
+    /// authorize stdin (on the next [`drive`](Self::drive)); the view stays read-only until it does.
+    pub fn set_take_control(&mut self, take_control: bool) {
+        self.take_control = take_control;
+    }
+
+    /// Queue operator input for the session; dropped while the view is read-only.
+    pub fn send_input(&mut self, bytes: &[u8]) {
+        if self.is_read_only() || bytes.is_empty() {
+            return;
+        }
+        self.pending_input.extend_from_slice(bytes);
+        self.scroll_offset = 0;
+    }
+
+    /// Apply one delivered output poll.
+    pub fn apply_output(&mut self, result: Result<TerminalOutput, String>) {
+        match result {
+            Ok(output) => {
+                self.error = None;
+                if output.gap {
+                    self.notice = Some("Earlier output was dropped by the backend log.".to_owned());
+                }
+                let scrollback_before = self.emulator.scrollback_len();
+                self.emulator.feed(&output.data);
+                if self.scroll_offset > 0 {
+                    // Keep a scrolled-back view on the same text while new output arrives.
+                    let grown = self.emulator.scrollback_len().saturating_sub(scrollback_before);
+                    self.scroll_offset =
+                        (self.scroll_offset + grown).min(self.emulator.scrollback_len());
+                }
+                let responses = self.emulator.take_responses();
+                self.send_input(&responses);
+                self.next_offset = output.next;
+                if output.exited {
+                    self.session.exited = true;
+                    self.session.exit_code = output.exit_code.or(self.session.exit_code);
+                    self.finished = output.data.is_empty();
+                }
+            }
+            Err(msg) => self.error = Some(msg),
+        }
+    }
+
+    /// Drain delivered backend results and start whatever call is due: the next output poll, queued
+    /// input, a resize, the take-control authorization. Returns `true` when something was delivered
+    /// (the caller repaints). Per-frame; no call is started without a `client`.
+    pub fn drive(&mut self, client: Option<&TerminalClient>, now: Instant) -> bool {
+        let mut delivered = false;
+        if let Some(result) = take_delivered(&mut self.output_cell) {
+            self.apply_output(result);
+            delivered = true;
+        }
+        if let Some(result) = take_delivered(&mut self.input_cell) {
+            if let Err(msg) = result {
+                self.error = Some(format!("Input not delivered: {msg}"));
+            }
+            delivered = true;
+        }
+        if let Some(result) = take_delivered(&mut self.resize_cell) {
+            if let Err(msg) = result {
+                self.error = Some(format!("Resize failed: {msg}"));
+            }
+            delivered = true;
+        }
+        if let Some(result) = take_delivered(&mut self.authorize_cell) {
+            match result {
+                Ok(()) => self.authorized = true,
+                Err(msg) => {
+                    self.take_control = false;
+                    self.error = Some(format!("Take control denied: {msg}"));
+                }
+            }
+            delivered = true;
+        }
+
+        let Some(client) = client else {
+            return delivered;
+        };
+        let session_id = self.session.session_id.clone();
+        let interval =
+            if self.error.is_some() { ERROR_RETRY_INTERVAL } else { OUTPUT_POLL_INTERVAL };
+        let poll_due = self.last_poll.is_none_or(|last| now.duration_since(last) >= interval);
+        if self.output_cell.is_none() && !self.finished && poll_due {
+            let cell: TerminalOutputCell = Arc::new(Mutex::new(None));
+            client.poll_output(&session_id, self.next_offset, cell.clone());
+            self.output_cell = Some(cell);
+            self.last_poll = Some(now);
+        }
+        if self.take_control && !self.authorized && self.authorize_cell.is_none() {
+            let cell: TerminalOpCell = Arc::new(Mutex::new(None));
+            client.authorize(&session_id, cell.clone());
+            self.authorize_cell = Some(cell);
+        }
+        if self.input_cell.is_none() && !self.pending_input.is_empty() {
+            if self.is_read_only() {
+                self.pending_input.clear();
+            } else {
+                let cell: TerminalOpCell = Arc::new(Mutex::new(None));
+                client.write_input(&session_id, &std::mem::take(&mut self.pending_input), cell.clone());
+                self.input_cell = Some(cell);
+            }
+        }
+        if self.session.interactive_allowed()
+            && self.resize_cell.is_none()
+            && self.wanted_size.is_some()
+            && self.wanted_size != self.sent_size
+        {
+            if let Some((rows, cols)) = self.wanted_size {
+                let cell: TerminalOpCell = Arc::new(Mutex::new(None));
+                client.resize(&session_id, rows, cols, cell.clone());
+                self.resize_cell = Some(cell);
+                self.sent_size = self.wanted_size;
+            }
+        }
+        delivered
+    }
+
+    /// Whether the view still needs frames (polling a live session or a call in flight).
+    pub fn is_busy(&self) -> bool {
+        !self.finished
+            || self.output_cell.is_some()
+            || self.input_cell.is_some()
+            || self.resize_cell.is_some()
+            || self.authorize_cell.is_some()
+    }
+
+    /// Render the toolbar and the grid, and route keyboard/mouse input. `sessions` feeds the
+    /// "Sessions" menu.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sessions: &[TerminalSessionInfo],
+    ) -> Option<TerminalViewEvent> {
+        let mut event = None;
+        let session_id = self.session.session_id.clone();
+        let read_only = self.is_read_only();
+
+        ui.horizontal(|ui| {
+            let title = self
+                .emulator
+                .title()
+                .filter(|t| !t.trim().is_empty())
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| self.session.label());
+            ui.strong(title);
+            if self.session.is_capture() {
+                ui.label(egui::RichText::new("capture · read-only").small().weak());
+            } else if read_only && !self.session.exited {
+                ui.label(egui::RichText::new("read-only").small().weak());
+            }
+            if self.session.exited {
+                let status = match self.session.exit_code {
+                    Some(code) => format!("exited ({code})"),
+                    None => "exited".to_owned(),
+                };
+                ui.label(egui::RichText::new(status).small());
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let new = ui.button("New");
+                set_author_id(ui, new.id, &terminal_author_id(&session_id, "new"));
+                if new.clicked() {
+                    event = Some(TerminalViewEvent::NewSession);
+                }
+                if let Some(picked) = sessions_menu(ui, sessions, Some(&session_id)) {
+                    event = Some(TerminalViewEvent::OpenSession(picked));
+                }
+                let selection = self.selection_text();
+                let copy = ui.add_enabled(selection.is_some(), egui::Button::new("Copy"));
+                set_author_id(ui, copy.id, &terminal_author_id(&session_id, "copy"));
+                if copy.clicked() {
+                    if let Some(text) = selection {
+                        ui.ctx().copy_text(text);
+                    }
+                }
+                if !self.session.is_human() && self.session.interactive_allowed() {
+                    let mut take_control = self.take_control;
+                    let label = if take_control && !self.authorized {
+                        "Take control (authorizing…)"
+                    } else {
+                        "Take control"
+                    };
+                    let toggle = ui.checkbox(&mut take_control, label);
+                    set_author_id(ui, toggle.id, &terminal_author_id(&session_id, "take_control"));
+                    if toggle.changed() {
+                        self.set_take_control(take_control);
+                    }
+                }
+            });
+        });
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+        }
+        if let Some(notice) = &self.notice {
+            ui.label(egui::RichText::new(notice).small().weak());
+        }
+        ui.separator();
+
+        self.show_grid(ui);
+        event
+    }
+
+    fn show_grid(&mut self, ui: &mut egui::Ui) {
+        let font = egui::FontId::monospace(GRID_FONT_SIZE);
+        let fg_default = ui.visuals().text_color();
+        let bg_default = ui.visuals().extreme_bg_color;
+        let cell = ui
+            .painter()
+            .layout_no_wrap("M".to_owned(), font.clone(), fg_default)
+            .size();
+        let (cell_w, cell_h) = (cell.x.max(1.0), cell.y.max(1.0));
+
+        // Size the emulator (and, via `drive`, the PTY) to the space the pane gives the grid.
+        let available = ui.available_size();
+        let cols = ((available.x / cell_w).floor() as usize).clamp(2, u16::MAX as usize);
+        let rows = ((available.y / cell_h).floor() as usize).clamp(1, u16::MAX as usize);
+        if self.emulator.size() != (rows, cols) {
+            self.emulator.resize(rows, cols);
+            self.scroll_offset = self.scroll_offset.min(self.emulator.scrollback_len());
+        }
+        self.wanted_size = Some((rows as u16, cols as u16));
+
+        let grid_id = ui.id().with(("terminal-grid", &self.session.session_id));
+        let (rect, _) = ui.allocate_exact_size(available, egui::Sense::hover());
+        let response = ui.interact(rect, grid_id, egui::Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, bg_default);
+
+        let top_index = self.emulator.scrollback_len() - self.scroll_offset;
+        let first_line = self.emulator.first_line_number();
+        let point_at = |pos: egui::Pos2| {
+            let row = (((pos.y - rect.top()) / cell_h).floor().max(0.0) as usize).min(rows - 1);
+            let col = (((pos.x - rect.left()) / cell_w).floor().max(0.0) as usize).min(cols - 1);
+            GridPoint { line: first_line + (top_index + row) as u64, col }
+        };
+
+        // Mouse: click focuses (and clears the selection), drag selects, the wheel scrolls history.
+        if response.clicked() {
+            response.request_focus();
+            self.selection = None;
+        }
+        if response.drag_started() {
+            response.request_focus();
+            if let Some(pos) = response.interact_pointer_pos() {
+                let point = point_at(pos);
+                self.selection = Some((point, point));
+            }
+        }
+        if response.dragged() {
+            if let (Some(pos), Some((anchor, _))) = (response.interact_pointer_pos(), self.selection) {
+                self.selection = Some((anchor, point_at(pos)));
+            }
+        }
+        if response.hovered() {
+            let delta = ui.input(|i| i.smooth_scroll_delta.y);
+            if delta != 0.0 {
+                self.scroll_remainder += delta / cell_h;
+                let lines = self.scroll_remainder.trunc();
+                self.scroll_remainder -= lines;
+                let offset = self.scroll_offset as i64 + lines as i64;
+                self.scroll_offset = offset.clamp(0, self.emulator.scrollback_len() as i64) as usize;
+            }
+        }
+
+        // Keyboard: while focused the grid keeps Tab/arrows/Escape instead of egui's focus navigation.
+        self.focused = response.has_focus();
+        if self.focused {
+            ui.memory_mut(|m| {
+                m.set_focus_lock_filter(
+                    grid_id,
+                    egui::EventFilter {
+                        tab: true,
+                        horizontal_arrows: true,
+                        vertical_arrows: true,
+                        escape: true,
+                    },
+                )
+            });
+            self.handle_keyboard(ui);
+        }
+
+        self.paint_grid(&painter, rect, (cell_w, cell_h), &font, fg_default, bg_default);
+
+        let label = self.session.label();
+        let value = self.emulator.screen_text();
+        let read_only = self.is_read_only();
+        let author_id = terminal_author_id(&self.session.session_id, "grid");
+        ui.ctx().accesskit_node_builder(grid_id, move |node| {
+            node.set_role(egui::accesskit::Role::Terminal);
+            node.set_label(label);
+            node.set_value(value);
+            if read_only {
+                node.set_read_only();
+            }
+            node.set_author_id(author_id);
+        });
+    }
+
+    fn handle_keyboard(&mut self, ui: &egui::Ui) {
+        let events = ui.input(|i| i.events.clone());
+        let app_cursor = self.emulator.app_cursor_keys();
+        let bracketed = self.emulator.bracketed_paste();
+        for event in events {
+            match event {
+                egui::Event::Text(text) => self.send_input(text.as_bytes()),
+                egui::Event::Paste(text) => self.send_input(&encode_paste(&text, bracketed)),
+                egui::Event::Copy => match self.selection_text() {
+                    Some(text) => ui.ctx().copy_text(text),
+                    None => self.send_input(&[0x03]),
+                },
+                egui::Event::Cut => self.send_input(&[0x18]),
+                egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                    if let Some(bytes) = encode_key(key, modifiers, app_cursor) {
+                        self.send_input(&bytes);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn paint_grid(
+        &self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        (cell_w, cell_h): (f32, f32),
+        font: &egui::FontId,
+        fg_default: egui::Color32,
+        bg_default: egui::Color32,
+    ) {
+        let (rows, _) = self.emulator.size();
+        let top_index = self.emulator.scrollback_len() - self.scroll_offset;
+        let first_line = self.emulator.first_line_number();
+        let selection = self.selection.map(|(a, b)| if a <= b { (a, b) } else { (b, a) });
+        let selected = |line: u64, col: usize| {
+            selection.is_some_and(|(start, end)| {
+                let point = GridPoint { line, col };
+                start <= point && point <= end
+            })
+        };
+        let selection_bg = painter.ctx().style().visuals.selection.bg_fill;
+
+        for row in 0..rows {
+            let Some(line) = self.emulator.line(top_index + row) else {
+                break;
+            };
+            let abs_line = first_line + (top_index + row) as u64;
+            let y = rect.top() + row as f32 * cell_h;
+            // Paint runs of same-styled cells: one background rect + one text shape per run.
+            let mut col = 0;
+            while col < line.cells.len() {
+                let style = line.cells[col].style;
+                let is_selected = selected(abs_line, col);
+                let mut end = col + 1;
+                while end < line.cells.len()
+                    && line.cells[end].style == style
+                    && selected(abs_line, end) == is_selected
+                {
+                    end += 1;
+                }
+                // Bold brightens the eight normal ANSI colors (the xterm default).
+                let fg_color = match style.fg {
+                    TermColor::Indexed(i) if style.bold && i < 8 => TermColor::Indexed(i + 8),
+                    other => other,
+                };
+                let mut fg = term_color(fg_color, fg_default);
+                let mut bg = term_color(style.bg, bg_default);
+                if style.inverse {
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+                if style.dim {
+                    fg = fg.gamma_multiply(0.6);
+                }
+                if is_selected {
+                    bg = selection_bg;
+                }
+                let run_rect = egui::Rect::from_min_size(
+                    egui::pos2(rect.left() + col as f32 * cell_w, y),
+                    egui::vec2((end - col) as f32 * cell_w, cell_h),
+                );
+                if bg != bg_default {
+                    painter.rect_filled(run_rect, 0.0, bg);
+                }
+                let text: String = line.cells[col..end].iter().map(|c| c.ch).collect();
+                if !text.trim_end().is_empty() {
+                    painter.text(run_rect.left_top(), egui::Align2::LEFT_TOP, text, font.clone(), fg);
+                    if style.underline {
+                        let stroke = egui::Stroke::new(1.0, fg);
+                        painter.hline(run_rect.x_range(), run_rect.bottom() - 1.0, stroke);
+                    }
+                }
+                col = end;
+            }
+        }
+
+        // The cursor: a filled block while focused, an outline otherwise; hidden while scrolled back.
+        if self.scroll_offset == 0 && self.emulator.cursor_visible() && !self.session.exited {
+            let (cursor_row, cursor_col) = self.emulator.cursor();
+            let cursor_rect = egui::Rect::from_min_size(
+                egui::pos2(rect.left() + cursor_col as f32 * cell_w, rect.top() + cursor_row as f32 * cell_h),
+                egui::vec2(cell_w, cell_h),
+            );
+            if self.focused {
+                painter.rect_filled(cursor_rect, 0.0, fg_default.gamma_multiply(0.5));
+            } else {
+                painter.rect_stroke(
+                    cursor_rect,
+                    0.0,
+                    egui::Stroke::new(1.0, fg_default),
+                    egui::StrokeKind::Inside,
+                );
+            }
+        }
+    }
+}
+
+/// The "Sessions" menu: every listed session except `current`; returns the picked one.
+fn sessions_menu(
+    ui: &mut egui::Ui,
+    sessions: &[TerminalSessionInfo],
+    current: Option<&str>,
+) -> Option<TerminalSessionInfo> {
+    let mut picked = None;
+    ui.menu_button("Sessions", |ui| {
+        let others: Vec<&TerminalSessionInfo> =
+            sessions.iter().filter(|s| Some(s.session_id.as_str()) != current).collect();
+        if others.is_empty() {
+            ui.label(egui::RichText::new("No other sessions").weak());
+        }
+        for session in others {
+            let mut label = session.label();
+            if session.is_capture() {
+                label.push_str(" (capture)");
+            } else if session.exited {
+                label.push_str(" (exited)");
+            }
+            if ui.button(label).clicked() {
+                picked = Some(session.clone());
+                ui.close();
+            }
+        }
+    });
+    picked
+}
+
+/// Attach a stable author_id to a widget's live AccessKit node.
+fn set_author_id(ui: &egui::Ui, id: egui::Id, author_id: &str) {
+    let author_id = author_id.to_owned();
+    ui.ctx().accesskit_node_builder(id, move |node| {
+        node.set_author_id(author_id);
+    });
+}
+
+/// A view's toolbar request, tagged with the pane it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalPaneEvent {
+    pub pane_id: PaneId,
+    pub event: TerminalViewEvent,
+}
+
+/// The [`TabBodyHost`] for Terminal tabs: a session tab renders its [`TerminalView`]; a Terminal tab
+/// with no session yet renders the New / attach picker (with `status`, e.g. "Starting shell…" or the
+/// backend error). Toolbar requests are collected into `events` for the app to apply after the frame.
+pub struct TerminalTabBodies<'a> {
+    pub views: &'a mut HashMap<String, TerminalView>,
+    pub sessions: &'a [TerminalSessionInfo],
+    pub status: Option<&'a str>,
+    pub events: Vec<TerminalPaneEvent>,
+}
+
+impl<'a> TerminalTabBodies<'a> {
+    pub fn new(
+        views: &'a mut HashMap<String, TerminalView>,
+        sessions: &'a [TerminalSessionInfo],
+        status: Option<&'a str>,
+    ) -> Self {
+        Self { views, sessions, status, events: Vec::new() }
+    }
+}
+
+impl TabBodyHost for TerminalTabBodies<'_> {
+    fn claims(&self, tab: &TabState) -> bool {
+        match terminal_tab_session_id(tab) {
+            Some(id) => self.views.contains_key(id),
+            None => tab.pane_type == PaneType::Terminal,
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, pane_id: &PaneId, tab: &TabState) -> bool {
+        let sessions = self.sessions;
+        let (event, focused) = match terminal_tab_session_id(tab).and_then(|id| self.views.get_mut(id)) {
+            Some(view) => {
+                let event = ui.push_id(pane_id.as_ref(), |ui| view.show(ui, sessions)).inner;
+                (event, view.has_focus())
+            }
+            None => {
+                let event = ui.push_id(pane_id.as_ref(), |ui| show_empty(ui, sessions, self.status)).inner;
+                (event, false)
+            }
+        };
+        if let Some(event) = event {
+            self.events.push(TerminalPaneEvent { pane_id: pane_id.clone(), event });
+        }
+        focused
+    }
+}
+
+/// The body of a Terminal tab with no session: New, or attach to a listed session.
+fn show_empty(
+    ui: &mut egui::Ui,
+    sessions: &[TerminalSessionInfo],
+    status: Option<&str>,
+) -> Option<TerminalViewEvent> {
+    let mut event = None;
+    ui.horizontal(|ui| {
+        ui.strong("Terminal");
+        let new = ui.button("New terminal");
+        set_author_id(ui, new.id, TERMINAL_EMPTY_NEW_AUTHOR_ID);
+        if new.clicked() {
+            event = Some(TerminalViewEvent::NewSession);
+        }
+        if let Some(picked) = sessions_menu(ui, sessions, None) {
+            event = Some(TerminalViewEvent::OpenSession(picked));
+        }
+    });
+    if let Some(status) = status {
+        ui.label(egui::RichText::new(status).weak());
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(kind: &str, session_type: &str) -> TerminalSessionInfo {
+        TerminalSessionInfo {
+            kind: kind.to_owned(),
+            session_type: session_type.to_owned(),
+            ..TerminalSessionInfo::unknown("sess-1")
+        }
+    }
+
+    fn output(data: &[u8], next: u64) -> TerminalOutput {
+        TerminalOutput { next, data: data.to_vec(), gap: false, exited: false, exit_code: None }
+    }
+
+    #[test]
+    fn read_only_rule_mirrors_terminal_panel() {
+        let human = TerminalView::new(session("INTERACTIVE", "HUMAN_DEV"));
+        assert!(!human.is_read_only());
+
+        let capture = TerminalView::new(session("CAPTURE", "AI_JOB"));
+        assert!(capture.is_read_only());
+
+        let mut ai = TerminalView::new(session("INTERACTIVE", "AI_JOB"));
+        assert!(ai.is_read_only());
+        ai.set_take_control(true);
+        assert!(ai.is_read_only(), "still read-only until the backend authorizes");
+        ai.authorized = true;
+        assert!(!ai.is_read_only());
+
+        let mut exited = TerminalView::new(session("INTERACTIVE", "HUMAN_DEV"));
+        exited.apply_output(Ok(TerminalOutput { exited: true, exit_code: Some(0), ..output(b"", 0) }));
+        assert!(exited.is_read_only());
+
+        assert!(TerminalView::new(TerminalSessionInfo::unknown("x")).is_read_only());
+    }
+
+    #[test]
+    fn read_only_view_drops_input() {
+        let mut capture = TerminalView::new(session("CAPTURE", "AI_JOB"));
+        capture.send_input(b"rm -rf /\r");
+        assert!(capture.pending_input().is_empty());
+
+        let mut human = TerminalView::new(session("INTERACTIVE", "HUMAN_DEV"));
+        human.send_input(b"ls\r");
+        assert_eq!(human.pending_input(), b"ls\r");
+    }
+
+    #[test]
+    fn output_advances_offset_and_answers_queries() {
+        let mut view = TerminalView::new(session("INTERACTIVE", "HUMAN_DEV"));
+        view.apply_output(Ok(output(b"hello\x1b[6n", 9)));
+        assert_eq!(view.next_offset, 9);
+        assert!(view.emulator().screen_text().starts_with("hello"));
+        assert_eq!(view.pending_input(), b"\x1b[1;6R");
+
+        // A capture session never writes the DSR reply back.
+        let mut capture = TerminalView::new(session("CAPTURE", "AI_JOB"));
+        capture.apply_output(Ok(output(b"\x1b[6n", 4)));
+        assert!(capture.pending_input().is_empty());
+    }
+
+    #[test]
+    fn exit_stops_polling_once_drained() {
+        let mut view = TerminalView::new(session("INTERACTIVE", "HUMAN_DEV"));
+        view.apply_output(Ok(TerminalOutput { exited: true, exit_code: Some(2), ..output(b"bye", 3) }));
+        assert!(view.session().exited);
+        assert!(view.is_busy(), "output that arrived with the exit may not be the last");
+        view.apply_output(Ok(TerminalOutput { exited: true, exit_code: Some(2), ..output(b"", 3) }));
+        assert!(!view.is_busy());
+        assert_eq!(view.session().exit_code, Some(2));
+    }
+
+    #[test]
+    fn update_session_keeps_an_observed_exit() {
+        let mut view = TerminalView::new(session("INTERACTIVE", "AI_JOB"));
+        view.apply_output(Ok(TerminalOutput { exited: true, exit_code: Some(1), ..output(b"", 0) }));
+        view.update_session(TerminalSessionInfo {
+            interactive_authorized: true,
+            ..session("INTERACTIVE", "AI_JOB")
+        });
+        assert!(view.session().exited);
+        assert_eq!(view.session().exit_code, Some(1));
+        assert!(view.authorized);
+    }
+
+    #[test]
+    fn keys_encode_like_xterm() {
+        let none = egui::Modifiers::NONE;
+        assert_eq!(encode_key(egui::Key::Enter, none, false), Some(b"\r".to_vec()));
+        assert_eq!(encode_key(egui::Key::ArrowUp, none, false), Some(b"\x1b[A".to_vec()));
+        assert_eq!(encode_key(egui::Key::ArrowUp, none, true), Some(b"\x1bOA".to_vec()));
+        assert_eq!(encode_key(egui::Key::Tab, egui::Modifiers::SHIFT, false), Some(b"\x1b[Z".to_vec()));
+        assert_eq!(encode_key(egui::Key::PageDown, none, false), Some(b"\x1b[6~".to_vec()));
+        assert_eq!(encode_key(egui::Key::F5, none, false), Some(b"\x1b[15~".to_vec()));
+        assert_eq!(encode_key(egui::Key::D, egui::Modifiers::CTRL, false), Some(vec![0x04]));
+        assert_eq!(encode_key(egui::Key::C, egui::Modifiers::CTRL, false), None);
+        assert_eq!(encode_key(egui::Key::A, none, false), None, "text arrives as Event::Text");
+    }
+
+    #[test]
+    fn paste_normalizes_newlines_and_brackets() {
+        assert_eq!(encode_paste("a\r\nb\nc", false), b"a\rb\rc".to_vec());
+        assert_eq!(encode_paste("x", true), b"\x1b[200~x\x1b[201~".to_vec());
+    }
+
+    #[test]
+    fn palette_covers_ansi_cube_and_grays() {
+        let default = egui::Color32::WHITE;
+        assert_eq!(term_color(TermColor::Default, default), default);
+        assert_eq!(term_color(TermColor::Indexed(1), default), egui::Color32::from_rgb(0xcd, 0, 0));
+        assert_eq!(term_color(TermColor::Indexed(16), default), egui::Color32::from_rgb(0, 0, 0));
+        assert_eq!(term_color(TermColor::Indexed(231), default), egui::Color32::from_rgb(255, 255, 255));
+        assert_eq!(term_color(TermColor::Indexed(232), default), egui::Color32::from_rgb(8, 8, 8));
+        assert_eq!(term_color(TermColor::Rgb(1, 2, 3), default), egui::Color32::from_rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn terminal_tabs_address_their_session() {
+        let mut tab = TabState::new(PaneType::Terminal);
+        assert_eq!(terminal_tab_session_id(&tab), None);
+        tab.content_id = Some("sess-1".to_owned());
+        assert_eq!(terminal_tab_session_id(&tab), Some("sess-1"));
+        let mut workspace = TabState::new(PaneType::Workspace);
+        workspace.content_id = Some("sess-1".to_owned());
+        assert_eq!(terminal_tab_session_id(&workspace), None);
+        assert_eq!(terminal_author_id("sess-1", "grid"), "terminal.sess-1.grid");
+    }
+}
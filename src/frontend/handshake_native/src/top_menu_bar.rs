@@ -17,7 +17,7 @@
 //! ## Menu structure and each action's wiring status
 //!
 //! Leaf items whose target already exists in the shell are ENABLED and dispatched. Leaf items whose
-//! target is a FUTURE microtask (a document/editor model, a file drawer, a terminal panel) are rendered
+//! target is a FUTURE microtask (a document/editor model, a file drawer) are rendered
 //! DISABLED with a disclosed reason in their tooltip — they are NOT fake-enabled. The action enum still
 //! carries every leaf so the wiring is mechanical once the target MT lands.
 //!
@@ -54,7 +54,7 @@
 //!   Open Swarm Board        ENABLED -> OpenSwarmBoard (opens the Swarm surface on the active pane)
 //!   Open Inference Lab      ENABLED -> NavigateToTab("inference-lab")
 //!   Open Flight Recorder    ENABLED -> NavigateToTab("flight-recorder")
-//!   Open Terminal           DISABLED (no native terminal panel yet — future MT)
+//!   Open Terminal           ENABLED -> OpenTerminal (a Terminal tab + new shell on the active pane)
 //! HELP
 //!   Open User Manual        ENABLED -> NavigateToTab("user-manual")
 //!   Open Settings…          ENABLED -> OpenSettings (sets settings_open; UI = MT-018)
@@ -244,7 +244,7 @@ pub enum MenuBarAction {
     OpenSwarmBoard,
     /// Navigate the active pane to a named tab/surface (the React `PaneTabId` string).
     NavigateToTab(String),
-    OpenTerminal, // disabled in MT-015 (no native terminal panel yet)
+    OpenTerminal,
     // HELP
     OpenSettings,
     ShowAbout,
@@ -446,7 +446,7 @@ impl MenuBar {
                 self.item(ui, "menu.run.swarm-board", "Open Swarm Board", None, true, MenuBarAction::OpenSwarmBoard, action);
                 self.item(ui, "menu.run.inference-lab", "Open Inference Lab", None, true, MenuBarAction::NavigateToTab("inference-lab".to_owned()), action);
                 self.item(ui, "menu.run.flight-recorder", "Open Flight Recorder", None, true, MenuBarAction::NavigateToTab("flight-recorder".to_owned()), action);
-                self.disabled_item(ui, "menu.run.terminal", "Open Terminal", None, "No native terminal panel yet (future MT)");
+                self.item(ui, "menu.run.terminal", "Open Terminal", None, true, MenuBarAction::OpenTerminal, action);
             }
             MenuId::Help => {
                 self.item(ui, "menu.help.user-manual", "Open User Manual", None, true, MenuBarAction::NavigateToTab("user-manual".to_owned()), action);
@@ -293,7 +293,7 @@ fn disabled_editor_row_cannot_run() {
     harness.run();
     harness.run();
 
-    // Filter to an editor command ("Bold"), which is disabled (no native editor surface yet).
+    // Filter to an editor command ("Bold"), which is disabled (no editor tab is open).
     palette_search(&harness).type_text("bold");
     harness.run();
     harness.run();
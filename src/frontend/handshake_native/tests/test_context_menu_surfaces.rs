@@ -314,14 +314,15 @@ fn secondary_click_pane_header_lock_toggles_lock_state() {
 }
 
 #[test]
-fn pane_header_menu_set_type_is_disabled() {
+fn pane_header_menu_set_type_items_are_addressable() {
     let mut harness = harness_for(app_three_tab_pane_a());
     harness.get_by_label("Pane header pane-a").click_secondary();
     harness.run();
     harness.run();
 
     let nodes = live_author_nodes(&harness);
-    // The four Set Type items are present (addressable) but future-target/disabled.
+    // The four Set Type items are present (addressable); Terminal opens a terminal tab, the other three
+    // (and Close) are future-target/disabled.
     for leaf in [
         "ctx-menu.pane.set_type_editor",
         "ctx-menu.pane.set_type_terminal",
@@ -331,7 +332,14 @@ fn pane_header_menu_set_type_is_disabled() {
     ] {
         assert!(nodes.iter().any(|(a, _, _)| a == leaf), "pane menu {leaf} present: {nodes:?}");
     }
-    println!("PASS: pane header Set Type / Close items are present but future-target (disabled)");
+    // Set Type -> Terminal is wired to the terminal runtime, so it must render enabled.
+    let terminal = harness
+        .root()
+        .children_recursive()
+        .find(|node| node.accesskit_node().author_id() == Some("ctx-menu.pane.set_type_terminal"))
+        .expect("Set Type Terminal node");
+    assert!(!terminal.accesskit_node().is_disabled(), "Set Type Terminal is enabled");
+    println!("PASS: pane header Set Type / Close items are present and addressable");
 }
 
 // ── Surface 4: explorer row (project-tree document / canvas / bookmark) ─────────────────────────────────
@@ -0,0 +1,340 @@
+//! Loom graph edge editing and delete confirmation, through the REAL surface and `HandshakeApp`.
+//!
+//! - `Connect to...` then clicking another node opens the relation-type picker; the picked type is the
+//!   `Connect` event (tag types are offered only for a tag-hub target);
+//! - `Disconnect...` lists the node's edges and emits `Disconnect` for the one picked;
+//! - the app sends the edge create / delete to the verified `/loom/edges` routes on the wire;
+//! - `Delete Block...` only opens a confirm dialog; a confirmed delete hides the node behind an Undo
+//!   toast, Undo sends nothing, and the DELETE reaches the wire only when the delete is committed.
+
+use std::sync::{Arc, Mutex};
+
+use egui_kittest::kittest::{NodeT, Queryable};
+use egui_kittest::Harness;
+use handshake_native::app::{HandshakeApp, HealthDisplayState};
+use handshake_native::backend_client::{
+    HealthInfo, LoomEdgeInfo, LoomEdgeKind, LoomGraphNodeInfo, LoomGraphSnapshot,
+};
+use handshake_native::loom_graph::{LoomGraphColors, LoomGraphEvent, LoomGraphSurface};
+
+fn ok_app() -> HandshakeApp {
+    HandshakeApp::with_health(HealthDisplayState::Ok(HealthInfo {
+        status: "ok".to_string(),
+        db_status: "ok".to_string(),
+        migration_version: Some(1),
+    }))
+}
+
+fn node(block_id: &str, title: &str, tag_hub: bool) -> LoomGraphNodeInfo {
+    LoomGraphNodeInfo {
+        block_id: block_id.to_owned(),
+        title: Some(title.to_owned()),
+        pinned: false,
+        favorite: false,
+        tag_hub,
+    }
+}
+
+fn edge() -> LoomEdgeInfo {
+    LoomEdgeInfo {
+        edge_id: "e1".to_owned(),
+        source_block_id: "a".to_owned(),
+        target_block_id: "b".to_owned(),
+        kind: LoomEdgeKind::Parent,
+        source_anchor: None,
+    }
+}
+
+fn graph() -> LoomGraphSnapshot {
+    LoomGraphSnapshot {
+        nodes: vec![node("a", "Alpha", false), node("b", "Beta", false), node("t", "Topic", true)],
+        edges: vec![edge()],
+        truncated: false,
+    }
+}
+
+/// A harness rendering the surface for `graph()` each frame, capturing the last emitted event.
+fn surface_harness() -> (Harness<'static>, Arc<Mutex<Option<LoomGraphEvent>>>) {
+    let captured = Arc::new(Mutex::new(None));
+    let cap = captured.clone();
+    let harness = Harness::builder().build_ui(move |ui| {
+        let colors = LoomGraphColors {
+            node_bg: egui::Color32::from_gray(40),
+            node_hover_bg: egui::Color32::from_gray(60),
+            node_text: egui::Color32::WHITE,
+        };
+        if let Some(e) = LoomGraphSurface::from_snapshot(&graph()).show(ui, colors) {
+            *cap.lock().unwrap() = Some(e);
+        }
+    });
+    (harness, captured)
+}
+
+fn author_ids<S>(harness: &Harness<'_, S>) -> Vec<String> {
+    harness
+        .root()
+        .children_recursive()
+        .filter_map(|n| n.accesskit_node().author_id().map(ToOwned::to_owned))
+        .collect()
+}
+
+#[test]
+fn connect_mode_then_relation_picker_emits_connect() {
+    let (mut harness, captured) = surface_harness();
+    harness.run();
+    harness.get_by_role_and_label(egui::accesskit::Role::TreeItem, "Alpha").click_secondary();
+    harness.run();
+    harness.get_by_label("Connect to...").click();
+    harness.run();
+    harness.get_by_role_and_label(egui::accesskit::Role::TreeItem, "Beta").click();
+    harness.run();
+
+    let ids = author_ids(&harness);
+    assert!(ids.iter().any(|id| id == "loom_graph.relation.parent"));
+    assert!(
+        !ids.iter().any(|id| id == "loom_graph.relation.tag"),
+        "a note target is not offered tag relations"
+    );
+
+    harness.get_by_label("Parent").click();
+    harness.run();
+    assert_eq!(
+        *captured.lock().unwrap(),
+        Some(LoomGraphEvent::Connect {
+            source_block_id: "a".to_owned(),
+            target_block_id: "b".to_owned(),
+            kind: LoomEdgeKind::Parent,
+        })
+    );
+}
+
+#[test]
+fn tag_hub_target_offers_tag_relations() {
+    let (mut harness, _) = surface_harness();
+    harness.run();
+    harness.get_by_role_and_label(egui::accesskit::Role::TreeItem, "Alpha").click_secondary();
+    harness.run();
+    harness.get_by_label("Connect to...").click();
+    harness.run();
+    harness.get_by_role_and_label(egui::accesskit::Role::TreeItem, "Topic").click();
+    harness.run();
+    let ids = author_ids(&harness);
+    assert!(ids.iter().any(|id| id == "loom_graph.relation.tag"));
+    assert!(ids.iter().any(|id| id == "loom_graph.relation.sub_tag"));
+}
+
+#[test]
+fn disconnect_picker_lists_edges_and_emits_disconnect() {
+    let (mut harness, captured) = surface_harness();
+    harness.run();
+    harness.get_by_role_and_label(egui::accesskit::Role::TreeItem, "Beta").click_secondary();
+    harness.run();
+    harness.get_by_label("Disconnect...").click();
+    harness.run();
+    assert!(author_ids(&harness).iter().any(|id| id == "loom_graph.edge.e1"));
+    harness.get_by_label("Parent ← Alpha").click();
+    harness.run();
+    assert_eq!(*captured.lock().unwrap(), Some(LoomGraphEvent::Disconnect { edge: edge() }));
+}
+
+// ── Wire proof through the REAL app ─────────────────────────────────────────────────────────────────
+
+struct CapturedReq {
+    request_line: String,
+    body: String,
+}
+
+/// Accept one request on `listener` and answer it with a receipted mutation body.
+fn capture_one(listener: std::net::TcpListener) -> CapturedReq {
+    use std::io::{Read, Write};
+    let (mut stream, _) = listener.accept().expect("accept");
+    let mut buf = [0u8; 8192];
+    let mut data = Vec::new();
+    loop {
+        let n = stream.read(&mut buf).expect("read");
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&data);
+        if let Some(hdr_end) = text.find("\r\n\r\n") {
+            let content_len = text[..hdr_end]
+                .lines()
+                .find_map(|l| {
+                    let l = l.to_ascii_lowercase();
+                    l.strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().ok())
+                })
+                .flatten()
+                .unwrap_or(0);
+            if text[hdr_end + 4..].len() >= content_len {
+                break;
+            }
+        }
+    }
+    let text = String::from_utf8_lossy(&data).into_owned();
+    let request_line = text.lines().next().unwrap_or("").to_owned();
+    let body = text.split("\r\n\r\n").nth(1).unwrap_or("").to_owned();
+    let reply = br#"{"receipt_event_id":"evt-1"}"#;
+    let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", reply.len());
+    let _ = stream.write_all(reply);
+    let _ = stream.flush();
+    CapturedReq { request_line, body }
+}
+
+fn capture_server() -> (std::net::TcpListener, String) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let port = listener.local_addr().unwrap().port();
+    (listener, format!("http://127.0.0.1:{port}"))
+}
+
+fn test_runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("runtime")
+}
+
+#[test]
+fn app_connect_posts_typed_user_edge() {
+    let rt = test_runtime();
+    let (listener, base) = capture_server();
+    let mut app = ok_app();
+    app.set_backend_base_url_for_test(&base, rt.handle().clone());
+
+    let dispatched = app.apply_loom_node_event(
+        LoomGraphEvent::Connect {
+            source_block_id: "a".to_owned(),
+            target_block_id: "t".to_owned(),
+            kind: LoomEdgeKind::Tag,
+        },
+        "ws1",
+    );
+    assert!(dispatched);
+    let cap = capture_one(listener);
+    assert_eq!(cap.request_line, "POST /workspaces/ws1/loom/edges HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(cap.body.trim()).expect("json");
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "source_block_id": "a",
+            "target_block_id": "t",
+            "edge_type": "tag",
+            "created_by": "user",
+        })
+    );
+}
+
+#[test]
+fn app_disconnect_deletes_edge_then_offers_undo() {
+    let rt = test_runtime();
+    let (listener, base) = capture_server();
+    let mut harness =
+        Harness::builder().build_state(|ctx, app: &mut HandshakeApp| app.ui(ctx), ok_app());
+    harness.state_mut().set_backend_base_url_for_test(&base, rt.handle().clone());
+    harness.state_mut().set_loom_graph("ws1", graph());
+    harness.run();
+
+    harness
+        .state_mut()
+        .apply_loom_node_event(LoomGraphEvent::Disconnect { edge: edge() }, "ws1");
+    let cap = capture_one(listener);
+    assert_eq!(cap.request_line, "DELETE /workspaces/ws1/loom/edges/e1 HTTP/1.1");
+
+    // The result is delivered off-thread; the next frames drain it into the receipt + Undo toast.
+    for _ in 0..50 {
+        harness.run();
+        if harness.state().loom_undo_available() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(harness.state().loom_last_receipt(), Some("evt-1"));
+    assert!(harness.state().loom_undo_available(), "a removed edge can be undone");
+}
+
+#[test]
+fn app_delete_waits_for_confirm_and_undo_window() {
+    let rt = test_runtime();
+    let (listener, base) = capture_server();
+    let mut harness =
+        Harness::builder().build_state(|ctx, app: &mut HandshakeApp| app.ui(ctx), ok_app());
+    harness.set_size(egui::Vec2::new(1200.0, 800.0));
+    harness.state_mut().set_backend_base_url_for_test(&base, rt.handle().clone());
+    harness.state_mut().set_loom_graph("ws1", graph());
+    harness.run();
+
+    let delete = LoomGraphEvent::Delete { block_id: "b".to_owned(), title: "Beta".to_owned() };
+    assert!(
+        !harness.state_mut().apply_loom_node_event(delete.clone(), "ws1"),
+        "a delete request sends nothing before it is confirmed"
+    );
+    harness.run();
+    assert!(harness.state().loom_delete_confirm_open());
+    harness.get_by_label("Delete").click();
+    harness.run();
+
+    let visible = |app: &HandshakeApp| -> Vec<String> {
+        let surface = app.loom_graph_surface().expect("graph loaded");
+        surface.nodes.into_iter().map(|n| n.state.block_id).collect()
+    };
+    assert!(!harness.state().loom_delete_confirm_open());
+    assert!(harness.state().loom_undo_available());
+    assert_eq!(visible(harness.state()), vec!["a", "t"], "the deleted block is hidden");
+    assert!(
+        harness.state().loom_graph_surface().unwrap().edges.is_empty(),
+        "its edges are hidden with it"
+    );
+
+    harness.get_by_label("Undo").click();
+    harness.run();
+    assert!(!harness.state().loom_undo_available());
+    assert_eq!(visible(harness.state()), vec!["a", "b", "t"], "undo restores it locally");
+
+    // Confirm again and commit without waiting out the window: only now does the DELETE go out.
+    harness.state_mut().apply_loom_node_event(delete, "ws1");
+    harness.run();
+    harness.get_by_label("Delete").click();
+    harness.run();
+    harness.state_mut().commit_loom_delete();
+    let cap = capture_one(listener);
+    assert_eq!(cap.request_line, "DELETE /workspaces/ws1/loom/blocks/b HTTP/1.1");
+}
+
+#[test]
+fn exit_inside_undo_window_sends_the_pending_delete() {
+    let rt = test_runtime();
+    let (listener, base) = capture_server();
+    let mut harness =
+        Harness::builder().build_state(|ctx, app: &mut HandshakeApp| app.ui(ctx), ok_app());
+    harness.set_size(egui::Vec2::new(1200.0, 800.0));
+    harness.state_mut().set_backend_base_url_for_test(&base, rt.handle().clone());
+    harness.state_mut().set_loom_graph("ws1", graph());
+    harness.run();
+
+    harness.state_mut().apply_loom_node_event(
+        LoomGraphEvent::Delete { block_id: "b".to_owned(), title: "Beta".to_owned() },
+        "ws1",
+    );
+    harness.run();
+    harness.get_by_label("Delete").click();
+    harness.run();
+    assert!(harness.state().loom_undo_available());
+
+    // The shell closes mid-window: the flush blocks on the DELETE instead of dropping it.
+    let server = std::thread::spawn(move || capture_one(listener));
+    let receipt = harness.state_mut().flush_loom_undo_on_exit();
+    let cap = server.join().expect("capture thread");
+    assert_eq!(cap.request_line, "DELETE /workspaces/ws1/loom/blocks/b HTTP/1.1");
+    assert_eq!(receipt.as_deref(), Some("evt-1"));
+    assert!(!harness.state().loom_undo_available());
+    assert_eq!(harness.state_mut().flush_loom_undo_on_exit(), None, "nothing left to flush");
+}
+
+#[test]
+fn edge_edit_without_runtime_is_disclosed() {
+    let mut app = ok_app();
+    let dispatched = app.apply_loom_node_event(LoomGraphEvent::Disconnect { edge: edge() }, "ws1");
+    assert!(!dispatched);
+    assert!(app.loom_edit_error().is_some_and(|e| e.contains("unavailable")));
+}
@@ -0,0 +1,170 @@
+//! Native rich-document editor pane, end-to-end through the REAL `HandshakeApp`.
+//!
+//! - opening a `KRD-` Workspace tab creates the tab's editor (and, with no runtime, surfaces the
+//!   "backend unavailable" error instead of a silent empty pane); a legacy document id does not;
+//! - a loaded document renders its blocks as addressable editor fields in the live AccessKit tree;
+//! - the palette's editor commands are enabled only while the editor tab is active, and running one
+//!   (Bold via Enter) edits the focused block and marks the tab dirty;
+//! - closing the tab drops the editor.
+//!
+//! ## No live backend needed
+//!
+//! The shell is built with `HandshakeApp::with_health(...)` (no runtime, no network); the load response
+//! is delivered straight into the editor the way the off-thread GET would.
+
+use std::sync::Arc;
+
+use egui_kittest::kittest::{NodeT, Queryable};
+use egui_kittest::Harness;
+use handshake_native::app::{HandshakeApp, HealthDisplayState};
+use handshake_native::backend_client::HealthInfo;
+use handshake_native::pane_registry::{PaneId, PaneType};
+use handshake_native::rich_document_editor::{BlockBody, FieldKey};
+use handshake_native::tab_bar::TabState;
+
+const DOC_ID: &str = "KRD-notes";
+
+fn ok_app() -> HandshakeApp {
+    HandshakeApp::with_health(HealthDisplayState::Ok(HealthInfo {
+        status: "ok".to_string(),
+        db_status: "ok".to_string(),
+        migration_version: Some(1),
+    }))
+}
+
+/// Open a Workspace tab for `document_id` on pane-a (the default command-target pane).
+fn open_document_tab(app: &mut HandshakeApp, document_id: &str) {
+    let pane_a: PaneId = Arc::from("pane-a");
+    let mut tab = TabState::new(PaneType::Workspace);
+    tab.content_id = Some(document_id.to_owned());
+    app.tab_bar_states_mut()
+        .get_mut(&pane_a)
+        .expect("seeded pane-a tab bar")
+        .insert_tab(tab);
+}
+
+fn load_response() -> serde_json::Value {
+    serde_json::json!({
+        "document": {
+            "rich_document_id": DOC_ID,
+            "title": "Notes",
+            "doc_version": 3,
+            "crdt_document_id": null,
+            "content_json": { "type": "doc", "content": [
+                { "type": "heading", "attrs": { "level": 1, "block_id": "h" }, "content": [{ "type": "text", "text": "Notes" }] },
+                { "type": "paragraph", "attrs": { "block_id": "p" }, "content": [{ "type": "text", "text": "Hello world" }] },
+            ] },
+        },
+        "tree": [],
+        "code_nodes": [],
+    })
+}
+
+fn harness_with_loaded_document() -> Harness<'static, HandshakeApp> {
+    let mut harness = Harness::builder().build_state(|ctx, a: &mut HandshakeApp| a.ui(ctx), ok_app());
+    harness.run();
+    open_document_tab(harness.state_mut(), DOC_ID);
+    harness.run();
+    harness
+        .state_mut()
+        .rich_document_editor_mut(DOC_ID)
+        .expect("editor created for the open KRD- tab")
+        .apply_load_result(Ok(load_response()));
+    harness.run();
+    harness
+}
+
+fn author_ids(harness: &Harness<'_, HandshakeApp>) -> Vec<String> {
+    harness
+        .root()
+        .children_recursive()
+        .filter_map(|n| n.accesskit_node().author_id().map(ToOwned::to_owned))
+        .collect()
+}
+
+#[test]
+fn krd_tab_gets_an_editor_and_legacy_tab_does_not() {
+    let mut harness = Harness::builder().build_state(|ctx, a: &mut HandshakeApp| a.ui(ctx), ok_app());
+    harness.run();
+    open_document_tab(harness.state_mut(), "doc-legacy");
+    open_document_tab(harness.state_mut(), DOC_ID);
+    harness.run();
+
+    assert!(harness.state().rich_document_editor("doc-legacy").is_none());
+    let editor = harness.state().rich_document_editor(DOC_ID).expect("KRD- tab has an editor");
+    assert!(
+        editor.error().is_some_and(|e| e.contains("unavailable")),
+        "no runtime: the load failure is surfaced, not silent"
+    );
+    assert!(!harness.state().rich_document_editor_active(), "nothing loaded yet");
+}
+
+#[test]
+fn loaded_document_renders_addressable_blocks() {
+    let harness = harness_with_loaded_document();
+    let ids = author_ids(&harness);
+    for expected in [
+        "rich-document-editor.block.0",
+        "rich-document-editor.block.1",
+        "rich-document-editor.save",
+        "rich-document-editor.reload",
+    ] {
+        assert!(ids.iter().any(|id| id == expected), "{expected} in the live tree: {ids:?}");
+    }
+    assert!(harness.state().rich_document_editor_active());
+}
+
+#[test]
+fn palette_bold_edits_the_focused_block_and_dirties_the_tab() {
+    let mut harness = harness_with_loaded_document();
+    {
+        let editor = harness.state_mut().rich_document_editor_mut(DOC_ID).unwrap();
+        let uid = editor.buffer().unwrap().blocks()[1].uid();
+        editor.set_focus(FieldKey { block_uid: uid, cell: None }, 0, 5);
+    }
+
+    harness.state_mut().open_command_palette();
+    harness.run();
+    harness.run();
+    // The palette search is the TextInput that is neither the bottom rail input nor an editor field.
+    let search = harness
+        .query_all_by_role(egui::accesskit::Role::TextInput)
+        .find(|n| {
+            let author_id = n.accesskit_node().author_id().unwrap_or_default();
+            author_id != "bottom-rail.input" && !author_id.starts_with("rich-document-editor.")
+        })
+        .expect("the palette search TextInput");
+    search.type_text("bold");
+    harness.run();
+    harness.run();
+    assert!(!harness.get_by_label("Bold").accesskit_node().is_disabled(), "Bold is runnable");
+
+    harness.key_press(egui::Key::Enter);
+    harness.run();
+    harness.run();
+
+    let app = harness.state();
+    let editor = app.rich_document_editor(DOC_ID).unwrap();
+    assert_eq!(
+        editor.buffer().unwrap().blocks()[1].body,
+        BlockBody::Paragraph { text: "**Hello** world".to_owned() }
+    );
+    let pane_a: PaneId = Arc::from("pane-a");
+    let tab = app.tab_bar_states()[&pane_a].active().unwrap();
+    assert!(tab.dirty, "the unsaved edit shows the tab's dirty dot");
+}
+
+#[test]
+fn closing_the_tab_drops_the_editor() {
+    let mut harness = harness_with_loaded_document();
+    let pane_a: PaneId = Arc::from("pane-a");
+    let bar = harness.state_mut().tab_bar_states_mut().get_mut(&pane_a).unwrap();
+    let index = bar
+        .tabs
+        .iter()
+        .position(|t| t.content_id.as_deref() == Some(DOC_ID))
+        .unwrap();
+    bar.close_tab(index);
+    harness.run();
+    assert!(harness.state().rich_document_editor(DOC_ID).is_none());
+}
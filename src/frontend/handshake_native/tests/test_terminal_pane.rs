@@ -0,0 +1,161 @@
+//! Native terminal pane, end-to-end through the REAL `HandshakeApp`.
+//!
+//! - RUN > Open Terminal opens a Terminal tab on the active pane and, with no runtime, surfaces the
+//!   "backend unavailable" error on it instead of a silent empty pane;
+//! - a session tab renders its output into an addressable `Role::Terminal` grid node;
+//! - an AI-job capture session is read-only (no Take control, typed keys are dropped), an interactive
+//!   AI-job session offers Take control, and a human shell queues what the operator types;
+//! - closing the tab drops the view.
+//!
+//! ## No live backend needed
+//!
+//! The shell is built with `HandshakeApp::with_health(...)` (no runtime, no network); session lists and
+//! output polls are delivered straight into the app the way the off-thread GETs would.
+
+use std::sync::Arc;
+
+use egui_kittest::kittest::{NodeT, Queryable};
+use egui_kittest::Harness;
+use handshake_native::app::{HandshakeApp, HealthDisplayState};
+use handshake_native::backend_client::{HealthInfo, TerminalOutput, TerminalSessionInfo};
+use handshake_native::pane_registry::{PaneId, PaneType};
+use handshake_native::tab_bar::TabState;
+use handshake_native::terminal_pane::terminal_author_id;
+
+fn ok_app() -> HandshakeApp {
+    HandshakeApp::with_health(HealthDisplayState::Ok(HealthInfo {
+        status: "ok".to_string(),
+        db_status: "ok".to_string(),
+        migration_version: Some(1),
+    }))
+}
+
+fn session(session_id: &str, kind: &str, session_type: &str) -> TerminalSessionInfo {
+    TerminalSessionInfo {
+        kind: kind.to_owned(),
+        session_type: session_type.to_owned(),
+        ..TerminalSessionInfo::unknown(session_id)
+    }
+}
+
+/// A harness showing `info` in a Terminal tab on pane-a, with `output` already delivered.
+fn harness_with_session(info: TerminalSessionInfo, output: &[u8]) -> Harness<'static, HandshakeApp> {
+    let mut harness = Harness::builder().build_state(|ctx, a: &mut HandshakeApp| a.ui(ctx), ok_app());
+    harness.run();
+    let session_id = info.session_id.clone();
+    let app = harness.state_mut();
+    app.set_terminal_sessions(vec![info]);
+    let pane_a: PaneId = Arc::from("pane-a");
+    let mut tab = TabState::new(PaneType::Terminal);
+    tab.content_id = Some(session_id.clone());
+    app.tab_bar_states_mut()
+        .get_mut(&pane_a)
+        .expect("seeded pane-a tab bar")
+        .insert_tab(tab);
+    harness.run();
+    harness
+        .state_mut()
+        .terminal_view_mut(&session_id)
+        .expect("view created for the open Terminal tab")
+        .apply_output(Ok(TerminalOutput {
+            next: output.len() as u64,
+            data: output.to_vec(),
+            gap: false,
+            exited: false,
+            exit_code: None,
+        }));
+    harness.run();
+    harness
+}
+
+fn author_ids(harness: &Harness<'_, HandshakeApp>) -> Vec<String> {
+    harness
+        .root()
+        .children_recursive()
+        .filter_map(|n| n.accesskit_node().author_id().map(ToOwned::to_owned))
+        .collect()
+}
+
+#[test]
+fn run_menu_opens_a_terminal_tab() {
+    let mut harness = Harness::builder().build_state(|ctx, a: &mut HandshakeApp| a.ui(ctx), ok_app());
+    harness.run();
+    harness.get_by_label("RUN").click();
+    harness.run();
+    harness.get_by_label("Open Terminal").click();
+    harness.run();
+    harness.run();
+
+    let app = harness.state();
+    let pane_a: PaneId = Arc::from("pane-a");
+    let active = app.tab_bar_states()[&pane_a].active().expect("an active tab");
+    assert_eq!(active.pane_type, PaneType::Terminal);
+    assert!(
+        app.terminal_error().is_some_and(|e| e.contains("unavailable")),
+        "no runtime: the spawn failure is surfaced, not silent"
+    );
+    assert!(author_ids(&harness).iter().any(|id| id == "terminal.new"));
+}
+
+#[test]
+fn capture_session_renders_read_only() {
+    let mut harness =
+        harness_with_session(session("cap-1", "CAPTURE", "AI_JOB"), b"cargo build\r\n   Compiling");
+    let grid = terminal_author_id("cap-1", "grid");
+    let node = harness
+        .root()
+        .children_recursive()
+        .find(|n| n.accesskit_node().author_id() == Some(grid.as_str()))
+        .expect("the terminal grid node");
+    assert_eq!(node.accesskit_node().role(), egui::accesskit::Role::Terminal);
+    assert!(node.accesskit_node().is_read_only());
+    assert!(node.accesskit_node().value().unwrap_or_default().contains("Compiling"));
+    assert!(
+        !author_ids(&harness).contains(&terminal_author_id("cap-1", "take_control")),
+        "a capture session cannot be taken over"
+    );
+
+    node.type_text("rm -rf /");
+    harness.run();
+    let view = harness.state().terminal_view("cap-1").unwrap();
+    assert!(view.is_read_only());
+    assert!(view.pending_input().is_empty(), "typed keys are dropped, never sent");
+}
+
+#[test]
+fn interactive_ai_session_offers_take_control() {
+    let harness = harness_with_session(session("ai-1", "INTERACTIVE", "AI_JOB"), b"$ ");
+    assert!(author_ids(&harness).contains(&terminal_author_id("ai-1", "take_control")));
+    assert!(harness.state().terminal_view("ai-1").unwrap().is_read_only());
+}
+
+#[test]
+fn human_shell_queues_typed_input() {
+    let mut harness = harness_with_session(session("sh-1", "INTERACTIVE", "HUMAN_DEV"), b"$ ");
+    let grid = terminal_author_id("sh-1", "grid");
+    harness
+        .root()
+        .children_recursive()
+        .find(|n| n.accesskit_node().author_id() == Some(grid.as_str()))
+        .expect("the terminal grid node")
+        .type_text("ls");
+    harness.run();
+    harness.key_press(egui::Key::Enter);
+    harness.run();
+    assert_eq!(harness.state().terminal_view("sh-1").unwrap().pending_input(), b"ls\r");
+}
+
+#[test]
+fn closing_the_tab_drops_the_view() {
+    let mut harness = harness_with_session(session("sh-1", "INTERACTIVE", "HUMAN_DEV"), b"$ ");
+    let pane_a: PaneId = Arc::from("pane-a");
+    let bar = harness.state_mut().tab_bar_states_mut().get_mut(&pane_a).unwrap();
+    let index = bar
+        .tabs
+        .iter()
+        .position(|t| t.content_id.as_deref() == Some("sh-1"))
+        .unwrap();
+    bar.close_tab(index);
+    harness.run();
+    assert!(harness.state().terminal_view("sh-1").is_none());
+}
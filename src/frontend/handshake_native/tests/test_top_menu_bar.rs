@@ -12,7 +12,7 @@
 //! - opening the VIEW menu and clicking "Reset Layout" arms the confirm (does NOT reset immediately —
 //!   red-team MC7/R7), and the explicit confirm resets to the seeded default (AC7);
 //! - the menu closes after an item is clicked (red-team R6 / MC6);
-//! - disabled leaves (Save, Open Terminal, …) render but are not clickable into an action (no fake-
+//! - disabled leaves (Save, Undo, …) render but are not clickable into an action (no fake-
 //!   enable) — they still appear in the open-menu tree as addressable disabled MenuItem nodes.
 //!
 //! ## No live backend needed
@@ -309,13 +309,13 @@ fn disabled_leaves_render_but_do_not_fire() {
         nodes.iter().any(|(a, _, _)| a == "menu.file.save"),
         "disabled Save leaf is still present + addressable in the open menu: {nodes:?}"
     );
-    // RUN > Open Terminal is likewise disabled (no native terminal panel yet).
+    // RUN > Open Terminal (enabled since the native terminal pane landed) stays addressable too.
     harness.get_by_label("RUN").click();
     harness.run();
     let nodes = live_author_nodes(&harness);
     assert!(
         nodes.iter().any(|(a, _, _)| a == "menu.run.terminal"),
-        "disabled Open Terminal leaf is present + addressable: {nodes:?}"
+        "Open Terminal leaf is present + addressable: {nodes:?}"
     );
 }
 